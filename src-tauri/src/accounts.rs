@@ -0,0 +1,844 @@
+//! Account definitions: each account's IMAP/SMTP (or NNTP) servers and
+//! sign-in.
+//!
+//! The main window registers each account's IMAP/SMTP configs once (and
+//! again whenever an OAuth token is refreshed); mail commands then take an
+//! `account_id` instead of a full config, so no webview can point a command
+//! at a server of its choosing with the user's credentials.
+//!
+//! Definitions are persisted to `accounts.json` in the profile's config
+//! directory, without passwords or OAuth tokens, so the backend knows the
+//! accounts before the frontend has registered them. `account_add`,
+//! `account_update`, `account_remove` and `account_list` manage them
+//! directly; `account_register` adds or updates one.
+//!
+//! Accounts from the managed policy ([`crate::managed`]) are added to every
+//! profile but never saved; they can't be updated or removed, and
+//! registering one only takes its password or token.
+//!
+//! Password accounts registered with an empty password keep it in the OS
+//! keychain ([`crate::credentials`]); it's read on first use and cached
+//! until the account is registered again. OAuth accounts registered without
+//! a token get a fresh one from [`crate::oauth::tokens`] on every lookup.
+//!
+//! Gmail accounts can send through the Gmail API instead of SMTP
+//! ([`MailTransport`]); the API takes the same OAuth token, see
+//! [`AccountRegistry::oauth_token`].
+//!
+//! Accounts registered with `ask_password` have no password stored on disk.
+//! The first lookup that needs one emits [`PasswordRequired`], waits for the
+//! user to answer through `account_provide_password` and keeps the password
+//! in memory for the rest of the session.
+//!
+//! Commands that send mail, upload messages, return raw message source or
+//! accept credentials are additionally limited to [`require_trusted_window`]:
+//! the main window and composers. Message and thread pop-outs render remote
+//! content and only get the read and mailbox commands.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tokio::sync::oneshot;
+
+use crate::credentials;
+use crate::events::PasswordRequired;
+use crate::identity::Identities;
+use crate::imap::folder_watch::FolderListCache;
+use crate::imap::session_pool::ImapSessionManager;
+use crate::imap::types::ImapConfig;
+use crate::managed;
+use crate::nntp::types::NntpConfig;
+use crate::oauth;
+use crate::profiles;
+use crate::smtp::types::SmtpConfig;
+
+const ACCOUNTS_FILE: &str = "accounts.json";
+
+/// Windows allowed to run sensitive commands, besides `compose-*` ones.
+const TRUSTED_WINDOWS: &[&str] = &["main"];
+const COMPOSE_WINDOW_PREFIX: &str = "compose-";
+
+/// How an OAuth account signs in. Its configs carry the access token as
+/// password, or none once the grant is in the keychain and
+/// [`crate::oauth::TokenManager`] supplies fresh ones.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct OAuthSettings {
+    pub provider: String,
+    pub client_id: Option<String>,
+}
+
+/// How an account's mail goes out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum MailTransport {
+    /// The account's SMTP server.
+    #[default]
+    Imap,
+    /// Gmail's REST API ([`crate::gmail_api`]), for accounts throttled on
+    /// Gmail's IMAP/SMTP limits.
+    GmailApi,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct AccountDefinition {
+    pub id: String,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub imap: Option<ImapConfig>,
+    #[serde(default)]
+    pub smtp: Option<SmtpConfig>,
+    /// News server of a newsgroup account; such accounts have no IMAP/SMTP.
+    #[serde(default)]
+    pub nntp: Option<NntpConfig>,
+    /// The configs carry no password; the user is asked once per session.
+    #[serde(default)]
+    pub ask_password: bool,
+    #[serde(default)]
+    pub oauth: Option<OAuthSettings>,
+    #[serde(default)]
+    pub transport: MailTransport,
+    /// Set by the managed policy; such accounts are read-only.
+    #[serde(default)]
+    pub managed: bool,
+}
+
+impl AccountDefinition {
+    /// The definition as persisted and listed: no passwords or tokens.
+    fn without_secrets(&self) -> Self {
+        let mut definition = self.clone();
+        if let Some(imap) = &mut definition.imap {
+            imap.password.clear();
+        }
+        if let Some(smtp) = &mut definition.smtp {
+            smtp.password.clear();
+        }
+        if let Some(nntp) = &mut definition.nntp {
+            nntp.password.clear();
+        }
+        definition
+    }
+}
+
+#[derive(Default)]
+struct Registry {
+    accounts: HashMap<String, AccountDefinition>,
+    /// Ids of the accounts from the managed policy.
+    managed: HashSet<String>,
+    /// Passwords of `ask_password` accounts entered this session.
+    session_passwords: HashMap<String, String>,
+    /// Passwords read from the keychain since the account was registered.
+    stored_passwords: HashMap<String, String>,
+    /// Lookups waiting for the user to answer a password prompt.
+    waiting: HashMap<String, Vec<oneshot::Sender<Option<String>>>>,
+}
+
+impl Registry {
+    /// The accounts without secrets, ordered by id.
+    fn definitions(&self) -> Vec<AccountDefinition> {
+        let mut definitions: Vec<AccountDefinition> = self
+            .accounts
+            .values()
+            .map(|account| AccountDefinition {
+                managed: self.managed.contains(&account.id),
+                ..account.without_secrets()
+            })
+            .collect();
+        definitions.sort_by(|a, b| a.id.cmp(&b.id));
+        definitions
+    }
+}
+
+type PasswordPrompt = Box<dyn Fn(&str) + Send + Sync>;
+
+/// Managed registry of account definitions, keyed by account id. The
+/// default has no file and keeps definitions in memory.
+#[derive(Default)]
+pub struct AccountRegistry {
+    path: Option<PathBuf>,
+    inner: Mutex<Registry>,
+    prompt: Option<PasswordPrompt>,
+}
+
+impl AccountRegistry {
+    /// A registry loaded from the profile's `accounts.json` that asks for
+    /// missing passwords by emitting [`PasswordRequired`] to the frontend.
+    pub fn new<R: tauri::Runtime>(app: tauri::AppHandle<R>) -> Self {
+        let path = profiles::config_dir(&app)
+            .map(|dir| dir.join(ACCOUNTS_FILE))
+            .map_err(|e| log::warn!("No config dir for account definitions: {e}"))
+            .ok();
+        let registry = Self::with_password_prompt(move |account_id| {
+            crate::events::emit(
+                &app,
+                PasswordRequired {
+                    account_id: account_id.to_string(),
+                },
+            )
+        });
+        let registry = match path {
+            Some(path) => registry.with_file(path),
+            None => registry,
+        };
+        registry.with_managed(managed::policy().accounts.clone())
+    }
+
+    /// The accounts of the profile config dir `dir`, including managed
+    /// ones, for running without the app. There's no password prompt, so
+    /// `ask_password` accounts need [`Self::provide_password`] first.
+    pub fn from_dir(dir: &Path) -> Self {
+        Self::default()
+            .with_file(dir.join(ACCOUNTS_FILE))
+            .with_managed(managed::policy().accounts.clone())
+    }
+
+    /// A registry that calls `prompt` when an `ask_password` account has no
+    /// session password yet. Without a prompt such lookups fail.
+    pub fn with_password_prompt(prompt: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        Self {
+            path: None,
+            inner: Mutex::default(),
+            prompt: Some(Box::new(prompt)),
+        }
+    }
+
+    /// Load definitions from `path` and save every change back to it. A
+    /// missing or malformed file starts empty.
+    pub fn with_file(mut self, path: PathBuf) -> Self {
+        let definitions: Vec<AccountDefinition> = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|json| {
+                serde_json::from_str(&json)
+                    .map_err(|e| log::warn!("Ignoring malformed {ACCOUNTS_FILE}: {e}"))
+                    .ok()
+            })
+            .unwrap_or_default();
+        self.inner.get_mut().unwrap().accounts =
+            definitions.into_iter().map(|d| (d.id.clone(), d)).collect();
+        self.path = Some(path);
+        self
+    }
+
+    /// Add the managed policy's accounts, replacing the user's definitions
+    /// with the same ids.
+    pub fn with_managed(mut self, accounts: Vec<AccountDefinition>) -> Self {
+        let inner = self.inner.get_mut().unwrap();
+        for account in accounts {
+            inner.managed.insert(account.id.clone());
+            inner.accounts.insert(account.id.clone(), account);
+        }
+        self
+    }
+
+    fn save(&self, inner: &Registry) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create config directory: {e}"))?;
+        }
+        let definitions: Vec<AccountDefinition> = inner
+            .definitions()
+            .into_iter()
+            .filter(|d| !d.managed)
+            .collect();
+        let json = serde_json::to_string_pretty(&definitions)
+            .map_err(|e| format!("Failed to serialize accounts: {e}"))?;
+        std::fs::write(path, json).map_err(|e| format!("Failed to write {ACCOUNTS_FILE}: {e}"))
+    }
+
+    /// Store an account, replacing any definition with the same id. A
+    /// managed account only takes the configs' passwords.
+    pub fn insert(&self, mut definition: AccountDefinition) -> Result<(), String> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.stored_passwords.remove(&definition.id);
+        if inner.managed.contains(&definition.id) {
+            let mut account = inner.accounts[&definition.id].clone();
+            if let (Some(config), Some(new)) = (&mut account.imap, definition.imap) {
+                config.password = new.password;
+            }
+            if let (Some(config), Some(new)) = (&mut account.smtp, definition.smtp) {
+                config.password = new.password;
+            }
+            if let (Some(config), Some(new)) = (&mut account.nntp, definition.nntp) {
+                config.password = new.password;
+            }
+            inner.accounts.insert(account.id.clone(), account);
+            return Ok(());
+        }
+        definition.managed = false;
+        inner.accounts.insert(definition.id.clone(), definition);
+        self.save(&inner)
+    }
+
+    fn require_unmanaged(&self, account_id: &str) -> Result<(), String> {
+        if self.inner.lock().unwrap().managed.contains(account_id) {
+            return Err(format!(
+                "Account {account_id} is managed by your organization"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Store a new account; fails if the id is taken.
+    pub fn add(&self, definition: AccountDefinition) -> Result<(), String> {
+        if self.contains(&definition.id) {
+            return Err(format!("Account {} already exists", definition.id));
+        }
+        self.insert(definition)
+    }
+
+    /// Replace an existing account's definition.
+    pub fn update(&self, definition: AccountDefinition) -> Result<(), String> {
+        if !self.contains(&definition.id) {
+            return Err(format!("No account {}", definition.id));
+        }
+        self.require_unmanaged(&definition.id)?;
+        self.insert(definition)
+    }
+
+    /// The stored definition of `account_id`, if any.
+    pub fn get(&self, account_id: &str) -> Option<AccountDefinition> {
+        self.inner.lock().unwrap().accounts.get(account_id).cloned()
+    }
+
+    fn contains(&self, account_id: &str) -> bool {
+        self.inner.lock().unwrap().accounts.contains_key(account_id)
+    }
+
+    /// Every account, without passwords or tokens, ordered by id.
+    pub fn list(&self) -> Vec<AccountDefinition> {
+        self.inner.lock().unwrap().definitions()
+    }
+
+    /// Forget an account, including its session password. Lookups still
+    /// waiting for a password fail.
+    pub fn remove(&self, account_id: &str) -> Result<(), String> {
+        self.require_unmanaged(account_id)?;
+        let (waiting, saved) = {
+            let mut inner = self.inner.lock().unwrap();
+            inner.accounts.remove(account_id);
+            inner.session_passwords.remove(account_id);
+            inner.stored_passwords.remove(account_id);
+            let waiting = inner.waiting.remove(account_id).unwrap_or_default();
+            (waiting, self.save(&inner))
+        };
+        for tx in waiting {
+            let _ = tx.send(None);
+        }
+        saved
+    }
+
+    /// Answer a password prompt: store the password for the session and
+    /// wake the lookups waiting for it. `None` means the user cancelled.
+    pub fn provide_password(&self, account_id: &str, password: Option<String>) {
+        let waiting = {
+            let mut inner = self.inner.lock().unwrap();
+            if let Some(password) = &password {
+                inner
+                    .session_passwords
+                    .insert(account_id.to_string(), password.clone());
+            }
+            inner.waiting.remove(account_id).unwrap_or_default()
+        };
+        for tx in waiting {
+            let _ = tx.send(password.clone());
+        }
+    }
+
+    pub async fn imap(&self, account_id: &str) -> Result<ImapConfig, String> {
+        let (config, ask_password) = self.lookup(account_id, |c| c.imap.clone());
+        let mut config = config
+            .ok_or_else(|| format!("No IMAP credentials registered for account {account_id}"))?;
+        managed::policy().check_config(&config.auth_method, config.accept_invalid_certs)?;
+        if ask_password {
+            config.password = self.session_password(account_id).await?;
+        } else if config.password.is_empty() {
+            config.password = self
+                .missing_password(account_id, &config.auth_method)
+                .await?;
+        }
+        Ok(config)
+    }
+
+    pub async fn smtp(&self, account_id: &str) -> Result<SmtpConfig, String> {
+        let (config, ask_password) = self.lookup(account_id, |c| c.smtp.clone());
+        let mut config = config
+            .ok_or_else(|| format!("No SMTP credentials registered for account {account_id}"))?;
+        managed::policy().check_config(&config.auth_method, config.accept_invalid_certs)?;
+        if ask_password {
+            config.password = self.session_password(account_id).await?;
+        } else if config.password.is_empty() {
+            config.password = self
+                .missing_password(account_id, &config.auth_method)
+                .await?;
+        }
+        Ok(config)
+    }
+
+    /// News servers only have password sign-in, and may need none at all.
+    pub async fn nntp(&self, account_id: &str) -> Result<NntpConfig, String> {
+        let (config, ask_password) = self.lookup(account_id, |c| c.nntp.clone());
+        let mut config =
+            config.ok_or_else(|| format!("No NNTP server registered for account {account_id}"))?;
+        managed::policy().check_config("password", config.accept_invalid_certs)?;
+        if config.username.is_empty() {
+            return Ok(config);
+        }
+        if ask_password {
+            config.password = self.session_password(account_id).await?;
+        } else if config.password.is_empty() {
+            config.password = self.stored_password(account_id).await?;
+        }
+        Ok(config)
+    }
+
+    /// A current OAuth access token of `account_id`, for the provider's
+    /// REST APIs: the token its IMAP config was registered with, or a fresh
+    /// one from its grant in the keychain.
+    pub async fn oauth_token(&self, account_id: &str) -> Result<String, String> {
+        let config = self
+            .lookup(account_id, |c| c.imap.clone())
+            .0
+            .filter(|c| c.auth_method == "oauth2")
+            .ok_or_else(|| format!("Account {account_id} doesn't sign in with OAuth"))?;
+        if !config.password.is_empty() {
+            return Ok(config.password);
+        }
+        self.missing_password(account_id, &config.auth_method).await
+    }
+
+    /// How `account_id` sends mail.
+    pub fn transport(&self, account_id: &str) -> MailTransport {
+        self.get(account_id)
+            .map(|d| d.transport)
+            .unwrap_or_default()
+    }
+
+    fn lookup<T>(
+        &self,
+        account_id: &str,
+        config: impl Fn(&AccountDefinition) -> Option<T>,
+    ) -> (Option<T>, bool) {
+        let inner = self.inner.lock().unwrap();
+        match inner.accounts.get(account_id) {
+            Some(c) => (config(c), c.ask_password),
+            None => (None, false),
+        }
+    }
+
+    /// Drop the cached keychain password, e.g. after it was changed.
+    pub fn forget_stored_password(&self, account_id: &str) {
+        self.inner
+            .lock()
+            .unwrap()
+            .stored_passwords
+            .remove(account_id);
+    }
+
+    /// The secret of a config registered without one: the keychain password
+    /// of a password account, or a current access token of an OAuth account
+    /// whose grant is in the keychain.
+    async fn missing_password(
+        &self,
+        account_id: &str,
+        auth_method: &str,
+    ) -> Result<String, String> {
+        if auth_method == "password" {
+            self.stored_password(account_id).await
+        } else {
+            oauth::tokens()
+                .access_token(account_id)
+                .await?
+                .ok_or_else(|| format!("No OAuth token registered for account {account_id}"))
+        }
+    }
+
+    /// The keychain password for `account_id`, read once per registration.
+    async fn stored_password(&self, account_id: &str) -> Result<String, String> {
+        if let Some(password) = self.inner.lock().unwrap().stored_passwords.get(account_id) {
+            return Ok(password.clone());
+        }
+        let password = credentials::stored_password(account_id).await?;
+        self.inner
+            .lock()
+            .unwrap()
+            .stored_passwords
+            .insert(account_id.to_string(), password.clone());
+        Ok(password)
+    }
+
+    /// The session password for `account_id`, prompting for it (once, however
+    /// many lookups are waiting) if it hasn't been entered yet.
+    async fn session_password(&self, account_id: &str) -> Result<String, String> {
+        let (rx, first) = {
+            let mut inner = self.inner.lock().unwrap();
+            if let Some(password) = inner.session_passwords.get(account_id) {
+                return Ok(password.clone());
+            }
+            if self.prompt.is_none() {
+                return Err(format!("No password entered for account {account_id}"));
+            }
+            let (tx, rx) = oneshot::channel();
+            let waiting = inner.waiting.entry(account_id.to_string()).or_default();
+            waiting.push(tx);
+            (rx, waiting.len() == 1)
+        };
+        if first {
+            if let Some(prompt) = &self.prompt {
+                prompt(account_id);
+            }
+        }
+        rx.await
+            .ok()
+            .flatten()
+            .ok_or_else(|| format!("No password entered for account {account_id}"))
+    }
+}
+
+fn is_trusted(label: &str) -> bool {
+    TRUSTED_WINDOWS.contains(&label) || label.starts_with(COMPOSE_WINDOW_PREFIX)
+}
+
+/// Reject a sensitive command invoked from a window other than the main
+/// window or a composer.
+pub fn require_trusted_window(window: &tauri::Window, command: &str) -> Result<(), String> {
+    let label = window.label();
+    if is_trusted(label) {
+        Ok(())
+    } else {
+        log::warn!("Blocked {command} from window {label}");
+        Err(format!("{command} is not allowed from this window"))
+    }
+}
+
+/// Add or update an account, e.g. with a refreshed OAuth token. With
+/// `ask_password` the configs' passwords are ignored and the user is asked
+/// once per session. Details left out keep their stored value.
+#[tauri::command]
+#[specta::specta]
+pub fn account_register(
+    window: tauri::Window,
+    registry: tauri::State<'_, AccountRegistry>,
+    definition: AccountDefinition,
+) -> Result<(), String> {
+    require_trusted_window(&window, "account_register")?;
+    let stored = registry.get(&definition.id).unwrap_or_default();
+    registry.insert(AccountDefinition {
+        email: definition.email.or(stored.email),
+        display_name: definition.display_name.or(stored.display_name),
+        oauth: definition.oauth.or(stored.oauth),
+        ..definition
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn account_add(
+    window: tauri::Window,
+    registry: tauri::State<'_, AccountRegistry>,
+    definition: AccountDefinition,
+) -> Result<(), String> {
+    require_trusted_window(&window, "account_add")?;
+    registry.add(definition)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn account_update(
+    window: tauri::Window,
+    registry: tauri::State<'_, AccountRegistry>,
+    definition: AccountDefinition,
+) -> Result<(), String> {
+    require_trusted_window(&window, "account_update")?;
+    registry.update(definition)
+}
+
+/// Every stored account, without passwords or tokens.
+#[tauri::command]
+#[specta::specta]
+pub fn account_list(
+    window: tauri::Window,
+    registry: tauri::State<'_, AccountRegistry>,
+) -> Result<Vec<AccountDefinition>, String> {
+    require_trusted_window(&window, "account_list")?;
+    Ok(registry.list())
+}
+
+/// Answer a [`PasswordRequired`] event; `None` cancels the waiting commands.
+/// Also used right after adding an account so its first sync doesn't prompt.
+#[tauri::command]
+#[specta::specta]
+pub fn account_provide_password(
+    window: tauri::Window,
+    registry: tauri::State<'_, AccountRegistry>,
+    account_id: String,
+    password: Option<String>,
+) -> Result<(), String> {
+    require_trusted_window(&window, "account_provide_password")?;
+    registry.provide_password(&account_id, password);
+    Ok(())
+}
+
+/// Delete an account's definition and forget its cached folder list and
+/// addresses and log out its idle IMAP sessions.
+#[tauri::command]
+#[specta::specta]
+pub fn account_remove(
+    window: tauri::Window,
+    registry: tauri::State<'_, AccountRegistry>,
+    folder_cache: tauri::State<'_, FolderListCache>,
+    identities: tauri::State<'_, Identities>,
+    sessions: tauri::State<'_, ImapSessionManager>,
+    account_id: String,
+) -> Result<(), String> {
+    require_trusted_window(&window, "account_remove")?;
+    registry.remove(&account_id)?;
+    folder_cache.remove(&account_id);
+    identities.remove(&account_id);
+    sessions.close_later(Some(&account_id));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn imap_config(host: &str) -> ImapConfig {
+        ImapConfig {
+            host: host.to_string(),
+            port: 993,
+            security: "tls".to_string(),
+            username: "user".to_string(),
+            password: "secret".to_string(),
+            auth_method: "password".to_string(),
+            accept_invalid_certs: false,
+            authorize_as: None,
+            delegation: None,
+            rate_limit_per_minute: None,
+        }
+    }
+
+    fn account(id: &str, imap: ImapConfig, ask_password: bool) -> AccountDefinition {
+        AccountDefinition {
+            id: id.to_string(),
+            imap: Some(imap),
+            ask_password,
+            ..Default::default()
+        }
+    }
+
+    fn waiting(registry: &AccountRegistry, account_id: &str) -> usize {
+        let inner = registry.inner.lock().unwrap();
+        inner.waiting.get(account_id).map_or(0, Vec::len)
+    }
+
+    #[tokio::test]
+    async fn test_registry_lookup() {
+        let registry = AccountRegistry::default();
+        registry
+            .insert(account("a", imap_config("imap.example.com"), false))
+            .unwrap();
+
+        assert_eq!(registry.imap("a").await.unwrap().host, "imap.example.com");
+        assert!(registry
+            .smtp("a")
+            .await
+            .unwrap_err()
+            .contains("No SMTP credentials"));
+        assert!(registry.imap("b").await.is_err());
+
+        registry
+            .insert(account("a", imap_config("imap2.example.com"), false))
+            .unwrap();
+        assert_eq!(registry.imap("a").await.unwrap().host, "imap2.example.com");
+
+        registry.remove("a").unwrap();
+        assert!(registry.imap("a").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_oauth_token_and_transport() {
+        let registry = AccountRegistry::default();
+        registry
+            .insert(account("pw", imap_config("imap.gmail.com"), false))
+            .unwrap();
+        let oauth = ImapConfig {
+            auth_method: "oauth2".to_string(),
+            password: "token".to_string(),
+            ..imap_config("imap.gmail.com")
+        };
+        registry
+            .insert(AccountDefinition {
+                transport: MailTransport::GmailApi,
+                ..account("oauth", oauth, false)
+            })
+            .unwrap();
+
+        assert_eq!(registry.oauth_token("oauth").await.unwrap(), "token");
+        assert!(registry
+            .oauth_token("pw")
+            .await
+            .unwrap_err()
+            .contains("doesn't sign in with OAuth"));
+        assert_eq!(registry.transport("oauth"), MailTransport::GmailApi);
+        assert_eq!(registry.transport("pw"), MailTransport::Imap);
+        assert_eq!(registry.transport("missing"), MailTransport::Imap);
+    }
+
+    #[tokio::test]
+    async fn test_definitions_persisted_without_secrets() {
+        let path = std::env::temp_dir().join(format!("sora-accounts-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let registry = AccountRegistry::default().with_file(path.clone());
+        registry
+            .add(AccountDefinition {
+                email: Some("user@example.com".to_string()),
+                ..account("a", imap_config("imap.example.com"), false)
+            })
+            .unwrap();
+        registry
+            .add(account("b", imap_config("imap.example.org"), true))
+            .unwrap();
+        assert!(registry
+            .add(account("a", imap_config("imap.example.com"), false))
+            .is_err());
+        assert!(registry
+            .update(account("c", imap_config("imap.example.com"), false))
+            .is_err());
+        registry
+            .update(account("b", imap_config("imap2.example.org"), true))
+            .unwrap();
+
+        // The password stays in memory only
+        assert_eq!(registry.imap("a").await.unwrap().password, "secret");
+        let listed = registry.list();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].imap.as_ref().unwrap().password, "");
+        assert!(!std::fs::read_to_string(&path).unwrap().contains("secret"));
+
+        let reloaded = AccountRegistry::default().with_file(path.clone());
+        let listed = reloaded.list();
+        assert_eq!(listed[0].email.as_deref(), Some("user@example.com"));
+        assert_eq!(listed[1].imap.as_ref().unwrap().host, "imap2.example.org");
+        assert!(listed[1].ask_password);
+
+        reloaded.remove("a").unwrap();
+        let reloaded = AccountRegistry::default().with_file(path.clone());
+        assert_eq!(reloaded.list().len(), 1);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_managed_accounts() {
+        let path = std::env::temp_dir().join(format!("sora-managed-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut corp = account("corp", imap_config("imap.corp.example"), false);
+        corp.imap.as_mut().unwrap().password.clear();
+        let registry = AccountRegistry::default()
+            .with_file(path.clone())
+            .with_managed(vec![corp]);
+        registry
+            .add(account("a", imap_config("imap.example.com"), false))
+            .unwrap();
+
+        // Registering only takes the password
+        registry
+            .insert(account("corp", imap_config("evil.example"), false))
+            .unwrap();
+        let config = registry.imap("corp").await.unwrap();
+        assert_eq!(config.host, "imap.corp.example");
+        assert_eq!(config.password, "secret");
+
+        assert!(registry
+            .update(account("corp", imap_config("evil.example"), false))
+            .is_err());
+        assert!(registry.remove("corp").is_err());
+        assert!(registry.list()[1].managed);
+
+        let reloaded = AccountRegistry::default().with_file(path.clone());
+        let listed = reloaded.list();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, "a");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_session_password_prompt() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let prompts = Arc::new(AtomicUsize::new(0));
+        let counter = prompts.clone();
+        let registry = Arc::new(AccountRegistry::with_password_prompt(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        }));
+        registry
+            .insert(account("a", imap_config("imap.example.com"), true))
+            .unwrap();
+
+        // Two lookups racing for the password share one prompt.
+        let first = tokio::spawn({
+            let registry = registry.clone();
+            async move { registry.imap("a").await }
+        });
+        let second = tokio::spawn({
+            let registry = registry.clone();
+            async move { registry.imap("a").await }
+        });
+        while waiting(&registry, "a") < 2 {
+            tokio::task::yield_now().await;
+        }
+        registry.provide_password("a", Some("typed".to_string()));
+
+        assert_eq!(first.await.unwrap().unwrap().password, "typed");
+        assert_eq!(second.await.unwrap().unwrap().password, "typed");
+        assert_eq!(registry.imap("a").await.unwrap().password, "typed");
+        assert_eq!(prompts.load(Ordering::SeqCst), 1);
+
+        registry.remove("a").unwrap();
+        registry
+            .insert(account("a", imap_config("imap.example.com"), true))
+            .unwrap();
+        let cancelled = tokio::spawn({
+            let registry = registry.clone();
+            async move { registry.imap("a").await }
+        });
+        while waiting(&registry, "a") < 1 {
+            tokio::task::yield_now().await;
+        }
+        registry.provide_password("a", None);
+        assert!(cancelled.await.unwrap().is_err());
+        assert_eq!(prompts.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_session_password_without_prompt() {
+        let registry = AccountRegistry::default();
+        registry
+            .insert(account("a", imap_config("imap.example.com"), true))
+            .unwrap();
+        assert!(registry.imap("a").await.is_err());
+
+        registry.provide_password("a", Some("typed".to_string()));
+        assert_eq!(registry.imap("a").await.unwrap().password, "typed");
+    }
+
+    #[test]
+    fn test_trusted_windows() {
+        assert!(is_trusted("main"));
+        assert!(is_trusted("compose-1718000000000"));
+        assert!(is_trusted(crate::quick_compose::QUICK_COMPOSE_LABEL));
+        assert!(!is_trusted("message-acc-INBOX-42"));
+        assert!(!is_trusted("thread-abc"));
+        assert!(!is_trusted("splashscreen"));
+    }
+}
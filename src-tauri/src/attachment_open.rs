@@ -0,0 +1,350 @@
+//! Open received attachments with the OS default application.
+//!
+//! The attachment is written to a per-message directory under the system
+//! temp dir with a sanitized filename and marked read-only (so edits don't
+//! look like they'd be saved back into the email), then handed to the
+//! default handler. Everything written is removed when the app exits, and
+//! leftovers from a previous run are cleared at startup.
+//!
+//! Files the OS would run instead of open (executables, scripts, shortcuts)
+//! are refused with a `DANGEROUS_ATTACHMENT:` error; the frontend offers to
+//! save them instead.
+
+use base64::Engine;
+use serde::Deserialize;
+use specta::Type;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::accounts::{require_trusted_window, AccountRegistry};
+use crate::attachment_preview::resolve_cache_path;
+use crate::imap::client as imap_client;
+
+/// Directory under the system temp dir that holds opened attachments.
+const TEMP_DIR_NAME: &str = "sora-attachments";
+/// Longest filename we write, in characters.
+const MAX_FILENAME_CHARS: usize = 150;
+
+/// Error prefix returned for attachments that would run code when opened.
+pub const DANGEROUS_ATTACHMENT_PREFIX: &str = "DANGEROUS_ATTACHMENT:";
+
+/// Extensions the default handler executes rather than displays, on any of
+/// Windows, macOS or Linux desktops.
+const DANGEROUS_EXTENSIONS: &[&str] = &[
+    "app",
+    "appimage",
+    "application",
+    "bat",
+    "cmd",
+    "com",
+    "command",
+    "cpl",
+    "desktop",
+    "exe",
+    "gadget",
+    "hta",
+    "inf",
+    "jar",
+    "js",
+    "jse",
+    "lnk",
+    "msc",
+    "msi",
+    "msp",
+    "pif",
+    "ps1",
+    "psm1",
+    "reg",
+    "scf",
+    "scr",
+    "sh",
+    "url",
+    "vb",
+    "vbe",
+    "vbs",
+    "website",
+    "ws",
+    "wsc",
+    "wsf",
+    "wsh",
+];
+
+/// Where to get the attachment bytes from.
+#[derive(Debug, Deserialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AttachmentSource {
+    /// Fetch the part from the IMAP server.
+    Imap {
+        account_id: String,
+        folder: String,
+        uid: u32,
+        part_id: String,
+    },
+    /// Already in the attachment cache (path relative to the app data dir).
+    Cached { local_path: String },
+    /// Bytes downloaded by the frontend (Gmail API), base64 or base64url.
+    Data { data: String },
+}
+
+/// Temp files written this session, removed on exit.
+#[derive(Default)]
+pub struct OpenedAttachments(Mutex<HashSet<PathBuf>>);
+
+fn temp_root() -> PathBuf {
+    std::env::temp_dir().join(TEMP_DIR_NAME)
+}
+
+/// Turn an untrusted name into a single safe path component.
+///
+/// Strips path separators, characters Windows rejects, control characters
+/// and leading dots, and avoids Windows device names like `CON`.
+fn sanitize_filename(name: &str, fallback: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let cleaned = cleaned
+        .trim()
+        .trim_start_matches('.')
+        .trim_end_matches(['.', ' ']);
+
+    let mut result: String = if cleaned.is_empty() {
+        fallback.to_string()
+    } else {
+        cleaned.to_string()
+    };
+
+    // Keep the extension when truncating long names
+    if result.chars().count() > MAX_FILENAME_CHARS {
+        let ext = result
+            .rsplit_once('.')
+            .map(|(_, ext)| ext)
+            .filter(|ext| ext.chars().count() <= 10)
+            .map(|ext| format!(".{ext}"))
+            .unwrap_or_default();
+        let keep = MAX_FILENAME_CHARS - ext.chars().count();
+        result = result.chars().take(keep).collect::<String>() + &ext;
+    }
+
+    let stem = result.split('.').next().unwrap_or("").to_ascii_uppercase();
+    let reserved = matches!(stem.as_str(), "CON" | "PRN" | "AUX" | "NUL")
+        || ((stem.starts_with("COM") || stem.starts_with("LPT"))
+            && stem.len() == 4
+            && stem.as_bytes()[3].is_ascii_digit());
+    if reserved {
+        result.insert(0, '_');
+    }
+    result
+}
+
+/// Whether opening `filename` with the default handler would run it.
+fn is_dangerous(filename: &str) -> bool {
+    filename
+        .rsplit_once('.')
+        .is_some_and(|(_, ext)| DANGEROUS_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+fn decode_any_base64(data: &str) -> Result<Vec<u8>, String> {
+    let normalized: String = data
+        .trim_end_matches('=')
+        .chars()
+        .map(|c| match c {
+            '-' => '+',
+            '_' => '/',
+            c => c,
+        })
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    base64::engine::general_purpose::STANDARD_NO_PAD
+        .decode(normalized)
+        .map_err(|e| format!("Invalid attachment data: {e}"))
+}
+
+async fn load_bytes(app: &AppHandle, source: AttachmentSource) -> Result<Vec<u8>, String> {
+    match source {
+        AttachmentSource::Imap {
+            account_id,
+            folder,
+            uid,
+            part_id,
+        } => {
+            let config = app.state::<AccountRegistry>().imap(&account_id).await?;
+            let mut session = imap_client::connect(&config).await?;
+            let data = imap_client::fetch_attachment(&mut session, &folder, uid, &part_id).await;
+            let _ = session.logout().await;
+            decode_any_base64(&data?)
+        }
+        AttachmentSource::Cached { local_path } => {
+            let path = resolve_cache_path(app, &local_path)?;
+            std::fs::read(&path).map_err(|e| format!("Failed to read cached attachment: {e}"))
+        }
+        AttachmentSource::Data { data } => decode_any_base64(&data),
+    }
+}
+
+fn set_writable(path: &Path) {
+    if let Ok(metadata) = std::fs::metadata(path) {
+        let mut perms = metadata.permissions();
+        #[allow(clippy::permissions_set_readonly_false)]
+        perms.set_readonly(false);
+        let _ = std::fs::set_permissions(path, perms);
+    }
+}
+
+/// Write `bytes` to `path` and mark the file read-only, replacing any
+/// earlier (read-only) copy.
+fn write_read_only(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    if path.exists() {
+        set_writable(path);
+    }
+    std::fs::write(path, bytes).map_err(|e| format!("Failed to write temp file: {e}"))?;
+    let mut perms = std::fs::metadata(path)
+        .map_err(|e| format!("Failed to stat temp file: {e}"))?
+        .permissions();
+    perms.set_readonly(true);
+    std::fs::set_permissions(path, perms)
+        .map_err(|e| format!("Failed to mark temp file read-only: {e}"))
+}
+
+/// Remove a directory tree that may contain read-only files.
+fn remove_tree(root: &Path) {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            remove_tree(&path);
+        } else {
+            set_writable(&path);
+            let _ = std::fs::remove_file(&path);
+        }
+    }
+    let _ = std::fs::remove_dir(root);
+}
+
+/// Remove every temp file written this session (called on exit).
+pub fn cleanup(app: &AppHandle) {
+    if let Some(opened) = app.try_state::<OpenedAttachments>() {
+        let files: Vec<PathBuf> = opened.0.lock().unwrap().drain().collect();
+        for file in &files {
+            set_writable(file);
+            let _ = std::fs::remove_file(file);
+        }
+        if !files.is_empty() {
+            log::info!("Removed {} opened attachment temp file(s)", files.len());
+        }
+    }
+    remove_tree(&temp_root());
+}
+
+/// Clear temp files left behind by a previous run that didn't exit cleanly.
+pub fn cleanup_stale() {
+    remove_tree(&temp_root());
+}
+
+/// Save an attachment to a temp file and open it with the default app.
+/// Returns the temp file path.
+#[tauri::command]
+#[specta::specta]
+pub async fn open_attachment(
+    app: AppHandle,
+    window: tauri::Window,
+    message_id: String,
+    filename: Option<String>,
+    source: AttachmentSource,
+) -> Result<String, String> {
+    require_trusted_window(&window, "open_attachment")?;
+    let name = sanitize_filename(filename.as_deref().unwrap_or_default(), "attachment");
+    if is_dangerous(&name) {
+        log::warn!("Refused to open {name}: it would run as a program");
+        return Err(format!(
+            "{DANGEROUS_ATTACHMENT_PREFIX}{name} could run code on this computer"
+        ));
+    }
+
+    let bytes = load_bytes(&app, source).await?;
+
+    let dir = temp_root().join(sanitize_filename(&message_id, "message"));
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create temp dir: {e}"))?;
+    let path = dir.join(name);
+
+    write_read_only(&path, &bytes)?;
+    app.state::<OpenedAttachments>()
+        .0
+        .lock()
+        .unwrap()
+        .insert(path.clone());
+
+    let path_str = path.to_string_lossy().into_owned();
+    app.opener()
+        .open_path(path_str.clone(), None::<&str>)
+        .map_err(|e| format!("Failed to open attachment: {e}"))?;
+    Ok(path_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_filename() {
+        assert_eq!(sanitize_filename("report.pdf", "x"), "report.pdf");
+        assert_eq!(sanitize_filename("../../etc/passwd", "x"), "_.._etc_passwd");
+        assert_eq!(sanitize_filename("a\\b:c*?.txt", "x"), "a_b_c__.txt");
+        assert_eq!(sanitize_filename("..", "attachment"), "attachment");
+        assert_eq!(sanitize_filename("  ", "attachment"), "attachment");
+        assert_eq!(sanitize_filename("CON.txt", "x"), "_CON.txt");
+        assert_eq!(sanitize_filename("com1", "x"), "_com1");
+        assert_eq!(sanitize_filename("computer.txt", "x"), "computer.txt");
+    }
+
+    #[test]
+    fn test_sanitize_filename_truncates_keeping_extension() {
+        let long = format!("{}.docx", "a".repeat(300));
+        let result = sanitize_filename(&long, "x");
+        assert_eq!(result.chars().count(), MAX_FILENAME_CHARS);
+        assert!(result.ends_with(".docx"));
+    }
+
+    #[test]
+    fn test_is_dangerous() {
+        assert!(is_dangerous("setup.exe"));
+        assert!(is_dangerous("invoice.pdf.EXE"));
+        assert!(is_dangerous("run.ps1"));
+        assert!(is_dangerous("Launcher.desktop"));
+        assert!(is_dangerous(&sanitize_filename("script.vbs. . ", "x")));
+        assert!(!is_dangerous("report.pdf"));
+        assert!(!is_dangerous("exe"));
+        assert!(!is_dangerous("notes.json"));
+    }
+
+    #[test]
+    fn test_decode_any_base64() {
+        assert_eq!(decode_any_base64("aGk_Pz8-").unwrap(), b"hi???>");
+        assert_eq!(decode_any_base64("aGk/Pz8+").unwrap(), b"hi???>");
+        assert_eq!(decode_any_base64("aGk=").unwrap(), b"hi");
+    }
+
+    #[test]
+    fn test_write_read_only_replaces_existing() {
+        let dir = std::env::temp_dir().join(format!("sora-open-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("a.txt");
+
+        write_read_only(&path, b"one").unwrap();
+        assert!(std::fs::metadata(&path).unwrap().permissions().readonly());
+        write_read_only(&path, b"two").unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"two");
+
+        remove_tree(&dir);
+        assert!(!dir.exists());
+    }
+}
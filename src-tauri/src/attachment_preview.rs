@@ -0,0 +1,198 @@
+//! Preview thumbnails for received attachments in the local cache.
+//!
+//! Thumbnails are rendered here and returned as small PNG data URLs, so the
+//! message view can show previews without pulling whole files into the
+//! webview.
+//!
+//! There is no PDF rasterizer in the app, so a PDF's thumbnail is not a
+//! rendering of its first page but the largest JPEG image embedded in that
+//! page. That covers scans and photo documents; text-only PDFs get no
+//! preview and the UI keeps its icon.
+
+use std::path::{Component, Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::attachments::preview_data_url;
+
+/// Default longest edge of a preview thumbnail.
+const DEFAULT_PREVIEW_SIZE: u32 = 240;
+
+/// Resolve a cache path (relative to the app data dir, as stored in
+/// `attachments.local_path`) without letting it escape that directory.
+pub(crate) fn resolve_cache_path(app: &AppHandle, local_path: &str) -> Result<PathBuf, String> {
+    let relative = checked_relative_path(local_path)?;
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("No app data dir: {e}"))?;
+    Ok(data_dir.join(relative))
+}
+
+/// `local_path` as a relative path made of plain names only: no root,
+/// prefix, `.` or `..`.
+fn checked_relative_path(local_path: &str) -> Result<&Path, String> {
+    let relative = Path::new(local_path);
+    if local_path.is_empty()
+        || relative
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_)))
+    {
+        return Err(format!("Invalid attachment cache path: {local_path}"));
+    }
+    Ok(relative)
+}
+
+/// Decode the largest JPEG image embedded in the first page of a PDF, used
+/// as the PDF's thumbnail.
+fn pdf_embedded_image(bytes: &[u8]) -> Option<image::DynamicImage> {
+    let doc = lopdf::Document::load_mem(bytes).ok()?;
+    let (_, page_id) = doc.get_pages().into_iter().next()?;
+    let images = doc.get_page_images(page_id).ok()?;
+
+    images
+        .into_iter()
+        .filter(|img| {
+            img.filters
+                .as_ref()
+                .is_some_and(|f| f.len() == 1 && f[0] == "DCTDecode")
+        })
+        .max_by_key(|img| img.width * img.height)
+        .and_then(|img| image::load_from_memory(img.content).ok())
+}
+
+fn render_preview(bytes: &[u8], is_pdf: bool, max_size: u32) -> Option<String> {
+    let img = if is_pdf {
+        pdf_embedded_image(bytes)?
+    } else {
+        image::load_from_memory(bytes).ok()?
+    };
+    preview_data_url(&img, max_size).ok()
+}
+
+/// Render a preview thumbnail for a cached image, or for a PDF from the
+/// largest image embedded in its first page.
+///
+/// Returns `None` when the file type can't be previewed.
+#[tauri::command]
+#[specta::specta]
+pub async fn attachment_preview_thumbnail(
+    app: AppHandle,
+    local_path: String,
+    mime_type: Option<String>,
+    max_size: Option<u32>,
+) -> Result<Option<String>, String> {
+    let path = resolve_cache_path(&app, &local_path)?;
+    let max_size = max_size.unwrap_or(DEFAULT_PREVIEW_SIZE);
+    let is_pdf = mime_type.as_deref() == Some("application/pdf");
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let bytes =
+            std::fs::read(&path).map_err(|e| format!("Failed to read cached attachment: {e}"))?;
+        Ok(render_preview(&bytes, is_pdf, max_size))
+    })
+    .await
+    .map_err(|e| format!("Thumbnail task failed: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{DynamicImage, ImageFormat, RgbImage};
+    use lopdf::{dictionary, Document, Object, Stream};
+    use std::io::Cursor;
+
+    fn encoded(width: u32, height: u32, format: ImageFormat) -> Vec<u8> {
+        let img =
+            DynamicImage::ImageRgb8(RgbImage::from_pixel(width, height, [200, 40, 40].into()));
+        let mut out = Cursor::new(Vec::new());
+        img.write_to(&mut out, format).unwrap();
+        out.into_inner()
+    }
+
+    /// A one-page PDF drawing each JPEG as an image XObject.
+    fn pdf_with_jpegs(jpegs: &[(u32, u32)]) -> Vec<u8> {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let mut xobjects = lopdf::Dictionary::new();
+        for (i, &(width, height)) in jpegs.iter().enumerate() {
+            let image = Stream::new(
+                dictionary! {
+                    "Type" => "XObject",
+                    "Subtype" => "Image",
+                    "Width" => width as i64,
+                    "Height" => height as i64,
+                    "ColorSpace" => "DeviceRGB",
+                    "BitsPerComponent" => 8,
+                    "Filter" => "DCTDecode",
+                },
+                encoded(width, height, ImageFormat::Jpeg),
+            );
+            xobjects.set(format!("Im{i}"), doc.add_object(image));
+        }
+        let content = doc.add_object(Stream::new(dictionary! {}, Vec::new()));
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+            "Resources" => dictionary! { "XObject" => xobjects },
+            "Contents" => content,
+        });
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => vec![page_id.into()],
+                "Count" => 1,
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+        let mut out = Vec::new();
+        doc.save_to(&mut out).unwrap();
+        out
+    }
+
+    fn decode_data_url(url: &str) -> DynamicImage {
+        use base64::Engine;
+        let b64 = url.strip_prefix("data:image/png;base64,").unwrap();
+        let png = base64::engine::general_purpose::STANDARD
+            .decode(b64)
+            .unwrap();
+        image::load_from_memory(&png).unwrap()
+    }
+
+    #[test]
+    fn test_checked_relative_path() {
+        assert!(checked_relative_path("attachments/abc/report.pdf").is_ok());
+        assert!(checked_relative_path("../secrets").is_err());
+        assert!(checked_relative_path("attachments/../../etc/passwd").is_err());
+        assert!(checked_relative_path("./attachments/a.png").is_err());
+        assert!(checked_relative_path("/etc/passwd").is_err());
+        assert!(checked_relative_path("").is_err());
+    }
+
+    #[test]
+    fn test_render_preview_image() {
+        let png = encoded(600, 300, ImageFormat::Png);
+        let img = decode_data_url(&render_preview(&png, false, 240).unwrap());
+        assert_eq!((img.width(), img.height()), (240, 120));
+
+        assert_eq!(render_preview(b"not an image", false, 240), None);
+    }
+
+    #[test]
+    fn test_render_preview_pdf_uses_largest_embedded_jpeg() {
+        let pdf = pdf_with_jpegs(&[(40, 20), (100, 50)]);
+        let img = decode_data_url(&render_preview(&pdf, true, 240).unwrap());
+        assert_eq!((img.width(), img.height()), (100, 50));
+    }
+
+    #[test]
+    fn test_render_preview_pdf_without_images() {
+        assert_eq!(render_preview(&pdf_with_jpegs(&[]), true, 240), None);
+        assert_eq!(render_preview(b"%PDF-1.5 truncated", true, 240), None);
+    }
+}
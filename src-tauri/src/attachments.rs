@@ -0,0 +1,1118 @@
+//! Compose attachment staging area.
+//!
+//! Files picked for a draft are registered here by path instead of being
+//! read into the webview as base64. The frontend's MIME builder emits a
+//! placeholder part (`X-Sora-Staged-Attachment: <id>`) for each one, and the
+//! backend swaps in the real headers and body when the message is sent or
+//! appended, so large files never travel through IPC.
+//!
+//! Images pasted from the system clipboard are staged the same way, as
+//! inline parts with a generated Content-ID.
+//!
+//! A message forwarded as an attachment is staged as a reference to its
+//! IMAP folder and UID. Servers that can copy it themselves (CATENATE, BURL)
+//! are given a URL instead of the content; otherwise it is downloaded once.
+
+use base64::Engine;
+use serde::Serialize;
+use specta::Type;
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri_plugin_fs::FsExt;
+
+use crate::accounts::{require_trusted_window, AccountRegistry};
+use crate::imap::client as imap_client;
+use crate::imap::types::ImapConfig;
+use crate::smtp::client::decode_base64url;
+
+/// Header line the MIME builder emits in place of a staged attachment part.
+pub const STAGED_PART_HEADER: &str = "X-Sora-Staged-Attachment: ";
+
+/// MIME type of a message forwarded as an attachment.
+const MESSAGE_MIME_TYPE: &str = "message/rfc822";
+
+/// Longest edge of image thumbnails returned by `attachment_stage`.
+const THUMBNAIL_SIZE: u32 = 128;
+/// Longest edge of the preview shown in the editor for pasted images.
+const INLINE_PREVIEW_SIZE: u32 = 1024;
+/// Defaults for `attachment_optimize_images`.
+const OPTIMIZE_MAX_EDGE: u32 = 1600;
+const OPTIMIZE_JPEG_QUALITY: u8 = 85;
+
+#[derive(Debug, Clone)]
+enum StagedSource {
+    File(PathBuf),
+    Memory(Vec<u8>),
+    /// A message on the IMAP server, attached as `message/rfc822`.
+    /// `content` is filled in if it ever has to be downloaded.
+    ServerMessage {
+        folder: String,
+        uid: u32,
+        content: Option<Vec<u8>>,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct StagedAttachment {
+    source: StagedSource,
+    filename: String,
+    mime_type: String,
+    size: u64,
+    /// Set for inline parts referenced from the HTML body as `cid:`.
+    content_id: Option<String>,
+}
+
+impl StagedAttachment {
+    fn read(&self) -> Result<Vec<u8>, String> {
+        match &self.source {
+            StagedSource::File(path) => std::fs::read(path)
+                .map_err(|e| format!("Failed to read attachment {}: {e}", self.filename)),
+            StagedSource::Memory(bytes) => Ok(bytes.clone()),
+            StagedSource::ServerMessage {
+                content: Some(bytes),
+                ..
+            } => Ok(bytes.clone()),
+            StagedSource::ServerMessage { content: None, .. } => Err(format!(
+                "{} hasn't been downloaded from the mail server",
+                self.filename
+            )),
+        }
+    }
+}
+
+/// Metadata returned to the composer for a staged attachment.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct StagedAttachmentInfo {
+    pub id: String,
+    pub filename: String,
+    pub mime_type: String,
+    pub size: u64,
+    /// PNG data URL for image attachments.
+    pub thumbnail: Option<String>,
+}
+
+/// A pasted image staged as an inline part.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct StagedInlineImage {
+    pub id: String,
+    pub content_id: String,
+    /// `cid:` URL to reference the part from the HTML body.
+    pub cid_url: String,
+    pub mime_type: String,
+    pub size: u64,
+    /// PNG data URL for displaying the image in the editor.
+    pub preview: String,
+}
+
+/// Before/after sizes for one image passed to `attachment_optimize_images`.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ImageOptimizeResult {
+    pub id: String,
+    pub filename: String,
+    pub mime_type: String,
+    pub original_size: u64,
+    /// Equal to `original_size` when the image was left as is.
+    pub optimized_size: u64,
+}
+
+/// Managed registry of staged attachments, keyed by id.
+#[derive(Default)]
+pub struct AttachmentStaging {
+    items: Mutex<HashMap<String, StagedAttachment>>,
+    next_id: Mutex<u64>,
+}
+
+impl AttachmentStaging {
+    fn next_id(&self) -> String {
+        let mut next_id = self.next_id.lock().unwrap();
+        *next_id += 1;
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        format!("att-{nanos:x}-{next_id}")
+    }
+
+    fn insert(&self, item: StagedAttachment) -> String {
+        let id = self.next_id();
+        self.insert_with_id(id.clone(), item);
+        id
+    }
+
+    fn insert_with_id(&self, id: String, item: StagedAttachment) {
+        self.items.lock().unwrap().insert(id, item);
+    }
+
+    fn get(&self, id: &str) -> Option<StagedAttachment> {
+        self.items.lock().unwrap().get(id).cloned()
+    }
+
+    fn replace(&self, id: &str, item: StagedAttachment) {
+        if let Some(existing) = self.items.lock().unwrap().get_mut(id) {
+            *existing = item;
+        }
+    }
+}
+
+/// Guess a MIME type from the file extension.
+pub(crate) fn mime_from_extension(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "svg" => "image/svg+xml",
+        "heic" => "image/heic",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "txt" => "text/plain",
+        "csv" => "text/csv",
+        "html" | "htm" => "text/html",
+        "ics" => "text/calendar",
+        "json" => "application/json",
+        "doc" => "application/msword",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xls" => "application/vnd.ms-excel",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "ppt" => "application/vnd.ms-powerpoint",
+        "pptx" => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        "mp3" => "audio/mpeg",
+        "mp4" => "video/mp4",
+        "mov" => "video/quicktime",
+        _ => "application/octet-stream",
+    }
+}
+
+fn encode_png(img: &image::DynamicImage) -> Result<Vec<u8>, String> {
+    let mut png = Vec::new();
+    img.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| format!("PNG encode failed: {e}"))?;
+    Ok(png)
+}
+
+/// Render a PNG data URL of an image scaled down to fit `max_edge`.
+pub(crate) fn preview_data_url(img: &image::DynamicImage, max_edge: u32) -> Result<String, String> {
+    let png = if img.width() > max_edge || img.height() > max_edge {
+        encode_png(&img.thumbnail(max_edge, max_edge))?
+    } else {
+        encode_png(img)?
+    };
+    let b64 = base64::engine::general_purpose::STANDARD.encode(png);
+    Ok(format!("data:image/png;base64,{b64}"))
+}
+
+/// Render a small PNG preview of an image file as a data URL.
+fn image_thumbnail(path: &Path) -> Option<String> {
+    let img = image::open(path).ok()?;
+    preview_data_url(&img, THUMBNAIL_SIZE).ok()
+}
+
+/// Downscale an image to fit `max_edge` and re-encode it as JPEG.
+///
+/// Returns `None` when the result wouldn't be smaller, or for images that
+/// don't survive the conversion (animated GIFs, transparency).
+fn optimize_image(bytes: &[u8], max_edge: u32, quality: u8) -> Result<Option<Vec<u8>>, String> {
+    let format = image::guess_format(bytes).map_err(|e| format!("Unknown image format: {e}"))?;
+    if format == image::ImageFormat::Gif {
+        return Ok(None);
+    }
+    let img = image::load_from_memory_with_format(bytes, format)
+        .map_err(|e| format!("Image decode failed: {e}"))?;
+    if img.color().has_alpha() && img.to_rgba8().pixels().any(|p| p[3] < u8::MAX) {
+        return Ok(None);
+    }
+
+    let img = if img.width() > max_edge || img.height() > max_edge {
+        img.resize(max_edge, max_edge, image::imageops::FilterType::Lanczos3)
+    } else {
+        img
+    };
+
+    let mut jpeg = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg, quality)
+        .encode_image(&img.to_rgb8())
+        .map_err(|e| format!("JPEG encode failed: {e}"))?;
+
+    Ok((jpeg.len() < bytes.len()).then_some(jpeg))
+}
+
+/// Swap a file name's extension for `.jpg`.
+fn jpeg_filename(filename: &str) -> String {
+    match filename.rsplit_once('.') {
+        Some((stem, _)) if !stem.is_empty() => format!("{stem}.jpg"),
+        _ => format!("{filename}.jpg"),
+    }
+}
+
+/// Make `name` unique among `taken` by appending " (2)", " (3)", ... before the extension.
+fn unique_entry_name(name: &str, taken: &mut HashSet<String>) -> String {
+    let mut candidate = name.to_string();
+    let (stem, ext) = match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{ext}")),
+        _ => (name, String::new()),
+    };
+    let mut n = 2;
+    while taken.contains(&candidate) {
+        candidate = format!("{stem} ({n}){ext}");
+        n += 1;
+    }
+    taken.insert(candidate.clone());
+    candidate
+}
+
+/// Build a zip archive from `(name, bytes)` entries, AES-256 encrypted when
+/// a password is given.
+fn build_zip(entries: &[(String, Vec<u8>)], password: Option<&str>) -> Result<Vec<u8>, String> {
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    let options = match password {
+        Some(password) => options.with_aes_encryption(zip::AesMode::Aes256, password),
+        None => options,
+    };
+
+    let mut taken = HashSet::new();
+    for (name, bytes) in entries {
+        let name = unique_entry_name(name, &mut taken);
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| format!("Failed to add {name} to zip: {e}"))?;
+        zip.write_all(bytes)
+            .map_err(|e| format!("Failed to add {name} to zip: {e}"))?;
+    }
+
+    let cursor = zip
+        .finish()
+        .map_err(|e| format!("Failed to finish zip: {e}"))?;
+    Ok(cursor.into_inner())
+}
+
+/// MIME headers for a staged attachment part, including the blank line
+/// that ends the header block.
+fn part_headers(item: &StagedAttachment) -> String {
+    if item.mime_type == MESSAGE_MIME_TYPE {
+        // message/rfc822 parts can't be base64 encoded (RFC 2046)
+        return format!(
+            "Content-Type: {MESSAGE_MIME_TYPE}; name=\"{0}\"\r\n\
+             Content-Disposition: attachment; filename=\"{0}\"\r\n\r\n",
+            item.filename
+        );
+    }
+
+    let mut headers = format!(
+        "Content-Type: {}; name=\"{}\"\r\nContent-Transfer-Encoding: base64\r\n",
+        item.mime_type, item.filename
+    );
+    match &item.content_id {
+        Some(cid) => headers.push_str(&format!(
+            "Content-ID: <{cid}>\r\nContent-Disposition: inline; filename=\"{}\"\r\n\r\n",
+            item.filename
+        )),
+        None => headers.push_str(&format!(
+            "Content-Disposition: attachment; filename=\"{}\"\r\n\r\n",
+            item.filename
+        )),
+    }
+    headers
+}
+
+/// Write the body of a staged attachment: base64 wrapped at 76 columns,
+/// or the message itself for `message/rfc822`.
+fn write_body(out: &mut Vec<u8>, item: &StagedAttachment) -> Result<(), String> {
+    let bytes = item.read()?;
+    if item.mime_type == MESSAGE_MIME_TYPE {
+        out.extend_from_slice(&bytes);
+        if !bytes.ends_with(b"\n") {
+            out.extend_from_slice(b"\r\n");
+        }
+        return Ok(());
+    }
+    write_base64_lines(out, &bytes);
+    Ok(())
+}
+
+/// Append `bytes` as base64 wrapped at 76 columns. Input split on a multiple
+/// of 57 bytes encodes to whole lines, so a body can be written in blocks.
+pub fn write_base64_lines(out: &mut Vec<u8>, bytes: &[u8]) {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+    for chunk in encoded.as_bytes().chunks(76) {
+        out.extend_from_slice(chunk);
+        out.extend_from_slice(b"\r\n");
+    }
+}
+
+/// A piece of an expanded message: literal bytes, or an IMAP URL for
+/// content the server can fetch itself.
+#[derive(Debug, PartialEq)]
+pub enum MessageChunk {
+    Bytes(Vec<u8>),
+    ServerUrl(String),
+}
+
+/// A piece of an expanded message for streaming: literal bytes, or a staged
+/// file whose base64 body is encoded from disk as it's sent.
+#[derive(Debug, PartialEq)]
+pub enum StreamChunk {
+    Bytes(Vec<u8>),
+    File { path: PathBuf, filename: String },
+}
+
+/// A raw message split at its staged-attachment placeholders.
+enum Segment {
+    Bytes(Vec<u8>),
+    Part(String, StagedAttachment),
+}
+
+/// Split a raw message into literal bytes and the staged attachments its
+/// placeholder parts refer to. Each placeholder (with the empty line that
+/// ends its header block) is dropped; the generated part supplies its own.
+fn split_placeholders(raw: &[u8], staging: &AttachmentStaging) -> Result<Vec<Segment>, String> {
+    let marker = STAGED_PART_HEADER.as_bytes();
+    let mut segments = Vec::new();
+    let mut out = Vec::with_capacity(raw.len());
+    let mut lines = raw.split(|&b| b == b'\n').peekable();
+    while let Some(line) = lines.next() {
+        let trimmed = line.strip_suffix(b"\r").unwrap_or(line);
+        let Some(id) = trimmed.strip_prefix(marker) else {
+            out.extend_from_slice(line);
+            if lines.peek().is_some() {
+                out.push(b'\n');
+            }
+            continue;
+        };
+
+        let id = String::from_utf8_lossy(id).trim().to_string();
+        let item = staging
+            .get(&id)
+            .ok_or_else(|| format!("Staged attachment {id} not found"))?;
+        if lines
+            .peek()
+            .is_some_and(|next| next.strip_suffix(b"\r").unwrap_or(next).is_empty())
+        {
+            lines.next();
+        }
+        segments.push(Segment::Bytes(std::mem::take(&mut out)));
+        segments.push(Segment::Part(id, item));
+    }
+    segments.push(Segment::Bytes(out));
+    Ok(segments)
+}
+
+/// Replace every staged-attachment placeholder part in a raw message with
+/// the attachment's real headers and base64 body.
+pub fn expand_staged_parts(raw: &[u8], staging: &AttachmentStaging) -> Result<Vec<u8>, String> {
+    let chunks = expand_staged_parts_with_urls(raw, staging, &HashMap::new())?;
+    Ok(chunks
+        .into_iter()
+        .filter_map(|chunk| match chunk {
+            MessageChunk::Bytes(bytes) => Some(bytes),
+            MessageChunk::ServerUrl(_) => None,
+        })
+        .collect::<Vec<_>>()
+        .concat())
+}
+
+/// Like [`expand_staged_parts`], but attachments listed in `urls` (staged
+/// id → IMAP URL) only get their headers written; the body is left as a
+/// [`MessageChunk::ServerUrl`] for the server to fetch (CATENATE, BURL).
+pub fn expand_staged_parts_with_urls(
+    raw: &[u8],
+    staging: &AttachmentStaging,
+    urls: &HashMap<String, String>,
+) -> Result<Vec<MessageChunk>, String> {
+    let mut chunks = Vec::new();
+    let mut out = Vec::with_capacity(raw.len());
+    for segment in split_placeholders(raw, staging)? {
+        match segment {
+            Segment::Bytes(bytes) => out.extend_from_slice(&bytes),
+            Segment::Part(id, item) => {
+                out.extend_from_slice(part_headers(&item).as_bytes());
+                match urls.get(&id) {
+                    Some(url) => {
+                        chunks.push(MessageChunk::Bytes(std::mem::take(&mut out)));
+                        chunks.push(MessageChunk::ServerUrl(url.clone()));
+                    }
+                    None => write_body(&mut out, &item)?,
+                }
+            }
+        }
+    }
+    chunks.push(MessageChunk::Bytes(out));
+    Ok(chunks)
+}
+
+/// Like [`expand_staged_parts`], but the bodies of staged files are left as
+/// [`StreamChunk::File`] so they can be read from disk while sending rather
+/// than held in memory.
+pub fn expand_staged_parts_for_streaming(
+    raw: &[u8],
+    staging: &AttachmentStaging,
+) -> Result<Vec<StreamChunk>, String> {
+    let mut chunks = Vec::new();
+    let mut out = Vec::new();
+    for segment in split_placeholders(raw, staging)? {
+        match segment {
+            Segment::Bytes(bytes) => out.extend_from_slice(&bytes),
+            Segment::Part(_, item) => {
+                out.extend_from_slice(part_headers(&item).as_bytes());
+                match &item.source {
+                    StagedSource::File(path) if item.mime_type != MESSAGE_MIME_TYPE => {
+                        chunks.push(StreamChunk::Bytes(std::mem::take(&mut out)));
+                        chunks.push(StreamChunk::File {
+                            path: path.clone(),
+                            filename: item.filename.clone(),
+                        });
+                    }
+                    _ => write_body(&mut out, &item)?,
+                }
+            }
+        }
+    }
+    chunks.push(StreamChunk::Bytes(out));
+    Ok(chunks)
+}
+
+/// Total size of the staged attachments a raw message refers to.
+pub fn staged_size(raw: &[u8], staging: &AttachmentStaging) -> u64 {
+    placeholder_ids(raw)
+        .iter()
+        .filter_map(|id| staging.get(id))
+        .map(|item| item.size)
+        .sum()
+}
+
+/// Ids of the staged-attachment placeholders in a raw message.
+fn placeholder_ids(raw: &[u8]) -> Vec<String> {
+    let marker = STAGED_PART_HEADER.as_bytes();
+    raw.split(|&b| b == b'\n')
+        .filter_map(|line| line.strip_prefix(marker))
+        .map(|id| String::from_utf8_lossy(id).trim().to_string())
+        .collect()
+}
+
+/// A staged attachment that refers to a message on the IMAP server.
+#[derive(Debug, Clone)]
+pub struct ServerMessageRef {
+    pub id: String,
+    pub folder: String,
+    pub uid: u32,
+}
+
+/// Server messages referenced by the placeholders in a raw message.
+pub fn server_messages(raw: &[u8], staging: &AttachmentStaging) -> Vec<ServerMessageRef> {
+    placeholder_ids(raw)
+        .into_iter()
+        .filter_map(|id| match staging.get(&id)?.source {
+            StagedSource::ServerMessage { folder, uid, .. } => {
+                Some(ServerMessageRef { id, folder, uid })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Download any server messages referenced by a raw message that haven't
+/// been fetched yet, so the message can be expanded locally.
+pub async fn download_server_messages(
+    config: &ImapConfig,
+    raw: &[u8],
+    staging: &AttachmentStaging,
+) -> Result<(), String> {
+    let missing: Vec<ServerMessageRef> = server_messages(raw, staging)
+        .into_iter()
+        .filter(|r| {
+            matches!(
+                staging.get(&r.id).map(|item| item.source),
+                Some(StagedSource::ServerMessage { content: None, .. })
+            )
+        })
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let mut session = imap_client::connect(config).await?;
+    for message in missing {
+        let bytes =
+            imap_client::fetch_raw_message_bytes(&mut session, &message.folder, message.uid)
+                .await?;
+        if let Some(item) = staging.items.lock().unwrap().get_mut(&message.id) {
+            if let StagedSource::ServerMessage { content, .. } = &mut item.source {
+                *content = Some(bytes);
+            }
+        }
+    }
+    let _ = session.logout().await;
+    Ok(())
+}
+
+/// Expand staged parts in a base64url-encoded message (the wire format the
+/// frontend uses for raw emails).
+pub fn expand_staged_base64url(
+    raw_base64url: &str,
+    staging: &AttachmentStaging,
+) -> Result<Vec<u8>, String> {
+    let raw = decode_base64url(raw_base64url)?;
+    expand_staged_parts(&raw, staging)
+}
+
+/// Stage a file from disk for the current draft. Only files the user
+/// picked in a file dialog or dropped on the window are accepted; both put
+/// the path in the fs plugin's scope.
+#[tauri::command]
+#[specta::specta]
+pub fn attachment_stage(
+    window: tauri::Window,
+    staging: tauri::State<'_, AttachmentStaging>,
+    path: String,
+) -> Result<StagedAttachmentInfo, String> {
+    require_trusted_window(&window, "attachment_stage")?;
+    let path = PathBuf::from(path);
+    if !window.fs_scope().is_allowed(&path) {
+        log::warn!(
+            "Refused to stage {}: not chosen by the user",
+            path.display()
+        );
+        return Err(format!("{} wasn't chosen in a file dialog", path.display()));
+    }
+    let metadata =
+        std::fs::metadata(&path).map_err(|e| format!("Cannot read {}: {e}", path.display()))?;
+    if !metadata.is_file() {
+        return Err(format!("{} is not a file", path.display()));
+    }
+
+    let filename = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "attachment".to_string());
+    let mime_type = mime_from_extension(&path).to_string();
+    let thumbnail = if mime_type.starts_with("image/") {
+        image_thumbnail(&path)
+    } else {
+        None
+    };
+
+    let id = staging.insert(StagedAttachment {
+        source: StagedSource::File(path),
+        filename: filename.clone(),
+        mime_type: mime_type.clone(),
+        size: metadata.len(),
+        content_id: None,
+    });
+
+    Ok(StagedAttachmentInfo {
+        id,
+        filename,
+        mime_type,
+        size: metadata.len(),
+        thumbnail,
+    })
+}
+
+/// Stage a message on the IMAP server to be forwarded as an attachment.
+/// Nothing is downloaded until a server can't copy it by URL.
+#[tauri::command]
+#[specta::specta]
+pub fn attachment_stage_server_message(
+    window: tauri::Window,
+    staging: tauri::State<'_, AttachmentStaging>,
+    folder: String,
+    uid: u32,
+    filename: String,
+    size: u64,
+) -> Result<StagedAttachmentInfo, String> {
+    require_trusted_window(&window, "attachment_stage_server_message")?;
+    let id = staging.insert(StagedAttachment {
+        source: StagedSource::ServerMessage {
+            folder,
+            uid,
+            content: None,
+        },
+        filename: filename.clone(),
+        mime_type: MESSAGE_MIME_TYPE.to_string(),
+        size,
+        content_id: None,
+    });
+
+    Ok(StagedAttachmentInfo {
+        id,
+        filename,
+        mime_type: MESSAGE_MIME_TYPE.to_string(),
+        size,
+        thumbnail: None,
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn attachment_unstage(staging: tauri::State<'_, AttachmentStaging>, id: String) {
+    staging.items.lock().unwrap().remove(&id);
+}
+
+/// Read a staged attachment as standard base64, for callers that have to
+/// persist the content (e.g. scheduled sends that outlive the session).
+/// `account_id` is needed to download a forwarded server message.
+///
+/// Only content staged in the app (pasted, resized, zipped or forwarded) can
+/// be read back; picked files are read from their path by the frontend, so
+/// this can't be used to read files outside the fs scope.
+#[tauri::command]
+#[specta::specta]
+pub async fn attachment_read(
+    window: tauri::Window,
+    staging: tauri::State<'_, AttachmentStaging>,
+    accounts: tauri::State<'_, AccountRegistry>,
+    id: String,
+    account_id: Option<String>,
+) -> Result<String, String> {
+    require_trusted_window(&window, "attachment_read")?;
+    let item = staging
+        .get(&id)
+        .ok_or_else(|| format!("Staged attachment {id} not found"))?;
+    if let StagedSource::File(_) = item.source {
+        return Err(format!(
+            "{} is a file on disk; read it from its path",
+            item.filename
+        ));
+    }
+    if let Some(account_id) = &account_id {
+        let config = accounts.imap(account_id).await?;
+        let placeholder = format!("{STAGED_PART_HEADER}{id}");
+        download_server_messages(&config, placeholder.as_bytes(), &staging).await?;
+    }
+    let item = staging
+        .get(&id)
+        .ok_or_else(|| format!("Staged attachment {id} not found"))?;
+    let bytes = item.read()?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+/// Resize and recompress staged image attachments in place (by default to
+/// at most 1600px at JPEG quality 85). Non-image ids are skipped.
+#[tauri::command]
+#[specta::specta]
+pub fn attachment_optimize_images(
+    window: tauri::Window,
+    staging: tauri::State<'_, AttachmentStaging>,
+    ids: Vec<String>,
+    max_edge: Option<u32>,
+    quality: Option<u8>,
+) -> Result<Vec<ImageOptimizeResult>, String> {
+    require_trusted_window(&window, "attachment_optimize_images")?;
+    let max_edge = max_edge.unwrap_or(OPTIMIZE_MAX_EDGE);
+    let quality = quality.unwrap_or(OPTIMIZE_JPEG_QUALITY).clamp(1, 100);
+
+    let mut results = Vec::new();
+    for id in ids {
+        let Some(item) = staging.get(&id) else {
+            continue;
+        };
+        if !item.mime_type.starts_with("image/") {
+            continue;
+        }
+
+        let original = item.read()?;
+        let original_size = original.len() as u64;
+        let optimized = match optimize_image(&original, max_edge, quality) {
+            Ok(optimized) => optimized,
+            Err(e) => {
+                log::warn!("Skipping image optimization for {}: {e}", item.filename);
+                None
+            }
+        };
+
+        let Some(jpeg) = optimized else {
+            results.push(ImageOptimizeResult {
+                id,
+                filename: item.filename,
+                mime_type: item.mime_type,
+                original_size,
+                optimized_size: original_size,
+            });
+            continue;
+        };
+
+        let optimized_size = jpeg.len() as u64;
+        let updated = StagedAttachment {
+            source: StagedSource::Memory(jpeg),
+            filename: jpeg_filename(&item.filename),
+            mime_type: "image/jpeg".to_string(),
+            size: optimized_size,
+            content_id: item.content_id,
+        };
+
+        results.push(ImageOptimizeResult {
+            id: id.clone(),
+            filename: updated.filename.clone(),
+            mime_type: updated.mime_type.clone(),
+            original_size,
+            optimized_size,
+        });
+        staging.replace(&id, updated);
+    }
+    Ok(results)
+}
+
+/// Bundle staged attachments into a single zip, optionally password
+/// protected (AES-256). The bundled items are replaced by the archive.
+#[tauri::command]
+#[specta::specta]
+pub fn attachment_zip(
+    window: tauri::Window,
+    staging: tauri::State<'_, AttachmentStaging>,
+    ids: Vec<String>,
+    archive_name: Option<String>,
+    password: Option<String>,
+) -> Result<StagedAttachmentInfo, String> {
+    require_trusted_window(&window, "attachment_zip")?;
+    if ids.is_empty() {
+        return Err("No attachments to zip".to_string());
+    }
+
+    let mut entries = Vec::with_capacity(ids.len());
+    for id in &ids {
+        let item = staging
+            .get(id)
+            .ok_or_else(|| format!("Staged attachment {id} not found"))?;
+        entries.push((item.filename.clone(), item.read()?));
+    }
+
+    let password = password.filter(|p| !p.is_empty());
+    let archive = build_zip(&entries, password.as_deref())?;
+
+    let name = archive_name
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| "attachments".to_string());
+    let filename = if name.to_ascii_lowercase().ends_with(".zip") {
+        name
+    } else {
+        format!("{name}.zip")
+    };
+
+    let size = archive.len() as u64;
+    let id = staging.insert(StagedAttachment {
+        source: StagedSource::Memory(archive),
+        filename: filename.clone(),
+        mime_type: "application/zip".to_string(),
+        size,
+        content_id: None,
+    });
+    {
+        let mut items = staging.items.lock().unwrap();
+        for old in &ids {
+            items.remove(old);
+        }
+    }
+
+    Ok(StagedAttachmentInfo {
+        id,
+        filename,
+        mime_type: "application/zip".to_string(),
+        size,
+        thumbnail: None,
+    })
+}
+
+/// Grab an image from the system clipboard and stage it as an inline part.
+#[tauri::command]
+#[specta::specta]
+pub fn clipboard_image_to_attachment(
+    window: tauri::Window,
+    staging: tauri::State<'_, AttachmentStaging>,
+) -> Result<StagedInlineImage, String> {
+    require_trusted_window(&window, "clipboard_image_to_attachment")?;
+    let mut clipboard =
+        arboard::Clipboard::new().map_err(|e| format!("Clipboard unavailable: {e}"))?;
+    let data = clipboard
+        .get_image()
+        .map_err(|e| format!("No image on clipboard: {e}"))?;
+
+    let rgba = image::RgbaImage::from_raw(
+        data.width as u32,
+        data.height as u32,
+        data.bytes.into_owned(),
+    )
+    .ok_or_else(|| "Clipboard image has an unexpected size".to_string())?;
+    let img = image::DynamicImage::ImageRgba8(rgba);
+
+    let png = encode_png(&img)?;
+    let preview = preview_data_url(&img, INLINE_PREVIEW_SIZE)?;
+
+    let id = staging.next_id();
+    let content_id = format!("{id}@velomail");
+    let size = png.len() as u64;
+    staging.insert_with_id(
+        id.clone(),
+        StagedAttachment {
+            source: StagedSource::Memory(png),
+            filename: format!("{id}.png"),
+            mime_type: "image/png".to_string(),
+            size,
+            content_id: Some(content_id.clone()),
+        },
+    );
+
+    Ok(StagedInlineImage {
+        id,
+        cid_url: format!("cid:{content_id}"),
+        content_id,
+        mime_type: "image/png".to_string(),
+        size,
+        preview,
+    })
+}
+
+/// Resolve staged parts for providers that send from the frontend (Gmail API).
+/// Returns the complete message, base64url-encoded.
+#[tauri::command]
+#[specta::specta]
+pub fn attachment_resolve_raw(
+    window: tauri::Window,
+    staging: tauri::State<'_, AttachmentStaging>,
+    raw_email: String,
+) -> Result<String, String> {
+    require_trusted_window(&window, "attachment_resolve_raw")?;
+    let raw = expand_staged_base64url(&raw_email, &staging)?;
+    Ok(base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(raw))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mime_from_extension() {
+        assert_eq!(mime_from_extension(Path::new("photo.JPG")), "image/jpeg");
+        assert_eq!(
+            mime_from_extension(Path::new("report.pdf")),
+            "application/pdf"
+        );
+        assert_eq!(
+            mime_from_extension(Path::new("noext")),
+            "application/octet-stream"
+        );
+    }
+
+    #[test]
+    fn test_expand_staged_parts() {
+        let path =
+            std::env::temp_dir().join(format!("sora-staging-test-{}.txt", std::process::id()));
+        std::fs::write(&path, b"hello").unwrap();
+
+        let staging = AttachmentStaging::default();
+        let id = staging.insert(StagedAttachment {
+            source: StagedSource::File(path.clone()),
+            filename: "hello.txt".to_string(),
+            mime_type: "text/plain".to_string(),
+            size: 5,
+            content_id: None,
+        });
+
+        let raw = format!("--b\r\n{STAGED_PART_HEADER}{id}\r\n\r\n--b--");
+        let expanded =
+            String::from_utf8(expand_staged_parts(raw.as_bytes(), &staging).unwrap()).unwrap();
+        assert_eq!(
+            expanded,
+            "--b\r\nContent-Type: text/plain; name=\"hello.txt\"\r\n\
+             Content-Transfer-Encoding: base64\r\n\
+             Content-Disposition: attachment; filename=\"hello.txt\"\r\n\r\n\
+             aGVsbG8=\r\n--b--"
+        );
+
+        let missing = format!("--b\r\n{STAGED_PART_HEADER}nope\r\n\r\n--b--");
+        assert!(expand_staged_parts(missing.as_bytes(), &staging).is_err());
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_expand_for_streaming() {
+        let path = PathBuf::from("/tmp/big.bin");
+        let staging = AttachmentStaging::default();
+        let file_id = staging.insert(StagedAttachment {
+            source: StagedSource::File(path.clone()),
+            filename: "big.bin".to_string(),
+            mime_type: "application/octet-stream".to_string(),
+            size: 30_000_000,
+            content_id: None,
+        });
+        let memory_id = staging.insert(StagedAttachment {
+            source: StagedSource::Memory(b"hi".to_vec()),
+            filename: "hi.txt".to_string(),
+            mime_type: "text/plain".to_string(),
+            size: 2,
+            content_id: None,
+        });
+
+        let raw = format!(
+            "--b\r\n{STAGED_PART_HEADER}{file_id}\r\n\r\n--b\r\n{STAGED_PART_HEADER}{memory_id}\r\n\r\n--b--"
+        );
+        let chunks = expand_staged_parts_for_streaming(raw.as_bytes(), &staging).unwrap();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(
+            chunks[1],
+            StreamChunk::File {
+                path,
+                filename: "big.bin".to_string()
+            }
+        );
+        let StreamChunk::Bytes(tail) = &chunks[2] else {
+            panic!("expected bytes")
+        };
+        let tail = String::from_utf8_lossy(tail);
+        assert!(tail.starts_with("--b\r\nContent-Type: text/plain;"));
+        assert!(tail.ends_with("aGk=\r\n--b--"));
+        assert_eq!(staged_size(raw.as_bytes(), &staging), 30_000_002);
+
+        // Blocks of whole 57-byte lines encode the same as the file at once
+        let data: Vec<u8> = (0..200u8).collect();
+        let mut whole = Vec::new();
+        write_base64_lines(&mut whole, &data);
+        let mut blocks = Vec::new();
+        for block in data.chunks(57 * 2) {
+            write_base64_lines(&mut blocks, block);
+        }
+        assert_eq!(whole, blocks);
+    }
+
+    #[test]
+    fn test_expand_with_server_urls() {
+        let staging = AttachmentStaging::default();
+        let id = staging.insert(StagedAttachment {
+            source: StagedSource::Memory(b"hello".to_vec()),
+            filename: "hello.txt".to_string(),
+            mime_type: "text/plain".to_string(),
+            size: 5,
+            content_id: None,
+        });
+
+        let raw = format!("--b\r\n{STAGED_PART_HEADER}{id}\r\n\r\n\r\n--b--");
+        let urls = HashMap::from([(id, "/Drafts;UIDVALIDITY=1/;UID=5/;SECTION=2".to_string())]);
+        let chunks = expand_staged_parts_with_urls(raw.as_bytes(), &staging, &urls).unwrap();
+        assert_eq!(chunks.len(), 3);
+        assert!(
+            matches!(&chunks[0], MessageChunk::Bytes(b) if b.ends_with(b"filename=\"hello.txt\"\r\n\r\n"))
+        );
+        assert_eq!(
+            chunks[1],
+            MessageChunk::ServerUrl("/Drafts;UIDVALIDITY=1/;UID=5/;SECTION=2".to_string())
+        );
+        assert_eq!(chunks[2], MessageChunk::Bytes(b"\r\n--b--".to_vec()));
+    }
+
+    #[test]
+    fn test_server_message_part() {
+        let staging = AttachmentStaging::default();
+        let id = staging.insert(StagedAttachment {
+            source: StagedSource::ServerMessage {
+                folder: "INBOX".to_string(),
+                uid: 7,
+                content: None,
+            },
+            filename: "Report.eml".to_string(),
+            mime_type: MESSAGE_MIME_TYPE.to_string(),
+            size: 12,
+            content_id: None,
+        });
+
+        let raw = format!("--b\r\n{STAGED_PART_HEADER}{id}\r\n\r\n\r\n--b--");
+        let refs = server_messages(raw.as_bytes(), &staging);
+        assert_eq!(refs.len(), 1);
+        assert_eq!((refs[0].folder.as_str(), refs[0].uid), ("INBOX", 7));
+        assert!(expand_staged_parts(raw.as_bytes(), &staging).is_err());
+
+        if let Some(item) = staging.items.lock().unwrap().get_mut(&id) {
+            item.source = StagedSource::ServerMessage {
+                folder: "INBOX".to_string(),
+                uid: 7,
+                content: Some(b"Subject: Hi\r\n\r\nHello".to_vec()),
+            };
+        }
+        let expanded =
+            String::from_utf8(expand_staged_parts(raw.as_bytes(), &staging).unwrap()).unwrap();
+        assert_eq!(
+            expanded,
+            "--b\r\nContent-Type: message/rfc822; name=\"Report.eml\"\r\n\
+             Content-Disposition: attachment; filename=\"Report.eml\"\r\n\r\n\
+             Subject: Hi\r\n\r\nHello\r\n\r\n--b--"
+        );
+    }
+
+    #[test]
+    fn test_optimize_image_downscales_to_jpeg() {
+        let img = image::RgbImage::from_fn(3200, 2000, |x, y| {
+            image::Rgb([(x % 256) as u8, (y % 256) as u8, ((x ^ y) % 256) as u8])
+        });
+        let png = encode_png(&image::DynamicImage::ImageRgb8(img)).unwrap();
+
+        let jpeg = optimize_image(&png, 1600, 85)
+            .unwrap()
+            .expect("should shrink");
+        assert!(jpeg.len() < png.len());
+        let decoded = image::load_from_memory(&jpeg).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (1600, 1000));
+    }
+
+    #[test]
+    fn test_jpeg_filename() {
+        assert_eq!(jpeg_filename("IMG_0001.HEIC.png"), "IMG_0001.HEIC.jpg");
+        assert_eq!(jpeg_filename("photo"), "photo.jpg");
+        assert_eq!(jpeg_filename(".hidden"), ".hidden.jpg");
+    }
+
+    #[test]
+    fn test_unique_entry_name() {
+        let mut taken = HashSet::new();
+        assert_eq!(unique_entry_name("a.txt", &mut taken), "a.txt");
+        assert_eq!(unique_entry_name("a.txt", &mut taken), "a (2).txt");
+        assert_eq!(unique_entry_name("a.txt", &mut taken), "a (3).txt");
+        assert_eq!(unique_entry_name("README", &mut taken), "README");
+        assert_eq!(unique_entry_name("README", &mut taken), "README (2)");
+    }
+
+    #[test]
+    fn test_build_zip_round_trip() {
+        let entries = vec![
+            ("a.txt".to_string(), b"first".to_vec()),
+            ("a.txt".to_string(), b"second".to_vec()),
+        ];
+
+        let plain = build_zip(&entries, None).unwrap();
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(plain)).unwrap();
+        assert_eq!(archive.len(), 2);
+        assert!(archive.by_name("a (2).txt").is_ok());
+
+        let encrypted = build_zip(&entries, Some("secret")).unwrap();
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(encrypted)).unwrap();
+        assert!(archive.by_index(0).is_err());
+        let mut file = archive.by_index_decrypt(0, b"secret").unwrap();
+        let mut content = String::new();
+        std::io::Read::read_to_string(&mut file, &mut content).unwrap();
+        assert_eq!(content, "first");
+    }
+
+    #[test]
+    fn test_expand_inline_part() {
+        let staging = AttachmentStaging::default();
+        let id = staging.insert(StagedAttachment {
+            source: StagedSource::Memory(b"png".to_vec()),
+            filename: "clip.png".to_string(),
+            mime_type: "image/png".to_string(),
+            size: 3,
+            content_id: Some("clip@velomail".to_string()),
+        });
+
+        let raw = format!("--r\r\n{STAGED_PART_HEADER}{id}\r\n\r\n\r\n--r--");
+        let expanded =
+            String::from_utf8(expand_staged_parts(raw.as_bytes(), &staging).unwrap()).unwrap();
+        assert!(expanded.contains("Content-ID: <clip@velomail>\r\nContent-Disposition: inline; filename=\"clip.png\"\r\n\r\ncG5n\r\n\r\n--r--"));
+    }
+}
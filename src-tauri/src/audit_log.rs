@@ -0,0 +1,267 @@
+//! The operation audit log.
+//!
+//! Every mutating mail operation (flag changes, moves, deletes, sends) gets
+//! a row in `operation_audit` once it has run: the frontend records the
+//! ones it runs, and `record` the ones the backend runs on its own (outbox
+//! sends, staged deletes). `audit_query` answers "where did that email
+//! go?" by querying those rows.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_sql::{DbInstances, DbPool};
+
+use crate::profiles::Profiles;
+
+const DEFAULT_LIMIT: i64 = 200;
+const MAX_LIMIT: i64 = 1000;
+
+/// Filters for `audit_query`. Every field is optional; set ones are ANDed.
+#[derive(Debug, Default, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditFilter {
+    pub account_id: Option<String>,
+    pub thread_id: Option<String>,
+    pub folder: Option<String>,
+    /// A UID (or Gmail message id) that must be among the entry's UIDs.
+    pub uid: Option<String>,
+    pub operation: Option<String>,
+    /// Only entries at or after this Unix timestamp (seconds).
+    pub since: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub account_id: String,
+    pub operation: String,
+    pub thread_id: Option<String>,
+    pub folder: Option<String>,
+    pub uids: String,
+    pub detail: Option<String>,
+    pub result: String,
+    pub error: Option<String>,
+    pub created_at: i64,
+}
+
+/// How an operation ended, as stored in the `result` column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AuditResult {
+    Ok,
+    Failed,
+}
+
+impl AuditResult {
+    fn as_str(self) -> &'static str {
+        match self {
+            AuditResult::Ok => "ok",
+            AuditResult::Failed => "failed",
+        }
+    }
+}
+
+/// An `operation_audit` row for an operation the backend ran.
+#[derive(Debug, PartialEq)]
+pub struct AuditRecord {
+    pub account_id: String,
+    pub operation: &'static str,
+    pub thread_id: Option<String>,
+    pub folder: Option<String>,
+    /// IMAP UIDs of the messages the operation touched.
+    pub uids: Vec<String>,
+    pub detail: Option<String>,
+    pub result: AuditResult,
+    pub error: Option<String>,
+}
+
+/// Add `records` to the audit log. Never fails the operation they describe:
+/// errors (e.g. the database isn't loaded yet) are only logged.
+pub async fn record(app: &AppHandle, records: Vec<AuditRecord>) {
+    if let Err(e) = insert(app, &records).await {
+        log::warn!("Failed to record audit entry: {e}");
+    }
+}
+
+async fn insert(app: &AppHandle, records: &[AuditRecord]) -> Result<(), String> {
+    let db = app.state::<DbInstances>();
+    let instances = db.0.read().await;
+    let DbPool::Sqlite(pool) = instances
+        .get(&app.state::<Profiles>().db_url())
+        .ok_or_else(|| "Database is not loaded".to_string())?;
+    for record in records {
+        let uids = serde_json::to_string(&record.uids).map_err(|e| e.to_string())?;
+        sqlx::query(
+            "INSERT INTO operation_audit (account_id, operation, thread_id, folder, uids, detail, result, error) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&record.account_id)
+        .bind(record.operation)
+        .bind(&record.thread_id)
+        .bind(&record.folder)
+        .bind(uids)
+        .bind(&record.detail)
+        .bind(record.result.as_str())
+        .bind(&record.error)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+enum Bind {
+    Text(String),
+    Int(i64),
+}
+
+/// Build the SELECT for `filter` and the values to bind, in order.
+fn build_query(filter: &AuditFilter) -> (String, Vec<Bind>) {
+    let mut conditions: Vec<&str> = Vec::new();
+    let mut binds = Vec::new();
+
+    let text_filters = [
+        ("account_id = ?", &filter.account_id),
+        ("thread_id = ?", &filter.thread_id),
+        ("folder = ?", &filter.folder),
+        ("operation = ?", &filter.operation),
+    ];
+    for (condition, value) in text_filters {
+        if let Some(value) = value {
+            conditions.push(condition);
+            binds.push(Bind::Text(value.clone()));
+        }
+    }
+    if let Some(uid) = &filter.uid {
+        // uids is a JSON array of strings, so match the quoted element
+        conditions.push("uids LIKE ? ESCAPE '\\'");
+        let escaped = uid
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        binds.push(Bind::Text(format!("%\"{escaped}\"%")));
+    }
+    if let Some(since) = filter.since {
+        conditions.push("created_at >= ?");
+        binds.push(Bind::Int(since));
+    }
+
+    let mut sql = String::from(
+        "SELECT id, account_id, operation, thread_id, folder, uids, detail, result, error, created_at FROM operation_audit",
+    );
+    if !conditions.is_empty() {
+        sql.push_str(" WHERE ");
+        sql.push_str(&conditions.join(" AND "));
+    }
+    sql.push_str(" ORDER BY created_at DESC, id DESC LIMIT ?");
+    binds.push(Bind::Int(
+        filter.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT),
+    ));
+    (sql, binds)
+}
+
+/// Audit entries matching `filter`, newest first.
+#[tauri::command]
+#[specta::specta]
+pub async fn audit_query(
+    db: State<'_, DbInstances>,
+    profiles: State<'_, Profiles>,
+    filter: AuditFilter,
+) -> Result<Vec<AuditEntry>, String> {
+    let instances = db.0.read().await;
+    let DbPool::Sqlite(pool) = instances
+        .get(&profiles.db_url())
+        .ok_or_else(|| "Database is not loaded".to_string())?;
+
+    type Row = (
+        i64,
+        String,
+        String,
+        Option<String>,
+        Option<String>,
+        String,
+        Option<String>,
+        String,
+        Option<String>,
+        i64,
+    );
+    let (sql, binds) = build_query(&filter);
+    let mut select = sqlx::query_as::<_, Row>(&sql);
+    for bind in binds {
+        select = match bind {
+            Bind::Text(value) => select.bind(value),
+            Bind::Int(value) => select.bind(value),
+        };
+    }
+    let rows = select
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to query audit log: {e}"))?;
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(
+                id,
+                account_id,
+                operation,
+                thread_id,
+                folder,
+                uids,
+                detail,
+                result,
+                error,
+                created_at,
+            )| {
+                AuditEntry {
+                    id,
+                    account_id,
+                    operation,
+                    thread_id,
+                    folder,
+                    uids,
+                    detail,
+                    result,
+                    error,
+                    created_at,
+                }
+            },
+        )
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_query_without_filters() {
+        let (sql, binds) = build_query(&AuditFilter::default());
+        assert!(!sql.contains("WHERE"));
+        assert!(sql.ends_with("ORDER BY created_at DESC, id DESC LIMIT ?"));
+        assert_eq!(binds, vec![Bind::Int(DEFAULT_LIMIT)]);
+    }
+
+    #[test]
+    fn test_build_query_combines_filters() {
+        let (sql, binds) = build_query(&AuditFilter {
+            account_id: Some("acct".into()),
+            uid: Some("4_2".into()),
+            since: Some(1_700_000_000),
+            limit: Some(5000),
+            ..Default::default()
+        });
+        assert!(
+            sql.contains("WHERE account_id = ? AND uids LIKE ? ESCAPE '\\' AND created_at >= ?")
+        );
+        assert_eq!(
+            binds,
+            vec![
+                Bind::Text("acct".into()),
+                Bind::Text("%\"4\\_2\"%".into()),
+                Bind::Int(1_700_000_000),
+                Bind::Int(MAX_LIMIT),
+            ]
+        );
+    }
+}
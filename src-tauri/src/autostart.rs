@@ -0,0 +1,85 @@
+//! Launch-on-login state, kept in sync with the start-hidden preference.
+//!
+//! The autostart entry always passes `--hidden`; whether that flag actually
+//! keeps the window in the tray is decided by `BackendSettings::start_hidden`.
+//! That way toggling "start hidden" never requires rewriting the OS login item.
+
+use serde::Serialize;
+use specta::Type;
+use tauri_plugin_autostart::ManagerExt;
+
+use crate::settings::SettingsState;
+
+/// Command-line flag the autostart entry launches with.
+pub const HIDDEN_ARG: &str = "--hidden";
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct AutostartState {
+    pub enabled: bool,
+    pub start_hidden: bool,
+}
+
+/// Whether this process should start hidden in the tray.
+pub fn should_start_hidden(settings: &SettingsState) -> bool {
+    std::env::args().any(|a| a == HIDDEN_ARG) && settings.get().start_hidden
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn autostart_get(
+    app: tauri::AppHandle,
+    settings: tauri::State<'_, SettingsState>,
+) -> Result<AutostartState, String> {
+    let enabled = app
+        .autolaunch()
+        .is_enabled()
+        .map_err(|e| format!("Failed to query autostart: {e}"))?;
+    Ok(AutostartState {
+        enabled,
+        start_hidden: settings.get().start_hidden,
+    })
+}
+
+/// Enable/disable launch-on-login and set the start-hidden preference together.
+///
+/// If persisting the preference fails, the login item is restored to its
+/// previous state so the two never disagree.
+#[tauri::command]
+#[specta::specta]
+pub fn autostart_set(
+    app: tauri::AppHandle,
+    settings: tauri::State<'_, SettingsState>,
+    enabled: bool,
+    start_hidden: bool,
+) -> Result<AutostartState, String> {
+    let launcher = app.autolaunch();
+    let was_enabled = launcher
+        .is_enabled()
+        .map_err(|e| format!("Failed to query autostart: {e}"))?;
+
+    let toggle = |on: bool| {
+        if on {
+            launcher.enable()
+        } else {
+            launcher.disable()
+        }
+    };
+
+    if was_enabled != enabled {
+        toggle(enabled).map_err(|e| format!("Failed to update autostart: {e}"))?;
+    }
+
+    if let Err(e) = settings.update(|s| s.start_hidden = start_hidden) {
+        if was_enabled != enabled {
+            if let Err(rollback) = toggle(was_enabled) {
+                log::warn!("Failed to roll back autostart change: {rollback}");
+            }
+        }
+        return Err(e);
+    }
+
+    Ok(AutostartState {
+        enabled,
+        start_hidden,
+    })
+}
@@ -0,0 +1,305 @@
+//! CalDAV free/busy lookup for suggesting meeting times in replies.
+//!
+//! Each calendar is asked for its busy time with a `free-busy-query`
+//! REPORT (RFC 4791 §7.10), which the server answers with a VFREEBUSY
+//! component; the busy periods of all calendars are merged and the gaps
+//! between them in the requested range are returned as free slots.
+
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::Serialize;
+use specta::Type;
+
+use crate::accounts::require_trusted_window;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Longest range that can be looked up at once.
+const MAX_RANGE_SECS: i64 = 62 * 24 * 60 * 60;
+
+/// A span of time in Unix seconds, end exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Type)]
+pub struct TimeSlot {
+    pub start: i64,
+    pub end: i64,
+}
+
+fn format_utc(ts: i64) -> Result<String, String> {
+    DateTime::<Utc>::from_timestamp(ts, 0)
+        .map(|dt| dt.format("%Y%m%dT%H%M%SZ").to_string())
+        .ok_or_else(|| format!("Invalid timestamp: {ts}"))
+}
+
+fn free_busy_query(start: i64, end: i64) -> Result<String, String> {
+    Ok(format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<C:free-busy-query xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <C:time-range start="{}" end="{}"/>
+</C:free-busy-query>"#,
+        format_utc(start)?,
+        format_utc(end)?
+    ))
+}
+
+/// Parse a DATE-TIME value. Servers send UTC in free/busy replies; a
+/// floating time without "Z" is read as UTC too.
+fn parse_date_time(value: &str) -> Option<i64> {
+    let value = value.trim().trim_end_matches('Z');
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .ok()
+        .map(|dt| dt.and_utc().timestamp())
+}
+
+/// Parse an iCalendar DURATION like "PT1H30M", "P1D" or "P2W" into seconds.
+fn parse_duration(value: &str) -> Option<i64> {
+    let rest = value.trim().trim_start_matches('+').strip_prefix('P')?;
+    let mut seconds = 0i64;
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in rest.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' if number.is_empty() => in_time = true,
+            _ => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                seconds += n * match (c, in_time) {
+                    ('W', false) => 7 * 24 * 3600,
+                    ('D', false) => 24 * 3600,
+                    ('H', true) => 3600,
+                    ('M', true) => 60,
+                    ('S', true) => 1,
+                    _ => return None,
+                };
+            }
+        }
+    }
+    number.is_empty().then_some(seconds)
+}
+
+/// Parse a PERIOD: "start/end" or "start/duration".
+fn parse_period(value: &str) -> Option<TimeSlot> {
+    let (start, end) = value.split_once('/')?;
+    let start = parse_date_time(start)?;
+    let end = if end.trim_start().starts_with(['P', '+']) {
+        start + parse_duration(end)?
+    } else {
+        parse_date_time(end)?
+    };
+    (end > start).then_some(TimeSlot { start, end })
+}
+
+/// The busy periods in an iCalendar free/busy reply. FREEBUSY properties
+/// marked FBTYPE=FREE are skipped; tentative and unavailable time counts
+/// as busy.
+fn parse_busy(ical: &str) -> Vec<TimeSlot> {
+    // Unfold continuation lines first
+    let unfolded = ical
+        .replace("\r\n", "\n")
+        .replace("\n ", "")
+        .replace("\n\t", "");
+    let mut busy = Vec::new();
+    for line in unfolded.lines() {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let mut parts = name.split(';');
+        if !parts
+            .next()
+            .is_some_and(|n| n.eq_ignore_ascii_case("FREEBUSY"))
+        {
+            continue;
+        }
+        let free = parts.any(|p| p.eq_ignore_ascii_case("FBTYPE=FREE"));
+        if !free {
+            busy.extend(value.split(',').filter_map(parse_period));
+        }
+    }
+    busy
+}
+
+/// The gaps of at least `min_secs` between `busy` periods within
+/// `start..end`.
+fn free_slots(mut busy: Vec<TimeSlot>, start: i64, end: i64, min_secs: i64) -> Vec<TimeSlot> {
+    busy.sort_by_key(|b| b.start);
+    let mut free = Vec::new();
+    let mut cursor = start;
+    for b in busy {
+        if b.end <= cursor {
+            continue;
+        }
+        if b.start >= end {
+            break;
+        }
+        if b.start - cursor >= min_secs {
+            free.push(TimeSlot {
+                start: cursor,
+                end: b.start,
+            });
+        }
+        cursor = cursor.max(b.end);
+    }
+    if end - cursor >= min_secs {
+        free.push(TimeSlot { start: cursor, end });
+    }
+    free
+}
+
+async fn query_calendar(
+    client: &reqwest::Client,
+    url: &str,
+    username: &str,
+    password: &str,
+    body: &str,
+) -> Result<Vec<TimeSlot>, String> {
+    let method = reqwest::Method::from_bytes(b"REPORT").map_err(|e| e.to_string())?;
+    let response = client
+        .request(method, url)
+        .basic_auth(username, Some(password))
+        .header("Depth", "1")
+        .header("Content-Type", "application/xml; charset=utf-8")
+        .body(body.to_string())
+        .send()
+        .await
+        .map_err(|e| format!("Free/busy request to {url} failed: {e}"))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(match status.as_u16() {
+            401 => "CalDAV server rejected the credentials".to_string(),
+            403 | 404 | 405 | 415 | 501 => {
+                format!("CalDAV server doesn't support free/busy queries on {url} (HTTP {status})")
+            }
+            _ => format!("Free/busy request to {url} failed: HTTP {status}"),
+        });
+    }
+    let text = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read free/busy reply: {e}"))?;
+    Ok(parse_busy(&text))
+}
+
+/// Free time across `calendar_urls` between `start` and `end` (Unix
+/// seconds), as slots of at least `min_minutes` (default 30).
+#[tauri::command]
+#[specta::specta]
+pub async fn caldav_freebusy(
+    window: tauri::Window,
+    calendar_urls: Vec<String>,
+    username: String,
+    password: String,
+    start: i64,
+    end: i64,
+    min_minutes: Option<u32>,
+) -> Result<Vec<TimeSlot>, String> {
+    require_trusted_window(&window, "caldav_freebusy")?;
+    if end <= start {
+        return Err("The end of the range must be after its start".to_string());
+    }
+    if end - start > MAX_RANGE_SECS {
+        return Err("Free/busy lookups are limited to two months".to_string());
+    }
+    if calendar_urls.is_empty() {
+        return Err("No calendars to look up".to_string());
+    }
+
+    let body = free_busy_query(start, end)?;
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut busy = Vec::new();
+    for url in &calendar_urls {
+        busy.extend(query_calendar(&client, url, &username, &password, &body).await?);
+    }
+    let min_secs = i64::from(min_minutes.unwrap_or(30)).max(1) * 60;
+    Ok(free_slots(busy, start, end, min_secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const T0: i64 = 1_760_004_000; // 2025-10-09T10:00:00Z
+
+    #[test]
+    fn test_parse_busy() {
+        let reply = "BEGIN:VCALENDAR\r\nBEGIN:VFREEBUSY\r\n\
+            DTSTART:20251009T000000Z\r\n\
+            FREEBUSY:20251009T100000Z/20251009T110000Z,20251009T130000Z/PT30M\r\n\
+            FREEBUSY;FBTYPE=BUSY-TENTATIVE:20251009T150000Z/\r\n 20251009T160000Z\r\n\
+            FREEBUSY;FBTYPE=FREE:20251009T170000Z/20251009T180000Z\r\n\
+            END:VFREEBUSY\r\nEND:VCALENDAR\r\n";
+        assert_eq!(
+            parse_busy(reply),
+            vec![
+                TimeSlot {
+                    start: T0,
+                    end: T0 + 3600
+                },
+                TimeSlot {
+                    start: T0 + 3 * 3600,
+                    end: T0 + 3 * 3600 + 1800
+                },
+                TimeSlot {
+                    start: T0 + 5 * 3600,
+                    end: T0 + 6 * 3600
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("PT1H30M"), Some(5400));
+        assert_eq!(parse_duration("P1DT2H"), Some(93_600));
+        assert_eq!(parse_duration("P2W"), Some(1_209_600));
+        assert_eq!(parse_duration("PT"), Some(0));
+        assert_eq!(parse_duration("P1H"), None);
+        assert_eq!(parse_duration("PT5"), None);
+    }
+
+    #[test]
+    fn test_free_slots() {
+        let busy = vec![
+            TimeSlot {
+                start: T0 + 3600,
+                end: T0 + 7200,
+            },
+            // Overlaps the previous one
+            TimeSlot {
+                start: T0 + 1800,
+                end: T0 + 5400,
+            },
+            // Leaves only a 15-minute gap
+            TimeSlot {
+                start: T0 + 8100,
+                end: T0 + 9000,
+            },
+            // Outside the range
+            TimeSlot {
+                start: T0 + 20_000,
+                end: T0 + 21_000,
+            },
+        ];
+        assert_eq!(
+            free_slots(busy, T0, T0 + 14_400, 1800),
+            vec![
+                TimeSlot {
+                    start: T0,
+                    end: T0 + 1800
+                },
+                TimeSlot {
+                    start: T0 + 9000,
+                    end: T0 + 14_400
+                },
+            ]
+        );
+        assert_eq!(
+            free_slots(Vec::new(), T0, T0 + 600, 1800),
+            Vec::<TimeSlot>::new()
+        );
+    }
+}
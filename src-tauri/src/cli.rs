@@ -0,0 +1,388 @@
+//! Headless command-line mode for scripts:
+//!
+//! ```text
+//! sora --send message.eml [--account work] [--profile name]
+//! sora --check [--account work] [--profile name]
+//! ```
+//!
+//! `--send` submits an RFC 5322 message (`-` reads it from stdin) over the
+//! account's SMTP server and copies it to the IMAP Sent folder, like the
+//! composer does. `--check` reports each account's unread and total Inbox
+//! counts. Both run before Tauri starts, so no window, tray or second
+//! instance is involved; they use the profile's `accounts.json`, keychain
+//! passwords and backend settings (read-only mode applies).
+//!
+//! Accounts are picked by id, address or display name. `ask_password`
+//! accounts take their password from `SORA_PASSWORD`. OAuth accounts work
+//! once the app has stored their grant in the keychain, which lets
+//! [`crate::oauth::tokens`] refresh their access tokens here too.
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::accounts::{AccountDefinition, AccountRegistry};
+use crate::imap::client as imap_client;
+use crate::profiles;
+use crate::rate_limit;
+use crate::settings::SettingsState;
+use crate::smtp::client as smtp_client;
+
+/// Must match `identifier` in tauri.conf.json, which names the app config
+/// dir.
+const APP_IDENTIFIER: &str = "dev.lutelute.sora";
+/// Password for `ask_password` accounts.
+const PASSWORD_ENV: &str = "SORA_PASSWORD";
+
+const USAGE: &str = "\
+Usage:
+  sora --send <file.eml | -> [--account <account>] [--profile <profile>]
+  sora --check [--account <account>] [--profile <profile>]
+
+Options:
+  --send <file>        Send a message file (\"-\" reads stdin) and copy it to Sent
+  --check              Show unread and total messages in each account's Inbox
+  --account <account>  Account id, address or display name; required with
+                       --send when there are several
+  --profile <profile>  Profile id or name; defaults to the last one used
+
+Accounts that ask for their password every session read it from SORA_PASSWORD.";
+
+#[derive(Debug, PartialEq)]
+enum Action {
+    Send(String),
+    Check,
+    Help,
+}
+
+#[derive(Debug, PartialEq)]
+struct Invocation {
+    action: Action,
+    account: Option<String>,
+}
+
+/// The value of `--flag value` or `--flag=value` at `args[*i]`, advancing
+/// `i` past a separate value.
+fn flag_value(args: &[String], i: &mut usize, flag: &str) -> Result<Option<String>, String> {
+    let arg = &args[*i];
+    if arg == flag {
+        *i += 1;
+        return args
+            .get(*i)
+            .cloned()
+            .map(Some)
+            .ok_or_else(|| format!("{flag} needs a value"));
+    }
+    Ok(arg
+        .strip_prefix(flag)
+        .and_then(|rest| rest.strip_prefix('='))
+        .map(str::to_string))
+}
+
+/// The headless invocation `args` ask for, or `None` for a normal launch.
+fn parse(args: &[String]) -> Result<Option<Invocation>, String> {
+    let mut action = None;
+    let mut account = None;
+    let mut unknown = None;
+    let mut i = 1;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        if let Some(path) = flag_value(args, &mut i, "--send")? {
+            action = Some(Action::Send(path));
+        } else if let Some(value) = flag_value(args, &mut i, "--account")? {
+            account = Some(value);
+        } else if arg == "--check" {
+            action = Some(Action::Check);
+        } else if arg == "--help" || arg == "-h" {
+            action = Some(Action::Help);
+        } else if flag_value(args, &mut i, profiles::PROFILE_ARG)?.is_none() {
+            unknown.get_or_insert(arg);
+        }
+        i += 1;
+    }
+    // Without an action other arguments belong to the app (deep links,
+    // --hidden)
+    match (action, unknown) {
+        (Some(_), Some(arg)) => Err(format!("Unknown argument {arg}")),
+        (action, _) => Ok(action.map(|action| Invocation { action, account })),
+    }
+}
+
+fn app_config_dir() -> Result<PathBuf, String> {
+    dirs::config_dir()
+        .map(|dir| dir.join(APP_IDENTIFIER))
+        .ok_or_else(|| "No config directory for this user".to_string())
+}
+
+fn label(account: &AccountDefinition) -> &str {
+    account.email.as_deref().unwrap_or(&account.id)
+}
+
+/// The account `query` names, or the only one there is.
+fn find_account<'a>(
+    accounts: &'a [AccountDefinition],
+    query: Option<&str>,
+) -> Result<&'a AccountDefinition, String> {
+    let Some(query) = query else {
+        return match accounts {
+            [account] => Ok(account),
+            [] => Err("No accounts in this profile".to_string()),
+            _ => Err(format!(
+                "Pick an account with --account: {}",
+                accounts.iter().map(label).collect::<Vec<_>>().join(", ")
+            )),
+        };
+    };
+    accounts
+        .iter()
+        .find(|a| a.id == query)
+        .or_else(|| {
+            accounts.iter().find(|a| {
+                [&a.email, &a.display_name]
+                    .into_iter()
+                    .flatten()
+                    .any(|v| v.eq_ignore_ascii_case(query))
+            })
+        })
+        .ok_or_else(|| format!("No account {query}"))
+}
+
+/// Supply the password of an account that asks for it every session.
+fn prepare(registry: &AccountRegistry, account: &AccountDefinition) -> Result<(), String> {
+    if account.ask_password {
+        let password = std::env::var(PASSWORD_ENV).map_err(|_| {
+            format!(
+                "{} asks for its password every session; set {PASSWORD_ENV}",
+                label(account)
+            )
+        })?;
+        registry.provide_password(&account.id, Some(password));
+    }
+    Ok(())
+}
+
+/// `raw` with bare LF line endings turned into CRLF, as SMTP and IMAP
+/// expect; message files saved on Unix usually have LF.
+fn to_crlf(raw: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(raw.len() + raw.len() / 32);
+    for (i, &b) in raw.iter().enumerate() {
+        if b == b'\n' && (i == 0 || raw[i - 1] != b'\r') {
+            out.push(b'\r');
+        }
+        out.push(b);
+    }
+    out
+}
+
+fn read_message(path: &str) -> Result<Vec<u8>, String> {
+    let mut raw = Vec::new();
+    if path == "-" {
+        std::io::stdin()
+            .read_to_end(&mut raw)
+            .map_err(|e| format!("Failed to read the message from stdin: {e}"))?;
+    } else {
+        raw = std::fs::read(Path::new(path)).map_err(|e| format!("Failed to read {path}: {e}"))?;
+    }
+    if raw.is_empty() {
+        return Err("The message is empty".to_string());
+    }
+    Ok(to_crlf(&raw))
+}
+
+async fn save_to_sent(
+    registry: &AccountRegistry,
+    account_id: &str,
+    raw: &[u8],
+) -> Result<(), String> {
+    let config = registry.imap(account_id).await?;
+    let mut session = imap_client::connect(&config).await?;
+    let folders = imap_client::list_folders(&mut session).await;
+    let sent = folders
+        .ok()
+        .and_then(|folders| {
+            folders
+                .into_iter()
+                .find(|f| f.special_use.as_deref() == Some("\\Sent"))
+        })
+        .map_or_else(|| "Sent".to_string(), |f| f.raw_path);
+    let appended = imap_client::append_message(&mut session, &sent, Some("(\\Seen)"), raw).await;
+    let _ = session.logout().await;
+    appended
+}
+
+async fn send(
+    registry: &AccountRegistry,
+    settings: &SettingsState,
+    account: &AccountDefinition,
+    path: &str,
+) -> Result<(), String> {
+    settings.require_writable("--send")?;
+    let raw = read_message(path)?;
+    let config = registry.smtp(&account.id).await?;
+    rate_limit::acquire_smtp(&config).await;
+    smtp_client::send_raw_bytes(&config, &raw).await?;
+    println!("Sent from {}", label(account));
+
+    if account.imap.is_some() {
+        if let Err(e) = save_to_sent(registry, &account.id, &raw).await {
+            eprintln!("Sent, but not copied to the Sent folder: {e}");
+        }
+    }
+    Ok(())
+}
+
+/// Print one line per account; fails if any account couldn't be checked.
+async fn check(registry: &AccountRegistry, accounts: &[&AccountDefinition]) -> Result<(), String> {
+    let mut failed = 0;
+    for account in accounts {
+        let status = async {
+            prepare(registry, account)?;
+            let config = registry.imap(&account.id).await?;
+            let mut session = imap_client::connect(&config).await?;
+            let status = imap_client::get_folder_status(&mut session, "INBOX").await;
+            let _ = session.logout().await;
+            status
+        };
+        match status.await {
+            Ok(status) => println!(
+                "{}: {} unread, {} in Inbox",
+                label(account),
+                status.unseen,
+                status.exists
+            ),
+            Err(e) => {
+                failed += 1;
+                eprintln!("{}: {e}", label(account));
+            }
+        }
+    }
+    match failed {
+        0 => Ok(()),
+        n => Err(format!("{n} of {} accounts failed", accounts.len())),
+    }
+}
+
+async fn run(invocation: Invocation, args: &[String]) -> Result<(), String> {
+    let dir = profiles::headless_config_dir(&app_config_dir()?, args);
+    let registry = AccountRegistry::from_dir(&dir);
+    let accounts = registry.list();
+
+    match invocation.action {
+        Action::Send(path) => {
+            let account = find_account(&accounts, invocation.account.as_deref())?;
+            prepare(&registry, account)?;
+            send(&registry, &SettingsState::from_dir(&dir), account, &path).await
+        }
+        Action::Check => {
+            let selected: Vec<&AccountDefinition> = match invocation.account.as_deref() {
+                Some(query) => vec![find_account(&accounts, Some(query))?],
+                None => accounts.iter().filter(|a| a.imap.is_some()).collect(),
+            };
+            if selected.is_empty() {
+                return Err("No IMAP accounts in this profile".to_string());
+            }
+            check(&registry, &selected).await
+        }
+        Action::Help => {
+            println!("{USAGE}");
+            Ok(())
+        }
+    }
+}
+
+/// Let release builds on Windows, which have no console of their own,
+/// print to the terminal they were started from.
+#[cfg(windows)]
+fn attach_console() {
+    use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    // SAFETY: no preconditions; fails harmlessly without a parent console
+    let _ = unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+}
+
+/// Run a headless invocation if the command line asks for one, returning
+/// the process exit code; `None` means start the app normally.
+pub fn run_from_args() -> Option<i32> {
+    let args: Vec<String> = std::env::args().collect();
+    let parsed = parse(&args).transpose()?;
+    #[cfg(windows)]
+    attach_console();
+
+    let code = match parsed {
+        Ok(invocation) => match tauri::async_runtime::block_on(run(invocation, &args)) {
+            Ok(()) => 0,
+            Err(e) => {
+                eprintln!("{e}");
+                1
+            }
+        },
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            2
+        }
+    };
+    let _ = std::io::stdout().flush();
+    Some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn account(id: &str, email: &str, name: Option<&str>) -> AccountDefinition {
+        AccountDefinition {
+            id: id.to_string(),
+            email: Some(email.to_string()),
+            display_name: name.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse(&args(&["sora"])).unwrap(), None);
+        assert_eq!(parse(&args(&["sora", "--hidden"])).unwrap(), None);
+        assert_eq!(
+            parse(&args(&["sora", "--send", "a.eml", "--account=work"])).unwrap(),
+            Some(Invocation {
+                action: Action::Send("a.eml".to_string()),
+                account: Some("work".to_string()),
+            })
+        );
+        assert_eq!(
+            parse(&args(&["sora", "--profile", "Work", "--check"])).unwrap(),
+            Some(Invocation {
+                action: Action::Check,
+                account: None,
+            })
+        );
+        assert!(parse(&args(&["sora", "--send"])).is_err());
+        assert!(parse(&args(&["sora", "--check", "--bogus"])).is_err());
+        assert!(parse(&args(&["sora", "mailto:a@example.com", "--check"])).is_err());
+    }
+
+    #[test]
+    fn test_find_account() {
+        let accounts = [
+            account("a1", "me@work.example", Some("Work")),
+            account("b2", "me@home.example", None),
+        ];
+        assert_eq!(find_account(&accounts, Some("b2")).unwrap().id, "b2");
+        assert_eq!(find_account(&accounts, Some("work")).unwrap().id, "a1");
+        assert_eq!(
+            find_account(&accounts, Some("ME@HOME.example")).unwrap().id,
+            "b2"
+        );
+        assert!(find_account(&accounts, Some("other")).is_err());
+        assert!(find_account(&accounts, None).is_err());
+        assert_eq!(find_account(&accounts[..1], None).unwrap().id, "a1");
+    }
+
+    #[test]
+    fn test_to_crlf() {
+        assert_eq!(to_crlf(b"a\nb\r\nc\n"), b"a\r\nb\r\nc\r\n");
+        assert_eq!(to_crlf(b"\n"), b"\r\n");
+    }
+}
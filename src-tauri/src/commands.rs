@@ -1,289 +1,1269 @@
+use std::collections::HashMap;
+
+use base64::Engine;
+
+use crate::accounts::{require_trusted_window, AccountRegistry, MailTransport};
+use crate::attachments::{self, AttachmentStaging};
+use crate::gmail_api;
+use crate::identity::Identities;
+use crate::imap::catenate::{self as imap_catenate, SavedDrafts};
 use crate::imap::client as imap_client;
+use crate::imap::folder_watch::FolderListCache;
+use crate::imap::idle as imap_idle;
+use crate::imap::keywords as imap_keywords;
+use crate::imap::notify as imap_notify;
+use crate::imap::session_pool::ImapSessionManager;
 use crate::imap::types::{
-    DeltaCheckRequest, DeltaCheckResult, ImapConfig, ImapFetchResult, ImapFolder,
-    ImapFolderStatus, ImapFolderSyncResult, ImapMessage,
+    DeltaCheckRequest, DeltaCheckResult, ImapAttachment, ImapConfig, ImapFetchResult, ImapFolder,
+    ImapFolderNode, ImapFolderStatus, ImapFolderSyncResult, ImapMessage, ImapMessageHeader,
+    ImapMessageKeywords,
 };
+use crate::imap::uid_set::build_uid_sets;
+use crate::managed;
+use crate::metrics::{self, Phase};
+use crate::nntp::client as nntp_client;
+use crate::nntp::types::{NntpConfig, NntpGroup, NntpGroupStatus, NntpOverview};
+use crate::rate_limit;
+use crate::settings::SettingsState;
+use crate::smtp::burl as smtp_burl;
+use crate::smtp::chunking as smtp_chunking;
 use crate::smtp::client as smtp_client;
-use crate::smtp::types::{SmtpConfig, SmtpSendResult};
+use crate::smtp::types::{SendAndAppendResult, SentCopy, SmtpConfig, SmtpSendResult, SubmitError};
 
 // ---------- IMAP commands ----------
 
 #[tauri::command]
-pub async fn imap_test_connection(config: ImapConfig) -> Result<String, String> {
+#[specta::specta]
+pub async fn imap_test_connection(
+    config: ImapConfig,
+    window: tauri::Window,
+) -> Result<String, String> {
+    require_trusted_window(&window, "imap_test_connection")?;
+    managed::policy().check_config(&config.auth_method, config.accept_invalid_certs)?;
     imap_client::test_connection(&config).await
 }
 
 #[tauri::command]
-pub async fn imap_list_folders(config: ImapConfig) -> Result<Vec<ImapFolder>, String> {
-    let mut session = imap_client::connect(&config).await?;
-    let folders = imap_client::list_folders(&mut session).await?;
-    let _ = session.logout().await;
+#[specta::specta]
+pub async fn imap_list_folders(
+    account_id: String,
+    accounts: tauri::State<'_, AccountRegistry>,
+    sessions: tauri::State<'_, ImapSessionManager>,
+    folder_cache: tauri::State<'_, FolderListCache>,
+) -> Result<Vec<ImapFolder>, String> {
+    let config = accounts.imap(&account_id).await?;
+    let mut session = sessions.checkout(&account_id, &config).await?;
+    let folders = metrics::time(
+        &config.host,
+        "list_folders",
+        Phase::Command,
+        imap_client::list_folders(&mut session),
+    )
+    .await;
+    sessions.checkin(session, &folders);
+    let folders = folders?;
+    // Emits `folders-changed` if folders were added, removed or renamed
+    folder_cache.update(&account_id, &folders);
     Ok(folders)
 }
 
 #[tauri::command]
+#[specta::specta]
+pub async fn imap_list_folder_tree(
+    account_id: String,
+    accounts: tauri::State<'_, AccountRegistry>,
+    sessions: tauri::State<'_, ImapSessionManager>,
+) -> Result<Vec<ImapFolderNode>, String> {
+    let config = accounts.imap(&account_id).await?;
+    let mut session = sessions.checkout(&account_id, &config).await?;
+    let tree = metrics::time(
+        &config.host,
+        "list_folder_tree",
+        Phase::Command,
+        imap_client::list_folder_tree(&mut session),
+    )
+    .await;
+    sessions.checkin(session, &tree);
+    tree
+}
+
+/// Create a folder. The folder management commands take UTF-8 paths
+/// (`ImapFolder::path`, levels joined by the delimiter) and encode them for
+/// the server; they return the raw path the other commands take.
+#[tauri::command]
+#[specta::specta]
+pub async fn imap_create_folder(
+    account_id: String,
+    path: String,
+    accounts: tauri::State<'_, AccountRegistry>,
+    sessions: tauri::State<'_, ImapSessionManager>,
+    settings: tauri::State<'_, SettingsState>,
+    window: tauri::Window,
+) -> Result<String, String> {
+    require_trusted_window(&window, "imap_create_folder")?;
+    create_folder(&accounts, &sessions, &settings, &account_id, &path).await
+}
+
+pub(crate) async fn create_folder(
+    accounts: &AccountRegistry,
+    sessions: &ImapSessionManager,
+    settings: &SettingsState,
+    account_id: &str,
+    path: &str,
+) -> Result<String, String> {
+    settings.require_writable("imap_create_folder")?;
+    let config = accounts.imap(account_id).await?;
+    let mut session = sessions.checkout(account_id, &config).await?;
+    let result = metrics::time(
+        &config.host,
+        "create_folder",
+        Phase::Command,
+        imap_client::create_folder(&mut session, path),
+    )
+    .await;
+    sessions.checkin(session, &result);
+    result
+}
+
+/// Rename (or move) the folder at `path` to `new_path`; returns the new raw
+/// path.
+#[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
+pub async fn imap_rename_folder(
+    account_id: String,
+    path: String,
+    new_path: String,
+    accounts: tauri::State<'_, AccountRegistry>,
+    sessions: tauri::State<'_, ImapSessionManager>,
+    settings: tauri::State<'_, SettingsState>,
+    window: tauri::Window,
+) -> Result<String, String> {
+    require_trusted_window(&window, "imap_rename_folder")?;
+    rename_folder(
+        &accounts,
+        &sessions,
+        &settings,
+        &account_id,
+        &path,
+        &new_path,
+    )
+    .await
+}
+
+pub(crate) async fn rename_folder(
+    accounts: &AccountRegistry,
+    sessions: &ImapSessionManager,
+    settings: &SettingsState,
+    account_id: &str,
+    path: &str,
+    new_path: &str,
+) -> Result<String, String> {
+    settings.require_writable("imap_rename_folder")?;
+    let config = accounts.imap(account_id).await?;
+    let mut session = sessions.checkout(account_id, &config).await?;
+    let result = metrics::time(
+        &config.host,
+        "rename_folder",
+        Phase::Command,
+        imap_client::rename_folder(&mut session, path, new_path),
+    )
+    .await;
+    sessions.checkin(session, &result);
+    result
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn imap_delete_folder(
+    account_id: String,
+    path: String,
+    accounts: tauri::State<'_, AccountRegistry>,
+    sessions: tauri::State<'_, ImapSessionManager>,
+    settings: tauri::State<'_, SettingsState>,
+    window: tauri::Window,
+) -> Result<(), String> {
+    require_trusted_window(&window, "imap_delete_folder")?;
+    delete_folder(&accounts, &sessions, &settings, &account_id, &path).await
+}
+
+pub(crate) async fn delete_folder(
+    accounts: &AccountRegistry,
+    sessions: &ImapSessionManager,
+    settings: &SettingsState,
+    account_id: &str,
+    path: &str,
+) -> Result<(), String> {
+    settings.require_writable("imap_delete_folder")?;
+    let config = accounts.imap(account_id).await?;
+    let mut session = sessions.checkout(account_id, &config).await?;
+    let result = metrics::time(
+        &config.host,
+        "delete_folder",
+        Phase::Command,
+        imap_client::delete_folder(&mut session, path),
+    )
+    .await;
+    sessions.checkin(session, &result);
+    result
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn imap_subscribe_folder(
+    account_id: String,
+    path: String,
+    accounts: tauri::State<'_, AccountRegistry>,
+    sessions: tauri::State<'_, ImapSessionManager>,
+    settings: tauri::State<'_, SettingsState>,
+    window: tauri::Window,
+) -> Result<(), String> {
+    require_trusted_window(&window, "imap_subscribe_folder")?;
+    subscribe_folder(&accounts, &sessions, &settings, &account_id, &path).await
+}
+
+pub(crate) async fn subscribe_folder(
+    accounts: &AccountRegistry,
+    sessions: &ImapSessionManager,
+    settings: &SettingsState,
+    account_id: &str,
+    path: &str,
+) -> Result<(), String> {
+    settings.require_writable("imap_subscribe_folder")?;
+    let config = accounts.imap(account_id).await?;
+    let mut session = sessions.checkout(account_id, &config).await?;
+    let result = metrics::time(
+        &config.host,
+        "subscribe_folder",
+        Phase::Command,
+        imap_client::subscribe_folder(&mut session, path),
+    )
+    .await;
+    sessions.checkin(session, &result);
+    result
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn imap_unsubscribe_folder(
+    account_id: String,
+    path: String,
+    accounts: tauri::State<'_, AccountRegistry>,
+    sessions: tauri::State<'_, ImapSessionManager>,
+    settings: tauri::State<'_, SettingsState>,
+    window: tauri::Window,
+) -> Result<(), String> {
+    require_trusted_window(&window, "imap_unsubscribe_folder")?;
+    unsubscribe_folder(&accounts, &sessions, &settings, &account_id, &path).await
+}
+
+pub(crate) async fn unsubscribe_folder(
+    accounts: &AccountRegistry,
+    sessions: &ImapSessionManager,
+    settings: &SettingsState,
+    account_id: &str,
+    path: &str,
+) -> Result<(), String> {
+    settings.require_writable("imap_unsubscribe_folder")?;
+    let config = accounts.imap(account_id).await?;
+    let mut session = sessions.checkout(account_id, &config).await?;
+    let result = metrics::time(
+        &config.host,
+        "unsubscribe_folder",
+        Phase::Command,
+        imap_client::unsubscribe_folder(&mut session, path),
+    )
+    .await;
+    sessions.checkin(session, &result);
+    result
+}
+
+#[tauri::command]
+#[specta::specta]
 pub async fn imap_fetch_messages(
-    config: ImapConfig,
+    account_id: String,
     folder: String,
     uids: Vec<u32>,
+    accounts: tauri::State<'_, AccountRegistry>,
+    sessions: tauri::State<'_, ImapSessionManager>,
+    identities: tauri::State<'_, Identities>,
 ) -> Result<ImapFetchResult, String> {
+    let config = accounts.imap(&account_id).await?;
     if uids.is_empty() {
         return Err("No UIDs provided".to_string());
     }
 
-    // Build a UID set string like "1,5,10,20"
-    let uid_set: String = uids
-        .iter()
-        .map(|u| u.to_string())
-        .collect::<Vec<_>>()
-        .join(",");
-
-    let mut session = imap_client::connect(&config).await?;
-    let result = imap_client::fetch_messages(&mut session, &folder, &uid_set).await;
-    let _ = session.logout().await;
-
-    match result {
-        Ok(r) => Ok(r),
-        Err(e) if e.starts_with("ASYNC_IMAP_EMPTY:") => {
-            // async-imap can't parse this server's responses — use raw TCP fallback
-            log::info!("Falling back to raw TCP fetch for folder {folder}");
-            imap_client::raw_fetch_messages(&config, &folder, &uid_set).await
+    // Long UID lists are compressed into ranges and split across commands
+    let mut session = sessions.checkout(&account_id, &config).await?;
+    let result = async {
+        let mut combined: Option<ImapFetchResult> = None;
+        for (i, uid_set) in build_uid_sets(&uids).into_iter().enumerate() {
+            // Checking the session out paid for the first command
+            if i > 0 {
+                rate_limit::acquire_imap(&config).await;
+            }
+            let batch = match metrics::time(
+                &config.host,
+                "fetch_messages",
+                Phase::Command,
+                imap_client::fetch_messages(&mut session, &folder, &uid_set),
+            )
+            .await
+            {
+                Ok(r) => r,
+                Err(e) if e.starts_with("ASYNC_IMAP_EMPTY:") => {
+                    // async-imap can't parse this server's responses — use raw TCP fallback
+                    log::info!("Falling back to raw TCP fetch for folder {folder}");
+                    imap_client::raw_fetch_messages(&config, &folder, &uid_set).await?
+                }
+                Err(e) => return Err(e),
+            };
+            match combined.as_mut() {
+                Some(r) => {
+                    r.messages.extend(batch.messages);
+                    r.folder_status = batch.folder_status;
+                }
+                None => combined = Some(batch),
+            }
+        }
+        combined.ok_or_else(|| "No UIDs provided".to_string())
+    }
+    .await;
+    sessions.checkin(session, &result);
+
+    let mut result = result?;
+    identities.annotate(&mut result.messages);
+    Ok(result)
+}
+
+/// Envelopes, flags and sizes of messages without their bodies, for filling
+/// a message list; bodies are fetched when a message is opened.
+#[tauri::command]
+#[specta::specta]
+pub async fn imap_fetch_headers(
+    account_id: String,
+    folder: String,
+    uids: Vec<u32>,
+    accounts: tauri::State<'_, AccountRegistry>,
+    sessions: tauri::State<'_, ImapSessionManager>,
+) -> Result<Vec<ImapMessageHeader>, String> {
+    let config = accounts.imap(&account_id).await?;
+    if uids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut session = sessions.checkout(&account_id, &config).await?;
+    let result = async {
+        let mut headers = Vec::new();
+        for (i, uid_set) in build_uid_sets(&uids).into_iter().enumerate() {
+            if i > 0 {
+                rate_limit::acquire_imap(&config).await;
+            }
+            headers.extend(
+                metrics::time(
+                    &config.host,
+                    "fetch_headers",
+                    Phase::Command,
+                    imap_client::fetch_headers(&mut session, &folder, &uid_set),
+                )
+                .await?,
+            );
+        }
+        Ok(headers)
+    }
+    .await;
+    sessions.checkin(session, &result);
+    result
+}
+
+/// Current keywords of messages already synced, for keyword changes made
+/// from other clients (team tags in a shared folder).
+#[tauri::command]
+#[specta::specta]
+pub async fn imap_fetch_keywords(
+    account_id: String,
+    folder: String,
+    uids: Vec<u32>,
+    accounts: tauri::State<'_, AccountRegistry>,
+    sessions: tauri::State<'_, ImapSessionManager>,
+) -> Result<Vec<ImapMessageKeywords>, String> {
+    let config = accounts.imap(&account_id).await?;
+    if uids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut session = sessions.checkout(&account_id, &config).await?;
+    let result = async {
+        let mut keywords = Vec::new();
+        for (i, uid_set) in build_uid_sets(&uids).into_iter().enumerate() {
+            if i > 0 {
+                rate_limit::acquire_imap(&config).await;
+            }
+            keywords.extend(
+                metrics::time(
+                    &config.host,
+                    "fetch_keywords",
+                    Phase::Command,
+                    imap_client::fetch_keywords(&mut session, &folder, &uid_set),
+                )
+                .await?,
+            );
         }
-        Err(e) => Err(e),
+        Ok(keywords)
     }
+    .await;
+    sessions.checkin(session, &result);
+    result
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn imap_fetch_new_uids(
-    config: ImapConfig,
+    account_id: String,
     folder: String,
     since_uid: u32,
+    accounts: tauri::State<'_, AccountRegistry>,
+    sessions: tauri::State<'_, ImapSessionManager>,
 ) -> Result<Vec<u32>, String> {
-    let mut session = imap_client::connect(&config).await?;
-    let uids = imap_client::fetch_new_uids(&mut session, &folder, since_uid).await?;
-    let _ = session.logout().await;
-    Ok(uids)
+    let config = accounts.imap(&account_id).await?;
+    let mut session = sessions.checkout(&account_id, &config).await?;
+    let uids = metrics::time(
+        &config.host,
+        "fetch_new_uids",
+        Phase::Command,
+        imap_client::fetch_new_uids(&mut session, &folder, since_uid),
+    )
+    .await;
+    sessions.checkin(session, &uids);
+    uids
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn imap_search_all_uids(
-    config: ImapConfig,
+    account_id: String,
+    folder: String,
+    accounts: tauri::State<'_, AccountRegistry>,
+    sessions: tauri::State<'_, ImapSessionManager>,
+) -> Result<Vec<u32>, String> {
+    let config = accounts.imap(&account_id).await?;
+    let mut session = sessions.checkout(&account_id, &config).await?;
+    let uids = metrics::time(
+        &config.host,
+        "search_all_uids",
+        Phase::Command,
+        imap_client::search_all_uids(&mut session, &folder),
+    )
+    .await;
+    sessions.checkin(session, &uids);
+    uids
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn imap_search_text(
+    account_id: String,
     folder: String,
+    text: String,
+    before: Option<String>,
+    accounts: tauri::State<'_, AccountRegistry>,
+    sessions: tauri::State<'_, ImapSessionManager>,
 ) -> Result<Vec<u32>, String> {
-    let mut session = imap_client::connect(&config).await?;
-    let uids = imap_client::search_all_uids(&mut session, &folder).await?;
-    let _ = session.logout().await;
-    Ok(uids)
+    let config = accounts.imap(&account_id).await?;
+    let mut session = sessions.checkout(&account_id, &config).await?;
+    let uids = metrics::time(
+        &config.host,
+        "search_text",
+        Phase::Command,
+        imap_client::search_text(&mut session, &folder, &text, before.as_deref()),
+    )
+    .await;
+    sessions.checkin(session, &uids);
+    uids
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn imap_fetch_message_body(
-    config: ImapConfig,
+    account_id: String,
     folder: String,
     uid: u32,
+    accounts: tauri::State<'_, AccountRegistry>,
+    sessions: tauri::State<'_, ImapSessionManager>,
+    identities: tauri::State<'_, Identities>,
 ) -> Result<ImapMessage, String> {
-    let mut session = imap_client::connect(&config).await?;
-    let message = imap_client::fetch_message_body(&mut session, &folder, uid).await?;
-    let _ = session.logout().await;
+    let config = accounts.imap(&account_id).await?;
+    let mut session = sessions.checkout(&account_id, &config).await?;
+    let message = metrics::time(
+        &config.host,
+        "fetch_message_body",
+        Phase::Command,
+        imap_client::fetch_message_body(&mut session, &folder, uid),
+    )
+    .await;
+    sessions.checkin(session, &message);
+    let mut message = message?;
+    identities.annotate([&mut message]);
     Ok(message)
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn imap_fetch_raw_message(
-    config: ImapConfig,
+    account_id: String,
     folder: String,
     uid: u32,
+    accounts: tauri::State<'_, AccountRegistry>,
+    sessions: tauri::State<'_, ImapSessionManager>,
+    window: tauri::Window,
 ) -> Result<String, String> {
-    let mut session = imap_client::connect(&config).await?;
-    let raw = imap_client::fetch_raw_message(&mut session, &folder, uid).await?;
-    let _ = session.logout().await;
-    Ok(raw)
+    require_trusted_window(&window, "imap_fetch_raw_message")?;
+    let config = accounts.imap(&account_id).await?;
+    let mut session = sessions.checkout(&account_id, &config).await?;
+    let raw = metrics::time(
+        &config.host,
+        "fetch_raw_message",
+        Phase::Command,
+        imap_client::fetch_raw_message(&mut session, &folder, uid),
+    )
+    .await;
+    sessions.checkin(session, &raw);
+    raw
 }
 
 #[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
 pub async fn imap_set_flags(
-    config: ImapConfig,
+    account_id: String,
     folder: String,
     uids: Vec<u32>,
     flags: Vec<String>,
     add: bool,
+    keywords: Option<Vec<String>>,
+    accounts: tauri::State<'_, AccountRegistry>,
+    sessions: tauri::State<'_, ImapSessionManager>,
+    settings: tauri::State<'_, SettingsState>,
+    window: tauri::Window,
 ) -> Result<(), String> {
-    if uids.is_empty() {
+    require_trusted_window(&window, "imap_set_flags")?;
+    set_flags(
+        &accounts,
+        &sessions,
+        &settings,
+        &account_id,
+        &folder,
+        &uids,
+        &flags,
+        add,
+        &keywords.unwrap_or_default(),
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn set_flags(
+    accounts: &AccountRegistry,
+    sessions: &ImapSessionManager,
+    settings: &SettingsState,
+    account_id: &str,
+    folder: &str,
+    uids: &[u32],
+    flags: &[String],
+    add: bool,
+    keywords: &[String],
+) -> Result<(), String> {
+    settings.require_writable("imap_set_flags")?;
+    let config = accounts.imap(account_id).await?;
+    if let Some(invalid) = keywords.iter().find(|k| !imap_keywords::valid_keyword(k)) {
+        return Err(format!("Invalid IMAP keyword: {invalid}"));
+    }
+    if uids.is_empty() || (flags.is_empty() && keywords.is_empty()) {
         return Ok(());
     }
 
-    let mut session = imap_client::connect(&config).await?;
-
-    let uid_set: String = uids
-        .iter()
-        .map(|u| u.to_string())
-        .collect::<Vec<_>>()
-        .join(",");
+    let mut session = sessions.checkout(account_id, &config).await?;
 
     let flag_op = if add { "+FLAGS" } else { "-FLAGS" };
 
-    // Format flags like "(\Seen \Flagged)"
+    // Format flags like "(\Seen \Flagged $Pinned $label1)"
     let flags_str = format!(
         "({})",
         flags
             .iter()
             .map(|f| {
-                // Ensure flags have the backslash prefix if they're standard flags
-                if f.starts_with('\\') {
+                // Ensure system flags have the backslash prefix; keywords
+                // like $Pinned are sent as they are
+                if f.starts_with(['\\', '$']) {
                     f.clone()
                 } else {
                     format!("\\{f}")
                 }
             })
+            // Keywords such as color categories are always sent as given
+            .chain(keywords.iter().cloned())
             .collect::<Vec<_>>()
             .join(" ")
     );
 
-    imap_client::set_flags(&mut session, &folder, &uid_set, flag_op, &flags_str).await?;
-    let _ = session.logout().await;
-    Ok(())
+    let result = metrics::time(
+        &config.host,
+        "set_flags",
+        Phase::Command,
+        imap_client::set_flags(&mut session, folder, uids, flag_op, &flags_str),
+    )
+    .await;
+    sessions.checkin(session, &result);
+    result
 }
 
 #[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
 pub async fn imap_move_messages(
-    config: ImapConfig,
+    account_id: String,
     folder: String,
     uids: Vec<u32>,
     destination: String,
+    accounts: tauri::State<'_, AccountRegistry>,
+    sessions: tauri::State<'_, ImapSessionManager>,
+    settings: tauri::State<'_, SettingsState>,
+    window: tauri::Window,
+) -> Result<(), String> {
+    require_trusted_window(&window, "imap_move_messages")?;
+    move_messages(
+        &accounts,
+        &sessions,
+        &settings,
+        &account_id,
+        &folder,
+        &uids,
+        &destination,
+    )
+    .await
+}
+
+pub(crate) async fn move_messages(
+    accounts: &AccountRegistry,
+    sessions: &ImapSessionManager,
+    settings: &SettingsState,
+    account_id: &str,
+    folder: &str,
+    uids: &[u32],
+    destination: &str,
 ) -> Result<(), String> {
+    settings.require_writable("imap_move_messages")?;
+    let config = accounts.imap(account_id).await?;
     if uids.is_empty() {
         return Ok(());
     }
 
-    let mut session = imap_client::connect(&config).await?;
-
-    let uid_set: String = uids
-        .iter()
-        .map(|u| u.to_string())
-        .collect::<Vec<_>>()
-        .join(",");
+    let mut session = sessions.checkout(account_id, &config).await?;
 
-    imap_client::move_messages(&mut session, &folder, &uid_set, &destination).await?;
-    let _ = session.logout().await;
-    Ok(())
+    let result = metrics::time(
+        &config.host,
+        "move_messages",
+        Phase::Command,
+        imap_client::move_messages(&mut session, folder, uids, destination),
+    )
+    .await;
+    sessions.checkin(session, &result);
+    result
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn imap_delete_messages(
-    config: ImapConfig,
+    account_id: String,
     folder: String,
     uids: Vec<u32>,
+    accounts: tauri::State<'_, AccountRegistry>,
+    sessions: tauri::State<'_, ImapSessionManager>,
+    settings: tauri::State<'_, SettingsState>,
+    window: tauri::Window,
+) -> Result<(), String> {
+    require_trusted_window(&window, "imap_delete_messages")?;
+    settings.require_writable("imap_delete_messages")?;
+    delete_messages(&accounts, &sessions, &account_id, &folder, &uids).await
+}
+
+/// Mark `uids` in `folder` `\Deleted` and expunge them. Also used by
+/// `staged_operations` once a delete's undo window has passed.
+pub(crate) async fn delete_messages(
+    accounts: &AccountRegistry,
+    sessions: &ImapSessionManager,
+    account_id: &str,
+    folder: &str,
+    uids: &[u32],
 ) -> Result<(), String> {
+    let config = accounts.imap(account_id).await?;
     if uids.is_empty() {
         return Ok(());
     }
 
-    let mut session = imap_client::connect(&config).await?;
-
-    let uid_set: String = uids
-        .iter()
-        .map(|u| u.to_string())
-        .collect::<Vec<_>>()
-        .join(",");
+    let mut session = sessions.checkout(account_id, &config).await?;
 
-    imap_client::delete_messages(&mut session, &folder, &uid_set).await?;
-    let _ = session.logout().await;
-    Ok(())
+    let result = metrics::time(
+        &config.host,
+        "delete_messages",
+        Phase::Command,
+        imap_client::delete_messages(&mut session, folder, uids),
+    )
+    .await;
+    sessions.checkin(session, &result);
+    result
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn imap_get_folder_status(
-    config: ImapConfig,
+    account_id: String,
     folder: String,
+    accounts: tauri::State<'_, AccountRegistry>,
+    sessions: tauri::State<'_, ImapSessionManager>,
 ) -> Result<ImapFolderStatus, String> {
-    let mut session = imap_client::connect(&config).await?;
-    let status = imap_client::get_folder_status(&mut session, &folder).await?;
-    let _ = session.logout().await;
-    Ok(status)
+    let config = accounts.imap(&account_id).await?;
+    let mut session = sessions.checkout(&account_id, &config).await?;
+    let status = metrics::time(
+        &config.host,
+        "get_folder_status",
+        Phase::Command,
+        imap_client::get_folder_status(&mut session, &folder),
+    )
+    .await;
+    sessions.checkin(session, &status);
+    status
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn imap_fetch_bodystructure(
+    account_id: String,
+    folder: String,
+    uid: u32,
+    accounts: tauri::State<'_, AccountRegistry>,
+    sessions: tauri::State<'_, ImapSessionManager>,
+) -> Result<Vec<ImapAttachment>, String> {
+    let config = accounts.imap(&account_id).await?;
+    let mut session = sessions.checkout(&account_id, &config).await?;
+    let attachments = metrics::time(
+        &config.host,
+        "fetch_bodystructure",
+        Phase::Command,
+        imap_client::fetch_bodystructure(&mut session, &folder, uid),
+    )
+    .await;
+    sessions.checkin(session, &attachments);
+    attachments
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn imap_fetch_attachment(
-    config: ImapConfig,
+    account_id: String,
     folder: String,
     uid: u32,
     part_id: String,
+    accounts: tauri::State<'_, AccountRegistry>,
+    sessions: tauri::State<'_, ImapSessionManager>,
 ) -> Result<String, String> {
-    let mut session = imap_client::connect(&config).await?;
-    let data = imap_client::fetch_attachment(&mut session, &folder, uid, &part_id).await?;
-    let _ = session.logout().await;
-    Ok(data)
+    let config = accounts.imap(&account_id).await?;
+    let mut session = sessions.checkout(&account_id, &config).await?;
+    let data = metrics::time(
+        &config.host,
+        "fetch_attachment",
+        Phase::Command,
+        imap_client::fetch_attachment(&mut session, &folder, uid, &part_id),
+    )
+    .await;
+    sessions.checkin(session, &data);
+    data
 }
 
 #[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
 pub async fn imap_append_message(
-    config: ImapConfig,
+    account_id: String,
     folder: String,
     flags: Option<String>,
     raw_message: String,
+    staging: tauri::State<'_, AttachmentStaging>,
+    accounts: tauri::State<'_, AccountRegistry>,
+    sessions: tauri::State<'_, ImapSessionManager>,
+    window: tauri::Window,
+    settings: tauri::State<'_, SettingsState>,
+) -> Result<(), String> {
+    require_trusted_window(&window, "imap_append_message")?;
+    settings.require_writable("imap_append_message")?;
+    let raw = smtp_client::decode_base64url(&raw_message)?;
+    append_message(
+        &accounts,
+        &sessions,
+        &staging,
+        &account_id,
+        &folder,
+        flags.as_deref(),
+        &raw,
+    )
+    .await
+}
+
+/// Append a message to `folder`, filling in staged attachments. Also used
+/// to save the copy of a sent message.
+async fn append_message(
+    accounts: &AccountRegistry,
+    sessions: &ImapSessionManager,
+    staging: &AttachmentStaging,
+    account_id: &str,
+    folder: &str,
+    flags: Option<&str>,
+    raw: &[u8],
 ) -> Result<(), String> {
-    let mut session = imap_client::connect(&config).await?;
+    let config = accounts.imap(account_id).await?;
+    if !attachments::server_messages(raw, staging).is_empty() {
+        // Forwarded messages are still on the server; let it copy them in
+        let flags = flags.unwrap_or("()");
+        imap_catenate::append(&config, folder, flags, raw, staging, HashMap::new()).await?;
+        return Ok(());
+    }
+
+    // Fill in staged attachments
+    let raw_bytes = attachments::expand_staged_parts(raw, staging)?;
 
-    // raw_message is base64url-encoded; decode it
-    let raw_bytes = base64url_decode(&raw_message)?;
+    let mut session = sessions.checkout(account_id, &config).await?;
 
-    let flags_ref = flags.as_deref();
-    imap_client::append_message(&mut session, &folder, flags_ref, &raw_bytes).await?;
-    let _ = session.logout().await;
-    Ok(())
+    let result = metrics::time(
+        &config.host,
+        "append_message",
+        Phase::Command,
+        imap_client::append_message(&mut session, folder, flags, &raw_bytes),
+    )
+    .await;
+    sessions.checkin(session, &result);
+    result
 }
 
-fn base64url_decode(input: &str) -> Result<Vec<u8>, String> {
-    use base64::Engine;
-    let engine = base64::engine::general_purpose::URL_SAFE_NO_PAD;
-    engine
-        .decode(input)
-        .map_err(|e| format!("base64url decode failed: {e}"))
+/// Save a draft, reusing unchanged attachments from the copy it replaces
+/// when the server supports CATENATE. Returns the new UID if reported.
+#[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
+pub async fn imap_save_draft(
+    account_id: String,
+    folder: String,
+    raw_message: String,
+    replaces_uid: Option<u32>,
+    staging: tauri::State<'_, AttachmentStaging>,
+    drafts: tauri::State<'_, SavedDrafts>,
+    accounts: tauri::State<'_, AccountRegistry>,
+    window: tauri::Window,
+    settings: tauri::State<'_, SettingsState>,
+) -> Result<Option<u32>, String> {
+    require_trusted_window(&window, "imap_save_draft")?;
+    settings.require_writable("imap_save_draft")?;
+    let config = accounts.imap(&account_id).await?;
+    let raw = smtp_client::decode_base64url(&raw_message)?;
+    imap_catenate::save_draft(&config, &folder, &raw, &staging, &drafts, replaces_uid).await
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn imap_sync_folder(
-    config: ImapConfig,
+    account_id: String,
     folder: String,
     batch_size: u32,
+    accounts: tauri::State<'_, AccountRegistry>,
+    sessions: tauri::State<'_, ImapSessionManager>,
+    identities: tauri::State<'_, Identities>,
 ) -> Result<ImapFolderSyncResult, String> {
-    let mut session = imap_client::connect(&config).await?;
-    let result = imap_client::sync_folder(&mut session, &folder, batch_size).await;
-    let _ = session.logout().await;
-    result
+    let config = accounts.imap(&account_id).await?;
+    let mut session = sessions.checkout(&account_id, &config).await?;
+    let result = metrics::time(
+        &config.host,
+        "sync_folder",
+        Phase::Command,
+        imap_client::sync_folder(&mut session, &folder, batch_size),
+    )
+    .await;
+    sessions.checkin(session, &result);
+    let mut result = result?;
+    identities.annotate(&mut result.messages);
+    Ok(result)
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn imap_raw_fetch_diagnostic(
-    config: ImapConfig,
+    account_id: String,
     folder: String,
     uid_range: String,
+    accounts: tauri::State<'_, AccountRegistry>,
+    window: tauri::Window,
 ) -> Result<String, String> {
+    require_trusted_window(&window, "imap_raw_fetch_diagnostic")?;
+    let config = accounts.imap(&account_id).await?;
     imap_client::raw_fetch_diagnostic(&config, &folder, &uid_range).await
 }
 
+/// Check any number of folders for new mail and current counts over one
+/// authenticated connection, instead of one connect per folder.
 #[tauri::command]
+#[specta::specta]
 pub async fn imap_delta_check(
-    config: ImapConfig,
+    account_id: String,
     folders: Vec<DeltaCheckRequest>,
+    accounts: tauri::State<'_, AccountRegistry>,
+    sessions: tauri::State<'_, ImapSessionManager>,
 ) -> Result<Vec<DeltaCheckResult>, String> {
-    let mut session = imap_client::connect(&config).await?;
-    let results = imap_client::delta_check_folders(&mut session, &folders).await?;
-    let _ = session.logout().await;
-    Ok(results)
+    let config = accounts.imap(&account_id).await?;
+    let mut session = sessions.checkout(&account_id, &config).await?;
+    let results = metrics::time(
+        &config.host,
+        "delta_check_folders",
+        Phase::Command,
+        imap_client::delta_check_folders(&mut session, &folders),
+    )
+    .await;
+    sessions.checkin(session, &results);
+    results
+}
+
+/// Start NOTIFY push for an account's folders; `false` if unsupported.
+#[tauri::command]
+#[specta::specta]
+pub async fn imap_start_notify(
+    app: tauri::AppHandle,
+    account_id: String,
+    folders: Vec<String>,
+    accounts: tauri::State<'_, AccountRegistry>,
+) -> Result<bool, String> {
+    let config = accounts.imap(&account_id).await?;
+    imap_notify::start(app, account_id, config, folders).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn imap_stop_notify(app: tauri::AppHandle, account_id: Option<String>) {
+    imap_notify::stop(&app, account_id.as_deref());
+}
+
+/// Start IDLE push for one folder; `false` if unsupported.
+#[tauri::command]
+#[specta::specta]
+pub async fn imap_start_idle(
+    app: tauri::AppHandle,
+    account_id: String,
+    folder: String,
+    accounts: tauri::State<'_, AccountRegistry>,
+) -> Result<bool, String> {
+    let config = accounts.imap(&account_id).await?;
+    imap_idle::start(app, account_id, config, folder).await
+}
+
+/// Stop IDLE for one folder, every folder of an account, or everything.
+#[tauri::command]
+#[specta::specta]
+pub fn imap_stop_idle(app: tauri::AppHandle, account_id: Option<String>, folder: Option<String>) {
+    imap_idle::stop(&app, account_id.as_deref(), folder.as_deref());
 }
 
 // ---------- SMTP commands ----------
 
+/// Tries at saving a sent message to the Sent folder.
+const SENT_APPEND_ATTEMPTS: u32 = 3;
+/// Wait before the next try, times the tries so far.
+const SENT_APPEND_RETRY_MS: u64 = 2_000;
+
 #[tauri::command]
+#[specta::specta]
 pub async fn smtp_send_email(
-    config: SmtpConfig,
+    account_id: String,
     raw_email: String,
+    staging: tauri::State<'_, AttachmentStaging>,
+    accounts: tauri::State<'_, AccountRegistry>,
+    window: tauri::Window,
+    settings: tauri::State<'_, SettingsState>,
 ) -> Result<SmtpSendResult, String> {
-    smtp_client::send_raw_email(&config, &raw_email).await
+    require_trusted_window(&window, "smtp_send_email")?;
+    settings.require_writable("smtp_send_email")?;
+    let raw = smtp_client::decode_base64url(&raw_email)?;
+    send_email(&accounts, &staging, &account_id, &raw).await
 }
 
+/// Send a message and save a `\Seen` copy in `sent_folder`, in one call so
+/// the copy isn't lost if the window goes away in between. Failing to save
+/// the copy is retried, then reported in `sent_copy` rather than as an
+/// error, since the message did go out.
 #[tauri::command]
-pub async fn smtp_test_connection(config: SmtpConfig) -> Result<SmtpSendResult, String> {
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
+pub async fn smtp_send_and_append(
+    account_id: String,
+    raw_email: String,
+    sent_folder: String,
+    staging: tauri::State<'_, AttachmentStaging>,
+    accounts: tauri::State<'_, AccountRegistry>,
+    sessions: tauri::State<'_, ImapSessionManager>,
+    window: tauri::Window,
+    settings: tauri::State<'_, SettingsState>,
+) -> Result<SendAndAppendResult, String> {
+    require_trusted_window(&window, "smtp_send_and_append")?;
+    settings.require_writable("smtp_send_and_append")?;
+    let raw = smtp_client::decode_base64url(&raw_email)?;
+    let sent = send_email(&accounts, &staging, &account_id, &raw).await?;
+    if !sent.success {
+        return Ok(SendAndAppendResult {
+            sent,
+            sent_copy: SentCopy::Skipped,
+        });
+    }
+    if accounts.transport(&account_id) == MailTransport::GmailApi {
+        // Gmail files sent mail itself
+        return Ok(SendAndAppendResult {
+            sent,
+            sent_copy: SentCopy::SavedByServer,
+        });
+    }
+
+    let mut attempt = 1;
+    let sent_copy = loop {
+        match append_message(
+            &accounts,
+            &sessions,
+            &staging,
+            &account_id,
+            &sent_folder,
+            Some("(\\Seen)"),
+            &raw,
+        )
+        .await
+        {
+            Ok(()) => break SentCopy::Saved,
+            Err(e) if attempt < SENT_APPEND_ATTEMPTS => {
+                log::warn!("Saving sent message to {sent_folder} failed (try {attempt}): {e}");
+                tokio::time::sleep(std::time::Duration::from_millis(
+                    SENT_APPEND_RETRY_MS * u64::from(attempt),
+                ))
+                .await;
+                attempt += 1;
+            }
+            Err(error) => {
+                log::error!("Sent message could not be saved to {sent_folder}: {error}");
+                break SentCopy::Failed { error };
+            }
+        }
+    };
+    Ok(SendAndAppendResult { sent, sent_copy })
+}
+
+/// Send a message from `account_id` through its transport. Also used by
+/// the outbox for queued messages.
+pub(crate) async fn send_email(
+    accounts: &AccountRegistry,
+    staging: &AttachmentStaging,
+    account_id: &str,
+    raw: &[u8],
+) -> Result<SmtpSendResult, String> {
+    if accounts.transport(account_id) == MailTransport::GmailApi {
+        gmail_api::send_composed(accounts, account_id, raw, staging, None).await?;
+        return Ok(SmtpSendResult {
+            success: true,
+            message: "Email sent through the Gmail API".to_string(),
+        });
+    }
+    let config = accounts.smtp(account_id).await?;
+    // Used to fetch messages forwarded as attachments, if the account has IMAP
+    let imap_config = accounts.imap(account_id).await.ok();
+    rate_limit::acquire_smtp(&config).await;
+
+    // Messages forwarded as attachments are still on the IMAP server. Try to
+    // have the submission server fetch them itself before downloading them.
+    let server_messages = attachments::server_messages(raw, staging);
+    if let Some(imap_config) = imap_config.as_ref().filter(|_| !server_messages.is_empty()) {
+        let burl = smtp_burl::send(&config, imap_config, raw, staging, &server_messages);
+        match metrics::time(&config.host, "smtp_burl", Phase::Command, burl).await {
+            Ok(Some(result)) => return Ok(result),
+            Ok(None) => {}
+            Err(SubmitError::NotSent(e)) => {
+                log::warn!("BURL submission failed, sending normally: {e}")
+            }
+            // Sending again could deliver the message twice
+            Err(SubmitError::MaybeSent(e)) => return Err(e),
+        }
+        attachments::download_server_messages(imap_config, raw, staging).await?;
+    }
+
+    // Stream big attachments from disk in BDAT chunks when the server allows it
+    if attachments::staged_size(raw, staging) >= smtp_chunking::STREAMING_THRESHOLD {
+        let bdat = smtp_chunking::send(&config, raw, staging);
+        match metrics::time(&config.host, "smtp_bdat", Phase::Command, bdat).await {
+            Ok(Some(result)) => return Ok(result),
+            Ok(None) => {}
+            Err(SubmitError::NotSent(e)) => {
+                log::warn!("BDAT submission failed, sending normally: {e}")
+            }
+            Err(SubmitError::MaybeSent(e)) => return Err(e),
+        }
+    }
+
+    let raw_bytes = attachments::expand_staged_parts(raw, staging)?;
+    metrics::time(
+        &config.host,
+        "smtp_send",
+        Phase::Command,
+        smtp_client::send_raw_bytes(&config, &raw_bytes),
+    )
+    .await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn smtp_test_connection(
+    config: SmtpConfig,
+    window: tauri::Window,
+) -> Result<SmtpSendResult, String> {
+    require_trusted_window(&window, "smtp_test_connection")?;
+    managed::policy().check_config(&config.auth_method, config.accept_invalid_certs)?;
     smtp_client::test_connection(&config).await
 }
+
+// ---------- NNTP commands ----------
+
+#[tauri::command]
+#[specta::specta]
+pub async fn nntp_test_connection(
+    config: NntpConfig,
+    window: tauri::Window,
+) -> Result<String, String> {
+    require_trusted_window(&window, "nntp_test_connection")?;
+    managed::policy().check_config("password", config.accept_invalid_certs)?;
+    nntp_client::test_connection(&config).await
+}
+
+/// Groups on the account's server matching the wildmat `pattern`
+/// (e.g. `comp.lang.*`), or all of them.
+#[tauri::command]
+#[specta::specta]
+pub async fn nntp_list_groups(
+    account_id: String,
+    pattern: Option<String>,
+    accounts: tauri::State<'_, AccountRegistry>,
+) -> Result<Vec<NntpGroup>, String> {
+    let config = accounts.nntp(&account_id).await?;
+    metrics::time(
+        &config.host,
+        "nntp_list_groups",
+        Phase::Command,
+        nntp_client::list_groups(&config, pattern.as_deref()),
+    )
+    .await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn nntp_group_status(
+    account_id: String,
+    group: String,
+    accounts: tauri::State<'_, AccountRegistry>,
+) -> Result<NntpGroupStatus, String> {
+    let config = accounts.nntp(&account_id).await?;
+    nntp_client::group_status(&config, &group).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn nntp_fetch_overview(
+    account_id: String,
+    group: String,
+    first: u32,
+    last: u32,
+    accounts: tauri::State<'_, AccountRegistry>,
+) -> Result<Vec<NntpOverview>, String> {
+    let config = accounts.nntp(&account_id).await?;
+    metrics::time(
+        &config.host,
+        "nntp_overview",
+        Phase::Command,
+        nntp_client::fetch_overview(&config, &group, first, last),
+    )
+    .await
+}
+
+/// Articles of `group` by number, parsed like IMAP messages with the
+/// article number as UID and the group as folder.
+#[tauri::command]
+#[specta::specta]
+pub async fn nntp_fetch_articles(
+    account_id: String,
+    group: String,
+    numbers: Vec<u32>,
+    accounts: tauri::State<'_, AccountRegistry>,
+) -> Result<Vec<ImapMessage>, String> {
+    let config = accounts.nntp(&account_id).await?;
+    metrics::time(
+        &config.host,
+        "nntp_fetch_articles",
+        Phase::Command,
+        nntp_client::fetch_articles(&config, &group, &numbers),
+    )
+    .await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn nntp_fetch_raw_article(
+    account_id: String,
+    group: String,
+    number: u32,
+    accounts: tauri::State<'_, AccountRegistry>,
+    window: tauri::Window,
+) -> Result<String, String> {
+    require_trusted_window(&window, "nntp_fetch_raw_article")?;
+    let config = accounts.nntp(&account_id).await?;
+    let raw = nntp_client::fetch_raw_article(&config, &group, number).await?;
+    Ok(String::from_utf8_lossy(&raw).into_owned())
+}
+
+/// The decoded bytes of an article's attachment, base64-encoded like
+/// `imap_fetch_attachment`.
+#[tauri::command]
+#[specta::specta]
+pub async fn nntp_fetch_attachment(
+    account_id: String,
+    group: String,
+    number: u32,
+    part_id: String,
+    accounts: tauri::State<'_, AccountRegistry>,
+) -> Result<String, String> {
+    let config = accounts.nntp(&account_id).await?;
+    let raw = nntp_client::fetch_raw_article(&config, &group, number).await?;
+    let data = imap_client::decode_attachment(&raw, number, &part_id)?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(&data))
+}
+
+/// Post a composed message (base64url, like `smtp_send_email`) to
+/// `newsgroups`.
+#[tauri::command]
+#[specta::specta]
+pub async fn nntp_post_article(
+    account_id: String,
+    raw_article: String,
+    newsgroups: Vec<String>,
+    accounts: tauri::State<'_, AccountRegistry>,
+    window: tauri::Window,
+    settings: tauri::State<'_, SettingsState>,
+) -> Result<(), String> {
+    require_trusted_window(&window, "nntp_post_article")?;
+    settings.require_writable("nntp_post_article")?;
+    let config = accounts.nntp(&account_id).await?;
+    let raw = smtp_client::decode_base64url(&raw_article)?;
+    let article = nntp_client::prepare_article(&raw, &newsgroups)?;
+    metrics::time(
+        &config.host,
+        "nntp_post",
+        Phase::Command,
+        nntp_client::post(&config, &article),
+    )
+    .await
+}
@@ -0,0 +1,393 @@
+//! Building outgoing messages.
+//!
+//! `smtp_compose_and_send` takes the parts of a message — recipients,
+//! subject, text and HTML bodies, attachments, inline images and reply
+//! headers — and builds the MIME message with lettre instead of the
+//! frontend assembling RFC 2822 itself:
+//!
+//! ```text
+//! multipart/mixed              (only with attachments)
+//! ├─ multipart/alternative     (only with both bodies)
+//! │  ├─ text/plain
+//! │  └─ multipart/related      (only with inline images)
+//! │     ├─ text/html
+//! │     └─ image/* (Content-ID)
+//! └─ attachments
+//! ```
+
+use std::path::Path;
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use lettre::message::header::{ContentType, InReplyTo, References};
+use lettre::message::{Attachment, Mailbox, Message, MultiPart, SinglePart};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::State;
+
+use crate::accounts::{require_trusted_window, AccountRegistry, MailTransport};
+use crate::attachments::{mime_from_extension, AttachmentStaging};
+use crate::gmail_api;
+use crate::metrics::{self, Phase};
+use crate::rate_limit;
+use crate::settings::SettingsState;
+use crate::smtp::client as smtp_client;
+
+/// Where an attachment's content comes from.
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AttachmentSource {
+    /// A file on disk.
+    Path { path: String },
+    /// Standard base64.
+    Base64 { data: String },
+}
+
+#[derive(Debug, Clone, Deserialize, Type)]
+pub struct ComposeAttachment {
+    pub filename: String,
+    /// Guessed from the filename when missing.
+    pub mime_type: Option<String>,
+    pub source: AttachmentSource,
+}
+
+/// An image the HTML body shows with `<img src="cid:...">`.
+#[derive(Debug, Clone, Deserialize, Type)]
+pub struct InlineImage {
+    /// Without angle brackets or the `cid:` prefix.
+    pub content_id: String,
+    pub filename: Option<String>,
+    pub mime_type: Option<String>,
+    pub source: AttachmentSource,
+}
+
+#[derive(Debug, Clone, Deserialize, Type)]
+pub struct ComposeRequest {
+    /// "Name <address>" or a bare address.
+    pub from: String,
+    #[serde(default)]
+    pub to: Vec<String>,
+    #[serde(default)]
+    pub cc: Vec<String>,
+    #[serde(default)]
+    pub bcc: Vec<String>,
+    pub reply_to: Option<String>,
+    pub subject: String,
+    pub text_body: Option<String>,
+    pub html_body: Option<String>,
+    #[serde(default)]
+    pub attachments: Vec<ComposeAttachment>,
+    #[serde(default)]
+    pub inline_images: Vec<InlineImage>,
+    /// Message-ID being replied to, with or without angle brackets.
+    pub in_reply_to: Option<String>,
+    #[serde(default)]
+    pub references: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct ComposeSendResult {
+    pub success: bool,
+    pub message: String,
+    /// The new message's Message-ID, without angle brackets.
+    pub message_id: String,
+    /// The message as sent, base64url, e.g. for the Sent folder. It has a
+    /// Bcc header only for Gmail API accounts, which take Bcc from it.
+    pub raw_email: String,
+}
+
+fn mailbox(address: &str) -> Result<Mailbox, String> {
+    address
+        .trim()
+        .parse()
+        .map_err(|e| format!("Invalid address '{address}': {e}"))
+}
+
+/// `id` in angle brackets, as message ids go in headers.
+fn bracketed(id: &str) -> String {
+    format!(
+        "<{}>",
+        id.trim().trim_start_matches('<').trim_end_matches('>')
+    )
+}
+
+/// A new Message-ID at the sender's domain.
+fn new_message_id(from: &Mailbox) -> String {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    format!("{hex}@{}", from.email.domain())
+}
+
+fn content_type(mime_type: Option<&str>, filename: &str) -> ContentType {
+    let guessed = mime_from_extension(Path::new(filename));
+    ContentType::parse(mime_type.unwrap_or(guessed))
+        .or_else(|_| ContentType::parse(guessed))
+        .unwrap_or_else(|_| {
+            ContentType::parse("application/octet-stream").expect("valid MIME type")
+        })
+}
+
+fn load(source: &AttachmentSource, name: &str) -> Result<Vec<u8>, String> {
+    match source {
+        AttachmentSource::Path { path } => {
+            std::fs::read(path).map_err(|e| format!("Failed to read attachment '{name}': {e}"))
+        }
+        AttachmentSource::Base64 { data } => STANDARD
+            .decode(data.trim())
+            .map_err(|e| format!("Invalid base64 for attachment '{name}': {e}")),
+    }
+}
+
+/// A body part, which lettre's builders take through different methods.
+enum Body {
+    Single(SinglePart),
+    Multi(MultiPart),
+}
+
+/// The HTML body, wrapped with its inline images when there are any.
+fn html_part(html: &str, images: &[InlineImage]) -> Result<Body, String> {
+    if images.is_empty() {
+        return Ok(Body::Single(SinglePart::html(html.to_string())));
+    }
+    let mut related = MultiPart::related().singlepart(SinglePart::html(html.to_string()));
+    for image in images {
+        let cid = image
+            .content_id
+            .trim()
+            .trim_start_matches('<')
+            .trim_end_matches('>');
+        let name = image.filename.clone().unwrap_or_else(|| cid.to_string());
+        let data = load(&image.source, &name)?;
+        let attachment = match &image.filename {
+            Some(filename) => Attachment::new_inline_with_name(cid.to_string(), filename.clone()),
+            None => Attachment::new_inline(cid.to_string()),
+        };
+        related = related
+            .singlepart(attachment.body(data, content_type(image.mime_type.as_deref(), &name)));
+    }
+    Ok(Body::Multi(related))
+}
+
+/// Build the message. The Bcc header is only kept when the transport has
+/// no separate envelope (the Gmail API); SMTP gets Bcc from the envelope.
+/// Returns the new Message-ID with the message.
+pub fn build_message(
+    request: &ComposeRequest,
+    keep_bcc: bool,
+) -> Result<(String, Message), String> {
+    let from = mailbox(&request.from)?;
+    let message_id = new_message_id(&from);
+    let mut builder = Message::builder()
+        .from(from)
+        .subject(request.subject.clone())
+        .message_id(Some(bracketed(&message_id)))
+        .date_now();
+    for address in &request.to {
+        builder = builder.to(mailbox(address)?);
+    }
+    for address in &request.cc {
+        builder = builder.cc(mailbox(address)?);
+    }
+    for address in &request.bcc {
+        builder = builder.bcc(mailbox(address)?);
+    }
+    if let Some(reply_to) = request.reply_to.as_deref().filter(|r| !r.trim().is_empty()) {
+        builder = builder.reply_to(mailbox(reply_to)?);
+    }
+    if let Some(in_reply_to) = request
+        .in_reply_to
+        .as_deref()
+        .filter(|id| !id.trim().is_empty())
+    {
+        builder = builder.header(InReplyTo::from(bracketed(in_reply_to)));
+    }
+    if !request.references.is_empty() {
+        let references: Vec<String> = request.references.iter().map(|id| bracketed(id)).collect();
+        builder = builder.header(References::from(references.join(" ")));
+    }
+    if keep_bcc {
+        builder = builder.keep_bcc();
+    }
+
+    let text = request.text_body.as_deref();
+    let html = request.html_body.as_deref();
+    let body = match (text, html) {
+        (Some(text), Some(html)) => {
+            let alternative =
+                MultiPart::alternative().singlepart(SinglePart::plain(text.to_string()));
+            match html_part(html, &request.inline_images)? {
+                Body::Single(part) => Body::Multi(alternative.singlepart(part)),
+                Body::Multi(part) => Body::Multi(alternative.multipart(part)),
+            }
+        }
+        (None, Some(html)) => html_part(html, &request.inline_images)?,
+        (text, None) => Body::Single(SinglePart::plain(text.unwrap_or_default().to_string())),
+    };
+
+    let message = if request.attachments.is_empty() {
+        match body {
+            Body::Single(part) => builder.singlepart(part),
+            Body::Multi(part) => builder.multipart(part),
+        }
+    } else {
+        let mut mixed = match body {
+            Body::Single(part) => MultiPart::mixed().singlepart(part),
+            Body::Multi(part) => MultiPart::mixed().multipart(part),
+        };
+        for attachment in &request.attachments {
+            let data = load(&attachment.source, &attachment.filename)?;
+            mixed = mixed.singlepart(Attachment::new(attachment.filename.clone()).body(
+                data,
+                content_type(attachment.mime_type.as_deref(), &attachment.filename),
+            ));
+        }
+        builder.multipart(mixed)
+    };
+    let message = message.map_err(|e| format!("Failed to build message: {e}"))?;
+    Ok((message_id, message))
+}
+
+/// Build a message from its parts and send it from `account_id`, through
+/// SMTP or the Gmail API as the account is set up.
+#[tauri::command]
+#[specta::specta]
+pub async fn smtp_compose_and_send(
+    account_id: String,
+    request: ComposeRequest,
+    staging: State<'_, AttachmentStaging>,
+    accounts: State<'_, AccountRegistry>,
+    window: tauri::Window,
+    settings: State<'_, SettingsState>,
+) -> Result<ComposeSendResult, String> {
+    require_trusted_window(&window, "smtp_compose_and_send")?;
+    settings.require_writable("smtp_compose_and_send")?;
+    let gmail = accounts.transport(&account_id) == MailTransport::GmailApi;
+    // Attachments are read from disk
+    let (message_id, message) = tokio::task::spawn_blocking(move || build_message(&request, gmail))
+        .await
+        .map_err(|e| format!("Failed to build message: {e}"))??;
+    let raw = message.formatted();
+
+    let sent = if gmail {
+        gmail_api::send_composed(&accounts, &account_id, &raw, &staging, None).await?;
+        "Email sent through the Gmail API".to_string()
+    } else {
+        let config = accounts.smtp(&account_id).await?;
+        rate_limit::acquire_smtp(&config).await;
+        let send = smtp_client::send_with_envelope(&config, message.envelope(), &raw);
+        metrics::time(&config.host, "smtp_send", Phase::Command, send)
+            .await?
+            .message
+    };
+    Ok(ComposeSendResult {
+        success: true,
+        message: sent,
+        message_id,
+        raw_email: URL_SAFE_NO_PAD.encode(&raw),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mail_parser::MimeHeaders;
+
+    fn request() -> ComposeRequest {
+        ComposeRequest {
+            from: "Alice <alice@example.com>".to_string(),
+            to: vec!["bob@example.com".to_string()],
+            cc: Vec::new(),
+            bcc: vec!["carol@example.com".to_string()],
+            reply_to: None,
+            subject: "Plans".to_string(),
+            text_body: Some("See you at noon".to_string()),
+            html_body: Some("<p>See you at <b>noon</b></p><img src=\"cid:logo\">".to_string()),
+            attachments: Vec::new(),
+            inline_images: Vec::new(),
+            in_reply_to: Some("root@example.com".to_string()),
+            references: vec!["<root@example.com>".to_string()],
+        }
+    }
+
+    fn parsed(message: &Message) -> mail_parser::Message<'static> {
+        mail_parser::MessageParser::default()
+            .parse(&message.formatted())
+            .expect("parses")
+            .into_owned()
+    }
+
+    #[test]
+    fn test_alternative_with_reply_headers() {
+        let (message_id, message) = build_message(&request(), false).unwrap();
+        assert!(message_id.ends_with("@example.com"));
+        assert_eq!(message.envelope().to().len(), 2);
+
+        let parsed = parsed(&message);
+        assert_eq!(parsed.subject(), Some("Plans"));
+        assert_eq!(parsed.in_reply_to().as_text(), Some("root@example.com"));
+        assert_eq!(parsed.message_id(), Some(message_id.as_str()));
+        assert!(parsed.bcc().is_none());
+        assert_eq!(parsed.body_text(0).as_deref(), Some("See you at noon"));
+        assert!(parsed.body_html(0).unwrap().contains("<b>noon</b>"));
+        assert_eq!(parsed.attachment_count(), 0);
+    }
+
+    #[test]
+    fn test_attachments_and_inline_images() {
+        let mut request = request();
+        request.inline_images.push(InlineImage {
+            content_id: "logo".to_string(),
+            filename: Some("logo.png".to_string()),
+            mime_type: None,
+            source: AttachmentSource::Base64 {
+                data: STANDARD.encode(b"\x89PNG"),
+            },
+        });
+        request.attachments.push(ComposeAttachment {
+            filename: "notes.txt".to_string(),
+            mime_type: Some("text/plain".to_string()),
+            source: AttachmentSource::Base64 {
+                data: STANDARD.encode(b"hello"),
+            },
+        });
+        let (_, message) = build_message(&request, true).unwrap();
+
+        let raw = String::from_utf8(message.formatted()).unwrap();
+        assert!(raw.contains("multipart/mixed"));
+        assert!(raw.contains("multipart/alternative"));
+        assert!(raw.contains("multipart/related"));
+        assert!(raw.contains("Content-ID: <logo>"));
+        assert!(raw.contains("Bcc: carol@example.com"));
+
+        let parsed = parsed(&message);
+        let names: Vec<_> = parsed
+            .attachments()
+            .filter_map(|a| a.attachment_name())
+            .collect();
+        assert!(names.contains(&"notes.txt"));
+    }
+
+    #[test]
+    fn test_rejects_bad_input() {
+        let mut bad_address = request();
+        bad_address.to = vec!["not an address".to_string()];
+        assert!(build_message(&bad_address, false)
+            .unwrap_err()
+            .contains("not an address"));
+
+        let mut bad_data = request();
+        bad_data.attachments.push(ComposeAttachment {
+            filename: "x.bin".to_string(),
+            mime_type: None,
+            source: AttachmentSource::Base64 {
+                data: "%%%".to_string(),
+            },
+        });
+        assert!(build_message(&bad_data, false)
+            .unwrap_err()
+            .contains("x.bin"));
+    }
+}
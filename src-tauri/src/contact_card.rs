@@ -0,0 +1,600 @@
+//! Contact details found in a received message.
+//!
+//! Senders often attach their card (`text/vcard`, usually `name.vcf`) or
+//! put phone numbers and links in their signature. [`contact_from_message`]
+//! pulls both out of the raw message so the contact sidebar can offer to
+//! save them to the address book in one click. A photo in the vCard becomes
+//! the contact's avatar; remote photo URLs are ignored, as fetching them
+//! would tell the sender the message was read.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use mail_parser::{MessageParser, MimeHeaders};
+use serde::Serialize;
+use specta::Type;
+
+use crate::attachments::preview_data_url;
+
+/// Longest edge of an avatar taken from a vCard photo.
+const AVATAR_SIZE: u32 = 128;
+/// How many lines at the end of the body count as the signature when the
+/// sender didn't use the `-- ` delimiter.
+const SIGNATURE_TAIL_LINES: usize = 8;
+/// Phone numbers have at most 15 digits (E.164); fewer than 7 is more
+/// likely a date, zip code or ticket number.
+const PHONE_DIGITS: std::ops::RangeInclusive<usize> = 7..=15;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Type)]
+pub struct MessageContact {
+    pub email: Option<String>,
+    pub display_name: Option<String>,
+    pub phones: Vec<String>,
+    pub urls: Vec<String>,
+    pub organization: Option<String>,
+    pub title: Option<String>,
+    /// A PNG data URL made from the vCard's photo.
+    pub avatar_url: Option<String>,
+    /// The details came from an attached vCard rather than only the
+    /// headers and signature.
+    pub from_vcard: bool,
+}
+
+/// One property of a vCard: `group.NAME;PARAM=a,b;BARE:value`.
+#[derive(Debug)]
+struct Property {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl Property {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// vCard 2.1 allows bare parameters, e.g. `PHOTO;JPEG;BASE64:`.
+    fn has_bare_param(&self, value: &str) -> bool {
+        self.params
+            .iter()
+            .any(|(n, v)| v.is_empty() && n.eq_ignore_ascii_case(value))
+    }
+
+    fn encoding_is(&self, encoding: &str) -> bool {
+        self.param("ENCODING")
+            .is_some_and(|e| e.eq_ignore_ascii_case(encoding))
+            || self.has_bare_param(encoding)
+    }
+}
+
+/// Split `text` into the vCards it contains, each a list of properties.
+fn parse_vcards(text: &str) -> Vec<Vec<Property>> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        match lines.last_mut() {
+            // Folded continuation (RFC 6350 §3.2)
+            Some(prev) if line.starts_with([' ', '\t']) => prev.push_str(&line[1..]),
+            // Quoted-printable soft line break (vCard 2.1)
+            Some(prev)
+                if prev.ends_with('=')
+                    && prev.to_ascii_uppercase().contains("QUOTED-PRINTABLE") =>
+            {
+                prev.pop();
+                prev.push_str(line);
+            }
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut cards = Vec::new();
+    let mut current: Option<Vec<Property>> = None;
+    for line in &lines {
+        let Some(property) = parse_property(line) else {
+            continue;
+        };
+        match (
+            property.name.as_str(),
+            property.value.to_ascii_uppercase().as_str(),
+        ) {
+            ("BEGIN", "VCARD") => current = Some(Vec::new()),
+            ("END", "VCARD") => cards.extend(current.take()),
+            _ => {
+                if let Some(card) = &mut current {
+                    card.push(property);
+                }
+            }
+        }
+    }
+    cards
+}
+
+fn parse_property(line: &str) -> Option<Property> {
+    // The value starts at the first colon outside a quoted parameter value
+    let mut in_quotes = false;
+    let colon = line.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            in_quotes = !in_quotes;
+            None
+        }
+        ':' if !in_quotes => Some(i),
+        _ => None,
+    })?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim();
+    let name = name
+        .rsplit_once('.')
+        .map_or(name, |(_, n)| n)
+        .to_ascii_uppercase();
+    let params = parts
+        .map(|p| match p.split_once('=') {
+            Some((n, v)) => (
+                n.trim().to_ascii_uppercase(),
+                v.trim_matches('"').to_string(),
+            ),
+            None => (p.trim().to_ascii_uppercase(), String::new()),
+        })
+        .collect();
+    Some(Property {
+        name,
+        params,
+        value: value.to_string(),
+    })
+}
+
+/// Split a structured value (`N`, `ORG`) on unescaped `;`.
+fn split_components(value: &str) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut escaped = false;
+    for c in value.chars() {
+        let part = parts.last_mut().expect("never empty");
+        if escaped {
+            part.push('\\');
+            part.push(c);
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == ';' {
+            parts.push(String::new());
+        } else {
+            part.push(c);
+        }
+    }
+    parts.into_iter().map(|p| unescape(&p)).collect()
+}
+
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out.trim().to_string()
+}
+
+fn decode_quoted_printable(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'=', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (b, _) => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// A property's value, decoded but still escaped so structured values
+/// can be split.
+fn raw_value(property: &Property) -> String {
+    if property.encoding_is("QUOTED-PRINTABLE") {
+        decode_quoted_printable(&property.value)
+    } else {
+        property.value.clone()
+    }
+}
+
+/// A property's text value, decoded and unescaped.
+fn text_value(property: &Property) -> String {
+    unescape(&raw_value(property))
+}
+
+/// The photo of a vCard as an avatar data URL, if it's embedded.
+fn photo_avatar(property: &Property) -> Option<String> {
+    let value = property.value.trim();
+    let encoded = if let Some(data_url) = value.strip_prefix("data:") {
+        // vCard 4: PHOTO:data:image/jpeg;base64,...
+        data_url.split_once(";base64,")?.1
+    } else if property.encoding_is("b") || property.encoding_is("BASE64") {
+        value
+    } else {
+        return None;
+    };
+    let compact: String = encoded.chars().filter(|c| !c.is_whitespace()).collect();
+    let bytes = STANDARD.decode(compact).ok()?;
+    let img = image::load_from_memory(&bytes).ok()?;
+    preview_data_url(&img, AVATAR_SIZE).ok()
+}
+
+fn push_unique(list: &mut Vec<String>, value: String) {
+    if !value.is_empty() && !list.contains(&value) {
+        list.push(value);
+    }
+}
+
+/// Fill `contact` from a vCard's properties.
+fn apply_vcard(contact: &mut MessageContact, card: &[Property]) {
+    let mut emails = Vec::new();
+    for property in card {
+        match property.name.as_str() {
+            "FN" => contact.display_name = Some(text_value(property)).filter(|n| !n.is_empty()),
+            "N" if contact.display_name.is_none() => {
+                // Family;Given;Additional;Prefix;Suffix
+                let parts = split_components(&raw_value(property));
+                let given = parts.get(1).map(String::as_str).unwrap_or_default();
+                let family = parts.first().map(String::as_str).unwrap_or_default();
+                let name = format!("{given} {family}").trim().to_string();
+                contact.display_name = Some(name).filter(|n| !n.is_empty());
+            }
+            "EMAIL" => push_unique(&mut emails, text_value(property).to_lowercase()),
+            "TEL" => {
+                let tel = text_value(property);
+                push_unique(
+                    &mut contact.phones,
+                    tel.trim_start_matches("tel:").to_string(),
+                );
+            }
+            "URL" => push_unique(&mut contact.urls, text_value(property)),
+            "ORG" => {
+                let org = split_components(&raw_value(property));
+                contact.organization = org.into_iter().next().filter(|o| !o.is_empty());
+            }
+            "TITLE" => contact.title = Some(text_value(property)).filter(|t| !t.is_empty()),
+            "PHOTO" => {
+                if let Some(avatar) = photo_avatar(property) {
+                    contact.avatar_url = Some(avatar);
+                }
+            }
+            _ => {}
+        }
+    }
+    if contact.email.is_none() {
+        contact.email = emails.into_iter().next();
+    }
+    contact.from_vcard = true;
+}
+
+/// The contacts in a `.vcf` file, each with every address its card lists
+/// (the contact's `email` is the first of them).
+pub(crate) fn contacts_from_vcf(text: &str) -> Vec<(MessageContact, Vec<String>)> {
+    parse_vcards(text)
+        .iter()
+        .map(|card| {
+            let mut contact = MessageContact::default();
+            apply_vcard(&mut contact, card);
+            let mut emails = Vec::new();
+            for property in card.iter().filter(|p| p.name == "EMAIL") {
+                push_unique(&mut emails, text_value(property).to_lowercase());
+            }
+            (contact, emails)
+        })
+        .collect()
+}
+
+/// The vCard that lists `sender`'s address, or the only one there is if
+/// it lists no address at all. A card with other addresses is someone
+/// else's (e.g. a forwarded contact), not the sender's.
+fn pick_vcard<'a>(cards: &'a [Vec<Property>], sender: Option<&str>) -> Option<&'a [Property]> {
+    let emails =
+        |card: &'a Vec<Property>| card.iter().filter(|p| p.name == "EMAIL").map(text_value);
+    let lists_sender = |card: &&'a Vec<Property>| {
+        sender.is_some_and(|sender| emails(card).any(|e| e.eq_ignore_ascii_case(sender)))
+    };
+    let anonymous = |card: &&'a Vec<Property>| cards.len() == 1 && emails(card).next().is_none();
+    cards
+        .iter()
+        .find(lists_sender)
+        .or_else(|| cards.iter().find(anonymous))
+        .map(Vec::as_slice)
+}
+
+/// The signature of a plain-text body: the lines after the `-- `
+/// delimiter, or else the last few lines before any quoted reply.
+fn signature_block(body: &str) -> Vec<&str> {
+    let own: Vec<&str> = body
+        .lines()
+        .take_while(|line| !line.trim_start().starts_with('>'))
+        .collect();
+    if let Some(delimiter) = own.iter().rposition(|line| line.trim_end() == "--") {
+        return own[delimiter + 1..]
+            .iter()
+            .copied()
+            .take(SIGNATURE_TAIL_LINES * 2)
+            .collect();
+    }
+    let lines: Vec<&str> = own.into_iter().filter(|l| !l.trim().is_empty()).collect();
+    lines[lines.len().saturating_sub(SIGNATURE_TAIL_LINES)..].to_vec()
+}
+
+/// `2024-01-15`, `15/01/2024`, `1.2.2024`: groups of digits where one has
+/// four and the others at most two.
+fn looks_like_date(candidate: &str) -> bool {
+    let groups: Vec<&str> = candidate
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|g| !g.is_empty())
+        .collect();
+    groups.len() == 3
+        && groups.iter().filter(|g| g.len() == 4).count() == 1
+        && groups.iter().all(|g| g.len() == 4 || g.len() <= 2)
+}
+
+/// Phone numbers in one signature line. Fax numbers are skipped.
+fn phones_in_line(line: &str) -> Vec<String> {
+    let is_phone_char = |c: char| c.is_ascii_digit() || " +()-./".contains(c);
+
+    let mut phones = Vec::new();
+    let mut pos = 0;
+    while let Some(offset) = line[pos..].find(|c: char| c.is_ascii_digit() || c == '+' || c == '(')
+    {
+        let start = pos + offset;
+        let len = line[start..]
+            .find(|c: char| !is_phone_char(c))
+            .unwrap_or(line.len() - start);
+        let candidate = line[start..start + len].trim_end_matches([' ', '-', '.', '/', '(']);
+        let digits = candidate.chars().filter(char::is_ascii_digit).count();
+        // The text since the previous number labels this one ("Fax:")
+        let label = line[..start]
+            .rsplit(|c: char| c.is_ascii_digit())
+            .next()
+            .unwrap_or_default();
+        if PHONE_DIGITS.contains(&digits)
+            && !looks_like_date(candidate)
+            && !label.to_ascii_lowercase().contains("fax")
+        {
+            phones.push(candidate.to_string());
+        }
+        pos = start + len.max(1);
+    }
+    phones
+}
+
+/// Web links in one signature line.
+fn urls_in_line(line: &str) -> Vec<String> {
+    line.split(|c: char| c.is_whitespace() || c == '<' || c == '>' || c == '|')
+        .map(|token| token.trim_end_matches(['.', ',', ';', ')', ']']))
+        .filter_map(|token| {
+            let lower = token.to_ascii_lowercase();
+            if lower.starts_with("https://") || lower.starts_with("http://") {
+                Some(token.to_string())
+            } else if lower.starts_with("www.") && token.len() > 4 {
+                Some(format!("https://{token}"))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Extract the sender's contact details from a raw RFC 5322 message.
+pub fn contact_from_raw(raw: &[u8]) -> Result<MessageContact, String> {
+    let message = MessageParser::default()
+        .parse(raw)
+        .ok_or_else(|| "Failed to parse message".to_string())?;
+
+    let sender = message.from().and_then(|from| from.first());
+    let sender_address = sender.and_then(|a| a.address()).map(str::to_lowercase);
+    let mut contact = MessageContact {
+        email: sender_address.clone(),
+        ..MessageContact::default()
+    };
+
+    let cards: Vec<Vec<Property>> = message
+        .parts
+        .iter()
+        .filter(|part| {
+            let is_vcard_type = part.content_type().is_some_and(|ct| {
+                ct.ctype().eq_ignore_ascii_case("text")
+                    && ct.subtype().is_some_and(|s| {
+                        ["vcard", "x-vcard", "directory"]
+                            .iter()
+                            .any(|v| s.eq_ignore_ascii_case(v))
+                    })
+            });
+            let is_vcf = part
+                .attachment_name()
+                .is_some_and(|n| n.to_ascii_lowercase().ends_with(".vcf"));
+            is_vcard_type || is_vcf
+        })
+        .flat_map(|part| parse_vcards(&String::from_utf8_lossy(part.contents())))
+        .collect();
+    if let Some(card) = pick_vcard(&cards, sender_address.as_deref()) {
+        apply_vcard(&mut contact, card);
+    }
+
+    if let Some(body) = message.body_text(0) {
+        for line in signature_block(&body) {
+            for phone in phones_in_line(line) {
+                push_unique(&mut contact.phones, phone);
+            }
+            for url in urls_in_line(line) {
+                push_unique(&mut contact.urls, url);
+            }
+        }
+    }
+
+    if contact.display_name.is_none() {
+        contact.display_name = sender
+            .and_then(|a| a.name())
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty());
+    }
+    Ok(contact)
+}
+
+/// Contact details of a message's sender from an attached vCard and the
+/// signature. Takes the raw message so it works for every provider.
+#[tauri::command]
+#[specta::specta]
+pub async fn contact_from_message(raw: String) -> Result<MessageContact, String> {
+    tauri::async_runtime::spawn_blocking(move || contact_from_raw(raw.as_bytes()))
+        .await
+        .map_err(|e| format!("Failed to read contact details: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(extra_parts: &str, body: &str) -> String {
+        format!(
+            "From: Alice Example <Alice@Example.com>\r\n\
+             To: bob@example.org\r\n\
+             Subject: Hello\r\n\
+             MIME-Version: 1.0\r\n\
+             Content-Type: multipart/mixed; boundary=\"b\"\r\n\
+             \r\n\
+             --b\r\n\
+             Content-Type: text/plain; charset=utf-8\r\n\
+             \r\n\
+             {body}\r\n\
+             {extra_parts}\
+             --b--\r\n"
+        )
+    }
+
+    #[test]
+    fn test_vcard_attachment() {
+        let vcard = "--b\r\n\
+             Content-Type: text/vcard; name=\"alice.vcf\"\r\n\
+             Content-Disposition: attachment; filename=\"alice.vcf\"\r\n\
+             \r\n\
+             BEGIN:VCARD\r\n\
+             VERSION:3.0\r\n\
+             N:Example;Alice;;;\r\n\
+             FN:Alice Example\\, PhD\r\n\
+             ORG:Example Corp;Research\r\n\
+             TITLE:Principal Engineer\r\n\
+             item1.EMAIL;TYPE=INTERNET:alice@example.com\r\n\
+             TEL;TYPE=CELL:+1 555 010 2030\r\n\
+             URL:https://alice.example.com/\r\n\
+             PHOTO;ENCODING=b;TYPE=PNG:iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAIAAACQd1Pe\r\n \
+             AAAADElEQVR4nGP4z8AAAAMBAQDJ/pLvAAAAAElFTkSuQmCC\r\n\
+             END:VCARD\r\n";
+        let raw = message(vcard, "Card attached.");
+        let contact = contact_from_raw(raw.as_bytes()).unwrap();
+
+        assert!(contact.from_vcard);
+        assert_eq!(contact.email.as_deref(), Some("alice@example.com"));
+        assert_eq!(contact.display_name.as_deref(), Some("Alice Example, PhD"));
+        assert_eq!(contact.organization.as_deref(), Some("Example Corp"));
+        assert_eq!(contact.title.as_deref(), Some("Principal Engineer"));
+        assert_eq!(contact.phones, vec!["+1 555 010 2030"]);
+        assert_eq!(contact.urls, vec!["https://alice.example.com/"]);
+        assert!(contact
+            .avatar_url
+            .unwrap()
+            .starts_with("data:image/png;base64,"));
+    }
+
+    #[test]
+    fn test_signature_only() {
+        let body = "See you Tuesday 2024-05-14.\r\n\
+                    \r\n\
+                    -- \r\n\
+                    Alice Example | Example Corp\r\n\
+                    Mobile: (555) 010-2030  Fax: 555 010 9999\r\n\
+                    Office: +44 20 7946 0958\r\n\
+                    www.example.com | <https://example.com/alice>.\r\n\
+                    \r\n\
+                    > On Monday, Bob wrote:\r\n\
+                    > call me at 555 123 4567";
+        let raw = message("", body);
+        let contact = contact_from_raw(raw.as_bytes()).unwrap();
+
+        assert!(!contact.from_vcard);
+        assert_eq!(contact.display_name.as_deref(), Some("Alice Example"));
+        assert_eq!(contact.email.as_deref(), Some("alice@example.com"));
+        assert_eq!(contact.phones, vec!["(555) 010-2030", "+44 20 7946 0958"]);
+        assert_eq!(
+            contact.urls,
+            vec!["https://www.example.com", "https://example.com/alice"]
+        );
+    }
+
+    #[test]
+    fn test_parse_vcards_folding_and_versions() {
+        let text = "BEGIN:VCARD\r\n\
+                    VERSION:2.1\r\n\
+                    FN;ENCODING=QUOTED-PRINTABLE;CHARSET=UTF-8:J=C3=BCrgen M=\r\n\
+                    =C3=BCller\r\n\
+                    NOTE:a long note that is\r\n \
+                    folded\r\n\
+                    END:VCARD\r\n\
+                    BEGIN:VCARD\r\n\
+                    VERSION:4.0\r\n\
+                    TEL;VALUE=uri;TYPE=\"voice,cell\":tel:+1-555-010-2030\r\n\
+                    END:VCARD\r\n";
+        let cards = parse_vcards(text);
+        assert_eq!(cards.len(), 2);
+
+        let mut first = MessageContact::default();
+        apply_vcard(&mut first, &cards[0]);
+        assert_eq!(first.display_name.as_deref(), Some("Jürgen Müller"));
+        let note = cards[0].iter().find(|p| p.name == "NOTE").unwrap();
+        assert_eq!(note.value, "a long note that isfolded");
+
+        let mut second = MessageContact::default();
+        apply_vcard(&mut second, &cards[1]);
+        assert_eq!(second.phones, vec!["+1-555-010-2030"]);
+    }
+
+    #[test]
+    fn test_forwarded_vcard_is_not_the_sender() {
+        let vcard = "--b\r\n\
+             Content-Type: text/x-vcard\r\n\
+             \r\n\
+             BEGIN:VCARD\r\n\
+             FN:Carol Other\r\n\
+             EMAIL:carol@example.net\r\n\
+             END:VCARD\r\n";
+        let raw = message(vcard, "Here's Carol's card.");
+        let contact = contact_from_raw(raw.as_bytes()).unwrap();
+
+        assert!(!contact.from_vcard);
+        assert_eq!(contact.display_name.as_deref(), Some("Alice Example"));
+        assert_eq!(contact.email.as_deref(), Some("alice@example.com"));
+    }
+
+    #[test]
+    fn test_phones_in_line() {
+        assert_eq!(
+            phones_in_line("T: +81 3-1234-5678"),
+            vec!["+81 3-1234-5678"]
+        );
+        assert!(phones_in_line("Updated 2024-01-15 and 15/01/2024").is_empty());
+        assert!(phones_in_line("Suite 1200, Springfield 12345").is_empty());
+        assert!(phones_in_line("Fax: 555 010 9999").is_empty());
+    }
+}
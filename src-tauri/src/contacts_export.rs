@@ -0,0 +1,373 @@
+//! Exporting the address book.
+//!
+//! Writes the contacts table — addresses harvested from mail as well as
+//! imported ones — to a vCard file (3.0 for older clients, 4.0 otherwise)
+//! or a CSV with Google Contacts' column names, which [`contacts_import`]
+//! and most other clients read back. Contact groups become vCard
+//! `CATEGORIES` and the CSV's "Group Membership" column.
+//!
+//! [`contacts_import`]: crate::contacts_import
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Deserialize;
+use specta::Type;
+use sqlx::SqlitePool;
+use tauri::State;
+use tauri_plugin_fs::FsExt;
+use tauri_plugin_sql::{DbInstances, DbPool};
+
+use crate::accounts::require_trusted_window;
+use crate::profiles::Profiles;
+
+/// vCard lines are folded at 75 octets (RFC 6350 §3.2).
+const FOLD_WIDTH: usize = 75;
+const CSV_HEADER: &str = "Name,E-mail 1 - Value,Phone 1 - Value,Website 1 - Value,\
+                          Organization 1 - Name,Organization 1 - Title,Notes,Group Membership";
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Vcard3,
+    Vcard4,
+    Csv,
+}
+
+/// Which contacts to export.
+#[derive(Debug, Deserialize, Type)]
+pub struct ExportFilter {
+    /// Only members of this contact group.
+    pub group_id: Option<String>,
+    /// Only contacts mailed with at least this often.
+    pub min_frequency: Option<i64>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct ExportedContact {
+    email: String,
+    display_name: Option<String>,
+    avatar_url: Option<String>,
+    notes: Option<String>,
+    phones: Vec<String>,
+    urls: Vec<String>,
+    organization: Option<String>,
+    job_title: Option<String>,
+    groups: Vec<String>,
+}
+
+/// A JSON list column (`phones`, `urls`), tolerating bad data.
+fn json_list(value: Option<String>) -> Vec<String> {
+    value
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_default()
+}
+
+async fn load_contacts(
+    pool: &SqlitePool,
+    filter: &ExportFilter,
+) -> Result<Vec<ExportedContact>, String> {
+    type Row = (
+        String,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+    );
+    let rows: Vec<Row> = sqlx::query_as(
+        "SELECT email, display_name, avatar_url, notes, phones, urls, organization, job_title
+         FROM contacts
+         WHERE ($1 IS NULL OR email IN (SELECT email FROM contact_group_members WHERE group_id = $1))
+           AND frequency >= $2
+         ORDER BY frequency DESC, email ASC",
+    )
+    .bind(&filter.group_id)
+    .bind(filter.min_frequency.unwrap_or(0))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to read contacts: {e}"))?;
+
+    let memberships: Vec<(String, String)> = sqlx::query_as(
+        "SELECT m.email, g.name FROM contact_group_members m
+         JOIN contact_groups g ON g.id = m.group_id
+         ORDER BY g.name",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to read contact groups: {e}"))?;
+    let mut groups: HashMap<String, Vec<String>> = HashMap::new();
+    for (email, name) in memberships {
+        groups.entry(email).or_default().push(name);
+    }
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(email, display_name, avatar_url, notes, phones, urls, organization, job_title)| {
+                ExportedContact {
+                    groups: groups.remove(&email).unwrap_or_default(),
+                    email,
+                    display_name,
+                    avatar_url,
+                    notes,
+                    phones: json_list(phones),
+                    urls: json_list(urls),
+                    organization,
+                    job_title,
+                }
+            },
+        )
+        .collect())
+}
+
+/// Escape a vCard text value (RFC 6350 §3.4).
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ',' => escaped.push_str("\\,"),
+            ';' => escaped.push_str("\\;"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Append a content line, folded so no line exceeds [`FOLD_WIDTH`] octets.
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > FOLD_WIDTH {
+            out.push_str("\r\n ");
+            // The leading space counts towards the continuation's width
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+/// The PHOTO property for an avatar: embedded if it's a data URL, linked
+/// otherwise.
+fn photo_line(avatar_url: &str, format: ExportFormat) -> Option<String> {
+    if let Some(data) = avatar_url.strip_prefix("data:") {
+        let (media_type, base64) = data.split_once(";base64,")?;
+        return Some(match format {
+            ExportFormat::Vcard3 => {
+                let subtype = media_type.strip_prefix("image/").unwrap_or(media_type);
+                format!(
+                    "PHOTO;ENCODING=b;TYPE={}:{base64}",
+                    subtype.to_ascii_uppercase()
+                )
+            }
+            _ => format!("PHOTO:{avatar_url}"),
+        });
+    }
+    if !avatar_url.starts_with("https://") && !avatar_url.starts_with("http://") {
+        return None;
+    }
+    Some(match format {
+        ExportFormat::Vcard3 => format!("PHOTO;VALUE=uri:{avatar_url}"),
+        _ => format!("PHOTO:{avatar_url}"),
+    })
+}
+
+fn write_vcard(out: &mut String, contact: &ExportedContact, format: ExportFormat) {
+    let version = if format == ExportFormat::Vcard3 {
+        "3.0"
+    } else {
+        "4.0"
+    };
+    push_line(out, "BEGIN:VCARD");
+    push_line(out, &format!("VERSION:{version}"));
+    // FN is required; fall back to the address like other clients do
+    let name = contact.display_name.as_deref().unwrap_or(&contact.email);
+    push_line(out, &format!("FN:{}", escape(name)));
+    if format == ExportFormat::Vcard3 {
+        // N is required in 3.0 but can be left empty
+        push_line(out, "N:;;;;");
+    }
+    let email_type = if format == ExportFormat::Vcard3 {
+        ";TYPE=INTERNET"
+    } else {
+        ""
+    };
+    push_line(
+        out,
+        &format!("EMAIL{email_type}:{}", escape(&contact.email)),
+    );
+    for phone in &contact.phones {
+        push_line(out, &format!("TEL:{}", escape(phone)));
+    }
+    for url in &contact.urls {
+        push_line(out, &format!("URL:{url}"));
+    }
+    if let Some(organization) = &contact.organization {
+        push_line(out, &format!("ORG:{}", escape(organization)));
+    }
+    if let Some(title) = &contact.job_title {
+        push_line(out, &format!("TITLE:{}", escape(title)));
+    }
+    if let Some(notes) = &contact.notes {
+        push_line(out, &format!("NOTE:{}", escape(notes)));
+    }
+    if !contact.groups.is_empty() {
+        let categories: Vec<String> = contact.groups.iter().map(|g| escape(g)).collect();
+        push_line(out, &format!("CATEGORIES:{}", categories.join(",")));
+    }
+    if let Some(photo) = contact
+        .avatar_url
+        .as_deref()
+        .and_then(|url| photo_line(url, format))
+    {
+        push_line(out, &photo);
+    }
+    push_line(out, "END:VCARD");
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn write_csv_row(out: &mut String, contact: &ExportedContact) {
+    // Google's separator for several values in one cell
+    let multi = |values: &[String]| values.join(" ::: ");
+    let fields = [
+        contact.display_name.clone().unwrap_or_default(),
+        contact.email.clone(),
+        multi(&contact.phones),
+        multi(&contact.urls),
+        contact.organization.clone().unwrap_or_default(),
+        contact.job_title.clone().unwrap_or_default(),
+        contact.notes.clone().unwrap_or_default(),
+        multi(&contact.groups),
+    ];
+    let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+    out.push_str(&row.join(","));
+    out.push_str("\r\n");
+}
+
+fn render(contacts: &[ExportedContact], format: ExportFormat) -> String {
+    let mut out = String::new();
+    if format == ExportFormat::Csv {
+        out.push_str(CSV_HEADER);
+        out.push_str("\r\n");
+        for contact in contacts {
+            write_csv_row(&mut out, contact);
+        }
+    } else {
+        for contact in contacts {
+            write_vcard(&mut out, contact, format);
+        }
+    }
+    out
+}
+
+/// Write the contacts matching `filter` to `path`, which must come from the
+/// save dialog. Returns how many were exported.
+#[tauri::command]
+#[specta::specta]
+pub async fn contacts_export(
+    window: tauri::Window,
+    db: State<'_, DbInstances>,
+    profiles: State<'_, Profiles>,
+    path: String,
+    format: ExportFormat,
+    filter: ExportFilter,
+) -> Result<u32, String> {
+    require_trusted_window(&window, "contacts_export")?;
+    let path = Path::new(&path);
+    if !window.fs_scope().is_allowed(path) {
+        log::warn!(
+            "Refused to export contacts to {}: not chosen by the user",
+            path.display()
+        );
+        return Err(format!("{} wasn't chosen in a save dialog", path.display()));
+    }
+
+    let instances = db.0.read().await;
+    let DbPool::Sqlite(pool) = instances
+        .get(&profiles.db_url())
+        .ok_or_else(|| "Database is not loaded".to_string())?;
+
+    let contacts = load_contacts(pool, &filter).await?;
+    std::fs::write(path, render(&contacts, format))
+        .map_err(|e| format!("Cannot write {}: {e}", path.display()))?;
+    log::info!(
+        "Exported {} contact(s) to {}",
+        contacts.len(),
+        path.display()
+    );
+    Ok(contacts.len() as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alice() -> ExportedContact {
+        ExportedContact {
+            email: "alice@example.com".to_string(),
+            display_name: Some("Example, Alice".to_string()),
+            phones: vec!["+1 555 0100".to_string(), "555 0101".to_string()],
+            organization: Some("Example Corp".to_string()),
+            job_title: Some("Engineer".to_string()),
+            notes: Some("Met at the \"offsite\"\nLikes tea".to_string()),
+            groups: vec!["Team".to_string()],
+            avatar_url: Some("data:image/png;base64,iVBORw0KGgo=".to_string()),
+            ..ExportedContact::default()
+        }
+    }
+
+    #[test]
+    fn test_vcard() {
+        let card = render(&[alice()], ExportFormat::Vcard3);
+        assert_eq!(
+            card,
+            "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Example\\, Alice\r\nN:;;;;\r\n\
+             EMAIL;TYPE=INTERNET:alice@example.com\r\nTEL:+1 555 0100\r\nTEL:555 0101\r\n\
+             ORG:Example Corp\r\nTITLE:Engineer\r\n\
+             NOTE:Met at the \"offsite\"\\nLikes tea\r\nCATEGORIES:Team\r\n\
+             PHOTO;ENCODING=b;TYPE=PNG:iVBORw0KGgo=\r\nEND:VCARD\r\n"
+        );
+        let card = render(&[alice()], ExportFormat::Vcard4);
+        assert!(card.contains("VERSION:4.0\r\n"));
+        assert!(!card.contains("N:;;;;"));
+        assert!(card.contains("\r\nEMAIL:alice@example.com\r\n"));
+        assert!(card.contains("PHOTO:data:image/png;base64,iVBORw0KGgo=\r\n"));
+    }
+
+    #[test]
+    fn test_fold_long_lines() {
+        let mut out = String::new();
+        push_line(&mut out, &format!("NOTE:{}", "é".repeat(50)));
+        let lines: Vec<&str> = out.split("\r\n").collect();
+        assert!(lines.iter().all(|l| l.len() <= FOLD_WIDTH));
+        assert!(lines[1].starts_with(' '));
+        let unfolded = out.replace("\r\n ", "");
+        assert_eq!(unfolded.trim_end(), format!("NOTE:{}", "é".repeat(50)));
+    }
+
+    #[test]
+    fn test_csv() {
+        let csv = render(&[alice()], ExportFormat::Csv);
+        assert_eq!(
+            csv,
+            format!(
+                "{CSV_HEADER}\r\n\"Example, Alice\",alice@example.com,+1 555 0100 ::: 555 0101,,\
+                 Example Corp,Engineer,\"Met at the \"\"offsite\"\"\nLikes tea\",Team\r\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,547 @@
+//! Importing an address book exported from another client.
+//!
+//! Reads a CSV export (Google Contacts, Outlook, or any file whose columns
+//! are mapped by hand) or a vCard file, merges entries that share an
+//! address, and adds them to the contacts table so autocompletion knows
+//! them before the first message is synced. A dry run only reports what
+//! would be imported, so the frontend can show a preview and let the user
+//! fix the column mapping first.
+//!
+//! Like details saved from a message's vCard, an import only fills in what
+//! a contact is missing; names and numbers already saved are kept.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use sqlx::SqlitePool;
+use tauri::State;
+use tauri_plugin_fs::FsExt;
+use tauri_plugin_sql::{DbInstances, DbPool};
+
+use crate::accounts::require_trusted_window;
+use crate::contact_card::{contacts_from_vcf, MessageContact};
+use crate::profiles::Profiles;
+
+/// Address books are a few megabytes at most; anything far larger is
+/// probably not one.
+const MAX_FILE_SIZE: u64 = 32 * 1024 * 1024;
+/// Google packs several values into one cell with this separator.
+const MULTI_VALUE_SEPARATOR: &str = ":::";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportFormat {
+    Csv,
+    Vcard,
+}
+
+/// The contact detail a CSV column holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ContactField {
+    /// The full name, used as is.
+    Name,
+    FirstName,
+    LastName,
+    Email,
+    Phone,
+    Url,
+    Organization,
+    Title,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct ImportColumn {
+    pub header: String,
+    /// `None` for columns that aren't imported.
+    pub field: Option<ContactField>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct ImportedContact {
+    pub email: String,
+    pub display_name: Option<String>,
+    pub phones: Vec<String>,
+    pub urls: Vec<String>,
+    pub organization: Option<String>,
+    pub title: Option<String>,
+    pub avatar_url: Option<String>,
+    /// A contact with this address is already saved; only its missing
+    /// details are filled in.
+    pub exists: bool,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct ContactsImport {
+    pub format: ImportFormat,
+    /// The CSV columns and what each was imported as; empty for vCards.
+    pub columns: Vec<ImportColumn>,
+    /// One per address, in file order.
+    pub contacts: Vec<ImportedContact>,
+    /// Entries without a usable address, which autocompletion can't use.
+    pub skipped: u32,
+    /// Entries folded into an earlier one with the same address.
+    pub merged: u32,
+    /// The contacts were saved (false for a dry run).
+    pub saved: bool,
+}
+
+/// Split CSV text into records (RFC 4180: quoted fields may contain the
+/// delimiter, doubled quotes and line breaks).
+fn parse_csv(text: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => quoted = false,
+                _ => field.push(c),
+            }
+        } else if c == '"' {
+            quoted = true;
+        } else if c == delimiter {
+            record.push(std::mem::take(&mut field));
+        } else if c == '\n' || c == '\r' {
+            if c == '\r' && chars.peek() == Some(&'\n') {
+                chars.next();
+            }
+            record.push(std::mem::take(&mut field));
+            records.push(std::mem::take(&mut record));
+        } else {
+            field.push(c);
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+        .into_iter()
+        .filter(|r| r.iter().any(|f| !f.trim().is_empty()))
+        .collect()
+}
+
+/// The delimiter of a CSV file, judged by its header line. Outlook uses
+/// semicolons in locales where the comma is the decimal separator.
+fn detect_delimiter(text: &str) -> char {
+    let header = text.lines().next().unwrap_or_default();
+    [',', ';', '\t']
+        .into_iter()
+        .max_by_key(|d| header.matches(*d).count())
+        .filter(|d| header.contains(*d))
+        .unwrap_or(',')
+}
+
+/// The field a column holds, judged by its header as Google Contacts and
+/// Outlook name them ("E-mail 1 - Value", "E-mail Address", "Job Title").
+fn guess_field(header: &str) -> Option<ContactField> {
+    let key: String = header
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect::<String>()
+        .to_ascii_lowercase();
+    if matches!(key.as_str(), "name" | "fullname" | "displayname") {
+        return Some(ContactField::Name);
+    }
+    // "E-mail 1 - Type", "E-mail Display Name" and the like describe a value
+    // rather than hold one
+    if ["type", "label", "display"].iter().any(|w| key.contains(w)) {
+        return None;
+    }
+    let field = match key.as_str() {
+        "firstname" | "givenname" => ContactField::FirstName,
+        "lastname" | "familyname" | "surname" => ContactField::LastName,
+        "company" | "organization" | "organizationname" | "organization1name" => {
+            ContactField::Organization
+        }
+        // Outlook's plain "Title" is the honorific (Mr., Dr.)
+        "jobtitle" | "organizationtitle" | "organization1title" => ContactField::Title,
+        "url" | "webpage" | "website" => ContactField::Url,
+        _ if key.contains("email") => ContactField::Email,
+        _ if key.contains("phone") || key.contains("mobile") => ContactField::Phone,
+        _ if key.starts_with("website") => ContactField::Url,
+        _ => return None,
+    };
+    Some(field)
+}
+
+/// The address in an imported value, lowercased, if it looks like one.
+fn normalize_email(value: &str) -> Option<String> {
+    let email = value.trim().trim_start_matches("mailto:").to_lowercase();
+    let (local, domain) = email.split_once('@')?;
+    let valid = !local.is_empty()
+        && domain.contains('.')
+        && !domain.contains('@')
+        && !email.contains(char::is_whitespace);
+    valid.then_some(email)
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    Some(value.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+fn push_unique(list: &mut Vec<String>, value: &str) {
+    if let Some(value) = non_empty(value) {
+        if !list.contains(&value) {
+            list.push(value);
+        }
+    }
+}
+
+fn fill(value: &mut Option<String>, other: &Option<String>) {
+    if value.is_none() {
+        value.clone_from(other);
+    }
+}
+
+/// Contacts keyed by address, in the order they were first seen.
+#[derive(Default)]
+struct Merged {
+    contacts: Vec<ImportedContact>,
+    index: HashMap<String, usize>,
+    skipped: u32,
+    merged: u32,
+}
+
+impl Merged {
+    /// Add an entry under each of its addresses, or count it as skipped if
+    /// it has none.
+    fn add(&mut self, details: MessageContact, emails: Vec<String>) {
+        let emails: Vec<String> = emails.iter().filter_map(|e| normalize_email(e)).collect();
+        if emails.is_empty() {
+            self.skipped += 1;
+            return;
+        }
+        for email in emails {
+            let Some(&i) = self.index.get(&email) else {
+                self.index.insert(email.clone(), self.contacts.len());
+                self.contacts.push(ImportedContact {
+                    email,
+                    display_name: details.display_name.clone(),
+                    phones: details.phones.clone(),
+                    urls: details.urls.clone(),
+                    organization: details.organization.clone(),
+                    title: details.title.clone(),
+                    avatar_url: details.avatar_url.clone(),
+                    exists: false,
+                });
+                continue;
+            };
+            self.merged += 1;
+            let existing = &mut self.contacts[i];
+            fill(&mut existing.display_name, &details.display_name);
+            fill(&mut existing.organization, &details.organization);
+            fill(&mut existing.title, &details.title);
+            fill(&mut existing.avatar_url, &details.avatar_url);
+            for phone in &details.phones {
+                push_unique(&mut existing.phones, phone);
+            }
+            for url in &details.urls {
+                push_unique(&mut existing.urls, url);
+            }
+        }
+    }
+}
+
+/// Map each CSV column to a field: `mapping` where given (by column
+/// position), otherwise guessed from the header.
+fn map_columns(header: &[String], mapping: Option<&[Option<ContactField>]>) -> Vec<ImportColumn> {
+    header
+        .iter()
+        .enumerate()
+        .map(|(i, h)| ImportColumn {
+            header: h.trim().to_string(),
+            field: match mapping {
+                Some(mapping) => mapping.get(i).copied().flatten(),
+                None => guess_field(h),
+            },
+        })
+        .collect()
+}
+
+fn import_csv(text: &str, mapping: Option<&[Option<ContactField>]>) -> (Vec<ImportColumn>, Merged) {
+    let mut records = parse_csv(text, detect_delimiter(text)).into_iter();
+    let columns = map_columns(&records.next().unwrap_or_default(), mapping);
+    let mut merged = Merged::default();
+
+    for record in records {
+        let mut details = MessageContact::default();
+        let mut emails = Vec::new();
+        let (mut first, mut last) = (None, None);
+        for (column, value) in columns.iter().zip(&record) {
+            let Some(field) = column.field else {
+                continue;
+            };
+            let values = || value.split(MULTI_VALUE_SEPARATOR);
+            match field {
+                ContactField::Name => {
+                    details.display_name = details.display_name.or(non_empty(value))
+                }
+                ContactField::FirstName => first = first.or(non_empty(value)),
+                ContactField::LastName => last = last.or(non_empty(value)),
+                ContactField::Email => values().for_each(|v| push_unique(&mut emails, v)),
+                ContactField::Phone => values().for_each(|v| push_unique(&mut details.phones, v)),
+                ContactField::Url => values().for_each(|v| push_unique(&mut details.urls, v)),
+                ContactField::Organization => {
+                    details.organization = details.organization.or(non_empty(value))
+                }
+                ContactField::Title => details.title = details.title.or(non_empty(value)),
+            }
+        }
+        if details.display_name.is_none() {
+            let name = [first, last]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(" ");
+            details.display_name = non_empty(&name);
+        }
+        merged.add(details, emails);
+    }
+    (columns, merged)
+}
+
+fn import_vcard(text: &str) -> Merged {
+    let mut merged = Merged::default();
+    for (contact, emails) in contacts_from_vcf(text) {
+        merged.add(contact, emails);
+    }
+    merged
+}
+
+fn is_vcard(path: &Path, text: &str) -> bool {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase());
+    matches!(extension.as_deref(), Some("vcf" | "vcard"))
+        || text
+            .trim_start()
+            .get(..11)
+            .is_some_and(|start| start.eq_ignore_ascii_case("BEGIN:VCARD"))
+}
+
+fn read_file(path: &Path) -> Result<String, String> {
+    let metadata =
+        std::fs::metadata(path).map_err(|e| format!("Cannot read {}: {e}", path.display()))?;
+    if !metadata.is_file() {
+        return Err(format!("{} is not a file", path.display()));
+    }
+    if metadata.len() > MAX_FILE_SIZE {
+        return Err(format!(
+            "{} is too large to be an address book",
+            path.display()
+        ));
+    }
+    let bytes = std::fs::read(path).map_err(|e| format!("Cannot read {}: {e}", path.display()))?;
+    let text = String::from_utf8_lossy(&bytes);
+    Ok(text.trim_start_matches('\u{feff}').to_string())
+}
+
+async fn saved_emails(pool: &SqlitePool) -> Result<HashSet<String>, String> {
+    let emails: Vec<String> = sqlx::query_scalar("SELECT email FROM contacts")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to read contacts: {e}"))?;
+    Ok(emails.into_iter().collect())
+}
+
+async fn save(pool: &SqlitePool, contacts: &[ImportedContact]) -> Result<(), String> {
+    let list = |values: &[String]| {
+        (!values.is_empty()).then(|| serde_json::to_string(values).unwrap_or_default())
+    };
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start import: {e}"))?;
+    for contact in contacts {
+        sqlx::query(
+            "INSERT INTO contacts (id, email, display_name, avatar_url, phones, urls, organization, job_title)
+             VALUES (lower(hex(randomblob(16))), $1, $2, $3, $4, $5, $6, $7)
+             ON CONFLICT(email) DO UPDATE SET
+               display_name = COALESCE(display_name, $2),
+               avatar_url = COALESCE(avatar_url, $3),
+               phones = COALESCE(phones, $4),
+               urls = COALESCE(urls, $5),
+               organization = COALESCE(organization, $6),
+               job_title = COALESCE(job_title, $7),
+               updated_at = unixepoch()",
+        )
+        .bind(&contact.email)
+        .bind(&contact.display_name)
+        .bind(&contact.avatar_url)
+        .bind(list(&contact.phones))
+        .bind(list(&contact.urls))
+        .bind(&contact.organization)
+        .bind(&contact.title)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to import {}: {e}", contact.email))?;
+    }
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to finish import: {e}"))
+}
+
+/// Import contacts from a CSV or vCard file chosen in the open dialog.
+/// `mapping` overrides the guessed meaning of each CSV column by position.
+/// With `dry_run` nothing is saved and the result is a preview.
+#[tauri::command]
+#[specta::specta]
+pub async fn contacts_import(
+    window: tauri::Window,
+    db: State<'_, DbInstances>,
+    profiles: State<'_, Profiles>,
+    path: String,
+    mapping: Option<Vec<Option<ContactField>>>,
+    dry_run: bool,
+) -> Result<ContactsImport, String> {
+    require_trusted_window(&window, "contacts_import")?;
+    let path = Path::new(&path);
+    if !window.fs_scope().is_allowed(path) {
+        log::warn!(
+            "Refused to import contacts from {}: not chosen by the user",
+            path.display()
+        );
+        return Err(format!("{} wasn't chosen in a file dialog", path.display()));
+    }
+    let text = read_file(path)?;
+    let (format, columns, mut merged) = if is_vcard(path, &text) {
+        (ImportFormat::Vcard, Vec::new(), import_vcard(&text))
+    } else {
+        let (columns, merged) = import_csv(&text, mapping.as_deref());
+        (ImportFormat::Csv, columns, merged)
+    };
+
+    let instances = db.0.read().await;
+    let DbPool::Sqlite(pool) = instances
+        .get(&profiles.db_url())
+        .ok_or_else(|| "Database is not loaded".to_string())?;
+    let saved = saved_emails(pool).await?;
+    for contact in &mut merged.contacts {
+        contact.exists = saved.contains(&contact.email);
+    }
+    if !dry_run {
+        save(pool, &merged.contacts).await?;
+        log::info!(
+            "Imported {} contact(s) from {}",
+            merged.contacts.len(),
+            path.display()
+        );
+    }
+
+    Ok(ContactsImport {
+        format,
+        columns,
+        contacts: merged.contacts,
+        skipped: merged.skipped,
+        merged: merged.merged,
+        saved: !dry_run,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv() {
+        let text = "Name,Notes\r\n\"Smith, Jane\",\"said \"\"hi\"\"\nthen left\"\r\n\r\nBob,\n";
+        assert_eq!(
+            parse_csv(text, ','),
+            vec![
+                vec!["Name", "Notes"],
+                vec!["Smith, Jane", "said \"hi\"\nthen left"],
+                vec!["Bob", ""],
+            ]
+        );
+        assert_eq!(detect_delimiter("First Name;Last Name;E-mail Address"), ';');
+    }
+
+    #[test]
+    fn test_google_export() {
+        let text = "Name,Given Name,Family Name,E-mail 1 - Type,E-mail 1 - Value,Phone 1 - Value,Organization 1 - Name,Organization 1 - Title\n\
+                    Alice Example,Alice,Example,* Work,alice@example.com ::: Alice@Home.example,+1 555 0100,Example Corp,Engineer\n\
+                    ,Bob,Jones,,bob@example.org,,,\n\
+                    No Address,,,,,555 0199,,\n";
+        let (columns, merged) = import_csv(text, None);
+
+        assert_eq!(columns[3].field, None);
+        assert_eq!(columns[4].field, Some(ContactField::Email));
+        assert_eq!(merged.skipped, 1);
+        let emails: Vec<&str> = merged.contacts.iter().map(|c| c.email.as_str()).collect();
+        assert_eq!(
+            emails,
+            vec!["alice@example.com", "alice@home.example", "bob@example.org"]
+        );
+        let alice = &merged.contacts[1];
+        assert_eq!(alice.display_name.as_deref(), Some("Alice Example"));
+        assert_eq!(alice.phones, vec!["+1 555 0100"]);
+        assert_eq!(alice.organization.as_deref(), Some("Example Corp"));
+        assert_eq!(alice.title.as_deref(), Some("Engineer"));
+        assert_eq!(
+            merged.contacts[2].display_name.as_deref(),
+            Some("Bob Jones")
+        );
+    }
+
+    #[test]
+    fn test_outlook_export_with_duplicates() {
+        let text = "Title,First Name,Last Name,Company,Job Title,E-mail Address,E-mail Display Name,Mobile Phone,Business Phone\n\
+                    Dr.,Carol,White,Acme,,carol@acme.example,Carol White (carol@acme.example),555 0101,\n\
+                    ,,,,CTO,CAROL@acme.example,,,555 0102\n";
+        let (columns, merged) = import_csv(text, None);
+
+        assert_eq!(columns[0].field, None);
+        assert_eq!(columns[4].field, Some(ContactField::Title));
+        assert_eq!(columns[6].field, None);
+        assert_eq!(merged.merged, 1);
+        assert_eq!(
+            merged.contacts,
+            vec![ImportedContact {
+                email: "carol@acme.example".to_string(),
+                display_name: Some("Carol White".to_string()),
+                phones: vec!["555 0101".to_string(), "555 0102".to_string()],
+                urls: Vec::new(),
+                organization: Some("Acme".to_string()),
+                title: Some("CTO".to_string()),
+                avatar_url: None,
+                exists: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_custom_mapping() {
+        let text = "Who,Where\nDan,dan@example.net\n";
+        let mapping = [Some(ContactField::Name), Some(ContactField::Email)];
+        let (_, merged) = import_csv(text, Some(&mapping));
+
+        assert_eq!(merged.contacts.len(), 1);
+        assert_eq!(merged.contacts[0].email, "dan@example.net");
+        assert_eq!(merged.contacts[0].display_name.as_deref(), Some("Dan"));
+    }
+
+    #[test]
+    fn test_vcard_file() {
+        let text = "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Erin\r\nEMAIL:erin@example.com\r\nEMAIL:erin@work.example\r\nEND:VCARD\r\n\
+                    BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Erin E.\r\nEMAIL:ERIN@example.com\r\nTEL:555 0103\r\nEND:VCARD\r\n";
+        assert!(is_vcard(Path::new("contacts.txt"), text));
+        let merged = import_vcard(text);
+
+        assert_eq!(merged.contacts.len(), 2);
+        assert_eq!(merged.merged, 1);
+        assert_eq!(merged.contacts[0].display_name.as_deref(), Some("Erin"));
+        assert_eq!(merged.contacts[0].phones, vec!["555 0103"]);
+        assert_eq!(merged.contacts[1].email, "erin@work.example");
+    }
+}
@@ -0,0 +1,127 @@
+//! Account passwords kept in the OS keychain (macOS Keychain, Windows
+//! Credential Manager, or the Secret Service on Linux).
+//!
+//! An account whose password is in the keychain registers its IMAP/SMTP
+//! configs with an empty password, and [`AccountRegistry`] fills it in from
+//! here when a command needs it, so the password doesn't travel through the
+//! webview on every registration. Without a usable keychain (e.g. no Secret
+//! Service running) `credentials_store` fails and the frontend keeps the
+//! password encrypted in the database as before.
+//!
+//! OAuth accounts keep their grant (refresh token and client) in a second
+//! entry, `<account id>:oauth`, managed by [`crate::oauth::TokenManager`].
+//!
+//! [`AccountRegistry`]: crate::accounts::AccountRegistry
+
+use keyring::Entry;
+
+use crate::accounts::{require_trusted_window, AccountRegistry};
+
+/// Keychain service the entries are stored under, one per account id.
+const SERVICE: &str = "dev.lutelute.sora";
+
+fn entry(account_id: &str) -> Result<Entry, String> {
+    Entry::new(SERVICE, account_id).map_err(|e| format!("Keychain unavailable: {e}"))
+}
+
+fn store(account_id: &str, secret: &str) -> Result<(), String> {
+    entry(account_id)?
+        .set_password(secret)
+        .map_err(|e| format!("Failed to store the password in the keychain: {e}"))
+}
+
+fn get(account_id: &str) -> Result<Option<String>, String> {
+    match entry(account_id)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!(
+            "Failed to read the password from the keychain: {e}"
+        )),
+    }
+}
+
+fn delete(account_id: &str) -> Result<(), String> {
+    match entry(account_id)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!(
+            "Failed to delete the password from the keychain: {e}"
+        )),
+    }
+}
+
+// Keychain calls can block on a D-Bus round trip or an OS unlock prompt, so
+// they run on a blocking thread.
+
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(f)
+        .await
+        .map_err(|e| format!("Keychain access failed: {e}"))?
+}
+
+fn grant_key(account_id: &str) -> String {
+    format!("{account_id}:oauth")
+}
+
+/// The OAuth grant JSON of an account, if its grant is in the keychain.
+pub async fn stored_oauth_grant(account_id: &str) -> Result<Option<String>, String> {
+    let key = grant_key(account_id);
+    blocking(move || get(&key)).await
+}
+
+pub async fn store_oauth_grant(account_id: &str, json: String) -> Result<(), String> {
+    let key = grant_key(account_id);
+    blocking(move || store(&key, &json)).await
+}
+
+pub async fn delete_oauth_grant(account_id: &str) -> Result<(), String> {
+    let key = grant_key(account_id);
+    blocking(move || delete(&key)).await
+}
+
+/// The keychain password of an account registered without one.
+pub async fn stored_password(account_id: &str) -> Result<String, String> {
+    let id = account_id.to_string();
+    blocking(move || get(&id))
+        .await?
+        .ok_or_else(|| format!("No password in the keychain for account {account_id}"))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn credentials_store(
+    window: tauri::Window,
+    registry: tauri::State<'_, AccountRegistry>,
+    account_id: String,
+    secret: String,
+) -> Result<(), String> {
+    require_trusted_window(&window, "credentials_store")?;
+    registry.forget_stored_password(&account_id);
+    blocking(move || store(&account_id, &secret)).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn credentials_get(
+    window: tauri::Window,
+    account_id: String,
+) -> Result<Option<String>, String> {
+    require_trusted_window(&window, "credentials_get")?;
+    blocking(move || get(&account_id)).await
+}
+
+/// Remove an account's password and OAuth grant; succeeds if there were
+/// none.
+#[tauri::command]
+#[specta::specta]
+pub async fn credentials_delete(
+    window: tauri::Window,
+    registry: tauri::State<'_, AccountRegistry>,
+    account_id: String,
+) -> Result<(), String> {
+    require_trusted_window(&window, "credentials_delete")?;
+    registry.forget_stored_password(&account_id);
+    crate::oauth::tokens().remove(&account_id).await?;
+    blocking(move || delete(&account_id)).await
+}
@@ -0,0 +1,488 @@
+//! Date grouping for the message list.
+//!
+//! Given message timestamps, assigns each one a display bucket (Today,
+//! Yesterday, This week, or "Month Year") and a short date label in the
+//! user's locale and timezone. Doing this in one pass here keeps grouping
+//! identical everywhere and avoids building thousands of `Intl` formatters
+//! in the webview for large caches.
+//!
+//! There is no ICU data in the app, so labels come from a small built-in
+//! table (English, German, French, Spanish, Japanese, Chinese, Korean);
+//! other locales fall back to English.
+
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, Timelike, Utc,
+};
+use serde::Serialize;
+use specta::Type;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Lang {
+    En,
+    De,
+    Fr,
+    Es,
+    Ja,
+    Zh,
+    Ko,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct DateLocale {
+    lang: Lang,
+    /// US-style English: month-first dates and 12-hour clock.
+    us: bool,
+    week_starts_sunday: bool,
+}
+
+impl DateLocale {
+    /// Parse a BCP 47 tag like "en-US", "de", "ja-JP" or "zh_Hans_CN".
+    fn parse(tag: &str) -> Self {
+        let mut parts = tag.split(['-', '_']);
+        let lang_code = parts.next().unwrap_or("").to_ascii_lowercase();
+        let region = parts.find(|p| p.len() == 2).map(|p| p.to_ascii_uppercase());
+        let region = region.as_deref();
+
+        let lang = match lang_code.as_str() {
+            "de" => Lang::De,
+            "fr" => Lang::Fr,
+            "es" => Lang::Es,
+            "ja" => Lang::Ja,
+            "zh" => Lang::Zh,
+            "ko" => Lang::Ko,
+            _ => Lang::En,
+        };
+        let us = lang == Lang::En && matches!(region, None | Some("US"));
+        let week_starts_sunday = us
+            || matches!(lang, Lang::Ja | Lang::Ko)
+            || matches!(region, Some("CA" | "MX" | "BR" | "TW" | "HK"));
+
+        Self {
+            lang,
+            us,
+            week_starts_sunday,
+        }
+    }
+
+    fn today(&self) -> &'static str {
+        match self.lang {
+            Lang::En => "Today",
+            Lang::De => "Heute",
+            Lang::Fr => "Aujourd'hui",
+            Lang::Es => "Hoy",
+            Lang::Ja => "今日",
+            Lang::Zh => "今天",
+            Lang::Ko => "오늘",
+        }
+    }
+
+    fn yesterday(&self) -> &'static str {
+        match self.lang {
+            Lang::En => "Yesterday",
+            Lang::De => "Gestern",
+            Lang::Fr => "Hier",
+            Lang::Es => "Ayer",
+            Lang::Ja => "昨日",
+            Lang::Zh => "昨天",
+            Lang::Ko => "어제",
+        }
+    }
+
+    fn this_week(&self) -> &'static str {
+        match self.lang {
+            Lang::En => "This week",
+            Lang::De => "Diese Woche",
+            Lang::Fr => "Cette semaine",
+            Lang::Es => "Esta semana",
+            Lang::Ja => "今週",
+            Lang::Zh => "本周",
+            Lang::Ko => "이번 주",
+        }
+    }
+
+    /// Full month name (`month` is 1-based).
+    fn month(&self, month: u32) -> &'static str {
+        const EN: [&str; 12] = [
+            "January",
+            "February",
+            "March",
+            "April",
+            "May",
+            "June",
+            "July",
+            "August",
+            "September",
+            "October",
+            "November",
+            "December",
+        ];
+        const DE: [&str; 12] = [
+            "Januar",
+            "Februar",
+            "März",
+            "April",
+            "Mai",
+            "Juni",
+            "Juli",
+            "August",
+            "September",
+            "Oktober",
+            "November",
+            "Dezember",
+        ];
+        const FR: [&str; 12] = [
+            "janvier",
+            "février",
+            "mars",
+            "avril",
+            "mai",
+            "juin",
+            "juillet",
+            "août",
+            "septembre",
+            "octobre",
+            "novembre",
+            "décembre",
+        ];
+        const ES: [&str; 12] = [
+            "enero",
+            "febrero",
+            "marzo",
+            "abril",
+            "mayo",
+            "junio",
+            "julio",
+            "agosto",
+            "septiembre",
+            "octubre",
+            "noviembre",
+            "diciembre",
+        ];
+        let names = match self.lang {
+            Lang::De => &DE,
+            Lang::Fr => &FR,
+            Lang::Es => &ES,
+            _ => &EN,
+        };
+        names[(month as usize - 1) % 12]
+    }
+
+    /// Abbreviated month name (`month` is 1-based).
+    fn month_short(&self, month: u32) -> &'static str {
+        const EN: [&str; 12] = [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ];
+        const DE: [&str; 12] = [
+            "Jan.", "Feb.", "März", "Apr.", "Mai", "Juni", "Juli", "Aug.", "Sept.", "Okt.", "Nov.",
+            "Dez.",
+        ];
+        const FR: [&str; 12] = [
+            "janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.",
+            "nov.", "déc.",
+        ];
+        const ES: [&str; 12] = [
+            "ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sept", "oct", "nov", "dic",
+        ];
+        let names = match self.lang {
+            Lang::De => &DE,
+            Lang::Fr => &FR,
+            Lang::Es => &ES,
+            _ => &EN,
+        };
+        names[(month as usize - 1) % 12]
+    }
+
+    /// Abbreviated weekday name (`weekday` counts from Monday = 0).
+    fn weekday_short(&self, weekday: u32) -> &'static str {
+        let names: [&str; 7] = match self.lang {
+            Lang::En => ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+            Lang::De => ["Mo.", "Di.", "Mi.", "Do.", "Fr.", "Sa.", "So."],
+            Lang::Fr => ["lun.", "mar.", "mer.", "jeu.", "ven.", "sam.", "dim."],
+            Lang::Es => ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"],
+            Lang::Ja => ["月", "火", "水", "木", "金", "土", "日"],
+            Lang::Zh => ["周一", "周二", "周三", "周四", "周五", "周六", "周日"],
+            Lang::Ko => ["월", "화", "수", "목", "금", "토", "일"],
+        };
+        names[weekday as usize % 7]
+    }
+
+    fn month_year(&self, year: i32, month: u32) -> String {
+        match self.lang {
+            Lang::Ja | Lang::Zh => format!("{year}年{month}月"),
+            Lang::Ko => format!("{year}년 {month}월"),
+            Lang::Es => format!("{} de {year}", self.month(month)),
+            _ => format!("{} {year}", self.month(month)),
+        }
+    }
+
+    fn time(&self, hour: u32, minute: u32) -> String {
+        if self.us {
+            let (h12, suffix) = match hour {
+                0 => (12, "AM"),
+                1..=11 => (hour, "AM"),
+                12 => (12, "PM"),
+                _ => (hour - 12, "PM"),
+            };
+            format!("{h12}:{minute:02} {suffix}")
+        } else {
+            format!("{hour:02}:{minute:02}")
+        }
+    }
+
+    fn month_day(&self, month: u32, day: u32) -> String {
+        match self.lang {
+            Lang::En if self.us => format!("{} {day}", self.month_short(month)),
+            Lang::En | Lang::Fr | Lang::Es => format!("{day} {}", self.month_short(month)),
+            Lang::De => format!("{day}. {}", self.month_short(month)),
+            Lang::Ja | Lang::Zh => format!("{month}月{day}日"),
+            Lang::Ko => format!("{month}월 {day}일"),
+        }
+    }
+
+    fn full_date(&self, year: i32, month: u32, day: u32) -> String {
+        match self.lang {
+            Lang::En if self.us => format!("{} {day}, {year}", self.month_short(month)),
+            Lang::En => format!("{day} {} {year}", self.month_short(month)),
+            Lang::De => format!("{day:02}.{month:02}.{year}"),
+            Lang::Fr | Lang::Es => format!("{day:02}/{month:02}/{year}"),
+            Lang::Ja | Lang::Zh => format!("{year}/{month:02}/{day:02}"),
+            Lang::Ko => format!("{year}. {month}. {day}."),
+        }
+    }
+}
+
+/// A display group in the message list.
+#[derive(Debug, Clone, Serialize, PartialEq, Type)]
+pub struct DateBucket {
+    /// Stable key: "today", "yesterday", "this_week" or "YYYY-MM".
+    pub key: String,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Type)]
+pub struct DateBucketItem {
+    /// Index into `DateBucketResult::buckets`.
+    pub bucket: usize,
+    pub short_date: String,
+}
+
+#[derive(Debug, Clone, Serialize, Default, Type)]
+pub struct DateBucketResult {
+    /// Buckets in order of first appearance in the input.
+    pub buckets: Vec<DateBucket>,
+    /// One entry per input timestamp, in input order.
+    pub items: Vec<DateBucketItem>,
+}
+
+/// Convert a millisecond timestamp to local wall-clock time.
+fn to_local(timestamp_ms: i64, offset: Option<FixedOffset>) -> NaiveDateTime {
+    let utc = DateTime::<Utc>::from_timestamp_millis(timestamp_ms).unwrap_or_default();
+    match offset {
+        Some(offset) => utc.with_timezone(&offset).naive_local(),
+        None => utc.with_timezone(&Local).naive_local(),
+    }
+}
+
+/// Bucket and format `timestamps` (milliseconds) relative to `now_ms`.
+fn bucket_dates(
+    timestamps: &[i64],
+    locale: DateLocale,
+    offset: Option<FixedOffset>,
+    now_ms: i64,
+) -> DateBucketResult {
+    let today = to_local(now_ms, offset).date();
+    let yesterday = today - Duration::days(1);
+    let days_into_week = if locale.week_starts_sunday {
+        today.weekday().num_days_from_sunday()
+    } else {
+        today.weekday().num_days_from_monday()
+    };
+    let week_start = today - Duration::days(days_into_week as i64);
+
+    let mut result = DateBucketResult::default();
+    let mut bucket_index: HashMap<String, usize> = HashMap::new();
+
+    for &ts in timestamps {
+        let local = to_local(ts, offset);
+        let date: NaiveDate = local.date();
+
+        // Future dates (clock skew) count as today
+        let (key, label, short_date) = if date >= today {
+            let time = locale.time(local.hour(), local.minute());
+            ("today".to_string(), locale.today().to_string(), time)
+        } else if date == yesterday {
+            let label = locale.yesterday().to_string();
+            ("yesterday".to_string(), label.clone(), label)
+        } else if date >= week_start {
+            let weekday = locale.weekday_short(date.weekday().num_days_from_monday());
+            (
+                "this_week".to_string(),
+                locale.this_week().to_string(),
+                weekday.to_string(),
+            )
+        } else {
+            let short = if date.year() == today.year() {
+                locale.month_day(date.month(), date.day())
+            } else {
+                locale.full_date(date.year(), date.month(), date.day())
+            };
+            (
+                format!("{:04}-{:02}", date.year(), date.month()),
+                locale.month_year(date.year(), date.month()),
+                short,
+            )
+        };
+
+        let bucket = *bucket_index.entry(key.clone()).or_insert_with(|| {
+            result.buckets.push(DateBucket { key, label });
+            result.buckets.len() - 1
+        });
+        result.items.push(DateBucketItem { bucket, short_date });
+    }
+
+    result
+}
+
+/// Group message timestamps (milliseconds) into list buckets with short
+/// date labels.
+///
+/// `locale` is a BCP 47 tag (e.g. from `navigator.language`).
+/// `utc_offset_minutes` overrides the timezone (minutes east of UTC); by
+/// default the system timezone is used, which also handles DST changes.
+#[tauri::command]
+#[specta::specta]
+pub fn bucket_message_dates(
+    timestamps: Vec<i64>,
+    locale: Option<String>,
+    utc_offset_minutes: Option<i32>,
+) -> Result<DateBucketResult, String> {
+    let offset = match utc_offset_minutes {
+        Some(minutes) => Some(
+            FixedOffset::east_opt(minutes * 60)
+                .ok_or_else(|| format!("Invalid UTC offset: {minutes} minutes"))?,
+        ),
+        None => None,
+    };
+    let locale = DateLocale::parse(locale.as_deref().unwrap_or("en-US"));
+    Ok(bucket_dates(
+        &timestamps,
+        locale,
+        offset,
+        Utc::now().timestamp_millis(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// Wednesday 2024-03-13 15:30 UTC
+    fn now_ms() -> i64 {
+        Utc.with_ymd_and_hms(2024, 3, 13, 15, 30, 0)
+            .unwrap()
+            .timestamp_millis()
+    }
+
+    fn ms(y: i32, m: u32, d: u32, h: u32, min: u32) -> i64 {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0)
+            .unwrap()
+            .timestamp_millis()
+    }
+
+    fn utc() -> Option<FixedOffset> {
+        FixedOffset::east_opt(0)
+    }
+
+    #[test]
+    fn test_buckets_en_us() {
+        let timestamps = [
+            ms(2024, 3, 13, 9, 5),  // today
+            ms(2024, 3, 12, 20, 0), // yesterday
+            ms(2024, 3, 10, 8, 0),  // Sunday: this week (US weeks start Sunday)
+            ms(2024, 3, 2, 8, 0),   // earlier this month
+            ms(2024, 3, 1, 8, 0),   // same month bucket
+            ms(2023, 11, 20, 8, 0), // last year
+        ];
+        let result = bucket_dates(&timestamps, DateLocale::parse("en-US"), utc(), now_ms());
+
+        let keys: Vec<&str> = result.buckets.iter().map(|b| b.key.as_str()).collect();
+        assert_eq!(
+            keys,
+            ["today", "yesterday", "this_week", "2024-03", "2023-11"]
+        );
+        assert_eq!(result.buckets[3].label, "March 2024");
+
+        let short: Vec<&str> = result.items.iter().map(|i| i.short_date.as_str()).collect();
+        assert_eq!(
+            short,
+            [
+                "9:05 AM",
+                "Yesterday",
+                "Sun",
+                "Mar 2",
+                "Mar 1",
+                "Nov 20, 2023"
+            ]
+        );
+        assert_eq!(result.items[4].bucket, 3);
+    }
+
+    #[test]
+    fn test_week_start_depends_on_locale() {
+        // Sunday 2024-03-10 is in the previous week when weeks start Monday
+        let result = bucket_dates(
+            &[ms(2024, 3, 10, 8, 0)],
+            DateLocale::parse("de-DE"),
+            utc(),
+            now_ms(),
+        );
+        assert_eq!(result.buckets[0].key, "2024-03");
+        assert_eq!(result.buckets[0].label, "März 2024");
+        assert_eq!(result.items[0].short_date, "10. März");
+    }
+
+    #[test]
+    fn test_cjk_formats() {
+        let timestamps = [ms(2024, 3, 13, 14, 5), ms(2023, 1, 5, 8, 0)];
+        let result = bucket_dates(&timestamps, DateLocale::parse("ja-JP"), utc(), now_ms());
+        assert_eq!(result.buckets[0].label, "今日");
+        assert_eq!(result.items[0].short_date, "14:05");
+        assert_eq!(result.buckets[1].label, "2023年1月");
+        assert_eq!(result.items[1].short_date, "2023/01/05");
+    }
+
+    #[test]
+    fn test_offset_moves_day_boundary() {
+        // 2024-03-13 02:00 UTC is still the 12th in New York (UTC-5)
+        let new_york = FixedOffset::west_opt(5 * 3600);
+        let result = bucket_dates(
+            &[ms(2024, 3, 13, 2, 0)],
+            DateLocale::parse("en-US"),
+            new_york,
+            now_ms(),
+        );
+        assert_eq!(result.buckets[0].key, "yesterday");
+    }
+
+    #[test]
+    fn test_future_dates_count_as_today() {
+        let result = bucket_dates(
+            &[ms(2024, 3, 20, 8, 0)],
+            DateLocale::parse("fr"),
+            utc(),
+            now_ms(),
+        );
+        assert_eq!(result.buckets[0].key, "today");
+        assert_eq!(result.buckets[0].label, "Aujourd'hui");
+    }
+
+    #[test]
+    fn test_unknown_locale_falls_back_to_english() {
+        let locale = DateLocale::parse("xx");
+        assert_eq!(locale.today(), "Today");
+        assert!(locale.us);
+        assert!(!DateLocale::parse("en-GB").us);
+    }
+}
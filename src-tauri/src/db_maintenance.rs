@@ -0,0 +1,177 @@
+//! Health checks and cleanup for the app database.
+//!
+//! Light maintenance (run in the background) checks the database with
+//! `quick_check`, checks the full-text indexes against the messages and
+//! notes and lets SQLite refresh its query statistics. Full maintenance
+//! (from settings) runs the complete `integrity_check`, rebuilds damaged
+//! indexes, and vacuums the file so space freed by deleted mail and evicted
+//! bodies is returned to the disk.
+
+use serde::Serialize;
+use specta::Type;
+use sqlx::SqlitePool;
+use tauri::State;
+use tauri_plugin_sql::{DbInstances, DbPool};
+
+use crate::profiles::Profiles;
+
+/// Problems reported by an integrity check, at most.
+const MAX_ERRORS: u32 = 100;
+
+/// Full-text indexes that maintenance checks and rebuilds.
+const FTS_TABLES: &[&str] = &["messages_fts", "notes_fts"];
+
+#[derive(Debug, Serialize, Type)]
+pub struct MaintenanceReport {
+    /// Database size in bytes before and after.
+    pub size_before: i64,
+    pub size_after: i64,
+    /// Problems the integrity check still finds after repairs; empty when
+    /// the database is healthy.
+    pub integrity_errors: Vec<String>,
+    /// Indexes were rebuilt because the integrity check failed.
+    pub reindexed: bool,
+    /// A full-text search index didn't match its table and was rebuilt.
+    pub fts_rebuilt: bool,
+    pub vacuumed: bool,
+}
+
+/// The problems in an `integrity_check`/`quick_check` result, which is a
+/// single "ok" row for a healthy database.
+fn integrity_errors(rows: Vec<String>) -> Vec<String> {
+    if rows.len() == 1 && rows[0] == "ok" {
+        Vec::new()
+    } else {
+        rows
+    }
+}
+
+async fn db_size(pool: &SqlitePool) -> Result<i64, String> {
+    sqlx::query_scalar("SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to read database size: {e}"))
+}
+
+async fn check_integrity(pool: &SqlitePool, full: bool) -> Result<Vec<String>, String> {
+    let pragma = if full {
+        "integrity_check"
+    } else {
+        "quick_check"
+    };
+    let rows = sqlx::query_scalar(&format!("PRAGMA {pragma}({MAX_ERRORS})"))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Integrity check failed to run: {e}"))?;
+    Ok(integrity_errors(rows))
+}
+
+/// Whether full-text index `fts` matches its table. Only corruption counts
+/// as a mismatch; other failures (e.g. a locked database) are errors.
+async fn fts_matches(pool: &SqlitePool, fts: &str) -> Result<bool, String> {
+    match sqlx::query(&format!(
+        "INSERT INTO {fts}({fts}, rank) VALUES('integrity-check', 1)"
+    ))
+    .execute(pool)
+    .await
+    {
+        Ok(_) => Ok(true),
+        Err(e) => {
+            let message = e.to_string();
+            if message.contains("malformed") || message.contains("corrupt") {
+                log::warn!("Full-text index {fts} is out of sync: {message}");
+                Ok(false)
+            } else {
+                Err(format!("Failed to check the search index: {message}"))
+            }
+        }
+    }
+}
+
+async fn execute(pool: &SqlitePool, sql: &str) -> Result<(), String> {
+    sqlx::query(sql)
+        .execute(pool)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("{sql} failed: {e}"))
+}
+
+async fn maintain(pool: &SqlitePool, full: bool) -> Result<MaintenanceReport, String> {
+    let size_before = db_size(pool).await?;
+
+    let mut integrity_errors = check_integrity(pool, full).await?;
+    let mut reindexed = false;
+    if !integrity_errors.is_empty() {
+        log::warn!("Database integrity check failed: {integrity_errors:?}");
+        // Damaged indexes are the common, fixable case
+        execute(pool, "REINDEX").await?;
+        reindexed = true;
+        integrity_errors = check_integrity(pool, full).await?;
+    }
+
+    let mut fts_rebuilt = false;
+    for fts in FTS_TABLES {
+        if reindexed || !fts_matches(pool, fts).await? {
+            execute(pool, &format!("INSERT INTO {fts}({fts}) VALUES('rebuild')")).await?;
+            fts_rebuilt = true;
+        }
+    }
+
+    if full {
+        execute(pool, "VACUUM").await?;
+        execute(pool, "ANALYZE").await?;
+    } else {
+        execute(pool, "PRAGMA optimize").await?;
+    }
+
+    Ok(MaintenanceReport {
+        size_before,
+        size_after: db_size(pool).await?,
+        integrity_errors,
+        reindexed,
+        fts_rebuilt,
+        vacuumed: full,
+    })
+}
+
+/// Check and clean up the database. `full` runs the complete integrity
+/// check and vacuums, which can take a while on a large mailbox; otherwise
+/// only the quick checks run.
+#[tauri::command]
+#[specta::specta]
+pub async fn db_maintenance(
+    db: State<'_, DbInstances>,
+    profiles: State<'_, Profiles>,
+    full: bool,
+) -> Result<MaintenanceReport, String> {
+    let instances = db.0.read().await;
+    let DbPool::Sqlite(pool) = instances
+        .get(&profiles.db_url())
+        .ok_or_else(|| "Database is not loaded".to_string())?;
+
+    let report = maintain(pool, full).await?;
+    log::info!(
+        "Database maintenance ({}): {} -> {} bytes, {} integrity problem(s), FTS rebuilt: {}",
+        if full { "full" } else { "light" },
+        report.size_before,
+        report.size_after,
+        report.integrity_errors.len(),
+        report.fts_rebuilt
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integrity_errors() {
+        assert!(integrity_errors(vec!["ok".to_string()]).is_empty());
+        let problems = vec![
+            "row 12 missing from index idx_messages_date".to_string(),
+            "wrong # of entries in index idx_messages_date".to_string(),
+        ];
+        assert_eq!(integrity_errors(problems.clone()), problems);
+    }
+}
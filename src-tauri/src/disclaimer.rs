@@ -0,0 +1,153 @@
+//! Admin-provisioned disclaimers added to every message sent from specific
+//! accounts.
+//!
+//! The policy is a JSON file outside the user's profile, so users can't
+//! edit it from the app:
+//!
+//! ```json
+//! { "disclaimers": [
+//!     { "accounts": ["@corp.example", "ceo@corp.example"],
+//!       "text": "Confidential...", "html": "<p>Confidential...</p>" }
+//! ] }
+//! ```
+//!
+//! `SORA_DISCLAIMER_POLICY` points at the file; without it the platform's
+//! managed-config location is used (`/etc/sora/disclaimer.json`,
+//! `/Library/Application Support/Sora/disclaimer.json`, or
+//! `%ProgramData%\Sora\disclaimer.json`). The file is read on every lookup
+//! so changes apply without a restart. The MIME builder (`buildRawEmail`)
+//! appends the disclaimer to both the text and the HTML part.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::identity::normalize_address;
+use crate::managed;
+
+/// Environment variable overriding where the policy file is.
+pub const POLICY_ENV: &str = "SORA_DISCLAIMER_POLICY";
+const POLICY_FILE: &str = "disclaimer.json";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct DisclaimerPolicy {
+    disclaimers: Vec<DisclaimerRule>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DisclaimerRule {
+    /// Account addresses, or `@domain` for every account at a domain.
+    accounts: Vec<String>,
+    text: String,
+    /// Defaults to `text`, escaped, with line breaks kept.
+    #[serde(default)]
+    html: Option<String>,
+}
+
+impl DisclaimerRule {
+    fn matches(&self, address: &str) -> bool {
+        let domain = address.rsplit_once('@').map(|(_, d)| d).unwrap_or("");
+        self.accounts.iter().any(|entry| {
+            let entry = entry.trim().to_lowercase();
+            match entry.strip_prefix('@') {
+                Some(d) => d == domain,
+                None => normalize_address(&entry).as_deref() == Some(address),
+            }
+        })
+    }
+}
+
+/// The footer to append to a message, in both formats.
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct Disclaimer {
+    pub text: String,
+    pub html: String,
+}
+
+fn policy_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(POLICY_ENV).filter(|p| !p.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+    Some(managed::system_config_dir()?.join(POLICY_FILE))
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The first disclaimer in `policy` (the file's JSON) for `account_email`.
+fn find_disclaimer(policy: &str, account_email: &str) -> Result<Option<Disclaimer>, String> {
+    let policy: DisclaimerPolicy =
+        serde_json::from_str(policy).map_err(|e| format!("Malformed disclaimer policy: {e}"))?;
+    let Some(address) = normalize_address(account_email) else {
+        return Ok(None);
+    };
+    Ok(policy
+        .disclaimers
+        .into_iter()
+        .find(|rule| rule.matches(&address))
+        .map(|rule| Disclaimer {
+            html: rule
+                .html
+                .unwrap_or_else(|| escape_html(&rule.text).replace('\n', "<br>")),
+            text: rule.text,
+        }))
+}
+
+/// The disclaimer the policy requires on mail from `account_email`, if any.
+/// A policy file that exists but can't be read or parsed is an error, so a
+/// broken deployment doesn't silently send mail without it.
+#[tauri::command]
+#[specta::specta]
+pub fn get_disclaimer(account_email: String) -> Result<Option<Disclaimer>, String> {
+    let Some(path) = policy_path() else {
+        return Ok(None);
+    };
+    let policy = match std::fs::read_to_string(&path) {
+        Ok(policy) => policy,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(format!(
+                "Failed to read disclaimer policy {}: {e}",
+                path.display()
+            ))
+        }
+    };
+    find_disclaimer(&policy, &account_email)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: &str = r#"{ "disclaimers": [
+        { "accounts": ["Legal@Corp.example"], "text": "Privileged", "html": "<b>Privileged</b>" },
+        { "accounts": ["@corp.example"], "text": "Confidential & internal\nDo not forward" }
+    ] }"#;
+
+    #[test]
+    fn test_find_disclaimer() {
+        let legal = find_disclaimer(POLICY, "Legal <legal+x@corp.example>")
+            .unwrap()
+            .unwrap();
+        assert_eq!(legal.html, "<b>Privileged</b>");
+
+        let other = find_disclaimer(POLICY, "ann@corp.example")
+            .unwrap()
+            .unwrap();
+        assert_eq!(other.text, "Confidential & internal\nDo not forward");
+        assert_eq!(other.html, "Confidential &amp; internal<br>Do not forward");
+
+        assert_eq!(
+            find_disclaimer(POLICY, "ann@mail.corp.example").unwrap(),
+            None
+        );
+        assert_eq!(find_disclaimer(POLICY, "me@home.example").unwrap(), None);
+        assert!(find_disclaimer("{", "ann@corp.example").is_err());
+    }
+}
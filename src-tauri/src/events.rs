@@ -0,0 +1,339 @@
+//! Events the backend sends to the frontend.
+//!
+//! Every event has a payload struct here and is registered in
+//! `specta_builder()`, so its name and payload type end up in
+//! `src/bindings.ts` next to the commands. Send events with [`emit`] rather
+//! than `Emitter::emit` with a string name.
+//!
+//! Payloads are versioned as a whole by [`SCHEMA_VERSION`], which is
+//! exported to the bindings too. Bump it whenever a payload changes in a way
+//! an older frontend can't read (a field removed, renamed or retyped);
+//! adding an optional field doesn't need a bump.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{Emitter, Manager, Runtime};
+use tauri_specta::Event;
+
+use crate::imap::types::ImapFolder;
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Send `event` to every window, logging (not returning) failures: a
+/// window that can't receive an event isn't the sender's problem.
+pub fn emit<E, R, H>(handle: &H, event: E)
+where
+    E: Event + Serialize + Clone,
+    R: Runtime,
+    H: Emitter<R> + Manager<R>,
+{
+    if let Err(e) = event.emit(handle) {
+        log::warn!("Failed to emit {}: {e}", E::NAME);
+    }
+}
+
+/// Command-line args of a second launch, forwarded by the single-instance
+/// plugin (e.g. a `mailto:` URL to open).
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SingleInstanceArgs {
+    pub args: Vec<String>,
+}
+
+impl Event for SingleInstanceArgs {
+    const NAME: &'static str = "single-instance-args";
+}
+
+/// "Check for Mail" was chosen in the tray menu.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct TrayCheckMail;
+
+impl Event for TrayCheckMail {
+    const NAME: &'static str = "tray-check-mail";
+}
+
+/// The main window's close button was pressed with the close behavior set
+/// to "ask"; the frontend answers with `apply_close_action`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CloseRequested;
+
+impl Event for CloseRequested {
+    const NAME: &'static str = "close-requested";
+}
+
+/// A folder changed on the server, pushed over IMAP NOTIFY. `folder` is
+/// `None` when the server dropped events and the whole account needs a
+/// resync.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ImapNotify {
+    pub account_id: String,
+    pub folder: Option<String>,
+    pub exists: Option<u32>,
+    pub unseen: Option<u32>,
+}
+
+impl Event for ImapNotify {
+    const NAME: &'static str = "imap-notify";
+}
+
+/// A NOTIFY connection ended, so the frontend can restart it.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ImapNotifyStopped {
+    pub account_id: String,
+    pub error: Option<String>,
+}
+
+impl Event for ImapNotifyStopped {
+    const NAME: &'static str = "imap-notify-stopped";
+}
+
+/// New messages arrived in a folder watched with IMAP IDLE; `exists` is the
+/// folder's new message count.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ImapNewMail {
+    pub account_id: String,
+    pub folder: String,
+    pub exists: u32,
+}
+
+impl Event for ImapNewMail {
+    const NAME: &'static str = "imap-new-mail";
+}
+
+/// A message was expunged from a folder watched with IDLE. `seq` is its
+/// sequence number at the time, not a UID.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ImapExpunge {
+    pub account_id: String,
+    pub folder: String,
+    pub seq: u32,
+}
+
+impl Event for ImapExpunge {
+    const NAME: &'static str = "imap-expunge";
+}
+
+/// A message's flags changed in a folder watched with IDLE. `uid` is only
+/// set if the server included it.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ImapFlagsChanged {
+    pub account_id: String,
+    pub folder: String,
+    pub seq: u32,
+    pub uid: Option<u32>,
+    pub flags: Vec<String>,
+}
+
+impl Event for ImapFlagsChanged {
+    const NAME: &'static str = "imap-flags-changed";
+}
+
+/// An IDLE connection ended, so the frontend can restart it.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ImapIdleStopped {
+    pub account_id: String,
+    pub folder: String,
+    pub error: Option<String>,
+}
+
+impl Event for ImapIdleStopped {
+    const NAME: &'static str = "imap-idle-stopped";
+}
+
+/// An account whose password isn't stored needs it for this session; the
+/// frontend asks the user and answers with `account_provide_password`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct PasswordRequired {
+    pub account_id: String,
+}
+
+impl Event for PasswordRequired {
+    const NAME: &'static str = "password-required";
+}
+
+/// A folder that disappeared from the server's LIST and reappeared under
+/// another path.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct FolderRename {
+    /// The folder's previous raw path.
+    pub from: String,
+    pub to: ImapFolder,
+}
+
+/// An account's folder list differs from the previous LIST: folders were
+/// created, deleted or renamed, possibly by another client. Removed folders
+/// are raw paths.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct FoldersChanged {
+    pub account_id: String,
+    pub added: Vec<ImapFolder>,
+    pub removed: Vec<String>,
+    pub renamed: Vec<FolderRename>,
+}
+
+impl Event for FoldersChanged {
+    const NAME: &'static str = "folders-changed";
+}
+
+/// A local API client asked for a new message; the frontend opens the
+/// composer with these fields filled in.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct ComposeRequested {
+    pub to: Vec<String>,
+    pub cc: Vec<String>,
+    pub bcc: Vec<String>,
+    pub subject: Option<String>,
+    /// Plain text.
+    pub body: Option<String>,
+}
+
+impl Event for ComposeRequested {
+    const NAME: &'static str = "compose-requested";
+}
+
+/// A message queued with `smtp_queue_email` was sent and left the outbox.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct OutboxSent {
+    pub id: String,
+    pub account_id: String,
+    /// The server's reply.
+    pub message: String,
+}
+
+impl Event for OutboxSent {
+    const NAME: &'static str = "outbox-sent";
+}
+
+/// A queued message failed for good (a permanent error, or too many
+/// retries). It stays in the outbox until it's retried or cancelled.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct OutboxFailed {
+    pub id: String,
+    pub account_id: String,
+    pub error: String,
+    pub attempts: u32,
+}
+
+impl Event for OutboxFailed {
+    const NAME: &'static str = "outbox-failed";
+}
+
+/// A delete staged with `imap_stage_delete` ran: it reached the server, or
+/// failed for good with `error` and the messages are still there.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct StagedOperationDone {
+    pub id: String,
+    pub account_id: String,
+    /// The conversations the delete covered.
+    pub thread_ids: Vec<String>,
+    pub error: Option<String>,
+}
+
+impl Event for StagedOperationDone {
+    const NAME: &'static str = "staged-operation-done";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Payload shapes the frontend relies on; changing one of these means
+    /// bumping `SCHEMA_VERSION`.
+    #[test]
+    fn test_payload_shapes() {
+        use serde_json::{json, to_value};
+
+        assert_eq!(
+            to_value(SingleInstanceArgs {
+                args: vec!["mailto:a@b.example".to_string()],
+            })
+            .unwrap(),
+            json!({ "args": ["mailto:a@b.example"] })
+        );
+        assert_eq!(to_value(TrayCheckMail).unwrap(), json!(null));
+        assert_eq!(
+            to_value(ImapNotify {
+                account_id: "acc".to_string(),
+                folder: None,
+                exists: Some(3),
+                unseen: None,
+            })
+            .unwrap(),
+            json!({ "account_id": "acc", "folder": null, "exists": 3, "unseen": null })
+        );
+        assert_eq!(
+            to_value(ImapNotifyStopped {
+                account_id: "acc".to_string(),
+                error: Some("timeout".to_string()),
+            })
+            .unwrap(),
+            json!({ "account_id": "acc", "error": "timeout" })
+        );
+        assert_eq!(
+            to_value(ImapNewMail {
+                account_id: "acc".to_string(),
+                folder: "INBOX".to_string(),
+                exists: 7,
+            })
+            .unwrap(),
+            json!({ "account_id": "acc", "folder": "INBOX", "exists": 7 })
+        );
+        assert_eq!(
+            to_value(ImapFlagsChanged {
+                account_id: "acc".to_string(),
+                folder: "INBOX".to_string(),
+                seq: 2,
+                uid: None,
+                flags: vec!["\\Seen".to_string()],
+            })
+            .unwrap(),
+            json!({ "account_id": "acc", "folder": "INBOX", "seq": 2, "uid": null, "flags": ["\\Seen"] })
+        );
+        assert_eq!(
+            to_value(PasswordRequired {
+                account_id: "acc".to_string(),
+            })
+            .unwrap(),
+            json!({ "account_id": "acc" })
+        );
+        assert_eq!(
+            to_value(FoldersChanged {
+                account_id: "acc".to_string(),
+                added: Vec::new(),
+                removed: vec!["Old".to_string()],
+                renamed: Vec::new(),
+            })
+            .unwrap(),
+            json!({ "account_id": "acc", "added": [], "removed": ["Old"], "renamed": [] })
+        );
+        assert_eq!(
+            to_value(ComposeRequested {
+                to: vec!["a@b.example".to_string()],
+                subject: Some("Hi".to_string()),
+                ..Default::default()
+            })
+            .unwrap(),
+            json!({ "to": ["a@b.example"], "cc": [], "bcc": [], "subject": "Hi", "body": null })
+        );
+        assert_eq!(
+            to_value(OutboxFailed {
+                id: "q1".to_string(),
+                account_id: "acc".to_string(),
+                error: "550 rejected".to_string(),
+                attempts: 1,
+            })
+            .unwrap(),
+            json!({ "id": "q1", "account_id": "acc", "error": "550 rejected", "attempts": 1 })
+        );
+        assert_eq!(
+            to_value(StagedOperationDone {
+                id: "s1".to_string(),
+                account_id: "acc".to_string(),
+                thread_ids: vec!["t1".to_string()],
+                error: None,
+            })
+            .unwrap(),
+            json!({ "id": "s1", "account_id": "acc", "thread_ids": ["t1"], "error": null })
+        );
+    }
+}
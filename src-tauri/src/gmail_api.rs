@@ -0,0 +1,390 @@
+//! Gmail REST API transport (`users.messages.*`) for Gmail accounts.
+//!
+//! Gmail throttles IMAP by bandwidth and simultaneous connections, and big
+//! mailboxes run into it during initial syncs. The REST API has separate,
+//! per-request quotas, so an account can list, fetch, label and send
+//! through it instead ([`crate::accounts::MailTransport::GmailApi`]). Requests are
+//! authorized with the account's existing OAuth access token
+//! ([`AccountRegistry::oauth_token`]): the `https://mail.google.com/` scope
+//! used for IMAP covers the API too.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::accounts::{require_trusted_window, AccountRegistry};
+use crate::attachments::{self, AttachmentStaging};
+use crate::settings::SettingsState;
+use crate::smtp::client as smtp_client;
+
+const API_BASE: &str = "https://gmail.googleapis.com/gmail/v1/users/me";
+/// Sends go through the upload endpoint, which takes messages up to 35 MB.
+const UPLOAD_BASE: &str = "https://gmail.googleapis.com/upload/gmail/v1/users/me";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Most message ids `messages.batchModify` takes per request.
+const BATCH_MODIFY_LIMIT: usize = 1000;
+
+/// A message id in a `messages.list` page.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct GmailMessageRef {
+    pub id: String,
+    #[serde(alias = "threadId")]
+    pub thread_id: String,
+}
+
+/// A page of `messages.list`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct GmailMessageList {
+    #[serde(default)]
+    pub messages: Vec<GmailMessageRef>,
+    /// Passed back to get the next page; `None` on the last one.
+    #[serde(alias = "nextPageToken")]
+    pub next_page_token: Option<String>,
+    #[serde(default, alias = "resultSizeEstimate")]
+    pub result_size_estimate: u32,
+}
+
+/// A message as returned by `messages.get` in `minimal`, `metadata` or
+/// `raw` format.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct GmailMessage {
+    pub id: String,
+    #[serde(alias = "threadId")]
+    pub thread_id: String,
+    #[serde(default, alias = "labelIds")]
+    pub label_ids: Vec<String>,
+    #[serde(default)]
+    pub snippet: String,
+    #[serde(alias = "historyId")]
+    pub history_id: Option<String>,
+    /// Milliseconds since the epoch, as a string.
+    #[serde(alias = "internalDate")]
+    pub internal_date: Option<String>,
+    #[serde(alias = "sizeEstimate")]
+    pub size_estimate: Option<u64>,
+    /// The RFC 822 source, base64url-encoded; only in `raw` format.
+    pub raw: Option<String>,
+}
+
+/// An authorized client for one account's mailbox.
+pub struct GmailApi {
+    client: reqwest::Client,
+    token: String,
+}
+
+impl GmailApi {
+    pub fn new(token: String) -> Result<Self, String> {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(Self { client, token })
+    }
+
+    /// A client with the OAuth token of `account_id`.
+    pub async fn for_account(registry: &AccountRegistry, account_id: &str) -> Result<Self, String> {
+        Self::new(registry.oauth_token(account_id).await?)
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, String> {
+        let response = request
+            .bearer_auth(&self.token)
+            .send()
+            .await
+            .map_err(|e| format!("Gmail API request failed: {e}"))?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let body = response.text().await.unwrap_or_default();
+        Err(api_error(status.as_u16(), &body))
+    }
+
+    async fn json<T: serde::de::DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<T, String> {
+        self.send(request)
+            .await?
+            .json()
+            .await
+            .map_err(|e| format!("Unexpected Gmail API response: {e}"))
+    }
+
+    /// One page of the messages matching `query` (Gmail search syntax) and
+    /// carrying all of `label_ids`, newest first.
+    pub async fn list_messages(
+        &self,
+        query: Option<&str>,
+        label_ids: &[String],
+        max_results: Option<u32>,
+        page_token: Option<&str>,
+    ) -> Result<GmailMessageList, String> {
+        let mut params: Vec<(&str, String)> = Vec::new();
+        if let Some(query) = query.filter(|q| !q.is_empty()) {
+            params.push(("q", query.to_string()));
+        }
+        params.extend(label_ids.iter().map(|id| ("labelIds", id.clone())));
+        if let Some(max) = max_results {
+            params.push(("maxResults", max.clamp(1, 500).to_string()));
+        }
+        if let Some(token) = page_token {
+            params.push(("pageToken", token.to_string()));
+        }
+        let url = format!("{API_BASE}/messages");
+        self.json(self.client.get(url).query(&params)).await
+    }
+
+    /// A message in `format`: "minimal", "metadata" or "raw".
+    pub async fn get_message(&self, id: &str, format: &str) -> Result<GmailMessage, String> {
+        let url = format!("{API_BASE}/messages/{}", check_id(id)?);
+        self.json(self.client.get(url).query(&[("format", format)]))
+            .await
+    }
+
+    /// Add and remove labels on `ids`, in batches of up to 1000.
+    pub async fn modify_labels(
+        &self,
+        ids: &[String],
+        add_label_ids: &[String],
+        remove_label_ids: &[String],
+    ) -> Result<(), String> {
+        let url = format!("{API_BASE}/messages/batchModify");
+        for batch in ids.chunks(BATCH_MODIFY_LIMIT) {
+            let body = serde_json::json!({
+                "ids": batch,
+                "addLabelIds": add_label_ids,
+                "removeLabelIds": remove_label_ids,
+            });
+            self.send(self.client.post(&url).json(&body)).await?;
+        }
+        Ok(())
+    }
+
+    /// Send an RFC 822 message, in `thread_id` if given. Gmail files it
+    /// under SENT itself.
+    pub async fn send_message(
+        &self,
+        raw: &[u8],
+        thread_id: Option<&str>,
+    ) -> Result<GmailMessage, String> {
+        let metadata = match thread_id {
+            Some(id) => serde_json::json!({ "threadId": id }),
+            None => serde_json::json!({}),
+        };
+        let (boundary, body) = multipart_related(&metadata.to_string(), raw);
+        let url = format!("{UPLOAD_BASE}/messages/send?uploadType=multipart");
+        let request = self
+            .client
+            .post(url)
+            .header(
+                "Content-Type",
+                format!("multipart/related; boundary={boundary}"),
+            )
+            .body(body);
+        self.json(request).await
+    }
+}
+
+/// Gmail ids are hex; anything else would change the request path.
+fn check_id(id: &str) -> Result<&str, String> {
+    if !id.is_empty() && id.bytes().all(|b| b.is_ascii_alphanumeric()) {
+        Ok(id)
+    } else {
+        Err(format!("Invalid Gmail message id: {id}"))
+    }
+}
+
+/// A readable error for a failed request, with the message from Gmail's
+/// JSON error body when there is one.
+fn api_error(status: u16, body: &str) -> String {
+    let message = serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|v| v["error"]["message"].as_str().map(str::to_string))
+        .unwrap_or_else(|| body.trim().chars().take(200).collect());
+    match status {
+        401 => "Gmail rejected the access token; sign in to the account again".to_string(),
+        429 => format!("Gmail API rate limit reached, try again later: {message}"),
+        _ => format!("Gmail API request failed: HTTP {status}: {message}"),
+    }
+}
+
+/// A `multipart/related` upload body of the JSON `metadata` and the
+/// message, with a boundary that doesn't occur in either.
+fn multipart_related(metadata: &str, raw: &[u8]) -> (String, Vec<u8>) {
+    let contains =
+        |haystack: &[u8], needle: &[u8]| haystack.windows(needle.len()).any(|w| w == needle);
+    let mut boundary = String::from("sora_gmail_upload");
+    let mut n = 0u32;
+    while contains(raw, boundary.as_bytes()) || metadata.contains(&boundary) {
+        n += 1;
+        boundary = format!("sora_gmail_upload_{n}");
+    }
+    let mut body = Vec::with_capacity(raw.len() + metadata.len() + 256);
+    body.extend_from_slice(
+        format!(
+            "--{boundary}\r\nContent-Type: application/json; charset=UTF-8\r\n\r\n{metadata}\r\n\
+             --{boundary}\r\nContent-Type: message/rfc822\r\n\r\n"
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(raw);
+    body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+    (boundary, body)
+}
+
+/// Send a composed message (with staged attachments) through the Gmail API
+/// for an account whose transport is
+/// [`crate::accounts::MailTransport::GmailApi`].
+pub async fn send_composed(
+    registry: &AccountRegistry,
+    account_id: &str,
+    raw: &[u8],
+    staging: &AttachmentStaging,
+    thread_id: Option<&str>,
+) -> Result<GmailMessage, String> {
+    // Messages forwarded as attachments are still on the IMAP server
+    if !attachments::server_messages(raw, staging).is_empty() {
+        let imap_config = registry.imap(account_id).await?;
+        attachments::download_server_messages(&imap_config, raw, staging).await?;
+    }
+    let raw = attachments::expand_staged_parts(raw, staging)?;
+    GmailApi::for_account(registry, account_id)
+        .await?
+        .send_message(&raw, thread_id)
+        .await
+}
+
+/// A page of the account's messages, as Gmail's `messages.list`.
+#[tauri::command]
+#[specta::specta]
+pub async fn gmail_list_messages(
+    accounts: tauri::State<'_, AccountRegistry>,
+    account_id: String,
+    query: Option<String>,
+    label_ids: Option<Vec<String>>,
+    max_results: Option<u32>,
+    page_token: Option<String>,
+) -> Result<GmailMessageList, String> {
+    GmailApi::for_account(&accounts, &account_id)
+        .await?
+        .list_messages(
+            query.as_deref(),
+            &label_ids.unwrap_or_default(),
+            max_results,
+            page_token.as_deref(),
+        )
+        .await
+}
+
+/// One message: "minimal" or "metadata" (the default), or "raw" for its
+/// source.
+#[tauri::command]
+#[specta::specta]
+pub async fn gmail_get_message(
+    window: tauri::Window,
+    accounts: tauri::State<'_, AccountRegistry>,
+    account_id: String,
+    message_id: String,
+    format: Option<String>,
+) -> Result<GmailMessage, String> {
+    let format = format.unwrap_or_else(|| "metadata".to_string());
+    match format.as_str() {
+        "raw" => require_trusted_window(&window, "gmail_get_message")?,
+        "minimal" | "metadata" => {}
+        _ => return Err(format!("Unsupported Gmail message format: {format}")),
+    }
+    GmailApi::for_account(&accounts, &account_id)
+        .await?
+        .get_message(&message_id, &format)
+        .await
+}
+
+/// Add and remove labels (including system ones like UNREAD, STARRED and
+/// INBOX) on messages.
+#[tauri::command]
+#[specta::specta]
+pub async fn gmail_modify_labels(
+    window: tauri::Window,
+    accounts: tauri::State<'_, AccountRegistry>,
+    settings: tauri::State<'_, SettingsState>,
+    account_id: String,
+    message_ids: Vec<String>,
+    add_label_ids: Vec<String>,
+    remove_label_ids: Vec<String>,
+) -> Result<(), String> {
+    require_trusted_window(&window, "gmail_modify_labels")?;
+    settings.require_writable("gmail_modify_labels")?;
+    if message_ids.is_empty() || (add_label_ids.is_empty() && remove_label_ids.is_empty()) {
+        return Ok(());
+    }
+    GmailApi::for_account(&accounts, &account_id)
+        .await?
+        .modify_labels(&message_ids, &add_label_ids, &remove_label_ids)
+        .await
+}
+
+/// Send a base64url-encoded message through the Gmail API, whatever the
+/// account's transport.
+#[tauri::command]
+#[specta::specta]
+pub async fn gmail_send(
+    window: tauri::Window,
+    accounts: tauri::State<'_, AccountRegistry>,
+    settings: tauri::State<'_, SettingsState>,
+    staging: tauri::State<'_, AttachmentStaging>,
+    account_id: String,
+    raw_email: String,
+    thread_id: Option<String>,
+) -> Result<GmailMessage, String> {
+    require_trusted_window(&window, "gmail_send")?;
+    settings.require_writable("gmail_send")?;
+    let raw = smtp_client::decode_base64url(&raw_email)?;
+    send_composed(&accounts, &account_id, &raw, &staging, thread_id.as_deref()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multipart_related() {
+        let (boundary, body) = multipart_related("{}", b"Subject: sora_gmail_upload\r\n\r\nHi");
+        assert_eq!(boundary, "sora_gmail_upload_1");
+        let body = String::from_utf8(body).unwrap();
+        assert!(body.starts_with("--sora_gmail_upload_1\r\nContent-Type: application/json"));
+        assert!(body.contains(
+            "Content-Type: message/rfc822\r\n\r\nSubject: sora_gmail_upload\r\n\r\nHi\r\n"
+        ));
+        assert!(body.ends_with("\r\n--sora_gmail_upload_1--\r\n"));
+    }
+
+    #[test]
+    fn test_api_error() {
+        let body = r#"{"error":{"code":429,"message":"User-rate limit exceeded","status":"RESOURCE_EXHAUSTED"}}"#;
+        assert_eq!(
+            api_error(429, body),
+            "Gmail API rate limit reached, try again later: User-rate limit exceeded"
+        );
+        assert_eq!(
+            api_error(500, "oops"),
+            "Gmail API request failed: HTTP 500: oops"
+        );
+        assert!(api_error(401, "").contains("sign in"));
+    }
+
+    #[test]
+    fn test_deserialize_message() {
+        let message: GmailMessage = serde_json::from_str(
+            r#"{"id":"18c1","threadId":"18c0","labelIds":["INBOX","UNREAD"],"snippet":"Hi","internalDate":"1700000000000","sizeEstimate":1234}"#,
+        )
+        .unwrap();
+        assert_eq!(message.thread_id, "18c0");
+        assert_eq!(message.label_ids, ["INBOX", "UNREAD"]);
+        assert_eq!(message.raw, None);
+        assert!(check_id("18c1").is_ok());
+        assert!(check_id("../labels").is_err());
+    }
+}
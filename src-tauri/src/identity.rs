@@ -0,0 +1,158 @@
+//! Which addresses are the user's own.
+//!
+//! The frontend registers each account's addresses with
+//! `identity_set_addresses`: the account address, its send-as aliases and
+//! any extra addresses the user listed. Messages fetched over IMAP are then
+//! marked `is_from_me` when their sender is one of those addresses on any
+//! account, so a conversation can tell the user's replies apart wherever
+//! they were filed, and reply-all can leave the user's own addresses out.
+//!
+//! Matching ignores case and a `+tag` in the local part (RFC 5233), so mail
+//! sent from `me+shop@example.com` counts as sent by `me@example.com`.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use crate::accounts::require_trusted_window;
+use crate::imap::types::ImapMessage;
+
+/// `user@example.com` from `Name <User+Tag@Example.com>`, or `None` if
+/// there's no address.
+pub fn normalize_address(address: &str) -> Option<String> {
+    let address = match (address.rfind('<'), address.rfind('>')) {
+        (Some(start), Some(end)) if start < end => &address[start + 1..end],
+        _ => address,
+    };
+    let address = address.trim().to_lowercase();
+    let (local, domain) = address.rsplit_once('@')?;
+    if local.is_empty() || domain.is_empty() {
+        return None;
+    }
+    let local = match local.split_once('+') {
+        Some((base, _)) if !base.is_empty() => base,
+        _ => local,
+    };
+    Some(format!("{local}@{domain}"))
+}
+
+/// Managed state: the normalized addresses of each account.
+#[derive(Default)]
+pub struct Identities {
+    accounts: Mutex<HashMap<String, HashSet<String>>>,
+}
+
+impl Identities {
+    /// Replace the addresses of `account_id`.
+    pub fn set(&self, account_id: &str, addresses: &[String]) {
+        let normalized = addresses
+            .iter()
+            .filter_map(|a| normalize_address(a))
+            .collect();
+        self.accounts
+            .lock()
+            .unwrap()
+            .insert(account_id.to_string(), normalized);
+    }
+
+    pub fn remove(&self, account_id: &str) {
+        self.accounts.lock().unwrap().remove(account_id);
+    }
+
+    /// Whether `address` belongs to `account_id`, or to any account if
+    /// `account_id` is `None`.
+    pub fn is_mine(&self, account_id: Option<&str>, address: &str) -> bool {
+        let Some(address) = normalize_address(address) else {
+            return false;
+        };
+        let accounts = self.accounts.lock().unwrap();
+        match account_id {
+            Some(id) => accounts.get(id).is_some_and(|a| a.contains(&address)),
+            None => accounts.values().any(|a| a.contains(&address)),
+        }
+    }
+
+    /// Set `is_from_me` on messages sent from any of the user's accounts.
+    pub fn annotate<'a>(&self, messages: impl IntoIterator<Item = &'a mut ImapMessage>) {
+        for message in messages {
+            message.is_from_me = message
+                .from_address
+                .as_deref()
+                .is_some_and(|from| self.is_mine(None, from));
+        }
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn identity_set_addresses(
+    window: tauri::Window,
+    identities: tauri::State<'_, Identities>,
+    account_id: String,
+    addresses: Vec<String>,
+) -> Result<(), String> {
+    require_trusted_window(&window, "identity_set_addresses")?;
+    identities.set(&account_id, &addresses);
+    Ok(())
+}
+
+/// For each of `addresses`, whether it's one of the user's: of
+/// `account_id` if given, otherwise of any account.
+#[tauri::command]
+#[specta::specta]
+pub fn identity_match(
+    identities: tauri::State<'_, Identities>,
+    account_id: Option<String>,
+    addresses: Vec<String>,
+) -> Vec<bool> {
+    addresses
+        .iter()
+        .map(|a| identities.is_mine(account_id.as_deref(), a))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_normalize_address() {
+        assert_eq!(
+            normalize_address("Me <Me+Shop@Example.COM>").as_deref(),
+            Some("me@example.com")
+        );
+        assert_eq!(
+            normalize_address(" me@example.com ").as_deref(),
+            Some("me@example.com")
+        );
+        // A leading "+" is part of the name, not a tag
+        assert_eq!(
+            normalize_address("+1@example.com").as_deref(),
+            Some("+1@example.com")
+        );
+        assert_eq!(normalize_address("undisclosed-recipients:;"), None);
+        assert_eq!(normalize_address("@example.com"), None);
+    }
+
+    #[test]
+    fn test_is_mine_across_accounts() {
+        let identities = Identities::default();
+        identities.set(
+            "work",
+            &strings(&["me@work.example", "Team <team@work.example>"]),
+        );
+        identities.set("home", &strings(&["me@home.example"]));
+
+        assert!(identities.is_mine(Some("work"), "ME+lists@work.example"));
+        assert!(identities.is_mine(Some("work"), "team@work.example"));
+        assert!(!identities.is_mine(Some("work"), "me@home.example"));
+        assert!(identities.is_mine(None, "Me <me@home.example>"));
+        assert!(!identities.is_mine(None, "someone@work.example"));
+
+        identities.remove("home");
+        assert!(!identities.is_mine(None, "me@home.example"));
+    }
+}
@@ -0,0 +1,399 @@
+//! Remote image proxy for HTML email.
+//!
+//! Loading remote images straight from the webview tells the sender the
+//! reader's IP address and when (and how often) a message was opened. In
+//! proxy mode the webview never contacts the sender: images are fetched
+//! here — optionally through a shared privacy proxy — without cookies or
+//! referrer and with tracking query parameters removed, cached on disk,
+//! and handed back as data URLs.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use base64::Engine;
+use reqwest::Url;
+use tauri::{AppHandle, Manager};
+
+/// Images larger than this are not proxied.
+const MAX_IMAGE_BYTES: usize = 5 * 1024 * 1024;
+
+/// Per-image request timeout.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Cached images older than this are fetched again.
+const CACHE_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Cache directory, relative to the app cache dir.
+const CACHE_DIR: &str = "image-proxy";
+
+/// Placeholder in a shared proxy URL that is replaced by the encoded image URL.
+const PROXY_URL_PLACEHOLDER: &str = "{url}";
+
+/// Generic user agent so requests don't identify the client.
+const USER_AGENT: &str = "Mozilla/5.0";
+
+/// Query parameters used for click/open tracking and campaign attribution.
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid",
+    "gclid",
+    "dclid",
+    "msclkid",
+    "mc_cid",
+    "mc_eid",
+    "_hsenc",
+    "_hsmi",
+    "mkt_tok",
+    "vero_id",
+    "oly_enc_id",
+    "oly_anon_id",
+    "rb_clickid",
+    "s_cid",
+    "trk",
+    "trkid",
+    "recipient",
+    "email",
+    "uid",
+    "user_id",
+    "subscriber_id",
+];
+
+/// Remove tracking parameters from an image URL.
+///
+/// Drops `utm_*` and known tracking keys, plus any parameter whose value is
+/// an email address. Returns `None` for anything that isn't http(s).
+pub fn strip_tracking_params(url: &str) -> Option<Url> {
+    let mut parsed = Url::parse(url.trim()).ok()?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        return None;
+    }
+    parsed.set_fragment(None);
+    if parsed.query().is_none() {
+        return Some(parsed);
+    }
+
+    let kept: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(key, value)| !is_tracking_param(key, value))
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    if kept.is_empty() {
+        parsed.set_query(None);
+    } else {
+        parsed.query_pairs_mut().clear().extend_pairs(kept);
+    }
+    Some(parsed)
+}
+
+fn is_tracking_param(key: &str, value: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    key.starts_with("utm_")
+        || TRACKING_PARAMS.contains(&key.as_str())
+        || (value.contains('@') && value.contains('.'))
+}
+
+/// Refuse hosts on the local machine or network: a message must not be
+/// able to make the app probe the reader's LAN.
+fn is_private_host(url: &Url) -> bool {
+    let Some(host) = url.host_str() else {
+        return true;
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return is_private_ip(ip);
+    }
+    let host = host.to_ascii_lowercase();
+    host == "localhost" || host.ends_with(".localhost") || host.ends_with(".local")
+}
+
+fn is_private_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+        }
+        IpAddr::V6(v6) => {
+            let first = v6.segments()[0];
+            v6.is_loopback()
+                || v6.is_unspecified()
+                || (first & 0xfe00) == 0xfc00 // unique local
+                || (first & 0xffc0) == 0xfe80 // link local
+                || v6.to_ipv4_mapped().is_some_and(|v4| is_private_ip(IpAddr::V4(v4)))
+        }
+    }
+}
+
+/// How requests reach the image host.
+#[derive(Debug, PartialEq)]
+enum Route {
+    Direct,
+    /// Plain HTTP(S) proxy, e.g. "http://proxy.example:3128".
+    HttpProxy(String),
+    /// URL-rewriting image proxy, e.g. "https://img.example/?u={url}".
+    Rewrite(String),
+}
+
+fn route(proxy: Option<&str>) -> Route {
+    match proxy.map(str::trim).filter(|p| !p.is_empty()) {
+        None => Route::Direct,
+        Some(p) if p.contains(PROXY_URL_PLACEHOLDER) => Route::Rewrite(p.to_string()),
+        Some(p) => Route::HttpProxy(p.to_string()),
+    }
+}
+
+/// Percent-encode everything outside the RFC 3986 unreserved set.
+fn encode_component(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() * 3);
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+    }
+    out
+}
+
+/// Stable file name for a cached image (64-bit FNV-1a of the URL).
+fn cache_key(url: &str) -> String {
+    let hash = url.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |h, b| {
+        (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{hash:016x}")
+}
+
+/// MIME type of supported raster image data. SVG is not accepted: it can
+/// carry scripts and external references of its own.
+fn image_mime(bytes: &[u8]) -> Option<&'static str> {
+    use image::ImageFormat;
+    match image::guess_format(bytes).ok()? {
+        ImageFormat::Png => Some("image/png"),
+        ImageFormat::Jpeg => Some("image/jpeg"),
+        ImageFormat::Gif => Some("image/gif"),
+        ImageFormat::WebP => Some("image/webp"),
+        ImageFormat::Bmp => Some("image/bmp"),
+        ImageFormat::Ico => Some("image/x-icon"),
+        _ => None,
+    }
+}
+
+fn data_url(bytes: &[u8]) -> Option<String> {
+    let mime = image_mime(bytes)?;
+    let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+    Some(format!("data:{mime};base64,{encoded}"))
+}
+
+fn read_cached(path: &Path) -> Option<Vec<u8>> {
+    let age = std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())?;
+    if age > CACHE_MAX_AGE {
+        return None;
+    }
+    std::fs::read(path).ok()
+}
+
+fn build_client(route: &Route) -> Result<reqwest::Client, String> {
+    let mut builder = reqwest::Client::builder()
+        .user_agent(USER_AGENT)
+        .timeout(FETCH_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::custom(|attempt| {
+            if attempt.previous().len() >= 5 {
+                attempt.error("too many redirects")
+            } else if is_private_host(attempt.url()) {
+                attempt.stop()
+            } else {
+                attempt.follow()
+            }
+        }));
+    if let Route::HttpProxy(proxy) = route {
+        let proxy = reqwest::Proxy::all(proxy).map_err(|e| format!("Invalid image proxy: {e}"))?;
+        builder = builder.proxy(proxy);
+    }
+    builder
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))
+}
+
+async fn fetch_image(
+    client: &reqwest::Client,
+    route: &Route,
+    url: &Url,
+) -> Result<Vec<u8>, String> {
+    let request_url = match route {
+        Route::Rewrite(template) => {
+            template.replace(PROXY_URL_PLACEHOLDER, &encode_component(url.as_str()))
+        }
+        _ => url.to_string(),
+    };
+    let mut response = client
+        .get(&request_url)
+        .header(reqwest::header::ACCEPT, "image/*")
+        .send()
+        .await
+        .map_err(|e| format!("Image request failed: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!("Image request failed: HTTP {}", response.status()));
+    }
+    if response
+        .content_length()
+        .is_some_and(|len| len > MAX_IMAGE_BYTES as u64)
+    {
+        return Err("Image too large".to_string());
+    }
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Image download failed: {e}"))?
+    {
+        if bytes.len() + chunk.len() > MAX_IMAGE_BYTES {
+            return Err("Image too large".to_string());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+fn cache_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| format!("No app cache dir: {e}"))?
+        .join(CACHE_DIR);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create image cache: {e}"))?;
+    Ok(dir)
+}
+
+/// Fetch remote images for display and return them as data URLs.
+///
+/// `proxy` is either an HTTP(S) proxy URL or a shared image proxy URL
+/// containing `{url}`, which is replaced by the encoded image URL. The
+/// result maps each input URL to its data URL; images that couldn't be
+/// fetched, aren't raster images, or point at private hosts are left out.
+#[tauri::command]
+#[specta::specta]
+pub async fn image_proxy_fetch(
+    app: AppHandle,
+    urls: Vec<String>,
+    proxy: Option<String>,
+) -> Result<HashMap<String, String>, String> {
+    let route = route(proxy.as_deref());
+    let client = build_client(&route)?;
+    let dir = cache_dir(&app)?;
+
+    let fetches = urls.into_iter().map(|original| {
+        let (client, route, dir) = (&client, &route, &dir);
+        async move {
+            let url = strip_tracking_params(&original).filter(|u| !is_private_host(u))?;
+            let path = dir.join(cache_key(url.as_str()));
+            if let Some(data) = read_cached(&path).as_deref().and_then(data_url) {
+                return Some((original, data));
+            }
+            let bytes = match fetch_image(client, route, &url).await {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    log::debug!("Image proxy: {url}: {e}");
+                    return None;
+                }
+            };
+            let data = data_url(&bytes)?;
+            if let Err(e) = std::fs::write(&path, &bytes) {
+                log::warn!("Image proxy: failed to cache {url}: {e}");
+            }
+            Some((original, data))
+        }
+    });
+
+    Ok(futures::future::join_all(fetches)
+        .await
+        .into_iter()
+        .flatten()
+        .collect())
+}
+
+/// Delete all cached proxied images.
+#[tauri::command]
+#[specta::specta]
+pub fn image_proxy_clear_cache(app: AppHandle) -> Result<(), String> {
+    let dir = cache_dir(&app)?;
+    std::fs::remove_dir_all(&dir).map_err(|e| format!("Failed to clear image cache: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strips_tracking_params() {
+        let url = strip_tracking_params(
+            "https://cdn.example.com/logo.png?w=200&utm_source=news&utm_medium=email&mc_eid=abc123&e=jane%40example.com#x",
+        )
+        .unwrap();
+        assert_eq!(url.as_str(), "https://cdn.example.com/logo.png?w=200");
+
+        let url = strip_tracking_params("https://t.example.com/open.gif?fbclid=1").unwrap();
+        assert_eq!(url.as_str(), "https://t.example.com/open.gif");
+
+        assert!(strip_tracking_params("javascript:alert(1)").is_none());
+        assert!(strip_tracking_params("data:image/png;base64,AAAA").is_none());
+    }
+
+    #[test]
+    fn test_private_hosts() {
+        let private = [
+            "http://localhost/a.png",
+            "http://127.0.0.1/a.png",
+            "http://192.168.1.1/a.png",
+            "http://10.0.0.5/a.png",
+            "http://[::1]/a.png",
+            "http://[fd00::1]/a.png",
+            "http://printer.local/a.png",
+        ];
+        for url in private {
+            assert!(is_private_host(&Url::parse(url).unwrap()), "{url}");
+        }
+        assert!(!is_private_host(
+            &Url::parse("https://93.184.216.34/a.png").unwrap()
+        ));
+        assert!(!is_private_host(
+            &Url::parse("https://cdn.example.com/a.png").unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_route_and_encoding() {
+        assert_eq!(route(None), Route::Direct);
+        assert_eq!(route(Some("  ")), Route::Direct);
+        assert_eq!(
+            route(Some("http://proxy:3128")),
+            Route::HttpProxy("http://proxy:3128".to_string())
+        );
+        assert_eq!(
+            route(Some("https://img.example/?u={url}")),
+            Route::Rewrite("https://img.example/?u={url}".to_string())
+        );
+        assert_eq!(
+            encode_component("https://a.com/x.png?a=1&b=2"),
+            "https%3A%2F%2Fa.com%2Fx.png%3Fa%3D1%26b%3D2"
+        );
+    }
+
+    #[test]
+    fn test_only_raster_images_accepted() {
+        let png = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a, 0, 0, 0, 0];
+        assert!(data_url(&png)
+            .unwrap()
+            .starts_with("data:image/png;base64,"));
+        assert!(data_url(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>").is_none());
+        assert!(data_url(b"<html>").is_none());
+        assert_eq!(cache_key("a"), cache_key("a"));
+        assert_ne!(cache_key("a"), cache_key("b"));
+    }
+}
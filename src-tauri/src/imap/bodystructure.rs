@@ -0,0 +1,295 @@
+//! Attachment metadata from a message's BODYSTRUCTURE.
+//!
+//! The server describes a message's MIME tree (RFC 3501 §7.4.2) without
+//! sending its content, so the attachment list — names, types, sizes and
+//! the section numbers `fetch_attachment` takes — is known before the
+//! message is downloaded. Part ids follow IMAP section numbering, the same
+//! numbering `build_imap_section_map` gives parts of a parsed message.
+//!
+//! Sizes are the server's encoded sizes; for base64 parts they are scaled
+//! down to an estimate of the decoded size. A `winmail.dat` is listed as
+//! one file, since unpacking it needs its content.
+
+use std::borrow::Cow;
+
+use async_imap::imap_proto::types::{
+    BodyContentCommon, BodyContentSinglePart, BodyStructure, ContentEncoding,
+};
+
+use super::client::decode_envelope_text;
+use super::types::ImapAttachment;
+
+type Params<'a> = Option<Vec<(Cow<'a, str>, Cow<'a, str>)>>;
+
+/// Base64 lines carry 57 bytes in 76 characters plus CRLF.
+fn decoded_size(other: &BodyContentSinglePart) -> u32 {
+    match other.transfer_encoding {
+        ContentEncoding::Base64 => (u64::from(other.octets) * 57 / 78) as u32,
+        _ => other.octets,
+    }
+}
+
+/// Decode an RFC 2231 extended value: `charset'language'percent-encoded`.
+/// Continuations after the first carry no charset prefix.
+fn decode_extended(value: &str, has_charset: bool) -> (Option<String>, Vec<u8>) {
+    let (charset, encoded) = if has_charset {
+        let mut fields = value.splitn(3, '\'');
+        match (fields.next(), fields.next(), fields.next()) {
+            (Some(charset), Some(_language), Some(rest)) => {
+                (Some(charset.to_ascii_lowercase()), rest)
+            }
+            _ => (None, value),
+        }
+    } else {
+        (None, value)
+    };
+
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut rest = encoded.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        let hex = tail.get(..2).and_then(|h| std::str::from_utf8(h).ok());
+        match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+            Some(decoded) if b == b'%' => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            _ => {
+                bytes.push(b);
+                rest = tail;
+            }
+        }
+    }
+    (charset, bytes)
+}
+
+fn decode_charset(charset: Option<&str>, bytes: &[u8]) -> String {
+    match charset {
+        Some("iso-8859-1" | "latin1" | "windows-1252") => {
+            bytes.iter().map(|&b| b as char).collect()
+        }
+        _ => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+/// A parameter's value, decoding RFC 2231 (`name*=`, `name*0*=`…) and RFC
+/// 2047 (`=?utf-8?…?=`, which many mailers use for file names anyway).
+fn param(params: &Params, name: &str) -> Option<String> {
+    let params = params.as_ref()?;
+    if let Some((_, value)) = params.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)) {
+        return Some(decode_envelope_text(value.as_bytes())).filter(|v| !v.is_empty());
+    }
+
+    // name*=… or continuations name*0=, name*1*=…, in order
+    let mut pieces: Vec<(u32, bool, &str)> = params
+        .iter()
+        .filter_map(|(k, v)| {
+            let rest = k
+                .get(name.len()..)
+                .filter(|_| k[..name.len()].eq_ignore_ascii_case(name))?;
+            let rest = rest.strip_prefix('*')?;
+            let (index, extended) = match rest.strip_suffix('*') {
+                Some(index) => (index, true),
+                None if rest.is_empty() => ("", true),
+                None => (rest, false),
+            };
+            let index = if index.is_empty() {
+                0
+            } else {
+                index.parse().ok()?
+            };
+            Some((index, extended, v.as_ref()))
+        })
+        .collect();
+    if pieces.is_empty() {
+        return None;
+    }
+    pieces.sort_by_key(|(index, ..)| *index);
+
+    let mut charset = None;
+    let mut bytes = Vec::new();
+    for (i, (_, extended, value)) in pieces.into_iter().enumerate() {
+        if extended {
+            let (piece_charset, decoded) = decode_extended(value, i == 0);
+            charset = charset.or(piece_charset);
+            bytes.extend(decoded);
+        } else {
+            bytes.extend(value.as_bytes());
+        }
+    }
+    Some(decode_charset(charset.as_deref(), &bytes)).filter(|v| !v.is_empty())
+}
+
+fn child_section(prefix: &str, n: usize) -> String {
+    if prefix.is_empty() {
+        n.to_string()
+    } else {
+        format!("{prefix}.{n}")
+    }
+}
+
+/// The attachment a leaf part describes, if it is one. Text parts are the
+/// message body unless they're marked as attachments or named files.
+fn leaf_attachment(
+    common: &BodyContentCommon,
+    other: &BodyContentSinglePart,
+    section: &str,
+) -> Option<ImapAttachment> {
+    let disposition = common.disposition.as_ref();
+    let disposition_is = |ty: &str| disposition.is_some_and(|d| d.ty.eq_ignore_ascii_case(ty));
+    let filename = disposition
+        .and_then(|d| param(&d.params, "filename"))
+        .or_else(|| param(&common.ty.params, "name"));
+    let content_id = other
+        .id
+        .as_deref()
+        .map(|id| id.trim().trim_matches(|c| c == '<' || c == '>').to_string())
+        .filter(|id| !id.is_empty());
+
+    let is_text = common.ty.ty.eq_ignore_ascii_case("text");
+    if is_text && !disposition_is("attachment") && filename.is_none() {
+        return None;
+    }
+
+    Some(ImapAttachment {
+        part_id: section.to_string(),
+        filename: filename.unwrap_or_else(|| "attachment".to_string()),
+        mime_type: format!("{}/{}", common.ty.ty, common.ty.subtype).to_ascii_lowercase(),
+        size: decoded_size(other),
+        // Images referenced from the HTML by Content-ID are inline even
+        // without a disposition
+        is_inline: disposition_is("inline") || (disposition.is_none() && content_id.is_some()),
+        content_id,
+    })
+}
+
+fn walk(structure: &BodyStructure, section: &str, attachments: &mut Vec<ImapAttachment>) {
+    match structure {
+        BodyStructure::Multipart { bodies, .. } => {
+            for (i, body) in bodies.iter().enumerate() {
+                walk(body, &child_section(section, i + 1), attachments);
+            }
+        }
+        BodyStructure::Message { common, other, .. } => {
+            // An attached email is one attachment; its own parts are listed
+            // when it's opened
+            let filename = common
+                .disposition
+                .as_ref()
+                .and_then(|d| param(&d.params, "filename"))
+                .or_else(|| param(&common.ty.params, "name"));
+            attachments.push(ImapAttachment {
+                part_id: section.to_string(),
+                filename: filename.unwrap_or_else(|| "attachment".to_string()),
+                mime_type: "message/rfc822".to_string(),
+                size: decoded_size(other),
+                content_id: None,
+                is_inline: false,
+            });
+        }
+        BodyStructure::Basic { common, other, .. } | BodyStructure::Text { common, other, .. } => {
+            attachments.extend(leaf_attachment(common, other, section));
+        }
+    }
+}
+
+/// The attachments described by a message's BODYSTRUCTURE. A body that
+/// isn't multipart is section "1".
+pub fn attachments(structure: &BodyStructure) -> Vec<ImapAttachment> {
+    let mut attachments = Vec::new();
+    match structure {
+        BodyStructure::Multipart { .. } => walk(structure, "", &mut attachments),
+        _ => walk(structure, "1", &mut attachments),
+    }
+    attachments
+}
+
+/// Whether the message has a file meant to be saved rather than shown
+/// inline in the body.
+pub fn has_attachments(structure: &BodyStructure) -> bool {
+    attachments(structure).iter().any(|a| !a.is_inline)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_imap::imap_proto::{parser::parse_response, AttributeValue, Response};
+
+    fn parse(bodystructure: &str) -> Vec<ImapAttachment> {
+        let line = format!("* 1 FETCH (UID 9 BODYSTRUCTURE {bodystructure})\r\n");
+        let Ok((_, Response::Fetch(_, attributes))) = parse_response(line.as_bytes()) else {
+            panic!("FETCH response didn't parse");
+        };
+        let structure = attributes
+            .iter()
+            .find_map(|a| match a {
+                AttributeValue::BodyStructure(b) => Some(b),
+                _ => None,
+            })
+            .expect("BODYSTRUCTURE");
+        attachments(structure)
+    }
+
+    #[test]
+    fn test_mixed_message() {
+        // alternative(text, related(html, image)), pdf, attached email
+        let attachments = parse(
+            "(((\"text\" \"plain\" (\"charset\" \"utf-8\") NIL NIL \"7bit\" 20 1 NIL NIL NIL NIL)\
+             ((\"text\" \"html\" (\"charset\" \"utf-8\") NIL NIL \"7bit\" 80 2 NIL NIL NIL NIL)\
+             (\"image\" \"png\" (\"name\" \"logo.png\") \"<logo@example.com>\" NIL \"base64\" 780 NIL NIL NIL NIL) \
+             \"related\" (\"boundary\" \"r\") NIL NIL NIL) \"alternative\" (\"boundary\" \"a\") NIL NIL NIL)\
+             (\"application\" \"pdf\" NIL NIL NIL \"base64\" 1560 NIL (\"attachment\" (\"filename*\" \"utf-8''R%C3%A9sum%C3%A9.pdf\")) NIL NIL)\
+             (\"message\" \"rfc822\" NIL NIL NIL \"7bit\" 300 (NIL \"Fwd\" NIL NIL NIL NIL NIL NIL NIL NIL) \
+             (\"text\" \"plain\" NIL NIL NIL \"7bit\" 10 1 NIL NIL NIL NIL) 12 NIL NIL NIL NIL) \
+             \"mixed\" (\"boundary\" \"m\") NIL NIL NIL)",
+        );
+
+        let summary: Vec<(&str, &str, &str, u32, bool)> = attachments
+            .iter()
+            .map(|a| {
+                (
+                    a.part_id.as_str(),
+                    a.filename.as_str(),
+                    a.mime_type.as_str(),
+                    a.size,
+                    a.is_inline,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("1.2.2", "logo.png", "image/png", 570, true),
+                ("2", "Résumé.pdf", "application/pdf", 1140, false),
+                ("3", "attachment", "message/rfc822", 300, false),
+            ]
+        );
+        assert_eq!(
+            attachments[0].content_id.as_deref(),
+            Some("logo@example.com")
+        );
+    }
+
+    #[test]
+    fn test_single_part_and_named_text() {
+        assert!(parse(
+            "(\"text\" \"plain\" (\"charset\" \"utf-8\") NIL NIL \"7bit\" 12 1 NIL NIL NIL NIL)"
+        )
+        .is_empty());
+
+        let attachments =
+            parse("(\"application\" \"octet-stream\" (\"name\" \"=?utf-8?q?d=C3=A9j=C3=A0.bin?=\") NIL NIL \"base64\" 78 NIL NIL NIL NIL)");
+        assert_eq!(attachments[0].part_id, "1");
+        assert_eq!(attachments[0].filename, "déjà.bin");
+        assert_eq!(attachments[0].size, 57);
+
+        // A text file is an attachment when it has a name
+        let attachments = parse(
+            "((\"text\" \"plain\" NIL NIL NIL \"7bit\" 5 1 NIL NIL NIL NIL)\
+             (\"text\" \"csv\" NIL NIL NIL \"7bit\" 40 2 NIL (\"attachment\" (\"filename*0\" \"big \" \"filename*1\" \"report.csv\")) NIL NIL) \
+             \"mixed\" (\"boundary\" \"m\") NIL NIL NIL)",
+        );
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(attachments[0].filename, "big report.csv");
+        assert_eq!(attachments[0].part_id, "2");
+    }
+}
@@ -0,0 +1,395 @@
+//! Appends that reuse content already on the server, via CATENATE (RFC 4469).
+//!
+//! Autosave appends a fresh copy of the draft every few seconds. When the
+//! previous copy is still on the server and the server supports CATENATE,
+//! attachment bodies that haven't changed are referenced by IMAP URL
+//! (RFC 5092) and copied server-side, so editing the text of a draft with a
+//! large attachment only uploads the text. Messages forwarded as
+//! attachments are referenced the same way, in drafts and in the Sent copy.
+//! Otherwise the fully expanded message goes up in a plain APPEND.
+//!
+//! async-imap supports neither CATENATE nor the APPENDUID response code, so
+//! this runs on a raw connection like the NOTIFY watcher.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::io::{AsyncWriteExt, BufReader};
+
+use super::client::{
+    capabilities_include, imap_quote, raw_connect_and_login, raw_send_and_wait,
+    raw_send_for_continuation, raw_uid_validity, ImapStream,
+};
+use super::types::ImapConfig;
+use crate::attachments::{self, AttachmentStaging, MessageChunk, STAGED_PART_HEADER};
+
+/// A draft appended by this session, and the body section each staged
+/// attachment ended up in.
+#[derive(Debug, Clone)]
+struct SavedDraft {
+    uid_validity: u32,
+    sections: HashMap<String, String>,
+}
+
+/// Drafts appended in this session, keyed by account, folder and UID.
+#[derive(Default)]
+pub struct SavedDrafts(Mutex<HashMap<String, SavedDraft>>);
+
+fn draft_key(config: &ImapConfig, folder: &str, uid: u32) -> String {
+    format!(
+        "{}@{}:{}/{folder}/{uid}",
+        config.username, config.host, config.port
+    )
+}
+
+/// Append a draft to `folder`, copying unchanged attachment bodies from the
+/// earlier copy with UID `replaces` when the server allows it.
+///
+/// Returns the new draft's UID, or `None` if the server doesn't report it
+/// (no UIDPLUS). The earlier copy is left in place for the caller to delete.
+pub async fn save_draft(
+    config: &ImapConfig,
+    folder: &str,
+    raw: &[u8],
+    staging: &AttachmentStaging,
+    drafts: &SavedDrafts,
+    replaces: Option<u32>,
+) -> Result<Option<u32>, String> {
+    let previous = replaces.and_then(|uid| {
+        let saved = drafts.0.lock().unwrap();
+        let draft = saved.get(&draft_key(config, folder, uid))?;
+        let path = message_path(folder, draft.uid_validity, uid);
+        Some(
+            draft
+                .sections
+                .iter()
+                .map(|(id, section)| (id.clone(), format!("{path}/;SECTION={section}")))
+                .collect::<HashMap<_, _>>(),
+        )
+    });
+
+    let response = append(
+        config,
+        folder,
+        "(\\Draft)",
+        raw,
+        staging,
+        previous.unwrap_or_default(),
+    )
+    .await?;
+    let Some((uid_validity, uid)) = parse_appenduid(&response) else {
+        return Ok(None);
+    };
+
+    let mut saved = drafts.0.lock().unwrap();
+    if let Some(old) = replaces {
+        saved.remove(&draft_key(config, folder, old));
+    }
+    saved.insert(
+        draft_key(config, folder, uid),
+        SavedDraft {
+            uid_validity,
+            sections: staged_sections(raw),
+        },
+    );
+    Ok(Some(uid))
+}
+
+/// Append a message with staged parts to `folder`, letting the server copy
+/// the parts in `urls` and any forwarded server messages when it supports
+/// CATENATE. Returns the tagged APPEND response.
+pub async fn append(
+    config: &ImapConfig,
+    folder: &str,
+    flags: &str,
+    raw: &[u8],
+    staging: &AttachmentStaging,
+    urls: HashMap<String, String>,
+) -> Result<String, String> {
+    let mut reader = raw_connect_and_login(config).await?;
+    let result = append_on(&mut reader, config, folder, flags, raw, staging, urls).await;
+    let _ = reader.get_mut().write_all(b"a9 LOGOUT\r\n").await;
+    result
+}
+
+async fn append_on(
+    reader: &mut BufReader<ImapStream>,
+    config: &ImapConfig,
+    folder: &str,
+    flags: &str,
+    raw: &[u8],
+    staging: &AttachmentStaging,
+    mut urls: HashMap<String, String>,
+) -> Result<String, String> {
+    let forwarded = attachments::server_messages(raw, staging);
+
+    if !urls.is_empty() || !forwarded.is_empty() {
+        let caps = raw_send_and_wait(reader, b"a2 CAPABILITY\r\n", "a2").await?;
+        if capabilities_include(&caps, "CATENATE") {
+            let attempt = async {
+                for (i, message) in forwarded.iter().enumerate() {
+                    let uid_validity =
+                        raw_uid_validity(reader, &format!("s{i}"), &message.folder).await?;
+                    urls.insert(
+                        message.id.clone(),
+                        message_path(&message.folder, uid_validity, message.uid),
+                    );
+                }
+                let chunks = attachments::expand_staged_parts_with_urls(raw, staging, &urls)?;
+                append_chunks(reader, "a3", folder, flags, &chunks).await
+            };
+            match attempt.await {
+                Ok(response) => return Ok(response),
+                Err(e) => log::warn!("CATENATE append failed, uploading the whole message: {e}"),
+            }
+        }
+    }
+
+    attachments::download_server_messages(config, raw, staging).await?;
+    let expanded = attachments::expand_staged_parts(raw, staging)?;
+    append_chunks(
+        reader,
+        "a4",
+        folder,
+        flags,
+        &[MessageChunk::Bytes(expanded)],
+    )
+    .await
+}
+
+/// Send `APPEND` with `flags`. A message made of a single byte chunk goes up
+/// as one literal; anything with server URLs is sent as a CATENATE list of
+/// `TEXT` literals and `URL`s.
+async fn append_chunks(
+    reader: &mut BufReader<ImapStream>,
+    tag: &str,
+    folder: &str,
+    flags: &str,
+    chunks: &[MessageChunk],
+) -> Result<String, String> {
+    let catenate = chunks
+        .iter()
+        .any(|c| matches!(c, MessageChunk::ServerUrl(_)));
+
+    let mut pending = format!("{tag} APPEND {} {flags} ", imap_quote(folder)).into_bytes();
+    if catenate {
+        pending.extend_from_slice(b"CATENATE (");
+    }
+
+    let mut first = true;
+    for chunk in chunks {
+        match chunk {
+            MessageChunk::Bytes(bytes) if catenate && bytes.is_empty() => continue,
+            MessageChunk::Bytes(bytes) => {
+                if catenate {
+                    if !first {
+                        pending.push(b' ');
+                    }
+                    pending.extend_from_slice(b"TEXT ");
+                }
+                pending.extend_from_slice(format!("{{{}}}\r\n", bytes.len()).as_bytes());
+                raw_send_for_continuation(reader, &pending, tag).await?;
+                reader
+                    .get_mut()
+                    .write_all(bytes)
+                    .await
+                    .map_err(|e| format!("{tag} write: {e}"))?;
+                pending.clear();
+            }
+            MessageChunk::ServerUrl(url) => {
+                if !first {
+                    pending.push(b' ');
+                }
+                pending.extend_from_slice(format!("URL {}", imap_quote(url)).as_bytes());
+            }
+        }
+        first = false;
+    }
+
+    if catenate {
+        pending.push(b')');
+    }
+    pending.extend_from_slice(b"\r\n");
+    raw_send_and_wait(reader, &pending, tag).await
+}
+
+/// Server-relative IMAP URL path of a message (RFC 5092).
+pub(super) fn message_path(folder: &str, uid_validity: u32, uid: u32) -> String {
+    format!(
+        "/{};UIDVALIDITY={uid_validity}/;UID={uid}",
+        encode_mailbox(folder)
+    )
+}
+
+/// `(uidvalidity, uid)` from an `[APPENDUID ...]` response code (RFC 4315).
+fn parse_appenduid(response: &str) -> Option<(u32, u32)> {
+    let start = response.find("[APPENDUID ")? + "[APPENDUID ".len();
+    let rest = &response[start..];
+    let mut fields = rest[..rest.find(']')?].split_whitespace();
+    let uid_validity = fields.next()?.parse().ok()?;
+    let uid = fields.next()?.parse().ok()?;
+    Some((uid_validity, uid))
+}
+
+/// Percent-encode a mailbox name for use in an IMAP URL path.
+pub(super) fn encode_mailbox(name: &str) -> String {
+    name.bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || b"-._~!$'()*+,&=:@/".contains(&b) {
+                (b as char).to_string()
+            } else {
+                format!("%{b:02X}")
+            }
+        })
+        .collect()
+}
+
+/// IMAP body section of each staged-attachment placeholder in an
+/// unexpanded draft, keyed by staged id.
+fn staged_sections(raw: &[u8]) -> HashMap<String, String> {
+    let mut sections = HashMap::new();
+    collect_sections(raw, "", &mut sections);
+    sections
+}
+
+fn collect_sections(entity: &[u8], section: &str, out: &mut HashMap<String, String>) {
+    let (headers, body) = split_entity(entity);
+    let headers = String::from_utf8_lossy(headers)
+        .replace("\r\n ", " ")
+        .replace("\r\n\t", " ");
+
+    if let Some(id) = headers
+        .lines()
+        .find_map(|l| l.strip_prefix(STAGED_PART_HEADER))
+    {
+        if !section.is_empty() {
+            out.insert(id.trim().to_string(), section.to_string());
+        }
+        return;
+    }
+
+    let Some(boundary) = multipart_boundary(&headers) else {
+        return;
+    };
+    for (i, part) in split_multipart(body, &boundary).into_iter().enumerate() {
+        let child = if section.is_empty() {
+            (i + 1).to_string()
+        } else {
+            format!("{section}.{}", i + 1)
+        };
+        collect_sections(part, &child, out);
+    }
+}
+
+/// Split a MIME entity into its header block and body.
+fn split_entity(entity: &[u8]) -> (&[u8], &[u8]) {
+    if let Some(body) = entity.strip_prefix(b"\r\n") {
+        return (&[], body);
+    }
+    match entity.windows(4).position(|w| w == b"\r\n\r\n") {
+        Some(pos) => (&entity[..pos + 2], &entity[pos + 4..]),
+        None => (entity, &[]),
+    }
+}
+
+/// Boundary parameter of a `multipart/*` Content-Type, if any.
+fn multipart_boundary(headers: &str) -> Option<String> {
+    let value = headers.lines().find_map(|l| {
+        let (name, value) = l.split_once(':')?;
+        name.trim()
+            .eq_ignore_ascii_case("content-type")
+            .then_some(value.trim())
+    })?;
+    if !value.to_ascii_lowercase().starts_with("multipart/") {
+        return None;
+    }
+
+    let start = value.to_ascii_lowercase().find("boundary=")? + "boundary=".len();
+    let rest = &value[start..];
+    let boundary = match rest.strip_prefix('"') {
+        Some(quoted) => &quoted[..quoted.find('"')?],
+        None => rest.split(|c: char| c == ';' || c.is_whitespace()).next()?,
+    };
+    (!boundary.is_empty()).then(|| boundary.to_string())
+}
+
+/// Body parts of a multipart entity, without their delimiter lines.
+fn split_multipart<'a>(body: &'a [u8], boundary: &str) -> Vec<&'a [u8]> {
+    let delimiter = format!("--{boundary}");
+    let mut parts = Vec::new();
+    let mut start = None;
+    let mut pos = 0;
+
+    for line in body.split(|&b| b == b'\n') {
+        let line_start = pos;
+        pos = (pos + line.len() + 1).min(body.len());
+
+        let end = line
+            .iter()
+            .rposition(|b| !b.is_ascii_whitespace())
+            .map_or(0, |i| i + 1);
+        let Some(rest) = line[..end].strip_prefix(delimiter.as_bytes()) else {
+            continue;
+        };
+        if !rest.is_empty() && rest != b"--" {
+            continue;
+        }
+        if let Some(start) = start {
+            // The CRLF before a delimiter belongs to the delimiter
+            let end = line_start.saturating_sub(2).max(start);
+            parts.push(&body[start..end]);
+        }
+        if rest == b"--" {
+            break;
+        }
+        start = Some(pos);
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_staged_sections() {
+        let raw = format!(
+            "Subject: Hi\r\n\
+             Content-Type: multipart/mixed; boundary=\"mix\"\r\n\r\n\
+             --mix\r\n\
+             Content-Type: multipart/related;\r\n boundary=\"rel\"\r\n\r\n\
+             --rel\r\n\
+             Content-Type: text/html\r\n\r\n\
+             <p>Hi</p>\r\n\
+             --rel\r\n\
+             {STAGED_PART_HEADER}inline-1\r\n\r\n\r\n\
+             --rel--\r\n\
+             --mix\r\n\
+             {STAGED_PART_HEADER}att-1\r\n\r\n\r\n\
+             --mix\r\n\
+             Content-Type: text/plain; name=\"a.txt\"\r\n\r\n\
+             aGk=\r\n\
+             --mix\r\n\
+             {STAGED_PART_HEADER}att-2\r\n\r\n\r\n\
+             --mix--"
+        );
+        let sections = staged_sections(raw.as_bytes());
+        assert_eq!(sections.len(), 3);
+        assert_eq!(sections["inline-1"], "1.2");
+        assert_eq!(sections["att-1"], "2");
+        assert_eq!(sections["att-2"], "4");
+    }
+
+    #[test]
+    fn test_parse_appenduid() {
+        let response = "a3 OK [APPENDUID 38505 3955] APPEND completed\r\n";
+        assert_eq!(parse_appenduid(response), Some((38505, 3955)));
+        assert_eq!(parse_appenduid("a3 OK APPEND completed\r\n"), None);
+    }
+
+    #[test]
+    fn test_encode_mailbox() {
+        assert_eq!(encode_mailbox("INBOX.Drafts"), "INBOX.Drafts");
+        assert_eq!(encode_mailbox("[Gmail]/Drafts"), "%5BGmail%5D/Drafts");
+        assert_eq!(encode_mailbox("My Drafts;x"), "My%20Drafts%3Bx");
+    }
+}
@@ -1,13 +1,23 @@
+use async_imap::imap_proto::types::{Address, Envelope};
 use async_imap::{types::Flag, Authenticator, Client, Session};
 use base64::Engine;
 use futures::StreamExt;
 use mail_parser::{MessageParser, MimeHeaders};
-use std::time::Duration;
-use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
 use tokio::net::TcpStream;
 use tokio_native_tls::TlsStream;
 
+use super::bodystructure;
+use super::date;
+use super::keywords;
+use super::tnef;
 use super::types::*;
+use super::uid_set::build_uid_sets;
+use crate::attachments::mime_from_extension;
+use crate::metrics::{self, Phase};
+use crate::rate_limit;
 
 // ---------- Timeout constants ----------
 
@@ -20,7 +30,7 @@ const IMAP_SEARCH_TIMEOUT: Duration = Duration::from_secs(60);
 const OVERALL_CONNECT_TIMEOUT: Duration = Duration::from_secs(60);
 
 /// Configure TCP keepalive and nodelay on a connected socket.
-fn configure_tcp_socket(stream: &TcpStream) {
+pub(crate) fn configure_tcp_socket(stream: &TcpStream) {
     // Set TCP nodelay via tokio's built-in API
     if let Err(e) = stream.set_nodelay(true) {
         log::warn!("Failed to set TCP_NODELAY: {e}");
@@ -62,6 +72,67 @@ impl Authenticator for XOAuth2 {
     }
 }
 
+// ---------- SASL PLAIN authenticator ----------
+
+/// SASL PLAIN with an authorization identity (RFC 4616): authenticate as
+/// `authcid` but act as `authzid`.
+struct PlainAuth {
+    response: Vec<u8>,
+}
+
+impl PlainAuth {
+    fn new(authzid: &str, authcid: &str, password: &str) -> Self {
+        Self {
+            response: format!("{authzid}\x00{authcid}\x00{password}").into_bytes(),
+        }
+    }
+}
+
+impl Authenticator for PlainAuth {
+    type Response = Vec<u8>;
+    fn process(&mut self, _challenge: &[u8]) -> Self::Response {
+        std::mem::take(&mut self.response)
+    }
+}
+
+/// How the configured credentials are used to log in.
+#[derive(Debug, PartialEq)]
+enum Login {
+    /// Plain LOGIN with this user name (the configured one, or Exchange's
+    /// `user\mailbox` form for delegated access).
+    Password(String),
+    /// AUTHENTICATE PLAIN acting as the given mailbox owner.
+    SaslPlain { authzid: String },
+    /// AUTHENTICATE XOAUTH2 for this user.
+    OAuth2(String),
+}
+
+fn login_mode(config: &ImapConfig) -> Login {
+    let delegate = config
+        .authorize_as
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+    match (config.auth_method.as_str(), delegate) {
+        // Delegated OAuth access (e.g. Office 365 shared mailboxes) names the
+        // target mailbox in the XOAUTH2 user field
+        ("oauth2", Some(mailbox)) => Login::OAuth2(mailbox.to_string()),
+        ("oauth2", None) => Login::OAuth2(config.username.clone()),
+        (_, Some(mailbox)) if config.delegation.as_deref() == Some("exchange") => {
+            Login::Password(format!("{}\\{mailbox}", config.username))
+        }
+        (_, Some(mailbox)) => Login::SaslPlain {
+            authzid: mailbox.to_string(),
+        },
+        (_, None) => Login::Password(config.username.clone()),
+    }
+}
+
+/// Quote a string for the IMAP protocol (backslash and double quote escaped).
+pub(super) fn imap_quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
 // ---------- Stream wrapper ----------
 
 /// Wrapper to unify TLS / plain streams so Session can be generic.
@@ -129,7 +200,9 @@ impl std::fmt::Debug for ImapStream {
 
 /// Build a TLS connector, optionally accepting invalid certificates
 /// (for local mail bridges like ProtonMail Bridge with self-signed certs).
-fn build_tls_connector(accept_invalid_certs: bool) -> Result<native_tls::TlsConnector, String> {
+pub(crate) fn build_tls_connector(
+    accept_invalid_certs: bool,
+) -> Result<native_tls::TlsConnector, String> {
     let mut builder = native_tls::TlsConnector::builder();
     if accept_invalid_certs {
         builder.danger_accept_invalid_certs(true);
@@ -140,15 +213,17 @@ fn build_tls_connector(accept_invalid_certs: bool) -> Result<native_tls::TlsConn
 
 // ---------- Public API ----------
 
-type ImapSession = Session<ImapStream>;
+pub(crate) type ImapSession = Session<ImapStream>;
 
 /// Establish an IMAP connection and authenticate.
 ///
 /// Supports TLS (direct), STARTTLS (upgrade), and plain connections.
 /// Auth methods: "password" (LOGIN) or "oauth2" (XOAUTH2).
 ///
-/// Wraps the entire connection + auth sequence in a 60s overall timeout.
+/// Wraps the entire connection + auth sequence in a 60s overall timeout,
+/// after waiting for the account's rate limiter.
 pub async fn connect(config: &ImapConfig) -> Result<ImapSession, String> {
+    rate_limit::acquire_imap(config).await;
     tokio::time::timeout(OVERALL_CONNECT_TIMEOUT, connect_inner(config))
         .await
         .map_err(|_| format!(
@@ -162,19 +237,85 @@ async fn connect_inner(config: &ImapConfig) -> Result<ImapSession, String> {
         return connect_starttls(config).await;
     }
 
-    let stream = connect_stream(config).await?;
+    let stream =
+        metrics::time(&config.host, "imap", Phase::Connect, connect_stream(config)).await?;
     let client = Client::new(stream);
 
-    tokio::time::timeout(AUTH_TIMEOUT, authenticate(client, config))
-        .await
-        .map_err(|_| format!(
-            "IMAP authentication timed out after {}s — check your server settings or network connection",
-            AUTH_TIMEOUT.as_secs()
-        ))?
+    metrics::time(&config.host, "imap", Phase::Auth, async {
+        tokio::time::timeout(AUTH_TIMEOUT, authenticate(client, config))
+            .await
+            .map_err(|_| format!(
+                "IMAP authentication timed out after {}s — check your server settings or network connection",
+                AUTH_TIMEOUT.as_secs()
+            ))?
+    })
+    .await
 }
 
 /// List all IMAP folders/mailboxes.
+///
+/// Servers with LIST-STATUS (RFC 5819) return folders, special-use roles and
+/// counts in a single `LIST ... RETURN (...)` exchange. Others get a plain
+/// LIST followed by one STATUS per folder.
 pub async fn list_folders(session: &mut ImapSession) -> Result<Vec<ImapFolder>, String> {
+    let caps = match tokio::time::timeout(IMAP_CMD_TIMEOUT, session.capabilities()).await {
+        Ok(Ok(caps)) => Some(caps),
+        _ => None,
+    };
+
+    if let Some(caps) = caps.filter(|c| c.has_str("LIST-STATUS")) {
+        match list_folders_extended(session, caps.has_str("SPECIAL-USE")).await {
+            Ok(folders) => return Ok(folders),
+            Err(e) => log::warn!("LIST-STATUS failed, falling back to LIST + STATUS: {e}"),
+        }
+    }
+
+    list_folders_with_status(session).await
+}
+
+/// Single round trip: `LIST "" "*" RETURN (SPECIAL-USE STATUS (MESSAGES UNSEEN))`.
+async fn list_folders_extended(
+    session: &mut ImapSession,
+    special_use: bool,
+) -> Result<Vec<ImapFolder>, String> {
+    let command = if special_use {
+        "LIST \"\" \"*\" RETURN (SPECIAL-USE STATUS (MESSAGES UNSEEN))"
+    } else {
+        "LIST \"\" \"*\" RETURN (STATUS (MESSAGES UNSEEN))"
+    };
+
+    let raw = tokio::time::timeout(
+        IMAP_CMD_TIMEOUT,
+        session.run_command_and_read_response(command),
+    )
+    .await
+    .map_err(|_| {
+        format!(
+            "LIST-STATUS timed out after {}s — check your server settings or network connection",
+            IMAP_CMD_TIMEOUT.as_secs()
+        )
+    })?
+    .map_err(|e| format!("LIST-STATUS failed: {e}"))?;
+
+    let entries = super::list_status::parse_list_status(&raw)?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| {
+            let delimiter = entry.delimiter.unwrap_or_else(|| "/".to_string());
+            build_folder(
+                entry.raw_path,
+                delimiter,
+                entry.attributes,
+                entry.exists.unwrap_or(0),
+                entry.unseen.unwrap_or(0),
+            )
+        })
+        .collect())
+}
+
+/// LIST followed by a STATUS per selectable folder.
+async fn list_folders_with_status(session: &mut ImapSession) -> Result<Vec<ImapFolder>, String> {
     let names_stream = tokio::time::timeout(IMAP_CMD_TIMEOUT, session.list(Some(""), Some("*")))
         .await
         .map_err(|_| format!("LIST timed out after {}s — check your server settings or network connection", IMAP_CMD_TIMEOUT.as_secs()))?
@@ -191,52 +332,205 @@ pub async fn list_folders(session: &mut ImapSession) -> Result<Vec<ImapFolder>,
     for name in &names {
         let raw_path = name.name().to_string();
         let delimiter = name.delimiter().unwrap_or("/").to_string();
+        let attributes = name_attributes(name);
 
-        // Decode modified UTF-7 (RFC 3501 §5.1.3) to UTF-8 for display
-        let path = utf7_imap::decode_utf7_imap(raw_path.clone());
-
-        // Extract display name (last segment after delimiter)
-        let display_name = path
-            .rsplit_once(&delimiter)
-            .map(|(_, last)| last.to_string())
-            .unwrap_or_else(|| path.clone());
-
-        // Detect special-use from attributes (RFC 6154)
-        let special_use = detect_special_use(name);
+        let no_select = super::folder_tree::has_attribute(&attributes, "\\Noselect");
+        let non_existent = super::folder_tree::has_attribute(&attributes, "\\NonExistent");
 
-        // Get message counts via STATUS — use raw_path for IMAP commands
-        let (exists, unseen) = match tokio::time::timeout(
-            IMAP_CMD_TIMEOUT,
-            session.status(&raw_path, "(MESSAGES UNSEEN)"),
-        ).await {
-            Ok(Ok(mailbox)) => (mailbox.exists, mailbox.unseen.unwrap_or(0)),
-            _ => (0, 0),
+        // Get message counts via STATUS — use raw_path for IMAP commands.
+        // Containers can't be STATUSed; skip them instead of logging a NO per folder.
+        let (exists, unseen) = if no_select || non_existent {
+            (0, 0)
+        } else {
+            match tokio::time::timeout(
+                IMAP_CMD_TIMEOUT,
+                session.status(&raw_path, "(MESSAGES UNSEEN)"),
+            )
+            .await
+            {
+                Ok(Ok(mailbox)) => (mailbox.exists, mailbox.unseen.unwrap_or(0)),
+                _ => (0, 0),
+            }
         };
 
-        folders.push(ImapFolder {
-            path,
-            raw_path,
-            name: display_name,
-            delimiter,
-            special_use,
-            exists,
-            unseen,
-        });
+        folders.push(build_folder(
+            raw_path, delimiter, attributes, exists, unseen,
+        ));
     }
 
     Ok(folders)
 }
 
+/// Assemble an `ImapFolder` from LIST data and STATUS counts.
+fn build_folder(
+    raw_path: String,
+    delimiter: String,
+    attributes: Vec<String>,
+    exists: u32,
+    unseen: u32,
+) -> ImapFolder {
+    // Decode modified UTF-7 (RFC 3501 §5.1.3) to UTF-8 for display
+    let path = utf7_imap::decode_utf7_imap(raw_path.clone());
+
+    // Extract display name (last segment after delimiter)
+    let display_name = path
+        .rsplit_once(&delimiter)
+        .map(|(_, last)| last.to_string())
+        .unwrap_or_else(|| path.clone());
+
+    // Detect special-use from attributes (RFC 6154)
+    let special_use = detect_special_use(&attributes, &raw_path);
+
+    let no_select = super::folder_tree::has_attribute(&attributes, "\\Noselect");
+    let non_existent = super::folder_tree::has_attribute(&attributes, "\\NonExistent");
+
+    ImapFolder {
+        path,
+        raw_path,
+        name: display_name,
+        delimiter,
+        special_use,
+        exists,
+        unseen,
+        attributes,
+        no_select,
+        non_existent,
+    }
+}
+
+/// Encode a folder path typed by the user (UTF-8, levels joined by the
+/// account's delimiter) as a modified UTF-7 mailbox name (RFC 3501 §5.1.3).
+pub fn encode_folder_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Folder name is empty".to_string());
+    }
+    // LIST wildcards and control characters can't be part of a name
+    if let Some(c) = name
+        .chars()
+        .find(|c| matches!(c, '*' | '%') || c.is_control())
+    {
+        return Err(format!("Folder names can't contain {c:?}"));
+    }
+    Ok(utf7_imap::encode_utf7_imap(name.to_string()))
+}
+
+/// Run a mailbox command (CREATE, DELETE…) with the command timeout.
+async fn mailbox_command(
+    command: &str,
+    mailbox: &str,
+    fut: impl std::future::Future<Output = async_imap::error::Result<()>>,
+) -> Result<(), String> {
+    tokio::time::timeout(IMAP_CMD_TIMEOUT, fut)
+        .await
+        .map_err(|_| format!("{command} {mailbox} timed out after {}s — check your server settings or network connection", IMAP_CMD_TIMEOUT.as_secs()))?
+        .map_err(|e| format!("{command} {mailbox} failed: {e}"))
+}
+
+/// Create a folder from a UTF-8 path, returning its raw (encoded) path.
+pub async fn create_folder(session: &mut ImapSession, path: &str) -> Result<String, String> {
+    let raw_path = encode_folder_name(path)?;
+    mailbox_command("CREATE", &raw_path, session.create(&raw_path)).await?;
+    Ok(raw_path)
+}
+
+/// Rename the folder at UTF-8 `path` to `new_path`, returning the new raw
+/// path. Renaming also moves the folder's children.
+pub async fn rename_folder(
+    session: &mut ImapSession,
+    path: &str,
+    new_path: &str,
+) -> Result<String, String> {
+    let raw_path = encode_folder_name(path)?;
+    let new_raw_path = encode_folder_name(new_path)?;
+    mailbox_command(
+        "RENAME",
+        &raw_path,
+        session.rename(&raw_path, &new_raw_path),
+    )
+    .await?;
+    Ok(new_raw_path)
+}
+
+pub async fn delete_folder(session: &mut ImapSession, path: &str) -> Result<(), String> {
+    let raw_path = encode_folder_name(path)?;
+    mailbox_command("DELETE", &raw_path, session.delete(&raw_path)).await
+}
+
+pub async fn subscribe_folder(session: &mut ImapSession, path: &str) -> Result<(), String> {
+    let raw_path = encode_folder_name(path)?;
+    mailbox_command("SUBSCRIBE", &raw_path, session.subscribe(&raw_path)).await
+}
+
+pub async fn unsubscribe_folder(session: &mut ImapSession, path: &str) -> Result<(), String> {
+    let raw_path = encode_folder_name(path)?;
+    mailbox_command("UNSUBSCRIBE", &raw_path, session.unsubscribe(&raw_path)).await
+}
+
+/// Error prefix returned when a folder exists only as a hierarchy container
+/// (`\Noselect`) or has been deleted (`\NonExistent`), so the frontend can
+/// tell it apart from connection failures.
+pub const NOT_SELECTABLE_PREFIX: &str = "NOT_SELECTABLE:";
+
+/// SELECT a folder, returning a `NOT_SELECTABLE:` error for containers.
+///
+/// When SELECT fails, the folder is re-LISTed to check its attributes; only
+/// then do we know whether the NO was caused by `\Noselect`/`\NonExistent`.
+async fn select_folder(
+    session: &mut ImapSession,
+    folder: &str,
+) -> Result<async_imap::types::Mailbox, String> {
+    let err = match tokio::time::timeout(IMAP_CMD_TIMEOUT, session.select(folder)).await {
+        Ok(Ok(mailbox)) => return Ok(mailbox),
+        Ok(Err(e)) => format!("SELECT {folder} failed: {e}"),
+        Err(_) => {
+            return Err(format!(
+                "SELECT {folder} timed out after {}s — check your server settings or network connection",
+                IMAP_CMD_TIMEOUT.as_secs()
+            ))
+        }
+    };
+
+    let names: Vec<_> = match tokio::time::timeout(IMAP_CMD_TIMEOUT, async {
+        let stream = session.list(Some(""), Some(folder)).await?;
+        Ok::<_, async_imap::error::Error>(stream.collect::<Vec<_>>().await)
+    })
+    .await
+    {
+        Ok(Ok(names)) => names.into_iter().filter_map(|r| r.ok()).collect(),
+        _ => return Err(err),
+    };
+
+    match names.first() {
+        None => Err(format!("{NOT_SELECTABLE_PREFIX}{folder} does not exist")),
+        Some(name) => {
+            let attributes = name_attributes(name);
+            if super::folder_tree::has_attribute(&attributes, "\\Noselect")
+                || super::folder_tree::has_attribute(&attributes, "\\NonExistent")
+            {
+                Err(format!(
+                    "{NOT_SELECTABLE_PREFIX}{folder} is a container folder and cannot be opened"
+                ))
+            } else {
+                Err(err)
+            }
+        }
+    }
+}
+
+/// List folders and nest them into a hierarchy for the sidebar tree.
+pub async fn list_folder_tree(session: &mut ImapSession) -> Result<Vec<ImapFolderNode>, String> {
+    let folders = list_folders(session).await?;
+    Ok(super::folder_tree::build_folder_tree(&folders))
+}
+
 /// Fetch messages from a folder by UID range (e.g. "1:100" or "500:*").
 pub async fn fetch_messages(
     session: &mut ImapSession,
     folder: &str,
     uid_range: &str,
 ) -> Result<ImapFetchResult, String> {
-    let mailbox = tokio::time::timeout(IMAP_CMD_TIMEOUT, session.select(folder))
-        .await
-        .map_err(|_| format!("SELECT {folder} timed out after {}s — check your server settings or network connection", IMAP_CMD_TIMEOUT.as_secs()))?
-        .map_err(|e| format!("SELECT {folder} failed: {e}"))?;
+    let mailbox = select_folder(session, folder).await?;
 
     let folder_status = ImapFolderStatus {
         uidvalidity: mailbox.uid_validity.unwrap_or(0),
@@ -308,12 +602,19 @@ pub async fn fetch_messages(
         // Extract INTERNALDATE as fallback for messages with unparseable Date headers
         let internal_date = fetch.internal_date().map(|dt| dt.timestamp());
 
-        match parse_message(&parser, raw, uid, folder, raw_size, is_read, is_starred, is_draft, internal_date) {
-            Ok(msg) => messages.push(msg),
-            Err(e) => {
-                log::warn!("Failed to parse message UID {uid}: {e}");
-            }
-        }
+        let mut message = parse_message(
+            &parser,
+            raw,
+            uid,
+            folder,
+            raw_size,
+            is_read,
+            is_starred,
+            is_draft,
+            internal_date,
+        );
+        message.keywords = keywords::from_flags(&flags);
+        messages.push(message);
     }
 
     Ok(ImapFetchResult {
@@ -328,10 +629,7 @@ pub async fn fetch_message_body(
     folder: &str,
     uid: u32,
 ) -> Result<ImapMessage, String> {
-    tokio::time::timeout(IMAP_CMD_TIMEOUT, session.select(folder))
-        .await
-        .map_err(|_| format!("SELECT {folder} timed out after {}s — check your server settings or network connection", IMAP_CMD_TIMEOUT.as_secs()))?
-        .map_err(|e| format!("SELECT {folder} failed: {e}"))?;
+    select_folder(session, folder).await?;
 
     let uid_str = uid.to_string();
     let fetches: Vec<_> = tokio::time::timeout(IMAP_FETCH_TIMEOUT, async {
@@ -363,7 +661,131 @@ pub async fn fetch_message_body(
     let is_draft = flags.iter().any(|f| matches!(f, Flag::Draft));
 
     let parser = MessageParser::default();
-    parse_message(&parser, raw, uid, folder, raw_size, is_read, is_starred, is_draft, None)
+    let mut message = parse_message(
+        &parser, raw, uid, folder, raw_size, is_read, is_starred, is_draft, None,
+    );
+    message.keywords = keywords::from_flags(&flags);
+    Ok(message)
+}
+
+/// Fetch the envelopes of messages by UID range, without their bodies.
+/// Much less to download than [`fetch_messages`], for showing a message list
+/// before the bodies are needed.
+pub async fn fetch_headers(
+    session: &mut ImapSession,
+    folder: &str,
+    uid_range: &str,
+) -> Result<Vec<ImapMessageHeader>, String> {
+    select_folder(session, folder).await?;
+
+    let fetches = tokio::time::timeout(IMAP_FETCH_TIMEOUT, async {
+        let stream = session
+            .uid_fetch(uid_range, "(UID FLAGS ENVELOPE INTERNALDATE RFC822.SIZE BODYSTRUCTURE)")
+            .await
+            .map_err(|e| format!("UID FETCH {folder} uids={uid_range} failed: {e}"))?;
+        Ok::<_, String>(stream.collect::<Vec<_>>().await)
+    })
+    .await
+    .map_err(|_| format!("UID FETCH {folder} timed out after {}s — check your server settings or network connection", IMAP_FETCH_TIMEOUT.as_secs()))??;
+
+    let mut headers = Vec::new();
+    for fetch in fetches {
+        let fetch = match fetch {
+            Ok(f) => f,
+            Err(e) => {
+                log::warn!("IMAP header fetch stream error in {folder}: {e}");
+                continue;
+            }
+        };
+        let uid = match fetch.uid {
+            Some(u) => u,
+            None => {
+                log::warn!("IMAP FETCH {folder}: response missing UID");
+                continue;
+            }
+        };
+
+        let internal_date = fetch.internal_date().map(|dt| dt.timestamp());
+        let mut header = header_from_envelope(fetch.envelope(), uid, folder, internal_date);
+        let flags: Vec<_> = fetch.flags().collect();
+        header.is_read = flags.iter().any(|f| matches!(f, Flag::Seen));
+        header.is_starred = flags.iter().any(|f| matches!(f, Flag::Flagged));
+        header.is_draft = flags.iter().any(|f| matches!(f, Flag::Draft));
+        header.raw_size = fetch.size.unwrap_or(0);
+        header.has_attachments = fetch
+            .bodystructure()
+            .is_some_and(bodystructure::has_attachments);
+        headers.push(header);
+    }
+    Ok(headers)
+}
+
+/// Fetch only the keywords of messages by UID range, to refresh ones that
+/// may have been changed from another client since they were synced.
+pub async fn fetch_keywords(
+    session: &mut ImapSession,
+    folder: &str,
+    uid_range: &str,
+) -> Result<Vec<ImapMessageKeywords>, String> {
+    select_folder(session, folder).await?;
+
+    let fetches = tokio::time::timeout(IMAP_FETCH_TIMEOUT, async {
+        let stream = session
+            .uid_fetch(uid_range, "(UID FLAGS)")
+            .await
+            .map_err(|e| format!("UID FETCH FLAGS {folder} uids={uid_range} failed: {e}"))?;
+        Ok::<_, String>(stream.collect::<Vec<_>>().await)
+    })
+    .await
+    .map_err(|_| format!("UID FETCH FLAGS {folder} timed out after {}s — check your server settings or network connection", IMAP_FETCH_TIMEOUT.as_secs()))??;
+
+    Ok(fetches
+        .into_iter()
+        .filter_map(|fetch| {
+            fetch
+                .map_err(|e| log::warn!("IMAP flag fetch stream error in {folder}: {e}"))
+                .ok()
+        })
+        .filter_map(|fetch| {
+            let flags: Vec<_> = fetch.flags().collect();
+            Some(ImapMessageKeywords {
+                uid: fetch.uid?,
+                keywords: keywords::from_flags(&flags),
+            })
+        })
+        .collect())
+}
+
+/// Attachment metadata of a message from its BODYSTRUCTURE, without
+/// downloading the message.
+pub async fn fetch_bodystructure(
+    session: &mut ImapSession,
+    folder: &str,
+    uid: u32,
+) -> Result<Vec<ImapAttachment>, String> {
+    select_folder(session, folder).await?;
+
+    let uid_str = uid.to_string();
+    let fetches: Vec<_> = tokio::time::timeout(IMAP_FETCH_TIMEOUT, async {
+        let stream = session
+            .uid_fetch(&uid_str, "(UID BODYSTRUCTURE)")
+            .await
+            .map_err(|e| format!("UID FETCH BODYSTRUCTURE failed: {e}"))?;
+        Ok::<_, String>(stream.collect::<Vec<_>>().await)
+    })
+    .await
+    .map_err(|_| format!("UID FETCH BODYSTRUCTURE for UID {uid} timed out after {}s — check your server settings or network connection", IMAP_FETCH_TIMEOUT.as_secs()))?
+    ?
+    .into_iter()
+    .filter_map(|r| r.ok())
+    .collect();
+
+    let structure = fetches
+        .iter()
+        .find(|f| f.uid == Some(uid))
+        .and_then(|f| f.bodystructure())
+        .ok_or_else(|| format!("Message UID {uid} not found in {folder}"))?;
+    Ok(bodystructure::attachments(structure))
 }
 
 /// Get UIDs of messages newer than `last_uid`.
@@ -372,10 +794,7 @@ pub async fn fetch_new_uids(
     folder: &str,
     last_uid: u32,
 ) -> Result<Vec<u32>, String> {
-    tokio::time::timeout(IMAP_CMD_TIMEOUT, session.select(folder))
-        .await
-        .map_err(|_| format!("SELECT {folder} timed out after {}s — check your server settings or network connection", IMAP_CMD_TIMEOUT.as_secs()))?
-        .map_err(|e| format!("SELECT {folder} failed: {e}"))?;
+    select_folder(session, folder).await?;
 
     let query = format!("{}:*", last_uid + 1);
     let uids = tokio::time::timeout(IMAP_SEARCH_TIMEOUT, session.uid_search(&query))
@@ -391,14 +810,8 @@ pub async fn fetch_new_uids(
 
 /// Search for all UIDs in a folder using `UID SEARCH ALL`.
 /// Returns real UIDs sorted ascending — avoids the sparse UID gap problem.
-pub async fn search_all_uids(
-    session: &mut ImapSession,
-    folder: &str,
-) -> Result<Vec<u32>, String> {
-    tokio::time::timeout(IMAP_CMD_TIMEOUT, session.select(folder))
-        .await
-        .map_err(|_| format!("SELECT {folder} timed out after {}s — check your server settings or network connection", IMAP_CMD_TIMEOUT.as_secs()))?
-        .map_err(|e| format!("SELECT {folder} failed: {e}"))?;
+pub async fn search_all_uids(session: &mut ImapSession, folder: &str) -> Result<Vec<u32>, String> {
+    select_folder(session, folder).await?;
 
     let uids = tokio::time::timeout(IMAP_SEARCH_TIMEOUT, session.uid_search("ALL"))
         .await
@@ -410,6 +823,48 @@ pub async fn search_all_uids(
     Ok(result)
 }
 
+/// Build `UID SEARCH` criteria matching `text` anywhere in a message,
+/// optionally limited to messages before an IMAP date (`1-Jan-2024`).
+fn text_search_criteria(text: &str, before: Option<&str>) -> String {
+    let mut criteria = String::new();
+    if !text.is_ascii() {
+        criteria.push_str("CHARSET UTF-8 ");
+    }
+    criteria.push_str("TEXT ");
+    criteria.push_str(&imap_quote(text));
+    if let Some(date) = before {
+        criteria.push_str(" BEFORE ");
+        criteria.push_str(date);
+    }
+    criteria
+}
+
+/// Search a folder on the server for messages containing `text`, for ranges
+/// that haven't been synced into the local index.
+pub async fn search_text(
+    session: &mut ImapSession,
+    folder: &str,
+    text: &str,
+    before: Option<&str>,
+) -> Result<Vec<u32>, String> {
+    select_folder(session, folder).await?;
+
+    let criteria = text_search_criteria(text, before);
+    let uids = tokio::time::timeout(IMAP_SEARCH_TIMEOUT, session.uid_search(&criteria))
+        .await
+        .map_err(|_| {
+            format!(
+                "UID SEARCH timed out after {}s — check your server settings or network connection",
+                IMAP_SEARCH_TIMEOUT.as_secs()
+            )
+        })?
+        .map_err(|e| format!("UID SEARCH failed: {e}"))?;
+
+    let mut result: Vec<u32> = uids.into_iter().collect();
+    result.sort();
+    Ok(result)
+}
+
 /// Set or remove flags on messages.
 ///
 /// `flag_op`: "+FLAGS" to add, "-FLAGS" to remove
@@ -417,26 +872,32 @@ pub async fn search_all_uids(
 pub async fn set_flags(
     session: &mut ImapSession,
     folder: &str,
-    uid_set: &str,
+    uids: &[u32],
     flag_op: &str,
     flags: &str,
 ) -> Result<(), String> {
-    tokio::time::timeout(IMAP_CMD_TIMEOUT, session.select(folder))
-        .await
-        .map_err(|_| format!("SELECT {folder} timed out after {}s — check your server settings or network connection", IMAP_CMD_TIMEOUT.as_secs()))?
-        .map_err(|e| format!("SELECT {folder} failed: {e}"))?;
+    select_folder(session, folder).await?;
 
     let query = format!("{flag_op} {flags}");
-    tokio::time::timeout(IMAP_CMD_TIMEOUT, async {
-        let stream = session
-            .uid_store(uid_set, &query)
-            .await
-            .map_err(|e| format!("UID STORE failed: {e}"))?;
-        let _: Vec<_> = stream.collect().await;
-        Ok::<_, String>(())
-    })
-    .await
-    .map_err(|_| format!("UID STORE timed out after {}s — check your server settings or network connection", IMAP_CMD_TIMEOUT.as_secs()))?
+    for uid_set in build_uid_sets(uids) {
+        tokio::time::timeout(IMAP_CMD_TIMEOUT, async {
+            let stream = session
+                .uid_store(&uid_set, &query)
+                .await
+                .map_err(|e| format!("UID STORE failed: {e}"))?;
+            let _: Vec<_> = stream.collect().await;
+            Ok::<_, String>(())
+        })
+        .await
+        .map_err(|_| {
+            format!(
+                "UID STORE timed out after {}s — check your server settings or network connection",
+                IMAP_CMD_TIMEOUT.as_secs()
+            )
+        })??;
+    }
+
+    Ok(())
 }
 
 /// Move messages between folders.
@@ -445,48 +906,34 @@ pub async fn set_flags(
 pub async fn move_messages(
     session: &mut ImapSession,
     source_folder: &str,
-    uid_set: &str,
+    uids: &[u32],
     dest_folder: &str,
 ) -> Result<(), String> {
-    tokio::time::timeout(IMAP_CMD_TIMEOUT, session.select(source_folder))
-        .await
-        .map_err(|_| format!("SELECT {source_folder} timed out after {}s — check your server settings or network connection", IMAP_CMD_TIMEOUT.as_secs()))?
-        .map_err(|e| format!("SELECT {source_folder} failed: {e}"))?;
-
-    // Try MOVE extension first
-    match tokio::time::timeout(IMAP_CMD_TIMEOUT, session.uid_mv(uid_set, dest_folder)).await {
-        Ok(Ok(())) => return Ok(()),
-        _ => {
-            // Fallback: COPY, then mark Deleted, then EXPUNGE
-            tokio::time::timeout(IMAP_CMD_TIMEOUT, session.uid_copy(uid_set, dest_folder))
-                .await
-                .map_err(|_| format!("UID COPY timed out after {}s — check your server settings or network connection", IMAP_CMD_TIMEOUT.as_secs()))?
-                .map_err(|e| format!("UID COPY failed: {e}"))?;
-
-            tokio::time::timeout(IMAP_CMD_TIMEOUT, async {
-                let store_stream = session
-                    .uid_store(uid_set, "+FLAGS (\\Deleted)")
+    select_folder(session, source_folder).await?;
+
+    let uid_sets = build_uid_sets(uids);
+    let mut needs_expunge = false;
+    for uid_set in &uid_sets {
+        // Try MOVE extension first
+        match tokio::time::timeout(IMAP_CMD_TIMEOUT, session.uid_mv(uid_set, dest_folder)).await {
+            Ok(Ok(())) => continue,
+            _ => {
+                // Fallback: COPY, then mark Deleted, then EXPUNGE once at the end
+                tokio::time::timeout(IMAP_CMD_TIMEOUT, session.uid_copy(uid_set, dest_folder))
                     .await
-                    .map_err(|e| format!("UID STORE +Deleted failed: {e}"))?;
-                let _: Vec<_> = store_stream.collect().await;
-                Ok::<_, String>(())
-            })
-            .await
-            .map_err(|_| format!("UID STORE +Deleted timed out after {}s — check your server settings or network connection", IMAP_CMD_TIMEOUT.as_secs()))??;
+                    .map_err(|_| format!("UID COPY timed out after {}s — check your server settings or network connection", IMAP_CMD_TIMEOUT.as_secs()))?
+                    .map_err(|e| format!("UID COPY failed: {e}"))?;
 
-            tokio::time::timeout(IMAP_CMD_TIMEOUT, async {
-                let expunge_stream = session
-                    .expunge()
-                    .await
-                    .map_err(|e| format!("EXPUNGE failed: {e}"))?;
-                let _: Vec<_> = expunge_stream.collect().await;
-                Ok::<_, String>(())
-            })
-            .await
-            .map_err(|_| format!("EXPUNGE timed out after {}s — check your server settings or network connection", IMAP_CMD_TIMEOUT.as_secs()))??;
+                store_deleted(session, uid_set).await?;
+                needs_expunge = true;
+            }
         }
     }
 
+    if needs_expunge {
+        expunge(session).await?;
+    }
+
     Ok(())
 }
 
@@ -494,13 +941,17 @@ pub async fn move_messages(
 pub async fn delete_messages(
     session: &mut ImapSession,
     folder: &str,
-    uid_set: &str,
+    uids: &[u32],
 ) -> Result<(), String> {
-    tokio::time::timeout(IMAP_CMD_TIMEOUT, session.select(folder))
-        .await
-        .map_err(|_| format!("SELECT {folder} timed out after {}s — check your server settings or network connection", IMAP_CMD_TIMEOUT.as_secs()))?
-        .map_err(|e| format!("SELECT {folder} failed: {e}"))?;
+    select_folder(session, folder).await?;
 
+    for uid_set in build_uid_sets(uids) {
+        store_deleted(session, &uid_set).await?;
+    }
+    expunge(session).await
+}
+
+async fn store_deleted(session: &mut ImapSession, uid_set: &str) -> Result<(), String> {
     tokio::time::timeout(IMAP_CMD_TIMEOUT, async {
         let store_stream = session
             .uid_store(uid_set, "+FLAGS (\\Deleted)")
@@ -510,8 +961,10 @@ pub async fn delete_messages(
         Ok::<_, String>(())
     })
     .await
-    .map_err(|_| format!("UID STORE +Deleted timed out after {}s — check your server settings or network connection", IMAP_CMD_TIMEOUT.as_secs()))??;
+    .map_err(|_| format!("UID STORE +Deleted timed out after {}s — check your server settings or network connection", IMAP_CMD_TIMEOUT.as_secs()))?
+}
 
+async fn expunge(session: &mut ImapSession) -> Result<(), String> {
     tokio::time::timeout(IMAP_CMD_TIMEOUT, async {
         let expunge_stream = session
             .expunge()
@@ -521,9 +974,12 @@ pub async fn delete_messages(
         Ok::<_, String>(())
     })
     .await
-    .map_err(|_| format!("EXPUNGE timed out after {}s — check your server settings or network connection", IMAP_CMD_TIMEOUT.as_secs()))??;
-
-    Ok(())
+    .map_err(|_| {
+        format!(
+            "EXPUNGE timed out after {}s — check your server settings or network connection",
+            IMAP_CMD_TIMEOUT.as_secs()
+        )
+    })?
 }
 
 /// Append a raw message to a folder (for saving sent mail or drafts).
@@ -573,10 +1029,7 @@ pub async fn fetch_attachment(
     uid: u32,
     part_id: &str,
 ) -> Result<String, String> {
-    tokio::time::timeout(IMAP_CMD_TIMEOUT, session.select(folder))
-        .await
-        .map_err(|_| format!("SELECT {folder} timed out after {}s — check your server settings or network connection", IMAP_CMD_TIMEOUT.as_secs()))?
-        .map_err(|e| format!("SELECT {folder} failed: {e}"))?;
+    select_folder(session, folder).await?;
 
     let uid_str = uid.to_string();
     let fetches: Vec<_> = tokio::time::timeout(IMAP_FETCH_TIMEOUT, async {
@@ -601,24 +1054,28 @@ pub async fn fetch_attachment(
         .body()
         .ok_or_else(|| format!("No body for UID {uid}"))?;
 
+    let data = decode_attachment(raw, uid, part_id)?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(&data))
+}
+
+/// Extract the decoded bytes of part `part_id` from a raw message. Also
+/// used for newsgroup articles, whose `uid` is the article number.
+pub(crate) fn decode_attachment(raw: &[u8], uid: u32, part_id: &str) -> Result<Vec<u8>, String> {
     // Parse the full message — mail-parser decodes content-transfer-encoding
     let parser = MessageParser::default();
     let message = parser
         .parse(raw)
         .ok_or_else(|| format!("Failed to parse message UID {uid}"))?;
 
-    // Build section map and find the part index for the requested section path
-    let section_map = build_imap_section_map(&message);
-    let target_part_idx = section_map
-        .iter()
-        .find(|(_, section)| section.as_str() == part_id)
-        .map(|(&idx, _)| idx)
-        .ok_or_else(|| format!("Section {part_id} not found in message UID {uid}"))?;
+    // Files unpacked from winmail.dat live inside their TNEF part's section
+    let (section_id, tnef_item) = match tnef::parse_part_id(part_id) {
+        Some((section, item)) => (section, Some(item)),
+        None => (part_id, None),
+    };
 
-    let part = message
-        .parts
-        .get(target_part_idx)
-        .ok_or_else(|| format!("Part index {target_part_idx} out of range for UID {uid}"))?;
+    // Find the part for the requested section path (possibly inside an attached message)
+    let part = find_section_part(&message, "", section_id)
+        .ok_or_else(|| format!("Section {section_id} not found in message UID {uid}"))?;
 
     // Extract the decoded binary content from the part
     let data = match &part.body {
@@ -636,7 +1093,12 @@ pub async fn fetch_attachment(
         }
     };
 
-    Ok(base64::engine::general_purpose::STANDARD.encode(&data))
+    match tnef_item {
+        Some(item) => tnef::decode(&data)?
+            .take(item)
+            .ok_or_else(|| format!("Part {part_id} not found in TNEF data for UID {uid}")),
+        None => Ok(data),
+    }
 }
 
 /// Fetch the raw RFC822 source of a single message by UID.
@@ -646,10 +1108,17 @@ pub async fn fetch_raw_message(
     folder: &str,
     uid: u32,
 ) -> Result<String, String> {
-    tokio::time::timeout(IMAP_CMD_TIMEOUT, session.select(folder))
-        .await
-        .map_err(|_| format!("SELECT {folder} timed out after {}s — check your server settings or network connection", IMAP_CMD_TIMEOUT.as_secs()))?
-        .map_err(|e| format!("SELECT {folder} failed: {e}"))?;
+    let raw = fetch_raw_message_bytes(session, folder, uid).await?;
+    Ok(String::from_utf8_lossy(&raw).to_string())
+}
+
+/// Fetch the raw RFC822 source of a single message by UID, byte for byte.
+pub async fn fetch_raw_message_bytes(
+    session: &mut ImapSession,
+    folder: &str,
+    uid: u32,
+) -> Result<Vec<u8>, String> {
+    select_folder(session, folder).await?;
 
     let uid_str = uid.to_string();
     let fetches: Vec<_> = tokio::time::timeout(IMAP_FETCH_TIMEOUT, async {
@@ -674,14 +1143,17 @@ pub async fn fetch_raw_message(
         .body()
         .ok_or_else(|| format!("No body for UID {uid}"))?;
 
-    Ok(String::from_utf8_lossy(raw).to_string())
+    Ok(raw.to_vec())
 }
 
 /// Check multiple folders for new UIDs in a single IMAP session.
 ///
-/// For each folder: SELECT, compare UIDVALIDITY, UID SEARCH for new messages.
+/// For each folder: STATUS for UIDVALIDITY and the current counts, then
+/// EXAMINE and UID SEARCH only if UIDNEXT says there is something new.
+/// EXAMINE opens the folder read-only, so checking never clears `\Recent`.
 /// This replaces N separate connections (status + fetch_new_uids per folder)
-/// with a single connection that checks all folders.
+/// with a single connection that checks all folders, and the returned
+/// STATUS lets callers refresh unread counts changed by other clients.
 pub async fn delta_check_folders(
     session: &mut ImapSession,
     folders: &[DeltaCheckRequest],
@@ -689,27 +1161,54 @@ pub async fn delta_check_folders(
     let mut results = Vec::with_capacity(folders.len());
 
     for req in folders {
-        let mailbox = match tokio::time::timeout(IMAP_CMD_TIMEOUT, session.select(&req.folder)).await {
-            Ok(Ok(m)) => m,
-            Ok(Err(e)) => {
-                log::warn!("delta_check: SELECT {} failed: {e}", req.folder);
-                continue;
+        // STATUS before EXAMINE: RFC 3501 advises against STATUS on the
+        // selected mailbox
+        let folder_status = match get_folder_status(session, &req.folder).await {
+            Ok(status) => Some(status),
+            Err(e) => {
+                log::warn!("delta_check: {e} for {}", req.folder);
+                None
             }
-            Err(_) => {
-                log::warn!("delta_check: SELECT {} timed out after {}s", req.folder, IMAP_CMD_TIMEOUT.as_secs());
+        };
+
+        if let Some(status) = &folder_status {
+            if !needs_uid_search(req, status) {
+                results.push(DeltaCheckResult {
+                    folder: req.folder.clone(),
+                    uidvalidity: status.uidvalidity,
+                    new_uids: vec![],
+                    uidvalidity_changed: uidvalidity_changed(req, status.uidvalidity),
+                    folder_status,
+                });
                 continue;
             }
-        };
+        }
 
-        let current_uidvalidity = mailbox.uid_validity.unwrap_or(0);
-        let uidvalidity_changed = req.uidvalidity != 0 && current_uidvalidity != req.uidvalidity;
+        let mailbox =
+            match tokio::time::timeout(IMAP_CMD_TIMEOUT, session.examine(&req.folder)).await {
+                Ok(Ok(m)) => m,
+                Ok(Err(e)) => {
+                    log::warn!("delta_check: EXAMINE {} failed: {e}", req.folder);
+                    continue;
+                }
+                Err(_) => {
+                    log::warn!(
+                        "delta_check: EXAMINE {} timed out after {}s",
+                        req.folder,
+                        IMAP_CMD_TIMEOUT.as_secs()
+                    );
+                    continue;
+                }
+            };
 
-        if uidvalidity_changed {
+        let current_uidvalidity = mailbox.uid_validity.unwrap_or(0);
+        if uidvalidity_changed(req, current_uidvalidity) {
             results.push(DeltaCheckResult {
                 folder: req.folder.clone(),
                 uidvalidity: current_uidvalidity,
                 new_uids: vec![],
                 uidvalidity_changed: true,
+                folder_status,
             });
             continue;
         }
@@ -737,12 +1236,24 @@ pub async fn delta_check_folders(
             uidvalidity: current_uidvalidity,
             new_uids,
             uidvalidity_changed: false,
+            folder_status,
         });
     }
 
     Ok(results)
 }
 
+fn uidvalidity_changed(req: &DeltaCheckRequest, current: u32) -> bool {
+    req.uidvalidity != 0 && current != req.uidvalidity
+}
+
+/// Whether a folder has to be selected and searched for new UIDs, judging by
+/// its STATUS. A server that omits UIDNEXT is always searched.
+fn needs_uid_search(req: &DeltaCheckRequest, status: &ImapFolderStatus) -> bool {
+    !uidvalidity_changed(req, status.uidvalidity)
+        && (status.uidnext == 0 || status.uidnext > req.last_uid + 1)
+}
+
 /// Sync a folder in a single IMAP session: SELECT → UID SEARCH ALL → batched UID FETCH.
 ///
 /// This avoids creating multiple TCP connections per folder (one for search,
@@ -754,10 +1265,7 @@ pub async fn sync_folder(
     batch_size: u32,
 ) -> Result<ImapFolderSyncResult, String> {
     // SELECT the folder
-    let mailbox = tokio::time::timeout(IMAP_CMD_TIMEOUT, session.select(folder))
-        .await
-        .map_err(|_| format!("SELECT {folder} timed out after {}s — check your server settings or network connection", IMAP_CMD_TIMEOUT.as_secs()))?
-        .map_err(|e| format!("SELECT {folder} failed: {e}"))?;
+    let mailbox = select_folder(session, folder).await?;
 
     let folder_status = ImapFolderStatus {
         uidvalidity: mailbox.uid_validity.unwrap_or(0),
@@ -796,13 +1304,7 @@ pub async fn sync_folder(
     let mut all_messages = Vec::new();
     let bs = batch_size as usize;
 
-    for chunk in uids.chunks(bs) {
-        let uid_set: String = chunk
-            .iter()
-            .map(|u| u.to_string())
-            .collect::<Vec<_>>()
-            .join(",");
-
+    for uid_set in uids.chunks(bs).flat_map(build_uid_sets) {
         let fetches = tokio::time::timeout(IMAP_FETCH_TIMEOUT, async {
             let stream = session
                 .uid_fetch(&uid_set, "UID FLAGS INTERNALDATE BODY.PEEK[]")
@@ -832,10 +1334,19 @@ pub async fn sync_folder(
                     let is_draft = flags.iter().any(|fl| matches!(fl, Flag::Draft));
                     let internal_date = f.internal_date().map(|dt| dt.timestamp());
 
-                    match parse_message(&parser, raw, uid, folder, raw_size, is_read, is_starred, is_draft, internal_date) {
-                        Ok(msg) => all_messages.push(msg),
-                        Err(e) => log::warn!("sync_folder: failed to parse UID {uid}: {e}"),
-                    }
+                    let mut message = parse_message(
+                        &parser,
+                        raw,
+                        uid,
+                        folder,
+                        raw_size,
+                        is_read,
+                        is_starred,
+                        is_draft,
+                        internal_date,
+                    );
+                    message.keywords = keywords::from_flags(&flags);
+                    all_messages.push(message);
                 }
                 Err(e) => log::warn!("IMAP sync_folder fetch stream error in {folder}: {e}"),
             }
@@ -887,31 +1398,7 @@ pub async fn raw_fetch_messages(
 ) -> Result<ImapFetchResult, String> {
     log::info!("RAW IMAP FETCH: connecting to {}:{} for folder {folder}, UIDs {uid_range}", config.host, config.port);
 
-    // Connect
-    let stream = if config.security == "starttls" {
-        raw_connect_starttls(config).await?
-    } else {
-        connect_stream(config).await?
-    };
-
-    let mut reader = BufReader::new(stream);
-
-    // Read greeting (for non-STARTTLS)
-    if config.security != "starttls" {
-        let mut line = String::new();
-        reader.read_line(&mut line).await.map_err(|e| format!("greeting: {e}"))?;
-    }
-
-    // LOGIN
-    let login_cmd = if config.auth_method == "oauth2" {
-        // XOAUTH2: AUTHENTICATE XOAUTH2 <base64>
-        let xoauth2 = format!("user={}\x01auth=Bearer {}\x01\x01", config.username, config.password);
-        let b64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, xoauth2.as_bytes());
-        format!("a1 AUTHENTICATE XOAUTH2 {b64}\r\n")
-    } else {
-        format!("a1 LOGIN \"{}\" \"{}\"\r\n", config.username, config.password)
-    };
-    raw_send_and_wait(&mut reader, login_cmd.as_bytes(), "a1").await?;
+    let mut reader = raw_connect_and_login(config).await?;
 
     // SELECT
     let select_cmd = format!("a2 SELECT \"{folder}\"\r\n");
@@ -960,7 +1447,7 @@ pub async fn raw_fetch_messages(
     let mut messages = Vec::new();
 
     for raw_msg in &raw_messages {
-        match parse_message(
+        let mut message = parse_message(
             &parser,
             &raw_msg.body,
             raw_msg.uid,
@@ -970,10 +1457,9 @@ pub async fn raw_fetch_messages(
             raw_msg.is_starred,
             raw_msg.is_draft,
             raw_msg.internal_date,
-        ) {
-            Ok(msg) => messages.push(msg),
-            Err(e) => log::warn!("RAW FETCH: failed to parse UID {}: {e}", raw_msg.uid),
-        }
+        );
+        message.keywords = raw_msg.keywords.clone();
+        messages.push(message);
     }
 
     // LOGOUT
@@ -1055,6 +1541,7 @@ struct RawFetchedMessage {
     is_read: bool,
     is_starred: bool,
     is_draft: bool,
+    keywords: Vec<String>,
     internal_date: Option<i64>,
     body: Vec<u8>,
 }
@@ -1096,8 +1583,66 @@ async fn raw_connect_starttls(config: &ImapConfig) -> Result<ImapStream, String>
     Ok(ImapStream::Tls(tls))
 }
 
+/// Connect via raw TCP/TLS, consume the greeting and authenticate (tag `a1`).
+pub(super) async fn raw_connect_and_login(
+    config: &ImapConfig,
+) -> Result<BufReader<ImapStream>, String> {
+    rate_limit::acquire_imap(config).await;
+    let stream = metrics::time(&config.host, "imap_raw", Phase::Connect, async {
+        if config.security == "starttls" {
+            raw_connect_starttls(config).await
+        } else {
+            connect_stream(config).await
+        }
+    })
+    .await?;
+
+    let mut reader = BufReader::new(stream);
+
+    // Read greeting (for non-STARTTLS)
+    if config.security != "starttls" {
+        let mut line = String::new();
+        reader
+            .read_line(&mut line)
+            .await
+            .map_err(|e| format!("greeting: {e}"))?;
+    }
+
+    // LOGIN
+    let b64 = |s: String| {
+        base64::Engine::encode(&base64::engine::general_purpose::STANDARD, s.as_bytes())
+    };
+    let login_cmd = match login_mode(config) {
+        // XOAUTH2: AUTHENTICATE XOAUTH2 <base64>
+        Login::OAuth2(user) => {
+            let xoauth2 = format!("user={user}\x01auth=Bearer {}\x01\x01", config.password);
+            format!("a1 AUTHENTICATE XOAUTH2 {}\r\n", b64(xoauth2))
+        }
+        Login::SaslPlain { authzid } => {
+            let plain = format!("{authzid}\x00{}\x00{}", config.username, config.password);
+            format!("a1 AUTHENTICATE PLAIN {}\r\n", b64(plain))
+        }
+        Login::Password(user) => {
+            format!(
+                "a1 LOGIN {} {}\r\n",
+                imap_quote(&user),
+                imap_quote(&config.password)
+            )
+        }
+    };
+    metrics::time(
+        &config.host,
+        "imap_raw",
+        Phase::Auth,
+        raw_send_and_wait(&mut reader, login_cmd.as_bytes(), "a1"),
+    )
+    .await?;
+
+    Ok(reader)
+}
+
 /// Send a command and read all response lines until the tagged response (e.g. "a1 OK ...").
-async fn raw_send_and_wait(
+pub(super) async fn raw_send_and_wait(
     reader: &mut tokio::io::BufReader<ImapStream>,
     cmd: &[u8],
     tag: &str,
@@ -1132,8 +1677,74 @@ async fn raw_send_and_wait(
     }
 }
 
+/// Send the part of a command that ends in a literal announcement
+/// (`... {123}\r\n`) and wait for the server's continuation request.
+pub(super) async fn raw_send_for_continuation(
+    reader: &mut tokio::io::BufReader<ImapStream>,
+    cmd: &[u8],
+    tag: &str,
+) -> Result<(), String> {
+    reader
+        .get_mut()
+        .write_all(cmd)
+        .await
+        .map_err(|e| format!("{tag} write: {e}"))?;
+
+    loop {
+        let mut line = String::new();
+        match tokio::time::timeout(
+            std::time::Duration::from_secs(30),
+            reader.read_line(&mut line),
+        )
+        .await
+        {
+            Ok(Ok(0)) => return Err(format!("{tag}: connection closed")),
+            Ok(Ok(_)) => {
+                if line.starts_with('+') {
+                    return Ok(());
+                }
+                if line.starts_with(&format!("{tag} ")) {
+                    return Err(format!("{tag} failed: {line}"));
+                }
+            }
+            Ok(Err(e)) => return Err(format!("{tag} read: {e}")),
+            Err(_) => return Err(format!("{tag}: timeout")),
+        }
+    }
+}
+
+/// UIDVALIDITY of a folder, via `STATUS` (no need to select it).
+pub(super) async fn raw_uid_validity(
+    reader: &mut tokio::io::BufReader<ImapStream>,
+    tag: &str,
+    folder: &str,
+) -> Result<u32, String> {
+    let cmd = format!("{tag} STATUS {} (UIDVALIDITY)\r\n", imap_quote(folder));
+    let response = raw_send_and_wait(reader, cmd.as_bytes(), tag).await?;
+    response
+        .lines()
+        .filter(|l| l.starts_with("* STATUS"))
+        .find_map(|l| {
+            let rest = &l[l.find("UIDVALIDITY ")? + "UIDVALIDITY ".len()..];
+            rest.split(|c: char| !c.is_ascii_digit())
+                .next()?
+                .parse()
+                .ok()
+        })
+        .ok_or_else(|| format!("No UIDVALIDITY in STATUS response for {folder}"))
+}
+
+/// Whether a `CAPABILITY` response lists `capability`.
+pub(super) fn capabilities_include(response: &str, capability: &str) -> bool {
+    response
+        .lines()
+        .filter(|l| l.starts_with("* CAPABILITY"))
+        .flat_map(|l| l.split_whitespace())
+        .any(|c| c.eq_ignore_ascii_case(capability))
+}
+
 /// Parse untagged responses like "* 3 EXISTS" → 3
-fn parse_untagged_number(line: &str, keyword: &str) -> Option<u32> {
+pub(super) fn parse_untagged_number(line: &str, keyword: &str) -> Option<u32> {
     // Format: "* <number> <KEYWORD>"
     let trimmed = line.trim();
     if !trimmed.starts_with("* ") || !trimmed.ends_with(keyword) {
@@ -1165,7 +1776,7 @@ fn extract_bracket_number(line: &str, keyword: &str) -> Option<u32> {
 /// a3 OK UID FETCH done
 /// ```
 async fn raw_parse_fetch_responses(
-    reader: &mut tokio::io::BufReader<ImapStream>,
+    reader: &mut (impl AsyncBufRead + Unpin),
     tag: &str,
 ) -> Result<Vec<RawFetchedMessage>, String> {
     let mut messages: Vec<RawFetchedMessage> = Vec::new();
@@ -1212,6 +1823,7 @@ async fn raw_parse_fetch_responses(
                 let is_read = flags_str.contains("\\Seen");
                 let is_starred = flags_str.contains("\\Flagged");
                 let is_draft = flags_str.contains("\\Draft");
+                let keywords = keywords::from_flag_list(&flags_str);
 
                 // Parse INTERNALDATE
                 let internal_date = extract_internal_date(&line);
@@ -1232,6 +1844,7 @@ async fn raw_parse_fetch_responses(
                         is_read,
                         is_starred,
                         is_draft,
+                        keywords,
                         internal_date,
                         body,
                     });
@@ -1272,65 +1885,7 @@ fn extract_internal_date(line: &str) -> Option<i64> {
     let idx = line.find("INTERNALDATE \"")?;
     let after = &line[idx + 14..];
     let end = after.find('"')?;
-    let date_str = &after[..end];
-    // Parse "DD-Mon-YYYY HH:MM:SS +ZZZZ" manually
-    parse_imap_date(date_str)
-}
-
-/// Parse IMAP date format "16-Feb-2026 12:00:00 +0000" to Unix timestamp.
-fn parse_imap_date(s: &str) -> Option<i64> {
-    let parts: Vec<&str> = s.split_whitespace().collect();
-    if parts.len() < 2 { return None; }
-
-    // "16-Feb-2026"
-    let date_parts: Vec<&str> = parts[0].split('-').collect();
-    if date_parts.len() != 3 { return None; }
-
-    let day: u32 = date_parts[0].parse().ok()?;
-    let month = match date_parts[1].to_lowercase().as_str() {
-        "jan" => 1u32, "feb" => 2, "mar" => 3, "apr" => 4,
-        "may" => 5, "jun" => 6, "jul" => 7, "aug" => 8,
-        "sep" => 9, "oct" => 10, "nov" => 11, "dec" => 12,
-        _ => return None,
-    };
-    let year: i64 = date_parts[2].parse().ok()?;
-
-    // "12:00:00"
-    let time_parts: Vec<&str> = parts.get(1)?.split(':').collect();
-    if time_parts.len() != 3 { return None; }
-    let hour: i64 = time_parts[0].parse().ok()?;
-    let minute: i64 = time_parts[1].parse().ok()?;
-    let second: i64 = time_parts[2].parse().ok()?;
-
-    // Timezone offset "+0000" (optional)
-    let tz_offset_secs: i64 = if let Some(tz) = parts.get(2) {
-        let sign = if tz.starts_with('-') { -1i64 } else { 1i64 };
-        let tz_num = tz.trim_start_matches(['+', '-']);
-        if tz_num.len() == 4 {
-            let tz_h: i64 = tz_num[..2].parse().unwrap_or(0);
-            let tz_m: i64 = tz_num[2..].parse().unwrap_or(0);
-            sign * (tz_h * 3600 + tz_m * 60)
-        } else { 0 }
-    } else { 0 };
-
-    // Convert to Unix timestamp (days since epoch)
-    // Simplified: use a basic calendar calculation
-    let mut days: i64 = 0;
-    for y in 1970..year {
-        days += if is_leap_year(y) { 366 } else { 365 };
-    }
-    let month_days = [0, 31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
-    for m in 1..month {
-        days += month_days[m as usize] as i64;
-        if m == 2 && is_leap_year(year) { days += 1; }
-    }
-    days += day as i64 - 1;
-
-    Some(days * 86400 + hour * 3600 + minute * 60 + second - tz_offset_secs)
-}
-
-fn is_leap_year(y: i64) -> bool {
-    (y % 4 == 0 && y % 100 != 0) || (y % 400 == 0)
+    date::parse_date(&after[..end])
 }
 
 /// Extract literal size from a line ending with {1234}\r\n
@@ -1393,6 +1948,7 @@ async fn connect_stream(config: &ImapConfig) -> Result<ImapStream, String> {
 /// connection, upgrade the underlying TCP stream to TLS, and then create a new
 /// Client on the TLS stream for authentication.
 async fn connect_starttls(config: &ImapConfig) -> Result<ImapSession, String> {
+    let connect_start = Instant::now();
     let addr = (&*config.host, config.port);
     let mut tcp = tokio::time::timeout(TCP_CONNECT_TIMEOUT, TcpStream::connect(addr))
         .await
@@ -1446,59 +2002,74 @@ async fn connect_starttls(config: &ImapConfig) -> Result<ImapSession, String> {
         ))?
         .map_err(|e| format!("TLS upgrade after STARTTLS failed: {e}"))?;
 
+    metrics::record(
+        &config.host,
+        "imap",
+        Phase::Connect,
+        connect_start.elapsed(),
+        true,
+    );
+
     // Create a new IMAP client on the TLS stream and authenticate
     let client = Client::new(ImapStream::Tls(tls));
-    tokio::time::timeout(AUTH_TIMEOUT, authenticate(client, config))
-        .await
-        .map_err(|_| format!(
-            "IMAP authentication timed out after {}s — check your server settings or network connection",
-            AUTH_TIMEOUT.as_secs()
-        ))?
+    metrics::time(&config.host, "imap", Phase::Auth, async {
+        tokio::time::timeout(AUTH_TIMEOUT, authenticate(client, config))
+            .await
+            .map_err(|_| format!(
+                "IMAP authentication timed out after {}s — check your server settings or network connection",
+                AUTH_TIMEOUT.as_secs()
+            ))?
+    })
+    .await
 }
 
-/// Authenticate with the IMAP server (LOGIN or XOAUTH2).
+/// Authenticate with the IMAP server (LOGIN, PLAIN for delegated access, or XOAUTH2).
 async fn authenticate(
     client: Client<ImapStream>,
     config: &ImapConfig,
 ) -> Result<ImapSession, String> {
-    match config.auth_method.as_str() {
-        "oauth2" => {
-            let auth = XOAuth2::new(&config.username, &config.password);
+    match login_mode(config) {
+        Login::OAuth2(user) => {
+            let auth = XOAuth2::new(&user, &config.password);
             client
                 .authenticate("XOAUTH2", auth)
                 .await
                 .map_err(|(e, _)| format!("XOAUTH2 authentication failed: {e}"))
         }
-        _ => client
-            .login(&config.username, &config.password)
+        Login::SaslPlain { authzid } => {
+            let auth = PlainAuth::new(&authzid, &config.username, &config.password);
+            client
+                .authenticate("PLAIN", auth)
+                .await
+                .map_err(|(e, _)| format!("Delegated login as {authzid} failed: {e}"))
+        }
+        Login::Password(user) => client
+            .login(&user, &config.password)
             .await
             .map_err(|(e, _)| format!("Login failed: {e}")),
     }
 }
 
 /// Detect special-use attribute from IMAP folder attributes and name heuristics.
-fn detect_special_use(name: &async_imap::types::Name) -> Option<String> {
-    use async_imap::types::NameAttribute;
-
+fn detect_special_use(attributes: &[String], raw_path: &str) -> Option<String> {
     // Check RFC 6154 attributes first
-    for attr in name.attributes() {
-        let special = match attr {
-            NameAttribute::Sent => Some("\\Sent"),
-            NameAttribute::Trash => Some("\\Trash"),
-            NameAttribute::Drafts => Some("\\Drafts"),
-            NameAttribute::Junk => Some("\\Junk"),
-            NameAttribute::Archive => Some("\\Archive"),
-            NameAttribute::All => Some("\\All"),
-            NameAttribute::Flagged => Some("\\Flagged"),
-            _ => None,
-        };
-        if let Some(s) = special {
-            return Some(s.to_string());
+    const SPECIAL_USE: [&str; 7] = [
+        "\\Sent",
+        "\\Trash",
+        "\\Drafts",
+        "\\Junk",
+        "\\Archive",
+        "\\All",
+        "\\Flagged",
+    ];
+    for attr in attributes {
+        if let Some(special) = SPECIAL_USE.iter().find(|s| s.eq_ignore_ascii_case(attr)) {
+            return Some(special.to_string());
         }
     }
 
     // Heuristic fallback based on common folder names
-    let lower = name.name().to_lowercase();
+    let lower = raw_path.to_lowercase();
     match lower.as_str() {
         "inbox" => Some("\\Inbox".to_string()),
         "sent" | "sent messages" | "sent items" | "[gmail]/sent mail" => {
@@ -1515,11 +2086,45 @@ fn detect_special_use(name: &async_imap::types::Name) -> Option<String> {
     }
 }
 
+/// Convert LIST attributes to their wire form (e.g. "\Noselect", "\HasChildren").
+fn name_attributes(name: &async_imap::types::Name) -> Vec<String> {
+    use async_imap::types::NameAttribute;
+
+    name.attributes()
+        .iter()
+        .map(|attr| match attr {
+            NameAttribute::NoInferiors => "\\Noinferiors".to_string(),
+            NameAttribute::NoSelect => "\\Noselect".to_string(),
+            NameAttribute::Marked => "\\Marked".to_string(),
+            NameAttribute::Unmarked => "\\Unmarked".to_string(),
+            NameAttribute::All => "\\All".to_string(),
+            NameAttribute::Archive => "\\Archive".to_string(),
+            NameAttribute::Drafts => "\\Drafts".to_string(),
+            NameAttribute::Flagged => "\\Flagged".to_string(),
+            NameAttribute::Junk => "\\Junk".to_string(),
+            NameAttribute::Sent => "\\Sent".to_string(),
+            NameAttribute::Trash => "\\Trash".to_string(),
+            NameAttribute::Extension(ext) => {
+                if ext.starts_with('\\') {
+                    ext.to_string()
+                } else {
+                    format!("\\{ext}")
+                }
+            }
+            other => format!("{other:?}"),
+        })
+        .collect()
+}
+
 /// Parse a raw email message into our ImapMessage struct.
 ///
 /// `internal_date`: optional INTERNALDATE timestamp from the IMAP server,
 /// used as fallback when the Date header cannot be parsed.
-fn parse_message(
+///
+/// Never drops a message: if mail-parser gives up, the basic headers are
+/// recovered by `parse_message_leniently` and the result is marked
+/// `parse_failed` so the UI can point to the raw view.
+pub(crate) fn parse_message(
     parser: &MessageParser,
     raw: &[u8],
     uid: u32,
@@ -1529,14 +2134,152 @@ fn parse_message(
     is_starred: bool,
     is_draft: bool,
     internal_date: Option<i64>,
-) -> Result<ImapMessage, String> {
-    let message = parser.parse(raw).ok_or("Failed to parse MIME message")?;
+) -> ImapMessage {
+    let start = Instant::now();
+    let mut parsed = match parser.parse(raw) {
+        Some(message) => build_message(&message, "", uid, folder, raw_size, internal_date),
+        None => {
+            log::warn!("Failed to parse MIME message UID {uid} in {folder}, using header fallback");
+            parse_message_leniently(raw, uid, folder, raw_size, internal_date)
+        }
+    };
+    parsed.is_read = is_read;
+    parsed.is_starred = is_starred;
+    parsed.is_draft = is_draft;
+    metrics::record(
+        "",
+        "parse_message",
+        Phase::Parse,
+        start.elapsed(),
+        !parsed.parse_failed,
+    );
+    parsed
+}
+
+/// Recover the basic headers of a message mail-parser couldn't handle.
+///
+/// Reads unfolded `Name: value` lines up to the first blank line. Values are
+/// kept as-is (no RFC 2047 decoding) and the body is left empty; the user
+/// can read the message in the raw view.
+fn parse_message_leniently(
+    raw: &[u8],
+    uid: u32,
+    folder: &str,
+    raw_size: u32,
+    internal_date: Option<i64>,
+) -> ImapMessage {
+    let text = String::from_utf8_lossy(raw);
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            break;
+        }
+        if line.starts_with([' ', '\t']) {
+            // Folded continuation of the previous header
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+    }
+
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(n, v)| n == name && !v.is_empty())
+            .map(|(_, v)| v.clone())
+    };
+    // Message ids without angle brackets, as mail-parser returns them
+    let ids = |name: &str| {
+        header(name).map(|v| {
+            v.split_whitespace()
+                .map(|id| id.trim_matches(|c| c == '<' || c == '>'))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+    };
+
+    let (from_address, from_name) = match header("from") {
+        Some(from) => match (from.rfind('<'), from.rfind('>')) {
+            (Some(start), Some(end)) if start < end => {
+                let name = from[..start].trim().trim_matches('"').trim();
+                (
+                    Some(from[start + 1..end].trim().to_string()),
+                    (!name.is_empty()).then(|| name.to_string()),
+                )
+            }
+            _ => (Some(from), None),
+        },
+        None => (None, None),
+    };
+
+    let date = header("date")
+        .and_then(|d| date::parse_date(&d))
+        .or(internal_date)
+        .unwrap_or(0);
+    let list = list_headers(headers.iter().map(|(n, v)| (n.as_str(), v.as_str())));
+
+    ImapMessage {
+        uid,
+        folder: folder.to_string(),
+        message_id: ids("message-id"),
+        in_reply_to: ids("in-reply-to"),
+        references: ids("references"),
+        from_address,
+        from_name,
+        to_addresses: header("to"),
+        cc_addresses: header("cc"),
+        bcc_addresses: header("bcc"),
+        reply_to: header("reply-to"),
+        mail_followup_to: header("mail-followup-to"),
+        subject: header("subject"),
+        date,
+        is_read: false,
+        is_starred: false,
+        is_draft: false,
+        body_html: None,
+        body_text: None,
+        snippet: None,
+        raw_size,
+        list_unsubscribe: header("list-unsubscribe"),
+        list_unsubscribe_post: header("list-unsubscribe-post"),
+        auth_results: header("authentication-results"),
+        attachments: Vec::new(),
+        attached_messages: Vec::new(),
+        parse_failed: true,
+        delivered_to: envelope_recipient(headers.iter().map(|(n, v)| (n.as_str(), v.as_str()))),
+        list_id: list.id,
+        list_post: list.post,
+        list_archive: list.archive,
+        is_from_me: false,
+        keywords: Vec::new(),
+    }
+}
 
+/// Build an ImapMessage from a parsed MIME message.
+///
+/// `section_prefix` is the IMAP section of the message/rfc822 part when
+/// building a message attached to another one (empty for the top level), so
+/// attachment part ids stay fetchable from the outer message. Flags are left
+/// unset for the caller to fill in.
+fn build_message(
+    message: &mail_parser::Message,
+    section_prefix: &str,
+    uid: u32,
+    folder: &str,
+    raw_size: u32,
+    internal_date: Option<i64>,
+) -> ImapMessage {
     let message_id = message.message_id().map(|s| s.to_string());
     let subject = message.subject().map(|s| s.to_string());
+    // mail-parser rejects some obsolete forms (asctime layout, stray
+    // tokens), so retry the raw header with the lenient parser
     let date = message
         .date()
         .map(|d| d.to_timestamp())
+        .or_else(|| message.header_raw("Date").and_then(date::parse_date))
         .or(internal_date)
         .unwrap_or(0);
 
@@ -1566,6 +2309,14 @@ fn parse_message(
     let cc_addresses = format_address_list(message.cc());
     let bcc_addresses = format_address_list(message.bcc());
     let reply_to = format_address_list(message.reply_to());
+    let mail_followup_to = message
+        .header_as(
+            mail_parser::HeaderName::Other("Mail-Followup-To".into()),
+            mail_parser::HeaderForm::Addresses,
+        )
+        .into_iter()
+        .next()
+        .and_then(|v| format_address_list(v.as_address()));
 
     // Body
     let body_text = message.body_text(0).map(|s| s.to_string());
@@ -1597,10 +2348,20 @@ fn parse_message(
         message.header(mail_parser::HeaderName::Other("Authentication-Results".into())),
     );
 
+    let raw_headers = message.headers().iter().map(|h| {
+        let value = message
+            .raw_message
+            .get(h.offset_start..h.offset_end)
+            .unwrap_or_default();
+        (h.name(), std::str::from_utf8(value).unwrap_or(""))
+    });
+    let list = list_headers(raw_headers.clone());
+    let delivered_to = envelope_recipient(raw_headers);
+
     // Build a map from mail-parser part index → IMAP MIME section path.
     // IMAP numbers children of multipart containers starting at 1 (e.g. "1", "2", "1.2.3").
     // mail-parser stores all parts flat in a Vec, with Multipart variants holding child indices.
-    let section_map = build_imap_section_map(&message);
+    let section_map = build_imap_section_map(message, section_prefix);
 
     log::debug!(
         "IMAP parse UID {uid}: {} parts, {} attachment indices {:?}, section_map: {:?}",
@@ -1610,6 +2371,29 @@ fn parse_message(
         section_map,
     );
 
+    // Emails attached as message/rfc822, parsed recursively
+    let attached_messages: Vec<ImapAttachedMessage> = message
+        .attachments
+        .iter()
+        .filter_map(|&part_idx| {
+            let mail_parser::PartType::Message(nested) = &message.parts.get(part_idx)?.body else {
+                return None;
+            };
+            let section = section_map.get(&part_idx)?;
+            Some(ImapAttachedMessage {
+                part_id: section.clone(),
+                message: build_message(
+                    nested,
+                    section,
+                    uid,
+                    folder,
+                    nested.raw_message.len() as u32,
+                    None,
+                ),
+            })
+        })
+        .collect();
+
     // Attachments
     let attachments: Vec<ImapAttachment> = message
         .attachments
@@ -1636,21 +2420,28 @@ fn parse_message(
                 })
                 .unwrap_or_else(|| "application/octet-stream".to_string());
 
-            Some(ImapAttachment {
+            let filename = att.attachment_name().unwrap_or("attachment").to_string();
+
+            // Unpack winmail.dat into the files it carries
+            if tnef::is_tnef(&mime_type, &filename) {
+                if let Some(expanded) = expand_tnef(uid, &section, att.contents()) {
+                    return Some(expanded);
+                }
+            }
+
+            Some(vec![ImapAttachment {
                 part_id: section,
-                filename: att
-                    .attachment_name()
-                    .unwrap_or("attachment")
-                    .to_string(),
+                filename,
                 mime_type,
                 size: att.len() as u32,
                 content_id: att.content_id().map(|s| s.to_string()),
                 is_inline: att.content_disposition().map_or(false, |cd| cd.is_inline()),
-            })
+            }])
         })
+        .flatten()
         .collect();
 
-    Ok(ImapMessage {
+    ImapMessage {
         uid,
         folder: folder.to_string(),
         message_id,
@@ -1662,11 +2453,12 @@ fn parse_message(
         cc_addresses,
         bcc_addresses,
         reply_to,
+        mail_followup_to,
         subject,
         date,
-        is_read,
-        is_starred,
-        is_draft,
+        is_read: false,
+        is_starred: false,
+        is_draft: false,
         body_html,
         body_text,
         snippet,
@@ -1675,56 +2467,231 @@ fn parse_message(
         list_unsubscribe_post,
         auth_results,
         attachments,
+        attached_messages,
+        parse_failed: false,
+        delivered_to,
+        list_id: list.id,
+        list_post: list.post,
+        list_archive: list.archive,
+        is_from_me: false,
+        keywords: Vec::new(),
+    }
+}
+
+/// Envelope-recipient headers, most reliable first. X-Original-To keeps the
+/// address before alias expansion; Delivered-To is added at final delivery.
+const ENVELOPE_RECIPIENT_HEADERS: [&str; 4] = [
+    "X-Original-To",
+    "Delivered-To",
+    "Envelope-To",
+    "X-Envelope-To",
+];
+
+/// Address the message was delivered to, from `(name, raw value)` header
+/// pairs in message order. The topmost occurrence wins, since headers added
+/// by the receiving server come first.
+fn envelope_recipient<'a>(
+    headers: impl Iterator<Item = (&'a str, &'a str)> + Clone,
+) -> Option<String> {
+    ENVELOPE_RECIPIENT_HEADERS.iter().find_map(|wanted| {
+        let (_, value) = headers
+            .clone()
+            .find(|(name, _)| name.eq_ignore_ascii_case(wanted))?;
+        let first = value.split(',').next()?.trim();
+        let address = first.trim_start_matches('<').trim_end_matches('>').trim();
+        address.contains('@').then(|| address.to_string())
     })
 }
 
+/// Mailing list headers: List-Id (RFC 2919), List-Post and List-Archive
+/// (RFC 2369).
+#[derive(Debug, Default, PartialEq)]
+struct ListHeaders {
+    /// List identifier without angle brackets, lowercased: "dev.lists.example.org"
+    id: Option<String>,
+    /// Posting address from the first mailto: entry; `None` for "NO"
+    post: Option<String>,
+    /// Archive URL, preferring http(s) over other schemes
+    archive: Option<String>,
+}
+
+/// Parse list headers from `(name, raw value)` pairs. The first occurrence
+/// of each header is used.
+fn list_headers<'a>(headers: impl Iterator<Item = (&'a str, &'a str)>) -> ListHeaders {
+    let mut list = ListHeaders::default();
+    let (mut seen_id, mut seen_post, mut seen_archive) = (false, false, false);
+    for (name, value) in headers {
+        if name.eq_ignore_ascii_case("List-Id") && !seen_id {
+            seen_id = true;
+            let id = angle_entries(value)
+                .into_iter()
+                .next()
+                .unwrap_or_else(|| value.trim().to_string());
+            list.id = (!id.is_empty()).then(|| id.to_ascii_lowercase());
+        } else if name.eq_ignore_ascii_case("List-Post") && !seen_post {
+            seen_post = true;
+            list.post = angle_entries(value).into_iter().find_map(|entry| {
+                let address = entry
+                    .get(..7)
+                    .filter(|scheme| scheme.eq_ignore_ascii_case("mailto:"))
+                    .map(|_| &entry[7..])?;
+                let address = address.split('?').next().unwrap_or_default();
+                address.contains('@').then(|| address.to_string())
+            });
+        } else if name.eq_ignore_ascii_case("List-Archive") && !seen_archive {
+            seen_archive = true;
+            let entries = angle_entries(value);
+            list.archive = entries
+                .iter()
+                .find(|e| {
+                    let lower = e.to_ascii_lowercase();
+                    lower.starts_with("https://") || lower.starts_with("http://")
+                })
+                .or(entries.first())
+                .cloned();
+        }
+    }
+    list
+}
+
+/// Contents of each `<...>` in a header value, with folding whitespace
+/// removed.
+fn angle_entries(value: &str) -> Vec<String> {
+    value
+        .split('<')
+        .skip(1)
+        .filter_map(|part| part.split_once('>'))
+        .map(|(inner, _)| inner.split_whitespace().collect::<String>())
+        .filter(|inner| !inner.is_empty())
+        .collect()
+}
+
+/// List the files inside a TNEF part as attachments with synthetic part ids.
+/// Returns `None` if the part can't be decoded or holds nothing useful, so
+/// the caller keeps the original winmail.dat.
+fn expand_tnef(uid: u32, section: &str, data: &[u8]) -> Option<Vec<ImapAttachment>> {
+    let contents = match tnef::decode(data) {
+        Ok(contents) => contents,
+        Err(e) => {
+            log::warn!("IMAP UID {uid}: failed to decode TNEF part {section}: {e}");
+            return None;
+        }
+    };
+
+    let mut attachments: Vec<ImapAttachment> = contents
+        .attachments
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let filename = item
+                .filename
+                .clone()
+                .unwrap_or_else(|| format!("attachment-{}", index + 1));
+            let mime_type = item
+                .mime_type
+                .clone()
+                .unwrap_or_else(|| mime_from_extension(Path::new(&filename)).to_string());
+            ImapAttachment {
+                part_id: tnef::part_id(section, tnef::TnefItem::Attachment(index)),
+                filename,
+                mime_type,
+                size: item.data.len() as u32,
+                content_id: item.content_id.clone(),
+                is_inline: false,
+            }
+        })
+        .collect();
+
+    if let Some(rtf) = &contents.rtf_body {
+        attachments.push(ImapAttachment {
+            part_id: tnef::part_id(section, tnef::TnefItem::RtfBody),
+            filename: tnef::RTF_BODY_FILENAME.to_string(),
+            mime_type: "application/rtf".to_string(),
+            size: rtf.len() as u32,
+            content_id: None,
+            is_inline: false,
+        });
+    }
+
+    (!attachments.is_empty()).then_some(attachments)
+}
+
 /// Build a mapping from mail-parser part index → IMAP MIME section path string.
 ///
 /// IMAP section numbering: children of a multipart container are numbered 1, 2, 3, ...
 /// Nested multipart children get dot-separated paths (e.g., "1.2" for the 2nd child of the 1st child).
 /// For non-multipart messages, the single body is section "1".
-fn build_imap_section_map(message: &mail_parser::Message) -> std::collections::HashMap<usize, String> {
+///
+/// `prefix` is the section of the message/rfc822 part for a message attached
+/// to another one (its parts are numbered "2.1", "2.2", ...), or empty for
+/// the top-level message.
+fn build_imap_section_map(
+    message: &mail_parser::Message,
+    prefix: &str,
+) -> std::collections::HashMap<usize, String> {
     use mail_parser::PartType;
 
     let mut map = std::collections::HashMap::new();
 
+    fn child_section(prefix: &str, n: usize) -> String {
+        if prefix.is_empty() {
+            n.to_string()
+        } else {
+            format!("{prefix}.{n}")
+        }
+    }
+
     fn walk(
         parts: &[mail_parser::MessagePart],
         part_idx: usize,
-        prefix: &str,
+        section: &str,
         map: &mut std::collections::HashMap<usize, String>,
     ) {
         if let Some(part) = parts.get(part_idx) {
             if let PartType::Multipart(children) = &part.body {
                 for (i, &child_idx) in children.iter().enumerate() {
-                    let section = if prefix.is_empty() {
-                        format!("{}", i + 1)
-                    } else {
-                        format!("{}.{}", prefix, i + 1)
-                    };
-                    walk(parts, child_idx, &section, map);
+                    walk(parts, child_idx, &child_section(section, i + 1), map);
                 }
             } else {
                 // Leaf part — use the section path as-is
-                let section = if prefix.is_empty() {
-                    // Non-multipart message: the body is section "1"
-                    "1".to_string()
-                } else {
-                    prefix.to_string()
-                };
-                map.insert(part_idx, section);
+                map.insert(part_idx, section.to_string());
             }
         }
     }
 
-    // Start from part 0 (root) with empty prefix
-    if !message.parts.is_empty() {
-        walk(&message.parts, 0, "", &mut map);
+    // Start from part 0 (root). A non-multipart body is section "1" of its message.
+    match message.parts.first().map(|root| &root.body) {
+        Some(PartType::Multipart(_)) => walk(&message.parts, 0, prefix, &mut map),
+        Some(_) => {
+            map.insert(0, child_section(prefix, 1));
+        }
+        None => {}
     }
 
     map
 }
 
+/// Find the part at an IMAP section path, descending into attached
+/// (message/rfc822) messages.
+fn find_section_part<'a, 'x>(
+    message: &'a mail_parser::Message<'x>,
+    prefix: &str,
+    section: &str,
+) -> Option<&'a mail_parser::MessagePart<'x>> {
+    for (idx, part_section) in build_imap_section_map(message, prefix) {
+        let part = message.parts.get(idx)?;
+        if part_section == section {
+            return Some(part);
+        }
+        if let mail_parser::PartType::Message(nested) = &part.body {
+            if section.starts_with(&format!("{part_section}.")) {
+                return find_section_part(nested, &part_section, section);
+            }
+        }
+    }
+    None
+}
+
 /// Extract a text value from a HeaderValue, if present.
 fn extract_header_text(hv: Option<&mail_parser::HeaderValue>) -> Option<String> {
     match hv {
@@ -1754,6 +2721,98 @@ fn extract_first_address(
     }
 }
 
+/// Decode RFC 2047 encoded words in an envelope string (subject, names).
+pub(super) fn decode_envelope_text(raw: &[u8]) -> String {
+    let text = String::from_utf8_lossy(raw);
+    if !text.contains("=?") {
+        return text.trim().to_string();
+    }
+    let header = format!("Subject: {text}\r\n\r\n");
+    MessageParser::default()
+        .parse(header.as_bytes())
+        .and_then(|m| m.subject().map(str::to_string))
+        .unwrap_or_else(|| text.trim().to_string())
+}
+
+/// The address and name of an envelope address. Group markers (RFC 3501
+/// §7.4.2: no host) aren't addresses.
+fn envelope_address(address: &Address) -> Option<(String, Option<String>)> {
+    let mailbox = String::from_utf8_lossy(address.mailbox.as_deref()?);
+    let host = String::from_utf8_lossy(address.host.as_deref()?);
+    let name = address
+        .name
+        .as_deref()
+        .map(decode_envelope_text)
+        .filter(|n| !n.is_empty());
+    Some((format!("{mailbox}@{host}"), name))
+}
+
+/// Format envelope addresses like [`format_address_list`].
+fn format_envelope_addresses(addresses: Option<&Vec<Address>>) -> Option<String> {
+    let parts: Vec<String> = addresses?
+        .iter()
+        .filter_map(envelope_address)
+        .map(|(email, name)| match name {
+            Some(name) => format!("{name} <{email}>"),
+            None => email,
+        })
+        .collect();
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join(", "))
+    }
+}
+
+fn header_from_envelope(
+    envelope: Option<&Envelope>,
+    uid: u32,
+    folder: &str,
+    internal_date: Option<i64>,
+) -> ImapMessageHeader {
+    // Message ids without angle brackets, as mail-parser returns them
+    let id = |raw: &Option<std::borrow::Cow<[u8]>>| {
+        raw.as_deref()
+            .map(|id| {
+                String::from_utf8_lossy(id)
+                    .trim()
+                    .trim_matches(|c| c == '<' || c == '>')
+                    .to_string()
+            })
+            .filter(|id| !id.is_empty())
+    };
+    let (from_address, from_name) = envelope
+        .and_then(|e| e.from.as_ref()?.iter().find_map(envelope_address))
+        .map_or((None, None), |(email, name)| (Some(email), name));
+    let date = envelope
+        .and_then(|e| e.date.as_deref())
+        .and_then(|d| date::parse_date(&String::from_utf8_lossy(d)))
+        .or(internal_date)
+        .unwrap_or(0);
+
+    ImapMessageHeader {
+        uid,
+        folder: folder.to_string(),
+        message_id: envelope.and_then(|e| id(&e.message_id)),
+        in_reply_to: envelope.and_then(|e| id(&e.in_reply_to)),
+        from_address,
+        from_name,
+        to_addresses: envelope.and_then(|e| format_envelope_addresses(e.to.as_ref())),
+        cc_addresses: envelope.and_then(|e| format_envelope_addresses(e.cc.as_ref())),
+        reply_to: envelope.and_then(|e| format_envelope_addresses(e.reply_to.as_ref())),
+        subject: envelope
+            .and_then(|e| e.subject.as_deref())
+            .map(decode_envelope_text)
+            .filter(|s| !s.is_empty()),
+        date,
+        is_read: false,
+        is_starred: false,
+        is_draft: false,
+        raw_size: 0,
+        has_attachments: false,
+    }
+}
+
 /// Format an address list as a comma-separated string of "Name <email>" or "email".
 fn format_address_list(addr: Option<&mail_parser::Address>) -> Option<String> {
     let addr = match addr {
@@ -1778,3 +2837,379 @@ fn format_address_list(addr: Option<&mail_parser::Address>) -> Option<String> {
         Some(parts.join(", "))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FORWARDED: &str = "From: Alice <alice@example.com>\r\n\
+To: bob@example.com\r\n\
+Subject: Fwd: Report\r\n\
+MIME-Version: 1.0\r\n\
+Content-Type: multipart/mixed; boundary=\"outer\"\r\n\
+\r\n\
+--outer\r\n\
+Content-Type: text/plain\r\n\
+\r\n\
+See attached.\r\n\
+--outer\r\n\
+Content-Type: message/rfc822\r\n\
+Content-Disposition: attachment\r\n\
+\r\n\
+From: Carol <carol@example.com>\r\n\
+To: alice@example.com\r\n\
+Subject: Report\r\n\
+Message-ID: <report@example.com>\r\n\
+MIME-Version: 1.0\r\n\
+Content-Type: multipart/mixed; boundary=\"inner\"\r\n\
+\r\n\
+--inner\r\n\
+Content-Type: text/plain\r\n\
+\r\n\
+Numbers inside.\r\n\
+--inner\r\n\
+Content-Type: text/csv; name=\"q3.csv\"\r\n\
+Content-Disposition: attachment; filename=\"q3.csv\"\r\n\
+\r\n\
+a,b\r\n\
+--inner--\r\n\
+\r\n\
+--outer--\r\n";
+
+    #[test]
+    fn test_parse_attached_message() {
+        let parser = MessageParser::default();
+        let msg = parse_message(
+            &parser,
+            FORWARDED.as_bytes(),
+            7,
+            "INBOX",
+            0,
+            true,
+            false,
+            false,
+            None,
+        );
+
+        assert!(msg.is_read);
+        assert_eq!(msg.attached_messages.len(), 1);
+        let attached = &msg.attached_messages[0];
+        assert_eq!(attached.part_id, "2");
+        assert_eq!(attached.message.uid, 7);
+        assert_eq!(attached.message.subject.as_deref(), Some("Report"));
+        assert_eq!(
+            attached.message.message_id.as_deref(),
+            Some("report@example.com")
+        );
+        assert_eq!(
+            attached.message.from_address.as_deref(),
+            Some("carol@example.com")
+        );
+        assert!(attached
+            .message
+            .body_text
+            .as_deref()
+            .unwrap()
+            .contains("Numbers inside."));
+
+        assert_eq!(attached.message.attachments.len(), 1);
+        assert_eq!(attached.message.attachments[0].filename, "q3.csv");
+        assert_eq!(attached.message.attachments[0].part_id, "2.2");
+    }
+
+    #[test]
+    fn test_delivered_to_from_envelope_headers() {
+        let parser = MessageParser::default();
+        let raw = b"Delivered-To: me@example.com\r\n\
+X-Original-To: <Sales@example.com>, other@example.com\r\n\
+To: undisclosed-recipients:;\r\n\
+Subject: Quote\r\n\r\nHi\r\n";
+        let msg = parse_message(&parser, raw, 1, "INBOX", 0, false, false, false, None);
+        assert_eq!(msg.delivered_to.as_deref(), Some("Sales@example.com"));
+
+        // Topmost Delivered-To is the final delivery
+        let raw =
+            b"Delivered-To: first@example.com\r\nDelivered-To: second@example.com\r\n\r\nHi\r\n";
+        let msg = parse_message(&parser, raw, 1, "INBOX", 0, false, false, false, None);
+        assert_eq!(msg.delivered_to.as_deref(), Some("first@example.com"));
+
+        let raw = b"To: me@example.com\r\n\r\nHi\r\n";
+        let msg = parse_message(&parser, raw, 1, "INBOX", 0, false, false, false, None);
+        assert_eq!(msg.delivered_to, None);
+    }
+
+    #[test]
+    fn test_list_headers() {
+        let parser = MessageParser::default();
+        let raw = b"List-Id: \"Dev talk\" <Dev.Lists.Example.org>\r\n\
+List-Post: <mailto:dev@lists.example.org?subject=hi>\r\n\
+List-Archive: <ftp://ftp.example.org/dev>,\r\n <https://lists.example.org/\r\n archive/dev>\r\n\
+Subject: Hello\r\n\r\nHi\r\n";
+        let msg = parse_message(&parser, raw, 1, "INBOX", 0, false, false, false, None);
+        assert_eq!(msg.list_id.as_deref(), Some("dev.lists.example.org"));
+        assert_eq!(msg.list_post.as_deref(), Some("dev@lists.example.org"));
+        assert_eq!(
+            msg.list_archive.as_deref(),
+            Some("https://lists.example.org/archive/dev")
+        );
+
+        // Announcement lists don't accept posts
+        let list = list_headers([("List-Post", " NO (posting not allowed)")].into_iter());
+        assert_eq!(list, ListHeaders::default());
+
+        let list = list_headers([("list-id", "announce.example.com")].into_iter());
+        assert_eq!(list.id.as_deref(), Some("announce.example.com"));
+    }
+
+    #[test]
+    fn test_mail_followup_to() {
+        let parser = MessageParser::default();
+        let raw = b"From: Alice <alice@example.com>
+To: dev@lists.example.org
+Mail-Followup-To: =?utf-8?q?D=C3=A9v?= <dev@lists.example.org>,
+ bob@example.com
+Subject: Hello
+
+Hi
+";
+        let msg = parse_message(&parser, raw, 1, "INBOX", 0, false, false, false, None);
+        assert_eq!(
+            msg.mail_followup_to.as_deref(),
+            Some("Dév <dev@lists.example.org>, bob@example.com")
+        );
+
+        let raw = b"From: alice@example.com
+
+Hi
+";
+        let msg = parse_message(&parser, raw, 1, "INBOX", 0, false, false, false, None);
+        assert_eq!(msg.mail_followup_to, None);
+    }
+
+    #[test]
+    fn test_parse_message_leniently() {
+        let raw = "From: \"Dana Lee\" <dana@example.com>\r\n\
+Subject: Broken\r\n message\r\n\
+Message-ID: <abc@example.com>\r\n\
+References: <one@example.com> <two@example.com>\r\n\
+Date: Tue, 1 Aug 2023 10:00:00 +0000\r\n\
+\r\n\
+Subject: not a header\r\n";
+        let msg = parse_message_leniently(raw.as_bytes(), 3, "INBOX", 99, None);
+
+        assert!(msg.parse_failed);
+        assert_eq!(msg.from_address.as_deref(), Some("dana@example.com"));
+        assert_eq!(msg.from_name.as_deref(), Some("Dana Lee"));
+        assert_eq!(msg.subject.as_deref(), Some("Broken message"));
+        assert_eq!(msg.message_id.as_deref(), Some("abc@example.com"));
+        assert_eq!(
+            msg.references.as_deref(),
+            Some("one@example.com two@example.com")
+        );
+        assert_eq!(msg.date, 1_690_884_000);
+        assert_eq!(msg.raw_size, 99);
+    }
+
+    #[test]
+    fn test_parse_message_falls_back_instead_of_dropping() {
+        let parser = MessageParser::default();
+        let msg = parse_message(
+            &parser,
+            b"",
+            5,
+            "INBOX",
+            0,
+            true,
+            false,
+            false,
+            Some(1_700_000_000),
+        );
+
+        assert!(msg.parse_failed);
+        assert!(msg.is_read);
+        assert_eq!(msg.uid, 5);
+        assert_eq!(msg.date, 1_700_000_000);
+    }
+
+    #[test]
+    fn test_unparseable_date_header_uses_lenient_parser() {
+        let parser = MessageParser::default();
+        let raw = b"From: a@example.com\r\nDate: Mon Feb 16 12:00:00 2026\r\n\r\nHi\r\n";
+        let msg = parse_message(&parser, raw, 1, "INBOX", 0, false, false, false, Some(0));
+
+        assert!(!msg.parse_failed);
+        assert_eq!(msg.date, 1_771_243_200);
+    }
+
+    #[test]
+    fn test_extract_internal_date() {
+        let line =
+            r#"* 1 FETCH (UID 7 INTERNALDATE " 6-Feb-2026 04:00:00 -0800" RFC822.SIZE 12 {12}"#;
+        assert_eq!(extract_internal_date(line), Some(1_770_379_200));
+        assert_eq!(extract_internal_date("* 1 FETCH (UID 7)"), None);
+    }
+
+    fn config(
+        auth_method: &str,
+        authorize_as: Option<&str>,
+        delegation: Option<&str>,
+    ) -> ImapConfig {
+        ImapConfig {
+            host: "imap.example.com".to_string(),
+            port: 993,
+            security: "tls".to_string(),
+            username: "assistant@example.com".to_string(),
+            password: "secret".to_string(),
+            auth_method: auth_method.to_string(),
+            accept_invalid_certs: false,
+            authorize_as: authorize_as.map(str::to_string),
+            delegation: delegation.map(str::to_string),
+            rate_limit_per_minute: None,
+        }
+    }
+
+    #[test]
+    fn test_login_mode_for_delegated_access() {
+        assert_eq!(
+            login_mode(&config("password", None, None)),
+            Login::Password("assistant@example.com".to_string())
+        );
+        assert_eq!(
+            login_mode(&config("password", Some(" boss@example.com "), None)),
+            Login::SaslPlain {
+                authzid: "boss@example.com".to_string()
+            }
+        );
+        assert_eq!(
+            login_mode(&config(
+                "password",
+                Some("boss@example.com"),
+                Some("exchange")
+            )),
+            Login::Password("assistant@example.com\\boss@example.com".to_string())
+        );
+        assert_eq!(
+            login_mode(&config("oauth2", Some("shared@example.com"), None)),
+            Login::OAuth2("shared@example.com".to_string())
+        );
+        assert_eq!(
+            login_mode(&config("password", Some(""), Some("exchange"))),
+            Login::Password("assistant@example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn test_imap_quote() {
+        assert_eq!(imap_quote("a\\b\"c"), "\"a\\\\b\\\"c\"");
+    }
+
+    #[test]
+    fn test_find_section_part_in_attached_message() {
+        let message = MessageParser::default()
+            .parse(FORWARDED.as_bytes())
+            .unwrap();
+
+        let part = find_section_part(&message, "", "2.2").unwrap();
+        assert_eq!(part.contents(), b"a,b");
+        assert!(find_section_part(&message, "", "2").is_some());
+        assert!(find_section_part(&message, "", "2.3").is_none());
+        assert!(find_section_part(&message, "", "3").is_none());
+    }
+
+    #[test]
+    fn test_text_search_criteria() {
+        assert_eq!(
+            text_search_criteria("invoice", Some("1-Mar-2024")),
+            "TEXT \"invoice\" BEFORE 1-Mar-2024"
+        );
+        assert_eq!(
+            text_search_criteria("café", None),
+            "CHARSET UTF-8 TEXT \"café\""
+        );
+    }
+
+    #[test]
+    fn test_needs_uid_search() {
+        let req = DeltaCheckRequest {
+            folder: "INBOX".to_string(),
+            last_uid: 41,
+            uidvalidity: 7,
+        };
+        let status = |uidvalidity, uidnext| ImapFolderStatus {
+            uidvalidity,
+            uidnext,
+            exists: 10,
+            unseen: 2,
+            highest_modseq: None,
+        };
+
+        assert!(!needs_uid_search(&req, &status(7, 42)));
+        assert!(needs_uid_search(&req, &status(7, 43)));
+        // No UIDNEXT: can't tell, so search
+        assert!(needs_uid_search(&req, &status(7, 0)));
+        // A new UIDVALIDITY is reported without searching
+        assert!(!needs_uid_search(&req, &status(8, 50)));
+    }
+
+    #[test]
+    fn test_header_from_envelope() {
+        use async_imap::imap_proto::{parser::parse_response, AttributeValue, Response};
+
+        let line = b"* 3 FETCH (UID 42 ENVELOPE (\"Tue, 14 May 2024 09:30:00 +0200\" \
+            \"=?utf-8?q?R=C3=A9union?= notes\" ((\"=?utf-8?q?Zo=C3=A9?=\" NIL \"zoe\" \"example.com\")) \
+            NIL NIL ((NIL NIL \"team\" \"example.org\")(\"Bob\" NIL \"bob\" \"example.org\")) \
+            ((NIL NIL \"friends\" NIL)(NIL NIL \"carol\" \"example.net\")(NIL NIL NIL NIL)) NIL \
+            \"<parent@example.com>\" \"<msg1@example.com>\") \
+            BODYSTRUCTURE ((\"text\" \"plain\" (\"charset\" \"utf-8\") NIL NIL \"7bit\" 12 1 NIL NIL NIL NIL)\
+            (\"application\" \"pdf\" (\"name\" \"agenda.pdf\") NIL NIL \"base64\" 2048 NIL NIL NIL NIL) \
+            \"mixed\" (\"boundary\" \"b\") NIL NIL NIL))\r\n";
+        let Ok((_, Response::Fetch(_, attributes))) = parse_response(line) else {
+            panic!("FETCH response didn't parse");
+        };
+        let envelope = attributes.iter().find_map(|a| match a {
+            AttributeValue::Envelope(e) => Some(e.as_ref()),
+            _ => None,
+        });
+        let structure = attributes.iter().find_map(|a| match a {
+            AttributeValue::BodyStructure(b) => Some(b),
+            _ => None,
+        });
+
+        let header = header_from_envelope(envelope, 42, "INBOX", Some(1));
+        assert_eq!(header.subject.as_deref(), Some("Réunion notes"));
+        assert_eq!(header.from_address.as_deref(), Some("zoe@example.com"));
+        assert_eq!(header.from_name.as_deref(), Some("Zoé"));
+        assert_eq!(
+            header.to_addresses.as_deref(),
+            Some("team@example.org, Bob <bob@example.org>")
+        );
+        // Group markers are dropped
+        assert_eq!(header.cc_addresses.as_deref(), Some("carol@example.net"));
+        assert_eq!(header.message_id.as_deref(), Some("msg1@example.com"));
+        assert_eq!(header.in_reply_to.as_deref(), Some("parent@example.com"));
+        assert_eq!(header.date, 1715671800);
+        assert!(structure.is_some_and(bodystructure::has_attachments));
+
+        // No envelope: only INTERNALDATE is known
+        let header = header_from_envelope(None, 7, "INBOX", Some(1715671800));
+        assert_eq!(header.date, 1715671800);
+        assert_eq!(header.subject, None);
+    }
+
+    #[test]
+    fn test_encode_folder_name() {
+        assert_eq!(encode_folder_name("Archive/2026").unwrap(), "Archive/2026");
+        assert_eq!(encode_folder_name(" Entwürfe ").unwrap(), "Entw&APw-rfe");
+        assert_eq!(encode_folder_name("Tom & Jerry").unwrap(), "Tom &- Jerry");
+        assert_eq!(encode_folder_name("日本語").unwrap(), "&ZeVnLIqe-");
+        let raw = encode_folder_name("Projekte/Größe & Co").unwrap();
+        assert_eq!(utf7_imap::decode_utf7_imap(raw), "Projekte/Größe & Co");
+
+        assert!(encode_folder_name("  ").is_err());
+        assert!(encode_folder_name("Inbox/*").is_err());
+        assert!(encode_folder_name("a\r\nb").is_err());
+    }
+}
+
+#[cfg(test)]
+mod fixture_tests;
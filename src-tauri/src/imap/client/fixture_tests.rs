@@ -0,0 +1,126 @@
+//! Golden-output regression tests for the message parsers.
+//!
+//! Every `fixtures/messages/*.eml` goes through `parse_message` and every
+//! `fixtures/fetch/*.imap` (a server's reply to `a3 UID FETCH ...`) through
+//! the raw FETCH parser; the result must match the `.json` file next to the
+//! fixture. Add a fixture with each server-compatibility fix. When output
+//! changes on purpose, regenerate with `UPDATE_GOLDEN=1 cargo test fixture`
+//! and review the diff.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use super::*;
+
+fn fixtures(kind: &str, extension: &str) -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src/imap/fixtures")
+        .join(kind);
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .unwrap_or_else(|e| panic!("{}: {e}", dir.display()))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == extension))
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty(), "no fixtures in {}", dir.display());
+    paths
+}
+
+/// Compare `output` with the fixture's golden file (or rewrite the golden
+/// file when `UPDATE_GOLDEN` is set). Returns what went wrong, if anything.
+fn check_golden(fixture: &Path, output: &impl Serialize) -> Option<String> {
+    let golden = fixture.with_extension("json");
+    let actual = serde_json::to_string_pretty(output).unwrap() + "\n";
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&golden, actual).unwrap();
+        return None;
+    }
+
+    let Ok(expected) = std::fs::read_to_string(&golden) else {
+        return Some(format!(
+            "{}: no golden file, run with UPDATE_GOLDEN=1",
+            fixture.display()
+        ));
+    };
+    if expected == actual {
+        return None;
+    }
+    let line = expected
+        .lines()
+        .zip(actual.lines())
+        .position(|(e, a)| e != a)
+        .unwrap_or_else(|| expected.lines().count().min(actual.lines().count()));
+    let nth = |text: &str| {
+        text.lines()
+            .nth(line)
+            .unwrap_or("<end of file>")
+            .to_string()
+    };
+    Some(format!(
+        "{} differs from {} at line {}:\n  expected: {}\n  actual:   {}",
+        fixture.display(),
+        golden.display(),
+        line + 1,
+        nth(&expected),
+        nth(&actual),
+    ))
+}
+
+#[test]
+fn test_message_fixtures() {
+    let parser = MessageParser::default();
+    let failures: Vec<String> = fixtures("messages", "eml")
+        .iter()
+        .filter_map(|path| {
+            let raw = std::fs::read(path).unwrap();
+            let message = parse_message(
+                &parser,
+                &raw,
+                1,
+                "INBOX",
+                raw.len() as u32,
+                false,
+                false,
+                false,
+                None,
+            );
+            check_golden(path, &message)
+        })
+        .collect();
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[tokio::test]
+async fn test_fetch_fixtures() {
+    let parser = MessageParser::default();
+    let mut failures = Vec::new();
+    for path in fixtures("fetch", "imap") {
+        let transcript = std::fs::read(&path).unwrap();
+        let raw_messages = match raw_parse_fetch_responses(&mut &transcript[..], "a3").await {
+            Ok(messages) => messages,
+            Err(e) => {
+                failures.push(format!("{}: {e}", path.display()));
+                continue;
+            }
+        };
+        let messages: Vec<ImapMessage> = raw_messages
+            .iter()
+            .map(|m| {
+                parse_message(
+                    &parser,
+                    &m.body,
+                    m.uid,
+                    "INBOX",
+                    m.body.len() as u32,
+                    m.is_read,
+                    m.is_starred,
+                    m.is_draft,
+                    m.internal_date,
+                )
+            })
+            .collect();
+        failures.extend(check_golden(&path, &messages));
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
@@ -0,0 +1,239 @@
+//! Lenient parser for mail dates.
+//!
+//! Handles RFC 2822 `Date:` headers including the obsolete forms from
+//! section 4.3 (two- and three-digit years, named zones like `PST` or `GMT`,
+//! military zones, comments, extra whitespace), IMAP `INTERNALDATE`
+//! (`16-Feb-2026 12:00:00 +0000`) and the asctime layout some old mailers
+//! wrote (`Mon Feb 16 12:00:00 2026`). Calendar math is left to chrono.
+
+use chrono::{FixedOffset, NaiveDate, TimeZone};
+
+/// Parse a mail or IMAP date to a Unix timestamp (seconds).
+///
+/// Unknown zone names count as UTC, as RFC 2822 requires for military
+/// zones. Returns `None` if no day, month, year and time can be found or
+/// the date doesn't exist.
+pub fn parse_date(s: &str) -> Option<i64> {
+    let cleaned = strip_comments(s);
+    let mut day: Option<u32> = None;
+    let mut month: Option<u32> = None;
+    let mut year: Option<i32> = None;
+    let mut time: Option<(u32, u32, u32)> = None;
+    let mut offset_secs: Option<i32> = None;
+    let mut pm: Option<bool> = None;
+
+    for token in tokens(&cleaned) {
+        if time.is_none() && token.contains(':') {
+            time = Some(parse_time(token)?);
+        } else if let Some(m) = month_from_name(token) {
+            month.get_or_insert(m);
+        } else if token.bytes().all(|b| b.is_ascii_digit()) {
+            // The day comes before the year in every layout we accept
+            if day.is_none() && year.is_none() && token.len() <= 2 {
+                day = Some(token.parse().ok()?);
+            } else if year.is_none() {
+                year = Some(expand_year(token.parse().ok()?, token.len()));
+            }
+        } else if token.eq_ignore_ascii_case("am") || token.eq_ignore_ascii_case("pm") {
+            pm = Some(token.eq_ignore_ascii_case("pm"));
+        } else if time.is_some() && offset_secs.is_none() {
+            offset_secs = zone_offset(token);
+        }
+    }
+
+    let (mut hour, minute, second) = time?;
+    match pm {
+        Some(true) if hour < 12 => hour += 12,
+        Some(false) if hour == 12 => hour = 0,
+        _ => {}
+    }
+    let date = NaiveDate::from_ymd_opt(year?, month?, day?)?;
+    // Leap second "23:59:60" is clamped rather than rejected
+    let naive = date.and_hms_opt(hour, minute, second.min(59))?;
+    let offset = FixedOffset::east_opt(offset_secs.unwrap_or(0))?;
+    Some(offset.from_local_datetime(&naive).single()?.timestamp())
+}
+
+/// Split on whitespace and commas, and break "16-Feb-2026" into its parts.
+/// Only tokens starting with a digit are split, so zones like "-0500" stay whole.
+fn tokens(s: &str) -> impl Iterator<Item = &str> {
+    s.split(|c: char| c.is_whitespace() || c == ',')
+        .flat_map(|t| {
+            if t.starts_with(|c: char| c.is_ascii_digit()) && !t.contains(':') {
+                t.split('-').collect::<Vec<_>>()
+            } else {
+                vec![t]
+            }
+        })
+        .filter(|t| !t.is_empty())
+}
+
+/// Remove RFC 2822 comments like "(PST)", including nested ones.
+fn strip_comments(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut depth = 0usize;
+    for c in s.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// "HH:MM" or "HH:MM:SS", also with a trailing fraction ("12:00:00.123").
+fn parse_time(token: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = token.split(':');
+    let hour = parts.next()?.parse().ok()?;
+    let minute = parts.next()?.parse().ok()?;
+    let second = match parts.next() {
+        Some(s) => s.split('.').next()?.parse().ok()?,
+        None => 0,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((hour, minute, second))
+}
+
+/// Month from an English name or abbreviation ("Feb", "february", "Sept").
+fn month_from_name(token: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = [
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ];
+    let lower = token.trim_end_matches('.').to_ascii_lowercase();
+    if lower.len() < 3 {
+        return None;
+    }
+    MONTHS
+        .iter()
+        .position(|m| lower.starts_with(m))
+        .map(|i| i as u32 + 1)
+}
+
+/// RFC 2822 4.3: two-digit years below 50 are 20xx, the rest 19xx;
+/// three-digit years are offsets from 1900.
+fn expand_year(year: i32, digits: usize) -> i32 {
+    match digits {
+        1 | 2 if year < 50 => 2000 + year,
+        1 | 2 => 1900 + year,
+        3 => 1900 + year,
+        _ => year,
+    }
+}
+
+/// Offset in seconds east of UTC for a numeric or named zone.
+fn zone_offset(token: &str) -> Option<i32> {
+    if let Some(sign) = token.chars().next().filter(|c| *c == '+' || *c == '-') {
+        let digits: String = token[1..].chars().filter(|c| *c != ':').collect();
+        if digits.len() != 4 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let hours: i32 = digits[..2].parse().ok()?;
+        let minutes: i32 = digits[2..].parse().ok()?;
+        let secs = hours * 3600 + minutes * 60;
+        return Some(if sign == '-' { -secs } else { secs });
+    }
+
+    // "GMT+0100" / "UTC-05:00"
+    let upper = token.to_ascii_uppercase();
+    for prefix in ["GMT", "UTC", "UT"] {
+        if let Some(rest) = upper.strip_prefix(prefix) {
+            if rest.starts_with(['+', '-']) {
+                return zone_offset(rest);
+            }
+        }
+    }
+
+    let hours = match upper.as_str() {
+        "UT" | "UTC" | "GMT" | "Z" => 0,
+        "EDT" => -4,
+        "EST" | "CDT" => -5,
+        "CST" | "MDT" => -6,
+        "MST" | "PDT" => -7,
+        "PST" => -8,
+        // Common non-RFC names seen in the wild
+        "BST" | "CET" => 1,
+        "CEST" | "EET" => 2,
+        "EEST" | "MSK" => 3,
+        "IST" => return Some(5 * 3600 + 30 * 60),
+        "JST" | "KST" => 9,
+        _ => 0,
+    };
+    Some(hours * 3600)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2026-02-16 12:00:00 UTC
+    const NOON: i64 = 1_771_243_200;
+
+    #[test]
+    fn test_rfc2822() {
+        assert_eq!(parse_date("Mon, 16 Feb 2026 12:00:00 +0000"), Some(NOON));
+        assert_eq!(parse_date("16 Feb 2026 13:30:00 +0130"), Some(NOON));
+        assert_eq!(
+            parse_date("Mon, 16 Feb 2026 07:00:00 -0500 (EST)"),
+            Some(NOON)
+        );
+        assert_eq!(parse_date("Mon,16 Feb 2026 12:00 +0000"), Some(NOON));
+    }
+
+    #[test]
+    fn test_imap_internaldate() {
+        assert_eq!(parse_date("16-Feb-2026 12:00:00 +0000"), Some(NOON));
+        assert_eq!(
+            parse_date(" 6-Feb-2026 12:00:00 -0800"),
+            Some(NOON - 10 * 86400 + 8 * 3600)
+        );
+    }
+
+    #[test]
+    fn test_named_zones() {
+        assert_eq!(parse_date("Mon, 16 Feb 2026 12:00:00 GMT"), Some(NOON));
+        assert_eq!(parse_date("Mon, 16 Feb 2026 04:00:00 PST"), Some(NOON));
+        assert_eq!(parse_date("Mon, 16 Feb 2026 05:00:00 PDT"), Some(NOON));
+        assert_eq!(parse_date("Mon, 16 Feb 2026 13:00:00 GMT+0100"), Some(NOON));
+        // Military and unknown zones count as UTC
+        assert_eq!(parse_date("Mon, 16 Feb 2026 12:00:00 Q"), Some(NOON));
+        assert_eq!(parse_date("Mon, 16 Feb 2026 12:00:00 XYZT"), Some(NOON));
+    }
+
+    #[test]
+    fn test_obsolete_years() {
+        assert_eq!(
+            parse_date("Fri, 1 Jan 99 00:00:00 +0000"),
+            Some(915_148_800)
+        );
+        assert_eq!(
+            parse_date("Sat, 1 Jan 00 00:00:00 +0000"),
+            Some(946_684_800)
+        );
+        assert_eq!(
+            parse_date("Fri, 1 Jan 099 00:00:00 +0000"),
+            Some(915_148_800)
+        );
+    }
+
+    #[test]
+    fn test_asctime_and_variants() {
+        assert_eq!(parse_date("Mon Feb 16 12:00:00 2026"), Some(NOON));
+        assert_eq!(
+            parse_date("Monday, 16 February 2026 12:00:00 PM GMT"),
+            Some(NOON)
+        );
+        assert_eq!(parse_date("16 Feb 2026 12:00:00.250 +0000"), Some(NOON));
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(parse_date(""), None);
+        assert_eq!(parse_date("not a date"), None);
+        assert_eq!(parse_date("31 Feb 2026 12:00:00 +0000"), None);
+        assert_eq!(parse_date("16 Feb 2026"), None);
+    }
+}
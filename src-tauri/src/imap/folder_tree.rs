@@ -0,0 +1,187 @@
+use super::types::{ImapFolder, ImapFolderNode};
+
+/// Derive a stable folder id from its raw (modified UTF-7) path.
+///
+/// Uses 64-bit FNV-1a so the id survives restarts and re-syncs without
+/// needing to be persisted, and stays safe to use as a DOM/React key.
+pub fn folder_id(raw_path: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in raw_path.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    format!("f{hash:016x}")
+}
+
+/// Whether a LIST attribute set contains the given attribute (case-insensitive).
+pub fn has_attribute(attributes: &[String], attr: &str) -> bool {
+    attributes.iter().any(|a| a.eq_ignore_ascii_case(attr))
+}
+
+/// Build a folder hierarchy from the flat LIST output.
+///
+/// Folders are nested by splitting `raw_path` on each folder's delimiter.
+/// Ancestors the server didn't list are synthesized as non-selectable
+/// containers. Siblings are ordered INBOX first, then alphabetically.
+pub fn build_folder_tree(folders: &[ImapFolder]) -> Vec<ImapFolderNode> {
+    let mut roots: Vec<ImapFolderNode> = Vec::new();
+
+    for folder in folders {
+        let raw_segments = split_path(&folder.raw_path, &folder.delimiter);
+        let mut decoded_segments = split_path(&folder.path, &folder.delimiter);
+        if decoded_segments.len() != raw_segments.len() {
+            decoded_segments = raw_segments.clone();
+        }
+        insert_node(&mut roots, folder, &raw_segments, &decoded_segments, 0);
+    }
+
+    finalize(&mut roots);
+    roots
+}
+
+fn split_path<'a>(path: &'a str, delimiter: &str) -> Vec<&'a str> {
+    if delimiter.is_empty() {
+        return vec![path];
+    }
+    path.split(delimiter).collect()
+}
+
+fn insert_node(
+    nodes: &mut Vec<ImapFolderNode>,
+    folder: &ImapFolder,
+    raw_segments: &[&str],
+    decoded_segments: &[&str],
+    depth: usize,
+) {
+    let raw_prefix = raw_segments[..=depth].join(&folder.delimiter);
+    let is_leaf = depth + 1 == raw_segments.len();
+
+    let idx = match nodes.iter().position(|n| n.raw_path == raw_prefix) {
+        Some(i) => i,
+        None => {
+            nodes.push(ImapFolderNode {
+                id: folder_id(&raw_prefix),
+                path: decoded_segments[..=depth].join(&folder.delimiter),
+                raw_path: raw_prefix,
+                name: decoded_segments[depth].to_string(),
+                delimiter: folder.delimiter.clone(),
+                special_use: None,
+                exists: 0,
+                unseen: 0,
+                attributes: vec!["\\Noselect".to_string()],
+                selectable: false,
+                has_children: true,
+                children: Vec::new(),
+            });
+            nodes.len() - 1
+        }
+    };
+
+    if is_leaf {
+        // Either a fresh node or a placeholder created by a child listed earlier
+        let node = &mut nodes[idx];
+        node.path = folder.path.clone();
+        node.name = folder.name.clone();
+        node.special_use = folder.special_use.clone();
+        node.exists = folder.exists;
+        node.unseen = folder.unseen;
+        node.attributes = folder.attributes.clone();
+        node.selectable = !folder.no_select && !folder.non_existent;
+        node.has_children = has_attribute(&folder.attributes, "\\HasChildren");
+    } else {
+        insert_node(
+            &mut nodes[idx].children,
+            folder,
+            raw_segments,
+            decoded_segments,
+            depth + 1,
+        );
+    }
+}
+
+/// Sort siblings and reconcile `has_children` with the actual tree shape.
+fn finalize(nodes: &mut [ImapFolderNode]) {
+    nodes.sort_by(|a, b| {
+        let a_inbox = a.raw_path.eq_ignore_ascii_case("INBOX");
+        let b_inbox = b.raw_path.eq_ignore_ascii_case("INBOX");
+        b_inbox
+            .cmp(&a_inbox)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    for node in nodes.iter_mut() {
+        if !node.children.is_empty() {
+            node.has_children = true;
+        }
+        finalize(&mut node.children);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn folder(raw_path: &str, attributes: &[&str]) -> ImapFolder {
+        ImapFolder {
+            path: raw_path.to_string(),
+            raw_path: raw_path.to_string(),
+            name: raw_path.rsplit('/').next().unwrap_or(raw_path).to_string(),
+            delimiter: "/".to_string(),
+            special_use: None,
+            exists: 0,
+            unseen: 0,
+            attributes: attributes.iter().map(|a| a.to_string()).collect(),
+            no_select: attributes.contains(&"\\Noselect"),
+            non_existent: false,
+        }
+    }
+
+    #[test]
+    fn test_folder_id_is_stable() {
+        assert_eq!(folder_id("INBOX"), folder_id("INBOX"));
+        assert_ne!(folder_id("INBOX"), folder_id("Sent"));
+        assert_eq!(folder_id("INBOX").len(), 17);
+    }
+
+    #[test]
+    fn test_build_tree_nests_children() {
+        let folders = vec![
+            folder("Work", &["\\HasChildren"]),
+            folder("Work/Projects", &["\\HasNoChildren"]),
+            folder("INBOX", &[]),
+            folder("Archive", &[]),
+        ];
+        let tree = build_folder_tree(&folders);
+        assert_eq!(tree.len(), 3);
+        assert_eq!(tree[0].raw_path, "INBOX");
+        assert_eq!(tree[1].raw_path, "Archive");
+        assert_eq!(tree[2].children.len(), 1);
+        assert_eq!(tree[2].children[0].name, "Projects");
+        assert!(tree[2].has_children);
+    }
+
+    #[test]
+    fn test_build_tree_synthesizes_missing_parent() {
+        let folders = vec![
+            folder("[Gmail]/Sent Mail", &[]),
+            folder("[Gmail]/Trash", &[]),
+        ];
+        let tree = build_folder_tree(&folders);
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].raw_path, "[Gmail]");
+        assert!(!tree[0].selectable);
+        assert_eq!(tree[0].children.len(), 2);
+    }
+
+    #[test]
+    fn test_build_tree_noselect_parent_listed_after_child() {
+        let folders = vec![
+            folder("[Gmail]/Drafts", &[]),
+            folder("[Gmail]", &["\\Noselect", "\\HasChildren"]),
+        ];
+        let tree = build_folder_tree(&folders);
+        assert_eq!(tree.len(), 1);
+        assert!(!tree[0].selectable);
+        assert_eq!(tree[0].attributes, vec!["\\Noselect", "\\HasChildren"]);
+        assert_eq!(tree[0].children.len(), 1);
+    }
+}
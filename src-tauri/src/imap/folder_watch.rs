@@ -0,0 +1,249 @@
+//! Noticing folders created, renamed or deleted on the server.
+//!
+//! Delta sync lists an account's folders on every pass, so the folder list
+//! is compared with the one from the previous LIST and any difference is
+//! sent to the frontend as [`FoldersChanged`]. That keeps the sidebar right
+//! when the user reorganizes folders from another client.
+//!
+//! IMAP has no rename notification outside NOTIFY, so renames are guessed:
+//! a removed and an added folder with the same parent (renamed in place) or
+//! the same name (moved to another parent) are reported as one rename when
+//! the pairing is unambiguous.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use crate::events::{FolderRename, FoldersChanged};
+use crate::imap::types::ImapFolder;
+
+type ChangeListener = Box<dyn Fn(FoldersChanged) + Send + Sync>;
+
+/// Managed state: the last folder list seen for each account.
+#[derive(Default)]
+pub struct FolderListCache {
+    folders: Mutex<HashMap<String, Vec<ImapFolder>>>,
+    listener: Option<ChangeListener>,
+}
+
+impl FolderListCache {
+    /// A cache that emits [`FoldersChanged`] to the frontend.
+    pub fn new<R: tauri::Runtime>(app: tauri::AppHandle<R>) -> Self {
+        Self::with_listener(move |change| crate::events::emit(&app, change))
+    }
+
+    /// A cache that calls `listener` whenever an account's folders change.
+    pub fn with_listener(listener: impl Fn(FoldersChanged) + Send + Sync + 'static) -> Self {
+        Self {
+            folders: Mutex::default(),
+            listener: Some(Box::new(listener)),
+        }
+    }
+
+    /// Record a fresh LIST of `account_id` and report how it differs from
+    /// the previous one. The first list of an account only seeds the cache.
+    pub fn update(&self, account_id: &str, folders: &[ImapFolder]) -> Option<FoldersChanged> {
+        let previous = self
+            .folders
+            .lock()
+            .unwrap()
+            .insert(account_id.to_string(), folders.to_vec())?;
+
+        let change = diff_folders(account_id, &previous, folders)?;
+        log::info!(
+            "Folders changed on {account_id}: {} added, {} removed, {} renamed",
+            change.added.len(),
+            change.removed.len(),
+            change.renamed.len()
+        );
+        if let Some(listener) = &self.listener {
+            listener(change.clone());
+        }
+        Some(change)
+    }
+
+    /// Forget an account's folders, e.g. when it's removed.
+    pub fn remove(&self, account_id: &str) {
+        self.folders.lock().unwrap().remove(account_id);
+    }
+}
+
+/// The raw path of `folder`'s parent, or `None` at the top level.
+fn parent_path(folder: &ImapFolder) -> Option<&str> {
+    if folder.delimiter.is_empty() {
+        return None;
+    }
+    folder
+        .raw_path
+        .rsplit_once(folder.delimiter.as_str())
+        .map(|(parent, _)| parent)
+}
+
+/// Pair removed and added folders into renames: first by parent, then by
+/// name. Returns the pairs and removes them from both lists.
+fn pair_renames(removed: &mut Vec<ImapFolder>, added: &mut Vec<ImapFolder>) -> Vec<FolderRename> {
+    let by_parent = |f: &ImapFolder| parent_path(f).unwrap_or_default().to_string();
+    let by_name = |f: &ImapFolder| f.name.clone();
+
+    let mut renamed = Vec::new();
+    for key in [&by_parent as &dyn Fn(&ImapFolder) -> String, &by_name] {
+        let count = |list: &[ImapFolder], k: &str| list.iter().filter(|f| key(f) == k).count();
+        let pairs: HashMap<usize, usize> = removed
+            .iter()
+            .enumerate()
+            .filter_map(|(i, old)| {
+                let k = key(old);
+                if count(removed, &k) != 1 || count(added, &k) != 1 {
+                    return None;
+                }
+                Some((i, added.iter().position(|f| key(f) == k)?))
+            })
+            .collect();
+        if pairs.is_empty() {
+            continue;
+        }
+
+        let mut pairs: Vec<(usize, usize)> = pairs.into_iter().collect();
+        pairs.sort_unstable();
+        for &(i, j) in &pairs {
+            renamed.push(FolderRename {
+                from: removed[i].raw_path.clone(),
+                to: added[j].clone(),
+            });
+        }
+        let taken_old: HashSet<usize> = pairs.iter().map(|&(i, _)| i).collect();
+        let taken_new: HashSet<usize> = pairs.iter().map(|&(_, j)| j).collect();
+        *removed = keep_untaken(std::mem::take(removed), &taken_old);
+        *added = keep_untaken(std::mem::take(added), &taken_new);
+    }
+    renamed
+}
+
+fn keep_untaken(list: Vec<ImapFolder>, taken: &HashSet<usize>) -> Vec<ImapFolder> {
+    list.into_iter()
+        .enumerate()
+        .filter(|(i, _)| !taken.contains(i))
+        .map(|(_, f)| f)
+        .collect()
+}
+
+/// The folders added, removed and renamed between two LISTs, or `None` if
+/// the set of folders is the same.
+pub fn diff_folders(
+    account_id: &str,
+    old: &[ImapFolder],
+    new: &[ImapFolder],
+) -> Option<FoldersChanged> {
+    let old_paths: HashSet<&str> = old.iter().map(|f| f.raw_path.as_str()).collect();
+    let new_paths: HashSet<&str> = new.iter().map(|f| f.raw_path.as_str()).collect();
+
+    let mut added: Vec<ImapFolder> = new
+        .iter()
+        .filter(|f| !old_paths.contains(f.raw_path.as_str()))
+        .cloned()
+        .collect();
+    let mut removed: Vec<ImapFolder> = old
+        .iter()
+        .filter(|f| !new_paths.contains(f.raw_path.as_str()))
+        .cloned()
+        .collect();
+    if added.is_empty() && removed.is_empty() {
+        return None;
+    }
+
+    let renamed = pair_renames(&mut removed, &mut added);
+    Some(FoldersChanged {
+        account_id: account_id.to_string(),
+        added,
+        removed: removed.into_iter().map(|f| f.raw_path).collect(),
+        renamed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn folder(raw_path: &str) -> ImapFolder {
+        ImapFolder {
+            path: raw_path.to_string(),
+            raw_path: raw_path.to_string(),
+            name: raw_path.rsplit('/').next().unwrap().to_string(),
+            delimiter: "/".to_string(),
+            special_use: None,
+            exists: 0,
+            unseen: 0,
+            attributes: Vec::new(),
+            no_select: false,
+            non_existent: false,
+        }
+    }
+
+    fn folders(paths: &[&str]) -> Vec<ImapFolder> {
+        paths.iter().map(|p| folder(p)).collect()
+    }
+
+    fn renames(change: &FoldersChanged) -> Vec<(&str, &str)> {
+        change
+            .renamed
+            .iter()
+            .map(|r| (r.from.as_str(), r.to.raw_path.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_added_and_removed() {
+        let old = folders(&["INBOX", "Work", "Work/Old"]);
+        let new = folders(&["INBOX", "Work", "Travel", "Archive/2024"]);
+        assert!(diff_folders("acc", &old, &old).is_none());
+
+        let change = diff_folders("acc", &old, &new).unwrap();
+        // "Work/Old" and "Archive/2024" share neither parent nor name
+        assert_eq!(change.removed, vec!["Work/Old"]);
+        let added: Vec<&str> = change.added.iter().map(|f| f.raw_path.as_str()).collect();
+        assert_eq!(added, vec!["Travel", "Archive/2024"]);
+        assert!(change.renamed.is_empty());
+    }
+
+    #[test]
+    fn test_diff_renames() {
+        // Renamed in place and moved under another parent
+        let old = folders(&["INBOX", "Work/Projects", "Receipts"]);
+        let new = folders(&["INBOX", "Work/Clients", "Archive/Receipts"]);
+        let change = diff_folders("acc", &old, &new).unwrap();
+        assert_eq!(
+            renames(&change),
+            vec![
+                ("Work/Projects", "Work/Clients"),
+                ("Receipts", "Archive/Receipts")
+            ]
+        );
+        assert!(change.added.is_empty() && change.removed.is_empty());
+
+        // Two folders gone and two new under one parent: no guessing
+        let old = folders(&["Work/A", "Work/B"]);
+        let new = folders(&["Work/C", "Work/D"]);
+        let change = diff_folders("acc", &old, &new).unwrap();
+        assert!(change.renamed.is_empty());
+        assert_eq!(change.removed.len(), 2);
+        assert_eq!(change.added.len(), 2);
+    }
+
+    #[test]
+    fn test_cache_seeds_then_reports() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let cache = FolderListCache::with_listener(move |change| {
+            sink.lock().unwrap().push(change.account_id)
+        });
+
+        assert!(cache.update("acc", &folders(&["INBOX"])).is_none());
+        assert!(cache.update("acc", &folders(&["INBOX"])).is_none());
+        let change = cache.update("acc", &folders(&["INBOX", "New"])).unwrap();
+        assert_eq!(change.added.len(), 1);
+        assert_eq!(*seen.lock().unwrap(), vec!["acc"]);
+
+        cache.remove("acc");
+        assert!(cache.update("acc", &folders(&["INBOX"])).is_none());
+    }
+}
@@ -0,0 +1,317 @@
+//! Single-folder push via IMAP IDLE (RFC 2177), for servers without NOTIFY.
+//!
+//! Each watched folder gets its own connection that EXAMINEs the folder and
+//! sits in IDLE. New messages, expunges and flag changes the server pushes
+//! are forwarded to the frontend as events, so it can sync the folder right
+//! away instead of waiting for the next poll.
+//!
+//! Like NOTIFY this runs on a raw connection: async-imap's IDLE handle only
+//! hands back one response at a time and has to be rebuilt around each.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::time::Instant;
+
+use super::client::{
+    capabilities_include, imap_quote, parse_untagged_number, raw_connect_and_login,
+    raw_send_and_wait, ImapStream,
+};
+use super::notify::trailing_literal_len;
+use super::types::ImapConfig;
+use crate::events::{self, ImapExpunge, ImapFlagsChanged, ImapIdleStopped, ImapNewMail};
+
+/// End and restart IDLE this often; RFC 2177 asks clients to do it at least
+/// every 29 minutes so servers don't log them out as inactive.
+const REISSUE_INTERVAL: Duration = Duration::from_secs(25 * 60);
+/// How long the server gets to accept IDLE or end it after DONE.
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Running IDLE connections, keyed by account id and folder.
+#[derive(Default)]
+pub struct IdleWatchers(Mutex<HashMap<(String, String), tauri::async_runtime::JoinHandle<()>>>);
+
+/// An untagged response pushed while idling.
+#[derive(Debug, PartialEq)]
+enum IdleUpdate {
+    Exists(u32),
+    Expunge(u32),
+    Flags {
+        seq: u32,
+        uid: Option<u32>,
+        flags: Vec<String>,
+    },
+}
+
+fn parse_update(line: &str) -> Option<IdleUpdate> {
+    if let Some(n) = parse_untagged_number(line, "EXISTS") {
+        return Some(IdleUpdate::Exists(n));
+    }
+    if let Some(n) = parse_untagged_number(line, "EXPUNGE") {
+        return Some(IdleUpdate::Expunge(n));
+    }
+
+    // "* 12 FETCH (UID 345 FLAGS (\Seen \Flagged))"
+    let rest = line.trim().strip_prefix("* ")?;
+    let (seq, rest) = rest.split_once(' ')?;
+    let seq = seq.parse().ok()?;
+    let rest = rest.strip_prefix("FETCH ")?;
+    let flags_start = rest.find("FLAGS (")? + "FLAGS (".len();
+    let flags_end = flags_start + rest[flags_start..].find(')')?;
+    let flags = rest[flags_start..flags_end]
+        .split_whitespace()
+        .map(str::to_string)
+        .collect();
+    let uid = rest.find("UID ").and_then(|i| {
+        rest[i + "UID ".len()..]
+            .split(|c: char| !c.is_ascii_digit())
+            .next()?
+            .parse()
+            .ok()
+    });
+    Some(IdleUpdate::Flags { seq, uid, flags })
+}
+
+/// Start watching `folder` (raw path) of an account.
+///
+/// Returns `Ok(false)` if the server doesn't support IDLE, in which case
+/// the frontend keeps relying on polling.
+pub async fn start(
+    app: AppHandle,
+    account_id: String,
+    config: ImapConfig,
+    folder: String,
+) -> Result<bool, String> {
+    stop(&app, Some(&account_id), Some(&folder));
+
+    let mut reader = raw_connect_and_login(&config).await?;
+
+    let caps = raw_send_and_wait(&mut reader, b"a2 CAPABILITY\r\n", "a2").await?;
+    if !capabilities_include(&caps, "IDLE") {
+        let _ = reader.get_mut().write_all(b"a3 LOGOUT\r\n").await;
+        return Ok(false);
+    }
+
+    // Read-only, so idling doesn't clear \Recent for other clients
+    let cmd = format!("a3 EXAMINE {}\r\n", imap_quote(&folder));
+    let examined = raw_send_and_wait(&mut reader, cmd.as_bytes(), "a3").await?;
+    let exists = examined
+        .lines()
+        .find_map(|l| parse_untagged_number(l, "EXISTS"))
+        .unwrap_or(0);
+
+    log::info!("IDLE started on {folder} of account {account_id}");
+
+    let task_app = app.clone();
+    let task_account = account_id.clone();
+    let task_folder = folder.clone();
+    let handle = tauri::async_runtime::spawn(async move {
+        let error = watch(&task_app, &task_account, &task_folder, &mut reader, exists)
+            .await
+            .err();
+        if let Some(e) = &error {
+            log::warn!("IDLE connection for {task_folder} of account {task_account} ended: {e}");
+        }
+        events::emit(
+            &task_app,
+            ImapIdleStopped {
+                account_id: task_account,
+                folder: task_folder,
+                error,
+            },
+        );
+    });
+
+    // A concurrent start for the same folder may have finished first
+    let previous = app
+        .state::<IdleWatchers>()
+        .0
+        .lock()
+        .unwrap()
+        .insert((account_id, folder), handle);
+    if let Some(previous) = previous {
+        previous.abort();
+    }
+
+    Ok(true)
+}
+
+/// Stop the IDLE connections of one folder, one account, or all of them.
+pub fn stop(app: &AppHandle, account_id: Option<&str>, folder: Option<&str>) {
+    let watchers = app.state::<IdleWatchers>();
+    let mut watchers = watchers.0.lock().unwrap();
+    watchers.retain(|(account, watched), handle| {
+        let matches =
+            account_id.map_or(true, |id| id == account) && folder.map_or(true, |f| f == watched);
+        if matches {
+            handle.abort();
+        }
+        !matches
+    });
+}
+
+/// Idle until the connection drops, restarting IDLE every
+/// [`REISSUE_INTERVAL`].
+async fn watch(
+    app: &AppHandle,
+    account_id: &str,
+    folder: &str,
+    reader: &mut BufReader<ImapStream>,
+    mut exists: u32,
+) -> Result<(), String> {
+    let mut tag = 4u32;
+    let mut idle_tag = send_idle(reader, &mut tag).await?;
+    // Accepted by the server with a "+" continuation
+    let mut idling = false;
+    let mut done_sent = false;
+    let mut deadline = Instant::now() + RESPONSE_TIMEOUT;
+    // Kept across timeouts: a cancelled read leaves partial data here
+    let mut line = Vec::new();
+
+    loop {
+        match tokio::time::timeout_at(deadline, reader.read_until(b'\n', &mut line)).await {
+            Err(_) if !idling => return Err("server didn't accept IDLE".to_string()),
+            Err(_) if done_sent => return Err("server didn't end IDLE".to_string()),
+            Err(_) => {
+                reader
+                    .get_mut()
+                    .write_all(b"DONE\r\n")
+                    .await
+                    .map_err(|e| format!("DONE write: {e}"))?;
+                done_sent = true;
+                deadline = Instant::now() + RESPONSE_TIMEOUT;
+                continue;
+            }
+            Ok(Ok(0)) => return Err("connection closed".to_string()),
+            Ok(Err(e)) => return Err(format!("read: {e}")),
+            Ok(Ok(_)) => {}
+        }
+
+        while let Some(len) = trailing_literal_len(&line) {
+            let start = line.len();
+            line.resize(start + len, 0);
+            reader
+                .read_exact(&mut line[start..])
+                .await
+                .map_err(|e| format!("read literal: {e}"))?;
+            reader
+                .read_until(b'\n', &mut line)
+                .await
+                .map_err(|e| format!("read: {e}"))?;
+        }
+
+        let text = String::from_utf8_lossy(&line).into_owned();
+        line.clear();
+
+        if text.starts_with('+') {
+            idling = true;
+            deadline = Instant::now() + REISSUE_INTERVAL;
+        } else if let Some(status) = text.strip_prefix(&format!("{idle_tag} ")) {
+            if !status.starts_with("OK") {
+                return Err(format!("IDLE failed: {}", text.trim_end()));
+            }
+            idle_tag = send_idle(reader, &mut tag).await?;
+            idling = false;
+            done_sent = false;
+            deadline = Instant::now() + RESPONSE_TIMEOUT;
+        } else if text.starts_with("* BYE") {
+            return Err(format!("server closed connection: {}", text.trim_end()));
+        } else if let Some(update) = parse_update(&text) {
+            handle_update(app, account_id, folder, update, &mut exists);
+        }
+    }
+}
+
+async fn send_idle(reader: &mut BufReader<ImapStream>, tag: &mut u32) -> Result<String, String> {
+    let idle_tag = format!("a{tag}");
+    *tag += 1;
+    reader
+        .get_mut()
+        .write_all(format!("{idle_tag} IDLE\r\n").as_bytes())
+        .await
+        .map_err(|e| format!("IDLE write: {e}"))?;
+    Ok(idle_tag)
+}
+
+fn handle_update(
+    app: &AppHandle,
+    account_id: &str,
+    folder: &str,
+    update: IdleUpdate,
+    exists: &mut u32,
+) {
+    match update {
+        IdleUpdate::Exists(n) => {
+            // EXISTS is also resent after expunges; only a larger count is new mail
+            let previous = std::mem::replace(exists, n);
+            if n > previous {
+                events::emit(
+                    app,
+                    ImapNewMail {
+                        account_id: account_id.to_string(),
+                        folder: folder.to_string(),
+                        exists: n,
+                    },
+                );
+            }
+        }
+        IdleUpdate::Expunge(seq) => {
+            *exists = exists.saturating_sub(1);
+            events::emit(
+                app,
+                ImapExpunge {
+                    account_id: account_id.to_string(),
+                    folder: folder.to_string(),
+                    seq,
+                },
+            );
+        }
+        IdleUpdate::Flags { seq, uid, flags } => events::emit(
+            app,
+            ImapFlagsChanged {
+                account_id: account_id.to_string(),
+                folder: folder.to_string(),
+                seq,
+                uid,
+                flags,
+            },
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_update() {
+        assert_eq!(
+            parse_update("* 23 EXISTS\r\n"),
+            Some(IdleUpdate::Exists(23))
+        );
+        assert_eq!(
+            parse_update("* 4 EXPUNGE\r\n"),
+            Some(IdleUpdate::Expunge(4))
+        );
+        assert_eq!(
+            parse_update("* 12 FETCH (UID 345 FLAGS (\\Seen \\Flagged))\r\n"),
+            Some(IdleUpdate::Flags {
+                seq: 12,
+                uid: Some(345),
+                flags: vec!["\\Seen".to_string(), "\\Flagged".to_string()],
+            })
+        );
+        assert_eq!(
+            parse_update("* 3 FETCH (FLAGS ())\r\n"),
+            Some(IdleUpdate::Flags {
+                seq: 3,
+                uid: None,
+                flags: Vec::new(),
+            })
+        );
+        assert_eq!(parse_update("* 2 RECENT\r\n"), None);
+        assert_eq!(parse_update("* OK Still here\r\n"), None);
+    }
+}
@@ -0,0 +1,67 @@
+//! IMAP keywords: the flags a client defines itself, as opposed to the
+//! system flags (`\Seen`, `\Flagged`, ...).
+//!
+//! Color categories are stored as keywords so other clients see them:
+//! Thunderbird's `$label1`..`$label5` for the five built-in colors and the
+//! category's own keyword for custom ones. Keywords come back on
+//! [`ImapMessage::keywords`](super::types::ImapMessage) and are set with
+//! the `keywords` argument of `imap_set_flags`. Team tags in a shared
+//! folder (`$Assigned:<member>`, `$Status:<state>`) are keywords too, and
+//! `imap_fetch_keywords` re-reads them so every client sharing the mailbox
+//! sees the same triage state.
+
+use async_imap::types::Flag;
+
+/// The keywords among a FETCH response's flags.
+pub fn from_flags(flags: &[Flag<'_>]) -> Vec<String> {
+    flags
+        .iter()
+        .filter_map(|flag| match flag {
+            Flag::Custom(name) if !name.starts_with('\\') => Some(name.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The keywords in a raw FLAGS list like `\Seen $label1 Work`.
+pub fn from_flag_list(list: &str) -> Vec<String> {
+    list.split_whitespace()
+        .filter(|flag| !flag.starts_with('\\'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Whether `keyword` can be sent as a keyword: an IMAP atom (RFC 3501
+/// `flag-keyword`), so no spaces, controls or `(){%*"\]`.
+pub fn valid_keyword(keyword: &str) -> bool {
+    !keyword.is_empty()
+        && keyword.bytes().all(|b| {
+            b.is_ascii_graphic()
+                && !matches!(b, b'(' | b')' | b'{' | b'%' | b'*' | b'"' | b'\\' | b']')
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_flag_list() {
+        assert_eq!(
+            from_flag_list("\\Seen $label1 \\Flagged Work"),
+            vec!["$label1", "Work"]
+        );
+        assert!(from_flag_list("").is_empty());
+    }
+
+    #[test]
+    fn test_valid_keyword() {
+        assert!(valid_keyword("$label3"));
+        assert!(valid_keyword("project_x"));
+        assert!(!valid_keyword(""));
+        assert!(!valid_keyword("two words"));
+        assert!(!valid_keyword("\\Seen"));
+        assert!(!valid_keyword("a)b"));
+        assert!(!valid_keyword("café"));
+    }
+}
@@ -0,0 +1,316 @@
+//! Parser for `LIST ... RETURN (SPECIAL-USE STATUS (...))` and `STATUS` responses.
+//!
+//! async-imap has no LIST-EXTENDED support, so the command is sent raw and
+//! the untagged `* LIST` / `* STATUS` lines are parsed here. The server sends
+//! each STATUS right after the LIST line of its mailbox (RFC 5819), but we
+//! match them up by name so ordering doesn't matter.
+
+use std::collections::HashMap;
+
+/// One mailbox from a LIST-STATUS response.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListStatusEntry {
+    /// Mailbox name as sent by the server (modified UTF-7).
+    pub raw_path: String,
+    pub delimiter: Option<String>,
+    /// Attributes in wire form, e.g. `\HasNoChildren`, `\Sent`.
+    pub attributes: Vec<String>,
+    /// `None` when the server sent no STATUS for this mailbox (e.g. `\Noselect`).
+    pub exists: Option<u32>,
+    pub unseen: Option<u32>,
+}
+
+/// Parse the untagged responses of a LIST-STATUS command.
+///
+/// Lines other than `* LIST` and `* STATUS` are ignored.
+pub fn parse_list_status(raw: &[u8]) -> Result<Vec<ListStatusEntry>, String> {
+    let mut entries: Vec<ListStatusEntry> = Vec::new();
+    let mut statuses: HashMap<String, (Option<u32>, Option<u32>)> = HashMap::new();
+    let mut p = Parser { buf: raw, pos: 0 };
+
+    while !p.at_end() {
+        if !p.eat(b"* ") {
+            p.skip_line();
+            continue;
+        }
+        let keyword = p.atom().to_ascii_uppercase();
+        p.skip_spaces();
+        match keyword.as_str() {
+            "LIST" => {
+                let attributes = p.paren_atoms()?;
+                p.skip_spaces();
+                let delimiter = p.nstring()?;
+                p.skip_spaces();
+                let raw_path = p.astring()?;
+                entries.push(ListStatusEntry {
+                    raw_path,
+                    delimiter,
+                    attributes,
+                    exists: None,
+                    unseen: None,
+                });
+            }
+            "STATUS" => {
+                let status = p.status()?;
+                statuses.insert(status.mailbox, (status.exists, status.unseen));
+            }
+            _ => {}
+        }
+        // Skip extended data (e.g. CHILDINFO) and the line ending
+        p.skip_line();
+    }
+
+    for entry in &mut entries {
+        if let Some((exists, unseen)) = statuses.get(&entry.raw_path) {
+            entry.exists = *exists;
+            entry.unseen = *unseen;
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Counts from an untagged `* STATUS` response.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusResponse {
+    pub mailbox: String,
+    pub exists: Option<u32>,
+    pub unseen: Option<u32>,
+    pub uidnext: Option<u32>,
+}
+
+/// Parse a single `* STATUS mailbox (...)` response; `None` for anything else.
+pub fn parse_status_response(raw: &[u8]) -> Option<StatusResponse> {
+    let mut p = Parser { buf: raw, pos: 0 };
+    if !p.eat(b"* ") || !p.atom().eq_ignore_ascii_case("STATUS") {
+        return None;
+    }
+    p.skip_spaces();
+    p.status().ok()
+}
+
+struct Parser<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn at_end(&self) -> bool {
+        self.pos >= self.buf.len()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.buf.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, prefix: &[u8]) -> bool {
+        if self.buf[self.pos..].starts_with(prefix) {
+            self.pos += prefix.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek() == Some(b' ') {
+            self.pos += 1;
+        }
+    }
+
+    /// Skip to the start of the next line, stepping over quoted strings and
+    /// literals so a CRLF inside them doesn't end the line early.
+    fn skip_line(&mut self) {
+        while let Some(c) = self.peek() {
+            match c {
+                b'\n' => {
+                    self.pos += 1;
+                    return;
+                }
+                b'"' => {
+                    let _ = self.quoted();
+                }
+                b'{' => {
+                    if self.literal().is_err() {
+                        self.pos += 1;
+                    }
+                }
+                _ => self.pos += 1,
+            }
+        }
+    }
+
+    /// Read an atom: everything up to a space, paren, or line ending.
+    fn atom(&mut self) -> String {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if matches!(c, b' ' | b'(' | b')' | b'\r' | b'\n') {
+                break;
+            }
+            self.pos += 1;
+        }
+        String::from_utf8_lossy(&self.buf[start..self.pos]).into_owned()
+    }
+
+    fn quoted(&mut self) -> Result<String, String> {
+        self.pos += 1; // opening quote
+        let mut out = Vec::new();
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            match c {
+                b'"' => return Ok(String::from_utf8_lossy(&out).into_owned()),
+                b'\\' => {
+                    if let Some(escaped) = self.peek() {
+                        out.push(escaped);
+                        self.pos += 1;
+                    }
+                }
+                _ => out.push(c),
+            }
+        }
+        Err("Unterminated quoted string in LIST response".to_string())
+    }
+
+    fn literal(&mut self) -> Result<String, String> {
+        let start = self.pos;
+        self.pos += 1; // '{'
+        let digits_start = self.pos;
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.pos += 1;
+        }
+        let len: usize = std::str::from_utf8(&self.buf[digits_start..self.pos])
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(|| "Invalid literal length in LIST response".to_string())?;
+        if !self.eat(b"}\r\n") {
+            self.pos = start;
+            return Err("Malformed literal in LIST response".to_string());
+        }
+        let end = self.pos + len;
+        if end > self.buf.len() {
+            self.pos = start;
+            return Err("Truncated literal in LIST response".to_string());
+        }
+        let value = String::from_utf8_lossy(&self.buf[self.pos..end]).into_owned();
+        self.pos = end;
+        Ok(value)
+    }
+
+    /// Read an astring (atom, quoted string, or literal).
+    fn astring(&mut self) -> Result<String, String> {
+        match self.peek() {
+            Some(b'"') => self.quoted(),
+            Some(b'{') => self.literal(),
+            Some(_) => Ok(self.atom()),
+            None => Err("Unexpected end of LIST response".to_string()),
+        }
+    }
+
+    /// Read an nstring, mapping `NIL` to `None`.
+    fn nstring(&mut self) -> Result<Option<String>, String> {
+        let rest = &self.buf[self.pos..];
+        if rest
+            .get(..3)
+            .is_some_and(|s| s.eq_ignore_ascii_case(b"NIL"))
+        {
+            self.pos += 3;
+            return Ok(None);
+        }
+        self.astring().map(Some)
+    }
+
+    /// Read the `mailbox (ITEM value ...)` part of a STATUS response.
+    fn status(&mut self) -> Result<StatusResponse, String> {
+        let mailbox = self.astring()?;
+        self.skip_spaces();
+        let items = self.paren_atoms()?;
+        let mut status = StatusResponse {
+            mailbox,
+            exists: None,
+            unseen: None,
+            uidnext: None,
+        };
+        for pair in items.chunks(2) {
+            if let [key, value] = pair {
+                match key.to_ascii_uppercase().as_str() {
+                    "MESSAGES" => status.exists = value.parse().ok(),
+                    "UNSEEN" => status.unseen = value.parse().ok(),
+                    "UIDNEXT" => status.uidnext = value.parse().ok(),
+                    _ => {}
+                }
+            }
+        }
+        Ok(status)
+    }
+
+    /// Read a flat parenthesized list of atoms, e.g. `(\HasNoChildren \Sent)`.
+    fn paren_atoms(&mut self) -> Result<Vec<String>, String> {
+        if !self.eat(b"(") {
+            return Err("Expected '(' in LIST response".to_string());
+        }
+        let mut items = Vec::new();
+        loop {
+            self.skip_spaces();
+            match self.peek() {
+                Some(b')') => {
+                    self.pos += 1;
+                    return Ok(items);
+                }
+                Some(b'\r') | Some(b'\n') | None => {
+                    return Err("Unterminated list in LIST response".to_string())
+                }
+                Some(_) => items.push(self.astring()?),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_list_status_joins_status() {
+        let raw = b"* LIST (\\HasNoChildren) \"/\" INBOX\r\n\
+* STATUS INBOX (MESSAGES 12 UNSEEN 3)\r\n\
+* LIST (\\HasNoChildren \\Sent) \"/\" \"Sent Items\"\r\n\
+* STATUS \"Sent Items\" (MESSAGES 40 UNSEEN 0)\r\n\
+* LIST (\\Noselect \\HasChildren) \"/\" Archive\r\n";
+        let entries = parse_list_status(raw).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].raw_path, "INBOX");
+        assert_eq!((entries[0].exists, entries[0].unseen), (Some(12), Some(3)));
+        assert_eq!(entries[1].raw_path, "Sent Items");
+        assert_eq!(entries[1].attributes, vec!["\\HasNoChildren", "\\Sent"]);
+        assert_eq!(entries[1].delimiter.as_deref(), Some("/"));
+        assert_eq!((entries[1].exists, entries[1].unseen), (Some(40), Some(0)));
+        assert_eq!(entries[2].exists, None);
+    }
+
+    #[test]
+    fn test_parse_list_status_literal_nil_and_extended_data() {
+        let raw = b"* LIST () NIL {5}\r\nA \"b\"\r\n\
+* LIST (\\HasChildren) \".\" \"Work\" (\"CHILDINFO\" (\"SUBSCRIBED\"))\r\n\
+* STATUS {5}\r\nA \"b\" (UNSEEN 1 MESSAGES 2)\r\n";
+        let entries = parse_list_status(raw).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].raw_path, "A \"b\"");
+        assert_eq!(entries[0].delimiter, None);
+        assert_eq!((entries[0].exists, entries[0].unseen), (Some(2), Some(1)));
+        assert_eq!(entries[1].raw_path, "Work");
+        assert_eq!(entries[1].delimiter.as_deref(), Some("."));
+    }
+
+    #[test]
+    fn test_parse_status_response() {
+        let status =
+            parse_status_response(b"* STATUS \"Sent Items\" (MESSAGES 5 UIDNEXT 44 UNSEEN 1)\r\n")
+                .unwrap();
+        assert_eq!(status.mailbox, "Sent Items");
+        assert_eq!(
+            (status.exists, status.unseen, status.uidnext),
+            (Some(5), Some(1), Some(44))
+        );
+        assert_eq!(parse_status_response(b"* 3 EXISTS\r\n"), None);
+    }
+}
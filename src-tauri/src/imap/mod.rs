@@ -1,2 +1,15 @@
+pub mod bodystructure;
+pub mod catenate;
 pub mod client;
+pub mod date;
+pub mod folder_tree;
+pub mod folder_watch;
+pub mod idle;
+pub mod keywords;
+pub mod list_status;
+pub mod notify;
+pub mod session_pool;
+pub mod tnef;
 pub mod types;
+pub mod uid_set;
+pub mod urlauth;
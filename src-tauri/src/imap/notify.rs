@@ -0,0 +1,240 @@
+//! Multi-folder push via IMAP NOTIFY (RFC 5465).
+//!
+//! One connection per account registers for new/expunged/flag-change events
+//! on all synced folders. The server then pushes a `STATUS` response for any
+//! folder that changes, which we forward to the frontend as an event so it
+//! can sync that folder right away instead of waiting for the next poll.
+//!
+//! async-imap can't read unsolicited responses outside IDLE, so this runs on
+//! a raw connection like the raw fetch fallback.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+
+use super::client::{capabilities_include, raw_connect_and_login, raw_send_and_wait, ImapStream};
+use super::list_status::parse_status_response;
+use super::types::ImapConfig;
+use crate::events::{self, ImapNotify, ImapNotifyStopped};
+
+/// Send a NOOP this often so servers don't drop the connection for
+/// inactivity (RFC 3501 requires at least a 30 minute autologout timer).
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Running NOTIFY connections, keyed by account id.
+#[derive(Default)]
+pub struct NotifyWatchers(Mutex<HashMap<String, tauri::async_runtime::JoinHandle<()>>>);
+
+/// Quote a mailbox name as an IMAP quoted string.
+fn quote_mailbox(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn notify_set_command(tag: &str, folders: &[String]) -> String {
+    let mailboxes: Vec<String> = folders.iter().map(|f| quote_mailbox(f)).collect();
+    format!(
+        "{tag} NOTIFY SET (mailboxes ({}) (MessageNew MessageExpunge FlagChange))\r\n",
+        mailboxes.join(" ")
+    )
+}
+
+/// Length of a literal announced at the end of a line (`... {12}\r\n`).
+pub(super) fn trailing_literal_len(line: &[u8]) -> Option<usize> {
+    let line = line
+        .strip_suffix(b"\r\n")
+        .or_else(|| line.strip_suffix(b"\n"))?;
+    let line = line.strip_suffix(b"}")?;
+    let open = line.iter().rposition(|&c| c == b'{')?;
+    std::str::from_utf8(&line[open + 1..]).ok()?.parse().ok()
+}
+
+/// Start watching `folders` (raw paths) for an account.
+///
+/// Returns `Ok(false)` if the server doesn't support NOTIFY, in which case
+/// the frontend keeps relying on polling.
+pub async fn start(
+    app: AppHandle,
+    account_id: String,
+    config: ImapConfig,
+    folders: Vec<String>,
+) -> Result<bool, String> {
+    stop(&app, Some(&account_id));
+
+    if folders.is_empty() {
+        return Ok(false);
+    }
+
+    let mut reader = raw_connect_and_login(&config).await?;
+
+    let caps = raw_send_and_wait(&mut reader, b"a2 CAPABILITY\r\n", "a2").await?;
+    if !capabilities_include(&caps, "NOTIFY") {
+        let _ = reader.get_mut().write_all(b"a3 LOGOUT\r\n").await;
+        return Ok(false);
+    }
+
+    let cmd = notify_set_command("a3", &folders);
+    raw_send_and_wait(&mut reader, cmd.as_bytes(), "a3").await?;
+
+    log::info!(
+        "NOTIFY registered for {} folder(s) of account {account_id}",
+        folders.len()
+    );
+
+    let task_app = app.clone();
+    let task_account = account_id.clone();
+    let handle = tauri::async_runtime::spawn(async move {
+        let error = watch(&task_app, &task_account, &mut reader).await.err();
+        if let Some(e) = &error {
+            log::warn!("NOTIFY connection for account {task_account} ended: {e}");
+        }
+        events::emit(
+            &task_app,
+            ImapNotifyStopped {
+                account_id: task_account,
+                error,
+            },
+        );
+    });
+
+    // A concurrent start for the same account may have finished first
+    let previous = app
+        .state::<NotifyWatchers>()
+        .0
+        .lock()
+        .unwrap()
+        .insert(account_id, handle);
+    if let Some(previous) = previous {
+        previous.abort();
+    }
+
+    Ok(true)
+}
+
+/// Stop the NOTIFY connection for one account, or all of them.
+pub fn stop(app: &AppHandle, account_id: Option<&str>) {
+    let watchers = app.state::<NotifyWatchers>();
+    let mut watchers = watchers.0.lock().unwrap();
+    match account_id {
+        Some(id) => {
+            if let Some(handle) = watchers.remove(id) {
+                handle.abort();
+            }
+        }
+        None => {
+            for (_, handle) in watchers.drain() {
+                handle.abort();
+            }
+        }
+    }
+}
+
+/// Read pushed responses until the connection drops.
+async fn watch(
+    app: &AppHandle,
+    account_id: &str,
+    reader: &mut BufReader<ImapStream>,
+) -> Result<(), String> {
+    let mut tag = 4u32;
+    // Kept across keepalive timeouts: a cancelled read leaves partial data here
+    let mut line = Vec::new();
+
+    loop {
+        match tokio::time::timeout(KEEPALIVE_INTERVAL, reader.read_until(b'\n', &mut line)).await {
+            Err(_) => {
+                let noop = format!("a{tag} NOOP\r\n");
+                tag += 1;
+                reader
+                    .get_mut()
+                    .write_all(noop.as_bytes())
+                    .await
+                    .map_err(|e| format!("NOOP write: {e}"))?;
+                continue;
+            }
+            Ok(Ok(0)) => return Err("connection closed".to_string()),
+            Ok(Err(e)) => return Err(format!("read: {e}")),
+            Ok(Ok(_)) => {}
+        }
+
+        // Pull in literals (e.g. mailbox names sent as {n}) and the rest of the line
+        while let Some(len) = trailing_literal_len(&line) {
+            let start = line.len();
+            line.resize(start + len, 0);
+            reader
+                .read_exact(&mut line[start..])
+                .await
+                .map_err(|e| format!("read literal: {e}"))?;
+            reader
+                .read_until(b'\n', &mut line)
+                .await
+                .map_err(|e| format!("read: {e}"))?;
+        }
+
+        handle_response(app, account_id, &line)?;
+        line.clear();
+    }
+}
+
+fn handle_response(app: &AppHandle, account_id: &str, line: &[u8]) -> Result<(), String> {
+    if let Some(status) = parse_status_response(line) {
+        events::emit(
+            app,
+            ImapNotify {
+                account_id: account_id.to_string(),
+                folder: Some(status.mailbox),
+                exists: status.exists,
+                unseen: status.unseen,
+            },
+        );
+        return Ok(());
+    }
+
+    let text = String::from_utf8_lossy(line);
+    if text.starts_with("* BYE") {
+        return Err(format!("server closed connection: {}", text.trim_end()));
+    }
+    if text.contains("[NOTIFICATIONOVERFLOW]") {
+        // Events were dropped; the frontend has to check every folder
+        events::emit(
+            app,
+            ImapNotify {
+                account_id: account_id.to_string(),
+                folder: None,
+                exists: None,
+                unseen: None,
+            },
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notify_set_command_quotes_mailboxes() {
+        let folders = vec!["INBOX".to_string(), "Work \"2024\"".to_string()];
+        assert_eq!(
+            notify_set_command("a3", &folders),
+            "a3 NOTIFY SET (mailboxes (\"INBOX\" \"Work \\\"2024\\\"\") (MessageNew MessageExpunge FlagChange))\r\n"
+        );
+    }
+
+    #[test]
+    fn test_trailing_literal_len() {
+        assert_eq!(trailing_literal_len(b"* STATUS {5}\r\n"), Some(5));
+        assert_eq!(
+            trailing_literal_len(b"* STATUS INBOX (MESSAGES 1)\r\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_capabilities_include() {
+        let resp = "* CAPABILITY IMAP4rev1 IDLE NOTIFY LIST-STATUS\r\na2 OK done\r\n";
+        assert!(capabilities_include(resp, "NOTIFY"));
+        assert!(!capabilities_include(resp, "CONDSTORE"));
+    }
+}
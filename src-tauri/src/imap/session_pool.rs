@@ -0,0 +1,240 @@
+//! Reusing authenticated IMAP sessions across commands.
+//!
+//! Connecting costs a TCP and TLS handshake plus LOGIN and CAPABILITY round
+//! trips, which on a distant server takes far longer than the command that
+//! follows. Commands check a session out of [`ImapSessionManager`] and check
+//! it back in when they're done, so browsing folders pays one connect per
+//! account instead of one per click.
+//!
+//! A session whose command failed is logged out instead of reused, since the
+//! failure may have left a response half read. One that sat idle for a while
+//! is checked with NOOP before reuse and replaced by a fresh login if the
+//! server dropped it; one idle for longer than servers tend to keep
+//! connections open is discarded without asking.
+//!
+//! An XOAUTH2 login the server rejects, e.g. for a token revoked before its
+//! expiry, is retried once with a token the [`oauth::TokenManager`] has just
+//! refreshed.
+
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::client::{self as imap_client, ImapSession};
+use super::types::ImapConfig;
+use crate::oauth;
+use crate::rate_limit;
+
+/// Idle sessions older than this are logged out rather than reused. RFC 3501
+/// lets servers drop connections after 30 minutes; many do it sooner.
+const MAX_IDLE: Duration = Duration::from_secs(5 * 60);
+/// Sessions idle at least this long are checked with NOOP before reuse.
+const CHECK_AFTER: Duration = Duration::from_secs(60);
+const NOOP_TIMEOUT: Duration = Duration::from_secs(10);
+const LOGOUT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Idle sessions kept per account; more concurrent commands open more
+/// connections, and the extra ones are logged out when they finish.
+const MAX_IDLE_SESSIONS: usize = 2;
+
+/// Sessions are only shared between commands for the same account and login,
+/// so re-registering an account with another server or user never reuses a
+/// session of the old one.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct SessionKey {
+    account_id: String,
+    host: String,
+    port: u16,
+    username: String,
+    authorize_as: Option<String>,
+}
+
+impl SessionKey {
+    fn new(account_id: &str, config: &ImapConfig) -> Self {
+        Self {
+            account_id: account_id.to_string(),
+            host: config.host.clone(),
+            port: config.port,
+            username: config.username.clone(),
+            authorize_as: config.authorize_as.clone(),
+        }
+    }
+}
+
+struct IdleSession {
+    session: ImapSession,
+    since: Instant,
+}
+
+/// A session checked out of the pool. Dereferences to the IMAP session;
+/// hand it back with [`ImapSessionManager::checkin`]. Dropping it closes the
+/// connection.
+pub(crate) struct PooledSession {
+    key: SessionKey,
+    session: ImapSession,
+}
+
+impl Deref for PooledSession {
+    type Target = ImapSession;
+
+    fn deref(&self) -> &ImapSession {
+        &self.session
+    }
+}
+
+impl DerefMut for PooledSession {
+    fn deref_mut(&mut self) -> &mut ImapSession {
+        &mut self.session
+    }
+}
+
+/// Managed state: authenticated sessions not in use, per account.
+#[derive(Default)]
+pub struct ImapSessionManager {
+    idle: Mutex<HashMap<SessionKey, Vec<IdleSession>>>,
+}
+
+impl ImapSessionManager {
+    /// An authenticated session for `account_id`: an idle one if it's still
+    /// alive, otherwise a new connection.
+    pub async fn checkout(
+        &self,
+        account_id: &str,
+        config: &ImapConfig,
+    ) -> Result<PooledSession, String> {
+        let key = SessionKey::new(account_id, config);
+        while let Some(idle) = self.take_idle(&key) {
+            let mut session = idle.session;
+            if idle.since.elapsed() >= CHECK_AFTER && !is_alive(&mut session).await {
+                log::info!("Idle IMAP session for {account_id} was dropped by the server");
+                continue;
+            }
+            // The connection was paid for once; each command still counts
+            rate_limit::acquire_imap(config).await;
+            return Ok(PooledSession { key, session });
+        }
+        let session = match imap_client::connect(config).await {
+            Err(e) if config.auth_method == "oauth2" && oauth::is_token_rejected(&e) => {
+                let Some(token) = oauth::tokens().refreshed(account_id).await? else {
+                    return Err(e);
+                };
+                log::info!("Retrying the IMAP login of {account_id} with a refreshed token");
+                let config = ImapConfig {
+                    password: token,
+                    ..config.clone()
+                };
+                imap_client::connect(&config).await?
+            }
+            result => result?,
+        };
+        Ok(PooledSession { key, session })
+    }
+
+    /// Return a session after its command. It's kept for reuse if the command
+    /// succeeded and logged out otherwise.
+    pub fn checkin<T>(&self, pooled: PooledSession, result: &Result<T, String>) {
+        let PooledSession { key, mut session } = pooled;
+        if result.is_err() {
+            logout_later(session);
+            return;
+        }
+        drain_unsolicited(&mut session);
+
+        let mut idle = self.idle.lock().unwrap();
+        let sessions = idle.entry(key).or_default();
+        if sessions.len() >= MAX_IDLE_SESSIONS {
+            drop(idle);
+            logout_later(session);
+            return;
+        }
+        sessions.push(IdleSession {
+            session,
+            since: Instant::now(),
+        });
+    }
+
+    /// Log out the idle sessions of `account_id`, or of every account.
+    /// Sessions checked out right now are returned and kept as usual.
+    pub async fn close(&self, account_id: Option<&str>) {
+        for session in self.take_all(account_id) {
+            logout(session).await;
+        }
+    }
+
+    /// [`close`](Self::close) without waiting for the servers to answer.
+    pub fn close_later(&self, account_id: Option<&str>) {
+        for session in self.take_all(account_id) {
+            logout_later(session);
+        }
+    }
+
+    fn take_all(&self, account_id: Option<&str>) -> Vec<ImapSession> {
+        let mut idle = self.idle.lock().unwrap();
+        let keys: Vec<SessionKey> = idle
+            .keys()
+            .filter(|k| account_id.map_or(true, |id| k.account_id == id))
+            .cloned()
+            .collect();
+        keys.iter()
+            .filter_map(|k| idle.remove(k))
+            .flatten()
+            .map(|s| s.session)
+            .collect()
+    }
+
+    /// The most recently used idle session for `key`, discarding any that
+    /// have been idle too long.
+    fn take_idle(&self, key: &SessionKey) -> Option<IdleSession> {
+        let mut idle = self.idle.lock().unwrap();
+        let sessions = idle.get_mut(key)?;
+        let (fresh, stale): (Vec<_>, Vec<_>) = std::mem::take(sessions)
+            .into_iter()
+            .partition(|s| s.since.elapsed() < MAX_IDLE);
+        *sessions = fresh;
+        let taken = sessions.pop();
+        if sessions.is_empty() {
+            idle.remove(key);
+        }
+        drop(idle);
+
+        for s in stale {
+            logout_later(s.session);
+        }
+        taken
+    }
+}
+
+async fn is_alive(session: &mut ImapSession) -> bool {
+    let alive = matches!(
+        tokio::time::timeout(NOOP_TIMEOUT, session.noop()).await,
+        Ok(Ok(()))
+    );
+    drain_unsolicited(session);
+    alive
+}
+
+/// Discard EXISTS/EXPUNGE and other untagged responses the server sent while
+/// the session was used, so they don't pile up in async-imap's channel.
+fn drain_unsolicited(session: &mut ImapSession) {
+    while session.unsolicited_responses.try_recv().is_ok() {}
+}
+
+async fn logout(mut session: ImapSession) {
+    let _ = tokio::time::timeout(LOGOUT_TIMEOUT, session.logout()).await;
+}
+
+fn logout_later(session: ImapSession) {
+    tauri::async_runtime::spawn(logout(session));
+}
+
+/// Log out the idle IMAP sessions of an account, e.g. before the user signs
+/// out, or of every account if `account_id` is `None`.
+#[tauri::command]
+#[specta::specta]
+pub async fn imap_close_account_sessions(
+    sessions: tauri::State<'_, ImapSessionManager>,
+    account_id: Option<String>,
+) -> Result<(), String> {
+    sessions.close(account_id.as_deref()).await;
+    Ok(())
+}
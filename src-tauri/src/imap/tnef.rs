@@ -0,0 +1,581 @@
+//! Decoder for TNEF (`application/ms-tnef`, usually `winmail.dat`).
+//!
+//! Outlook sometimes wraps a message's attachments and rich-text body in a
+//! single TNEF blob. We unpack it so the files show up as ordinary
+//! attachments. Each unpacked item gets a synthetic part id
+//! (`<section>:tnef:<n>`, or `<section>:tnef:rtf` for the body) that
+//! `fetch_attachment` resolves by decoding the TNEF part again.
+//!
+//! Format references: MS-OXTNEF (stream layout, MAPI properties) and
+//! MS-OXRTFCP (compressed RTF).
+
+const TNEF_SIGNATURE: u32 = 0x223E_9F78;
+
+const LVL_MESSAGE: u8 = 0x01;
+const LVL_ATTACHMENT: u8 = 0x02;
+
+// Attribute ids (type in the high word, id in the low word)
+const ATT_ATTACH_REND_DATA: u32 = 0x0006_9002;
+const ATT_ATTACH_TITLE: u32 = 0x0001_8010;
+const ATT_ATTACH_DATA: u32 = 0x0006_800F;
+const ATT_ATTACHMENT: u32 = 0x0006_9005;
+const ATT_MAPI_PROPS: u32 = 0x0006_9003;
+
+// MAPI property ids
+const PR_RTF_COMPRESSED: u16 = 0x1009;
+const PR_ATTACH_DATA_OBJ: u16 = 0x3701;
+const PR_ATTACH_LONG_FILENAME: u16 = 0x3707;
+const PR_ATTACH_MIME_TAG: u16 = 0x370E;
+const PR_ATTACH_CONTENT_ID: u16 = 0x3712;
+
+// MAPI property types
+const PT_STRING8: u16 = 0x001E;
+const PT_UNICODE: u16 = 0x001F;
+const PT_BINARY: u16 = 0x0102;
+const PT_OBJECT: u16 = 0x000D;
+const MV_FLAG: u16 = 0x1000;
+
+/// Marker between a MIME section and the TNEF item in synthetic part ids.
+const PART_ID_MARKER: &str = ":tnef:";
+const RTF_BODY_KEY: &str = "rtf";
+
+/// Filename given to the unpacked rich-text body.
+pub const RTF_BODY_FILENAME: &str = "message.rtf";
+
+/// A file unpacked from a TNEF stream.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TnefAttachment {
+    pub filename: Option<String>,
+    pub mime_type: Option<String>,
+    pub content_id: Option<String>,
+    pub data: Vec<u8>,
+}
+
+/// Everything we extract from a TNEF stream.
+#[derive(Debug, Default)]
+pub struct TnefContents {
+    pub attachments: Vec<TnefAttachment>,
+    /// Decompressed RTF body, if the message had one.
+    pub rtf_body: Option<Vec<u8>>,
+}
+
+/// Which item of a TNEF part a synthetic part id refers to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TnefItem {
+    Attachment(usize),
+    RtfBody,
+}
+
+pub fn is_tnef(mime_type: &str, filename: &str) -> bool {
+    mime_type.eq_ignore_ascii_case("application/ms-tnef")
+        || mime_type.eq_ignore_ascii_case("application/vnd.ms-tnef")
+        || filename.eq_ignore_ascii_case("winmail.dat")
+}
+
+pub fn part_id(section: &str, item: TnefItem) -> String {
+    match item {
+        TnefItem::Attachment(index) => format!("{section}{PART_ID_MARKER}{index}"),
+        TnefItem::RtfBody => format!("{section}{PART_ID_MARKER}{RTF_BODY_KEY}"),
+    }
+}
+
+/// Split a synthetic part id into the MIME section of the TNEF part and the
+/// item inside it. `None` for ordinary part ids.
+pub fn parse_part_id(part_id: &str) -> Option<(&str, TnefItem)> {
+    let (section, key) = part_id.split_once(PART_ID_MARKER)?;
+    let item = if key == RTF_BODY_KEY {
+        TnefItem::RtfBody
+    } else {
+        TnefItem::Attachment(key.parse().ok()?)
+    };
+    Some((section, item))
+}
+
+impl TnefContents {
+    /// Take the bytes of one item.
+    pub fn take(mut self, item: TnefItem) -> Option<Vec<u8>> {
+        match item {
+            TnefItem::Attachment(index) => {
+                (index < self.attachments.len()).then(|| self.attachments.swap_remove(index).data)
+            }
+            TnefItem::RtfBody => self.rtf_body,
+        }
+    }
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn remaining(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], String> {
+        if len > self.remaining() {
+            return Err("Truncated TNEF data".to_string());
+        }
+        let slice = &self.buf[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        let b = self.bytes(2)?;
+        Ok(u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let b = self.bytes(4)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    /// Skip padding to the next 4-byte boundary after a value of `len` bytes.
+    fn skip_padding(&mut self, len: usize) -> Result<(), String> {
+        let pad = (4 - len % 4) % 4;
+        self.bytes(pad.min(self.remaining())).map(|_| ())
+    }
+}
+
+/// Decode a TNEF stream.
+pub fn decode(data: &[u8]) -> Result<TnefContents, String> {
+    let mut r = Reader { buf: data, pos: 0 };
+    if r.u32()? != TNEF_SIGNATURE {
+        return Err("Not a TNEF stream".to_string());
+    }
+    let _key = r.u16()?;
+
+    let mut contents = TnefContents::default();
+    while r.remaining() > 0 {
+        let level = r.u8()?;
+        let id = r.u32()?;
+        let len = r.u32()? as usize;
+        let value = r.bytes(len)?;
+        let _checksum = r.u16()?;
+
+        match (level, id) {
+            (LVL_ATTACHMENT, ATT_ATTACH_REND_DATA) => {
+                contents.attachments.push(TnefAttachment::default());
+            }
+            (LVL_ATTACHMENT, ATT_ATTACH_TITLE) => {
+                if let Some(att) = contents.attachments.last_mut() {
+                    // The long filename from the MAPI properties wins
+                    att.filename.get_or_insert_with(|| string8(value));
+                }
+            }
+            (LVL_ATTACHMENT, ATT_ATTACH_DATA) => {
+                if let Some(att) = contents.attachments.last_mut() {
+                    att.data = value.to_vec();
+                }
+            }
+            (LVL_ATTACHMENT, ATT_ATTACHMENT) => {
+                if let Some(att) = contents.attachments.last_mut() {
+                    apply_attachment_props(att, value)?;
+                }
+            }
+            (LVL_MESSAGE, ATT_MAPI_PROPS) => {
+                for prop in parse_mapi_props(value)? {
+                    if prop.id == PR_RTF_COMPRESSED {
+                        if let Some(compressed) = prop.values.first() {
+                            // The attachments are still worth having without the body
+                            match decompress_rtf(compressed) {
+                                Ok(rtf) => contents.rtf_body = Some(rtf),
+                                Err(e) => log::warn!("Skipping unreadable TNEF RTF body: {e}"),
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    contents.attachments.retain(|a| !a.data.is_empty());
+    Ok(contents)
+}
+
+fn apply_attachment_props(att: &mut TnefAttachment, value: &[u8]) -> Result<(), String> {
+    for prop in parse_mapi_props(value)? {
+        let Some(first) = prop.values.first() else {
+            continue;
+        };
+        match prop.id {
+            PR_ATTACH_LONG_FILENAME => att.filename = Some(prop_string(prop.ty, first)),
+            PR_ATTACH_MIME_TAG => att.mime_type = Some(prop_string(prop.ty, first)),
+            PR_ATTACH_CONTENT_ID => att.content_id = Some(prop_string(prop.ty, first)),
+            PR_ATTACH_DATA_OBJ if prop.ty == PT_BINARY && att.data.is_empty() => {
+                att.data = first.clone();
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+struct MapiProp {
+    ty: u16,
+    id: u16,
+    values: Vec<Vec<u8>>,
+}
+
+/// Size of a fixed-width MAPI value as stored in TNEF (before padding).
+fn fixed_size(ty: u16) -> Option<usize> {
+    match ty {
+        0x0002 | 0x0003 | 0x0004 | 0x000A | 0x000B => Some(4),
+        0x0005 | 0x0006 | 0x0007 | 0x0014 | 0x0040 => Some(8),
+        0x0048 => Some(16),
+        _ => None,
+    }
+}
+
+fn parse_mapi_props(data: &[u8]) -> Result<Vec<MapiProp>, String> {
+    let mut r = Reader { buf: data, pos: 0 };
+    let count = r.u32()?;
+    let mut props = Vec::new();
+
+    for _ in 0..count {
+        let ty = r.u16()?;
+        let id = r.u16()?;
+
+        // Named properties carry a GUID and a name or numeric id
+        if id >= 0x8000 {
+            r.bytes(16)?;
+            if r.u32()? == 0 {
+                r.u32()?;
+            } else {
+                let name_len = r.u32()? as usize;
+                r.bytes(name_len)?;
+                r.skip_padding(name_len)?;
+            }
+        }
+
+        let base = ty & !MV_FLAG;
+        let is_var = matches!(base, PT_STRING8 | PT_UNICODE | PT_BINARY | PT_OBJECT);
+        let value_count = if ty & MV_FLAG != 0 || is_var {
+            r.u32()?
+        } else {
+            1
+        };
+
+        let mut values = Vec::new();
+        for _ in 0..value_count {
+            if is_var {
+                let len = r.u32()? as usize;
+                values.push(r.bytes(len)?.to_vec());
+                r.skip_padding(len)?;
+            } else {
+                let size = fixed_size(base)
+                    .ok_or_else(|| format!("Unsupported MAPI property type {base:#06x}"))?;
+                values.push(r.bytes(size)?.to_vec());
+            }
+        }
+        props.push(MapiProp {
+            ty: base,
+            id,
+            values,
+        });
+    }
+
+    Ok(props)
+}
+
+fn string8(value: &[u8]) -> String {
+    String::from_utf8_lossy(value)
+        .trim_end_matches('\0')
+        .trim()
+        .to_string()
+}
+
+fn prop_string(ty: u16, value: &[u8]) -> String {
+    if ty == PT_UNICODE {
+        let units: Vec<u16> = value
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+            .trim_end_matches('\0')
+            .trim()
+            .to_string()
+    } else {
+        string8(value)
+    }
+}
+
+/// Dictionary preload for compressed RTF (MS-OXRTFCP 2.1.3.1.1).
+const RTF_PREBUF: &[u8] = b"{\\rtf1\\ansi\\mac\\deff0\\deftab720{\\fonttbl;}{\\f0\\fnil \\froman \\fswiss \\fmodern \\fscript \\fdecor MS Sans SerifSymbolArialTimes New RomanCourier{\\colortbl\\red0\\green0\\blue0\r\n\\par \\pard\\plain\\f0\\fs20\\b\\i\\u\\tab\\tx";
+
+const RTF_COMPRESSED: u32 = 0x7546_5A4C; // "LZFu"
+const RTF_UNCOMPRESSED: u32 = 0x414C_454D; // "MELA"
+
+/// Decompress a PR_RTF_COMPRESSED value.
+pub fn decompress_rtf(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut r = Reader { buf: data, pos: 0 };
+    let comp_size = r.u32()? as usize;
+    let raw_size = r.u32()? as usize;
+    let comp_type = r.u32()?;
+    let _crc = r.u32()?;
+
+    // comp_size counts the 12 header bytes after itself; a hostile or
+    // truncated header can put it before the body or past the buffer
+    let end = comp_size.saturating_add(4).min(data.len()).max(r.pos);
+
+    match comp_type {
+        RTF_UNCOMPRESSED => {
+            let body = &data[r.pos..end];
+            Ok(body[..raw_size.min(body.len())].to_vec())
+        }
+        RTF_COMPRESSED => {
+            let mut dict = [0u8; 4096];
+            dict[..RTF_PREBUF.len()].copy_from_slice(RTF_PREBUF);
+            let mut write_pos = RTF_PREBUF.len();
+            // Each input byte expands to at most 8 bytes, whatever raw_size claims
+            let mut out = Vec::with_capacity(raw_size.min(data.len() * 8));
+            let mut pos = r.pos;
+
+            'outer: while pos < end && out.len() < raw_size {
+                let control = data[pos];
+                pos += 1;
+                for bit in 0..8 {
+                    if pos >= end {
+                        break 'outer;
+                    }
+                    if control & (1 << bit) == 0 {
+                        let byte = data[pos];
+                        pos += 1;
+                        out.push(byte);
+                        dict[write_pos] = byte;
+                        write_pos = (write_pos + 1) % 4096;
+                    } else {
+                        if pos + 1 >= end {
+                            break 'outer;
+                        }
+                        let token = u16::from_be_bytes([data[pos], data[pos + 1]]);
+                        pos += 2;
+                        let offset = (token >> 4) as usize;
+                        let length = (token & 0x0F) as usize + 2;
+                        // A reference to the current write position marks the end
+                        if offset == write_pos {
+                            break 'outer;
+                        }
+                        for i in 0..length {
+                            let byte = dict[(offset + i) % 4096];
+                            out.push(byte);
+                            dict[write_pos] = byte;
+                            write_pos = (write_pos + 1) % 4096;
+                        }
+                    }
+                }
+            }
+
+            out.truncate(raw_size);
+            Ok(out)
+        }
+        other => Err(format!("Unknown compressed RTF type {other:#010x}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attribute(level: u8, id: u32, value: &[u8]) -> Vec<u8> {
+        let mut out = vec![level];
+        out.extend_from_slice(&id.to_le_bytes());
+        out.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        out.extend_from_slice(value);
+        let checksum = value.iter().map(|&b| b as u32).sum::<u32>() as u16;
+        out.extend_from_slice(&checksum.to_le_bytes());
+        out
+    }
+
+    fn unicode_prop(id: u16, text: &str) -> Vec<u8> {
+        let mut encoded: Vec<u8> = text
+            .encode_utf16()
+            .chain([0])
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        let len = encoded.len() as u32;
+        encoded.resize(encoded.len().div_ceil(4) * 4, 0);
+        let mut out = Vec::new();
+        out.extend_from_slice(&PT_UNICODE.to_le_bytes());
+        out.extend_from_slice(&id.to_le_bytes());
+        out.extend_from_slice(&1u32.to_le_bytes());
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&encoded);
+        out
+    }
+
+    fn binary_prop(id: u16, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&PT_BINARY.to_le_bytes());
+        out.extend_from_slice(&id.to_le_bytes());
+        out.extend_from_slice(&1u32.to_le_bytes());
+        out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        out.extend_from_slice(data);
+        out.resize(out.len().div_ceil(4) * 4, 0);
+        out
+    }
+
+    #[test]
+    fn test_rtf_prebuf_length() {
+        assert_eq!(RTF_PREBUF.len(), 207);
+    }
+
+    #[test]
+    fn test_decompress_rtf_spec_example() {
+        // MS-OXRTFCP 3.1.1
+        let compressed = [
+            0x2d, 0x00, 0x00, 0x00, 0x2b, 0x00, 0x00, 0x00, 0x4c, 0x5a, 0x46, 0x75, 0xf1, 0xc5,
+            0xc7, 0xa7, 0x03, 0x00, 0x0a, 0x00, 0x72, 0x63, 0x70, 0x67, 0x31, 0x32, 0x35, 0x42,
+            0x32, 0x0a, 0xf3, 0x20, 0x68, 0x65, 0x6c, 0x09, 0x00, 0x20, 0x62, 0x77, 0x05, 0xb0,
+            0x6c, 0x64, 0x7d, 0x0a, 0x80, 0x0f, 0xa0,
+        ];
+        let rtf = decompress_rtf(&compressed).unwrap();
+        assert_eq!(
+            String::from_utf8(rtf).unwrap(),
+            "{\\rtf1\\ansi\\ansicpg1252\\pard hello world}\r\n"
+        );
+    }
+
+    #[test]
+    fn test_decompress_rtf_truncated() {
+        assert!(decompress_rtf(&[0x2d, 0x00, 0x00]).is_err());
+
+        // Spec example cut off mid-stream: what was decoded so far survives
+        let compressed = [
+            0x2d, 0x00, 0x00, 0x00, 0x2b, 0x00, 0x00, 0x00, 0x4c, 0x5a, 0x46, 0x75, 0xf1, 0xc5,
+            0xc7, 0xa7, 0x03, 0x00, 0x0a, 0x00, 0x72, 0x63, 0x70, 0x67,
+        ];
+        let rtf = decompress_rtf(&compressed).unwrap();
+        assert!(rtf.starts_with(b"{\\rtf1\\ansi\\ansicpg"));
+    }
+
+    #[test]
+    fn test_decompress_rtf_hostile_header() {
+        let header = |comp_size: u32, raw_size: u32, comp_type: u32| {
+            let mut out = comp_size.to_le_bytes().to_vec();
+            out.extend_from_slice(&raw_size.to_le_bytes());
+            out.extend_from_slice(&comp_type.to_le_bytes());
+            out.extend_from_slice(&0u32.to_le_bytes());
+            out
+        };
+
+        // comp_size smaller than the header itself
+        let mut data = header(0, 5, RTF_UNCOMPRESSED);
+        data.extend_from_slice(b"hello");
+        assert_eq!(decompress_rtf(&data).unwrap(), b"");
+        let mut data = header(0, 5, RTF_COMPRESSED);
+        data.extend_from_slice(&[0x00, b'a']);
+        assert_eq!(decompress_rtf(&data).unwrap(), b"");
+
+        // comp_size past the end of the buffer
+        let mut data = header(u32::MAX, 5, RTF_UNCOMPRESSED);
+        data.extend_from_slice(b"hello world");
+        assert_eq!(decompress_rtf(&data).unwrap(), b"hello");
+
+        // raw_size far larger than the input could ever expand to
+        let mut data = header(u32::MAX, u32::MAX, RTF_COMPRESSED);
+        data.extend_from_slice(&[0x00, b'a', b'b']);
+        assert_eq!(decompress_rtf(&data).unwrap(), b"ab");
+
+        assert!(decompress_rtf(&header(16, 0, 0x1234_5678)).is_err());
+    }
+
+    #[test]
+    fn test_decode_attachments() {
+        let mut stream = TNEF_SIGNATURE.to_le_bytes().to_vec();
+        stream.extend_from_slice(&0x1234u16.to_le_bytes());
+
+        // First attachment: short title, overridden by the long filename
+        stream.extend(attribute(LVL_ATTACHMENT, ATT_ATTACH_REND_DATA, &[0; 14]));
+        stream.extend(attribute(
+            LVL_ATTACHMENT,
+            ATT_ATTACH_TITLE,
+            b"REPORT~1.DOC\0",
+        ));
+        stream.extend(attribute(LVL_ATTACHMENT, ATT_ATTACH_DATA, b"doc bytes"));
+        let mut props = 2u32.to_le_bytes().to_vec();
+        props.extend(unicode_prop(
+            PR_ATTACH_LONG_FILENAME,
+            "Quarterly report.docx",
+        ));
+        props.extend(unicode_prop(PR_ATTACH_MIME_TAG, "application/msword"));
+        stream.extend(attribute(LVL_ATTACHMENT, ATT_ATTACHMENT, &props));
+
+        // Second attachment: title only
+        stream.extend(attribute(LVL_ATTACHMENT, ATT_ATTACH_REND_DATA, &[0; 14]));
+        stream.extend(attribute(LVL_ATTACHMENT, ATT_ATTACH_TITLE, b"notes.txt\0"));
+        stream.extend(attribute(LVL_ATTACHMENT, ATT_ATTACH_DATA, b"hello"));
+
+        let contents = decode(&stream).unwrap();
+        assert_eq!(contents.attachments.len(), 2);
+        assert_eq!(
+            contents.attachments[0].filename.as_deref(),
+            Some("Quarterly report.docx")
+        );
+        assert_eq!(
+            contents.attachments[0].mime_type.as_deref(),
+            Some("application/msword")
+        );
+        assert_eq!(contents.attachments[0].data, b"doc bytes");
+        assert_eq!(
+            contents.attachments[1].filename.as_deref(),
+            Some("notes.txt")
+        );
+        assert!(contents.rtf_body.is_none());
+
+        assert_eq!(
+            contents.take(TnefItem::Attachment(1)),
+            Some(b"hello".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_decode_keeps_attachments_with_bad_rtf_body() {
+        let mut stream = TNEF_SIGNATURE.to_le_bytes().to_vec();
+        stream.extend_from_slice(&0x1234u16.to_le_bytes());
+
+        // Header with an unknown compression type
+        let mut rtf = 16u32.to_le_bytes().to_vec();
+        rtf.extend_from_slice(&0u32.to_le_bytes());
+        rtf.extend_from_slice(&0x1234_5678u32.to_le_bytes());
+        rtf.extend_from_slice(&0u32.to_le_bytes());
+        let mut props = 1u32.to_le_bytes().to_vec();
+        props.extend(binary_prop(PR_RTF_COMPRESSED, &rtf));
+        stream.extend(attribute(LVL_MESSAGE, ATT_MAPI_PROPS, &props));
+
+        stream.extend(attribute(LVL_ATTACHMENT, ATT_ATTACH_REND_DATA, &[0; 14]));
+        stream.extend(attribute(LVL_ATTACHMENT, ATT_ATTACH_TITLE, b"notes.txt\0"));
+        stream.extend(attribute(LVL_ATTACHMENT, ATT_ATTACH_DATA, b"hello"));
+
+        let contents = decode(&stream).unwrap();
+        assert!(contents.rtf_body.is_none());
+        assert_eq!(contents.attachments.len(), 1);
+        assert_eq!(contents.attachments[0].data, b"hello");
+    }
+
+    #[test]
+    fn test_decode_rejects_non_tnef() {
+        assert!(decode(b"PK\x03\x04 not tnef").is_err());
+    }
+
+    #[test]
+    fn test_part_id_round_trip() {
+        assert_eq!(part_id("2", TnefItem::Attachment(3)), "2:tnef:3");
+        assert_eq!(
+            parse_part_id("2:tnef:3"),
+            Some(("2", TnefItem::Attachment(3)))
+        );
+        assert_eq!(
+            parse_part_id("1.2:tnef:rtf"),
+            Some(("1.2", TnefItem::RtfBody))
+        );
+        assert_eq!(parse_part_id("1.2"), None);
+    }
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
+use specta::Type;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct ImapConfig {
     pub host: String,
     pub port: u16,
@@ -10,9 +11,21 @@ pub struct ImapConfig {
     pub auth_method: String, // "password" or "oauth2"
     #[serde(default)]
     pub accept_invalid_certs: bool,
+    /// Mailbox of another user to open with these credentials
+    /// (delegated/shared access), e.g. an assistant reading a manager's mail.
+    #[serde(default)]
+    pub authorize_as: Option<String>,
+    /// How `authorize_as` is sent: "sasl" (SASL PLAIN authorization
+    /// identity, the default) or "exchange" (`user\mailbox` login name).
+    #[serde(default)]
+    pub delegation: Option<String>,
+    /// IMAP connections/commands per minute; overrides the provider preset.
+    /// `0` disables rate limiting.
+    #[serde(default)]
+    pub rate_limit_per_minute: Option<u32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct ImapFolder {
     pub path: String,      // decoded UTF-8 display name
     pub raw_path: String,  // original modified UTF-7 path for IMAP commands
@@ -21,9 +34,35 @@ pub struct ImapFolder {
     pub special_use: Option<String>, // "\Sent", "\Trash", "\Drafts", "\Junk", "\Archive", "\All"
     pub exists: u32,
     pub unseen: u32,
+    #[serde(default)]
+    pub attributes: Vec<String>, // raw LIST attributes, e.g. "\Noselect", "\HasChildren"
+    #[serde(default)]
+    pub no_select: bool, // hierarchy-only container, cannot be SELECTed
+    #[serde(default)]
+    pub non_existent: bool, // listed (e.g. subscribed) but no longer exists
+}
+
+/// A folder in the hierarchy returned by `imap_list_folder_tree`.
+///
+/// Containers that the server lists only implicitly (e.g. "Work" when only
+/// "Work/Projects" exists) are synthesized with `selectable: false`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ImapFolderNode {
+    pub id: String, // stable id derived from raw_path
+    pub path: String,
+    pub raw_path: String,
+    pub name: String,
+    pub delimiter: String,
+    pub special_use: Option<String>,
+    pub exists: u32,
+    pub unseen: u32,
+    pub attributes: Vec<String>,
+    pub selectable: bool,
+    pub has_children: bool,
+    pub children: Vec<ImapFolderNode>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct ImapMessage {
     pub uid: u32,
     pub folder: String,
@@ -49,9 +88,80 @@ pub struct ImapMessage {
     pub list_unsubscribe_post: Option<String>,
     pub auth_results: Option<String>,
     pub attachments: Vec<ImapAttachment>,
+    /// Emails attached as message/rfc822 parts, parsed recursively.
+    #[serde(default)]
+    pub attached_messages: Vec<ImapAttachedMessage>,
+    /// MIME parsing failed; only the basic headers were recovered.
+    #[serde(default)]
+    pub parse_failed: bool,
+    /// Envelope recipient (X-Original-To / Delivered-To / Envelope-To): the
+    /// alias or catch-all address the message was delivered to, which may
+    /// not appear in To/Cc.
+    #[serde(default)]
+    pub delivered_to: Option<String>,
+    /// Mailing list identifier from List-Id, e.g. "dev.lists.example.org".
+    #[serde(default)]
+    pub list_id: Option<String>,
+    /// Address for posting to the list (List-Post), used by "reply to list".
+    #[serde(default)]
+    pub list_post: Option<String>,
+    /// List archive URL (List-Archive).
+    #[serde(default)]
+    pub list_archive: Option<String>,
+    /// Where replies to all should go instead of From/To/Cc
+    /// (Mail-Followup-To), set by list members who don't want a copy.
+    #[serde(default)]
+    pub mail_followup_to: Option<String>,
+    /// Sent from one of the user's addresses (see `identity`).
+    #[serde(default)]
+    pub is_from_me: bool,
+    /// IMAP keywords set on the message, e.g. color categories like
+    /// `$label1` (see `keywords`).
+    #[serde(default)]
+    pub keywords: Vec<String>,
+}
+
+/// A message's keywords, fetched without anything else to pick up keyword
+/// changes made by other clients (e.g. team tags, see `keywords`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct ImapMessageKeywords {
+    pub uid: u32,
+    pub keywords: Vec<String>,
+}
+
+/// The envelope of a message, fetched without its body, for filling a
+/// message list quickly. The body is fetched when the message is opened.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct ImapMessageHeader {
+    pub uid: u32,
+    pub folder: String,
+    pub message_id: Option<String>,
+    pub in_reply_to: Option<String>,
+    pub from_address: Option<String>,
+    pub from_name: Option<String>,
+    pub to_addresses: Option<String>,
+    pub cc_addresses: Option<String>,
+    pub reply_to: Option<String>,
+    pub subject: Option<String>,
+    pub date: i64,
+    pub is_read: bool,
+    pub is_starred: bool,
+    pub is_draft: bool,
+    pub raw_size: u32,
+    /// The body structure has a part meant to be saved rather than shown.
+    pub has_attachments: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// An email attached to another message. `part_id` is its message/rfc822
+/// section; part ids of its own attachments are relative to the outer
+/// message, so they can be fetched with the outer message's UID.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ImapAttachedMessage {
+    pub part_id: String,
+    pub message: ImapMessage,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct ImapAttachment {
     pub part_id: String,
     pub filename: String,
@@ -61,7 +171,7 @@ pub struct ImapAttachment {
     pub is_inline: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct ImapFolderStatus {
     pub uidvalidity: u32,
     pub uidnext: u32,
@@ -70,30 +180,34 @@ pub struct ImapFolderStatus {
     pub highest_modseq: Option<u64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct ImapFetchResult {
     pub messages: Vec<ImapMessage>,
     pub folder_status: ImapFolderStatus,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct ImapFolderSyncResult {
     pub uids: Vec<u32>,
     pub messages: Vec<ImapMessage>,
     pub folder_status: ImapFolderStatus,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct DeltaCheckRequest {
     pub folder: String,
     pub last_uid: u32,
     pub uidvalidity: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct DeltaCheckResult {
     pub folder: String,
     pub uidvalidity: u32,
     pub new_uids: Vec<u32>,
     pub uidvalidity_changed: bool,
+    /// Current STATUS counts, so unread badges refresh in the same round
+    /// trip. `None` if the server rejected STATUS.
+    #[serde(default)]
+    pub folder_status: Option<ImapFolderStatus>,
 }
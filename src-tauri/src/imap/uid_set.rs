@@ -0,0 +1,100 @@
+//! Compact UID sets for bulk commands.
+//!
+//! Joining UIDs with commas gives command lines like "1,2,3,…,9999" that
+//! some servers reject (RFC 7162 asks clients to stay under 8192 octets,
+//! and older servers cut off much earlier). Consecutive UIDs are collapsed
+//! into ranges ("1:9999") and whatever is still too long is split into
+//! several sets, one command each.
+
+/// Longest UID set sent in a single command, in bytes.
+pub const MAX_UID_SET_LEN: usize = 1000;
+
+/// Build UID sets covering `uids`, each at most `MAX_UID_SET_LEN` bytes.
+///
+/// Input order and duplicates don't matter. Returns an empty Vec for no UIDs.
+pub fn build_uid_sets(uids: &[u32]) -> Vec<String> {
+    build_uid_sets_with_limit(uids, MAX_UID_SET_LEN)
+}
+
+fn build_uid_sets_with_limit(uids: &[u32], max_len: usize) -> Vec<String> {
+    let mut sorted = uids.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+
+    let mut sets = Vec::new();
+    let mut current = String::new();
+    for (start, end) in ranges(&sorted) {
+        let item = if start == end {
+            start.to_string()
+        } else {
+            format!("{start}:{end}")
+        };
+        if !current.is_empty() && current.len() + 1 + item.len() > max_len {
+            sets.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(',');
+        }
+        current.push_str(&item);
+    }
+    if !current.is_empty() {
+        sets.push(current);
+    }
+    sets
+}
+
+/// Collapse sorted, deduplicated UIDs into inclusive `(start, end)` runs.
+fn ranges(sorted: &[u32]) -> Vec<(u32, u32)> {
+    let mut runs: Vec<(u32, u32)> = Vec::new();
+    for &uid in sorted {
+        match runs.last_mut() {
+            Some((_, end)) if end.checked_add(1) == Some(uid) => *end = uid,
+            _ => runs.push((uid, uid)),
+        }
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collapses_consecutive_uids() {
+        let uids: Vec<u32> = (1..=9999).collect();
+        assert_eq!(build_uid_sets(&uids), vec!["1:9999"]);
+        assert_eq!(build_uid_sets(&[7, 3, 4, 5, 10, 9, 4]), vec!["3:5,7,9:10"]);
+        assert_eq!(build_uid_sets(&[42]), vec!["42"]);
+        assert!(build_uid_sets(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_handles_u32_max() {
+        assert_eq!(
+            build_uid_sets(&[u32::MAX - 1, u32::MAX, 1]),
+            vec!["1,4294967294:4294967295"]
+        );
+    }
+
+    #[test]
+    fn test_splits_long_sets() {
+        // Every other UID: no ranges possible
+        let uids: Vec<u32> = (0..2000).map(|i| 1000 + i * 2).collect();
+        let sets = build_uid_sets(&uids);
+
+        assert!(sets.len() > 1);
+        assert!(sets.iter().all(|s| s.len() <= MAX_UID_SET_LEN));
+        let rejoined: Vec<u32> = sets
+            .iter()
+            .flat_map(|s| s.split(','))
+            .map(|u| u.parse().unwrap())
+            .collect();
+        assert_eq!(rejoined, uids);
+    }
+
+    #[test]
+    fn test_split_keeps_ranges_whole() {
+        let sets = build_uid_sets_with_limit(&[1, 2, 3, 10, 20, 21], 8);
+        assert_eq!(sets, vec!["1:3,10", "20:21"]);
+    }
+}
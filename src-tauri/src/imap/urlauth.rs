@@ -0,0 +1,136 @@
+//! URLAUTH (RFC 4467) for handing messages to the submission server.
+//!
+//! `GENURLAUTH` issues an IMAP URL that only the named submission user may
+//! fetch. The SMTP server resolves it itself when given to `BURL`, so a
+//! forwarded message never travels through the client.
+
+use std::collections::HashMap;
+use tokio::io::{AsyncWriteExt, BufReader};
+
+use super::catenate::{encode_mailbox, message_path};
+use super::client::{
+    capabilities_include, imap_quote, raw_connect_and_login, raw_send_and_wait, raw_uid_validity,
+    ImapStream,
+};
+use super::types::ImapConfig;
+use crate::attachments::ServerMessageRef;
+
+/// Authorized URLs for `messages`, keyed by staged id, that `submitter`
+/// (the SMTP login) may fetch. `None` if the server doesn't support URLAUTH.
+pub async fn submission_urls(
+    config: &ImapConfig,
+    messages: &[ServerMessageRef],
+    submitter: &str,
+) -> Result<Option<HashMap<String, String>>, String> {
+    let mut reader = raw_connect_and_login(config).await?;
+
+    let caps = raw_send_and_wait(&mut reader, b"a2 CAPABILITY\r\n", "a2").await?;
+    if !capabilities_include(&caps, "URLAUTH") {
+        let _ = reader.get_mut().write_all(b"a9 LOGOUT\r\n").await;
+        return Ok(None);
+    }
+
+    let result = generate(&mut reader, config, messages, submitter).await;
+    let _ = reader.get_mut().write_all(b"a9 LOGOUT\r\n").await;
+    result.map(Some)
+}
+
+async fn generate(
+    reader: &mut BufReader<ImapStream>,
+    config: &ImapConfig,
+    messages: &[ServerMessageRef],
+    submitter: &str,
+) -> Result<HashMap<String, String>, String> {
+    let mut urls = HashMap::new();
+    for (i, message) in messages.iter().enumerate() {
+        let uid_validity = raw_uid_validity(reader, &format!("s{i}"), &message.folder).await?;
+        let url = format!(
+            "{};urlauth=submit+{}",
+            server_url(
+                config,
+                &message_path(&message.folder, uid_validity, message.uid)
+            ),
+            encode_user(submitter)
+        );
+
+        let tag = format!("g{i}");
+        let cmd = format!("{tag} GENURLAUTH {} INTERNAL\r\n", imap_quote(&url));
+        let response = raw_send_and_wait(reader, cmd.as_bytes(), &tag).await?;
+        let authorized = parse_genurlauth(&response)
+            .ok_or_else(|| format!("No URL in GENURLAUTH response for {}", message.folder))?;
+        urls.insert(message.id.clone(), authorized);
+    }
+    Ok(urls)
+}
+
+/// Percent-encode a user name for the userinfo or `urlauth=` part of a URL.
+fn encode_user(user: &str) -> String {
+    encode_mailbox(user)
+        .replace('@', "%40")
+        .replace(':', "%3A")
+        .replace('/', "%2F")
+}
+
+/// Absolute `imap://` URL for a server-relative path on this account.
+fn server_url(config: &ImapConfig, path: &str) -> String {
+    let port = if config.port == 143 {
+        String::new()
+    } else {
+        format!(":{}", config.port)
+    };
+    format!(
+        "imap://{}@{}{port}{path}",
+        encode_user(&config.username),
+        config.host
+    )
+}
+
+/// The authorized URL from a `* GENURLAUTH` response line.
+fn parse_genurlauth(response: &str) -> Option<String> {
+    let line = response.lines().find(|l| l.starts_with("* GENURLAUTH "))?;
+    let value = line["* GENURLAUTH ".len()..].trim();
+    match value.strip_prefix('"') {
+        Some(quoted) => Some(quoted[..quoted.find('"')?].to_string()),
+        None => value.split_whitespace().next().map(str::to_string),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(port: u16) -> ImapConfig {
+        serde_json::from_value(serde_json::json!({
+            "host": "imap.example.com",
+            "port": port,
+            "security": "tls",
+            "username": "joe@example.com",
+            "password": "secret",
+            "auth_method": "password",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_server_url() {
+        assert_eq!(
+            server_url(&config(993), "/INBOX;UIDVALIDITY=1/;UID=20"),
+            "imap://joe%40example.com@imap.example.com:993/INBOX;UIDVALIDITY=1/;UID=20"
+        );
+        assert_eq!(
+            server_url(&config(143), "/INBOX;UIDVALIDITY=1/;UID=20"),
+            "imap://joe%40example.com@imap.example.com/INBOX;UIDVALIDITY=1/;UID=20"
+        );
+    }
+
+    #[test]
+    fn test_parse_genurlauth() {
+        let response = "* GENURLAUTH \"imap://joe@example.com/INBOX/;uid=20;urlauth=submit+fred:internal:91354a47\"\r\n\
+                        g0 OK GENURLAUTH completed\r\n";
+        assert_eq!(
+            parse_genurlauth(response).as_deref(),
+            Some("imap://joe@example.com/INBOX/;uid=20;urlauth=submit+fred:internal:91354a47")
+        );
+        assert_eq!(parse_genurlauth("g0 OK\r\n"), None);
+    }
+}
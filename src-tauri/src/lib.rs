@@ -1,17 +1,72 @@
+use std::path::Path;
+
+use specta_typescript::{BigIntExportBehavior, Typescript};
+use tauri::Manager;
 #[cfg(not(target_os = "linux"))]
 use tauri::{
     menu::{Menu, MenuItem},
     tray::{TrayIconBuilder, TrayIconId},
 };
-use tauri::{Emitter, Manager};
 use tauri_plugin_autostart::MacosLauncher;
 
+mod accounts;
+mod attachment_open;
+mod attachment_preview;
+mod attachments;
+mod audit_log;
+mod autostart;
+mod caldav;
+mod cli;
 mod commands;
+mod compose;
+mod contact_card;
+mod contacts_export;
+mod contacts_import;
+mod credentials;
+mod date_buckets;
+mod db_maintenance;
+mod disclaimer;
+mod events;
+mod gmail_api;
+mod identity;
+mod image_proxy;
 mod imap;
+mod link_check;
+#[cfg(target_os = "linux")]
+mod linux_tray;
+mod local_api;
+mod managed;
+mod metrics;
+mod nntp;
+mod notes;
+mod notify_rules;
+mod notify_sounds;
 mod oauth;
+mod otp;
+mod outbox;
+mod popout;
+mod profile_lock;
+mod profiles;
+mod purchases;
+mod quick_compose;
+mod rate_limit;
+mod recipients;
+mod search_snippets;
+mod send_policy;
+mod settings;
 mod smtp;
+mod snippets;
+mod staged_operations;
+mod store;
+#[cfg(all(test, feature = "integration-tests"))]
+mod testing;
+mod threading;
+mod tray_badge;
+
+use settings::{CloseBehavior, SettingsState};
 
 #[tauri::command]
+#[specta::specta]
 fn close_splashscreen(app: tauri::AppHandle) {
     if let Some(w) = app.get_webview_window("splashscreen") {
         let _ = w.close();
@@ -23,6 +78,7 @@ fn close_splashscreen(app: tauri::AppHandle) {
 }
 
 #[tauri::command]
+#[specta::specta]
 fn set_tray_tooltip(app: tauri::AppHandle, tooltip: String) -> Result<(), String> {
     #[cfg(not(target_os = "linux"))]
     {
@@ -33,22 +89,263 @@ fn set_tray_tooltip(app: tauri::AppHandle, tooltip: String) -> Result<(), String
     }
     #[cfg(target_os = "linux")]
     {
-        let _ = tooltip;
-        let _ = app;
-        log::debug!("set_tray_tooltip is not supported on Linux (KSNI tray)");
+        linux_tray::set_tooltip(&app, tooltip);
         Ok(())
     }
 }
 
 #[tauri::command]
+#[specta::specta]
+fn set_tray_unread_count(app: tauri::AppHandle, count: u32) {
+    #[cfg(target_os = "linux")]
+    linux_tray::set_unread_count(&app, count);
+    #[cfg(not(target_os = "linux"))]
+    if let Err(e) = tray_badge::apply(&app, count) {
+        log::debug!("Failed to draw tray badge: {e}");
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
 fn open_devtools(app: tauri::AppHandle) {
     if let Some(w) = app.get_webview_window("main") {
         w.open_devtools();
     }
 }
 
+#[tauri::command]
+#[specta::specta]
+fn quit_app(app: tauri::AppHandle) {
+    app.exit(0);
+}
+
+/// Perform a close action chosen by the frontend's "close window?" dialog.
+#[tauri::command]
+#[specta::specta]
+fn apply_close_action(window: tauri::Window, action: CloseBehavior) {
+    apply_close_behavior(&window, action);
+}
+
+/// Hide, minimize, or quit for a main-window close. `Ask` forwards the
+/// decision to the frontend via the `close-requested` event.
+fn apply_close_behavior(window: &tauri::Window, behavior: CloseBehavior) {
+    match behavior {
+        CloseBehavior::Hide => {
+            let _ = window.hide();
+        }
+        CloseBehavior::Minimize => {
+            let _ = window.minimize();
+        }
+        CloseBehavior::Quit => window.app_handle().exit(0),
+        CloseBehavior::Ask => {
+            events::emit(window, events::CloseRequested);
+        }
+    }
+}
+
+/// Commands and events exposed to the frontend. `src/bindings.ts` is
+/// generated from these, so new commands only need adding here.
+fn specta_builder() -> tauri_specta::Builder {
+    tauri_specta::Builder::new()
+        .commands(tauri_specta::collect_commands![
+            oauth::start_oauth_server,
+            oauth::oauth_generate_pkce,
+            oauth::oauth_provider_profile,
+            oauth::oauth_exchange_token,
+            oauth::oauth_refresh_token,
+            oauth::oauth_store_grant,
+            set_tray_tooltip,
+            set_tray_unread_count,
+            close_splashscreen,
+            open_devtools,
+            quit_app,
+            apply_close_action,
+            settings::get_close_behavior,
+            settings::set_close_behavior,
+            settings::get_read_only,
+            settings::set_read_only,
+            autostart::autostart_get,
+            autostart::autostart_set,
+            popout::open_message_window,
+            popout::list_message_windows,
+            popout::close_message_windows,
+            quick_compose::open_quick_compose,
+            quick_compose::get_quick_compose_shortcut,
+            quick_compose::set_quick_compose_shortcut,
+            accounts::account_register,
+            accounts::account_add,
+            accounts::account_update,
+            accounts::account_remove,
+            accounts::account_list,
+            credentials::credentials_store,
+            credentials::credentials_get,
+            credentials::credentials_delete,
+            accounts::account_provide_password,
+            profile_lock::profile_status,
+            profile_lock::profile_data_key,
+            profile_lock::profile_unlock,
+            profile_lock::profile_set_master_password,
+            profiles::profile_current,
+            profiles::profile_list,
+            profiles::profile_create,
+            profiles::profile_switch,
+            commands::imap_test_connection,
+            commands::imap_list_folders,
+            commands::imap_list_folder_tree,
+            commands::imap_create_folder,
+            commands::imap_rename_folder,
+            commands::imap_delete_folder,
+            commands::imap_subscribe_folder,
+            commands::imap_unsubscribe_folder,
+            commands::imap_fetch_messages,
+            commands::imap_fetch_headers,
+            commands::imap_fetch_keywords,
+            commands::imap_fetch_new_uids,
+            commands::imap_search_all_uids,
+            commands::imap_search_text,
+            commands::imap_fetch_message_body,
+            commands::imap_fetch_raw_message,
+            commands::imap_set_flags,
+            commands::imap_move_messages,
+            commands::imap_delete_messages,
+            commands::imap_get_folder_status,
+            commands::imap_fetch_bodystructure,
+            commands::imap_fetch_attachment,
+            commands::imap_append_message,
+            commands::imap_save_draft,
+            commands::imap_sync_folder,
+            commands::imap_raw_fetch_diagnostic,
+            commands::imap_delta_check,
+            commands::imap_start_notify,
+            commands::imap_stop_notify,
+            commands::imap_start_idle,
+            commands::imap_stop_idle,
+            imap::session_pool::imap_close_account_sessions,
+            commands::smtp_send_email,
+            commands::smtp_send_and_append,
+            compose::smtp_compose_and_send,
+            outbox::smtp_queue_email,
+            outbox::outbox_list,
+            outbox::outbox_retry,
+            outbox::outbox_cancel,
+            staged_operations::imap_stage_delete,
+            staged_operations::undo_operation,
+            staged_operations::staged_operation_list,
+            commands::smtp_test_connection,
+            commands::nntp_test_connection,
+            commands::nntp_list_groups,
+            commands::nntp_group_status,
+            commands::nntp_fetch_overview,
+            commands::nntp_fetch_articles,
+            commands::nntp_fetch_raw_article,
+            commands::nntp_fetch_attachment,
+            commands::nntp_post_article,
+            attachments::attachment_stage,
+            attachments::attachment_stage_server_message,
+            attachments::attachment_unstage,
+            attachments::attachment_read,
+            attachments::attachment_resolve_raw,
+            attachments::attachment_optimize_images,
+            attachments::attachment_zip,
+            attachments::clipboard_image_to_attachment,
+            attachment_preview::attachment_preview_thumbnail,
+            attachment_open::open_attachment,
+            date_buckets::bucket_message_dates,
+            image_proxy::image_proxy_fetch,
+            image_proxy::image_proxy_clear_cache,
+            caldav::caldav_freebusy,
+            gmail_api::gmail_list_messages,
+            gmail_api::gmail_get_message,
+            gmail_api::gmail_modify_labels,
+            gmail_api::gmail_send,
+            link_check::check_link,
+            link_check::link_blocklist_refresh,
+            recipients::validate_recipients,
+            send_policy::check_send_policy,
+            send_policy::get_send_policy,
+            send_policy::set_send_policy,
+            notify_rules::notify_new_mail,
+            notify_rules::get_notification_rules,
+            notify_rules::set_notification_rules,
+            notify_sounds::notification_sounds_list,
+            notify_sounds::notification_sound_add,
+            notify_sounds::notification_sound_remove,
+            notify_sounds::notification_sound_preview,
+            disclaimer::get_disclaimer,
+            managed::get_managed_policy,
+            local_api::local_api_get,
+            local_api::local_api_set,
+            local_api::local_api_regenerate_token,
+            snippets::expand_snippet,
+            contact_card::contact_from_message,
+            contacts_export::contacts_export,
+            contacts_import::contacts_import,
+            identity::identity_set_addresses,
+            identity::identity_match,
+            search_snippets::search_snippets,
+            audit_log::audit_query,
+            store::cache_query_messages,
+            store::cache_get_message,
+            threading::cache_thread_messages,
+            purchases::purchases_query,
+            purchases::purchases_totals,
+            notes::notes_list,
+            notes::notes_save,
+            notes::notes_delete,
+            notes::notes_search,
+            notes::notes_mark_synced,
+            notes::notes_import,
+            otp::otp_copy_code,
+            otp::get_otp_clipboard_seconds,
+            otp::set_otp_clipboard_seconds,
+            db_maintenance::db_maintenance,
+            metrics::get_metrics,
+        ])
+        .events(tauri_specta::collect_events![
+            events::SingleInstanceArgs,
+            events::TrayCheckMail,
+            events::CloseRequested,
+            events::ImapNotify,
+            events::ImapNotifyStopped,
+            events::ImapNewMail,
+            events::ImapExpunge,
+            events::ImapFlagsChanged,
+            events::ImapIdleStopped,
+            events::PasswordRequired,
+            events::FoldersChanged,
+            events::ComposeRequested,
+            events::OutboxSent,
+            events::OutboxFailed,
+            events::StagedOperationDone,
+        ])
+        .constant("EVENT_SCHEMA_VERSION", events::SCHEMA_VERSION)
+}
+
+/// Where the generated bindings are committed.
+const BINDINGS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../src/bindings.ts");
+
+/// Write typed wrappers for every command plus the command and event payload
+/// types to `path`. Debug builds refresh `src/bindings.ts` on startup, and
+/// `cargo test bindings` fails while the committed file is stale.
+fn export_bindings(builder: &tauri_specta::Builder, path: &Path) -> Result<(), String> {
+    builder
+        .export(
+            Typescript::default()
+                .header("// @ts-nocheck")
+                // Timestamps and sizes fit in a JS number
+                .bigint(BigIntExportBehavior::Number),
+            path,
+        )
+        .map_err(|e| format!("Failed to export bindings to {}: {e}", path.display()))
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // `--send`/`--check` run headless and exit without starting Tauri
+    if let Some(code) = cli::run_from_args() {
+        std::process::exit(code);
+    }
+
     // Set explicit AUMID on Windows so toast notifications show "Sora"
     // instead of "Windows PowerShell"
     #[cfg(windows)]
@@ -60,6 +357,8 @@ pub fn run() {
         }
     }
 
+    let specta = specta_builder();
+
     tauri::Builder::default()
         // Single instance MUST be first
         .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
@@ -68,12 +367,14 @@ pub fn run() {
                 let _ = window.set_focus();
                 let _ = window.unminimize();
             }
+            // A launch with `--profile` restarts into that profile
+            profiles::switch_from_args(app, &argv);
             // Forward args for deep linking
-            let _ = app.emit("single-instance-args", argv);
+            events::emit(app, events::SingleInstanceArgs { args: argv });
         }))
         .plugin(tauri_plugin_autostart::init(
             MacosLauncher::LaunchAgent,
-            Some(vec!["--hidden"]),
+            Some(vec![autostart::HIDDEN_ARG]),
         ))
         .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
@@ -86,33 +387,9 @@ pub fn run() {
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_os::init())
-        .invoke_handler(tauri::generate_handler![
-            oauth::start_oauth_server,
-            oauth::oauth_exchange_token,
-            oauth::oauth_refresh_token,
-            set_tray_tooltip,
-            close_splashscreen,
-            open_devtools,
-            commands::imap_test_connection,
-            commands::imap_list_folders,
-            commands::imap_fetch_messages,
-            commands::imap_fetch_new_uids,
-            commands::imap_search_all_uids,
-            commands::imap_fetch_message_body,
-            commands::imap_fetch_raw_message,
-            commands::imap_set_flags,
-            commands::imap_move_messages,
-            commands::imap_delete_messages,
-            commands::imap_get_folder_status,
-            commands::imap_fetch_attachment,
-            commands::imap_append_message,
-            commands::imap_sync_folder,
-            commands::imap_raw_fetch_diagnostic,
-            commands::imap_delta_check,
-            commands::smtp_send_email,
-            commands::smtp_test_connection,
-        ])
-        .setup(|app| {
+        .invoke_handler(specta.invoke_handler())
+        .setup(move |app| {
+            specta.mount_events(app);
             {
                 let level = if cfg!(debug_assertions) {
                     log::LevelFilter::Debug
@@ -126,15 +403,51 @@ pub fn run() {
                         .build(),
                 )?;
             }
+            #[cfg(debug_assertions)]
+            if let Err(e) = export_bindings(&specta, Path::new(BINDINGS_PATH)) {
+                log::error!("{e}");
+            }
+
+            // Everything stored per profile resolves its paths through this
+            app.manage(profiles::Profiles::load(app.handle()));
+            app.manage(SettingsState::load(app.handle()));
+            app.manage(profile_lock::ProfileLock::load(app.handle()));
+            app.manage(accounts::AccountRegistry::new(app.handle().clone()));
+            app.manage(popout::MessageWindows::default());
+            app.manage(imap::notify::NotifyWatchers::default());
+            app.manage(imap::idle::IdleWatchers::default());
+            app.manage(imap::folder_watch::FolderListCache::new(
+                app.handle().clone(),
+            ));
+            app.manage(identity::Identities::default());
+            app.manage(imap::session_pool::ImapSessionManager::default());
+            app.manage(attachments::AttachmentStaging::default());
+            app.manage(imap::catenate::SavedDrafts::default());
+            app.manage(attachment_open::OpenedAttachments::default());
+            app.manage(link_check::LinkBlocklist::default());
+            app.manage(local_api::LocalApi::default());
+            local_api::start(app.handle());
+            app.manage(outbox::Outbox::load(app.handle()));
+            outbox::start(app.handle());
+            app.manage(staged_operations::StagedOperations::load(app.handle()));
+            staged_operations::start(app.handle());
+            app.manage(notify_rules::Notifier::default());
+            notify_rules::start(app.handle());
+            attachment_open::cleanup_stale();
+            quick_compose::register_saved_shortcut(app.handle());
+            #[cfg(not(target_os = "linux"))]
+            app.manage(tray_badge::TrayBadgeCache::default());
 
             #[cfg(not(target_os = "linux"))]
             {
                 // Build system tray menu
                 let show = MenuItem::with_id(app, "show", "Show Sora", true, None::<&str>)?;
+                let quick_compose =
+                    MenuItem::with_id(app, "quick_compose", "Quick Compose", true, None::<&str>)?;
                 let check_mail =
                     MenuItem::with_id(app, "check_mail", "Check for Mail", true, None::<&str>)?;
                 let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-                let menu = Menu::with_items(app, &[&show, &check_mail, &quit])?;
+                let menu = Menu::with_items(app, &[&show, &quick_compose, &check_mail, &quit])?;
 
                 let icon = app
                     .default_window_icon()
@@ -153,9 +466,14 @@ pub fn run() {
                                 let _ = window.set_focus();
                             }
                         }
+                        "quick_compose" => {
+                            if let Err(e) = quick_compose::open(app) {
+                                log::warn!("{e}");
+                            }
+                        }
                         "check_mail" => {
                             if let Some(window) = app.get_webview_window("main") {
-                                let _ = window.emit("tray-check-mail", ());
+                                events::emit(&window, events::TrayCheckMail);
                             }
                         }
                         "quit" => {
@@ -176,51 +494,7 @@ pub fn run() {
             }
 
             #[cfg(target_os = "linux")]
-            {
-                use tray_item::{IconSource, TrayItem};
-
-                let app_handle = app.handle().clone();
-
-                std::thread::spawn(move || {
-                    let mut tray = match TrayItem::new("Sora", IconSource::Resource("mail-read")) {
-                        Ok(t) => t,
-                        Err(e) => {
-                            log::warn!("Failed to create system tray: {e}");
-                            return;
-                        }
-                    };
-
-                    let app_handle_show = app_handle.clone();
-                    if let Err(e) = tray.add_menu_item("Show Sora", move || {
-                        if let Some(window) = app_handle_show.get_webview_window("main") {
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        }
-                    }) {
-                        log::warn!("Failed to add tray menu item 'Show Sora': {e}");
-                    }
-
-                    let app_handle_check = app_handle.clone();
-                    if let Err(e) = tray.add_menu_item("Check for Mail", move || {
-                        if let Some(window) = app_handle_check.get_webview_window("main") {
-                            let _ = window.emit("tray-check-mail", ());
-                        }
-                    }) {
-                        log::warn!("Failed to add tray menu item 'Check for Mail': {e}");
-                    }
-
-                    let app_handle_quit = app_handle.clone();
-                    if let Err(e) = tray.add_menu_item("Quit", move || {
-                        app_handle_quit.exit(0);
-                    }) {
-                        log::warn!("Failed to add tray menu item 'Quit': {e}");
-                    }
-
-                    loop {
-                        std::thread::park();
-                    }
-                });
-            }
+            linux_tray::spawn(app.handle().clone());
 
             // On Windows/Linux, remove decorations for custom titlebar.
             // macOS uses titleBarStyle: "overlay" from config instead, which
@@ -233,7 +507,8 @@ pub fn run() {
             }
 
             // Start hidden in tray if launched with --hidden (autostart)
-            if std::env::args().any(|a| a == "--hidden") {
+            // and the user hasn't turned "start hidden" off
+            if autostart::should_start_hidden(&app.state::<SettingsState>()) {
                 if let Some(window) = app.get_webview_window("main") {
                     let _ = window.hide();
                 }
@@ -246,16 +521,63 @@ pub fn run() {
             Ok(())
         })
         .on_window_event(|window, event| {
-            // Minimize to tray on close instead of quitting (main window only)
+            // Re-render the tray badge at the new DPI when the main window
+            // moves to a monitor with a different scale factor
+            #[cfg(not(target_os = "linux"))]
+            if let tauri::WindowEvent::ScaleFactorChanged { .. } = event {
+                if window.label() == "main" {
+                    tray_badge::refresh(window.app_handle());
+                }
+            }
+
+            // Main window close follows the configured close behavior
+            // (hide to tray by default); other windows close normally.
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                 if window.label() == "main" {
-                    let _ = window.hide();
-                    api.prevent_close();
+                    let behavior = window
+                        .try_state::<SettingsState>()
+                        .map(|s| s.get().close_behavior)
+                        .unwrap_or_default();
+                    if behavior != CloseBehavior::Quit {
+                        api.prevent_close();
+                    }
+                    apply_close_behavior(window, behavior);
                 }
             }
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                attachment_open::cleanup(app);
+            }
+        });
 
     log::info!("Tauri application exited normally");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Regenerate with `UPDATE_GOLDEN=1 cargo test bindings` and commit the
+    /// result.
+    #[test]
+    fn test_bindings_up_to_date() {
+        let builder = specta_builder();
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            export_bindings(&builder, Path::new(BINDINGS_PATH)).unwrap();
+            return;
+        }
+
+        let fresh = std::env::temp_dir().join(format!("sora-bindings-{}.ts", std::process::id()));
+        export_bindings(&builder, &fresh).unwrap();
+        let actual = std::fs::read_to_string(&fresh).unwrap();
+        let _ = std::fs::remove_file(&fresh);
+        let committed = std::fs::read_to_string(BINDINGS_PATH).unwrap_or_default();
+        assert!(
+            committed == actual,
+            "src/bindings.ts is stale: regenerate it with `UPDATE_GOLDEN=1 cargo test bindings`"
+        );
+    }
+}
@@ -0,0 +1,519 @@
+//! Link safety check before opening a URL from a message.
+//!
+//! Shortened links are expanded here by following their redirects without
+//! loading the target page, so the reader sees where a link really goes.
+//! The destination is compared against the link's display text and looked
+//! up in a local copy of public phishing/malware URL feeds (OpenPhish,
+//! URLhaus), which is refreshed periodically.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::Url;
+use serde::Serialize;
+use specta::Type;
+use tauri::{AppHandle, Manager, State};
+
+/// Redirect hops followed when expanding a shortened link.
+const MAX_REDIRECTS: usize = 10;
+
+/// Per-request timeout while expanding.
+const EXPAND_TIMEOUT: Duration = Duration::from_secs(8);
+
+/// Feeds are downloaded again once they are older than this.
+const FEED_MAX_AGE: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Blocklist directory, relative to the app data dir.
+const BLOCKLIST_DIR: &str = "link-blocklist";
+
+struct Feed {
+    name: &'static str,
+    file: &'static str,
+    url: &'static str,
+}
+
+/// Plain-text feeds with one URL per line ('#' starts a comment).
+const FEEDS: &[Feed] = &[
+    Feed {
+        name: "OpenPhish",
+        file: "openphish.txt",
+        url: "https://openphish.com/feed.txt",
+    },
+    Feed {
+        name: "URLhaus",
+        file: "urlhaus.txt",
+        url: "https://urlhaus.abuse.ch/downloads/text_online/",
+    },
+];
+
+/// Hosts whose only purpose is redirecting to another URL.
+const SHORTENERS: &[&str] = &[
+    "bit.ly",
+    "bitly.com",
+    "t.co",
+    "tinyurl.com",
+    "goo.gl",
+    "ow.ly",
+    "is.gd",
+    "buff.ly",
+    "rebrand.ly",
+    "cutt.ly",
+    "shorturl.at",
+    "rb.gy",
+    "t.ly",
+    "tiny.cc",
+    "lnkd.in",
+    "s.id",
+    "v.gd",
+    "bl.ink",
+    "short.io",
+    "trib.al",
+    "dlvr.it",
+    "soo.gd",
+    "qr.ae",
+    "x.gd",
+];
+
+/// Second-level domains used under country codes (co.uk, com.au, ...).
+const GENERIC_SLDS: &[&str] = &["co", "com", "net", "org", "ac", "gov", "edu", "ne", "or"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    Safe,
+    Suspicious,
+    Dangerous,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct LinkCheckResult {
+    pub url: String,
+    /// Where the link ends up after expanding shorteners.
+    pub final_url: String,
+    /// Intermediate URLs, in order, excluding `url` and `final_url`.
+    pub redirects: Vec<String>,
+    pub final_domain: Option<String>,
+    /// Domain the link text claims to go to, if it looks like a URL.
+    pub display_domain: Option<String>,
+    pub domain_mismatch: bool,
+    /// Feed that lists the link or one of its redirects.
+    pub blocklist_source: Option<String>,
+    pub verdict: Verdict,
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct BlocklistStatus {
+    pub entries: usize,
+    /// Unix ms of the oldest loaded feed, if any are loaded.
+    pub updated_at: Option<i64>,
+}
+
+#[derive(Default)]
+struct Blocklist {
+    /// Normalized URL → feed name.
+    urls: HashMap<String, &'static str>,
+    /// Hosts listed as a whole (feed entry without a path) → feed name.
+    hosts: HashMap<String, &'static str>,
+    updated_at: Option<SystemTime>,
+}
+
+impl Blocklist {
+    fn add_feed(&mut self, name: &'static str, text: &str) {
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some(url) = parse_link(line) else {
+                continue;
+            };
+            if matches!(url.path(), "" | "/") && url.query().is_none() {
+                if let Some(host) = url.host_str() {
+                    self.hosts.insert(host.to_string(), name);
+                }
+            } else {
+                self.urls.insert(normalize_url(&url), name);
+            }
+        }
+    }
+
+    fn lookup(&self, url: &Url) -> Option<&'static str> {
+        if let Some(name) = self.urls.get(&normalize_url(url)) {
+            return Some(name);
+        }
+        let host = url.host_str()?;
+        self.hosts.get(host).copied()
+    }
+
+    fn len(&self) -> usize {
+        self.urls.len() + self.hosts.len()
+    }
+}
+
+/// Managed state holding the loaded feeds.
+#[derive(Default)]
+pub struct LinkBlocklist(RwLock<Blocklist>);
+
+fn parse_link(s: &str) -> Option<Url> {
+    let url = Url::parse(s.trim()).ok()?;
+    (matches!(url.scheme(), "http" | "https") && url.host_str().is_some()).then_some(url)
+}
+
+/// Comparable form of a URL: lowercase host, no fragment, no default port,
+/// no trailing slash. `Url` already lowercases scheme and host.
+fn normalize_url(url: &Url) -> String {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    let s = url.as_str();
+    let s = s
+        .strip_prefix("https://")
+        .or_else(|| s.strip_prefix("http://"))
+        .unwrap_or(s);
+    s.trim_end_matches('/').to_string()
+}
+
+fn is_shortener(host: &str) -> bool {
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    SHORTENERS.contains(&host)
+}
+
+/// Organization part of a host: "mail.shop.co.uk" → "shop.co.uk".
+fn registrable_domain(host: &str) -> String {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    let labels: Vec<&str> = host.split('.').collect();
+    if labels.len() <= 2 || host.parse::<std::net::IpAddr>().is_ok() {
+        return host;
+    }
+    let n = labels.len();
+    let keep = if labels[n - 1].len() == 2 && GENERIC_SLDS.contains(&labels[n - 2]) {
+        3
+    } else {
+        2
+    };
+    labels[n - keep..].join(".")
+}
+
+/// Domain named by a link's text ("www.bank.com", "https://bank.com/login"),
+/// or `None` if the text doesn't look like a URL.
+fn display_domain(text: &str) -> Option<String> {
+    let text = text.trim();
+    if text.contains(char::is_whitespace) || !text.contains('.') {
+        return None;
+    }
+    let candidate = if text.contains("://") {
+        text.to_string()
+    } else {
+        format!("http://{text}")
+    };
+    let url = parse_link(&candidate)?;
+    let host = url.host_str()?;
+    let tld = host.rsplit('.').next()?;
+    (tld.len() >= 2 && tld.chars().all(|c| c.is_ascii_alphabetic())).then(|| host.to_string())
+}
+
+/// Combine the findings into a verdict and human-readable reasons.
+fn assess(
+    final_url: &Url,
+    display_text: Option<&str>,
+    blocklist_source: Option<&str>,
+) -> (Option<String>, bool, Verdict, Vec<String>) {
+    let mut reasons = Vec::new();
+    let display = display_text.and_then(display_domain);
+    let mismatch = match (&display, final_url.host_str()) {
+        (Some(shown), Some(actual)) => registrable_domain(shown) != registrable_domain(actual),
+        _ => false,
+    };
+    if let Some(source) = blocklist_source {
+        reasons.push(format!("Listed as malicious by {source}"));
+    }
+    if mismatch {
+        reasons.push(format!(
+            "Link text shows {} but the link goes to {}",
+            display.as_deref().unwrap_or_default(),
+            final_url.host_str().unwrap_or_default()
+        ));
+    }
+    let verdict = if blocklist_source.is_some() {
+        Verdict::Dangerous
+    } else if mismatch {
+        Verdict::Suspicious
+    } else {
+        Verdict::Safe
+    };
+    (display, mismatch, verdict, reasons)
+}
+
+/// Follow redirects from shortener hosts without loading the destination.
+/// Returns every URL visited, starting with `url`.
+async fn expand(url: &Url, blocklist: &LinkBlocklist) -> Result<Vec<Url>, String> {
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .timeout(EXPAND_TIMEOUT)
+        .user_agent("Mozilla/5.0")
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+
+    let mut chain = vec![url.clone()];
+    while chain.len() <= MAX_REDIRECTS {
+        let current = chain.last().expect("chain is never empty");
+        if !current.host_str().is_some_and(is_shortener) {
+            break;
+        }
+        // Never contact a host that is already known to be malicious
+        if blocklist
+            .0
+            .read()
+            .map_err(|e| e.to_string())?
+            .lookup(current)
+            .is_some()
+        {
+            break;
+        }
+
+        let mut response = client
+            .head(current.clone())
+            .send()
+            .await
+            .map_err(|e| format!("Failed to expand link: {e}"))?;
+        if response.status() == reqwest::StatusCode::METHOD_NOT_ALLOWED {
+            response = client
+                .get(current.clone())
+                .send()
+                .await
+                .map_err(|e| format!("Failed to expand link: {e}"))?;
+        }
+        if !response.status().is_redirection() {
+            break;
+        }
+        let Some(next) = response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|location| current.join(location).ok())
+            .filter(|u| matches!(u.scheme(), "http" | "https"))
+        else {
+            break;
+        };
+        chain.push(next);
+    }
+    Ok(chain)
+}
+
+/// Check a link before it is opened.
+///
+/// Expansion failures are reported in `reasons` rather than as an error, so
+/// the UI can still show the blocklist and display-text findings.
+#[tauri::command]
+#[specta::specta]
+pub async fn check_link(
+    blocklist: State<'_, LinkBlocklist>,
+    url: String,
+    display_text: Option<String>,
+) -> Result<LinkCheckResult, String> {
+    let parsed = parse_link(&url).ok_or_else(|| format!("Not a web link: {url}"))?;
+
+    let mut reasons = Vec::new();
+    let chain = match expand(&parsed, &blocklist).await {
+        Ok(chain) => chain,
+        Err(e) => {
+            reasons.push(e);
+            vec![parsed.clone()]
+        }
+    };
+    let final_url = chain.last().cloned().unwrap_or(parsed);
+
+    let blocklist_source = {
+        let list = blocklist.0.read().map_err(|e| e.to_string())?;
+        chain.iter().find_map(|u| list.lookup(u))
+    };
+    let (display_domain, domain_mismatch, verdict, mut found) =
+        assess(&final_url, display_text.as_deref(), blocklist_source);
+    found.append(&mut reasons);
+
+    let redirects = if chain.len() > 2 {
+        chain[1..chain.len() - 1]
+            .iter()
+            .map(Url::to_string)
+            .collect()
+    } else {
+        Vec::new()
+    };
+    Ok(LinkCheckResult {
+        url,
+        final_url: final_url.to_string(),
+        redirects,
+        final_domain: final_url.host_str().map(str::to_string),
+        display_domain,
+        domain_mismatch,
+        blocklist_source: blocklist_source.map(str::to_string),
+        verdict,
+        reasons: found,
+    })
+}
+
+fn blocklist_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("No app data dir: {e}"))?
+        .join(BLOCKLIST_DIR);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create blocklist dir: {e}"))?;
+    Ok(dir)
+}
+
+fn is_stale(path: &Path) -> bool {
+    let age = std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+    match age {
+        Some(age) => age > FEED_MAX_AGE,
+        None => true,
+    }
+}
+
+/// Download feeds that are missing or stale (all of them with `force`),
+/// then reload the blocklist from disk. A feed that fails to download keeps
+/// its previous copy.
+#[tauri::command]
+#[specta::specta]
+pub async fn link_blocklist_refresh(
+    app: AppHandle,
+    blocklist: State<'_, LinkBlocklist>,
+    force: Option<bool>,
+) -> Result<BlocklistStatus, String> {
+    let dir = blocklist_dir(&app)?;
+    let force = force.unwrap_or(false);
+    let client = reqwest::Client::new();
+
+    for feed in FEEDS {
+        let path = dir.join(feed.file);
+        if !force && !is_stale(&path) {
+            continue;
+        }
+        let body = match client.get(feed.url).send().await {
+            Ok(response) if response.status().is_success() => response.text().await,
+            Ok(response) => {
+                log::warn!(
+                    "{} feed download failed: HTTP {}",
+                    feed.name,
+                    response.status()
+                );
+                continue;
+            }
+            Err(e) => Err(e),
+        };
+        match body {
+            Ok(text) => {
+                if let Err(e) = std::fs::write(&path, text) {
+                    log::warn!("Failed to save {} feed: {e}", feed.name);
+                }
+            }
+            Err(e) => log::warn!("{} feed download failed: {e}", feed.name),
+        }
+    }
+
+    let mut loaded = Blocklist::default();
+    for feed in FEEDS {
+        let path = dir.join(feed.file);
+        let Ok(text) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        loaded.add_feed(feed.name, &text);
+        let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+        loaded.updated_at = match (loaded.updated_at, modified) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+    }
+
+    let status = BlocklistStatus {
+        entries: loaded.len(),
+        updated_at: loaded
+            .updated_at
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_millis() as i64),
+    };
+    *blocklist.0.write().map_err(|e| e.to_string())? = loaded;
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_blocklist_lookup() {
+        let mut list = Blocklist::default();
+        list.add_feed(
+            "OpenPhish",
+            "# comment\nhttps://evil.example.com/login/\n\nhttp://bad-host.example/\nnot a url\n",
+        );
+
+        assert_eq!(list.len(), 2);
+        assert_eq!(
+            list.lookup(&url("http://EVIL.example.com/login#x")),
+            Some("OpenPhish")
+        );
+        assert_eq!(
+            list.lookup(&url("https://bad-host.example/anything")),
+            Some("OpenPhish")
+        );
+        assert_eq!(list.lookup(&url("https://evil.example.com/other")), None);
+    }
+
+    #[test]
+    fn test_registrable_domain() {
+        assert_eq!(registrable_domain("www.paypal.com"), "paypal.com");
+        assert_eq!(registrable_domain("mail.shop.co.uk"), "shop.co.uk");
+        assert_eq!(registrable_domain("example.com"), "example.com");
+        assert_eq!(registrable_domain("192.168.1.1"), "192.168.1.1");
+    }
+
+    #[test]
+    fn test_display_domain() {
+        assert_eq!(
+            display_domain("www.bank.com"),
+            Some("www.bank.com".to_string())
+        );
+        assert_eq!(
+            display_domain("https://bank.com/login"),
+            Some("bank.com".to_string())
+        );
+        assert_eq!(display_domain("Click here"), None);
+        assert_eq!(display_domain("v1.2"), None);
+    }
+
+    #[test]
+    fn test_assess() {
+        let (_, mismatch, verdict, reasons) = assess(
+            &url("https://login.evil.net/x"),
+            Some("www.paypal.com"),
+            None,
+        );
+        assert!(mismatch);
+        assert_eq!(verdict, Verdict::Suspicious);
+        assert_eq!(reasons.len(), 1);
+
+        let (_, mismatch, verdict, _) =
+            assess(&url("https://www.paypal.com/x"), Some("paypal.com"), None);
+        assert!(!mismatch);
+        assert_eq!(verdict, Verdict::Safe);
+
+        let (_, _, verdict, _) = assess(&url("https://x.example/"), None, Some("URLhaus"));
+        assert_eq!(verdict, Verdict::Dangerous);
+    }
+
+    #[test]
+    fn test_shortener_hosts() {
+        assert!(is_shortener("bit.ly"));
+        assert!(is_shortener("www.tinyurl.com"));
+        assert!(!is_shortener("example.com"));
+    }
+}
@@ -0,0 +1,198 @@
+//! Linux system tray built on the StatusNotifierItem (SNI) D-Bus protocol.
+//!
+//! The tray service runs on its own thread under a small watchdog: if the
+//! session bus is unavailable or the service dies, it is recreated with
+//! exponential backoff. When no SNI host (panel) is running yet, the service
+//! stays alive and registers itself as soon as one appears.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+
+use crate::tray_badge::badge_label;
+
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(5);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(300);
+
+/// Tray contents shared between the watchdog and Tauri commands, so a
+/// recreated tray picks up the latest tooltip and unread count.
+#[derive(Debug, Clone)]
+struct TrayContent {
+    tooltip: String,
+    unread: u32,
+}
+
+impl Default for TrayContent {
+    fn default() -> Self {
+        Self {
+            tooltip: "Sora".to_string(),
+            unread: 0,
+        }
+    }
+}
+
+/// Managed state holding the live tray handle (if any) and its contents.
+#[derive(Default)]
+pub struct LinuxTray {
+    handle: Mutex<Option<ksni::Handle<SoraTray>>>,
+    content: Mutex<TrayContent>,
+}
+
+struct SoraTray {
+    app: AppHandle,
+    content: TrayContent,
+}
+
+impl SoraTray {
+    fn show_main_window(&self) {
+        if let Some(window) = self.app.get_webview_window("main") {
+            let _ = window.show();
+            let _ = window.unminimize();
+            let _ = window.set_focus();
+        }
+    }
+}
+
+impl ksni::Tray for SoraTray {
+    fn id(&self) -> String {
+        "sora".to_string()
+    }
+
+    fn title(&self) -> String {
+        match badge_label(self.content.unread) {
+            Some(count) => format!("Sora ({count})"),
+            None => "Sora".to_string(),
+        }
+    }
+
+    fn icon_name(&self) -> String {
+        if self.content.unread > 0 {
+            "mail-unread".to_string()
+        } else {
+            "mail-read".to_string()
+        }
+    }
+
+    fn tool_tip(&self) -> ksni::ToolTip {
+        ksni::ToolTip {
+            title: self.content.tooltip.clone(),
+            ..Default::default()
+        }
+    }
+
+    fn activate(&mut self, _x: i32, _y: i32) {
+        self.show_main_window();
+    }
+
+    fn menu(&self) -> Vec<ksni::MenuItem<Self>> {
+        use ksni::menu::StandardItem;
+
+        let show_label = match badge_label(self.content.unread) {
+            Some(count) => format!("Show Sora ({count} unread)"),
+            None => "Show Sora".to_string(),
+        };
+
+        vec![
+            StandardItem {
+                label: show_label,
+                activate: Box::new(|tray: &mut Self| tray.show_main_window()),
+                ..Default::default()
+            }
+            .into(),
+            StandardItem {
+                label: "Quick Compose".to_string(),
+                activate: Box::new(|tray: &mut Self| {
+                    if let Err(e) = crate::quick_compose::open(&tray.app) {
+                        log::warn!("{e}");
+                    }
+                }),
+                ..Default::default()
+            }
+            .into(),
+            StandardItem {
+                label: "Check for Mail".to_string(),
+                activate: Box::new(|tray: &mut Self| {
+                    if let Some(window) = tray.app.get_webview_window("main") {
+                        crate::events::emit(&window, crate::events::TrayCheckMail);
+                    }
+                }),
+                ..Default::default()
+            }
+            .into(),
+            ksni::MenuItem::Separator,
+            StandardItem {
+                label: "Quit".to_string(),
+                activate: Box::new(|tray: &mut Self| tray.app.exit(0)),
+                ..Default::default()
+            }
+            .into(),
+        ]
+    }
+
+    fn watcher_offine(&self) -> bool {
+        // Keep the service alive; ksni re-registers once a host shows up.
+        log::info!("StatusNotifier host went away; tray will re-register when it returns");
+        true
+    }
+}
+
+/// Start the tray service under a watchdog thread.
+pub fn spawn(app: AppHandle) {
+    app.manage(LinuxTray::default());
+
+    std::thread::Builder::new()
+        .name("linux-tray".to_string())
+        .spawn(move || {
+            let mut delay = INITIAL_RETRY_DELAY;
+            loop {
+                let state = app.state::<LinuxTray>();
+                let content = state.content.lock().unwrap().clone();
+                let service = ksni::TrayService::new(SoraTray {
+                    app: app.clone(),
+                    content,
+                });
+                *state.handle.lock().unwrap() = Some(service.handle());
+
+                let result = service.run();
+                state.handle.lock().unwrap().take();
+
+                match result {
+                    Ok(()) => {
+                        log::info!("Linux tray service stopped");
+                        return;
+                    }
+                    Err(e) => {
+                        log::warn!(
+                            "Linux tray service failed: {e} — retrying in {}s",
+                            delay.as_secs()
+                        );
+                        std::thread::sleep(delay);
+                        delay = (delay * 2).min(MAX_RETRY_DELAY);
+                    }
+                }
+            }
+        })
+        .map(|_| ())
+        .unwrap_or_else(|e| log::warn!("Failed to spawn Linux tray thread: {e}"));
+}
+
+/// Apply a change to the tray contents and push it to the live tray, if any.
+fn update(app: &AppHandle, f: impl Fn(&mut TrayContent)) {
+    let Some(state) = app.try_state::<LinuxTray>() else {
+        return;
+    };
+    f(&mut state.content.lock().unwrap());
+    let handle = state.handle.lock().unwrap();
+    if let Some(handle) = handle.as_ref() {
+        handle.update(|tray| f(&mut tray.content));
+    }
+}
+
+pub fn set_tooltip(app: &AppHandle, tooltip: String) {
+    update(app, |content| content.tooltip = tooltip.clone());
+}
+
+pub fn set_unread_count(app: &AppHandle, count: u32) {
+    update(app, |content| content.unread = count);
+}
@@ -0,0 +1,574 @@
+//! Opt-in HTTP API on localhost for launchers and automation tools
+//! (Raycast, Alfred, AutoHotkey scripts):
+//!
+//! - `GET /v1/unread` — unread Inbox conversations, in total and per account
+//! - `GET /v1/search?q=...&account=...&limit=...` — full-text search over
+//!   cached messages
+//! - `POST /v1/compose` — open the composer prefilled from a JSON body
+//!   (`to`, `cc`, `bcc`, `subject`, `body`)
+//!
+//! Every request needs `Authorization: Bearer <token>`. The token is kept
+//! in `local-api-token` in the profile's config dir, so scripts can read it,
+//! and is shown in Settings. The server only listens on 127.0.0.1 and
+//! refuses requests carrying an `Origin` header, so web pages can't call it
+//! through the user's browser. Data comes from the app database, so
+//! results need the main window to have loaded it.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use aes_gcm::aead::OsRng;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_sql::{DbInstances, DbPool};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::accounts::require_trusted_window;
+use crate::events::{self, ComposeRequested};
+use crate::profiles::{self, Profiles};
+use crate::settings::SettingsState;
+
+pub const DEFAULT_PORT: u16 = 17300;
+const TOKEN_FILE: &str = "local-api-token";
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+const MAX_BODY_BYTES: usize = 64 * 1024;
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_SEARCH_LIMIT: i64 = 20;
+const MAX_SEARCH_LIMIT: i64 = 100;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct LocalApiSettings {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for LocalApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_PORT,
+        }
+    }
+}
+
+/// What Settings shows about the API.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct LocalApiStatus {
+    pub enabled: bool,
+    pub port: u16,
+    /// Whether the server is listening.
+    pub running: bool,
+    /// Why the server isn't running although enabled, e.g. port in use.
+    pub error: Option<String>,
+    /// `None` while the API is disabled.
+    pub token: Option<String>,
+}
+
+/// Managed state: the running server, if any.
+#[derive(Default)]
+pub struct LocalApi {
+    server: Mutex<Option<tauri::async_runtime::JoinHandle<()>>>,
+    error: Mutex<Option<String>>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Request {
+    method: String,
+    path: String,
+    query: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn param(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| s.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (escaped, bytes[i]) {
+            (Some(byte), _) => {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+            (None, b'+') => out.push(b' '),
+            (None, b) => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// The request line and headers of `head` (everything before the blank
+/// line), without the body.
+fn parse_head(head: &str) -> Result<Request, String> {
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split(' ');
+    let (Some(method), Some(target)) = (request_line.next(), request_line.next()) else {
+        return Err("Malformed request line".to_string());
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(name), percent_decode(value))
+        })
+        .collect();
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+    Ok(Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        query,
+        headers,
+        body: Vec::new(),
+    })
+}
+
+async fn read_request(stream: &mut TcpStream) -> Result<Request, String> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let head_end = loop {
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        if buf.len() > MAX_HEADER_BYTES {
+            return Err("Headers too large".to_string());
+        }
+        let n = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|e| format!("Failed to read request: {e}"))?;
+        if n == 0 {
+            return Err("Connection closed".to_string());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+
+    let mut request = parse_head(&String::from_utf8_lossy(&buf[..head_end]))?;
+    let length: usize = request
+        .header("content-length")
+        .map(|v| v.parse().map_err(|_| "Bad Content-Length".to_string()))
+        .transpose()?
+        .unwrap_or(0);
+    if length > MAX_BODY_BYTES {
+        return Err("Body too large".to_string());
+    }
+    let mut body = buf.split_off(head_end + 4);
+    while body.len() < length {
+        let n = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|e| format!("Failed to read request: {e}"))?;
+        if n == 0 {
+            return Err("Connection closed".to_string());
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+    body.truncate(length);
+    request.body = body;
+    Ok(request)
+}
+
+/// Compare without stopping at the first differing byte, so response
+/// timing doesn't reveal how much of a guessed token was right.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// `None` if `request` may be served, else the status and error to send.
+fn reject(request: &Request, token: &str) -> Option<(u16, &'static str)> {
+    if request.header("origin").is_some() {
+        return Some((403, "Browser requests are not allowed"));
+    }
+    let presented = request
+        .header("authorization")
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .unwrap_or_default();
+    if !constant_time_eq(presented.trim().as_bytes(), token.as_bytes()) {
+        return Some((401, "Missing or wrong token"));
+    }
+    None
+}
+
+/// Quote every word of `query` for FTS5, so punctuation in it can't form
+/// operators or break the syntax.
+fn fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+fn response(status: u16, body: &Value) -> Vec<u8> {
+    let reason = match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    };
+    let body = body.to_string();
+    format!(
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nX-Content-Type-Options: nosniff\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+    .into_bytes()
+}
+
+fn error(status: u16, message: &str) -> (u16, Value) {
+    (status, json!({ "error": message }))
+}
+
+async fn unread(pool: &SqlitePool) -> Result<Value, String> {
+    let rows = sqlx::query_as::<_, (String, String, i64)>(
+        "SELECT a.id, a.email, COUNT(t.id) FROM accounts a
+         LEFT JOIN thread_labels tl ON tl.account_id = a.id AND tl.label_id = 'INBOX'
+         LEFT JOIN threads t ON t.account_id = tl.account_id AND t.id = tl.thread_id AND t.is_read = 0
+         WHERE a.is_active = 1
+         GROUP BY a.id ORDER BY a.email",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to count unread mail: {e}"))?;
+    let total: i64 = rows.iter().map(|(_, _, n)| n).sum();
+    let accounts: Vec<Value> = rows
+        .into_iter()
+        .map(|(id, email, unread)| json!({ "account_id": id, "email": email, "unread": unread }))
+        .collect();
+    Ok(json!({ "total": total, "accounts": accounts }))
+}
+
+async fn search(pool: &SqlitePool, request: &Request) -> Result<(u16, Value), String> {
+    let Some(query) = request.param("q").and_then(fts_query) else {
+        return Ok(error(400, "Missing q"));
+    };
+    let limit = request
+        .param("limit")
+        .and_then(|l| l.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+    type Row = (
+        String,
+        String,
+        String,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        i64,
+    );
+    let rows = sqlx::query_as::<_, Row>(
+        "SELECT m.id, m.account_id, m.thread_id, m.subject, m.from_name, m.from_address, m.snippet, m.date
+         FROM messages_fts
+         JOIN messages m ON m.rowid = messages_fts.rowid
+         WHERE messages_fts MATCH ?1 AND (?2 IS NULL OR m.account_id = ?2)
+         ORDER BY rank LIMIT ?3",
+    )
+    .bind(query)
+    .bind(request.param("account"))
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Search failed: {e}"))?;
+    let results: Vec<Value> = rows
+        .into_iter()
+        .map(
+            |(id, account_id, thread_id, subject, from_name, from_address, snippet, date)| {
+                json!({
+                    "message_id": id,
+                    "account_id": account_id,
+                    "thread_id": thread_id,
+                    "subject": subject,
+                    "from_name": from_name,
+                    "from_address": from_address,
+                    "snippet": snippet,
+                    "date": date,
+                })
+            },
+        )
+        .collect();
+    Ok((200, json!({ "results": results })))
+}
+
+async fn route(app: &AppHandle, request: &Request) -> (u16, Value) {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/v1/unread" | "/v1/search") => {}
+        ("POST", "/v1/compose") => {
+            return match serde_json::from_slice::<ComposeRequested>(&request.body) {
+                Ok(compose) => {
+                    events::emit(app, compose);
+                    (202, json!({ "ok": true }))
+                }
+                Err(e) => error(400, &format!("Invalid compose body: {e}")),
+            };
+        }
+        (_, "/v1/unread" | "/v1/search" | "/v1/compose") => {
+            return error(405, "Method not allowed")
+        }
+        _ => return error(404, "Not found"),
+    }
+
+    let db = app.state::<DbInstances>();
+    let instances = db.0.read().await;
+    let pool = match instances.get(&app.state::<Profiles>().db_url()) {
+        Some(DbPool::Sqlite(pool)) => pool,
+        None => return error(503, "The mail database isn't loaded yet"),
+    };
+    let result = if request.path == "/v1/unread" {
+        unread(pool).await.map(|body| (200, body))
+    } else {
+        search(pool, request).await
+    };
+    result.unwrap_or_else(|e| {
+        log::warn!("Local API {}: {e}", request.path);
+        error(500, &e)
+    })
+}
+
+async fn handle(app: AppHandle, token: String, mut stream: TcpStream) {
+    let request = match tokio::time::timeout(READ_TIMEOUT, read_request(&mut stream)).await {
+        Ok(Ok(request)) => request,
+        Ok(Err(e)) => {
+            let _ = stream
+                .write_all(&response(400, &json!({ "error": e })))
+                .await;
+            return;
+        }
+        Err(_) => return,
+    };
+    let (status, body) = match reject(&request, &token) {
+        Some((status, message)) => error(status, message),
+        None => route(&app, &request).await,
+    };
+    let _ = stream.write_all(&response(status, &body)).await;
+    let _ = stream.flush().await;
+}
+
+fn token_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(profiles::config_dir(app)?.join(TOKEN_FILE))
+}
+
+fn write_token(path: &Path) -> Result<String, String> {
+    use base64::Engine;
+
+    let mut bytes = [0u8; 32];
+    aes_gcm::aead::rand_core::RngCore::fill_bytes(&mut OsRng, &mut bytes);
+    let token = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create config directory: {e}"))?;
+    }
+    std::fs::write(path, &token).map_err(|e| format!("Failed to write {TOKEN_FILE}: {e}"))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Failed to restrict {TOKEN_FILE}: {e}"))?;
+    }
+    Ok(token)
+}
+
+/// The API token, created on first use.
+fn token(app: &AppHandle) -> Result<String, String> {
+    let path = token_path(app)?;
+    match std::fs::read_to_string(&path) {
+        Ok(token) if !token.trim().is_empty() => Ok(token.trim().to_string()),
+        _ => write_token(&path),
+    }
+}
+
+impl LocalApi {
+    /// Stop the server, waiting until its listener is closed so the port
+    /// can be bound again.
+    async fn stop(&self) {
+        let server = self.server.lock().unwrap().take();
+        if let Some(server) = server {
+            server.abort();
+            let _ = server.await;
+        }
+        *self.error.lock().unwrap() = None;
+    }
+
+    fn status(&self, app: &AppHandle, settings: &LocalApiSettings) -> LocalApiStatus {
+        LocalApiStatus {
+            enabled: settings.enabled,
+            port: settings.port,
+            running: self.server.lock().unwrap().is_some(),
+            error: self.error.lock().unwrap().clone(),
+            token: settings.enabled.then(|| token(app).ok()).flatten(),
+        }
+    }
+}
+
+/// Stop the server and start it again if the settings enable it. A port
+/// that can't be bound is recorded for [`LocalApiStatus::error`].
+pub async fn restart(app: &AppHandle) -> Result<(), String> {
+    let api = app.state::<LocalApi>();
+    api.stop().await;
+    let settings = app.state::<SettingsState>().get().local_api;
+    if !settings.enabled {
+        return Ok(());
+    }
+    let token = token(app)?;
+    let listener = match TcpListener::bind(("127.0.0.1", settings.port)).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            let message = format!("Can't listen on port {}: {e}", settings.port);
+            *api.error.lock().unwrap() = Some(message.clone());
+            return Err(message);
+        }
+    };
+    log::info!("Local API listening on 127.0.0.1:{}", settings.port);
+
+    let app = app.clone();
+    let server = tauri::async_runtime::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tauri::async_runtime::spawn(handle(app.clone(), token.clone(), stream));
+                }
+                Err(e) => log::warn!("Local API accept failed: {e}"),
+            }
+        }
+    });
+    *api.server.lock().unwrap() = Some(server);
+    Ok(())
+}
+
+/// Start the server at launch if it's enabled.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = restart(&app).await {
+            log::warn!("Local API not started: {e}");
+        }
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn local_api_get(
+    app: AppHandle,
+    window: tauri::Window,
+    api: State<'_, LocalApi>,
+    settings: State<'_, SettingsState>,
+) -> Result<LocalApiStatus, String> {
+    require_trusted_window(&window, "local_api_get")?;
+    Ok(api.status(&app, &settings.get().local_api))
+}
+
+/// Turn the API on or off or move it to another port. Fails, with the
+/// settings saved, if the server can't start.
+#[tauri::command]
+#[specta::specta]
+pub async fn local_api_set(
+    app: AppHandle,
+    window: tauri::Window,
+    settings: State<'_, SettingsState>,
+    api: State<'_, LocalApi>,
+    local_api: LocalApiSettings,
+) -> Result<LocalApiStatus, String> {
+    require_trusted_window(&window, "local_api_set")?;
+    if local_api.port < 1024 {
+        return Err("Pick a port from 1024 up".to_string());
+    }
+    settings.update(|s| s.local_api = local_api)?;
+    restart(&app).await?;
+    Ok(api.status(&app, &settings.get().local_api))
+}
+
+/// Replace the token, cutting off every tool that used the old one.
+#[tauri::command]
+#[specta::specta]
+pub async fn local_api_regenerate_token(
+    app: AppHandle,
+    window: tauri::Window,
+    settings: State<'_, SettingsState>,
+    api: State<'_, LocalApi>,
+) -> Result<LocalApiStatus, String> {
+    require_trusted_window(&window, "local_api_regenerate_token")?;
+    write_token(&token_path(&app)?)?;
+    restart(&app).await?;
+    Ok(api.status(&app, &settings.get().local_api))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(head: &str) -> Request {
+        parse_head(head).unwrap()
+    }
+
+    #[test]
+    fn test_parse_head() {
+        let parsed =
+            request("GET /v1/search?q=quarterly+report&account=a%201 HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer abc");
+        assert_eq!(parsed.method, "GET");
+        assert_eq!(parsed.path, "/v1/search");
+        assert_eq!(parsed.param("q"), Some("quarterly report"));
+        assert_eq!(parsed.param("account"), Some("a 1"));
+        assert_eq!(parsed.header("AUTHORIZATION"), Some("Bearer abc"));
+        assert!(parse_head("").is_err());
+    }
+
+    #[test]
+    fn test_reject() {
+        let ok = request("GET /v1/unread HTTP/1.1\r\nAuthorization: Bearer secret");
+        assert_eq!(reject(&ok, "secret"), None);
+        assert_eq!(reject(&ok, "other").unwrap().0, 401);
+
+        let missing = request("GET /v1/unread HTTP/1.1");
+        assert_eq!(reject(&missing, "secret").unwrap().0, 401);
+
+        let browser = request(
+            "GET /v1/unread HTTP/1.1\r\nAuthorization: Bearer secret\r\nOrigin: https://evil.example",
+        );
+        assert_eq!(reject(&browser, "secret").unwrap().0, 403);
+    }
+
+    #[test]
+    fn test_fts_query() {
+        assert_eq!(
+            fts_query(r#"invoice "Q3" OR-NOT"#).unwrap(),
+            r#""invoice" """Q3""" "OR-NOT""#
+        );
+        assert_eq!(fts_query("   "), None);
+    }
+}
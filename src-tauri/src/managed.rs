@@ -0,0 +1,346 @@
+//! Managed deployment configuration set by an administrator (MDM, group
+//! policy or configuration management) that the user can't override.
+//!
+//! The policy is merged from, in increasing precedence:
+//! - `*.json` files in `managed.d` under the system config dir
+//!   ([`system_config_dir`]), in file name order, or in
+//!   `SORA_MANAGED_CONFIG_DIR` if set;
+//! - on macOS, the managed preferences of a configuration profile
+//!   (`/Library/Managed Preferences/dev.lutelute.sora.plist`);
+//! - on Windows, a JSON string in the `Policy` value of
+//!   `HKLM\SOFTWARE\Policies\Sora`.
+//!
+//! Objects are merged key by key; anything else is replaced.
+//!
+//! ```json
+//! { "allowed_auth_methods": ["oauth2"],
+//!   "disable_accept_invalid_certs": true,
+//!   "settings": { "read_only": false, "send_policy": { "max_cc": 20 } },
+//!   "accounts": [{ "id": "corp", "email": "...", "imap": { ... } }] }
+//! ```
+//!
+//! `settings` keys take precedence over the user's backend settings and
+//! can't be changed; `accounts` are added to every profile and can't be
+//! edited or removed; a password account's password comes from the user
+//! (`ask_password`) or the keychain, never from the policy. The policy is
+//! read once per process.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use specta::Type;
+
+use crate::accounts::AccountDefinition;
+use crate::settings::BackendSettings;
+
+/// Environment variable overriding the `managed.d` directory.
+pub const MANAGED_DIR_ENV: &str = "SORA_MANAGED_CONFIG_DIR";
+const MANAGED_DIR: &str = "managed.d";
+/// Returned when a change touches a setting the policy sets.
+pub const MANAGED_SETTING_ERROR: &str = "This setting is managed by your organization";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ManagedPolicy {
+    /// Auth methods accounts may use ("password", "oauth2"); empty allows
+    /// every method.
+    pub allowed_auth_methods: Vec<String>,
+    /// Refuse accounts that skip TLS certificate checks.
+    pub disable_accept_invalid_certs: bool,
+    /// Backend settings (`backend-settings.json` keys) forced over the
+    /// user's.
+    pub settings: Map<String, Value>,
+    /// Accounts every profile has.
+    pub accounts: Vec<AccountDefinition>,
+}
+
+/// What the frontend needs to show which controls are locked.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ManagedPolicySummary {
+    pub allowed_auth_methods: Vec<String>,
+    pub disable_accept_invalid_certs: bool,
+    /// Top-level backend settings keys the policy sets.
+    pub locked_settings: Vec<String>,
+    pub account_ids: Vec<String>,
+}
+
+/// Where admin-provisioned files live: `/etc/sora`,
+/// `/Library/Application Support/Sora` or `%ProgramData%\Sora`.
+pub fn system_config_dir() -> Option<PathBuf> {
+    if cfg!(target_os = "windows") {
+        Some(PathBuf::from(std::env::var_os("ProgramData")?).join("Sora"))
+    } else if cfg!(target_os = "macos") {
+        Some(PathBuf::from("/Library/Application Support/Sora"))
+    } else {
+        Some(PathBuf::from("/etc/sora"))
+    }
+}
+
+/// Merge `overlay` into `base`: objects key by key, anything else replaced.
+fn merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                merge(base.entry(key).or_insert(Value::Null), value);
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// The `*.json` fragments in `dir`, in file name order. Unreadable or
+/// malformed files are logged and skipped.
+fn read_fragments(dir: &Path) -> Vec<Value> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let json = std::fs::read_to_string(&path)
+                .map_err(|e| log::warn!("Failed to read {}: {e}", path.display()))
+                .ok()?;
+            serde_json::from_str(&json)
+                .map_err(|e| log::warn!("Ignoring malformed {}: {e}", path.display()))
+                .ok()
+        })
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn managed_preferences() -> Option<Value> {
+    let path = Path::new("/Library/Managed Preferences/dev.lutelute.sora.plist");
+    if !path.exists() {
+        return None;
+    }
+    plist::from_file(path)
+        .map_err(|e| log::warn!("Ignoring malformed managed preferences: {e}"))
+        .ok()
+}
+
+#[cfg(windows)]
+fn group_policy() -> Option<Value> {
+    use windows::core::w;
+    use windows::Win32::System::Registry::{RegGetValueW, HKEY_LOCAL_MACHINE, RRF_RT_REG_SZ};
+
+    let key = w!("SOFTWARE\\Policies\\Sora");
+    let mut size = 0u32;
+    // SAFETY: size query; no buffer is written
+    unsafe {
+        RegGetValueW(
+            HKEY_LOCAL_MACHINE,
+            key,
+            w!("Policy"),
+            RRF_RT_REG_SZ,
+            None,
+            None,
+            Some(&mut size as *mut u32),
+        )
+    }
+    .ok()
+    .ok()?;
+    let mut buf = vec![0u16; (size as usize).div_ceil(2)];
+    // SAFETY: `buf` holds `size` bytes
+    unsafe {
+        RegGetValueW(
+            HKEY_LOCAL_MACHINE,
+            key,
+            w!("Policy"),
+            RRF_RT_REG_SZ,
+            None,
+            Some(buf.as_mut_ptr().cast()),
+            Some(&mut size as *mut u32),
+        )
+    }
+    .ok()
+    .ok()?;
+    let json = String::from_utf16_lossy(&buf[..size as usize / 2]);
+    serde_json::from_str(json.trim_end_matches('\0'))
+        .map_err(|e| log::warn!("Ignoring malformed group policy: {e}"))
+        .ok()
+}
+
+/// Whether `after` sets a leaf of `locked` to something other than both
+/// its `before` value and the locked value.
+fn changes_locked(locked: &Value, before: &Value, after: &Value) -> bool {
+    match locked {
+        Value::Object(locked) => locked.iter().any(|(key, locked)| {
+            changes_locked(
+                locked,
+                before.get(key).unwrap_or(&Value::Null),
+                after.get(key).unwrap_or(&Value::Null),
+            )
+        }),
+        locked => after != before && after != locked,
+    }
+}
+
+fn parse(sources: Vec<Value>) -> ManagedPolicy {
+    let mut merged = Value::Object(Map::new());
+    for source in sources {
+        merge(&mut merged, source);
+    }
+    serde_json::from_value(merged)
+        .map_err(|e| log::warn!("Ignoring invalid managed configuration: {e}"))
+        .unwrap_or_default()
+}
+
+fn load() -> ManagedPolicy {
+    let dir = std::env::var_os(MANAGED_DIR_ENV)
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| system_config_dir().map(|d| d.join(MANAGED_DIR)));
+    #[allow(unused_mut)]
+    let mut sources = dir.as_deref().map(read_fragments).unwrap_or_default();
+    #[cfg(target_os = "macos")]
+    sources.extend(managed_preferences());
+    #[cfg(windows)]
+    sources.extend(group_policy());
+
+    let policy = parse(sources);
+    if !policy.settings.is_empty() || !policy.accounts.is_empty() {
+        log::info!(
+            "Managed configuration: {} locked setting(s), {} account(s)",
+            policy.settings.len(),
+            policy.accounts.len()
+        );
+    }
+    policy
+}
+
+/// The managed policy, read on first use.
+pub fn policy() -> &'static ManagedPolicy {
+    static POLICY: OnceLock<ManagedPolicy> = OnceLock::new();
+    POLICY.get_or_init(load)
+}
+
+impl ManagedPolicy {
+    /// Fail if an IMAP/SMTP config signs in or connects in a way the
+    /// policy forbids.
+    pub fn check_config(
+        &self,
+        auth_method: &str,
+        accept_invalid_certs: bool,
+    ) -> Result<(), String> {
+        if !self.allowed_auth_methods.is_empty()
+            && !self.allowed_auth_methods.iter().any(|m| m == auth_method)
+        {
+            return Err(format!(
+                "Your organization doesn't allow {auth_method} sign-in"
+            ));
+        }
+        if self.disable_accept_invalid_certs && accept_invalid_certs {
+            return Err(
+                "Your organization doesn't allow accepting invalid certificates".to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    /// `settings` with the policy's values taking precedence.
+    pub fn apply_settings(&self, settings: BackendSettings) -> BackendSettings {
+        if self.settings.is_empty() {
+            return settings;
+        }
+        let Ok(mut value) = serde_json::to_value(&settings) else {
+            return settings;
+        };
+        merge(&mut value, Value::Object(self.settings.clone()));
+        serde_json::from_value(value)
+            .map_err(|e| log::warn!("Ignoring invalid managed settings: {e}"))
+            .unwrap_or(settings)
+    }
+
+    /// Fail if going from `before` to `after` changes a locked setting.
+    /// Writing back the managed value itself (e.g. a settings form saving
+    /// everything it shows) isn't a change.
+    pub fn check_settings_change(
+        &self,
+        before: &BackendSettings,
+        after: &BackendSettings,
+    ) -> Result<(), String> {
+        if self.settings.is_empty() {
+            return Ok(());
+        }
+        let (Ok(before), Ok(after)) = (serde_json::to_value(before), serde_json::to_value(after))
+        else {
+            return Ok(());
+        };
+        if changes_locked(&Value::Object(self.settings.clone()), &before, &after) {
+            return Err(MANAGED_SETTING_ERROR.to_string());
+        }
+        Ok(())
+    }
+
+    fn summary(&self) -> ManagedPolicySummary {
+        ManagedPolicySummary {
+            allowed_auth_methods: self.allowed_auth_methods.clone(),
+            disable_accept_invalid_certs: self.disable_accept_invalid_certs,
+            locked_settings: self.settings.keys().cloned().collect(),
+            account_ids: self.accounts.iter().map(|a| a.id.clone()).collect(),
+        }
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_managed_policy() -> ManagedPolicySummary {
+    policy().summary()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_merge_and_enforce() {
+        let policy = parse(vec![
+            json!({
+                "allowed_auth_methods": ["password"],
+                "settings": { "read_only": true, "send_policy": { "max_cc": 5 } }
+            }),
+            json!({
+                "allowed_auth_methods": ["oauth2"],
+                "disable_accept_invalid_certs": true,
+                "settings": { "send_policy": { "old_thread_days": 30 } }
+            }),
+        ]);
+
+        // Later sources win; objects merge key by key
+        assert_eq!(policy.allowed_auth_methods, ["oauth2"]);
+        assert!(policy.check_config("oauth2", false).is_ok());
+        assert!(policy.check_config("password", false).is_err());
+        assert!(policy.check_config("oauth2", true).is_err());
+
+        let user = BackendSettings {
+            start_hidden: false,
+            ..Default::default()
+        };
+        let effective = policy.apply_settings(user.clone());
+        assert!(effective.read_only);
+        assert!(!effective.start_hidden);
+        assert_eq!(effective.send_policy.max_cc, Some(5));
+        assert_eq!(effective.send_policy.old_thread_days, Some(30));
+        // Keys the policy leaves out keep the user's value
+        assert!(effective.send_policy.warn_empty_subject);
+
+        let mut changed = user.clone();
+        changed.start_hidden = true;
+        changed.send_policy.max_cc = Some(5);
+        changed.send_policy.warn_empty_subject = false;
+        assert!(policy.check_settings_change(&user, &changed).is_ok());
+        changed.send_policy.max_cc = Some(50);
+        assert_eq!(
+            policy.check_settings_change(&user, &changed).unwrap_err(),
+            MANAGED_SETTING_ERROR
+        );
+    }
+}
@@ -0,0 +1,177 @@
+//! In-memory timing metrics for mail server operations.
+//!
+//! Connection setup, authentication, each IMAP/SMTP command and message
+//! parsing record how long they took, grouped by server host and operation,
+//! so reports of a slow server can be checked against real numbers via
+//! `get_metrics`. Nothing is persisted; the registry starts empty each run.
+
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use specta::Type;
+
+/// Recent samples kept per metric for percentiles.
+const MAX_SAMPLES: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum Phase {
+    Connect,
+    Auth,
+    Command,
+    Parse,
+}
+
+#[derive(Default)]
+struct Stats {
+    count: u64,
+    errors: u64,
+    total_ms: f64,
+    max_ms: f64,
+    last_ms: f64,
+    samples: VecDeque<f64>,
+}
+
+type Key = (String, String, Phase);
+
+#[derive(Debug, Serialize, Type)]
+pub struct MetricSummary {
+    /// Server host; empty for work that doesn't talk to a server (parsing).
+    pub host: String,
+    pub operation: String,
+    pub phase: Phase,
+    pub count: u64,
+    pub errors: u64,
+    pub avg_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+    pub last_ms: f64,
+}
+
+fn registry() -> &'static Mutex<HashMap<Key, Stats>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<Key, Stats>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Record one timed operation.
+pub fn record(host: &str, operation: &str, phase: Phase, elapsed: Duration, ok: bool) {
+    let ms = elapsed.as_secs_f64() * 1000.0;
+    let mut registry = registry().lock().unwrap_or_else(|e| e.into_inner());
+    let stats = registry
+        .entry((host.to_ascii_lowercase(), operation.to_string(), phase))
+        .or_default();
+    stats.count += 1;
+    if !ok {
+        stats.errors += 1;
+    }
+    stats.total_ms += ms;
+    stats.max_ms = stats.max_ms.max(ms);
+    stats.last_ms = ms;
+    if stats.samples.len() == MAX_SAMPLES {
+        stats.samples.pop_front();
+    }
+    stats.samples.push_back(ms);
+}
+
+/// Await `fut` and record how long it took and whether it succeeded.
+pub async fn time<T, E, F>(host: &str, operation: &str, phase: Phase, fut: F) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    let start = Instant::now();
+    let result = fut.await;
+    record(host, operation, phase, start.elapsed(), result.is_ok());
+    result
+}
+
+/// Nearest-rank percentile of sorted samples.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+fn summarize(key: &Key, stats: &Stats) -> MetricSummary {
+    let mut sorted: Vec<f64> = stats.samples.iter().copied().collect();
+    sorted.sort_by(f64::total_cmp);
+    MetricSummary {
+        host: key.0.clone(),
+        operation: key.1.clone(),
+        phase: key.2,
+        count: stats.count,
+        errors: stats.errors,
+        avg_ms: stats.total_ms / stats.count.max(1) as f64,
+        p50_ms: percentile(&sorted, 50.0),
+        p95_ms: percentile(&sorted, 95.0),
+        max_ms: stats.max_ms,
+        last_ms: stats.last_ms,
+    }
+}
+
+/// Timing summaries for every recorded operation, grouped by host. Pass
+/// `reset` to start a fresh measurement window afterwards.
+#[tauri::command]
+#[specta::specta]
+pub fn get_metrics(reset: Option<bool>) -> Vec<MetricSummary> {
+    let mut registry = registry().lock().unwrap_or_else(|e| e.into_inner());
+    let mut summaries: Vec<MetricSummary> = registry
+        .iter()
+        .map(|(key, stats)| summarize(key, stats))
+        .collect();
+    summaries
+        .sort_by(|a, b| (&a.host, &a.operation, a.phase).cmp(&(&b.host, &b.operation, b.phase)));
+    if reset.unwrap_or(false) {
+        registry.clear();
+    }
+    summaries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile_nearest_rank() {
+        let sorted: Vec<f64> = (1..=100).map(f64::from).collect();
+        assert_eq!(percentile(&sorted, 50.0), 50.0);
+        assert_eq!(percentile(&sorted, 95.0), 95.0);
+        assert_eq!(percentile(&[7.0], 95.0), 7.0);
+        assert_eq!(percentile(&[], 50.0), 0.0);
+    }
+
+    #[test]
+    fn test_record_and_summarize() {
+        let host = "metrics-test.example.com";
+        record(
+            host,
+            "fetch_messages",
+            Phase::Command,
+            Duration::from_millis(10),
+            true,
+        );
+        record(
+            host,
+            "fetch_messages",
+            Phase::Command,
+            Duration::from_millis(30),
+            false,
+        );
+
+        let summary = get_metrics(None)
+            .into_iter()
+            .find(|m| m.host == host)
+            .unwrap();
+        assert_eq!(summary.operation, "fetch_messages");
+        assert_eq!(summary.count, 2);
+        assert_eq!(summary.errors, 1);
+        assert!((summary.avg_ms - 20.0).abs() < 0.5);
+        assert!((summary.max_ms - 30.0).abs() < 0.5);
+        assert!((summary.last_ms - 30.0).abs() < 0.5);
+    }
+}
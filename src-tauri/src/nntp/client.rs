@@ -0,0 +1,639 @@
+//! NNTP client (RFC 3977): newsgroup lists, overview headers, articles and
+//! posting. Articles are parsed like IMAP messages, with the article number
+//! as UID and the group as folder, so newsgroups sync through the same
+//! folder/message code as mail.
+//!
+//! Each call opens its own connection: newsreaders talk to a server rarely
+//! and briefly, so there's no session pool.
+
+use std::future::Future;
+use std::time::Duration;
+
+use mail_parser::MessageParser;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+
+use super::types::{NntpConfig, NntpGroup, NntpGroupStatus, NntpOverview};
+use crate::imap::client::{build_tls_connector, configure_tcp_socket, parse_message, ImapStream};
+use crate::imap::types::ImapMessage;
+
+const TCP_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(30);
+const CMD_TIMEOUT: Duration = Duration::from_secs(30);
+/// Full group lists and large overview ranges take a while on big servers.
+const LIST_TIMEOUT: Duration = Duration::from_secs(120);
+/// Multi-line responses larger than this are refused, so a misbehaving
+/// server can't exhaust memory.
+const MAX_RESPONSE_BYTES: usize = 64 * 1024 * 1024;
+
+/// An authenticated connection in reader mode.
+pub struct NntpSession {
+    stream: BufReader<ImapStream>,
+    /// From the greeting or `MODE READER`: 200 allows posting, 201 doesn't.
+    posting_allowed: bool,
+}
+
+/// Reject command arguments that would break the command line, such as
+/// group names or credentials containing spaces or line breaks.
+fn check_argument(kind: &str, value: &str) -> Result<(), String> {
+    if value.is_empty() || value.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(format!("Invalid {kind}: {value:?}"));
+    }
+    Ok(())
+}
+
+async fn with_timeout<T>(
+    duration: Duration,
+    what: &str,
+    fut: impl Future<Output = Result<T, String>>,
+) -> Result<T, String> {
+    tokio::time::timeout(duration, fut).await.map_err(|_| {
+        format!(
+            "{what} timed out after {}s — check your server settings or network connection",
+            duration.as_secs()
+        )
+    })?
+}
+
+/// Split a status line like `211 1234 3000 4233 comp.lang.rust` into its
+/// code and text.
+fn parse_status_line(line: &str) -> Result<(u16, String), String> {
+    let line = line.trim_end_matches(['\r', '\n']);
+    let code = line
+        .get(..3)
+        .and_then(|c| c.parse::<u16>().ok())
+        .ok_or_else(|| format!("Unexpected NNTP response: {line}"))?;
+    Ok((code, line[3..].trim().to_string()))
+}
+
+/// Read a dot-terminated multi-line block, undoing dot-stuffing. Lines
+/// come back joined with CRLF.
+async fn read_multiline<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<Vec<u8>, String> {
+    let mut block = Vec::new();
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        let n = reader
+            .read_until(b'\n', &mut line)
+            .await
+            .map_err(|e| format!("Failed to read NNTP response: {e}"))?;
+        if n == 0 {
+            return Err("Connection closed in the middle of a response".to_string());
+        }
+        let mut content = line.as_slice();
+        while let Some(rest) = content
+            .strip_suffix(b"\n")
+            .or_else(|| content.strip_suffix(b"\r"))
+        {
+            content = rest;
+        }
+        if content == b"." {
+            return Ok(block);
+        }
+        let content = content.strip_prefix(b".").unwrap_or(content);
+        if block.len() + content.len() > MAX_RESPONSE_BYTES {
+            return Err(format!(
+                "NNTP response exceeds {} MB",
+                MAX_RESPONSE_BYTES / (1024 * 1024)
+            ));
+        }
+        block.extend_from_slice(content);
+        block.extend_from_slice(b"\r\n");
+    }
+}
+
+/// Prepare an article for `POST`: CRLF line endings, dot-stuffing and the
+/// terminating dot line.
+fn dot_stuff(article: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(article.len() + 16);
+    for line in article.split_inclusive(|&b| b == b'\n') {
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        if line.starts_with(b".") {
+            out.push(b'.');
+        }
+        out.extend_from_slice(line);
+        out.extend_from_slice(b"\r\n");
+    }
+    out.extend_from_slice(b".\r\n");
+    out
+}
+
+/// Address a composed message to `newsgroups`: sets the Newsgroups header
+/// and drops Bcc, which must not be published with the article.
+pub fn prepare_article(raw: &[u8], newsgroups: &[String]) -> Result<Vec<u8>, String> {
+    if newsgroups.is_empty() {
+        return Err("Choose at least one newsgroup to post to".to_string());
+    }
+    for group in newsgroups {
+        check_argument("newsgroup", group)?;
+        if group.contains(',') {
+            return Err(format!("Invalid newsgroup: {group:?}"));
+        }
+    }
+
+    let header_end = raw
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .map(|i| i + 2)
+        .or_else(|| raw.windows(2).position(|w| w == b"\n\n").map(|i| i + 1))
+        .ok_or("The article has no body")?;
+    let (headers, body) = raw.split_at(header_end);
+
+    let mut out = format!("Newsgroups: {}\r\n", newsgroups.join(",")).into_bytes();
+    let mut skipping = false;
+    for line in headers.split_inclusive(|&b| b == b'\n') {
+        let continuation = line.starts_with(b" ") || line.starts_with(b"\t");
+        if !continuation {
+            let name = line.split(|&b| b == b':').next().unwrap_or_default();
+            skipping =
+                name.eq_ignore_ascii_case(b"bcc") || name.eq_ignore_ascii_case(b"newsgroups");
+        }
+        if !skipping {
+            out.extend_from_slice(line);
+        }
+    }
+    out.extend_from_slice(body);
+    Ok(out)
+}
+
+/// Parse a `LIST ACTIVE` line: `name high low status`.
+fn parse_active_line(line: &str) -> Option<NntpGroup> {
+    let mut fields = line.split_whitespace();
+    let name = fields.next()?.to_string();
+    let last = fields.next()?.parse().ok()?;
+    let first = fields.next()?.parse().ok()?;
+    let status = fields.next().unwrap_or("y");
+    Some(NntpGroup {
+        name,
+        first,
+        last,
+        posting_allowed: status == "y",
+    })
+}
+
+/// Parse the text of a `211` reply: `count first last name`.
+fn parse_group_response(text: &str) -> Result<NntpGroupStatus, String> {
+    let fields: Vec<&str> = text.split_whitespace().collect();
+    let number = |i: usize| fields.get(i).and_then(|f| f.parse::<u32>().ok());
+    match (number(0), number(1), number(2), fields.get(3)) {
+        (Some(count), Some(first), Some(last), Some(name)) => Ok(NntpGroupStatus {
+            name: name.to_string(),
+            count,
+            first,
+            last,
+        }),
+        _ => Err(format!("Unexpected GROUP response: {text}")),
+    }
+}
+
+/// Parse an overview line: number, subject, from, date, message-id,
+/// references, bytes and lines separated by tabs, then optional extras.
+fn parse_overview_line(line: &str) -> Option<NntpOverview> {
+    let fields: Vec<&str> = line.split('\t').collect();
+    let field = |i: usize| {
+        fields
+            .get(i)
+            .map(|f| f.trim().to_string())
+            .unwrap_or_default()
+    };
+    Some(NntpOverview {
+        number: fields.first()?.trim().parse().ok()?,
+        subject: field(1),
+        from: field(2),
+        date: field(3),
+        message_id: field(4),
+        references: field(5),
+        bytes: field(6).parse().unwrap_or(0),
+        lines: field(7).parse().unwrap_or(0),
+    })
+}
+
+async fn tcp_connect(config: &NntpConfig) -> Result<TcpStream, String> {
+    let tcp = tokio::time::timeout(TCP_CONNECT_TIMEOUT, TcpStream::connect((&*config.host, config.port)))
+        .await
+        .map_err(|_| format!(
+            "TCP connect to {}:{} timed out after {}s — check your server settings or network connection",
+            config.host, config.port, TCP_CONNECT_TIMEOUT.as_secs()
+        ))?
+        .map_err(|e| format!("TCP connect to {}:{} failed: {e}", config.host, config.port))?;
+    configure_tcp_socket(&tcp);
+    Ok(tcp)
+}
+
+async fn tls_handshake(config: &NntpConfig, tcp: TcpStream) -> Result<ImapStream, String> {
+    let connector =
+        tokio_native_tls::TlsConnector::from(build_tls_connector(config.accept_invalid_certs)?);
+    with_timeout(
+        TLS_HANDSHAKE_TIMEOUT,
+        &format!("TLS handshake with {}", config.host),
+        async {
+            connector
+                .connect(&config.host, tcp)
+                .await
+                .map(ImapStream::Tls)
+                .map_err(|e| format!("TLS handshake with {} failed: {e}", config.host))
+        },
+    )
+    .await
+}
+
+/// Connect, switch to reader mode and authenticate if the config has a
+/// username. Supports TLS (direct), STARTTLS and plain connections.
+pub async fn connect(config: &NntpConfig) -> Result<NntpSession, String> {
+    let tcp = tcp_connect(config).await?;
+    let stream = match config.security.as_str() {
+        "tls" => tls_handshake(config, tcp).await?,
+        "starttls" | "none" => ImapStream::Plain(tcp),
+        other => {
+            return Err(format!(
+                "Unknown security mode: {other}. Use \"tls\", \"starttls\", or \"none\"."
+            ))
+        }
+    };
+    let mut session = NntpSession {
+        stream: BufReader::new(stream),
+        posting_allowed: false,
+    };
+
+    let (code, text) = session.read_status(CMD_TIMEOUT).await?;
+    match code {
+        200 | 201 => session.posting_allowed = code == 200,
+        _ => return Err(format!("Unexpected server greeting: {code} {text}")),
+    }
+
+    if config.security == "starttls" {
+        session.expect("STARTTLS", &[382]).await?;
+        // The server sends nothing more until the handshake, so no buffered
+        // bytes are lost here
+        let ImapStream::Plain(tcp) = session.stream.into_inner() else {
+            unreachable!("STARTTLS sessions start in plain text")
+        };
+        session.stream = BufReader::new(tls_handshake(config, tcp).await?);
+    }
+
+    // Servers that also act as transit agents only list groups in reader mode
+    let (code, _) = session.command("MODE READER").await?;
+    if code == 200 || code == 201 {
+        session.posting_allowed = code == 200;
+    }
+
+    if !config.username.is_empty() {
+        session.authenticate(config).await?;
+    }
+    Ok(session)
+}
+
+impl NntpSession {
+    async fn read_status(&mut self, timeout: Duration) -> Result<(u16, String), String> {
+        let stream = &mut self.stream;
+        with_timeout(timeout, "NNTP response", async {
+            let mut line = String::new();
+            let n = stream
+                .read_line(&mut line)
+                .await
+                .map_err(|e| format!("Failed to read NNTP response: {e}"))?;
+            if n == 0 {
+                return Err("NNTP server closed the connection".to_string());
+            }
+            parse_status_line(&line)
+        })
+        .await
+    }
+
+    async fn send_line(&mut self, line: &str) -> Result<(), String> {
+        let stream = self.stream.get_mut();
+        stream
+            .write_all(format!("{line}\r\n").as_bytes())
+            .await
+            .map_err(|e| format!("Failed to send NNTP command: {e}"))?;
+        stream
+            .flush()
+            .await
+            .map_err(|e| format!("Failed to send NNTP command: {e}"))
+    }
+
+    async fn command(&mut self, line: &str) -> Result<(u16, String), String> {
+        self.send_line(line).await?;
+        self.read_status(CMD_TIMEOUT).await
+    }
+
+    /// Send `line` and fail unless the reply code is one of `expected`.
+    async fn expect(&mut self, line: &str, expected: &[u16]) -> Result<(u16, String), String> {
+        let (code, text) = self.command(line).await?;
+        if !expected.contains(&code) {
+            let verb = line.split(' ').next().unwrap_or(line);
+            return Err(format!("{verb} failed: {code} {text}"));
+        }
+        Ok((code, text))
+    }
+
+    async fn read_block(&mut self, timeout: Duration) -> Result<Vec<u8>, String> {
+        let stream = &mut self.stream;
+        with_timeout(timeout, "NNTP response", read_multiline(stream)).await
+    }
+
+    async fn authenticate(&mut self, config: &NntpConfig) -> Result<(), String> {
+        check_argument("username", &config.username)?;
+        let (code, text) = self
+            .command(&format!("AUTHINFO USER {}", config.username))
+            .await?;
+        let (code, text) = if code == 381 {
+            if config.password.contains(['\r', '\n']) {
+                return Err("Invalid password".to_string());
+            }
+            self.command(&format!("AUTHINFO PASS {}", config.password))
+                .await?
+        } else {
+            (code, text)
+        };
+        if code != 281 {
+            return Err(format!("NNTP authentication failed: {code} {text}"));
+        }
+        Ok(())
+    }
+
+    /// Select `group` and return its article range.
+    pub async fn group(&mut self, group: &str) -> Result<NntpGroupStatus, String> {
+        check_argument("newsgroup", group)?;
+        let (code, text) = self.command(&format!("GROUP {group}")).await?;
+        match code {
+            211 => parse_group_response(&text),
+            411 => Err(format!("No such newsgroup: {group}")),
+            _ => Err(format!("GROUP failed: {code} {text}")),
+        }
+    }
+
+    /// Groups matching the wildmat `pattern` (e.g. `comp.lang.*`), or all
+    /// of them.
+    pub async fn list_groups(&mut self, pattern: Option<&str>) -> Result<Vec<NntpGroup>, String> {
+        let line = match pattern {
+            Some(pattern) => {
+                check_argument("group pattern", pattern)?;
+                format!("LIST ACTIVE {pattern}")
+            }
+            None => "LIST ACTIVE".to_string(),
+        };
+        self.expect(&line, &[215]).await?;
+        let block = self.read_block(LIST_TIMEOUT).await?;
+        Ok(String::from_utf8_lossy(&block)
+            .lines()
+            .filter_map(parse_active_line)
+            .collect())
+    }
+
+    /// Overview lines of articles `first..=last` in the selected group,
+    /// falling back to `XOVER` on servers older than RFC 3977.
+    pub async fn overview(&mut self, first: u32, last: u32) -> Result<Vec<NntpOverview>, String> {
+        let range = format!("{first}-{last}");
+        let (mut code, mut text) = self.command(&format!("OVER {range}")).await?;
+        if code == 500 {
+            (code, text) = self.command(&format!("XOVER {range}")).await?;
+        }
+        match code {
+            224 => {}
+            // No articles in the range
+            420 | 423 => return Ok(Vec::new()),
+            _ => return Err(format!("OVER failed: {code} {text}")),
+        }
+        let block = self.read_block(LIST_TIMEOUT).await?;
+        Ok(String::from_utf8_lossy(&block)
+            .lines()
+            .filter_map(parse_overview_line)
+            .collect())
+    }
+
+    /// The raw article `number` of the selected group, or `None` if it has
+    /// expired or been cancelled.
+    pub async fn article(&mut self, number: u32) -> Result<Option<Vec<u8>>, String> {
+        let (code, text) = self.command(&format!("ARTICLE {number}")).await?;
+        match code {
+            220 => self.read_block(LIST_TIMEOUT).await.map(Some),
+            423 | 430 => Ok(None),
+            _ => Err(format!("ARTICLE {number} failed: {code} {text}")),
+        }
+    }
+
+    /// Post a complete article, headers included.
+    pub async fn post(&mut self, article: &[u8]) -> Result<(), String> {
+        let (code, text) = self.command("POST").await?;
+        match code {
+            340 => {}
+            440 => return Err(format!("Posting is not allowed on this server: {text}")),
+            _ => return Err(format!("POST failed: {code} {text}")),
+        }
+        let stream = self.stream.get_mut();
+        stream
+            .write_all(&dot_stuff(article))
+            .await
+            .map_err(|e| format!("Failed to send article: {e}"))?;
+        stream
+            .flush()
+            .await
+            .map_err(|e| format!("Failed to send article: {e}"))?;
+        let (code, text) = self.read_status(LIST_TIMEOUT).await?;
+        if code != 240 {
+            return Err(format!("The server rejected the article: {code} {text}"));
+        }
+        Ok(())
+    }
+
+    pub async fn quit(mut self) {
+        let _ = self.command("QUIT").await;
+    }
+}
+
+/// Connect and report whether the server lets this account read and post.
+pub async fn test_connection(config: &NntpConfig) -> Result<String, String> {
+    let session = connect(config).await?;
+    let posting = session.posting_allowed;
+    session.quit().await;
+    Ok(if posting {
+        "Connected successfully. Posting is allowed.".to_string()
+    } else {
+        "Connected successfully. This server is read-only.".to_string()
+    })
+}
+
+pub async fn list_groups(
+    config: &NntpConfig,
+    pattern: Option<&str>,
+) -> Result<Vec<NntpGroup>, String> {
+    let mut session = connect(config).await?;
+    let groups = session.list_groups(pattern).await;
+    session.quit().await;
+    groups
+}
+
+pub async fn group_status(config: &NntpConfig, group: &str) -> Result<NntpGroupStatus, String> {
+    let mut session = connect(config).await?;
+    let status = session.group(group).await;
+    session.quit().await;
+    status
+}
+
+pub async fn fetch_overview(
+    config: &NntpConfig,
+    group: &str,
+    first: u32,
+    last: u32,
+) -> Result<Vec<NntpOverview>, String> {
+    let mut session = connect(config).await?;
+    let result = async {
+        session.group(group).await?;
+        session.overview(first, last).await
+    }
+    .await;
+    session.quit().await;
+    result
+}
+
+/// Fetch and parse articles of `group` by number. Articles that are gone
+/// from the server are left out.
+pub async fn fetch_articles(
+    config: &NntpConfig,
+    group: &str,
+    numbers: &[u32],
+) -> Result<Vec<ImapMessage>, String> {
+    let mut session = connect(config).await?;
+    let result = async {
+        session.group(group).await?;
+        let parser = MessageParser::default();
+        let mut messages = Vec::with_capacity(numbers.len());
+        for &number in numbers {
+            if let Some(raw) = session.article(number).await? {
+                let size = raw.len() as u32;
+                messages.push(parse_message(
+                    &parser, &raw, number, group, size, false, false, false, None,
+                ));
+            }
+        }
+        Ok(messages)
+    }
+    .await;
+    session.quit().await;
+    result
+}
+
+/// The raw source of one article, for the raw view and attachments.
+pub async fn fetch_raw_article(
+    config: &NntpConfig,
+    group: &str,
+    number: u32,
+) -> Result<Vec<u8>, String> {
+    let mut session = connect(config).await?;
+    let result = async {
+        session.group(group).await?;
+        session
+            .article(number)
+            .await?
+            .ok_or_else(|| format!("Article {number} is no longer on the server"))
+    }
+    .await;
+    session.quit().await;
+    result
+}
+
+pub async fn post(config: &NntpConfig, article: &[u8]) -> Result<(), String> {
+    let mut session = connect(config).await?;
+    let result = session.post(article).await;
+    session.quit().await;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_lines() {
+        assert_eq!(
+            parse_status_line("211 12 3000 3011 comp.lang.rust\r\n").unwrap(),
+            (211, "12 3000 3011 comp.lang.rust".to_string())
+        );
+        assert_eq!(parse_status_line("200\r\n").unwrap(), (200, String::new()));
+        assert!(parse_status_line("* OK IMAP4rev1\r\n").is_err());
+    }
+
+    #[test]
+    fn group_and_active_lines() {
+        assert_eq!(
+            parse_group_response("12 3000 3011 comp.lang.rust").unwrap(),
+            NntpGroupStatus {
+                name: "comp.lang.rust".to_string(),
+                count: 12,
+                first: 3000,
+                last: 3011,
+            }
+        );
+        assert!(parse_group_response("nonsense").is_err());
+
+        let group = parse_active_line("comp.lang.rust 3011 3000 y").unwrap();
+        assert_eq!(
+            (group.first, group.last, group.posting_allowed),
+            (3000, 3011, true)
+        );
+        assert!(
+            !parse_active_line("news.announce.newgroups 90 1 m")
+                .unwrap()
+                .posting_allowed
+        );
+        assert!(parse_active_line("broken").is_none());
+    }
+
+    #[test]
+    fn overview_lines() {
+        let line = "3011\tRe: Pinning\tAda <ada@example.org>\tTue, 06 Oct 2026 09:30:00 +0000\t<b@example.org>\t<a@example.org>\t2048\t40\tXref: news.example.org comp.lang.rust:3011";
+        let overview = parse_overview_line(line).unwrap();
+        assert_eq!(overview.number, 3011);
+        assert_eq!(overview.subject, "Re: Pinning");
+        assert_eq!(overview.message_id, "<b@example.org>");
+        assert_eq!(overview.references, "<a@example.org>");
+        assert_eq!((overview.bytes, overview.lines), (2048, 40));
+
+        let short = parse_overview_line("7\tHello\tBob").unwrap();
+        assert_eq!((short.number, short.bytes), (7, 0));
+        assert!(parse_overview_line("\tno number").is_none());
+    }
+
+    #[tokio::test]
+    async fn multiline_blocks_are_unstuffed() {
+        let mut response: &[u8] = b"Subject: hi\r\n\r\n..dotted\r\nplain\n.\r\n211 next\r\n";
+        let block = read_multiline(&mut response).await.unwrap();
+        assert_eq!(block, b"Subject: hi\r\n\r\n.dotted\r\nplain\r\n");
+        assert_eq!(response, b"211 next\r\n");
+
+        let mut truncated: &[u8] = b"line\r\n";
+        assert!(read_multiline(&mut truncated).await.is_err());
+    }
+
+    #[test]
+    fn articles_are_dot_stuffed() {
+        assert_eq!(
+            dot_stuff(b"Subject: x\n\n.hidden\r\nend"),
+            b"Subject: x\r\n\r\n..hidden\r\nend\r\n.\r\n"
+        );
+    }
+
+    #[test]
+    fn prepared_articles_name_their_groups() {
+        let raw = b"From: a@example.org\r\nBcc: secret@example.org,\r\n other@example.org\r\nNewsgroups: old.group\r\nSubject: hi\r\n\r\nBody\r\n";
+        let article =
+            prepare_article(raw, &["comp.lang.rust".to_string(), "alt.test".to_string()]).unwrap();
+        assert_eq!(
+            String::from_utf8(article).unwrap(),
+            "Newsgroups: comp.lang.rust,alt.test\r\nFrom: a@example.org\r\nSubject: hi\r\n\r\nBody\r\n"
+        );
+
+        assert!(prepare_article(raw, &[]).is_err());
+        assert!(prepare_article(raw, &["bad group".to_string()]).is_err());
+        assert!(prepare_article(b"no body", &["alt.test".to_string()]).is_err());
+    }
+
+    #[test]
+    fn arguments_cannot_inject_commands() {
+        assert!(check_argument("newsgroup", "comp.lang.rust").is_ok());
+        assert!(check_argument("newsgroup", "alt.test\r\nQUIT").is_err());
+        assert!(check_argument("newsgroup", "").is_err());
+    }
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct NntpConfig {
+    pub host: String,
+    pub port: u16,
+    pub security: String, // "tls", "starttls", "none"
+    /// Empty for servers that allow reading without AUTHINFO.
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub password: String,
+    #[serde(default)]
+    pub accept_invalid_certs: bool,
+}
+
+/// A newsgroup from `LIST ACTIVE`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct NntpGroup {
+    pub name: String,
+    /// Lowest and highest article numbers; `last < first` when empty.
+    pub first: u32,
+    pub last: u32,
+    /// The server accepts posts to this group (flag `y`, not `n` or moderated `m`).
+    pub posting_allowed: bool,
+}
+
+/// A group's article range, as returned by `GROUP`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct NntpGroupStatus {
+    pub name: String,
+    /// Estimated number of articles.
+    pub count: u32,
+    pub first: u32,
+    pub last: u32,
+}
+
+/// One line of the overview database (`OVER`/`XOVER`): an article's
+/// headers without fetching it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct NntpOverview {
+    pub number: u32,
+    pub subject: String,
+    pub from: String,
+    pub date: String,
+    pub message_id: String,
+    pub references: String,
+    pub bytes: u32,
+    pub lines: u32,
+}
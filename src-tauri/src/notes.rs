@@ -0,0 +1,387 @@
+//! Private notes attached to messages and threads.
+//!
+//! Notes live in the `notes` table, keyed by the Message-ID header of the
+//! message they belong to (so they survive a resync that changes local
+//! message ids) and the thread it's in, with a `notes_fts` index over their
+//! text. They never leave the device unless the user turns on note sync,
+//! which `services/notes.ts` does by keeping a copy of each note as a
+//! message in the account's Notes folder; `notes_import` and
+//! `notes_mark_synced` are its side of that.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use sqlx::SqlitePool;
+use tauri::State;
+use tauri_plugin_sql::{DbInstances, DbPool};
+
+use crate::profiles::Profiles;
+
+const DEFAULT_SEARCH_LIMIT: i64 = 50;
+const MAX_SEARCH_LIMIT: i64 = 500;
+
+/// Shortest query the trigram index can match; shorter ones scan the table.
+const MIN_FTS_QUERY_CHARS: usize = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct Note {
+    pub id: String,
+    pub account_id: String,
+    /// Message-ID header (without angle brackets) of the message the note
+    /// is attached to; `None` for a note on a whole thread.
+    pub message_id_header: Option<String>,
+    pub thread_id: Option<String>,
+    pub body: String,
+    /// Unix timestamps in seconds.
+    pub created_at: i64,
+    pub updated_at: i64,
+    /// When the note was last copied to or from the server, if ever.
+    pub synced_at: Option<i64>,
+}
+
+/// A new note, or changes to one when `id` is set.
+#[derive(Debug, Deserialize, Type)]
+pub struct NoteInput {
+    pub id: Option<String>,
+    pub message_id_header: Option<String>,
+    pub thread_id: Option<String>,
+    pub body: String,
+}
+
+type Row = (
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    String,
+    i64,
+    i64,
+    Option<i64>,
+);
+
+const COLUMNS: &str =
+    "id, account_id, message_id_header, thread_id, body, created_at, updated_at, synced_at";
+
+fn note_from_row(
+    (id, account_id, message_id_header, thread_id, body, created_at, updated_at, synced_at): Row,
+) -> Note {
+    Note {
+        id,
+        account_id,
+        message_id_header,
+        thread_id,
+        body,
+        created_at,
+        updated_at,
+        synced_at,
+    }
+}
+
+/// A Message-ID as stored in `messages.message_id_header`: no angle
+/// brackets, and blank counts as none.
+fn normalize_message_id(id: Option<String>) -> Option<String> {
+    id.map(|id| {
+        id.trim()
+            .trim_start_matches('<')
+            .trim_end_matches('>')
+            .to_string()
+    })
+    .filter(|id| !id.is_empty())
+}
+
+/// `query` as a single FTS5 phrase, so operators and punctuation in it are
+/// matched literally.
+fn fts_phrase(query: &str) -> String {
+    format!("\"{}\"", query.replace('"', "\"\""))
+}
+
+async fn get_note(pool: &SqlitePool, account_id: &str, id: &str) -> Result<Option<Note>, String> {
+    let row = sqlx::query_as::<_, Row>(&format!(
+        "SELECT {COLUMNS} FROM notes WHERE account_id = ?1 AND id = ?2"
+    ))
+    .bind(account_id)
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Failed to read note: {e}"))?;
+    Ok(row.map(note_from_row))
+}
+
+/// The thread a message is in, when it's in the local cache.
+async fn thread_for_message(
+    pool: &SqlitePool,
+    account_id: &str,
+    message_id_header: &str,
+) -> Result<Option<String>, String> {
+    let row: Option<(String,)> = sqlx::query_as(
+        "SELECT thread_id FROM messages WHERE account_id = ?1 AND message_id_header = ?2 LIMIT 1",
+    )
+    .bind(account_id)
+    .bind(message_id_header)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Failed to look up message: {e}"))?;
+    Ok(row.map(|(thread_id,)| thread_id))
+}
+
+/// Notes on the message with Message-ID `message_id_header` and on thread
+/// `thread_id` (including those on its messages), oldest first. With
+/// neither, all of the account's notes.
+#[tauri::command]
+#[specta::specta]
+pub async fn notes_list(
+    db: State<'_, DbInstances>,
+    profiles: State<'_, Profiles>,
+    account_id: String,
+    message_id_header: Option<String>,
+    thread_id: Option<String>,
+) -> Result<Vec<Note>, String> {
+    let instances = db.0.read().await;
+    let DbPool::Sqlite(pool) = instances
+        .get(&profiles.db_url())
+        .ok_or_else(|| "Database is not loaded".to_string())?;
+    let rows = sqlx::query_as::<_, Row>(&format!(
+        "SELECT {COLUMNS} FROM notes
+         WHERE account_id = ?1
+           AND ((?2 IS NULL AND ?3 IS NULL) OR message_id_header = ?2 OR thread_id = ?3)
+         ORDER BY created_at, rowid"
+    ))
+    .bind(&account_id)
+    .bind(normalize_message_id(message_id_header))
+    .bind(thread_id.filter(|t| !t.is_empty()))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to read notes: {e}"))?;
+    Ok(rows.into_iter().map(note_from_row).collect())
+}
+
+/// Add a note, or change the text of note `input.id`. A note on a message
+/// gets the message's thread when none is given.
+#[tauri::command]
+#[specta::specta]
+pub async fn notes_save(
+    db: State<'_, DbInstances>,
+    profiles: State<'_, Profiles>,
+    account_id: String,
+    input: NoteInput,
+) -> Result<Note, String> {
+    let instances = db.0.read().await;
+    let DbPool::Sqlite(pool) = instances
+        .get(&profiles.db_url())
+        .ok_or_else(|| "Database is not loaded".to_string())?;
+    let body = input.body.trim();
+    if body.is_empty() {
+        return Err("Note is empty".to_string());
+    }
+
+    let id = match input.id {
+        Some(id) => {
+            let updated = sqlx::query(
+                "UPDATE notes SET body = ?1, updated_at = unixepoch() WHERE account_id = ?2 AND id = ?3",
+            )
+            .bind(body)
+            .bind(&account_id)
+            .bind(&id)
+            .execute(pool)
+            .await
+            .map_err(|e| format!("Failed to save note: {e}"))?;
+            if updated.rows_affected() == 0 {
+                return Err(format!("Note not found: {id}"));
+            }
+            id
+        }
+        None => {
+            let message_id_header = normalize_message_id(input.message_id_header);
+            let thread_id = match (
+                input.thread_id.filter(|t| !t.is_empty()),
+                &message_id_header,
+            ) {
+                (Some(thread_id), _) => Some(thread_id),
+                (None, Some(message_id)) => {
+                    thread_for_message(pool, &account_id, message_id).await?
+                }
+                (None, None) => None,
+            };
+            if message_id_header.is_none() && thread_id.is_none() {
+                return Err("A note needs a message or a thread".to_string());
+            }
+            let (id,): (String,) = sqlx::query_as(
+                "INSERT INTO notes (id, account_id, message_id_header, thread_id, body)
+                 VALUES (lower(hex(randomblob(16))), ?1, ?2, ?3, ?4)
+                 RETURNING id",
+            )
+            .bind(&account_id)
+            .bind(&message_id_header)
+            .bind(&thread_id)
+            .bind(body)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| format!("Failed to save note: {e}"))?;
+            id
+        }
+    };
+    get_note(pool, &account_id, &id)
+        .await?
+        .ok_or_else(|| format!("Note not found: {id}"))
+}
+
+/// Delete a note. Returns it, so a synced copy can be removed from the
+/// server too, or `None` if there was no such note.
+#[tauri::command]
+#[specta::specta]
+pub async fn notes_delete(
+    db: State<'_, DbInstances>,
+    profiles: State<'_, Profiles>,
+    account_id: String,
+    id: String,
+) -> Result<Option<Note>, String> {
+    let instances = db.0.read().await;
+    let DbPool::Sqlite(pool) = instances
+        .get(&profiles.db_url())
+        .ok_or_else(|| "Database is not loaded".to_string())?;
+    let note = get_note(pool, &account_id, &id).await?;
+    sqlx::query("DELETE FROM notes WHERE account_id = ?1 AND id = ?2")
+        .bind(&account_id)
+        .bind(&id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to delete note: {e}"))?;
+    Ok(note)
+}
+
+/// Notes containing `query`, most recently changed first, in one account
+/// or all of them.
+#[tauri::command]
+#[specta::specta]
+pub async fn notes_search(
+    db: State<'_, DbInstances>,
+    profiles: State<'_, Profiles>,
+    query: String,
+    account_id: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<Note>, String> {
+    let instances = db.0.read().await;
+    let DbPool::Sqlite(pool) = instances
+        .get(&profiles.db_url())
+        .ok_or_else(|| "Database is not loaded".to_string())?;
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let limit = limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+    let (matches, pattern) = if query.chars().count() >= MIN_FTS_QUERY_CHARS {
+        (
+            "rowid IN (SELECT rowid FROM notes_fts WHERE notes_fts MATCH ?1)",
+            fts_phrase(query),
+        )
+    } else {
+        ("instr(lower(body), lower(?1)) > 0", query.to_string())
+    };
+    let rows = sqlx::query_as::<_, Row>(&format!(
+        "SELECT {COLUMNS} FROM notes
+         WHERE {matches} AND (?2 IS NULL OR account_id = ?2)
+         ORDER BY updated_at DESC LIMIT ?3"
+    ))
+    .bind(pattern)
+    .bind(account_id.filter(|a| !a.is_empty()))
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to search notes: {e}"))?;
+    Ok(rows.into_iter().map(note_from_row).collect())
+}
+
+/// Record that notes `ids` were copied to the server at `synced_at`.
+#[tauri::command]
+#[specta::specta]
+pub async fn notes_mark_synced(
+    db: State<'_, DbInstances>,
+    profiles: State<'_, Profiles>,
+    account_id: String,
+    ids: Vec<String>,
+    synced_at: i64,
+) -> Result<(), String> {
+    let instances = db.0.read().await;
+    let DbPool::Sqlite(pool) = instances
+        .get(&profiles.db_url())
+        .ok_or_else(|| "Database is not loaded".to_string())?;
+    for id in ids {
+        sqlx::query("UPDATE notes SET synced_at = ?1 WHERE account_id = ?2 AND id = ?3")
+            .bind(synced_at)
+            .bind(&account_id)
+            .bind(&id)
+            .execute(pool)
+            .await
+            .map_err(|e| format!("Failed to update note {id}: {e}"))?;
+    }
+    Ok(())
+}
+
+/// Store notes read from the server. A note replaces the local copy only
+/// when it was changed later; its thread is looked up from its message.
+/// Returns how many notes were added or changed.
+#[tauri::command]
+#[specta::specta]
+pub async fn notes_import(
+    db: State<'_, DbInstances>,
+    profiles: State<'_, Profiles>,
+    account_id: String,
+    notes: Vec<Note>,
+) -> Result<u32, String> {
+    let instances = db.0.read().await;
+    let DbPool::Sqlite(pool) = instances
+        .get(&profiles.db_url())
+        .ok_or_else(|| "Database is not loaded".to_string())?;
+    let mut changed = 0;
+    for note in notes {
+        let message_id_header = normalize_message_id(note.message_id_header);
+        let thread_id = match (&message_id_header, note.thread_id) {
+            (Some(message_id), _) => thread_for_message(pool, &account_id, message_id).await?,
+            (None, thread_id) => thread_id,
+        };
+        let result = sqlx::query(
+            "INSERT INTO notes (id, account_id, message_id_header, thread_id, body, created_at, updated_at, synced_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)
+             ON CONFLICT(id) DO UPDATE SET
+               body = excluded.body,
+               thread_id = COALESCE(excluded.thread_id, notes.thread_id),
+               updated_at = excluded.updated_at,
+               synced_at = excluded.updated_at
+             WHERE notes.account_id = excluded.account_id AND excluded.updated_at > notes.updated_at",
+        )
+        .bind(&note.id)
+        .bind(&account_id)
+        .bind(&message_id_header)
+        .bind(&thread_id)
+        .bind(&note.body)
+        .bind(note.created_at)
+        .bind(note.updated_at)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to import note {}: {e}", note.id))?;
+        changed += result.rows_affected() as u32;
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_message_id() {
+        assert_eq!(
+            normalize_message_id(Some(" <abc@example.com> ".into())).as_deref(),
+            Some("abc@example.com")
+        );
+        assert_eq!(normalize_message_id(Some("<>".into())), None);
+        assert_eq!(normalize_message_id(None), None);
+    }
+
+    #[test]
+    fn test_fts_phrase_quotes_query() {
+        assert_eq!(fts_phrase("call back"), "\"call back\"");
+        assert_eq!(fts_phrase("say \"hi\" OR"), "\"say \"\"hi\"\" OR\"");
+    }
+}
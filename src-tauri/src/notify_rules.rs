@@ -0,0 +1,470 @@
+//! Rules for new-mail notifications, applied in the backend so they hold
+//! whether or not a window is open.
+//!
+//! The frontend hands each message it would notify about to
+//! `notify_new_mail`, which decides what happens to it:
+//!
+//! - Messages from VIP senders (the `notification_vips` table) are shown
+//!   at once with their own sound, even during Do Not Disturb.
+//! - With a digest interval set, other messages are collected and shown as
+//!   one summary notification per interval; digests wait for DND to end.
+//! - Otherwise they're shown after a short pause that batches a sync's
+//!   worth of mail into one notification, or dropped during DND.
+//!
+//! DND is either switched on or follows daily quiet hours. Each rule has
+//! its own sound, and accounts can override the sound for other senders
+//! (see `notify_sounds`).
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::Timelike;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_notification::NotificationExt;
+use tauri_plugin_sql::{DbInstances, DbPool};
+use tokio::sync::Notify;
+
+use crate::accounts::require_trusted_window;
+use crate::identity::normalize_address;
+use crate::notify_sounds::{self, NotificationSound, Playback};
+use crate::profiles::Profiles;
+use crate::settings::SettingsState;
+
+/// How long non-VIP notifications wait for more mail before showing, so a
+/// sync that brings ten messages shows one notification.
+const BATCH_MS: i64 = 2_000;
+/// How often a digest held back by DND checks whether DND has ended.
+const DND_RECHECK_MS: i64 = 60_000;
+/// Senders named in a digest before it says "and N others".
+const DIGEST_NAMES: usize = 3;
+
+/// System sound VIP notifications use unless the rules name another.
+#[cfg(target_os = "macos")]
+pub const DEFAULT_VIP_SOUND: &str = "Glass";
+#[cfg(target_os = "windows")]
+pub const DEFAULT_VIP_SOUND: &str = "IM";
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub const DEFAULT_VIP_SOUND: &str = "message-new-instant";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct NotificationRules {
+    /// Do Not Disturb: only VIP senders notify.
+    pub dnd: bool,
+    /// Daily quiet hours in minutes after local midnight, during which DND
+    /// is on too. A start after the end spans midnight.
+    pub quiet_start: Option<u16>,
+    pub quiet_end: Option<u16>,
+    /// Show notifications from other senders as one digest this often;
+    /// `None` shows them as they come.
+    pub digest_minutes: Option<u32>,
+    /// Sound for VIP notifications.
+    pub vip_sound: NotificationSound,
+    /// Sound for notifications from other senders, batches and digests.
+    pub sound: NotificationSound,
+    /// Per-account replacements for `sound`, by account id.
+    pub account_sounds: BTreeMap<String, NotificationSound>,
+}
+
+impl Default for NotificationRules {
+    fn default() -> Self {
+        Self {
+            dnd: false,
+            quiet_start: None,
+            quiet_end: None,
+            digest_minutes: None,
+            vip_sound: NotificationSound::system(DEFAULT_VIP_SOUND),
+            sound: NotificationSound::Silent,
+            account_sounds: BTreeMap::new(),
+        }
+    }
+}
+
+impl NotificationRules {
+    /// Whether DND is in effect at `minute` (minutes after local midnight).
+    pub fn dnd_active(&self, minute: u16) -> bool {
+        if self.dnd {
+            return true;
+        }
+        match (self.quiet_start, self.quiet_end) {
+            (Some(start), Some(end)) if start < end => (start..end).contains(&minute),
+            (Some(start), Some(end)) if start > end => minute >= start || minute < end,
+            _ => false,
+        }
+    }
+
+    /// The sound for a notification about `notices` from other senders: their
+    /// account's, when they're all from one account that has its own.
+    pub fn sound_for(&self, notices: &[MailNotice]) -> &NotificationSound {
+        let account = notices.first().map(|n| n.account_id.as_str());
+        match account {
+            Some(account) if notices.iter().all(|n| n.account_id == account) => {
+                self.account_sounds.get(account).unwrap_or(&self.sound)
+            }
+            _ => &self.sound,
+        }
+    }
+}
+
+/// What `notify_new_mail` did with a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum Delivery {
+    /// Shown right away (VIP sender).
+    Shown,
+    /// Shown shortly, together with any other mail that arrives meanwhile.
+    Batched,
+    /// Held for the next digest.
+    Digest,
+    /// Dropped because DND is on.
+    Suppressed,
+}
+
+/// A new message to notify about.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct MailNotice {
+    pub account_id: String,
+    pub thread_id: Option<String>,
+    pub from_name: Option<String>,
+    pub from_address: Option<String>,
+    pub subject: Option<String>,
+}
+
+impl MailNotice {
+    fn sender(&self) -> &str {
+        self.from_name
+            .as_deref()
+            .or(self.from_address.as_deref())
+            .unwrap_or("Unknown")
+    }
+
+    fn subject(&self) -> &str {
+        self.subject
+            .as_deref()
+            .filter(|s| !s.is_empty())
+            .unwrap_or("(No subject)")
+    }
+}
+
+/// The rules' decision for a message from a VIP sender or not.
+pub fn decide(rules: &NotificationRules, vip: bool, minute: u16) -> Delivery {
+    if vip {
+        Delivery::Shown
+    } else if rules.digest_minutes.is_some() {
+        Delivery::Digest
+    } else if rules.dnd_active(minute) {
+        Delivery::Suppressed
+    } else {
+        Delivery::Batched
+    }
+}
+
+/// Title and body of the notification for held messages: the message
+/// itself when there's one, otherwise a count and who they're from.
+pub fn summary(notices: &[MailNotice]) -> Option<(String, String)> {
+    match notices {
+        [] => None,
+        [notice] => Some((notice.sender().to_string(), notice.subject().to_string())),
+        _ => {
+            let mut senders: Vec<&str> = Vec::new();
+            for notice in notices {
+                if !senders.contains(&notice.sender()) {
+                    senders.push(notice.sender());
+                }
+            }
+            let named = senders[..senders.len().min(DIGEST_NAMES)].join(", ");
+            let body = match senders.len().saturating_sub(DIGEST_NAMES) {
+                0 => format!("From {named}"),
+                1 => format!("From {named} and 1 other"),
+                others => format!("From {named} and {others} others"),
+            };
+            Some((format!("{} new emails", notices.len()), body))
+        }
+    }
+}
+
+fn local_minute() -> u16 {
+    let now = chrono::Local::now();
+    (now.hour() * 60 + now.minute()) as u16
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+#[derive(Default)]
+struct Pending {
+    notices: Vec<MailNotice>,
+    /// When to show them, in Unix milliseconds.
+    show_at: Option<i64>,
+}
+
+/// Managed state: messages waiting for a batch or digest notification.
+#[derive(Default)]
+pub struct Notifier {
+    pending: Mutex<Pending>,
+    wake: Notify,
+}
+
+impl Notifier {
+    fn hold(&self, notice: MailNotice, show_at: i64, keep_earlier: bool) {
+        let mut pending = self.pending.lock().unwrap();
+        pending.notices.push(notice);
+        pending.show_at = match pending.show_at {
+            Some(at) if keep_earlier => Some(at.min(show_at)),
+            _ => Some(show_at),
+        };
+        drop(pending);
+        self.wake.notify_one();
+    }
+}
+
+async fn is_vip(pool: &SqlitePool, account_id: &str, address: &str) -> Result<bool, String> {
+    sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM notification_vips WHERE account_id = ?1 AND email_address = ?2",
+    )
+    .bind(account_id)
+    .bind(address)
+    .fetch_one(pool)
+    .await
+    .map(|count| count > 0)
+    .map_err(|e| format!("Failed to look up VIP senders: {e}"))
+}
+
+fn show(app: &AppHandle, title: &str, body: &str, sound: &NotificationSound) {
+    let mut builder = app.notification().builder().title(title).body(body);
+    let mut file = None;
+    match notify_sounds::resolve(app, sound) {
+        Ok(Playback::Silent) => {}
+        Ok(Playback::System(name)) => builder = builder.sound(name),
+        Ok(Playback::File(path)) => file = Some(path),
+        Err(e) => log::warn!("Notification sound: {e}"),
+    }
+    if let Err(e) = builder.show() {
+        log::warn!("Failed to show notification: {e}");
+        return;
+    }
+    if let Some(path) = file {
+        if let Err(e) = notify_sounds::play_file(&path) {
+            log::warn!("Notification sound: {e}");
+        }
+    }
+}
+
+/// Show held messages that are due; returns how long until the next are.
+fn flush_due(app: &AppHandle, notifier: &Notifier) -> Option<Duration> {
+    let now = now_ms();
+    let mut pending = notifier.pending.lock().unwrap();
+    let show_at = pending.show_at?;
+    if show_at > now {
+        return Some(Duration::from_millis((show_at - now) as u64));
+    }
+    let rules = app.state::<SettingsState>().get().notification_rules;
+    if rules.dnd_active(local_minute()) {
+        pending.show_at = Some(now + DND_RECHECK_MS);
+        return Some(Duration::from_millis(DND_RECHECK_MS as u64));
+    }
+    let notices = std::mem::take(&mut pending.notices);
+    pending.show_at = None;
+    drop(pending);
+    if let Some((title, body)) = summary(&notices) {
+        show(app, &title, &body, rules.sound_for(&notices));
+    }
+    None
+}
+
+/// Start the task that shows batched and digest notifications.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let notifier = app.state::<Notifier>();
+        loop {
+            match flush_due(&app, &notifier) {
+                Some(wait) => {
+                    tokio::select! {
+                        _ = notifier.wake.notified() => {}
+                        _ = tokio::time::sleep(wait) => {}
+                    }
+                }
+                None => notifier.wake.notified().await,
+            }
+        }
+    });
+}
+
+/// Notify about a new message as the notification rules say.
+#[tauri::command]
+#[specta::specta]
+pub async fn notify_new_mail(
+    app: AppHandle,
+    notice: MailNotice,
+    db: State<'_, DbInstances>,
+    profiles: State<'_, Profiles>,
+    settings: State<'_, SettingsState>,
+    notifier: State<'_, Notifier>,
+) -> Result<Delivery, String> {
+    let rules = settings.get().notification_rules;
+    let address = notice.from_address.as_deref().and_then(normalize_address);
+    let vip = match &address {
+        Some(address) => {
+            let instances = db.0.read().await;
+            let DbPool::Sqlite(pool) = instances
+                .get(&profiles.db_url())
+                .ok_or_else(|| "Database is not loaded".to_string())?;
+            is_vip(pool, &notice.account_id, address).await?
+        }
+        None => false,
+    };
+
+    let delivery = decide(&rules, vip, local_minute());
+    match delivery {
+        Delivery::Shown => show(
+            &app,
+            &format!("★ {}", notice.sender()),
+            notice.subject(),
+            &rules.vip_sound,
+        ),
+        Delivery::Batched => notifier.hold(notice, now_ms() + BATCH_MS, false),
+        Delivery::Digest => {
+            let interval = i64::from(rules.digest_minutes.unwrap_or(0)) * 60_000;
+            notifier.hold(notice, now_ms() + interval, true)
+        }
+        Delivery::Suppressed => {}
+    }
+    Ok(delivery)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_notification_rules(settings: State<'_, SettingsState>) -> NotificationRules {
+    settings.get().notification_rules
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_notification_rules(
+    window: tauri::Window,
+    settings: State<'_, SettingsState>,
+    notifier: State<'_, Notifier>,
+    rules: NotificationRules,
+) -> Result<(), String> {
+    require_trusted_window(&window, "set_notification_rules")?;
+    let quiet = [rules.quiet_start, rules.quiet_end];
+    if quiet.into_iter().flatten().any(|minute| minute >= 24 * 60) {
+        return Err("Quiet hours must be within a day".to_string());
+    }
+    settings.update(|s| s.notification_rules = rules)?;
+    // A digest interval may have been turned off or shortened
+    notifier.wake.notify_one();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notice(from: &str) -> MailNotice {
+        notice_in("acc", from)
+    }
+
+    fn notice_in(account: &str, from: &str) -> MailNotice {
+        MailNotice {
+            account_id: account.to_string(),
+            thread_id: None,
+            from_name: Some(from.to_string()),
+            from_address: None,
+            subject: Some(format!("Hi from {from}")),
+        }
+    }
+
+    #[test]
+    fn test_quiet_hours() {
+        let overnight = NotificationRules {
+            quiet_start: Some(22 * 60),
+            quiet_end: Some(7 * 60),
+            ..Default::default()
+        };
+        assert!(overnight.dnd_active(23 * 60));
+        assert!(overnight.dnd_active(6 * 60 + 59));
+        assert!(!overnight.dnd_active(7 * 60));
+        assert!(!overnight.dnd_active(12 * 60));
+
+        let lunch = NotificationRules {
+            quiet_start: Some(12 * 60),
+            quiet_end: Some(13 * 60),
+            ..Default::default()
+        };
+        assert!(lunch.dnd_active(12 * 60 + 30));
+        assert!(!lunch.dnd_active(13 * 60));
+        assert!(NotificationRules {
+            dnd: true,
+            ..Default::default()
+        }
+        .dnd_active(0));
+    }
+
+    #[test]
+    fn test_decide() {
+        let dnd = NotificationRules {
+            dnd: true,
+            ..Default::default()
+        };
+        assert_eq!(decide(&dnd, true, 0), Delivery::Shown);
+        assert_eq!(decide(&dnd, false, 0), Delivery::Suppressed);
+        assert_eq!(
+            decide(&NotificationRules::default(), false, 0),
+            Delivery::Batched
+        );
+        let digest = NotificationRules {
+            dnd: true,
+            digest_minutes: Some(30),
+            ..Default::default()
+        };
+        assert_eq!(decide(&digest, false, 0), Delivery::Digest);
+    }
+
+    #[test]
+    fn test_summary() {
+        assert_eq!(summary(&[]), None);
+        assert_eq!(
+            summary(&[notice("Ann")]),
+            Some(("Ann".to_string(), "Hi from Ann".to_string()))
+        );
+        let many: Vec<_> = ["Ann", "Bob", "Ann", "Cy", "Di", "Ed"]
+            .into_iter()
+            .map(notice)
+            .collect();
+        assert_eq!(
+            summary(&many),
+            Some((
+                "6 new emails".to_string(),
+                "From Ann, Bob, Cy and 2 others".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_sound_for() {
+        let chime = NotificationSound::Bundled {
+            id: "chime".to_string(),
+        };
+        let mut rules = NotificationRules::default();
+        rules
+            .account_sounds
+            .insert("work".to_string(), chime.clone());
+
+        assert_eq!(rules.sound_for(&[notice_in("work", "Ann")]), &chime);
+        assert_eq!(
+            rules.sound_for(&[notice_in("work", "Ann"), notice_in("home", "Bob")]),
+            &NotificationSound::Silent
+        );
+        assert_eq!(
+            rules.sound_for(&[notice_in("home", "Bob")]),
+            &NotificationSound::Silent
+        );
+    }
+}
@@ -0,0 +1,364 @@
+//! Notification sounds: system sounds played by the notification itself,
+//! plus sound files (bundled with the app or added by the user) that the
+//! backend plays, so they work with every window hidden.
+//!
+//! Bundled sounds ship as resources under `sounds/`; user sounds are
+//! copied into `sounds/` in the profile data dir and referred to by file
+//! name. Files are played with the platform's command-line player
+//! (`afplay`, PowerShell's `SoundPlayer`, or `paplay`/`pw-play`/`aplay`).
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::accounts::require_trusted_window;
+use crate::profiles;
+
+/// Directory of sound files, under the resource dir and the profile data dir.
+const SOUNDS_DIR: &str = "sounds";
+/// Largest sound file that can be added.
+const MAX_SOUND_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Sounds shipped with the app: id (file stem) and label.
+const BUNDLED: &[(&str, &str)] = &[("chime", "Chime"), ("ping", "Ping"), ("pop", "Pop")];
+
+/// System sounds offered for notifications.
+#[cfg(target_os = "macos")]
+const SYSTEM: &[&str] = &["Glass", "Ping", "Pop", "Purr", "Submarine", "Tink"];
+#[cfg(target_os = "windows")]
+const SYSTEM: &[&str] = &["Default", "IM", "Mail", "Reminder", "SMS"];
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const SYSTEM: &[&str] = &[
+    "message-new-instant",
+    "message-new-email",
+    "bell",
+    "complete",
+];
+
+/// File types the platform player handles.
+#[cfg(target_os = "macos")]
+const PLAYABLE: &[&str] = &["wav", "aiff", "aif", "mp3", "m4a", "caf"];
+#[cfg(target_os = "windows")]
+const PLAYABLE: &[&str] = &["wav"];
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const PLAYABLE: &[&str] = &["wav", "ogg", "oga", "flac"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NotificationSound {
+    /// No sound.
+    Silent,
+    /// A sound the OS plays with the notification, by name.
+    System { name: String },
+    /// A sound shipped with the app, by id.
+    Bundled { id: String },
+    /// A sound file the user added, by file name.
+    Custom { file: String },
+}
+
+impl NotificationSound {
+    pub fn system(name: &str) -> Self {
+        Self::System {
+            name: name.to_string(),
+        }
+    }
+}
+
+/// A sound offered in settings.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct SoundOption {
+    pub sound: NotificationSound,
+    pub label: String,
+}
+
+/// How to make a sound.
+#[derive(Debug, PartialEq)]
+pub enum Playback {
+    Silent,
+    /// Pass the name to the notification.
+    System(String),
+    /// Play the file alongside the notification.
+    File(PathBuf),
+}
+
+fn custom_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(profiles::data_dir(app)?.join(SOUNDS_DIR))
+}
+
+/// `file` as the name of a file directly in the user sounds dir.
+fn custom_path(dir: &Path, file: &str) -> Result<PathBuf, String> {
+    let name = Path::new(file).file_name().and_then(|n| n.to_str());
+    if name != Some(file) || file.starts_with('.') {
+        return Err(format!("Invalid sound file name: {file}"));
+    }
+    Ok(dir.join(file))
+}
+
+/// An added sound, labelled with its file name without the extension.
+fn custom_option(file: String) -> SoundOption {
+    SoundOption {
+        label: Path::new(&file)
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| file.clone()),
+        sound: NotificationSound::Custom { file },
+    }
+}
+
+/// Whether the platform player handles files with `path`'s extension.
+fn is_playable(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| PLAYABLE.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// A file name for an added sound that doesn't clash with `taken`.
+fn import_name(path: &Path, taken: impl Fn(&str) -> bool) -> String {
+    let stem: String = path
+        .file_stem()
+        .map(|s| s.to_string_lossy())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' || c == ' ' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(60)
+        .collect();
+    let stem = match stem.trim() {
+        "" => "sound",
+        stem => stem,
+    };
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    let mut name = format!("{stem}.{ext}");
+    let mut n = 2;
+    while taken(&name) {
+        name = format!("{stem}-{n}.{ext}");
+        n += 1;
+    }
+    name
+}
+
+/// How to play `sound`, or why it can't be.
+pub fn resolve(app: &AppHandle, sound: &NotificationSound) -> Result<Playback, String> {
+    let path = match sound {
+        NotificationSound::Silent => return Ok(Playback::Silent),
+        NotificationSound::System { name } => return Ok(Playback::System(name.clone())),
+        NotificationSound::Bundled { id } => {
+            if !BUNDLED.iter().any(|(bundled, _)| bundled == id) {
+                return Err(format!("Unknown bundled sound: {id}"));
+            }
+            app.path()
+                .resource_dir()
+                .map_err(|e| format!("No resource dir: {e}"))?
+                .join(SOUNDS_DIR)
+                .join(format!("{id}.wav"))
+        }
+        NotificationSound::Custom { file } => custom_path(&custom_dir(app)?, file)?,
+    };
+    if !path.is_file() {
+        return Err(format!("Sound file {} is missing", path.display()));
+    }
+    Ok(Playback::File(path))
+}
+
+/// Commands that can play `path`, tried in order until one starts.
+fn players(path: &Path) -> Vec<Command> {
+    #[cfg(target_os = "macos")]
+    {
+        let mut afplay = Command::new("afplay");
+        afplay.arg(path);
+        vec![afplay]
+    }
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        let quoted = path.display().to_string().replace('\'', "''");
+        let mut powershell = Command::new("powershell");
+        powershell
+            .args(["-NoProfile", "-NonInteractive", "-Command"])
+            .arg(format!(
+                "(New-Object Media.SoundPlayer '{quoted}').PlaySync()"
+            ))
+            .creation_flags(CREATE_NO_WINDOW);
+        vec![powershell]
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        ["paplay", "pw-play", "aplay"]
+            .into_iter()
+            .map(|player| {
+                let mut command = Command::new(player);
+                command.arg(path);
+                command
+            })
+            .collect()
+    }
+}
+
+/// Start playing a sound file without waiting for it to finish.
+pub fn play_file(path: &Path) -> Result<(), String> {
+    for mut command in players(path) {
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        match command.spawn() {
+            Ok(mut child) => {
+                // Reap the player once it's done
+                std::thread::spawn(move || child.wait());
+                return Ok(());
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(format!("Failed to play {}: {e}", path.display())),
+        }
+    }
+    Err("No audio player found to play notification sounds".to_string())
+}
+
+/// Sounds to pick from: none, system sounds, bundled and added ones.
+#[tauri::command]
+#[specta::specta]
+pub fn notification_sounds_list(app: AppHandle) -> Result<Vec<SoundOption>, String> {
+    let mut options = vec![SoundOption {
+        sound: NotificationSound::Silent,
+        label: "None".to_string(),
+    }];
+    options.extend(SYSTEM.iter().map(|name| SoundOption {
+        sound: NotificationSound::system(name),
+        label: name.to_string(),
+    }));
+    options.extend(BUNDLED.iter().map(|(id, label)| SoundOption {
+        sound: NotificationSound::Bundled { id: id.to_string() },
+        label: label.to_string(),
+    }));
+
+    let dir = custom_dir(&app)?;
+    let mut custom: Vec<String> = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && is_playable(path))
+            .filter_map(|path| Some(path.file_name()?.to_str()?.to_string()))
+            .collect(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(format!("Failed to list {}: {e}", dir.display())),
+    };
+    custom.sort();
+    options.extend(custom.into_iter().map(custom_option));
+    Ok(options)
+}
+
+/// Copy a sound file into the profile so it can be used for notifications.
+#[tauri::command]
+#[specta::specta]
+pub fn notification_sound_add(
+    app: AppHandle,
+    window: tauri::Window,
+    path: String,
+) -> Result<SoundOption, String> {
+    require_trusted_window(&window, "notification_sound_add")?;
+    let path = PathBuf::from(path);
+    if !is_playable(&path) {
+        return Err(format!(
+            "Notification sounds must be {} files",
+            PLAYABLE.join(", ")
+        ));
+    }
+    let metadata =
+        std::fs::metadata(&path).map_err(|e| format!("Cannot read {}: {e}", path.display()))?;
+    if !metadata.is_file() {
+        return Err(format!("{} is not a file", path.display()));
+    }
+    if metadata.len() > MAX_SOUND_BYTES {
+        return Err(format!(
+            "Notification sounds can be at most {} MB",
+            MAX_SOUND_BYTES / 1024 / 1024
+        ));
+    }
+
+    let dir = custom_dir(&app)?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    let file = import_name(&path, |name| dir.join(name).exists());
+    std::fs::copy(&path, dir.join(&file))
+        .map_err(|e| format!("Failed to copy {}: {e}", path.display()))?;
+    Ok(custom_option(file))
+}
+
+/// Remove an added sound. Notifications still set to it play no sound.
+#[tauri::command]
+#[specta::specta]
+pub fn notification_sound_remove(
+    app: AppHandle,
+    window: tauri::Window,
+    file: String,
+) -> Result<(), String> {
+    require_trusted_window(&window, "notification_sound_remove")?;
+    let path = custom_path(&custom_dir(&app)?, &file)?;
+    match std::fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to remove {}: {e}", path.display())),
+    }
+}
+
+/// Play a sound as a notification would. System sounds can only be played
+/// by a notification, so previewing one shows a test notification.
+#[tauri::command]
+#[specta::specta]
+pub fn notification_sound_preview(app: AppHandle, sound: NotificationSound) -> Result<(), String> {
+    match resolve(&app, &sound)? {
+        Playback::Silent => Ok(()),
+        Playback::System(name) => app
+            .notification()
+            .builder()
+            .title("Sora")
+            .body("Notification sound preview")
+            .sound(name)
+            .show()
+            .map_err(|e| format!("Failed to show notification: {e}")),
+        Playback::File(path) => play_file(&path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_path_stays_in_dir() {
+        let dir = Path::new("/data/sounds");
+        assert_eq!(
+            custom_path(dir, "bell.wav"),
+            Ok(PathBuf::from("/data/sounds/bell.wav"))
+        );
+        assert!(custom_path(dir, "../settings.json").is_err());
+        assert!(custom_path(dir, "a/b.wav").is_err());
+        assert!(custom_path(dir, "..").is_err());
+        assert!(custom_path(dir, ".hidden.wav").is_err());
+        assert!(custom_path(dir, "").is_err());
+    }
+
+    #[test]
+    fn test_import_name() {
+        let taken = |name: &str| name == "Bell.wav" || name == "Bell-2.wav";
+        assert_eq!(import_name(Path::new("/x/Bell.WAV"), taken), "Bell-3.wav");
+        assert_eq!(
+            import_name(Path::new("/x/ding:dong?.wav"), |_| false),
+            "ding_dong_.wav"
+        );
+        assert_eq!(import_name(Path::new("/x/?.ogg"), |_| false), "_.ogg");
+    }
+}
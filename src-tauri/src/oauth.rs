@@ -1,10 +1,34 @@
+//! OAuth sign-in: the localhost redirect server, PKCE, built-in provider
+//! profiles, the token exchange and the [`TokenManager`] that keeps
+//! IMAP/SMTP access tokens fresh.
+//!
+//! Once the frontend has handed an account's refresh token over with
+//! `oauth_store_grant`, the grant lives in the OS keychain and the account
+//! is registered without a token. [`crate::accounts::AccountRegistry`]
+//! then asks the token manager for an access token on every lookup, which
+//! refreshes it shortly before it expires, and a session pool login that
+//! fails with XOAUTH2 is retried once with a newly refreshed token.
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use specta::Type;
 use std::collections::HashMap;
-use std::time::Duration;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpListener;
 
-#[derive(Serialize)]
+use crate::accounts::require_trusted_window;
+use crate::credentials;
+
+/// Access tokens are refreshed when they have less than this left.
+const REFRESH_MARGIN: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Serialize, Type)]
 pub struct OAuthResult {
     pub code: String,
     pub state: String,
@@ -13,6 +37,7 @@ pub struct OAuthResult {
 /// Binds to a localhost port for OAuth callback. Tries the given port first,
 /// falls back to nearby ports if taken.
 #[tauri::command]
+#[specta::specta]
 pub async fn start_oauth_server(port: u16, state: String) -> Result<OAuthResult, String> {
     // Try the requested port, then a few alternatives
     let mut listener = None;
@@ -149,7 +174,7 @@ fn urlencoding_decode(s: &str) -> String {
     String::from_utf8(result).unwrap_or_else(|_| s.to_string())
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Type)]
 pub struct TokenExchangeResult {
     pub access_token: String,
     pub refresh_token: Option<String>,
@@ -161,6 +186,7 @@ pub struct TokenExchangeResult {
 
 /// Exchange an OAuth authorization code for tokens via Rust HTTP client (avoids CORS).
 #[tauri::command]
+#[specta::specta]
 pub async fn oauth_exchange_token(
     token_url: String,
     code: String,
@@ -210,8 +236,149 @@ pub async fn oauth_exchange_token(
         .map_err(|e| format!("Failed to parse token response: {}", e))
 }
 
+/// A PKCE (RFC 7636) pair: the challenge goes in the authorization URL
+/// with `code_challenge_method=S256`, the verifier to [`oauth_exchange_token`].
+#[derive(Serialize, Type)]
+pub struct PkcePair {
+    pub code_verifier: String,
+    pub code_challenge: String,
+}
+
+/// The S256 challenge for `verifier`: its base64url SHA-256 digest.
+fn pkce_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+/// Generate a PKCE verifier and challenge, so public clients (no client
+/// secret) can sign in.
+#[tauri::command]
+#[specta::specta]
+pub fn oauth_generate_pkce() -> PkcePair {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    // 32 random bytes give the 43-character verifier RFC 7636 recommends
+    let code_verifier = URL_SAFE_NO_PAD.encode(bytes);
+    PkcePair {
+        code_challenge: pkce_challenge(&code_verifier),
+        code_verifier,
+    }
+}
+
+/// Endpoints, scopes and mail servers of a built-in OAuth provider.
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct OAuthProviderProfile {
+    pub id: String,
+    pub name: String,
+    pub auth_url: String,
+    pub token_url: String,
+    pub scopes: Vec<String>,
+    pub use_pkce: bool,
+    /// Sent again with each refresh, or Microsoft issues a token for its
+    /// first resource only.
+    pub refresh_scope: Option<String>,
+    pub imap_host: String,
+    pub imap_port: u16,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub smtp_security: String,
+    /// Whether SMTP `AUTH XOAUTH2` may carry the token on the command line
+    /// (a SASL initial response); see [`smtp_xoauth2_initial_response`].
+    pub smtp_initial_response: bool,
+}
+
+const MICROSOFT_SCOPES: &[&str] = &[
+    "https://outlook.office.com/IMAP.AccessAsUser.All",
+    "https://outlook.office.com/SMTP.Send",
+    "offline_access",
+    "openid",
+    "profile",
+    "email",
+];
+
+/// Microsoft 365 and Outlook.com submission servers.
+const MICROSOFT_SMTP_HOSTS: &[&str] = &[
+    "smtp.office365.com",
+    "smtp-mail.outlook.com",
+    "smtp.outlook.com",
+];
+
+/// The Microsoft identity platform directory to sign in against: "common"
+/// (work and personal accounts, the default), "organizations",
+/// "consumers", a tenant id or one of the tenant's domains.
+fn microsoft_tenant(tenant: Option<&str>) -> Result<String, String> {
+    let tenant = tenant
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .unwrap_or("common");
+    let valid = tenant.len() <= 253
+        && tenant.split('.').all(|label| {
+            !label.is_empty()
+                && label
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        });
+    if valid {
+        Ok(tenant.to_ascii_lowercase())
+    } else {
+        Err(format!("Invalid Microsoft tenant: {tenant}"))
+    }
+}
+
+/// The built-in profile of `provider`. Only Microsoft takes a `tenant`;
+/// apps registered for a single organization must sign in against it.
+pub fn provider_profile(
+    provider: &str,
+    tenant: Option<&str>,
+) -> Result<OAuthProviderProfile, String> {
+    match provider {
+        "microsoft" => {
+            let endpoint = format!(
+                "https://login.microsoftonline.com/{}/oauth2/v2.0",
+                microsoft_tenant(tenant)?
+            );
+            let smtp_host = MICROSOFT_SMTP_HOSTS[0];
+            Ok(OAuthProviderProfile {
+                id: provider.to_string(),
+                name: "Microsoft".to_string(),
+                auth_url: format!("{endpoint}/authorize"),
+                token_url: format!("{endpoint}/token"),
+                scopes: MICROSOFT_SCOPES.iter().map(|s| s.to_string()).collect(),
+                use_pkce: true,
+                refresh_scope: Some(MICROSOFT_SCOPES.join(" ")),
+                imap_host: "outlook.office365.com".to_string(),
+                imap_port: 993,
+                smtp_host: smtp_host.to_string(),
+                smtp_port: 587,
+                smtp_security: "starttls".to_string(),
+                smtp_initial_response: smtp_xoauth2_initial_response(smtp_host),
+            })
+        }
+        _ => Err(format!("No built-in OAuth profile for {provider}")),
+    }
+}
+
+/// Whether SMTP `AUTH XOAUTH2` on `host` may send the token with the
+/// command. Microsoft's servers take it only in reply to their empty 334
+/// challenge.
+pub fn smtp_xoauth2_initial_response(host: &str) -> bool {
+    !MICROSOFT_SMTP_HOSTS
+        .iter()
+        .any(|h| host.trim_end_matches('.').eq_ignore_ascii_case(h))
+}
+
+/// The built-in OAuth profile of `provider` (e.g. "microsoft").
+#[tauri::command]
+#[specta::specta]
+pub fn oauth_provider_profile(
+    provider: String,
+    tenant: Option<String>,
+) -> Result<OAuthProviderProfile, String> {
+    provider_profile(&provider, tenant.as_deref())
+}
+
 /// Refresh an OAuth token via Rust HTTP client (avoids CORS).
 #[tauri::command]
+#[specta::specta]
 pub async fn oauth_refresh_token(
     token_url: String,
     refresh_token: String,
@@ -219,23 +386,35 @@ pub async fn oauth_refresh_token(
     client_secret: Option<String>,
     scope: Option<String>,
 ) -> Result<TokenExchangeResult, String> {
+    refresh(&OAuthGrant {
+        token_url,
+        client_id,
+        client_secret,
+        scope,
+        refresh_token,
+    })
+    .await
+}
+
+async fn refresh(grant: &OAuthGrant) -> Result<TokenExchangeResult, String> {
+    let token_url = &grant.token_url;
     let mut params = vec![
-        ("refresh_token", refresh_token),
-        ("client_id", client_id),
+        ("refresh_token", grant.refresh_token.clone()),
+        ("client_id", grant.client_id.clone()),
         ("grant_type", "refresh_token".to_string()),
     ];
-    if let Some(secret) = client_secret {
+    if let Some(secret) = &grant.client_secret {
         if !secret.is_empty() {
-            params.push(("client_secret", secret));
+            params.push(("client_secret", secret.clone()));
         }
     }
-    if let Some(s) = scope {
-        params.push(("scope", s));
+    if let Some(s) = &grant.scope {
+        params.push(("scope", s.clone()));
     }
 
     let client = reqwest::Client::new();
     let response = client
-        .post(&token_url)
+        .post(token_url)
         .form(&params)
         .send()
         .await
@@ -254,3 +433,260 @@ pub async fn oauth_refresh_token(
         .await
         .map_err(|e| format!("Failed to parse token response: {}", e))
 }
+
+/// What refreshing an account's access token takes. Stored in the keychain
+/// as JSON, next to the account's password entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct OAuthGrant {
+    pub token_url: String,
+    pub client_id: String,
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    #[serde(default)]
+    pub scope: Option<String>,
+    pub refresh_token: String,
+}
+
+struct AccessToken {
+    token: String,
+    expires_at: SystemTime,
+}
+
+impl AccessToken {
+    fn new(token: String, expires_in: Duration) -> Self {
+        Self {
+            token,
+            expires_at: SystemTime::now() + expires_in,
+        }
+    }
+
+    fn is_fresh(&self) -> bool {
+        self.expires_at
+            .duration_since(SystemTime::now())
+            .is_ok_and(|left| left > REFRESH_MARGIN)
+    }
+}
+
+/// Access tokens of the accounts whose grant is in the keychain, refreshed
+/// on demand. One per process, see [`tokens`].
+#[derive(Default)]
+pub struct TokenManager {
+    /// Grants read from the keychain; `None` for accounts without one.
+    grants: Mutex<HashMap<String, Option<OAuthGrant>>>,
+    tokens: Mutex<HashMap<String, AccessToken>>,
+    /// Held while refreshing, so concurrent lookups refresh once.
+    refreshing: tokio::sync::Mutex<()>,
+}
+
+impl TokenManager {
+    async fn grant(&self, account_id: &str) -> Result<Option<OAuthGrant>, String> {
+        if let Some(grant) = self.grants.lock().unwrap().get(account_id) {
+            return Ok(grant.clone());
+        }
+        let grant = match credentials::stored_oauth_grant(account_id).await? {
+            Some(json) => Some(
+                serde_json::from_str::<OAuthGrant>(&json)
+                    .map_err(|e| format!("Malformed OAuth grant for account {account_id}: {e}"))?,
+            ),
+            None => None,
+        };
+        self.grants
+            .lock()
+            .unwrap()
+            .insert(account_id.to_string(), grant.clone());
+        Ok(grant)
+    }
+
+    fn cached(&self, account_id: &str) -> Option<String> {
+        self.tokens
+            .lock()
+            .unwrap()
+            .get(account_id)
+            .filter(|token| token.is_fresh())
+            .map(|token| token.token.clone())
+    }
+
+    /// Keep `grant` for `account_id`, with its current access token if
+    /// there is one.
+    pub async fn store(
+        &self,
+        account_id: &str,
+        grant: OAuthGrant,
+        access_token: Option<(String, Duration)>,
+    ) -> Result<(), String> {
+        let json = serde_json::to_string(&grant)
+            .map_err(|e| format!("Failed to serialize OAuth grant: {e}"))?;
+        credentials::store_oauth_grant(account_id, json).await?;
+        self.grants
+            .lock()
+            .unwrap()
+            .insert(account_id.to_string(), Some(grant));
+        let mut tokens = self.tokens.lock().unwrap();
+        match access_token {
+            Some((token, expires_in)) => {
+                tokens.insert(account_id.to_string(), AccessToken::new(token, expires_in));
+            }
+            None => {
+                tokens.remove(account_id);
+            }
+        }
+        Ok(())
+    }
+
+    /// A current access token for `account_id`, refreshed if it's about to
+    /// expire. `None` if the account's grant isn't in the keychain.
+    pub async fn access_token(&self, account_id: &str) -> Result<Option<String>, String> {
+        if let Some(token) = self.cached(account_id) {
+            return Ok(Some(token));
+        }
+        let _refreshing = self.refreshing.lock().await;
+        // Another lookup may have refreshed it while this one waited
+        if let Some(token) = self.cached(account_id) {
+            return Ok(Some(token));
+        }
+        let Some(grant) = self.grant(account_id).await? else {
+            return Ok(None);
+        };
+        let result = refresh(&grant).await?;
+        log::info!("Refreshed the OAuth access token of account {account_id}");
+
+        // Providers that rotate refresh tokens invalidate the old one
+        if let Some(rotated) = result.refresh_token.filter(|t| *t != grant.refresh_token) {
+            let rotated = OAuthGrant {
+                refresh_token: rotated,
+                ..grant
+            };
+            if let Err(e) = self.store(account_id, rotated, None).await {
+                log::warn!("Failed to keep the new refresh token of {account_id}: {e}");
+            }
+        }
+        self.tokens.lock().unwrap().insert(
+            account_id.to_string(),
+            AccessToken::new(
+                result.access_token.clone(),
+                Duration::from_secs(result.expires_in),
+            ),
+        );
+        Ok(Some(result.access_token))
+    }
+
+    /// A new access token after the server rejected the current one, or
+    /// `None` if the account's grant isn't in the keychain.
+    pub async fn refreshed(&self, account_id: &str) -> Result<Option<String>, String> {
+        self.tokens.lock().unwrap().remove(account_id);
+        self.access_token(account_id).await
+    }
+
+    /// Forget the account's grant and token, in memory and in the keychain.
+    pub async fn remove(&self, account_id: &str) -> Result<(), String> {
+        self.tokens.lock().unwrap().remove(account_id);
+        self.grants.lock().unwrap().remove(account_id);
+        credentials::delete_oauth_grant(account_id).await
+    }
+}
+
+/// The process's token manager.
+pub fn tokens() -> &'static TokenManager {
+    static TOKENS: OnceLock<TokenManager> = OnceLock::new();
+    TOKENS.get_or_init(TokenManager::default)
+}
+
+/// Whether `error` is an IMAP login rejected for its OAuth token.
+pub fn is_token_rejected(error: &str) -> bool {
+    error.starts_with("XOAUTH2 authentication failed")
+}
+
+/// Hand an account's refresh token to the backend, which keeps it in the
+/// OS keychain and refreshes access tokens itself from then on.
+/// `expires_at` (Unix seconds) goes with `access_token`. Fails when there's
+/// no usable keychain.
+#[tauri::command]
+#[specta::specta]
+pub async fn oauth_store_grant(
+    window: tauri::Window,
+    account_id: String,
+    grant: OAuthGrant,
+    access_token: Option<String>,
+    expires_at: Option<i64>,
+) -> Result<(), String> {
+    require_trusted_window(&window, "oauth_store_grant")?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+    let access_token = access_token
+        .zip(expires_at)
+        .map(|(token, at)| (token, Duration::from_secs((at - now).max(0) as u64)));
+    tokens().store(&account_id, grant, access_token).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_access_token_freshness() {
+        assert!(AccessToken::new("t".into(), Duration::from_secs(3600)).is_fresh());
+        assert!(!AccessToken::new("t".into(), Duration::from_secs(60)).is_fresh());
+        assert!(!AccessToken::new("t".into(), Duration::ZERO).is_fresh());
+    }
+
+    #[test]
+    fn test_pkce_pair() {
+        assert_eq!(
+            pkce_challenge("M25iVXpKU3puUjFaYWg3T1NDTDQtcW1ROUY5YXlwalNoc0hhakxifmZHag"),
+            "qjrzSW9gMiUgpUvqgEPE4_-8swvyCtfOVvg55o5S_es"
+        );
+
+        let pair = oauth_generate_pkce();
+        assert_eq!(pair.code_verifier.len(), 43);
+        assert!(pair
+            .code_verifier
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_'));
+        assert_eq!(pair.code_challenge, pkce_challenge(&pair.code_verifier));
+        assert_ne!(pair.code_verifier, oauth_generate_pkce().code_verifier);
+    }
+
+    #[test]
+    fn test_microsoft_profile() {
+        let profile = provider_profile("microsoft", None).unwrap();
+        assert_eq!(
+            profile.auth_url,
+            "https://login.microsoftonline.com/common/oauth2/v2.0/authorize"
+        );
+        assert!(!profile.smtp_initial_response);
+
+        let profile = provider_profile("microsoft", Some(" Contoso.onmicrosoft.com ")).unwrap();
+        assert_eq!(
+            profile.token_url,
+            "https://login.microsoftonline.com/contoso.onmicrosoft.com/oauth2/v2.0/token"
+        );
+        assert!(
+            provider_profile("microsoft", Some("72f988bf-86f1-41af-91ab-2d7cd011db47")).is_ok()
+        );
+        assert!(provider_profile("microsoft", Some("evil.com/x?")).is_err());
+        assert!(provider_profile("microsoft", Some("a..b")).is_err());
+        assert!(provider_profile("google", None).is_err());
+    }
+
+    #[test]
+    fn test_smtp_xoauth2_initial_response() {
+        assert!(!smtp_xoauth2_initial_response("smtp.office365.com"));
+        assert!(!smtp_xoauth2_initial_response("SMTP-Mail.Outlook.com."));
+        assert!(smtp_xoauth2_initial_response("smtp.gmail.com"));
+    }
+
+    #[tokio::test]
+    async fn test_cached_token_is_used() {
+        let manager = TokenManager::default();
+        manager.tokens.lock().unwrap().insert(
+            "a".into(),
+            AccessToken::new("fresh".into(), Duration::from_secs(3600)),
+        );
+        assert_eq!(
+            manager.access_token("a").await.unwrap().as_deref(),
+            Some("fresh")
+        );
+    }
+}
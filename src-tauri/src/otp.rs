@@ -0,0 +1,115 @@
+//! Copying one-time codes to the clipboard.
+//!
+//! The frontend spots verification codes in new mail and offers "Copy code"
+//! on the notification. The copy is cleared again after
+//! `BackendSettings::otp_clipboard_seconds`, unless something else has been
+//! copied since, so the code doesn't linger on the clipboard.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use tauri::State;
+
+use crate::settings::SettingsState;
+
+/// How long a copied code stays on the clipboard unless configured.
+pub const DEFAULT_CLIPBOARD_SECONDS: u32 = 60;
+const MAX_CLIPBOARD_SECONDS: u32 = 3600;
+
+/// Bumped by every copy, so an older copy's timer doesn't clear a newer one.
+static COPY_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Whether `code` looks like a one-time code: 3 to 12 letters and digits,
+/// possibly grouped with dashes or spaces.
+fn valid_code(code: &str) -> bool {
+    let significant = code.chars().filter(|c| c.is_ascii_alphanumeric()).count();
+    (3..=12).contains(&significant)
+        && code
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == ' ')
+}
+
+/// Put `code` on the clipboard and, if `expire_after` is set, clear it once
+/// that has passed if it's still the clipboard's content.
+fn copy_with_expiry(code: String, expire_after: Option<Duration>) -> Result<(), String> {
+    let generation = COPY_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let (tx, rx) = mpsc::channel();
+    // The clipboard is owned by this thread until the code expires, which
+    // also keeps it served on X11 until then.
+    std::thread::spawn(move || {
+        let mut clipboard = match arboard::Clipboard::new() {
+            Ok(clipboard) => clipboard,
+            Err(e) => {
+                let _ = tx.send(Err(format!("Clipboard unavailable: {e}")));
+                return;
+            }
+        };
+        if let Err(e) = clipboard.set_text(code.clone()) {
+            let _ = tx.send(Err(format!("Failed to copy the code: {e}")));
+            return;
+        }
+        let _ = tx.send(Ok(()));
+
+        let Some(delay) = expire_after else {
+            return;
+        };
+        std::thread::sleep(delay);
+        let still_ours = COPY_GENERATION.load(Ordering::SeqCst) == generation
+            && clipboard.get_text().is_ok_and(|text| text == code);
+        if still_ours {
+            if let Err(e) = clipboard.clear() {
+                log::warn!("Failed to clear the copied code: {e}");
+            }
+        }
+    });
+    rx.recv()
+        .map_err(|_| "Clipboard thread exited unexpectedly".to_string())?
+}
+
+/// Copy a one-time code, clearing it after the configured time.
+#[tauri::command]
+#[specta::specta]
+pub fn otp_copy_code(settings: State<'_, SettingsState>, code: String) -> Result<(), String> {
+    let code = code.trim().to_string();
+    if !valid_code(&code) {
+        return Err("Not a one-time code".to_string());
+    }
+    let seconds = settings
+        .get()
+        .otp_clipboard_seconds
+        .min(MAX_CLIPBOARD_SECONDS);
+    let expire_after = (seconds > 0).then(|| Duration::from_secs(seconds.into()));
+    copy_with_expiry(code, expire_after)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_otp_clipboard_seconds(settings: State<'_, SettingsState>) -> u32 {
+    settings.get().otp_clipboard_seconds
+}
+
+/// How long copied codes stay on the clipboard; 0 keeps them.
+#[tauri::command]
+#[specta::specta]
+pub fn set_otp_clipboard_seconds(
+    settings: State<'_, SettingsState>,
+    seconds: u32,
+) -> Result<(), String> {
+    settings.update(|s| s.otp_clipboard_seconds = seconds.min(MAX_CLIPBOARD_SECONDS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_code() {
+        assert!(valid_code("123456"));
+        assert!(valid_code("G-482913"));
+        assert!(valid_code("482 913"));
+        assert!(!valid_code("12"));
+        assert!(!valid_code("https://example.com/verify"));
+        assert!(!valid_code("1234567890123"));
+    }
+}
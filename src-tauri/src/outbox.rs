@@ -0,0 +1,536 @@
+//! Outbox for messages that should go out even if the network blips.
+//!
+//! `smtp_queue_email` writes the message (with staged attachments filled
+//! in, so it doesn't depend on this run's staging) to `outbox/` in the
+//! profile data dir and returns at once. A background task sends queued
+//! messages through the same path as `smtp_send_email`; transient failures
+//! (4xx replies, connection and TLS errors, timeouts) are retried with
+//! exponential backoff, and the frontend hears the outcome through the
+//! `outbox-sent` and `outbox-failed` events. Failed messages stay in the
+//! outbox until they're retried or cancelled, so nothing is lost.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::Notify;
+
+use crate::accounts::{require_trusted_window, AccountRegistry};
+use crate::attachments::{self, AttachmentStaging};
+use crate::audit_log::{self, AuditRecord, AuditResult};
+use crate::commands;
+use crate::events::{self, OutboxFailed, OutboxSent};
+use crate::profiles;
+use crate::settings::SettingsState;
+use crate::smtp::client as smtp_client;
+
+const OUTBOX_DIR: &str = "outbox";
+
+/// Sends tried before a message is marked failed.
+const MAX_ATTEMPTS: u32 = 8;
+/// Wait after the first transient failure; doubles with each one after.
+const BASE_BACKOFF_MS: i64 = 30_000;
+const MAX_BACKOFF_MS: i64 = 30 * 60_000;
+/// How long the sender sleeps with nothing due; queueing wakes it sooner.
+const IDLE_WAIT: Duration = Duration::from_secs(3600);
+/// How long to wait before trying again while read-only mode is on.
+const READ_ONLY_WAIT: Duration = Duration::from_secs(60);
+
+/// Substrings of send errors that mean the server couldn't be reached or
+/// dropped the connection, so a later try may well work.
+const TRANSIENT_MARKERS: &[&str] = &[
+    "transient error",
+    "network error",
+    "connection error",
+    "tls error",
+    "tcp connect",
+    "tls handshake",
+    "timed out",
+    "timeout",
+    "connection reset",
+    "connection refused",
+    "connection aborted",
+    "broken pipe",
+    "unreachable",
+    "dns",
+    "failed to lookup",
+    "read failed",
+    "write failed",
+    "transport has been shut down",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum OutboxStatus {
+    /// Waiting for `next_attempt_at`.
+    Queued,
+    Sending,
+    /// Gave up; waits for `outbox_retry` or `outbox_cancel`.
+    Failed,
+}
+
+/// A queued message. The message itself is in `<id>.eml` next to it.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct OutboxEntry {
+    pub id: String,
+    pub account_id: String,
+    pub subject: Option<String>,
+    /// Unix timestamps in milliseconds.
+    pub queued_at: i64,
+    pub next_attempt_at: i64,
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    pub status: OutboxStatus,
+}
+
+/// Whether a send error is worth retrying. SMTP replies decide when there
+/// is one (4xx yes, 5xx no); otherwise only connection trouble is, not
+/// e.g. a bad address or a missing password.
+pub fn is_transient(error: &str) -> bool {
+    let lower = error.to_lowercase();
+    if lower.contains("permanent error") {
+        return false;
+    }
+    // `SmtpSession` errors end in "failed: <code> <text>"
+    if let Some(code) = lower
+        .split("failed: ")
+        .nth(1)
+        .and_then(|rest| rest.get(..3))
+        .filter(|code| code.bytes().all(|b| b.is_ascii_digit()))
+    {
+        return code.starts_with('4');
+    }
+    TRANSIENT_MARKERS
+        .iter()
+        .any(|marker| lower.contains(marker))
+}
+
+/// Wait before the next try after `attempts` failed sends.
+pub fn backoff_ms(attempts: u32) -> i64 {
+    let doublings = attempts.saturating_sub(1).min(16);
+    (BASE_BACKOFF_MS << doublings).min(MAX_BACKOFF_MS)
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+fn new_id() -> String {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn subject_of(raw: &[u8]) -> Option<String> {
+    mail_parser::MessageParser::default()
+        .parse_headers(raw)
+        .and_then(|message| message.subject().map(str::to_string))
+}
+
+/// Managed outbox: the entries in memory, mirrored to the outbox dir.
+pub struct Outbox {
+    dir: Option<PathBuf>,
+    entries: Mutex<HashMap<String, OutboxEntry>>,
+    wake: Notify,
+}
+
+impl Outbox {
+    pub fn load(app: &AppHandle) -> Self {
+        let dir = profiles::data_dir(app)
+            .map(|dir| dir.join(OUTBOX_DIR))
+            .map_err(|e| log::warn!("No data dir for the outbox: {e}"))
+            .ok();
+        Self::from_dir(dir)
+    }
+
+    /// The outbox in `dir`. Messages that were being sent when the app
+    /// last stopped are queued again.
+    fn from_dir(dir: Option<PathBuf>) -> Self {
+        let mut entries = HashMap::new();
+        let files = dir.as_ref().and_then(|dir| std::fs::read_dir(dir).ok());
+        for file in files.into_iter().flatten().flatten() {
+            let path = file.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let entry = std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|json| {
+                    serde_json::from_str::<OutboxEntry>(&json).map_err(|e| e.to_string())
+                });
+            match entry {
+                Ok(mut entry) => {
+                    if entry.status == OutboxStatus::Sending {
+                        entry.status = OutboxStatus::Queued;
+                    }
+                    entries.insert(entry.id.clone(), entry);
+                }
+                Err(e) => log::warn!("Ignoring outbox entry {}: {e}", path.display()),
+            }
+        }
+        Self {
+            dir,
+            entries: Mutex::new(entries),
+            wake: Notify::new(),
+        }
+    }
+
+    fn dir(&self) -> Result<&PathBuf, String> {
+        self.dir
+            .as_ref()
+            .ok_or_else(|| "The outbox has no data dir".to_string())
+    }
+
+    fn save(&self, entry: &OutboxEntry) -> Result<(), String> {
+        let path = self.dir()?.join(format!("{}.json", entry.id));
+        let json = serde_json::to_string_pretty(entry)
+            .map_err(|e| format!("Failed to serialize outbox entry: {e}"))?;
+        std::fs::write(path, json).map_err(|e| format!("Failed to write outbox entry: {e}"))
+    }
+
+    fn save_logged(&self, entry: &OutboxEntry) {
+        if let Err(e) = self.save(entry) {
+            log::warn!("{e}");
+        }
+    }
+
+    /// Queue `raw` to be sent from `account_id` as soon as possible.
+    fn push(&self, account_id: &str, raw: &[u8]) -> Result<OutboxEntry, String> {
+        let dir = self.dir()?;
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create the outbox: {e}"))?;
+        let now = now_ms();
+        let entry = OutboxEntry {
+            id: new_id(),
+            account_id: account_id.to_string(),
+            subject: subject_of(raw),
+            queued_at: now,
+            next_attempt_at: now,
+            attempts: 0,
+            last_error: None,
+            status: OutboxStatus::Queued,
+        };
+        std::fs::write(dir.join(format!("{}.eml", entry.id)), raw)
+            .map_err(|e| format!("Failed to write queued message: {e}"))?;
+        self.save(&entry)?;
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(entry.id.clone(), entry.clone());
+        self.wake.notify_one();
+        Ok(entry)
+    }
+
+    fn read_raw(&self, id: &str) -> Result<Vec<u8>, String> {
+        std::fs::read(self.dir()?.join(format!("{id}.eml")))
+            .map_err(|e| format!("Failed to read queued message: {e}"))
+    }
+
+    fn remove(&self, id: &str) -> Option<OutboxEntry> {
+        let entry = self.entries.lock().unwrap().remove(id)?;
+        if let Ok(dir) = self.dir() {
+            for ext in ["eml", "json"] {
+                let _ = std::fs::remove_file(dir.join(format!("{id}.{ext}")));
+            }
+        }
+        Some(entry)
+    }
+
+    /// Queued entries due at `now`, oldest first, marked as being sent.
+    fn take_due(&self, now: i64) -> Vec<OutboxEntry> {
+        let mut entries = self.entries.lock().unwrap();
+        let mut due: Vec<OutboxEntry> = entries
+            .values_mut()
+            .filter(|e| e.status == OutboxStatus::Queued && e.next_attempt_at <= now)
+            .map(|e| {
+                e.status = OutboxStatus::Sending;
+                e.clone()
+            })
+            .collect();
+        drop(entries);
+        due.sort_by_key(|e| e.queued_at);
+        due
+    }
+
+    /// Record a failed send: queue it again after a backoff, or mark it
+    /// failed when the error is permanent or it has run out of tries.
+    fn record_failure(&self, id: &str, error: &str, now: i64) -> Option<OutboxEntry> {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.get_mut(id)?;
+        entry.attempts += 1;
+        entry.last_error = Some(error.to_string());
+        if is_transient(error) && entry.attempts < MAX_ATTEMPTS {
+            entry.status = OutboxStatus::Queued;
+            entry.next_attempt_at = now + backoff_ms(entry.attempts);
+        } else {
+            entry.status = OutboxStatus::Failed;
+        }
+        let entry = entry.clone();
+        drop(entries);
+        self.save_logged(&entry);
+        Some(entry)
+    }
+
+    /// Send entry `id` (or every queued and failed one) now.
+    fn retry(&self, id: Option<&str>) {
+        let now = now_ms();
+        let mut entries = self.entries.lock().unwrap();
+        let changed: Vec<OutboxEntry> = entries
+            .values_mut()
+            .filter(|e| e.status != OutboxStatus::Sending && id.map_or(true, |id| e.id == id))
+            .map(|e| {
+                if e.status == OutboxStatus::Failed {
+                    e.attempts = 0;
+                }
+                e.status = OutboxStatus::Queued;
+                e.next_attempt_at = now;
+                e.clone()
+            })
+            .collect();
+        drop(entries);
+        for entry in &changed {
+            self.save_logged(entry);
+        }
+        self.wake.notify_one();
+    }
+
+    /// How long until the next queued entry is due.
+    fn next_wait(&self, now: i64) -> Duration {
+        self.entries
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|e| e.status == OutboxStatus::Queued)
+            .map(|e| Duration::from_millis((e.next_attempt_at - now).max(0) as u64))
+            .min()
+            .unwrap_or(IDLE_WAIT)
+    }
+
+    fn list(&self) -> Vec<OutboxEntry> {
+        let mut entries: Vec<OutboxEntry> =
+            self.entries.lock().unwrap().values().cloned().collect();
+        entries.sort_by_key(|e| e.queued_at);
+        entries
+    }
+}
+
+/// Send what's due; returns how long to sleep before looking again.
+/// The audit log row for a send that went out, or failed for good.
+fn send_audit(entry: &OutboxEntry, error: Option<&str>) -> AuditRecord {
+    AuditRecord {
+        account_id: entry.account_id.clone(),
+        operation: "send",
+        thread_id: None,
+        folder: None,
+        uids: Vec::new(),
+        detail: entry.subject.clone(),
+        result: if error.is_some() {
+            AuditResult::Failed
+        } else {
+            AuditResult::Ok
+        },
+        error: error.map(str::to_string),
+    }
+}
+
+async fn send_due(app: &AppHandle, outbox: &Outbox) -> Duration {
+    if app
+        .state::<SettingsState>()
+        .require_writable("outbox")
+        .is_err()
+    {
+        return READ_ONLY_WAIT;
+    }
+    let accounts = app.state::<AccountRegistry>();
+    let staging = app.state::<AttachmentStaging>();
+    for entry in outbox.take_due(now_ms()) {
+        let result = match outbox.read_raw(&entry.id) {
+            Ok(raw) => commands::send_email(&accounts, &staging, &entry.account_id, &raw).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(sent) => {
+                outbox.remove(&entry.id);
+                audit_log::record(app, vec![send_audit(&entry, None)]).await;
+                events::emit(
+                    app,
+                    OutboxSent {
+                        id: entry.id,
+                        account_id: entry.account_id,
+                        message: sent.message,
+                    },
+                );
+            }
+            Err(error) => match outbox.record_failure(&entry.id, &error, now_ms()) {
+                Some(entry) if entry.status == OutboxStatus::Failed => {
+                    log::warn!("Outbox message {} failed: {error}", entry.id);
+                    audit_log::record(app, vec![send_audit(&entry, Some(&error))]).await;
+                    events::emit(
+                        app,
+                        OutboxFailed {
+                            id: entry.id,
+                            account_id: entry.account_id,
+                            error,
+                            attempts: entry.attempts,
+                        },
+                    );
+                }
+                Some(entry) => log::info!(
+                    "Outbox message {} not sent ({error}); retrying in {}s",
+                    entry.id,
+                    backoff_ms(entry.attempts) / 1000
+                ),
+                // Cancelled while it was being sent
+                None => {}
+            },
+        }
+    }
+    outbox.next_wait(now_ms())
+}
+
+/// Start the background sender.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let outbox = app.state::<Outbox>();
+        loop {
+            let wait = send_due(&app, &outbox).await;
+            tokio::select! {
+                _ = outbox.wake.notified() => {}
+                _ = tokio::time::sleep(wait) => {}
+            }
+        }
+    });
+}
+
+/// Queue a message to be sent from `account_id` in the background, retried
+/// while the network or server is having trouble. Resolves to the outbox
+/// entry once the message is safely on disk.
+/// `raw_email` is base64url, as for `smtp_send_email`.
+#[tauri::command]
+#[specta::specta]
+pub async fn smtp_queue_email(
+    account_id: String,
+    raw_email: String,
+    outbox: State<'_, Outbox>,
+    staging: State<'_, AttachmentStaging>,
+    accounts: State<'_, AccountRegistry>,
+    window: tauri::Window,
+    settings: State<'_, SettingsState>,
+) -> Result<OutboxEntry, String> {
+    require_trusted_window(&window, "smtp_queue_email")?;
+    settings.require_writable("smtp_queue_email")?;
+    let raw = smtp_client::decode_base64url(&raw_email)?;
+    // Staging only lasts this run, so store the message complete
+    if !attachments::server_messages(&raw, &staging).is_empty() {
+        let imap_config = accounts.imap(&account_id).await?;
+        attachments::download_server_messages(&imap_config, &raw, &staging).await?;
+    }
+    let raw = attachments::expand_staged_parts(&raw, &staging)?;
+    outbox.push(&account_id, &raw)
+}
+
+/// Everything in the outbox, oldest first.
+#[tauri::command]
+#[specta::specta]
+pub fn outbox_list(outbox: State<'_, Outbox>) -> Vec<OutboxEntry> {
+    outbox.list()
+}
+
+/// Send entry `id`, or every entry, now, including failed ones.
+#[tauri::command]
+#[specta::specta]
+pub fn outbox_retry(outbox: State<'_, Outbox>, id: Option<String>) {
+    outbox.retry(id.as_deref());
+}
+
+/// Take a message out of the outbox without sending it. Fails while it's
+/// being sent.
+#[tauri::command]
+#[specta::specta]
+pub fn outbox_cancel(outbox: State<'_, Outbox>, id: String) -> Result<(), String> {
+    let sending = outbox
+        .entries
+        .lock()
+        .unwrap()
+        .get(&id)
+        .is_some_and(|e| e.status == OutboxStatus::Sending);
+    if sending {
+        return Err("The message is being sent".to_string());
+    }
+    outbox.remove(&id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_transient() {
+        assert!(is_transient(
+            "SMTP send error: transient error (421): try later"
+        ));
+        assert!(is_transient(
+            "SMTP send error: network error: connection reset by peer"
+        ));
+        assert!(is_transient(
+            "TCP connect to smtp.example.com:587 failed: refused"
+        ));
+        assert!(is_transient("SMTP RCPT failed: 451 greylisted"));
+        assert!(!is_transient("SMTP RCPT failed: 550 no such user"));
+        assert!(!is_transient(
+            "SMTP send error: permanent error (554): spam"
+        ));
+        assert!(!is_transient("Invalid From address 'x': missing @"));
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_cap() {
+        assert_eq!(backoff_ms(1), 30_000);
+        assert_eq!(backoff_ms(2), 60_000);
+        assert_eq!(backoff_ms(4), 240_000);
+        assert_eq!(backoff_ms(20), MAX_BACKOFF_MS);
+    }
+
+    #[test]
+    fn test_queue_survives_restart() {
+        let dir = std::env::temp_dir().join(format!("sora-outbox-test-{}", new_id()));
+        let outbox = Outbox::from_dir(Some(dir.clone()));
+        let entry = outbox
+            .push("acc", b"Subject: Hello\r\n\r\nBody\r\n")
+            .unwrap();
+        assert_eq!(entry.subject.as_deref(), Some("Hello"));
+
+        // Interrupted mid-send: queued again on the next start
+        assert_eq!(outbox.take_due(now_ms()).len(), 1);
+        let reloaded = Outbox::from_dir(Some(dir.clone()));
+        let entries = reloaded.list();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].status, OutboxStatus::Queued);
+        assert_eq!(
+            reloaded.read_raw(&entry.id).unwrap(),
+            b"Subject: Hello\r\n\r\nBody\r\n"
+        );
+
+        reloaded.take_due(now_ms());
+        let failed = reloaded
+            .record_failure(&entry.id, "SMTP RCPT failed: 421 busy", 1_000)
+            .unwrap();
+        assert_eq!(failed.status, OutboxStatus::Queued);
+        assert_eq!(failed.next_attempt_at, 1_000 + BASE_BACKOFF_MS);
+        let failed = reloaded
+            .record_failure(&entry.id, "SMTP RCPT failed: 550 no such user", 2_000)
+            .unwrap();
+        assert_eq!(failed.status, OutboxStatus::Failed);
+
+        reloaded.remove(&entry.id);
+        assert!(Outbox::from_dir(Some(dir.clone())).list().is_empty());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,166 @@
+//! Secondary windows that show a single message outside the main window.
+//!
+//! Each message gets at most one window: opening it again focuses the
+//! existing one. Open windows are tracked so they can be closed in bulk
+//! (e.g. when an account is removed) and are dropped from the registry as
+//! soon as they are destroyed.
+
+use serde::Serialize;
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+
+const MESSAGE_WINDOW_WIDTH: f64 = 800.0;
+const MESSAGE_WINDOW_HEIGHT: f64 = 700.0;
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct MessageWindowInfo {
+    pub label: String,
+    pub account: String,
+    pub folder: String,
+    pub uid: u32,
+}
+
+/// Registry of open message windows, keyed by window label.
+#[derive(Default)]
+pub struct MessageWindows(Mutex<HashMap<String, MessageWindowInfo>>);
+
+/// Window label for a message. Labels only allow `[a-zA-Z0-9-/:_]`, so the
+/// account and folder are sanitized; the uid keeps labels unique per folder.
+fn message_window_label(account: &str, folder: &str, uid: u32) -> String {
+    let sanitize = |s: &str| -> String {
+        s.chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    };
+    format!("message-{}-{}-{uid}", sanitize(account), sanitize(folder))
+}
+
+/// Percent-encode a query string component (RFC 3986 unreserved chars kept).
+fn encode_query_component(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    out
+}
+
+/// Open a message in its own window, or focus it if it's already open.
+#[tauri::command]
+#[specta::specta]
+pub async fn open_message_window(
+    app: AppHandle,
+    account: String,
+    folder: String,
+    uid: u32,
+    title: Option<String>,
+) -> Result<String, String> {
+    let label = message_window_label(&account, &folder, uid);
+
+    if let Some(existing) = app.get_webview_window(&label) {
+        let _ = existing.unminimize();
+        let _ = existing.show();
+        let _ = existing.set_focus();
+        return Ok(label);
+    }
+
+    let url = format!(
+        "index.html?message={uid}&account={}&folder={}",
+        encode_query_component(&account),
+        encode_query_component(&folder),
+    );
+
+    let window = WebviewWindowBuilder::new(&app, &label, WebviewUrl::App(url.into()))
+        .title(title.as_deref().unwrap_or("Message"))
+        .inner_size(MESSAGE_WINDOW_WIDTH, MESSAGE_WINDOW_HEIGHT)
+        .center()
+        .disable_drag_drop_handler()
+        .build()
+        .map_err(|e| format!("Failed to open message window: {e}"))?;
+
+    let registry = app.state::<MessageWindows>();
+    registry.0.lock().unwrap().insert(
+        label.clone(),
+        MessageWindowInfo {
+            label: label.clone(),
+            account,
+            folder,
+            uid,
+        },
+    );
+
+    let app_handle = app.clone();
+    let closed_label = label.clone();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::Destroyed = event {
+            let registry = app_handle.state::<MessageWindows>();
+            registry.0.lock().unwrap().remove(&closed_label);
+        }
+    });
+
+    Ok(label)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn list_message_windows(registry: tauri::State<'_, MessageWindows>) -> Vec<MessageWindowInfo> {
+    registry.0.lock().unwrap().values().cloned().collect()
+}
+
+/// Close all message windows, or only those belonging to `account`.
+#[tauri::command]
+#[specta::specta]
+pub fn close_message_windows(
+    app: AppHandle,
+    registry: tauri::State<'_, MessageWindows>,
+    account: Option<String>,
+) {
+    let labels: Vec<String> = registry
+        .0
+        .lock()
+        .unwrap()
+        .values()
+        .filter(|w| account.as_ref().map_or(true, |a| &w.account == a))
+        .map(|w| w.label.clone())
+        .collect();
+
+    for label in labels {
+        if let Some(window) = app.get_webview_window(&label) {
+            let _ = window.close();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_window_label_sanitizes() {
+        assert_eq!(
+            message_window_label("acc@example.com", "[Gmail]/Sent Mail", 42),
+            "message-acc_example_com-_Gmail__Sent_Mail-42"
+        );
+    }
+
+    #[test]
+    fn test_encode_query_component() {
+        assert_eq!(
+            encode_query_component("INBOX/Sub Folder&x"),
+            "INBOX%2FSub%20Folder%26x"
+        );
+        assert_eq!(encode_query_component("a-b_c.d~"), "a-b_c.d~");
+    }
+}
@@ -0,0 +1,368 @@
+//! Optional master password protecting stored credentials.
+//!
+//! Account passwords, OAuth tokens and other secrets are encrypted in the
+//! database by the frontend (`src/utils/crypto.ts`) with a random data key
+//! kept in `velo.key` in the profile's data directory. Without a master password
+//! that file holds the key as plain base64, which relies on nobody else
+//! being able to read the user's files. With one, the file holds the data
+//! key wrapped with AES-256-GCM under a key derived from the password with
+//! Argon2id; the app starts locked and the frontend shows an unlock screen
+//! until `profile_unlock` succeeds. The unwrapped key only lives in memory.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use aes_gcm::aead::{Aead, KeyInit, OsRng};
+use aes_gcm::{AeadCore, Aes256Gcm, Nonce};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager};
+
+use crate::accounts::require_trusted_window;
+use crate::profiles;
+
+const KEY_FILE: &str = "velo.key";
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+
+/// The data key encrypted under a master password, as stored in `velo.key`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct WrappedKey {
+    version: u32,
+    /// Argon2id memory cost in KiB, iterations and lanes.
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+    salt: String,
+    nonce: String,
+    key: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type)]
+pub struct ProfileStatus {
+    /// A master password is set.
+    pub protected: bool,
+    /// The data key isn't available until `profile_unlock` succeeds.
+    pub locked: bool,
+}
+
+#[derive(Default)]
+struct KeyState {
+    key: Option<Vec<u8>>,
+    wrapped: Option<WrappedKey>,
+    /// `velo.key` exists but couldn't be read; never overwrite it.
+    malformed: bool,
+}
+
+/// Managed state holding the data key (once unlocked) and its file location.
+pub struct ProfileLock {
+    path: Option<PathBuf>,
+    state: Mutex<KeyState>,
+}
+
+impl ProfileLock {
+    /// Read `velo.key`, leaving the profile locked if it's protected. A
+    /// missing file is fine: the key is generated on first use.
+    pub fn load(app: &AppHandle) -> Self {
+        let path = profiles::data_dir(app)
+            .map(|dir| dir.join(KEY_FILE))
+            .map_err(|e| log::warn!("No data dir for {KEY_FILE}: {e}"))
+            .ok();
+
+        let state = path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .map(|contents| parse_key_file(&contents))
+            .unwrap_or_default();
+
+        Self {
+            path,
+            state: Mutex::new(state),
+        }
+    }
+
+    pub fn status(&self) -> ProfileStatus {
+        let state = self.state.lock().unwrap();
+        ProfileStatus {
+            protected: state.wrapped.is_some(),
+            locked: state.key.is_none() && state.wrapped.is_some(),
+        }
+    }
+
+    /// The data key, generating and saving one on first use.
+    pub fn data_key(&self) -> Result<Vec<u8>, String> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(key) = &state.key {
+            return Ok(key.clone());
+        }
+        if state.wrapped.is_some() {
+            return Err("The profile is locked".to_string());
+        }
+        if state.malformed {
+            return Err(format!("{KEY_FILE} is malformed"));
+        }
+        let key = Aes256Gcm::generate_key(OsRng).to_vec();
+        self.write(&STANDARD.encode(&key))?;
+        state.key = Some(key.clone());
+        Ok(key)
+    }
+
+    pub fn unlock(&self, password: &str) -> Result<(), String> {
+        let mut state = self.state.lock().unwrap();
+        let Some(wrapped) = &state.wrapped else {
+            return Ok(());
+        };
+        state.key = Some(unwrap_key(wrapped, password)?);
+        Ok(())
+    }
+
+    /// Set, change or (with `new_password: None`) remove the master
+    /// password. Changing or removing it requires the current one.
+    pub fn set_password(
+        &self,
+        current_password: Option<&str>,
+        new_password: Option<&str>,
+    ) -> Result<(), String> {
+        // Fails while locked; generates the key if there is none yet
+        let key = self.data_key()?;
+
+        let mut state = self.state.lock().unwrap();
+        if let Some(wrapped) = &state.wrapped {
+            unwrap_key(wrapped, current_password.unwrap_or_default())?;
+        }
+
+        match new_password {
+            Some(password) => {
+                if password.is_empty() {
+                    return Err("The master password can't be empty".to_string());
+                }
+                let wrapped = wrap_key(&key, password, &default_params())?;
+                let json = serde_json::to_string_pretty(&wrapped)
+                    .map_err(|e| format!("Failed to serialize {KEY_FILE}: {e}"))?;
+                self.write(&json)?;
+                state.wrapped = Some(wrapped);
+            }
+            None => {
+                self.write(&STANDARD.encode(&key))?;
+                state.wrapped = None;
+            }
+        }
+        Ok(())
+    }
+
+    /// Replace `velo.key` without leaving a half-written file behind. On
+    /// unix only the owner can read it.
+    fn write(&self, contents: &str) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Err(format!("No location for {KEY_FILE}"));
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create app data directory: {e}"))?;
+        }
+        let tmp = path.with_extension("key.tmp");
+        std::fs::write(&tmp, contents).map_err(|e| format!("Failed to write {KEY_FILE}: {e}"))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(0o600))
+                .map_err(|e| format!("Failed to restrict {KEY_FILE}: {e}"))?;
+        }
+        std::fs::rename(&tmp, path).map_err(|e| format!("Failed to write {KEY_FILE}: {e}"))
+    }
+}
+
+/// A plain base64 key (every install before master passwords) or a
+/// [`WrappedKey`] as JSON.
+fn parse_key_file(contents: &str) -> KeyState {
+    let contents = contents.trim();
+    if contents.starts_with('{') {
+        match serde_json::from_str(contents) {
+            Ok(wrapped) => {
+                return KeyState {
+                    wrapped: Some(wrapped),
+                    ..KeyState::default()
+                }
+            }
+            Err(e) => log::warn!("Malformed {KEY_FILE}: {e}"),
+        }
+    } else {
+        match STANDARD.decode(contents) {
+            Ok(key) if key.len() == KEY_LEN => {
+                return KeyState {
+                    key: Some(key),
+                    ..KeyState::default()
+                }
+            }
+            _ => log::warn!("Malformed {KEY_FILE}"),
+        }
+    }
+    KeyState {
+        malformed: true,
+        ..KeyState::default()
+    }
+}
+
+/// Argon2id with 64 MiB, three passes and one lane, above the OWASP
+/// minimum while keeping unlock under a second on modest hardware.
+fn default_params() -> Params {
+    Params::new(64 * 1024, 3, 1, Some(KEY_LEN)).expect("valid Argon2 params")
+}
+
+fn derive_key(password: &str, salt: &[u8], params: Params) -> Result<[u8; KEY_LEN], String> {
+    let mut out = [0u8; KEY_LEN];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(password.as_bytes(), salt, &mut out)
+        .map_err(|e| format!("Failed to derive key: {e}"))?;
+    Ok(out)
+}
+
+fn wrap_key(key: &[u8], password: &str, params: &Params) -> Result<WrappedKey, String> {
+    let mut salt = [0u8; SALT_LEN];
+    aes_gcm::aead::rand_core::RngCore::fill_bytes(&mut OsRng, &mut salt);
+    let kek = derive_key(password, &salt, params.clone())?;
+
+    let cipher = Aes256Gcm::new_from_slice(&kek).map_err(|e| e.to_string())?;
+    let nonce = Aes256Gcm::generate_nonce(OsRng);
+    let encrypted = cipher
+        .encrypt(&nonce, key)
+        .map_err(|_| "Failed to encrypt the data key".to_string())?;
+
+    Ok(WrappedKey {
+        version: 1,
+        m_cost: params.m_cost(),
+        t_cost: params.t_cost(),
+        p_cost: params.p_cost(),
+        salt: STANDARD.encode(salt),
+        nonce: STANDARD.encode(nonce),
+        key: STANDARD.encode(encrypted),
+    })
+}
+
+fn unwrap_key(wrapped: &WrappedKey, password: &str) -> Result<Vec<u8>, String> {
+    let decode = |s: &str| {
+        STANDARD
+            .decode(s)
+            .map_err(|e| format!("Malformed {KEY_FILE}: {e}"))
+    };
+    let params = Params::new(
+        wrapped.m_cost,
+        wrapped.t_cost,
+        wrapped.p_cost,
+        Some(KEY_LEN),
+    )
+    .map_err(|e| format!("Malformed {KEY_FILE}: {e}"))?;
+    let kek = derive_key(password, &decode(&wrapped.salt)?, params)?;
+
+    let nonce = decode(&wrapped.nonce)?;
+    if nonce.len() != 12 {
+        return Err(format!("Malformed {KEY_FILE}: bad nonce"));
+    }
+    let cipher = Aes256Gcm::new_from_slice(&kek).map_err(|e| e.to_string())?;
+    cipher
+        .decrypt(Nonce::from_slice(&nonce), decode(&wrapped.key)?.as_slice())
+        .map_err(|_| "Wrong master password".to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn profile_status(lock: tauri::State<'_, ProfileLock>) -> ProfileStatus {
+    lock.status()
+}
+
+/// The data key as base64, for `crypto.ts`. Fails while the profile is
+/// locked.
+#[tauri::command]
+#[specta::specta]
+pub fn profile_data_key(lock: tauri::State<'_, ProfileLock>) -> Result<String, String> {
+    lock.data_key().map(|key| STANDARD.encode(key))
+}
+
+// Deriving a key with Argon2 takes a noticeable moment, so the password
+// commands run on a blocking thread.
+
+#[tauri::command]
+#[specta::specta]
+pub async fn profile_unlock(app: AppHandle, password: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || app.state::<ProfileLock>().unlock(&password))
+        .await
+        .map_err(|e| format!("Unlock failed: {e}"))?
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn profile_set_master_password(
+    app: AppHandle,
+    window: tauri::Window,
+    current_password: Option<String>,
+    new_password: Option<String>,
+) -> Result<(), String> {
+    require_trusted_window(&window, "profile_set_master_password")?;
+    tauri::async_runtime::spawn_blocking(move || {
+        app.state::<ProfileLock>()
+            .set_password(current_password.as_deref(), new_password.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Failed to set the master password: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_params() -> Params {
+        Params::new(256, 1, 1, Some(KEY_LEN)).unwrap()
+    }
+
+    #[test]
+    fn test_wrap_round_trip() {
+        let key = [7u8; KEY_LEN];
+        let wrapped = wrap_key(&key, "correct horse", &test_params()).unwrap();
+
+        assert_eq!(unwrap_key(&wrapped, "correct horse").unwrap(), key);
+        assert_eq!(
+            unwrap_key(&wrapped, "wrong").unwrap_err(),
+            "Wrong master password"
+        );
+    }
+
+    #[test]
+    fn test_parse_key_file() {
+        let plain = parse_key_file(&format!("{}\n", STANDARD.encode([1u8; KEY_LEN])));
+        assert_eq!(plain.key.as_deref(), Some(&[1u8; KEY_LEN][..]));
+        assert!(plain.wrapped.is_none());
+
+        let wrapped = wrap_key(&[1u8; KEY_LEN], "pw", &test_params()).unwrap();
+        let parsed = parse_key_file(&serde_json::to_string_pretty(&wrapped).unwrap());
+        assert!(parsed.key.is_none());
+        assert_eq!(parsed.wrapped.unwrap().m_cost, 256);
+
+        let garbage = parse_key_file("not a key");
+        assert!(garbage.key.is_none() && garbage.wrapped.is_none());
+        assert!(garbage.malformed);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_key_file_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir =
+            std::env::temp_dir().join(format!("sora-profile-lock-test-{}", std::process::id()));
+        let lock = ProfileLock {
+            path: Some(dir.join(KEY_FILE)),
+            state: Mutex::new(KeyState::default()),
+        };
+        lock.data_key().unwrap();
+
+        let mode = std::fs::metadata(dir.join(KEY_FILE))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
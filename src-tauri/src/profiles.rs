@@ -0,0 +1,431 @@
+//! Isolated profiles (e.g. work and personal), one per app process.
+//!
+//! Each profile has its own database, backend settings, credential key and
+//! attachment cache under `profiles/<id>/` in the app config and data
+//! directories; the `default` profile uses the directories themselves, so
+//! installs from before profiles keep their data. The profile list lives in
+//! `profiles.json` in the app config dir.
+//!
+//! The profile is chosen at launch: a pending switch first, then
+//! `--profile <id or name>`, then the last one used. Switching restarts the
+//! app, so every window and all in-memory state belong to one profile.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::accounts::require_trusted_window;
+
+const PROFILES_FILE: &str = "profiles.json";
+const PROFILES_DIR: &str = "profiles";
+const DB_FILE: &str = "velo.db";
+pub const DEFAULT_PROFILE_ID: &str = "default";
+/// Command-line flag selecting a profile by id or name.
+pub const PROFILE_ARG: &str = "--profile";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+}
+
+/// The profile this process runs, with where its files live.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ActiveProfile {
+    #[serde(flatten)]
+    pub profile: Profile,
+    /// Directory of the profile's files relative to the app config and data
+    /// dirs; empty for the default profile.
+    pub dir: String,
+    /// The URL the frontend passes to `Database.load`.
+    pub db_url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct ProfilesFile {
+    profiles: Vec<Profile>,
+    last_used: Option<String>,
+    /// Set by `profile_switch` right before restarting.
+    switch_to: Option<String>,
+}
+
+impl Default for ProfilesFile {
+    fn default() -> Self {
+        Self {
+            profiles: vec![Profile {
+                id: DEFAULT_PROFILE_ID.to_string(),
+                name: "Default".to_string(),
+            }],
+            last_used: None,
+            switch_to: None,
+        }
+    }
+}
+
+impl ProfilesFile {
+    /// A profile by id, or by name ignoring case.
+    fn find(&self, id_or_name: &str) -> Option<&Profile> {
+        self.profiles
+            .iter()
+            .find(|p| p.id == id_or_name)
+            .or_else(|| {
+                self.profiles
+                    .iter()
+                    .find(|p| p.name.eq_ignore_ascii_case(id_or_name))
+            })
+    }
+
+    /// Pick the profile to launch, consuming a pending switch.
+    fn select(&mut self, args: &[String]) -> Profile {
+        let pending = self.switch_to.take();
+        let requested = requested_profile(args);
+        let chosen = [pending.as_deref(), requested, self.last_used.as_deref()]
+            .into_iter()
+            .flatten()
+            .find_map(|id| self.find(id).cloned());
+        chosen.unwrap_or_else(|| self.profiles[0].clone())
+    }
+
+    /// Read `path`, falling back to just the default profile.
+    fn read(path: Option<&Path>) -> Self {
+        let file: Self = path
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|json| {
+                serde_json::from_str(&json)
+                    .map_err(|e| log::warn!("Ignoring malformed {PROFILES_FILE}: {e}"))
+                    .ok()
+            })
+            .unwrap_or_default();
+        if file.profiles.is_empty() {
+            return Self::default();
+        }
+        file
+    }
+}
+
+/// The value of `--profile <value>` or `--profile=<value>`, if any.
+fn requested_profile(args: &[String]) -> Option<&str> {
+    args.iter().enumerate().find_map(|(i, arg)| {
+        if arg == PROFILE_ARG {
+            args.get(i + 1).map(String::as_str)
+        } else {
+            arg.strip_prefix(PROFILE_ARG)?.strip_prefix('=')
+        }
+    })
+}
+
+/// Turn a display name into a directory-safe id that isn't taken yet.
+fn new_profile_id(name: &str, existing: &[Profile]) -> String {
+    let slug: String = name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let slug = slug.trim_matches('-');
+    let base = if slug.is_empty() { "profile" } else { slug };
+    let taken = |id: &str| existing.iter().any(|p| p.id == id);
+    if !taken(base) {
+        return base.to_string();
+    }
+    (2..)
+        .map(|n| format!("{base}-{n}"))
+        .find(|id| !taken(id))
+        .expect("unbounded range")
+}
+
+/// Managed state: the profile list and the profile this process runs.
+pub struct Profiles {
+    path: Option<PathBuf>,
+    file: Mutex<ProfilesFile>,
+    active: Profile,
+}
+
+impl Profiles {
+    /// Load the profile list and choose this launch's profile from the
+    /// command line and the saved state.
+    pub fn load<R: Runtime>(app: &AppHandle<R>) -> Self {
+        let path = app
+            .path()
+            .app_config_dir()
+            .map(|dir| dir.join(PROFILES_FILE))
+            .map_err(|e| log::warn!("No app config dir for {PROFILES_FILE}: {e}"))
+            .ok();
+
+        let mut file = ProfilesFile::read(path.as_deref());
+        let args: Vec<String> = std::env::args().collect();
+        let active = file.select(&args);
+        file.last_used = Some(active.id.clone());
+        log::info!("Using profile {}", active.id);
+
+        let profiles = Self {
+            path,
+            file: Mutex::new(file),
+            active,
+        };
+        if let Err(e) = profiles.save() {
+            log::warn!("{e}");
+        }
+        profiles
+    }
+
+    pub fn active(&self) -> ActiveProfile {
+        ActiveProfile {
+            profile: self.active.clone(),
+            dir: self.dir_prefix().trim_end_matches('/').to_string(),
+            db_url: self.db_url(),
+        }
+    }
+
+    /// The profile's database, as keyed in the SQL plugin's `DbInstances`.
+    pub fn db_url(&self) -> String {
+        format!("sqlite:{}{DB_FILE}", self.dir_prefix())
+    }
+
+    pub fn list(&self) -> Vec<Profile> {
+        self.file.lock().unwrap().profiles.clone()
+    }
+
+    pub fn create(&self, name: &str) -> Result<Profile, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Profile name can't be empty".to_string());
+        }
+        let profile = {
+            let mut file = self.file.lock().unwrap();
+            if file.find(name).is_some() {
+                return Err(format!("A profile named {name} already exists"));
+            }
+            let profile = Profile {
+                id: new_profile_id(name, &file.profiles),
+                name: name.to_string(),
+            };
+            file.profiles.push(profile.clone());
+            profile
+        };
+        self.save()?;
+        Ok(profile)
+    }
+
+    /// Remember `id` as the profile to start with on the next launch.
+    fn set_pending_switch(&self, id: &str) -> Result<(), String> {
+        {
+            let mut file = self.file.lock().unwrap();
+            let id = file
+                .find(id)
+                .map(|p| p.id.clone())
+                .ok_or_else(|| format!("Unknown profile {id}"))?;
+            file.switch_to = Some(id);
+        }
+        self.save()
+    }
+
+    fn dir_prefix(&self) -> String {
+        dir_prefix(&self.active.id)
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create app config directory: {e}"))?;
+        }
+        let json = serde_json::to_string_pretty(&*self.file.lock().unwrap())
+            .map_err(|e| format!("Failed to serialize {PROFILES_FILE}: {e}"))?;
+        std::fs::write(path, json).map_err(|e| format!("Failed to write {PROFILES_FILE}: {e}"))
+    }
+}
+
+/// `profiles/<id>/` for named profiles, empty for the default one.
+/// Always `/`-separated, as the frontend joins paths with it.
+fn dir_prefix(id: &str) -> String {
+    if id == DEFAULT_PROFILE_ID {
+        String::new()
+    } else {
+        format!("{PROFILES_DIR}/{id}/")
+    }
+}
+
+/// The config directory of the profile `args` ask for (or the last one
+/// used) under `app_config_dir`, for running without the app. Unlike a
+/// launch this leaves a pending switch and the last used profile alone.
+pub fn headless_config_dir(app_config_dir: &Path, args: &[String]) -> PathBuf {
+    let file = ProfilesFile::read(Some(&app_config_dir.join(PROFILES_FILE)));
+    let profile = [requested_profile(args), file.last_used.as_deref()]
+        .into_iter()
+        .flatten()
+        .find_map(|id| file.find(id))
+        .unwrap_or(&file.profiles[0]);
+    app_config_dir.join(dir_prefix(&profile.id))
+}
+
+/// The active profile's directory under the app config dir (database and
+/// backend settings).
+pub fn config_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let base = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("No app config dir: {e}"))?;
+    Ok(base.join(app.state::<Profiles>().dir_prefix()))
+}
+
+/// The active profile's directory under the app data dir (credential key
+/// and attachment cache).
+pub fn data_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let base = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("No app data dir: {e}"))?;
+    Ok(base.join(app.state::<Profiles>().dir_prefix()))
+}
+
+/// Restart into profile `id` (by id or name), unless it's already running.
+pub fn switch<R: Runtime>(app: &AppHandle<R>, id: &str) -> Result<(), String> {
+    let profiles = app.state::<Profiles>();
+    if profiles.active.id == id || profiles.active.name.eq_ignore_ascii_case(id) {
+        return Ok(());
+    }
+    profiles.set_pending_switch(id)?;
+    log::info!("Switching to profile {id}");
+    app.restart()
+}
+
+/// A second launch asked for another profile: switch to it.
+pub fn switch_from_args<R: Runtime>(app: &AppHandle<R>, args: &[String]) {
+    if let Some(id) = requested_profile(args) {
+        if let Err(e) = switch(app, id) {
+            log::warn!("Profile switch from second launch failed: {e}");
+        }
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn profile_current(profiles: tauri::State<'_, Profiles>) -> ActiveProfile {
+    profiles.active()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn profile_list(profiles: tauri::State<'_, Profiles>) -> Vec<Profile> {
+    profiles.list()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn profile_create(
+    window: tauri::Window,
+    profiles: tauri::State<'_, Profiles>,
+    name: String,
+) -> Result<Profile, String> {
+    require_trusted_window(&window, "profile_create")?;
+    profiles.create(&name)
+}
+
+/// Restart the app in another profile. Doesn't return on success.
+#[tauri::command]
+#[specta::specta]
+pub fn profile_switch(app: AppHandle, window: tauri::Window, id: String) -> Result<(), String> {
+    require_trusted_window(&window, "profile_switch")?;
+    switch(&app, &id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn file_with(names: &[(&str, &str)]) -> ProfilesFile {
+        let mut file = ProfilesFile::default();
+        for (id, name) in names {
+            file.profiles.push(Profile {
+                id: id.to_string(),
+                name: name.to_string(),
+            });
+        }
+        file
+    }
+
+    #[test]
+    fn test_requested_profile() {
+        assert_eq!(
+            requested_profile(&args(&["sora", "--profile", "work"])),
+            Some("work")
+        );
+        assert_eq!(
+            requested_profile(&args(&["sora", "--profile=Work"])),
+            Some("Work")
+        );
+        assert_eq!(requested_profile(&args(&["sora", "--profile"])), None);
+        assert_eq!(requested_profile(&args(&["sora", "--hidden"])), None);
+        assert_eq!(requested_profile(&args(&["sora", "--profiles"])), None);
+    }
+
+    #[test]
+    fn test_select_order() {
+        let mut file = file_with(&[("work", "Work"), ("home", "Home")]);
+        assert_eq!(file.select(&args(&["sora"])).id, "default");
+
+        file.last_used = Some("home".to_string());
+        assert_eq!(file.select(&args(&["sora"])).id, "home");
+        assert_eq!(
+            file.select(&args(&["sora", "--profile", "WORK"])).id,
+            "work"
+        );
+        assert_eq!(
+            file.select(&args(&["sora", "--profile", "gone"])).id,
+            "home"
+        );
+
+        file.switch_to = Some("default".to_string());
+        assert_eq!(
+            file.select(&args(&["sora", "--profile", "work"])).id,
+            "default"
+        );
+        assert_eq!(file.switch_to, None);
+    }
+
+    #[test]
+    fn test_headless_config_dir() {
+        let base = std::env::temp_dir().join(format!("sora-profiles-{}", std::process::id()));
+        std::fs::create_dir_all(&base).unwrap();
+        let mut file = file_with(&[("work", "Work")]);
+        file.last_used = Some("work".to_string());
+        file.switch_to = Some("default".to_string());
+        std::fs::write(
+            base.join(PROFILES_FILE),
+            serde_json::to_string(&file).unwrap(),
+        )
+        .unwrap();
+
+        // A pending switch is for the next launch of the app
+        assert_eq!(
+            headless_config_dir(&base, &args(&["sora", "--check"])),
+            base.join("profiles/work/")
+        );
+        assert_eq!(
+            headless_config_dir(&base, &args(&["sora", "--profile=default"])),
+            base.join("")
+        );
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn test_new_profile_id() {
+        let existing = file_with(&[("work", "Work")]).profiles;
+        assert_eq!(new_profile_id("Personal Mail", &existing), "personal-mail");
+        assert_eq!(new_profile_id("work", &existing), "work-2");
+        assert_eq!(new_profile_id("  ", &existing), "profile");
+        assert_eq!(new_profile_id("../etc", &existing), "etc");
+        assert_eq!(new_profile_id("Default", &existing), "default-2");
+    }
+}
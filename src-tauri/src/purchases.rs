@@ -0,0 +1,259 @@
+//! Read side of the purchases ledger.
+//!
+//! The frontend recognizes receipts and invoices as messages sync and
+//! records vendor, date, currency and amount in the `purchases` table. These
+//! commands list and total them for an expenses view. Nothing leaves the
+//! device; the table is only filled from mail already in the local cache.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use sqlx::SqlitePool;
+use tauri::State;
+use tauri_plugin_sql::{DbInstances, DbPool};
+
+use crate::profiles::Profiles;
+
+const DEFAULT_PAGE_SIZE: i64 = 100;
+const MAX_PAGE_SIZE: i64 = 1000;
+
+/// A recorded purchase.
+#[derive(Debug, Serialize, Type)]
+pub struct Purchase {
+    pub account_id: String,
+    pub message_id: String,
+    pub thread_id: String,
+    /// "receipt" or "invoice".
+    pub kind: String,
+    pub vendor: String,
+    /// Unix timestamp in milliseconds.
+    pub purchased_at: i64,
+    /// ISO 4217 code.
+    pub currency: String,
+    /// Hundredths of the currency unit.
+    pub amount_cents: i64,
+    pub order_number: Option<String>,
+}
+
+/// Which purchases to list or total. Unset fields don't filter.
+#[derive(Debug, Default, Deserialize, Type)]
+pub struct PurchaseFilter {
+    pub account_id: Option<String>,
+    /// Unix milliseconds, inclusive.
+    pub since: Option<i64>,
+    /// Unix milliseconds, exclusive.
+    pub until: Option<i64>,
+    /// Case-insensitive substring of the vendor.
+    pub vendor: Option<String>,
+    pub currency: Option<String>,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct PurchasePage {
+    pub purchases: Vec<Purchase>,
+    /// Purchases matching the filter in all pages.
+    pub total: i64,
+}
+
+/// The sum of the purchases in one currency, and one vendor when grouped
+/// by vendor. Currencies are never added together.
+#[derive(Debug, Serialize, Type)]
+pub struct PurchaseTotal {
+    pub currency: String,
+    pub vendor: Option<String>,
+    pub amount_cents: i64,
+    pub count: i64,
+}
+
+type Row = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    i64,
+    String,
+    i64,
+    Option<String>,
+);
+
+const WHERE: &str = "WHERE (?1 IS NULL OR account_id = ?1)
+       AND (?2 IS NULL OR purchased_at >= ?2)
+       AND (?3 IS NULL OR purchased_at < ?3)
+       AND (?4 IS NULL OR instr(lower(vendor), lower(?4)) > 0)
+       AND (?5 IS NULL OR currency = upper(?5))";
+
+/// `offset` and `limit` brought into range.
+fn page_bounds(offset: Option<i64>, limit: Option<i64>) -> (i64, i64) {
+    (
+        offset.unwrap_or(0).max(0),
+        limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE),
+    )
+}
+
+/// Blank filter fields count as unset.
+fn normalize(filter: PurchaseFilter) -> PurchaseFilter {
+    let blank = |s: Option<String>| s.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    PurchaseFilter {
+        account_id: blank(filter.account_id),
+        vendor: blank(filter.vendor),
+        currency: blank(filter.currency),
+        ..filter
+    }
+}
+
+async fn query_purchases(
+    pool: &SqlitePool,
+    filter: &PurchaseFilter,
+    offset: i64,
+    limit: i64,
+) -> Result<PurchasePage, String> {
+    let sql = format!(
+        "SELECT account_id, message_id, thread_id, kind, vendor, purchased_at, currency,
+                amount_cents, order_number
+         FROM purchases {WHERE}
+         ORDER BY purchased_at DESC LIMIT ?6 OFFSET ?7"
+    );
+    let rows = sqlx::query_as::<_, Row>(&sql)
+        .bind(&filter.account_id)
+        .bind(filter.since)
+        .bind(filter.until)
+        .bind(&filter.vendor)
+        .bind(&filter.currency)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to read purchases: {e}"))?;
+
+    let (total,): (i64,) = sqlx::query_as(&format!("SELECT COUNT(*) FROM purchases {WHERE}"))
+        .bind(&filter.account_id)
+        .bind(filter.since)
+        .bind(filter.until)
+        .bind(&filter.vendor)
+        .bind(&filter.currency)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to count purchases: {e}"))?;
+
+    let purchases = rows
+        .into_iter()
+        .map(
+            |(
+                account_id,
+                message_id,
+                thread_id,
+                kind,
+                vendor,
+                purchased_at,
+                currency,
+                amount_cents,
+                order_number,
+            )| Purchase {
+                account_id,
+                message_id,
+                thread_id,
+                kind,
+                vendor,
+                purchased_at,
+                currency,
+                amount_cents,
+                order_number,
+            },
+        )
+        .collect();
+    Ok(PurchasePage { purchases, total })
+}
+
+async fn query_totals(
+    pool: &SqlitePool,
+    filter: &PurchaseFilter,
+    by_vendor: bool,
+) -> Result<Vec<PurchaseTotal>, String> {
+    let (vendor, group) = if by_vendor {
+        ("vendor", "currency, vendor COLLATE NOCASE")
+    } else {
+        ("NULL", "currency")
+    };
+    let sql = format!(
+        "SELECT currency, {vendor}, SUM(amount_cents), COUNT(*) FROM purchases {WHERE}
+         GROUP BY {group} ORDER BY SUM(amount_cents) DESC"
+    );
+    let rows = sqlx::query_as::<_, (String, Option<String>, i64, i64)>(&sql)
+        .bind(&filter.account_id)
+        .bind(filter.since)
+        .bind(filter.until)
+        .bind(&filter.vendor)
+        .bind(&filter.currency)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to total purchases: {e}"))?;
+    Ok(rows
+        .into_iter()
+        .map(|(currency, vendor, amount_cents, count)| PurchaseTotal {
+            currency,
+            vendor,
+            amount_cents,
+            count,
+        })
+        .collect())
+}
+
+/// A page of the purchases matching `filter`, newest first.
+#[tauri::command]
+#[specta::specta]
+pub async fn purchases_query(
+    db: State<'_, DbInstances>,
+    profiles: State<'_, Profiles>,
+    filter: Option<PurchaseFilter>,
+    offset: Option<i64>,
+    limit: Option<i64>,
+) -> Result<PurchasePage, String> {
+    let instances = db.0.read().await;
+    let DbPool::Sqlite(pool) = instances
+        .get(&profiles.db_url())
+        .ok_or_else(|| "Database is not loaded".to_string())?;
+    let (offset, limit) = page_bounds(offset, limit);
+    query_purchases(pool, &normalize(filter.unwrap_or_default()), offset, limit).await
+}
+
+/// What was spent per currency, or per currency and vendor, on the
+/// purchases matching `filter`, largest first.
+#[tauri::command]
+#[specta::specta]
+pub async fn purchases_totals(
+    db: State<'_, DbInstances>,
+    profiles: State<'_, Profiles>,
+    filter: Option<PurchaseFilter>,
+    by_vendor: Option<bool>,
+) -> Result<Vec<PurchaseTotal>, String> {
+    let instances = db.0.read().await;
+    let DbPool::Sqlite(pool) = instances
+        .get(&profiles.db_url())
+        .ok_or_else(|| "Database is not loaded".to_string())?;
+    query_totals(
+        pool,
+        &normalize(filter.unwrap_or_default()),
+        by_vendor.unwrap_or(false),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_blank_fields() {
+        let filter = normalize(PurchaseFilter {
+            account_id: Some(" ".into()),
+            since: Some(1),
+            vendor: Some(" Acme ".into()),
+            ..Default::default()
+        });
+
+        assert_eq!(filter.account_id, None);
+        assert_eq!(filter.since, Some(1));
+        assert_eq!(filter.vendor.as_deref(), Some("Acme"));
+        assert_eq!(filter.currency, None);
+    }
+}
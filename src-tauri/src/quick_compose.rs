@@ -0,0 +1,207 @@
+//! Small frameless compose window opened from the tray or a global shortcut,
+//! so a short email can be sent without raising the main window.
+
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+use crate::settings::SettingsState;
+
+/// Matches the `compose-*` capability so the window can use the compose APIs.
+pub const QUICK_COMPOSE_LABEL: &str = "compose-quick";
+pub const DEFAULT_QUICK_COMPOSE_SHORTCUT: &str = "CmdOrCtrl+Alt+N";
+
+const WINDOW_WIDTH: f64 = 480.0;
+const WINDOW_HEIGHT: f64 = 360.0;
+/// Gap between the cursor (or tray icon) and the window edge, in logical px.
+const CURSOR_MARGIN: f64 = 12.0;
+
+/// Rectangle in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rect {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+/// Place a window of `size` next to `point`, flipping to the other side of
+/// the cursor when it would overflow and clamping it inside `bounds`.
+///
+/// Tray icons sit at a screen edge, so this naturally puts the window just
+/// above a bottom taskbar or just below a top menu bar.
+fn place_near_point(point: (f64, f64), size: (f64, f64), bounds: Rect, margin: f64) -> (f64, f64) {
+    let (px, py) = point;
+    let (w, h) = size;
+
+    let mut x = px + margin;
+    if x + w > bounds.x + bounds.width {
+        x = px - margin - w;
+    }
+    let mut y = py + margin;
+    if y + h > bounds.y + bounds.height {
+        y = py - margin - h;
+    }
+
+    let x = x.clamp(bounds.x, (bounds.x + bounds.width - w).max(bounds.x));
+    let y = y.clamp(bounds.y, (bounds.y + bounds.height - h).max(bounds.y));
+    (x, y)
+}
+
+/// Logical position for the quick-compose window near the mouse cursor.
+fn position_near_cursor(app: &AppHandle) -> Option<(f64, f64)> {
+    let cursor = app.cursor_position().ok()?;
+    let monitor = app
+        .monitor_from_point(cursor.x, cursor.y)
+        .ok()
+        .flatten()
+        .or_else(|| app.primary_monitor().ok().flatten())?;
+
+    let scale = monitor.scale_factor();
+    let work_area = monitor.work_area();
+    let bounds = Rect {
+        x: work_area.position.x as f64,
+        y: work_area.position.y as f64,
+        width: work_area.size.width as f64,
+        height: work_area.size.height as f64,
+    };
+
+    let (x, y) = place_near_point(
+        (cursor.x, cursor.y),
+        (WINDOW_WIDTH * scale, WINDOW_HEIGHT * scale),
+        bounds,
+        CURSOR_MARGIN * scale,
+    );
+    Some((x / scale, y / scale))
+}
+
+/// Show the quick-compose window near the cursor, creating it if needed.
+pub fn open(app: &AppHandle) -> Result<(), String> {
+    let position = position_near_cursor(app);
+
+    if let Some(window) = app.get_webview_window(QUICK_COMPOSE_LABEL) {
+        if let Some((x, y)) = position {
+            let _ = window.set_position(tauri::LogicalPosition::new(x, y));
+        }
+        let _ = window.show();
+        let _ = window.set_focus();
+        return Ok(());
+    }
+
+    let mut builder = WebviewWindowBuilder::new(
+        app,
+        QUICK_COMPOSE_LABEL,
+        WebviewUrl::App("index.html?compose=true&quick=true".into()),
+    )
+    .title("Quick Compose")
+    .inner_size(WINDOW_WIDTH, WINDOW_HEIGHT)
+    .decorations(false)
+    .resizable(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .disable_drag_drop_handler();
+
+    builder = match position {
+        Some((x, y)) => builder.position(x, y),
+        None => builder.center(),
+    };
+
+    builder
+        .build()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open quick compose window: {e}"))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn open_quick_compose(app: AppHandle) -> Result<(), String> {
+    open(&app)
+}
+
+/// Register the quick-compose global shortcut from the saved setting.
+pub fn register_saved_shortcut(app: &AppHandle) {
+    let shortcut = app.state::<SettingsState>().get().quick_compose_shortcut;
+    if let Some(shortcut) = shortcut {
+        if let Err(e) = register_shortcut(app, &shortcut) {
+            log::warn!("Failed to register quick compose shortcut {shortcut}: {e}");
+        }
+    }
+}
+
+fn register_shortcut(app: &AppHandle, shortcut: &str) -> Result<(), String> {
+    app.global_shortcut()
+        .on_shortcut(shortcut, |app, _shortcut, event| {
+            if event.state == ShortcutState::Pressed {
+                if let Err(e) = open(app) {
+                    log::warn!("{e}");
+                }
+            }
+        })
+        .map_err(|e| e.to_string())
+}
+
+/// The quick-compose global shortcut, or `None` when it's disabled.
+#[tauri::command]
+#[specta::specta]
+pub fn get_quick_compose_shortcut(settings: tauri::State<'_, SettingsState>) -> Option<String> {
+    settings.get().quick_compose_shortcut
+}
+
+/// Change (or clear, with `None`) the quick-compose global shortcut.
+///
+/// The new shortcut is registered before the old one is released, so one
+/// that's invalid or taken by another app leaves the old one working.
+#[tauri::command]
+#[specta::specta]
+pub fn set_quick_compose_shortcut(
+    app: AppHandle,
+    settings: tauri::State<'_, SettingsState>,
+    shortcut: Option<String>,
+) -> Result<(), String> {
+    let old = settings.get().quick_compose_shortcut;
+    if old == shortcut {
+        return Ok(());
+    }
+    if let Some(new) = &shortcut {
+        register_shortcut(&app, new)
+            .map_err(|e| format!("Failed to register shortcut {new}: {e}"))?;
+    }
+    if let Some(old) = old {
+        if let Err(e) = app.global_shortcut().unregister(old.as_str()) {
+            log::debug!("Quick compose shortcut {old} was not registered: {e}");
+        }
+    }
+    settings.update(|s| s.quick_compose_shortcut = shortcut)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCREEN: Rect = Rect {
+        x: 0.0,
+        y: 0.0,
+        width: 1920.0,
+        height: 1040.0,
+    };
+
+    #[test]
+    fn test_place_near_point_below_right() {
+        assert_eq!(
+            place_near_point((100.0, 100.0), (480.0, 360.0), SCREEN, 10.0),
+            (110.0, 110.0)
+        );
+    }
+
+    #[test]
+    fn test_place_near_point_flips_at_bottom_right_tray() {
+        // Cursor on a Windows taskbar tray icon
+        let (x, y) = place_near_point((1850.0, 1030.0), (480.0, 360.0), SCREEN, 10.0);
+        assert_eq!((x, y), (1360.0, 660.0));
+    }
+
+    #[test]
+    fn test_place_near_point_clamps_to_bounds() {
+        let (x, y) = place_near_point((5.0, 5.0), (2000.0, 360.0), SCREEN, 10.0);
+        assert_eq!((x, y), (0.0, 15.0));
+    }
+}
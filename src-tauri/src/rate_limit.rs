@@ -0,0 +1,226 @@
+//! Per-account token bucket rate limiting for IMAP commands and SMTP sends.
+//!
+//! Large resyncs open connections and issue fetches back to back, which can
+//! trip provider quotas (Gmail's IMAP bandwidth and command limits, Outlook's
+//! 30 messages a minute). Every IMAP connection and SMTP send takes a token
+//! from its account's bucket first and waits when the bucket is empty.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::imap::types::ImapConfig;
+use crate::smtp::types::SmtpConfig;
+
+/// Limits for a provider, matched by server host suffix.
+struct ProviderPreset {
+    host_suffix: &'static str,
+    imap_per_minute: u32,
+    smtp_per_minute: u32,
+}
+
+const PRESETS: &[ProviderPreset] = &[
+    ProviderPreset {
+        host_suffix: "gmail.com",
+        imap_per_minute: 120,
+        smtp_per_minute: 20,
+    },
+    ProviderPreset {
+        host_suffix: "googlemail.com",
+        imap_per_minute: 120,
+        smtp_per_minute: 20,
+    },
+    ProviderPreset {
+        host_suffix: "outlook.com",
+        imap_per_minute: 120,
+        smtp_per_minute: 30,
+    },
+    ProviderPreset {
+        host_suffix: "office365.com",
+        imap_per_minute: 120,
+        smtp_per_minute: 30,
+    },
+    ProviderPreset {
+        host_suffix: "yahoo.com",
+        imap_per_minute: 60,
+        smtp_per_minute: 10,
+    },
+    ProviderPreset {
+        host_suffix: "me.com",
+        imap_per_minute: 120,
+        smtp_per_minute: 20,
+    },
+];
+
+/// Limits for servers without a preset.
+const DEFAULT_IMAP_PER_MINUTE: u32 = 300;
+const DEFAULT_SMTP_PER_MINUTE: u32 = 60;
+
+/// Share of a minute's allowance that may be spent in one burst.
+const BURST_DIVISOR: u32 = 4;
+
+fn preset_for(host: &str) -> Option<&'static ProviderPreset> {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    PRESETS.iter().find(|p| {
+        host == p.host_suffix
+            || host
+                .strip_suffix(p.host_suffix)
+                .is_some_and(|rest| rest.ends_with('.'))
+    })
+}
+
+/// IMAP commands per minute for this config: the explicit override, else the
+/// provider preset. `0` means unlimited.
+fn imap_limit(config: &ImapConfig) -> u32 {
+    config.rate_limit_per_minute.unwrap_or_else(|| {
+        preset_for(&config.host).map_or(DEFAULT_IMAP_PER_MINUTE, |p| p.imap_per_minute)
+    })
+}
+
+/// SMTP sends per minute for this config. `0` means unlimited.
+fn smtp_limit(config: &SmtpConfig) -> u32 {
+    config.rate_limit_per_minute.unwrap_or_else(|| {
+        preset_for(&config.host).map_or(DEFAULT_SMTP_PER_MINUTE, |p| p.smtp_per_minute)
+    })
+}
+
+struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    refill_per_sec: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(per_minute: u32, now: Instant) -> Self {
+        let capacity = f64::from((per_minute / BURST_DIVISOR).max(1));
+        Self {
+            capacity,
+            tokens: capacity,
+            refill_per_sec: f64::from(per_minute) / 60.0,
+            last_refill: now,
+        }
+    }
+
+    /// Take a token, or report how long until one is available.
+    fn try_take(&mut self, now: Instant) -> Result<(), Duration> {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - self.tokens) / self.refill_per_sec,
+            ))
+        }
+    }
+}
+
+fn buckets() -> &'static Mutex<HashMap<String, TokenBucket>> {
+    static BUCKETS: OnceLock<Mutex<HashMap<String, TokenBucket>>> = OnceLock::new();
+    BUCKETS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+async fn acquire(key: String, per_minute: u32) {
+    if per_minute == 0 {
+        return;
+    }
+    loop {
+        let wait = {
+            let mut buckets = buckets().lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            let bucket = buckets
+                .entry(key.clone())
+                .or_insert_with(|| TokenBucket::new(per_minute, now));
+            // Pick up a changed limit without losing the tokens already spent
+            let refill_per_sec = f64::from(per_minute) / 60.0;
+            if bucket.refill_per_sec != refill_per_sec {
+                *bucket = TokenBucket {
+                    tokens: bucket.tokens,
+                    ..TokenBucket::new(per_minute, now)
+                };
+            }
+            match bucket.try_take(now) {
+                Ok(()) => return,
+                Err(wait) => wait,
+            }
+        };
+        log::debug!(
+            "Rate limit reached for {key}, waiting {}ms",
+            wait.as_millis()
+        );
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// Wait for a token before issuing an IMAP command on this account.
+pub async fn acquire_imap(config: &ImapConfig) {
+    let key = format!(
+        "imap:{}@{}",
+        config.username,
+        config.host.to_ascii_lowercase()
+    );
+    acquire(key, imap_limit(config)).await;
+}
+
+/// Wait for a token before sending a message through this account.
+pub async fn acquire_smtp(config: &SmtpConfig) {
+    let key = format!(
+        "smtp:{}@{}",
+        config.username,
+        config.host.to_ascii_lowercase()
+    );
+    acquire(key, smtp_limit(config)).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_matches_host_suffix() {
+        assert_eq!(
+            preset_for("imap.gmail.com").map(|p| p.smtp_per_minute),
+            Some(20)
+        );
+        assert_eq!(
+            preset_for("SMTP.Office365.com.").map(|p| p.smtp_per_minute),
+            Some(30)
+        );
+        assert!(preset_for("notgmail.com").is_none());
+        assert!(preset_for("mail.example.org").is_none());
+    }
+
+    #[test]
+    fn test_bucket_allows_burst_then_waits() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(60, start);
+        for _ in 0..15 {
+            assert!(bucket.try_take(start).is_ok());
+        }
+        let wait = bucket.try_take(start).unwrap_err();
+        assert!((wait.as_secs_f64() - 1.0).abs() < 1e-6);
+
+        // One token a second comes back
+        assert!(bucket.try_take(start + Duration::from_secs(1)).is_ok());
+        assert!(bucket.try_take(start + Duration::from_secs(1)).is_err());
+    }
+
+    #[test]
+    fn test_bucket_refill_is_capped() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(8, start);
+        assert!(bucket.try_take(start).is_ok());
+        assert!(bucket.try_take(start).is_ok());
+        assert!(bucket.try_take(start).is_err());
+
+        let later = start + Duration::from_secs(3600);
+        assert!(bucket.try_take(later).is_ok());
+        assert!(bucket.try_take(later).is_ok());
+        assert!(bucket.try_take(later).is_err());
+    }
+}
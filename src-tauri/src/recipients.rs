@@ -0,0 +1,370 @@
+//! Recipient address checks for the composer.
+//!
+//! Each address is checked for syntax, its domain is looked up in DNS to see
+//! whether it can receive mail at all (MX records, or an A/AAAA record as the
+//! implicit MX), and likely typos of well-known mail domains get a suggested
+//! correction. Nothing is sent to the recipient's server.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use hickory_resolver::config::{ResolverConfig, ResolverOpts};
+use hickory_resolver::error::ResolveErrorKind;
+use hickory_resolver::TokioAsyncResolver;
+use serde::Serialize;
+use specta::Type;
+
+/// Per-domain DNS timeout; slow lookups report `unknown` rather than block sending.
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Mail domains common enough that a near miss is almost certainly a typo.
+const KNOWN_DOMAINS: &[&str] = &[
+    "gmail.com",
+    "googlemail.com",
+    "yahoo.com",
+    "yahoo.co.jp",
+    "yahoo.co.uk",
+    "ymail.com",
+    "hotmail.com",
+    "hotmail.co.uk",
+    "outlook.com",
+    "live.com",
+    "msn.com",
+    "icloud.com",
+    "me.com",
+    "mac.com",
+    "mail.com",
+    "email.com",
+    "aol.com",
+    "protonmail.com",
+    "proton.me",
+    "gmx.com",
+    "gmx.de",
+    "gmx.net",
+    "web.de",
+    "yandex.ru",
+    "mail.ru",
+    "qq.com",
+    "163.com",
+    "126.com",
+    "naver.com",
+    "comcast.net",
+    "verizon.net",
+    "att.net",
+    "sbcglobal.net",
+    "fastmail.com",
+    "zoho.com",
+    "tutanota.com",
+    "hey.com",
+    "docomo.ne.jp",
+    "ezweb.ne.jp",
+    "softbank.ne.jp",
+];
+
+/// Misspelled top-level domains and what they were meant to be.
+const TLD_TYPOS: &[(&str, &str)] = &[
+    ("con", "com"),
+    ("cmo", "com"),
+    ("ocm", "com"),
+    ("vom", "com"),
+    ("xom", "com"),
+    ("comm", "com"),
+    ("coom", "com"),
+    ("cpm", "com"),
+    ("om", "com"),
+    ("nte", "net"),
+    ("ner", "net"),
+    ("nett", "net"),
+    ("ogr", "org"),
+    ("rog", "org"),
+    ("orgg", "org"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum DomainStatus {
+    /// The domain has MX records (or an address record acting as one).
+    AcceptsMail,
+    /// The domain doesn't exist, or publishes a null MX (RFC 7505).
+    NoMailServer,
+    /// DNS couldn't be reached or timed out.
+    Unknown,
+}
+
+#[derive(Debug, Serialize, Type)]
+pub struct RecipientCheck {
+    pub address: String,
+    pub valid_syntax: bool,
+    /// Why the address isn't valid, when `valid_syntax` is false.
+    pub syntax_error: Option<String>,
+    /// `None` when the syntax check already failed.
+    pub domain_status: Option<DomainStatus>,
+    /// Corrected address for a likely domain typo (gamil.com → gmail.com).
+    pub suggestion: Option<String>,
+}
+
+/// Check an `addr-spec` (RFC 5321 mailbox) and split it into local part
+/// and domain.
+fn parse_address(address: &str) -> Result<(&str, &str), String> {
+    let (local, domain) = address
+        .rsplit_once('@')
+        .ok_or_else(|| "Missing @".to_string())?;
+
+    if local.is_empty() {
+        return Err("Nothing before the @".to_string());
+    }
+    if local.len() > 64 {
+        return Err("The part before the @ is too long".to_string());
+    }
+    let quoted = local.len() >= 2 && local.starts_with('"') && local.ends_with('"');
+    if !quoted {
+        let atext = |c: char| c.is_alphanumeric() || "!#$%&'*+-/=?^_`{|}~".contains(c);
+        if local.starts_with('.') || local.ends_with('.') || local.contains("..") {
+            return Err("Misplaced dot before the @".to_string());
+        }
+        if let Some(c) = local.chars().find(|&c| c != '.' && !atext(c)) {
+            return Err(format!("'{c}' isn't allowed before the @"));
+        }
+    }
+
+    if domain.is_empty() {
+        return Err("Nothing after the @".to_string());
+    }
+    if domain.starts_with('[') && domain.ends_with(']') {
+        return Ok((local, domain));
+    }
+    if domain.len() > 253 {
+        return Err("Domain is too long".to_string());
+    }
+    let labels: Vec<&str> = domain.split('.').collect();
+    if labels.len() < 2 {
+        return Err(format!(
+            "{domain} is missing a top-level domain (like .com)"
+        ));
+    }
+    for label in &labels {
+        if label.is_empty() {
+            return Err("Misplaced dot in the domain".to_string());
+        }
+        if label.len() > 63 {
+            return Err("Domain label is too long".to_string());
+        }
+        if label.starts_with('-') || label.ends_with('-') {
+            return Err("Domain labels can't start or end with a hyphen".to_string());
+        }
+        if let Some(c) = label.chars().find(|&c| !c.is_alphanumeric() && c != '-') {
+            return Err(format!("'{c}' isn't allowed in the domain"));
+        }
+    }
+    Ok((local, domain))
+}
+
+/// Edit distance counting a swap of adjacent characters as one edit
+/// (optimal string alignment), so "gamil" is one edit from "gmail".
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// The domain a likely typo was meant to be, if any.
+fn suggest_domain(domain: &str) -> Option<String> {
+    let domain = domain.to_ascii_lowercase();
+    if KNOWN_DOMAINS.contains(&domain.as_str()) {
+        return None;
+    }
+
+    // Fix the TLD first so "gmial.con" still finds gmail.com
+    let fixed_tld = match domain.rsplit_once('.') {
+        Some((name, tld)) => match TLD_TYPOS.iter().find(|(typo, _)| *typo == tld) {
+            Some((_, correct)) => format!("{name}.{correct}"),
+            None => domain.clone(),
+        },
+        None => domain.clone(),
+    };
+
+    // Allow one edit for short domains, two for longer ones
+    let max_distance = if fixed_tld.len() <= 8 { 1 } else { 2 };
+    let closest = KNOWN_DOMAINS
+        .iter()
+        .map(|known| (edit_distance(&fixed_tld, known), *known))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance);
+
+    match closest {
+        Some((_, known)) => Some(known.to_string()),
+        None if fixed_tld != domain => Some(fixed_tld),
+        None => None,
+    }
+}
+
+fn build_resolver() -> TokioAsyncResolver {
+    TokioAsyncResolver::tokio_from_system_conf().unwrap_or_else(|e| {
+        log::warn!("Couldn't read system DNS config, using defaults: {e}");
+        let mut opts = ResolverOpts::default();
+        opts.timeout = LOOKUP_TIMEOUT;
+        TokioAsyncResolver::tokio(ResolverConfig::default(), opts)
+    })
+}
+
+/// Whether `domain` can receive mail, per its MX records or, without any,
+/// an address record (RFC 5321 §5.1).
+async fn lookup_domain(resolver: &TokioAsyncResolver, domain: &str) -> DomainStatus {
+    // Address literals ([192.0.2.1]) have nothing to look up
+    if domain.starts_with('[') {
+        return DomainStatus::AcceptsMail;
+    }
+    let fqdn = format!("{}.", domain.trim_end_matches('.'));
+
+    let mx = tokio::time::timeout(LOOKUP_TIMEOUT, resolver.mx_lookup(fqdn.as_str())).await;
+    match mx {
+        Ok(Ok(records)) => {
+            // A single "." exchange is a null MX: the domain takes no mail
+            return if records.iter().all(|mx| mx.exchange().is_root()) {
+                DomainStatus::NoMailServer
+            } else {
+                DomainStatus::AcceptsMail
+            };
+        }
+        Ok(Err(e)) if matches!(e.kind(), ResolveErrorKind::NoRecordsFound { .. }) => {}
+        Ok(Err(e)) => {
+            log::debug!("MX lookup for {domain} failed: {e}");
+            return DomainStatus::Unknown;
+        }
+        Err(_) => return DomainStatus::Unknown,
+    }
+
+    match tokio::time::timeout(LOOKUP_TIMEOUT, resolver.lookup_ip(fqdn.as_str())).await {
+        Ok(Ok(_)) => DomainStatus::AcceptsMail,
+        Ok(Err(e)) if matches!(e.kind(), ResolveErrorKind::NoRecordsFound { .. }) => {
+            DomainStatus::NoMailServer
+        }
+        _ => DomainStatus::Unknown,
+    }
+}
+
+/// Check recipient addresses before sending. Results are in input order;
+/// each domain is looked up once.
+#[tauri::command]
+#[specta::specta]
+pub async fn validate_recipients(addresses: Vec<String>) -> Result<Vec<RecipientCheck>, String> {
+    let parsed: Vec<_> = addresses
+        .into_iter()
+        .map(|address| {
+            let address = address.trim().to_string();
+            let parts = parse_address(&address).map(|(l, d)| (l.to_string(), d.to_string()));
+            (address, parts)
+        })
+        .collect();
+
+    let mut domains: Vec<String> = parsed
+        .iter()
+        .filter_map(|(_, parts)| parts.as_ref().ok().map(|(_, d)| d.to_ascii_lowercase()))
+        .collect();
+    domains.sort();
+    domains.dedup();
+
+    let resolver = build_resolver();
+    let statuses: HashMap<String, DomainStatus> = futures::future::join_all(
+        domains
+            .iter()
+            .map(|domain| lookup_domain(&resolver, domain)),
+    )
+    .await
+    .into_iter()
+    .zip(domains.iter())
+    .map(|(status, domain)| (domain.clone(), status))
+    .collect();
+
+    Ok(parsed
+        .into_iter()
+        .map(|(address, parts)| match parts {
+            Ok((local, domain)) => RecipientCheck {
+                suggestion: suggest_domain(&domain).map(|d| format!("{local}@{d}")),
+                domain_status: statuses.get(&domain.to_ascii_lowercase()).copied(),
+                address,
+                valid_syntax: true,
+                syntax_error: None,
+            },
+            Err(e) => RecipientCheck {
+                address,
+                valid_syntax: false,
+                syntax_error: Some(e),
+                domain_status: None,
+                suggestion: None,
+            },
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_address() {
+        assert_eq!(
+            parse_address("jo.e+tag@example.com"),
+            Ok(("jo.e+tag", "example.com"))
+        );
+        assert_eq!(
+            parse_address("\"joe smith\"@example.com"),
+            Ok(("\"joe smith\"", "example.com"))
+        );
+        assert_eq!(parse_address("joe@[192.0.2.1]"), Ok(("joe", "[192.0.2.1]")));
+        assert!(parse_address("joe.example.com").is_err());
+        assert!(parse_address("@example.com").is_err());
+        assert!(parse_address("joe@").is_err());
+        assert!(parse_address("joe@localhost").is_err());
+        assert!(parse_address("joe..smith@example.com").is_err());
+        assert!(parse_address("joe smith@example.com").is_err());
+        assert!(parse_address("joe@exa_mple.com").is_err());
+        assert!(parse_address("joe@example..com").is_err());
+        assert!(parse_address("joe@-example.com").is_err());
+    }
+
+    #[test]
+    fn test_suggest_domain() {
+        assert_eq!(suggest_domain("gamil.com").as_deref(), Some("gmail.com"));
+        assert_eq!(suggest_domain("gmial.con").as_deref(), Some("gmail.com"));
+        assert_eq!(
+            suggest_domain("hotmial.com").as_deref(),
+            Some("hotmail.com")
+        );
+        assert_eq!(suggest_domain("outlok.com").as_deref(), Some("outlook.com"));
+        assert_eq!(suggest_domain("Yahooo.com").as_deref(), Some("yahoo.com"));
+        assert_eq!(
+            suggest_domain("example.con").as_deref(),
+            Some("example.com")
+        );
+        assert_eq!(suggest_domain("gmail.com"), None);
+        assert_eq!(suggest_domain("example.com"), None);
+        assert_eq!(suggest_domain("acme.co"), None);
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("gamil", "gmail"), 1);
+        assert_eq!(edit_distance("gmail", "gmail"), 0);
+        assert_eq!(edit_distance("gmal", "gmail"), 1);
+        assert_eq!(edit_distance("abc", ""), 3);
+    }
+}
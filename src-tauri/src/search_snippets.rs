@@ -0,0 +1,229 @@
+//! Highlighted snippets for search results.
+//!
+//! Bodies are read straight from the app database and cut down here, so the
+//! results list gets a short window of text around the first match instead
+//! of every matching message's full body.
+
+use std::collections::HashMap;
+
+use tauri::State;
+use tauri_plugin_sql::{DbInstances, DbPool};
+
+use crate::profiles::Profiles;
+
+/// Marks the start of a highlighted term in a snippet.
+pub const HIGHLIGHT_START: char = '\u{2}';
+/// Marks the end of a highlighted term in a snippet.
+pub const HIGHLIGHT_END: char = '\u{3}';
+
+/// Characters of context returned per snippet.
+const SNIPPET_CHARS: usize = 160;
+
+/// Words to highlight from a search query: free text only, lowercased, with
+/// operators (`from:x`), boolean keywords and FTS punctuation dropped.
+fn query_terms(query: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for token in query.split_whitespace() {
+        if token.contains(':') || matches!(token, "AND" | "OR" | "NOT") {
+            continue;
+        }
+        let term = token
+            .trim_matches(|c: char| !c.is_alphanumeric())
+            .to_lowercase();
+        if !term.is_empty() && !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+    // Longest first, so "reports" wins over "report" at the same position
+    terms.sort_by_key(|t| std::cmp::Reverse(t.chars().count()));
+    terms
+}
+
+/// Fold a char for matching while keeping one char per input char, so match
+/// positions index the original text.
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// Cut a window of `text` around the first word starting with one of `terms`
+/// and wrap every matched term in it with the highlight markers. `None` when
+/// nothing matches.
+fn highlight(text: &str, terms: &[String], max_chars: usize) -> Option<String> {
+    // Collapse whitespace and drop any stray marker characters from the body
+    let chars: Vec<char> = text
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .filter(|&c| c != HIGHLIGHT_START && c != HIGHLIGHT_END)
+        .collect();
+    let folded: Vec<char> = chars.iter().map(|&c| fold(c)).collect();
+    let terms: Vec<Vec<char>> = terms
+        .iter()
+        .map(|t| t.chars().map(fold).collect())
+        .collect();
+
+    let mut matches: Vec<(usize, usize)> = Vec::new();
+    let mut i = 0;
+    while i < folded.len() {
+        let word_start = i == 0 || !folded[i - 1].is_alphanumeric();
+        let hit = if word_start {
+            terms
+                .iter()
+                .find(|t| !t.is_empty() && folded[i..].starts_with(t))
+        } else {
+            None
+        };
+        match hit {
+            Some(term) => {
+                matches.push((i, i + term.len()));
+                i += term.len();
+            }
+            None => i += 1,
+        }
+    }
+    let &(first, first_end) = matches.first()?;
+
+    // Center the window on the first hit, snapping to word boundaries
+    let mut start = first.saturating_sub(max_chars / 3);
+    if start > 0 {
+        if let Some(space) = chars[start..first].iter().position(|&c| c == ' ') {
+            start += space + 1;
+        }
+    }
+    let mut end = (start + max_chars).min(chars.len()).max(first_end);
+    if end < chars.len() {
+        if let Some(space) = chars[first_end..end].iter().rposition(|&c| c == ' ') {
+            end = first_end + space;
+        }
+    }
+
+    let mut out = String::new();
+    if start > 0 {
+        out.push('…');
+    }
+    let mut pending = matches.iter().filter(|&&(s, _)| s < end).peekable();
+    let mut open_until = None;
+    for (pos, &c) in chars.iter().enumerate().take(end).skip(start) {
+        if let Some(&&(s, e)) = pending.peek() {
+            if s == pos {
+                out.push(HIGHLIGHT_START);
+                open_until = Some(e.min(end));
+                pending.next();
+            }
+        }
+        out.push(c);
+        if open_until == Some(pos + 1) {
+            out.push(HIGHLIGHT_END);
+            open_until = None;
+        }
+    }
+    if end < chars.len() {
+        out.push('…');
+    }
+    Some(out)
+}
+
+/// Highlighted snippets for the given messages of one account, keyed by
+/// message id. Messages whose body has no match for the query are left out
+/// and keep their stored snippet.
+#[tauri::command]
+#[specta::specta]
+pub async fn search_snippets(
+    db: State<'_, DbInstances>,
+    profiles: State<'_, Profiles>,
+    account_id: String,
+    message_ids: Vec<String>,
+    query: String,
+) -> Result<HashMap<String, String>, String> {
+    let terms = query_terms(&query);
+    if terms.is_empty() || message_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let instances = db.0.read().await;
+    let DbPool::Sqlite(pool) = instances
+        .get(&profiles.db_url())
+        .ok_or_else(|| "Database is not loaded".to_string())?;
+
+    let sql = format!(
+        "SELECT id, COALESCE(body_text, snippet) FROM messages WHERE account_id = ? AND id IN ({})",
+        vec!["?"; message_ids.len()].join(", ")
+    );
+    let mut select = sqlx::query_as::<_, (String, Option<String>)>(&sql).bind(&account_id);
+    for id in &message_ids {
+        select = select.bind(id);
+    }
+    let rows = select
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to load message bodies: {e}"))?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|(id, body)| {
+            highlight(body.as_deref()?, &terms, SNIPPET_CHARS).map(|snippet| (id, snippet))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn marked(s: &str) -> String {
+        s.replace(HIGHLIGHT_START, "[").replace(HIGHLIGHT_END, "]")
+    }
+
+    #[test]
+    fn test_query_terms() {
+        assert_eq!(
+            query_terms("from:alice \"Quarterly report\" OR budget* is:unread"),
+            vec!["quarterly", "report", "budget"]
+        );
+        assert!(query_terms("has:attachment").is_empty());
+    }
+
+    #[test]
+    fn test_highlight_marks_every_hit_case_insensitively() {
+        let terms = query_terms("invoice");
+        let snippet = highlight(
+            "Your Invoice is attached.\nPay the invoice soon.",
+            &terms,
+            160,
+        );
+        assert_eq!(
+            marked(&snippet.unwrap()),
+            "Your [Invoice] is attached. Pay the [invoice] soon."
+        );
+    }
+
+    #[test]
+    fn test_highlight_matches_word_starts_only() {
+        let terms = query_terms("port");
+        assert_eq!(highlight("the report", &terms, 160), None);
+        assert_eq!(
+            marked(&highlight("portable", &terms, 160).unwrap()),
+            "[port]able"
+        );
+    }
+
+    #[test]
+    fn test_highlight_centers_on_first_hit() {
+        let text = format!("{} needle {}", "lead ".repeat(40), "tail ".repeat(40));
+        let snippet = marked(&highlight(&text, &query_terms("needle"), 40).unwrap());
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+        assert!(snippet.contains("[needle]"));
+        let before = snippet.find("[needle]").unwrap();
+        assert!(before > 5 && before < 20, "{snippet}");
+        assert!(snippet.chars().count() <= 44);
+    }
+
+    #[test]
+    fn test_highlight_no_match() {
+        assert_eq!(
+            highlight("nothing here", &query_terms("missing"), 160),
+            None
+        );
+    }
+}
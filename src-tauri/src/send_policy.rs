@@ -0,0 +1,360 @@
+//! Checks run on an outgoing message before it's sent.
+//!
+//! The composer passes the raw message it's about to send to
+//! `check_send_policy` and asks the user to confirm when any warning comes
+//! back: recipients outside the organization's domains, a long Cc list, an
+//! empty subject, or a reply to a conversation that went quiet long ago.
+//! Which checks run is part of the backend settings, so the same policy
+//! applies whichever window sends.
+
+use mail_parser::{HeaderValue, MessageParser};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use sqlx::SqlitePool;
+use tauri::State;
+use tauri_plugin_sql::{DbInstances, DbPool};
+
+use crate::accounts::require_trusted_window;
+use crate::identity::{normalize_address, Identities};
+use crate::profiles::Profiles;
+use crate::settings::SettingsState;
+use crate::smtp::client::decode_base64url;
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct SendPolicy {
+    /// Recipients outside these domains and their subdomains are external.
+    /// Empty turns the external recipient check off.
+    pub internal_domains: Vec<String>,
+    /// Warn when Cc lists more addresses than this.
+    pub max_cc: Option<u32>,
+    pub warn_empty_subject: bool,
+    /// Warn when replying to a conversation whose last message is older
+    /// than this many days.
+    pub old_thread_days: Option<u32>,
+}
+
+impl Default for SendPolicy {
+    fn default() -> Self {
+        Self {
+            internal_domains: Vec::new(),
+            max_cc: None,
+            warn_empty_subject: true,
+            old_thread_days: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SendWarningKind {
+    ExternalRecipients,
+    LargeCc,
+    EmptySubject,
+    OldThread,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct SendWarning {
+    pub kind: SendWarningKind,
+    pub message: String,
+    /// The addresses the warning is about (the external ones), if any.
+    pub addresses: Vec<String>,
+}
+
+/// The parts of an outgoing message the checks look at.
+#[derive(Debug, Default, PartialEq)]
+struct Outgoing {
+    recipients: Vec<String>,
+    cc_count: usize,
+    subject: String,
+    in_reply_to: Option<String>,
+}
+
+fn parse_outgoing(raw: &[u8]) -> Result<Outgoing, String> {
+    let message = MessageParser::default()
+        .parse(raw)
+        .ok_or_else(|| "Could not parse the message".to_string())?;
+
+    let addresses = |header: Option<&mail_parser::Address>| -> Vec<String> {
+        header
+            .map(|a| {
+                a.iter()
+                    .filter_map(|addr| addr.address.as_deref().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let cc = addresses(message.cc());
+    let mut recipients = addresses(message.to());
+    recipients.extend(cc.iter().cloned());
+    recipients.extend(addresses(message.bcc()));
+
+    let in_reply_to = match message.in_reply_to() {
+        HeaderValue::Text(id) => Some(id.to_string()),
+        HeaderValue::TextList(ids) => ids.first().map(|id| id.to_string()),
+        _ => None,
+    };
+
+    Ok(Outgoing {
+        recipients,
+        cc_count: cc.len(),
+        subject: message.subject().unwrap_or("").to_string(),
+        in_reply_to,
+    })
+}
+
+/// Whether `address` is at one of `domains` or a subdomain of one.
+fn is_internal(address: &str, domains: &[String]) -> bool {
+    let Some((_, domain)) = address.rsplit_once('@') else {
+        return false;
+    };
+    let domain = domain.trim_end_matches('>').to_ascii_lowercase();
+    domains.iter().any(|internal| {
+        let internal = internal.trim().trim_start_matches('@').to_ascii_lowercase();
+        !internal.is_empty()
+            && (domain == internal
+                || domain
+                    .strip_suffix(&internal)
+                    .is_some_and(|sub| sub.ends_with('.')))
+    })
+}
+
+fn plural(n: usize, one: &str, many: &str) -> String {
+    format!("{n} {}", if n == 1 { one } else { many })
+}
+
+/// The warnings for `outgoing` under `policy`. `thread_last_date` is when
+/// the replied-to conversation last had a message (ms), if known;
+/// `is_mine` says whether an address is the user's own.
+fn evaluate(
+    policy: &SendPolicy,
+    outgoing: &Outgoing,
+    thread_last_date: Option<i64>,
+    now_ms: i64,
+    is_mine: impl Fn(&str) -> bool,
+) -> Vec<SendWarning> {
+    let mut warnings = Vec::new();
+
+    if !policy.internal_domains.is_empty() {
+        let mut external: Vec<String> = Vec::new();
+        for address in &outgoing.recipients {
+            let Some(normalized) = normalize_address(address) else {
+                continue;
+            };
+            if !is_internal(&normalized, &policy.internal_domains)
+                && !is_mine(&normalized)
+                && !external.contains(&normalized)
+            {
+                external.push(normalized);
+            }
+        }
+        if !external.is_empty() {
+            warnings.push(SendWarning {
+                kind: SendWarningKind::ExternalRecipients,
+                message: format!(
+                    "{} outside {}",
+                    plural(external.len(), "recipient is", "recipients are"),
+                    policy.internal_domains.join(", ")
+                ),
+                addresses: external,
+            });
+        }
+    }
+
+    if let Some(max_cc) = policy.max_cc {
+        if outgoing.cc_count > max_cc as usize {
+            warnings.push(SendWarning {
+                kind: SendWarningKind::LargeCc,
+                message: format!(
+                    "Cc lists {} (more than {max_cc})",
+                    plural(outgoing.cc_count, "person", "people")
+                ),
+                addresses: Vec::new(),
+            });
+        }
+    }
+
+    if policy.warn_empty_subject && outgoing.subject.trim().is_empty() {
+        warnings.push(SendWarning {
+            kind: SendWarningKind::EmptySubject,
+            message: "The subject is empty".to_string(),
+            addresses: Vec::new(),
+        });
+    }
+
+    if let (Some(days), Some(last)) = (policy.old_thread_days, thread_last_date) {
+        let age_days = (now_ms - last) / DAY_MS;
+        if age_days > i64::from(days) {
+            warnings.push(SendWarning {
+                kind: SendWarningKind::OldThread,
+                message: format!(
+                    "This conversation has been quiet for {}",
+                    plural(age_days as usize, "day", "days")
+                ),
+                addresses: Vec::new(),
+            });
+        }
+    }
+
+    warnings
+}
+
+/// When the conversation containing the message `in_reply_to` (a
+/// Message-ID, or the local message id the composer uses) last had a
+/// message, in ms.
+async fn thread_last_date(
+    pool: &SqlitePool,
+    account_id: &str,
+    in_reply_to: &str,
+) -> Result<Option<i64>, String> {
+    let bare = in_reply_to
+        .trim()
+        .trim_start_matches('<')
+        .trim_end_matches('>');
+    sqlx::query_scalar::<_, Option<i64>>(
+        "SELECT MAX(m.date) FROM messages r
+         JOIN messages m ON m.account_id = r.account_id AND m.thread_id = r.thread_id
+         WHERE r.account_id = ?1 AND (r.message_id_header IN (?2, ?3) OR r.id = ?2)",
+    )
+    .bind(account_id)
+    .bind(bare)
+    .bind(format!("<{bare}>"))
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Failed to look up the replied-to conversation: {e}"))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_send_policy(settings: State<'_, SettingsState>) -> SendPolicy {
+    settings.get().send_policy
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_send_policy(
+    window: tauri::Window,
+    settings: State<'_, SettingsState>,
+    policy: SendPolicy,
+) -> Result<(), String> {
+    require_trusted_window(&window, "set_send_policy")?;
+    settings.update(|s| s.send_policy = policy)
+}
+
+/// Check a message (base64url, as `smtp_send_email` takes it) against the
+/// send policy before sending it. An empty list means it can go.
+#[tauri::command]
+#[specta::specta]
+pub async fn check_send_policy(
+    account_id: String,
+    raw_email: String,
+    db: State<'_, DbInstances>,
+    profiles: State<'_, Profiles>,
+    settings: State<'_, SettingsState>,
+    identities: State<'_, Identities>,
+) -> Result<Vec<SendWarning>, String> {
+    let policy = settings.get().send_policy;
+    let outgoing = parse_outgoing(&decode_base64url(&raw_email)?)?;
+
+    let mut last_date = None;
+    if let (Some(_), Some(in_reply_to)) = (policy.old_thread_days, &outgoing.in_reply_to) {
+        let instances = db.0.read().await;
+        let DbPool::Sqlite(pool) = instances
+            .get(&profiles.db_url())
+            .ok_or_else(|| "Database is not loaded".to_string())?;
+        last_date = thread_last_date(pool, &account_id, in_reply_to).await?;
+    }
+
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    Ok(evaluate(&policy, &outgoing, last_date, now_ms, |a| {
+        identities.is_mine(Some(&account_id), a)
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RAW: &str = "From: me@corp.example\r\n\
+        To: Ann <ann@corp.example>, bob@partner.example\r\n\
+        Cc: carol@eu.corp.example, Bob <BOB@partner.example>, me+cc@corp.example\r\n\
+        Bcc: dave@other.example\r\n\
+        Subject: \r\n\
+        In-Reply-To: <thread-1@corp.example>\r\n\
+        \r\n\
+        Hi\r\n";
+
+    #[test]
+    fn test_parse_outgoing() {
+        let outgoing = parse_outgoing(RAW.as_bytes()).unwrap();
+        assert_eq!(
+            outgoing.recipients,
+            [
+                "ann@corp.example",
+                "bob@partner.example",
+                "carol@eu.corp.example",
+                "BOB@partner.example",
+                "me+cc@corp.example",
+                "dave@other.example"
+            ]
+        );
+        assert_eq!(outgoing.cc_count, 3);
+        assert_eq!(
+            outgoing.in_reply_to.as_deref(),
+            Some("thread-1@corp.example")
+        );
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let outgoing = parse_outgoing(RAW.as_bytes()).unwrap();
+        let now = 400 * DAY_MS;
+        let policy = SendPolicy {
+            internal_domains: vec!["corp.example".to_string()],
+            max_cc: Some(2),
+            warn_empty_subject: true,
+            old_thread_days: Some(90),
+        };
+
+        let warnings = evaluate(&policy, &outgoing, Some(100 * DAY_MS), now, |a| {
+            a == "dave@other.example"
+        });
+        let kinds: Vec<SendWarningKind> = warnings.iter().map(|w| w.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                SendWarningKind::ExternalRecipients,
+                SendWarningKind::LargeCc,
+                SendWarningKind::EmptySubject,
+                SendWarningKind::OldThread
+            ]
+        );
+        // Subdomains are internal, duplicates and the user's own addresses dropped
+        assert_eq!(warnings[0].addresses, ["bob@partner.example"]);
+        assert_eq!(warnings[0].message, "1 recipient is outside corp.example");
+        assert_eq!(warnings[1].message, "Cc lists 3 people (more than 2)");
+        assert_eq!(
+            warnings[3].message,
+            "This conversation has been quiet for 300 days"
+        );
+
+        // Nothing to say under the defaults once there's a subject
+        let outgoing = Outgoing {
+            subject: "Hello".to_string(),
+            ..outgoing
+        };
+        assert!(evaluate(&SendPolicy::default(), &outgoing, Some(0), now, |_| false).is_empty());
+    }
+
+    #[test]
+    fn test_is_internal() {
+        let domains = vec!["@Corp.example".to_string()];
+        assert!(is_internal("a@corp.example", &domains));
+        assert!(is_internal("a@mail.corp.example", &domains));
+        assert!(!is_internal("a@notcorp.example", &domains));
+        assert!(!is_internal("a@corp.example.org", &domains));
+    }
+}
@@ -0,0 +1,188 @@
+//! Settings the backend needs before (or without) the frontend, persisted as
+//! JSON in the profile's config directory.
+//!
+//! Most user settings live in the frontend's SQLite `settings` table. Only
+//! values that the Rust side must act on by itself — e.g. what the window
+//! close button does — are mirrored here.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+use crate::accounts::require_trusted_window;
+use crate::local_api::LocalApiSettings;
+use crate::managed;
+use crate::notify_rules::NotificationRules;
+use crate::profiles;
+use crate::send_policy::SendPolicy;
+
+const SETTINGS_FILE: &str = "backend-settings.json";
+/// Returned by mutating commands while read-only mode is on.
+pub const READ_ONLY_ERROR: &str = "Read-only mode is on; changes are disabled";
+
+/// What happens when the main window's close button is pressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum CloseBehavior {
+    /// Hide the window and keep running in the tray.
+    #[default]
+    Hide,
+    /// Minimize the window to the taskbar/dock.
+    Minimize,
+    /// Exit the application.
+    Quit,
+    /// Emit `close-requested` so the frontend can ask (and optionally remember).
+    Ask,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackendSettings {
+    pub close_behavior: CloseBehavior,
+    /// Whether a launch-on-login start (`--hidden`) stays in the tray.
+    pub start_hidden: bool,
+    /// Global shortcut for the quick-compose window; `None` disables it.
+    pub quick_compose_shortcut: Option<String>,
+    /// Refuse every command that changes server state (send, flag, move,
+    /// delete, append), e.g. for demos or reviewing an archive.
+    pub read_only: bool,
+    /// Checks the composer runs before sending (`check_send_policy`).
+    pub send_policy: SendPolicy,
+    /// The opt-in localhost API for automation tools.
+    pub local_api: LocalApiSettings,
+    /// Seconds before a one-time code copied from a notification is
+    /// cleared from the clipboard; 0 leaves it there.
+    pub otp_clipboard_seconds: u32,
+    /// VIP senders, Do Not Disturb and digests (see `notify_rules`).
+    pub notification_rules: NotificationRules,
+}
+
+impl Default for BackendSettings {
+    fn default() -> Self {
+        Self {
+            close_behavior: CloseBehavior::default(),
+            start_hidden: true,
+            quick_compose_shortcut: Some(
+                crate::quick_compose::DEFAULT_QUICK_COMPOSE_SHORTCUT.to_string(),
+            ),
+            read_only: false,
+            send_policy: SendPolicy::default(),
+            local_api: LocalApiSettings::default(),
+            otp_clipboard_seconds: crate::otp::DEFAULT_CLIPBOARD_SECONDS,
+            notification_rules: NotificationRules::default(),
+        }
+    }
+}
+
+/// Managed state wrapping the loaded settings and their file location.
+/// The default has no file and keeps changes in memory.
+#[derive(Default)]
+pub struct SettingsState {
+    path: Option<PathBuf>,
+    settings: Mutex<BackendSettings>,
+}
+
+impl SettingsState {
+    /// Load settings from disk, falling back to defaults on any error.
+    pub fn load(app: &AppHandle) -> Self {
+        let path = profiles::config_dir(app)
+            .map(|dir| dir.join(SETTINGS_FILE))
+            .map_err(|e| log::warn!("No config dir for backend settings: {e}"))
+            .ok();
+        Self::from_path(path)
+    }
+
+    /// Load the settings of the profile config dir `dir`, e.g. without the
+    /// app running.
+    pub fn from_dir(dir: &Path) -> Self {
+        Self::from_path(Some(dir.join(SETTINGS_FILE)))
+    }
+
+    fn from_path(path: Option<PathBuf>) -> Self {
+        let settings = path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|json| {
+                serde_json::from_str(&json)
+                    .map_err(|e| log::warn!("Ignoring malformed {SETTINGS_FILE}: {e}"))
+                    .ok()
+            })
+            .unwrap_or_default();
+
+        Self {
+            path,
+            settings: Mutex::new(settings),
+        }
+    }
+
+    /// The settings in effect: the user's, with managed policy values
+    /// taking precedence (see [`crate::managed`]).
+    pub fn get(&self) -> BackendSettings {
+        managed::policy().apply_settings(self.settings.lock().unwrap().clone())
+    }
+
+    /// Fail with a read-only error if read-only mode is on. Mutating
+    /// commands call this before touching the server.
+    pub fn require_writable(&self, command: &str) -> Result<(), String> {
+        if self.get().read_only {
+            log::warn!("Refused {command} in read-only mode");
+            return Err(READ_ONLY_ERROR.to_string());
+        }
+        Ok(())
+    }
+
+    /// Apply a change and write the result back to disk. Fails, changing
+    /// nothing, if the change touches a managed setting.
+    pub fn update(&self, f: impl FnOnce(&mut BackendSettings)) -> Result<(), String> {
+        let mut settings = self.settings.lock().unwrap();
+        let mut updated = settings.clone();
+        f(&mut updated);
+        managed::policy().check_settings_change(&settings, &updated)?;
+        *settings = updated;
+
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create settings directory: {e}"))?;
+        }
+        let json = serde_json::to_string_pretty(&*settings)
+            .map_err(|e| format!("Failed to serialize settings: {e}"))?;
+        std::fs::write(path, json).map_err(|e| format!("Failed to write {SETTINGS_FILE}: {e}"))
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_close_behavior(state: tauri::State<'_, SettingsState>) -> CloseBehavior {
+    state.get().close_behavior
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_close_behavior(
+    state: tauri::State<'_, SettingsState>,
+    behavior: CloseBehavior,
+) -> Result<(), String> {
+    state.update(|s| s.close_behavior = behavior)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_read_only(state: tauri::State<'_, SettingsState>) -> bool {
+    state.get().read_only
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_read_only(
+    window: tauri::Window,
+    state: tauri::State<'_, SettingsState>,
+    enabled: bool,
+) -> Result<(), String> {
+    require_trusted_window(&window, "set_read_only")?;
+    state.update(|s| s.read_only = enabled)
+}
@@ -0,0 +1,81 @@
+//! Submission with BURL (RFC 4468).
+//!
+//! A message forwarded as an attachment is still on the IMAP server. With
+//! BURL the submission server fetches it there through a URLAUTH URL, so the
+//! client only uploads the parts the user wrote.
+
+use lettre::address::Envelope;
+use std::collections::HashMap;
+
+use super::client::extract_envelope;
+use super::session::SmtpSession;
+use super::types::{SmtpConfig, SmtpSendResult, SubmitError};
+use crate::attachments::{self, AttachmentStaging, MessageChunk, ServerMessageRef};
+use crate::imap::types::ImapConfig;
+use crate::imap::urlauth;
+
+/// Send `raw` with the server messages in `refs` submitted by reference.
+/// `Ok(None)` if the SMTP or IMAP server lacks the needed extensions, in which
+/// case the caller sends the message normally, as it may after
+/// [`SubmitError::NotSent`].
+pub async fn send(
+    config: &SmtpConfig,
+    imap_config: &ImapConfig,
+    raw: &[u8],
+    staging: &AttachmentStaging,
+    refs: &[ServerMessageRef],
+) -> Result<Option<SmtpSendResult>, SubmitError> {
+    let envelope = extract_envelope(raw)?;
+    let mut session = SmtpSession::connect(config).await?;
+    let supports_imap_urls = session.extension("BURL").is_some_and(|params| {
+        params.split_whitespace().any(|p| {
+            p.eq_ignore_ascii_case("imap") || p.to_ascii_lowercase().starts_with("imap://")
+        })
+    });
+    if !supports_imap_urls || session.extension("CHUNKING").is_none() {
+        session.quit().await;
+        return Ok(None);
+    }
+
+    let Some(urls) = urlauth::submission_urls(imap_config, refs, &config.username).await? else {
+        session.quit().await;
+        return Ok(None);
+    };
+
+    let result = submit(&mut session, &envelope, raw, staging, &urls).await;
+    session.quit().await;
+    result.map(|()| {
+        Some(SmtpSendResult {
+            success: true,
+            message: "Email sent successfully".to_string(),
+        })
+    })
+}
+
+async fn submit(
+    session: &mut SmtpSession,
+    envelope: &Envelope,
+    raw: &[u8],
+    staging: &AttachmentStaging,
+    urls: &HashMap<String, String>,
+) -> Result<(), SubmitError> {
+    session.envelope(envelope).await?;
+
+    let chunks = attachments::expand_staged_parts_with_urls(raw, staging, urls)?;
+    let count = chunks.len();
+    for (i, chunk) in chunks.into_iter().enumerate() {
+        let last = i + 1 == count;
+        match chunk {
+            MessageChunk::Bytes(bytes) if bytes.is_empty() && !last => {}
+            MessageChunk::Bytes(bytes) => session
+                .bdat(&bytes, last)
+                .await
+                .map_err(SubmitError::chunk(last))?,
+            MessageChunk::ServerUrl(url) => session
+                .burl(&url, last)
+                .await
+                .map_err(SubmitError::chunk(last))?,
+        }
+    }
+    Ok(())
+}
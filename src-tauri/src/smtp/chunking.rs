@@ -0,0 +1,93 @@
+//! Sending large messages with BDAT (RFC 3030).
+//!
+//! Staged files are base64-encoded from disk one block at a time and each
+//! block goes out as its own `BDAT` chunk, so a 25 MB attachment is never
+//! held in memory whole and a stalled link only times out the current chunk.
+
+use tokio::io::AsyncReadExt;
+
+use super::client::extract_envelope;
+use super::session::SmtpSession;
+use super::types::{SmtpConfig, SmtpSendResult, SubmitError};
+use crate::attachments::{self, AttachmentStaging, StreamChunk};
+
+/// Messages with at least this much staged attachment data are streamed.
+pub const STREAMING_THRESHOLD: u64 = 10 * 1024 * 1024;
+
+/// Raw bytes read from disk per chunk: a whole number of 57-byte base64
+/// input lines, about 1 MB.
+const BLOCK_SIZE: usize = 57 * 18_396;
+
+/// Send `raw` with its staged files streamed from disk. `Ok(None)` if the
+/// server doesn't advertise CHUNKING, in which case the caller sends the
+/// message normally, as it may after [`SubmitError::NotSent`].
+pub async fn send(
+    config: &SmtpConfig,
+    raw: &[u8],
+    staging: &AttachmentStaging,
+) -> Result<Option<SmtpSendResult>, SubmitError> {
+    let envelope = extract_envelope(raw)?;
+    let chunks = attachments::expand_staged_parts_for_streaming(raw, staging)?;
+
+    let mut session = SmtpSession::connect(config).await?;
+    if session.extension("CHUNKING").is_none() {
+        session.quit().await;
+        return Ok(None);
+    }
+
+    let result = async {
+        session.envelope(&envelope).await?;
+        let count = chunks.len();
+        for (i, chunk) in chunks.into_iter().enumerate() {
+            let last = i + 1 == count;
+            match chunk {
+                StreamChunk::Bytes(bytes) if bytes.is_empty() && !last => {}
+                StreamChunk::Bytes(bytes) => session
+                    .bdat(&bytes, last)
+                    .await
+                    .map_err(SubmitError::chunk(last))?,
+                StreamChunk::File { path, filename } => {
+                    let mut file = tokio::fs::File::open(&path)
+                        .await
+                        .map_err(|e| format!("Failed to read attachment {filename}: {e}"))?;
+                    let mut block = vec![0u8; BLOCK_SIZE];
+                    loop {
+                        let n = read_block(&mut file, &mut block)
+                            .await
+                            .map_err(|e| format!("Failed to read attachment {filename}: {e}"))?;
+                        if n == 0 {
+                            break;
+                        }
+                        let mut encoded = Vec::with_capacity(n / 57 * 78 + 78);
+                        attachments::write_base64_lines(&mut encoded, &block[..n]);
+                        session.bdat(&encoded, false).await?;
+                    }
+                }
+            }
+        }
+        Ok::<_, SubmitError>(())
+    }
+    .await;
+    session.quit().await;
+
+    result.map(|()| {
+        Some(SmtpSendResult {
+            success: true,
+            message: "Email sent successfully".to_string(),
+        })
+    })
+}
+
+/// Fill `block` from `file`, stopping short only at end of file, so every
+/// block but the last encodes to whole base64 lines.
+async fn read_block(file: &mut tokio::fs::File, block: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < block.len() {
+        let n = file.read(&mut block[filled..]).await?;
+        if n == 0 {
+            break;
+        }
+        filled += n;
+    }
+    Ok(filled)
+}
@@ -7,10 +7,12 @@ use lettre::{
     AsyncSmtpTransport, AsyncTransport, Tokio1Executor,
 };
 
+use super::session::SmtpSession;
 use super::types::{SmtpConfig, SmtpSendResult};
+use crate::oauth;
 
 /// Decode a base64url-encoded string (Gmail format) to raw bytes.
-fn decode_base64url(input: &str) -> Result<Vec<u8>, String> {
+pub(crate) fn decode_base64url(input: &str) -> Result<Vec<u8>, String> {
     URL_SAFE_NO_PAD
         .decode(input)
         .map_err(|e| format!("Base64 decode error: {}", e))
@@ -44,7 +46,8 @@ fn build_transport(
                     .dangerous_accept_invalid_hostnames(true)
                     .build()
                     .map_err(|e| format!("SMTP TLS params error: {}", e))?;
-                builder = builder.tls(Tls::Required(tls_params));
+                // Wrapper, not Required: Required would try STARTTLS on a TLS port
+                builder = builder.tls(Tls::Wrapper(tls_params));
             }
 
             builder.build()
@@ -85,7 +88,7 @@ fn build_transport(
 ///
 /// The envelope tells the SMTP server who the mail is from and who to deliver
 /// it to, which is separate from the header fields visible to the recipient.
-fn extract_envelope(raw: &[u8]) -> Result<lettre::address::Envelope, String> {
+pub(crate) fn extract_envelope(raw: &[u8]) -> Result<lettre::address::Envelope, String> {
     let message = mail_parser::MessageParser::default()
         .parse(raw)
         .ok_or("Failed to parse email for envelope extraction")?;
@@ -142,21 +145,49 @@ fn extract_envelope(raw: &[u8]) -> Result<lettre::address::Envelope, String> {
         .map_err(|e| format!("Envelope error: {}", e))
 }
 
-/// Send a pre-built RFC 2822 email via SMTP.
-///
-/// The `raw_email_base64url` parameter is the full email message encoded as
-/// base64url (the same encoding Gmail uses: `+` → `-`, `/` → `_`, no padding).
-/// The function decodes it, extracts the envelope from headers, and sends it.
-pub async fn send_raw_email(
+/// Whether `config` must authenticate through [`SmtpSession`]: lettre
+/// can't send XOAUTH2 without an initial response.
+fn needs_session(config: &SmtpConfig) -> bool {
+    config.auth_method == "oauth2" && !oauth::smtp_xoauth2_initial_response(&config.host)
+}
+
+/// Send an already-decoded RFC 2822 message.
+pub async fn send_raw_bytes(
+    config: &SmtpConfig,
+    raw_bytes: &[u8],
+) -> Result<SmtpSendResult, String> {
+    let envelope = extract_envelope(raw_bytes)?;
+    send_with_envelope(config, &envelope, raw_bytes).await
+}
+
+/// Send a message to the recipients in `envelope`, which may include some
+/// (Bcc) that the message's headers don't show.
+pub async fn send_with_envelope(
     config: &SmtpConfig,
-    raw_email_base64url: &str,
+    envelope: &lettre::address::Envelope,
+    raw_bytes: &[u8],
 ) -> Result<SmtpSendResult, String> {
-    let raw_bytes = decode_base64url(raw_email_base64url)?;
-    let envelope = extract_envelope(&raw_bytes)?;
+    if needs_session(config) {
+        let mut session = SmtpSession::connect(config)
+            .await
+            .map_err(|e| format!("SMTP send error: {e}"))?;
+        let result = async {
+            session.envelope(envelope).await?;
+            session.data(raw_bytes).await
+        }
+        .await;
+        session.quit().await;
+        return result
+            .map(|()| SmtpSendResult {
+                success: true,
+                message: "Email sent successfully".to_string(),
+            })
+            .map_err(|e| format!("SMTP send error: {e}"));
+    }
     let transport = build_transport(config)?;
 
     transport
-        .send_raw(&envelope, &raw_bytes)
+        .send_raw(envelope, raw_bytes)
         .await
         .map(|_response| SmtpSendResult {
             success: true,
@@ -167,6 +198,16 @@ pub async fn send_raw_email(
 
 /// Test SMTP connectivity by connecting, authenticating, and disconnecting.
 pub async fn test_connection(config: &SmtpConfig) -> Result<SmtpSendResult, String> {
+    if needs_session(config) {
+        let session = SmtpSession::connect(config)
+            .await
+            .map_err(|e| format!("SMTP test error: {e}"))?;
+        session.quit().await;
+        return Ok(SmtpSendResult {
+            success: true,
+            message: "Connection successful".to_string(),
+        });
+    }
     let transport = build_transport(config)?;
 
     transport
@@ -1,2 +1,5 @@
+pub mod burl;
+pub mod chunking;
 pub mod client;
+mod session;
 pub mod types;
@@ -0,0 +1,298 @@
+//! Minimal SMTP client for extensions lettre doesn't expose.
+//!
+//! lettre only submits messages with DATA. BURL (RFC 4468) and streaming
+//! with BDAT (RFC 3030) need raw command access, so those sends open their
+//! own connection here, like the raw IMAP connections used for NOTIFY and
+//! CATENATE. So do OAuth sends to Microsoft, whose servers reject the
+//! XOAUTH2 initial response lettre always sends.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use lettre::address::Envelope;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+
+use super::types::SmtpConfig;
+use crate::imap::client::{build_tls_connector, configure_tcp_socket, ImapStream};
+use crate::metrics::{self, Phase};
+use crate::oauth;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+const REPLY_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// An authenticated SMTP connection.
+pub(super) struct SmtpSession {
+    reader: BufReader<ImapStream>,
+    /// EHLO keywords with their parameters, e.g. `BURL imap`.
+    extensions: Vec<String>,
+}
+
+impl SmtpSession {
+    /// Connect (implicit TLS, STARTTLS or plain per `config.security`),
+    /// EHLO and authenticate.
+    pub(super) async fn connect(config: &SmtpConfig) -> Result<Self, String> {
+        let connect_start = Instant::now();
+        let tcp = tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect((&*config.host, config.port)))
+            .await
+            .map_err(|_| format!(
+                "TCP connect to {}:{} timed out after {}s — check your server settings or network connection",
+                config.host, config.port, CONNECT_TIMEOUT.as_secs()
+            ))?
+            .map_err(|e| format!("TCP connect to {}:{} failed: {e}", config.host, config.port))?;
+        configure_tcp_socket(&tcp);
+
+        let stream = if config.security == "tls" {
+            ImapStream::Tls(tls_handshake(config, tcp).await?)
+        } else {
+            ImapStream::Plain(tcp)
+        };
+
+        let mut session = Self {
+            reader: BufReader::new(stream),
+            extensions: Vec::new(),
+        };
+        session.expect_reply("greeting", 2).await?;
+        session.ehlo().await?;
+
+        if config.security == "starttls" {
+            session.command("STARTTLS", 2).await?;
+            let ImapStream::Plain(tcp) = session.reader.into_inner() else {
+                return Err("STARTTLS on an encrypted connection".to_string());
+            };
+            session.reader = BufReader::new(ImapStream::Tls(tls_handshake(config, tcp).await?));
+            session.ehlo().await?;
+        }
+
+        metrics::record(
+            &config.host,
+            "smtp",
+            Phase::Connect,
+            connect_start.elapsed(),
+            true,
+        );
+
+        metrics::time(
+            &config.host,
+            "smtp",
+            Phase::Auth,
+            session.authenticate(config),
+        )
+        .await?;
+        Ok(session)
+    }
+
+    /// Parameters of an EHLO keyword, or `None` if the server doesn't offer it.
+    pub(super) fn extension(&self, keyword: &str) -> Option<&str> {
+        self.extensions.iter().find_map(|line| {
+            let (name, params) = line.split_once(' ').unwrap_or((line, ""));
+            name.eq_ignore_ascii_case(keyword).then_some(params)
+        })
+    }
+
+    async fn ehlo(&mut self) -> Result<(), String> {
+        let lines = self.command("EHLO localhost", 2).await?;
+        self.extensions = lines.into_iter().skip(1).collect();
+        Ok(())
+    }
+
+    async fn authenticate(&mut self, config: &SmtpConfig) -> Result<(), String> {
+        if config.auth_method == "oauth2" {
+            let token = format!(
+                "user={}\x01auth=Bearer {}\x01\x01",
+                config.username, config.password
+            );
+            let token = STANDARD.encode(token);
+            let (code, lines) = if oauth::smtp_xoauth2_initial_response(&config.host) {
+                self.send(&format!("AUTH XOAUTH2 {token}")).await?
+            } else {
+                // The token goes in reply to the server's empty challenge
+                self.command("AUTH XOAUTH2", 3).await?;
+                self.send(&token).await?
+            };
+            if code == 334 {
+                // The challenge carries the error details; an empty reply ends the exchange
+                let _ = self.send("").await;
+            }
+            return check_reply("AUTH", code, &lines, 2);
+        }
+
+        let mechanisms = self.extension("AUTH").unwrap_or("").to_ascii_uppercase();
+        if mechanisms.split_whitespace().any(|m| m == "LOGIN") && !mechanisms.contains("PLAIN") {
+            self.command("AUTH LOGIN", 3).await?;
+            self.command(&STANDARD.encode(&config.username), 3).await?;
+            self.command(&STANDARD.encode(&config.password), 2).await?;
+            return Ok(());
+        }
+
+        let plain = format!("\0{}\0{}", config.username, config.password);
+        self.command(&format!("AUTH PLAIN {}", STANDARD.encode(plain)), 2)
+            .await?;
+        Ok(())
+    }
+
+    /// `MAIL FROM` and one `RCPT TO` per recipient.
+    pub(super) async fn envelope(&mut self, envelope: &Envelope) -> Result<(), String> {
+        let from = envelope.from().map(|a| a.to_string()).unwrap_or_default();
+        let body = if self.extension("8BITMIME").is_some() {
+            " BODY=8BITMIME"
+        } else {
+            ""
+        };
+        self.command(&format!("MAIL FROM:<{from}>{body}"), 2)
+            .await?;
+        for rcpt in envelope.to() {
+            self.command(&format!("RCPT TO:<{rcpt}>"), 2).await?;
+        }
+        Ok(())
+    }
+
+    /// Send the whole message with `DATA`.
+    pub(super) async fn data(&mut self, raw: &[u8]) -> Result<(), String> {
+        self.command("DATA", 3).await?;
+        self.write(&dot_stuff(raw)).await?;
+        self.expect_reply("DATA", 2).await
+    }
+
+    /// Send a chunk of the message with `BDAT` (RFC 3030).
+    pub(super) async fn bdat(&mut self, chunk: &[u8], last: bool) -> Result<(), String> {
+        let header = format!(
+            "BDAT {}{}\r\n",
+            chunk.len(),
+            if last { " LAST" } else { "" }
+        );
+        self.write(header.as_bytes()).await?;
+        self.write(chunk).await?;
+        self.expect_reply("BDAT", 2).await
+    }
+
+    /// Have the server append the content of an IMAP URL (RFC 4468).
+    pub(super) async fn burl(&mut self, url: &str, last: bool) -> Result<(), String> {
+        self.command(&format!("BURL {url}{}", if last { " LAST" } else { "" }), 2)
+            .await?;
+        Ok(())
+    }
+
+    pub(super) async fn quit(mut self) {
+        let _ = self.send("QUIT").await;
+    }
+
+    /// Send a command and require a reply in the given class (2 for 2xx, 3 for 3xx).
+    async fn command(&mut self, cmd: &str, class: u16) -> Result<Vec<String>, String> {
+        let (code, lines) = self.send(cmd).await?;
+        // Only the verb goes into errors, so credentials never end up in messages
+        let verb = cmd.split_whitespace().next().unwrap_or("AUTH");
+        check_reply(verb, code, &lines, class)?;
+        Ok(lines)
+    }
+
+    async fn send(&mut self, cmd: &str) -> Result<(u16, Vec<String>), String> {
+        self.write(format!("{cmd}\r\n").as_bytes()).await?;
+        self.read_reply().await
+    }
+
+    async fn expect_reply(&mut self, what: &str, class: u16) -> Result<(), String> {
+        let (code, lines) = self.read_reply().await?;
+        check_reply(what, code, &lines, class)
+    }
+
+    async fn write(&mut self, bytes: &[u8]) -> Result<(), String> {
+        let stream = self.reader.get_mut();
+        stream
+            .write_all(bytes)
+            .await
+            .map_err(|e| format!("SMTP write failed: {e}"))?;
+        stream
+            .flush()
+            .await
+            .map_err(|e| format!("SMTP write failed: {e}"))
+    }
+
+    /// Read a (possibly multi-line) reply: its code and the text of each line.
+    async fn read_reply(&mut self) -> Result<(u16, Vec<String>), String> {
+        let mut lines = Vec::new();
+        loop {
+            let mut line = String::new();
+            let n = tokio::time::timeout(REPLY_TIMEOUT, self.reader.read_line(&mut line))
+                .await
+                .map_err(|_| format!("SMTP reply timed out after {}s", REPLY_TIMEOUT.as_secs()))?
+                .map_err(|e| format!("SMTP read failed: {e}"))?;
+            if n == 0 {
+                return Err("SMTP connection closed".to_string());
+            }
+
+            let line = line.trim_end();
+            let code = line
+                .get(..3)
+                .and_then(|c| c.parse::<u16>().ok())
+                .ok_or_else(|| format!("Malformed SMTP reply: {line}"))?;
+            lines.push(line.get(4..).unwrap_or("").to_string());
+            if line.as_bytes().get(3) != Some(&b'-') {
+                return Ok((code, lines));
+            }
+        }
+    }
+}
+
+/// `raw` as DATA content: lines starting with "." get another one, and
+/// the terminating "." line is appended.
+fn dot_stuff(raw: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(raw.len() + 64);
+    let mut line_start = true;
+    for &b in raw {
+        if line_start && b == b'.' {
+            out.push(b'.');
+        }
+        out.push(b);
+        line_start = b == b'\n';
+    }
+    if !out.is_empty() && !out.ends_with(b"\r\n") {
+        out.extend_from_slice(b"\r\n");
+    }
+    out.extend_from_slice(b".\r\n");
+    out
+}
+
+fn check_reply(what: &str, code: u16, lines: &[String], class: u16) -> Result<(), String> {
+    if code / 100 == class {
+        Ok(())
+    } else {
+        Err(format!("SMTP {what} failed: {code} {}", lines.join(" ")))
+    }
+}
+
+async fn tls_handshake(
+    config: &SmtpConfig,
+    tcp: TcpStream,
+) -> Result<tokio_native_tls::TlsStream<TcpStream>, String> {
+    let connector =
+        tokio_native_tls::TlsConnector::from(build_tls_connector(config.accept_invalid_certs)?);
+    tokio::time::timeout(CONNECT_TIMEOUT, connector.connect(&config.host, tcp))
+        .await
+        .map_err(|_| format!(
+            "TLS handshake with {} timed out after {}s — check your server settings or network connection",
+            config.host, CONNECT_TIMEOUT.as_secs()
+        ))?
+        .map_err(|e| format!("TLS handshake with {} failed: {e}", config.host))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_reply() {
+        assert!(check_reply("MAIL", 250, &["OK".to_string()], 2).is_ok());
+        let err =
+            check_reply("BURL", 554, &["5.7.8 URL resolution failed".to_string()], 2).unwrap_err();
+        assert_eq!(err, "SMTP BURL failed: 554 5.7.8 URL resolution failed");
+    }
+
+    #[test]
+    fn test_dot_stuff() {
+        assert_eq!(
+            dot_stuff(b"Hi\r\n.\r\n..x\r\nend"),
+            b"Hi\r\n..\r\n...x\r\nend\r\n.\r\n"
+        );
+        assert_eq!(dot_stuff(b".top\r\n"), b"..top\r\n.\r\n");
+    }
+}
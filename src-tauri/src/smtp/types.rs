@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
+use specta::Type;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct SmtpConfig {
     pub host: String,
     pub port: u16,
@@ -10,10 +11,65 @@ pub struct SmtpConfig {
     pub auth_method: String, // "password" or "oauth2"
     #[serde(default)]
     pub accept_invalid_certs: bool,
+    /// Messages sent per minute; overrides the provider preset. `0` disables
+    /// rate limiting.
+    #[serde(default)]
+    pub rate_limit_per_minute: Option<u32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct SmtpSendResult {
     pub success: bool,
     pub message: String,
 }
+
+/// Why a BURL or BDAT submission failed.
+#[derive(Debug)]
+pub enum SubmitError {
+    /// Failed before the final chunk went out, so the server delivers
+    /// nothing and the message can be sent again with DATA.
+    NotSent(String),
+    /// Failed after the final chunk went out. The server may already have
+    /// accepted the message, so sending it again could deliver it twice.
+    MaybeSent(String),
+}
+
+impl SubmitError {
+    /// Classify an error from sending a chunk: once the `LAST` one is out
+    /// the outcome is unknown.
+    pub fn chunk(last: bool) -> impl FnOnce(String) -> Self {
+        move |e| {
+            if last {
+                Self::MaybeSent(e)
+            } else {
+                Self::NotSent(e)
+            }
+        }
+    }
+}
+
+impl From<String> for SubmitError {
+    fn from(e: String) -> Self {
+        Self::NotSent(e)
+    }
+}
+
+/// What became of the Sent folder copy of a message.
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum SentCopy {
+    /// Appended to the Sent folder.
+    Saved,
+    /// The transport saves sent mail itself (Gmail API).
+    SavedByServer,
+    /// Not tried, because the message wasn't sent.
+    Skipped,
+    /// The message was sent, but every try at saving the copy failed.
+    Failed { error: String },
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct SendAndAppendResult {
+    pub sent: SmtpSendResult,
+    pub sent_copy: SentCopy,
+}
@@ -0,0 +1,261 @@
+//! Text snippet expansion for the composer.
+//!
+//! Snippets are short abbreviations (";addr", ";sig2") that expand into
+//! longer plain text as they're typed. The `snippets` table is managed by
+//! the frontend (`services/db/snippets.ts`); this module finds the snippet
+//! the text before the caret ends with and fills in its `{{variables}}`,
+//! so expansion behaves the same on every platform without a system text
+//! expander. An account's own snippet wins over a global one with the same
+//! abbreviation, and the longest matching abbreviation wins overall.
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use sqlx::SqlitePool;
+use tauri::State;
+use tauri_plugin_sql::{DbInstances, DbPool};
+
+use crate::profiles::Profiles;
+
+/// Marks where the caret goes after expanding; removed from the text.
+const CURSOR_VARIABLE: &str = "cursor";
+
+/// What the variables of a snippet are filled in from — the same values
+/// compose templates use.
+#[derive(Debug, Default, Deserialize, Type)]
+pub struct SnippetContext {
+    pub recipient_email: Option<String>,
+    /// Looked up in the contacts when missing.
+    pub recipient_name: Option<String>,
+    pub sender_email: Option<String>,
+    pub sender_name: Option<String>,
+    pub subject: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Type)]
+pub struct SnippetExpansion {
+    /// The abbreviation that was matched; the caller replaces this many
+    /// characters before the caret.
+    pub abbreviation: String,
+    /// Plain text with variables filled in.
+    pub text: String,
+    /// Caret position within `text` in UTF-16 code units, when the snippet
+    /// has a `{{cursor}}` marker.
+    pub cursor: Option<u32>,
+}
+
+struct Snippet {
+    abbreviation: String,
+    body: String,
+    is_global: bool,
+}
+
+/// Whether `text` ends with `abbreviation` as a whole word. Abbreviations
+/// starting with punctuation (";addr") match anywhere, since they can't be
+/// the tail of an ordinary word.
+fn ends_with_abbreviation(text: &str, abbreviation: &str) -> bool {
+    let Some(before) = text.strip_suffix(abbreviation) else {
+        return false;
+    };
+    let starts_with_word_char = abbreviation
+        .chars()
+        .next()
+        .is_some_and(char::is_alphanumeric);
+    !starts_with_word_char
+        || !before
+            .chars()
+            .next_back()
+            .is_some_and(char::is_alphanumeric)
+}
+
+fn find_snippet<'a>(text: &str, snippets: &'a [Snippet]) -> Option<&'a Snippet> {
+    snippets
+        .iter()
+        .filter(|s| !s.abbreviation.is_empty() && ends_with_abbreviation(text, &s.abbreviation))
+        .max_by_key(|s| (s.abbreviation.chars().count(), !s.is_global))
+}
+
+fn split_name(name: &str) -> (&str, &str) {
+    let name = name.trim();
+    match name.split_once(char::is_whitespace) {
+        Some((first, last)) => (first, last.trim_start()),
+        None => (name, ""),
+    }
+}
+
+fn variable_value(name: &str, ctx: &SnippetContext, now: &DateTime<Local>) -> Option<String> {
+    let recipient_name = ctx.recipient_name.as_deref().unwrap_or("");
+    let value = match name {
+        "first_name" => split_name(recipient_name).0.to_string(),
+        "last_name" => split_name(recipient_name).1.to_string(),
+        "email" => ctx.recipient_email.clone().unwrap_or_default(),
+        "my_name" => ctx.sender_name.clone().unwrap_or_default(),
+        "my_email" => ctx.sender_email.clone().unwrap_or_default(),
+        "subject" => ctx.subject.clone().unwrap_or_default(),
+        "date" => now.format("%B %-d, %Y").to_string(),
+        "day" => now.format("%A").to_string(),
+        "time" => now.format("%-I:%M %p").to_string(),
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Fill in `{{variable}}`s. Unknown variables are left as typed so a typo
+/// shows up in the draft instead of vanishing.
+fn fill_variables(
+    body: &str,
+    ctx: &SnippetContext,
+    now: &DateTime<Local>,
+) -> (String, Option<u32>) {
+    let mut text = String::with_capacity(body.len());
+    let mut cursor = None;
+    let mut rest = body;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        text.push_str(&rest[..start]);
+        let placeholder = &rest[start..start + 2 + len + 2];
+        let name = rest[start + 2..start + 2 + len].trim();
+        if name == CURSOR_VARIABLE {
+            cursor.get_or_insert(text.encode_utf16().count() as u32);
+        } else {
+            match variable_value(name, ctx, now) {
+                Some(value) => text.push_str(&value),
+                None => text.push_str(placeholder),
+            }
+        }
+        rest = &rest[start + placeholder.len()..];
+    }
+    text.push_str(rest);
+    (text, cursor)
+}
+
+async fn load_snippets(
+    pool: &SqlitePool,
+    account_id: Option<&str>,
+) -> Result<Vec<Snippet>, String> {
+    let rows: Vec<(String, String, Option<String>)> = sqlx::query_as(
+        "SELECT abbreviation, body, account_id FROM snippets
+         WHERE account_id IS NULL OR account_id = ?1",
+    )
+    .bind(account_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to load snippets: {e}"))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(abbreviation, body, account_id)| Snippet {
+            abbreviation,
+            body,
+            is_global: account_id.is_none(),
+        })
+        .collect())
+}
+
+async fn contact_name(pool: &SqlitePool, email: &str) -> Option<String> {
+    sqlx::query_scalar::<_, Option<String>>("SELECT display_name FROM contacts WHERE email = ?1")
+        .bind(email.trim().to_lowercase())
+        .fetch_optional(pool)
+        .await
+        .ok()
+        .flatten()
+        .flatten()
+}
+
+/// Expand the snippet whose abbreviation `text` (what's before the caret)
+/// ends with, or `None` when there isn't one.
+#[tauri::command]
+#[specta::specta]
+pub async fn expand_snippet(
+    db: State<'_, DbInstances>,
+    profiles: State<'_, Profiles>,
+    text: String,
+    account_id: Option<String>,
+    context: SnippetContext,
+) -> Result<Option<SnippetExpansion>, String> {
+    let instances = db.0.read().await;
+    let DbPool::Sqlite(pool) = instances
+        .get(&profiles.db_url())
+        .ok_or_else(|| "Database is not loaded".to_string())?;
+
+    let snippets = load_snippets(pool, account_id.as_deref()).await?;
+    let Some(snippet) = find_snippet(&text, &snippets) else {
+        return Ok(None);
+    };
+
+    let mut context = context;
+    if context.recipient_name.is_none() {
+        if let Some(email) = context.recipient_email.as_deref() {
+            context.recipient_name = contact_name(pool, email).await;
+        }
+    }
+
+    let (text, cursor) = fill_variables(&snippet.body, &context, &Local::now());
+    Ok(Some(SnippetExpansion {
+        abbreviation: snippet.abbreviation.clone(),
+        text,
+        cursor,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn snippet(abbreviation: &str, body: &str, is_global: bool) -> Snippet {
+        Snippet {
+            abbreviation: abbreviation.to_string(),
+            body: body.to_string(),
+            is_global,
+        }
+    }
+
+    #[test]
+    fn test_find_snippet() {
+        let snippets = [
+            snippet(";addr", "global address", true),
+            snippet(";addr", "account address", false),
+            snippet("addr", "word", true),
+            snippet("r", "letter", true),
+        ];
+
+        assert_eq!(
+            find_snippet("Ship to;addr", &snippets).unwrap().body,
+            "account address"
+        );
+        assert_eq!(find_snippet("my addr", &snippets).unwrap().body, "word");
+        // Word abbreviations don't fire inside other words
+        assert!(find_snippet("ladder", &snippets).is_none());
+        assert_eq!(find_snippet("r", &snippets).unwrap().body, "letter");
+        assert!(find_snippet(";add", &snippets).is_none());
+    }
+
+    #[test]
+    fn test_fill_variables() {
+        let now = Local.with_ymd_and_hms(2026, 3, 5, 14, 7, 0).unwrap();
+        let ctx = SnippetContext {
+            recipient_name: Some("Ada  Lovelace King".to_string()),
+            sender_name: Some("Bob".to_string()),
+            ..SnippetContext::default()
+        };
+
+        let (text, cursor) = fill_variables(
+            "Hi {{ first_name }},\n{{cursor}}\n— {{my_name}}, {{date}} ({{day}}, {{time}}) {{unknown}} {{email}}|{{",
+            &ctx,
+            &now,
+        );
+        assert_eq!(
+            text,
+            "Hi Ada,\n\n— Bob, March 5, 2026 (Thursday, 2:07 PM) {{unknown}} |{{"
+        );
+        assert_eq!(cursor, Some(8));
+        assert_eq!(split_name("Ada  Lovelace King"), ("Ada", "Lovelace King"));
+
+        // The caret offset counts UTF-16 units, as the editor does
+        let (_, cursor) = fill_variables("😀é{{cursor}}", &ctx, &now);
+        assert_eq!(cursor, Some(3));
+    }
+}
@@ -0,0 +1,493 @@
+//! Undo window for permanent deletes.
+//!
+//! `imap_stage_delete` doesn't touch the server: it records which messages
+//! to delete in `staged_operations.json` in the profile data dir and
+//! returns at once. A background task marks them `\Deleted` and expunges
+//! them when the undo window has passed, unless `undo_operation` took the
+//! operation back first. The backend holds the operation, so closing or
+//! reloading a window doesn't drop it, and one still waiting when the app
+//! quits runs on the next start. Transient failures are retried like
+//! outbox sends; the frontend hears the outcome through
+//! `staged-operation-done`.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::Notify;
+
+use crate::accounts::{require_trusted_window, AccountRegistry};
+use crate::audit_log::{self, AuditRecord, AuditResult};
+use crate::commands;
+use crate::events::{self, StagedOperationDone};
+use crate::imap::session_pool::ImapSessionManager;
+use crate::outbox;
+use crate::profiles;
+use crate::settings::SettingsState;
+
+const STAGED_OPERATIONS_FILE: &str = "staged_operations.json";
+
+/// Tries before a failing delete is given up and reported.
+const MAX_ATTEMPTS: u32 = 5;
+/// How long the runner sleeps with nothing due; staging wakes it sooner.
+const IDLE_WAIT: Duration = Duration::from_secs(3600);
+/// How long to wait before trying again while read-only mode is on.
+const READ_ONLY_WAIT: Duration = Duration::from_secs(60);
+
+/// Messages in one folder to delete, and the conversation they belong to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct DeleteTarget {
+    pub thread_id: Option<String>,
+    pub folder: String,
+    pub uids: Vec<u32>,
+}
+
+/// A permanent delete waiting out its undo window.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct StagedOperation {
+    pub id: String,
+    pub account_id: String,
+    /// Shown in the undo toast, e.g. "Conversation deleted".
+    pub label: String,
+    pub targets: Vec<DeleteTarget>,
+    /// Unix timestamp in milliseconds after which the delete runs.
+    pub run_at: i64,
+    pub attempts: u32,
+    /// Being deleted right now, so it can't be undone any more.
+    #[serde(skip)]
+    running: bool,
+}
+
+impl StagedOperation {
+    fn thread_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = Vec::new();
+        for id in self.targets.iter().filter_map(|t| t.thread_id.as_ref()) {
+            if !ids.contains(id) {
+                ids.push(id.clone());
+            }
+        }
+        ids
+    }
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+fn new_id() -> String {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Managed staged operations, mirrored to a file in the profile data dir.
+pub struct StagedOperations {
+    path: Option<PathBuf>,
+    operations: Mutex<Vec<StagedOperation>>,
+    wake: Notify,
+}
+
+impl StagedOperations {
+    pub fn load(app: &AppHandle) -> Self {
+        let path = profiles::data_dir(app)
+            .map(|dir| dir.join(STAGED_OPERATIONS_FILE))
+            .map_err(|e| log::warn!("No data dir for staged operations: {e}"))
+            .ok();
+        Self::from_path(path)
+    }
+
+    fn from_path(path: Option<PathBuf>) -> Self {
+        let operations = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|json| {
+                serde_json::from_str::<Vec<StagedOperation>>(&json).unwrap_or_else(|e| {
+                    log::warn!("Ignoring unreadable staged operations: {e}");
+                    Vec::new()
+                })
+            })
+            .unwrap_or_default();
+        Self {
+            path,
+            operations: Mutex::new(operations),
+            wake: Notify::new(),
+        }
+    }
+
+    fn save(&self, operations: &[StagedOperation]) -> Result<(), String> {
+        let path = self
+            .path
+            .as_ref()
+            .ok_or_else(|| "Staged operations have no data dir".to_string())?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        }
+        let json = serde_json::to_string_pretty(operations)
+            .map_err(|e| format!("Failed to serialize staged operations: {e}"))?;
+        std::fs::write(path, json).map_err(|e| format!("Failed to write staged operations: {e}"))
+    }
+
+    fn save_logged(&self, operations: &[StagedOperation]) {
+        if let Err(e) = self.save(operations) {
+            log::warn!("{e}");
+        }
+    }
+
+    /// Hold a delete of `targets` for `delay_ms`. Fails if it can't be
+    /// written down, since it would otherwise be lost on quit.
+    fn push(
+        &self,
+        account_id: &str,
+        label: &str,
+        targets: Vec<DeleteTarget>,
+        delay_ms: i64,
+    ) -> Result<StagedOperation, String> {
+        let operation = StagedOperation {
+            id: new_id(),
+            account_id: account_id.to_string(),
+            label: label.to_string(),
+            targets,
+            run_at: now_ms() + delay_ms,
+            attempts: 0,
+            running: false,
+        };
+        let mut operations = self.operations.lock().unwrap();
+        operations.push(operation.clone());
+        if let Err(e) = self.save(&operations) {
+            operations.pop();
+            return Err(e);
+        }
+        drop(operations);
+        self.wake.notify_one();
+        Ok(operation)
+    }
+
+    /// Cancel operation `id`. False once it has started running or when
+    /// it's unknown (already done or undone).
+    fn undo(&self, id: &str) -> bool {
+        let mut operations = self.operations.lock().unwrap();
+        let Some(index) = operations.iter().position(|op| op.id == id && !op.running) else {
+            return false;
+        };
+        operations.remove(index);
+        self.save_logged(&operations);
+        true
+    }
+
+    fn remove(&self, id: &str) {
+        let mut operations = self.operations.lock().unwrap();
+        operations.retain(|op| op.id != id);
+        self.save_logged(&operations);
+    }
+
+    /// Operations due at `now`, marked as running.
+    fn take_due(&self, now: i64) -> Vec<StagedOperation> {
+        let mut operations = self.operations.lock().unwrap();
+        operations
+            .iter_mut()
+            .filter(|op| !op.running && op.run_at <= now)
+            .map(|op| {
+                op.running = true;
+                op.clone()
+            })
+            .collect()
+    }
+
+    /// Record a failed run: try again after a backoff, or drop the
+    /// operation and return it when the error is permanent or it has run
+    /// out of tries.
+    fn record_failure(&self, id: &str, error: &str, now: i64) -> Option<StagedOperation> {
+        let mut operations = self.operations.lock().unwrap();
+        let index = operations.iter().position(|op| op.id == id)?;
+        let op = &mut operations[index];
+        op.attempts += 1;
+        op.running = false;
+        let failed = if outbox::is_transient(error) && op.attempts < MAX_ATTEMPTS {
+            op.run_at = now + outbox::backoff_ms(op.attempts);
+            None
+        } else {
+            Some(operations.remove(index))
+        };
+        self.save_logged(&operations);
+        failed
+    }
+
+    /// How long until the next operation is due.
+    fn next_wait(&self, now: i64) -> Duration {
+        self.operations
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|op| !op.running)
+            .map(|op| Duration::from_millis((op.run_at - now).max(0) as u64))
+            .min()
+            .unwrap_or(IDLE_WAIT)
+    }
+
+    fn list(&self) -> Vec<StagedOperation> {
+        self.operations.lock().unwrap().clone()
+    }
+}
+
+/// Run what's due; returns how long to sleep before looking again.
+/// Audit log rows for a finished delete: the first `deleted` targets went
+/// through, and the rest failed with `error`.
+fn delete_audit(op: &StagedOperation, deleted: usize, error: Option<&str>) -> Vec<AuditRecord> {
+    op.targets
+        .iter()
+        .enumerate()
+        .map(|(i, target)| {
+            let error = if i < deleted { None } else { error };
+            AuditRecord {
+                account_id: op.account_id.clone(),
+                operation: "permanentDelete",
+                thread_id: target.thread_id.clone(),
+                folder: Some(target.folder.clone()),
+                uids: target.uids.iter().map(u32::to_string).collect(),
+                detail: None,
+                result: if error.is_some() {
+                    AuditResult::Failed
+                } else {
+                    AuditResult::Ok
+                },
+                error: error.map(str::to_string),
+            }
+        })
+        .collect()
+}
+
+async fn run_due(app: &AppHandle, staged: &StagedOperations) -> Duration {
+    if app
+        .state::<SettingsState>()
+        .require_writable("staged delete")
+        .is_err()
+    {
+        return READ_ONLY_WAIT;
+    }
+    let accounts = app.state::<AccountRegistry>();
+    let sessions = app.state::<ImapSessionManager>();
+    for op in staged.take_due(now_ms()) {
+        let mut result = Ok(());
+        let mut deleted = 0;
+        for target in &op.targets {
+            result = commands::delete_messages(
+                &accounts,
+                &sessions,
+                &op.account_id,
+                &target.folder,
+                &target.uids,
+            )
+            .await;
+            if result.is_err() {
+                break;
+            }
+            deleted += 1;
+        }
+        let error = match result {
+            Ok(()) => {
+                staged.remove(&op.id);
+                None
+            }
+            Err(error) => match staged.record_failure(&op.id, &error, now_ms()) {
+                Some(_) => {
+                    log::warn!("Staged delete {} failed: {error}", op.id);
+                    Some(error)
+                }
+                None => {
+                    log::info!("Staged delete {} failed ({error}); retrying", op.id);
+                    continue;
+                }
+            },
+        };
+        audit_log::record(app, delete_audit(&op, deleted, error.as_deref())).await;
+        events::emit(
+            app,
+            StagedOperationDone {
+                id: op.id.clone(),
+                account_id: op.account_id.clone(),
+                thread_ids: op.thread_ids(),
+                error,
+            },
+        );
+    }
+    staged.next_wait(now_ms())
+}
+
+/// Start the background runner.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let staged = app.state::<StagedOperations>();
+        loop {
+            let wait = run_due(&app, &staged).await;
+            tokio::select! {
+                _ = staged.wake.notified() => {}
+                _ = tokio::time::sleep(wait) => {}
+            }
+        }
+    });
+}
+
+/// Permanently delete `targets` from `account_id` after `undo_seconds`,
+/// unless `undo_operation` cancels it first. Resolves once the operation is
+/// safely on disk; nothing is sent to the server until the window passes.
+#[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
+pub fn imap_stage_delete(
+    window: tauri::Window,
+    staged: State<'_, StagedOperations>,
+    settings: State<'_, SettingsState>,
+    account_id: String,
+    label: String,
+    targets: Vec<DeleteTarget>,
+    undo_seconds: u32,
+) -> Result<StagedOperation, String> {
+    require_trusted_window(&window, "imap_stage_delete")?;
+    settings.require_writable("imap_stage_delete")?;
+    staged.push(&account_id, &label, targets, i64::from(undo_seconds) * 1000)
+}
+
+/// Cancel a staged delete before it reaches the server. False once it has
+/// started running or already finished.
+#[tauri::command]
+#[specta::specta]
+pub fn undo_operation(
+    window: tauri::Window,
+    staged: State<'_, StagedOperations>,
+    id: String,
+) -> Result<bool, String> {
+    require_trusted_window(&window, "undo_operation")?;
+    Ok(staged.undo(&id))
+}
+
+/// Staged deletes still waiting or running, e.g. to show their undo toast
+/// again after a reload.
+#[tauri::command]
+#[specta::specta]
+pub fn staged_operation_list(staged: State<'_, StagedOperations>) -> Vec<StagedOperation> {
+    staged.list()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(thread_id: &str, uids: &[u32]) -> DeleteTarget {
+        DeleteTarget {
+            thread_id: Some(thread_id.to_string()),
+            folder: "Trash".to_string(),
+            uids: uids.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_staged_operations_survive_restart() {
+        let path = std::env::temp_dir()
+            .join(format!("sora-staged-test-{}", new_id()))
+            .join(STAGED_OPERATIONS_FILE);
+        let staged = StagedOperations::from_path(Some(path.clone()));
+        let op = staged
+            .push("acc", "Deleted", vec![target("t1", &[1, 2])], 10_000)
+            .unwrap();
+        assert!(staged.take_due(now_ms()).is_empty());
+
+        // Still pending after a restart, and due once its window has passed
+        let reloaded = StagedOperations::from_path(Some(path.clone()));
+        let list = reloaded.list();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].targets, vec![target("t1", &[1, 2])]);
+        let due = reloaded.take_due(op.run_at);
+        assert_eq!(due.len(), 1);
+
+        // Running operations can't be undone
+        assert!(!reloaded.undo(&op.id));
+        reloaded.remove(&op.id);
+        assert!(StagedOperations::from_path(Some(path.clone()))
+            .list()
+            .is_empty());
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_undo_before_run() {
+        let staged = StagedOperations::from_path(Some(
+            std::env::temp_dir()
+                .join(format!("sora-staged-test-{}", new_id()))
+                .join(STAGED_OPERATIONS_FILE),
+        ));
+        let op = staged
+            .push("acc", "Deleted", vec![target("t1", &[1])], 0)
+            .unwrap();
+        assert!(staged.undo(&op.id));
+        assert!(!staged.undo(&op.id));
+        assert!(staged.take_due(now_ms()).is_empty());
+        let _ = std::fs::remove_dir_all(staged.path.unwrap().parent().unwrap());
+    }
+
+    #[test]
+    fn test_failures_retry_then_give_up() {
+        let staged = StagedOperations::from_path(None);
+        staged.operations.lock().unwrap().push(StagedOperation {
+            id: "op".to_string(),
+            account_id: "acc".to_string(),
+            label: "Deleted".to_string(),
+            targets: vec![target("t1", &[1]), target("t2", &[2])],
+            run_at: 0,
+            attempts: 0,
+            running: false,
+        });
+
+        staged.take_due(0);
+        assert!(staged
+            .record_failure(
+                "op",
+                "TCP connect to imap.example.com:993 failed: timed out",
+                1_000
+            )
+            .is_none());
+        assert_eq!(staged.list()[0].run_at, 1_000 + outbox::backoff_ms(1));
+        assert!(!staged.list()[0].running);
+
+        let failed = staged
+            .record_failure("op", "NO [NONEXISTENT] Unknown mailbox", 2_000)
+            .unwrap();
+        assert_eq!(
+            failed.thread_ids(),
+            vec!["t1".to_string(), "t2".to_string()]
+        );
+        assert!(staged.list().is_empty());
+    }
+
+    #[test]
+    fn test_delete_audit_marks_targets_after_the_failure() {
+        let op = StagedOperation {
+            id: "op".to_string(),
+            account_id: "acc".to_string(),
+            label: "Deleted".to_string(),
+            targets: vec![target("t1", &[1, 2]), target("t2", &[3])],
+            run_at: 0,
+            attempts: 1,
+            running: false,
+        };
+
+        let records = delete_audit(&op, 1, Some("NO Mailbox is read-only"));
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].thread_id.as_deref(), Some("t1"));
+        assert_eq!(records[0].uids, vec!["1".to_string(), "2".to_string()]);
+        assert_eq!(records[0].result, AuditResult::Ok);
+        assert_eq!(records[0].error, None);
+        assert_eq!(records[1].result, AuditResult::Failed);
+        assert_eq!(records[1].error.as_deref(), Some("NO Mailbox is read-only"));
+
+        assert!(delete_audit(&op, 2, None)
+            .iter()
+            .all(|record| record.result == AuditResult::Ok));
+    }
+}
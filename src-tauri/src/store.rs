@@ -0,0 +1,215 @@
+//! Read side of the local message cache.
+//!
+//! The sync subsystem stores headers, bodies, flags and per-folder state
+//! (`messages`, `folder_sync_state`) as it reconciles with the server. These
+//! commands serve a folder page or a single message straight from that
+//! cache, so the UI can render offline, or before the first server round
+//! trip finishes, without going through the IMAP commands. Pinned messages
+//! come first in a folder page.
+
+use serde::Serialize;
+use specta::Type;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{FromRow, Row, SqlitePool};
+use tauri::State;
+use tauri_plugin_sql::{DbInstances, DbPool};
+
+use crate::profiles::Profiles;
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 500;
+
+/// A cached message. The bodies are only filled in by `cache_get_message`.
+#[derive(Debug, Serialize, Type)]
+pub struct CachedMessage {
+    pub id: String,
+    pub thread_id: String,
+    pub uid: Option<i64>,
+    pub folder: Option<String>,
+    pub from_address: Option<String>,
+    pub from_name: Option<String>,
+    pub to_addresses: Option<String>,
+    pub cc_addresses: Option<String>,
+    pub subject: Option<String>,
+    pub snippet: Option<String>,
+    /// Unix timestamp in milliseconds.
+    pub date: i64,
+    pub is_read: bool,
+    pub is_starred: bool,
+    /// Pinned to the top of its folder.
+    pub is_pinned: bool,
+    /// Whether the body has been downloaded.
+    pub body_cached: bool,
+    pub body_html: Option<String>,
+    pub body_text: Option<String>,
+}
+
+/// One page of a cached folder and where its sync stands.
+#[derive(Debug, Serialize, Type)]
+pub struct CachedPage {
+    pub messages: Vec<CachedMessage>,
+    /// Cached messages in the whole folder.
+    pub total: i64,
+    /// `None` until the folder has been synced once.
+    pub uidvalidity: Option<i64>,
+    pub last_uid: Option<i64>,
+    pub last_sync_at: Option<i64>,
+}
+
+/// Columns read by [`CachedMessage`]'s `FromRow`; `{bodies}` is replaced
+/// with the body columns or NULLs.
+const SELECT: &str = "SELECT id, thread_id, imap_uid, imap_folder, from_address, from_name,
+     to_addresses, cc_addresses, subject, snippet, date, COALESCE(is_read, 0),
+     COALESCE(is_starred, 0), COALESCE(is_pinned, 0), COALESCE(body_cached, 0), {bodies}
+     FROM messages WHERE account_id = ? AND imap_folder = ?";
+
+// Read by position: the row is wider than the tuples sqlx implements
+// `FromRow` for.
+impl<'r> FromRow<'r, SqliteRow> for CachedMessage {
+    fn from_row(row: &'r SqliteRow) -> sqlx::Result<Self> {
+        Ok(CachedMessage {
+            id: row.try_get(0)?,
+            thread_id: row.try_get(1)?,
+            uid: row.try_get(2)?,
+            folder: row.try_get(3)?,
+            from_address: row.try_get(4)?,
+            from_name: row.try_get(5)?,
+            to_addresses: row.try_get(6)?,
+            cc_addresses: row.try_get(7)?,
+            subject: row.try_get(8)?,
+            snippet: row.try_get(9)?,
+            date: row.try_get(10)?,
+            is_read: row.try_get::<i64, _>(11)? != 0,
+            is_starred: row.try_get::<i64, _>(12)? != 0,
+            is_pinned: row.try_get::<i64, _>(13)? != 0,
+            body_cached: row.try_get::<i64, _>(14)? != 0,
+            body_html: row.try_get(15)?,
+            body_text: row.try_get(16)?,
+        })
+    }
+}
+
+/// `offset` and `limit` brought into range.
+fn page_bounds(offset: Option<i64>, limit: Option<i64>) -> (i64, i64) {
+    (
+        offset.unwrap_or(0).max(0),
+        limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE),
+    )
+}
+
+async fn query_page(
+    pool: &SqlitePool,
+    account_id: &str,
+    folder: &str,
+    offset: i64,
+    limit: i64,
+) -> Result<CachedPage, String> {
+    let sql = format!(
+        "{} ORDER BY COALESCE(is_pinned, 0) DESC, date DESC, imap_uid DESC LIMIT ? OFFSET ?",
+        SELECT.replace("{bodies}", "NULL, NULL")
+    );
+    let rows = sqlx::query_as::<_, CachedMessage>(&sql)
+        .bind(account_id)
+        .bind(folder)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to read cached messages: {e}"))?;
+
+    let (total,): (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM messages WHERE account_id = ? AND imap_folder = ?")
+            .bind(account_id)
+            .bind(folder)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| format!("Failed to count cached messages: {e}"))?;
+
+    let state: Option<(Option<i64>, Option<i64>, Option<i64>)> = sqlx::query_as(
+        "SELECT uidvalidity, last_uid, last_sync_at FROM folder_sync_state
+         WHERE account_id = ? AND folder_path = ?",
+    )
+    .bind(account_id)
+    .bind(folder)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Failed to read folder state: {e}"))?;
+    let (uidvalidity, last_uid, last_sync_at) = state.unwrap_or_default();
+
+    Ok(CachedPage {
+        messages: rows,
+        total,
+        uidvalidity,
+        last_uid,
+        last_sync_at,
+    })
+}
+
+async fn get_message(
+    pool: &SqlitePool,
+    account_id: &str,
+    folder: &str,
+    uid: i64,
+) -> Result<Option<CachedMessage>, String> {
+    let sql = format!(
+        "{} AND imap_uid = ? LIMIT 1",
+        SELECT.replace("{bodies}", "body_html, body_text")
+    );
+    sqlx::query_as::<_, CachedMessage>(&sql)
+        .bind(account_id)
+        .bind(folder)
+        .bind(uid)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to read cached message: {e}"))
+}
+
+/// A page of `folder`'s cached messages, pinned ones first and then
+/// newest first, with the folder's sync state.
+#[tauri::command]
+#[specta::specta]
+pub async fn cache_query_messages(
+    db: State<'_, DbInstances>,
+    profiles: State<'_, Profiles>,
+    account_id: String,
+    folder: String,
+    offset: Option<i64>,
+    limit: Option<i64>,
+) -> Result<CachedPage, String> {
+    let instances = db.0.read().await;
+    let DbPool::Sqlite(pool) = instances
+        .get(&profiles.db_url())
+        .ok_or_else(|| "Database is not loaded".to_string())?;
+    let (offset, limit) = page_bounds(offset, limit);
+    query_page(pool, &account_id, &folder, offset, limit).await
+}
+
+/// The cached message with `uid` in `folder`, bodies included, or `None`
+/// if it hasn't been synced.
+#[tauri::command]
+#[specta::specta]
+pub async fn cache_get_message(
+    db: State<'_, DbInstances>,
+    profiles: State<'_, Profiles>,
+    account_id: String,
+    folder: String,
+    uid: i64,
+) -> Result<Option<CachedMessage>, String> {
+    let instances = db.0.read().await;
+    let DbPool::Sqlite(pool) = instances
+        .get(&profiles.db_url())
+        .ok_or_else(|| "Database is not loaded".to_string())?;
+    get_message(pool, &account_id, &folder, uid).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_bounds() {
+        assert_eq!(page_bounds(None, None), (0, DEFAULT_PAGE_SIZE));
+        assert_eq!(page_bounds(Some(-5), Some(0)), (0, 1));
+        assert_eq!(page_bounds(Some(100), Some(10_000)), (100, MAX_PAGE_SIZE));
+    }
+}
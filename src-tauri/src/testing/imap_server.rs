@@ -0,0 +1,869 @@
+//! Minimal in-memory IMAP4rev1 server.
+//!
+//! Commands: CAPABILITY, NOOP, LOGIN, STARTTLS, LOGOUT, SELECT/EXAMINE,
+//! LIST, STATUS, APPEND, EXPUNGE and UID FETCH/STORE/SEARCH/COPY/MOVE. Every
+//! command line is logged (without its tag) so tests can check which path the
+//! client took.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex};
+
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_native_tls::TlsAcceptor;
+
+use super::{tls_acceptor, PASSWORD, USERNAME};
+use crate::imap::client::ImapStream;
+use crate::imap::types::ImapConfig;
+
+/// INTERNALDATE reported for every message.
+const INTERNAL_DATE: &str = "16-Feb-2026 12:00:00 +0000";
+
+/// Switches for the server's transport and for reproducing server quirks.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct ImapServerOptions {
+    /// Speak TLS from the first byte instead of offering STARTTLS.
+    pub implicit_tls: bool,
+    /// Reject `UID MOVE`, like servers without RFC 6851.
+    pub no_move: bool,
+    /// Answer a `UID FETCH` whose item list isn't parenthesized (how
+    /// async-imap sends it) without any FETCH data, like servers whose
+    /// responses async-imap can't parse. The raw client parenthesizes its
+    /// items and still gets the messages.
+    pub hide_fetch_from_async_imap: bool,
+}
+
+struct Message {
+    uid: u32,
+    flags: Vec<String>,
+    body: Vec<u8>,
+}
+
+struct Mailbox {
+    attributes: &'static str,
+    uid_validity: u32,
+    uid_next: u32,
+    messages: Vec<Message>,
+}
+
+impl Mailbox {
+    fn new(attributes: &'static str, uid_validity: u32) -> Self {
+        Self {
+            attributes,
+            uid_validity,
+            uid_next: 1,
+            messages: Vec::new(),
+        }
+    }
+
+    fn add(&mut self, flags: Vec<String>, body: Vec<u8>) -> u32 {
+        let uid = self.uid_next;
+        self.uid_next += 1;
+        self.messages.push(Message { uid, flags, body });
+        uid
+    }
+
+    fn unseen(&self) -> usize {
+        self.messages
+            .iter()
+            .filter(|m| !m.flags.iter().any(|f| f == "\\Seen"))
+            .count()
+    }
+
+    /// Indexes of the messages whose UIDs are in `set`.
+    fn matching(&self, set: &str) -> Vec<usize> {
+        let max_uid = self.messages.last().map_or(0, |m| m.uid);
+        (0..self.messages.len())
+            .filter(|&i| in_uid_set(set, self.messages[i].uid, max_uid))
+            .collect()
+    }
+
+    /// Remove the messages at `indexes`, returning them and one EXPUNGE
+    /// response per message (highest sequence number first, so the numbers
+    /// stay valid as the client applies them).
+    fn expunge(&mut self, indexes: &[usize]) -> (Vec<Message>, Vec<u8>) {
+        let mut removed = Vec::new();
+        let mut responses = Vec::new();
+        for &i in indexes.iter().rev() {
+            removed.push(self.messages.remove(i));
+            responses.extend(format!("* {} EXPUNGE\r\n", i + 1).into_bytes());
+        }
+        removed.reverse();
+        (removed, responses)
+    }
+}
+
+/// Whether `uid` is in an IMAP UID set like `1:3,7,10:*`.
+fn in_uid_set(set: &str, uid: u32, max_uid: u32) -> bool {
+    let bound = |s: &str| {
+        if s == "*" {
+            Some(max_uid)
+        } else {
+            s.parse::<u32>().ok()
+        }
+    };
+    set.split(',').any(|range| match range.split_once(':') {
+        Some((a, b)) => match (bound(a), bound(b)) {
+            (Some(a), Some(b)) => (a.min(b)..=a.max(b)).contains(&uid),
+            _ => false,
+        },
+        None => bound(range) == Some(uid),
+    })
+}
+
+/// Split command arguments into atoms, quoted strings (unescaped) and
+/// parenthesized lists (kept whole, parentheses included).
+fn arguments(input: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            ' ' => {
+                chars.next();
+            }
+            '"' => {
+                chars.next();
+                let mut arg = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => arg.extend(chars.next()),
+                        '"' => break,
+                        c => arg.push(c),
+                    }
+                }
+                args.push(arg);
+            }
+            '(' => {
+                let mut arg = String::new();
+                let mut depth = 0;
+                for c in chars.by_ref() {
+                    arg.push(c);
+                    match c {
+                        '(' => depth += 1,
+                        ')' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                args.push(arg);
+            }
+            _ => {
+                let mut arg = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c == ' ' {
+                        break;
+                    }
+                    arg.push(c);
+                    chars.next();
+                }
+                args.push(arg);
+            }
+        }
+    }
+    args
+}
+
+/// Flags of a parenthesized flag list like `(\Seen \Flagged)`.
+fn flag_list(list: &str) -> Vec<String> {
+    list.trim_start_matches('(')
+        .trim_end_matches(')')
+        .split_whitespace()
+        .map(str::to_string)
+        .collect()
+}
+
+/// An IMAP quoted string, or NIL.
+fn nstring(value: Option<&str>) -> String {
+    match value {
+        Some(v) => format!("\"{}\"", v.replace('\\', "\\\\").replace('"', "\\\"")),
+        None => "NIL".to_string(),
+    }
+}
+
+/// ENVELOPE of a message (RFC 3501 §7.4.2) from its unfolded headers.
+/// Addresses are `Name <a@b>` or `a@b`, separated by commas.
+fn envelope(body: &[u8]) -> String {
+    let text = String::from_utf8_lossy(body);
+    let header = |name: &str| {
+        text.lines().take_while(|l| !l.is_empty()).find_map(|l| {
+            let (n, v) = l.split_once(':')?;
+            n.eq_ignore_ascii_case(name).then(|| v.trim().to_string())
+        })
+    };
+    let addresses = |name: &str| match header(name) {
+        Some(list) => {
+            let list: String = list
+                .split(',')
+                .map(|a| {
+                    let (display, address) = match a.split_once('<') {
+                        Some((n, a)) => (
+                            Some(n.trim().trim_matches('"')).filter(|n| !n.is_empty()),
+                            a.trim_end_matches('>'),
+                        ),
+                        None => (None, a),
+                    };
+                    let (mailbox, host) = address
+                        .trim()
+                        .split_once('@')
+                        .unwrap_or((address.trim(), ""));
+                    format!(
+                        "({} NIL {} {})",
+                        nstring(display),
+                        nstring(Some(mailbox)),
+                        nstring(Some(host))
+                    )
+                })
+                .collect();
+            format!("({list})")
+        }
+        None => "NIL".to_string(),
+    };
+    let from = addresses("From");
+    let reply_to = if header("Reply-To").is_some() {
+        addresses("Reply-To")
+    } else {
+        from.clone()
+    };
+    format!(
+        "({} {} {from} {from} {reply_to} {} {} {} {} {})",
+        nstring(header("Date").as_deref()),
+        nstring(header("Subject").as_deref()),
+        addresses("To"),
+        addresses("Cc"),
+        addresses("Bcc"),
+        nstring(header("In-Reply-To").as_deref()),
+        nstring(header("Message-ID").as_deref()),
+    )
+}
+
+struct State {
+    mailboxes: BTreeMap<String, Mailbox>,
+    subscribed: BTreeSet<String>,
+    commands: Vec<String>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        let mailboxes = [
+            ("INBOX", "\\HasNoChildren", 1001),
+            ("Sent", "\\HasNoChildren \\Sent", 1002),
+            ("Trash", "\\HasNoChildren \\Trash", 1003),
+            ("Archive", "\\HasNoChildren \\Archive", 1004),
+        ]
+        .into_iter()
+        .map(|(name, attributes, uid_validity)| {
+            (name.to_string(), Mailbox::new(attributes, uid_validity))
+        })
+        .collect();
+        Self {
+            mailboxes,
+            subscribed: BTreeSet::new(),
+            commands: Vec::new(),
+        }
+    }
+}
+
+/// A running test IMAP server. It stops with the test's runtime.
+pub(crate) struct TestImapServer {
+    port: u16,
+    state: Arc<Mutex<State>>,
+}
+
+impl TestImapServer {
+    /// Start listening on a free local port with INBOX, Sent, Trash and
+    /// Archive mailboxes.
+    pub(crate) async fn start(options: ImapServerOptions) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind test IMAP server");
+        let port = listener
+            .local_addr()
+            .expect("test IMAP server address")
+            .port();
+        let state = Arc::new(Mutex::new(State::default()));
+        let acceptor = tls_acceptor();
+
+        let server_state = Arc::clone(&state);
+        tokio::spawn(async move {
+            while let Ok((tcp, _)) = listener.accept().await {
+                let connection = Connection {
+                    state: Arc::clone(&server_state),
+                    options,
+                    selected: None,
+                    authenticated: false,
+                };
+                tokio::spawn(connection.serve(tcp, acceptor.clone()));
+            }
+        });
+
+        Self { port, state }
+    }
+
+    /// Client config for this server. `security` must match how it was
+    /// started: "tls" for implicit TLS, otherwise "none" or "starttls".
+    pub(crate) fn config(&self, security: &str) -> ImapConfig {
+        ImapConfig {
+            host: "127.0.0.1".to_string(),
+            port: self.port,
+            security: security.to_string(),
+            username: USERNAME.to_string(),
+            password: PASSWORD.to_string(),
+            auth_method: "password".to_string(),
+            accept_invalid_certs: true,
+            authorize_as: None,
+            delegation: None,
+            // Tests share a host and user; don't let them throttle each other
+            rate_limit_per_minute: Some(0),
+        }
+    }
+
+    /// Store a message in `folder`, returning its UID.
+    pub(crate) fn add_message(&self, folder: &str, flags: &[&str], body: &[u8]) -> u32 {
+        let mut state = self.state.lock().unwrap();
+        let mailbox = state.mailboxes.get_mut(folder).expect("test mailbox");
+        mailbox.add(flags.iter().map(|f| f.to_string()).collect(), body.to_vec())
+    }
+
+    pub(crate) fn uids(&self, folder: &str) -> Vec<u32> {
+        let state = self.state.lock().unwrap();
+        state.mailboxes[folder]
+            .messages
+            .iter()
+            .map(|m| m.uid)
+            .collect()
+    }
+
+    pub(crate) fn flags(&self, folder: &str, uid: u32) -> Vec<String> {
+        let state = self.state.lock().unwrap();
+        state.mailboxes[folder]
+            .messages
+            .iter()
+            .find(|m| m.uid == uid)
+            .map(|m| m.flags.clone())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn body(&self, folder: &str, uid: u32) -> Option<Vec<u8>> {
+        let state = self.state.lock().unwrap();
+        state.mailboxes[folder]
+            .messages
+            .iter()
+            .find(|m| m.uid == uid)
+            .map(|m| m.body.clone())
+    }
+
+    /// Mailbox names as stored, i.e. modified UTF-7.
+    pub(crate) fn mailbox_names(&self) -> Vec<String> {
+        self.state
+            .lock()
+            .unwrap()
+            .mailboxes
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    pub(crate) fn subscribed(&self) -> Vec<String> {
+        self.state
+            .lock()
+            .unwrap()
+            .subscribed
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Every command received so far, without tags.
+    pub(crate) fn commands(&self) -> Vec<String> {
+        self.state.lock().unwrap().commands.clone()
+    }
+}
+
+struct Connection {
+    state: Arc<Mutex<State>>,
+    options: ImapServerOptions,
+    selected: Option<String>,
+    authenticated: bool,
+}
+
+impl Connection {
+    async fn serve(mut self, tcp: TcpStream, acceptor: TlsAcceptor) -> std::io::Result<()> {
+        let stream = if self.options.implicit_tls {
+            ImapStream::Tls(acceptor.accept(tcp).await.map_err(std::io::Error::other)?)
+        } else {
+            ImapStream::Plain(tcp)
+        };
+        let mut conn = BufReader::new(stream);
+        let greeting = format!(
+            "* OK [CAPABILITY {}] Test server ready\r\n",
+            self.capabilities(&conn)
+        );
+        write(&mut conn, greeting.as_bytes()).await?;
+
+        loop {
+            let mut line = String::new();
+            if conn.read_line(&mut line).await? == 0 {
+                return Ok(());
+            }
+            let line = line.trim_end_matches(['\r', '\n']);
+            let Some((tag, rest)) = line.split_once(' ') else {
+                write(&mut conn, b"* BAD Missing command\r\n").await?;
+                continue;
+            };
+            let (command, args) = rest.split_once(' ').unwrap_or((rest, ""));
+            let command = command.to_ascii_uppercase();
+            self.state.lock().unwrap().commands.push(rest.to_string());
+
+            match command.as_str() {
+                "STARTTLS" if matches!(conn.get_ref(), ImapStream::Plain(_)) => {
+                    write(
+                        &mut conn,
+                        format!("{tag} OK Begin TLS negotiation now\r\n").as_bytes(),
+                    )
+                    .await?;
+                    let ImapStream::Plain(tcp) = conn.into_inner() else {
+                        unreachable!("checked above");
+                    };
+                    let tls = acceptor.accept(tcp).await.map_err(std::io::Error::other)?;
+                    conn = BufReader::new(ImapStream::Tls(tls));
+                }
+                "LOGOUT" => {
+                    write(
+                        &mut conn,
+                        format!("* BYE Logging out\r\n{tag} OK LOGOUT completed\r\n").as_bytes(),
+                    )
+                    .await?;
+                    return Ok(());
+                }
+                "APPEND" if self.authenticated => {
+                    let response = self.append(&mut conn, tag, args).await?;
+                    write(&mut conn, &response).await?;
+                }
+                _ => {
+                    let response = self.respond(&conn, tag, &command, args);
+                    write(&mut conn, &response).await?;
+                }
+            }
+        }
+    }
+
+    fn capabilities(&self, conn: &BufReader<ImapStream>) -> String {
+        let mut caps = String::from("IMAP4rev1 UIDPLUS");
+        if matches!(conn.get_ref(), ImapStream::Plain(_)) {
+            caps.push_str(" STARTTLS");
+        }
+        if !self.options.no_move {
+            caps.push_str(" MOVE");
+        }
+        caps
+    }
+
+    /// Response to everything except STARTTLS, LOGOUT and APPEND.
+    fn respond(
+        &mut self,
+        conn: &BufReader<ImapStream>,
+        tag: &str,
+        command: &str,
+        args: &str,
+    ) -> Vec<u8> {
+        let args_list = arguments(args);
+        let done = |status: &str, text: &str| format!("{tag} {status} {text}\r\n").into_bytes();
+
+        match command {
+            "CAPABILITY" => {
+                let mut out = format!("* CAPABILITY {}\r\n", self.capabilities(conn)).into_bytes();
+                out.extend(done("OK", "CAPABILITY completed"));
+                out
+            }
+            "NOOP" => done("OK", "NOOP completed"),
+            "LOGIN" => {
+                if args_list.first().map(String::as_str) == Some(USERNAME)
+                    && args_list.get(1).map(String::as_str) == Some(PASSWORD)
+                {
+                    self.authenticated = true;
+                    done("OK", "LOGIN completed")
+                } else {
+                    done("NO", "[AUTHENTICATIONFAILED] Invalid credentials")
+                }
+            }
+            _ if !self.authenticated => done("NO", "Not authenticated"),
+            "SELECT" | "EXAMINE" => self.select(tag, &args_list),
+            "LIST" => {
+                let pattern = args_list.get(1).map(String::as_str).unwrap_or("");
+                let state = self.state.lock().unwrap();
+                let mut out = Vec::new();
+                for (name, mailbox) in &state.mailboxes {
+                    if pattern == "*" || pattern == "%" || pattern == name {
+                        out.extend(
+                            format!("* LIST ({}) \"/\" \"{name}\"\r\n", mailbox.attributes)
+                                .into_bytes(),
+                        );
+                    }
+                }
+                out.extend(done("OK", "LIST completed"));
+                out
+            }
+            "CREATE" => {
+                let mut state = self.state.lock().unwrap();
+                let Some(name) = args_list.first() else {
+                    return done("BAD", "Missing mailbox name");
+                };
+                if state.mailboxes.contains_key(name) {
+                    return done("NO", "[ALREADYEXISTS] Mailbox exists");
+                }
+                let uid_validity = state
+                    .mailboxes
+                    .values()
+                    .map(|m| m.uid_validity)
+                    .max()
+                    .unwrap_or(1000)
+                    + 1;
+                state
+                    .mailboxes
+                    .insert(name.clone(), Mailbox::new("\\HasNoChildren", uid_validity));
+                done("OK", "CREATE completed")
+            }
+            "DELETE" => {
+                let mut state = self.state.lock().unwrap();
+                match args_list
+                    .first()
+                    .and_then(|name| state.mailboxes.remove(name))
+                {
+                    Some(_) => done("OK", "DELETE completed"),
+                    None => done("NO", "[NONEXISTENT] Unknown mailbox"),
+                }
+            }
+            "RENAME" => {
+                let mut state = self.state.lock().unwrap();
+                let (Some(from), Some(to)) = (args_list.first(), args_list.get(1)) else {
+                    return done("BAD", "RENAME needs two mailbox names");
+                };
+                if state.mailboxes.contains_key(to) {
+                    return done("NO", "[ALREADYEXISTS] Mailbox exists");
+                }
+                let Some(mailbox) = state.mailboxes.remove(from) else {
+                    return done("NO", "[NONEXISTENT] Unknown mailbox");
+                };
+                state.mailboxes.insert(to.clone(), mailbox);
+                done("OK", "RENAME completed")
+            }
+            "SUBSCRIBE" | "UNSUBSCRIBE" => {
+                let mut state = self.state.lock().unwrap();
+                let Some(name) = args_list.first().cloned() else {
+                    return done("BAD", "Missing mailbox name");
+                };
+                if command == "SUBSCRIBE" {
+                    state.subscribed.insert(name);
+                } else {
+                    state.subscribed.remove(&name);
+                }
+                done("OK", &format!("{command} completed"))
+            }
+            "STATUS" => {
+                let state = self.state.lock().unwrap();
+                let Some(mailbox) = args_list.first().and_then(|name| state.mailboxes.get(name))
+                else {
+                    return done("NO", "[NONEXISTENT] Unknown mailbox");
+                };
+                let requested = args_list
+                    .get(1)
+                    .map(String::as_str)
+                    .unwrap_or("")
+                    .to_ascii_uppercase();
+                let items: Vec<String> = flag_list(&requested)
+                    .into_iter()
+                    .filter_map(|item| {
+                        let value = match item.as_str() {
+                            "MESSAGES" => mailbox.messages.len() as u32,
+                            "UNSEEN" => mailbox.unseen() as u32,
+                            "RECENT" => 0,
+                            "UIDNEXT" => mailbox.uid_next,
+                            "UIDVALIDITY" => mailbox.uid_validity,
+                            _ => return None,
+                        };
+                        Some(format!("{item} {value}"))
+                    })
+                    .collect();
+                let mut out =
+                    format!("* STATUS \"{}\" ({})\r\n", args_list[0], items.join(" ")).into_bytes();
+                out.extend(done("OK", "STATUS completed"));
+                out
+            }
+            "EXPUNGE" => {
+                let Some(selected) = self.selected.clone() else {
+                    return done("NO", "No mailbox selected");
+                };
+                let mut state = self.state.lock().unwrap();
+                let mailbox = state
+                    .mailboxes
+                    .get_mut(&selected)
+                    .expect("selected mailbox");
+                let deleted: Vec<usize> = (0..mailbox.messages.len())
+                    .filter(|&i| mailbox.messages[i].flags.iter().any(|f| f == "\\Deleted"))
+                    .collect();
+                let (_, mut out) = mailbox.expunge(&deleted);
+                out.extend(done("OK", "EXPUNGE completed"));
+                out
+            }
+            "UID" => {
+                let (sub, rest) = args.split_once(' ').unwrap_or((args, ""));
+                match self.uid_command(&sub.to_ascii_uppercase(), rest) {
+                    Ok(mut out) => {
+                        out.extend(done("OK", &format!("UID {sub} completed")));
+                        out
+                    }
+                    Err((status, text)) => done(status, &text),
+                }
+            }
+            _ => done("BAD", &format!("Unknown command {command}")),
+        }
+    }
+
+    fn select(&mut self, tag: &str, args: &[String]) -> Vec<u8> {
+        let state = self.state.lock().unwrap();
+        let Some((name, mailbox)) = args
+            .first()
+            .and_then(|name| state.mailboxes.get_key_value(name))
+        else {
+            self.selected = None;
+            return format!("{tag} NO [NONEXISTENT] Unknown mailbox\r\n").into_bytes();
+        };
+        self.selected = Some(name.clone());
+        format!(
+            "* FLAGS (\\Answered \\Flagged \\Deleted \\Seen \\Draft)\r\n\
+             * OK [PERMANENTFLAGS (\\Answered \\Flagged \\Deleted \\Seen \\Draft \\*)] Flags permitted\r\n\
+             * {} EXISTS\r\n\
+             * 0 RECENT\r\n\
+             * OK [UIDVALIDITY {}] UIDs valid\r\n\
+             * OK [UIDNEXT {}] Predicted next UID\r\n\
+             {tag} OK [READ-WRITE] SELECT completed\r\n",
+            mailbox.messages.len(),
+            mailbox.uid_validity,
+            mailbox.uid_next,
+        )
+        .into_bytes()
+    }
+
+    /// Untagged responses for a `UID` command, or the tagged failure.
+    fn uid_command(&mut self, sub: &str, args: &str) -> Result<Vec<u8>, (&'static str, String)> {
+        let selected = self
+            .selected
+            .clone()
+            .ok_or(("NO", "No mailbox selected".to_string()))?;
+        let (set, rest) = args.split_once(' ').unwrap_or((args, ""));
+        let mut state = self.state.lock().unwrap();
+        let mut out = Vec::new();
+
+        match sub {
+            "FETCH" => {
+                if self.options.hide_fetch_from_async_imap && !rest.starts_with('(') {
+                    return Ok(out);
+                }
+                let items = rest.to_ascii_uppercase();
+                let mailbox = &state.mailboxes[&selected];
+                for i in mailbox.matching(set) {
+                    let message = &mailbox.messages[i];
+                    let mut parts = vec![format!("UID {}", message.uid)];
+                    if items.contains("FLAGS") {
+                        parts.push(format!("FLAGS ({})", message.flags.join(" ")));
+                    }
+                    if items.contains("INTERNALDATE") {
+                        parts.push(format!("INTERNALDATE \"{INTERNAL_DATE}\""));
+                    }
+                    if items.contains("RFC822.SIZE") {
+                        parts.push(format!("RFC822.SIZE {}", message.body.len()));
+                    }
+                    if items.contains("ENVELOPE") {
+                        parts.push(format!("ENVELOPE {}", envelope(&message.body)));
+                    }
+                    if items.contains("BODYSTRUCTURE") {
+                        // Every test message is a single text part
+                        let lines = message.body.split(|&b| b == b'\n').count();
+                        parts.push(format!("BODYSTRUCTURE (\"text\" \"plain\" (\"charset\" \"utf-8\") NIL NIL \"7bit\" {} {lines} NIL NIL NIL NIL)", message.body.len()));
+                    }
+                    if items.contains("BODY[]") || items.contains("BODY.PEEK[]") {
+                        out.extend(
+                            format!(
+                                "* {} FETCH ({} BODY[] {{{}}}\r\n",
+                                i + 1,
+                                parts.join(" "),
+                                message.body.len()
+                            )
+                            .into_bytes(),
+                        );
+                        out.extend(&message.body);
+                        out.extend(b")\r\n");
+                    } else {
+                        out.extend(
+                            format!("* {} FETCH ({})\r\n", i + 1, parts.join(" ")).into_bytes(),
+                        );
+                    }
+                }
+            }
+            "STORE" => {
+                let (operation, flags) = rest.split_once(' ').unwrap_or((rest, ""));
+                let operation = operation.to_ascii_uppercase();
+                let flags = flag_list(flags);
+                let mailbox = state
+                    .mailboxes
+                    .get_mut(&selected)
+                    .expect("selected mailbox");
+                for i in mailbox.matching(set) {
+                    let message = &mut mailbox.messages[i];
+                    match operation.trim_end_matches(".SILENT") {
+                        "+FLAGS" => {
+                            for flag in &flags {
+                                if !message.flags.contains(flag) {
+                                    message.flags.push(flag.clone());
+                                }
+                            }
+                        }
+                        "-FLAGS" => message.flags.retain(|f| !flags.contains(f)),
+                        "FLAGS" => message.flags = flags.clone(),
+                        _ => return Err(("BAD", format!("Unknown STORE operation {operation}"))),
+                    }
+                    if !operation.ends_with(".SILENT") {
+                        out.extend(
+                            format!(
+                                "* {} FETCH (UID {} FLAGS ({}))\r\n",
+                                i + 1,
+                                message.uid,
+                                message.flags.join(" ")
+                            )
+                            .into_bytes(),
+                        );
+                    }
+                }
+            }
+            "SEARCH" => {
+                let mailbox = &state.mailboxes[&selected];
+                let indexes = if args.eq_ignore_ascii_case("ALL") {
+                    (0..mailbox.messages.len()).collect()
+                } else if args.starts_with(|c: char| c.is_ascii_digit()) {
+                    mailbox.matching(set)
+                } else {
+                    return Err(("BAD", format!("Unsupported search criteria {args}")));
+                };
+                let uids: String = indexes
+                    .into_iter()
+                    .map(|i| format!(" {}", mailbox.messages[i].uid))
+                    .collect();
+                out.extend(format!("* SEARCH{uids}\r\n").into_bytes());
+            }
+            "COPY" | "MOVE" => {
+                if sub == "MOVE" && self.options.no_move {
+                    return Err(("BAD", "Unknown command UID MOVE".to_string()));
+                }
+                let destination = arguments(rest).into_iter().next().unwrap_or_default();
+                if !state.mailboxes.contains_key(&destination) {
+                    return Err(("NO", "[TRYCREATE] Unknown mailbox".to_string()));
+                }
+                let source = state
+                    .mailboxes
+                    .get_mut(&selected)
+                    .expect("selected mailbox");
+                let indexes = source.matching(set);
+                let messages: Vec<(Vec<String>, Vec<u8>)> = if sub == "MOVE" {
+                    let (removed, expunged) = source.expunge(&indexes);
+                    out.extend(expunged);
+                    removed.into_iter().map(|m| (m.flags, m.body)).collect()
+                } else {
+                    indexes
+                        .iter()
+                        .map(|&i| {
+                            (
+                                source.messages[i].flags.clone(),
+                                source.messages[i].body.clone(),
+                            )
+                        })
+                        .collect()
+                };
+                let destination = state
+                    .mailboxes
+                    .get_mut(&destination)
+                    .expect("checked above");
+                for (flags, body) in messages {
+                    destination.add(flags, body);
+                }
+            }
+            _ => return Err(("BAD", format!("Unknown command UID {sub}"))),
+        }
+        Ok(out)
+    }
+
+    /// `APPEND mailbox [(flags)] [date] {size}` followed by the literal.
+    async fn append(
+        &mut self,
+        conn: &mut BufReader<ImapStream>,
+        tag: &str,
+        args: &str,
+    ) -> std::io::Result<Vec<u8>> {
+        let Some((prefix, literal)) = args.rsplit_once('{') else {
+            return Ok(format!("{tag} BAD Missing message literal\r\n").into_bytes());
+        };
+        let literal = literal.trim_end_matches('}');
+        let non_sync = literal.ends_with('+');
+        let Ok(size) = literal.trim_end_matches('+').parse::<usize>() else {
+            return Ok(format!("{tag} BAD Invalid literal\r\n").into_bytes());
+        };
+
+        if !non_sync {
+            write(conn, b"+ Ready for literal data\r\n").await?;
+        }
+        let mut body = vec![0u8; size];
+        conn.read_exact(&mut body).await?;
+        let mut end = String::new();
+        conn.read_line(&mut end).await?;
+
+        let args = arguments(prefix);
+        let flags = args
+            .iter()
+            .skip(1)
+            .find(|a| a.starts_with('('))
+            .map(|a| flag_list(a))
+            .unwrap_or_default();
+        let mut state = self.state.lock().unwrap();
+        let Some(mailbox) = args.first().and_then(|name| state.mailboxes.get_mut(name)) else {
+            return Ok(format!("{tag} NO [TRYCREATE] Unknown mailbox\r\n").into_bytes());
+        };
+        let uid = mailbox.add(flags, body);
+        Ok(format!(
+            "{tag} OK [APPENDUID {} {uid}] APPEND completed\r\n",
+            mailbox.uid_validity
+        )
+        .into_bytes())
+    }
+}
+
+async fn write(conn: &mut BufReader<ImapStream>, bytes: &[u8]) -> std::io::Result<()> {
+    let stream = conn.get_mut();
+    stream.write_all(bytes).await?;
+    stream.flush().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_uid_set() {
+        assert!(in_uid_set("1:3,7", 2, 10));
+        assert!(in_uid_set("1:3,7", 7, 10));
+        assert!(!in_uid_set("1:3,7", 5, 10));
+        assert!(in_uid_set("8:*", 10, 10));
+        // n:* includes the highest UID even when n is above it
+        assert!(in_uid_set("12:*", 10, 10));
+    }
+
+    #[test]
+    fn test_arguments() {
+        assert_eq!(
+            arguments(r#""Sent Items" (\Seen \Draft) "a \"b\"" atom"#),
+            vec!["Sent Items", r"(\Seen \Draft)", r#"a "b""#, "atom"]
+        );
+    }
+}
@@ -0,0 +1,610 @@
+use tauri::Manager;
+
+use super::{app_with_account, ImapServerOptions, TestImapServer, ACCOUNT_ID};
+use crate::commands;
+use crate::imap::client as imap_client;
+use crate::imap::session_pool::ImapSessionManager;
+use crate::imap::types::DeltaCheckRequest;
+use crate::settings::{SettingsState, READ_ONLY_ERROR};
+
+fn sample_message(subject: &str) -> Vec<u8> {
+    format!(
+        "From: Alice <alice@example.com>\r\n\
+         To: user@example.com\r\n\
+         Subject: {subject}\r\n\
+         Date: Mon, 16 Feb 2026 12:00:00 +0000\r\n\
+         Message-ID: <{}@example.com>\r\n\
+         \r\n\
+         Hello from {subject}.\r\n",
+        subject.replace(' ', "-").to_lowercase()
+    )
+    .into_bytes()
+}
+
+async fn plain_server() -> TestImapServer {
+    TestImapServer::start(ImapServerOptions::default()).await
+}
+
+#[tokio::test]
+async fn test_list_folders() {
+    let server = plain_server().await;
+    server.add_message("INBOX", &[], &sample_message("Unread"));
+    server.add_message("INBOX", &["\\Seen"], &sample_message("Read"));
+
+    let app = app_with_account(server.config("none"));
+
+    let folders = commands::imap_list_folders(
+        ACCOUNT_ID.to_string(),
+        app.state(),
+        app.state(),
+        app.state(),
+    )
+    .await
+    .unwrap();
+
+    let inbox = folders.iter().find(|f| f.raw_path == "INBOX").unwrap();
+    assert_eq!((inbox.exists, inbox.unseen), (2, 1));
+    let sent = folders.iter().find(|f| f.raw_path == "Sent").unwrap();
+    assert_eq!(sent.special_use.as_deref(), Some("\\Sent"));
+}
+
+#[tokio::test]
+async fn test_folder_management() {
+    let server = plain_server().await;
+    let app = app_with_account(server.config("none"));
+    let path = "Entwürfe & Notizen".to_string();
+
+    // Names go to the server in modified UTF-7
+    let raw = commands::create_folder(&app.state(), &app.state(), &app.state(), ACCOUNT_ID, &path)
+        .await
+        .unwrap();
+    assert_eq!(raw, "Entw&APw-rfe &- Notizen");
+    assert!(server.mailbox_names().contains(&raw));
+    let err =
+        commands::create_folder(&app.state(), &app.state(), &app.state(), ACCOUNT_ID, &path).await;
+    assert!(err.is_err());
+
+    commands::subscribe_folder(&app.state(), &app.state(), &app.state(), ACCOUNT_ID, &path)
+        .await
+        .unwrap();
+    assert_eq!(server.subscribed(), vec![raw.clone()]);
+    commands::unsubscribe_folder(&app.state(), &app.state(), &app.state(), ACCOUNT_ID, &path)
+        .await
+        .unwrap();
+    assert!(server.subscribed().is_empty());
+
+    let renamed = commands::rename_folder(
+        &app.state(),
+        &app.state(),
+        &app.state(),
+        ACCOUNT_ID,
+        &path,
+        "Archive/Größe",
+    )
+    .await
+    .unwrap();
+    assert_eq!(renamed, "Archive/Gr&APYA3w-e");
+    assert!(server.mailbox_names().contains(&renamed));
+    assert!(!server.mailbox_names().contains(&raw));
+
+    commands::delete_folder(
+        &app.state(),
+        &app.state(),
+        &app.state(),
+        ACCOUNT_ID,
+        "Archive/Größe",
+    )
+    .await
+    .unwrap();
+    assert!(!server.mailbox_names().contains(&renamed));
+}
+
+#[tokio::test]
+async fn test_wrong_password_is_rejected() {
+    let server = plain_server().await;
+    let mut config = server.config("none");
+    config.password = "wrong".to_string();
+
+    let err = imap_client::connect(&config).await.err().unwrap();
+    assert!(err.contains("Login failed"), "{err}");
+}
+
+#[tokio::test]
+async fn test_fetch_messages() {
+    let server = plain_server().await;
+    let first = server.add_message("INBOX", &["\\Seen", "\\Flagged"], &sample_message("First"));
+    let second = server.add_message("INBOX", &[], &sample_message("Second"));
+
+    let app = app_with_account(server.config("none"));
+
+    let result = commands::imap_fetch_messages(
+        ACCOUNT_ID.to_string(),
+        "INBOX".to_string(),
+        vec![first, second],
+        app.state(),
+        app.state(),
+        app.state(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(result.folder_status.uidvalidity, 1001);
+    assert_eq!(result.folder_status.exists, 2);
+    assert_eq!(result.messages.len(), 2);
+    let message = &result.messages[0];
+    assert_eq!(message.uid, first);
+    assert_eq!(message.subject.as_deref(), Some("First"));
+    assert!(message.is_read && message.is_starred);
+    assert!(!result.messages[1].is_read);
+}
+
+#[tokio::test]
+async fn test_fetch_headers() {
+    let server = plain_server().await;
+    let uid = server.add_message("INBOX", &["\\Flagged"], &sample_message("Headers Only"));
+    let app = app_with_account(server.config("none"));
+
+    let headers = commands::imap_fetch_headers(
+        ACCOUNT_ID.to_string(),
+        "INBOX".to_string(),
+        vec![uid],
+        app.state(),
+        app.state(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(headers.len(), 1);
+    let header = &headers[0];
+    assert_eq!(header.uid, uid);
+    assert_eq!(header.subject.as_deref(), Some("Headers Only"));
+    assert_eq!(header.from_address.as_deref(), Some("alice@example.com"));
+    assert_eq!(header.from_name.as_deref(), Some("Alice"));
+    assert_eq!(header.to_addresses.as_deref(), Some("user@example.com"));
+    assert_eq!(
+        header.message_id.as_deref(),
+        Some("headers-only@example.com")
+    );
+    assert_eq!(header.date, 1771243200);
+    assert_eq!(
+        header.raw_size as usize,
+        sample_message("Headers Only").len()
+    );
+    assert!(header.is_starred && !header.is_read);
+    assert!(!header.has_attachments);
+    // The bodies weren't downloaded
+    assert!(server.commands().iter().all(|c| !c.contains("BODY.PEEK[]")));
+}
+
+#[tokio::test]
+async fn test_fetch_bodystructure() {
+    let server = plain_server().await;
+    let uid = server.add_message("INBOX", &[], &sample_message("Structure"));
+    let app = app_with_account(server.config("none"));
+
+    // A plain text message has no attachments
+    let attachments = commands::imap_fetch_bodystructure(
+        ACCOUNT_ID.to_string(),
+        "INBOX".to_string(),
+        uid,
+        app.state(),
+        app.state(),
+    )
+    .await
+    .unwrap();
+    assert!(attachments.is_empty());
+    assert!(server.commands().iter().all(|c| !c.contains("BODY.PEEK[]")));
+
+    let missing = commands::imap_fetch_bodystructure(
+        ACCOUNT_ID.to_string(),
+        "INBOX".to_string(),
+        uid + 1,
+        app.state(),
+        app.state(),
+    )
+    .await;
+    assert!(missing.is_err());
+}
+
+#[tokio::test]
+async fn test_set_flags() {
+    let server = plain_server().await;
+    let uid = server.add_message("INBOX", &[], &sample_message("Flags"));
+    let app = app_with_account(server.config("none"));
+
+    commands::set_flags(
+        &app.state(),
+        &app.state(),
+        &app.state(),
+        ACCOUNT_ID,
+        "INBOX",
+        &[uid],
+        &["Seen".to_string(), "Flagged".to_string()],
+        true,
+        &[],
+    )
+    .await
+    .unwrap();
+    assert_eq!(server.flags("INBOX", uid), vec!["\\Seen", "\\Flagged"]);
+
+    commands::set_flags(
+        &app.state(),
+        &app.state(),
+        &app.state(),
+        ACCOUNT_ID,
+        "INBOX",
+        &[uid],
+        &["Flagged".to_string()],
+        false,
+        &[],
+    )
+    .await
+    .unwrap();
+    assert_eq!(server.flags("INBOX", uid), vec!["\\Seen"]);
+
+    commands::set_flags(
+        &app.state(),
+        &app.state(),
+        &app.state(),
+        ACCOUNT_ID,
+        "INBOX",
+        &[uid],
+        &[],
+        true,
+        &["$label2".to_string(), "Travel".to_string()],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        server.flags("INBOX", uid),
+        vec!["\\Seen", "$label2", "Travel"]
+    );
+    let fetched = commands::imap_fetch_messages(
+        ACCOUNT_ID.to_string(),
+        "INBOX".to_string(),
+        vec![uid],
+        app.state(),
+        app.state(),
+        app.state(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(fetched.messages[0].keywords, vec!["$label2", "Travel"]);
+
+    let err = commands::set_flags(
+        &app.state(),
+        &app.state(),
+        &app.state(),
+        ACCOUNT_ID,
+        "INBOX",
+        &[uid],
+        &[],
+        true,
+        &["two words".to_string()],
+    )
+    .await
+    .unwrap_err();
+    assert!(err.contains("two words"));
+}
+
+#[tokio::test]
+async fn test_commands_reuse_session() {
+    let server = plain_server().await;
+    let uid = server.add_message("INBOX", &[], &sample_message("Reused"));
+    let app = app_with_account(server.config("none"));
+
+    commands::imap_list_folders(
+        ACCOUNT_ID.to_string(),
+        app.state(),
+        app.state(),
+        app.state(),
+    )
+    .await
+    .unwrap();
+    commands::imap_fetch_messages(
+        ACCOUNT_ID.to_string(),
+        "INBOX".to_string(),
+        vec![uid],
+        app.state(),
+        app.state(),
+        app.state(),
+    )
+    .await
+    .unwrap();
+
+    let logins = |commands: &[String]| commands.iter().filter(|c| c.starts_with("LOGIN")).count();
+    assert_eq!(logins(&server.commands()), 1);
+
+    app.state::<ImapSessionManager>()
+        .close(Some(ACCOUNT_ID))
+        .await;
+    assert!(server.commands().iter().any(|c| c == "LOGOUT"));
+
+    commands::imap_list_folders(
+        ACCOUNT_ID.to_string(),
+        app.state(),
+        app.state(),
+        app.state(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(logins(&server.commands()), 2);
+}
+
+#[tokio::test]
+async fn test_read_only_mode_refuses_changes() {
+    let server = plain_server().await;
+    let uid = server.add_message("INBOX", &[], &sample_message("Read only"));
+    let app = app_with_account(server.config("none"));
+    app.state::<SettingsState>()
+        .update(|s| s.read_only = true)
+        .unwrap();
+
+    let err = commands::set_flags(
+        &app.state(),
+        &app.state(),
+        &app.state(),
+        ACCOUNT_ID,
+        "INBOX",
+        &[uid],
+        &["Seen".to_string()],
+        true,
+        &[],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err, READ_ONLY_ERROR);
+    let err = commands::move_messages(
+        &app.state(),
+        &app.state(),
+        &app.state(),
+        ACCOUNT_ID,
+        "INBOX",
+        &[uid],
+        "Archive",
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err, READ_ONLY_ERROR);
+
+    assert!(server.flags("INBOX", uid).is_empty());
+    assert_eq!(server.uids("INBOX"), vec![uid]);
+}
+
+#[tokio::test]
+async fn test_delta_check_reports_status() {
+    let server = plain_server().await;
+    let old = server.add_message("INBOX", &[], &sample_message("Old"));
+    server.add_message("Sent", &["\\Seen"], &sample_message("Sent"));
+    let app = app_with_account(server.config("none"));
+    let new = server.add_message("INBOX", &[], &sample_message("New"));
+
+    let results = commands::imap_delta_check(
+        ACCOUNT_ID.to_string(),
+        vec![
+            DeltaCheckRequest {
+                folder: "INBOX".to_string(),
+                last_uid: old,
+                uidvalidity: 1001,
+            },
+            DeltaCheckRequest {
+                folder: "Sent".to_string(),
+                last_uid: 1,
+                uidvalidity: 1002,
+            },
+        ],
+        app.state(),
+        app.state(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(results[0].new_uids, vec![new]);
+    let inbox = results[0].folder_status.as_ref().unwrap();
+    assert_eq!((inbox.exists, inbox.unseen, inbox.uidnext), (2, 2, new + 1));
+
+    // Nothing new in Sent: STATUS alone answers it
+    assert!(results[1].new_uids.is_empty());
+    assert_eq!(results[1].folder_status.as_ref().unwrap().unseen, 0);
+    // One connection, and folders are only opened read-only
+    let commands = server.commands();
+    assert_eq!(
+        commands.iter().filter(|c| c.starts_with("LOGIN")).count(),
+        1
+    );
+    assert!(!commands.iter().any(|c| c.starts_with("SELECT")));
+    let examined: Vec<_> = commands
+        .iter()
+        .filter(|c| c.starts_with("EXAMINE"))
+        .collect();
+    assert_eq!(examined.len(), 1);
+    assert!(examined[0].contains("INBOX"));
+}
+
+#[tokio::test]
+async fn test_move_messages() {
+    let server = plain_server().await;
+    let uid = server.add_message("INBOX", &["\\Seen"], &sample_message("Move me"));
+    let kept = server.add_message("INBOX", &[], &sample_message("Stay"));
+
+    let app = app_with_account(server.config("none"));
+
+    commands::move_messages(
+        &app.state(),
+        &app.state(),
+        &app.state(),
+        ACCOUNT_ID,
+        "INBOX",
+        &[uid],
+        "Archive",
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(server.uids("INBOX"), vec![kept]);
+    assert_eq!(server.uids("Archive").len(), 1);
+    assert!(server.commands().iter().any(|c| c.starts_with("UID MOVE")));
+}
+
+#[tokio::test]
+async fn test_move_falls_back_to_copy_and_expunge() {
+    let server = TestImapServer::start(ImapServerOptions {
+        no_move: true,
+        ..Default::default()
+    })
+    .await;
+    let uid = server.add_message("INBOX", &[], &sample_message("Copy me"));
+
+    let app = app_with_account(server.config("none"));
+
+    commands::move_messages(
+        &app.state(),
+        &app.state(),
+        &app.state(),
+        ACCOUNT_ID,
+        "INBOX",
+        &[uid],
+        "Trash",
+    )
+    .await
+    .unwrap();
+
+    assert!(server.uids("INBOX").is_empty());
+    let trashed = server.uids("Trash");
+    assert_eq!(trashed.len(), 1);
+    // The copy doesn't carry the \Deleted flag set on the original
+    assert!(server.flags("Trash", trashed[0]).is_empty());
+    let commands = server.commands();
+    assert!(commands.iter().any(|c| c.starts_with("UID COPY")));
+    assert!(commands.iter().any(|c| c == "EXPUNGE"));
+}
+
+#[tokio::test]
+async fn test_append_message() {
+    let server = plain_server().await;
+    let raw = sample_message("Sent copy");
+
+    let mut session = imap_client::connect(&server.config("none")).await.unwrap();
+    imap_client::append_message(&mut session, "Sent", Some("(\\Seen)"), &raw)
+        .await
+        .unwrap();
+    let _ = session.logout().await;
+
+    let uids = server.uids("Sent");
+    assert_eq!(uids.len(), 1);
+    assert_eq!(server.flags("Sent", uids[0]), vec!["\\Seen"]);
+    assert_eq!(server.body("Sent", uids[0]).unwrap(), raw);
+}
+
+#[tokio::test]
+async fn test_starttls() {
+    let server = plain_server().await;
+    let uid = server.add_message("INBOX", &[], &sample_message("Over STARTTLS"));
+    let app = app_with_account(server.config("starttls"));
+
+    let result = commands::imap_fetch_messages(
+        ACCOUNT_ID.to_string(),
+        "INBOX".to_string(),
+        vec![uid],
+        app.state(),
+        app.state(),
+        app.state(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.messages[0].subject.as_deref(), Some("Over STARTTLS"));
+    assert!(commands::imap_list_folders(
+        ACCOUNT_ID.to_string(),
+        app.state(),
+        app.state(),
+        app.state()
+    )
+    .await
+    .is_ok());
+
+    let commands = server.commands();
+    let starttls = commands.iter().position(|c| c == "STARTTLS").unwrap();
+    let login = commands
+        .iter()
+        .position(|c| c.starts_with("LOGIN"))
+        .unwrap();
+    assert!(starttls < login, "credentials were sent before STARTTLS");
+}
+
+#[tokio::test]
+async fn test_implicit_tls() {
+    let server = TestImapServer::start(ImapServerOptions {
+        implicit_tls: true,
+        ..Default::default()
+    })
+    .await;
+    server.add_message("INBOX", &[], &sample_message("Over TLS"));
+
+    let app = app_with_account(server.config("tls"));
+
+    let folders = commands::imap_list_folders(
+        ACCOUNT_ID.to_string(),
+        app.state(),
+        app.state(),
+        app.state(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        folders
+            .iter()
+            .find(|f| f.raw_path == "INBOX")
+            .unwrap()
+            .exists,
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_fetch_falls_back_to_raw_client() {
+    let server = TestImapServer::start(ImapServerOptions {
+        hide_fetch_from_async_imap: true,
+        ..Default::default()
+    })
+    .await;
+    let first = server.add_message("INBOX", &["\\Seen"], &sample_message("Raw one"));
+    let second = server.add_message("INBOX", &["\\Draft"], &sample_message("Raw two"));
+
+    let app = app_with_account(server.config("none"));
+
+    let result = commands::imap_fetch_messages(
+        ACCOUNT_ID.to_string(),
+        "INBOX".to_string(),
+        vec![first, second],
+        app.state(),
+        app.state(),
+        app.state(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(result.messages.len(), 2);
+    assert_eq!(result.messages[0].subject.as_deref(), Some("Raw one"));
+    assert!(result.messages[0].is_read);
+    assert!(result.messages[1].is_draft);
+    assert_eq!(result.folder_status.uidvalidity, 1001);
+    assert!(server
+        .commands()
+        .iter()
+        .any(|c| c.ends_with("(UID FLAGS INTERNALDATE BODY.PEEK[])")));
+}
+
+#[tokio::test]
+async fn test_raw_fetch_over_starttls() {
+    let server = plain_server().await;
+    let uid = server.add_message("INBOX", &["\\Flagged"], &sample_message("Raw STARTTLS"));
+
+    let result =
+        imap_client::raw_fetch_messages(&server.config("starttls"), "INBOX", &uid.to_string())
+            .await
+            .unwrap();
+
+    assert_eq!(result.messages.len(), 1);
+    assert_eq!(result.messages[0].subject.as_deref(), Some("Raw STARTTLS"));
+    assert!(result.messages[0].is_starred);
+}
@@ -0,0 +1,66 @@
+//! In-process IMAP and SMTP servers for integration tests.
+//!
+//! Only built for `cargo test --features integration-tests`. The servers
+//! listen on `127.0.0.1` with an OS-assigned port, keep their mail in memory
+//! and implement just enough of each protocol for the client code paths we
+//! exercise: plain, implicit TLS and STARTTLS connections, folder listing,
+//! fetch, flags, move/copy, expunge, append and message submission. TLS uses
+//! a self-signed certificate, so test configs set `accept_invalid_certs`.
+
+mod imap_server;
+mod imap_tests;
+mod smtp_server;
+mod smtp_tests;
+
+pub(crate) use imap_server::{ImapServerOptions, TestImapServer};
+pub(crate) use smtp_server::{SmtpServerOptions, TestSmtpServer};
+
+use tauri::test::{mock_app, MockRuntime};
+use tauri::Manager;
+
+use crate::accounts::{AccountDefinition, AccountRegistry};
+use crate::identity::Identities;
+use crate::imap::folder_watch::FolderListCache;
+use crate::imap::session_pool::ImapSessionManager;
+use crate::imap::types::ImapConfig;
+use crate::settings::SettingsState;
+
+/// Credentials both test servers accept.
+pub(crate) const USERNAME: &str = "user@example.com";
+pub(crate) const PASSWORD: &str = "secret";
+
+/// Account id [`app_with_account`] registers its config under.
+pub(crate) const ACCOUNT_ID: &str = "test-account";
+
+/// A mock app whose account registry holds `config` as [`ACCOUNT_ID`], with
+/// default backend settings, an empty folder list cache, no registered
+/// identities and no pooled sessions, for calling
+/// commands that look credentials up by account:
+/// `commands::imap_list_folders(ACCOUNT_ID.to_string(), app.state(), app.state(), app.state())`.
+pub(crate) fn app_with_account(config: ImapConfig) -> tauri::App<MockRuntime> {
+    let app = mock_app();
+    let registry = AccountRegistry::default();
+    registry
+        .insert(AccountDefinition {
+            id: ACCOUNT_ID.to_string(),
+            imap: Some(config),
+            ..Default::default()
+        })
+        .expect("register test account");
+    app.manage(registry);
+    app.manage(SettingsState::default());
+    app.manage(FolderListCache::default());
+    app.manage(Identities::default());
+    app.manage(ImapSessionManager::default());
+    app
+}
+
+fn tls_acceptor() -> tokio_native_tls::TlsAcceptor {
+    let identity = native_tls::Identity::from_pkcs8(
+        include_bytes!("fixtures/localhost.crt"),
+        include_bytes!("fixtures/localhost.key"),
+    )
+    .expect("test TLS identity");
+    let acceptor = native_tls::TlsAcceptor::new(identity).expect("test TLS acceptor");
+    tokio_native_tls::TlsAcceptor::from(acceptor)
+}
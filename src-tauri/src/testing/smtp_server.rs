@@ -0,0 +1,268 @@
+//! Minimal in-memory SMTP submission server.
+//!
+//! Commands: EHLO/HELO, STARTTLS, AUTH PLAIN/LOGIN, MAIL, RCPT, DATA, RSET,
+//! NOOP and QUIT. Accepted messages are kept for tests to inspect.
+
+use std::sync::{Arc, Mutex};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_native_tls::TlsAcceptor;
+
+use super::{tls_acceptor, PASSWORD, USERNAME};
+use crate::imap::client::ImapStream;
+use crate::smtp::types::SmtpConfig;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct SmtpServerOptions {
+    /// Speak TLS from the first byte instead of offering STARTTLS.
+    pub implicit_tls: bool,
+}
+
+/// A message accepted with DATA, with its dot-stuffing removed.
+#[derive(Debug, Clone)]
+pub(crate) struct ReceivedMessage {
+    pub from: String,
+    pub recipients: Vec<String>,
+    pub data: Vec<u8>,
+}
+
+#[derive(Default)]
+struct State {
+    received: Vec<ReceivedMessage>,
+    commands: Vec<String>,
+}
+
+/// A running test SMTP server. It stops with the test's runtime.
+pub(crate) struct TestSmtpServer {
+    port: u16,
+    state: Arc<Mutex<State>>,
+}
+
+impl TestSmtpServer {
+    pub(crate) async fn start(options: SmtpServerOptions) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind test SMTP server");
+        let port = listener
+            .local_addr()
+            .expect("test SMTP server address")
+            .port();
+        let state = Arc::new(Mutex::new(State::default()));
+        let acceptor = tls_acceptor();
+
+        let server_state = Arc::clone(&state);
+        tokio::spawn(async move {
+            while let Ok((tcp, _)) = listener.accept().await {
+                tokio::spawn(serve(
+                    tcp,
+                    Arc::clone(&server_state),
+                    options,
+                    acceptor.clone(),
+                ));
+            }
+        });
+
+        Self { port, state }
+    }
+
+    /// Client config for this server. `security` must match how it was
+    /// started: "tls" for implicit TLS, otherwise "none" or "starttls".
+    pub(crate) fn config(&self, security: &str) -> SmtpConfig {
+        SmtpConfig {
+            host: "127.0.0.1".to_string(),
+            port: self.port,
+            security: security.to_string(),
+            username: USERNAME.to_string(),
+            password: PASSWORD.to_string(),
+            auth_method: "password".to_string(),
+            accept_invalid_certs: true,
+            rate_limit_per_minute: Some(0),
+        }
+    }
+
+    pub(crate) fn received(&self) -> Vec<ReceivedMessage> {
+        self.state.lock().unwrap().received.clone()
+    }
+
+    /// Every command line received so far (AUTH exchanges show only the
+    /// mechanism).
+    pub(crate) fn commands(&self) -> Vec<String> {
+        self.state.lock().unwrap().commands.clone()
+    }
+}
+
+async fn serve(
+    tcp: TcpStream,
+    state: Arc<Mutex<State>>,
+    options: SmtpServerOptions,
+    acceptor: TlsAcceptor,
+) -> std::io::Result<()> {
+    let stream = if options.implicit_tls {
+        ImapStream::Tls(acceptor.accept(tcp).await.map_err(std::io::Error::other)?)
+    } else {
+        ImapStream::Plain(tcp)
+    };
+    let mut conn = BufReader::new(stream);
+    write(&mut conn, "220 localhost ESMTP test server ready").await?;
+
+    let mut authenticated = false;
+    let mut from: Option<String> = None;
+    let mut recipients: Vec<String> = Vec::new();
+
+    loop {
+        let Some(line) = read_line(&mut conn).await? else {
+            return Ok(());
+        };
+        let (verb, args) = line.split_once(' ').unwrap_or((line.as_str(), ""));
+        let verb = verb.to_ascii_uppercase();
+        let logged = if verb == "AUTH" {
+            format!("AUTH {}", args.split_whitespace().next().unwrap_or(""))
+        } else {
+            line.clone()
+        };
+        state.lock().unwrap().commands.push(logged);
+
+        match verb.as_str() {
+            "EHLO" => {
+                from = None;
+                recipients.clear();
+                let starttls = if matches!(conn.get_ref(), ImapStream::Plain(_)) {
+                    "250-STARTTLS\r\n"
+                } else {
+                    ""
+                };
+                let reply =
+                    format!("250-localhost\r\n250-8BITMIME\r\n{starttls}250 AUTH PLAIN LOGIN");
+                write(&mut conn, &reply).await?;
+            }
+            "HELO" => write(&mut conn, "250 localhost").await?,
+            "STARTTLS" if matches!(conn.get_ref(), ImapStream::Plain(_)) => {
+                write(&mut conn, "220 2.0.0 Ready to start TLS").await?;
+                let ImapStream::Plain(tcp) = conn.into_inner() else {
+                    unreachable!("checked above");
+                };
+                let tls = acceptor.accept(tcp).await.map_err(std::io::Error::other)?;
+                conn = BufReader::new(ImapStream::Tls(tls));
+                authenticated = false;
+            }
+            "AUTH" => {
+                authenticated = authenticate(&mut conn, args).await?;
+                let reply = if authenticated {
+                    "235 2.7.0 Authentication successful"
+                } else {
+                    "535 5.7.8 Authentication credentials invalid"
+                };
+                write(&mut conn, reply).await?;
+            }
+            "MAIL" if !authenticated => {
+                write(&mut conn, "530 5.7.0 Authentication required").await?
+            }
+            "MAIL" => {
+                from = Some(angle_address(args));
+                recipients.clear();
+                write(&mut conn, "250 2.1.0 OK").await?;
+            }
+            "RCPT" if from.is_none() => write(&mut conn, "503 5.5.1 MAIL first").await?,
+            "RCPT" => {
+                recipients.push(angle_address(args));
+                write(&mut conn, "250 2.1.5 OK").await?;
+            }
+            "DATA" if recipients.is_empty() => write(&mut conn, "503 5.5.1 RCPT first").await?,
+            "DATA" => {
+                write(&mut conn, "354 End data with <CR><LF>.<CR><LF>").await?;
+                let data = read_data(&mut conn).await?;
+                state.lock().unwrap().received.push(ReceivedMessage {
+                    from: from.take().unwrap_or_default(),
+                    recipients: std::mem::take(&mut recipients),
+                    data,
+                });
+                write(&mut conn, "250 2.0.0 OK queued").await?;
+            }
+            "RSET" => {
+                from = None;
+                recipients.clear();
+                write(&mut conn, "250 2.0.0 OK").await?;
+            }
+            "NOOP" => write(&mut conn, "250 2.0.0 OK").await?,
+            "QUIT" => {
+                write(&mut conn, "221 2.0.0 Bye").await?;
+                return Ok(());
+            }
+            _ => write(&mut conn, "502 5.5.2 Command not implemented").await?,
+        }
+    }
+}
+
+/// Run an `AUTH PLAIN` or `AUTH LOGIN` exchange; true if the credentials
+/// match.
+async fn authenticate(conn: &mut BufReader<ImapStream>, args: &str) -> std::io::Result<bool> {
+    let (mechanism, initial) = args.split_once(' ').unwrap_or((args, ""));
+    let decode = |s: &str| {
+        STANDARD
+            .decode(s.trim())
+            .map(|b| String::from_utf8_lossy(&b).into_owned())
+            .unwrap_or_default()
+    };
+
+    match mechanism.to_ascii_uppercase().as_str() {
+        "PLAIN" => {
+            let response = if initial.is_empty() {
+                write(conn, "334 ").await?;
+                read_line(conn).await?.unwrap_or_default()
+            } else {
+                initial.to_string()
+            };
+            let plain = decode(&response);
+            let mut parts = plain.split('\0').skip(1);
+            Ok(parts.next() == Some(USERNAME) && parts.next() == Some(PASSWORD))
+        }
+        "LOGIN" => {
+            write(conn, &format!("334 {}", STANDARD.encode("Username:"))).await?;
+            let user = decode(&read_line(conn).await?.unwrap_or_default());
+            write(conn, &format!("334 {}", STANDARD.encode("Password:"))).await?;
+            let password = decode(&read_line(conn).await?.unwrap_or_default());
+            Ok(user == USERNAME && password == PASSWORD)
+        }
+        _ => Ok(false),
+    }
+}
+
+/// The address in `FROM:<a@b> BODY=8BITMIME` or `TO:<a@b>`.
+fn angle_address(args: &str) -> String {
+    args.split_once('<')
+        .and_then(|(_, rest)| rest.split_once('>'))
+        .map(|(address, _)| address.to_string())
+        .unwrap_or_default()
+}
+
+/// Read DATA content up to the lone `.` line, undoing dot-stuffing.
+async fn read_data(conn: &mut BufReader<ImapStream>) -> std::io::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    loop {
+        let mut line = Vec::new();
+        if conn.read_until(b'\n', &mut line).await? == 0 {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        if line == b".\r\n" {
+            return Ok(data);
+        }
+        let unstuffed = line.strip_prefix(b".").unwrap_or(&line[..]);
+        data.extend_from_slice(unstuffed);
+    }
+}
+
+async fn read_line(conn: &mut BufReader<ImapStream>) -> std::io::Result<Option<String>> {
+    let mut line = String::new();
+    if conn.read_line(&mut line).await? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line.trim_end_matches(['\r', '\n']).to_string()))
+}
+
+async fn write(conn: &mut BufReader<ImapStream>, reply: &str) -> std::io::Result<()> {
+    let stream = conn.get_mut();
+    stream.write_all(format!("{reply}\r\n").as_bytes()).await?;
+    stream.flush().await
+}
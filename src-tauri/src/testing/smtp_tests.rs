@@ -0,0 +1,79 @@
+use super::{SmtpServerOptions, TestSmtpServer};
+use crate::smtp::client as smtp_client;
+
+const RAW: &[u8] = b"From: user@example.com\r\n\
+To: bob@example.com\r\n\
+Cc: carol@example.com\r\n\
+Subject: Integration\r\n\
+\r\n\
+Hello Bob.\r\n\
+.A line that starts with a dot";
+
+#[tokio::test]
+async fn test_send_plain() {
+    let server = TestSmtpServer::start(SmtpServerOptions::default()).await;
+
+    let result = smtp_client::send_raw_bytes(&server.config("none"), RAW)
+        .await
+        .unwrap();
+    assert!(result.success);
+
+    let received = server.received();
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0].from, "user@example.com");
+    assert_eq!(
+        received[0].recipients,
+        vec!["bob@example.com", "carol@example.com"]
+    );
+    // Dot-stuffing is applied by the client and undone by the server; the
+    // client ends the last line before the terminating dot
+    assert_eq!(received[0].data, [RAW, b"\r\n"].concat());
+}
+
+#[tokio::test]
+async fn test_send_starttls() {
+    let server = TestSmtpServer::start(SmtpServerOptions::default()).await;
+
+    smtp_client::send_raw_bytes(&server.config("starttls"), RAW)
+        .await
+        .unwrap();
+
+    assert_eq!(server.received().len(), 1);
+    let commands = server.commands();
+    let starttls = commands.iter().position(|c| c == "STARTTLS").unwrap();
+    let auth = commands.iter().position(|c| c.starts_with("AUTH")).unwrap();
+    assert!(starttls < auth, "credentials were sent before STARTTLS");
+}
+
+#[tokio::test]
+async fn test_send_implicit_tls() {
+    let server = TestSmtpServer::start(SmtpServerOptions { implicit_tls: true }).await;
+
+    smtp_client::send_raw_bytes(&server.config("tls"), RAW)
+        .await
+        .unwrap();
+
+    assert_eq!(server.received().len(), 1);
+    assert!(!server.commands().iter().any(|c| c == "STARTTLS"));
+}
+
+#[tokio::test]
+async fn test_wrong_password_is_rejected() {
+    let server = TestSmtpServer::start(SmtpServerOptions::default()).await;
+    let mut config = server.config("none");
+    config.password = "wrong".to_string();
+
+    let err = smtp_client::send_raw_bytes(&config, RAW).await.unwrap_err();
+    assert!(err.contains("SMTP send error"), "{err}");
+    assert!(server.received().is_empty());
+}
+
+#[tokio::test]
+async fn test_connection() {
+    let server = TestSmtpServer::start(SmtpServerOptions::default()).await;
+
+    let result = smtp_client::test_connection(&server.config("starttls"))
+        .await
+        .unwrap();
+    assert!(result.success);
+}
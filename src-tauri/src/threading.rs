@@ -0,0 +1,491 @@
+//! Conversation threading.
+//!
+//! Messages are grouped with the JWZ algorithm
+//! (<https://www.jwz.org/doc/threading.html>) over Message-ID, In-Reply-To
+//! and References, and roots left over are merged by normalized subject.
+//! A thread's id is derived from its root Message-ID, so the same
+//! conversation gets the same id whichever of its messages a sync sees.
+//! `cache_thread_messages` additionally reuses the thread a message (or one
+//! it refers to) already has in the local cache, so replies synced later
+//! join their thread even when the root Message-ID comes out differently.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use sqlx::SqlitePool;
+use tauri::State;
+use tauri_plugin_sql::{DbInstances, DbPool};
+
+use crate::profiles::Profiles;
+
+/// The headers threading needs from a message.
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadableMessage {
+    /// Local message id.
+    pub id: String,
+    /// RFC 2822 Message-ID, without angle brackets.
+    pub message_id: String,
+    pub in_reply_to: Option<String>,
+    /// Space-separated Message-IDs.
+    pub references: Option<String>,
+    pub subject: Option<String>,
+    /// Unix timestamp in milliseconds.
+    pub date: i64,
+}
+
+/// A conversation: its id and its messages' local ids, oldest first.
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct Thread {
+    pub thread_id: String,
+    pub message_ids: Vec<String>,
+}
+
+/// Reply and forward prefixes in the locales `utils/subject.ts` knows.
+const REPLY_PREFIXES: &[&str] = &[
+    "re", "fwd", "fw", "aw", "wg", "sv", "vs", "vb", "tr", "rv", "antw", "odp", "ynt", "回复",
+    "回覆", "答复", "答覆", "转发", "轉寄", "轉發", "返信", "転送",
+];
+
+/// Length of a leading "[list-tag] ", if any.
+fn list_tag_len(s: &str) -> Option<usize> {
+    let rest = s.strip_prefix('[')?;
+    let close = rest.find(']')?;
+    let after = &rest[close + 1..];
+    Some(s.len() - after.trim_start().len())
+}
+
+/// Length of a leading "Re: ", "Fwd[2]: ", "AW： " and the like, if any.
+fn reply_prefix_len(s: &str) -> Option<usize> {
+    REPLY_PREFIXES.iter().find_map(|prefix| {
+        let head = s.get(..prefix.len())?;
+        if !head.eq_ignore_ascii_case(prefix) {
+            return None;
+        }
+        let mut rest = s[prefix.len()..].trim_start();
+        // Counters like "[2]", "(2)" or "^2"
+        for (open, close) in [("[", Some(']')), ("(", Some(')')), ("^", None)] {
+            if let Some(counter) = rest.strip_prefix(open) {
+                let digits = counter.len()
+                    - counter
+                        .trim_start_matches(|c: char| c.is_ascii_digit())
+                        .len();
+                let after = &counter[digits..];
+                let after = match close {
+                    Some(close) => after.strip_prefix(close),
+                    None => Some(after),
+                };
+                if let Some(after) = after.filter(|_| digits > 0) {
+                    rest = after;
+                    break;
+                }
+            }
+        }
+        let rest = rest.trim_start().strip_prefix([':', '：'])?;
+        Some(s.len() - rest.trim_start().len())
+    })
+}
+
+/// Strip reply/forward prefixes and list tags and collapse whitespace, like
+/// `normalizeSubject` in `utils/subject.ts`.
+pub fn normalize_subject(subject: Option<&str>) -> String {
+    let collapsed = subject
+        .unwrap_or_default()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut s = collapsed.as_str();
+    loop {
+        let mut changed = false;
+        if let Some(len) = list_tag_len(s) {
+            s = &s[len..];
+            changed = true;
+        }
+        if let Some(len) = reply_prefix_len(s) {
+            s = &s[len..];
+            changed = true;
+        }
+        if !changed {
+            break;
+        }
+    }
+    s.trim().to_string()
+}
+
+/// The Message-IDs in a References or In-Reply-To header.
+pub fn parse_references(header: Option<&str>) -> Vec<String> {
+    let header = header.unwrap_or_default();
+    let mut ids: Vec<String> = header
+        .split('<')
+        .skip(1)
+        .filter_map(|part| part.split_once('>'))
+        .map(|(id, _)| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .collect();
+    if ids.is_empty() {
+        ids = header
+            .split_whitespace()
+            .map(|token| {
+                token
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string()
+            })
+            .filter(|id| !id.is_empty())
+            .collect();
+    }
+    ids
+}
+
+/// The thread id for a root Message-ID: a djb2 hash over its UTF-16 code
+/// units, matching the ids the frontend used to generate.
+pub fn thread_id_for(root_message_id: &str) -> String {
+    let hash = root_message_id.encode_utf16().fold(5381u32, |hash, unit| {
+        (hash << 5).wrapping_add(hash).wrapping_add(u32::from(unit))
+    });
+    format!("imap-thread-{hash:x}")
+}
+
+/// A node in the threading forest; phantoms stand for referenced messages
+/// that weren't given.
+struct Container {
+    message_id: String,
+    message: Option<usize>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+}
+
+/// Containers in the order their Message-IDs were first seen.
+struct Forest<'a> {
+    messages: &'a [ThreadableMessage],
+    containers: Vec<Container>,
+    by_id: HashMap<String, usize>,
+}
+
+impl<'a> Forest<'a> {
+    fn container(&mut self, message_id: &str) -> usize {
+        if let Some(&index) = self.by_id.get(message_id) {
+            return index;
+        }
+        self.containers.push(Container {
+            message_id: message_id.to_string(),
+            message: None,
+            parent: None,
+            children: Vec::new(),
+        });
+        self.by_id
+            .insert(message_id.to_string(), self.containers.len() - 1);
+        self.containers.len() - 1
+    }
+
+    /// Whether `ancestor` is `node` or above it.
+    fn is_ancestor(&self, node: usize, ancestor: usize) -> bool {
+        let mut current = Some(node);
+        while let Some(index) = current {
+            if index == ancestor {
+                return true;
+            }
+            current = self.containers[index].parent;
+        }
+        false
+    }
+
+    /// Make `parent` the parent of `child` unless that would form a cycle.
+    fn link(&mut self, parent: usize, child: usize) {
+        if self.is_ancestor(parent, child) || self.containers[child].parent == Some(parent) {
+            return;
+        }
+        if let Some(old) = self.containers[child].parent.take() {
+            self.containers[old].children.retain(|&c| c != child);
+        }
+        self.containers[child].parent = Some(parent);
+        self.containers[parent].children.push(child);
+    }
+
+    fn date(&self, node: usize) -> Option<i64> {
+        self.containers[node].message.map(|m| self.messages[m].date)
+    }
+
+    /// The subject of `node`, or of its first descendant with one.
+    fn subject(&self, node: usize) -> Option<&'a str> {
+        let messages = self.messages;
+        self.containers[node]
+            .message
+            .and_then(|m| messages[m].subject.as_deref())
+            .filter(|s| !s.is_empty())
+            .or_else(|| {
+                self.containers[node]
+                    .children
+                    .iter()
+                    .find_map(|&child| self.subject(child))
+            })
+    }
+
+    fn roots(&self) -> Vec<usize> {
+        (0..self.containers.len())
+            .filter(|&index| self.containers[index].parent.is_none())
+            .collect()
+    }
+
+    fn collect(&self, node: usize, into: &mut Vec<usize>, visited: &mut HashSet<usize>) {
+        if !visited.insert(node) {
+            return;
+        }
+        into.extend(self.containers[node].message);
+        for &child in &self.containers[node].children {
+            self.collect(child, into, visited);
+        }
+    }
+}
+
+/// Group messages into conversations.
+pub fn thread_messages(messages: &[ThreadableMessage]) -> Vec<Thread> {
+    let mut forest = Forest {
+        messages,
+        containers: Vec::new(),
+        by_id: HashMap::new(),
+    };
+
+    // Link each message under the chain of messages it refers to
+    for (index, message) in messages.iter().enumerate() {
+        let node = forest.container(&message.message_id);
+        forest.containers[node].message = Some(index);
+
+        let mut refs = parse_references(message.references.as_deref());
+        for id in parse_references(message.in_reply_to.as_deref()) {
+            if !refs.contains(&id) {
+                refs.push(id);
+            }
+        }
+
+        let mut previous = None;
+        for id in &refs {
+            let referenced = forest.container(id);
+            if let Some(previous) = previous {
+                // Keep links made earlier rather than breaking chains
+                if forest.containers[referenced].parent.is_none() {
+                    forest.link(previous, referenced);
+                }
+            }
+            previous = Some(referenced);
+        }
+        if let Some(previous) = previous.filter(|&p| p != node) {
+            forest.link(previous, node);
+        }
+    }
+
+    // Merge roots with the same subject: under a phantom root, else under
+    // the older message
+    let mut by_subject: HashMap<String, usize> = HashMap::new();
+    for root in forest.roots() {
+        let subject = normalize_subject(forest.subject(root));
+        if subject.is_empty() {
+            continue;
+        }
+        let Some(&existing) = by_subject.get(&subject) else {
+            by_subject.insert(subject, root);
+            continue;
+        };
+        let root_is_phantom = forest.containers[root].message.is_none();
+        let existing_is_phantom = forest.containers[existing].message.is_none();
+        let root_first = if existing_is_phantom != root_is_phantom {
+            root_is_phantom
+        } else {
+            forest.date(existing).unwrap_or(0) > forest.date(root).unwrap_or(0)
+        };
+        if root_first {
+            forest.link(root, existing);
+            by_subject.insert(subject, root);
+        } else {
+            forest.link(existing, root);
+        }
+    }
+
+    let mut visited = HashSet::new();
+    let mut threads = Vec::new();
+    for root in forest.roots() {
+        let mut members = Vec::new();
+        forest.collect(root, &mut members, &mut visited);
+        if members.is_empty() {
+            continue;
+        }
+        members.sort_by_key(|&m| messages[m].date);
+        threads.push(Thread {
+            thread_id: thread_id_for(&forest.containers[root].message_id),
+            message_ids: members
+                .into_iter()
+                .map(|m| messages[m].id.clone())
+                .collect(),
+        });
+    }
+    threads
+}
+
+/// The thread already cached for any of `message_ids`, oldest message's
+/// first. Messages still waiting for threading carry their own id as
+/// thread id and are skipped.
+async fn cached_thread_id(
+    pool: &SqlitePool,
+    account_id: &str,
+    message_ids: &[&str],
+) -> Result<Option<String>, String> {
+    let ids = serde_json::to_string(message_ids).map_err(|e| e.to_string())?;
+    let row: Option<(String,)> = sqlx::query_as(
+        "SELECT thread_id FROM messages
+         WHERE account_id = ?1 AND thread_id != id
+           AND message_id_header IN (SELECT value FROM json_each(?2))
+         ORDER BY date ASC LIMIT 1",
+    )
+    .bind(account_id)
+    .bind(ids)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Failed to look up cached threads: {e}"))?;
+    Ok(row.map(|(thread_id,)| thread_id))
+}
+
+/// Thread `messages` and keep the ids of threads the cache already has:
+/// a thread takes over the cached thread of any message in it or referred
+/// to by it, and threads that land on the same cached thread are merged.
+#[tauri::command]
+#[specta::specta]
+pub async fn cache_thread_messages(
+    db: State<'_, DbInstances>,
+    profiles: State<'_, Profiles>,
+    account_id: String,
+    messages: Vec<ThreadableMessage>,
+) -> Result<Vec<Thread>, String> {
+    let instances = db.0.read().await;
+    let DbPool::Sqlite(pool) = instances
+        .get(&profiles.db_url())
+        .ok_or_else(|| "Database is not loaded".to_string())?;
+
+    let by_local_id: HashMap<&str, &ThreadableMessage> =
+        messages.iter().map(|m| (m.id.as_str(), m)).collect();
+    let mut threads: Vec<Thread> = Vec::new();
+    let mut index_of: HashMap<String, usize> = HashMap::new();
+    for mut thread in thread_messages(&messages) {
+        let mut known: Vec<String> = Vec::new();
+        for message in thread
+            .message_ids
+            .iter()
+            .filter_map(|id| by_local_id.get(id.as_str()))
+        {
+            known.push(message.message_id.clone());
+            known.extend(parse_references(message.references.as_deref()));
+            known.extend(parse_references(message.in_reply_to.as_deref()));
+        }
+        known.sort();
+        known.dedup();
+        let known: Vec<&str> = known.iter().map(String::as_str).collect();
+        if let Some(cached) = cached_thread_id(pool, &account_id, &known).await? {
+            thread.thread_id = cached;
+        }
+
+        match index_of.get(&thread.thread_id) {
+            Some(&index) => threads[index].message_ids.extend(thread.message_ids),
+            None => {
+                index_of.insert(thread.thread_id.clone(), threads.len());
+                threads.push(thread);
+            }
+        }
+    }
+    for thread in &mut threads {
+        thread
+            .message_ids
+            .sort_by_key(|id| by_local_id.get(id.as_str()).map_or(0, |m| m.date));
+    }
+    Ok(threads)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(
+        id: &str,
+        message_id: &str,
+        in_reply_to: Option<&str>,
+        references: Option<&str>,
+        subject: &str,
+        date: i64,
+    ) -> ThreadableMessage {
+        ThreadableMessage {
+            id: id.to_string(),
+            message_id: message_id.to_string(),
+            in_reply_to: in_reply_to.map(str::to_string),
+            references: references.map(str::to_string),
+            subject: Some(subject.to_string()),
+            date,
+        }
+    }
+
+    #[test]
+    fn test_normalize_subject() {
+        assert_eq!(normalize_subject(Some("Re: Fwd: Hello")), "Hello");
+        assert_eq!(
+            normalize_subject(Some("[dev] RE[2]:  Build  broke")),
+            "Build broke"
+        );
+        assert_eq!(normalize_subject(Some("AW： Termin")), "Termin");
+        assert_eq!(normalize_subject(Some("Report: Q3")), "Report: Q3");
+        assert_eq!(normalize_subject(None), "");
+    }
+
+    #[test]
+    fn test_parse_references() {
+        assert_eq!(parse_references(Some("<a@x> <b@x>")), vec!["a@x", "b@x"]);
+        assert_eq!(parse_references(Some("a@x b@x")), vec!["a@x", "b@x"]);
+        assert!(parse_references(Some("  ")).is_empty());
+    }
+
+    #[test]
+    fn test_thread_id_matches_frontend() {
+        // generateThreadId("root@example.com") in threadBuilder.ts
+        assert_eq!(thread_id_for("root@example.com"), "imap-thread-cc8be742");
+        assert_eq!(thread_id_for("ünïcödé-😀@x"), "imap-thread-5dfc09c6");
+    }
+
+    #[test]
+    fn test_threads_replies_under_root() {
+        let messages = vec![
+            message("2", "b@x", Some("<a@x>"), Some("<a@x>"), "Re: Plan", 2),
+            message("1", "a@x", None, None, "Plan", 1),
+            message(
+                "3",
+                "c@x",
+                Some("<b@x>"),
+                Some("<a@x> <b@x>"),
+                "Re: Plan",
+                3,
+            ),
+            message("4", "d@x", None, None, "Other", 4),
+        ];
+        let threads = thread_messages(&messages);
+        assert_eq!(threads.len(), 2);
+        assert_eq!(threads[0].thread_id, thread_id_for("a@x"));
+        assert_eq!(threads[0].message_ids, vec!["1", "2", "3"]);
+        assert_eq!(threads[1].message_ids, vec!["4"]);
+    }
+
+    #[test]
+    fn test_thread_id_is_stable_without_root() {
+        let reply = message("2", "b@x", Some("<a@x>"), Some("<a@x>"), "Re: Plan", 2);
+        let alone = thread_messages(std::slice::from_ref(&reply));
+        assert_eq!(alone[0].thread_id, thread_id_for("a@x"));
+    }
+
+    #[test]
+    fn test_merges_by_subject() {
+        let messages = vec![
+            message("1", "a@x", None, None, "Lunch", 1),
+            message("2", "b@x", None, None, "Re: Lunch", 2),
+        ];
+        let threads = thread_messages(&messages);
+        assert_eq!(threads.len(), 1);
+        assert_eq!(threads[0].thread_id, thread_id_for("a@x"));
+        assert_eq!(threads[0].message_ids, vec!["1", "2"]);
+    }
+}
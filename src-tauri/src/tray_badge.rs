@@ -0,0 +1,304 @@
+//! Unread-count badge drawn onto the tray icon (Windows/macOS).
+//!
+//! Icons are rendered at the tray's physical pixel size for the current
+//! display scale (16 logical px on Windows, 18 on macOS), so the overlay
+//! stays crisp on high-DPI monitors instead of being stretched by the OS.
+//! Rendered bitmaps are cached per (size, label) since the unread count
+//! changes far more often than the set of counts actually seen.
+//!
+//! The Linux tray shows the count as text (see `linux_tray`) and only uses
+//! [`badge_label`]; the renderer is still built there so it's tested on
+//! every platform.
+#![cfg_attr(target_os = "linux", allow(dead_code))]
+
+#[cfg(not(target_os = "linux"))]
+use std::collections::HashMap;
+#[cfg(not(target_os = "linux"))]
+use std::sync::Mutex;
+
+/// Tray icon size in logical pixels.
+#[cfg(target_os = "macos")]
+const TRAY_ICON_LOGICAL_SIZE: f64 = 18.0;
+#[cfg(not(target_os = "macos"))]
+const TRAY_ICON_LOGICAL_SIZE: f64 = 16.0;
+
+/// Upper bound on cached bitmaps; the cache is simply cleared when full.
+const MAX_CACHED_ICONS: usize = 64;
+
+const BADGE_COLOR: [u8; 3] = [0xE5, 0x39, 0x35];
+const TEXT_COLOR: [u8; 3] = [0xFF, 0xFF, 0xFF];
+
+/// 3x5 bitmap glyphs, one row per byte (low 3 bits, MSB = leftmost column).
+const GLYPH_WIDTH: u32 = 3;
+const GLYPH_HEIGHT: u32 = 5;
+
+fn glyph(c: char) -> [u8; 5] {
+    match c {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        '+' => [0b000, 0b010, 0b111, 0b010, 0b000],
+        _ => [0; 5],
+    }
+}
+
+/// Text shown in the badge: the count, capped at "99+".
+pub fn badge_label(count: u32) -> Option<String> {
+    match count {
+        0 => None,
+        1..=99 => Some(count.to_string()),
+        _ => Some("99+".to_string()),
+    }
+}
+
+/// Physical tray icon size for a display scale factor.
+pub fn icon_size_for_scale(scale_factor: f64) -> u32 {
+    (TRAY_ICON_LOGICAL_SIZE * scale_factor.max(1.0)).round() as u32
+}
+
+/// Downscale (or upscale) an RGBA bitmap to `size`×`size` with a box filter.
+fn resize_rgba(src: &[u8], src_w: u32, src_h: u32, size: u32) -> Vec<u8> {
+    let mut out = vec![0u8; (size * size * 4) as usize];
+    let sx = src_w as f64 / size as f64;
+    let sy = src_h as f64 / size as f64;
+
+    for y in 0..size {
+        let y0 = (y as f64 * sy).floor() as u32;
+        let y1 = (((y + 1) as f64 * sy).ceil() as u32).clamp(y0 + 1, src_h);
+        for x in 0..size {
+            let x0 = (x as f64 * sx).floor() as u32;
+            let x1 = (((x + 1) as f64 * sx).ceil() as u32).clamp(x0 + 1, src_w);
+
+            // Premultiply so transparent pixels don't darken edges
+            let mut acc = [0f64; 4];
+            let mut n = 0f64;
+            for yy in y0..y1 {
+                for xx in x0..x1 {
+                    let i = ((yy * src_w + xx) * 4) as usize;
+                    let a = src[i + 3] as f64 / 255.0;
+                    acc[0] += src[i] as f64 * a;
+                    acc[1] += src[i + 1] as f64 * a;
+                    acc[2] += src[i + 2] as f64 * a;
+                    acc[3] += a;
+                    n += 1.0;
+                }
+            }
+
+            let o = ((y * size + x) * 4) as usize;
+            if acc[3] > 0.0 {
+                out[o] = (acc[0] / acc[3]).round() as u8;
+                out[o + 1] = (acc[1] / acc[3]).round() as u8;
+                out[o + 2] = (acc[2] / acc[3]).round() as u8;
+            }
+            out[o + 3] = (acc[3] / n * 255.0).round() as u8;
+        }
+    }
+    out
+}
+
+/// Alpha-blend a color with the given coverage onto pixel `(x, y)`.
+fn blend(buf: &mut [u8], size: u32, x: u32, y: u32, color: [u8; 3], coverage: f64) {
+    if coverage <= 0.0 || x >= size || y >= size {
+        return;
+    }
+    let i = ((y * size + x) * 4) as usize;
+    let a = coverage.min(1.0);
+    let dst_a = buf[i + 3] as f64 / 255.0;
+    let out_a = a + dst_a * (1.0 - a);
+    for c in 0..3 {
+        let src = color[c] as f64 * a;
+        let dst = buf[i + c] as f64 * dst_a * (1.0 - a);
+        buf[i + c] = ((src + dst) / out_a).round() as u8;
+    }
+    buf[i + 3] = (out_a * 255.0).round() as u8;
+}
+
+/// Render the base icon at `size` px with an unread badge in the top-right corner.
+///
+/// The badge is a pill (a circle for one digit) sized relative to the icon,
+/// with text drawn from a pixel font at an integer scale so it stays sharp.
+pub fn render_badge_icon(base: &[u8], base_w: u32, base_h: u32, size: u32, label: &str) -> Vec<u8> {
+    let mut buf = resize_rgba(base, base_w, base_h, size);
+
+    let px = (size / 10).max(1); // pixel-font scale
+    let chars = label.chars().count() as u32;
+    let text_w = chars * GLYPH_WIDTH * px + chars.saturating_sub(1) * px;
+    let text_h = GLYPH_HEIGHT * px;
+
+    let height = (text_h + 2 * px).max(size * 11 / 20);
+    let width = (text_w + 2 * px).max(height).min(size);
+    let left = size - width;
+    let radius = height as f64 / 2.0;
+
+    // Pill = all points within `radius` of the horizontal center segment
+    let cy = radius;
+    let seg_x0 = left as f64 + radius;
+    let seg_x1 = (left + width) as f64 - radius;
+    for y in 0..height {
+        for x in left..size {
+            let fx = x as f64 + 0.5;
+            let fy = y as f64 + 0.5;
+            let nearest_x = fx.clamp(seg_x0, seg_x1.max(seg_x0));
+            let dist = ((fx - nearest_x).powi(2) + (fy - cy).powi(2)).sqrt();
+            blend(&mut buf, size, x, y, BADGE_COLOR, radius - dist + 0.5);
+        }
+    }
+
+    let text_x = left + (width - text_w.min(width)) / 2;
+    let text_y = (height - text_h.min(height)) / 2;
+    for (ci, c) in label.chars().enumerate() {
+        let gx = text_x + ci as u32 * (GLYPH_WIDTH + 1) * px;
+        for (row, bits) in glyph(c).iter().enumerate() {
+            for col in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - col)) == 0 {
+                    continue;
+                }
+                for dy in 0..px {
+                    for dx in 0..px {
+                        let x = gx + col * px + dx;
+                        let y = text_y + row as u32 * px + dy;
+                        blend(&mut buf, size, x, y, TEXT_COLOR, 1.0);
+                    }
+                }
+            }
+        }
+    }
+
+    buf
+}
+
+/// Cache of rendered badge icons plus the last count shown, so the icon can
+/// be re-rendered when the display scale changes.
+#[cfg(not(target_os = "linux"))]
+#[derive(Default)]
+pub struct TrayBadgeCache {
+    icons: Mutex<HashMap<(u32, String), Vec<u8>>>,
+    last_count: Mutex<u32>,
+}
+
+#[cfg(not(target_os = "linux"))]
+impl TrayBadgeCache {
+    fn get_or_render(
+        &self,
+        base: &[u8],
+        base_w: u32,
+        base_h: u32,
+        size: u32,
+        label: &str,
+    ) -> Vec<u8> {
+        let mut icons = self.icons.lock().unwrap();
+        let key = (size, label.to_string());
+        if let Some(rgba) = icons.get(&key) {
+            return rgba.clone();
+        }
+        if icons.len() >= MAX_CACHED_ICONS {
+            icons.clear();
+        }
+        let rgba = render_badge_icon(base, base_w, base_h, size, label);
+        icons.insert(key, rgba.clone());
+        rgba
+    }
+}
+
+/// Draw the unread badge onto the tray icon for the current display scale.
+#[cfg(not(target_os = "linux"))]
+pub fn apply(app: &tauri::AppHandle, count: u32) -> Result<(), String> {
+    use tauri::image::Image;
+    use tauri::tray::TrayIconId;
+    use tauri::Manager;
+
+    let cache = app.state::<TrayBadgeCache>();
+    *cache.last_count.lock().unwrap() = count;
+
+    let tray = app
+        .tray_by_id(&TrayIconId::new("main-tray"))
+        .ok_or_else(|| "Tray icon not found".to_string())?;
+    let base = app
+        .default_window_icon()
+        .ok_or_else(|| "No default icon".to_string())?;
+
+    let scale = app
+        .primary_monitor()
+        .ok()
+        .flatten()
+        .map(|m| m.scale_factor())
+        .unwrap_or(1.0);
+    let size = icon_size_for_scale(scale);
+
+    let icon = match badge_label(count) {
+        Some(label) => {
+            let rgba = cache.get_or_render(base.rgba(), base.width(), base.height(), size, &label);
+            Image::new_owned(rgba, size, size)
+        }
+        None => base.clone(),
+    };
+    tray.set_icon(Some(icon)).map_err(|e| e.to_string())
+}
+
+/// Re-render the badge for the last count, e.g. after a DPI change.
+#[cfg(not(target_os = "linux"))]
+pub fn refresh(app: &tauri::AppHandle) {
+    use tauri::Manager;
+
+    let count = *app.state::<TrayBadgeCache>().last_count.lock().unwrap();
+    if let Err(e) = apply(app, count) {
+        log::debug!("Failed to refresh tray badge: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_badge_label() {
+        assert_eq!(badge_label(0), None);
+        assert_eq!(badge_label(7).as_deref(), Some("7"));
+        assert_eq!(badge_label(99).as_deref(), Some("99"));
+        assert_eq!(badge_label(100).as_deref(), Some("99+"));
+        assert_eq!(badge_label(150).as_deref(), Some("99+"));
+    }
+
+    #[test]
+    fn test_icon_size_for_scale() {
+        assert_eq!(icon_size_for_scale(1.0), TRAY_ICON_LOGICAL_SIZE as u32);
+        assert_eq!(
+            icon_size_for_scale(2.0),
+            (TRAY_ICON_LOGICAL_SIZE * 2.0) as u32
+        );
+        assert_eq!(icon_size_for_scale(0.5), TRAY_ICON_LOGICAL_SIZE as u32);
+    }
+
+    #[test]
+    fn test_render_badge_icon_size_and_badge_color() {
+        let base = vec![0u8; 64 * 64 * 4];
+        for size in [16, 24, 32, 40] {
+            let rgba = render_badge_icon(&base, 64, 64, size, "3");
+            assert_eq!(rgba.len(), (size * size * 4) as usize);
+            // Left edge of the badge's vertical center is solid badge color
+            let badge_h = (size * 11 / 20).max(5 * (size / 10).max(1) + 2 * (size / 10).max(1));
+            let y = badge_h / 2;
+            let x = size - badge_h + 1;
+            let i = ((y * size + x) * 4) as usize;
+            assert_eq!(
+                &rgba[i..i + 4],
+                &[BADGE_COLOR[0], BADGE_COLOR[1], BADGE_COLOR[2], 255]
+            );
+        }
+    }
+
+    #[test]
+    fn test_render_badge_icon_keeps_base_outside_badge() {
+        let base = [10u8, 20, 30, 255].repeat(32 * 32);
+        let rgba = render_badge_icon(&base, 32, 32, 32, "99+");
+        let i = ((31 * 32) * 4) as usize; // bottom-left pixel
+        assert_eq!(&rgba[i..i + 4], &[10, 20, 30, 255]);
+    }
+}
@@ -2,8 +2,10 @@ import { useEffect, useState, useCallback, useRef } from "react";
 import { Outlet } from "@tanstack/react-router";
 import { Sidebar } from "./components/layout/Sidebar";
 import { AddAccount } from "./components/accounts/AddAccount";
+import { PasswordPrompt } from "./components/accounts/PasswordPrompt";
 import { Composer } from "./components/composer/Composer";
 import { UndoSendToast } from "./components/composer/UndoSendToast";
+import { UndoActionToast } from "./components/ui/UndoActionToast";
 import { CommandPalette } from "./components/search/CommandPalette";
 import { ShortcutsHelp } from "./components/search/ShortcutsHelp";
 import { AskInbox } from "./components/search/AskInbox";
@@ -18,8 +20,13 @@ import {
   stopBackgroundSync,
   syncAccount,
   triggerSync,
+  syncDueFolders,
   onSyncStatus,
 } from "./services/gmail/syncManager";
+import { initImapPush } from "./services/imap/imapPush";
+import { imapCloseAccountSessions } from "./services/imap/tauriCommands";
+import { initFolderChanges } from "./services/imap/folderChanges";
+import { initStagedOperations } from "./services/stagedOperations";
 import { initializeClients } from "./services/gmail/tokenManager";
 import {
   startSnoozeChecker,
@@ -37,6 +44,10 @@ import {
   startBundleChecker,
   stopBundleChecker,
 } from "./services/bundles/bundleManager";
+import {
+  startFeedChecker,
+  stopFeedChecker,
+} from "./services/feeds/feedManager";
 import { initNotifications } from "./services/notifications/notificationManager";
 import {
   initGlobalShortcut,
@@ -53,20 +64,33 @@ import {
   startPreCacheManager,
   stopPreCacheManager,
 } from "./services/attachments/preCacheManager";
+import { startCacheEviction, stopCacheEviction } from "./services/cacheEviction";
+import { startDbMaintenance, stopDbMaintenance } from "./services/db/maintenance";
 import {
   startUpdateChecker,
   stopUpdateChecker,
 } from "./services/updateManager";
+import {
+  startLinkBlocklistUpdater,
+  stopLinkBlocklistUpdater,
+} from "./services/phishing/linkCheck";
 import { fetchSendAsAliases } from "./services/gmail/sendAs";
 import { getGmailClient } from "./services/gmail/tokenManager";
 import { invoke } from "@tauri-apps/api/core";
 import { DndProvider } from "./components/dnd/DndProvider";
 import { TitleBar } from "./components/layout/TitleBar";
+import { UnlockScreen } from "./components/layout/UnlockScreen";
+import { CloseWindowDialog } from "./components/layout/CloseWindowDialog";
+import { getProfileStatus } from "./services/profileLock";
 import { useShortcutStore } from "./stores/shortcutStore";
 import { getIncompleteTaskCount } from "./services/db/tasks";
 import { useTaskStore } from "./stores/taskStore";
 import { ContextMenuPortal } from "./components/ui/ContextMenuPortal";
 import { OfflineBanner } from "./components/ui/OfflineBanner";
+import { ReadOnlyBanner } from "./components/ui/ReadOnlyBanner";
+import { getReadOnly } from "./services/readOnly";
+import { importManagedAccounts } from "./services/managedPolicy";
+import { registerIdentity } from "./services/identity";
 import { UpdateToast } from "./components/ui/UpdateToast";
 import { ErrorBoundary } from "./components/ui/ErrorBoundary";
 import { getThemeById, COLOR_THEMES } from "./constants/themes";
@@ -100,6 +124,7 @@ export default function App() {
   const sidebarCollapsed = useUIStore((s) => s.sidebarCollapsed);
   const [showAddAccount, setShowAddAccount] = useState(false);
   const [initialized, setInitialized] = useState(false);
+  const [onUnlocked, setOnUnlocked] = useState<(() => void) | null>(null);
   const [syncStatus, setSyncStatus] = useState<string | null>(null);
   const [showCommandPalette, setShowCommandPalette] = useState(false);
   const [showShortcutsHelp, setShowShortcutsHelp] = useState(false);
@@ -112,6 +137,13 @@ export default function App() {
   // Register global keyboard shortcuts
   useKeyboardShortcuts();
 
+  // Read-only mode lives in the backend settings
+  useEffect(() => {
+    getReadOnly()
+      .then((readOnly) => useUIStore.getState().setReadOnly(readOnly))
+      .catch(() => {});
+  }, []);
+
   // Network status detection
   useEffect(() => {
     const { setOnline } = useUIStore.getState();
@@ -124,7 +156,11 @@ export default function App() {
       const activeIds = accounts.filter((a) => a.isActive).map((a) => a.id);
       if (activeIds.length > 0) triggerSync(activeIds);
     };
-    const handleOffline = () => setOnline(false);
+    const handleOffline = () => {
+      setOnline(false);
+      // Pooled IMAP connections don't survive a network change
+      imapCloseAccountSessions().catch(() => {});
+    };
 
     window.addEventListener("online", handleOnline);
     window.addEventListener("offline", handleOffline);
@@ -175,10 +211,47 @@ export default function App() {
     return () => { unlisten?.(); };
   }, []);
 
+  // NOTIFY/IDLE push: sync the folder the server reported as changed
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    initImapPush((accountId, folder) => {
+      if (folder) {
+        syncDueFolders(accountId);
+      } else {
+        triggerSync([accountId]);
+      }
+    }).then((fn) => { unlisten = fn; });
+    return () => { unlisten?.(); };
+  }, []);
+
+  // Folders created, renamed or deleted on the server: update the labels
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    initFolderChanges().then((fn) => { unlisten = fn; });
+    return () => { unlisten?.(); };
+  }, []);
+
+  // Deletes held in the backend: restore their undo toast, apply their outcome
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    initStagedOperations().then((fn) => { unlisten = fn; });
+    return () => { unlisten?.(); };
+  }, []);
+
   // Initialize database, load accounts, start sync
   useEffect(() => {
     async function init() {
       try {
+        // With a master password, stored credentials can't be decrypted
+        // until the user unlocks the profile
+        if ((await getProfileStatus()).locked) {
+          await new Promise<void>((resolve) => {
+            setOnUnlocked(() => resolve);
+            invoke("close_splashscreen").catch(() => {});
+          });
+          setOnUnlocked(null);
+        }
+
         await runMigrations();
 
         const ui = useUIStore.getState();
@@ -268,6 +341,16 @@ export default function App() {
           ui.setReduceMotion(true);
         }
 
+        // Restore image proxy mode
+        const savedImageProxy = await getSetting("image_proxy_enabled");
+        if (savedImageProxy === "true") {
+          ui.setImageProxyEnabled(true);
+        }
+        const savedImageProxyUrl = await getSetting("image_proxy_url");
+        if (savedImageProxyUrl) {
+          ui.setImageProxyUrl(savedImageProxyUrl);
+        }
+
         // Restore task sidebar visibility
         const savedTaskSidebar = await getSetting("task_sidebar_visible");
         if (savedTaskSidebar === "true") {
@@ -286,6 +369,11 @@ export default function App() {
         // Load custom keyboard shortcuts
         await useShortcutStore.getState().loadKeyMap();
 
+        // Add accounts provisioned by the managed policy
+        await importManagedAccounts().catch((err) =>
+          console.warn("Failed to import managed accounts:", err),
+        );
+
         const dbAccounts = await getAllAccounts();
         const mapped = dbAccounts.map((a) => ({
           id: a.id,
@@ -313,18 +401,30 @@ export default function App() {
           }
         }
 
+        // Tell the backend which addresses are the user's, so synced
+        // messages are marked as sent by them
+        for (const account of mapped.filter((a) => a.provider !== "caldav")) {
+          await registerIdentity(account).catch((err) =>
+            console.warn(`Failed to register addresses for ${account.id}:`, err),
+          );
+        }
+
         // Start background sync for active accounts
         if (activeIds.length > 0) {
           startBackgroundSync(activeIds);
         }
 
-        // Start snooze, scheduled send, follow-up, bundle, and queue checkers
+        // Start snooze, scheduled send, follow-up, bundle, feed, and queue checkers
         startSnoozeChecker();
         startScheduledSendChecker();
         startFollowUpChecker();
         startBundleChecker();
+        startFeedChecker();
         startQueueProcessor();
         startPreCacheManager();
+        startCacheEviction();
+        startDbMaintenance();
+        startLinkBlocklistUpdater();
 
         // Initialize notifications
         await initNotifications();
@@ -362,8 +462,12 @@ export default function App() {
       stopScheduledSendChecker();
       stopFollowUpChecker();
       stopBundleChecker();
+      stopFeedChecker();
       stopQueueProcessor();
       stopPreCacheManager();
+      stopCacheEviction();
+      stopDbMaintenance();
+      stopLinkBlocklistUpdater();
       stopUpdateChecker();
       unregisterComposeShortcut();
       deepLinkCleanupRef.current?.();
@@ -515,6 +619,10 @@ export default function App() {
     startBackgroundSync(activeIds, true);
   }, []);
 
+  if (onUnlocked) {
+    return <UnlockScreen onUnlocked={onUnlocked} />;
+  }
+
   if (!initialized) {
     return (
       <div className="flex h-screen items-center justify-center bg-bg-primary">
@@ -532,6 +640,7 @@ export default function App() {
   return (
     <div className="flex flex-col h-screen overflow-hidden text-text-primary">
       <OfflineBanner />
+      <ReadOnlyBanner />
       {/* Animated gradient blobs for glassmorphism effect */}
       <div className="animated-bg" aria-hidden="true">
         <div className="blob" />
@@ -571,7 +680,10 @@ export default function App() {
         <Composer />
       </ErrorBoundary>
       <UndoSendToast />
+      <UndoActionToast />
       <UpdateToast />
+      <CloseWindowDialog />
+      <PasswordPrompt />
       <ErrorBoundary name="CommandPalette">
         <CommandPalette
           isOpen={showCommandPalette}
@@ -1,4 +1,5 @@
 import { useEffect, useState } from "react";
+import { getCurrentWindow } from "@tauri-apps/api/window";
 import { Composer } from "./components/composer/Composer";
 import { UndoSendToast } from "./components/composer/UndoSendToast";
 import { useAccountStore } from "./stores/accountStore";
@@ -12,6 +13,9 @@ import { getThemeById, COLOR_THEMES } from "./constants/themes";
 import type { ColorThemeId } from "./constants/themes";
 import type { ComposerMode } from "./stores/composerStore";
 
+/** Opened by the backend's quick-compose shortcut or tray item (`quick_compose.rs`). */
+const isQuickCompose = new URLSearchParams(window.location.search).get("quick") === "true";
+
 export default function ComposerWindow() {
   const { setTheme, setFontScale, setColorTheme } = useUIStore();
   const { setAccounts } = useAccountStore();
@@ -93,11 +97,11 @@ export default function ComposerWindow() {
           draftId,
         });
 
-        // Set fromEmail and force fullpage mode
+        // Set fromEmail and fill the window
         if (fromEmail) {
           useComposerStore.getState().setFromEmail(fromEmail);
         }
-        useComposerStore.getState().setViewMode("fullpage");
+        useComposerStore.getState().setViewMode(isQuickCompose ? "compact" : "fullpage");
       } catch (err) {
         console.error("Failed to initialize composer window:", err);
         setError("Failed to load composer");
@@ -109,6 +113,32 @@ export default function ComposerWindow() {
     // eslint-disable-next-line react-hooks/exhaustive-deps -- store setters are stable references
   }, []);
 
+  // The quick-compose window is hidden rather than closed once its message is
+  // sent or discarded, and starts a new one when the shortcut shows it again
+  useEffect(() => {
+    if (!isQuickCompose || loading) return;
+    const appWindow = getCurrentWindow();
+
+    const unsubscribe = useComposerStore.subscribe((state) => {
+      // Wait out the undo-send delay: the send runs in this window
+      if (!state.isOpen && !state.undoSendVisible) {
+        appWindow.hide().catch((err) => console.error("Failed to hide quick compose window:", err));
+      }
+    });
+    const unlisten = appWindow.onFocusChanged(({ payload: focused }) => {
+      const state = useComposerStore.getState();
+      if (focused && !state.isOpen) {
+        state.openComposer();
+        state.setViewMode("compact");
+      }
+    });
+
+    return () => {
+      unsubscribe();
+      unlisten.then((fn) => fn());
+    };
+  }, [loading]);
+
   // Sync theme class to <html>
   const theme = useUIStore((s) => s.theme);
   useEffect(() => {
@@ -9,6 +9,7 @@ import { getAllAccounts } from "./services/db/accounts";
 import { getSetting } from "./services/db/settings";
 import { initializeClients } from "./services/gmail/tokenManager";
 import { getThreadById, getThreadLabelIds } from "./services/db/threads";
+import { getThreadIdByImapUid } from "./services/db/messages";
 import { getThemeById, COLOR_THEMES } from "./constants/themes";
 import type { ColorThemeId } from "./constants/themes";
 import type { Thread } from "./stores/threadStore";
@@ -22,10 +23,13 @@ export default function ThreadWindow() {
 
   useEffect(() => {
     const params = new URLSearchParams(window.location.search);
-    const threadId = params.get("thread");
+    let threadId = params.get("thread");
     const accountId = params.get("account");
+    // Message windows (opened via open_message_window) identify an IMAP message by folder + UID
+    const messageUid = params.get("message");
+    const messageFolder = params.get("folder");
 
-    if (!threadId || !accountId) {
+    if ((!threadId && !(messageUid && messageFolder)) || !accountId) {
       setError("Missing thread or account parameter");
       setLoading(false);
       return;
@@ -71,6 +75,15 @@ export default function ThreadWindow() {
         // Initialize Gmail clients
         await initializeClients();
 
+        if (!threadId) {
+          threadId = await getThreadIdByImapUid(accountId!, messageFolder!, Number(messageUid));
+          if (!threadId) {
+            setError("Message not found");
+            setLoading(false);
+            return;
+          }
+        }
+
         // Fetch thread
         const dbThread = await getThreadById(accountId!, threadId!);
         if (!dbThread) {
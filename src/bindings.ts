@@ -0,0 +1,2517 @@
+// @ts-nocheck
+// This file was generated by [tauri-specta](https://github.com/oscartbeaumont/tauri-specta). Do not edit this file manually.
+
+/** user-defined commands **/
+
+
+export const commands = {
+/**
+ * Binds to a localhost port for OAuth callback. Tries the given port first,
+ * falls back to nearby ports if taken.
+ */
+async startOauthServer(port: number, state: string) : Promise<Result<OAuthResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_oauth_server", { port, state }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Generate a PKCE verifier and challenge, so public clients (no client
+ * secret) can sign in.
+ */
+async oauthGeneratePkce() : Promise<PkcePair> {
+    return await TAURI_INVOKE("oauth_generate_pkce");
+},
+/**
+ * The built-in OAuth profile of `provider` (e.g. "microsoft").
+ */
+async oauthProviderProfile(provider: string, tenant: string | null) : Promise<Result<OAuthProviderProfile, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("oauth_provider_profile", { provider, tenant }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Exchange an OAuth authorization code for tokens via Rust HTTP client (avoids CORS).
+ */
+async oauthExchangeToken(tokenUrl: string, code: string, clientId: string, redirectUri: string, codeVerifier: string | null, clientSecret: string | null, scope: string | null) : Promise<Result<TokenExchangeResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("oauth_exchange_token", { tokenUrl, code, clientId, redirectUri, codeVerifier, clientSecret, scope }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Refresh an OAuth token via Rust HTTP client (avoids CORS).
+ */
+async oauthRefreshToken(tokenUrl: string, refreshToken: string, clientId: string, clientSecret: string | null, scope: string | null) : Promise<Result<TokenExchangeResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("oauth_refresh_token", { tokenUrl, refreshToken, clientId, clientSecret, scope }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Hand an account's refresh token to the backend, which keeps it in the
+ * OS keychain and refreshes access tokens itself from then on.
+ * `expires_at` (Unix seconds) goes with `access_token`. Fails when there's
+ * no usable keychain.
+ */
+async oauthStoreGrant(accountId: string, grant: OAuthGrant, accessToken: string | null, expiresAt: number | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("oauth_store_grant", { accountId, grant, accessToken, expiresAt }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setTrayTooltip(tooltip: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_tray_tooltip", { tooltip }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setTrayUnreadCount(count: number) : Promise<void> {
+    await TAURI_INVOKE("set_tray_unread_count", { count });
+},
+async closeSplashscreen() : Promise<void> {
+    await TAURI_INVOKE("close_splashscreen");
+},
+async openDevtools() : Promise<void> {
+    await TAURI_INVOKE("open_devtools");
+},
+async quitApp() : Promise<void> {
+    await TAURI_INVOKE("quit_app");
+},
+/**
+ * Perform a close action chosen by the frontend's "close window?" dialog.
+ */
+async applyCloseAction(action: CloseBehavior) : Promise<void> {
+    await TAURI_INVOKE("apply_close_action", { action });
+},
+async getCloseBehavior() : Promise<CloseBehavior> {
+    return await TAURI_INVOKE("get_close_behavior");
+},
+async setCloseBehavior(behavior: CloseBehavior) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_close_behavior", { behavior }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getReadOnly() : Promise<boolean> {
+    return await TAURI_INVOKE("get_read_only");
+},
+async setReadOnly(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_read_only", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async autostartGet() : Promise<Result<AutostartState, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("autostart_get") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Enable/disable launch-on-login and set the start-hidden preference together.
+ * 
+ * If persisting the preference fails, the login item is restored to its
+ * previous state so the two never disagree.
+ */
+async autostartSet(enabled: boolean, startHidden: boolean) : Promise<Result<AutostartState, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("autostart_set", { enabled, startHidden }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Open a message in its own window, or focus it if it's already open.
+ */
+async openMessageWindow(account: string, folder: string, uid: number, title: string | null) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_message_window", { account, folder, uid, title }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listMessageWindows() : Promise<MessageWindowInfo[]> {
+    return await TAURI_INVOKE("list_message_windows");
+},
+/**
+ * Close all message windows, or only those belonging to `account`.
+ */
+async closeMessageWindows(account: string | null) : Promise<void> {
+    await TAURI_INVOKE("close_message_windows", { account });
+},
+async openQuickCompose() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_quick_compose") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * The quick-compose global shortcut, or `None` when it's disabled.
+ */
+async getQuickComposeShortcut() : Promise<string | null> {
+    return await TAURI_INVOKE("get_quick_compose_shortcut");
+},
+/**
+ * Change (or clear, with `None`) the quick-compose global shortcut.
+ * 
+ * The new shortcut is registered before the old one is released, so one
+ * that's invalid or taken by another app leaves the old one working.
+ */
+async setQuickComposeShortcut(shortcut: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_quick_compose_shortcut", { shortcut }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Add or update an account, e.g. with a refreshed OAuth token. With
+ * `ask_password` the configs' passwords are ignored and the user is asked
+ * once per session. Details left out keep their stored value.
+ */
+async accountRegister(definition: AccountDefinition) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("account_register", { definition }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async accountAdd(definition: AccountDefinition) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("account_add", { definition }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async accountUpdate(definition: AccountDefinition) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("account_update", { definition }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Delete an account's definition and forget its cached folder list and
+ * addresses and log out its idle IMAP sessions.
+ */
+async accountRemove(accountId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("account_remove", { accountId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Every stored account, without passwords or tokens.
+ */
+async accountList() : Promise<Result<AccountDefinition[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("account_list") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async credentialsStore(accountId: string, secret: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("credentials_store", { accountId, secret }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async credentialsGet(accountId: string) : Promise<Result<string | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("credentials_get", { accountId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Remove an account's password and OAuth grant; succeeds if there were
+ * none.
+ */
+async credentialsDelete(accountId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("credentials_delete", { accountId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Answer a [`PasswordRequired`] event; `None` cancels the waiting commands.
+ * Also used right after adding an account so its first sync doesn't prompt.
+ */
+async accountProvidePassword(accountId: string, password: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("account_provide_password", { accountId, password }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async profileStatus() : Promise<ProfileStatus> {
+    return await TAURI_INVOKE("profile_status");
+},
+/**
+ * The data key as base64, for `crypto.ts`. Fails while the profile is
+ * locked.
+ */
+async profileDataKey() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("profile_data_key") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async profileUnlock(password: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("profile_unlock", { password }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async profileSetMasterPassword(currentPassword: string | null, newPassword: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("profile_set_master_password", { currentPassword, newPassword }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async profileCurrent() : Promise<ActiveProfile> {
+    return await TAURI_INVOKE("profile_current");
+},
+async profileList() : Promise<Profile[]> {
+    return await TAURI_INVOKE("profile_list");
+},
+async profileCreate(name: string) : Promise<Result<Profile, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("profile_create", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Restart the app in another profile. Doesn't return on success.
+ */
+async profileSwitch(id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("profile_switch", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async imapTestConnection(config: ImapConfig) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_test_connection", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async imapListFolders(accountId: string) : Promise<Result<ImapFolder[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_list_folders", { accountId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async imapListFolderTree(accountId: string) : Promise<Result<ImapFolderNode[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_list_folder_tree", { accountId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Create a folder. The folder management commands take UTF-8 paths
+ * (`ImapFolder::path`, levels joined by the delimiter) and encode them for
+ * the server; they return the raw path the other commands take.
+ */
+async imapCreateFolder(accountId: string, path: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_create_folder", { accountId, path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Rename (or move) the folder at `path` to `new_path`; returns the new raw
+ * path.
+ */
+async imapRenameFolder(accountId: string, path: string, newPath: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_rename_folder", { accountId, path, newPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async imapDeleteFolder(accountId: string, path: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_delete_folder", { accountId, path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async imapSubscribeFolder(accountId: string, path: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_subscribe_folder", { accountId, path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async imapUnsubscribeFolder(accountId: string, path: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_unsubscribe_folder", { accountId, path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async imapFetchMessages(accountId: string, folder: string, uids: number[]) : Promise<Result<ImapFetchResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_fetch_messages", { accountId, folder, uids }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Envelopes, flags and sizes of messages without their bodies, for filling
+ * a message list; bodies are fetched when a message is opened.
+ */
+async imapFetchHeaders(accountId: string, folder: string, uids: number[]) : Promise<Result<ImapMessageHeader[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_fetch_headers", { accountId, folder, uids }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Current keywords of messages already synced, for keyword changes made
+ * from other clients (team tags in a shared folder).
+ */
+async imapFetchKeywords(accountId: string, folder: string, uids: number[]) : Promise<Result<ImapMessageKeywords[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_fetch_keywords", { accountId, folder, uids }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async imapFetchNewUids(accountId: string, folder: string, sinceUid: number) : Promise<Result<number[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_fetch_new_uids", { accountId, folder, sinceUid }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async imapSearchAllUids(accountId: string, folder: string) : Promise<Result<number[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_search_all_uids", { accountId, folder }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async imapSearchText(accountId: string, folder: string, text: string, before: string | null) : Promise<Result<number[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_search_text", { accountId, folder, text, before }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async imapFetchMessageBody(accountId: string, folder: string, uid: number) : Promise<Result<ImapMessage, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_fetch_message_body", { accountId, folder, uid }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async imapFetchRawMessage(accountId: string, folder: string, uid: number) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_fetch_raw_message", { accountId, folder, uid }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async imapSetFlags(accountId: string, folder: string, uids: number[], flags: string[], add: boolean, keywords: string[] | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_set_flags", { accountId, folder, uids, flags, add, keywords }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async imapMoveMessages(accountId: string, folder: string, uids: number[], destination: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_move_messages", { accountId, folder, uids, destination }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async imapDeleteMessages(accountId: string, folder: string, uids: number[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_delete_messages", { accountId, folder, uids }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async imapGetFolderStatus(accountId: string, folder: string) : Promise<Result<ImapFolderStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_get_folder_status", { accountId, folder }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async imapFetchBodystructure(accountId: string, folder: string, uid: number) : Promise<Result<ImapAttachment[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_fetch_bodystructure", { accountId, folder, uid }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async imapFetchAttachment(accountId: string, folder: string, uid: number, partId: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_fetch_attachment", { accountId, folder, uid, partId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async imapAppendMessage(accountId: string, folder: string, flags: string | null, rawMessage: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_append_message", { accountId, folder, flags, rawMessage }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Save a draft, reusing unchanged attachments from the copy it replaces
+ * when the server supports CATENATE. Returns the new UID if reported.
+ */
+async imapSaveDraft(accountId: string, folder: string, rawMessage: string, replacesUid: number | null) : Promise<Result<number | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_save_draft", { accountId, folder, rawMessage, replacesUid }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async imapSyncFolder(accountId: string, folder: string, batchSize: number) : Promise<Result<ImapFolderSyncResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_sync_folder", { accountId, folder, batchSize }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async imapRawFetchDiagnostic(accountId: string, folder: string, uidRange: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_raw_fetch_diagnostic", { accountId, folder, uidRange }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Check any number of folders for new mail and current counts over one
+ * authenticated connection, instead of one connect per folder.
+ */
+async imapDeltaCheck(accountId: string, folders: DeltaCheckRequest[]) : Promise<Result<DeltaCheckResult[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_delta_check", { accountId, folders }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Start NOTIFY push for an account's folders; `false` if unsupported.
+ */
+async imapStartNotify(accountId: string, folders: string[]) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_start_notify", { accountId, folders }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async imapStopNotify(accountId: string | null) : Promise<void> {
+    await TAURI_INVOKE("imap_stop_notify", { accountId });
+},
+/**
+ * Start IDLE push for one folder; `false` if unsupported.
+ */
+async imapStartIdle(accountId: string, folder: string) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_start_idle", { accountId, folder }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Stop IDLE for one folder, every folder of an account, or everything.
+ */
+async imapStopIdle(accountId: string | null, folder: string | null) : Promise<void> {
+    await TAURI_INVOKE("imap_stop_idle", { accountId, folder });
+},
+/**
+ * Log out the idle IMAP sessions of an account, e.g. before the user signs
+ * out, or of every account if `account_id` is `None`.
+ */
+async imapCloseAccountSessions(accountId: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_close_account_sessions", { accountId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async smtpSendEmail(accountId: string, rawEmail: string) : Promise<Result<SmtpSendResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("smtp_send_email", { accountId, rawEmail }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Send a message and save a `\Seen` copy in `sent_folder`, in one call so
+ * the copy isn't lost if the window goes away in between. Failing to save
+ * the copy is retried, then reported in `sent_copy` rather than as an
+ * error, since the message did go out.
+ */
+async smtpSendAndAppend(accountId: string, rawEmail: string, sentFolder: string) : Promise<Result<SendAndAppendResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("smtp_send_and_append", { accountId, rawEmail, sentFolder }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Build a message from its parts and send it from `account_id`, through
+ * SMTP or the Gmail API as the account is set up.
+ */
+async smtpComposeAndSend(accountId: string, request: ComposeRequest) : Promise<Result<ComposeSendResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("smtp_compose_and_send", { accountId, request }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Queue a message to be sent from `account_id` in the background, retried
+ * while the network or server is having trouble. Resolves to the outbox
+ * entry once the message is safely on disk.
+ * `raw_email` is base64url, as for `smtp_send_email`.
+ */
+async smtpQueueEmail(accountId: string, rawEmail: string) : Promise<Result<OutboxEntry, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("smtp_queue_email", { accountId, rawEmail }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Everything in the outbox, oldest first.
+ */
+async outboxList() : Promise<OutboxEntry[]> {
+    return await TAURI_INVOKE("outbox_list");
+},
+/**
+ * Send entry `id`, or every entry, now, including failed ones.
+ */
+async outboxRetry(id: string | null) : Promise<void> {
+    await TAURI_INVOKE("outbox_retry", { id });
+},
+/**
+ * Take a message out of the outbox without sending it. Fails while it's
+ * being sent.
+ */
+async outboxCancel(id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("outbox_cancel", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Permanently delete `targets` from `account_id` after `undo_seconds`,
+ * unless `undo_operation` cancels it first. Resolves once the operation is
+ * safely on disk; nothing is sent to the server until the window passes.
+ */
+async imapStageDelete(accountId: string, label: string, targets: DeleteTarget[], undoSeconds: number) : Promise<Result<StagedOperation, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("imap_stage_delete", { accountId, label, targets, undoSeconds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Cancel a staged delete before it reaches the server. False once it has
+ * started running or already finished.
+ */
+async undoOperation(id: string) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("undo_operation", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Staged deletes still waiting or running, e.g. to show their undo toast
+ * again after a reload.
+ */
+async stagedOperationList() : Promise<StagedOperation[]> {
+    return await TAURI_INVOKE("staged_operation_list");
+},
+async smtpTestConnection(config: SmtpConfig) : Promise<Result<SmtpSendResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("smtp_test_connection", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async nntpTestConnection(config: NntpConfig) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("nntp_test_connection", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Groups on the account's server matching the wildmat `pattern`
+ * (e.g. `comp.lang.*`), or all of them.
+ */
+async nntpListGroups(accountId: string, pattern: string | null) : Promise<Result<NntpGroup[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("nntp_list_groups", { accountId, pattern }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async nntpGroupStatus(accountId: string, group: string) : Promise<Result<NntpGroupStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("nntp_group_status", { accountId, group }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async nntpFetchOverview(accountId: string, group: string, first: number, last: number) : Promise<Result<NntpOverview[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("nntp_fetch_overview", { accountId, group, first, last }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Articles of `group` by number, parsed like IMAP messages with the
+ * article number as UID and the group as folder.
+ */
+async nntpFetchArticles(accountId: string, group: string, numbers: number[]) : Promise<Result<ImapMessage[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("nntp_fetch_articles", { accountId, group, numbers }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async nntpFetchRawArticle(accountId: string, group: string, number: number) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("nntp_fetch_raw_article", { accountId, group, number }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * The decoded bytes of an article's attachment, base64-encoded like
+ * `imap_fetch_attachment`.
+ */
+async nntpFetchAttachment(accountId: string, group: string, number: number, partId: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("nntp_fetch_attachment", { accountId, group, number, partId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Post a composed message (base64url, like `smtp_send_email`) to
+ * `newsgroups`.
+ */
+async nntpPostArticle(accountId: string, rawArticle: string, newsgroups: string[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("nntp_post_article", { accountId, rawArticle, newsgroups }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Stage a file from disk for the current draft. Only files the user
+ * picked in a file dialog or dropped on the window are accepted; both put
+ * the path in the fs plugin's scope.
+ */
+async attachmentStage(path: string) : Promise<Result<StagedAttachmentInfo, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("attachment_stage", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Stage a message on the IMAP server to be forwarded as an attachment.
+ * Nothing is downloaded until a server can't copy it by URL.
+ */
+async attachmentStageServerMessage(folder: string, uid: number, filename: string, size: number) : Promise<Result<StagedAttachmentInfo, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("attachment_stage_server_message", { folder, uid, filename, size }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async attachmentUnstage(id: string) : Promise<void> {
+    await TAURI_INVOKE("attachment_unstage", { id });
+},
+/**
+ * Read a staged attachment as standard base64, for callers that have to
+ * persist the content (e.g. scheduled sends that outlive the session).
+ * `account_id` is needed to download a forwarded server message.
+ * 
+ * Only content staged in the app (pasted, resized, zipped or forwarded) can
+ * be read back; picked files are read from their path by the frontend, so
+ * this can't be used to read files outside the fs scope.
+ */
+async attachmentRead(id: string, accountId: string | null) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("attachment_read", { id, accountId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Resolve staged parts for providers that send from the frontend (Gmail API).
+ * Returns the complete message, base64url-encoded.
+ */
+async attachmentResolveRaw(rawEmail: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("attachment_resolve_raw", { rawEmail }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Resize and recompress staged image attachments in place (by default to
+ * at most 1600px at JPEG quality 85). Non-image ids are skipped.
+ */
+async attachmentOptimizeImages(ids: string[], maxEdge: number | null, quality: number | null) : Promise<Result<ImageOptimizeResult[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("attachment_optimize_images", { ids, maxEdge, quality }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Bundle staged attachments into a single zip, optionally password
+ * protected (AES-256). The bundled items are replaced by the archive.
+ */
+async attachmentZip(ids: string[], archiveName: string | null, password: string | null) : Promise<Result<StagedAttachmentInfo, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("attachment_zip", { ids, archiveName, password }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Grab an image from the system clipboard and stage it as an inline part.
+ */
+async clipboardImageToAttachment() : Promise<Result<StagedInlineImage, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clipboard_image_to_attachment") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Render a preview thumbnail for a cached image, or for a PDF from the
+ * largest image embedded in its first page.
+ * 
+ * Returns `None` when the file type can't be previewed.
+ */
+async attachmentPreviewThumbnail(localPath: string, mimeType: string | null, maxSize: number | null) : Promise<Result<string | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("attachment_preview_thumbnail", { localPath, mimeType, maxSize }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Save an attachment to a temp file and open it with the default app.
+ * Returns the temp file path.
+ */
+async openAttachment(messageId: string, filename: string | null, source: AttachmentSource) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_attachment", { messageId, filename, source }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Group message timestamps (milliseconds) into list buckets with short
+ * date labels.
+ * 
+ * `locale` is a BCP 47 tag (e.g. from `navigator.language`).
+ * `utc_offset_minutes` overrides the timezone (minutes east of UTC); by
+ * default the system timezone is used, which also handles DST changes.
+ */
+async bucketMessageDates(timestamps: number[], locale: string | null, utcOffsetMinutes: number | null) : Promise<Result<DateBucketResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("bucket_message_dates", { timestamps, locale, utcOffsetMinutes }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Fetch remote images for display and return them as data URLs.
+ * 
+ * `proxy` is either an HTTP(S) proxy URL or a shared image proxy URL
+ * containing `{url}`, which is replaced by the encoded image URL. The
+ * result maps each input URL to its data URL; images that couldn't be
+ * fetched, aren't raster images, or point at private hosts are left out.
+ */
+async imageProxyFetch(urls: string[], proxy: string | null) : Promise<Result<Partial<{ [key in string]: string }>, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("image_proxy_fetch", { urls, proxy }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Delete all cached proxied images.
+ */
+async imageProxyClearCache() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("image_proxy_clear_cache") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Free time across `calendar_urls` between `start` and `end` (Unix
+ * seconds), as slots of at least `min_minutes` (default 30).
+ */
+async caldavFreebusy(calendarUrls: string[], username: string, password: string, start: number, end: number, minMinutes: number | null) : Promise<Result<TimeSlot[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("caldav_freebusy", { calendarUrls, username, password, start, end, minMinutes }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * A page of the account's messages, as Gmail's `messages.list`.
+ */
+async gmailListMessages(accountId: string, query: string | null, labelIds: string[] | null, maxResults: number | null, pageToken: string | null) : Promise<Result<GmailMessageList, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("gmail_list_messages", { accountId, query, labelIds, maxResults, pageToken }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * One message: "minimal" or "metadata" (the default), or "raw" for its
+ * source.
+ */
+async gmailGetMessage(accountId: string, messageId: string, format: string | null) : Promise<Result<GmailMessage, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("gmail_get_message", { accountId, messageId, format }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Add and remove labels (including system ones like UNREAD, STARRED and
+ * INBOX) on messages.
+ */
+async gmailModifyLabels(accountId: string, messageIds: string[], addLabelIds: string[], removeLabelIds: string[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("gmail_modify_labels", { accountId, messageIds, addLabelIds, removeLabelIds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Send a base64url-encoded message through the Gmail API, whatever the
+ * account's transport.
+ */
+async gmailSend(accountId: string, rawEmail: string, threadId: string | null) : Promise<Result<GmailMessage, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("gmail_send", { accountId, rawEmail, threadId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Check a link before it is opened.
+ * 
+ * Expansion failures are reported in `reasons` rather than as an error, so
+ * the UI can still show the blocklist and display-text findings.
+ */
+async checkLink(url: string, displayText: string | null) : Promise<Result<LinkCheckResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_link", { url, displayText }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Download feeds that are missing or stale (all of them with `force`),
+ * then reload the blocklist from disk. A feed that fails to download keeps
+ * its previous copy.
+ */
+async linkBlocklistRefresh(force: boolean | null) : Promise<Result<BlocklistStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("link_blocklist_refresh", { force }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Check recipient addresses before sending. Results are in input order;
+ * each domain is looked up once.
+ */
+async validateRecipients(addresses: string[]) : Promise<Result<RecipientCheck[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("validate_recipients", { addresses }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Check a message (base64url, as `smtp_send_email` takes it) against the
+ * send policy before sending it. An empty list means it can go.
+ */
+async checkSendPolicy(accountId: string, rawEmail: string) : Promise<Result<SendWarning[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_send_policy", { accountId, rawEmail }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getSendPolicy() : Promise<SendPolicy> {
+    return await TAURI_INVOKE("get_send_policy");
+},
+async setSendPolicy(policy: SendPolicy) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_send_policy", { policy }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Notify about a new message as the notification rules say.
+ */
+async notifyNewMail(notice: MailNotice) : Promise<Result<Delivery, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("notify_new_mail", { notice }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getNotificationRules() : Promise<NotificationRules> {
+    return await TAURI_INVOKE("get_notification_rules");
+},
+async setNotificationRules(rules: NotificationRules) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_notification_rules", { rules }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sounds to pick from: none, system sounds, bundled and added ones.
+ */
+async notificationSoundsList() : Promise<Result<SoundOption[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("notification_sounds_list") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Copy a sound file into the profile so it can be used for notifications.
+ */
+async notificationSoundAdd(path: string) : Promise<Result<SoundOption, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("notification_sound_add", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Remove an added sound. Notifications still set to it play no sound.
+ */
+async notificationSoundRemove(file: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("notification_sound_remove", { file }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Play a sound as a notification would. System sounds can only be played
+ * by a notification, so previewing one shows a test notification.
+ */
+async notificationSoundPreview(sound: NotificationSound) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("notification_sound_preview", { sound }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * The disclaimer the policy requires on mail from `account_email`, if any.
+ * A policy file that exists but can't be read or parsed is an error, so a
+ * broken deployment doesn't silently send mail without it.
+ */
+async getDisclaimer(accountEmail: string) : Promise<Result<Disclaimer | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_disclaimer", { accountEmail }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getManagedPolicy() : Promise<ManagedPolicySummary> {
+    return await TAURI_INVOKE("get_managed_policy");
+},
+async localApiGet() : Promise<Result<LocalApiStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("local_api_get") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Turn the API on or off or move it to another port. Fails, with the
+ * settings saved, if the server can't start.
+ */
+async localApiSet(localApi: LocalApiSettings) : Promise<Result<LocalApiStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("local_api_set", { localApi }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Replace the token, cutting off every tool that used the old one.
+ */
+async localApiRegenerateToken() : Promise<Result<LocalApiStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("local_api_regenerate_token") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Expand the snippet whose abbreviation `text` (what's before the caret)
+ * ends with, or `None` when there isn't one.
+ */
+async expandSnippet(text: string, accountId: string | null, context: SnippetContext) : Promise<Result<SnippetExpansion | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("expand_snippet", { text, accountId, context }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Contact details of a message's sender from an attached vCard and the
+ * signature. Takes the raw message so it works for every provider.
+ */
+async contactFromMessage(raw: string) : Promise<Result<MessageContact, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("contact_from_message", { raw }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Write the contacts matching `filter` to `path`, which must come from the
+ * save dialog. Returns how many were exported.
+ */
+async contactsExport(path: string, format: ExportFormat, filter: ExportFilter) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("contacts_export", { path, format, filter }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Import contacts from a CSV or vCard file chosen in the open dialog.
+ * `mapping` overrides the guessed meaning of each CSV column by position.
+ * With `dry_run` nothing is saved and the result is a preview.
+ */
+async contactsImport(path: string, mapping: (ContactField | null)[] | null, dryRun: boolean) : Promise<Result<ContactsImport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("contacts_import", { path, mapping, dryRun }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async identitySetAddresses(accountId: string, addresses: string[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("identity_set_addresses", { accountId, addresses }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * For each of `addresses`, whether it's one of the user's: of
+ * `account_id` if given, otherwise of any account.
+ */
+async identityMatch(accountId: string | null, addresses: string[]) : Promise<boolean[]> {
+    return await TAURI_INVOKE("identity_match", { accountId, addresses });
+},
+/**
+ * Highlighted snippets for the given messages of one account, keyed by
+ * message id. Messages whose body has no match for the query are left out
+ * and keep their stored snippet.
+ */
+async searchSnippets(accountId: string, messageIds: string[], query: string) : Promise<Result<Partial<{ [key in string]: string }>, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("search_snippets", { accountId, messageIds, query }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Audit entries matching `filter`, newest first.
+ */
+async auditQuery(filter: AuditFilter) : Promise<Result<AuditEntry[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("audit_query", { filter }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * A page of `folder`'s cached messages, pinned ones first and then
+ * newest first, with the folder's sync state.
+ */
+async cacheQueryMessages(accountId: string, folder: string, offset: number | null, limit: number | null) : Promise<Result<CachedPage, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cache_query_messages", { accountId, folder, offset, limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * The cached message with `uid` in `folder`, bodies included, or `None`
+ * if it hasn't been synced.
+ */
+async cacheGetMessage(accountId: string, folder: string, uid: number) : Promise<Result<CachedMessage | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cache_get_message", { accountId, folder, uid }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Thread `messages` and keep the ids of threads the cache already has:
+ * a thread takes over the cached thread of any message in it or referred
+ * to by it, and threads that land on the same cached thread are merged.
+ */
+async cacheThreadMessages(accountId: string, messages: ThreadableMessage[]) : Promise<Result<Thread[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cache_thread_messages", { accountId, messages }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * A page of the purchases matching `filter`, newest first.
+ */
+async purchasesQuery(filter: PurchaseFilter | null, offset: number | null, limit: number | null) : Promise<Result<PurchasePage, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("purchases_query", { filter, offset, limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * What was spent per currency, or per currency and vendor, on the
+ * purchases matching `filter`, largest first.
+ */
+async purchasesTotals(filter: PurchaseFilter | null, byVendor: boolean | null) : Promise<Result<PurchaseTotal[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("purchases_totals", { filter, byVendor }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Notes on the message with Message-ID `message_id_header` and on thread
+ * `thread_id` (including those on its messages), oldest first. With
+ * neither, all of the account's notes.
+ */
+async notesList(accountId: string, messageIdHeader: string | null, threadId: string | null) : Promise<Result<Note[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("notes_list", { accountId, messageIdHeader, threadId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Add a note, or change the text of note `input.id`. A note on a message
+ * gets the message's thread when none is given.
+ */
+async notesSave(accountId: string, input: NoteInput) : Promise<Result<Note, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("notes_save", { accountId, input }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Delete a note. Returns it, so a synced copy can be removed from the
+ * server too, or `None` if there was no such note.
+ */
+async notesDelete(accountId: string, id: string) : Promise<Result<Note | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("notes_delete", { accountId, id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Notes containing `query`, most recently changed first, in one account
+ * or all of them.
+ */
+async notesSearch(query: string, accountId: string | null, limit: number | null) : Promise<Result<Note[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("notes_search", { query, accountId, limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Record that notes `ids` were copied to the server at `synced_at`.
+ */
+async notesMarkSynced(accountId: string, ids: string[], syncedAt: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("notes_mark_synced", { accountId, ids, syncedAt }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Store notes read from the server. A note replaces the local copy only
+ * when it was changed later; its thread is looked up from its message.
+ * Returns how many notes were added or changed.
+ */
+async notesImport(accountId: string, notes: Note[]) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("notes_import", { accountId, notes }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Copy a one-time code, clearing it after the configured time.
+ */
+async otpCopyCode(code: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("otp_copy_code", { code }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getOtpClipboardSeconds() : Promise<number> {
+    return await TAURI_INVOKE("get_otp_clipboard_seconds");
+},
+/**
+ * How long copied codes stay on the clipboard; 0 keeps them.
+ */
+async setOtpClipboardSeconds(seconds: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_otp_clipboard_seconds", { seconds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Check and clean up the database. `full` runs the complete integrity
+ * check and vacuums, which can take a while on a large mailbox; otherwise
+ * only the quick checks run.
+ */
+async dbMaintenance(full: boolean) : Promise<Result<MaintenanceReport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("db_maintenance", { full }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Timing summaries for every recorded operation, grouped by host. Pass
+ * `reset` to start a fresh measurement window afterwards.
+ */
+async getMetrics(reset: boolean | null) : Promise<MetricSummary[]> {
+    return await TAURI_INVOKE("get_metrics", { reset });
+}
+}
+
+/** user-defined events **/
+
+
+export const events = __makeEvents__<{
+closeRequested: CloseRequested,
+composeRequested: ComposeRequested,
+foldersChanged: FoldersChanged,
+imapExpunge: ImapExpunge,
+imapFlagsChanged: ImapFlagsChanged,
+imapIdleStopped: ImapIdleStopped,
+imapNewMail: ImapNewMail,
+imapNotify: ImapNotify,
+imapNotifyStopped: ImapNotifyStopped,
+outboxFailed: OutboxFailed,
+outboxSent: OutboxSent,
+passwordRequired: PasswordRequired,
+singleInstanceArgs: SingleInstanceArgs,
+stagedOperationDone: StagedOperationDone,
+trayCheckMail: TrayCheckMail
+}>({
+closeRequested: "close-requested",
+composeRequested: "compose-requested",
+foldersChanged: "folders-changed",
+imapExpunge: "imap-expunge",
+imapFlagsChanged: "imap-flags-changed",
+imapIdleStopped: "imap-idle-stopped",
+imapNewMail: "imap-new-mail",
+imapNotify: "imap-notify",
+imapNotifyStopped: "imap-notify-stopped",
+outboxFailed: "outbox-failed",
+outboxSent: "outbox-sent",
+passwordRequired: "password-required",
+singleInstanceArgs: "single-instance-args",
+stagedOperationDone: "staged-operation-done",
+trayCheckMail: "tray-check-mail"
+})
+
+/** user-defined constants **/
+
+export const EVENT_SCHEMA_VERSION = 1 as const;
+
+/** user-defined types **/
+
+export type AccountDefinition = { id: string; email?: string | null; display_name?: string | null; imap?: ImapConfig | null; smtp?: SmtpConfig | null; 
+/**
+ * News server of a newsgroup account; such accounts have no IMAP/SMTP.
+ */
+nntp?: NntpConfig | null; 
+/**
+ * The configs carry no password; the user is asked once per session.
+ */
+ask_password?: boolean; oauth?: OAuthSettings | null; transport?: MailTransport; 
+/**
+ * Set by the managed policy; such accounts are read-only.
+ */
+managed?: boolean }
+/**
+ * The profile this process runs, with where its files live.
+ */
+export type ActiveProfile = ({ id: string; name: string }) & { 
+/**
+ * Directory of the profile's files relative to the app config and data
+ * dirs; empty for the default profile.
+ */
+dir: string; 
+/**
+ * The URL the frontend passes to `Database.load`.
+ */
+db_url: string }
+/**
+ * Where an attachment's content comes from.
+ */
+export type AttachmentSource = 
+/**
+ * A file on disk.
+ */
+{ kind: "path"; path: string } | 
+/**
+ * Standard base64.
+ */
+{ kind: "base_64"; data: string }
+/**
+ * Where to get the attachment bytes from.
+ */
+export type AttachmentSource = 
+/**
+ * Fetch the part from the IMAP server.
+ */
+{ kind: "imap"; account_id: string; folder: string; uid: number; part_id: string } | 
+/**
+ * Already in the attachment cache (path relative to the app data dir).
+ */
+{ kind: "cached"; local_path: string } | 
+/**
+ * Bytes downloaded by the frontend (Gmail API), base64 or base64url.
+ */
+{ kind: "data"; data: string }
+export type AuditEntry = { id: number; account_id: string; operation: string; thread_id: string | null; folder: string | null; uids: string; detail: string | null; result: string; error: string | null; created_at: number }
+/**
+ * Filters for `audit_query`. Every field is optional; set ones are ANDed.
+ */
+export type AuditFilter = { accountId: string | null; threadId: string | null; folder: string | null; 
+/**
+ * A UID (or Gmail message id) that must be among the entry's UIDs.
+ */
+uid: string | null; operation: string | null; 
+/**
+ * Only entries at or after this Unix timestamp (seconds).
+ */
+since: number | null; limit: number | null }
+export type AutostartState = { enabled: boolean; start_hidden: boolean }
+export type BlocklistStatus = { entries: number; 
+/**
+ * Unix ms of the oldest loaded feed, if any are loaded.
+ */
+updated_at: number | null }
+/**
+ * A cached message. The bodies are only filled in by `cache_get_message`.
+ */
+export type CachedMessage = { id: string; thread_id: string; uid: number | null; folder: string | null; from_address: string | null; from_name: string | null; to_addresses: string | null; cc_addresses: string | null; subject: string | null; snippet: string | null; 
+/**
+ * Unix timestamp in milliseconds.
+ */
+date: number; is_read: boolean; is_starred: boolean; 
+/**
+ * Pinned to the top of its folder.
+ */
+is_pinned: boolean; 
+/**
+ * Whether the body has been downloaded.
+ */
+body_cached: boolean; body_html: string | null; body_text: string | null }
+/**
+ * One page of a cached folder and where its sync stands.
+ */
+export type CachedPage = { messages: CachedMessage[]; 
+/**
+ * Cached messages in the whole folder.
+ */
+total: number; 
+/**
+ * `None` until the folder has been synced once.
+ */
+uidvalidity: number | null; last_uid: number | null; last_sync_at: number | null }
+/**
+ * What happens when the main window's close button is pressed.
+ */
+export type CloseBehavior = 
+/**
+ * Hide the window and keep running in the tray.
+ */
+"hide" | 
+/**
+ * Minimize the window to the taskbar/dock.
+ */
+"minimize" | 
+/**
+ * Exit the application.
+ */
+"quit" | 
+/**
+ * Emit `close-requested` so the frontend can ask (and optionally remember).
+ */
+"ask"
+/**
+ * The main window's close button was pressed with the close behavior set
+ * to "ask"; the frontend answers with `apply_close_action`.
+ */
+export type CloseRequested = null
+export type ComposeAttachment = { filename: string; 
+/**
+ * Guessed from the filename when missing.
+ */
+mime_type: string | null; source: AttachmentSource }
+export type ComposeRequest = { 
+/**
+ * "Name <address>" or a bare address.
+ */
+from: string; to?: string[]; cc?: string[]; bcc?: string[]; reply_to: string | null; subject: string; text_body: string | null; html_body: string | null; attachments?: ComposeAttachment[]; inline_images?: InlineImage[]; 
+/**
+ * Message-ID being replied to, with or without angle brackets.
+ */
+in_reply_to: string | null; references?: string[] }
+/**
+ * A local API client asked for a new message; the frontend opens the
+ * composer with these fields filled in.
+ */
+export type ComposeRequested = { to: string[]; cc: string[]; bcc: string[]; subject: string | null; 
+/**
+ * Plain text.
+ */
+body: string | null }
+export type ComposeSendResult = { success: boolean; message: string; 
+/**
+ * The new message's Message-ID, without angle brackets.
+ */
+message_id: string; 
+/**
+ * The message as sent, base64url, e.g. for the Sent folder. It has a
+ * Bcc header only for Gmail API accounts, which take Bcc from it.
+ */
+raw_email: string }
+/**
+ * The contact detail a CSV column holds.
+ */
+export type ContactField = 
+/**
+ * The full name, used as is.
+ */
+"name" | "first_name" | "last_name" | "email" | "phone" | "url" | "organization" | "title"
+export type ContactsImport = { format: ImportFormat; 
+/**
+ * The CSV columns and what each was imported as; empty for vCards.
+ */
+columns: ImportColumn[]; 
+/**
+ * One per address, in file order.
+ */
+contacts: ImportedContact[]; 
+/**
+ * Entries without a usable address, which autocompletion can't use.
+ */
+skipped: number; 
+/**
+ * Entries folded into an earlier one with the same address.
+ */
+merged: number; 
+/**
+ * The contacts were saved (false for a dry run).
+ */
+saved: boolean }
+/**
+ * A display group in the message list.
+ */
+export type DateBucket = { 
+/**
+ * Stable key: "today", "yesterday", "this_week" or "YYYY-MM".
+ */
+key: string; label: string }
+export type DateBucketItem = { 
+/**
+ * Index into `DateBucketResult::buckets`.
+ */
+bucket: number; short_date: string }
+export type DateBucketResult = { 
+/**
+ * Buckets in order of first appearance in the input.
+ */
+buckets: DateBucket[]; 
+/**
+ * One entry per input timestamp, in input order.
+ */
+items: DateBucketItem[] }
+/**
+ * Messages in one folder to delete, and the conversation they belong to.
+ */
+export type DeleteTarget = { thread_id: string | null; folder: string; uids: number[] }
+/**
+ * What `notify_new_mail` did with a message.
+ */
+export type Delivery = 
+/**
+ * Shown right away (VIP sender).
+ */
+"shown" | 
+/**
+ * Shown shortly, together with any other mail that arrives meanwhile.
+ */
+"batched" | 
+/**
+ * Held for the next digest.
+ */
+"digest" | 
+/**
+ * Dropped because DND is on.
+ */
+"suppressed"
+export type DeltaCheckRequest = { folder: string; last_uid: number; uidvalidity: number }
+export type DeltaCheckResult = { folder: string; uidvalidity: number; new_uids: number[]; uidvalidity_changed: boolean; 
+/**
+ * Current STATUS counts, so unread badges refresh in the same round
+ * trip. `None` if the server rejected STATUS.
+ */
+folder_status?: ImapFolderStatus | null }
+/**
+ * The footer to append to a message, in both formats.
+ */
+export type Disclaimer = { text: string; html: string }
+export type DomainStatus = 
+/**
+ * The domain has MX records (or an address record acting as one).
+ */
+"accepts_mail" | 
+/**
+ * The domain doesn't exist, or publishes a null MX (RFC 7505).
+ */
+"no_mail_server" | 
+/**
+ * DNS couldn't be reached or timed out.
+ */
+"unknown"
+/**
+ * Which contacts to export.
+ */
+export type ExportFilter = { 
+/**
+ * Only members of this contact group.
+ */
+group_id: string | null; 
+/**
+ * Only contacts mailed with at least this often.
+ */
+min_frequency: number | null }
+export type ExportFormat = "vcard_3" | "vcard_4" | "csv"
+/**
+ * A folder that disappeared from the server's LIST and reappeared under
+ * another path.
+ */
+export type FolderRename = { 
+/**
+ * The folder's previous raw path.
+ */
+from: string; to: ImapFolder }
+/**
+ * An account's folder list differs from the previous LIST: folders were
+ * created, deleted or renamed, possibly by another client. Removed folders
+ * are raw paths.
+ */
+export type FoldersChanged = { account_id: string; added: ImapFolder[]; removed: string[]; renamed: FolderRename[] }
+/**
+ * A message as returned by `messages.get` in `minimal`, `metadata` or
+ * `raw` format.
+ */
+export type GmailMessage = { id: string; thread_id: string; label_ids?: string[]; snippet?: string; history_id: string | null; 
+/**
+ * Milliseconds since the epoch, as a string.
+ */
+internal_date: string | null; size_estimate: number | null; 
+/**
+ * The RFC 822 source, base64url-encoded; only in `raw` format.
+ */
+raw: string | null }
+/**
+ * A page of `messages.list`.
+ */
+export type GmailMessageList = { messages?: GmailMessageRef[]; 
+/**
+ * Passed back to get the next page; `None` on the last one.
+ */
+next_page_token: string | null; result_size_estimate?: number }
+/**
+ * A message id in a `messages.list` page.
+ */
+export type GmailMessageRef = { id: string; thread_id: string }
+/**
+ * Before/after sizes for one image passed to `attachment_optimize_images`.
+ */
+export type ImageOptimizeResult = { id: string; filename: string; mime_type: string; original_size: number; 
+/**
+ * Equal to `original_size` when the image was left as is.
+ */
+optimized_size: number }
+/**
+ * An email attached to another message. `part_id` is its message/rfc822
+ * section; part ids of its own attachments are relative to the outer
+ * message, so they can be fetched with the outer message's UID.
+ */
+export type ImapAttachedMessage = { part_id: string; message: ImapMessage }
+export type ImapAttachment = { part_id: string; filename: string; mime_type: string; size: number; content_id: string | null; is_inline: boolean }
+export type ImapConfig = { host: string; port: number; security: string; username: string; password: string; auth_method: string; accept_invalid_certs?: boolean; 
+/**
+ * Mailbox of another user to open with these credentials
+ * (delegated/shared access), e.g. an assistant reading a manager's mail.
+ */
+authorize_as?: string | null; 
+/**
+ * How `authorize_as` is sent: "sasl" (SASL PLAIN authorization
+ * identity, the default) or "exchange" (`user\mailbox` login name).
+ */
+delegation?: string | null; 
+/**
+ * IMAP connections/commands per minute; overrides the provider preset.
+ * `0` disables rate limiting.
+ */
+rate_limit_per_minute?: number | null }
+/**
+ * A message was expunged from a folder watched with IDLE. `seq` is its
+ * sequence number at the time, not a UID.
+ */
+export type ImapExpunge = { account_id: string; folder: string; seq: number }
+export type ImapFetchResult = { messages: ImapMessage[]; folder_status: ImapFolderStatus }
+/**
+ * A message's flags changed in a folder watched with IDLE. `uid` is only
+ * set if the server included it.
+ */
+export type ImapFlagsChanged = { account_id: string; folder: string; seq: number; uid: number | null; flags: string[] }
+export type ImapFolder = { path: string; raw_path: string; name: string; delimiter: string; special_use: string | null; exists: number; unseen: number; attributes?: string[]; no_select?: boolean; non_existent?: boolean }
+/**
+ * A folder in the hierarchy returned by `imap_list_folder_tree`.
+ * 
+ * Containers that the server lists only implicitly (e.g. "Work" when only
+ * "Work/Projects" exists) are synthesized with `selectable: false`.
+ */
+export type ImapFolderNode = { id: string; path: string; raw_path: string; name: string; delimiter: string; special_use: string | null; exists: number; unseen: number; attributes: string[]; selectable: boolean; has_children: boolean; children: ImapFolderNode[] }
+export type ImapFolderStatus = { uidvalidity: number; uidnext: number; exists: number; unseen: number; highest_modseq: number | null }
+export type ImapFolderSyncResult = { uids: number[]; messages: ImapMessage[]; folder_status: ImapFolderStatus }
+/**
+ * An IDLE connection ended, so the frontend can restart it.
+ */
+export type ImapIdleStopped = { account_id: string; folder: string; error: string | null }
+export type ImapMessage = { uid: number; folder: string; message_id: string | null; in_reply_to: string | null; references: string | null; from_address: string | null; from_name: string | null; to_addresses: string | null; cc_addresses: string | null; bcc_addresses: string | null; reply_to: string | null; subject: string | null; date: number; is_read: boolean; is_starred: boolean; is_draft: boolean; body_html: string | null; body_text: string | null; snippet: string | null; raw_size: number; list_unsubscribe: string | null; list_unsubscribe_post: string | null; auth_results: string | null; attachments: ImapAttachment[]; 
+/**
+ * Emails attached as message/rfc822 parts, parsed recursively.
+ */
+attached_messages?: ImapAttachedMessage[]; 
+/**
+ * MIME parsing failed; only the basic headers were recovered.
+ */
+parse_failed?: boolean; 
+/**
+ * Envelope recipient (X-Original-To / Delivered-To / Envelope-To): the
+ * alias or catch-all address the message was delivered to, which may
+ * not appear in To/Cc.
+ */
+delivered_to?: string | null; 
+/**
+ * Mailing list identifier from List-Id, e.g. "dev.lists.example.org".
+ */
+list_id?: string | null; 
+/**
+ * Address for posting to the list (List-Post), used by "reply to list".
+ */
+list_post?: string | null; 
+/**
+ * List archive URL (List-Archive).
+ */
+list_archive?: string | null; 
+/**
+ * Where replies to all should go instead of From/To/Cc
+ * (Mail-Followup-To), set by list members who don't want a copy.
+ */
+mail_followup_to?: string | null; 
+/**
+ * Sent from one of the user's addresses (see `identity`).
+ */
+is_from_me?: boolean; 
+/**
+ * IMAP keywords set on the message, e.g. color categories like
+ * `$label1` (see `keywords`).
+ */
+keywords?: string[] }
+/**
+ * The envelope of a message, fetched without its body, for filling a
+ * message list quickly. The body is fetched when the message is opened.
+ */
+export type ImapMessageHeader = { uid: number; folder: string; message_id: string | null; in_reply_to: string | null; from_address: string | null; from_name: string | null; to_addresses: string | null; cc_addresses: string | null; reply_to: string | null; subject: string | null; date: number; is_read: boolean; is_starred: boolean; is_draft: boolean; raw_size: number; 
+/**
+ * The body structure has a part meant to be saved rather than shown.
+ */
+has_attachments: boolean }
+/**
+ * A message's keywords, fetched without anything else to pick up keyword
+ * changes made by other clients (e.g. team tags, see `keywords`).
+ */
+export type ImapMessageKeywords = { uid: number; keywords: string[] }
+/**
+ * New messages arrived in a folder watched with IMAP IDLE; `exists` is the
+ * folder's new message count.
+ */
+export type ImapNewMail = { account_id: string; folder: string; exists: number }
+/**
+ * A folder changed on the server, pushed over IMAP NOTIFY. `folder` is
+ * `None` when the server dropped events and the whole account needs a
+ * resync.
+ */
+export type ImapNotify = { account_id: string; folder: string | null; exists: number | null; unseen: number | null }
+/**
+ * A NOTIFY connection ended, so the frontend can restart it.
+ */
+export type ImapNotifyStopped = { account_id: string; error: string | null }
+export type ImportColumn = { header: string; 
+/**
+ * `None` for columns that aren't imported.
+ */
+field: ContactField | null }
+export type ImportFormat = "csv" | "vcard"
+export type ImportedContact = { email: string; display_name: string | null; phones: string[]; urls: string[]; organization: string | null; title: string | null; avatar_url: string | null; 
+/**
+ * A contact with this address is already saved; only its missing
+ * details are filled in.
+ */
+exists: boolean }
+/**
+ * An image the HTML body shows with `<img src="cid:...">`.
+ */
+export type InlineImage = { 
+/**
+ * Without angle brackets or the `cid:` prefix.
+ */
+content_id: string; filename: string | null; mime_type: string | null; source: AttachmentSource }
+export type LinkCheckResult = { url: string; 
+/**
+ * Where the link ends up after expanding shorteners.
+ */
+final_url: string; 
+/**
+ * Intermediate URLs, in order, excluding `url` and `final_url`.
+ */
+redirects: string[]; final_domain: string | null; 
+/**
+ * Domain the link text claims to go to, if it looks like a URL.
+ */
+display_domain: string | null; domain_mismatch: boolean; 
+/**
+ * Feed that lists the link or one of its redirects.
+ */
+blocklist_source: string | null; verdict: Verdict; reasons: string[] }
+export type LocalApiSettings = { enabled: boolean; port: number }
+/**
+ * What Settings shows about the API.
+ */
+export type LocalApiStatus = { enabled: boolean; port: number; 
+/**
+ * Whether the server is listening.
+ */
+running: boolean; 
+/**
+ * Why the server isn't running although enabled, e.g. port in use.
+ */
+error: string | null; 
+/**
+ * `None` while the API is disabled.
+ */
+token: string | null }
+/**
+ * A new message to notify about.
+ */
+export type MailNotice = { account_id: string; thread_id: string | null; from_name: string | null; from_address: string | null; subject: string | null }
+/**
+ * How an account's mail goes out.
+ */
+export type MailTransport = 
+/**
+ * The account's SMTP server.
+ */
+"imap" | 
+/**
+ * Gmail's REST API ([`crate::gmail_api`]), for accounts throttled on
+ * Gmail's IMAP/SMTP limits.
+ */
+"gmail_api"
+export type MaintenanceReport = { 
+/**
+ * Database size in bytes before and after.
+ */
+size_before: number; size_after: number; 
+/**
+ * Problems the integrity check still finds after repairs; empty when
+ * the database is healthy.
+ */
+integrity_errors: string[]; 
+/**
+ * Indexes were rebuilt because the integrity check failed.
+ */
+reindexed: boolean; 
+/**
+ * A full-text search index didn't match its table and was rebuilt.
+ */
+fts_rebuilt: boolean; vacuumed: boolean }
+/**
+ * What the frontend needs to show which controls are locked.
+ */
+export type ManagedPolicySummary = { allowed_auth_methods: string[]; disable_accept_invalid_certs: boolean; 
+/**
+ * Top-level backend settings keys the policy sets.
+ */
+locked_settings: string[]; account_ids: string[] }
+export type MessageContact = { email: string | null; display_name: string | null; phones: string[]; urls: string[]; organization: string | null; title: string | null; 
+/**
+ * A PNG data URL made from the vCard's photo.
+ */
+avatar_url: string | null; 
+/**
+ * The details came from an attached vCard rather than only the
+ * headers and signature.
+ */
+from_vcard: boolean }
+export type MessageWindowInfo = { label: string; account: string; folder: string; uid: number }
+export type MetricSummary = { 
+/**
+ * Server host; empty for work that doesn't talk to a server (parsing).
+ */
+host: string; operation: string; phase: Phase; count: number; errors: number; avg_ms: number; p50_ms: number; p95_ms: number; max_ms: number; last_ms: number }
+export type NntpConfig = { host: string; port: number; security: string; 
+/**
+ * Empty for servers that allow reading without AUTHINFO.
+ */
+username?: string; password?: string; accept_invalid_certs?: boolean }
+/**
+ * A newsgroup from `LIST ACTIVE`.
+ */
+export type NntpGroup = { name: string; 
+/**
+ * Lowest and highest article numbers; `last < first` when empty.
+ */
+first: number; last: number; 
+/**
+ * The server accepts posts to this group (flag `y`, not `n` or moderated `m`).
+ */
+posting_allowed: boolean }
+/**
+ * A group's article range, as returned by `GROUP`.
+ */
+export type NntpGroupStatus = { name: string; 
+/**
+ * Estimated number of articles.
+ */
+count: number; first: number; last: number }
+/**
+ * One line of the overview database (`OVER`/`XOVER`): an article's
+ * headers without fetching it.
+ */
+export type NntpOverview = { number: number; subject: string; from: string; date: string; message_id: string; references: string; bytes: number; lines: number }
+export type Note = { id: string; account_id: string; 
+/**
+ * Message-ID header (without angle brackets) of the message the note
+ * is attached to; `None` for a note on a whole thread.
+ */
+message_id_header: string | null; thread_id: string | null; body: string; 
+/**
+ * Unix timestamps in seconds.
+ */
+created_at: number; updated_at: number; 
+/**
+ * When the note was last copied to or from the server, if ever.
+ */
+synced_at: number | null }
+/**
+ * A new note, or changes to one when `id` is set.
+ */
+export type NoteInput = { id: string | null; message_id_header: string | null; thread_id: string | null; body: string }
+export type NotificationRules = { 
+/**
+ * Do Not Disturb: only VIP senders notify.
+ */
+dnd: boolean; 
+/**
+ * Daily quiet hours in minutes after local midnight, during which DND
+ * is on too. A start after the end spans midnight.
+ */
+quiet_start: number | null; quiet_end: number | null; 
+/**
+ * Show notifications from other senders as one digest this often;
+ * `None` shows them as they come.
+ */
+digest_minutes: number | null; 
+/**
+ * Sound for VIP notifications.
+ */
+vip_sound: NotificationSound; 
+/**
+ * Sound for notifications from other senders, batches and digests.
+ */
+sound: NotificationSound; 
+/**
+ * Per-account replacements for `sound`, by account id.
+ */
+account_sounds: Partial<{ [key in string]: NotificationSound }> }
+export type NotificationSound = 
+/**
+ * No sound.
+ */
+{ kind: "silent" } | 
+/**
+ * A sound the OS plays with the notification, by name.
+ */
+{ kind: "system"; name: string } | 
+/**
+ * A sound shipped with the app, by id.
+ */
+{ kind: "bundled"; id: string } | 
+/**
+ * A sound file the user added, by file name.
+ */
+{ kind: "custom"; file: string }
+/**
+ * What refreshing an account's access token takes. Stored in the keychain
+ * as JSON, next to the account's password entry.
+ */
+export type OAuthGrant = { token_url: string; client_id: string; client_secret?: string | null; scope?: string | null; refresh_token: string }
+/**
+ * Endpoints, scopes and mail servers of a built-in OAuth provider.
+ */
+export type OAuthProviderProfile = { id: string; name: string; auth_url: string; token_url: string; scopes: string[]; use_pkce: boolean; 
+/**
+ * Sent again with each refresh, or Microsoft issues a token for its
+ * first resource only.
+ */
+refresh_scope: string | null; imap_host: string; imap_port: number; smtp_host: string; smtp_port: number; smtp_security: string; 
+/**
+ * Whether SMTP `AUTH XOAUTH2` may carry the token on the command line
+ * (a SASL initial response); see [`smtp_xoauth2_initial_response`].
+ */
+smtp_initial_response: boolean }
+export type OAuthResult = { code: string; state: string }
+/**
+ * How an OAuth account signs in. Its configs carry the access token as
+ * password, or none once the grant is in the keychain and
+ * [`crate::oauth::TokenManager`] supplies fresh ones.
+ */
+export type OAuthSettings = { provider: string; client_id: string | null }
+/**
+ * A queued message. The message itself is in `<id>.eml` next to it.
+ */
+export type OutboxEntry = { id: string; account_id: string; subject: string | null; 
+/**
+ * Unix timestamps in milliseconds.
+ */
+queued_at: number; next_attempt_at: number; attempts: number; last_error: string | null; status: OutboxStatus }
+/**
+ * A queued message failed for good (a permanent error, or too many
+ * retries). It stays in the outbox until it's retried or cancelled.
+ */
+export type OutboxFailed = { id: string; account_id: string; error: string; attempts: number }
+/**
+ * A message queued with `smtp_queue_email` was sent and left the outbox.
+ */
+export type OutboxSent = { id: string; account_id: string; 
+/**
+ * The server's reply.
+ */
+message: string }
+export type OutboxStatus = 
+/**
+ * Waiting for `next_attempt_at`.
+ */
+"queued" | "sending" | 
+/**
+ * Gave up; waits for `outbox_retry` or `outbox_cancel`.
+ */
+"failed"
+/**
+ * An account whose password isn't stored needs it for this session; the
+ * frontend asks the user and answers with `account_provide_password`.
+ */
+export type PasswordRequired = { account_id: string }
+export type Phase = "connect" | "auth" | "command" | "parse"
+/**
+ * A PKCE (RFC 7636) pair: the challenge goes in the authorization URL
+ * with `code_challenge_method=S256`, the verifier to [`oauth_exchange_token`].
+ */
+export type PkcePair = { code_verifier: string; code_challenge: string }
+export type Profile = { id: string; name: string }
+export type ProfileStatus = { 
+/**
+ * A master password is set.
+ */
+protected: boolean; 
+/**
+ * The data key isn't available until `profile_unlock` succeeds.
+ */
+locked: boolean }
+/**
+ * A recorded purchase.
+ */
+export type Purchase = { account_id: string; message_id: string; thread_id: string; 
+/**
+ * "receipt" or "invoice".
+ */
+kind: string; vendor: string; 
+/**
+ * Unix timestamp in milliseconds.
+ */
+purchased_at: number; 
+/**
+ * ISO 4217 code.
+ */
+currency: string; 
+/**
+ * Hundredths of the currency unit.
+ */
+amount_cents: number; order_number: string | null }
+/**
+ * Which purchases to list or total. Unset fields don't filter.
+ */
+export type PurchaseFilter = { account_id: string | null; 
+/**
+ * Unix milliseconds, inclusive.
+ */
+since: number | null; 
+/**
+ * Unix milliseconds, exclusive.
+ */
+until: number | null; 
+/**
+ * Case-insensitive substring of the vendor.
+ */
+vendor: string | null; currency: string | null }
+export type PurchasePage = { purchases: Purchase[]; 
+/**
+ * Purchases matching the filter in all pages.
+ */
+total: number }
+/**
+ * The sum of the purchases in one currency, and one vendor when grouped
+ * by vendor. Currencies are never added together.
+ */
+export type PurchaseTotal = { currency: string; vendor: string | null; amount_cents: number; count: number }
+export type RecipientCheck = { address: string; valid_syntax: boolean; 
+/**
+ * Why the address isn't valid, when `valid_syntax` is false.
+ */
+syntax_error: string | null; 
+/**
+ * `None` when the syntax check already failed.
+ */
+domain_status: DomainStatus | null; 
+/**
+ * Corrected address for a likely domain typo (gamil.com → gmail.com).
+ */
+suggestion: string | null }
+export type SendAndAppendResult = { sent: SmtpSendResult; sent_copy: SentCopy }
+export type SendPolicy = { 
+/**
+ * Recipients outside these domains and their subdomains are external.
+ * Empty turns the external recipient check off.
+ */
+internal_domains: string[]; 
+/**
+ * Warn when Cc lists more addresses than this.
+ */
+max_cc: number | null; warn_empty_subject: boolean; 
+/**
+ * Warn when replying to a conversation whose last message is older
+ * than this many days.
+ */
+old_thread_days: number | null }
+export type SendWarning = { kind: SendWarningKind; message: string; 
+/**
+ * The addresses the warning is about (the external ones), if any.
+ */
+addresses: string[] }
+export type SendWarningKind = "external_recipients" | "large_cc" | "empty_subject" | "old_thread"
+/**
+ * What became of the Sent folder copy of a message.
+ */
+export type SentCopy = 
+/**
+ * Appended to the Sent folder.
+ */
+{ status: "saved" } | 
+/**
+ * The transport saves sent mail itself (Gmail API).
+ */
+{ status: "saved_by_server" } | 
+/**
+ * Not tried, because the message wasn't sent.
+ */
+{ status: "skipped" } | 
+/**
+ * The message was sent, but every try at saving the copy failed.
+ */
+{ status: "failed"; error: string }
+/**
+ * Command-line args of a second launch, forwarded by the single-instance
+ * plugin (e.g. a `mailto:` URL to open).
+ */
+export type SingleInstanceArgs = { args: string[] }
+export type SmtpConfig = { host: string; port: number; security: string; username: string; password: string; auth_method: string; accept_invalid_certs?: boolean; 
+/**
+ * Messages sent per minute; overrides the provider preset. `0` disables
+ * rate limiting.
+ */
+rate_limit_per_minute?: number | null }
+export type SmtpSendResult = { success: boolean; message: string }
+/**
+ * What the variables of a snippet are filled in from — the same values
+ * compose templates use.
+ */
+export type SnippetContext = { recipient_email: string | null; 
+/**
+ * Looked up in the contacts when missing.
+ */
+recipient_name: string | null; sender_email: string | null; sender_name: string | null; subject: string | null }
+export type SnippetExpansion = { 
+/**
+ * The abbreviation that was matched; the caller replaces this many
+ * characters before the caret.
+ */
+abbreviation: string; 
+/**
+ * Plain text with variables filled in.
+ */
+text: string; 
+/**
+ * Caret position within `text` in UTF-16 code units, when the snippet
+ * has a `{{cursor}}` marker.
+ */
+cursor: number | null }
+/**
+ * A sound offered in settings.
+ */
+export type SoundOption = { sound: NotificationSound; label: string }
+/**
+ * Metadata returned to the composer for a staged attachment.
+ */
+export type StagedAttachmentInfo = { id: string; filename: string; mime_type: string; size: number; 
+/**
+ * PNG data URL for image attachments.
+ */
+thumbnail: string | null }
+/**
+ * A pasted image staged as an inline part.
+ */
+export type StagedInlineImage = { id: string; content_id: string; 
+/**
+ * `cid:` URL to reference the part from the HTML body.
+ */
+cid_url: string; mime_type: string; size: number; 
+/**
+ * PNG data URL for displaying the image in the editor.
+ */
+preview: string }
+/**
+ * A permanent delete waiting out its undo window.
+ */
+export type StagedOperation = { id: string; account_id: string; 
+/**
+ * Shown in the undo toast, e.g. "Conversation deleted".
+ */
+label: string; targets: DeleteTarget[]; 
+/**
+ * Unix timestamp in milliseconds after which the delete runs.
+ */
+run_at: number; attempts: number }
+/**
+ * A delete staged with `imap_stage_delete` ran: it reached the server, or
+ * failed for good with `error` and the messages are still there.
+ */
+export type StagedOperationDone = { id: string; account_id: string; 
+/**
+ * The conversations the delete covered.
+ */
+thread_ids: string[]; error: string | null }
+/**
+ * A conversation: its id and its messages' local ids, oldest first.
+ */
+export type Thread = { threadId: string; messageIds: string[] }
+/**
+ * The headers threading needs from a message.
+ */
+export type ThreadableMessage = { 
+/**
+ * Local message id.
+ */
+id: string; 
+/**
+ * RFC 2822 Message-ID, without angle brackets.
+ */
+messageId: string; inReplyTo: string | null; 
+/**
+ * Space-separated Message-IDs.
+ */
+references: string | null; subject: string | null; 
+/**
+ * Unix timestamp in milliseconds.
+ */
+date: number }
+/**
+ * A span of time in Unix seconds, end exclusive.
+ */
+export type TimeSlot = { start: number; end: number }
+export type TokenExchangeResult = { access_token: string; refresh_token: string | null; expires_in: number; token_type: string; scope: string | null; id_token: string | null }
+/**
+ * "Check for Mail" was chosen in the tray menu.
+ */
+export type TrayCheckMail = null
+export type Verdict = "safe" | "suspicious" | "dangerous"
+
+/** tauri-specta globals **/
+
+import {
+	invoke as TAURI_INVOKE,
+	Channel as TAURI_CHANNEL,
+} from "@tauri-apps/api/core";
+import * as TAURI_API_EVENT from "@tauri-apps/api/event";
+import { type WebviewWindow as __WebviewWindow__ } from "@tauri-apps/api/webviewWindow";
+
+type __EventObj__<T> = {
+	listen: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.listen<T>>;
+	once: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.once<T>>;
+	emit: null extends T
+		? (payload?: T) => ReturnType<typeof TAURI_API_EVENT.emit>
+		: (payload: T) => ReturnType<typeof TAURI_API_EVENT.emit>;
+};
+
+export type Result<T, E> =
+	| { status: "ok"; data: T }
+	| { status: "error"; error: E };
+
+function __makeEvents__<T extends Record<string, any>>(
+	mappings: Record<keyof T, string>,
+) {
+	return new Proxy(
+		{} as unknown as {
+			[K in keyof T]: __EventObj__<T[K]> & {
+				(handle: __WebviewWindow__): __EventObj__<T[K]>;
+			};
+		},
+		{
+			get: (_, event) => {
+				const name = mappings[event as keyof T];
+
+				return new Proxy((() => {}) as any, {
+					apply: (_, __, [window]: [__WebviewWindow__]) => ({
+						listen: (arg: any) => window.listen(name, arg),
+						once: (arg: any) => window.once(name, arg),
+						emit: (arg: any) => window.emit(name, arg),
+					}),
+					get: (_, command: keyof __EventObj__<any>) => {
+						switch (command) {
+							case "listen":
+								return (arg: any) => TAURI_API_EVENT.listen(name, arg);
+							case "once":
+								return (arg: any) => TAURI_API_EVENT.once(name, arg);
+							case "emit":
+								return (arg: any) => TAURI_API_EVENT.emit(name, arg);
+						}
+					},
+				});
+			},
+		},
+	);
+}
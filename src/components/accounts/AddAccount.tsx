@@ -1,5 +1,5 @@
 import { useState } from "react";
-import { Mail, Calendar } from "lucide-react";
+import { Mail, Calendar, Newspaper } from "lucide-react";
 import { startOAuthFlow } from "@/services/gmail/auth";
 import { insertAccount } from "@/services/db/accounts";
 import { getClientId, getClientSecret } from "@/services/gmail/tokenManager";
@@ -8,14 +8,16 @@ import { Modal } from "@/components/ui/Modal";
 import { SetupClientId } from "./SetupClientId";
 import { AddImapAccount } from "./AddImapAccount";
 import { AddCalDavAccount } from "./AddCalDavAccount";
+import { AddNntpAccount } from "./AddNntpAccount";
 import { getCurrentUnixTimestamp } from "@/utils/timestamp";
+import { registerIdentity } from "@/services/identity";
 
 interface AddAccountProps {
   onClose: () => void;
   onSuccess: () => void;
 }
 
-type View = "select-provider" | "gmail" | "imap" | "caldav";
+type View = "select-provider" | "gmail" | "imap" | "caldav" | "nntp";
 
 export function AddAccount({ onClose, onSuccess }: AddAccountProps) {
   const [view, setView] = useState<View>("select-provider");
@@ -57,6 +59,9 @@ export function AddAccount({ onClose, onSuccess }: AddAccountProps) {
         avatarUrl: userInfo.picture,
         isActive: true,
       });
+      registerIdentity({ id: accountId, email: userInfo.email }).catch((err) =>
+        console.warn("Failed to register account addresses:", err),
+      );
 
       onSuccess();
     } catch (err) {
@@ -94,6 +99,16 @@ export function AddAccount({ onClose, onSuccess }: AddAccountProps) {
     );
   }
 
+  if (view === "nntp") {
+    return (
+      <AddNntpAccount
+        onClose={onClose}
+        onSuccess={onSuccess}
+        onBack={() => setView("select-provider")}
+      />
+    );
+  }
+
   if (view === "imap") {
     return (
       <AddImapAccount
@@ -223,6 +238,23 @@ export function AddAccount({ onClose, onSuccess }: AddAccountProps) {
             </div>
           </button>
 
+          <button
+            onClick={() => setView("nntp")}
+            className="w-full flex items-center gap-4 p-4 rounded-lg border border-border-primary bg-bg-secondary hover:bg-bg-hover transition-colors text-left group"
+          >
+            <div className="flex-shrink-0 w-10 h-10 rounded-lg bg-bg-tertiary flex items-center justify-center">
+              <Newspaper className="w-5 h-5 text-text-secondary" />
+            </div>
+            <div className="flex-1 min-w-0">
+              <div className="text-sm font-medium text-text-primary group-hover:text-accent transition-colors">
+                Newsgroups (NNTP)
+              </div>
+              <div className="text-xs text-text-tertiary mt-0.5">
+                Read and post to Usenet groups on any news server
+              </div>
+            </div>
+          </button>
+
           <button
             onClick={() => setView("caldav")}
             className="w-full flex items-center gap-4 p-4 rounded-lg border border-border-primary bg-bg-secondary hover:bg-bg-hover transition-colors text-left group"
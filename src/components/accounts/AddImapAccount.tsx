@@ -21,8 +21,10 @@ import {
   getDefaultSmtpPort,
   type SecurityType,
 } from "@/services/imap/autoDiscovery";
-import { getOAuthProvider } from "@/services/oauth/providers";
+import { resolveOAuthProvider } from "@/services/oauth/providers";
 import { startProviderOAuthFlow } from "@/services/oauth/oauthFlow";
+import { providePassword, type ImapDelegation } from "@/services/imap/tauriCommands";
+import { registerIdentity } from "@/services/identity";
 
 interface AddImapAccountProps {
   onClose: () => void;
@@ -46,12 +48,18 @@ interface FormState {
   password: string;
   smtpPassword: string;
   samePassword: boolean;
+  askPassword: boolean;
   acceptInvalidCerts: boolean;
+  // Delegated access to another user's mailbox
+  authorizeAs: string;
+  delegation: ImapDelegation;
   // OAuth2 fields
   authMode: AuthMode;
   oauthProvider: string | null;
   oauthClientId: string;
   oauthClientSecret: string;
+  /** Microsoft directory to sign in against; blank for "common" */
+  oauthTenant: string;
   oauthAccessToken: string | null;
   oauthRefreshToken: string | null;
   oauthExpiresAt: number | null;
@@ -71,11 +79,15 @@ const initialFormState: FormState = {
   password: "",
   smtpPassword: "",
   samePassword: true,
+  askPassword: false,
   acceptInvalidCerts: false,
+  authorizeAs: "",
+  delegation: "sasl",
   authMode: "password",
   oauthProvider: null,
   oauthClientId: "",
   oauthClientSecret: "",
+  oauthTenant: "",
   oauthAccessToken: null,
   oauthRefreshToken: null,
   oauthExpiresAt: null,
@@ -240,12 +252,6 @@ export function AddImapAccount({
   );
 
   const handleOAuthConnect = async (providerId: string) => {
-    const provider = getOAuthProvider(providerId);
-    if (!provider) {
-      setOauthError(`Unknown provider: ${providerId}`);
-      return;
-    }
-
     if (!form.oauthClientId.trim()) {
       setOauthError("Please enter a Client ID first.");
       return;
@@ -255,6 +261,12 @@ export function AddImapAccount({
     setOauthError(null);
 
     try {
+      const provider = await resolveOAuthProvider(providerId, form.oauthTenant);
+      if (!provider) {
+        setOauthError(`Unknown provider: ${providerId}`);
+        return;
+      }
+
       const { tokens, userInfo } = await startProviderOAuthFlow(
         provider,
         form.oauthClientId.trim(),
@@ -295,6 +307,8 @@ export function AddImapAccount({
             password: isOAuth ? (form.oauthAccessToken ?? "") : form.password,
             auth_method: isOAuth ? "oauth2" : "password",
             accept_invalid_certs: form.acceptInvalidCerts,
+            authorize_as: form.authorizeAs.trim() || undefined,
+            delegation: form.delegation,
           },
         },
       );
@@ -349,6 +363,7 @@ export function AddImapAccount({
       const email = (isOAuth ? form.oauthEmail : null) ?? form.email.trim();
 
       const imapUsername = form.imapUsername.trim() || null;
+      const authorizeAs = form.authorizeAs.trim() || null;
 
       if (isOAuth) {
         await insertOAuthImapAccount({
@@ -368,8 +383,11 @@ export function AddImapAccount({
           oauthProvider: form.oauthProvider!,
           oauthClientId: form.oauthClientId.trim(),
           oauthClientSecret: form.oauthClientSecret.trim() || null,
+          oauthTenant: form.oauthProvider === "microsoft" ? form.oauthTenant : null,
           imapUsername,
           acceptInvalidCerts: form.acceptInvalidCerts,
+          authorizeAs,
+          delegation: form.delegation,
         });
       } else {
         await insertImapAccount({
@@ -387,7 +405,14 @@ export function AddImapAccount({
           password: form.samePassword ? form.password : form.password,
           imapUsername,
           acceptInvalidCerts: form.acceptInvalidCerts,
+          authorizeAs,
+          delegation: form.delegation,
+          askPassword: form.askPassword,
         });
+        if (form.askPassword) {
+          // Already typed in this session; don't ask again on the first sync
+          await providePassword(accountId, form.password);
+        }
       }
 
       addAccount({
@@ -397,6 +422,9 @@ export function AddImapAccount({
         avatarUrl: null,
         isActive: true,
       });
+      registerIdentity({ id: accountId, email }).catch((err) =>
+        console.warn("Failed to register account addresses:", err),
+      );
 
       onSuccess();
     } catch (err) {
@@ -482,7 +510,14 @@ export function AddImapAccount({
 
   const renderOAuthSection = () => {
     const providerId = form.oauthProvider ?? detectedOAuthProviderId;
-    const providerName = providerId === "microsoft" ? "Microsoft" : providerId === "yahoo" ? "Yahoo" : "Provider";
+    const providerName =
+      providerId === "microsoft"
+        ? "Microsoft"
+        : providerId === "yahoo"
+          ? "Yahoo"
+          : providerId === "google"
+            ? "Google"
+            : "Provider";
 
     return (
       <div className="space-y-3">
@@ -514,6 +549,22 @@ export function AddImapAccount({
             disabled={hasOAuthTokens}
           />
         </div>
+        {providerId === "microsoft" && (
+          <div>
+            <label htmlFor="oauth-tenant" className={labelClass}>
+              Tenant (optional)
+            </label>
+            <input
+              id="oauth-tenant"
+              type="text"
+              value={form.oauthTenant}
+              onChange={(e) => updateForm("oauthTenant", e.target.value)}
+              placeholder="common, organizations, or your tenant ID/domain"
+              className={inputClass}
+              disabled={hasOAuthTokens}
+            />
+          </div>
+        )}
 
         {hasOAuthTokens ? (
           <div className="flex items-center gap-2 p-3 rounded-lg bg-success/10 border border-success/20">
@@ -556,6 +607,9 @@ export function AddImapAccount({
           {providerId === "yahoo" && (
             <>Register at the Yahoo Developer Network with redirect URI <code className="text-accent">http://127.0.0.1:17248</code>.</>
           )}
+          {providerId === "google" && (
+            <>Create a Desktop app client in the Google Cloud Console with the Gmail API enabled; its client secret is required.</>
+          )}
         </p>
       </div>
     );
@@ -631,6 +685,24 @@ export function AddImapAccount({
               If your provider requires it, use an app-specific password.
             </p>
           </div>
+          <div className="flex items-center gap-2">
+            <input
+              id="imap-ask-password"
+              type="checkbox"
+              checked={form.askPassword}
+              onChange={(e) => updateForm("askPassword", e.target.checked)}
+              className="rounded border-border-primary text-accent focus:ring-accent"
+            />
+            <label
+              htmlFor="imap-ask-password"
+              className="text-sm text-text-secondary"
+            >
+              Don't save password
+            </label>
+          </div>
+          <p className="text-xs text-text-tertiary -mt-2 ml-6">
+            You'll be asked for it once each time Velo starts
+          </p>
         </>
       )}
 
@@ -724,6 +796,40 @@ export function AddImapAccount({
       <p className="text-xs text-text-tertiary -mt-2 ml-6">
         Enable for local mail bridges like ProtonMail Bridge
       </p>
+      <div>
+        <label htmlFor="imap-authorize-as" className={labelClass}>
+          Open another user's mailbox (optional)
+        </label>
+        <input
+          id="imap-authorize-as"
+          type="text"
+          value={form.authorizeAs}
+          onChange={(e) => updateForm("authorizeAs", e.target.value)}
+          placeholder="manager@example.com"
+          className={inputClass}
+        />
+        <p className="text-xs text-text-tertiary mt-1">
+          Sign in with your own credentials but access a mailbox you have been delegated.
+        </p>
+      </div>
+      {form.authorizeAs.trim() && !isOAuth && (
+        <div>
+          <label htmlFor="imap-delegation" className={labelClass}>
+            Delegation method
+          </label>
+          <select
+            id="imap-delegation"
+            value={form.delegation}
+            onChange={(e) =>
+              updateForm("delegation", e.target.value as ImapDelegation)
+            }
+            className={selectClass}
+          >
+            <option value="sasl">Authorization identity (SASL PLAIN)</option>
+            <option value="exchange">Exchange (user\mailbox)</option>
+          </select>
+        </div>
+      )}
     </div>
   );
 
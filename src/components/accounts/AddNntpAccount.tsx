@@ -0,0 +1,344 @@
+import { useState, useCallback } from "react";
+import {
+  ArrowLeft,
+  ArrowRight,
+  CheckCircle2,
+  XCircle,
+  Loader2,
+  Newspaper,
+} from "lucide-react";
+import { Modal } from "@/components/ui/Modal";
+import { TextField } from "@/components/ui/TextField";
+import { insertNntpAccount } from "@/services/db/accounts";
+import { useAccountStore } from "@/stores/accountStore";
+import { nntpTestConnection } from "@/services/nntp/tauriCommands";
+import { subscribeGroup } from "@/services/nntp/nntpSync";
+
+interface AddNntpAccountProps {
+  onClose: () => void;
+  onSuccess: () => void;
+  onBack: () => void;
+}
+
+type Step = "basic" | "server" | "test" | "done";
+type SecurityType = "ssl" | "starttls" | "none";
+
+const selectClass =
+  "w-full px-3 py-2 bg-bg-secondary border border-border-primary rounded-lg text-sm text-text-primary outline-none focus:border-accent transition-colors appearance-none";
+const labelClass = "block text-xs font-medium text-text-secondary mb-1";
+
+function defaultPort(security: SecurityType): number {
+  return security === "ssl" ? 563 : 119;
+}
+
+/** Map UI security value ("ssl") to Rust config value ("tls") */
+function mapSecurity(security: SecurityType): "tls" | "starttls" | "none" {
+  return security === "ssl" ? "tls" : security;
+}
+
+export function AddNntpAccount({ onClose, onSuccess, onBack }: AddNntpAccountProps) {
+  const addAccount = useAccountStore((s) => s.addAccount);
+  const [step, setStep] = useState<Step>("basic");
+
+  // Form state
+  const [email, setEmail] = useState("");
+  const [displayName, setDisplayName] = useState("");
+  const [host, setHost] = useState("");
+  const [port, setPort] = useState(563);
+  const [security, setSecurity] = useState<SecurityType>("ssl");
+  const [acceptInvalidCerts, setAcceptInvalidCerts] = useState(false);
+  const [username, setUsername] = useState("");
+  const [password, setPassword] = useState("");
+  const [groups, setGroups] = useState("");
+
+  // Test state
+  const [testing, setTesting] = useState(false);
+  const [testResult, setTestResult] = useState<{ success: boolean; message: string } | null>(null);
+
+  // Creating account
+  const [creating, setCreating] = useState(false);
+
+  const handleTest = useCallback(async () => {
+    setTesting(true);
+    setTestResult(null);
+
+    try {
+      const message = await nntpTestConnection({
+        host: host.trim(),
+        port,
+        security: mapSecurity(security),
+        username: username.trim(),
+        password: username.trim() ? password : "",
+        accept_invalid_certs: acceptInvalidCerts,
+      });
+      setTestResult({ success: true, message });
+    } catch (err) {
+      setTestResult({
+        success: false,
+        message: err instanceof Error ? err.message : String(err),
+      });
+    }
+    setTesting(false);
+  }, [host, port, security, username, password, acceptInvalidCerts]);
+
+  const handleCreate = useCallback(async () => {
+    setCreating(true);
+    try {
+      const id = crypto.randomUUID();
+      await insertNntpAccount({
+        id,
+        email: email.trim(),
+        displayName: displayName || null,
+        host: host.trim(),
+        port,
+        security,
+        username: username.trim(),
+        password,
+        acceptInvalidCerts,
+      });
+      for (const group of groups.split(/[\s,]+/).filter(Boolean)) {
+        await subscribeGroup(id, group);
+      }
+
+      addAccount({
+        id,
+        email: email.trim(),
+        displayName: displayName || null,
+        avatarUrl: null,
+        isActive: true,
+      });
+
+      setStep("done");
+    } catch (err) {
+      console.error("Failed to create newsgroup account:", err);
+      setTestResult({ success: false, message: "Failed to save account" });
+    } finally {
+      setCreating(false);
+    }
+  }, [email, displayName, host, port, security, username, password, acceptInvalidCerts, groups, addAccount]);
+
+  return (
+    <Modal isOpen={true} onClose={onClose} title="Add Newsgroup Account" width="w-full max-w-md">
+      <div className="p-4">
+        {step === "basic" && (
+          <div className="space-y-4">
+            <div className="flex items-center gap-3 mb-4">
+              <div className="w-10 h-10 rounded-full bg-accent/10 flex items-center justify-center">
+                <Newspaper size={20} className="text-accent" />
+              </div>
+              <div>
+                <h3 className="text-sm font-medium text-text-primary">Newsgroups (NNTP)</h3>
+                <p className="text-xs text-text-tertiary">
+                  Read and post to Usenet groups on any news server
+                </p>
+              </div>
+            </div>
+
+            <TextField
+              label="Posting Address"
+              type="email"
+              value={email}
+              onChange={(e) => setEmail(e.target.value)}
+              placeholder="your@email.com"
+              autoFocus
+            />
+
+            <TextField
+              label="Display Name (optional)"
+              type="text"
+              value={displayName}
+              onChange={(e) => setDisplayName(e.target.value)}
+              placeholder="Your Name"
+            />
+
+            <div className="flex justify-between pt-2">
+              <button
+                onClick={onBack}
+                className="flex items-center gap-1 text-sm text-text-secondary hover:text-text-primary transition-colors"
+              >
+                <ArrowLeft size={14} />
+                Back
+              </button>
+              <button
+                onClick={() => setStep("server")}
+                disabled={!email.trim()}
+                className="flex items-center gap-1 px-4 py-2 text-sm font-medium text-white bg-accent hover:bg-accent-hover rounded-md transition-colors disabled:opacity-50"
+              >
+                Next
+                <ArrowRight size={14} />
+              </button>
+            </div>
+          </div>
+        )}
+
+        {step === "server" && (
+          <div className="space-y-4">
+            <TextField
+              label="News Server"
+              type="text"
+              value={host}
+              onChange={(e) => setHost(e.target.value)}
+              placeholder="news.example.com"
+            />
+
+            <div className="grid grid-cols-2 gap-3">
+              <TextField
+                label="Port"
+                type="number"
+                value={port}
+                onChange={(e) => setPort(parseInt(e.target.value, 10) || 0)}
+              />
+              <div>
+                <label htmlFor="nntp-security" className={labelClass}>
+                  Security
+                </label>
+                <select
+                  id="nntp-security"
+                  value={security}
+                  onChange={(e) => {
+                    const value = e.target.value as SecurityType;
+                    setSecurity(value);
+                    setPort(defaultPort(value));
+                  }}
+                  className={selectClass}
+                >
+                  <option value="ssl">SSL/TLS</option>
+                  <option value="starttls">STARTTLS</option>
+                  <option value="none">None</option>
+                </select>
+              </div>
+            </div>
+
+            <div className="flex items-center gap-2">
+              <input
+                id="nntp-accept-invalid-certs"
+                type="checkbox"
+                checked={acceptInvalidCerts}
+                onChange={(e) => setAcceptInvalidCerts(e.target.checked)}
+                className="rounded border-border-primary text-accent focus:ring-accent"
+              />
+              <label htmlFor="nntp-accept-invalid-certs" className="text-sm text-text-secondary">
+                Accept self-signed certificates
+              </label>
+            </div>
+
+            <TextField
+              label="Username (leave empty if the server allows anonymous reading)"
+              type="text"
+              value={username}
+              onChange={(e) => setUsername(e.target.value)}
+              placeholder="Username"
+            />
+
+            {username.trim() && (
+              <TextField
+                label="Password"
+                type="password"
+                value={password}
+                onChange={(e) => setPassword(e.target.value)}
+                placeholder="Password"
+              />
+            )}
+
+            <TextField
+              label="Groups to subscribe (optional)"
+              type="text"
+              value={groups}
+              onChange={(e) => setGroups(e.target.value)}
+              placeholder="comp.lang.rust, alt.test"
+            />
+
+            <div className="flex justify-between pt-2">
+              <button
+                onClick={() => setStep("basic")}
+                className="flex items-center gap-1 text-sm text-text-secondary hover:text-text-primary transition-colors"
+              >
+                <ArrowLeft size={14} />
+                Back
+              </button>
+              <button
+                onClick={() => { setStep("test"); handleTest(); }}
+                disabled={!host.trim() || !port || (!!username.trim() && !password)}
+                className="flex items-center gap-1 px-4 py-2 text-sm font-medium text-white bg-accent hover:bg-accent-hover rounded-md transition-colors disabled:opacity-50"
+              >
+                Test & Connect
+                <ArrowRight size={14} />
+              </button>
+            </div>
+          </div>
+        )}
+
+        {step === "test" && (
+          <div className="space-y-4">
+            <div className="text-center py-6">
+              {testing && (
+                <>
+                  <Loader2 size={32} className="animate-spin text-accent mx-auto mb-3" />
+                  <p className="text-sm text-text-secondary">Testing connection...</p>
+                </>
+              )}
+
+              {!testing && testResult?.success && (
+                <>
+                  <CheckCircle2 size={32} className="text-success mx-auto mb-3" />
+                  <p className="text-sm font-medium text-text-primary">{testResult.message}</p>
+                </>
+              )}
+
+              {!testing && testResult && !testResult.success && (
+                <>
+                  <XCircle size={32} className="text-danger mx-auto mb-3" />
+                  <p className="text-sm font-medium text-text-primary">Connection failed</p>
+                  <p className="text-xs text-text-tertiary mt-1">{testResult.message}</p>
+                </>
+              )}
+            </div>
+
+            <div className="flex justify-between pt-2">
+              <button
+                onClick={() => { setStep("server"); setTestResult(null); }}
+                className="flex items-center gap-1 text-sm text-text-secondary hover:text-text-primary transition-colors"
+              >
+                <ArrowLeft size={14} />
+                Back
+              </button>
+
+              {testResult?.success ? (
+                <button
+                  onClick={handleCreate}
+                  disabled={creating}
+                  className="flex items-center gap-1 px-4 py-2 text-sm font-medium text-white bg-accent hover:bg-accent-hover rounded-md transition-colors disabled:opacity-50"
+                >
+                  {creating ? "Creating..." : "Add Account"}
+                </button>
+              ) : !testing ? (
+                <button
+                  onClick={handleTest}
+                  className="flex items-center gap-1 px-4 py-2 text-sm font-medium text-white bg-accent hover:bg-accent-hover rounded-md transition-colors"
+                >
+                  Retry
+                </button>
+              ) : null}
+            </div>
+          </div>
+        )}
+
+        {step === "done" && (
+          <div className="text-center py-6">
+            <CheckCircle2 size={32} className="text-success mx-auto mb-3" />
+            <p className="text-sm font-medium text-text-primary">Newsgroup account added!</p>
+            <p className="text-xs text-text-tertiary mt-1">
+              Each subscribed group appears as a folder once it syncs.
+            </p>
+            <button
+              onClick={onSuccess}
+              className="mt-4 px-4 py-2 text-sm font-medium text-white bg-accent hover:bg-accent-hover rounded-md transition-colors"
+            >
+              Done
+            </button>
+          </div>
+        )}
+      </div>
+    </Modal>
+  );
+}
@@ -0,0 +1,70 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { render, screen, fireEvent, act, waitFor } from "@testing-library/react";
+
+const handlers: ((event: { payload: { account_id: string } }) => void)[] = [];
+
+vi.mock("@tauri-apps/api/event", () => ({
+  listen: vi.fn((_name: string, handler: (typeof handlers)[number]) => {
+    handlers.push(handler);
+    return Promise.resolve(() => {});
+  }),
+}));
+
+vi.mock("@/services/imap/tauriCommands", () => ({
+  providePassword: vi.fn(() => Promise.resolve()),
+}));
+
+import { PasswordPrompt } from "./PasswordPrompt";
+import { providePassword } from "@/services/imap/tauriCommands";
+import { useAccountStore } from "@/stores/accountStore";
+
+async function requestPassword(accountId: string) {
+  await waitFor(() => expect(handlers.length).toBeGreaterThan(0));
+  act(() => {
+    handlers[handlers.length - 1]!({ payload: { account_id: accountId } });
+  });
+}
+
+describe("PasswordPrompt", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    handlers.length = 0;
+    useAccountStore.setState({
+      accounts: [
+        { id: "acc-1", email: "alice@example.com", displayName: null, avatarUrl: null, isActive: true },
+        { id: "acc-2", email: "bob@example.com", displayName: null, avatarUrl: null, isActive: true },
+      ],
+      activeAccountId: "acc-1",
+    });
+  });
+
+  it("stays hidden until a password is requested", () => {
+    render(<PasswordPrompt />);
+    expect(screen.queryByText("Password required")).not.toBeInTheDocument();
+  });
+
+  it("sends the entered password for the requesting account", async () => {
+    render(<PasswordPrompt />);
+    await requestPassword("acc-1");
+
+    expect(screen.getByText(/alice@example.com/)).toBeInTheDocument();
+    fireEvent.change(screen.getByPlaceholderText("Password"), { target: { value: "hunter2" } });
+    fireEvent.click(screen.getByText("Unlock"));
+
+    expect(providePassword).toHaveBeenCalledWith("acc-1", "hunter2");
+  });
+
+  it("cancels with null and then asks for the next account", async () => {
+    render(<PasswordPrompt />);
+    await requestPassword("acc-1");
+    await requestPassword("acc-2");
+    await requestPassword("acc-1");
+
+    fireEvent.click(screen.getByText("Cancel"));
+    expect(providePassword).toHaveBeenCalledWith("acc-1", null);
+
+    expect(screen.getByText(/bob@example.com/)).toBeInTheDocument();
+    fireEvent.click(screen.getByText("Cancel"));
+    expect(providePassword).toHaveBeenCalledTimes(2);
+  });
+});
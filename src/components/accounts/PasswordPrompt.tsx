@@ -0,0 +1,77 @@
+import { useEffect, useState } from "react";
+import { Modal } from "@/components/ui/Modal";
+import { Button } from "@/components/ui/Button";
+import { useAccountStore } from "@/stores/accountStore";
+import { providePassword } from "@/services/imap/tauriCommands";
+
+/**
+ * Asks for the password of an account that doesn't store it, the first time
+ * the backend needs it this session (`password-required`). Requests for
+ * several accounts are asked one after another.
+ */
+export function PasswordPrompt() {
+  const accounts = useAccountStore((s) => s.accounts);
+  const [queue, setQueue] = useState<string[]>([]);
+  const [password, setPassword] = useState("");
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    import("@tauri-apps/api/event").then(({ listen }) => {
+      listen<{ account_id: string }>("password-required", (event) => {
+        const id = event.payload.account_id;
+        setQueue((q) => (q.includes(id) ? q : [...q, id]));
+      }).then((fn) => { unlisten = fn; });
+    });
+    return () => { unlisten?.(); };
+  }, []);
+
+  const accountId = queue[0];
+  const account = accounts.find((a) => a.id === accountId);
+
+  const answer = (value: string | null) => {
+    if (!accountId) return;
+    providePassword(accountId, value).catch((err) => {
+      console.error("Failed to provide password:", err);
+    });
+    setQueue((q) => q.slice(1));
+    setPassword("");
+  };
+
+  return (
+    <Modal
+      isOpen={!!accountId}
+      onClose={() => answer(null)}
+      title="Password required"
+      width="w-96"
+    >
+      <form
+        className="p-4 space-y-3"
+        onSubmit={(e) => {
+          e.preventDefault();
+          if (password) answer(password);
+        }}
+      >
+        <p className="text-sm text-text-secondary">
+          Enter the password for {account?.email ?? accountId}. It is kept in
+          memory until you quit and never saved.
+        </p>
+        <input
+          type="password"
+          autoFocus
+          value={password}
+          onChange={(e) => setPassword(e.target.value)}
+          placeholder="Password"
+          className="w-full bg-bg-tertiary text-text-primary text-sm px-3 py-1.5 rounded-md border border-border-primary focus:border-accent focus:outline-none placeholder:text-text-tertiary"
+        />
+        <div className="flex justify-end gap-2 pt-1">
+          <Button type="button" variant="secondary" onClick={() => answer(null)}>
+            Cancel
+          </Button>
+          <Button type="submit" variant="primary" disabled={!password}>
+            Unlock
+          </Button>
+        </div>
+      </form>
+    </Modal>
+  );
+}
@@ -14,17 +14,15 @@ describe("SetupClientId", () => {
     expect(saveButton).toBeDisabled();
   });
 
-  it("disables Save button when only client ID is provided", () => {
+  it("enables Save button when only client ID is provided", () => {
     render(<SetupClientId onComplete={() => {}} onCancel={() => {}} />);
-    const inputs = screen.getAllByRole("textbox");
-    // Client ID is the text input; secret is password (not a textbox role)
     const clientIdInput = screen.getByPlaceholderText(
       "Paste your Client ID here...",
     );
     fireEvent.change(clientIdInput, { target: { value: "my-client-id" } });
 
     const saveButton = screen.getByText("Save & Continue");
-    expect(saveButton).toBeDisabled();
+    expect(saveButton).not.toBeDisabled();
   });
 
   it("disables Save button when only client secret is provided", () => {
@@ -54,10 +52,12 @@ describe("SetupClientId", () => {
     expect(saveButton).not.toBeDisabled();
   });
 
-  it("shows helper text about client secret being required", () => {
+  it("shows helper text about when the client secret is required", () => {
     render(<SetupClientId onComplete={() => {}} onCancel={() => {}} />);
     expect(
-      screen.getByText("Required for Web application credentials"),
+      screen.getByText(
+        "Required for Web application credentials; leave empty for a public (PKCE-only) client",
+      ),
     ).toBeInTheDocument();
   });
 
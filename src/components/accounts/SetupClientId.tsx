@@ -15,12 +15,15 @@ export function SetupClientId({ onComplete, onCancel }: SetupClientIdProps) {
   const handleSave = async () => {
     const trimmedId = clientId.trim();
     const trimmedSecret = clientSecret.trim();
-    if (!trimmedId || !trimmedSecret) return;
+    if (!trimmedId) return;
 
     setSaving(true);
     try {
       await setSetting("google_client_id", trimmedId);
-      await setSecureSetting("google_client_secret", trimmedSecret);
+      // Desktop app clients sign in with PKCE alone and have no secret
+      if (trimmedSecret) {
+        await setSecureSetting("google_client_secret", trimmedSecret);
+      }
       onComplete();
     } catch {
       setSaving(false);
@@ -48,7 +51,7 @@ export function SetupClientId({ onComplete, onCancel }: SetupClientIdProps) {
             Add <code className="bg-bg-tertiary px-1 rounded text-xs">http://127.0.0.1:17248</code>{" "}
             as an authorized redirect URI
           </li>
-          <li>Copy the Client ID and Client Secret below</li>
+          <li>Copy the Client ID (and Client Secret, if any) below</li>
         </ol>
 
         <input
@@ -67,7 +70,7 @@ export function SetupClientId({ onComplete, onCancel }: SetupClientIdProps) {
           className="w-full px-3 py-2 bg-bg-secondary border border-border-primary rounded-lg text-sm mb-1 outline-none focus:border-accent"
         />
         <p className="text-text-tertiary text-xs mb-4">
-          Required for Web application credentials
+          Required for Web application credentials; leave empty for a public (PKCE-only) client
         </p>
 
         <div className="flex gap-3 justify-end">
@@ -79,7 +82,7 @@ export function SetupClientId({ onComplete, onCancel }: SetupClientIdProps) {
           </button>
           <button
             onClick={handleSave}
-            disabled={!clientId.trim() || !clientSecret.trim() || saving}
+            disabled={!clientId.trim() || saving}
             className="px-4 py-2 text-sm bg-accent text-white rounded-lg hover:bg-accent-hover transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
           >
             {saving ? "Saving..." : "Save & Continue"}
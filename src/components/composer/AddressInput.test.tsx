@@ -7,6 +7,10 @@ const mockSearchContacts = vi.fn().mockResolvedValue([]);
 vi.mock("@/services/db/contacts", () => ({
   searchContacts: (...args: unknown[]) => mockSearchContacts(...args),
 }));
+vi.mock("@/services/db/contactGroups", () => ({
+  searchContactGroups: vi.fn().mockResolvedValue([]),
+  resolveContactGroup: vi.fn().mockResolvedValue([]),
+}));
 
 describe("AddressInput debounce behavior", () => {
   beforeEach(() => {
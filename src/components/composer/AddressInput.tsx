@@ -1,5 +1,21 @@
 import { useState, useRef, useCallback, useEffect } from "react";
+import { Users } from "lucide-react";
 import { searchContacts, type DbContact } from "@/services/db/contacts";
+import {
+  searchContactGroups,
+  resolveContactGroup,
+  type DbContactGroup,
+} from "@/services/db/contactGroups";
+import { groupRecipient, parseGroupRecipient } from "@/utils/contactGroups";
+
+/** An autocomplete entry: a single contact or a contact group. */
+type Suggestion =
+  | { kind: "contact"; contact: DbContact }
+  | { kind: "group"; group: DbContactGroup };
+
+function suggestionAddress(s: Suggestion): string {
+  return s.kind === "contact" ? s.contact.email : groupRecipient(s.group.name);
+}
 
 interface AddressInputProps {
   label: string;
@@ -15,7 +31,8 @@ export function AddressInput({
   placeholder = "Add recipients...",
 }: AddressInputProps) {
   const [inputValue, setInputValue] = useState("");
-  const [suggestions, setSuggestions] = useState<DbContact[]>([]);
+  const [suggestions, setSuggestions] = useState<Suggestion[]>([]);
+  const [groupMembers, setGroupMembers] = useState<Record<string, string>>({});
   const [showSuggestions, setShowSuggestions] = useState(false);
   const [selectedIdx, setSelectedIdx] = useState(-1);
   const inputRef = useRef<HTMLInputElement>(null);
@@ -35,7 +52,14 @@ export function AddressInput({
       if (searchTimerRef.current) clearTimeout(searchTimerRef.current);
       if (value.length >= 2) {
         searchTimerRef.current = setTimeout(async () => {
-          const results = await searchContacts(value, 5);
+          const [groups, contacts] = await Promise.all([
+            searchContactGroups(value, 3),
+            searchContacts(value, 5),
+          ]);
+          const results: Suggestion[] = [
+            ...groups.map((group) => ({ kind: "group" as const, group })),
+            ...contacts.map((contact) => ({ kind: "contact" as const, contact })),
+          ];
           setSuggestions(results);
           setShowSuggestions(results.length > 0);
           setSelectedIdx(-1);
@@ -62,6 +86,19 @@ export function AddressInput({
     [addresses, onChange],
   );
 
+  // Member list shown when hovering a group chip
+  const loadGroupMembers = useCallback(
+    async (name: string) => {
+      if (groupMembers[name] !== undefined) return;
+      const members = await resolveContactGroup(name);
+      const summary = members.length > 0
+        ? members.map((m) => (m.display_name ? `${m.display_name} <${m.email}>` : m.email)).join("\n")
+        : "No members";
+      setGroupMembers((prev) => ({ ...prev, [name]: summary }));
+    },
+    [groupMembers],
+  );
+
   const removeAddress = useCallback(
     (index: number) => {
       onChange(addresses.filter((_, i) => i !== index));
@@ -73,7 +110,7 @@ export function AddressInput({
     if (e.key === "Enter" || e.key === "Tab" || e.key === ",") {
       e.preventDefault();
       if (showSuggestions && selectedIdx >= 0) {
-        addAddress(suggestions[selectedIdx]!.email);
+        addAddress(suggestionAddress(suggestions[selectedIdx]!));
       } else if (inputValue.trim()) {
         addAddress(inputValue);
       }
@@ -96,20 +133,26 @@ export function AddressInput({
         {label}
       </span>
       <div className="flex-1 flex flex-wrap items-center gap-1 min-h-[32px] relative">
-        {addresses.map((addr) => (
-          <span
-            key={addr}
-            className="inline-flex items-center gap-1 bg-accent-light text-accent text-xs px-2 py-0.5 rounded-full"
-          >
-            {addr}
-            <button
-              onClick={() => onChange(addresses.filter((a) => a !== addr))}
-              className="hover:text-danger text-[0.625rem] leading-none"
+        {addresses.map((addr) => {
+          const group = parseGroupRecipient(addr);
+          return (
+            <span
+              key={addr}
+              className="inline-flex items-center gap-1 bg-accent-light text-accent text-xs px-2 py-0.5 rounded-full"
+              title={group !== null ? groupMembers[group] : undefined}
+              onMouseEnter={group !== null ? () => loadGroupMembers(group) : undefined}
             >
-              ×
-            </button>
-          </span>
-        ))}
+              {group !== null && <Users size={11} />}
+              {group ?? addr}
+              <button
+                onClick={() => onChange(addresses.filter((a) => a !== addr))}
+                className="hover:text-danger text-[0.625rem] leading-none"
+              >
+                ×
+              </button>
+            </span>
+          );
+        })}
         <input
           ref={inputRef}
           type="text"
@@ -130,22 +173,36 @@ export function AddressInput({
         {/* Autocomplete dropdown */}
         {showSuggestions && (
           <div className="absolute top-full left-0 mt-1 w-full bg-bg-primary border border-border-primary rounded-md shadow-lg z-50 py-1">
-            {suggestions.map((contact, i) => (
+            {suggestions.map((suggestion, i) => (
               <button
-                key={contact.id}
+                key={suggestion.kind === "contact" ? suggestion.contact.id : `group-${suggestion.group.id}`}
                 onMouseDown={(e) => e.preventDefault()}
-                onClick={() => addAddress(contact.email)}
+                onClick={() => addAddress(suggestionAddress(suggestion))}
                 className={`w-full text-left px-3 py-1.5 text-sm hover:bg-bg-hover ${
                   i === selectedIdx ? "bg-bg-hover" : ""
                 }`}
               >
-                <div className="text-text-primary">
-                  {contact.display_name ?? contact.email}
-                </div>
-                {contact.display_name && (
-                  <div className="text-xs text-text-tertiary">
-                    {contact.email}
-                  </div>
+                {suggestion.kind === "group" ? (
+                  <>
+                    <div className="flex items-center gap-1.5 text-text-primary">
+                      <Users size={13} />
+                      {suggestion.group.name}
+                    </div>
+                    <div className="text-xs text-text-tertiary">
+                      {suggestion.group.member_count} member{suggestion.group.member_count !== 1 ? "s" : ""}
+                    </div>
+                  </>
+                ) : (
+                  <>
+                    <div className="text-text-primary">
+                      {suggestion.contact.display_name ?? suggestion.contact.email}
+                    </div>
+                    {suggestion.contact.display_name && (
+                      <div className="text-xs text-text-tertiary">
+                        {suggestion.contact.email}
+                      </div>
+                    )}
+                  </>
                 )}
               </button>
             ))}
@@ -1,56 +1,108 @@
-import { useRef } from "react";
-import { Paperclip, X } from "lucide-react";
+import { useState } from "react";
+import { open } from "@tauri-apps/plugin-dialog";
+import { FileArchive, ImageDown, Paperclip, X } from "lucide-react";
 import { useComposerStore, type ComposerAttachment } from "@/stores/composerStore";
-import { readFileAsBase64 } from "@/utils/fileUtils";
+import {
+  optimizeStagedImages,
+  stageAttachment,
+  unstageAttachment,
+} from "@/services/attachments/staging";
 import { formatFileSize } from "@/utils/fileTypeHelpers";
+import { ZipAttachmentsDialog } from "./ZipAttachmentsDialog";
 
 const MAX_TOTAL_SIZE = 24 * 1024 * 1024; // 24MB
 
 export function AttachmentPicker() {
-  const inputRef = useRef<HTMLInputElement | null>(null);
   const attachments = useComposerStore((s) => s.attachments);
   const addAttachment = useComposerStore((s) => s.addAttachment);
   const removeAttachment = useComposerStore((s) => s.removeAttachment);
+  const updateAttachment = useComposerStore((s) => s.updateAttachment);
+  const [isOptimizing, setIsOptimizing] = useState(false);
+  const [optimizeSummary, setOptimizeSummary] = useState<string | null>(null);
+  const [showZip, setShowZip] = useState(false);
 
   const totalSize = attachments.reduce((sum, a) => sum + a.size, 0);
+  const stagedCount = attachments.filter((a) => a.stagedId).length;
+  const stagedImages = attachments.filter(
+    (a) => a.stagedId && a.mimeType.startsWith("image/") && a.mimeType !== "image/gif",
+  );
+
+  const handlePick = async () => {
+    const selected = await open({ multiple: true, directory: false });
+    if (!selected) return;
+    const paths = Array.isArray(selected) ? selected : [selected];
 
-  const handleFiles = async (files: FileList) => {
-    for (const file of Array.from(files)) {
-      if (totalSize + file.size > MAX_TOTAL_SIZE) {
+    let runningTotal = totalSize;
+    for (const path of paths) {
+      // Files are staged in the backend by path; only metadata comes back
+      const staged = await stageAttachment(path);
+      if (runningTotal + staged.size > MAX_TOTAL_SIZE) {
         console.warn("Attachment size limit exceeded (24MB)");
+        await unstageAttachment(staged.id);
         break;
       }
-      const content = await readFileAsBase64(file);
+      runningTotal += staged.size;
       const attachment: ComposerAttachment = {
         id: crypto.randomUUID(),
-        file,
-        filename: file.name,
-        mimeType: file.type || "application/octet-stream",
-        size: file.size,
-        content,
+        filename: staged.filename,
+        mimeType: staged.mime_type,
+        size: staged.size,
+        stagedId: staged.id,
+        path,
+        thumbnail: staged.thumbnail,
       };
       addAttachment(attachment);
     }
-    // Reset input so re-selecting the same file works
-    if (inputRef.current) inputRef.current.value = "";
+  };
+
+  const handleOptimize = async () => {
+    setIsOptimizing(true);
+    try {
+      const inlineIds = useComposerStore.getState().inlineImages.map((i) => i.stagedId);
+      const results = await optimizeStagedImages([
+        ...stagedImages.map((a) => a.stagedId!),
+        ...inlineIds,
+      ]);
+
+      let before = 0;
+      let after = 0;
+      for (const result of results) {
+        before += result.original_size;
+        after += result.optimized_size;
+        const att = attachments.find((a) => a.stagedId === result.id);
+        if (att) {
+          updateAttachment(att.id, {
+            filename: result.filename,
+            mimeType: result.mime_type,
+            size: result.optimized_size,
+            // A resized image is held in memory, no longer read from the file
+            path: result.optimized_size < result.original_size ? undefined : att.path,
+          });
+        }
+      }
+      setOptimizeSummary(
+        after < before
+          ? `Images: ${formatFileSize(before)} → ${formatFileSize(after)}`
+          : "Images are already small",
+      );
+    } catch (err) {
+      console.error("Failed to resize images:", err);
+    } finally {
+      setIsOptimizing(false);
+    }
+  };
+
+  const handleRemove = (att: ComposerAttachment) => {
+    removeAttachment(att.id);
+    if (att.stagedId) unstageAttachment(att.stagedId).catch(() => {});
   };
 
   return (
     <div className="px-4">
-      <input
-        ref={inputRef}
-        type="file"
-        multiple
-        className="hidden"
-        onChange={(e) => {
-          if (e.target.files) handleFiles(e.target.files);
-        }}
-      />
-
       <div className="flex items-center gap-2 flex-wrap">
         <button
           type="button"
-          onClick={() => inputRef.current?.click()}
+          onClick={handlePick}
           className="flex items-center gap-1 text-xs text-text-tertiary hover:text-text-primary transition-colors py-1"
           title="Attach files"
         >
@@ -63,6 +115,13 @@ export function AttachmentPicker() {
             key={att.id}
             className="flex items-center gap-1.5 bg-bg-secondary border border-border-secondary rounded-md px-2 py-1 text-xs"
           >
+            {att.thumbnail && (
+              <img
+                src={att.thumbnail}
+                alt=""
+                className="w-5 h-5 rounded-sm object-cover"
+              />
+            )}
             <span className="text-text-primary truncate max-w-[150px]">
               {att.filename}
             </span>
@@ -70,7 +129,7 @@ export function AttachmentPicker() {
               {formatFileSize(att.size)}
             </span>
             <button
-              onClick={() => removeAttachment(att.id)}
+              onClick={() => handleRemove(att)}
               className="text-text-tertiary hover:text-text-primary"
             >
               <X size={12} />
@@ -78,12 +137,43 @@ export function AttachmentPicker() {
           </div>
         ))}
 
+        {stagedImages.length > 0 && (
+          <button
+            type="button"
+            onClick={handleOptimize}
+            disabled={isOptimizing}
+            className="flex items-center gap-1 text-xs text-text-tertiary hover:text-text-primary transition-colors py-1 disabled:opacity-50"
+            title="Resize images to 1600px and recompress as JPEG"
+          >
+            <ImageDown size={14} />
+            <span>{isOptimizing ? "Resizing..." : "Resize images"}</span>
+          </button>
+        )}
+
+        {stagedCount > 1 && (
+          <button
+            type="button"
+            onClick={() => setShowZip(true)}
+            className="flex items-center gap-1 text-xs text-text-tertiary hover:text-text-primary transition-colors py-1"
+            title="Bundle attachments into a zip"
+          >
+            <FileArchive size={14} />
+            <span>Zip</span>
+          </button>
+        )}
+
         {attachments.length > 0 && (
           <span className="text-xs text-text-tertiary">
             {formatFileSize(totalSize)} total
           </span>
         )}
+
+        {optimizeSummary && (
+          <span className="text-xs text-text-tertiary">{optimizeSummary}</span>
+        )}
       </div>
+
+      <ZipAttachmentsDialog isOpen={showZip} onClose={() => setShowZip(false)} />
     </div>
   );
 }
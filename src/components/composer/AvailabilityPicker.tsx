@@ -0,0 +1,163 @@
+import { useState, useEffect, useRef, useCallback } from "react";
+import { CalendarClock, ChevronDown, Loader2 } from "lucide-react";
+import { useAccountStore } from "@/stores/accountStore";
+import { useComposerStore } from "@/stores/composerStore";
+import {
+  fetchFreeSlots,
+  findCalDavAccount,
+  formatAvailability,
+  formatSlot,
+  isMeetingRelated,
+  suggestSlots,
+  type TimeSlot,
+} from "@/services/calendar/availability";
+import type { DbAccount } from "@/services/db/accounts";
+import type { Editor } from "@tiptap/react";
+
+interface AvailabilityPickerProps {
+  editor: Editor | null;
+}
+
+/** Days ahead, starting tomorrow, that suggestions are drawn from. */
+const LOOKAHEAD_DAYS = 7;
+
+/**
+ * Offers free times from the user's CalDAV calendar when replying to a
+ * thread about a meeting, and inserts the chosen ones into the reply.
+ */
+export function AvailabilityPicker({ editor }: AvailabilityPickerProps) {
+  const activeAccountId = useAccountStore((s) => s.activeAccountId);
+  const { mode, subject } = useComposerStore();
+  const [calDavAccount, setCalDavAccount] = useState<DbAccount | null>(null);
+  const [isOpen, setIsOpen] = useState(false);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [slots, setSlots] = useState<TimeSlot[]>([]);
+  const [selected, setSelected] = useState<Set<number>>(new Set());
+  const dropdownRef = useRef<HTMLDivElement | null>(null);
+
+  const isReply = mode === "reply" || mode === "replyAll" || mode === "replyList";
+  const visible = isReply && isMeetingRelated(subject) && calDavAccount !== null;
+
+  useEffect(() => {
+    if (!isReply) return;
+    findCalDavAccount(activeAccountId).then(setCalDavAccount).catch(() => setCalDavAccount(null));
+  }, [activeAccountId, isReply]);
+
+  // Close dropdown on outside click
+  useEffect(() => {
+    if (!isOpen) return;
+    const handleClick = (e: MouseEvent) => {
+      if (dropdownRef.current && !dropdownRef.current.contains(e.target as Node)) {
+        setIsOpen(false);
+      }
+    };
+    document.addEventListener("mousedown", handleClick);
+    return () => document.removeEventListener("mousedown", handleClick);
+  }, [isOpen]);
+
+  const handleOpen = useCallback(async () => {
+    if (isOpen) {
+      setIsOpen(false);
+      return;
+    }
+    if (!calDavAccount) return;
+    setIsOpen(true);
+    setLoading(true);
+    setError(null);
+    try {
+      const start = new Date();
+      start.setDate(start.getDate() + 1);
+      start.setHours(0, 0, 0, 0);
+      const end = new Date(start);
+      end.setDate(end.getDate() + LOOKAHEAD_DAYS);
+      const suggestions = suggestSlots(await fetchFreeSlots(calDavAccount, start, end));
+      setSlots(suggestions);
+      setSelected(new Set(suggestions.map((_, i) => i)));
+    } catch (err) {
+      setSlots([]);
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setLoading(false);
+    }
+  }, [isOpen, calDavAccount]);
+
+  const toggle = useCallback((index: number) => {
+    setSelected((prev) => {
+      const next = new Set(prev);
+      if (next.has(index)) next.delete(index);
+      else next.add(index);
+      return next;
+    });
+  }, []);
+
+  const handleInsert = useCallback(() => {
+    if (!editor) return;
+    const chosen = slots.filter((_, i) => selected.has(i));
+    if (chosen.length === 0) return;
+    editor.commands.insertContent(formatAvailability(chosen));
+    setIsOpen(false);
+  }, [editor, slots, selected]);
+
+  if (!visible) return null;
+
+  return (
+    <div className="relative" ref={dropdownRef}>
+      <button
+        onClick={handleOpen}
+        className="flex items-center gap-1 text-xs text-text-tertiary hover:text-text-secondary transition-colors"
+        title="Suggest free times from your calendar"
+      >
+        <CalendarClock size={12} />
+        Availability
+        <ChevronDown size={10} />
+      </button>
+
+      {isOpen && (
+        <div className="absolute bottom-full mb-1 left-0 bg-bg-primary border border-border-primary rounded-md shadow-lg glass-modal w-72 max-h-64 overflow-y-auto z-10">
+          {loading && (
+            <div className="flex items-center gap-2 px-3 py-2 text-xs text-text-secondary">
+              <Loader2 size={12} className="animate-spin" />
+              Checking your calendar...
+            </div>
+          )}
+          {!loading && error && (
+            <div className="px-3 py-2 text-xs text-danger">{error}</div>
+          )}
+          {!loading && !error && slots.length === 0 && (
+            <div className="px-3 py-2 text-xs text-text-tertiary">
+              No free time in working hours over the next {LOOKAHEAD_DAYS} days
+            </div>
+          )}
+          {!loading && slots.length > 0 && (
+            <>
+              {slots.map((slot, i) => (
+                <label
+                  key={slot.start}
+                  className="flex items-center gap-2 px-3 py-1.5 hover:bg-bg-hover text-xs text-text-primary cursor-pointer"
+                >
+                  <input
+                    type="checkbox"
+                    checked={selected.has(i)}
+                    onChange={() => toggle(i)}
+                    className="rounded border-border-primary text-accent focus:ring-accent"
+                  />
+                  {formatSlot(slot)}
+                </label>
+              ))}
+              <div className="flex justify-end px-3 py-2 border-t border-border-secondary">
+                <button
+                  onClick={handleInsert}
+                  disabled={selected.size === 0}
+                  className="px-3 py-1 text-xs font-medium text-white bg-accent hover:bg-accent-hover rounded-md transition-colors disabled:opacity-50"
+                >
+                  Insert
+                </button>
+              </div>
+            </>
+          )}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -4,7 +4,7 @@ import { useEditor, EditorContent } from "@tiptap/react";
 import StarterKit from "@tiptap/starter-kit";
 import Placeholder from "@tiptap/extension-placeholder";
 import Image from "@tiptap/extension-image";
-import { Clock, Maximize2, Minimize2, ExternalLink } from "lucide-react";
+import { AtSign, Clock, Maximize2, Minimize2, ExternalLink } from "lucide-react";
 
 import { Button } from "@/components/ui/Button";
 import { AddressInput } from "./AddressInput";
@@ -14,7 +14,10 @@ import { AttachmentPicker } from "./AttachmentPicker";
 import { ScheduleSendDialog } from "./ScheduleSendDialog";
 import { SignatureSelector } from "./SignatureSelector";
 import { TemplatePicker } from "./TemplatePicker";
+import { AvailabilityPicker } from "./AvailabilityPicker";
 import { FromSelector } from "./FromSelector";
+import { RecipientWarnings } from "./RecipientWarnings";
+import { SendWarnings } from "./SendWarnings";
 import { useComposerStore } from "@/stores/composerStore";
 import { useAccountStore } from "@/stores/accountStore";
 import { useUIStore } from "@/stores/uiStore";
@@ -24,11 +27,26 @@ import { upsertContact } from "@/services/db/contacts";
 import { getSetting } from "@/services/db/settings";
 import { insertScheduledEmail } from "@/services/db/scheduledEmails";
 import { getDefaultSignature } from "@/services/db/signatures";
+import { getMessage } from "@/services/db/messages";
+import { getAccount } from "@/services/db/accounts";
+import { resolveRecipientGroups } from "@/services/db/contactGroups";
+import { expandGroupRecipients, parseGroupRecipient } from "@/utils/contactGroups";
+import { registerAccountCredentials } from "@/services/imap/imapConfigBuilder";
 import { getAliasesForAccount, mapDbAlias, type SendAsAlias } from "@/services/db/sendAsAliases";
-import { resolveFromAddress } from "@/utils/resolveFromAddress";
+import { resolveFromAddress, resolveReplyFromEmail } from "@/utils/resolveFromAddress";
+import { plusAddressForRecipients } from "@/utils/plusAddress";
 import { startAutoSave, stopAutoSave } from "@/services/composer/draftAutoSave";
 import { getTemplatesForAccount, type DbTemplate } from "@/services/db/templates";
+import { getSnippetsForAccount } from "@/services/db/snippets";
+import { endsWithAbbreviation, expandSnippet, snippetToHtml } from "@/services/composer/snippets";
+import { checkSendPolicy, type SendWarning } from "@/services/composer/sendPolicy";
+import { getDisclaimer } from "@/services/composer/disclaimer";
 import { readFileAsBase64 } from "@/utils/fileUtils";
+import {
+  clipboardImageToAttachment,
+  readPickedFile,
+  readStagedAttachment,
+} from "@/services/attachments/staging";
 import { interpolateVariables } from "@/utils/templateVariables";
 import { sanitizeHtml } from "@/utils/sanitize";
 
@@ -42,6 +60,7 @@ export function Composer() {
   const subject = useComposerStore((s) => s.subject);
   const showCcBcc = useComposerStore((s) => s.showCcBcc);
   const fromEmail = useComposerStore((s) => s.fromEmail);
+  const plusAddressing = useComposerStore((s) => s.plusAddressing);
   const viewMode = useComposerStore((s) => s.viewMode);
   const signatureHtml = useComposerStore((s) => s.signatureHtml);
   const isSaving = useComposerStore((s) => s.isSaving);
@@ -55,6 +74,7 @@ export function Composer() {
   const setSubject = useComposerStore((s) => s.setSubject);
   const setShowCcBcc = useComposerStore((s) => s.setShowCcBcc);
   const setFromEmail = useComposerStore((s) => s.setFromEmail);
+  const setPlusAddressing = useComposerStore((s) => s.setPlusAddressing);
   const setViewMode = useComposerStore((s) => s.setViewMode);
   const addAttachment = useComposerStore((s) => s.addAttachment);
 
@@ -63,10 +83,12 @@ export function Composer() {
   const activeAccount = accounts.find((a) => a.id === activeAccountId);
   const sendingRef = useRef(false);
   const [showSchedule, setShowSchedule] = useState(false);
+  const [sendWarnings, setSendWarnings] = useState<SendWarning[]>([]);
   const [showAiAssist, setShowAiAssist] = useState(false);
   const [isDragging, setIsDragging] = useState(false);
   const [aliases, setAliases] = useState<SendAsAlias[]>([]);
   const templateShortcutsRef = useRef<DbTemplate[]>([]);
+  const snippetAbbreviationsRef = useRef<string[]>([]);
   const dragCounterRef = useRef(0);
   const overlayRef = useRef<HTMLDivElement | null>(null);
 
@@ -90,8 +112,6 @@ export function Composer() {
 
       // Check for template shortcut triggers
       const templates = templateShortcutsRef.current;
-      if (templates.length === 0) return;
-
       const text = ed.state.doc.textContent;
       for (const tmpl of templates) {
         if (!tmpl.shortcut) continue;
@@ -119,9 +139,43 @@ export function Composer() {
               setSubject(tmpl.subject);
             }
           }
-          break;
+          return;
         }
       }
+
+      // Check for snippet abbreviations just before the caret
+      const { from: caret, $from } = ed.state.selection;
+      const before = $from.parent.textBetween(0, $from.parentOffset, undefined, "\n");
+      if (!endsWithAbbreviation(before, snippetAbbreviationsRef.current)) return;
+      const state = useComposerStore.getState();
+      const { accounts, activeAccountId: accountId } = useAccountStore.getState();
+      const account = accounts.find((a) => a.id === accountId);
+      expandSnippet(before, accountId, {
+        recipient_email: state.to[0] ?? null,
+        recipient_name: null,
+        sender_email: account?.email ?? null,
+        sender_name: account?.displayName ?? null,
+        subject: state.subject || null,
+      })
+        .then((expansion) => {
+          if (!expansion) return;
+          // Leave the text alone if it changed while expanding
+          const start = caret - expansion.abbreviation.length;
+          if (
+            ed.state.selection.from !== caret ||
+            ed.state.doc.textBetween(start, caret) !== expansion.abbreviation
+          ) {
+            return;
+          }
+          ed.chain()
+            .deleteRange({ from: start, to: caret })
+            .insertContent(snippetToHtml(expansion.text))
+            .run();
+          if (expansion.cursor !== null) {
+            ed.commands.setTextSelection(start + expansion.cursor);
+          }
+        })
+        .catch((err) => console.error("Failed to expand snippet:", err));
     },
     editorProps: {
       attributes: {
@@ -137,6 +191,27 @@ export function Composer() {
         }
         return false;
       },
+      handlePaste: (view, event) => {
+        // Pasted images are read from the system clipboard by the backend and
+        // staged as inline parts, instead of being inlined as base64 here.
+        const hasImage = Array.from(event.clipboardData?.items ?? []).some(
+          (item) => item.kind === "file" && item.type.startsWith("image/"),
+        );
+        if (!hasImage) return false;
+
+        clipboardImageToAttachment()
+          .then((staged) => {
+            useComposerStore.getState().addInlineImage({
+              stagedId: staged.id,
+              contentId: staged.content_id,
+              src: staged.preview,
+            });
+            const node = view.state.schema.nodes.image?.create({ src: staged.preview });
+            if (node) view.dispatch(view.state.tr.replaceSelectionWith(node));
+          })
+          .catch((err) => console.error("Failed to paste image:", err));
+        return true;
+      },
     },
   });
 
@@ -144,12 +219,18 @@ export function Composer() {
   useEffect(() => {
     if (!isOpen || !activeAccountId) return;
     let cancelled = false;
+    const { mode, inReplyToMessageId } = useComposerStore.getState();
+    const isResponse = mode === "reply" || mode === "replyAll" || mode === "replyList" || mode === "forward";
 
     Promise.all([
       getDefaultSignature(activeAccountId),
       getAliasesForAccount(activeAccountId),
       getTemplatesForAccount(activeAccountId),
-    ]).then(([sig, dbAliases, templates]) => {
+      getSnippetsForAccount(activeAccountId),
+      isResponse && inReplyToMessageId
+        ? getMessage(activeAccountId, inReplyToMessageId).catch(() => null)
+        : Promise.resolve(null),
+    ]).then(([sig, dbAliases, templates, snippets, original]) => {
       if (cancelled) return;
       const store = useComposerStore.getState();
 
@@ -162,8 +243,13 @@ export function Composer() {
       // Aliases + fromEmail resolution
       const mapped = dbAliases.map(mapDbAlias);
       setAliases(mapped);
-      if (!store.fromEmail && mapped.length > 0) {
-        if (store.mode === "reply" || store.mode === "replyAll" || store.mode === "forward") {
+      const accountEmail = useAccountStore.getState().accounts.find((a) => a.id === activeAccountId)?.email;
+      if (!store.fromEmail && original && accountEmail) {
+        // Reply from the alias (or catch-all address) the original was delivered to
+        const resolved = resolveReplyFromEmail(mapped, accountEmail, original);
+        if (resolved) store.setFromEmail(resolved);
+      } else if (!store.fromEmail && mapped.length > 0) {
+        if (isResponse) {
           const resolved = resolveFromAddress(mapped, store.to.join(", "), store.cc.join(", "));
           if (resolved) store.setFromEmail(resolved.email);
         } else {
@@ -174,6 +260,7 @@ export function Composer() {
 
       // Templates
       templateShortcutsRef.current = templates.filter((t) => t.shortcut);
+      snippetAbbreviationsRef.current = snippets.map((s) => s.abbreviation);
     });
 
     return () => { cancelled = true; };
@@ -233,18 +320,28 @@ export function Composer() {
     return `${editorHtml}<div style="margin-top:16px;border-top:1px solid #e5e5e5;padding-top:12px">${sanitizeHtml(signatureHtml)}</div>`;
   }, [editor, signatureHtml]);
 
-  const handleSend = useCallback(async () => {
+  const handleSend = useCallback(async (confirmed = false) => {
     if (!activeAccountId || !activeAccount || sendingRef.current) return;
     const state = useComposerStore.getState();
     if (state.to.length === 0) return;
 
     sendingRef.current = true;
-    stopAutoSave();
+    setSendWarnings([]);
 
     const html = getFullHtml();
-    const senderEmail = state.fromEmail ?? activeAccount.email;
+    const recipients = [...state.to, ...state.cc, ...state.bcc];
+    const groups = await resolveRecipientGroups(recipients);
+    const groupSyntax = (await getSetting("contact_group_syntax")) === "true";
+    const baseSender = state.fromEmail ?? activeAccount.email;
+    // Replies to the plus address keep the tag, so leaks stay traceable
+    const plusSender = state.plusAddressing
+      ? plusAddressForRecipients(baseSender, expandGroupRecipients(state.to, groups))
+      : null;
+    const senderEmail = plusSender ?? baseSender;
+    const disclaimer = await getDisclaimer(activeAccount.email);
     const raw = buildRawEmail({
       from: senderEmail,
+      replyTo: plusSender ?? undefined,
       to: state.to,
       cc: state.cc.length > 0 ? state.cc : undefined,
       bcc: state.bcc.length > 0 ? state.bcc : undefined,
@@ -257,10 +354,29 @@ export function Composer() {
             filename: a.filename,
             mimeType: a.mimeType,
             content: a.content,
+            stagedId: a.stagedId,
           }))
         : undefined,
+      stagedInlineImages: state.inlineImages,
+      groups,
+      groupSyntax,
+      disclaimer,
     });
 
+    // External recipients, long Cc lists etc. need a second click
+    if (!confirmed) {
+      const warnings = await checkSendPolicy(activeAccountId, raw).catch((err) => {
+        console.warn("Send policy check failed:", err);
+        return [];
+      });
+      if (warnings.length > 0) {
+        setSendWarnings(warnings);
+        sendingRef.current = false;
+        return;
+      }
+    }
+    stopAutoSave();
+
     // Get undo send delay
     const delaySetting = await getSetting("undo_send_delay_seconds");
     const delay = parseInt(delaySetting ?? "5", 10) * 1000;
@@ -284,7 +400,7 @@ export function Composer() {
         }
 
         // Update contacts frequency
-        for (const addr of [...state.to, ...state.cc, ...state.bcc]) {
+        for (const addr of expandGroupRecipients(recipients, groups)) {
           await upsertContact(addr, null);
         }
       } catch (err) {
@@ -299,26 +415,44 @@ export function Composer() {
     closeComposer();
   }, [activeAccountId, activeAccount, closeComposer, getFullHtml]);
 
+  const handleReplaceRecipient = useCallback((address: string, replacement: string) => {
+    const state = useComposerStore.getState();
+    const swap = (list: string[]) => list.map((a) => (a === address ? replacement : a));
+    state.setTo(swap(state.to));
+    state.setCc(swap(state.cc));
+    state.setBcc(swap(state.bcc));
+  }, []);
+
   const handleSchedule = useCallback(async (scheduledAt: number) => {
     if (!activeAccountId || !activeAccount) return;
     const state = useComposerStore.getState();
     if (state.to.length === 0) return;
 
     const html = getFullHtml();
+    const groups = await resolveRecipientGroups([...state.to, ...state.cc, ...state.bcc]);
+    const listed = (list: string[]) => expandGroupRecipients(list, groups).join(", ");
 
+    // Staged attachments are only held for this session, so persist their
+    // content. Forwarded messages may still need downloading from the server.
+    const dbAccount = activeAccount.provider === "imap" ? await getAccount(activeAccountId) : null;
+    const imapAccountId = dbAccount ? await registerAccountCredentials(dbAccount) : undefined;
     const attachmentData = state.attachments.length > 0
-      ? JSON.stringify(state.attachments.map((a) => ({
+      ? JSON.stringify(await Promise.all(state.attachments.map(async (a) => ({
           filename: a.filename,
           mimeType: a.mimeType,
-          content: a.content,
-        })))
+          content: a.path
+            ? await readPickedFile(a.path)
+            : a.stagedId
+              ? await readStagedAttachment(a.stagedId, imapAccountId)
+              : a.content,
+        }))))
       : null;
 
     await insertScheduledEmail({
       accountId: activeAccountId,
-      toAddresses: state.to.join(", "),
-      ccAddresses: state.cc.length > 0 ? state.cc.join(", ") : null,
-      bccAddresses: state.bcc.length > 0 ? state.bcc.join(", ") : null,
+      toAddresses: listed(state.to),
+      ccAddresses: state.cc.length > 0 ? listed(state.cc) : null,
+      bccAddresses: state.bcc.length > 0 ? listed(state.bcc) : null,
       subject: state.subject,
       bodyHtml: html,
       replyToMessageId: state.inReplyToMessageId,
@@ -412,15 +546,19 @@ export function Composer() {
   }, [editor, closeComposer]);
 
   const isFullpage = viewMode === "fullpage";
+  // The frameless quick-compose window: no window chrome, so the header drags it
+  const isCompact = viewMode === "compact";
 
   const modeLabel =
     mode === "reply"
       ? "Reply"
       : mode === "replyAll"
         ? "Reply All"
-        : mode === "forward"
-          ? "Forward"
-          : "New Message";
+        : mode === "replyList"
+          ? "Reply to List"
+          : mode === "forward"
+            ? "Forward"
+            : "New Message";
 
   const savedLabel = isSaving
     ? "Saving..."
@@ -430,7 +568,7 @@ export function Composer() {
 
   return (
     <CSSTransition nodeRef={overlayRef} in={isOpen} timeout={200} classNames="slide-up" unmountOnExit>
-    <div ref={overlayRef} className={`fixed inset-0 z-50 flex ${isFullpage ? "items-stretch justify-center p-4" : "items-end justify-center pb-4"} pointer-events-none`}>
+    <div ref={overlayRef} className={`fixed inset-0 z-50 flex ${isCompact ? "items-stretch" : isFullpage ? "items-stretch justify-center p-4" : "items-end justify-center pb-4"} pointer-events-none`}>
       {/* Backdrop */}
       <div
         className="absolute inset-0 pointer-events-auto backdrop-animate"
@@ -440,7 +578,7 @@ export function Composer() {
       {/* Composer window */}
       <div
         className={`relative bg-bg-primary border rounded-lg glass-modal pointer-events-auto flex flex-col slide-up-panel ${
-          isFullpage ? "w-full h-full max-w-5xl" : "w-full max-w-2xl max-h-[80vh]"
+          isCompact ? "w-full h-full" : isFullpage ? "w-full h-full max-w-5xl" : "w-full max-w-2xl max-h-[80vh]"
         } ${isDragging ? "border-accent border-2" : "border-border-primary"}`}
         onDragEnter={handleDragEnter}
         onDragLeave={handleDragLeave}
@@ -454,25 +592,32 @@ export function Composer() {
         )}
 
         {/* Header */}
-        <div className="flex items-center justify-between px-4 py-2.5 border-b border-border-primary bg-bg-secondary rounded-t-lg">
-          <span className="text-sm font-medium text-text-primary">
+        <div
+          data-tauri-drag-region={isCompact || undefined}
+          className={`flex items-center justify-between px-4 border-b border-border-primary bg-bg-secondary rounded-t-lg ${isCompact ? "py-1.5" : "py-2.5"}`}
+        >
+          <span data-tauri-drag-region={isCompact || undefined} className="text-sm font-medium text-text-primary">
             {modeLabel}
           </span>
           <div className="flex items-center gap-1">
-            <button
-              onClick={() => setViewMode(isFullpage ? "modal" : "fullpage")}
-              className="text-text-tertiary hover:text-text-primary p-1 rounded transition-colors"
-              title={isFullpage ? "Collapse" : "Expand"}
-            >
-              {isFullpage ? <Minimize2 size={14} /> : <Maximize2 size={14} />}
-            </button>
-            <button
-              onClick={handlePopOutComposer}
-              className="text-text-tertiary hover:text-text-primary p-1 rounded transition-colors"
-              title="Open in new window"
-            >
-              <ExternalLink size={14} />
-            </button>
+            {!isCompact && (
+              <>
+                <button
+                  onClick={() => setViewMode(isFullpage ? "modal" : "fullpage")}
+                  className="text-text-tertiary hover:text-text-primary p-1 rounded transition-colors"
+                  title={isFullpage ? "Collapse" : "Expand"}
+                >
+                  {isFullpage ? <Minimize2 size={14} /> : <Maximize2 size={14} />}
+                </button>
+                <button
+                  onClick={handlePopOutComposer}
+                  className="text-text-tertiary hover:text-text-primary p-1 rounded transition-colors"
+                  title="Open in new window"
+                >
+                  <ExternalLink size={14} />
+                </button>
+              </>
+            )}
             <button
               onClick={closeComposer}
               className="text-text-tertiary hover:text-text-primary text-lg leading-none p-1"
@@ -503,6 +648,10 @@ export function Composer() {
               Cc / Bcc
             </button>
           )}
+          <RecipientWarnings
+            addresses={[...to, ...cc, ...bcc].filter((a) => parseGroupRecipient(a) === null)}
+            onReplace={handleReplaceRecipient}
+          />
         </div>
 
         {/* Subject */}
@@ -522,17 +671,19 @@ export function Composer() {
         </div>
 
         {/* Editor toolbar */}
-        <EditorToolbar
-          editor={editor}
-          onToggleAiAssist={() => setShowAiAssist(!showAiAssist)}
-          aiAssistOpen={showAiAssist}
-        />
+        {!isCompact && (
+          <EditorToolbar
+            editor={editor}
+            onToggleAiAssist={() => setShowAiAssist(!showAiAssist)}
+            aiAssistOpen={showAiAssist}
+          />
+        )}
 
         {/* AI Assist Panel */}
-        {showAiAssist && (
+        {showAiAssist && !isCompact && (
           <AiAssistPanel
             editor={editor}
-            isReplyMode={mode === "reply" || mode === "replyAll"}
+            isReplyMode={mode === "reply" || mode === "replyAll" || mode === "replyList"}
           />
         )}
 
@@ -552,19 +703,44 @@ export function Composer() {
           <AttachmentPicker />
         </div>
 
+        {sendWarnings.length > 0 && (
+          <SendWarnings
+            warnings={sendWarnings}
+            onSendAnyway={() => handleSend(true)}
+            onCancel={() => setSendWarnings([])}
+          />
+        )}
+
         {/* Footer */}
         <div className="flex items-center justify-between px-4 py-2.5 border-t border-border-primary bg-bg-secondary rounded-b-lg">
           <div className="flex items-center gap-3">
             <div className="text-xs text-text-tertiary">
-              {fromEmail ?? activeAccount?.email ?? "No account"}
+              {(plusAddressing && activeAccount
+                ? plusAddressForRecipients(fromEmail ?? activeAccount.email, to)
+                : null) ?? fromEmail ?? activeAccount?.email ?? "No account"}
             </div>
+            {!isCompact && (
+              <button
+                onClick={() => setPlusAddressing(!plusAddressing)}
+                className={`p-1 rounded transition-colors ${plusAddressing ? "text-accent bg-accent/10" : "text-text-tertiary hover:text-text-primary hover:bg-bg-hover"}`}
+                title="Send from a per-recipient address (you+tag@…) to see who shares it"
+                aria-pressed={plusAddressing}
+              >
+                <AtSign size={13} />
+              </button>
+            )}
             {savedLabel && (
               <span className={`text-xs text-text-tertiary italic transition-opacity duration-200 ${isSaving ? "animate-pulse" : ""}`}>
                 {savedLabel}
               </span>
             )}
-            <SignatureSelector />
-            <TemplatePicker editor={editor} />
+            {!isCompact && (
+              <>
+                <SignatureSelector />
+                <TemplatePicker editor={editor} />
+                <AvailabilityPicker editor={editor} />
+              </>
+            )}
           </div>
           <div className="flex items-center gap-2">
             <Button
@@ -575,7 +751,7 @@ export function Composer() {
             </Button>
             <div className="flex items-center">
               <button
-                onClick={handleSend}
+                onClick={() => handleSend()}
                 disabled={to.length === 0}
                 className="px-4 py-1.5 text-xs font-medium text-white bg-accent hover:bg-accent-hover rounded-l-md transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
               >
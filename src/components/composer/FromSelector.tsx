@@ -8,10 +8,13 @@ interface FromSelectorProps {
 
 /**
  * Dropdown for selecting a send-as alias in the composer.
- * Only visible when more than one alias is available.
+ * Only visible when more than one address is available. A selected address
+ * that isn't an alias (e.g. a catch-all address a reply defaulted to) is
+ * listed as an extra option.
  */
 export function FromSelector({ aliases, selectedEmail, onChange }: FromSelectorProps) {
-  if (aliases.length <= 1) return null;
+  const isExtra = !!selectedEmail && !aliases.some((a) => a.email === selectedEmail);
+  if (aliases.length + (isExtra ? 1 : 0) <= 1) return null;
 
   return (
     <div className="flex items-center gap-2">
@@ -26,6 +29,7 @@ export function FromSelector({ aliases, selectedEmail, onChange }: FromSelectorP
         }}
         className="flex-1 bg-transparent text-sm text-text-primary outline-none cursor-pointer hover:bg-bg-hover rounded px-1 py-0.5 -ml-1 border-none"
       >
+        {isExtra && <option value={selectedEmail}>{selectedEmail}</option>}
         {aliases.map((alias) => (
           <option key={alias.id} value={alias.email}>
             {alias.displayName
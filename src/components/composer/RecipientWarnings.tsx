@@ -0,0 +1,66 @@
+import { useEffect, useState } from "react";
+import { AlertTriangle } from "lucide-react";
+import {
+  recipientIssues,
+  validateRecipients,
+  type RecipientIssue,
+} from "@/services/composer/recipientValidation";
+
+interface RecipientWarningsProps {
+  addresses: string[];
+  onReplace: (address: string, replacement: string) => void;
+}
+
+/** Delay after the last recipient change before checking (DNS lookups). */
+const CHECK_DELAY_MS = 600;
+
+/**
+ * Inline warnings for recipients that look wrong: bad syntax, a domain that
+ * doesn't accept mail, or a likely typo with a one-click fix.
+ */
+export function RecipientWarnings({ addresses, onReplace }: RecipientWarningsProps) {
+  const [issues, setIssues] = useState<RecipientIssue[]>([]);
+  const key = addresses.join("\n");
+
+  useEffect(() => {
+    if (!key) {
+      setIssues([]);
+      return;
+    }
+    let cancelled = false;
+    const timer = setTimeout(() => {
+      validateRecipients(key.split("\n"))
+        .then((checks) => {
+          if (!cancelled) setIssues(recipientIssues(checks));
+        })
+        .catch((err) => console.warn("Recipient validation failed:", err));
+    }, CHECK_DELAY_MS);
+    return () => {
+      cancelled = true;
+      clearTimeout(timer);
+    };
+  }, [key]);
+
+  if (issues.length === 0) return null;
+
+  return (
+    <div className="ml-10 space-y-0.5">
+      {issues.map((issue) => (
+        <div key={issue.address} className="flex items-center gap-1.5 text-xs text-warning">
+          <AlertTriangle size={12} className="shrink-0" />
+          <span className="truncate">
+            {issue.address}: {issue.message}
+          </span>
+          {issue.suggestion && (
+            <button
+              onClick={() => onReplace(issue.address, issue.suggestion!)}
+              className="text-accent hover:text-accent-hover shrink-0"
+            >
+              Use {issue.suggestion}
+            </button>
+          )}
+        </div>
+      ))}
+    </div>
+  );
+}
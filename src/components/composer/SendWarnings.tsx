@@ -0,0 +1,38 @@
+import { AlertTriangle } from "lucide-react";
+import { Button } from "@/components/ui/Button";
+import type { SendWarning } from "@/services/composer/sendPolicy";
+
+interface SendWarningsProps {
+  warnings: SendWarning[];
+  onSendAnyway: () => void;
+  onCancel: () => void;
+}
+
+/** Send policy warnings shown in place of sending until the user confirms. */
+export function SendWarnings({ warnings, onSendAnyway, onCancel }: SendWarningsProps) {
+  return (
+    <div className="px-4 py-2.5 border-t border-border-primary bg-warning/10 space-y-1.5">
+      {warnings.map((warning) => (
+        <div key={warning.kind} className="text-xs text-warning">
+          <div className="flex items-center gap-1.5">
+            <AlertTriangle size={12} className="shrink-0" />
+            <span>{warning.message}</span>
+          </div>
+          {warning.addresses.length > 0 && (
+            <div className="ml-[18px] text-text-tertiary truncate">
+              {warning.addresses.join(", ")}
+            </div>
+          )}
+        </div>
+      ))}
+      <div className="flex justify-end gap-2">
+        <Button variant="secondary" onClick={onCancel}>
+          Back to draft
+        </Button>
+        <Button variant="primary" onClick={onSendAnyway}>
+          Send anyway
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,128 @@
+import { useEffect, useState } from "react";
+import { Modal } from "@/components/ui/Modal";
+import { Button } from "@/components/ui/Button";
+import { useComposerStore } from "@/stores/composerStore";
+import { zipStagedAttachments } from "@/services/attachments/staging";
+import { formatFileSize } from "@/utils/fileTypeHelpers";
+
+interface ZipAttachmentsDialogProps {
+  isOpen: boolean;
+  onClose: () => void;
+}
+
+export function ZipAttachmentsDialog({ isOpen, onClose }: ZipAttachmentsDialogProps) {
+  const attachments = useComposerStore((s) => s.attachments);
+  const addAttachment = useComposerStore((s) => s.addAttachment);
+  const removeAttachment = useComposerStore((s) => s.removeAttachment);
+  const staged = attachments.filter((a) => a.stagedId);
+
+  const [selected, setSelected] = useState<Set<string>>(new Set());
+  const [archiveName, setArchiveName] = useState("attachments");
+  const [password, setPassword] = useState("");
+  const [isZipping, setIsZipping] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  // Select everything each time the dialog opens
+  useEffect(() => {
+    if (!isOpen) return;
+    setSelected(new Set(useComposerStore.getState().attachments
+      .filter((a) => a.stagedId)
+      .map((a) => a.id)));
+    setArchiveName("attachments");
+    setPassword("");
+    setError(null);
+  }, [isOpen]);
+
+  const toggle = (id: string) => {
+    setSelected((prev) => {
+      const next = new Set(prev);
+      if (next.has(id)) next.delete(id);
+      else next.add(id);
+      return next;
+    });
+  };
+
+  const handleZip = async () => {
+    const chosen = staged.filter((a) => selected.has(a.id));
+    if (chosen.length === 0) return;
+
+    setIsZipping(true);
+    setError(null);
+    try {
+      const archive = await zipStagedAttachments(
+        chosen.map((a) => a.stagedId!),
+        archiveName,
+        password || undefined,
+      );
+      for (const att of chosen) removeAttachment(att.id);
+      addAttachment({
+        id: crypto.randomUUID(),
+        filename: archive.filename,
+        mimeType: archive.mime_type,
+        size: archive.size,
+        stagedId: archive.id,
+      });
+      onClose();
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setIsZipping(false);
+    }
+  };
+
+  return (
+    <Modal isOpen={isOpen} onClose={onClose} title="Zip Attachments" width="w-96">
+      <div className="p-4 space-y-3">
+        <div className="max-h-40 overflow-y-auto space-y-1">
+          {staged.map((att) => (
+            <label key={att.id} className="flex items-center gap-2 text-sm text-text-primary">
+              <input
+                type="checkbox"
+                checked={selected.has(att.id)}
+                onChange={() => toggle(att.id)}
+              />
+              <span className="truncate flex-1">{att.filename}</span>
+              <span className="text-xs text-text-tertiary">{formatFileSize(att.size)}</span>
+            </label>
+          ))}
+        </div>
+        <div>
+          <label className="block text-xs font-medium text-text-secondary mb-1">
+            Archive name
+          </label>
+          <input
+            type="text"
+            value={archiveName}
+            onChange={(e) => setArchiveName(e.target.value)}
+            placeholder="attachments"
+            className="w-full bg-bg-tertiary text-text-primary text-sm px-3 py-1.5 rounded-md border border-border-primary focus:border-accent focus:outline-none placeholder:text-text-tertiary"
+          />
+        </div>
+        <div>
+          <label className="block text-xs font-medium text-text-secondary mb-1">
+            Password (optional, AES-256)
+          </label>
+          <input
+            type="password"
+            value={password}
+            onChange={(e) => setPassword(e.target.value)}
+            className="w-full bg-bg-tertiary text-text-primary text-sm px-3 py-1.5 rounded-md border border-border-primary focus:border-accent focus:outline-none"
+          />
+        </div>
+        {error && <p className="text-xs text-danger">{error}</p>}
+        <div className="flex justify-end gap-2 pt-1">
+          <Button variant="secondary" onClick={onClose}>
+            Cancel
+          </Button>
+          <Button
+            variant="primary"
+            onClick={handleZip}
+            disabled={selected.size === 0 || isZipping}
+          >
+            {isZipping ? "Zipping..." : "Zip"}
+          </Button>
+        </div>
+      </div>
+    </Modal>
+  );
+}
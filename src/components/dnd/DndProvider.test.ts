@@ -42,6 +42,17 @@ describe("resolveLabelChange", () => {
     });
   });
 
+  it("refuses drops onto the Feeds folder", () => {
+    expect(resolveLabelChange("feeds", "inbox")).toBeNull();
+  });
+
+  it("takes feed entries out of Feeds when dragged to trash", () => {
+    expect(resolveLabelChange("trash", "feeds")).toEqual({
+      addLabelIds: ["TRASH"],
+      removeLabelIds: ["FEEDS"],
+    });
+  });
+
   it("works with user label IDs (not in LABEL_MAP)", () => {
     const result = resolveLabelChange("Label_1", "inbox");
     expect(result).toEqual({
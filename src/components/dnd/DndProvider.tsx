@@ -21,6 +21,7 @@ const LABEL_MAP: Record<string, string> = {
   trash: "TRASH",
   spam: "SPAM",
   snoozed: "SNOOZED",
+  feeds: "FEEDS",
   all: "",
 };
 
@@ -43,6 +44,9 @@ export function resolveLabelChange(
   // No-op if same label
   if (targetGmailId === sourceGmailId) return null;
 
+  // The Feeds folder only holds feed entries
+  if (targetGmailId === "FEEDS") return null;
+
   // Dragging to trash: add TRASH, remove source (if specific)
   if (targetGmailId === "TRASH") {
     const removeLabelIds = sourceGmailId && sourceGmailId !== "" ? [sourceGmailId] : [];
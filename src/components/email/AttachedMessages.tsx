@@ -0,0 +1,163 @@
+import { useEffect, useState } from "react";
+import { ChevronDown, ChevronRight, Mail, Reply } from "lucide-react";
+import { getEmailProvider } from "@/services/email/providerFactory";
+import type { ParsedAttachedMessage, ParsedMessage } from "@/services/gmail/messageParser";
+import type { DbAttachment } from "@/services/db/attachments";
+import { useComposerStore } from "@/stores/composerStore";
+import { formatFullDate } from "@/utils/date";
+import { escapeHtml, sanitizeHtml } from "@/utils/sanitize";
+import { EmailRenderer } from "./EmailRenderer";
+import { AttachmentList } from "./AttachmentList";
+
+interface AttachedMessagesProps {
+  accountId: string;
+  messageId: string;
+  blockImages: boolean;
+}
+
+/** Emails attached as message/rfc822, rendered inline below the body. */
+export function AttachedMessages({ accountId, messageId, blockImages }: AttachedMessagesProps) {
+  const [attached, setAttached] = useState<ParsedAttachedMessage[]>([]);
+
+  useEffect(() => {
+    let cancelled = false;
+    getEmailProvider(accountId)
+      .then((provider) => provider.fetchMessage(messageId))
+      .then((parsed) => {
+        if (!cancelled) setAttached(parsed.attachedMessages ?? []);
+      })
+      .catch((err) => console.error("Failed to load attached emails:", err));
+    return () => {
+      cancelled = true;
+    };
+  }, [accountId, messageId]);
+
+  if (attached.length === 0) return null;
+
+  return (
+    <div className="mt-3 space-y-2">
+      {attached.map((a) => (
+        <AttachedMessageCard
+          key={a.partId}
+          accountId={accountId}
+          messageId={messageId}
+          attached={a}
+          blockImages={blockImages}
+        />
+      ))}
+    </div>
+  );
+}
+
+function AttachedMessageCard({
+  accountId,
+  messageId,
+  attached,
+  blockImages,
+}: {
+  accountId: string;
+  messageId: string;
+  attached: ParsedAttachedMessage;
+  blockImages: boolean;
+}) {
+  const [expanded, setExpanded] = useState(true);
+  const openComposer = useComposerStore((s) => s.openComposer);
+  const msg = attached.message;
+  const fromDisplay = msg.fromName ?? msg.fromAddress ?? "Unknown";
+
+  // Part ids are relative to the outer message, so fetch through its id
+  const attachments: DbAttachment[] = msg.attachments.map((att) => ({
+    id: `${messageId}:${att.gmailAttachmentId}`,
+    message_id: messageId,
+    account_id: accountId,
+    filename: att.filename,
+    mime_type: att.mimeType,
+    size: att.size,
+    gmail_attachment_id: att.gmailAttachmentId,
+    content_id: att.contentId,
+    is_inline: att.isInline ? 1 : 0,
+    local_path: null,
+  }));
+
+  const handleReply = () => {
+    const replyTo = msg.replyTo ?? msg.fromAddress;
+    openComposer({
+      mode: "reply",
+      to: replyTo ? [replyTo] : [],
+      subject: `Re: ${msg.subject ?? ""}`,
+      bodyHtml: buildAttachedQuote(msg),
+    });
+  };
+
+  return (
+    <div className="rounded-md border border-border-primary">
+      <div className="flex items-center justify-between gap-2 px-3 py-2 bg-bg-secondary rounded-t-md">
+        <button
+          onClick={() => setExpanded(!expanded)}
+          className="flex items-center gap-2 min-w-0 text-left"
+        >
+          {expanded ? <ChevronDown size={14} /> : <ChevronRight size={14} />}
+          <Mail size={14} className="text-text-tertiary shrink-0" />
+          <div className="min-w-0">
+            <span className="text-sm font-medium text-text-primary truncate block">
+              {msg.subject ?? "(no subject)"}
+            </span>
+            <span className="text-xs text-text-tertiary truncate block">
+              {fromDisplay}
+              {msg.date > 0 && ` · ${formatFullDate(msg.date)}`}
+            </span>
+          </div>
+        </button>
+        <button
+          onClick={handleReply}
+          className="flex items-center gap-1.5 px-2.5 py-1 text-xs text-text-secondary border border-border-primary hover:bg-bg-hover rounded-md transition-colors shrink-0"
+          title="Reply to the attached email"
+        >
+          <Reply size={13} />
+          Reply
+        </button>
+      </div>
+
+      {expanded && (
+        <div className="px-3 pb-3">
+          {msg.toAddresses && (
+            <div className="pt-2 text-xs text-text-tertiary">To: {msg.toAddresses}</div>
+          )}
+          <EmailRenderer
+            html={msg.bodyHtml}
+            text={msg.bodyText}
+            blockImages={blockImages}
+            senderAddress={msg.fromAddress}
+            accountId={accountId}
+            messageId={messageId}
+            inlineAttachments={attachments.filter((a) => a.content_id)}
+          />
+          <AttachmentList
+            accountId={accountId}
+            messageId={messageId}
+            attachments={attachments}
+          />
+          {msg.attachedMessages?.map((nested) => (
+            <div key={nested.partId} className="mt-3">
+              <AttachedMessageCard
+                accountId={accountId}
+                messageId={messageId}
+                attached={nested}
+                blockImages={blockImages}
+              />
+            </div>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+}
+
+function buildAttachedQuote(msg: ParsedMessage): string {
+  const date = new Date(msg.date).toLocaleString();
+  const from = msg.fromName
+    ? `${escapeHtml(msg.fromName)} &lt;${escapeHtml(msg.fromAddress ?? "")}&gt;`
+    : escapeHtml(msg.fromAddress ?? "Unknown");
+  const body = msg.bodyHtml ? sanitizeHtml(msg.bodyHtml) : escapeHtml(msg.bodyText ?? "");
+  return `<br><br><div style="border-left:2px solid #ccc;padding-left:12px;margin-left:0;color:#666">On ${date}, ${from} wrote:<br>${body}</div>`;
+}
@@ -3,8 +3,10 @@ import { save } from "@tauri-apps/plugin-dialog";
 import { writeFile } from "@tauri-apps/plugin-fs";
 import { getAttachmentsForMessage, type DbAttachment } from "@/services/db/attachments";
 import { getEmailProvider } from "@/services/email/providerFactory";
+import { openAttachment } from "@/services/attachments/openAttachment";
+import { isDangerousAttachmentError } from "@/services/attachments/openAttachmentFile";
 import { Modal } from "@/components/ui/Modal";
-import { Download, Eye } from "lucide-react";
+import { Download, ExternalLink, Eye } from "lucide-react";
 import { formatFileSize, isImage, isPdf, isText, canPreview, getFileIcon } from "@/utils/fileTypeHelpers";
 
 /** Dedup attachments by filename+size (content-based) */
@@ -91,6 +93,7 @@ export function AttachmentPreview({
 }) {
   const [loading, setLoading] = useState(false);
   const [saving, setSaving] = useState(false);
+  const [opening, setOpening] = useState(false);
   const [blobUrl, setBlobUrl] = useState<string | null>(null);
   const [error, setError] = useState<string | null>(null);
   const bytesRef = useRef<Uint8Array | null>(null);
@@ -164,6 +167,25 @@ export function AttachmentPreview({
     }
   };
 
+  const handleOpen = async () => {
+    if (opening) return;
+
+    setOpening(true);
+    try {
+      await openAttachment(accountId, messageId, attachment);
+    } catch (err) {
+      if (isDangerousAttachmentError(err)) {
+        // Programs and scripts are never launched from an email; save instead
+        console.warn("Not opening attachment that would run as a program:", err);
+        await handleDownload();
+      } else {
+        console.error("Failed to open attachment:", err);
+      }
+    } finally {
+      setOpening(false);
+    }
+  };
+
   const handleClose = () => {
     if (blobUrl) URL.revokeObjectURL(blobUrl);
     onClose();
@@ -183,6 +205,15 @@ export function AttachmentPreview({
         )}
       </div>
       <div className="flex items-center gap-2 shrink-0 ml-4">
+        <button
+          onClick={handleOpen}
+          disabled={opening}
+          className="flex items-center gap-1.5 px-3 py-1.5 text-xs font-medium text-text-secondary border border-border-primary hover:bg-bg-hover rounded-md transition-colors disabled:opacity-50"
+          title="Open with the default app"
+        >
+          <ExternalLink size={13} />
+          {opening ? "Opening..." : "Open"}
+        </button>
         <button
           onClick={handleDownload}
           disabled={saving}
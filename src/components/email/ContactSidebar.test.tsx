@@ -11,6 +11,10 @@ const mockContact: DbContact = {
   frequency: 10,
   last_contacted_at: Date.now(),
   notes: "Important client",
+  phones: null,
+  urls: null,
+  organization: null,
+  job_title: null,
 };
 
 vi.mock("@/services/db/contacts", () => ({
@@ -22,6 +26,7 @@ vi.mock("@/services/db/contacts", () => ({
   upsertContact: vi.fn(() => Promise.resolve()),
   updateContact: vi.fn(() => Promise.resolve()),
   updateContactNotes: vi.fn(() => Promise.resolve()),
+  saveContactDetails: vi.fn(() => Promise.resolve()),
   getAttachmentsFromContact: vi.fn(() => Promise.resolve([])),
   getContactsFromSameDomain: vi.fn(() => Promise.resolve([])),
   getLatestAuthResult: vi.fn(() => Promise.resolve(null)),
@@ -37,6 +42,15 @@ vi.mock("@/services/contacts/gravatar", () => ({
   fetchAndCacheGravatarUrl: vi.fn(() => Promise.resolve(null)),
 }));
 
+vi.mock("@/services/contacts/messageContact", async (importOriginal) => ({
+  ...(await importOriginal<typeof import("@/services/contacts/messageContact")>()),
+  getMessageContact: vi.fn(() => Promise.reject(new Error("no message"))),
+}));
+
+vi.mock("@tauri-apps/plugin-opener", () => ({
+  openUrl: vi.fn(),
+}));
+
 vi.mock("@/services/db/threads", () => ({
   getThreadById: vi.fn(),
   getThreadLabelIds: vi.fn(),
@@ -57,7 +71,9 @@ import {
   getAttachmentsFromContact,
   getContactsFromSameDomain,
   getLatestAuthResult,
+  saveContactDetails,
 } from "@/services/db/contacts";
+import { getMessageContact } from "@/services/contacts/messageContact";
 import { isVipSender } from "@/services/db/notificationVips";
 
 const defaultProps = {
@@ -186,4 +202,37 @@ describe("ContactSidebar", () => {
 
     expect(screen.queryByText("Notes")).not.toBeInTheDocument();
   });
+
+  it("offers to save details from the message's vCard", async () => {
+    vi.mocked(getMessageContact).mockResolvedValueOnce({
+      email: "alice@company.com",
+      display_name: "Alice Smith",
+      phones: ["+1 555 010 2030"],
+      urls: ["https://company.com/alice"],
+      organization: "Company Inc",
+      title: "CTO",
+      avatar_url: null,
+      from_vcard: true,
+    });
+
+    render(<ContactSidebar {...defaultProps} messageId="msg-1" />);
+
+    await waitFor(() => {
+      expect(screen.getByText("Save Contact Card")).toBeInTheDocument();
+    });
+    expect(screen.getByText("+1 555 010 2030")).toBeInTheDocument();
+    expect(screen.getByText("CTO \u00B7 Company Inc")).toBeInTheDocument();
+
+    fireEvent.click(screen.getByText("Save Contact Card"));
+    await waitFor(() => {
+      expect(saveContactDetails).toHaveBeenCalledWith("alice@company.com", {
+        displayName: "Alice Smith",
+        avatarUrl: null,
+        phones: ["+1 555 010 2030"],
+        urls: ["https://company.com/alice"],
+        organization: "Company Inc",
+        jobTitle: "CTO",
+      });
+    });
+  });
 });
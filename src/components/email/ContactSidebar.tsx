@@ -1,16 +1,20 @@
 import { useState, useEffect, useRef, useCallback } from "react";
 import {
   Mail, Clock, X, Send, Copy, Star, UserPlus, Check, PenLine,
-  Paperclip, Building2, ChevronDown, ChevronRight,
+  Paperclip, Building2, ChevronDown, ChevronRight, Phone, Globe, Contact,
 } from "lucide-react";
+import { openUrl } from "@tauri-apps/plugin-opener";
 import {
   getContactByEmail, getContactStats, getRecentThreadsWithContact,
-  upsertContact, updateContact, updateContactNotes,
+  upsertContact, updateContact, updateContactNotes, saveContactDetails,
   getAttachmentsFromContact, getContactsFromSameDomain, getLatestAuthResult,
   type ContactStats, type DbContact, type ContactAttachment, type SameDomainContact,
 } from "@/services/db/contacts";
 import { isVipSender, addVipSender, removeVipSender } from "@/services/db/notificationVips";
 import { fetchAndCacheGravatarUrl } from "@/services/contacts/gravatar";
+import {
+  getMessageContact, hasNewDetails, parseContactList, type MessageContact,
+} from "@/services/contacts/messageContact";
 import { useThreadStore } from "@/stores/threadStore";
 import { useComposerStore } from "@/stores/composerStore";
 import { getThreadById, getThreadLabelIds } from "@/services/db/threads";
@@ -23,10 +27,12 @@ interface ContactSidebarProps {
   email: string;
   name: string | null;
   accountId: string;
+  /** Message to read a vCard and signature details from */
+  messageId?: string | null;
   onClose: () => void;
 }
 
-export function ContactSidebar({ email, name, accountId, onClose }: ContactSidebarProps) {
+export function ContactSidebar({ email, name, accountId, messageId, onClose }: ContactSidebarProps) {
   const [avatarUrl, setAvatarUrl] = useState<string | null>(null);
   const [stats, setStats] = useState<ContactStats | null>(null);
   const [recentThreads, setRecentThreads] = useState<{ thread_id: string; subject: string | null; last_message_at: number | null }[]>([]);
@@ -41,6 +47,8 @@ export function ContactSidebar({ email, name, accountId, onClose }: ContactSideb
   const [addedFeedback, setAddedFeedback] = useState(false);
   const [editingName, setEditingName] = useState(false);
   const [editNameValue, setEditNameValue] = useState("");
+  const [found, setFound] = useState<MessageContact | null>(null);
+  const [detailsSaved, setDetailsSaved] = useState(false);
 
   const loadedRef = useRef<string | null>(null);
   const notesTimerRef = useRef<ReturnType<typeof setTimeout> | null>(null);
@@ -115,6 +123,18 @@ export function ContactSidebar({ email, name, accountId, onClose }: ContactSideb
     return () => { cancelled = true; };
   }, [email, accountId]);
 
+  // Details from the message's vCard and signature
+  useEffect(() => {
+    setFound(null);
+    setDetailsSaved(false);
+    if (!messageId) return;
+    let cancelled = false;
+    getMessageContact(accountId, messageId)
+      .then((c) => { if (!cancelled) setFound(c); })
+      .catch((err) => console.warn("Failed to read contact details from message:", err));
+    return () => { cancelled = true; };
+  }, [accountId, messageId]);
+
   // -- Event handlers --
 
   const handleCompose = useCallback(() => {
@@ -163,6 +183,23 @@ export function ContactSidebar({ email, name, accountId, onClose }: ContactSideb
     addedTimerRef.current = setTimeout(() => setAddedFeedback(false), 1500);
   }, [email, name]);
 
+  // Only trust details about this sender, not e.g. a forwarded card
+  const card = found && found.email?.toLowerCase() === email.toLowerCase() ? found : null;
+
+  const handleSaveDetails = useCallback(async () => {
+    if (!card) return;
+    await saveContactDetails(email, {
+      displayName: card.from_vcard ? card.display_name : (name ?? card.display_name),
+      avatarUrl: card.avatar_url,
+      phones: card.phones,
+      urls: card.urls,
+      organization: card.organization,
+      jobTitle: card.title,
+    });
+    setContact(await getContactByEmail(email));
+    setDetailsSaved(true);
+  }, [card, email, name]);
+
   const handleStartEditName = useCallback(() => {
     setEditNameValue(contact?.display_name ?? name ?? "");
     setEditingName(true);
@@ -185,7 +222,17 @@ export function ContactSidebar({ email, name, accountId, onClose }: ContactSideb
     };
   }, []);
 
-  const displayName = contact?.display_name ?? name ?? email.split("@")[0];
+  const displayName = contact?.display_name ?? name ?? card?.display_name ?? email.split("@")[0];
+  const shownAvatar = contact?.avatar_url ?? card?.avatar_url ?? avatarUrl;
+  const savedPhones = parseContactList(contact?.phones ?? null);
+  const savedUrls = parseContactList(contact?.urls ?? null);
+  const phones = savedPhones.length > 0 ? savedPhones : (card?.phones ?? []);
+  const urls = savedUrls.length > 0 ? savedUrls : (card?.urls ?? []);
+  const role = [
+    contact?.job_title ?? card?.title,
+    contact?.organization ?? card?.organization,
+  ].filter(Boolean).join(" \u00B7 ");
+  const canSaveDetails = !!card && !detailsSaved && hasNewDetails(card, contact);
   const initial = (displayName?.[0] ?? "?").toUpperCase();
   const domain = email.includes("@") ? email.split("@")[1] : null;
 
@@ -205,9 +252,9 @@ export function ContactSidebar({ email, name, accountId, onClose }: ContactSideb
 
         {/* Avatar */}
         <div className="flex flex-col items-center text-center mb-4">
-          {avatarUrl ? (
+          {shownAvatar ? (
             <img
-              src={avatarUrl}
+              src={shownAvatar}
               alt={displayName}
               className="w-16 h-16 rounded-full mb-2"
             />
@@ -249,6 +296,9 @@ export function ContactSidebar({ email, name, accountId, onClose }: ContactSideb
           <div className="text-xs text-text-tertiary mt-0.5">
             {email}
           </div>
+          {role && (
+            <div className="text-xs text-text-secondary mt-0.5">{role}</div>
+          )}
         </div>
 
         {/* Quick Actions Row */}
@@ -308,6 +358,40 @@ export function ContactSidebar({ email, name, accountId, onClose }: ContactSideb
           </button>
         ) : null}
 
+        {/* Details from the message's vCard or signature */}
+        {canSaveDetails && (
+          <button
+            onClick={handleSaveDetails}
+            className="w-full flex items-center justify-center gap-1.5 px-3 py-1.5 text-xs font-medium text-accent border border-accent/30 rounded-md hover:bg-accent/10 transition-colors mb-4"
+          >
+            <Contact size={12} />
+            <span>{card?.from_vcard ? "Save Contact Card" : "Save Details from Signature"}</span>
+          </button>
+        )}
+
+        {/* Phones and links */}
+        {(phones.length > 0 || urls.length > 0) && (
+          <div className="space-y-2 mb-4">
+            {phones.map((phone) => (
+              <div key={phone} className="flex items-center gap-2 text-xs text-text-secondary">
+                <Phone size={12} className="text-text-tertiary shrink-0" />
+                <span className="truncate select-text">{phone}</span>
+              </div>
+            ))}
+            {urls.map((url) => (
+              <button
+                key={url}
+                onClick={() => openUrl(url)}
+                title={url}
+                className="w-full flex items-center gap-2 text-xs text-text-secondary hover:text-accent transition-colors"
+              >
+                <Globe size={12} className="text-text-tertiary shrink-0" />
+                <span className="truncate">{url.replace(/^https?:\/\//, "")}</span>
+              </button>
+            ))}
+          </div>
+        )}
+
         {/* Stats */}
         {stats && (
           <div className="space-y-2 mb-4">
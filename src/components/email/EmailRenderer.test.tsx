@@ -16,9 +16,13 @@ vi.mock("@/services/db/imageAllowlist", () => ({
   addToAllowlist: vi.fn(),
 }));
 
+vi.mock("@/services/phishing/linkCheck", () => ({
+  reviewLink: vi.fn().mockResolvedValue(null),
+}));
+
 vi.mock("@/stores/uiStore", () => ({
-  useUIStore: (selector: (s: { theme: string }) => string) =>
-    selector({ theme: "light" }),
+  useUIStore: (selector: (s: { theme: string; imageProxyEnabled: boolean; imageProxyUrl: string }) => unknown) =>
+    selector({ theme: "light", imageProxyEnabled: false, imageProxyUrl: "" }),
 }));
 
 const mockFetchAttachment = vi.fn();
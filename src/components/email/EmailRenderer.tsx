@@ -1,7 +1,15 @@
 import { useRef, useCallback, useLayoutEffect, useMemo, useState, useEffect } from "react";
 import { ImageOff } from "lucide-react";
 import { openUrl } from "@tauri-apps/plugin-opener";
-import { stripRemoteImages, hasBlockedImages } from "@/utils/imageBlocker";
+import {
+  stripRemoteImages,
+  hasBlockedImages,
+  collectRemoteImageUrls,
+  proxyRemoteImages,
+} from "@/utils/imageBlocker";
+import { fetchProxiedImages } from "@/services/imageProxy";
+import { reviewLink, type LinkReview } from "@/services/phishing/linkCheck";
+import { LinkConfirmDialog } from "./LinkConfirmDialog";
 import { addToAllowlist } from "@/services/db/imageAllowlist";
 import { escapeHtml, sanitizeHtml } from "@/utils/sanitize";
 import { useUIStore } from "@/stores/uiStore";
@@ -33,8 +41,12 @@ export function EmailRenderer({
   const rafRef = useRef<number>(0);
   const [overrideShow, setOverrideShow] = useState(false);
   const [cidMap, setCidMap] = useState<Map<string, string>>(new Map());
+  const [proxiedImages, setProxiedImages] = useState<Map<string, string>>(new Map());
+  const [pendingLink, setPendingLink] = useState<LinkReview | null>(null);
 
   const theme = useUIStore((s) => s.theme);
+  const imageProxyEnabled = useUIStore((s) => s.imageProxyEnabled);
+  const imageProxyUrl = useUIStore((s) => s.imageProxyUrl);
   const isDark = theme === "dark"
     || (theme === "system" && window.matchMedia("(prefers-color-scheme: dark)").matches);
 
@@ -90,6 +102,23 @@ export function EmailRenderer({
   }, [html]);
 
   const isPlainText = !sanitizedBody;
+  const proxyImages = imageProxyEnabled && !shouldBlock && !!sanitizedBody;
+
+  // In proxy mode, remote images are fetched by the backend, never by the iframe
+  useEffect(() => {
+    if (!proxyImages || !sanitizedBody) return;
+    const urls = collectRemoteImageUrls(sanitizedBody);
+    if (urls.length === 0) return;
+
+    let cancelled = false;
+    fetchProxiedImages(urls, imageProxyUrl)
+      .then((images) => {
+        if (!cancelled) setProxiedImages(images);
+      })
+      .catch((err) => console.error("Failed to load proxied images:", err));
+
+    return () => { cancelled = true; };
+  }, [proxyImages, sanitizedBody, imageProxyUrl]);
 
   const bodyHtml = useMemo(() => {
     let body = sanitizedBody
@@ -97,6 +126,8 @@ export function EmailRenderer({
 
     if (shouldBlock && sanitizedBody) {
       body = stripRemoteImages(body);
+    } else if (proxyImages) {
+      body = proxyRemoteImages(body, proxiedImages);
     }
 
     // Replace cid: references with resolved data URIs
@@ -108,7 +139,7 @@ export function EmailRenderer({
     }
 
     return body;
-  }, [sanitizedBody, text, shouldBlock, cidMap]);
+  }, [sanitizedBody, text, shouldBlock, proxyImages, proxiedImages, cidMap]);
 
   const blocked = useMemo(() => {
     if (!shouldBlock || !sanitizedBody) return false;
@@ -180,15 +211,19 @@ export function EmailRenderer({
     resizeObserver.observe(doc.body);
     observerRef.current = resizeObserver;
 
-    // Open links in external browser via Tauri opener
+    // Open links in external browser via Tauri opener, after a safety check
     const handleClick = (e: MouseEvent) => {
       const target = e.target as HTMLElement;
       const anchor = target.closest("a");
       if (anchor?.href) {
         e.preventDefault();
-        openUrl(anchor.href).catch((err) => {
-          console.error("Failed to open link:", err);
-        });
+        const href = anchor.href;
+        reviewLink(href, anchor.textContent ?? "")
+          .catch(() => null)
+          .then((review) => {
+            if (review) setPendingLink(review);
+            else openExternal(href);
+          });
       }
     };
     doc.addEventListener("click", handleClick);
@@ -200,6 +235,11 @@ export function EmailRenderer({
     };
   }, [bodyHtml, isDark, isPlainText]);
 
+  const handleConfirmLink = useCallback(() => {
+    if (pendingLink) openExternal(pendingLink.analysis.url);
+    setPendingLink(null);
+  }, [pendingLink]);
+
   const handleLoadImages = useCallback(() => {
     setOverrideShow(true);
   }, []);
@@ -242,7 +282,21 @@ export function EmailRenderer({
         style={{ overflow: "hidden" }}
         title="Email content"
       />
+      {pendingLink && (
+        <LinkConfirmDialog
+          linkAnalysis={pendingLink.analysis}
+          linkCheck={pendingLink.check}
+          onCancel={() => setPendingLink(null)}
+          onConfirm={handleConfirmLink}
+        />
+      )}
     </div>
   );
 }
 
+function openExternal(url: string) {
+  openUrl(url).catch((err) => {
+    console.error("Failed to open link:", err);
+  });
+}
+
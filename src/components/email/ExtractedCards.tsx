@@ -0,0 +1,145 @@
+import { useMemo } from "react";
+import { BedDouble, Package, Plane } from "lucide-react";
+import { openUrl } from "@tauri-apps/plugin-opener";
+import type { DbMessage } from "@/services/db/messages";
+import {
+  extractStructuredData,
+  type ExtractedItem,
+  type FlightInfo,
+  type HotelInfo,
+  type ParcelInfo,
+} from "@/services/extraction/structuredData";
+
+interface ExtractedCardsProps {
+  message: Pick<DbMessage, "subject" | "body_html" | "body_text" | "from_address">;
+}
+
+/** Summary cards for the flights, hotel stays and parcels in a message. */
+export function ExtractedCards({ message }: ExtractedCardsProps) {
+  const items = useMemo(
+    () => extractStructuredData(message),
+    [message.subject, message.body_html, message.body_text, message.from_address], // eslint-disable-line react-hooks/exhaustive-deps
+  );
+
+  if (items.length === 0) return null;
+
+  return (
+    <div className="mb-3 flex flex-col gap-2">
+      {items.map((item, i) => (
+        <ExtractedCard key={i} item={item} />
+      ))}
+    </div>
+  );
+}
+
+function ExtractedCard({ item }: { item: ExtractedItem }) {
+  switch (item.kind) {
+    case "flight":
+      return <FlightCard flight={item} />;
+    case "hotel":
+      return <HotelCard hotel={item} />;
+    case "parcel":
+      return <ParcelCard parcel={item} />;
+  }
+}
+
+function formatWhen(value: string | null): string | null {
+  if (!value) return null;
+  const date = new Date(value);
+  if (isNaN(date.getTime())) return value;
+  // Date-only values have no meaningful time of day
+  return /^\d{4}-\d{2}-\d{2}$/.test(value)
+    ? date.toLocaleDateString(undefined, { timeZone: "UTC", dateStyle: "medium" })
+    : date.toLocaleString(undefined, { dateStyle: "medium", timeStyle: "short" });
+}
+
+function CardShell({
+  icon,
+  title,
+  children,
+  action,
+}: {
+  icon: React.ReactNode;
+  title: string;
+  children: React.ReactNode;
+  action?: React.ReactNode;
+}) {
+  return (
+    <div className="px-3 py-2 rounded-md bg-bg-secondary border border-border-primary flex items-start gap-2">
+      <span className="text-accent shrink-0 mt-0.5">{icon}</span>
+      <div className="flex-1 min-w-0">
+        <p className="text-sm font-medium text-text-primary truncate">{title}</p>
+        <div className="text-xs text-text-secondary mt-0.5 space-y-0.5">{children}</div>
+      </div>
+      {action}
+    </div>
+  );
+}
+
+function FlightCard({ flight }: { flight: FlightInfo }) {
+  const route =
+    flight.departureAirport && flight.arrivalAirport
+      ? `${flight.departureAirport} → ${flight.arrivalAirport}`
+      : null;
+  const departs = formatWhen(flight.departureTime);
+  const arrives = formatWhen(flight.arrivalTime);
+
+  return (
+    <CardShell
+      icon={<Plane size={16} />}
+      title={[flight.airline, flight.flightNumber].filter(Boolean).join(" ")}
+    >
+      {route && <p>{route}</p>}
+      {departs && (
+        <p>
+          Departs {departs}
+          {arrives && <> · Arrives {arrives}</>}
+        </p>
+      )}
+      {flight.reservationNumber && <p>Confirmation: {flight.reservationNumber}</p>}
+    </CardShell>
+  );
+}
+
+function HotelCard({ hotel }: { hotel: HotelInfo }) {
+  const checkIn = formatWhen(hotel.checkIn);
+  const checkOut = formatWhen(hotel.checkOut);
+
+  return (
+    <CardShell icon={<BedDouble size={16} />} title={hotel.name}>
+      {hotel.address && <p className="truncate">{hotel.address}</p>}
+      {checkIn && (
+        <p>
+          Check-in {checkIn}
+          {checkOut && <> · Check-out {checkOut}</>}
+        </p>
+      )}
+      {hotel.reservationNumber && <p>Confirmation: {hotel.reservationNumber}</p>}
+    </CardShell>
+  );
+}
+
+function ParcelCard({ parcel }: { parcel: ParcelInfo }) {
+  const expected = formatWhen(parcel.expectedArrival);
+  const trackingUrl = parcel.trackingUrl;
+
+  return (
+    <CardShell
+      icon={<Package size={16} />}
+      title={parcel.carrierName ? `${parcel.carrierName} package` : "Package"}
+      action={
+        trackingUrl && (
+          <button
+            onClick={() => openUrl(trackingUrl).catch((err) => console.error("Failed to open tracking page:", err))}
+            className="text-xs text-accent hover:underline shrink-0 self-center"
+          >
+            Track package
+          </button>
+        )
+      }
+    >
+      <p className="font-mono select-text">{parcel.trackingNumber}</p>
+      {expected && <p>Expected by {expected}</p>}
+    </CardShell>
+  );
+}
@@ -1,6 +1,7 @@
 import { useState, useEffect, useRef, useCallback } from "react";
 import type { DbAttachment } from "@/services/db/attachments";
 import { getEmailProvider } from "@/services/email/providerFactory";
+import { getAttachmentPreview } from "@/services/attachments/previewThumbnails";
 import { FileText } from "lucide-react";
 import { formatFileSize, isImage, isPdf } from "@/utils/fileTypeHelpers";
 
@@ -69,7 +70,7 @@ export function InlineAttachmentPreview({
               onClick={() => onAttachmentClick(att)}
               className="flex items-center gap-2 px-3 py-2 rounded-md bg-bg-tertiary/50 hover:bg-bg-hover transition-colors w-full text-left"
             >
-              <FileText size={16} className="text-danger shrink-0" />
+              <PdfPreviewIcon attachment={att} />
               <div className="min-w-0">
                 <div className="text-xs text-text-primary truncate">
                   {att.filename ?? "Document.pdf"}
@@ -88,6 +89,35 @@ export function InlineAttachmentPreview({
   );
 }
 
+/** First-page preview for cached PDFs, falling back to the file icon. */
+function PdfPreviewIcon({ attachment }: { attachment: DbAttachment }) {
+  const [previewUrl, setPreviewUrl] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!attachment.local_path) return;
+    let cancelled = false;
+    getAttachmentPreview(attachment.local_path, "application/pdf")
+      .then((url) => {
+        if (!cancelled) setPreviewUrl(url);
+      })
+      .catch(() => {});
+    return () => {
+      cancelled = true;
+    };
+  }, [attachment.local_path]);
+
+  if (previewUrl) {
+    return (
+      <img
+        src={previewUrl}
+        alt=""
+        className="w-8 h-10 object-cover rounded-sm border border-border-secondary shrink-0"
+      />
+    );
+  }
+  return <FileText size={16} className="text-danger shrink-0" />;
+}
+
 function ImageThumbnail({
   attachment,
   accountId,
@@ -105,7 +135,26 @@ function ImageThumbnail({
   const loadedRef = useRef(false);
 
   const loadThumbnail = useCallback(async () => {
-    if (loadedRef.current || !attachment.gmail_attachment_id) return;
+    if (loadedRef.current) return;
+
+    // Cached attachments are thumbnailed by the backend
+    if (attachment.local_path) {
+      loadedRef.current = true;
+      setLoading(true);
+      try {
+        const preview = await getAttachmentPreview(attachment.local_path, attachment.mime_type);
+        if (preview) {
+          setThumbnailUrl(preview);
+          return;
+        }
+      } catch (err) {
+        console.error("Failed to render thumbnail:", err);
+      } finally {
+        setLoading(false);
+      }
+    }
+
+    if (!attachment.gmail_attachment_id) return;
     loadedRef.current = true;
     setLoading(true);
 
@@ -154,7 +203,7 @@ function ImageThumbnail({
   // Cleanup blob URL
   useEffect(() => {
     return () => {
-      if (thumbnailUrl) URL.revokeObjectURL(thumbnailUrl);
+      if (thumbnailUrl?.startsWith("blob:")) URL.revokeObjectURL(thumbnailUrl);
     };
   }, [thumbnailUrl]);
 
@@ -2,12 +2,13 @@ import { useState, useCallback, useEffect, useRef } from "react";
 import { useEditor, EditorContent } from "@tiptap/react";
 import StarterKit from "@tiptap/starter-kit";
 import Placeholder from "@tiptap/extension-placeholder";
-import { Reply, ReplyAll, Forward, Send, Maximize2, RotateCcw, X, Loader2 } from "lucide-react";
+import { Reply, ReplyAll, Users, Forward, Send, Maximize2, RotateCcw, X, Loader2 } from "lucide-react";
 import { useAccountStore } from "@/stores/accountStore";
 import { useComposerStore } from "@/stores/composerStore";
 import { useUIStore } from "@/stores/uiStore";
 import { sendEmail, archiveThread } from "@/services/emailActions";
 import { buildRawEmail } from "@/utils/emailBuilder";
+import { getDisclaimer } from "@/services/composer/disclaimer";
 import { upsertContact } from "@/services/db/contacts";
 import { getSetting } from "@/services/db/settings";
 import { getDefaultSignature } from "@/services/db/signatures";
@@ -18,9 +19,17 @@ import {
   type AutoDraftMode,
 } from "@/services/ai/writingStyleService";
 import type { DbMessage } from "@/services/db/messages";
+import { buildReplyRecipients, findMyAddresses } from "@/services/email/replyRecipients";
 import type { Thread } from "@/stores/threadStore";
 
-type ReplyMode = "reply" | "replyAll" | "forward";
+type ReplyMode = "reply" | "replyAll" | "replyList" | "forward";
+
+const MODE_LABELS: Record<ReplyMode, string> = {
+  reply: "Reply",
+  replyAll: "Reply All",
+  replyList: "Reply to List",
+  forward: "Forward",
+};
 
 interface InlineReplyProps {
   thread: Thread;
@@ -107,13 +116,14 @@ export function InlineReply({ thread, messages, accountId, noReply, onSent }: In
   useEffect(() => {
     const handler = (e: Event) => {
       const detail = (e as CustomEvent).detail as { mode: ReplyMode } | undefined;
+      if (detail?.mode === "replyList" && !lastMessage?.list_post) return;
       if (detail?.mode) {
         activateMode(detail.mode);
       }
     };
     window.addEventListener("velo-inline-reply", handler);
     return () => window.removeEventListener("velo-inline-reply", handler);
-  }, [activateMode]);
+  }, [activateMode, lastMessage?.list_post]);
 
   // Scroll into view when activated
   useEffect(() => {
@@ -122,36 +132,30 @@ export function InlineReply({ thread, messages, accountId, noReply, onSent }: In
     }
   }, [mode]);
 
+  // The user's own addresses among the possible recipients, left out of reply-all
+  const [myAddresses, setMyAddresses] = useState<Set<string>>(new Set());
+  useEffect(() => {
+    if (!lastMessage) return;
+    let cancelled = false;
+    findMyAddresses(lastMessage, { id: accountId, email: activeAccount?.email }).then((mine) => {
+      if (!cancelled) setMyAddresses(mine);
+    });
+    return () => {
+      cancelled = true;
+    };
+  }, [lastMessage, accountId, activeAccount?.email]);
+
   const getRecipients = useCallback((): { to: string[]; cc: string[] } => {
     if (!lastMessage) return { to: [], cc: [] };
 
     if (mode === "forward") return { to: [], cc: [] };
 
-    const replyTo = lastMessage.reply_to ?? lastMessage.from_address;
-
-    if (mode === "reply") {
-      return { to: replyTo ? [replyTo] : [], cc: [] };
+    if (mode === "replyList") {
+      return { to: lastMessage.list_post ? [lastMessage.list_post] : [], cc: [] };
     }
 
-    // replyAll
-    const allTo = new Set<string>();
-    if (replyTo) allTo.add(replyTo);
-    if (lastMessage.to_addresses) {
-      lastMessage.to_addresses.split(",").forEach((a) => allTo.add(a.trim()));
-    }
-    // Remove self from recipients
-    if (activeAccount?.email) allTo.delete(activeAccount.email);
-
-    const ccList: string[] = [];
-    if (lastMessage.cc_addresses) {
-      lastMessage.cc_addresses.split(",").forEach((a) => {
-        const trimmed = a.trim();
-        if (trimmed && trimmed !== activeAccount?.email) ccList.push(trimmed);
-      });
-    }
-
-    return { to: Array.from(allTo), cc: ccList };
-  }, [lastMessage, mode, activeAccount?.email]);
+    return buildReplyRecipients(lastMessage, mode === "reply" ? "reply" : "replyAll", (a) => myAddresses.has(a));
+  }, [lastMessage, mode, myAddresses]);
 
   const getSubject = useCallback((): string => {
     const sub = lastMessage?.subject ?? "";
@@ -179,6 +183,7 @@ export function InlineReply({ thread, messages, accountId, noReply, onSent }: In
         htmlBody: html,
         inReplyTo: lastMessage?.id,
         threadId: thread.id,
+        disclaimer: await getDisclaimer(activeAccount.email),
       });
 
       // Get undo send delay
@@ -227,7 +232,7 @@ export function InlineReply({ thread, messages, accountId, noReply, onSent }: In
     const bodyHtml = editor.getHTML();
 
     openComposer({
-      mode: mode === "forward" ? "forward" : mode === "replyAll" ? "replyAll" : "reply",
+      mode: mode ?? "reply",
       to,
       cc,
       subject: getSubject(),
@@ -242,7 +247,7 @@ export function InlineReply({ thread, messages, accountId, noReply, onSent }: In
   }, [editor, lastMessage, getRecipients, getSubject, mode, thread.id, openComposer]);
 
   const handleRegenerateDraft = useCallback(async () => {
-    if (!editor || !mode || mode === "forward") return;
+    if (!editor || (mode !== "reply" && mode !== "replyAll")) return;
     autoDraftAbortRef.current = false;
     setAutoDraftLoading(true);
     try {
@@ -321,6 +326,16 @@ export function InlineReply({ thread, messages, accountId, noReply, onSent }: In
           <ReplyAll size={14} />
           Reply All
         </button>
+        {lastMessage.list_post && (
+          <button
+            onClick={() => activateMode("replyList")}
+            title={`Reply to ${lastMessage.list_post}`}
+            className="flex items-center gap-1.5 px-4 py-2 text-xs text-text-secondary border border-border-primary rounded-lg hover:bg-bg-hover hover:text-text-primary transition-colors"
+          >
+            <Users size={14} />
+            Reply to List
+          </button>
+        )}
         <button
           onClick={() => activateMode("forward")}
           className="flex items-center gap-1.5 px-4 py-2 text-xs text-text-secondary border border-border-primary rounded-lg hover:bg-bg-hover hover:text-text-primary transition-colors"
@@ -334,7 +349,9 @@ export function InlineReply({ thread, messages, accountId, noReply, onSent }: In
 
   // Expanded state — editor visible
   const { to } = getRecipients();
-  const modeLabel = mode === "reply" ? "Reply" : mode === "replyAll" ? "Reply All" : "Forward";
+  const modes: ReplyMode[] = lastMessage.list_post
+    ? ["reply", "replyAll", "replyList", "forward"]
+    : ["reply", "replyAll", "forward"];
 
   return (
     <div ref={containerRef} className="mx-4 my-3 border border-border-primary rounded-lg overflow-hidden bg-bg-primary">
@@ -342,7 +359,7 @@ export function InlineReply({ thread, messages, accountId, noReply, onSent }: In
       <div className="flex items-center justify-between px-3 py-2 bg-bg-secondary border-b border-border-secondary">
         <div className="flex items-center gap-2">
           <div className="flex items-center gap-1">
-            {(["reply", "replyAll", "forward"] as const).map((m) => (
+            {modes.map((m) => (
               <button
                 key={m}
                 onClick={() => setMode(m)}
@@ -352,7 +369,7 @@ export function InlineReply({ thread, messages, accountId, noReply, onSent }: In
                     : "text-text-tertiary hover:text-text-primary"
                 }`}
               >
-                {m === "reply" ? "Reply" : m === "replyAll" ? "Reply All" : "Forward"}
+                {MODE_LABELS[m]}
               </button>
             ))}
           </div>
@@ -422,7 +439,7 @@ export function InlineReply({ thread, messages, accountId, noReply, onSent }: In
           className="flex items-center gap-1.5 px-4 py-1.5 text-xs font-medium text-white bg-accent hover:bg-accent-hover rounded-md transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
         >
           <Send size={12} />
-          {modeLabel}
+          {MODE_LABELS[mode]}
         </button>
       </div>
     </div>
@@ -1,15 +1,18 @@
 import { ShieldAlert, ExternalLink } from "lucide-react";
 import { Modal } from "@/components/ui/Modal";
 import type { LinkAnalysis } from "@/utils/phishingDetector";
+import type { LinkCheckResult } from "@/services/phishing/linkCheck";
 
 interface LinkConfirmDialogProps {
   linkAnalysis: LinkAnalysis;
+  linkCheck?: LinkCheckResult | null;
   onCancel: () => void;
   onConfirm: () => void;
 }
 
-export function LinkConfirmDialog({ linkAnalysis, onCancel, onConfirm }: LinkConfirmDialogProps) {
-  const isHigh = linkAnalysis.riskLevel === "high";
+export function LinkConfirmDialog({ linkAnalysis, linkCheck, onCancel, onConfirm }: LinkConfirmDialogProps) {
+  const isHigh = linkAnalysis.riskLevel === "high" || linkCheck?.verdict === "dangerous";
+  const expanded = linkCheck && linkCheck.final_url !== linkCheck.url ? linkCheck : null;
   const borderColor = isHigh ? "border-danger/40" : "border-warning/40";
   const headerBg = isHigh ? "bg-danger/10" : "bg-warning/10";
   const headerText = isHigh ? "text-danger" : "text-warning";
@@ -46,6 +49,32 @@ export function LinkConfirmDialog({ linkAnalysis, onCancel, onConfirm }: LinkCon
           </div>
         </div>
 
+        {/* Destination after expanding shorteners */}
+        {expanded && (
+          <div>
+            <label className="text-xs text-text-tertiary block mb-1">
+              Opens{expanded.redirects.length > 0 ? ` (after ${expanded.redirects.length + 1} redirects)` : ""}
+            </label>
+            <div className="flex items-start gap-2 p-2 bg-bg-tertiary rounded-md">
+              <ExternalLink size={14} className="text-text-tertiary shrink-0 mt-0.5" />
+              <span className="text-xs text-text-primary break-all font-mono leading-relaxed">
+                {expanded.final_url}
+              </span>
+            </div>
+          </div>
+        )}
+
+        {/* Blocklist and display-text findings */}
+        {linkCheck && linkCheck.reasons.length > 0 && (
+          <ul className="space-y-1 px-2">
+            {linkCheck.reasons.map((reason) => (
+              <li key={reason} className={`text-xs ${linkCheck.verdict === "dangerous" ? "text-danger" : "text-text-secondary"}`}>
+                {reason}
+              </li>
+            ))}
+          </ul>
+        )}
+
         {/* Display text if different */}
         {linkAnalysis.displayText && (
           <div>
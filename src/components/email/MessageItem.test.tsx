@@ -1,5 +1,5 @@
 import { describe, it, expect, vi, beforeEach } from "vitest";
-import { render, screen, act } from "@testing-library/react";
+import { render, screen, act, fireEvent } from "@testing-library/react";
 import { createRef } from "react";
 import { MessageItem } from "./MessageItem";
 import type { DbMessage } from "@/services/db/messages";
@@ -52,6 +52,15 @@ function makeMessage(overrides: Partial<DbMessage> = {}): DbMessage {
     message_id_header: null,
     references_header: null,
     in_reply_to_header: null,
+    parse_failed: 0,
+    delivered_to: null,
+    list_id: null,
+    list_post: null,
+    list_archive: null,
+    is_from_me: 0,
+    mail_followup_to: null,
+    is_pinned: 0,
+    keywords: null,
     ...overrides,
   };
 }
@@ -122,6 +131,23 @@ describe("MessageItem", () => {
     expect(container.querySelector("[data-testid='email-renderer']")).toBeInTheDocument();
   });
 
+  it("shows a parse failure notice with a link to the raw source", () => {
+    const listener = vi.fn();
+    window.addEventListener("velo-view-raw-message", listener);
+    render(
+      <MessageItem message={makeMessage({ parse_failed: 1 })} isLast={true} blockImages={false} />,
+    );
+
+    expect(screen.getByText(/couldn't be fully parsed/)).toBeInTheDocument();
+    fireEvent.click(screen.getByText("View source"));
+    expect(listener).toHaveBeenCalledTimes(1);
+    expect((listener.mock.calls[0]![0] as CustomEvent).detail).toEqual({
+      messageId: "m1",
+      accountId: "a1",
+    });
+    window.removeEventListener("velo-view-raw-message", listener);
+  });
+
   it("forwards ref to outer div", () => {
     const ref = createRef<HTMLDivElement>();
     render(
@@ -3,11 +3,23 @@ import { formatFullDate } from "@/utils/date";
 import { EmailRenderer } from "./EmailRenderer";
 import { InlineAttachmentPreview } from "./InlineAttachmentPreview";
 import { AttachmentList, getAttachmentsForMessage } from "./AttachmentList";
+import { AttachedMessages } from "./AttachedMessages";
 import type { DbMessage } from "@/services/db/messages";
 import type { DbAttachment } from "@/services/db/attachments";
-import { MailMinus } from "lucide-react";
+import { AlertTriangle, MailMinus, Pin } from "lucide-react";
 import { AuthBadge } from "./AuthBadge";
 import { AuthWarningBanner } from "./AuthWarningBanner";
+import { ExtractedCards } from "./ExtractedCards";
+import { categoriesFromKeywords } from "@/services/colorCategories";
+import { TEAM_STATUSES, teamTagsFromKeywords } from "@/services/teamTags";
+
+/** "alice@example.com · In Progress" for a message with team tags, else null. */
+function teamTagLabel(keywords: string | null): string | null {
+  const { assignee, status } = teamTagsFromKeywords(keywords);
+  const statusLabel = TEAM_STATUSES.find((s) => s.value === status)?.label;
+  const parts = [assignee, statusLabel].filter(Boolean);
+  return parts.length > 0 ? parts.join(" · ") : null;
+}
 
 interface MessageItemProps {
   message: DbMessage;
@@ -91,6 +103,22 @@ export const MessageItem = memo(forwardRef<HTMLDivElement, MessageItemProps>(fun
               <span className="text-sm font-medium text-text-primary truncate flex items-center gap-1">
                 {fromDisplay}
                 <AuthBadge authResults={message.auth_results} />
+                {message.is_pinned === 1 && (
+                  <Pin size={12} className="text-accent shrink-0" aria-label="Pinned" />
+                )}
+                {categoriesFromKeywords(message.keywords).map((category) => (
+                  <span
+                    key={category.keyword}
+                    title={category.name}
+                    className="w-2 h-2 rounded-full shrink-0"
+                    style={{ backgroundColor: category.color }}
+                  />
+                ))}
+                {teamTagLabel(message.keywords) && (
+                  <span className="text-[10px] font-normal px-1.5 py-0.5 rounded bg-accent/10 text-accent shrink-0">
+                    {teamTagLabel(message.keywords)}
+                  </span>
+                )}
               </span>
               {!expanded && (
                 <span className="text-xs text-text-tertiary truncate block">
@@ -123,6 +151,27 @@ export const MessageItem = memo(forwardRef<HTMLDivElement, MessageItemProps>(fun
             />
           )}
 
+          {message.parse_failed === 1 && (
+            <div className="mb-3 px-3 py-2 rounded-md bg-warning/10 border border-warning/30 flex items-center gap-2 text-xs text-text-secondary">
+              <AlertTriangle size={14} className="text-warning shrink-0" />
+              <span className="flex-1">
+                This message couldn&apos;t be fully parsed. Only its headers are shown.
+              </span>
+              <button
+                onClick={() =>
+                  window.dispatchEvent(
+                    new CustomEvent("velo-view-raw-message", {
+                      detail: { messageId: message.id, accountId: message.account_id },
+                    }),
+                  )
+                }
+                className="text-accent hover:underline shrink-0"
+              >
+                View source
+              </button>
+            </div>
+          )}
+
           {message.list_unsubscribe && (
             <UnsubscribeLink
               header={message.list_unsubscribe}
@@ -134,6 +183,8 @@ export const MessageItem = memo(forwardRef<HTMLDivElement, MessageItemProps>(fun
             />
           )}
 
+          <ExtractedCards message={message} />
+
           {blockImages != null ? (
             <EmailRenderer
               html={message.body_html}
@@ -163,6 +214,14 @@ export const MessageItem = memo(forwardRef<HTMLDivElement, MessageItemProps>(fun
             attachments={attachments}
             referencedCids={referencedCids}
           />
+
+          {blockImages != null && attachments.some((a) => a.mime_type === "message/rfc822") && (
+            <AttachedMessages
+              accountId={message.account_id}
+              messageId={message.id}
+              blockImages={blockImages}
+            />
+          )}
         </div>
       )}
     </div>
@@ -12,6 +12,7 @@ vi.mock("@dnd-kit/core", () => ({
   }),
 }));
 
+let mockSearchSnippets: Record<string, string> = {};
 vi.mock("@/stores/threadStore", () => ({
   useThreadStore: Object.assign(
     (selector: (s: Record<string, unknown>) => unknown) =>
@@ -19,6 +20,7 @@ vi.mock("@/stores/threadStore", () => ({
         selectedThreadIds: new Set(),
         toggleThreadSelection: vi.fn(),
         selectThreadRange: vi.fn(),
+        searchSnippets: mockSearchSnippets,
       }),
     { getState: () => ({ selectedThreadIds: new Set() }) },
   ),
@@ -58,6 +60,7 @@ describe("ThreadCard", () => {
 
   beforeEach(() => {
     vi.clearAllMocks();
+    mockSearchSnippets = {};
   });
 
   it("renders sender name and subject", () => {
@@ -66,6 +69,13 @@ describe("ThreadCard", () => {
     expect(screen.getByText("Test subject")).toBeInTheDocument();
   });
 
+  it("shows the highlighted search snippet in place of the stored one", () => {
+    mockSearchSnippets = { t1: "…the \u0002invoice\u0003 is due" };
+    render(<ThreadCard thread={makeThread()} isSelected={false} onClick={onClick} />);
+    expect(screen.getByText("invoice").tagName).toBe("MARK");
+    expect(screen.queryByText("Test snippet")).not.toBeInTheDocument();
+  });
+
   it("applies red background for spam threads", () => {
     const { container } = render(
       <ThreadCard
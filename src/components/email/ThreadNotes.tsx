@@ -0,0 +1,181 @@
+import { useState, useEffect, useCallback } from "react";
+import { StickyNote, Plus, Pencil, Trash2 } from "lucide-react";
+import { listNotes, type Note } from "@/services/db/notes";
+import { removeNote, writeNote, type NotesChangedDetail } from "@/services/notes";
+import type { DbMessage } from "@/services/db/messages";
+
+interface ThreadNotesProps {
+  threadId: string;
+  accountId: string;
+  messages: DbMessage[];
+}
+
+/** What the editor is writing: a new note (on a message or the thread) or changes to one. */
+type Draft = { noteId: string | null; messageIdHeader: string | null; body: string };
+
+export function ThreadNotes({ threadId, accountId, messages }: ThreadNotesProps) {
+  const [notes, setNotes] = useState<Note[]>([]);
+  const [draft, setDraft] = useState<Draft | null>(null);
+  const [saving, setSaving] = useState(false);
+
+  const load = useCallback(() => {
+    listNotes(accountId, { threadId })
+      .then(setNotes)
+      .catch((err) => console.error("Failed to load notes:", err));
+  }, [accountId, threadId]);
+
+  useEffect(() => {
+    setDraft(null);
+    load();
+  }, [load]);
+
+  useEffect(() => {
+    const handler = (e: Event) => {
+      const detail = (e as CustomEvent<NotesChangedDetail>).detail;
+      if (detail.accountId === accountId && (detail.threadId === null || detail.threadId === threadId)) {
+        load();
+      }
+    };
+    window.addEventListener("velo-notes-changed", handler);
+    return () => window.removeEventListener("velo-notes-changed", handler);
+  }, [accountId, threadId, load]);
+
+  // "Add Note" from a message's context menu
+  useEffect(() => {
+    const handler = (e: Event) => {
+      const detail = (e as CustomEvent<{ threadId: string; messageIdHeader: string | null }>).detail;
+      if (detail.threadId !== threadId) return;
+      setDraft({ noteId: null, messageIdHeader: detail.messageIdHeader, body: "" });
+    };
+    window.addEventListener("velo-add-note", handler);
+    return () => window.removeEventListener("velo-add-note", handler);
+  }, [threadId]);
+
+  const handleSave = useCallback(async () => {
+    if (!draft || !draft.body.trim()) return;
+    setSaving(true);
+    try {
+      await writeNote(accountId, {
+        id: draft.noteId,
+        messageIdHeader: draft.messageIdHeader,
+        threadId,
+        body: draft.body,
+      });
+      setDraft(null);
+    } catch (err) {
+      console.error("Failed to save note:", err);
+    } finally {
+      setSaving(false);
+    }
+  }, [accountId, threadId, draft]);
+
+  const handleDelete = useCallback(
+    async (id: string) => {
+      try {
+        await removeNote(accountId, id);
+      } catch (err) {
+        console.error("Failed to delete note:", err);
+      }
+    },
+    [accountId],
+  );
+
+  const senderOf = (messageIdHeader: string | null) => {
+    if (!messageIdHeader) return null;
+    const msg = messages.find((m) => m.message_id_header === messageIdHeader);
+    return msg ? (msg.from_name ?? msg.from_address ?? "a message") : "a message";
+  };
+
+  if (notes.length === 0 && !draft) return null;
+
+  return (
+    <div className="mx-4 my-2 p-3 rounded-lg bg-warning/5 border border-warning/20">
+      <div className="flex items-center gap-2">
+        <StickyNote size={14} className="text-warning shrink-0" />
+        <span className="text-xs font-medium text-warning flex-1">Notes</span>
+        {!draft && (
+          <button
+            onClick={() => setDraft({ noteId: null, messageIdHeader: null, body: "" })}
+            className="p-0.5 rounded text-text-tertiary hover:text-text-primary hover:bg-bg-hover"
+            title="Add a note to this thread"
+          >
+            <Plus size={14} />
+          </button>
+        )}
+      </div>
+
+      {notes.map((note) =>
+        draft?.noteId === note.id ? null : (
+          <div key={note.id} className="group mt-2 text-sm text-text-primary">
+            <div className="flex items-start gap-2">
+              <p className="flex-1 whitespace-pre-wrap break-words">{note.body}</p>
+              <div className="flex gap-1 opacity-0 group-hover:opacity-100 transition-opacity">
+                <button
+                  onClick={() =>
+                    setDraft({ noteId: note.id, messageIdHeader: note.message_id_header, body: note.body })
+                  }
+                  className="p-0.5 rounded text-text-tertiary hover:text-text-primary"
+                  title="Edit note"
+                >
+                  <Pencil size={12} />
+                </button>
+                <button
+                  onClick={() => handleDelete(note.id)}
+                  className="p-0.5 rounded text-text-tertiary hover:text-danger"
+                  title="Delete note"
+                >
+                  <Trash2 size={12} />
+                </button>
+              </div>
+            </div>
+            <div className="text-xs text-text-tertiary mt-0.5">
+              {note.message_id_header ? `On the message from ${senderOf(note.message_id_header)} · ` : ""}
+              {new Date(note.updated_at * 1000).toLocaleString()}
+            </div>
+          </div>
+        ),
+      )}
+
+      {draft && (
+        <div className="mt-2">
+          {draft.messageIdHeader && !draft.noteId && (
+            <div className="text-xs text-text-tertiary mb-1">
+              On the message from {senderOf(draft.messageIdHeader)}
+            </div>
+          )}
+          <textarea
+            autoFocus
+            value={draft.body}
+            onChange={(e) => setDraft({ ...draft, body: e.target.value })}
+            onKeyDown={(e) => {
+              if (e.key === "Enter" && (e.metaKey || e.ctrlKey)) {
+                e.preventDefault();
+                handleSave();
+              } else if (e.key === "Escape") {
+                setDraft(null);
+              }
+            }}
+            rows={3}
+            placeholder="Only you can see notes"
+            className="w-full px-2 py-1.5 text-sm rounded border border-border-primary bg-bg-primary text-text-primary resize-y focus:outline-none focus:border-accent"
+          />
+          <div className="flex justify-end gap-2 mt-1">
+            <button
+              onClick={() => setDraft(null)}
+              className="px-2 py-1 text-xs rounded text-text-secondary hover:bg-bg-hover"
+            >
+              Cancel
+            </button>
+            <button
+              onClick={handleSave}
+              disabled={saving || !draft.body.trim()}
+              className="px-2 py-1 text-xs rounded bg-accent text-white hover:bg-accent-hover disabled:opacity-50"
+            >
+              {saving ? "Saving..." : "Save"}
+            </button>
+          </div>
+        </div>
+      )}
+    </div>
+  );
+}
@@ -1,7 +1,12 @@
 import { useEffect, useState, useRef, useCallback } from "react";
 import { MessageItem } from "./MessageItem";
 import { ActionBar } from "./ActionBar";
-import { getMessagesForThread, type DbMessage } from "@/services/db/messages";
+import type { DbMessage } from "@/services/db/messages";
+import type { MessagePinnedDetail } from "@/services/messagePins";
+import type { MessageCategoriesDetail } from "@/services/colorCategories";
+import { getTeamFolder, type TeamFolder } from "@/services/teamTags";
+import { getConversation } from "@/services/email/conversation";
+import { getReplyRecipients } from "@/services/email/replyRecipients";
 import { useAccountStore } from "@/stores/accountStore";
 import { useUIStore } from "@/stores/uiStore";
 import { useThreadStore, type Thread } from "@/stores/threadStore";
@@ -9,11 +14,14 @@ import { useComposerStore } from "@/stores/composerStore";
 import { useContextMenuStore } from "@/stores/contextMenuStore";
 import { markThreadRead } from "@/services/emailActions";
 import { getSetting } from "@/services/db/settings";
+import { getAttachmentsForMessage } from "@/services/db/attachments";
+import { stageServerMessage } from "@/services/attachments/staging";
 import { getAllowlistedSenders } from "@/services/db/imageAllowlist";
 import { VolumeX } from "lucide-react";
 import { escapeHtml, sanitizeHtml } from "@/utils/sanitize";
 import { isNoReplyAddress } from "@/utils/noReply";
 import { ThreadSummary } from "./ThreadSummary";
+import { ThreadNotes } from "./ThreadNotes";
 import { SmartReplySuggestions } from "./SmartReplySuggestions";
 import { InlineReply } from "./InlineReply";
 import { ContactSidebar } from "./ContactSidebar";
@@ -24,6 +32,31 @@ import { MessageSkeleton } from "@/components/ui/Skeleton";
 import { RawMessageModal } from "./RawMessageModal";
 import { BehaviorSuggestionBanner } from "@/components/ai/BehaviorSuggestionBanner";
 
+/**
+ * Attach the original of a forwarded IMAP message that has attachments, so
+ * they go along. The message stays on the server until it's sent.
+ */
+async function attachOriginalMessage(message: DbMessage) {
+  if (message.imap_folder == null || message.imap_uid == null) return;
+  const attachments = await getAttachmentsForMessage(message.account_id, message.id);
+  if (!attachments.some((a) => !a.is_inline)) return;
+
+  const name = (message.subject ?? "").replace(/[\\/:*?"<>|]/g, "_").trim() || "message";
+  const staged = await stageServerMessage(
+    message.imap_folder,
+    message.imap_uid,
+    `${name}.eml`,
+    message.raw_size ?? 0,
+  );
+  useComposerStore.getState().addAttachment({
+    id: crypto.randomUUID(),
+    filename: staged.filename,
+    mimeType: staged.mime_type,
+    size: staged.size,
+    stagedId: staged.id,
+  });
+}
+
 interface ThreadViewProps {
   thread: Thread;
 }
@@ -60,6 +93,7 @@ async function handlePopOut(thread: Thread) {
 
 export function ThreadView({ thread }: ThreadViewProps) {
   const activeAccountId = useAccountStore((s) => s.activeAccountId);
+  const activeAccountEmail = useAccountStore((s) => s.accounts.find((a) => a.id === s.activeAccountId)?.email);
   const contactSidebarVisible = useUIStore((s) => s.contactSidebarVisible);
   const toggleContactSidebar = useUIStore((s) => s.toggleContactSidebar);
   const taskSidebarVisible = useUIStore((s) => s.taskSidebarVisible);
@@ -71,6 +105,7 @@ export function ThreadView({ thread }: ThreadViewProps) {
   // null = not yet loaded; defer iframe rendering until setting is known
   const [blockImages, setBlockImages] = useState<boolean | null>(null);
   const [allowlistedSenders, setAllowlistedSenders] = useState<Set<string>>(new Set());
+  const [teamFolder, setTeamFolder] = useState<TeamFolder | null>(null);
 
   // Preload settings eagerly on mount (parallel with message loading)
   useEffect(() => {
@@ -81,12 +116,18 @@ export function ThreadView({ thread }: ThreadViewProps) {
   useEffect(() => {
     if (!activeAccountId) return;
     setLoading(true);
-    getMessagesForThread(activeAccountId, thread.id)
+    getConversation(activeAccountId, thread.id)
       .then(setMessages)
       .catch(console.error)
       .finally(() => setLoading(false));
   }, [activeAccountId, thread.id]);
 
+  // Shared team folder, whose messages get assignment and status tags
+  useEffect(() => {
+    if (!activeAccountId) return;
+    getTeamFolder(activeAccountId).then(setTeamFolder).catch(() => setTeamFolder(null));
+  }, [activeAccountId]);
+
   // Check per-sender allowlist (single batch query instead of N queries)
   useEffect(() => {
     if (!activeAccountId || messages.length === 0) return;
@@ -132,41 +173,32 @@ export function ThreadView({ thread }: ThreadViewProps) {
   const defaultReplyMode = useUIStore((s) => s.defaultReplyMode);
   const lastMessage = messages[messages.length - 1];
 
-  const handleReply = useCallback(() => {
+  const handleReply = useCallback(async () => {
     if (!lastMessage) return;
-    const replyTo = lastMessage.reply_to ?? lastMessage.from_address;
+    const { to } = await getReplyRecipients(lastMessage, "reply", { id: lastMessage.account_id, email: activeAccountEmail });
     openComposer({
       mode: "reply",
-      to: replyTo ? [replyTo] : [],
+      to,
       subject: `Re: ${lastMessage.subject ?? ""}`,
       bodyHtml: buildQuote(lastMessage),
       threadId: lastMessage.thread_id,
       inReplyToMessageId: lastMessage.id,
     });
-  }, [lastMessage, openComposer]);
+  }, [lastMessage, activeAccountEmail, openComposer]);
 
-  const handleReplyAll = useCallback(() => {
+  const handleReplyAll = useCallback(async () => {
     if (!lastMessage) return;
-    const replyTo = lastMessage.reply_to ?? lastMessage.from_address;
-    const allRecipients = new Set<string>();
-    if (replyTo) allRecipients.add(replyTo);
-    if (lastMessage.to_addresses) {
-      lastMessage.to_addresses.split(",").forEach((a) => allRecipients.add(a.trim()));
-    }
-    const ccList: string[] = [];
-    if (lastMessage.cc_addresses) {
-      lastMessage.cc_addresses.split(",").forEach((a) => ccList.push(a.trim()));
-    }
+    const { to, cc } = await getReplyRecipients(lastMessage, "replyAll", { id: lastMessage.account_id, email: activeAccountEmail });
     openComposer({
       mode: "replyAll",
-      to: Array.from(allRecipients),
-      cc: ccList,
+      to,
+      cc,
       subject: `Re: ${lastMessage.subject ?? ""}`,
       bodyHtml: buildQuote(lastMessage),
       threadId: lastMessage.thread_id,
       inReplyToMessageId: lastMessage.id,
     });
-  }, [lastMessage, openComposer]);
+  }, [lastMessage, activeAccountEmail, openComposer]);
 
   const handleForward = useCallback(() => {
     if (!lastMessage) return;
@@ -178,6 +210,9 @@ export function ThreadView({ thread }: ThreadViewProps) {
       threadId: lastMessage.thread_id,
       inReplyToMessageId: lastMessage.id,
     });
+    attachOriginalMessage(lastMessage).catch((err) =>
+      console.error("Failed to attach original message:", err),
+    );
   }, [lastMessage, openComposer]);
 
   const handlePrint = useCallback(() => {
@@ -290,6 +325,27 @@ export function ThreadView({ thread }: ThreadViewProps) {
     return () => window.removeEventListener("velo-view-raw-message", handler);
   }, []);
 
+  // Keep pins made from the context menu in sync with the open thread
+  useEffect(() => {
+    const handler = (e: Event) => {
+      const { messageId, pinned } = (e as CustomEvent<MessagePinnedDetail>).detail;
+      setMessages((prev) =>
+        prev.map((m) => (m.id === messageId ? { ...m, is_pinned: pinned ? 1 : 0 } : m)),
+      );
+    };
+    window.addEventListener("velo-message-pinned", handler);
+    return () => window.removeEventListener("velo-message-pinned", handler);
+  }, []);
+
+  useEffect(() => {
+    const handler = (e: Event) => {
+      const { messageId, keywords } = (e as CustomEvent<MessageCategoriesDetail>).detail;
+      setMessages((prev) => prev.map((m) => (m.id === messageId ? { ...m, keywords } : m)));
+    };
+    window.addEventListener("velo-message-categories", handler);
+    return () => window.removeEventListener("velo-message-categories", handler);
+  }, []);
+
   // Listen for extract-task event from keyboard shortcut
   useEffect(() => {
     const handler = (e: Event) => {
@@ -313,12 +369,19 @@ export function ThreadView({ thread }: ThreadViewProps) {
       replyTo: msg.reply_to,
       toAddresses: msg.to_addresses,
       ccAddresses: msg.cc_addresses,
+      isFromMe: msg.is_from_me,
+      mailFollowupTo: msg.mail_followup_to,
+      isPinned: msg.is_pinned === 1,
+      keywords: msg.keywords,
+      isImap: msg.imap_uid != null,
+      teamMembers: teamFolder && msg.imap_folder === teamFolder.folder ? teamFolder.members : null,
+      messageIdHeader: msg.message_id_header,
       subject: msg.subject,
       date: msg.date,
       bodyHtml: msg.body_html,
       bodyText: msg.body_text,
     });
-  }, [openMenu]);
+  }, [openMenu, teamFolder]);
 
   const handleExport = useCallback(async () => {
     if (messages.length === 0) return;
@@ -423,6 +486,15 @@ export function ThreadView({ thread }: ThreadViewProps) {
           />
         )}
 
+        {/* Notes */}
+        {activeAccountId && (
+          <ThreadNotes
+            threadId={thread.id}
+            accountId={activeAccountId}
+            messages={messages}
+          />
+        )}
+
         {/* Behavior Suggestion */}
         {activeAccountId && lastMessage && (
           <div className="px-6 pt-2">
@@ -473,7 +545,7 @@ export function ThreadView({ thread }: ThreadViewProps) {
               noReply={noReply}
               onSent={() => {
                 // Reload messages after sending
-                getMessagesForThread(activeAccountId, thread.id)
+                getConversation(activeAccountId, thread.id)
                   .then(setMessages)
                   .catch(console.error);
               }}
@@ -495,6 +567,7 @@ export function ThreadView({ thread }: ThreadViewProps) {
               email={primarySender}
               name={primarySenderName}
               accountId={activeAccountId}
+              messageId={lastMessage?.id}
               onClose={toggleContactSidebar}
             />
           </div>
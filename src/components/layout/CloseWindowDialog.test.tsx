@@ -0,0 +1,69 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { render, screen, fireEvent, act, waitFor } from "@testing-library/react";
+
+const handlers: (() => void)[] = [];
+
+vi.mock("@tauri-apps/api/event", () => ({
+  listen: vi.fn((_name: string, handler: () => void) => {
+    handlers.push(handler);
+    return Promise.resolve(() => {});
+  }),
+}));
+
+vi.mock("@/services/closeBehavior", () => ({
+  applyCloseAction: vi.fn(() => Promise.resolve()),
+  setCloseBehavior: vi.fn(() => Promise.resolve()),
+}));
+
+import { CloseWindowDialog } from "./CloseWindowDialog";
+import { applyCloseAction, setCloseBehavior } from "@/services/closeBehavior";
+
+async function requestClose() {
+  await waitFor(() => expect(handlers.length).toBeGreaterThan(0));
+  act(() => {
+    handlers[handlers.length - 1]!();
+  });
+}
+
+describe("CloseWindowDialog", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    handlers.length = 0;
+  });
+
+  it("stays hidden until the window is closed", () => {
+    render(<CloseWindowDialog />);
+    expect(screen.queryByText("Close Sora?")).not.toBeInTheDocument();
+  });
+
+  it("applies the chosen action without saving it", async () => {
+    render(<CloseWindowDialog />);
+    await requestClose();
+
+    fireEvent.click(screen.getByText("Quit"));
+
+    await waitFor(() => expect(applyCloseAction).toHaveBeenCalledWith("quit"));
+    expect(setCloseBehavior).not.toHaveBeenCalled();
+  });
+
+  it("saves the choice when asked to remember it", async () => {
+    render(<CloseWindowDialog />);
+    await requestClose();
+
+    fireEvent.click(screen.getByLabelText("Remember my choice"));
+    fireEvent.click(screen.getByText("Hide to tray"));
+
+    await waitFor(() => expect(applyCloseAction).toHaveBeenCalledWith("hide"));
+    expect(setCloseBehavior).toHaveBeenCalledWith("hide");
+  });
+
+  it("does nothing on cancel", async () => {
+    render(<CloseWindowDialog />);
+    await requestClose();
+
+    fireEvent.click(screen.getByText("Cancel"));
+
+    expect(applyCloseAction).not.toHaveBeenCalled();
+    expect(setCloseBehavior).not.toHaveBeenCalled();
+  });
+});
@@ -0,0 +1,74 @@
+import { useState, useEffect, useCallback } from "react";
+import { Modal } from "@/components/ui/Modal";
+import { Button } from "@/components/ui/Button";
+import {
+  applyCloseAction,
+  setCloseBehavior,
+  type CloseBehavior,
+} from "@/services/closeBehavior";
+
+/**
+ * Asks what closing the main window should do when the close behavior is
+ * "ask", optionally remembering the answer as the new behavior.
+ */
+export function CloseWindowDialog() {
+  const [isOpen, setIsOpen] = useState(false);
+  const [remember, setRemember] = useState(false);
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    import("@tauri-apps/api/event").then(({ listen }) => {
+      listen("close-requested", () => {
+        setRemember(false);
+        setIsOpen(true);
+      }).then((fn) => { unlisten = fn; });
+    });
+    return () => { unlisten?.(); };
+  }, []);
+
+  const close = useCallback(() => setIsOpen(false), []);
+
+  const choose = async (action: Exclude<CloseBehavior, "ask">) => {
+    setIsOpen(false);
+    try {
+      if (remember) await setCloseBehavior(action);
+    } catch (err) {
+      console.error("Failed to save close behavior:", err);
+    }
+    await applyCloseAction(action).catch((err) =>
+      console.error("Failed to close window:", err),
+    );
+  };
+
+  return (
+    <Modal isOpen={isOpen} onClose={close} title="Close Sora?" width="w-96">
+      <div className="p-4 space-y-4">
+        <p className="text-sm text-text-secondary">
+          Keep Sora running in the tray to get new mail notifications, or quit it completely.
+        </p>
+        <label className="flex items-center gap-2 text-sm text-text-primary">
+          <input
+            type="checkbox"
+            checked={remember}
+            onChange={(e) => setRemember(e.target.checked)}
+          />
+          Remember my choice
+        </label>
+        <div className="flex justify-end gap-2">
+          <Button variant="secondary" onClick={close}>
+            Cancel
+          </Button>
+          <Button variant="secondary" onClick={() => choose("minimize")}>
+            Minimize
+          </Button>
+          <Button variant="secondary" onClick={() => choose("quit")}>
+            Quit
+          </Button>
+          <Button variant="primary" onClick={() => choose("hide")}>
+            Hide to tray
+          </Button>
+        </div>
+      </div>
+    </Modal>
+  );
+}
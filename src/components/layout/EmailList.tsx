@@ -9,7 +9,8 @@ import { useAccountStore } from "@/stores/accountStore";
 import { useUIStore } from "@/stores/uiStore";
 import { useActiveLabel, useSelectedThreadId, useActiveCategory } from "@/hooks/useRouteNavigation";
 import { navigateToThread, navigateToLabel } from "@/router/navigate";
-import { getThreadsForAccount, getThreadsForCategory, getThreadLabelIds, deleteThread as deleteThreadFromDb } from "@/services/db/threads";
+import { getThreadsForAccount, getThreadsForCategory, getThreadLabelIds } from "@/services/db/threads";
+import { emptyTrash, permanentDeleteThreads } from "@/services/emailActions";
 import { getCategoriesForThreads, getCategoryUnreadCounts } from "@/services/db/threadCategories";
 import { getActiveFollowUpThreadIds } from "@/services/db/followUpReminders";
 import { getBundleRules, getHeldThreadIds, getBundleSummaries, type DbBundleRule } from "@/services/db/bundleRules";
@@ -41,6 +42,7 @@ const LABEL_MAP: Record<string, string> = {
   trash: "TRASH",
   spam: "SPAM",
   snoozed: "SNOOZED",
+  feeds: "FEEDS",
   all: "", // no filter
 };
 
@@ -156,15 +158,12 @@ export function EmailList({ width, listRef }: { width?: number; listRef?: React.
     const ids = [...selectedThreadIds];
     removeThreads(ids);
     try {
+      if (isTrashView) {
+        await permanentDeleteThreads(activeAccountId, ids);
+        return;
+      }
       const client = await getGmailClient(activeAccountId);
-      await Promise.all(ids.map(async (id) => {
-        if (isTrashView) {
-          await client.deleteThread(id);
-          await deleteThreadFromDb(activeAccountId, id);
-        } else {
-          await client.modifyThread(id, ["TRASH"], ["INBOX"]);
-        }
-      }));
+      await Promise.all(ids.map((id) => client.modifyThread(id, ["TRASH"], ["INBOX"])));
     } catch (err) {
       console.error("Bulk delete failed:", err);
     }
@@ -516,15 +515,26 @@ export function EmailList({ width, listRef }: { width?: number; listRef?: React.
             {filteredThreads.length} conversation{filteredThreads.length !== 1 ? "s" : ""}
           </span>
         </div>
-        <select
-          value={readFilter}
-          onChange={(e) => setReadFilter(e.target.value as "all" | "read" | "unread")}
-          className="text-xs bg-bg-tertiary text-text-secondary px-2 py-1 rounded border border-border-primary"
-        >
-          <option value="all">All</option>
-          <option value="unread">Unread</option>
-          <option value="read">Read</option>
-        </select>
+        <div className="flex items-center gap-2">
+          {activeLabel === "trash" && filteredThreads.length > 0 && (
+            <button
+              onClick={() => activeAccountId && emptyTrash(activeAccountId)}
+              className="flex items-center gap-1 text-xs text-text-secondary hover:text-danger px-2 py-1 rounded hover:bg-bg-hover transition-colors"
+            >
+              <Trash2 size={12} />
+              Empty trash
+            </button>
+          )}
+          <select
+            value={readFilter}
+            onChange={(e) => setReadFilter(e.target.value as "all" | "read" | "unread")}
+            className="text-xs bg-bg-tertiary text-text-secondary px-2 py-1 rounded border border-border-primary"
+          >
+            <option value="all">All</option>
+            <option value="unread">Unread</option>
+            <option value="read">Read</option>
+          </select>
+        </div>
       </div>
 
       {/* Category tabs (inbox + split mode only) */}
@@ -9,6 +9,10 @@ import { useAccountStore } from "@/stores/accountStore";
 import { useLabelStore, type Label } from "@/stores/labelStore";
 import { useContextMenuStore } from "@/stores/contextMenuStore";
 import { useSmartFolderStore } from "@/stores/smartFolderStore";
+import {
+  SMART_FOLDER_COUNTS_EVENT,
+  type SmartFolderCountsDetail,
+} from "@/services/search/smartFolderCounts";
 import { useActiveLabel, useActiveCategory } from "@/hooks/useRouteNavigation";
 import { navigateToLabel } from "@/router/navigate";
 import {
@@ -40,6 +44,7 @@ import {
   Paperclip,
   FolderSearch,
   Loader2,
+  Rss,
   type LucideIcon,
 } from "lucide-react";
 import { useTaskStore } from "@/stores/taskStore";
@@ -58,6 +63,7 @@ export const ALL_NAV_ITEMS: { id: string; label: string; icon: LucideIcon }[] =
   { id: "trash", label: "Trash", icon: Trash2 },
   { id: "spam", label: "Spam", icon: Ban },
   { id: "all", label: "All Mail", icon: Mail },
+  { id: "feeds", label: "Feeds", icon: Rss },
   { id: "tasks", label: "Tasks", icon: CheckSquare },
   { id: "calendar", label: "Calendar", icon: Calendar },
   { id: "attachments", label: "Attachments", icon: Paperclip },
@@ -219,9 +225,10 @@ export function Sidebar({ collapsed, onAddAccount }: SidebarProps) {
   const loadLabels = useLabelStore((s) => s.loadLabels);
   const deleteLabel = useLabelStore((s) => s.deleteLabel);
   const smartFolders = useSmartFolderStore((s) => s.folders);
-  const smartFolderCounts = useSmartFolderStore((s) => s.unreadCounts);
+  const smartFolderCounts = useSmartFolderStore((s) => s.counts);
   const loadSmartFolders = useSmartFolderStore((s) => s.loadFolders);
-  const refreshSmartFolderCounts = useSmartFolderStore((s) => s.refreshUnreadCounts);
+  const refreshSmartFolderCounts = useSmartFolderStore((s) => s.refreshCounts);
+  const setSmartFolderCounts = useSmartFolderStore((s) => s.setCounts);
   const createSmartFolder = useSmartFolderStore((s) => s.createFolder);
   const SECTION_IDS = new Set(["smart-folders", "labels"]);
 
@@ -299,6 +306,16 @@ export function Sidebar({ collapsed, onAddAccount }: SidebarProps) {
     };
   }, [activeAccountId, loadLabels, refreshSmartFolderCounts]);
 
+  // Badge counts recomputed by the sync engine after each account sync
+  useEffect(() => {
+    const handler = (e: Event) => {
+      const { accountId, counts } = (e as CustomEvent<SmartFolderCountsDetail>).detail;
+      if (accountId === activeAccountId) setSmartFolderCounts(counts);
+    };
+    window.addEventListener(SMART_FOLDER_COUNTS_EVENT, handler);
+    return () => window.removeEventListener(SMART_FOLDER_COUNTS_EVENT, handler);
+  }, [activeAccountId, setSmartFolderCounts]);
+
   const handleDeleteLabel = useCallback(async (labelId: string) => {
     if (!activeAccountId) return;
     try {
@@ -0,0 +1,67 @@
+import { useState } from "react";
+import { Lock } from "lucide-react";
+import { Button } from "@/components/ui/Button";
+import { unlockProfile } from "@/services/profileLock";
+
+/**
+ * Shown at startup instead of the app while a master password protects the
+ * stored credentials.
+ */
+export function UnlockScreen({ onUnlocked }: { onUnlocked: () => void }) {
+  const [password, setPassword] = useState("");
+  const [error, setError] = useState<string | null>(null);
+  const [unlocking, setUnlocking] = useState(false);
+
+  const handleUnlock = async () => {
+    if (!password || unlocking) return;
+    setUnlocking(true);
+    setError(null);
+    try {
+      await unlockProfile(password);
+      onUnlocked();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+      setPassword("");
+      setUnlocking(false);
+    }
+  };
+
+  return (
+    <div className="flex h-screen items-center justify-center bg-bg-primary">
+      <form
+        className="w-80 flex flex-col items-center gap-4"
+        onSubmit={(e) => {
+          e.preventDefault();
+          handleUnlock();
+        }}
+      >
+        <Lock size={28} className="text-text-tertiary" />
+        <div className="text-center">
+          <h1 className="text-base font-semibold text-text-primary">Velo is locked</h1>
+          <p className="text-xs text-text-tertiary mt-1">
+            Enter your master password to decrypt your accounts.
+          </p>
+        </div>
+        <input
+          type="password"
+          autoFocus
+          value={password}
+          onChange={(e) => setPassword(e.target.value)}
+          placeholder="Master password"
+          aria-label="Master password"
+          className="w-full bg-bg-tertiary text-text-primary text-sm px-3 py-1.5 rounded-md border border-border-primary focus:border-accent focus:outline-none placeholder:text-text-tertiary"
+        />
+        {error && <p className="text-xs text-danger">{error}</p>}
+        <Button
+          type="submit"
+          variant="primary"
+          size="md"
+          className="w-full"
+          disabled={!password || unlocking}
+        >
+          {unlocking ? "Unlocking..." : "Unlock"}
+        </Button>
+      </form>
+    </div>
+  );
+}
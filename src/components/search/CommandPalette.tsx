@@ -6,6 +6,9 @@ import { useThreadStore } from "@/stores/threadStore";
 import { useAccountStore } from "@/stores/accountStore";
 import { getGmailClient } from "@/services/gmail/tokenManager";
 import { getTemplatesForAccount, type DbTemplate } from "@/services/db/templates";
+import { getThreadListArchive } from "@/services/db/messages";
+import { getCurrentProfile, listProfiles, switchProfile, type Profile } from "@/services/profiles";
+import { openUrl } from "@tauri-apps/plugin-opener";
 import { useActiveLabel } from "@/hooks/useRouteNavigation";
 import { navigateToLabel, navigateBack, getSelectedThreadId } from "@/router/navigate";
 
@@ -33,12 +36,20 @@ export function CommandPalette({ isOpen, onClose }: CommandPaletteProps) {
   const activeLabel = useActiveLabel();
   const activeAccountId = useAccountStore((s) => s.activeAccountId);
   const [templates, setTemplates] = useState<DbTemplate[]>([]);
+  const [otherProfiles, setOtherProfiles] = useState<Profile[]>([]);
 
   useEffect(() => {
     if (!isOpen || !activeAccountId) return;
     getTemplatesForAccount(activeAccountId).then(setTemplates);
   }, [isOpen, activeAccountId]);
 
+  useEffect(() => {
+    if (!isOpen) return;
+    Promise.all([getCurrentProfile(), listProfiles()])
+      .then(([current, all]) => setOtherProfiles(all.filter((p) => p.id !== current.id)))
+      .catch(() => setOtherProfiles([]));
+  }, [isOpen]);
+
   const commands: Command[] = useMemo(() => [
     // Navigation
     { id: "go-inbox", label: "Go to Inbox", shortcut: "g i", category: "Navigation", action: () => { navigateToLabel("inbox"); onClose(); } },
@@ -70,6 +81,23 @@ export function CommandPalette({ isOpen, onClose }: CommandPaletteProps) {
       }
     } },
 
+    { id: "reply-list", label: "Reply to Mailing List", category: "Actions", action: () => {
+      onClose();
+      window.dispatchEvent(new CustomEvent("velo-inline-reply", { detail: { mode: "replyList" } }));
+    } },
+    { id: "list-archive", label: "Open Mailing List Archive", category: "Actions", action: async () => {
+      onClose();
+      const threadId = getSelectedThreadId();
+      const accountId = useAccountStore.getState().activeAccountId;
+      if (!threadId || !accountId) return;
+      try {
+        const url = await getThreadListArchive(accountId, threadId);
+        if (url) await openUrl(url);
+      } catch (err) {
+        console.error("Failed to open list archive:", err);
+      }
+    } },
+
     // Tasks
     { id: "task-create", label: "Create Task", category: "Tasks", action: () => {
       onClose();
@@ -109,7 +137,18 @@ export function CommandPalette({ isOpen, onClose }: CommandPaletteProps) {
         onClose();
       },
     })),
-  ], [onClose, openComposer, activeLabel, toggleSidebar, setTheme, templates]);
+
+    // Profiles
+    ...otherProfiles.map((profile) => ({
+      id: `profile-${profile.id}`,
+      label: `Switch to Profile: ${profile.name}`,
+      category: "Profiles",
+      action: () => {
+        onClose();
+        switchProfile(profile.id).catch((err) => console.error("Failed to switch profile:", err));
+      },
+    })),
+  ], [onClose, openComposer, activeLabel, toggleSidebar, setTheme, templates, otherProfiles]);
 
   const filtered = query
     ? commands.filter(
@@ -0,0 +1,20 @@
+import { useMemo } from "react";
+import { splitHighlights } from "@/services/search/snippets";
+
+/** Search result context with the matched terms emphasized. */
+export function HighlightedSnippet({ snippet }: { snippet: string }) {
+  const segments = useMemo(() => splitHighlights(snippet), [snippet]);
+  return (
+    <>
+      {segments.map((segment, i) =>
+        segment.highlighted ? (
+          <mark key={i} className="bg-accent/20 text-text-primary rounded-sm">
+            {segment.text}
+          </mark>
+        ) : (
+          <span key={i}>{segment.text}</span>
+        ),
+      )}
+    </>
+  );
+}
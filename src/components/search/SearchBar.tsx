@@ -1,5 +1,6 @@
 import { useState, useRef, useCallback } from "react";
 import { searchMessages } from "@/services/db/search";
+import { getThreadSnippets } from "@/services/search/snippets";
 import { useAccountStore } from "@/stores/accountStore";
 import { useThreadStore } from "@/stores/threadStore";
 import { useSmartFolderStore } from "@/stores/smartFolderStore";
@@ -28,6 +29,7 @@ export function SearchBar() {
 
       if (value.trim().length < 2) {
         setSearch(value, null);
+        useThreadStore.getState().setSearchSnippets({});
         return;
       }
 
@@ -36,6 +38,14 @@ export function SearchBar() {
           const hits = await searchMessages(value, activeAccountId ?? undefined, 100);
           const threadIds = new Set(hits.map((h) => h.thread_id));
           useThreadStore.getState().setSearch(value, threadIds);
+          // Context snippets are a nicety; the filtered list is already shown
+          getThreadSnippets(hits, value)
+            .then((snippets) => {
+              if (useThreadStore.getState().searchQuery === value) {
+                useThreadStore.getState().setSearchSnippets(snippets);
+              }
+            })
+            .catch(() => {});
         } catch {
           useThreadStore.getState().setSearch(value, null);
         }
@@ -6,6 +6,8 @@ import {
   deleteContact,
   type DbContact,
 } from "@/services/db/contacts";
+import { ContactExport } from "./ContactExport";
+import { ContactImport } from "./ContactImport";
 
 export function ContactEditor() {
   const [contacts, setContacts] = useState<DbContact[]>([]);
@@ -145,6 +147,9 @@ export function ContactEditor() {
       <p className="text-xs text-text-tertiary">
         {contacts.length} contact{contacts.length !== 1 ? "s" : ""} total
       </p>
+
+      <ContactImport onImported={loadContacts} />
+      <ContactExport />
     </div>
   );
 }
@@ -0,0 +1,98 @@
+import { useEffect, useState } from "react";
+import { save } from "@tauri-apps/plugin-dialog";
+import { Download } from "lucide-react";
+import { Button } from "@/components/ui/Button";
+import { getContactGroups, type DbContactGroup } from "@/services/db/contactGroups";
+import {
+  EXPORT_FORMATS,
+  exportContacts,
+  exportExtension,
+  type ContactExportFormat,
+} from "@/services/contacts/contactExport";
+
+const selectClass =
+  "bg-bg-tertiary text-text-primary text-xs px-2 py-1 rounded-md border border-border-primary focus:border-accent outline-none";
+
+/** Save the address book, or part of it, as a vCard or CSV file. */
+export function ContactExport() {
+  const [format, setFormat] = useState<ContactExportFormat>("vcard3");
+  const [groups, setGroups] = useState<DbContactGroup[]>([]);
+  const [groupId, setGroupId] = useState("");
+  const [minFrequency, setMinFrequency] = useState("0");
+  const [busy, setBusy] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+
+  useEffect(() => {
+    getContactGroups().then(setGroups).catch(() => setGroups([]));
+  }, []);
+
+  const handleExport = async () => {
+    const extension = exportExtension(format);
+    const path = await save({
+      defaultPath: `contacts.${extension}`,
+      filters: [{ name: "Contacts", extensions: [extension] }],
+    });
+    if (!path) return;
+
+    setBusy(true);
+    setMessage(null);
+    try {
+      const count = await exportContacts(path, format, {
+        group_id: groupId || null,
+        min_frequency: minFrequency === "0" ? null : parseInt(minFrequency, 10),
+      });
+      setMessage(`Exported ${count} contact${count !== 1 ? "s" : ""}`);
+    } catch (err) {
+      console.error("Failed to export contacts:", err);
+      setMessage(`Export failed: ${String(err)}`);
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  return (
+    <div className="space-y-1.5">
+      <div className="flex items-center gap-2">
+        <select
+          value={format}
+          onChange={(e) => setFormat(e.target.value as ContactExportFormat)}
+          className={selectClass}
+        >
+          {EXPORT_FORMATS.map((f) => (
+            <option key={f.value} value={f.value}>
+              {f.label}
+            </option>
+          ))}
+        </select>
+        <select value={groupId} onChange={(e) => setGroupId(e.target.value)} className={selectClass}>
+          <option value="">All contacts</option>
+          {groups.map((g) => (
+            <option key={g.id} value={g.id}>
+              {g.name}
+            </option>
+          ))}
+        </select>
+        <select
+          value={minFrequency}
+          onChange={(e) => setMinFrequency(e.target.value)}
+          className={selectClass}
+        >
+          <option value="0">Any frequency</option>
+          <option value="2">Mailed 2+ times</option>
+          <option value="5">Mailed 5+ times</option>
+          <option value="10">Mailed 10+ times</option>
+        </select>
+        <Button
+          variant="secondary"
+          icon={<Download size={13} />}
+          onClick={handleExport}
+          disabled={busy}
+          className="ml-auto bg-bg-tertiary text-text-primary border border-border-primary"
+        >
+          {busy ? "Exporting..." : "Export..."}
+        </Button>
+      </div>
+      {message && <p className="text-xs text-text-tertiary">{message}</p>}
+    </div>
+  );
+}
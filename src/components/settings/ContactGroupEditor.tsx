@@ -0,0 +1,161 @@
+import { useState, useEffect, useCallback } from "react";
+import { Pencil, Trash2, Check, X, Plus } from "lucide-react";
+import {
+  getContactGroups,
+  createContactGroup,
+  renameContactGroup,
+  setContactGroupMembers,
+  resolveContactGroup,
+  deleteContactGroup,
+  type DbContactGroup,
+} from "@/services/db/contactGroups";
+
+/** Split a pasted member list on commas, semicolons and whitespace. */
+function parseMembers(text: string): string[] {
+  return text
+    .split(/[\s,;]+/)
+    .map((s) => s.trim())
+    .filter((s) => s.includes("@"));
+}
+
+export function ContactGroupEditor() {
+  const [groups, setGroups] = useState<DbContactGroup[]>([]);
+  const [editingId, setEditingId] = useState<string | null>(null);
+  const [creating, setCreating] = useState(false);
+  const [name, setName] = useState("");
+  const [members, setMembers] = useState("");
+
+  const loadGroups = useCallback(async () => {
+    setGroups(await getContactGroups());
+  }, []);
+
+  useEffect(() => {
+    loadGroups();
+    // eslint-disable-next-line react-hooks/exhaustive-deps -- loadGroups is stable (no deps), run once on mount
+  }, []);
+
+  const resetForm = () => {
+    setEditingId(null);
+    setCreating(false);
+    setName("");
+    setMembers("");
+  };
+
+  const handleEdit = async (group: DbContactGroup) => {
+    const current = await resolveContactGroup(group.name);
+    setCreating(false);
+    setEditingId(group.id);
+    setName(group.name);
+    setMembers(current.map((m) => m.email).join(", "));
+  };
+
+  const handleSave = async () => {
+    if (!name.trim()) return;
+    const emails = parseMembers(members);
+    if (editingId) {
+      await renameContactGroup(editingId, name);
+      await setContactGroupMembers(editingId, emails);
+    } else {
+      await createContactGroup(name, emails);
+    }
+    resetForm();
+    await loadGroups();
+  };
+
+  const handleDelete = async (id: string) => {
+    await deleteContactGroup(id);
+    if (editingId === id) resetForm();
+    await loadGroups();
+  };
+
+  const form = (
+    <div className="space-y-2 p-2 bg-bg-tertiary rounded">
+      <input
+        type="text"
+        value={name}
+        onChange={(e) => setName(e.target.value)}
+        placeholder="Group name"
+        autoFocus
+        className="w-full px-2 py-1 bg-bg-primary border border-border-primary rounded text-sm text-text-primary outline-none focus:border-accent"
+      />
+      <textarea
+        value={members}
+        onChange={(e) => setMembers(e.target.value)}
+        placeholder="Member addresses, separated by commas"
+        rows={3}
+        className="w-full px-2 py-1 bg-bg-primary border border-border-primary rounded text-sm text-text-primary outline-none focus:border-accent resize-none"
+      />
+      <div className="flex justify-end gap-1">
+        <button
+          onClick={resetForm}
+          className="p-1 text-text-tertiary hover:text-text-primary hover:bg-bg-hover rounded"
+        >
+          <X size={14} />
+        </button>
+        <button
+          onClick={handleSave}
+          className="p-1 text-success hover:bg-bg-hover rounded"
+        >
+          <Check size={14} />
+        </button>
+      </div>
+    </div>
+  );
+
+  return (
+    <div className="space-y-3">
+      {groups.length === 0 && !creating ? (
+        <p className="text-sm text-text-tertiary py-2">No groups yet</p>
+      ) : (
+        <div className="space-y-1">
+          {groups.map((group) =>
+            editingId === group.id ? (
+              <div key={group.id}>{form}</div>
+            ) : (
+              <div
+                key={group.id}
+                className="flex items-center justify-between py-1.5 px-2 rounded hover:bg-bg-hover group"
+              >
+                <div className="text-sm text-text-primary truncate">{group.name}</div>
+                <div className="flex items-center gap-1">
+                  <span className="text-xs text-text-tertiary mr-2">
+                    {group.member_count} member{group.member_count !== 1 ? "s" : ""}
+                  </span>
+                  <button
+                    onClick={() => handleEdit(group)}
+                    className="p-1 text-text-tertiary hover:text-text-primary opacity-0 group-hover:opacity-100 transition-opacity"
+                    title="Edit group"
+                  >
+                    <Pencil size={13} />
+                  </button>
+                  <button
+                    onClick={() => handleDelete(group.id)}
+                    className="p-1 text-text-tertiary hover:text-danger opacity-0 group-hover:opacity-100 transition-opacity"
+                    title="Delete group"
+                  >
+                    <Trash2 size={13} />
+                  </button>
+                </div>
+              </div>
+            ),
+          )}
+        </div>
+      )}
+
+      {creating ? (
+        form
+      ) : (
+        <button
+          onClick={() => {
+            resetForm();
+            setCreating(true);
+          }}
+          className="flex items-center gap-1 text-xs text-accent hover:text-accent-hover"
+        >
+          <Plus size={12} />
+          New group
+        </button>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,170 @@
+import { useState } from "react";
+import { open } from "@tauri-apps/plugin-dialog";
+import { Upload } from "lucide-react";
+import { Button } from "@/components/ui/Button";
+import {
+  CONTACT_FIELD_LABELS,
+  columnMapping,
+  importContacts,
+  importSummary,
+  previewContactImport,
+  type ContactField,
+  type ContactsImport,
+} from "@/services/contacts/contactImport";
+
+/** Entries listed in the preview; the rest are only counted. */
+const PREVIEW_ROWS = 50;
+
+/**
+ * Pick a CSV or vCard file, check the column mapping and the contacts it
+ * yields, then import them.
+ */
+export function ContactImport({ onImported }: { onImported: () => void }) {
+  const [path, setPath] = useState<string | null>(null);
+  const [preview, setPreview] = useState<ContactsImport | null>(null);
+  const [mapping, setMapping] = useState<(ContactField | null)[] | null>(null);
+  const [busy, setBusy] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+
+  const reset = () => {
+    setPath(null);
+    setPreview(null);
+    setMapping(null);
+  };
+
+  const handlePick = async () => {
+    const selected = await open({
+      multiple: false,
+      directory: false,
+      filters: [{ name: "Contacts", extensions: ["csv", "vcf", "vcard"] }],
+    });
+    if (typeof selected !== "string") return;
+
+    setBusy(true);
+    setMessage(null);
+    try {
+      const result = await previewContactImport(selected);
+      setPath(selected);
+      setPreview(result);
+      setMapping(result.format === "csv" ? columnMapping(result) : null);
+    } catch (err) {
+      console.error("Failed to read contacts file:", err);
+      setMessage(`Couldn't read the file: ${String(err)}`);
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const handleMappingChange = async (index: number, field: ContactField | null) => {
+    if (!path || !mapping) return;
+    const next = mapping.map((f, i) => (i === index ? field : f));
+    setMapping(next);
+    setBusy(true);
+    try {
+      setPreview(await previewContactImport(path, next));
+    } catch (err) {
+      console.error("Failed to apply column mapping:", err);
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const handleImport = async () => {
+    if (!path) return;
+    setBusy(true);
+    try {
+      const result = await importContacts(path, mapping);
+      setMessage(`Imported: ${importSummary(result)}`);
+      reset();
+      onImported();
+    } catch (err) {
+      console.error("Failed to import contacts:", err);
+      setMessage(`Import failed: ${String(err)}`);
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  if (!preview) {
+    return (
+      <div className="flex items-center justify-between">
+        <p className="text-xs text-text-tertiary">
+          {message ?? "Import a CSV from Google Contacts or Outlook, or a vCard file"}
+        </p>
+        <Button
+          variant="secondary"
+          icon={<Upload size={13} />}
+          onClick={handlePick}
+          disabled={busy}
+          className="bg-bg-tertiary text-text-primary border border-border-primary"
+        >
+          {busy ? "Reading..." : "Import..."}
+        </Button>
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-3 border border-border-primary rounded-md p-3">
+      {preview.format === "csv" && mapping && (
+        <div className="space-y-1.5">
+          <p className="text-xs text-text-secondary">Columns</p>
+          {preview.columns.map((column, i) => (
+            <div key={i} className="flex items-center justify-between gap-3">
+              <span className="text-xs text-text-primary truncate">
+                {column.header || `Column ${i + 1}`}
+              </span>
+              <select
+                value={mapping[i] ?? ""}
+                onChange={(e) =>
+                  handleMappingChange(i, (e.target.value || null) as ContactField | null)
+                }
+                disabled={busy}
+                className="w-36 bg-bg-tertiary text-text-primary text-xs px-2 py-1 rounded-md border border-border-primary focus:border-accent outline-none"
+              >
+                <option value="">Don't import</option>
+                {Object.entries(CONTACT_FIELD_LABELS).map(([field, label]) => (
+                  <option key={field} value={field}>
+                    {label}
+                  </option>
+                ))}
+              </select>
+            </div>
+          ))}
+        </div>
+      )}
+
+      <p className="text-xs text-text-secondary">{importSummary(preview)}</p>
+      {preview.contacts.length > 0 && (
+        <div className="space-y-0.5 max-h-[200px] overflow-y-auto">
+          {preview.contacts.slice(0, PREVIEW_ROWS).map((contact) => (
+            <div key={contact.email} className="flex items-center justify-between text-xs py-0.5">
+              <span className="text-text-primary truncate">
+                {contact.display_name ? `${contact.display_name} <${contact.email}>` : contact.email}
+              </span>
+              {contact.exists && <span className="text-text-tertiary shrink-0 ml-2">saved</span>}
+            </div>
+          ))}
+          {preview.contacts.length > PREVIEW_ROWS && (
+            <p className="text-xs text-text-tertiary">
+              and {preview.contacts.length - PREVIEW_ROWS} more
+            </p>
+          )}
+        </div>
+      )}
+
+      <div className="flex justify-end gap-2">
+        <Button variant="secondary" onClick={reset} disabled={busy}>
+          Cancel
+        </Button>
+        <Button
+          variant="primary"
+          onClick={handleImport}
+          disabled={busy || preview.contacts.length === 0}
+        >
+          Import {preview.contacts.length} contact{preview.contacts.length !== 1 ? "s" : ""}
+        </Button>
+      </div>
+    </div>
+  );
+}
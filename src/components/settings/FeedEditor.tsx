@@ -0,0 +1,122 @@
+import { useState, useEffect, useCallback } from "react";
+import { Trash2, RefreshCw } from "lucide-react";
+import { useAccountStore } from "@/stores/accountStore";
+import {
+  getFeedsForAccount,
+  insertFeed,
+  deleteFeed,
+  type DbFeed,
+} from "@/services/db/feeds";
+import { isValidFeedUrl, refreshFeed } from "@/services/feeds/feedManager";
+
+export function FeedEditor() {
+  const activeAccountId = useAccountStore((s) => s.activeAccountId);
+  const [feeds, setFeeds] = useState<DbFeed[]>([]);
+  const [url, setUrl] = useState("");
+  const [refreshingId, setRefreshingId] = useState<string | null>(null);
+
+  const loadFeeds = useCallback(async () => {
+    if (!activeAccountId) return;
+    setFeeds(await getFeedsForAccount(activeAccountId));
+  }, [activeAccountId]);
+
+  useEffect(() => {
+    loadFeeds();
+  }, [loadFeeds]);
+
+  const refresh = useCallback(async (feed: DbFeed) => {
+    setRefreshingId(feed.id);
+    try {
+      const added = await refreshFeed(feed);
+      if (added > 0) window.dispatchEvent(new Event("velo-sync-done"));
+    } catch {
+      // Recorded on the feed and shown below
+    } finally {
+      setRefreshingId(null);
+      await loadFeeds();
+    }
+  }, [loadFeeds]);
+
+  const handleAdd = useCallback(async () => {
+    const trimmed = url.trim();
+    if (!activeAccountId || !isValidFeedUrl(trimmed)) return;
+    if (feeds.some((f) => f.url === trimmed)) return;
+
+    const id = await insertFeed(activeAccountId, trimmed);
+    setUrl("");
+    const added = (await getFeedsForAccount(activeAccountId)).find((f) => f.id === id);
+    if (added) await refresh(added);
+  }, [activeAccountId, url, feeds, refresh]);
+
+  const handleDelete = useCallback(async (id: string) => {
+    if (!activeAccountId) return;
+    await deleteFeed(activeAccountId, id);
+    await loadFeeds();
+    window.dispatchEvent(new Event("velo-sync-done"));
+  }, [activeAccountId, loadFeeds]);
+
+  const urlInvalid = url.trim() !== "" && !isValidFeedUrl(url.trim());
+
+  return (
+    <div className="space-y-3">
+      {feeds.map((feed) => (
+        <div
+          key={feed.id}
+          className="flex items-center justify-between py-2 px-3 bg-bg-secondary rounded-md"
+        >
+          <div className="flex-1 min-w-0">
+            <div className="text-sm font-medium text-text-primary truncate">
+              {feed.title ?? feed.url}
+            </div>
+            <div className="text-xs text-text-tertiary truncate">{feed.url}</div>
+            {feed.last_error && (
+              <div className="text-xs text-danger truncate" title={feed.last_error}>
+                {feed.last_error}
+              </div>
+            )}
+          </div>
+          <div className="flex items-center gap-1">
+            <button
+              onClick={() => refresh(feed)}
+              disabled={refreshingId === feed.id}
+              className="p-1 text-text-tertiary hover:text-text-primary disabled:opacity-50"
+              title="Check now"
+            >
+              <RefreshCw size={13} className={refreshingId === feed.id ? "animate-spin" : ""} />
+            </button>
+            <button
+              onClick={() => handleDelete(feed.id)}
+              className="p-1 text-text-tertiary hover:text-danger"
+              title="Unsubscribe"
+            >
+              <Trash2 size={13} />
+            </button>
+          </div>
+        </div>
+      ))}
+
+      <div className="flex items-center gap-2">
+        <input
+          type="url"
+          value={url}
+          onChange={(e) => setUrl(e.target.value)}
+          onKeyDown={(e) => {
+            if (e.key === "Enter") handleAdd();
+          }}
+          placeholder="https://example.com/feed.xml"
+          className="flex-1 px-3 py-1.5 bg-bg-tertiary border border-border-primary rounded text-sm text-text-primary outline-none focus:border-accent"
+        />
+        <button
+          onClick={handleAdd}
+          disabled={!activeAccountId || !isValidFeedUrl(url.trim())}
+          className="px-3 py-1.5 text-xs font-medium text-white bg-accent hover:bg-accent-hover rounded-md transition-colors disabled:opacity-50"
+        >
+          Subscribe
+        </button>
+      </div>
+      {urlInvalid && (
+        <p className="text-xs text-danger">Feed addresses must start with https://</p>
+      )}
+    </div>
+  );
+}
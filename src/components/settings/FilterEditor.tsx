@@ -1,6 +1,7 @@
 import { useState, useEffect, useCallback, useMemo } from "react";
 import { Trash2, Pencil } from "lucide-react";
 import { TextField } from "@/components/ui/TextField";
+import { isValidWebhookUrl } from "@/services/filters/webhook";
 import { useAccountStore } from "@/stores/accountStore";
 import { getLabelsForAccount, type DbLabel } from "@/services/db/labels";
 import {
@@ -27,11 +28,13 @@ export function FilterEditor() {
   const [criteriaSubject, setCriteriaSubject] = useState("");
   const [criteriaBody, setCriteriaBody] = useState("");
   const [criteriaHasAttachment, setCriteriaHasAttachment] = useState(false);
+  const [criteriaCategory, setCriteriaCategory] = useState("");
   const [actionLabel, setActionLabel] = useState("");
   const [actionArchive, setActionArchive] = useState(false);
   const [actionStar, setActionStar] = useState(false);
   const [actionMarkRead, setActionMarkRead] = useState(false);
   const [actionTrash, setActionTrash] = useState(false);
+  const [actionWebhook, setActionWebhook] = useState("");
 
   const loadFilters = useCallback(async () => {
     if (!activeAccountId) return;
@@ -55,11 +58,13 @@ export function FilterEditor() {
     setCriteriaSubject("");
     setCriteriaBody("");
     setCriteriaHasAttachment(false);
+    setCriteriaCategory("");
     setActionLabel("");
     setActionArchive(false);
     setActionStar(false);
     setActionMarkRead(false);
     setActionTrash(false);
+    setActionWebhook("");
     setEditingId(null);
     setShowForm(false);
   }, []);
@@ -71,6 +76,7 @@ export function FilterEditor() {
     if (criteriaSubject.trim()) c.subject = criteriaSubject.trim();
     if (criteriaBody.trim()) c.body = criteriaBody.trim();
     if (criteriaHasAttachment) c.hasAttachment = true;
+    if (criteriaCategory.trim()) c.category = criteriaCategory.trim();
     return c;
   };
 
@@ -81,6 +87,7 @@ export function FilterEditor() {
     if (actionStar) a.star = true;
     if (actionMarkRead) a.markRead = true;
     if (actionTrash) a.trash = true;
+    if (actionWebhook.trim()) a.webhookUrl = actionWebhook.trim();
     return a;
   };
 
@@ -102,7 +109,7 @@ export function FilterEditor() {
 
     resetForm();
     await loadFilters();
-  }, [activeAccountId, name, editingId, resetForm, loadFilters, criteriaFrom, criteriaTo, criteriaSubject, criteriaBody, criteriaHasAttachment, actionLabel, actionArchive, actionStar, actionMarkRead, actionTrash]);
+  }, [activeAccountId, name, editingId, resetForm, loadFilters, criteriaFrom, criteriaTo, criteriaSubject, criteriaBody, criteriaHasAttachment, criteriaCategory, actionLabel, actionArchive, actionStar, actionMarkRead, actionTrash, actionWebhook]);
 
   const handleEdit = useCallback((filter: DbFilterRule) => {
     setEditingId(filter.id);
@@ -118,11 +125,13 @@ export function FilterEditor() {
     setCriteriaSubject(criteria.subject ?? "");
     setCriteriaBody(criteria.body ?? "");
     setCriteriaHasAttachment(criteria.hasAttachment ?? false);
+    setCriteriaCategory(criteria.category ?? "");
     setActionLabel(actions.applyLabel ?? "");
     setActionArchive(actions.archive ?? false);
     setActionStar(actions.star ?? false);
     setActionMarkRead(actions.markRead ?? false);
     setActionTrash(actions.trash ?? false);
+    setActionWebhook(actions.webhookUrl ?? "");
     setShowForm(true);
   }, []);
 
@@ -137,6 +146,8 @@ export function FilterEditor() {
     await loadFilters();
   }, [loadFilters]);
 
+  const webhookInvalid = actionWebhook.trim() !== "" && !isValidWebhookUrl(actionWebhook.trim());
+
   const filterDescriptions = useMemo(() => {
     const map = new Map<string, string>();
     for (const filter of filters) {
@@ -148,6 +159,7 @@ export function FilterEditor() {
         if (c.subject) parts.push(`subject: ${c.subject}`);
         if (c.body) parts.push(`body: ${c.body}`);
         if (c.hasAttachment) parts.push("has attachment");
+        if (c.category) parts.push(`category: ${c.category}`);
         map.set(filter.id, parts.join(", ") || "No criteria");
       } catch {
         map.set(filter.id, "Invalid criteria");
@@ -242,6 +254,12 @@ export function FilterEditor() {
                 onChange={(e) => setCriteriaBody(e.target.value)}
                 placeholder="Body contains..."
               />
+              <TextField
+                type="text"
+                value={criteriaCategory}
+                onChange={(e) => setCriteriaCategory(e.target.value)}
+                placeholder="Category is... (IMAP)"
+              />
               <label className="flex items-center gap-1.5 text-xs text-text-secondary">
                 <input
                   type="checkbox"
@@ -290,13 +308,25 @@ export function FilterEditor() {
                   Trash
                 </label>
               </div>
+              <div className="flex items-center gap-2">
+                <span className="text-xs text-text-secondary w-20 shrink-0">Call webhook</span>
+                <TextField
+                  type="url"
+                  value={actionWebhook}
+                  onChange={(e) => setActionWebhook(e.target.value)}
+                  placeholder="https://example.com/hook"
+                />
+              </div>
+              {webhookInvalid && (
+                <p className="text-xs text-danger">Use an https:// URL (http:// only for localhost)</p>
+              )}
             </div>
           </div>
 
           <div className="flex items-center gap-2">
             <button
               onClick={handleSave}
-              disabled={!name.trim()}
+              disabled={!name.trim() || webhookInvalid}
               className="px-3 py-1.5 text-xs font-medium text-white bg-accent hover:bg-accent-hover rounded-md transition-colors disabled:opacity-50"
             >
               {editingId ? "Update" : "Save"}
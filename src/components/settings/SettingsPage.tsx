@@ -5,14 +5,45 @@ import { navigateToLabel, navigateToSettings } from "@/router/navigate";
 import { useAccountStore } from "@/stores/accountStore";
 import { getSetting, setSetting, getSecureSetting, setSecureSetting } from "@/services/db/settings";
 import { PROVIDER_MODELS } from "@/services/ai/types";
-import { deleteAccount } from "@/services/db/accounts";
+import { deleteAccount, getAccount, updateAccountTeamFolder, updateAccountTransport, type DbAccount } from "@/services/db/accounts";
+import { parseTeamMembers, syncTeamTags } from "@/services/teamTags";
 import { removeClient, reauthorizeAccount } from "@/services/gmail/tokenManager";
-import { triggerSync, forceFullSync, resyncAccount } from "@/services/gmail/syncManager";
+import { triggerSync, forceFullSync, resyncAccount, retrySyncNow } from "@/services/gmail/syncManager";
+import { getPollSchedule, type FolderPollState } from "@/services/imap/pollScheduler";
+import { stopImapPush } from "@/services/imap/imapPush";
+import { registerAccountCredentials } from "@/services/imap/imapConfigBuilder";
+import { getProfileStatus, setMasterPassword, type ProfileStatus } from "@/services/profileLock";
+import {
+  getCurrentProfile,
+  listProfiles,
+  createProfile,
+  switchProfile,
+  type Profile,
+} from "@/services/profiles";
+import { credentialsDelete, unregisterAccount } from "@/services/imap/tauriCommands";
+import { clearImageProxyCache } from "@/services/imageProxy";
+import { setReadOnly as saveReadOnly } from "@/services/readOnly";
+import { getCloseBehavior, setCloseBehavior, type CloseBehavior } from "@/services/closeBehavior";
+import { getManagedPolicy, MANAGED_SETTING_ERROR } from "@/services/managedPolicy";
+import {
+  getLocalApi,
+  setLocalApi,
+  regenerateLocalApiToken,
+  type LocalApiStatus,
+} from "@/services/localApi";
+import {
+  getSendPolicy,
+  parseDomainList,
+  setSendPolicy,
+  type SendPolicy,
+} from "@/services/composer/sendPolicy";
+import { getExtraAddresses, setExtraAddresses } from "@/services/identity";
 import {
   registerComposeShortcut,
   getCurrentShortcut,
   DEFAULT_SHORTCUT,
 } from "@/services/globalShortcut";
+import { getQuickComposeShortcut, setQuickComposeShortcut } from "@/services/quickCompose";
 import {
   ArrowLeft,
   RefreshCw,
@@ -34,15 +65,19 @@ import {
   ChevronUp,
   ChevronDown,
   RotateCcw,
+  Play,
   type LucideIcon,
 } from "lucide-react";
 import { SignatureEditor } from "./SignatureEditor";
 import { TemplateEditor } from "./TemplateEditor";
+import { SnippetEditor } from "./SnippetEditor";
 import { FilterEditor } from "./FilterEditor";
 import { LabelEditor } from "./LabelEditor";
 import { ContactEditor } from "./ContactEditor";
+import { ContactGroupEditor } from "./ContactGroupEditor";
 import { SubscriptionManager } from "./SubscriptionManager";
 import { SmartFolderEditor } from "./SmartFolderEditor";
+import { FeedEditor } from "./FeedEditor";
 import { QuickStepEditor } from "./QuickStepEditor";
 import { SmartLabelEditor } from "./SmartLabelEditor";
 import { SHORTCUTS, getDefaultKeyMap } from "@/constants/shortcuts";
@@ -57,7 +92,30 @@ import {
 import { ALL_NAV_ITEMS } from "@/components/layout/Sidebar";
 import type { SidebarNavItem } from "@/stores/uiStore";
 import { Button } from "@/components/ui/Button";
+import { useAccountHealthStore } from "@/stores/accountHealthStore";
+import { resetAccountHealth } from "@/services/sync/accountHealth";
 import { TextField } from "@/components/ui/TextField";
+import { BODY_CACHE_DAYS_KEY, evictOldContent } from "@/services/cacheEviction";
+import { runDbMaintenance } from "@/services/db/maintenance";
+import { getOtpClipboardSeconds, setOtpClipboardSeconds } from "@/services/extraction/otp";
+import {
+  formatMinutes,
+  getNotificationRules,
+  parseMinutes,
+  setNotificationRules,
+  type NotificationRules,
+} from "@/services/notifications/notificationRules";
+import {
+  SOUND_FILE_EXTENSIONS,
+  addNotificationSound,
+  listNotificationSounds,
+  previewNotificationSound,
+  removeNotificationSound,
+  soundFromKey,
+  soundKey,
+  type NotificationSound,
+  type SoundOption,
+} from "@/services/notifications/notificationSounds";
 import appIcon from "@/assets/icon.png";
 
 type SettingsTab = "general" | "notifications" | "composing" | "mail-rules" | "people" | "accounts" | "shortcuts" | "ai" | "about";
@@ -95,13 +153,25 @@ export function SettingsPage() {
   const setInboxViewMode = useUIStore((s) => s.setInboxViewMode);
   const reduceMotion = useUIStore((s) => s.reduceMotion);
   const setReduceMotion = useUIStore((s) => s.setReduceMotion);
+  const imageProxyEnabled = useUIStore((s) => s.imageProxyEnabled);
+  const setImageProxyEnabled = useUIStore((s) => s.setImageProxyEnabled);
+  const isReadOnly = useUIStore((s) => s.isReadOnly);
+  const [lockedSettings, setLockedSettings] = useState<string[]>([]);
+  const imageProxyUrl = useUIStore((s) => s.imageProxyUrl);
+  const setImageProxyUrl = useUIStore((s) => s.setImageProxyUrl);
+  const [imageProxyUrlDraft, setImageProxyUrlDraft] = useState(imageProxyUrl);
   const accounts = useAccountStore((s) => s.accounts);
+  const accountHealth = useAccountHealthStore((s) => s.health);
   const removeAccountFromStore = useAccountStore((s) => s.removeAccount);
   const { tab } = useParams({ strict: false }) as { tab?: string };
   const activeTab = (tab && tabs.some((t) => t.id === tab) ? tab : "general") as SettingsTab;
   const setActiveTab = (t: SettingsTab) => navigateToSettings(t);
   const [notificationsEnabled, setNotificationsEnabled] = useState(true);
   const [undoSendDelay, setUndoSendDelay] = useState("5");
+  const [undoDeleteDelay, setUndoDeleteDelay] = useState("10");
+  const [groupSyntax, setGroupSyntax] = useState(false);
+  const [mirrorPins, setMirrorPins] = useState(false);
+  const [syncNotes, setSyncNotes] = useState(false);
   const [clientId, setClientId] = useState("");
   const [clientSecret, setClientSecret] = useState("");
   const [apiSettingsSaved, setApiSettingsSaved] = useState(false);
@@ -134,12 +204,16 @@ export function SettingsPage() {
   const [styleAnalyzing, setStyleAnalyzing] = useState(false);
   const [styleAnalyzeDone, setStyleAnalyzeDone] = useState(false);
   const [cacheMaxMb, setCacheMaxMb] = useState("500");
+  const [bodyCacheDays, setBodyCacheDays] = useState("0");
   const [cacheSizeMb, setCacheSizeMb] = useState<number | null>(null);
   const [clearingCache, setClearingCache] = useState(false);
+  const [maintainingDb, setMaintainingDb] = useState(false);
+  const [dbMaintenanceResult, setDbMaintenanceResult] = useState<string | null>(null);
   const [reauthStatus, setReauthStatus] = useState<Record<string, "idle" | "authorizing" | "done" | "error">>({});
   const [resyncStatus, setResyncStatus] = useState<Record<string, "idle" | "syncing" | "done" | "error">>({});
   const [autoArchiveCategories, setAutoArchiveCategories] = useState<Set<string>>(() => new Set());
   const [smartNotifications, setSmartNotifications] = useState(true);
+  const [junkGraceSeconds, setJunkGraceSeconds] = useState("0");
   const [notifyCategories, setNotifyCategories] = useState<Set<string>>(() => new Set(["Primary"]));
   const [vipSenders, setVipSenders] = useState<{ email_address: string; display_name: string | null }[]>([]);
   const [newVipEmail, setNewVipEmail] = useState("");
@@ -151,6 +225,11 @@ export function SettingsPage() {
       setNotificationsEnabled(notif !== "false");
       const delay = await getSetting("undo_send_delay_seconds");
       setUndoSendDelay(delay ?? "5");
+      const deleteDelay = await getSetting("undo_delete_seconds");
+      setUndoDeleteDelay(deleteDelay ?? "10");
+      setGroupSyntax((await getSetting("contact_group_syntax")) === "true");
+      setMirrorPins((await getSetting("mirror_pins_imap")) === "true");
+      setSyncNotes((await getSetting("sync_notes_imap")) === "true");
       const id = await getSetting("google_client_id");
       setClientId(id ?? "");
       const secret = await getSecureSetting("google_client_secret");
@@ -164,6 +243,13 @@ export function SettingsPage() {
       const syncDays = await getSetting("sync_period_days");
       setSyncPeriodDays(syncDays ?? "365");
 
+      // Settings the managed policy locks
+      try {
+        setLockedSettings((await getManagedPolicy()).locked_settings);
+      } catch (err) {
+        console.error("Failed to load managed policy:", err);
+      }
+
       // Load autostart state
       try {
         const { isEnabled } = await import("@tauri-apps/plugin-autostart");
@@ -217,6 +303,8 @@ export function SettingsPage() {
       // Load smart notification settings
       const smartNotif = await getSetting("smart_notifications");
       setSmartNotifications(smartNotif !== "false");
+      const junkGrace = await getSetting("junk_grace_seconds");
+      setJunkGraceSeconds(junkGrace ?? "0");
       const notifCats = await getSetting("notify_categories");
       if (notifCats) {
         setNotifyCategories(new Set(notifCats.split(",").map((s) => s.trim()).filter(Boolean)));
@@ -235,6 +323,7 @@ export function SettingsPage() {
       // Load cache settings
       const cacheMax = await getSetting("attachment_cache_max_mb");
       setCacheMaxMb(cacheMax ?? "500");
+      setBodyCacheDays((await getSetting(BODY_CACHE_DAYS_KEY)) ?? "0");
       try {
         const { getCacheSize } = await import("@/services/attachments/cacheManager");
         const size = await getCacheSize();
@@ -257,6 +346,11 @@ export function SettingsPage() {
     await setSetting("undo_send_delay_seconds", value);
   }, []);
 
+  const handleUndoDeleteDelayChange = useCallback(async (value: string) => {
+    setUndoDeleteDelay(value);
+    await setSetting("undo_delete_seconds", value);
+  }, []);
+
   const handleSaveApiSettings = useCallback(async () => {
     const trimmedId = clientId.trim();
     if (trimmedId) {
@@ -309,6 +403,10 @@ export function SettingsPage() {
   const handleRemoveAccount = useCallback(
     async (accountId: string) => {
       removeClient(accountId);
+      stopImapPush(accountId).catch(() => {});
+      unregisterAccount(accountId).catch(() => {});
+      credentialsDelete(accountId).catch(() => {});
+      resetAccountHealth(accountId);
       await deleteAccount(accountId);
       removeAccountFromStore(accountId);
     },
@@ -524,6 +622,7 @@ export function SettingsPage() {
                       checked={autostartEnabled}
                       onToggle={handleAutostartToggle}
                     />
+                    <CloseBehaviorRow />
                   </Section>
 
                   <Section title="Privacy & Security">
@@ -537,6 +636,39 @@ export function SettingsPage() {
                         await setSetting("block_remote_images", newVal ? "true" : "false");
                       }}
                     />
+                    <ToggleRow
+                      label="Load images through Sora"
+                      description="Fetches remote images in the background so senders can't see your IP address or when you read their message"
+                      checked={imageProxyEnabled}
+                      onToggle={() => setImageProxyEnabled(!imageProxyEnabled)}
+                    />
+                    {imageProxyEnabled && (
+                      <div className="space-y-2">
+                        <TextField
+                          label="Privacy proxy (optional)"
+                          size="md"
+                          type="text"
+                          value={imageProxyUrlDraft}
+                          onChange={(e) => setImageProxyUrlDraft(e.target.value)}
+                          onBlur={() => {
+                            const trimmed = imageProxyUrlDraft.trim();
+                            if (trimmed !== imageProxyUrl) setImageProxyUrl(trimmed);
+                          }}
+                          placeholder="http://proxy:3128 or https://img.example/?url={url}"
+                        />
+                        <Button
+                          variant="secondary"
+                          size="md"
+                          onClick={() => {
+                            clearImageProxyCache().catch((err) =>
+                              console.error("Failed to clear image cache:", err),
+                            );
+                          }}
+                        >
+                          Clear image cache
+                        </Button>
+                      </div>
+                    )}
                     <ToggleRow
                       label="Phishing link detection"
                       description="Scan message links for phishing indicators and show warnings"
@@ -564,6 +696,29 @@ export function SettingsPage() {
                         </select>
                       </SettingRow>
                     )}
+                    <ToggleRow
+                      label="Read-only mode"
+                      description={
+                        lockedSettings.includes("read_only")
+                          ? MANAGED_SETTING_ERROR
+                          : "Refuses sending, moving, deleting and flagging, for demos or reviewing an archive without changing anything on the server"
+                      }
+                      checked={isReadOnly}
+                      disabled={lockedSettings.includes("read_only")}
+                      onToggle={async () => {
+                        const newVal = !isReadOnly;
+                        try {
+                          await saveReadOnly(newVal);
+                          useUIStore.getState().setReadOnly(newVal);
+                        } catch (err) {
+                          console.error("Failed to set read-only mode:", err);
+                        }
+                      }}
+                    />
+                  </Section>
+
+                  <Section title="Local API">
+                    <LocalApiControls />
                   </Section>
 
                   <Section title="Storage">
@@ -611,6 +766,60 @@ export function SettingsPage() {
                         <option value="2000">2 GB</option>
                       </select>
                     </SettingRow>
+                    <SettingRow label="Keep bodies and attachments">
+                      <select
+                        value={bodyCacheDays}
+                        onChange={async (e) => {
+                          const val = e.target.value;
+                          setBodyCacheDays(val);
+                          await setSetting(BODY_CACHE_DAYS_KEY, val);
+                          evictOldContent().catch((err) => console.error("Failed to evict old content:", err));
+                        }}
+                        className="w-48 bg-bg-tertiary text-text-primary text-sm px-3 py-1.5 rounded-md border border-border-primary focus:border-accent outline-none"
+                      >
+                        <option value="0">Forever</option>
+                        <option value="30">30 days</option>
+                        <option value="90">90 days</option>
+                        <option value="180">180 days</option>
+                        <option value="365">1 year</option>
+                      </select>
+                    </SettingRow>
+                    <p className="text-xs text-text-tertiary">
+                      Older messages keep their headers and stay searchable; their content is downloaded again when opened.
+                    </p>
+                    <div className="flex items-center justify-between">
+                      <div>
+                        <span className="text-sm text-text-secondary">Database</span>
+                        <p className="text-xs text-text-tertiary mt-0.5">
+                          {dbMaintenanceResult ?? "Check for damage and reclaim unused space"}
+                        </p>
+                      </div>
+                      <Button
+                        variant="secondary"
+                        onClick={async () => {
+                          setMaintainingDb(true);
+                          try {
+                            const report = await runDbMaintenance(true);
+                            const mb = (bytes: number) => `${Math.round(bytes / (1024 * 1024) * 10) / 10} MB`;
+                            const repaired = report.reindexed || report.fts_rebuilt ? ", repaired indexes" : "";
+                            setDbMaintenanceResult(
+                              report.integrity_errors.length > 0
+                                ? `${report.integrity_errors.length} problem(s) could not be repaired`
+                                : `${mb(report.size_before)} → ${mb(report.size_after)}${repaired}`,
+                            );
+                          } catch (err) {
+                            console.error("Database maintenance failed:", err);
+                            setDbMaintenanceResult("Maintenance failed");
+                          } finally {
+                            setMaintainingDb(false);
+                          }
+                        }}
+                        disabled={maintainingDb}
+                        className="bg-bg-tertiary text-text-primary border border-border-primary"
+                      >
+                        {maintainingDb ? "Checking..." : "Check & Optimize"}
+                      </Button>
+                    </div>
                   </Section>
                 </>
               )}
@@ -633,8 +842,30 @@ export function SettingsPage() {
                         await setSetting("smart_notifications", newVal ? "true" : "false");
                       }}
                     />
+                    <SettingRow label="Wait for spam filtering">
+                      <select
+                        value={junkGraceSeconds}
+                        onChange={async (e) => {
+                          const val = e.target.value;
+                          setJunkGraceSeconds(val);
+                          await setSetting("junk_grace_seconds", val);
+                        }}
+                        className="w-48 bg-bg-tertiary text-text-primary text-sm px-3 py-1.5 rounded-md border border-border-primary focus:border-accent outline-none"
+                      >
+                        <option value="0">Off</option>
+                        <option value="15">15 seconds</option>
+                        <option value="30">30 seconds</option>
+                        <option value="60">1 minute</option>
+                      </select>
+                    </SettingRow>
+                    <p className="text-xs text-text-tertiary -mt-1">
+                      Delay new mail notifications and skip them if the message gets moved to Junk
+                    </p>
+                    <OtpClipboardRow />
                   </Section>
 
+                  <NotificationRulesSettings />
+
                   {smartNotifications && (
                     <>
                       <Section title="Category Filters">
@@ -663,68 +894,68 @@ export function SettingsPage() {
                           </div>
                         </div>
                       </Section>
+                    </>
+                  )}
 
-                      <Section title="VIP Senders">
-                        <p className="text-xs text-text-tertiary mb-2">
-                          These senders always trigger notifications regardless of category
-                        </p>
-                        <div className="space-y-1.5">
-                          {vipSenders.map((vip) => (
-                            <div key={vip.email_address} className="flex items-center justify-between py-1.5 px-3 bg-bg-secondary rounded-md">
-                              <span className="text-xs text-text-primary truncate">
-                                {vip.display_name ? `${vip.display_name} (${vip.email_address})` : vip.email_address}
-                              </span>
-                              <button
-                                onClick={async () => {
-                                  const activeId = accounts.find((a) => a.isActive)?.id;
-                                  if (!activeId) return;
-                                  const { removeVipSender } = await import("@/services/db/notificationVips");
-                                  await removeVipSender(activeId, vip.email_address);
-                                  setVipSenders((prev) => prev.filter((v) => v.email_address !== vip.email_address));
-                                }}
-                                className="text-xs text-danger hover:text-danger/80 ml-2 shrink-0"
-                              >
-                                Remove
-                              </button>
-                            </div>
-                          ))}
-                        </div>
-                        <div className="flex gap-2 mt-2">
-                          <input
-                            type="email"
-                            value={newVipEmail}
-                            onChange={(e) => setNewVipEmail(e.target.value)}
-                            placeholder="email@example.com"
-                            className="flex-1 px-3 py-1.5 bg-bg-tertiary border border-border-primary rounded-md text-xs text-text-primary outline-none focus:border-accent"
-                            onKeyDown={async (e) => {
-                              if (e.key !== "Enter" || !newVipEmail.trim()) return;
-                              const activeId = accounts.find((a) => a.isActive)?.id;
-                              if (!activeId) return;
-                              const { addVipSender } = await import("@/services/db/notificationVips");
-                              await addVipSender(activeId, newVipEmail.trim());
-                              setVipSenders((prev) => [...prev, { email_address: newVipEmail.trim().toLowerCase(), display_name: null }]);
-                              setNewVipEmail("");
-                            }}
-                          />
-                          <Button
-                            variant="primary"
+                  <Section title="VIP Senders">
+                    <p className="text-xs text-text-tertiary mb-2">
+                      These senders always notify, with their own sound, regardless of category or Do Not Disturb
+                    </p>
+                    <div className="space-y-1.5">
+                      {vipSenders.map((vip) => (
+                        <div key={vip.email_address} className="flex items-center justify-between py-1.5 px-3 bg-bg-secondary rounded-md">
+                          <span className="text-xs text-text-primary truncate">
+                            {vip.display_name ? `${vip.display_name} (${vip.email_address})` : vip.email_address}
+                          </span>
+                          <button
                             onClick={async () => {
-                              if (!newVipEmail.trim()) return;
                               const activeId = accounts.find((a) => a.isActive)?.id;
                               if (!activeId) return;
-                              const { addVipSender } = await import("@/services/db/notificationVips");
-                              await addVipSender(activeId, newVipEmail.trim());
-                              setVipSenders((prev) => [...prev, { email_address: newVipEmail.trim().toLowerCase(), display_name: null }]);
-                              setNewVipEmail("");
+                              const { removeVipSender } = await import("@/services/db/notificationVips");
+                              await removeVipSender(activeId, vip.email_address);
+                              setVipSenders((prev) => prev.filter((v) => v.email_address !== vip.email_address));
                             }}
-                            disabled={!newVipEmail.trim()}
+                            className="text-xs text-danger hover:text-danger/80 ml-2 shrink-0"
                           >
-                            Add
-                          </Button>
+                            Remove
+                          </button>
                         </div>
-                      </Section>
-                    </>
-                  )}
+                      ))}
+                    </div>
+                    <div className="flex gap-2 mt-2">
+                      <input
+                        type="email"
+                        value={newVipEmail}
+                        onChange={(e) => setNewVipEmail(e.target.value)}
+                        placeholder="email@example.com"
+                        className="flex-1 px-3 py-1.5 bg-bg-tertiary border border-border-primary rounded-md text-xs text-text-primary outline-none focus:border-accent"
+                        onKeyDown={async (e) => {
+                          if (e.key !== "Enter" || !newVipEmail.trim()) return;
+                          const activeId = accounts.find((a) => a.isActive)?.id;
+                          if (!activeId) return;
+                          const { addVipSender } = await import("@/services/db/notificationVips");
+                          await addVipSender(activeId, newVipEmail.trim());
+                          setVipSenders((prev) => [...prev, { email_address: newVipEmail.trim().toLowerCase(), display_name: null }]);
+                          setNewVipEmail("");
+                        }}
+                      />
+                      <Button
+                        variant="primary"
+                        onClick={async () => {
+                          if (!newVipEmail.trim()) return;
+                          const activeId = accounts.find((a) => a.isActive)?.id;
+                          if (!activeId) return;
+                          const { addVipSender } = await import("@/services/db/notificationVips");
+                          await addVipSender(activeId, newVipEmail.trim());
+                          setVipSenders((prev) => [...prev, { email_address: newVipEmail.trim().toLowerCase(), display_name: null }]);
+                          setNewVipEmail("");
+                        }}
+                        disabled={!newVipEmail.trim()}
+                      >
+                        Add
+                      </Button>
+                    </div>
+                  </Section>
                 </>
               )}
 
@@ -748,6 +979,23 @@ export function SettingsPage() {
                       checked={sendAndArchive}
                       onToggle={() => setSendAndArchive(!sendAndArchive)}
                     />
+                    <ToggleRow
+                      label="Show contact groups by name"
+                      description="Send groups as a named list (Team: a, b;) instead of individual addresses"
+                      checked={groupSyntax}
+                      onToggle={async () => {
+                        const newVal = !groupSyntax;
+                        setGroupSyntax(newVal);
+                        await setSetting("contact_group_syntax", newVal ? "true" : "false");
+                      }}
+                    />
+                  </Section>
+
+                  <Section title="Send checks">
+                    <p className="text-xs text-text-tertiary">
+                      Ask before sending a message that matches any of these.
+                    </p>
+                    <SendCheckSettings />
                   </Section>
 
                   <Section title="Behavior">
@@ -776,6 +1024,38 @@ export function SettingsPage() {
                         <option value="manual">Manually</option>
                       </select>
                     </SettingRow>
+                    <SettingRow label="Undo permanent delete">
+                      <select
+                        value={undoDeleteDelay}
+                        onChange={(e) => handleUndoDeleteDelayChange(e.target.value)}
+                        className="w-48 bg-bg-tertiary text-text-primary text-sm px-3 py-1.5 rounded-md border border-border-primary focus:border-accent outline-none"
+                      >
+                        <option value="0">Off</option>
+                        <option value="5">5 seconds</option>
+                        <option value="10">10 seconds</option>
+                        <option value="30">30 seconds</option>
+                      </select>
+                    </SettingRow>
+                    <ToggleRow
+                      label="Sync pins to the server"
+                      description="Also mark pinned IMAP messages with the $Pinned keyword"
+                      checked={mirrorPins}
+                      onToggle={async () => {
+                        const newVal = !mirrorPins;
+                        setMirrorPins(newVal);
+                        await setSetting("mirror_pins_imap", newVal ? "true" : "false");
+                      }}
+                    />
+                    <ToggleRow
+                      label="Sync notes to the server"
+                      description="Keep a copy of each note in a Notes folder on IMAP accounts, so your other devices see it"
+                      checked={syncNotes}
+                      onToggle={async () => {
+                        const newVal = !syncNotes;
+                        setSyncNotes(newVal);
+                        await setSetting("sync_notes_imap", newVal ? "true" : "false");
+                      }}
+                    />
                   </Section>
 
                   <Section title="Signatures">
@@ -785,6 +1065,13 @@ export function SettingsPage() {
                   <Section title="Templates">
                     <TemplateEditor />
                   </Section>
+
+                  <Section title="Snippets">
+                    <p className="text-xs text-text-tertiary mb-3">
+                      Type an abbreviation while composing to replace it with the snippet's text.
+                    </p>
+                    <SnippetEditor />
+                  </Section>
                 </>
               )}
 
@@ -818,6 +1105,13 @@ export function SettingsPage() {
                     <SmartFolderEditor />
                   </Section>
 
+                  <Section title="Feeds">
+                    <p className="text-xs text-text-tertiary mb-3">
+                      Subscribe to RSS and Atom feeds. New entries arrive in the Feeds folder every 30 minutes and stay on this computer.
+                    </p>
+                    <FeedEditor />
+                  </Section>
+
                   <Section title="Quick Steps">
                     <p className="text-xs text-text-tertiary mb-3">
                       Quick steps let you chain multiple actions together into a single click.
@@ -837,6 +1131,13 @@ export function SettingsPage() {
                     <ContactEditor />
                   </Section>
 
+                  <Section title="Contact Groups">
+                    <p className="text-xs text-text-tertiary mb-3">
+                      Address a whole group at once by typing its name in To, Cc or Bcc.
+                    </p>
+                    <ContactGroupEditor />
+                  </Section>
+
                   <Section title="Subscriptions">
                     <p className="text-xs text-text-tertiary mb-3">
                       View all detected newsletter and promotional senders. Unsubscribe using RFC 8058 one-click POST, mailto, or browser fallback.
@@ -848,6 +1149,8 @@ export function SettingsPage() {
 
               {activeTab === "accounts" && (
                 <>
+                  <ProfilesSection />
+
                   <Section title="Mail Accounts">
                     {accounts.filter((a) => a.provider !== "caldav").length === 0 ? (
                       <p className="text-sm text-text-tertiary">
@@ -856,7 +1159,8 @@ export function SettingsPage() {
                     ) : (
                       <div className="space-y-2">
                         {accounts.filter((a) => a.provider !== "caldav").map((account) => {
-                          const providerLabel = account.provider === "imap" ? "IMAP" : "Gmail";
+                          const providerLabel = account.provider === "imap" ? "IMAP" : account.provider === "nntp" ? "NNTP" : "Gmail";
+                          const health = accountHealth[account.id];
                           return (
                             <div
                               key={account.id}
@@ -872,8 +1176,23 @@ export function SettingsPage() {
                                 <div className="text-xs text-text-tertiary">
                                   {account.email}
                                 </div>
+                                {health && health.state !== "ok" && (
+                                  <div className={`text-xs ${health.state === "failing" ? "text-danger" : "text-warning"}`}>
+                                    {health.state === "failing" ? "Sync failing" : "Sync degraded"}
+                                    {health.reason && `: ${health.reason}`}
+                                    {health.nextRetryAt && ` — retrying at ${new Date(health.nextRetryAt).toLocaleTimeString([], { hour: "numeric", minute: "2-digit" })}`}
+                                  </div>
+                                )}
                               </div>
                               <div className="flex items-center gap-3">
+                                {health && health.state !== "ok" && (
+                                  <button
+                                    onClick={() => retrySyncNow(account.id)}
+                                    className="text-xs text-accent hover:text-accent-hover transition-colors"
+                                  >
+                                    Retry now
+                                  </button>
+                                )}
                                 <button
                                   onClick={() => handleReauthorizeAccount(account.id, account.email)}
                                   disabled={reauthStatus[account.id] === "authorizing"}
@@ -941,8 +1260,14 @@ export function SettingsPage() {
 
                   <SendAsAliasesSection />
 
+                  <MyAddressesSection />
+
                   <ImapCalDavSection />
 
+                  <GmailTransportSection />
+
+                  <TeamFolderSection />
+
                   <Section title="Google API">
                     <div className="space-y-3">
                       <TextField
@@ -972,6 +1297,8 @@ export function SettingsPage() {
                     </div>
                   </Section>
 
+                  <MasterPasswordSection />
+
                   <Section title="Sync">
                     <div className="flex items-center justify-between">
                       <span className="text-sm text-text-secondary">
@@ -1512,6 +1839,54 @@ function SendAsAliasesSection() {
   );
 }
 
+function MyAddressesSection() {
+  const accounts = useAccountStore((s) => s.accounts);
+  const activeAccount = accounts.find((a) => a.isActive);
+  const [saved, setSaved] = useState("");
+  const [draft, setDraft] = useState("");
+
+  useEffect(() => {
+    if (!activeAccount) return;
+    let cancelled = false;
+    getExtraAddresses(activeAccount.id).then((addresses) => {
+      if (cancelled) return;
+      setSaved(addresses.join(", "));
+      setDraft(addresses.join(", "));
+    });
+    return () => { cancelled = true; };
+  }, [activeAccount]);
+
+  if (!activeAccount) return null;
+
+  const handleSave = async () => {
+    if (draft.trim() === saved) return;
+    const addresses = draft.split(/[,\s]+/).filter(Boolean);
+    try {
+      await setExtraAddresses(activeAccount, addresses);
+      setSaved(draft.trim());
+    } catch (err) {
+      console.error("Failed to save addresses:", err);
+    }
+  };
+
+  return (
+    <Section title="My Addresses">
+      <p className="text-xs text-text-tertiary mb-3">
+        Other addresses that are yours, such as an old address or one that forwards here. Messages from them count as sent by you and are left out when replying to all. Variants like you+news@example.com are recognized automatically.
+      </p>
+      <TextField
+        label={`Also me on ${activeAccount.email}`}
+        size="md"
+        type="text"
+        value={draft}
+        onChange={(e) => setDraft(e.target.value)}
+        onBlur={handleSave}
+        placeholder="old@example.com, me@forwarder.example"
+      />
+    </Section>
+  );
+}
+
 function SyncOfflineSection() {
   const [pendingCount, setPendingCount] = useState(0);
   const [failedCount, setFailedCount] = useState(0);
@@ -1596,6 +1971,196 @@ function SyncOfflineSection() {
   );
 }
 
+function MasterPasswordSection() {
+  const [status, setStatus] = useState<ProfileStatus | null>(null);
+  const [currentPassword, setCurrentPassword] = useState("");
+  const [newPassword, setNewPassword] = useState("");
+  const [confirmPassword, setConfirmPassword] = useState("");
+  const [error, setError] = useState<string | null>(null);
+  const [saved, setSaved] = useState(false);
+  const [saving, setSaving] = useState(false);
+
+  useEffect(() => {
+    getProfileStatus().then(setStatus).catch(() => {});
+  }, []);
+
+  const apply = async (next: string | null) => {
+    setSaving(true);
+    setError(null);
+    try {
+      await setMasterPassword(status?.protected ? currentPassword : null, next);
+      setStatus(await getProfileStatus());
+      setCurrentPassword("");
+      setNewPassword("");
+      setConfirmPassword("");
+      setSaved(true);
+      setTimeout(() => setSaved(false), 2000);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  if (!status) return null;
+
+  const mismatch = confirmPassword.length > 0 && newPassword !== confirmPassword;
+  const canSet = newPassword.length > 0 && newPassword === confirmPassword
+    && (!status.protected || currentPassword.length > 0);
+
+  return (
+    <Section title="Master Password">
+      <p className="text-xs text-text-tertiary">
+        {status.protected
+          ? "Stored passwords and tokens are encrypted with your master password, which Velo asks for at startup."
+          : "Encrypt stored passwords and tokens with a master password that Velo asks for at startup. Useful when no OS keychain protects your files. It can't be recovered if forgotten."}
+      </p>
+      {status.protected && (
+        <TextField
+          label="Current password"
+          size="md"
+          type="password"
+          value={currentPassword}
+          onChange={(e) => setCurrentPassword(e.target.value)}
+        />
+      )}
+      <TextField
+        label={status.protected ? "New password" : "Password"}
+        size="md"
+        type="password"
+        value={newPassword}
+        onChange={(e) => setNewPassword(e.target.value)}
+      />
+      <TextField
+        label="Confirm password"
+        size="md"
+        type="password"
+        value={confirmPassword}
+        onChange={(e) => setConfirmPassword(e.target.value)}
+        error={mismatch ? "Passwords don't match" : undefined}
+      />
+      {error && <p className="text-xs text-danger">{error}</p>}
+      <div className="flex gap-2">
+        <Button
+          variant="primary"
+          size="md"
+          onClick={() => apply(newPassword)}
+          disabled={!canSet || saving}
+        >
+          {saved ? "Saved!" : status.protected ? "Change password" : "Set password"}
+        </Button>
+        {status.protected && (
+          <Button
+            variant="secondary"
+            size="md"
+            onClick={() => apply(null)}
+            disabled={!currentPassword || saving}
+            className="bg-bg-tertiary text-text-primary border border-border-primary"
+          >
+            Remove password
+          </Button>
+        )}
+      </div>
+    </Section>
+  );
+}
+
+function ProfilesSection() {
+  const [currentId, setCurrentId] = useState<string | null>(null);
+  const [profiles, setProfiles] = useState<Profile[]>([]);
+  const [newName, setNewName] = useState("");
+  const [error, setError] = useState<string | null>(null);
+  const [busy, setBusy] = useState(false);
+
+  useEffect(() => {
+    Promise.all([getCurrentProfile(), listProfiles()])
+      .then(([current, all]) => {
+        setCurrentId(current.id);
+        setProfiles(all);
+      })
+      .catch(() => {});
+  }, []);
+
+  const handleCreate = async () => {
+    setBusy(true);
+    setError(null);
+    try {
+      await createProfile(newName);
+      setProfiles(await listProfiles());
+      setNewName("");
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const handleSwitch = async (id: string) => {
+    setBusy(true);
+    setError(null);
+    try {
+      // Restarts the app on success
+      await switchProfile(id);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+      setBusy(false);
+    }
+  };
+
+  if (!currentId) return null;
+
+  return (
+    <Section title="Profiles">
+      <p className="text-xs text-text-tertiary">
+        Each profile has its own accounts, mail cache and settings. Switching
+        restarts Velo. Launch with <code>--profile &lt;name&gt;</code> to open a
+        specific profile.
+      </p>
+      <div className="space-y-2">
+        {profiles.map((profile) => (
+          <div
+            key={profile.id}
+            className="flex items-center justify-between py-2.5 px-4 bg-bg-secondary rounded-lg"
+          >
+            <span className="text-sm text-text-primary">{profile.name}</span>
+            {profile.id === currentId ? (
+              <span className="text-xs text-text-tertiary">Current</span>
+            ) : (
+              <button
+                onClick={() => handleSwitch(profile.id)}
+                disabled={busy}
+                className="text-xs text-accent hover:text-accent-hover transition-colors disabled:opacity-50"
+              >
+                Switch
+              </button>
+            )}
+          </div>
+        ))}
+      </div>
+      <div className="flex items-end gap-2">
+        <div className="flex-1">
+          <TextField
+            label="New profile"
+            size="md"
+            value={newName}
+            onChange={(e) => setNewName(e.target.value)}
+            placeholder="e.g. Work"
+          />
+        </div>
+        <Button
+          variant="primary"
+          size="md"
+          onClick={handleCreate}
+          disabled={!newName.trim() || busy}
+        >
+          Create
+        </Button>
+      </div>
+      {error && <p className="text-xs text-danger">{error}</p>}
+    </Section>
+  );
+}
+
 function DeveloperTab() {
   const [appVersion, setAppVersion] = useState("");
   const [tauriVersion, setTauriVersion] = useState("");
@@ -1722,6 +2287,8 @@ function DeveloperTab() {
         </div>
       </Section>
 
+      <PollScheduleSection />
+
       <Section title="Developer Tools">
         <div className="flex items-center justify-between">
           <div>
@@ -1832,6 +2399,43 @@ function AboutTab() {
 }
 
 
+function formatDuration(ms: number): string {
+  const secs = Math.max(0, Math.round(ms / 1000));
+  return secs < 60 ? `${secs}s` : `${Math.floor(secs / 60)}m ${secs % 60}s`;
+}
+
+function PollScheduleSection() {
+  const accounts = useAccountStore((s) => s.accounts);
+  const [schedule, setSchedule] = useState<FolderPollState[]>(() => getPollSchedule());
+  const [now, setNow] = useState(() => Date.now());
+
+  useEffect(() => {
+    const id = setInterval(() => {
+      setSchedule(getPollSchedule());
+      setNow(Date.now());
+    }, 5_000);
+    return () => clearInterval(id);
+  }, []);
+
+  const accountEmail = (id: string) => accounts.find((a) => a.id === id)?.email ?? id;
+
+  return (
+    <Section title="IMAP Poll Schedule">
+      {schedule.length === 0 ? (
+        <p className="text-xs text-text-tertiary">No IMAP folders polled yet</p>
+      ) : (
+        schedule.map((s) => (
+          <InfoRow
+            key={`${s.accountId}:${s.folder}`}
+            label={`${accountEmail(s.accountId)} — ${s.folder}`}
+            value={`every ${formatDuration(s.intervalMs)}, next in ${formatDuration(s.nextPollAt - now)}`}
+          />
+        ))
+      )}
+    </Section>
+  );
+}
+
 function InfoRow({ label, value }: { label: string; value: string }) {
   return (
     <div className="flex items-center justify-between">
@@ -1841,6 +2445,95 @@ function InfoRow({ label, value }: { label: string; value: string }) {
   );
 }
 
+/** Accelerator string for the global-shortcut APIs, or null for a bare modifier. */
+function globalShortcutFromKeyEvent(e: React.KeyboardEvent): string | null {
+  const key = e.key;
+  if (key === "Control" || key === "Meta" || key === "Shift" || key === "Alt") return null;
+
+  const parts: string[] = [];
+  if (e.ctrlKey || e.metaKey) parts.push("CmdOrCtrl");
+  if (e.altKey) parts.push("Alt");
+  if (e.shiftKey) parts.push("Shift");
+  parts.push(key.length === 1 ? key.toUpperCase() : key);
+  return parts.join("+");
+}
+
+/** The backend-registered shortcut for the small quick-compose window. */
+function QuickComposeShortcutRow() {
+  const [shortcut, setShortcut] = useState<string | null | undefined>(undefined);
+  const [recording, setRecording] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    getQuickComposeShortcut()
+      .then(setShortcut)
+      .catch((err) => console.error("Failed to load quick compose shortcut:", err));
+  }, []);
+
+  const save = useCallback(async (next: string | null) => {
+    setError(null);
+    try {
+      await setQuickComposeShortcut(next);
+      setShortcut(next);
+    } catch (err) {
+      setError(String(err));
+    }
+  }, []);
+
+  const handleRecord = useCallback((e: React.KeyboardEvent) => {
+    if (!recording) return;
+    e.preventDefault();
+    e.stopPropagation();
+
+    const next = globalShortcutFromKeyEvent(e);
+    if (next) {
+      setRecording(false);
+      save(next);
+    }
+  }, [recording, save]);
+
+  if (shortcut === undefined) return null;
+
+  return (
+    <div>
+      <div className="flex items-center justify-between">
+        <div>
+          <span className="text-sm text-text-secondary">Quick compose window</span>
+          <p className="text-xs text-text-tertiary mt-0.5">
+            Open a small compose window next to the cursor from any app
+          </p>
+        </div>
+        <div className="flex items-center gap-2">
+          <kbd className="text-xs bg-bg-tertiary px-2 py-1 rounded border border-border-primary font-mono">
+            {shortcut ?? "Off"}
+          </kbd>
+          <button
+            onClick={() => setRecording(true)}
+            onKeyDown={handleRecord}
+            onBlur={() => setRecording(false)}
+            className={`text-xs px-2.5 py-1 rounded-md transition-colors ${
+              recording
+                ? "bg-accent text-white"
+                : "bg-bg-tertiary text-text-secondary hover:text-text-primary border border-border-primary"
+            }`}
+          >
+            {recording ? "Press keys..." : "Change"}
+          </button>
+          {shortcut && (
+            <button
+              onClick={() => save(null)}
+              className="text-xs text-text-tertiary hover:text-text-primary transition-colors"
+            >
+              Turn off
+            </button>
+          )}
+        </div>
+      </div>
+      {error && <p className="text-xs text-danger mt-1">{error}</p>}
+    </div>
+  );
+}
+
 function ShortcutsTab() {
   const keyMap = useShortcutStore((s) => s.keyMap);
   const setKey = useShortcutStore((s) => s.setKey);
@@ -1862,15 +2555,8 @@ function ShortcutsTab() {
     e.preventDefault();
     e.stopPropagation();
 
-    const parts: string[] = [];
-    if (e.ctrlKey || e.metaKey) parts.push("CmdOrCtrl");
-    if (e.altKey) parts.push("Alt");
-    if (e.shiftKey) parts.push("Shift");
-
-    const key = e.key;
-    if (key !== "Control" && key !== "Meta" && key !== "Shift" && key !== "Alt") {
-      parts.push(key.length === 1 ? key.toUpperCase() : key);
-      const shortcut = parts.join("+");
+    const shortcut = globalShortcutFromKeyEvent(e);
+    if (shortcut) {
       setComposeShortcut(shortcut);
       setRecordingGlobal(false);
       registerComposeShortcut(shortcut).catch((err) => {
@@ -1908,9 +2594,9 @@ function ShortcutsTab() {
       <Section title="Global Shortcut">
         <div className="flex items-center justify-between">
           <div>
-            <span className="text-sm text-text-secondary">Quick compose</span>
+            <span className="text-sm text-text-secondary">Compose</span>
             <p className="text-xs text-text-tertiary mt-0.5">
-              Open compose window from any app
+              Open the composer in the main window from any app
             </p>
           </div>
           <div className="flex items-center gap-2">
@@ -1932,6 +2618,7 @@ function ShortcutsTab() {
             </button>
           </div>
         </div>
+        <QuickComposeShortcutRow />
       </Section>
 
       <div className="flex items-center justify-between mb-4">
@@ -2039,6 +2726,124 @@ function CalDavSettingsInline({ account, onSaved }: { account: import("@/service
   return <CalDav account={account} onSaved={onSaved} />;
 }
 
+/**
+ * Lets a Gmail account signed in with OAuth send through the Gmail API
+ * instead of SMTP, for accounts running into Gmail's IMAP/SMTP limits.
+ */
+function GmailTransportSection() {
+  const activeAccountId = useAccountStore((s) => s.activeAccountId);
+  const [account, setAccount] = useState<DbAccount | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!activeAccountId) return;
+    getAccount(activeAccountId).then(setAccount);
+  }, [activeAccountId]);
+
+  const isGmailOAuth =
+    account?.provider === "imap" &&
+    account.auth_method === "oauth2" &&
+    /(^|\.)(gmail|googlemail)\.com$/i.test(account.imap_host ?? "");
+  if (!account || !isGmailOAuth) return null;
+
+  const handleChange = async (transport: "imap" | "gmail_api") => {
+    setError(null);
+    try {
+      await updateAccountTransport(account.id, transport);
+      const updated = { ...account, transport };
+      await registerAccountCredentials(updated);
+      setAccount(updated);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  };
+
+  return (
+    <Section title="Sending">
+      <SettingRow label="Send through">
+        <select
+          value={account.transport === "gmail_api" ? "gmail_api" : "imap"}
+          onChange={(e) => handleChange(e.target.value as "imap" | "gmail_api")}
+          className="w-48 bg-bg-tertiary text-text-primary text-sm px-3 py-1.5 rounded-md border border-border-primary focus:border-accent outline-none"
+        >
+          <option value="imap">SMTP</option>
+          <option value="gmail_api">Gmail API</option>
+        </select>
+      </SettingRow>
+      <p className="text-xs text-text-tertiary">
+        The Gmail API has its own quotas; use it if Gmail throttles this account's SMTP sends.
+      </p>
+      {error && <p className="text-xs text-danger">{error}</p>}
+    </Section>
+  );
+}
+
+/**
+ * Experimental: a folder of an IMAP account shared with a team, whose
+ * messages can be assigned and given a status that every member's Velo
+ * sees (see `teamTags.ts`).
+ */
+function TeamFolderSection() {
+  const activeAccountId = useAccountStore((s) => s.activeAccountId);
+  const [account, setAccount] = useState<DbAccount | null>(null);
+  const [folder, setFolder] = useState("");
+  const [members, setMembers] = useState("");
+  const [saved, setSaved] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!activeAccountId) return;
+    getAccount(activeAccountId).then((loaded) => {
+      setAccount(loaded);
+      setFolder(loaded?.team_folder ?? "");
+      setMembers(loaded?.team_members ?? "");
+      setSaved(false);
+    });
+  }, [activeAccountId]);
+
+  if (account?.provider !== "imap") return null;
+
+  const handleSave = async () => {
+    setError(null);
+    try {
+      const path = folder.trim() || null;
+      await updateAccountTeamFolder(account.id, path, parseTeamMembers(members));
+      setSaved(true);
+      setTimeout(() => setSaved(false), 2000);
+      if (path) await syncTeamTags(account.id);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  };
+
+  return (
+    <Section title="Team Folder (Experimental)">
+      <TextField
+        label="Shared folder"
+        type="text"
+        value={folder}
+        onChange={(e) => setFolder(e.target.value)}
+        placeholder="e.g. Shared/Support"
+      />
+      <TextField
+        label="Team members"
+        type="text"
+        value={members}
+        onChange={(e) => setMembers(e.target.value)}
+        placeholder="alice@example.com, bob@example.com"
+      />
+      <p className="text-xs text-text-tertiary">
+        Messages in this folder can be assigned and given a status from their context menu. The tags are
+        stored on the server as IMAP keywords, so everyone using Velo on this mailbox sees the same state.
+      </p>
+      <Button variant="primary" size="md" onClick={handleSave}>
+        {saved ? "Saved!" : "Save"}
+      </Button>
+      {error && <p className="text-xs text-danger">{error}</p>}
+    </Section>
+  );
+}
+
 function SidebarNavEditor() {
   const sidebarNavConfig = useUIStore((s) => s.sidebarNavConfig);
   const setSidebarNavConfig = useUIStore((s) => s.setSidebarNavConfig);
@@ -2182,6 +2987,528 @@ function SettingRow({
   );
 }
 
+function CloseBehaviorRow() {
+  const [behavior, setBehavior] = useState<CloseBehavior | null>(null);
+
+  useEffect(() => {
+    getCloseBehavior()
+      .then(setBehavior)
+      .catch((err) => console.error("Failed to load close behavior:", err));
+  }, []);
+
+  if (behavior === null) return null;
+
+  return (
+    <SettingRow label="Closing the window">
+      <select
+        value={behavior}
+        onChange={async (e) => {
+          const val = e.target.value as CloseBehavior;
+          setBehavior(val);
+          try {
+            await setCloseBehavior(val);
+          } catch (err) {
+            console.error("Failed to save close behavior:", err);
+          }
+        }}
+        className="w-48 bg-bg-tertiary text-text-primary text-sm px-3 py-1.5 rounded-md border border-border-primary focus:border-accent outline-none"
+      >
+        <option value="hide">Hides to tray</option>
+        <option value="minimize">Minimizes</option>
+        <option value="quit">Quits Sora</option>
+        <option value="ask">Asks every time</option>
+      </select>
+    </SettingRow>
+  );
+}
+
+function OtpClipboardRow() {
+  const [seconds, setSeconds] = useState<number | null>(null);
+
+  useEffect(() => {
+    getOtpClipboardSeconds()
+      .then(setSeconds)
+      .catch((err) => console.error("Failed to load code clipboard setting:", err));
+  }, []);
+
+  if (seconds === null) return null;
+
+  return (
+    <>
+      <SettingRow label="Clear copied codes">
+        <select
+          value={String(seconds)}
+          onChange={async (e) => {
+            const val = Number(e.target.value);
+            setSeconds(val);
+            try {
+              await setOtpClipboardSeconds(val);
+            } catch (err) {
+              console.error("Failed to save code clipboard setting:", err);
+            }
+          }}
+          className="w-48 bg-bg-tertiary text-text-primary text-sm px-3 py-1.5 rounded-md border border-border-primary focus:border-accent outline-none"
+        >
+          <option value="30">After 30 seconds</option>
+          <option value="60">After 1 minute</option>
+          <option value="120">After 2 minutes</option>
+          <option value="300">After 5 minutes</option>
+          <option value="0">Never</option>
+        </select>
+      </SettingRow>
+      <p className="text-xs text-text-tertiary -mt-1">
+        Verification codes show in notifications with a Copy code action; the copy is removed from the clipboard after this time
+      </p>
+    </>
+  );
+}
+
+/**
+ * The backend's notification rules. Both sections edit one copy, so saving
+ * in one doesn't undo a change made in the other.
+ */
+function NotificationRulesSettings() {
+  const [rules, setRules] = useState<NotificationRules | null>(null);
+
+  useEffect(() => {
+    getNotificationRules()
+      .then(setRules)
+      .catch((err) => console.error("Failed to load notification rules:", err));
+  }, []);
+
+  if (!rules) return null;
+
+  const save = async (next: NotificationRules) => {
+    setRules(next);
+    try {
+      await setNotificationRules(next);
+    } catch (err) {
+      console.error("Failed to save notification rules:", err);
+    }
+  };
+
+  return (
+    <>
+      <DoNotDisturbSection rules={rules} save={save} />
+      <NotificationSoundsSection rules={rules} save={save} />
+    </>
+  );
+}
+
+interface NotificationRulesProps {
+  rules: NotificationRules;
+  save: (next: NotificationRules) => Promise<void>;
+}
+
+/** Do Not Disturb, quiet hours and digests, enforced by the backend. */
+function DoNotDisturbSection({ rules, save }: NotificationRulesProps) {
+  const quietHours = rules.quiet_start !== null && rules.quiet_end !== null;
+  const timeInput =
+    "w-24 bg-bg-tertiary text-text-primary text-sm px-2 py-1 rounded-md border border-border-primary focus:border-accent outline-none";
+
+  return (
+    <Section title="Do Not Disturb">
+      <ToggleRow
+        label="Do Not Disturb"
+        description="Only VIP senders notify"
+        checked={rules.dnd}
+        onToggle={() => save({ ...rules, dnd: !rules.dnd })}
+      />
+      <ToggleRow
+        label="Quiet hours"
+        description="Turn Do Not Disturb on every day between these times"
+        checked={quietHours}
+        onToggle={() =>
+          save(
+            quietHours
+              ? { ...rules, quiet_start: null, quiet_end: null }
+              : { ...rules, quiet_start: 22 * 60, quiet_end: 7 * 60 },
+          )
+        }
+      />
+      {quietHours && (
+        <SettingRow label="From / until">
+          <div className="flex items-center gap-2">
+            <input
+              type="time"
+              value={formatMinutes(rules.quiet_start ?? 0)}
+              onChange={(e) => {
+                const minutes = parseMinutes(e.target.value);
+                if (minutes !== null) save({ ...rules, quiet_start: minutes });
+              }}
+              className={timeInput}
+            />
+            <input
+              type="time"
+              value={formatMinutes(rules.quiet_end ?? 0)}
+              onChange={(e) => {
+                const minutes = parseMinutes(e.target.value);
+                if (minutes !== null) save({ ...rules, quiet_end: minutes });
+              }}
+              className={timeInput}
+            />
+          </div>
+        </SettingRow>
+      )}
+      <SettingRow label="Other senders">
+        <select
+          value={String(rules.digest_minutes ?? 0)}
+          onChange={(e) => {
+            const minutes = Number(e.target.value);
+            save({ ...rules, digest_minutes: minutes > 0 ? minutes : null });
+          }}
+          className="w-48 bg-bg-tertiary text-text-primary text-sm px-3 py-1.5 rounded-md border border-border-primary focus:border-accent outline-none"
+        >
+          <option value="0">Notify as mail arrives</option>
+          <option value="15">Digest every 15 minutes</option>
+          <option value="30">Digest every 30 minutes</option>
+          <option value="60">Digest every hour</option>
+          <option value="240">Digest every 4 hours</option>
+        </select>
+      </SettingRow>
+      <p className="text-xs text-text-tertiary -mt-1">
+        Digests sum up mail from non-VIP senders in one notification and wait until Do Not Disturb ends
+      </p>
+    </Section>
+  );
+}
+
+/** Sounds for VIP and other senders and per account, played by the backend. */
+function NotificationSoundsSection({ rules, save }: NotificationRulesProps) {
+  const accounts = useAccountStore((s) => s.accounts);
+  const [options, setOptions] = useState<SoundOption[]>([]);
+  const [error, setError] = useState<string | null>(null);
+
+  const loadOptions = useCallback(() => {
+    listNotificationSounds()
+      .then(setOptions)
+      .catch((err) => console.error("Failed to list notification sounds:", err));
+  }, []);
+
+  useEffect(() => {
+    loadOptions();
+  }, [loadOptions]);
+
+  const preview = (sound: NotificationSound) => {
+    setError(null);
+    previewNotificationSound(sound).catch((err) => setError(String(err)));
+  };
+
+  const handleAdd = async () => {
+    const { open } = await import("@tauri-apps/plugin-dialog");
+    const selected = await open({
+      multiple: false,
+      directory: false,
+      filters: [{ name: "Sounds", extensions: SOUND_FILE_EXTENSIONS }],
+    });
+    if (typeof selected !== "string") return;
+    setError(null);
+    try {
+      const added = await addNotificationSound(selected);
+      loadOptions();
+      preview(added.sound);
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const handleRemove = async (file: string) => {
+    setError(null);
+    try {
+      await removeNotificationSound(file);
+      loadOptions();
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const setAccountSound = (accountId: string, sound: NotificationSound | null) => {
+    const { [accountId]: _, ...rest } = rules.account_sounds;
+    save({ ...rules, account_sounds: sound ? { ...rest, [accountId]: sound } : rest });
+  };
+
+  const custom = options.filter((o) => o.sound.kind === "custom");
+
+  return (
+    <Section title="Notification Sounds">
+      <SoundPicker
+        label="VIP senders"
+        sound={rules.vip_sound}
+        options={options}
+        onChange={(sound) => sound && save({ ...rules, vip_sound: sound })}
+        onPreview={preview}
+      />
+      <SoundPicker
+        label="Other senders"
+        sound={rules.sound}
+        options={options}
+        onChange={(sound) => sound && save({ ...rules, sound })}
+        onPreview={preview}
+      />
+      {accounts.length > 1 &&
+        accounts.map((account) => (
+          <SoundPicker
+            key={account.id}
+            label={account.email}
+            sound={rules.account_sounds[account.id] ?? null}
+            fallback={rules.sound}
+            options={options}
+            onChange={(sound) => setAccountSound(account.id, sound)}
+            onPreview={preview}
+          />
+        ))}
+      {custom.map((option) => (
+        <div key={soundKey(option.sound)} className="flex items-center justify-between">
+          <span className="text-sm text-text-secondary truncate">{option.label}</span>
+          <button
+            onClick={() => option.sound.kind === "custom" && handleRemove(option.sound.file)}
+            className="text-xs text-accent hover:text-accent-hover transition-colors shrink-0 ml-3"
+          >
+            Remove
+          </button>
+        </div>
+      ))}
+      <button
+        onClick={handleAdd}
+        className="text-xs text-accent hover:text-accent-hover transition-colors"
+      >
+        Add sound...
+      </button>
+      {error && <p className="text-xs text-danger">{error}</p>}
+      <p className="text-xs text-text-tertiary -mt-1">
+        Account sounds apply to senders other than VIPs. Sounds play even when every window is hidden
+      </p>
+    </Section>
+  );
+}
+
+/** A sound select with a preview button; with `fallback`, the sound can also be left unset. */
+function SoundPicker({
+  label,
+  sound,
+  fallback,
+  options,
+  onChange,
+  onPreview,
+}: {
+  label: string;
+  sound: NotificationSound | null;
+  fallback?: NotificationSound;
+  options: SoundOption[];
+  onChange: (sound: NotificationSound | null) => void;
+  onPreview: (sound: NotificationSound) => void;
+}) {
+  const value = sound ? soundKey(sound) : "";
+  const known = !sound || options.some((o) => soundKey(o.sound) === value);
+  const effective = sound ?? fallback;
+
+  return (
+    <SettingRow label={label}>
+      <div className="flex items-center gap-2">
+        <select
+          value={value}
+          onChange={(e) => onChange(e.target.value ? soundFromKey(e.target.value) : null)}
+          className="w-44 bg-bg-tertiary text-text-primary text-sm px-3 py-1.5 rounded-md border border-border-primary focus:border-accent outline-none"
+        >
+          {fallback && <option value="">Same as other senders</option>}
+          {!known && <option value={value}>(Missing sound)</option>}
+          {options.map((option) => (
+            <option key={soundKey(option.sound)} value={soundKey(option.sound)}>
+              {option.label}
+            </option>
+          ))}
+        </select>
+        <button
+          onClick={() => effective && onPreview(effective)}
+          title="Preview"
+          className="p-1.5 text-text-tertiary hover:text-text-primary transition-colors"
+        >
+          <Play size={14} />
+        </button>
+      </div>
+    </SettingRow>
+  );
+}
+
+function SendCheckSettings() {
+  const [policy, setPolicy] = useState<SendPolicy | null>(null);
+  const [domains, setDomains] = useState("");
+
+  useEffect(() => {
+    getSendPolicy()
+      .then((p) => {
+        setPolicy(p);
+        setDomains(p.internal_domains.join(", "));
+      })
+      .catch((err) => console.error("Failed to load send checks:", err));
+  }, []);
+
+  if (!policy) return null;
+
+  const save = async (next: SendPolicy) => {
+    setPolicy(next);
+    try {
+      await setSendPolicy(next);
+    } catch (err) {
+      console.error("Failed to save send checks:", err);
+    }
+  };
+
+  const selectClass =
+    "w-48 bg-bg-tertiary text-text-primary text-sm px-3 py-1.5 rounded-md border border-border-primary focus:border-accent outline-none";
+
+  return (
+    <>
+      <div>
+        <label className="text-sm text-text-secondary">Internal domains</label>
+        <p className="text-xs text-text-tertiary mt-0.5 mb-1.5">
+          Warn when sending to anyone outside these domains. Leave empty to turn off.
+        </p>
+        <input
+          type="text"
+          value={domains}
+          onChange={(e) => setDomains(e.target.value)}
+          onBlur={() => {
+            const parsed = parseDomainList(domains);
+            setDomains(parsed.join(", "));
+            save({ ...policy, internal_domains: parsed });
+          }}
+          placeholder="example.com, example.org"
+          className="w-full px-3 py-1.5 bg-bg-tertiary border border-border-primary rounded text-sm text-text-primary outline-none focus:border-accent"
+        />
+      </div>
+      <SettingRow label="Large Cc list">
+        <select
+          value={policy.max_cc ?? ""}
+          onChange={(e) => save({ ...policy, max_cc: e.target.value ? parseInt(e.target.value, 10) : null })}
+          className={selectClass}
+        >
+          <option value="">Off</option>
+          <option value="5">More than 5 people</option>
+          <option value="10">More than 10 people</option>
+          <option value="20">More than 20 people</option>
+        </select>
+      </SettingRow>
+      <SettingRow label="Reply to an old conversation">
+        <select
+          value={policy.old_thread_days ?? ""}
+          onChange={(e) =>
+            save({ ...policy, old_thread_days: e.target.value ? parseInt(e.target.value, 10) : null })
+          }
+          className={selectClass}
+        >
+          <option value="">Off</option>
+          <option value="30">Quiet for 30 days</option>
+          <option value="90">Quiet for 3 months</option>
+          <option value="365">Quiet for a year</option>
+        </select>
+      </SettingRow>
+      <ToggleRow
+        label="Empty subject"
+        checked={policy.warn_empty_subject}
+        onToggle={() => save({ ...policy, warn_empty_subject: !policy.warn_empty_subject })}
+      />
+    </>
+  );
+}
+
+function LocalApiControls() {
+  const [status, setStatus] = useState<LocalApiStatus | null>(null);
+  const [port, setPort] = useState("");
+  const [saveError, setSaveError] = useState<string | null>(null);
+  const [copied, setCopied] = useState(false);
+
+  const apply = (next: LocalApiStatus) => {
+    setStatus(next);
+    setPort(String(next.port));
+  };
+
+  useEffect(() => {
+    getLocalApi()
+      .then(apply)
+      .catch((err) => console.error("Failed to load local API settings:", err));
+  }, []);
+
+  if (!status) return null;
+
+  const run = async (action: () => Promise<LocalApiStatus>) => {
+    setSaveError(null);
+    try {
+      apply(await action());
+    } catch (err) {
+      setSaveError(String(err));
+      // The settings are saved even when the server fails to start
+      getLocalApi().then(apply).catch(() => {});
+    }
+  };
+
+  const error = saveError ?? status.error;
+
+  return (
+    <>
+      <ToggleRow
+        label="Enable local API"
+        description="Lets launchers and scripts on this computer read unread counts, search mail and open the composer"
+        checked={status.enabled}
+        onToggle={() => run(() => setLocalApi({ enabled: !status.enabled, port: status.port }))}
+      />
+      {status.enabled && (
+        <>
+          <SettingRow label="Port">
+            <input
+              type="number"
+              min={1024}
+              max={65535}
+              value={port}
+              onChange={(e) => setPort(e.target.value)}
+              onBlur={() => {
+                const parsed = parseInt(port, 10);
+                if (Number.isNaN(parsed) || parsed === status.port) {
+                  setPort(String(status.port));
+                  return;
+                }
+                run(() => setLocalApi({ enabled: true, port: parsed }));
+              }}
+              className="w-24 px-3 py-1.5 bg-bg-tertiary border border-border-primary rounded text-sm text-text-primary outline-none focus:border-accent"
+            />
+          </SettingRow>
+          {status.token && (
+            <div>
+              <label className="text-sm text-text-secondary">Token</label>
+              <p className="text-xs text-text-tertiary mt-0.5 mb-1.5">
+                Send as <code>Authorization: Bearer &lt;token&gt;</code> to http://127.0.0.1:{status.port}/v1/
+              </p>
+              <div className="flex items-center gap-2">
+                <input
+                  type="text"
+                  readOnly
+                  value={status.token}
+                  className="flex-1 px-3 py-1.5 bg-bg-tertiary border border-border-primary rounded text-xs font-mono text-text-primary outline-none"
+                />
+                <Button
+                  variant="secondary"
+                  size="md"
+                  onClick={async () => {
+                    await navigator.clipboard.writeText(status.token ?? "");
+                    setCopied(true);
+                    setTimeout(() => setCopied(false), 2000);
+                  }}
+                >
+                  {copied ? "Copied" : "Copy"}
+                </Button>
+                <Button variant="secondary" size="md" onClick={() => run(regenerateLocalApiToken)}>
+                  Regenerate
+                </Button>
+              </div>
+            </div>
+          )}
+          {error && <p className="text-xs text-danger">{error}</p>}
+        </>
+      )}
+    </>
+  );
+}
+
 const DAY_NAMES = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
 
 function BundleSettings() {
@@ -2303,11 +3630,13 @@ function ToggleRow({
   description,
   checked,
   onToggle,
+  disabled,
 }: {
   label: string;
   description?: string;
   checked: boolean;
   onToggle: () => void;
+  disabled?: boolean;
 }) {
   return (
     <div className="flex items-center justify-between">
@@ -2319,7 +3648,8 @@ function ToggleRow({
       </div>
       <button
         onClick={onToggle}
-        className={`w-10 h-5 rounded-full transition-colors relative shrink-0 ml-4 ${
+        disabled={disabled}
+        className={`w-10 h-5 rounded-full transition-colors relative shrink-0 ml-4 disabled:opacity-50 ${
           checked ? "bg-accent" : "bg-bg-tertiary"
         }`}
       >
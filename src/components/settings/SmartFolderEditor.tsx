@@ -7,12 +7,14 @@ import {
   updateSmartFolder,
   deleteSmartFolder,
   type DbSmartFolder,
+  type SmartFolderCountMode,
 } from "@/services/db/smartFolders";
 import { useSmartFolderStore } from "@/stores/smartFolderStore";
 
 export function SmartFolderEditor() {
   const activeAccountId = useAccountStore((s) => s.activeAccountId);
   const reloadStore = useSmartFolderStore((s) => s.loadFolders);
+  const refreshCounts = useSmartFolderStore((s) => s.refreshCounts);
   const [folders, setFolders] = useState<DbSmartFolder[]>([]);
   const [editingId, setEditingId] = useState<string | null>(null);
   const [showForm, setShowForm] = useState(false);
@@ -22,6 +24,7 @@ export function SmartFolderEditor() {
   const [query, setQuery] = useState("");
   const [icon, setIcon] = useState("Search");
   const [color, setColor] = useState("");
+  const [countMode, setCountMode] = useState<SmartFolderCountMode>("unread");
 
   const loadFolders = useCallback(async () => {
     const f = await getSmartFolders(activeAccountId ?? undefined);
@@ -38,6 +41,7 @@ export function SmartFolderEditor() {
     setQuery("");
     setIcon("Search");
     setColor("");
+    setCountMode("unread");
     setEditingId(null);
     setShowForm(false);
   }, []);
@@ -51,6 +55,7 @@ export function SmartFolderEditor() {
         query: query.trim(),
         icon: icon.trim() || "Search",
         color: color.trim() || undefined,
+        countMode,
       });
     } else {
       await insertSmartFolder({
@@ -59,13 +64,15 @@ export function SmartFolderEditor() {
         accountId: activeAccountId ?? undefined,
         icon: icon.trim() || "Search",
         color: color.trim() || undefined,
+        countMode,
       });
     }
 
     resetForm();
     await loadFolders();
     await reloadStore(activeAccountId ?? undefined);
-  }, [activeAccountId, name, query, icon, color, editingId, resetForm, loadFolders, reloadStore]);
+    if (activeAccountId) await refreshCounts(activeAccountId);
+  }, [activeAccountId, name, query, icon, color, countMode, editingId, resetForm, loadFolders, reloadStore, refreshCounts]);
 
   const handleEdit = useCallback((folder: DbSmartFolder) => {
     setEditingId(folder.id);
@@ -73,6 +80,7 @@ export function SmartFolderEditor() {
     setQuery(folder.query);
     setIcon(folder.icon);
     setColor(folder.color ?? "");
+    setCountMode(folder.count_mode ?? "unread");
     setShowForm(true);
   }, []);
 
@@ -169,6 +177,20 @@ export function SmartFolderEditor() {
               />
             </div>
           </div>
+          <div>
+            <label className="text-xs text-text-secondary block mb-1">
+              Sidebar badge
+            </label>
+            <select
+              value={countMode}
+              onChange={(e) => setCountMode(e.target.value as SmartFolderCountMode)}
+              className="w-full px-3 py-1 bg-bg-tertiary border border-border-primary rounded text-xs text-text-primary outline-none focus:border-accent"
+            >
+              <option value="unread">Unread messages</option>
+              <option value="total">All matching conversations</option>
+              <option value="none">No badge</option>
+            </select>
+          </div>
 
           <div className="flex items-center gap-2">
             <button
@@ -0,0 +1,201 @@
+import { useState, useEffect, useCallback } from "react";
+import { Trash2, Pencil } from "lucide-react";
+import { useAccountStore } from "@/stores/accountStore";
+import {
+  getSnippetsForAccount,
+  insertSnippet,
+  updateSnippet,
+  deleteSnippet,
+  type DbSnippet,
+} from "@/services/db/snippets";
+import { SNIPPET_VARIABLES } from "@/services/composer/snippets";
+import { TEMPLATE_VARIABLES } from "@/utils/templateVariables";
+
+const inputClass =
+  "w-full px-3 py-1.5 bg-bg-tertiary border border-border-primary rounded text-sm text-text-primary outline-none focus:border-accent";
+
+/**
+ * Plain-text snippets that replace an abbreviation (e.g. ";addr") as it's
+ * typed in the composer.
+ */
+export function SnippetEditor() {
+  const activeAccountId = useAccountStore((s) => s.activeAccountId);
+  const [snippets, setSnippets] = useState<DbSnippet[]>([]);
+  const [editingId, setEditingId] = useState<string | null>(null);
+  const [abbreviation, setAbbreviation] = useState("");
+  const [body, setBody] = useState("");
+  const [description, setDescription] = useState("");
+  const [accountOnly, setAccountOnly] = useState(false);
+  const [showForm, setShowForm] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const loadSnippets = useCallback(async () => {
+    if (!activeAccountId) return;
+    setSnippets(await getSnippetsForAccount(activeAccountId));
+  }, [activeAccountId]);
+
+  useEffect(() => {
+    loadSnippets();
+  }, [loadSnippets]);
+
+  const resetForm = useCallback(() => {
+    setAbbreviation("");
+    setBody("");
+    setDescription("");
+    setAccountOnly(false);
+    setEditingId(null);
+    setShowForm(false);
+    setError(null);
+  }, []);
+
+  const handleSave = useCallback(async () => {
+    if (!activeAccountId || !abbreviation.trim() || !body) return;
+    try {
+      if (editingId) {
+        await updateSnippet(editingId, {
+          abbreviation,
+          body,
+          description: description.trim() || null,
+        });
+      } else {
+        await insertSnippet({
+          accountId: accountOnly ? activeAccountId : null,
+          abbreviation,
+          body,
+          description: description.trim() || null,
+        });
+      }
+    } catch (err) {
+      console.error("Failed to save snippet:", err);
+      setError(`A snippet for "${abbreviation.trim()}" already exists`);
+      return;
+    }
+    resetForm();
+    await loadSnippets();
+  }, [activeAccountId, abbreviation, body, description, accountOnly, editingId, resetForm, loadSnippets]);
+
+  const handleEdit = useCallback((snippet: DbSnippet) => {
+    setEditingId(snippet.id);
+    setAbbreviation(snippet.abbreviation);
+    setBody(snippet.body);
+    setDescription(snippet.description ?? "");
+    setAccountOnly(snippet.account_id !== null);
+    setShowForm(true);
+    setError(null);
+  }, []);
+
+  const handleDelete = useCallback(async (id: string) => {
+    await deleteSnippet(id);
+    if (editingId === id) resetForm();
+    await loadSnippets();
+  }, [editingId, resetForm, loadSnippets]);
+
+  return (
+    <div className="space-y-3">
+      {snippets.map((snippet) => (
+        <div
+          key={snippet.id}
+          className="flex items-center justify-between py-2 px-3 bg-bg-secondary rounded-md"
+        >
+          <div className="flex-1 min-w-0">
+            <div className="text-sm font-medium text-text-primary flex items-center gap-2">
+              <kbd className="text-[0.625rem] bg-bg-tertiary text-text-tertiary px-1.5 py-0.5 rounded">
+                {snippet.abbreviation}
+              </kbd>
+              {snippet.description}
+            </div>
+            <div className="text-xs text-text-tertiary truncate">{snippet.body}</div>
+          </div>
+          <div className="flex items-center gap-1">
+            <button
+              onClick={() => handleEdit(snippet)}
+              className="p-1 text-text-tertiary hover:text-text-primary"
+            >
+              <Pencil size={13} />
+            </button>
+            <button
+              onClick={() => handleDelete(snippet.id)}
+              className="p-1 text-text-tertiary hover:text-danger"
+            >
+              <Trash2 size={13} />
+            </button>
+          </div>
+        </div>
+      ))}
+
+      {showForm ? (
+        <div className="border border-border-primary rounded-md p-3 space-y-2">
+          <div className="flex gap-2">
+            <input
+              type="text"
+              value={abbreviation}
+              onChange={(e) => setAbbreviation(e.target.value)}
+              placeholder="Abbreviation, e.g. ;addr"
+              className={`${inputClass} w-40 shrink-0`}
+            />
+            <input
+              type="text"
+              value={description}
+              onChange={(e) => setDescription(e.target.value)}
+              placeholder="Description (optional)"
+              className={inputClass}
+            />
+          </div>
+          <textarea
+            value={body}
+            onChange={(e) => setBody(e.target.value)}
+            placeholder="Text to insert"
+            rows={4}
+            className={`${inputClass} resize-y font-mono text-xs`}
+          />
+          <div className="flex flex-wrap gap-1">
+            {[...TEMPLATE_VARIABLES, ...SNIPPET_VARIABLES].map((v) => (
+              <button
+                key={v.key}
+                type="button"
+                title={v.desc}
+                onClick={() => setBody((b) => b + v.key)}
+                className="text-[0.625rem] text-accent hover:text-accent-hover bg-bg-tertiary px-1.5 py-0.5 rounded"
+              >
+                {v.key}
+              </button>
+            ))}
+          </div>
+          {!editingId && (
+            <label className="flex items-center gap-2 text-xs text-text-secondary">
+              <input
+                type="checkbox"
+                checked={accountOnly}
+                onChange={(e) => setAccountOnly(e.target.checked)}
+              />
+              Only for this account
+            </label>
+          )}
+          {error && <p className="text-xs text-danger">{error}</p>}
+          <div className="flex items-center gap-2">
+            <button
+              onClick={handleSave}
+              disabled={!abbreviation.trim() || !body}
+              className="px-3 py-1.5 text-xs font-medium text-white bg-accent hover:bg-accent-hover rounded-md transition-colors disabled:opacity-50"
+            >
+              {editingId ? "Update" : "Save"}
+            </button>
+            <button
+              onClick={resetForm}
+              className="px-3 py-1.5 text-xs text-text-secondary hover:text-text-primary rounded-md transition-colors"
+            >
+              Cancel
+            </button>
+          </div>
+        </div>
+      ) : (
+        <button
+          onClick={() => setShowForm(true)}
+          className="text-xs text-accent hover:text-accent-hover"
+        >
+          + Add snippet
+        </button>
+      )}
+    </div>
+  );
+}
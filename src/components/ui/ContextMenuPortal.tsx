@@ -11,6 +11,10 @@ import { deleteThread as deleteThreadFromDb, pinThread as pinThreadDb, unpinThre
 import { deleteDraftsForThread } from "@/services/gmail/draftDeletion";
 import { getGmailClient } from "@/services/gmail/tokenManager";
 import { getMessagesForThread } from "@/services/db/messages";
+import { getReplyRecipients } from "@/services/email/replyRecipients";
+import { setPinned } from "@/services/messagePins";
+import { BUILTIN_CATEGORIES, categoriesFromKeywords, setMessageCategory } from "@/services/colorCategories";
+import { TEAM_STATUSES, setTeamAssignee, setTeamStatus, teamTagsFromKeywords } from "@/services/teamTags";
 import { snoozeThread } from "@/services/snooze/snoozeManager";
 import { getEnabledQuickStepsForAccount, type DbQuickStep } from "@/services/db/quickSteps";
 import { executeQuickStep } from "@/services/quickSteps/executor";
@@ -37,6 +41,10 @@ import {
   Zap,
   Code,
   RefreshCw,
+  Palette,
+  StickyNote,
+  UserCheck,
+  CircleDot,
 } from "lucide-react";
 import { triggerSync } from "@/services/gmail/syncManager";
 import { useUIStore } from "@/stores/uiStore";
@@ -206,6 +214,7 @@ function ThreadMenu({
   const threads = useThreadStore((s) => s.threads);
   const selectedThreadIds = useThreadStore((s) => s.selectedThreadIds);
   const activeAccountId = useAccountStore((s) => s.activeAccountId);
+  const activeAccountEmail = useAccountStore((s) => s.accounts.find((a) => a.id === s.activeAccountId)?.email);
   const activeLabel = getActiveLabel();
   const labels = useLabelStore((s) => s.labels);
   const openComposer = useComposerStore((s) => s.openComposer);
@@ -244,10 +253,10 @@ function ThreadMenu({
     const messages = await getMessagesForThread(activeAccountId, thread.id);
     const lastMessage = messages[messages.length - 1];
     if (!lastMessage) return;
-    const replyTo = lastMessage.reply_to ?? lastMessage.from_address;
+    const { to } = await getReplyRecipients(lastMessage, "reply", { id: activeAccountId, email: activeAccountEmail });
     openComposer({
       mode: "reply",
-      to: replyTo ? [replyTo] : [],
+      to,
       subject: `Re: ${lastMessage.subject ?? ""}`,
       bodyHtml: buildQuote(lastMessage),
       threadId: lastMessage.thread_id,
@@ -259,20 +268,11 @@ function ThreadMenu({
     const messages = await getMessagesForThread(activeAccountId, thread.id);
     const lastMessage = messages[messages.length - 1];
     if (!lastMessage) return;
-    const replyTo = lastMessage.reply_to ?? lastMessage.from_address;
-    const allRecipients = new Set<string>();
-    if (replyTo) allRecipients.add(replyTo);
-    if (lastMessage.to_addresses) {
-      lastMessage.to_addresses.split(",").forEach((a) => allRecipients.add(a.trim()));
-    }
-    const ccList: string[] = [];
-    if (lastMessage.cc_addresses) {
-      lastMessage.cc_addresses.split(",").forEach((a) => ccList.push(a.trim()));
-    }
+    const { to, cc } = await getReplyRecipients(lastMessage, "replyAll", { id: activeAccountId, email: activeAccountEmail });
     openComposer({
       mode: "replyAll",
-      to: Array.from(allRecipients),
-      cc: ccList,
+      to,
+      cc,
       subject: `Re: ${lastMessage.subject ?? ""}`,
       bodyHtml: buildQuote(lastMessage),
       threadId: lastMessage.thread_id,
@@ -586,6 +586,85 @@ function ThreadMenu({
   return <ContextMenu items={items} position={position} onClose={onClose} />;
 }
 
+/** "Categorize" submenu: the built-in color categories plus custom ones the
+ *  message already has, each toggling its IMAP keyword. */
+function categorizeItem(accountId: string, messageId: string, keywords: string | null): ContextMenuItem {
+  const current = categoriesFromKeywords(keywords);
+  const custom = current.filter((c) => !BUILTIN_CATEGORIES.some((b) => b.keyword === c.keyword));
+  return {
+    id: "categorize",
+    label: "Categorize",
+    icon: Palette,
+    children: [...BUILTIN_CATEGORIES, ...custom].map((category) => {
+      const checked = current.some((c) => c.keyword === category.keyword);
+      return {
+        id: `category-${category.keyword}`,
+        label: category.name,
+        checked,
+        action: () => {
+          setMessageCategory(accountId, messageId, category.keyword, !checked).catch((err) =>
+            console.error("Failed to set category:", err),
+          );
+        },
+      };
+    }),
+  };
+}
+
+/** "Assign To" and "Status" submenus for a message in the account's shared
+ *  team folder; the choices replace the message's team tags on the server. */
+function teamItems(
+  accountId: string,
+  messageId: string,
+  keywords: string | null,
+  members: string[],
+): ContextMenuItem[] {
+  const { assignee, status } = teamTagsFromKeywords(keywords);
+  const onError = (err: unknown) => console.error("Failed to set team tag:", err);
+  // Keep an assignee set elsewhere that's no longer in the member list
+  const choices = assignee && !members.some((m) => m.toLowerCase() === assignee.toLowerCase())
+    ? [...members, assignee]
+    : members;
+  return [
+    {
+      id: "team-assign",
+      label: "Assign To",
+      icon: UserCheck,
+      children: [
+        ...choices.map((member) => ({
+          id: `team-assign-${member}`,
+          label: member,
+          checked: assignee?.toLowerCase() === member.toLowerCase(),
+          action: () => {
+            setTeamAssignee(accountId, messageId, member).catch(onError);
+          },
+        })),
+        {
+          id: "team-unassign",
+          label: "Unassigned",
+          checked: assignee === null,
+          action: () => {
+            setTeamAssignee(accountId, messageId, null).catch(onError);
+          },
+        },
+      ],
+    },
+    {
+      id: "team-status",
+      label: "Status",
+      icon: CircleDot,
+      children: TEAM_STATUSES.map((option) => ({
+        id: `team-status-${option.value}`,
+        label: option.label,
+        checked: status === option.value,
+        action: () => {
+          setTeamStatus(accountId, messageId, status === option.value ? null : option.value).catch(onError);
+        },
+      })),
+    },
+  ];
+}
+
 function MessageMenu({
   position,
   data,
@@ -596,6 +675,7 @@ function MessageMenu({
   onClose: () => void;
 }) {
   const openComposer = useComposerStore((s) => s.openComposer);
+  const accounts = useAccountStore((s) => s.accounts);
 
   const messageId = data["messageId"] as string;
   const threadId = data["threadId"] as string;
@@ -605,18 +685,28 @@ function MessageMenu({
   const replyTo = data["replyTo"] as string | null;
   const toAddresses = data["toAddresses"] as string | null;
   const ccAddresses = data["ccAddresses"] as string | null;
+  const isFromMe = data["isFromMe"] as number | null;
+  const mailFollowupTo = data["mailFollowupTo"] as string | null;
   const subject = data["subject"] as string | null;
   const date = data["date"] as string | number;
   const bodyHtml = data["bodyHtml"] as string | null;
   const bodyText = data["bodyText"] as string | null;
+  const isPinned = data["isPinned"] === true;
+  const keywords = data["keywords"] as string | null;
+  const isImap = data["isImap"] === true;
+  const messageIdHeader = data["messageIdHeader"] as string | null;
+  const teamMembers = data["teamMembers"] as string[] | null;
 
   const msg = { from_name: fromName, from_address: fromAddress, date, body_html: bodyHtml, body_text: bodyText, subject, to_addresses: toAddresses };
 
-  const handleReply = () => {
-    const replyAddr = replyTo ?? fromAddress;
+  const replySource = { from_address: fromAddress, reply_to: replyTo, to_addresses: toAddresses, cc_addresses: ccAddresses, is_from_me: isFromMe, mail_followup_to: mailFollowupTo };
+  const replyAccount = { id: accountId ?? "", email: accounts.find((a) => a.id === accountId)?.email };
+
+  const handleReply = async () => {
+    const { to } = await getReplyRecipients(replySource, "reply", replyAccount);
     openComposer({
       mode: "reply",
-      to: replyAddr ? [replyAddr] : [],
+      to,
       subject: `Re: ${subject ?? ""}`,
       bodyHtml: buildQuote(msg),
       threadId,
@@ -624,21 +714,12 @@ function MessageMenu({
     });
   };
 
-  const handleReplyAll = () => {
-    const replyAddr = replyTo ?? fromAddress;
-    const allRecipients = new Set<string>();
-    if (replyAddr) allRecipients.add(replyAddr);
-    if (toAddresses) {
-      toAddresses.split(",").forEach((a) => allRecipients.add(a.trim()));
-    }
-    const ccList: string[] = [];
-    if (ccAddresses) {
-      ccAddresses.split(",").forEach((a) => ccList.push(a.trim()));
-    }
+  const handleReplyAll = async () => {
+    const { to, cc } = await getReplyRecipients(replySource, "replyAll", replyAccount);
     openComposer({
       mode: "replyAll",
-      to: Array.from(allRecipients),
-      cc: ccList,
+      to,
+      cc,
       subject: `Re: ${subject ?? ""}`,
       bodyHtml: buildQuote(msg),
       threadId,
@@ -697,6 +778,26 @@ function MessageMenu({
     },
     ...(accountId
       ? [
+          {
+            id: "pin-message",
+            label: isPinned ? "Unpin Message" : "Pin Message",
+            icon: Pin,
+            action: () => setPinned(accountId, messageId, !isPinned),
+          },
+          ...(isImap ? [categorizeItem(accountId, messageId, keywords)] : []),
+          ...(isImap && teamMembers ? teamItems(accountId, messageId, keywords, teamMembers) : []),
+          {
+            id: "add-note",
+            label: "Add Note",
+            icon: StickyNote,
+            action: () => {
+              window.dispatchEvent(
+                new CustomEvent("velo-add-note", {
+                  detail: { threadId, messageIdHeader },
+                }),
+              );
+            },
+          },
           { id: "sep-2", label: "", separator: true },
           {
             id: "view-source",
@@ -0,0 +1,15 @@
+import { useUIStore } from "@/stores/uiStore";
+import { Lock } from "lucide-react";
+
+export function ReadOnlyBanner() {
+  const isReadOnly = useUIStore((s) => s.isReadOnly);
+
+  if (!isReadOnly) return null;
+
+  return (
+    <div className="fixed bottom-0 left-0 right-0 z-50 flex items-center justify-center gap-2 bg-accent/90 text-white text-xs px-4 py-1.5 backdrop-blur-sm">
+      <Lock size={14} />
+      <span>Read-only mode — sending, moving, deleting and flagging are disabled</span>
+    </div>
+  );
+}
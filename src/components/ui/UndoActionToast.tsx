@@ -0,0 +1,57 @@
+import { useRef } from "react";
+import { CSSTransition } from "react-transition-group";
+import { useStagedOperationStore } from "@/stores/stagedOperationStore";
+import { undoOperation } from "@/services/stagedOperations";
+
+/**
+ * Undo prompt for the most recent delete still inside its undo window, or
+ * the error of one that failed once it ran.
+ */
+export function UndoActionToast() {
+  const latest = useStagedOperationStore((s) => s.operations[s.operations.length - 1]);
+  const error = useStagedOperationStore((s) => s.error);
+  const setError = useStagedOperationStore((s) => s.setError);
+  const toastRef = useRef<HTMLDivElement>(null);
+  const remainingMs = latest ? Math.max(latest.expiresAt - Date.now(), 0) : 0;
+
+  const handleUndo = (id: string) => {
+    undoOperation(id).catch((err) => setError(`Undo failed: ${String(err)}`));
+  };
+
+  return (
+    <CSSTransition nodeRef={toastRef} in={!!latest || !!error} timeout={200} classNames="toast" unmountOnExit>
+      <div ref={toastRef} className="fixed bottom-4 left-1/2 -translate-x-1/2 z-50 bg-text-primary text-bg-primary rounded-lg shadow-lg overflow-hidden">
+        {latest ? (
+          <>
+            <div className="px-4 py-2.5 flex items-center gap-3">
+              <span className="text-sm">{latest.label}</span>
+              <button
+                onClick={() => handleUndo(latest.id)}
+                className="text-sm font-medium text-accent hover:text-accent-hover underline"
+              >
+                Undo
+              </button>
+            </div>
+            <div className="h-0.5 bg-white/20">
+              <div
+                key={latest.id}
+                className="h-full bg-accent rounded-full"
+                style={{ animation: `countdownBar ${remainingMs / 1000}s linear forwards` }}
+              />
+            </div>
+          </>
+        ) : error && (
+          <div className="px-4 py-2.5 flex items-center gap-3 max-w-md">
+            <span className="text-sm text-danger">{error}</span>
+            <button
+              onClick={() => setError(null)}
+              className="text-sm font-medium text-accent hover:text-accent-hover underline shrink-0"
+            >
+              Dismiss
+            </button>
+          </div>
+        )}
+      </div>
+    </CSSTransition>
+  );
+}
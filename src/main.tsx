@@ -7,7 +7,8 @@ import ComposerWindow from "./ComposerWindow";
 import "./styles/globals.css";
 
 const params = new URLSearchParams(window.location.search);
-const isThreadWindow = params.has("thread") && params.has("account");
+const isThreadWindow =
+  params.has("account") && (params.has("thread") || (params.has("message") && params.has("folder")));
 const isComposerWindow = params.has("compose");
 
 function Root() {
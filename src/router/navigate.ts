@@ -2,7 +2,7 @@ import { router } from "./index";
 
 /** Known system labels that map to /mail/$label */
 const SYSTEM_LABELS = new Set([
-  "inbox", "starred", "snoozed", "sent", "drafts", "trash", "spam", "all",
+  "inbox", "starred", "snoozed", "sent", "drafts", "trash", "spam", "all", "feeds",
 ]);
 
 /**
@@ -36,6 +36,15 @@ function makeMessage(overrides: Partial<DbMessage> = {}): DbMessage {
     in_reply_to_header: null,
     imap_uid: null,
     imap_folder: null,
+    parse_failed: 0,
+    delivered_to: null,
+    list_id: null,
+    list_post: null,
+    list_archive: null,
+    is_from_me: 0,
+    mail_followup_to: null,
+    is_pinned: 0,
+    keywords: null,
     ...overrides,
   };
 }
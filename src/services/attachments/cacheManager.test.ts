@@ -17,13 +17,19 @@ vi.mock("@/services/db/settings", () => ({
   getSetting: vi.fn(() => Promise.resolve("500")),
 }));
 
+vi.mock("@/services/profiles", () => ({
+  profilePath: vi.fn((path: string) => Promise.resolve(path)),
+}));
+
 import {
   cacheAttachment,
   loadCachedAttachment,
   getCacheSize,
   evictOldestCached,
+  evictCachedBefore,
   clearAllCache,
 } from "./cacheManager";
+import { profilePath } from "@/services/profiles";
 
 describe("cacheManager", () => {
   beforeEach(() => {
@@ -57,6 +63,20 @@ describe("cacheManager", () => {
       );
     });
 
+    it("caches inside the current profile's directory", async () => {
+      vi.mocked(profilePath).mockImplementationOnce((path) =>
+        Promise.resolve(`profiles/work/${path}`),
+      );
+      const data = new Uint8Array([10, 20, 30]);
+      const result = await cacheAttachment("att-1", data);
+
+      expect(tauriFs.mock.mkdir).toHaveBeenCalledWith("profiles/work/attachment_cache", {
+        baseDir: 26,
+        recursive: true,
+      });
+      expect(result).toMatch(/^profiles\/work\/attachment_cache\//);
+    });
+
     it("returns relative path", async () => {
       const result = await cacheAttachment("att-1", new Uint8Array([1]));
       expect(result).toMatch(/^attachment_cache\//);
@@ -129,6 +149,23 @@ describe("cacheManager", () => {
     });
   });
 
+  describe("evictCachedBefore", () => {
+    it("removes attachments of messages older than the cutoff", async () => {
+      mockSelect.mockResolvedValueOnce([
+        { id: "att-old", local_path: "attachment_cache/old", cache_size: 2000 },
+      ]);
+
+      expect(await evictCachedBefore(1_000)).toBe(1);
+
+      expect(mockSelect).toHaveBeenCalledWith(expect.stringContaining("m.date < $1"), [1_000]);
+      expect(tauriFs.mock.remove).toHaveBeenCalledWith("attachment_cache/old", { baseDir: 26 });
+      expect(mockExecute).toHaveBeenCalledWith(
+        expect.stringContaining("UPDATE attachments SET local_path = NULL"),
+        ["att-old"],
+      );
+    });
+  });
+
   describe("clearAllCache", () => {
     it("removes cache dir with baseDir option", async () => {
       await clearAllCache();
@@ -1,6 +1,8 @@
 import { getDb } from "@/services/db/connection";
 import { getSetting } from "@/services/db/settings";
+import { profilePath } from "@/services/profiles";
 
+/** Relative to the current profile's directory under AppData. */
 const CACHE_DIR = "attachment_cache";
 
 function hashFileName(id: string): string {
@@ -24,16 +26,17 @@ export async function cacheAttachment(
   try {
     const { mkdir, writeFile: fsWriteFile, BaseDirectory } = await import("@tauri-apps/plugin-fs");
     const baseDir = BaseDirectory.AppData;
+    const cacheDir = await profilePath(CACHE_DIR);
 
     // Ensure cache directory exists
     try {
-      await mkdir(CACHE_DIR, { baseDir, recursive: true });
+      await mkdir(cacheDir, { baseDir, recursive: true });
     } catch {
       // directory may already exist
     }
 
     const { join } = await import("@tauri-apps/api/path");
-    const relPath = await join(CACHE_DIR, hashFileName(attachmentId));
+    const relPath = await join(cacheDir, hashFileName(attachmentId));
     await fsWriteFile(relPath, data, { baseDir });
 
     // Update DB — store relative path under AppData
@@ -81,34 +84,63 @@ export async function evictOldestCached(): Promise<void> {
   let freed = 0;
 
   // Get oldest cached attachments
-  const rows = await db.select<{ id: string; local_path: string; cache_size: number }[]>(
+  const rows = await db.select<CachedRow[]>(
     "SELECT id, local_path, cache_size FROM attachments WHERE cached_at IS NOT NULL ORDER BY cached_at ASC LIMIT 100",
   );
 
   for (const row of rows) {
     if (freed >= excess) break;
+    await uncache(row);
+    freed += row.cache_size;
+  }
+}
 
-    try {
-      const { remove, BaseDirectory } = await import("@tauri-apps/plugin-fs");
-      await remove(row.local_path, { baseDir: BaseDirectory.AppData });
-    } catch {
-      // file may not exist
-    }
+/**
+ * Remove the cached files of attachments on messages dated before `before`
+ * (ms), except starred ones. They're downloaded again when opened.
+ * Returns the number of attachments evicted.
+ */
+export async function evictCachedBefore(before: number): Promise<number> {
+  const db = await getDb();
+  const rows = await db.select<CachedRow[]>(
+    `SELECT a.id, a.local_path, a.cache_size
+     FROM attachments a
+     INNER JOIN messages m ON m.account_id = a.account_id AND m.id = a.message_id
+     WHERE a.cached_at IS NOT NULL AND m.is_starred = 0 AND m.date < $1`,
+    [before],
+  );
+  for (const row of rows) {
+    await uncache(row);
+  }
+  return rows.length;
+}
 
-    await db.execute(
-      "UPDATE attachments SET local_path = NULL, cached_at = NULL, cache_size = NULL WHERE id = $1",
-      [row.id],
-    );
+interface CachedRow {
+  id: string;
+  local_path: string;
+  cache_size: number;
+}
 
-    freed += row.cache_size;
+async function uncache(row: CachedRow): Promise<void> {
+  try {
+    const { remove, BaseDirectory } = await import("@tauri-apps/plugin-fs");
+    await remove(row.local_path, { baseDir: BaseDirectory.AppData });
+  } catch {
+    // file may not exist
   }
+
+  const db = await getDb();
+  await db.execute(
+    "UPDATE attachments SET local_path = NULL, cached_at = NULL, cache_size = NULL WHERE id = $1",
+    [row.id],
+  );
 }
 
 export async function clearAllCache(): Promise<void> {
   try {
     const { remove, BaseDirectory } = await import("@tauri-apps/plugin-fs");
     try {
-      await remove(CACHE_DIR, { baseDir: BaseDirectory.AppData, recursive: true });
+      await remove(await profilePath(CACHE_DIR), { baseDir: BaseDirectory.AppData, recursive: true });
     } catch {
       // directory may not exist
     }
@@ -0,0 +1,27 @@
+import type { DbAttachment } from "@/services/db/attachments";
+import { getEmailProvider } from "@/services/email/providerFactory";
+import { openAttachmentFile } from "./openAttachmentFile";
+
+/**
+ * Open an attachment with the default app, reading it from the cache when
+ * possible and otherwise downloading it through the account's provider.
+ */
+export async function openAttachment(
+  accountId: string,
+  messageId: string,
+  attachment: DbAttachment,
+): Promise<void> {
+  if (attachment.local_path) {
+    await openAttachmentFile(messageId, attachment.filename, {
+      kind: "cached",
+      local_path: attachment.local_path,
+    });
+    return;
+  }
+
+  if (!attachment.gmail_attachment_id) {
+    throw new Error("Attachment has no download id");
+  }
+  const provider = await getEmailProvider(accountId);
+  await provider.openAttachment(messageId, attachment.gmail_attachment_id, attachment.filename);
+}
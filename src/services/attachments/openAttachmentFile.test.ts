@@ -0,0 +1,34 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { invoke } from "@tauri-apps/api/core";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+import { isDangerousAttachmentError, openAttachmentFile } from "./openAttachmentFile";
+
+const mockInvoke = vi.mocked(invoke);
+
+describe("openAttachmentFile", () => {
+  beforeEach(() => {
+    mockInvoke.mockReset();
+  });
+
+  it("passes the source to the backend", async () => {
+    mockInvoke.mockResolvedValue("/tmp/sora-attachments/m1/report.pdf");
+
+    const path = await openAttachmentFile("m1", "report.pdf", { kind: "cached", local_path: "a/b.pdf" });
+
+    expect(path).toBe("/tmp/sora-attachments/m1/report.pdf");
+    expect(mockInvoke).toHaveBeenCalledWith("open_attachment", {
+      messageId: "m1",
+      filename: "report.pdf",
+      source: { kind: "cached", local_path: "a/b.pdf" },
+    });
+  });
+
+  it("recognizes refusals to open programs", () => {
+    expect(isDangerousAttachmentError("DANGEROUS_ATTACHMENT:setup.exe could run code on this computer")).toBe(true);
+    expect(isDangerousAttachmentError(new Error("Failed to open attachment"))).toBe(false);
+  });
+});
@@ -0,0 +1,31 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** Where the backend reads the attachment bytes from. */
+export type OpenAttachmentSource =
+  | { kind: "imap"; account_id: string; folder: string; uid: number; part_id: string }
+  | { kind: "cached"; local_path: string }
+  | { kind: "data"; data: string };
+
+/**
+ * Same prefix as the backend's `DANGEROUS_ATTACHMENT_PREFIX`: the file would
+ * run as a program (e.g. `.exe`, `.js`, `.desktop`), so it is only saved.
+ */
+export const DANGEROUS_ATTACHMENT_PREFIX = "DANGEROUS_ATTACHMENT:";
+
+export function isDangerousAttachmentError(err: unknown): boolean {
+  return String(err).startsWith(DANGEROUS_ATTACHMENT_PREFIX);
+}
+
+/**
+ * Write an attachment to a read-only temp file and open it with the OS
+ * default app. Temp files are removed when the app exits. Attachments that
+ * would run as programs are refused with a `DANGEROUS_ATTACHMENT:` error.
+ * Returns the temp file path.
+ */
+export async function openAttachmentFile(
+  messageId: string,
+  filename: string | null,
+  source: OpenAttachmentSource,
+): Promise<string> {
+  return invoke<string>("open_attachment", { messageId, filename, source });
+}
@@ -0,0 +1,30 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** Rendered previews by cache path (null = not previewable). */
+const previewCache = new Map<string, string | null>();
+
+/**
+ * Get a small PNG data URI preview of a cached image or PDF attachment,
+ * rendered by the backend. A PDF's preview is the largest image embedded in
+ * its first page, not a rendering of the page. Returns null when the file
+ * can't be previewed.
+ */
+export async function getAttachmentPreview(
+  localPath: string,
+  mimeType: string | null,
+): Promise<string | null> {
+  const cached = previewCache.get(localPath);
+  if (cached !== undefined) return cached;
+
+  const preview = await invoke<string | null>("attachment_preview_thumbnail", {
+    localPath,
+    mimeType,
+    maxSize: null,
+  });
+  previewCache.set(localPath, preview);
+  return preview;
+}
+
+export function clearAttachmentPreviewCache(): void {
+  previewCache.clear();
+}
@@ -0,0 +1,147 @@
+import { invoke } from "@tauri-apps/api/core";
+import { STAGED_PART_HEADER } from "@/utils/emailBuilder";
+import { readFileAsBase64 } from "@/utils/fileUtils";
+
+/**
+ * Compose attachments staged in the backend.
+ *
+ * Picked files are registered by path and referenced by id; the MIME builder
+ * emits a placeholder part that the backend fills in at send time, so file
+ * bytes never pass through the webview.
+ */
+
+export interface StagedAttachment {
+  id: string;
+  filename: string;
+  mime_type: string;
+  size: number;
+  /** PNG data URL, only for images. */
+  thumbnail: string | null;
+}
+
+/** A pasted image staged as an inline (`cid:`) part. */
+export interface StagedInlineImage {
+  id: string;
+  content_id: string;
+  cid_url: string;
+  mime_type: string;
+  size: number;
+  /** PNG data URL used to display the image in the editor. */
+  preview: string;
+}
+
+export async function stageAttachment(path: string): Promise<StagedAttachment> {
+  return invoke<StagedAttachment>("attachment_stage", { path });
+}
+
+/**
+ * Stage a message on the IMAP server as a `message/rfc822` attachment (for
+ * forwarding). Only the reference is kept; the content is fetched by the
+ * server (CATENATE/BURL) or downloaded when the message goes out.
+ */
+export async function stageServerMessage(
+  folder: string,
+  uid: number,
+  filename: string,
+  size: number,
+): Promise<StagedAttachment> {
+  return invoke<StagedAttachment>("attachment_stage_server_message", {
+    folder,
+    uid,
+    filename,
+    size,
+  });
+}
+
+export async function unstageAttachment(id: string): Promise<void> {
+  return invoke("attachment_unstage", { id });
+}
+
+/** Before/after sizes for an image passed to `optimizeStagedImages`. */
+export interface ImageOptimizeResult {
+  id: string;
+  filename: string;
+  mime_type: string;
+  original_size: number;
+  /** Same as original_size when the image was left as is. */
+  optimized_size: number;
+}
+
+/**
+ * Resize and recompress staged images in place. Defaults to a 1600px
+ * longest edge at JPEG quality 85; non-images are skipped.
+ */
+export async function optimizeStagedImages(
+  ids: string[],
+  maxEdge?: number,
+  quality?: number,
+): Promise<ImageOptimizeResult[]> {
+  return invoke<ImageOptimizeResult[]>("attachment_optimize_images", {
+    ids,
+    maxEdge: maxEdge ?? null,
+    quality: quality ?? null,
+  });
+}
+
+/**
+ * Bundle staged attachments into one zip (AES-256 when a password is given).
+ * The bundled ids are unstaged and replaced by the returned archive.
+ */
+export async function zipStagedAttachments(
+  ids: string[],
+  archiveName?: string,
+  password?: string,
+): Promise<StagedAttachment> {
+  return invoke<StagedAttachment>("attachment_zip", {
+    ids,
+    archiveName: archiveName ?? null,
+    password: password ?? null,
+  });
+}
+
+/** Stage the image on the system clipboard as an inline part. */
+export async function clipboardImageToAttachment(): Promise<StagedInlineImage> {
+  return invoke<StagedInlineImage>("clipboard_image_to_attachment");
+}
+
+/**
+ * Read a staged attachment as base64 (for content that must be persisted).
+ * Pass the (registered) account id for attachments that are still on the
+ * server. Only content staged in the app can be read back; use
+ * `readPickedFile` for attachments that still come from a picked file.
+ */
+export async function readStagedAttachment(
+  id: string,
+  accountId?: string,
+): Promise<string> {
+  return invoke<string>("attachment_read", {
+    id,
+    accountId: accountId ?? null,
+  });
+}
+
+/**
+ * Read a file the user picked as base64. The file dialog allows the path in
+ * the fs scope, so the fs plugin can read it.
+ */
+export async function readPickedFile(path: string): Promise<string> {
+  const { readFile } = await import("@tauri-apps/plugin-fs");
+  return readFileAsBase64(new File([await readFile(path)], ""));
+}
+
+/**
+ * Check whether a base64url-encoded message contains staged placeholders.
+ */
+export function hasStagedParts(rawBase64Url: string): boolean {
+  const base64 = rawBase64Url.replace(/-/g, "+").replace(/_/g, "/");
+  return atob(base64).includes(`${STAGED_PART_HEADER}: `);
+}
+
+/**
+ * Fill in staged attachment parts for providers that send from the frontend.
+ * Returns the message unchanged when it has no placeholders.
+ */
+export async function resolveStagedParts(rawBase64Url: string): Promise<string> {
+  if (!hasStagedParts(rawBase64Url)) return rawBase64Url;
+  return invoke<string>("attachment_resolve_raw", { rawEmail: rawBase64Url });
+}
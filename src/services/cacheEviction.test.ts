@@ -0,0 +1,42 @@
+import { describe, it, expect, beforeEach, vi } from "vitest";
+
+vi.mock("./db/settings", () => ({
+  getSetting: vi.fn(),
+}));
+vi.mock("./db/messages", () => ({
+  evictMessageBodies: vi.fn(() => Promise.resolve(0)),
+}));
+vi.mock("./attachments/cacheManager", () => ({
+  evictCachedBefore: vi.fn(() => Promise.resolve(0)),
+}));
+
+import { getSetting } from "./db/settings";
+import { evictMessageBodies } from "./db/messages";
+import { evictCachedBefore } from "./attachments/cacheManager";
+import { evictOldContent } from "./cacheEviction";
+
+describe("evictOldContent", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+  });
+
+  it("keeps everything by default", async () => {
+    vi.mocked(getSetting).mockResolvedValue(null);
+
+    await evictOldContent();
+
+    expect(evictMessageBodies).not.toHaveBeenCalled();
+    expect(evictCachedBefore).not.toHaveBeenCalled();
+  });
+
+  it("evicts bodies and attachments older than the window", async () => {
+    vi.mocked(getSetting).mockResolvedValue("30");
+    const now = Date.UTC(2026, 5, 1);
+    const cutoff = Date.UTC(2026, 4, 2);
+
+    await evictOldContent(now);
+
+    expect(evictMessageBodies).toHaveBeenCalledWith(cutoff);
+    expect(evictCachedBefore).toHaveBeenCalledWith(cutoff);
+  });
+});
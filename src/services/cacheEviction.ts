@@ -0,0 +1,41 @@
+import { createBackgroundChecker, type BackgroundChecker } from "./backgroundCheckers";
+import { getSetting } from "./db/settings";
+import { evictMessageBodies } from "./db/messages";
+import { evictCachedBefore } from "./attachments/cacheManager";
+
+/**
+ * Setting: days to keep message bodies and attachments of old messages in
+ * the local cache; "0" keeps them forever. Envelopes, plain text (for
+ * search) and starred messages always stay, and evicted content is
+ * downloaded again when opened.
+ */
+export const BODY_CACHE_DAYS_KEY = "body_cache_days";
+
+const DAY_MS = 24 * 60 * 60 * 1000;
+
+let checker: BackgroundChecker | null = null;
+
+export async function evictOldContent(now: number = Date.now()): Promise<void> {
+  const days = parseInt((await getSetting(BODY_CACHE_DAYS_KEY)) ?? "0", 10);
+  if (!(days > 0)) return;
+
+  const before = now - days * DAY_MS;
+  const bodies = await evictMessageBodies(before);
+  const attachments = await evictCachedBefore(before);
+  if (bodies > 0 || attachments > 0) {
+    console.log(
+      `[cacheEviction] Dropped ${bodies} bodies and ${attachments} attachments older than ${days} days`,
+    );
+  }
+}
+
+export function startCacheEviction(): void {
+  if (checker) return;
+  checker = createBackgroundChecker("CacheEviction", () => evictOldContent(), 6 * 60 * 60 * 1000);
+  checker.start();
+}
+
+export function stopCacheEviction(): void {
+  checker?.stop();
+  checker = null;
+}
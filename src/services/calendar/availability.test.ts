@@ -0,0 +1,170 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+vi.mock("@/services/db/accounts", () => ({
+  getAccount: vi.fn(),
+  getAllAccounts: vi.fn(),
+}));
+
+vi.mock("@/services/db/calendars", () => ({
+  getVisibleCalendars: vi.fn(),
+}));
+
+import { invoke } from "@tauri-apps/api/core";
+import { getAccount, getAllAccounts } from "@/services/db/accounts";
+import { getVisibleCalendars, type DbCalendar } from "@/services/db/calendars";
+import { createMockImapAccount } from "@/test/mocks";
+import {
+  fetchFreeSlots,
+  findCalDavAccount,
+  formatAvailability,
+  isMeetingRelated,
+  suggestSlots,
+} from "./availability";
+
+/** Unix seconds for a local date and time. */
+function at(day: number, hour: number, minute = 0): number {
+  // October 2025: the 13th is a Monday
+  return new Date(2025, 9, day, hour, minute).getTime() / 1000;
+}
+
+const caldavAccount = createMockImapAccount({
+  id: "acc-cal",
+  provider: "caldav",
+  calendar_provider: "caldav",
+  caldav_url: "https://dav.example.com/",
+  caldav_username: "me",
+  caldav_password: "secret",
+});
+
+beforeEach(() => {
+  vi.clearAllMocks();
+});
+
+describe("isMeetingRelated", () => {
+  it("recognizes meeting subjects", () => {
+    expect(isMeetingRelated("Re: Meeting next week?")).toBe(true);
+    expect(isMeetingRelated("Can we schedule a call")).toBe(true);
+    expect(isMeetingRelated("Your availability for an interview")).toBe(true);
+  });
+
+  it("ignores other subjects", () => {
+    expect(isMeetingRelated("Invoice #42")).toBe(false);
+    expect(isMeetingRelated("Recall of product batch")).toBe(false);
+    expect(isMeetingRelated("")).toBe(false);
+  });
+});
+
+describe("suggestSlots", () => {
+  it("keeps the working-hours part of weekday slots", () => {
+    const slots = [
+      // Monday evening to Tuesday 10:30
+      { start: at(13, 18), end: at(14, 10, 30) },
+      // Saturday
+      { start: at(18, 9), end: at(18, 17) },
+      // Too short
+      { start: at(15, 9), end: at(15, 9, 15) },
+    ];
+
+    expect(suggestSlots(slots)).toEqual([{ start: at(14, 9), end: at(14, 10, 30) }]);
+  });
+
+  it("splits long slots per day and limits them", () => {
+    const week = [{ start: at(13, 0), end: at(20, 0) }];
+
+    const suggestions = suggestSlots(week, { limit: 3 });
+
+    expect(suggestions).toEqual([
+      { start: at(13, 9), end: at(13, 17) },
+      { start: at(14, 9), end: at(14, 17) },
+      { start: at(15, 9), end: at(15, 17) },
+    ]);
+  });
+
+  it("offers at most perDay slots a day", () => {
+    const slots = [
+      { start: at(13, 9), end: at(13, 10) },
+      { start: at(13, 11), end: at(13, 12) },
+      { start: at(13, 14), end: at(13, 15) },
+    ];
+
+    expect(suggestSlots(slots, { perDay: 2 })).toHaveLength(2);
+  });
+});
+
+describe("formatAvailability", () => {
+  it("lists each slot", () => {
+    const html = formatAvailability(
+      [
+        { start: at(13, 9), end: at(13, 10) },
+        { start: at(14, 14), end: at(14, 15) },
+      ],
+      "en-US",
+    );
+
+    expect(html).toMatch(/^<p>I'm available at any of these times:<\/p><ul>/);
+    expect(html.match(/<li>/g)).toHaveLength(2);
+    expect(html).toContain("Mon, Oct 13");
+  });
+});
+
+describe("findCalDavAccount", () => {
+  it("prefers the given account when it has CalDAV", async () => {
+    vi.mocked(getAccount).mockResolvedValue({ ...caldavAccount, id: "acc-1", provider: "imap" });
+
+    const account = await findCalDavAccount("acc-1");
+
+    expect(account?.id).toBe("acc-1");
+    expect(getAllAccounts).not.toHaveBeenCalled();
+  });
+
+  it("falls back to a standalone CalDAV account", async () => {
+    vi.mocked(getAccount).mockResolvedValue(createMockImapAccount());
+    vi.mocked(getAllAccounts).mockResolvedValue([createMockImapAccount(), caldavAccount]);
+
+    expect((await findCalDavAccount("acc-imap"))?.id).toBe("acc-cal");
+  });
+
+  it("returns null without a CalDAV account", async () => {
+    vi.mocked(getAccount).mockResolvedValue(createMockImapAccount());
+    vi.mocked(getAllAccounts).mockResolvedValue([createMockImapAccount()]);
+
+    expect(await findCalDavAccount("acc-imap")).toBeNull();
+  });
+});
+
+describe("fetchFreeSlots", () => {
+  it("asks the backend about the visible CalDAV calendars", async () => {
+    vi.mocked(getVisibleCalendars).mockResolvedValue([
+      { provider: "caldav", remote_id: "https://dav.example.com/cal/work/" } as DbCalendar,
+    ]);
+    vi.mocked(invoke).mockResolvedValue([]);
+
+    await fetchFreeSlots(
+      caldavAccount,
+      new Date(at(13, 0) * 1000),
+      new Date(at(20, 0) * 1000),
+    );
+
+    expect(invoke).toHaveBeenCalledWith("caldav_freebusy", {
+      calendarUrls: ["https://dav.example.com/cal/work/"],
+      username: "me",
+      password: "secret",
+      start: at(13, 0),
+      end: at(20, 0),
+      minMinutes: 30,
+    });
+  });
+
+  it("fails without synced calendars", async () => {
+    vi.mocked(getVisibleCalendars).mockResolvedValue([]);
+
+    await expect(
+      fetchFreeSlots(caldavAccount, new Date(), new Date(Date.now() + 3600_000)),
+    ).rejects.toThrow("No CalDAV calendars");
+    expect(invoke).not.toHaveBeenCalled();
+  });
+});
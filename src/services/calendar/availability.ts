@@ -0,0 +1,135 @@
+import { invoke } from "@tauri-apps/api/core";
+import { getAccount, getAllAccounts, type DbAccount } from "@/services/db/accounts";
+import { getVisibleCalendars } from "@/services/db/calendars";
+
+/** A span of time in Unix seconds, end exclusive. */
+export interface TimeSlot {
+  start: number;
+  end: number;
+}
+
+export interface SuggestOptions {
+  /** First and last hour of the working day, local time */
+  dayStartHour?: number;
+  dayEndHour?: number;
+  minMinutes?: number;
+  /** Most suggestions overall and per day */
+  limit?: number;
+  perDay?: number;
+}
+
+const MEETING_PATTERN =
+  /\b(meet|meeting|call|schedul\w*|availab\w*|appointment|catch[- ]up|interview|invit\w*|calendar|reschedul\w*)\b/i;
+
+/** Whether a thread's subject suggests it is about arranging a meeting. */
+export function isMeetingRelated(subject: string | null | undefined): boolean {
+  return !!subject && MEETING_PATTERN.test(subject);
+}
+
+function hasCalDav(account: DbAccount): boolean {
+  return (
+    !!account.caldav_url &&
+    (account.provider === "caldav" || account.calendar_provider === "caldav")
+  );
+}
+
+/**
+ * The account whose CalDAV calendars answer availability lookups: the
+ * given account when it has CalDAV configured, otherwise the first
+ * standalone CalDAV account.
+ */
+export async function findCalDavAccount(accountId: string | null): Promise<DbAccount | null> {
+  const account = accountId ? await getAccount(accountId) : null;
+  if (account && hasCalDav(account)) return account;
+  const accounts = await getAllAccounts();
+  return accounts.find((a) => a.provider === "caldav" && hasCalDav(a)) ?? null;
+}
+
+/** Free time in the account's visible CalDAV calendars between `start` and `end`. */
+export async function fetchFreeSlots(
+  account: DbAccount,
+  start: Date,
+  end: Date,
+  minMinutes = 30,
+): Promise<TimeSlot[]> {
+  if (!account.caldav_password) {
+    throw new Error("CalDAV credentials not configured");
+  }
+  const calendars = await getVisibleCalendars(account.id);
+  const calendarUrls = calendars
+    .filter((c) => c.provider === "caldav")
+    .map((c) => c.remote_id);
+  if (calendarUrls.length === 0) {
+    throw new Error("No CalDAV calendars to check; sync the calendar first");
+  }
+  return invoke<TimeSlot[]>("caldav_freebusy", {
+    calendarUrls,
+    username: account.caldav_username ?? account.email,
+    password: account.caldav_password,
+    start: Math.floor(start.getTime() / 1000),
+    end: Math.floor(end.getTime() / 1000),
+    minMinutes,
+  });
+}
+
+/**
+ * Turn free slots into meeting suggestions: the parts of each slot that
+ * fall within working hours on weekdays (local time), earliest first.
+ */
+export function suggestSlots(slots: TimeSlot[], options: SuggestOptions = {}): TimeSlot[] {
+  const {
+    dayStartHour = 9,
+    dayEndHour = 17,
+    minMinutes = 30,
+    limit = 6,
+    perDay = 2,
+  } = options;
+  const perDayCount = new Map<string, number>();
+  const suggestions: TimeSlot[] = [];
+
+  for (const slot of [...slots].sort((a, b) => a.start - b.start)) {
+    const day = new Date(slot.start * 1000);
+    day.setHours(0, 0, 0, 0);
+    while (day.getTime() / 1000 < slot.end && suggestions.length < limit) {
+      const weekday = day.getDay();
+      const dayStart = new Date(day);
+      dayStart.setHours(dayStartHour);
+      const dayEnd = new Date(day);
+      dayEnd.setHours(dayEndHour);
+      const start = Math.max(slot.start, dayStart.getTime() / 1000);
+      const end = Math.min(slot.end, dayEnd.getTime() / 1000);
+      const key = day.toDateString();
+      const count = perDayCount.get(key) ?? 0;
+      if (weekday !== 0 && weekday !== 6 && end - start >= minMinutes * 60 && count < perDay) {
+        suggestions.push({ start, end });
+        perDayCount.set(key, count + 1);
+      }
+      day.setDate(day.getDate() + 1);
+    }
+  }
+  return suggestions;
+}
+
+function escapeHtml(text: string): string {
+  return text.replace(/&/g, "&amp;").replace(/</g, "&lt;").replace(/>/g, "&gt;");
+}
+
+/** "Tue, Oct 20, 10:00 AM – 11:30 AM GMT+2" in the user's locale. */
+export function formatSlot(slot: TimeSlot, locale?: string): string {
+  const start = new Date(slot.start * 1000);
+  const end = new Date(slot.end * 1000);
+  const day = start.toLocaleDateString(locale, { weekday: "short", month: "short", day: "numeric" });
+  const from = start.toLocaleTimeString(locale, { hour: "numeric", minute: "2-digit" });
+  const to = end.toLocaleTimeString(locale, {
+    hour: "numeric",
+    minute: "2-digit",
+    timeZoneName: "short",
+  });
+  return `${day}, ${from} – ${to}`;
+}
+
+/** The reply text offering `slots`, as HTML for the editor. */
+export function formatAvailability(slots: TimeSlot[], locale?: string): string {
+  const items = slots.map((s) => `<li>${escapeHtml(formatSlot(s, locale))}</li>`).join("");
+  return `<p>I'm available at any of these times:</p><ul>${items}</ul>`;
+}
@@ -0,0 +1,32 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { invoke } from "@tauri-apps/api/core";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+import { applyCloseAction, getCloseBehavior, setCloseBehavior } from "./closeBehavior";
+
+const mockInvoke = vi.mocked(invoke);
+
+describe("closeBehavior", () => {
+  beforeEach(() => {
+    mockInvoke.mockReset();
+  });
+
+  it("reads the backend setting", async () => {
+    mockInvoke.mockResolvedValue("ask");
+
+    expect(await getCloseBehavior()).toBe("ask");
+    expect(mockInvoke).toHaveBeenCalledWith("get_close_behavior");
+  });
+
+  it("saves the behavior and applies an action", async () => {
+    mockInvoke.mockResolvedValue(undefined);
+
+    await setCloseBehavior("minimize");
+    expect(mockInvoke).toHaveBeenCalledWith("set_close_behavior", { behavior: "minimize" });
+    await applyCloseAction("quit");
+    expect(mockInvoke).toHaveBeenCalledWith("apply_close_action", { action: "quit" });
+  });
+});
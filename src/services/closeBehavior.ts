@@ -0,0 +1,20 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * What the main window's close button does. The backend applies it; with
+ * "ask" it emits `close-requested` and `CloseWindowDialog` asks instead.
+ */
+export type CloseBehavior = "hide" | "minimize" | "quit" | "ask";
+
+export async function getCloseBehavior(): Promise<CloseBehavior> {
+  return invoke<CloseBehavior>("get_close_behavior");
+}
+
+export async function setCloseBehavior(behavior: CloseBehavior): Promise<void> {
+  return invoke<void>("set_close_behavior", { behavior });
+}
+
+/** Hide, minimize or quit for a close the user was asked about. */
+export async function applyCloseAction(action: Exclude<CloseBehavior, "ask">): Promise<void> {
+  return invoke<void>("apply_close_action", { action });
+}
@@ -0,0 +1,82 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+
+vi.mock("./db/messages", () => ({
+  getMessage: vi.fn(),
+  setMessageKeywords: vi.fn(),
+}));
+
+vi.mock("./imap/tauriCommands", () => ({
+  imapSetFlags: vi.fn(),
+}));
+
+import { getMessage, setMessageKeywords } from "./db/messages";
+import { imapSetFlags } from "./imap/tauriCommands";
+import {
+  categoriesFromKeywords,
+  hasCategory,
+  keywordForCategory,
+  setMessageCategory,
+} from "./colorCategories";
+
+describe("keywordForCategory", () => {
+  it("uses $labelN for the built-in colors", () => {
+    expect(keywordForCategory("Work")).toBe("$label2");
+    expect(keywordForCategory("to do")).toBe("$label4");
+  });
+
+  it("makes a valid keyword from a custom name", () => {
+    expect(keywordForCategory("Travel")).toBe("Travel");
+    expect(keywordForCategory("Project (X)")).toBe("Project__X_");
+  });
+});
+
+describe("categoriesFromKeywords", () => {
+  it("skips keywords that aren't categories", () => {
+    expect(categoriesFromKeywords("$Forwarded $label1 $Pinned Travel")).toEqual([
+      { keyword: "$label1", name: "Important", color: "#ef4444" },
+      { keyword: "Travel", name: "Travel", color: "#64748b" },
+    ]);
+    expect(categoriesFromKeywords(null)).toEqual([]);
+  });
+});
+
+describe("hasCategory", () => {
+  it("matches by name or keyword, ignoring case", () => {
+    expect(hasCategory(["$LABEL3"], "Personal")).toBe(true);
+    expect(hasCategory("travel", "Travel")).toBe(true);
+    expect(hasCategory("$label1", "Work")).toBe(false);
+  });
+});
+
+describe("setMessageCategory", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+  });
+
+  it("sets the keyword on the server and locally", async () => {
+    vi.mocked(getMessage).mockResolvedValue({
+      id: "msg-1",
+      imap_uid: 7,
+      imap_folder: "INBOX",
+      keywords: "$label1",
+    } as Awaited<ReturnType<typeof getMessage>>);
+
+    await setMessageCategory("acct-1", "msg-1", "$label2", true);
+
+    expect(imapSetFlags).toHaveBeenCalledWith("acct-1", "INBOX", [7], [], true, ["$label2"]);
+    expect(setMessageKeywords).toHaveBeenCalledWith("acct-1", "msg-1", ["$label1", "$label2"]);
+  });
+
+  it("keeps the local copy unchanged when the server refuses", async () => {
+    vi.mocked(getMessage).mockResolvedValue({
+      id: "msg-1",
+      imap_uid: 7,
+      imap_folder: "INBOX",
+      keywords: "$label1",
+    } as Awaited<ReturnType<typeof getMessage>>);
+    vi.mocked(imapSetFlags).mockRejectedValue(new Error("Invalid IMAP keyword"));
+
+    await expect(setMessageCategory("acct-1", "msg-1", "$label1", false)).rejects.toThrow();
+    expect(setMessageKeywords).not.toHaveBeenCalled();
+  });
+});
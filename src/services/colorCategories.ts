@@ -0,0 +1,110 @@
+import { getMessage, setMessageKeywords } from "./db/messages";
+import { imapSetFlags } from "./imap/tauriCommands";
+import { isTeamKeyword } from "./teamTags";
+
+/**
+ * Outlook-style color categories, stored as IMAP keywords so other clients
+ * see them: the five built-in ones use Thunderbird's `$label1`..`$label5`,
+ * custom ones a keyword made from their name. Gmail accounts have labels
+ * instead and don't get categories.
+ */
+
+export interface ColorCategory {
+  keyword: string;
+  name: string;
+  color: string;
+}
+
+export const BUILTIN_CATEGORIES: ColorCategory[] = [
+  { keyword: "$label1", name: "Important", color: "#ef4444" },
+  { keyword: "$label2", name: "Work", color: "#f97316" },
+  { keyword: "$label3", name: "Personal", color: "#22c55e" },
+  { keyword: "$label4", name: "To Do", color: "#3b82f6" },
+  { keyword: "$label5", name: "Later", color: "#a855f7" },
+];
+
+/** Color for custom categories, which carry no color on the server. */
+const CUSTOM_COLOR = "#64748b";
+
+/** Keywords clients set for other purposes, which aren't categories. */
+const NON_CATEGORY_KEYWORDS = new Set([
+  "$forwarded",
+  "$junk",
+  "$notjunk",
+  "junk",
+  "nonjunk",
+  "notjunk",
+  "$mdnsent",
+  "$phishing",
+  "$pinned",
+  "$submitpending",
+  "$submitted",
+  "$hasattachment",
+  "$hasnoattachment",
+  "$important",
+]);
+
+/** The keywords in a message's space-separated `keywords` column or a list. */
+function keywordList(keywords: string | string[] | null | undefined): string[] {
+  if (!keywords) return [];
+  return Array.isArray(keywords) ? keywords : keywords.split(" ").filter(Boolean);
+}
+
+/** The keyword for a category name: `$labelN` for a built-in one, else the
+ *  name with characters IMAP keywords can't hold replaced by "_". */
+export function keywordForCategory(name: string): string {
+  const builtin = BUILTIN_CATEGORIES.find((c) => c.name.toLowerCase() === name.trim().toLowerCase());
+  if (builtin) return builtin.keyword;
+  return name.trim().replace(/[^\x21-\x7e]|[(){%*"\\\]]/g, "_");
+}
+
+/** The categories among a message's keywords. Keywords are case-insensitive. */
+export function categoriesFromKeywords(keywords: string | string[] | null | undefined): ColorCategory[] {
+  return keywordList(keywords)
+    .filter((k) => !NON_CATEGORY_KEYWORDS.has(k.toLowerCase()) && !isTeamKeyword(k))
+    .map(
+      (k) =>
+        BUILTIN_CATEGORIES.find((c) => c.keyword === k.toLowerCase()) ?? {
+          keyword: k,
+          name: k,
+          color: CUSTOM_COLOR,
+        },
+    );
+}
+
+/** Whether a message's keywords include category `name` (or its keyword). */
+export function hasCategory(keywords: string | string[] | null | undefined, name: string): boolean {
+  const wanted = [keywordForCategory(name).toLowerCase(), name.trim().toLowerCase()];
+  return keywordList(keywords).some((k) => wanted.includes(k.toLowerCase()));
+}
+
+export interface MessageCategoriesDetail {
+  messageId: string;
+  keywords: string;
+}
+
+/**
+ * Add or remove a category on a message: locally, and on the server as a
+ * keyword when it's an IMAP message.
+ */
+export async function setMessageCategory(
+  accountId: string,
+  messageId: string,
+  keyword: string,
+  on: boolean,
+): Promise<void> {
+  const message = await getMessage(accountId, messageId);
+  if (!message) return;
+
+  const others = keywordList(message.keywords).filter((k) => k.toLowerCase() !== keyword.toLowerCase());
+  const keywords = on ? [...others, keyword] : others;
+  if (message.imap_uid != null && message.imap_folder) {
+    await imapSetFlags(accountId, message.imap_folder, [message.imap_uid], [], on, [keyword]);
+  }
+  await setMessageKeywords(accountId, messageId, keywords);
+  window.dispatchEvent(
+    new CustomEvent<MessageCategoriesDetail>("velo-message-categories", {
+      detail: { messageId, keywords: keywords.join(" ") },
+    }),
+  );
+}
@@ -0,0 +1,13 @@
+import { describe, it, expect } from "vitest";
+import { unwrap } from "./commandResult";
+
+describe("unwrap", () => {
+  it("returns the data of an ok result", () => {
+    expect(unwrap<number[]>({ status: "ok", data: [1, 2] })).toEqual([1, 2]);
+  });
+
+  it("throws the error string of a failed result, like invoke", async () => {
+    const failed = async () => unwrap({ status: "error", error: "Not connected" });
+    await expect(failed()).rejects.toBe("Not connected");
+  });
+});
@@ -0,0 +1,12 @@
+import type { Result } from "@/bindings";
+
+/**
+ * The data of a command result from `@/bindings`, or its error thrown as
+ * `invoke` would. Wrappers name the type they return: the generated types
+ * are looser than the hand-written ones (plain strings instead of string
+ * unions, serde defaults as optional fields).
+ */
+export function unwrap<T>(result: Result<unknown, string>): T {
+  if (result.status === "error") throw result.error;
+  return result.data as T;
+}
@@ -0,0 +1,20 @@
+import { describe, it, expect, vi } from "vitest";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+import { invoke } from "@tauri-apps/api/core";
+import { getDisclaimer } from "./disclaimer";
+
+describe("disclaimer", () => {
+  it("looks the disclaimer up by account address", async () => {
+    vi.mocked(invoke).mockResolvedValueOnce({ text: "Confidential", html: "<p>Confidential</p>" });
+
+    await expect(getDisclaimer("ann@corp.example")).resolves.toEqual({
+      text: "Confidential",
+      html: "<p>Confidential</p>",
+    });
+    expect(invoke).toHaveBeenCalledWith("get_disclaimer", { accountEmail: "ann@corp.example" });
+  });
+});
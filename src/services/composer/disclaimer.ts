@@ -0,0 +1,11 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { Disclaimer } from "@/utils/emailBuilder";
+
+/**
+ * The disclaimer the admin policy requires on mail sent from the account,
+ * or null. Rejects when the policy file exists but is broken, so the
+ * message isn't sent without it.
+ */
+export function getDisclaimer(accountEmail: string): Promise<Disclaimer | null> {
+  return invoke<Disclaimer | null>("get_disclaimer", { accountEmail });
+}
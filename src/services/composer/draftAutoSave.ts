@@ -2,6 +2,7 @@ import { useComposerStore } from "@/stores/composerStore";
 import { createDraft as createDraftAction, updateDraft as updateDraftAction } from "@/services/emailActions";
 import { buildRawEmail } from "@/utils/emailBuilder";
 import { useAccountStore } from "@/stores/accountStore";
+import { resolveRecipientGroups } from "@/services/db/contactGroups";
 
 let debounceTimer: ReturnType<typeof setTimeout> | null = null;
 let unsubscribe: (() => void) | null = null;
@@ -28,6 +29,7 @@ async function saveDraft(): Promise<void> {
     const raw = buildRawEmail({
       from: account.email,
       to: state.to.length > 0 ? state.to : [""],
+      groups: await resolveRecipientGroups(state.to),
       subject: state.subject,
       htmlBody: state.bodyHtml,
       threadId: state.threadId ?? undefined,
@@ -36,8 +38,10 @@ async function saveDraft(): Promise<void> {
             filename: a.filename,
             mimeType: a.mimeType,
             content: a.content,
+            stagedId: a.stagedId,
           }))
         : undefined,
+      stagedInlineImages: state.inlineImages,
     });
 
     if (state.draftId) {
@@ -0,0 +1,40 @@
+import { describe, it, expect } from "vitest";
+import { recipientIssues, type RecipientCheck } from "./recipientValidation";
+
+function check(overrides: Partial<RecipientCheck>): RecipientCheck {
+  return {
+    address: "joe@example.com",
+    valid_syntax: true,
+    syntax_error: null,
+    domain_status: "accepts_mail",
+    suggestion: null,
+    ...overrides,
+  };
+}
+
+describe("recipientIssues", () => {
+  it("ignores valid addresses and DNS failures", () => {
+    expect(recipientIssues([check({}), check({ domain_status: "unknown" })])).toEqual([]);
+  });
+
+  it("reports syntax errors", () => {
+    const issues = recipientIssues([
+      check({ address: "joe.example.com", valid_syntax: false, syntax_error: "Missing @", domain_status: null }),
+    ]);
+    expect(issues).toEqual([{ address: "joe.example.com", message: "Missing @", suggestion: null }]);
+  });
+
+  it("offers typo corrections", () => {
+    const issues = recipientIssues([
+      check({ address: "joe@gamil.com", suggestion: "joe@gmail.com" }),
+    ]);
+    expect(issues).toEqual([
+      { address: "joe@gamil.com", message: "Possible typo", suggestion: "joe@gmail.com" },
+    ]);
+  });
+
+  it("reports domains without a mail server", () => {
+    const issues = recipientIssues([check({ domain_status: "no_mail_server" })]);
+    expect(issues[0]?.message).toBe("This domain doesn't accept email");
+  });
+});
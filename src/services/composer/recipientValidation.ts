@@ -0,0 +1,47 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type RecipientDomainStatus = "accepts_mail" | "no_mail_server" | "unknown";
+
+export interface RecipientCheck {
+  address: string;
+  valid_syntax: boolean;
+  /** Why the address isn't valid, when valid_syntax is false */
+  syntax_error: string | null;
+  /** null when the syntax check already failed */
+  domain_status: RecipientDomainStatus | null;
+  /** Corrected address for a likely domain typo (gamil.com → gmail.com) */
+  suggestion: string | null;
+}
+
+export interface RecipientIssue {
+  address: string;
+  message: string;
+  suggestion: string | null;
+}
+
+/**
+ * Check recipient syntax, look up whether each domain accepts mail, and
+ * suggest corrections for common domain typos.
+ */
+export function validateRecipients(addresses: string[]): Promise<RecipientCheck[]> {
+  return invoke<RecipientCheck[]>("validate_recipients", { addresses });
+}
+
+/** Problems worth showing before send; DNS failures aren't reported. */
+export function recipientIssues(checks: RecipientCheck[]): RecipientIssue[] {
+  const issues: RecipientIssue[] = [];
+  for (const check of checks) {
+    let message: string | null = null;
+    if (!check.valid_syntax) {
+      message = check.syntax_error ?? "Invalid address";
+    } else if (check.suggestion) {
+      message = "Possible typo";
+    } else if (check.domain_status === "no_mail_server") {
+      message = "This domain doesn't accept email";
+    }
+    if (message) {
+      issues.push({ address: check.address, message, suggestion: check.suggestion });
+    }
+  }
+  return issues;
+}
@@ -0,0 +1,28 @@
+import { describe, it, expect, vi } from "vitest";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+import { invoke } from "@tauri-apps/api/core";
+import { checkSendPolicy, parseDomainList } from "./sendPolicy";
+
+describe("sendPolicy", () => {
+  it("checks the raw message for the account", async () => {
+    vi.mocked(invoke).mockResolvedValueOnce([]);
+
+    await expect(checkSendPolicy("acc-1", "UmF3")).resolves.toEqual([]);
+    expect(invoke).toHaveBeenCalledWith("check_send_policy", {
+      accountId: "acc-1",
+      rawEmail: "UmF3",
+    });
+  });
+
+  it("normalizes a typed domain list", () => {
+    expect(parseDomainList("Example.com, @corp.example\nexample.com;;  ")).toEqual([
+      "example.com",
+      "corp.example",
+    ]);
+    expect(parseDomainList("")).toEqual([]);
+  });
+});
@@ -0,0 +1,46 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** Checks run before sending; stored in the backend settings. */
+export interface SendPolicy {
+  /** Recipients outside these domains (and subdomains) are external; empty turns the check off */
+  internal_domains: string[];
+  /** Warn when Cc lists more addresses than this */
+  max_cc: number | null;
+  warn_empty_subject: boolean;
+  /** Warn when replying to a conversation quiet for longer than this */
+  old_thread_days: number | null;
+}
+
+export type SendWarningKind = "external_recipients" | "large_cc" | "empty_subject" | "old_thread";
+
+export interface SendWarning {
+  kind: SendWarningKind;
+  message: string;
+  /** The external addresses, for external_recipients */
+  addresses: string[];
+}
+
+/**
+ * Check a message built by `buildRawEmail` against the send policy.
+ * Resolves to an empty list when it can be sent without asking.
+ */
+export function checkSendPolicy(accountId: string, rawEmail: string): Promise<SendWarning[]> {
+  return invoke<SendWarning[]>("check_send_policy", { accountId, rawEmail });
+}
+
+export function getSendPolicy(): Promise<SendPolicy> {
+  return invoke<SendPolicy>("get_send_policy");
+}
+
+export function setSendPolicy(policy: SendPolicy): Promise<void> {
+  return invoke("set_send_policy", { policy });
+}
+
+/** Domains typed as a comma/space/newline separated list, normalized. */
+export function parseDomainList(input: string): string[] {
+  const domains = input
+    .split(/[\s,;]+/)
+    .map((d) => d.trim().replace(/^@/, "").toLowerCase())
+    .filter((d) => d.length > 0);
+  return [...new Set(domains)];
+}
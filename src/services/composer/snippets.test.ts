@@ -0,0 +1,38 @@
+import { describe, it, expect, vi } from "vitest";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+import { invoke } from "@tauri-apps/api/core";
+import { endsWithAbbreviation, expandSnippet, snippetToHtml } from "./snippets";
+
+describe("snippets", () => {
+  it("passes the text and context to the backend", async () => {
+    vi.mocked(invoke).mockResolvedValueOnce(null);
+    const context = {
+      recipient_email: "a@example.com",
+      recipient_name: null,
+      sender_email: null,
+      sender_name: "Bob",
+      subject: null,
+    };
+
+    await expect(expandSnippet("Ship to ;addr", "acc-1", context)).resolves.toBeNull();
+    expect(invoke).toHaveBeenCalledWith("expand_snippet", {
+      text: "Ship to ;addr",
+      accountId: "acc-1",
+      context,
+    });
+  });
+
+  it("only matches abbreviations at the end of the text", () => {
+    expect(endsWithAbbreviation("Ship to ;addr", [";sig", ";addr"])).toBe(true);
+    expect(endsWithAbbreviation(";addr later", [";addr"])).toBe(false);
+    expect(endsWithAbbreviation("anything", [""])).toBe(false);
+  });
+
+  it("escapes text and turns line breaks into hard breaks", () => {
+    expect(snippetToHtml("Acme <Sales>\n1 Main St")).toBe("Acme &lt;Sales&gt;<br>1 Main St");
+  });
+});
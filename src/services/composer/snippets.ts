@@ -0,0 +1,52 @@
+import { invoke } from "@tauri-apps/api/core";
+import { escapeHtml } from "@/utils/sanitize";
+
+/** Values snippet variables are filled in from; same as for templates. */
+export interface SnippetContext {
+  recipient_email: string | null;
+  /** Looked up in the contacts when null */
+  recipient_name: string | null;
+  sender_email: string | null;
+  sender_name: string | null;
+  subject: string | null;
+}
+
+export interface SnippetExpansion {
+  /** The abbreviation that matched, to be replaced */
+  abbreviation: string;
+  /** Plain text with variables filled in */
+  text: string;
+  /** Caret offset within text (UTF-16 units) from a {{cursor}} marker */
+  cursor: number | null;
+}
+
+/** Variables snippets support on top of the template ones. */
+export const SNIPPET_VARIABLES = [
+  { key: "{{time}}", desc: "Current time" },
+  { key: "{{cursor}}", desc: "Where the cursor goes after expanding" },
+];
+
+/**
+ * Expand the snippet whose abbreviation `text` (what's before the caret)
+ * ends with. Resolves to null when no snippet matches.
+ */
+export function expandSnippet(
+  text: string,
+  accountId: string | null,
+  context: SnippetContext,
+): Promise<SnippetExpansion | null> {
+  return invoke<SnippetExpansion | null>("expand_snippet", { text, accountId, context });
+}
+
+/**
+ * Cheap check before asking the backend: whether `text` ends with any of
+ * the abbreviations. The backend decides word boundaries and precedence.
+ */
+export function endsWithAbbreviation(text: string, abbreviations: string[]): boolean {
+  return abbreviations.some((a) => a.length > 0 && text.endsWith(a));
+}
+
+/** Editor HTML for expanded plain text; line breaks become hard breaks. */
+export function snippetToHtml(text: string): string {
+  return text.split("\n").map(escapeHtml).join("<br>");
+}
@@ -0,0 +1,29 @@
+import { describe, it, expect, vi } from "vitest";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(() => Promise.resolve(3)),
+}));
+
+import { invoke } from "@tauri-apps/api/core";
+import { exportContacts, exportExtension } from "./contactExport";
+
+describe("contactExport", () => {
+  it("passes the format and filter to the backend", async () => {
+    const count = await exportContacts("/tmp/team.vcf", "vcard4", {
+      group_id: "g1",
+      min_frequency: 2,
+    });
+
+    expect(count).toBe(3);
+    expect(invoke).toHaveBeenCalledWith("contacts_export", {
+      path: "/tmp/team.vcf",
+      format: "vcard4",
+      filter: { group_id: "g1", min_frequency: 2 },
+    });
+  });
+
+  it("uses the extension of each format", () => {
+    expect(exportExtension("vcard3")).toBe("vcf");
+    expect(exportExtension("csv")).toBe("csv");
+  });
+});
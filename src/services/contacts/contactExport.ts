@@ -0,0 +1,35 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * Exporting the address book (harvested and imported contacts) as a vCard
+ * or CSV file. The backend reads the contacts and writes the file.
+ */
+
+export type ContactExportFormat = "vcard3" | "vcard4" | "csv";
+
+export interface ContactExportFilter {
+  /** Only members of this contact group */
+  group_id: string | null;
+  /** Only contacts mailed with at least this often */
+  min_frequency: number | null;
+}
+
+export const EXPORT_FORMATS: { value: ContactExportFormat; label: string; extension: string }[] = [
+  { value: "vcard3", label: "vCard 3.0", extension: "vcf" },
+  { value: "vcard4", label: "vCard 4.0", extension: "vcf" },
+  { value: "csv", label: "CSV (Google Contacts)", extension: "csv" },
+];
+
+/** Write the matching contacts to `path`. Resolves to how many were exported. */
+export function exportContacts(
+  path: string,
+  format: ContactExportFormat,
+  filter: ContactExportFilter = { group_id: null, min_frequency: null },
+): Promise<number> {
+  return invoke<number>("contacts_export", { path, format, filter });
+}
+
+/** File extension for a format. */
+export function exportExtension(format: ContactExportFormat): string {
+  return EXPORT_FORMATS.find((f) => f.value === format)?.extension ?? "vcf";
+}
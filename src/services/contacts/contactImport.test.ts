@@ -0,0 +1,78 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+import { invoke } from "@tauri-apps/api/core";
+import {
+  columnMapping,
+  importContacts,
+  importSummary,
+  previewContactImport,
+  type ContactsImport,
+  type ImportedContact,
+} from "./contactImport";
+
+function contact(email: string, exists = false): ImportedContact {
+  return {
+    email,
+    display_name: null,
+    phones: [],
+    urls: [],
+    organization: null,
+    title: null,
+    avatar_url: null,
+    exists,
+  };
+}
+
+function result(overrides: Partial<ContactsImport> = {}): ContactsImport {
+  return {
+    format: "csv",
+    columns: [
+      { header: "Name", field: "name" },
+      { header: "E-mail 1 - Type", field: null },
+      { header: "E-mail 1 - Value", field: "email" },
+    ],
+    contacts: [contact("a@example.com"), contact("b@example.com", true)],
+    skipped: 0,
+    merged: 0,
+    saved: false,
+    ...overrides,
+  };
+}
+
+describe("contactImport", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+  });
+
+  it("previews as a dry run and imports with the edited mapping", async () => {
+    vi.mocked(invoke).mockResolvedValue(result());
+
+    const preview = await previewContactImport("/tmp/contacts.csv");
+    expect(invoke).toHaveBeenCalledWith("contacts_import", {
+      path: "/tmp/contacts.csv",
+      mapping: null,
+      dryRun: true,
+    });
+
+    const mapping = columnMapping(preview);
+    expect(mapping).toEqual(["name", null, "email"]);
+    mapping[0] = "first_name";
+    await importContacts("/tmp/contacts.csv", mapping);
+    expect(invoke).toHaveBeenLastCalledWith("contacts_import", {
+      path: "/tmp/contacts.csv",
+      mapping: ["first_name", null, "email"],
+      dryRun: false,
+    });
+  });
+
+  it("summarizes new, updated, merged and skipped entries", () => {
+    expect(importSummary(result())).toBe("1 new, 1 updated");
+    expect(importSummary(result({ merged: 1, skipped: 3 }))).toBe(
+      "1 new, 1 updated · 1 duplicate merged · 3 without an address",
+    );
+  });
+});
@@ -0,0 +1,94 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * Importing contacts from a CSV export (Google Contacts, Outlook) or a
+ * vCard file. The backend reads and parses the file; a dry run returns the
+ * same result without saving, which the import dialog shows as a preview.
+ */
+
+export type ContactField =
+  | "name"
+  | "first_name"
+  | "last_name"
+  | "email"
+  | "phone"
+  | "url"
+  | "organization"
+  | "title";
+
+export interface ImportColumn {
+  header: string;
+  /** null for columns that aren't imported */
+  field: ContactField | null;
+}
+
+export interface ImportedContact {
+  email: string;
+  display_name: string | null;
+  phones: string[];
+  urls: string[];
+  organization: string | null;
+  title: string | null;
+  avatar_url: string | null;
+  /** Already saved; only missing details are filled in. */
+  exists: boolean;
+}
+
+export interface ContactsImport {
+  format: "csv" | "vcard";
+  /** CSV columns and what each is imported as; empty for vCards. */
+  columns: ImportColumn[];
+  contacts: ImportedContact[];
+  /** Entries without a usable address */
+  skipped: number;
+  /** Entries merged into another with the same address */
+  merged: number;
+  saved: boolean;
+}
+
+export const CONTACT_FIELD_LABELS: Record<ContactField, string> = {
+  name: "Full name",
+  first_name: "First name",
+  last_name: "Last name",
+  email: "Email",
+  phone: "Phone",
+  url: "Website",
+  organization: "Company",
+  title: "Job title",
+};
+
+/**
+ * Parse a file without saving anything. `mapping` overrides the guessed
+ * field of each CSV column, by position.
+ */
+export function previewContactImport(
+  path: string,
+  mapping: (ContactField | null)[] | null = null,
+): Promise<ContactsImport> {
+  return invoke<ContactsImport>("contacts_import", { path, mapping, dryRun: true });
+}
+
+/** Import a file with the mapping confirmed in the preview. */
+export function importContacts(
+  path: string,
+  mapping: (ContactField | null)[] | null = null,
+): Promise<ContactsImport> {
+  return invoke<ContactsImport>("contacts_import", { path, mapping, dryRun: false });
+}
+
+/** The mapping the backend used, to start editing from. */
+export function columnMapping(result: ContactsImport): (ContactField | null)[] {
+  return result.columns.map((c) => c.field);
+}
+
+/** "12 new, 3 updated · 2 without an address", for the preview and result. */
+export function importSummary(result: ContactsImport): string {
+  const updated = result.contacts.filter((c) => c.exists).length;
+  const parts = [`${result.contacts.length - updated} new`, `${updated} updated`];
+  let summary = parts.join(", ");
+  if (result.merged > 0) {
+    summary += ` · ${result.merged} duplicate${result.merged === 1 ? "" : "s"} merged`;
+  }
+  if (result.skipped > 0) summary += ` · ${result.skipped} without an address`;
+  return summary;
+}
@@ -0,0 +1,95 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+const fetchRawMessage = vi.fn();
+vi.mock("@/services/email/providerFactory", () => ({
+  getEmailProvider: vi.fn(() => Promise.resolve({ fetchRawMessage })),
+}));
+
+import { invoke } from "@tauri-apps/api/core";
+import {
+  getMessageContact,
+  hasNewDetails,
+  parseContactList,
+  type MessageContact,
+} from "./messageContact";
+import type { DbContact } from "@/services/db/contacts";
+
+function found(overrides: Partial<MessageContact> = {}): MessageContact {
+  return {
+    email: "alice@example.com",
+    display_name: "Alice",
+    phones: [],
+    urls: [],
+    organization: null,
+    title: null,
+    avatar_url: null,
+    from_vcard: false,
+    ...overrides,
+  };
+}
+
+const saved: DbContact = {
+  id: "c-1",
+  email: "alice@example.com",
+  display_name: "Alice",
+  avatar_url: null,
+  frequency: 1,
+  last_contacted_at: null,
+  notes: null,
+  phones: '["+1 555 010 2030"]',
+  urls: null,
+  organization: null,
+  job_title: null,
+};
+
+describe("getMessageContact", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    fetchRawMessage.mockResolvedValue("From: alice@example.com\r\n\r\nHi");
+    vi.mocked(invoke).mockResolvedValue(found());
+  });
+
+  it("parses the raw message once per message", async () => {
+    await getMessageContact("acc-1", "msg-1");
+    await getMessageContact("acc-1", "msg-1");
+
+    expect(fetchRawMessage).toHaveBeenCalledTimes(1);
+    expect(invoke).toHaveBeenCalledWith("contact_from_message", {
+      raw: "From: alice@example.com\r\n\r\nHi",
+    });
+  });
+
+  it("retries after a failure", async () => {
+    fetchRawMessage.mockRejectedValueOnce(new Error("offline"));
+    await expect(getMessageContact("acc-1", "msg-2")).rejects.toThrow("offline");
+
+    await getMessageContact("acc-1", "msg-2");
+    expect(fetchRawMessage).toHaveBeenCalledTimes(2);
+  });
+});
+
+describe("parseContactList", () => {
+  it("reads JSON string arrays and ignores anything else", () => {
+    expect(parseContactList('["a","b"]')).toEqual(["a", "b"]);
+    expect(parseContactList(null)).toEqual([]);
+    expect(parseContactList("not json")).toEqual([]);
+    expect(parseContactList('{"a":1}')).toEqual([]);
+  });
+});
+
+describe("hasNewDetails", () => {
+  it("offers everything for an unsaved sender with details", () => {
+    expect(hasNewDetails(found({ urls: ["https://example.com"] }), null)).toBe(true);
+    expect(hasNewDetails(found(), null)).toBe(false);
+  });
+
+  it("only counts details the saved contact lacks", () => {
+    expect(hasNewDetails(found({ phones: ["+1 555 999 0000"] }), saved)).toBe(false);
+    expect(hasNewDetails(found({ organization: "Example Corp" }), saved)).toBe(true);
+    expect(hasNewDetails(found({ display_name: "Alice E.", from_vcard: true }), saved)).toBe(false);
+  });
+});
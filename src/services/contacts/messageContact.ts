@@ -0,0 +1,62 @@
+import { invoke } from "@tauri-apps/api/core";
+import { getEmailProvider } from "@/services/email/providerFactory";
+import type { DbContact } from "@/services/db/contacts";
+
+/** Sender details found in a message's attached vCard and signature. */
+export interface MessageContact {
+  email: string | null;
+  display_name: string | null;
+  phones: string[];
+  urls: string[];
+  organization: string | null;
+  title: string | null;
+  /** PNG data URL made from the vCard photo */
+  avatar_url: string | null;
+  from_vcard: boolean;
+}
+
+const cache = new Map<string, Promise<MessageContact>>();
+
+/**
+ * Extract the sender's contact details from a message. The raw message is
+ * fetched once per message and session.
+ */
+export function getMessageContact(
+  accountId: string,
+  messageId: string,
+): Promise<MessageContact> {
+  const key = `${accountId}:${messageId}`;
+  let pending = cache.get(key);
+  if (!pending) {
+    pending = getEmailProvider(accountId)
+      .then((provider) => provider.fetchRawMessage(messageId))
+      .then((raw) => invoke<MessageContact>("contact_from_message", { raw }));
+    pending.catch(() => cache.delete(key));
+    cache.set(key, pending);
+  }
+  return pending;
+}
+
+/** Parse a JSON list column (`phones`, `urls`), tolerating bad data. */
+export function parseContactList(value: string | null): string[] {
+  if (!value) return [];
+  try {
+    const list: unknown = JSON.parse(value);
+    return Array.isArray(list) ? list.filter((v): v is string => typeof v === "string") : [];
+  } catch {
+    return [];
+  }
+}
+
+/** Whether the message has details the saved contact doesn't. */
+export function hasNewDetails(found: MessageContact, contact: DbContact | null): boolean {
+  const isNew = (value: string | null, saved: string | null | undefined) => !!value && !saved;
+  return (
+    (found.from_vcard && isNew(found.display_name, contact?.display_name)) ||
+    isNew(found.avatar_url, contact?.avatar_url) ||
+    isNew(found.organization, contact?.organization) ||
+    isNew(found.title, contact?.job_title) ||
+    (found.phones.length > 0 && parseContactList(contact?.phones ?? null).length === 0) ||
+    (found.urls.length > 0 && parseContactList(contact?.urls ?? null).length === 0)
+  );
+}
@@ -0,0 +1,36 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export interface DateBucket {
+  /** "today", "yesterday", "this_week" or "YYYY-MM" */
+  key: string;
+  label: string;
+}
+
+export interface DateBucketItem {
+  /** Index into `DateBucketResult.buckets` */
+  bucket: number;
+  short_date: string;
+}
+
+export interface DateBucketResult {
+  buckets: DateBucket[];
+  /** One entry per input timestamp, in input order */
+  items: DateBucketItem[];
+}
+
+/**
+ * Group message timestamps (ms) into list sections (Today, Yesterday,
+ * This week, Month Year) with localized short dates. Uses the system
+ * timezone unless `utcOffsetMinutes` is given.
+ */
+export function bucketMessageDates(
+  timestamps: number[],
+  locale: string = navigator.language,
+  utcOffsetMinutes?: number,
+): Promise<DateBucketResult> {
+  return invoke<DateBucketResult>("bucket_message_dates", {
+    timestamps,
+    locale,
+    utcOffsetMinutes,
+  });
+}
@@ -3,6 +3,7 @@ import {
   getAccount,
   getAccountByEmail,
   insertImapAccount,
+  insertNntpAccount,
   insertAccount,
   deleteAccount,
   updateAccountTokens,
@@ -224,6 +225,56 @@ describe("accounts", () => {
     });
   });
 
+  describe("insertNntpAccount", () => {
+    it("stores the news server in the IMAP columns", async () => {
+      mockExecute.mockResolvedValue(undefined);
+
+      await insertNntpAccount({
+        id: "news-1",
+        email: "reader@example.org",
+        displayName: "Reader",
+        host: "news.example.org",
+        port: 563,
+        security: "tls",
+        username: "reader",
+        password: "pass",
+      });
+
+      const [sql, params] = mockExecute.mock.calls[0] as [string, unknown[]];
+      expect(sql).toContain("'nntp'");
+      expect(params).toEqual([
+        "news-1",
+        "reader@example.org",
+        "Reader",
+        "news.example.org",
+        563,
+        "tls",
+        "enc:pass",
+        "reader",
+        0,
+      ]);
+    });
+
+    it("stores no password for anonymous servers", async () => {
+      mockExecute.mockResolvedValue(undefined);
+
+      await insertNntpAccount({
+        id: "news-2",
+        email: "reader@example.org",
+        displayName: null,
+        host: "news.example.org",
+        port: 119,
+        security: "none",
+        username: "",
+        password: "",
+      });
+
+      const [, params] = mockExecute.mock.calls[0] as [string, unknown[]];
+      expect(params[6]).toBeNull();
+      expect(params[7]).toBeNull();
+    });
+  });
+
   describe("insertAccount (Gmail/OAuth)", () => {
     it("inserts OAuth account with encrypted tokens", async () => {
       mockExecute.mockResolvedValue(undefined);
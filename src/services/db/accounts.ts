@@ -26,6 +26,10 @@ export interface DbAccount {
   oauth_provider: string | null;
   oauth_client_id: string | null;
   oauth_client_secret: string | null;
+  /** Microsoft directory the account signs in against; null for "common". */
+  oauth_tenant: string | null;
+  /** "imap", or "gmail_api" to send through the Gmail API. */
+  transport: string;
   imap_username: string | null;
   caldav_url: string | null;
   caldav_username: string | null;
@@ -34,6 +38,15 @@ export interface DbAccount {
   caldav_home_url: string | null;
   calendar_provider: string | null;
   accept_invalid_certs: number;
+  imap_authorize_as: string | null;
+  imap_delegation: string | null;
+  /** 1 when the password isn't stored and is asked for once per session. */
+  ask_password: number;
+  /** Raw path of a folder shared with a team, whose messages get assignment
+   *  and status tags (see `teamTags.ts`); null when there's none. */
+  team_folder: string | null;
+  /** Comma-separated addresses messages in `team_folder` can be assigned to. */
+  team_members: string | null;
 }
 
 async function decryptAccountTokens(account: DbAccount): Promise<DbAccount> {
@@ -175,6 +188,24 @@ export async function updateAccountAllTokens(
   );
 }
 
+/** Forget the stored IMAP password once it's kept in the OS keychain. */
+export async function clearImapPassword(id: string): Promise<void> {
+  const db = await getDb();
+  await db.execute(
+    "UPDATE accounts SET imap_password = NULL, updated_at = unixepoch() WHERE id = $1",
+    [id],
+  );
+}
+
+/** Forget the OAuth tokens once the backend keeps the grant in the OS keychain. */
+export async function clearOAuthTokens(id: string): Promise<void> {
+  const db = await getDb();
+  await db.execute(
+    "UPDATE accounts SET access_token = NULL, refresh_token = NULL, token_expires_at = NULL, updated_at = unixepoch() WHERE id = $1",
+    [id],
+  );
+}
+
 export async function deleteAccount(id: string): Promise<void> {
   const db = await getDb();
   await db.execute("DELETE FROM accounts WHERE id = $1", [id]);
@@ -195,12 +226,16 @@ export async function insertImapAccount(account: {
   password: string;
   imapUsername?: string | null;
   acceptInvalidCerts?: boolean;
+  authorizeAs?: string | null;
+  delegation?: string | null;
+  /** Don't store the password; the user is asked for it once per session. */
+  askPassword?: boolean;
 }): Promise<void> {
   const db = await getDb();
-  const encPassword = await encryptValue(account.password);
+  const encPassword = account.askPassword ? null : await encryptValue(account.password);
   await db.execute(
-    `INSERT INTO accounts (id, email, display_name, avatar_url, access_token, refresh_token, provider, imap_host, imap_port, imap_security, smtp_host, smtp_port, smtp_security, auth_method, imap_password, imap_username, accept_invalid_certs)
-     VALUES ($1, $2, $3, $4, NULL, NULL, 'imap', $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)`,
+    `INSERT INTO accounts (id, email, display_name, avatar_url, access_token, refresh_token, provider, imap_host, imap_port, imap_security, smtp_host, smtp_port, smtp_security, auth_method, imap_password, imap_username, accept_invalid_certs, imap_authorize_as, imap_delegation, ask_password)
+     VALUES ($1, $2, $3, $4, NULL, NULL, 'imap', $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)`,
     [
       account.id,
       account.email,
@@ -216,6 +251,44 @@ export async function insertImapAccount(account: {
       encPassword,
       account.imapUsername || null,
       account.acceptInvalidCerts ? 1 : 0,
+      account.authorizeAs || null,
+      account.authorizeAs ? (account.delegation ?? null) : null,
+      account.askPassword ? 1 : 0,
+    ],
+  );
+}
+
+/**
+ * Insert a newsgroup account. The news server goes in the IMAP columns; the
+ * email address is only used as the From of posted articles.
+ */
+export async function insertNntpAccount(account: {
+  id: string;
+  email: string;
+  displayName: string | null;
+  host: string;
+  port: number;
+  security: string;
+  /** Empty for servers that allow reading without signing in. */
+  username: string;
+  password: string;
+  acceptInvalidCerts?: boolean;
+}): Promise<void> {
+  const db = await getDb();
+  const encPassword = account.username ? await encryptValue(account.password) : null;
+  await db.execute(
+    `INSERT INTO accounts (id, email, display_name, avatar_url, access_token, refresh_token, provider, imap_host, imap_port, imap_security, auth_method, imap_password, imap_username, accept_invalid_certs)
+     VALUES ($1, $2, $3, NULL, NULL, NULL, 'nntp', $4, $5, $6, 'password', $7, $8, $9)`,
+    [
+      account.id,
+      account.email,
+      account.displayName,
+      account.host,
+      account.port,
+      account.security,
+      encPassword,
+      account.username || null,
+      account.acceptInvalidCerts ? 1 : 0,
     ],
   );
 }
@@ -248,6 +321,29 @@ export async function insertCalDavAccount(account: {
   );
 }
 
+export async function updateAccountTransport(
+  accountId: string,
+  transport: "imap" | "gmail_api",
+): Promise<void> {
+  const db = await getDb();
+  await db.execute(
+    "UPDATE accounts SET transport = $1, updated_at = unixepoch() WHERE id = $2",
+    [transport, accountId],
+  );
+}
+
+export async function updateAccountTeamFolder(
+  accountId: string,
+  folder: string | null,
+  members: string[],
+): Promise<void> {
+  const db = await getDb();
+  await db.execute(
+    "UPDATE accounts SET team_folder = $1, team_members = $2, updated_at = unixepoch() WHERE id = $3",
+    [folder, members.length > 0 ? members.join(", ") : null, accountId],
+  );
+}
+
 export async function updateAccountCalDav(
   accountId: string,
   fields: {
@@ -294,8 +390,11 @@ export async function insertOAuthImapAccount(account: {
   oauthProvider: string;
   oauthClientId: string;
   oauthClientSecret: string | null;
+  oauthTenant?: string | null;
   imapUsername?: string | null;
   acceptInvalidCerts?: boolean;
+  authorizeAs?: string | null;
+  delegation?: string | null;
 }): Promise<void> {
   const db = await getDb();
   const encAccessToken = await encryptValue(account.accessToken);
@@ -304,8 +403,8 @@ export async function insertOAuthImapAccount(account: {
     ? await encryptValue(account.oauthClientSecret)
     : null;
   await db.execute(
-    `INSERT INTO accounts (id, email, display_name, avatar_url, access_token, refresh_token, token_expires_at, provider, imap_host, imap_port, imap_security, smtp_host, smtp_port, smtp_security, auth_method, imap_password, oauth_provider, oauth_client_id, oauth_client_secret, imap_username, accept_invalid_certs)
-     VALUES ($1, $2, $3, $4, $5, $6, $7, 'imap', $8, $9, $10, $11, $12, $13, 'oauth2', NULL, $14, $15, $16, $17, $18)`,
+    `INSERT INTO accounts (id, email, display_name, avatar_url, access_token, refresh_token, token_expires_at, provider, imap_host, imap_port, imap_security, smtp_host, smtp_port, smtp_security, auth_method, imap_password, oauth_provider, oauth_client_id, oauth_client_secret, imap_username, accept_invalid_certs, imap_authorize_as, imap_delegation, oauth_tenant)
+     VALUES ($1, $2, $3, $4, $5, $6, $7, 'imap', $8, $9, $10, $11, $12, $13, 'oauth2', NULL, $14, $15, $16, $17, $18, $19, $20, $21)`,
     [
       account.id,
       account.email,
@@ -325,6 +424,9 @@ export async function insertOAuthImapAccount(account: {
       encClientSecret,
       account.imapUsername || null,
       account.acceptInvalidCerts ? 1 : 0,
+      account.authorizeAs || null,
+      account.authorizeAs ? (account.delegation ?? null) : null,
+      account.oauthTenant?.trim() || null,
     ],
   );
 }
@@ -0,0 +1,70 @@
+import { describe, it, expect, beforeEach, vi } from "vitest";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+vi.mock("@/services/db/connection", async (importOriginal) => {
+  const actual = await importOriginal<typeof import("@/services/db/connection")>();
+  return {
+    ...actual,
+    getDb: vi.fn(),
+  };
+});
+
+import { invoke } from "@tauri-apps/api/core";
+import { getDb } from "@/services/db/connection";
+import { getAuditTarget, recordAudit, queryAuditLog } from "./auditLog";
+import { createMockDb } from "@/test/mocks";
+
+const mockDb = createMockDb();
+
+describe("auditLog service", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    vi.mocked(getDb).mockResolvedValue(
+      mockDb as unknown as Awaited<ReturnType<typeof getDb>>,
+    );
+  });
+
+  describe("getAuditTarget", () => {
+    it("uses IMAP folders and UIDs of the selected messages", async () => {
+      mockDb.select.mockResolvedValueOnce([
+        { id: "m1", imap_folder: "INBOX", imap_uid: 41 },
+        { id: "m2", imap_folder: "Archive", imap_uid: 7 },
+        { id: "m3", imap_folder: "INBOX", imap_uid: 42 },
+      ] as never);
+
+      const target = await getAuditTarget("acct-1", "t1", ["m1", "m2"]);
+
+      expect(target).toEqual({ threadId: "t1", folder: "INBOX, Archive", uids: ["41", "7"] });
+    });
+
+    it("falls back to message ids for Gmail messages", async () => {
+      mockDb.select.mockResolvedValueOnce([
+        { id: "gm1", imap_folder: null, imap_uid: null },
+      ] as never);
+
+      const target = await getAuditTarget("acct-1", "t1");
+
+      expect(target).toEqual({ threadId: "t1", folder: null, uids: ["gm1"] });
+    });
+  });
+
+  it("recordAudit stores uids as JSON", async () => {
+    await recordAudit("acct-1", "moveToFolder", { threadId: "t1", folder: "INBOX", uids: ["5"], detail: "Archive" }, "ok");
+
+    expect(mockDb.execute).toHaveBeenCalledWith(
+      expect.stringContaining("INSERT INTO operation_audit"),
+      ["acct-1", "moveToFolder", "t1", "INBOX", '["5"]', "Archive", "ok", null],
+    );
+  });
+
+  it("queryAuditLog passes the filter to the backend", async () => {
+    vi.mocked(invoke).mockResolvedValueOnce([]);
+
+    await queryAuditLog({ accountId: "acct-1", uid: "5" });
+
+    expect(invoke).toHaveBeenCalledWith("audit_query", { filter: { accountId: "acct-1", uid: "5" } });
+  });
+});
@@ -0,0 +1,98 @@
+import { invoke } from "@tauri-apps/api/core";
+import { getDb } from "./connection";
+
+export type AuditResult = "ok" | "queued" | "failed";
+
+export interface DbAuditEntry {
+  id: number;
+  account_id: string;
+  operation: string;
+  thread_id: string | null;
+  folder: string | null;
+  /** JSON array of IMAP UIDs, or provider message ids for Gmail. */
+  uids: string;
+  detail: string | null;
+  result: AuditResult;
+  error: string | null;
+  created_at: number;
+}
+
+/** Where the messages an operation touches live on the server. */
+export interface AuditTarget {
+  threadId?: string | null;
+  folder?: string | null;
+  uids: string[];
+  detail?: string | null;
+}
+
+/** Filters for `queryAuditLog`; all optional and combined with AND. */
+export interface AuditFilter {
+  accountId?: string;
+  threadId?: string;
+  folder?: string;
+  /** A single UID or message id that must appear in the entry. */
+  uid?: string;
+  operation?: string;
+  /** Unix seconds. */
+  since?: number;
+  limit?: number;
+}
+
+/**
+ * Resolve the folder and UIDs of a thread's messages, limited to
+ * `messageIds` when given. Messages without an IMAP location (Gmail) are
+ * identified by their message id. Call this before any local delete.
+ */
+export async function getAuditTarget(
+  accountId: string,
+  threadId: string,
+  messageIds: string[] = [],
+): Promise<AuditTarget> {
+  const db = await getDb();
+  const rows = await db.select<{ id: string; imap_folder: string | null; imap_uid: number | null }[]>(
+    "SELECT id, imap_folder, imap_uid FROM messages WHERE account_id = $1 AND thread_id = $2",
+    [accountId, threadId],
+  );
+  const wanted = messageIds.length > 0 ? new Set(messageIds) : null;
+  const folders = new Set<string>();
+  const uids: string[] = [];
+  for (const row of rows) {
+    if (wanted && !wanted.has(row.id)) continue;
+    if (row.imap_folder) folders.add(row.imap_folder);
+    uids.push(row.imap_uid !== null ? String(row.imap_uid) : row.id);
+  }
+  return {
+    threadId,
+    folder: folders.size > 0 ? [...folders].join(", ") : null,
+    uids: uids.length > 0 ? uids : messageIds,
+  };
+}
+
+export async function recordAudit(
+  accountId: string,
+  operation: string,
+  target: AuditTarget,
+  result: AuditResult,
+  error?: string | null,
+): Promise<void> {
+  const db = await getDb();
+  await db.execute(
+    `INSERT INTO operation_audit (account_id, operation, thread_id, folder, uids, detail, result, error)
+     VALUES ($1, $2, $3, $4, $5, $6, $7, $8)`,
+    [
+      accountId,
+      operation,
+      target.threadId ?? null,
+      target.folder ?? null,
+      JSON.stringify(target.uids),
+      target.detail ?? null,
+      result,
+      error ?? null,
+    ],
+  );
+}
+
+/** Audit entries matching `filter`, newest first. */
+export function queryAuditLog(filter: AuditFilter = {}): Promise<DbAuditEntry[]> {
+  return invoke<DbAuditEntry[]>("audit_query", { filter });
+}
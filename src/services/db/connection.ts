@@ -1,10 +1,13 @@
 import Database from "@tauri-apps/plugin-sql";
+import { getCurrentProfile } from "@/services/profiles";
 
 let db: Database | null = null;
 
 export async function getDb(): Promise<Database> {
   if (!db) {
-    db = await Database.load("sqlite:velo.db");
+    // Each profile has its own database
+    const { db_url } = await getCurrentProfile();
+    db = await Database.load(db_url);
   }
   return db;
 }
@@ -0,0 +1,74 @@
+import { describe, it, expect, beforeEach, vi } from "vitest";
+
+const { mockGetDb } = vi.hoisted(() => ({
+  mockGetDb: vi.fn(),
+}));
+
+vi.mock("@/services/db/connection", async (importOriginal) => {
+  const actual = await importOriginal<typeof import("@/services/db/connection")>();
+  return {
+    ...actual,
+    getDb: mockGetDb,
+  };
+});
+
+import { getDb } from "@/services/db/connection";
+import {
+  createContactGroup,
+  resolveContactGroups,
+  setContactGroupMembers,
+} from "./contactGroups";
+import { createMockDb } from "@/test/mocks";
+
+const mockDb = createMockDb();
+
+describe("contactGroups service", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    vi.mocked(getDb).mockResolvedValue(
+      mockDb as unknown as Awaited<ReturnType<typeof getDb>>,
+    );
+  });
+
+  it("creates a group and its normalized, de-duplicated members in a transaction", async () => {
+    const id = await createContactGroup(" Team ", ["A@Example.com", "a@example.com", "b@example.com"]);
+
+    const calls = mockDb.execute.mock.calls as unknown as [string, unknown[]][];
+    expect(calls[0]![0]).toBe("BEGIN TRANSACTION");
+    expect(calls[1]).toEqual([
+      "INSERT INTO contact_groups (id, name) VALUES ($1, $2)",
+      [id, "Team"],
+    ]);
+    const members = calls.filter(([sql]) => sql.includes("contact_group_members"));
+    expect(members.map(([, params]) => params)).toEqual([
+      [id, "a@example.com"],
+      [id, "b@example.com"],
+    ]);
+    expect(calls[calls.length - 1]![0]).toBe("COMMIT");
+  });
+
+  it("replaces members", async () => {
+    await setContactGroupMembers("g1", ["c@example.com"]);
+
+    expect(mockDb.execute).toHaveBeenCalledWith(
+      "DELETE FROM contact_group_members WHERE group_id = $1",
+      ["g1"],
+    );
+    expect(mockDb.execute).toHaveBeenCalledWith(
+      expect.stringContaining("INSERT OR IGNORE INTO contact_group_members"),
+      ["g1", "c@example.com"],
+    );
+  });
+
+  it("resolves group names to member addresses", async () => {
+    mockDb.select.mockResolvedValueOnce([
+      { email: "a@example.com", display_name: "A" },
+      { email: "b@example.com", display_name: null },
+    ] as never);
+
+    const groups = await resolveContactGroups(["Team", "Team"]);
+
+    expect(groups).toEqual({ Team: ["a@example.com", "b@example.com"] });
+    expect(mockDb.select).toHaveBeenCalledTimes(1);
+  });
+});
@@ -0,0 +1,146 @@
+import { getDb, withTransaction } from "./connection";
+import { normalizeEmail } from "@/utils/emailUtils";
+import { parseGroupRecipient } from "@/utils/contactGroups";
+
+export interface DbContactGroup {
+  id: string;
+  name: string;
+  member_count: number;
+}
+
+export interface ContactGroupMember {
+  email: string;
+  display_name: string | null;
+}
+
+const GROUP_SELECT = `SELECT g.id, g.name, COUNT(m.email) as member_count
+   FROM contact_groups g
+   LEFT JOIN contact_group_members m ON m.group_id = g.id`;
+
+/**
+ * Get all contact groups with their member counts, by name.
+ */
+export async function getContactGroups(): Promise<DbContactGroup[]> {
+  const db = await getDb();
+  return db.select<DbContactGroup[]>(
+    `${GROUP_SELECT} GROUP BY g.id ORDER BY g.name ASC`,
+  );
+}
+
+/**
+ * Search contact groups by name for autocomplete.
+ */
+export async function searchContactGroups(
+  query: string,
+  limit = 5,
+): Promise<DbContactGroup[]> {
+  const db = await getDb();
+  return db.select<DbContactGroup[]>(
+    `${GROUP_SELECT} WHERE g.name LIKE $1 GROUP BY g.id ORDER BY g.name ASC LIMIT $2`,
+    [`%${query}%`, limit],
+  );
+}
+
+async function insertMembers(
+  db: Awaited<ReturnType<typeof getDb>>,
+  groupId: string,
+  emails: string[],
+): Promise<void> {
+  for (const email of new Set(emails.map(normalizeEmail))) {
+    await db.execute(
+      "INSERT OR IGNORE INTO contact_group_members (group_id, email) VALUES ($1, $2)",
+      [groupId, email],
+    );
+  }
+}
+
+/**
+ * Create a named group with the given member addresses. Returns its id.
+ */
+export async function createContactGroup(
+  name: string,
+  emails: string[],
+): Promise<string> {
+  const id = crypto.randomUUID();
+  await withTransaction(async (db) => {
+    await db.execute(
+      "INSERT INTO contact_groups (id, name) VALUES ($1, $2)",
+      [id, name.trim()],
+    );
+    await insertMembers(db, id, emails);
+  });
+  return id;
+}
+
+export async function renameContactGroup(id: string, name: string): Promise<void> {
+  const db = await getDb();
+  await db.execute(
+    "UPDATE contact_groups SET name = $1, updated_at = unixepoch() WHERE id = $2",
+    [name.trim(), id],
+  );
+}
+
+/**
+ * Replace a group's members.
+ */
+export async function setContactGroupMembers(
+  id: string,
+  emails: string[],
+): Promise<void> {
+  await withTransaction(async (db) => {
+    await db.execute("DELETE FROM contact_group_members WHERE group_id = $1", [id]);
+    await insertMembers(db, id, emails);
+    await db.execute(
+      "UPDATE contact_groups SET updated_at = unixepoch() WHERE id = $1",
+      [id],
+    );
+  });
+}
+
+export async function deleteContactGroup(id: string): Promise<void> {
+  const db = await getDb();
+  await db.execute("DELETE FROM contact_groups WHERE id = $1", [id]);
+}
+
+/**
+ * Members of a group by name, with display names from contacts, for showing
+ * who a group recipient will reach.
+ */
+export async function resolveContactGroup(name: string): Promise<ContactGroupMember[]> {
+  const db = await getDb();
+  return db.select<ContactGroupMember[]>(
+    `SELECT m.email, c.display_name
+     FROM contact_groups g
+     INNER JOIN contact_group_members m ON m.group_id = g.id
+     LEFT JOIN contacts c ON c.email = m.email
+     WHERE g.name = $1
+     ORDER BY COALESCE(c.display_name, m.email) ASC`,
+    [name],
+  );
+}
+
+/**
+ * Member addresses of the named groups, keyed by group name (for expanding
+ * group recipients when a message is built).
+ */
+export async function resolveContactGroups(
+  names: string[],
+): Promise<Record<string, string[]>> {
+  const groups: Record<string, string[]> = {};
+  for (const name of new Set(names)) {
+    groups[name] = (await resolveContactGroup(name)).map((m) => m.email);
+  }
+  return groups;
+}
+
+/**
+ * Members of every group token among `recipients` (see utils/contactGroups).
+ */
+export async function resolveRecipientGroups(
+  recipients: string[],
+): Promise<Record<string, string[]>> {
+  const names = recipients
+    .map(parseGroupRecipient)
+    .filter((name): name is string => name !== null);
+  return resolveContactGroups(names);
+}
@@ -11,7 +11,7 @@ vi.mock("@/services/db/connection", async (importOriginal) => {
 import { getDb } from "@/services/db/connection";
 import {
   getAllContacts, updateContact, deleteContact,
-  updateContactNotes, getAttachmentsFromContact,
+  updateContactNotes, saveContactDetails, getAttachmentsFromContact,
   getContactsFromSameDomain, getLatestAuthResult,
 } from "./contacts";
 import { createMockDb } from "@/test/mocks";
@@ -86,6 +86,33 @@ describe("contacts service", () => {
     });
   });
 
+  describe("saveContactDetails", () => {
+    it("stores lists as JSON and keeps details already saved", async () => {
+      await saveContactDetails("Alice@Example.com", {
+        displayName: "Alice",
+        avatarUrl: null,
+        phones: ["+1 555 010 2030"],
+        urls: [],
+        organization: "Example Corp",
+        jobTitle: null,
+      });
+
+      const [sql, params] = mockDb.execute.mock.calls[0] as unknown as [string, unknown[]];
+      expect(sql).toContain("ON CONFLICT(email) DO UPDATE");
+      expect(sql).toContain("phones = COALESCE(phones, $5)");
+      expect(params).toEqual([
+        expect.any(String),
+        "alice@example.com",
+        "Alice",
+        null,
+        '["+1 555 010 2030"]',
+        null,
+        "Example Corp",
+        null,
+      ]);
+    });
+  });
+
   describe("getAttachmentsFromContact", () => {
     it("queries with correct JOIN and default limit", async () => {
       await getAttachmentsFromContact("sender@test.com");
@@ -9,6 +9,12 @@ export interface DbContact {
   frequency: number;
   last_contacted_at: number | null;
   notes: string | null;
+  /** JSON array of phone numbers */
+  phones: string | null;
+  /** JSON array of web links */
+  urls: string | null;
+  organization: string | null;
+  job_title: string | null;
 }
 
 export interface ContactAttachment {
@@ -160,6 +166,47 @@ export async function updateContactAvatar(
   );
 }
 
+/**
+ * Add a contact with the details found in a message (vCard or signature),
+ * or fill in an existing one. Details already saved are kept.
+ */
+export async function saveContactDetails(
+  email: string,
+  details: {
+    displayName: string | null;
+    avatarUrl: string | null;
+    phones: string[];
+    urls: string[];
+    organization: string | null;
+    jobTitle: string | null;
+  },
+): Promise<void> {
+  const db = await getDb();
+  const list = (values: string[]) => (values.length > 0 ? JSON.stringify(values) : null);
+  await db.execute(
+    `INSERT INTO contacts (id, email, display_name, avatar_url, phones, urls, organization, job_title, last_contacted_at)
+     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, unixepoch())
+     ON CONFLICT(email) DO UPDATE SET
+       display_name = COALESCE(display_name, $3),
+       avatar_url = COALESCE(avatar_url, $4),
+       phones = COALESCE(phones, $5),
+       urls = COALESCE(urls, $6),
+       organization = COALESCE(organization, $7),
+       job_title = COALESCE(job_title, $8),
+       updated_at = unixepoch()`,
+    [
+      crypto.randomUUID(),
+      normalizeEmail(email),
+      details.displayName,
+      details.avatarUrl,
+      list(details.phones),
+      list(details.urls),
+      details.organization,
+      details.jobTitle,
+    ],
+  );
+}
+
 /**
  * Update a contact's notes by email.
  */
@@ -0,0 +1,60 @@
+import { describe, it, expect, beforeEach, vi } from "vitest";
+
+const { mockGetDb } = vi.hoisted(() => ({
+  mockGetDb: vi.fn(),
+}));
+
+vi.mock("@/services/db/connection", async (importOriginal) => {
+  const actual = await importOriginal<typeof import("@/services/db/connection")>();
+  return {
+    ...actual,
+    getDb: mockGetDb,
+  };
+});
+
+import { getDb } from "@/services/db/connection";
+import { deleteFeed, getSeenFeedItems, isFeedThreadId } from "./feeds";
+import { createMockDb } from "@/test/mocks";
+
+const mockDb = createMockDb();
+
+describe("feeds service", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    vi.mocked(getDb).mockResolvedValue(
+      mockDb as unknown as Awaited<ReturnType<typeof getDb>>,
+    );
+  });
+
+  it("recognizes feed entry threads", () => {
+    expect(isFeedThreadId("feed-f1-1a2b")).toBe(true);
+    expect(isFeedThreadId("imap-thread-123")).toBe(false);
+  });
+
+  it("looks up seen entries by guid", async () => {
+    mockDb.select.mockResolvedValueOnce([{ guid: "a" }] as never);
+
+    const seen = await getSeenFeedItems("f1", ["a", "b"]);
+
+    expect(seen).toEqual(new Set(["a"]));
+    expect(mockDb.select).toHaveBeenCalledWith(
+      expect.stringContaining("guid IN ($2, $3)"),
+      ["f1", "a", "b"],
+    );
+  });
+
+  it("skips the query when there are no entries", async () => {
+    await expect(getSeenFeedItems("f1", [])).resolves.toEqual(new Set());
+    expect(mockDb.select).not.toHaveBeenCalled();
+  });
+
+  it("deletes the feed's threads with the feed", async () => {
+    await deleteFeed("acc-1", "f1");
+
+    expect(mockDb.execute).toHaveBeenCalledWith(
+      expect.stringContaining("DELETE FROM threads"),
+      ["acc-1", "feed-f1-%"],
+    );
+    expect(mockDb.execute).toHaveBeenCalledWith("DELETE FROM feeds WHERE id = $1", ["f1"]);
+  });
+});
@@ -0,0 +1,118 @@
+import { getDb } from "./connection";
+
+export interface DbFeed {
+  id: string;
+  account_id: string;
+  url: string;
+  title: string | null;
+  site_url: string | null;
+  etag: string | null;
+  last_modified: string | null;
+  last_checked_at: number | null;
+  last_error: string | null;
+  created_at: number;
+}
+
+/** Label of the threads feed entries are stored in. */
+export const FEEDS_LABEL_ID = "FEEDS";
+
+/** Feed entry threads are `feed-<feed id>-<hash of the entry guid>`. */
+export function isFeedThreadId(threadId: string): boolean {
+  return threadId.startsWith("feed-");
+}
+
+export async function getFeedsForAccount(accountId: string): Promise<DbFeed[]> {
+  const db = await getDb();
+  return db.select<DbFeed[]>(
+    "SELECT * FROM feeds WHERE account_id = $1 ORDER BY COALESCE(title, url) COLLATE NOCASE",
+    [accountId],
+  );
+}
+
+export async function insertFeed(accountId: string, url: string): Promise<string> {
+  const db = await getDb();
+  const id = crypto.randomUUID();
+  await db.execute(
+    "INSERT INTO feeds (id, account_id, url) VALUES ($1, $2, $3)",
+    [id, accountId, url.trim()],
+  );
+  return id;
+}
+
+/** Record the outcome of a poll. The feed's title is kept once known. */
+export async function updateFeedStatus(
+  id: string,
+  status: {
+    title?: string | null;
+    siteUrl?: string | null;
+    etag?: string | null;
+    lastModified?: string | null;
+    error: string | null;
+    checkedAt: number;
+  },
+): Promise<void> {
+  const db = await getDb();
+  await db.execute(
+    `UPDATE feeds SET title = COALESCE($1, title), site_url = COALESCE($2, site_url),
+       etag = COALESCE($3, etag), last_modified = COALESCE($4, last_modified),
+       last_error = $5, last_checked_at = $6
+     WHERE id = $7`,
+    [
+      status.title ?? null,
+      status.siteUrl ?? null,
+      status.etag ?? null,
+      status.lastModified ?? null,
+      status.error,
+      status.checkedAt,
+      id,
+    ],
+  );
+}
+
+/** Remove a feed and the entries it put in the Feeds folder. */
+export async function deleteFeed(accountId: string, id: string): Promise<void> {
+  const db = await getDb();
+  await db.execute(
+    "DELETE FROM threads WHERE account_id = $1 AND id LIKE $2",
+    [accountId, `feed-${id}-%`],
+  );
+  await db.execute("DELETE FROM feeds WHERE id = $1", [id]);
+}
+
+/**
+ * Which of `guids` have already been delivered. Entries stay seen after
+ * they're deleted, so they don't come back on the next poll.
+ */
+export async function getSeenFeedItems(
+  feedId: string,
+  guids: string[],
+): Promise<Set<string>> {
+  if (guids.length === 0) return new Set();
+  const db = await getDb();
+  const placeholders = guids.map((_, i) => `$${i + 2}`).join(", ");
+  const rows = await db.select<{ guid: string }[]>(
+    `SELECT guid FROM feed_items WHERE feed_id = $1 AND guid IN (${placeholders})`,
+    [feedId, ...guids],
+  );
+  return new Set(rows.map((r) => r.guid));
+}
+
+export async function markFeedItemSeen(feedId: string, guid: string): Promise<void> {
+  const db = await getDb();
+  await db.execute(
+    "INSERT OR IGNORE INTO feed_items (feed_id, guid) VALUES ($1, $2)",
+    [feedId, guid],
+  );
+}
+
+/** Take an archived, trashed or moved feed entry out of the Feeds folder. */
+export async function removeFromFeedsFolder(
+  accountId: string,
+  threadId: string,
+): Promise<void> {
+  const db = await getDb();
+  await db.execute(
+    "DELETE FROM thread_labels WHERE account_id = $1 AND thread_id = $2 AND label_id = $3",
+    [accountId, threadId, FEEDS_LABEL_ID],
+  );
+}
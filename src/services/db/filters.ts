@@ -6,6 +6,8 @@ export interface FilterCriteria {
   subject?: string;
   body?: string;
   hasAttachment?: boolean;
+  /** Color category name (IMAP keyword, see `colorCategories.ts`) */
+  category?: string;
 }
 
 export interface FilterActions {
@@ -14,6 +16,8 @@ export interface FilterActions {
   star?: boolean;
   markRead?: boolean;
   trash?: boolean;
+  /** POST a JSON summary of each matched message here (see `filters/webhook.ts`) */
+  webhookUrl?: string;
 }
 
 export interface DbFilterRule {
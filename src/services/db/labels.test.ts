@@ -9,7 +9,7 @@ vi.mock("@/services/db/connection", async (importOriginal) => {
 });
 
 import { getDb } from "@/services/db/connection";
-import { updateLabelSortOrder } from "./labels";
+import { deleteLabelsForFolder, updateLabelSortOrder } from "./labels";
 import { createMockDb } from "@/test/mocks";
 
 const mockDb = createMockDb();
@@ -46,4 +46,22 @@ describe("labels service", () => {
       expect(mockDb.execute).not.toHaveBeenCalled();
     });
   });
+
+  describe("deleteLabelsForFolder", () => {
+    it("removes thread assignments before the folder's labels", async () => {
+      await deleteLabelsForFolder("acc-1", "Work/Old");
+
+      expect(mockDb.execute).toHaveBeenCalledTimes(2);
+      expect(mockDb.execute).toHaveBeenNthCalledWith(
+        1,
+        expect.stringContaining("DELETE FROM thread_labels"),
+        ["acc-1", "Work/Old"],
+      );
+      expect(mockDb.execute).toHaveBeenNthCalledWith(
+        2,
+        expect.stringContaining("DELETE FROM labels WHERE account_id = $1 AND imap_folder_path = $2"),
+        ["acc-1", "Work/Old"],
+      );
+    });
+  });
 });
@@ -72,6 +72,26 @@ export async function deleteLabel(
   );
 }
 
+/**
+ * Delete the labels mapped to an IMAP folder that no longer exists, along
+ * with their thread assignments.
+ */
+export async function deleteLabelsForFolder(
+  accountId: string,
+  folderPath: string,
+): Promise<void> {
+  const db = await getDb();
+  await db.execute(
+    `DELETE FROM thread_labels WHERE account_id = $1 AND label_id IN
+       (SELECT id FROM labels WHERE account_id = $1 AND imap_folder_path = $2)`,
+    [accountId, folderPath],
+  );
+  await db.execute(
+    "DELETE FROM labels WHERE account_id = $1 AND imap_folder_path = $2",
+    [accountId, folderPath],
+  );
+}
+
 export async function updateLabelSortOrder(
   accountId: string,
   labelOrders: { id: string; sortOrder: number }[],
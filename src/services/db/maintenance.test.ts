@@ -0,0 +1,48 @@
+import { describe, it, expect, beforeEach, vi } from "vitest";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+vi.mock("./settings", () => ({
+  getSetting: vi.fn(),
+  setSetting: vi.fn(),
+}));
+
+import { invoke } from "@tauri-apps/api/core";
+import { getSetting, setSetting } from "./settings";
+import { runLightMaintenanceIfDue } from "./maintenance";
+
+const DAY = 24 * 60 * 60 * 1000;
+
+describe("runLightMaintenanceIfDue", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    vi.mocked(invoke).mockResolvedValue({
+      size_before: 2048,
+      size_after: 2048,
+      integrity_errors: [],
+      reindexed: false,
+      fts_rebuilt: false,
+      vacuumed: false,
+    });
+  });
+
+  it("runs light maintenance once a day", async () => {
+    const now = 10 * DAY;
+    vi.mocked(getSetting).mockResolvedValue(String(now - DAY));
+
+    await runLightMaintenanceIfDue(now);
+
+    expect(setSetting).toHaveBeenCalledWith("db_maintenance_last_run", String(now));
+    expect(invoke).toHaveBeenCalledWith("db_maintenance", { full: false });
+  });
+
+  it("skips when it ran recently", async () => {
+    const now = 10 * DAY;
+    vi.mocked(getSetting).mockResolvedValue(String(now - DAY / 2));
+
+    await runLightMaintenanceIfDue(now);
+
+    expect(invoke).not.toHaveBeenCalled();
+  });
+});
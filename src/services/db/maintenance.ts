@@ -0,0 +1,51 @@
+import { invoke } from "@tauri-apps/api/core";
+import { createBackgroundChecker, type BackgroundChecker } from "../backgroundCheckers";
+import { getSetting, setSetting } from "./settings";
+
+/** Result of `db_maintenance`; sizes are in bytes. */
+export interface MaintenanceReport {
+  size_before: number;
+  size_after: number;
+  /** Problems still found after repairs; empty when the database is healthy. */
+  integrity_errors: string[];
+  reindexed: boolean;
+  fts_rebuilt: boolean;
+  vacuumed: boolean;
+}
+
+const LAST_RUN_KEY = "db_maintenance_last_run";
+const LIGHT_INTERVAL_MS = 24 * 60 * 60 * 1000;
+
+let checker: BackgroundChecker | null = null;
+
+/**
+ * Check and clean up the database. A full run also vacuums the file, which
+ * can take a while on a large mailbox; a light one only runs quick checks.
+ */
+export function runDbMaintenance(full: boolean): Promise<MaintenanceReport> {
+  return invoke<MaintenanceReport>("db_maintenance", { full });
+}
+
+/** Light maintenance, at most once a day across restarts. */
+export async function runLightMaintenanceIfDue(now: number = Date.now()): Promise<void> {
+  const lastRun = parseInt((await getSetting(LAST_RUN_KEY)) ?? "0", 10);
+  if (now - lastRun < LIGHT_INTERVAL_MS) return;
+
+  // Recorded first so a run that keeps failing doesn't retry every hour
+  await setSetting(LAST_RUN_KEY, String(now));
+  const report = await runDbMaintenance(false);
+  if (report.integrity_errors.length > 0) {
+    console.warn("[dbMaintenance] Integrity problems remain:", report.integrity_errors);
+  }
+}
+
+export function startDbMaintenance(): void {
+  if (checker) return;
+  checker = createBackgroundChecker("DbMaintenance", () => runLightMaintenanceIfDue(), 60 * 60 * 1000);
+  checker.start();
+}
+
+export function stopDbMaintenance(): void {
+  checker?.stop();
+  checker = null;
+}
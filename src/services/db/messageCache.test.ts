@@ -0,0 +1,52 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { invoke } from "@tauri-apps/api/core";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+import { getCachedMessage, queryCachedMessages } from "./messageCache";
+
+const mockInvoke = vi.mocked(invoke);
+
+describe("messageCache", () => {
+  beforeEach(() => {
+    mockInvoke.mockReset();
+  });
+
+  it("queries a folder page", async () => {
+    const page = { messages: [], total: 0, uidvalidity: null, last_uid: null, last_sync_at: null };
+    mockInvoke.mockResolvedValue(page);
+
+    await expect(queryCachedMessages("acct-1", "INBOX", 50, 25)).resolves.toEqual(page);
+    expect(mockInvoke).toHaveBeenCalledWith("cache_query_messages", {
+      accountId: "acct-1",
+      folder: "INBOX",
+      offset: 50,
+      limit: 25,
+    });
+  });
+
+  it("leaves the page bounds to the backend when omitted", async () => {
+    mockInvoke.mockResolvedValue({ messages: [], total: 0 });
+
+    await queryCachedMessages("acct-1", "INBOX");
+    expect(mockInvoke).toHaveBeenCalledWith("cache_query_messages", {
+      accountId: "acct-1",
+      folder: "INBOX",
+      offset: null,
+      limit: null,
+    });
+  });
+
+  it("gets one message by UID", async () => {
+    mockInvoke.mockResolvedValue(null);
+
+    await expect(getCachedMessage("acct-1", "INBOX", 42)).resolves.toBeNull();
+    expect(mockInvoke).toHaveBeenCalledWith("cache_get_message", {
+      accountId: "acct-1",
+      folder: "INBOX",
+      uid: 42,
+    });
+  });
+});
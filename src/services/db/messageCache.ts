@@ -0,0 +1,65 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * Read-only view of the local message cache from the backend (`store.rs`):
+ * a folder page or a single message by UID, straight from SQLite, so a
+ * folder can be shown offline before sync has talked to the server.
+ */
+
+export interface CachedMessage {
+  id: string;
+  thread_id: string;
+  uid: number | null;
+  folder: string | null;
+  from_address: string | null;
+  from_name: string | null;
+  to_addresses: string | null;
+  cc_addresses: string | null;
+  subject: string | null;
+  snippet: string | null;
+  /** Milliseconds since the epoch */
+  date: number;
+  is_read: boolean;
+  is_starred: boolean;
+  /** Pinned messages come first in a folder page */
+  is_pinned: boolean;
+  /** Whether the body has been downloaded */
+  body_cached: boolean;
+  /** Only set by `getCachedMessage` */
+  body_html: string | null;
+  body_text: string | null;
+}
+
+export interface CachedPage {
+  messages: CachedMessage[];
+  /** Cached messages in the whole folder */
+  total: number;
+  /** Null until the folder has been synced once */
+  uidvalidity: number | null;
+  last_uid: number | null;
+  last_sync_at: number | null;
+}
+
+/** A page of `folder`'s cached messages, pinned first, then newest first. */
+export function queryCachedMessages(
+  accountId: string,
+  folder: string,
+  offset?: number,
+  limit?: number,
+): Promise<CachedPage> {
+  return invoke<CachedPage>("cache_query_messages", {
+    accountId,
+    folder,
+    offset: offset ?? null,
+    limit: limit ?? null,
+  });
+}
+
+/** The cached message with `uid` in `folder`, or null if it hasn't been synced. */
+export function getCachedMessage(
+  accountId: string,
+  folder: string,
+  uid: number,
+): Promise<CachedMessage | null> {
+  return invoke<CachedMessage | null>("cache_get_message", { accountId, folder, uid });
+}
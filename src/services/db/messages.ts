@@ -28,6 +28,17 @@ export interface DbMessage {
   in_reply_to_header: string | null;
   imap_uid: number | null;
   imap_folder: string | null;
+  parse_failed: number;
+  delivered_to: string | null;
+  list_id: string | null;
+  list_post: string | null;
+  list_archive: string | null;
+  is_from_me: number;
+  mail_followup_to: string | null;
+  /** Pinned to the top of its folder (local; see `messagePins.ts`) */
+  is_pinned: number;
+  /** Space-separated IMAP keywords, e.g. color categories (see `colorCategories.ts`) */
+  keywords: string | null;
 }
 
 export async function getMessagesForThread(
@@ -41,6 +52,78 @@ export async function getMessagesForThread(
   );
 }
 
+export async function getMessage(
+  accountId: string,
+  messageId: string,
+): Promise<DbMessage | null> {
+  const db = await getDb();
+  const rows = await db.select<DbMessage[]>(
+    "SELECT * FROM messages WHERE account_id = $1 AND id = $2",
+    [accountId, messageId],
+  );
+  return rows[0] ?? null;
+}
+
+/** Date of the oldest synced message for an account, or null if none. */
+export async function getOldestMessageDate(
+  accountId: string,
+): Promise<number | null> {
+  const db = await getDb();
+  const rows = await db.select<{ oldest: number | null }[]>(
+    "SELECT MIN(date) as oldest FROM messages WHERE account_id = $1",
+    [accountId],
+  );
+  return rows[0]?.oldest ?? null;
+}
+
+/** Store a body fetched on demand for a message synced headers-only. */
+export async function cacheMessageBody(
+  accountId: string,
+  messageId: string,
+  bodyHtml: string | null,
+  bodyText: string | null,
+): Promise<void> {
+  const db = await getDb();
+  await db.execute(
+    "UPDATE messages SET body_html = $1, body_text = $2, body_cached = 1 WHERE account_id = $3 AND id = $4",
+    [bodyHtml, bodyText, accountId, messageId],
+  );
+}
+
+/**
+ * Drop the cached HTML bodies of messages dated before `before` (ms),
+ * except starred ones and feed entries, which can't be fetched again. The
+ * plain text stays so search still finds them; `getConversation` fetches
+ * the body again when the thread is opened.
+ * Returns the number of messages evicted.
+ */
+export async function evictMessageBodies(before: number): Promise<number> {
+  const db = await getDb();
+  const result = await db.execute(
+    "UPDATE messages SET body_html = NULL, body_cached = 0 WHERE body_cached = 1 AND is_starred = 0 AND date < $1 AND id NOT LIKE 'feed-%'",
+    [before],
+  );
+  return result.rowsAffected;
+}
+
+/**
+ * Most recent List-Archive URL among the messages of a thread, or null if
+ * the thread isn't from a mailing list with an archive.
+ */
+export async function getThreadListArchive(
+  accountId: string,
+  threadId: string,
+): Promise<string | null> {
+  const db = await getDb();
+  const rows = await db.select<{ list_archive: string }[]>(
+    `SELECT list_archive FROM messages
+     WHERE account_id = $1 AND thread_id = $2 AND list_archive IS NOT NULL
+     ORDER BY date DESC LIMIT 1`,
+    [accountId, threadId],
+  );
+  return rows[0]?.list_archive ?? null;
+}
+
 export async function upsertMessage(msg: {
   id: string;
   accountId: string;
@@ -68,11 +151,19 @@ export async function upsertMessage(msg: {
   inReplyToHeader?: string | null;
   imapUid?: number | null;
   imapFolder?: string | null;
+  parseFailed?: boolean;
+  deliveredTo?: string | null;
+  listId?: string | null;
+  listPost?: string | null;
+  listArchive?: string | null;
+  isFromMe?: boolean;
+  mailFollowupTo?: string | null;
+  keywords?: string[] | null;
 }): Promise<void> {
   const db = await getDb();
   await db.execute(
-    `INSERT INTO messages (id, account_id, thread_id, from_address, from_name, to_addresses, cc_addresses, bcc_addresses, reply_to, subject, snippet, date, is_read, is_starred, body_html, body_text, body_cached, raw_size, internal_date, list_unsubscribe, list_unsubscribe_post, auth_results, message_id_header, references_header, in_reply_to_header, imap_uid, imap_folder)
-     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27)
+    `INSERT INTO messages (id, account_id, thread_id, from_address, from_name, to_addresses, cc_addresses, bcc_addresses, reply_to, subject, snippet, date, is_read, is_starred, body_html, body_text, body_cached, raw_size, internal_date, list_unsubscribe, list_unsubscribe_post, auth_results, message_id_header, references_header, in_reply_to_header, imap_uid, imap_folder, parse_failed, delivered_to, list_id, list_post, list_archive, is_from_me, mail_followup_to, keywords)
+     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29, $30, $31, $32, $33, $34, $35)
      ON CONFLICT(account_id, id) DO UPDATE SET
        from_address = $4, from_name = $5, to_addresses = $6, cc_addresses = $7,
        bcc_addresses = $8, reply_to = $9, subject = $10, snippet = $11,
@@ -83,7 +174,12 @@ export async function upsertMessage(msg: {
        auth_results = $22, message_id_header = COALESCE($23, message_id_header),
        references_header = COALESCE($24, references_header),
        in_reply_to_header = COALESCE($25, in_reply_to_header),
-       imap_uid = COALESCE($26, imap_uid), imap_folder = COALESCE($27, imap_folder)`,
+       imap_uid = COALESCE($26, imap_uid), imap_folder = COALESCE($27, imap_folder),
+       parse_failed = $28, delivered_to = COALESCE($29, delivered_to),
+       list_id = COALESCE($30, list_id), list_post = COALESCE($31, list_post),
+       list_archive = COALESCE($32, list_archive), is_from_me = $33,
+       mail_followup_to = COALESCE($34, mail_followup_to),
+       keywords = COALESCE($35, keywords)`,
     [
       msg.id,
       msg.accountId,
@@ -112,6 +208,14 @@ export async function upsertMessage(msg: {
       msg.inReplyToHeader ?? null,
       msg.imapUid ?? null,
       msg.imapFolder ?? null,
+      msg.parseFailed ? 1 : 0,
+      msg.deliveredTo ?? null,
+      msg.listId ?? null,
+      msg.listPost ?? null,
+      msg.listArchive ?? null,
+      msg.isFromMe ? 1 : 0,
+      msg.mailFollowupTo ?? null,
+      msg.keywords ? msg.keywords.join(" ") : null,
     ],
   );
 }
@@ -144,6 +248,84 @@ export async function updateMessageThreadIds(
   }
 }
 
+export async function setMessagePinned(
+  accountId: string,
+  messageId: string,
+  pinned: boolean,
+): Promise<void> {
+  const db = await getDb();
+  await db.execute(
+    "UPDATE messages SET is_pinned = $1 WHERE account_id = $2 AND id = $3",
+    [pinned ? 1 : 0, accountId, messageId],
+  );
+}
+
+export async function setMessageKeywords(
+  accountId: string,
+  messageId: string,
+  keywords: string[],
+): Promise<void> {
+  const db = await getDb();
+  await db.execute(
+    "UPDATE messages SET keywords = $1 WHERE account_id = $2 AND id = $3",
+    [keywords.join(" "), accountId, messageId],
+  );
+}
+
+/** Synced messages in an IMAP folder with their UIDs and keywords. */
+export async function getFolderMessageKeywords(
+  accountId: string,
+  folder: string,
+): Promise<{ id: string; imap_uid: number; keywords: string | null }[]> {
+  const db = await getDb();
+  return db.select<{ id: string; imap_uid: number; keywords: string | null }[]>(
+    "SELECT id, imap_uid, keywords FROM messages WHERE account_id = $1 AND imap_folder = $2 AND imap_uid IS NOT NULL",
+    [accountId, folder],
+  );
+}
+
+/**
+ * Where a thread's messages live on the IMAP server, by folder. Limited to
+ * `messageIds` when given; messages without a UID are left out.
+ */
+export async function getThreadImapLocations(
+  accountId: string,
+  threadId: string,
+  messageIds: string[] = [],
+): Promise<{ folder: string; uids: number[] }[]> {
+  const db = await getDb();
+  const rows = await db.select<{ id: string; imap_folder: string; imap_uid: number }[]>(
+    "SELECT id, imap_folder, imap_uid FROM messages WHERE account_id = $1 AND thread_id = $2 AND imap_folder IS NOT NULL AND imap_uid IS NOT NULL",
+    [accountId, threadId],
+  );
+  const wanted = messageIds.length > 0 ? new Set(messageIds) : null;
+  const byFolder = new Map<string, number[]>();
+  for (const row of rows) {
+    if (wanted && !wanted.has(row.id)) continue;
+    const uids = byFolder.get(row.imap_folder);
+    if (uids) uids.push(row.imap_uid);
+    else byFolder.set(row.imap_folder, [row.imap_uid]);
+  }
+  return [...byFolder].map(([folder, uids]) => ({ folder, uids }));
+}
+
+/**
+ * Resolve the thread containing an IMAP message, identified by folder + UID.
+ * Used by message pop-out windows opened from the backend.
+ */
+export async function getThreadIdByImapUid(
+  accountId: string,
+  folder: string,
+  uid: number,
+): Promise<string | null> {
+  const db = await getDb();
+  const rows = await db.select<{ thread_id: string }[]>(
+    "SELECT thread_id FROM messages WHERE account_id = $1 AND imap_folder = $2 AND imap_uid = $3 LIMIT 1",
+    [accountId, folder, uid],
+  );
+  return rows[0]?.thread_id ?? null;
+}
+
 export async function deleteAllMessagesForAccount(
   accountId: string,
 ): Promise<void> {
@@ -849,6 +849,237 @@ const MIGRATIONS = [
         ('ai_behavior_suggestions_enabled', 'true');
     `,
   },
+  {
+    version: 25,
+    description: "Flag messages whose MIME structure could not be parsed",
+    sql: `ALTER TABLE messages ADD COLUMN parse_failed INTEGER DEFAULT 0;`,
+  },
+  {
+    version: 26,
+    description: "Delegated mailbox access for IMAP accounts",
+    sql: `
+      ALTER TABLE accounts ADD COLUMN imap_authorize_as TEXT;
+      ALTER TABLE accounts ADD COLUMN imap_delegation TEXT;
+    `,
+  },
+  {
+    version: 27,
+    description: "Envelope recipient of messages for alias-aware replies",
+    sql: `ALTER TABLE messages ADD COLUMN delivered_to TEXT;`,
+  },
+  {
+    version: 28,
+    description: "Mailing list headers for reply-to-list and archive links",
+    sql: `
+      ALTER TABLE messages ADD COLUMN list_id TEXT;
+      ALTER TABLE messages ADD COLUMN list_post TEXT;
+      ALTER TABLE messages ADD COLUMN list_archive TEXT;
+    `,
+  },
+  {
+    version: 29,
+    description: "Contact groups (distribution lists)",
+    sql: `
+      CREATE TABLE IF NOT EXISTS contact_groups (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+        created_at INTEGER DEFAULT (unixepoch()),
+        updated_at INTEGER DEFAULT (unixepoch())
+      );
+      CREATE TABLE IF NOT EXISTS contact_group_members (
+        group_id TEXT NOT NULL REFERENCES contact_groups(id) ON DELETE CASCADE,
+        email TEXT NOT NULL,
+        PRIMARY KEY (group_id, email)
+      );
+      CREATE INDEX IF NOT EXISTS idx_contact_group_members_email ON contact_group_members(email);
+    `,
+  },
+  {
+    version: 30,
+    description: "Smart folder badge count mode",
+    sql: `ALTER TABLE smart_folders ADD COLUMN count_mode TEXT DEFAULT 'unread';`,
+  },
+  {
+    version: 31,
+    description: "Operation audit log",
+    sql: `
+      CREATE TABLE IF NOT EXISTS operation_audit (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        account_id TEXT NOT NULL,
+        operation TEXT NOT NULL,
+        thread_id TEXT,
+        folder TEXT,
+        uids TEXT NOT NULL DEFAULT '[]',
+        detail TEXT,
+        result TEXT NOT NULL,
+        error TEXT,
+        created_at INTEGER DEFAULT (unixepoch())
+      );
+      CREATE INDEX IF NOT EXISTS idx_operation_audit_account ON operation_audit(account_id, created_at);
+      CREATE INDEX IF NOT EXISTS idx_operation_audit_thread ON operation_audit(account_id, thread_id);
+    `,
+  },
+  {
+    version: 32,
+    description: "Ask for the IMAP password once per session instead of storing it",
+    sql: `ALTER TABLE accounts ADD COLUMN ask_password INTEGER NOT NULL DEFAULT 0;`,
+  },
+  {
+    version: 33,
+    description: "Contact phone numbers, links, organization and title",
+    sql: `
+      ALTER TABLE contacts ADD COLUMN phones TEXT;
+      ALTER TABLE contacts ADD COLUMN urls TEXT;
+      ALTER TABLE contacts ADD COLUMN organization TEXT;
+      ALTER TABLE contacts ADD COLUMN job_title TEXT;
+    `,
+  },
+  {
+    version: 34,
+    description: "Mark messages sent from one of the user's addresses",
+    sql: `ALTER TABLE messages ADD COLUMN is_from_me INTEGER NOT NULL DEFAULT 0;`,
+  },
+  {
+    version: 35,
+    description: "Store Mail-Followup-To for reply-all",
+    sql: `ALTER TABLE messages ADD COLUMN mail_followup_to TEXT;`,
+  },
+  {
+    version: 36,
+    description: "Text snippets expanded from abbreviations while composing",
+    sql: `
+      CREATE TABLE IF NOT EXISTS snippets (
+        id TEXT PRIMARY KEY,
+        account_id TEXT REFERENCES accounts(id) ON DELETE CASCADE,
+        abbreviation TEXT NOT NULL,
+        body TEXT NOT NULL,
+        description TEXT,
+        created_at INTEGER DEFAULT (unixepoch())
+      );
+      CREATE UNIQUE INDEX IF NOT EXISTS idx_snippets_abbreviation ON snippets(IFNULL(account_id, ''), abbreviation);
+    `,
+  },
+  {
+    version: 37,
+    description: "RSS/Atom feed subscriptions",
+    sql: `
+      CREATE TABLE IF NOT EXISTS feeds (
+        id TEXT PRIMARY KEY,
+        account_id TEXT NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+        url TEXT NOT NULL,
+        title TEXT,
+        site_url TEXT,
+        etag TEXT,
+        last_modified TEXT,
+        last_checked_at INTEGER,
+        last_error TEXT,
+        created_at INTEGER DEFAULT (unixepoch()),
+        UNIQUE(account_id, url)
+      );
+      CREATE TABLE IF NOT EXISTS feed_items (
+        feed_id TEXT NOT NULL REFERENCES feeds(id) ON DELETE CASCADE,
+        guid TEXT NOT NULL,
+        seen_at INTEGER DEFAULT (unixepoch()),
+        PRIMARY KEY (feed_id, guid)
+      );
+    `,
+  },
+  {
+    version: 38,
+    description: "Microsoft OAuth tenant per account",
+    sql: `
+      ALTER TABLE accounts ADD COLUMN oauth_tenant TEXT;
+      UPDATE accounts SET oauth_tenant = 'consumers' WHERE oauth_provider = 'microsoft';
+    `,
+  },
+  {
+    version: 39,
+    description: "Per-account mail transport (IMAP/SMTP or Gmail API)",
+    sql: `
+      ALTER TABLE accounts ADD COLUMN transport TEXT NOT NULL DEFAULT 'imap';
+    `,
+  },
+  {
+    version: 40,
+    description: "Purchases detected in receipts and invoices",
+    sql: `
+      CREATE TABLE IF NOT EXISTS purchases (
+        message_id TEXT NOT NULL,
+        account_id TEXT NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+        thread_id TEXT NOT NULL,
+        kind TEXT NOT NULL,
+        vendor TEXT NOT NULL,
+        purchased_at INTEGER NOT NULL,
+        currency TEXT NOT NULL,
+        amount_cents INTEGER NOT NULL,
+        order_number TEXT,
+        created_at INTEGER DEFAULT (unixepoch()),
+        PRIMARY KEY (account_id, message_id)
+      );
+      CREATE INDEX IF NOT EXISTS idx_purchases_date ON purchases(account_id, purchased_at DESC);
+    `,
+  },
+  {
+    version: 41,
+    description: "Per-message pins",
+    sql: `
+      ALTER TABLE messages ADD COLUMN is_pinned INTEGER DEFAULT 0;
+      CREATE INDEX IF NOT EXISTS idx_messages_folder_pinned ON messages(account_id, imap_folder, is_pinned DESC, date DESC);
+    `,
+  },
+  {
+    version: 42,
+    description: "IMAP keywords (color categories)",
+    sql: `
+      ALTER TABLE messages ADD COLUMN keywords TEXT;
+    `,
+  },
+  {
+    version: 43,
+    description: "Notes on messages and threads",
+    sql: `
+      CREATE TABLE IF NOT EXISTS notes (
+        id TEXT PRIMARY KEY,
+        account_id TEXT NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+        message_id_header TEXT,
+        thread_id TEXT,
+        body TEXT NOT NULL,
+        created_at INTEGER NOT NULL DEFAULT (unixepoch()),
+        updated_at INTEGER NOT NULL DEFAULT (unixepoch()),
+        synced_at INTEGER
+      );
+      CREATE INDEX IF NOT EXISTS idx_notes_message ON notes(account_id, message_id_header);
+      CREATE INDEX IF NOT EXISTS idx_notes_thread ON notes(account_id, thread_id);
+
+      CREATE VIRTUAL TABLE IF NOT EXISTS notes_fts USING fts5(
+        body,
+        content='notes',
+        content_rowid='rowid',
+        tokenize='trigram'
+      );
+
+      CREATE TRIGGER IF NOT EXISTS notes_ai AFTER INSERT ON notes BEGIN
+        INSERT INTO notes_fts(rowid, body) VALUES (new.rowid, new.body);
+      END;
+
+      CREATE TRIGGER IF NOT EXISTS notes_ad AFTER DELETE ON notes BEGIN
+        INSERT INTO notes_fts(notes_fts, rowid, body) VALUES ('delete', old.rowid, old.body);
+      END;
+
+      CREATE TRIGGER IF NOT EXISTS notes_au AFTER UPDATE OF body ON notes BEGIN
+        INSERT INTO notes_fts(notes_fts, rowid, body) VALUES ('delete', old.rowid, old.body);
+        INSERT INTO notes_fts(rowid, body) VALUES (new.rowid, new.body);
+      END;
+    `,
+  },
+  {
+    version: 44,
+    description: "Shared team folder for assignment and status tags",
+    sql: `
+      ALTER TABLE accounts ADD COLUMN team_folder TEXT;
+      ALTER TABLE accounts ADD COLUMN team_members TEXT;
+    `,
+  },
 ];
 
 /**
@@ -0,0 +1,88 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * Private notes on messages and threads, kept in the `notes` table by the
+ * backend (`notes.rs`). `services/notes.ts` adds syncing them to the
+ * server.
+ */
+
+export interface Note {
+  id: string;
+  account_id: string;
+  /** Message-ID header (no angle brackets) of the message; null for a thread note */
+  message_id_header: string | null;
+  thread_id: string | null;
+  body: string;
+  /** Seconds since the epoch */
+  created_at: number;
+  updated_at: number;
+  /** When the note was last copied to or from the server */
+  synced_at: number | null;
+}
+
+/**
+ * Notes on a message (by Message-ID) and/or a thread, oldest first; with
+ * neither, all of the account's notes.
+ */
+export function listNotes(
+  accountId: string,
+  target: { messageIdHeader?: string | null; threadId?: string | null } = {},
+): Promise<Note[]> {
+  return invoke<Note[]>("notes_list", {
+    accountId,
+    messageIdHeader: target.messageIdHeader ?? null,
+    threadId: target.threadId ?? null,
+  });
+}
+
+/** Add a note, or change the text of note `id`. */
+export function saveNote(
+  accountId: string,
+  input: {
+    id?: string | null;
+    messageIdHeader?: string | null;
+    threadId?: string | null;
+    body: string;
+  },
+): Promise<Note> {
+  return invoke<Note>("notes_save", {
+    accountId,
+    input: {
+      id: input.id ?? null,
+      message_id_header: input.messageIdHeader ?? null,
+      thread_id: input.threadId ?? null,
+      body: input.body,
+    },
+  });
+}
+
+/** Delete a note; resolves to the deleted note, or null if there was none. */
+export function deleteNote(accountId: string, id: string): Promise<Note | null> {
+  return invoke<Note | null>("notes_delete", { accountId, id });
+}
+
+/** Notes containing `query`, most recently changed first. */
+export function searchNotes(
+  query: string,
+  accountId?: string | null,
+  limit?: number,
+): Promise<Note[]> {
+  return invoke<Note[]>("notes_search", {
+    query,
+    accountId: accountId ?? null,
+    limit: limit ?? null,
+  });
+}
+
+export function markNotesSynced(
+  accountId: string,
+  ids: string[],
+  syncedAt: number,
+): Promise<void> {
+  return invoke<void>("notes_mark_synced", { accountId, ids, syncedAt });
+}
+
+/** Store notes read from the server; resolves to how many changed. */
+export function importNotes(accountId: string, notes: Note[]): Promise<number> {
+  return invoke<number>("notes_import", { accountId, notes });
+}
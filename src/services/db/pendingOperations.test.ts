@@ -94,7 +94,7 @@ describe("pendingOperations DB service", () => {
   describe("incrementRetry", () => {
     it("increments retry count with exponential backoff", async () => {
       mockDb.select.mockResolvedValueOnce([{ retry_count: 0, max_retries: 10 }]);
-      await incrementRetry("op-1");
+      expect(await incrementRetry("op-1")).toBe(true);
       expect(mockDb.execute).toHaveBeenCalledWith(
         expect.stringContaining("retry_count = $1"),
         expect.arrayContaining([1]),
@@ -103,7 +103,7 @@ describe("pendingOperations DB service", () => {
 
     it("marks as failed when max retries reached", async () => {
       mockDb.select.mockResolvedValueOnce([{ retry_count: 9, max_retries: 10 }]);
-      await incrementRetry("op-1");
+      expect(await incrementRetry("op-1")).toBe(false);
       expect(mockDb.execute).toHaveBeenCalledWith(
         expect.stringContaining("status = 'failed'"),
         [10, "op-1"],
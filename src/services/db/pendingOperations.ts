@@ -73,14 +73,18 @@ export async function deleteOperation(id: string): Promise<void> {
 
 const BACKOFF_SCHEDULE = [60, 300, 900, 3600];
 
-export async function incrementRetry(id: string): Promise<void> {
+/**
+ * Schedule another try with backoff. Returns false when the operation has
+ * used up its retries and was marked failed instead.
+ */
+export async function incrementRetry(id: string): Promise<boolean> {
   const db = await getDb();
   const rows = await db.select<{ retry_count: number; max_retries: number }[]>(
     `SELECT retry_count, max_retries FROM pending_operations WHERE id = $1`,
     [id],
   );
   const op = rows[0];
-  if (!op) return;
+  if (!op) return false;
 
   const newCount = op.retry_count + 1;
   if (newCount >= op.max_retries) {
@@ -88,7 +92,7 @@ export async function incrementRetry(id: string): Promise<void> {
       `UPDATE pending_operations SET status = 'failed', retry_count = $1 WHERE id = $2`,
       [newCount, id],
     );
-    return;
+    return false;
   }
 
   const backoffIdx = Math.min(newCount - 1, BACKOFF_SCHEDULE.length - 1);
@@ -99,6 +103,7 @@ export async function incrementRetry(id: string): Promise<void> {
     `UPDATE pending_operations SET retry_count = $1, next_retry_at = $2 WHERE id = $3`,
     [newCount, nextRetryAt, id],
   );
+  return true;
 }
 
 export async function getPendingOpsCount(accountId?: string): Promise<number> {
@@ -0,0 +1,47 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { invoke } from "@tauri-apps/api/core";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+vi.mock("./connection", () => ({
+  getDb: vi.fn(),
+}));
+
+import { getPurchaseTotals, queryPurchases } from "./purchases";
+
+const mockInvoke = vi.mocked(invoke);
+
+describe("purchases", () => {
+  beforeEach(() => {
+    mockInvoke.mockReset();
+  });
+
+  it("queries purchases with the filter", async () => {
+    mockInvoke.mockResolvedValue({ purchases: [], total: 0 });
+
+    await queryPurchases({ accountId: "acct-1", since: 1000, vendor: "amazon" }, 0, 20);
+    expect(mockInvoke).toHaveBeenCalledWith("purchases_query", {
+      filter: {
+        account_id: "acct-1",
+        since: 1000,
+        until: null,
+        vendor: "amazon",
+        currency: null,
+      },
+      offset: 0,
+      limit: 20,
+    });
+  });
+
+  it("totals per vendor when asked", async () => {
+    mockInvoke.mockResolvedValue([]);
+
+    await getPurchaseTotals({}, true);
+    expect(mockInvoke).toHaveBeenCalledWith("purchases_totals", {
+      filter: { account_id: null, since: null, until: null, vendor: null, currency: null },
+      byVendor: true,
+    });
+  });
+});
@@ -0,0 +1,119 @@
+import { invoke } from "@tauri-apps/api/core";
+import { getDb } from "./connection";
+import type { PurchaseInfo, PurchaseKind } from "@/services/extraction/receipts";
+
+/**
+ * Purchases recognized in receipts and invoices. Sync writes them here; the
+ * backend (`purchases.rs`) lists and totals them for the expenses view.
+ */
+
+export interface Purchase {
+  account_id: string;
+  message_id: string;
+  thread_id: string;
+  kind: PurchaseKind;
+  vendor: string;
+  /** Milliseconds since the epoch */
+  purchased_at: number;
+  /** ISO 4217 code */
+  currency: string;
+  /** Hundredths of the currency unit */
+  amount_cents: number;
+  order_number: string | null;
+}
+
+/** Unset fields don't filter. */
+export interface PurchaseFilter {
+  accountId?: string;
+  /** Milliseconds, inclusive */
+  since?: number;
+  /** Milliseconds, exclusive */
+  until?: number;
+  /** Case-insensitive substring of the vendor */
+  vendor?: string;
+  currency?: string;
+}
+
+export interface PurchasePage {
+  purchases: Purchase[];
+  /** Purchases matching the filter in all pages */
+  total: number;
+}
+
+/** Spent in one currency, and by one vendor when grouped by vendor. */
+export interface PurchaseTotal {
+  currency: string;
+  vendor: string | null;
+  amount_cents: number;
+  count: number;
+}
+
+export async function upsertPurchase(
+  accountId: string,
+  messageId: string,
+  threadId: string,
+  purchase: PurchaseInfo,
+): Promise<void> {
+  const db = await getDb();
+  await db.execute(
+    `INSERT INTO purchases (account_id, message_id, thread_id, kind, vendor, purchased_at, currency, amount_cents, order_number)
+     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+     ON CONFLICT(account_id, message_id) DO UPDATE SET
+       thread_id = $3, kind = $4, vendor = $5, purchased_at = $6, currency = $7,
+       amount_cents = $8, order_number = $9`,
+    [
+      accountId,
+      messageId,
+      threadId,
+      purchase.kind,
+      purchase.vendor,
+      purchase.date,
+      purchase.currency,
+      purchase.amountCents,
+      purchase.orderNumber,
+    ],
+  );
+}
+
+/** Forget a message that was wrongly taken for a receipt. */
+export async function deletePurchase(accountId: string, messageId: string): Promise<void> {
+  const db = await getDb();
+  await db.execute(
+    "DELETE FROM purchases WHERE account_id = $1 AND message_id = $2",
+    [accountId, messageId],
+  );
+}
+
+function filterArg(filter: PurchaseFilter) {
+  return {
+    account_id: filter.accountId ?? null,
+    since: filter.since ?? null,
+    until: filter.until ?? null,
+    vendor: filter.vendor ?? null,
+    currency: filter.currency ?? null,
+  };
+}
+
+/** A page of the matching purchases, newest first. */
+export function queryPurchases(
+  filter: PurchaseFilter = {},
+  offset?: number,
+  limit?: number,
+): Promise<PurchasePage> {
+  return invoke<PurchasePage>("purchases_query", {
+    filter: filterArg(filter),
+    offset: offset ?? null,
+    limit: limit ?? null,
+  });
+}
+
+/** Totals of the matching purchases per currency, or per currency and vendor. */
+export function getPurchaseTotals(
+  filter: PurchaseFilter = {},
+  byVendor = false,
+): Promise<PurchaseTotal[]> {
+  return invoke<PurchaseTotal[]>("purchases_totals", {
+    filter: filterArg(filter),
+    byVendor,
+  });
+}
@@ -125,7 +125,7 @@ describe("smartFolders service", () => {
 
       expect(mockDb.execute).toHaveBeenCalledWith(
         expect.stringContaining("INSERT INTO smart_folders"),
-        expect.arrayContaining(["Test", "from:boss", null, "Search", null]),
+        expect.arrayContaining(["Test", "from:boss", null, "Search", null, "unread"]),
       );
     });
   });
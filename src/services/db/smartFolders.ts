@@ -1,5 +1,8 @@
 import { getDb, buildDynamicUpdate, selectFirstBy } from "./connection";
 
+/** What a smart folder's sidebar badge counts: unread messages, all matching threads, or nothing. */
+export type SmartFolderCountMode = "unread" | "total" | "none";
+
 export interface DbSmartFolder {
   id: string;
   account_id: string | null;
@@ -10,6 +13,7 @@ export interface DbSmartFolder {
   sort_order: number;
   is_default: number;
   created_at: number;
+  count_mode: SmartFolderCountMode;
 }
 
 /**
@@ -45,11 +49,12 @@ export async function insertSmartFolder(folder: {
   accountId?: string;
   icon?: string;
   color?: string;
+  countMode?: SmartFolderCountMode;
 }): Promise<string> {
   const db = await getDb();
   const id = crypto.randomUUID();
   await db.execute(
-    "INSERT INTO smart_folders (id, account_id, name, query, icon, color) VALUES ($1, $2, $3, $4, $5, $6)",
+    "INSERT INTO smart_folders (id, account_id, name, query, icon, color, count_mode) VALUES ($1, $2, $3, $4, $5, $6, $7)",
     [
       id,
       folder.accountId ?? null,
@@ -57,6 +62,7 @@ export async function insertSmartFolder(folder: {
       folder.query,
       folder.icon ?? "Search",
       folder.color ?? null,
+      folder.countMode ?? "unread",
     ],
   );
   return id;
@@ -64,13 +70,20 @@ export async function insertSmartFolder(folder: {
 
 export async function updateSmartFolder(
   id: string,
-  updates: { name?: string; query?: string; icon?: string; color?: string },
+  updates: {
+    name?: string;
+    query?: string;
+    icon?: string;
+    color?: string;
+    countMode?: SmartFolderCountMode;
+  },
 ): Promise<void> {
   const fields: [string, unknown][] = [];
   if (updates.name !== undefined) fields.push(["name", updates.name]);
   if (updates.query !== undefined) fields.push(["query", updates.query]);
   if (updates.icon !== undefined) fields.push(["icon", updates.icon]);
   if (updates.color !== undefined) fields.push(["color", updates.color]);
+  if (updates.countMode !== undefined) fields.push(["count_mode", updates.countMode]);
 
   const built = buildDynamicUpdate("smart_folders", "id", id, fields);
   if (!built) return;
@@ -0,0 +1,60 @@
+import { describe, it, expect, beforeEach, vi } from "vitest";
+
+const { mockGetDb } = vi.hoisted(() => ({
+  mockGetDb: vi.fn(),
+}));
+
+vi.mock("@/services/db/connection", async (importOriginal) => {
+  const actual = await importOriginal<typeof import("@/services/db/connection")>();
+  return {
+    ...actual,
+    getDb: mockGetDb,
+  };
+});
+
+import { getDb } from "@/services/db/connection";
+import { getSnippetsForAccount, insertSnippet, updateSnippet } from "./snippets";
+import { createMockDb } from "@/test/mocks";
+
+const mockDb = createMockDb();
+
+describe("snippets service", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    vi.mocked(getDb).mockResolvedValue(
+      mockDb as unknown as Awaited<ReturnType<typeof getDb>>,
+    );
+  });
+
+  it("includes global snippets for an account", async () => {
+    await getSnippetsForAccount("acc-1");
+
+    expect(mockDb.select).toHaveBeenCalledWith(
+      expect.stringContaining("account_id = $1 OR account_id IS NULL"),
+      ["acc-1"],
+    );
+  });
+
+  it("trims the abbreviation but keeps the body as written", async () => {
+    const id = await insertSnippet({
+      accountId: null,
+      abbreviation: " ;addr ",
+      body: "1 Main St\nSpringfield\n",
+      description: null,
+    });
+
+    expect(mockDb.execute).toHaveBeenCalledWith(
+      expect.stringContaining("INSERT INTO snippets"),
+      [id, null, ";addr", "1 Main St\nSpringfield\n", null],
+    );
+  });
+
+  it("updates only the given fields", async () => {
+    await updateSnippet("s1", { body: "Thanks!" });
+
+    expect(mockDb.execute).toHaveBeenCalledWith(
+      "UPDATE snippets SET body = $1 WHERE id = $2",
+      ["Thanks!", "s1"],
+    );
+  });
+});
@@ -0,0 +1,58 @@
+import { getDb, buildDynamicUpdate } from "./connection";
+
+export interface DbSnippet {
+  id: string;
+  account_id: string | null;
+  abbreviation: string;
+  body: string;
+  description: string | null;
+  created_at: number;
+}
+
+/**
+ * Get the snippets usable from an account: its own and the global ones
+ * (account_id IS NULL), by abbreviation.
+ */
+export async function getSnippetsForAccount(accountId: string): Promise<DbSnippet[]> {
+  const db = await getDb();
+  return db.select<DbSnippet[]>(
+    "SELECT * FROM snippets WHERE account_id = $1 OR account_id IS NULL ORDER BY abbreviation, created_at",
+    [accountId],
+  );
+}
+
+export async function insertSnippet(snippet: {
+  accountId: string | null;
+  abbreviation: string;
+  body: string;
+  description: string | null;
+}): Promise<string> {
+  const db = await getDb();
+  const id = crypto.randomUUID();
+  await db.execute(
+    "INSERT INTO snippets (id, account_id, abbreviation, body, description) VALUES ($1, $2, $3, $4, $5)",
+    [id, snippet.accountId, snippet.abbreviation.trim(), snippet.body, snippet.description],
+  );
+  return id;
+}
+
+export async function updateSnippet(
+  id: string,
+  updates: { abbreviation?: string; body?: string; description?: string | null },
+): Promise<void> {
+  const db = await getDb();
+  const fields: [string, unknown][] = [];
+  if (updates.abbreviation !== undefined) fields.push(["abbreviation", updates.abbreviation.trim()]);
+  if (updates.body !== undefined) fields.push(["body", updates.body]);
+  if (updates.description !== undefined) fields.push(["description", updates.description]);
+
+  const query = buildDynamicUpdate("snippets", "id", id, fields);
+  if (query) {
+    await db.execute(query.sql, query.params);
+  }
+}
+
+export async function deleteSnippet(id: string): Promise<void> {
+  const db = await getDb();
+  await db.execute("DELETE FROM snippets WHERE id = $1", [id]);
+}
@@ -9,7 +9,13 @@ vi.mock("@/services/db/connection", async (importOriginal) => {
 });
 
 import { getDb } from "@/services/db/connection";
-import { muteThread, unmuteThread, getMutedThreadIds, deleteAllThreadsForAccount } from "./threads";
+import {
+  muteThread,
+  unmuteThread,
+  getMutedThreadIds,
+  deleteAllThreadsForAccount,
+  markFolderThreadsRead,
+} from "./threads";
 import { createMockDb } from "@/test/mocks";
 
 const mockDb = createMockDb();
@@ -30,6 +36,30 @@ describe("threads service - deleteAllThreadsForAccount", () => {
   });
 });
 
+describe("threads service - markFolderThreadsRead", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    vi.mocked(getDb).mockResolvedValue(mockDb as unknown as Awaited<ReturnType<typeof getDb>>);
+  });
+
+  it("marks the folder's messages read, then threads with nothing unread left", async () => {
+    mockDb.execute
+      .mockResolvedValueOnce({ rowsAffected: 4 })
+      .mockResolvedValueOnce({ rowsAffected: 2 });
+
+    expect(await markFolderThreadsRead("acc-1", "INBOX")).toBe(2);
+
+    const [messagesSql, messagesParams] = mockDb.execute.mock.calls[0] as unknown as [string, unknown[]];
+    expect(messagesSql).toContain("UPDATE messages SET is_read = 1");
+    expect(messagesSql).toContain("pending_operations");
+    expect(messagesParams).toEqual(["acc-1", "INBOX"]);
+
+    const [threadsSql] = mockDb.execute.mock.calls[1] as unknown as [string];
+    expect(threadsSql).toContain("UPDATE threads SET is_read = 1");
+    expect(threadsSql).toContain("NOT EXISTS");
+  });
+});
+
 describe("threads service - mute", () => {
   beforeEach(() => {
     vi.clearAllMocks();
@@ -186,6 +186,39 @@ export async function getUnreadInboxCount(): Promise<number> {
   return rows[0]?.count ?? 0;
 }
 
+/**
+ * Mark every message in an IMAP folder read, and the threads that have no
+ * unread messages left, after the server reported no unseen messages there
+ * (read in another client). Threads with queued local changes keep their
+ * state. Returns the number of threads that became read.
+ */
+export async function markFolderThreadsRead(
+  accountId: string,
+  folder: string,
+): Promise<number> {
+  const db = await getDb();
+  const pendingThreads =
+    "SELECT resource_id FROM pending_operations WHERE account_id = $1 AND status = 'pending'";
+  await db.execute(
+    `UPDATE messages SET is_read = 1
+     WHERE account_id = $1 AND imap_folder = $2 AND is_read = 0
+       AND thread_id NOT IN (${pendingThreads})`,
+    [accountId, folder],
+  );
+  const result = await db.execute(
+    `UPDATE threads SET is_read = 1
+     WHERE account_id = $1 AND is_read = 0
+       AND id IN (SELECT thread_id FROM messages WHERE account_id = $1 AND imap_folder = $2)
+       AND id NOT IN (${pendingThreads})
+       AND NOT EXISTS (
+         SELECT 1 FROM messages m
+         WHERE m.account_id = threads.account_id AND m.thread_id = threads.id AND m.is_read = 0
+       )`,
+    [accountId, folder],
+  );
+  return result.rowsAffected;
+}
+
 export async function deleteThread(
   accountId: string,
   threadId: string,
@@ -261,3 +294,15 @@ export async function getMutedThreadIds(
   );
   return new Set(rows.map((r) => r.id));
 }
+
+export async function getThreadIdsWithLabel(
+  accountId: string,
+  labelId: string,
+): Promise<string[]> {
+  const db = await getDb();
+  const rows = await db.select<{ thread_id: string }[]>(
+    "SELECT thread_id FROM thread_labels WHERE account_id = $1 AND label_id = $2",
+    [accountId, labelId],
+  );
+  return rows.map((r) => r.thread_id);
+}
@@ -5,11 +5,15 @@ import { parseMailtoUrl } from "../utils/mailtoParser";
 import { useComposerStore } from "../stores/composerStore";
 import { escapeHtml } from "../utils/sanitize";
 
-async function handleUrl(url: string): Promise<void> {
-  if (!url.startsWith("mailto:")) return;
-
-  const fields = parseMailtoUrl(url);
+interface ComposeFields {
+  to: string[];
+  cc: string[];
+  bcc: string[];
+  subject: string | null;
+  body: string | null;
+}
 
+async function openCompose(fields: ComposeFields): Promise<void> {
   // Show and focus the main window
   const mainWindow = await WebviewWindow.getByLabel("main");
   if (mainWindow) {
@@ -23,11 +27,17 @@ async function handleUrl(url: string): Promise<void> {
     to: fields.to,
     cc: fields.cc,
     bcc: fields.bcc,
-    subject: fields.subject,
+    subject: fields.subject ?? "",
     bodyHtml: fields.body ? `<p>${escapeHtml(fields.body)}</p>` : "",
   });
 }
 
+async function handleUrl(url: string): Promise<void> {
+  if (!url.startsWith("mailto:")) return;
+
+  await openCompose(parseMailtoUrl(url));
+}
+
 export async function initDeepLinkHandler(): Promise<() => void> {
   const cleanups: Array<() => void> = [];
 
@@ -45,8 +55,8 @@ export async function initDeepLinkHandler(): Promise<() => void> {
 
   // Listen for forwarded args from single-instance plugin
   try {
-    const unlistenArgs = await listen<string[]>("single-instance-args", (event) => {
-      for (const arg of event.payload) {
+    const unlistenArgs = await listen<{ args: string[] }>("single-instance-args", (event) => {
+      for (const arg of event.payload.args) {
         if (arg.startsWith("mailto:")) {
           handleUrl(arg);
         }
@@ -57,6 +67,16 @@ export async function initDeepLinkHandler(): Promise<() => void> {
     console.error("Failed to listen for single-instance args:", err);
   }
 
+  // Listen for compose requests from the local API
+  try {
+    const unlistenCompose = await listen<ComposeFields>("compose-requested", (event) => {
+      openCompose(event.payload);
+    });
+    cleanups.push(unlistenCompose);
+  } catch (err) {
+    console.error("Failed to listen for compose requests:", err);
+  }
+
   return () => {
     for (const cleanup of cleanups) {
       cleanup();
@@ -0,0 +1,98 @@
+import { describe, it, expect, beforeEach, vi } from "vitest";
+import type { DbMessage } from "../db/messages";
+
+const mockGetMessagesForThread = vi.fn();
+const mockCacheMessageBody = vi.fn();
+vi.mock("../db/messages", () => ({
+  getMessagesForThread: (...args: unknown[]) => mockGetMessagesForThread(...args),
+  cacheMessageBody: (...args: unknown[]) => mockCacheMessageBody(...args),
+}));
+
+const mockFetchMessage = vi.fn();
+vi.mock("./providerFactory", () => ({
+  getEmailProvider: vi.fn(() =>
+    Promise.resolve({ fetchMessage: mockFetchMessage }),
+  ),
+}));
+
+import { dedupeConversation, getConversation } from "./conversation";
+
+function msg(overrides: Partial<DbMessage>): DbMessage {
+  return {
+    id: "m",
+    account_id: "acc-1",
+    thread_id: "t-1",
+    date: 0,
+    body_html: "<p>hi</p>",
+    body_text: "hi",
+    body_cached: 1,
+    message_id_header: null,
+    imap_folder: "INBOX",
+    ...overrides,
+  } as DbMessage;
+}
+
+describe("dedupeConversation", () => {
+  it("keeps one copy per Message-ID and orders by date", () => {
+    const result = dedupeConversation([
+      msg({ id: "sent-2", date: 200, imap_folder: "Sent", message_id_header: "<b@x>" }),
+      msg({ id: "inbox-1", date: 100, message_id_header: "<a@x>" }),
+      msg({ id: "inbox-2", date: 200, message_id_header: "<B@x>" }),
+    ]);
+    expect(result.map((m) => m.id)).toEqual(["inbox-1", "sent-2"]);
+  });
+
+  it("prefers the copy with a cached body", () => {
+    const result = dedupeConversation([
+      msg({ id: "sent-1", body_cached: 0, body_html: null, message_id_header: "<a@x>" }),
+      msg({ id: "inbox-1", message_id_header: "<a@x>" }),
+    ]);
+    expect(result.map((m) => m.id)).toEqual(["inbox-1"]);
+  });
+
+  it("keeps messages without a Message-ID", () => {
+    const result = dedupeConversation([msg({ id: "a" }), msg({ id: "b", date: 1 })]);
+    expect(result).toHaveLength(2);
+  });
+});
+
+describe("getConversation", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+  });
+
+  it("does not hit the server when every body is cached", async () => {
+    mockGetMessagesForThread.mockResolvedValue([msg({ id: "a" })]);
+    await getConversation("acc-1", "t-1");
+    expect(mockFetchMessage).not.toHaveBeenCalled();
+  });
+
+  it("fetches and caches missing bodies", async () => {
+    mockGetMessagesForThread.mockResolvedValue([
+      msg({ id: "a" }),
+      msg({ id: "b", date: 1, body_cached: 0, body_html: null, body_text: null }),
+    ]);
+    mockFetchMessage.mockResolvedValue({ bodyHtml: "<p>later</p>", bodyText: "later" });
+
+    const result = await getConversation("acc-1", "t-1");
+
+    expect(mockFetchMessage).toHaveBeenCalledWith("b");
+    expect(mockCacheMessageBody).toHaveBeenCalledWith("acc-1", "b", "<p>later</p>", "later");
+    expect(result[1]!.body_html).toBe("<p>later</p>");
+    expect(result[1]!.body_cached).toBe(1);
+  });
+
+  it("returns the message uncached when the fetch fails", async () => {
+    vi.spyOn(console, "warn").mockImplementation(() => {});
+    mockGetMessagesForThread.mockResolvedValue([
+      msg({ id: "b", body_cached: 0, body_html: null }),
+    ]);
+    mockFetchMessage.mockRejectedValue(new Error("offline"));
+
+    const result = await getConversation("acc-1", "t-1");
+
+    expect(result).toHaveLength(1);
+    expect(result[0]!.body_cached).toBe(0);
+    expect(mockCacheMessageBody).not.toHaveBeenCalled();
+  });
+});
@@ -0,0 +1,55 @@
+import {
+  getMessagesForThread,
+  cacheMessageBody,
+  type DbMessage,
+} from "../db/messages";
+import { getEmailProvider } from "./providerFactory";
+
+/**
+ * Pick one copy per Message-ID. IMAP accounts see the same message in
+ * several folders (a reply in Sent that was also delivered to INBOX); the
+ * copy with a cached body wins, otherwise the first one synced.
+ */
+export function dedupeConversation(messages: DbMessage[]): DbMessage[] {
+  const byKey = new Map<string, DbMessage>();
+  for (const msg of messages) {
+    const key = msg.message_id_header?.trim().toLowerCase() || `id:${msg.id}`;
+    const existing = byKey.get(key);
+    if (!existing || (!existing.body_cached && msg.body_cached)) {
+      byKey.set(key, msg);
+    }
+  }
+  return [...byKey.values()].sort((a, b) => a.date - b.date);
+}
+
+/**
+ * All messages of a thread across folders, oldest first and one per
+ * Message-ID, with any bodies not yet cached fetched from the server.
+ * A failed fetch leaves that message as-is so the rest still render.
+ */
+export async function getConversation(
+  accountId: string,
+  threadId: string,
+): Promise<DbMessage[]> {
+  const messages = dedupeConversation(
+    await getMessagesForThread(accountId, threadId),
+  );
+  const missing = messages.filter((m) => !m.body_cached);
+  if (missing.length === 0) return messages;
+
+  const provider = await getEmailProvider(accountId);
+  await Promise.all(
+    missing.map(async (msg) => {
+      try {
+        const parsed = await provider.fetchMessage(msg.id);
+        await cacheMessageBody(accountId, msg.id, parsed.bodyHtml, parsed.bodyText);
+        msg.body_html = parsed.bodyHtml;
+        msg.body_text = parsed.bodyText;
+        msg.body_cached = 1;
+      } catch (err) {
+        console.warn(`Failed to fetch body for message ${msg.id}:`, err);
+      }
+    }),
+  );
+  return messages;
+}
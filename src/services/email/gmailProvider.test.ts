@@ -1,6 +1,6 @@
 import { GmailApiProvider } from "./gmailProvider";
 import type { GmailClient } from "../gmail/client";
-import { createMockGmailClient } from "@/test/mocks";
+import { createMockGmailClient, createMockGmailMessage } from "@/test/mocks";
 
 describe("GmailApiProvider", () => {
   let provider: GmailApiProvider;
@@ -443,6 +443,31 @@ describe("GmailApiProvider", () => {
     });
   });
 
+  describe("searchMessages", () => {
+    it("adds a before: bound and returns the latest message of each thread", async () => {
+      vi.mocked(mockClient.listThreads).mockResolvedValue({
+        threads: [{ id: "thread-1", snippet: "", historyId: "1" }],
+      } as never);
+      vi.mocked(mockClient.getThread).mockResolvedValue({
+        id: "thread-1",
+        historyId: "1",
+        messages: [
+          createMockGmailMessage({ id: "msg-1" }),
+          createMockGmailMessage({ id: "msg-2" }),
+        ],
+      });
+
+      const result = await provider.searchMessages("invoice", 1_700_000_000_500, 20);
+
+      expect(mockClient.listThreads).toHaveBeenCalledWith({
+        q: "invoice before:1700000000",
+        maxResults: 20,
+      });
+      expect(mockClient.getThread).toHaveBeenCalledWith("thread-1", "metadata");
+      expect(result.map((m) => m.id)).toEqual(["msg-2"]);
+    });
+  });
+
   describe("fetchAttachment", () => {
     it("delegates to client.getAttachment", async () => {
       vi.mocked(mockClient.getAttachment).mockResolvedValue({
@@ -1,6 +1,8 @@
 import type { EmailProvider, EmailFolder, SyncResult } from "./types";
 import type { GmailClient } from "../gmail/client";
 import { parseGmailMessage, type ParsedMessage } from "../gmail/messageParser";
+import { resolveStagedParts } from "../attachments/staging";
+import { openAttachmentFile } from "../attachments/openAttachmentFile";
 
 /** Map Gmail system label IDs to IMAP special-use flags */
 const GMAIL_SPECIAL_USE: Record<string, string | null> = {
@@ -137,6 +139,16 @@ export class GmailApiProvider implements EmailProvider {
     return { data: resp.data, size: resp.size };
   }
 
+  async openAttachment(
+    messageId: string,
+    attachmentId: string,
+    filename: string | null,
+  ): Promise<void> {
+    // The Gmail API is only reachable from the frontend, so pass the bytes along
+    const resp = await this.client.getAttachment(messageId, attachmentId);
+    await openAttachmentFile(messageId, filename, { kind: "data", data: resp.data });
+  }
+
   async fetchRawMessage(messageId: string): Promise<string> {
     // Gmail API with format=raw returns a { raw: string } field (base64url-encoded RFC822)
     const resp = await this.client.getMessage(messageId, "raw") as unknown as { raw: string };
@@ -144,6 +156,23 @@ export class GmailApiProvider implements EmailProvider {
     return atob(base64);
   }
 
+  async searchMessages(
+    query: string,
+    before: number | null,
+    limit: number,
+  ): Promise<ParsedMessage[]> {
+    // Gmail's before: operator takes epoch seconds
+    const q = before !== null ? `${query} before:${Math.floor(before / 1000)}` : query;
+    const resp = await this.client.listThreads({ q, maxResults: limit });
+    const threads = await Promise.all(
+      (resp.threads ?? []).map((t) => this.client.getThread(t.id, "metadata")),
+    );
+    return threads.flatMap((thread) => {
+      const latest = thread.messages[thread.messages.length - 1];
+      return latest ? [parseGmailMessage(latest)] : [];
+    });
+  }
+
   async archive(threadId: string, _messageIds: string[]): Promise<void> {
     await this.client.modifyThread(threadId, undefined, ["INBOX"]);
   }
@@ -215,7 +244,8 @@ export class GmailApiProvider implements EmailProvider {
     rawBase64Url: string,
     threadId?: string,
   ): Promise<{ id: string }> {
-    const resp = await this.client.sendMessage(rawBase64Url, threadId);
+    const raw = await resolveStagedParts(rawBase64Url);
+    const resp = await this.client.sendMessage(raw, threadId);
     return { id: resp.id };
   }
 
@@ -223,7 +253,8 @@ export class GmailApiProvider implements EmailProvider {
     rawBase64Url: string,
     threadId?: string,
   ): Promise<{ draftId: string }> {
-    const resp = await this.client.createDraft(rawBase64Url, threadId);
+    const raw = await resolveStagedParts(rawBase64Url);
+    const resp = await this.client.createDraft(raw, threadId);
     return { draftId: resp.id };
   }
 
@@ -232,11 +263,8 @@ export class GmailApiProvider implements EmailProvider {
     rawBase64Url: string,
     threadId?: string,
   ): Promise<{ draftId: string }> {
-    const resp = await this.client.updateDraft(
-      draftId,
-      rawBase64Url,
-      threadId,
-    );
+    const raw = await resolveStagedParts(rawBase64Url);
+    const resp = await this.client.updateDraft(draftId, raw, threadId);
     return { draftId: resp.id };
   }
 
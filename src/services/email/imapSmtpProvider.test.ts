@@ -1,5 +1,5 @@
 import { describe, it, expect, vi, beforeEach } from "vitest";
-import { ImapSmtpProvider } from "./imapSmtpProvider";
+import { ImapSmtpProvider, formatImapDate } from "./imapSmtpProvider";
 
 // Mock all external dependencies
 vi.mock("../db/accounts", () => ({
@@ -9,6 +9,8 @@ vi.mock("../db/accounts", () => ({
 vi.mock("../imap/imapConfigBuilder", () => ({
   buildImapConfig: vi.fn(),
   buildSmtpConfig: vi.fn(),
+  moveImapPasswordToKeychain: vi.fn((account: unknown) => Promise.resolve(account)),
+  moveOAuthGrantToKeychain: vi.fn((account: unknown) => Promise.resolve(account)),
 }));
 
 vi.mock("../imap/imapSync", () => ({
@@ -24,16 +26,22 @@ vi.mock("../imap/folderMapper", () => ({
 
 vi.mock("../imap/tauriCommands", () => ({
   imapListFolders: vi.fn(),
+  imapCreateFolder: vi.fn(),
+  imapRenameFolder: vi.fn(),
+  imapDeleteFolder: vi.fn(),
   imapSetFlags: vi.fn(),
   imapMoveMessages: vi.fn(),
   imapDeleteMessages: vi.fn(),
   imapFetchMessageBody: vi.fn(),
   imapFetchAttachment: vi.fn(),
   imapFetchRawMessage: vi.fn(),
+  imapFetchMessages: vi.fn(),
+  imapSearchText: vi.fn(),
   imapTestConnection: vi.fn(),
-  imapAppendMessage: vi.fn(),
-  smtpSendEmail: vi.fn(),
+  imapSaveDraft: vi.fn(),
+  smtpSendAndAppend: vi.fn(),
   smtpTestConnection: vi.fn(),
+  registerAccount: vi.fn(),
 }));
 
 vi.mock("../imap/messageHelper", () => ({
@@ -55,13 +63,17 @@ import { buildImapConfig, buildSmtpConfig } from "../imap/imapConfigBuilder";
 import { mapFolderToLabel, getSyncableFolders } from "../imap/folderMapper";
 import {
   imapListFolders,
+  imapCreateFolder,
+  imapRenameFolder,
+  imapDeleteFolder,
   imapSetFlags,
   imapMoveMessages,
   imapDeleteMessages,
   imapTestConnection,
-  imapAppendMessage,
-  smtpSendEmail,
+  imapSaveDraft,
+  smtpSendAndAppend,
   smtpTestConnection,
+  registerAccount,
 } from "../imap/tauriCommands";
 import { findSpecialFolder } from "../imap/messageHelper";
 import { upsertMessage } from "../db/messages";
@@ -119,6 +131,30 @@ describe("ImapSmtpProvider", () => {
     expect(provider.type).toBe("imap");
   });
 
+  describe("credential registration", () => {
+    it("registers the account's configs once before the first command", async () => {
+      vi.mocked(imapListFolders).mockResolvedValue([]);
+      vi.mocked(getSyncableFolders).mockReturnValue([]);
+
+      await provider.listFolders();
+      await provider.listFolders();
+
+      expect(registerAccount).toHaveBeenCalledTimes(1);
+      expect(registerAccount).toHaveBeenCalledWith("acc-1", mockImapConfig, mockSmtpConfig, false);
+    });
+
+    it("registers again after the config cache is cleared", async () => {
+      vi.mocked(imapListFolders).mockResolvedValue([]);
+      vi.mocked(getSyncableFolders).mockReturnValue([]);
+
+      await provider.listFolders();
+      provider.clearConfigCache();
+      await provider.listFolders();
+
+      expect(registerAccount).toHaveBeenCalledTimes(2);
+    });
+  });
+
   // ---------- Folder operations ----------
 
   describe("listFolders", () => {
@@ -152,7 +188,7 @@ describe("ImapSmtpProvider", () => {
 
       const folders = await provider.listFolders();
 
-      expect(imapListFolders).toHaveBeenCalledWith(mockImapConfig);
+      expect(imapListFolders).toHaveBeenCalledWith("acc-1");
       expect(folders).toHaveLength(2);
       expect(folders[0]).toEqual({
         id: "INBOX",
@@ -168,26 +204,70 @@ describe("ImapSmtpProvider", () => {
   });
 
   describe("createFolder", () => {
-    it("throws an informative error", async () => {
-      await expect(provider.createFolder("test")).rejects.toThrow(
-        "not supported",
-      );
+    beforeEach(() => {
+      vi.mocked(imapListFolders).mockResolvedValue([
+        { path: "INBOX", name: "INBOX", delimiter: ".", special_use: "\\Inbox", exists: 0, unseen: 0 },
+      ] as never);
+      vi.mocked(mapFolderToLabel).mockImplementation((f) => ({
+        labelId: `folder-${f.path}`,
+        labelName: f.name,
+        type: "user",
+      }));
+    });
+
+    it("creates a top-level folder", async () => {
+      vi.mocked(imapCreateFolder).mockResolvedValue("Entw&APw-rfe");
+
+      const folder = await provider.createFolder("Entwürfe");
+
+      expect(imapCreateFolder).toHaveBeenCalledWith("acc-1", "Entwürfe");
+      expect(folder).toMatchObject({
+        id: "folder-Entwürfe",
+        name: "Entwürfe",
+        path: "Entwürfe",
+        type: "user",
+        delimiter: ".",
+      });
+    });
+
+    it("nests under the parent using the server's delimiter", async () => {
+      vi.mocked(imapCreateFolder).mockResolvedValue("INBOX.Receipts");
+
+      const folder = await provider.createFolder("Receipts", "INBOX");
+
+      expect(imapCreateFolder).toHaveBeenCalledWith("acc-1", "INBOX.Receipts");
+      expect(folder.path).toBe("INBOX.Receipts");
     });
   });
 
   describe("deleteFolder", () => {
-    it("throws an informative error", async () => {
-      await expect(provider.deleteFolder("test")).rejects.toThrow(
-        "not supported",
-      );
+    it("calls imapDeleteFolder", async () => {
+      vi.mocked(imapDeleteFolder).mockResolvedValue(undefined);
+
+      await provider.deleteFolder("Old");
+
+      expect(imapDeleteFolder).toHaveBeenCalledWith("acc-1", "Old");
     });
   });
 
   describe("renameFolder", () => {
-    it("throws an informative error", async () => {
-      await expect(provider.renameFolder("old", "new")).rejects.toThrow(
-        "not supported",
-      );
+    beforeEach(() => {
+      vi.mocked(imapListFolders).mockResolvedValue([
+        { path: "Work/Old", name: "Old", delimiter: "/", special_use: null, exists: 0, unseen: 0 },
+      ] as never);
+      vi.mocked(imapRenameFolder).mockResolvedValue("");
+    });
+
+    it("renames the last level of the path", async () => {
+      await provider.renameFolder("Work/Old", "New");
+
+      expect(imapRenameFolder).toHaveBeenCalledWith("acc-1", "Work/Old", "Work/New");
+    });
+
+    it("moves the folder when given a full path", async () => {
+      await provider.renameFolder("Work/Old", "Archive/Old");
+
+      expect(imapRenameFolder).toHaveBeenCalledWith("acc-1", "Work/Old", "Archive/Old");
     });
   });
 
@@ -200,7 +280,7 @@ describe("ImapSmtpProvider", () => {
 
       const result = await provider.fetchRawMessage("imap-acc-1-INBOX-42");
 
-      expect(imapFetchRawMessage).toHaveBeenCalledWith(mockImapConfig, "INBOX", 42);
+      expect(imapFetchRawMessage).toHaveBeenCalledWith("acc-1", "INBOX", 42);
       expect(result).toBe("From: test@example.com\r\nSubject: Hello\r\n\r\nBody");
     });
 
@@ -211,6 +291,44 @@ describe("ImapSmtpProvider", () => {
     });
   });
 
+  describe("searchMessages", () => {
+    it("searches INBOX for the free text before the given date", async () => {
+      const { imapSearchText, imapFetchMessages } = await import("../imap/tauriCommands");
+      const { imapMessageToParsedMessage } = await import("../imap/imapSync");
+      vi.mocked(imapSearchText).mockResolvedValue([3, 8, 21]);
+      vi.mocked(imapFetchMessages).mockResolvedValue({
+        messages: [{ uid: 8 }, { uid: 21 }],
+        folder_status: {},
+      } as never);
+      vi.mocked(imapMessageToParsedMessage).mockImplementation(
+        (msg) => ({ parsed: { id: `imap-acc-1-INBOX-${msg.uid}` } }) as never,
+      );
+
+      const result = await provider.searchMessages(
+        "from:alice invoice",
+        Date.UTC(2024, 2, 1),
+        2,
+      );
+
+      expect(imapSearchText).toHaveBeenCalledWith("acc-1", "INBOX", "invoice", "1-Mar-2024");
+      expect(imapFetchMessages).toHaveBeenCalledWith("acc-1", "INBOX", [8, 21]);
+      expect(result.map((m) => m.id)).toEqual(["imap-acc-1-INBOX-8", "imap-acc-1-INBOX-21"]);
+    });
+
+    it("skips the server when the query has no free text", async () => {
+      const { imapSearchText } = await import("../imap/tauriCommands");
+
+      expect(await provider.searchMessages("is:unread", null, 10)).toEqual([]);
+      expect(imapSearchText).not.toHaveBeenCalled();
+    });
+  });
+
+  describe("formatImapDate", () => {
+    it("formats a UTC day without zero padding", () => {
+      expect(formatImapDate(Date.UTC(2025, 0, 5, 23, 0))).toBe("5-Jan-2025");
+    });
+  });
+
   // ---------- Actions ----------
 
   describe("archive", () => {
@@ -225,7 +343,7 @@ describe("ImapSmtpProvider", () => {
 
       expect(findSpecialFolder).toHaveBeenCalledWith("acc-1", "\\Archive");
       expect(imapMoveMessages).toHaveBeenCalledWith(
-        mockImapConfig,
+        "acc-1",
         "INBOX",
         [100, 200],
         "Archive",
@@ -248,7 +366,7 @@ describe("ImapSmtpProvider", () => {
       await provider.archive("thread-1", ["imap-acc-1-INBOX-100"]);
 
       expect(imapMoveMessages).toHaveBeenCalledWith(
-        mockImapConfig,
+        "acc-1",
         "INBOX",
         [100],
         "Archive",
@@ -265,7 +383,7 @@ describe("ImapSmtpProvider", () => {
 
       expect(findSpecialFolder).toHaveBeenCalledWith("acc-1", "\\Trash");
       expect(imapMoveMessages).toHaveBeenCalledWith(
-        mockImapConfig,
+        "acc-1",
         "INBOX",
         [100],
         "Deleted Items",
@@ -284,12 +402,12 @@ describe("ImapSmtpProvider", () => {
 
       expect(imapDeleteMessages).toHaveBeenCalledTimes(2);
       expect(imapDeleteMessages).toHaveBeenCalledWith(
-        mockImapConfig,
+        "acc-1",
         "INBOX",
         [100],
       );
       expect(imapDeleteMessages).toHaveBeenCalledWith(
-        mockImapConfig,
+        "acc-1",
         "Sent",
         [200],
       );
@@ -303,7 +421,7 @@ describe("ImapSmtpProvider", () => {
       await provider.markRead("thread-1", ["imap-acc-1-INBOX-100"], true);
 
       expect(imapSetFlags).toHaveBeenCalledWith(
-        mockImapConfig,
+        "acc-1",
         "INBOX",
         [100],
         ["Seen"],
@@ -317,7 +435,7 @@ describe("ImapSmtpProvider", () => {
       await provider.markRead("thread-1", ["imap-acc-1-INBOX-100"], false);
 
       expect(imapSetFlags).toHaveBeenCalledWith(
-        mockImapConfig,
+        "acc-1",
         "INBOX",
         [100],
         ["Seen"],
@@ -333,7 +451,7 @@ describe("ImapSmtpProvider", () => {
       await provider.star("thread-1", ["imap-acc-1-INBOX-100"], true);
 
       expect(imapSetFlags).toHaveBeenCalledWith(
-        mockImapConfig,
+        "acc-1",
         "INBOX",
         [100],
         ["Flagged"],
@@ -350,7 +468,7 @@ describe("ImapSmtpProvider", () => {
       await provider.spam("thread-1", ["imap-acc-1-INBOX-100"], true);
 
       expect(imapMoveMessages).toHaveBeenCalledWith(
-        mockImapConfig,
+        "acc-1",
         "INBOX",
         [100],
         "Junk E-Mail",
@@ -364,7 +482,7 @@ describe("ImapSmtpProvider", () => {
       await provider.spam("thread-1", ["imap-acc-1-Junk-100"], false);
 
       expect(imapMoveMessages).toHaveBeenCalledWith(
-        mockImapConfig,
+        "acc-1",
         "Junk",
         [100],
         "INBOX",
@@ -379,7 +497,7 @@ describe("ImapSmtpProvider", () => {
       await provider.moveToFolder("thread-1", ["imap-acc-1-INBOX-100"], "Work");
 
       expect(imapMoveMessages).toHaveBeenCalledWith(
-        mockImapConfig,
+        "acc-1",
         "INBOX",
         [100],
         "Work",
@@ -423,16 +541,20 @@ describe("ImapSmtpProvider", () => {
     const rawBase64Url = btoa(rawEmail).replace(/\+/g, "-").replace(/\//g, "_").replace(/=+$/, "");
 
     it("sends via SMTP, saves locally, and copies to Sent folder", async () => {
-      vi.mocked(smtpSendEmail).mockResolvedValue({
-        success: true,
-        message: "OK",
+      vi.mocked(smtpSendAndAppend).mockResolvedValue({
+        sent: { success: true, message: "OK" },
+        sent_copy: { status: "saved" },
       });
       vi.mocked(findSpecialFolder).mockResolvedValue("Sent Items");
-      vi.mocked(imapAppendMessage).mockResolvedValue(undefined);
 
       const result = await provider.sendMessage(rawBase64Url);
 
-      expect(smtpSendEmail).toHaveBeenCalledWith(mockSmtpConfig, rawBase64Url);
+      // Sends and copies to the server Sent folder in one call
+      expect(smtpSendAndAppend).toHaveBeenCalledWith(
+        "acc-1",
+        rawBase64Url,
+        "Sent Items",
+      );
       // Should save message to local DB
       expect(upsertThread).toHaveBeenCalled();
       expect(setThreadLabels).toHaveBeenCalledWith(
@@ -449,23 +571,15 @@ describe("ImapSmtpProvider", () => {
           isRead: true,
         }),
       );
-      // Should copy to server Sent folder
-      expect(imapAppendMessage).toHaveBeenCalledWith(
-        mockImapConfig,
-        "Sent Items",
-        rawBase64Url,
-        "(\\Seen)",
-      );
       expect(result.id).toMatch(/^imap-sent-/);
     });
 
     it("adds SENT label to existing thread when replying", async () => {
-      vi.mocked(smtpSendEmail).mockResolvedValue({
-        success: true,
-        message: "OK",
+      vi.mocked(smtpSendAndAppend).mockResolvedValue({
+        sent: { success: true, message: "OK" },
+        sent_copy: { status: "saved" },
       });
       vi.mocked(findSpecialFolder).mockResolvedValue("Sent");
-      vi.mocked(imapAppendMessage).mockResolvedValue(undefined);
       vi.mocked(getThreadLabelIds).mockResolvedValue(["INBOX"]);
 
       const result = await provider.sendMessage(rawBase64Url, "existing-thread-1");
@@ -488,9 +602,10 @@ describe("ImapSmtpProvider", () => {
     });
 
     it("throws if SMTP send fails", async () => {
-      vi.mocked(smtpSendEmail).mockResolvedValue({
-        success: false,
-        message: "Authentication failed",
+      vi.mocked(findSpecialFolder).mockResolvedValue("Sent");
+      vi.mocked(smtpSendAndAppend).mockResolvedValue({
+        sent: { success: false, message: "Authentication failed" },
+        sent_copy: { status: "skipped" },
       });
 
       await expect(provider.sendMessage(rawBase64Url)).rejects.toThrow(
@@ -499,80 +614,80 @@ describe("ImapSmtpProvider", () => {
     });
 
     it("succeeds even if Sent folder copy fails", async () => {
-      vi.mocked(smtpSendEmail).mockResolvedValue({
-        success: true,
-        message: "OK",
-      });
       vi.mocked(findSpecialFolder).mockResolvedValue("Sent");
-      vi.mocked(imapAppendMessage).mockRejectedValue(
-        new Error("APPEND failed"),
-      );
+      vi.mocked(smtpSendAndAppend).mockResolvedValue({
+        sent: { success: true, message: "OK" },
+        sent_copy: { status: "failed", error: "APPEND failed" },
+      });
 
       const spy = vi.spyOn(console, "error").mockImplementation(() => {});
       const result = await provider.sendMessage(rawBase64Url);
       expect(result.id).toMatch(/^imap-sent-/);
       // Should still have saved locally
       expect(upsertMessage).toHaveBeenCalled();
+      expect(spy).toHaveBeenCalled();
       spy.mockRestore();
     });
   });
 
   describe("createDraft", () => {
-    it("appends to Drafts folder with Draft flag", async () => {
+    it("saves to Drafts folder and uses the returned UID", async () => {
       vi.mocked(findSpecialFolder).mockResolvedValue("INBOX.Drafts");
-      vi.mocked(imapAppendMessage).mockResolvedValue(undefined);
+      vi.mocked(imapSaveDraft).mockResolvedValue(42);
 
       const result = await provider.createDraft("base64data");
 
-      expect(imapAppendMessage).toHaveBeenCalledWith(
-        mockImapConfig,
+      expect(imapSaveDraft).toHaveBeenCalledWith(
+        "acc-1",
         "INBOX.Drafts",
         "base64data",
-        "(\\Draft)",
+        null,
       );
-      expect(result.draftId).toMatch(/^imap-draft-/);
+      expect(result.draftId).toBe("imap-acc-1-INBOX.Drafts-42");
     });
 
-    it("falls back to 'Drafts' when special folder not found", async () => {
+    it("generates a draft ID when the server doesn't report the UID", async () => {
       vi.mocked(findSpecialFolder).mockResolvedValue(null);
-      vi.mocked(imapAppendMessage).mockResolvedValue(undefined);
+      vi.mocked(imapSaveDraft).mockResolvedValue(null);
 
-      await provider.createDraft("base64data");
+      const result = await provider.createDraft("base64data");
 
-      expect(imapAppendMessage).toHaveBeenCalledWith(
-        mockImapConfig,
+      expect(imapSaveDraft).toHaveBeenCalledWith(
+        "acc-1",
         "Drafts",
         "base64data",
-        "(\\Draft)",
+        null,
       );
+      expect(result.draftId).toMatch(/^imap-draft-/);
     });
   });
 
   describe("updateDraft", () => {
-    it("deletes old draft and creates new one", async () => {
+    it("saves the new draft against the old one, then deletes the old one", async () => {
       vi.mocked(findSpecialFolder).mockResolvedValue("Drafts");
       vi.mocked(imapDeleteMessages).mockResolvedValue(undefined);
-      vi.mocked(imapAppendMessage).mockResolvedValue(undefined);
+      vi.mocked(imapSaveDraft).mockResolvedValue(501);
 
       const result = await provider.updateDraft(
         "imap-acc-1-Drafts-500",
         "newBase64data",
       );
 
-      // Should delete old draft
+      expect(imapSaveDraft).toHaveBeenCalledWith(
+        "acc-1",
+        "Drafts",
+        "newBase64data",
+        500,
+      );
       expect(imapDeleteMessages).toHaveBeenCalledWith(
-        mockImapConfig,
+        "acc-1",
         "Drafts",
         [500],
       );
-      // Should create new draft
-      expect(imapAppendMessage).toHaveBeenCalledWith(
-        mockImapConfig,
-        "Drafts",
-        "newBase64data",
-        "(\\Draft)",
+      expect(vi.mocked(imapSaveDraft).mock.invocationCallOrder[0]).toBeLessThan(
+        vi.mocked(imapDeleteMessages).mock.invocationCallOrder[0]!,
       );
-      expect(result.draftId).toMatch(/^imap-draft-/);
+      expect(result.draftId).toBe("imap-acc-1-Drafts-501");
     });
   });
 
@@ -583,7 +698,7 @@ describe("ImapSmtpProvider", () => {
       await provider.deleteDraft("imap-acc-1-Drafts-500");
 
       expect(imapDeleteMessages).toHaveBeenCalledWith(
-        mockImapConfig,
+        "acc-1",
         "Drafts",
         [500],
       );
@@ -696,12 +811,12 @@ describe("ImapSmtpProvider", () => {
 
       expect(imapDeleteMessages).toHaveBeenCalledTimes(2);
       expect(imapDeleteMessages).toHaveBeenCalledWith(
-        mockImapConfig,
+        "acc-1",
         "INBOX",
         [100, 200],
       );
       expect(imapDeleteMessages).toHaveBeenCalledWith(
-        mockImapConfig,
+        "acc-1",
         "Sent",
         [300],
       );
@@ -715,7 +830,7 @@ describe("ImapSmtpProvider", () => {
       ]);
 
       expect(imapDeleteMessages).toHaveBeenCalledWith(
-        mockImapConfig,
+        "acc-1",
         "INBOX.Sub-Folder",
         [100],
       );
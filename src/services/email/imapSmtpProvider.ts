@@ -1,33 +1,47 @@
 import type { EmailProvider, EmailFolder, SyncResult } from "./types";
 import type { ParsedMessage } from "../gmail/messageParser";
-import { buildImapConfig, buildSmtpConfig } from "../imap/imapConfigBuilder";
+import {
+  buildImapConfig,
+  buildSmtpConfig,
+  moveImapPasswordToKeychain,
+  moveOAuthGrantToKeychain,
+} from "../imap/imapConfigBuilder";
 import { imapInitialSync, imapDeltaSync, imapMessageToParsedMessage } from "../imap/imapSync";
 import { mapFolderToLabel, getSyncableFolders } from "../imap/folderMapper";
 import {
   imapListFolders,
+  imapCreateFolder,
+  imapRenameFolder,
+  imapDeleteFolder,
   imapSetFlags,
   imapMoveMessages,
   imapDeleteMessages,
   imapFetchMessageBody,
   imapFetchAttachment,
   imapFetchRawMessage,
+  imapFetchMessages,
+  imapSearchText,
   imapTestConnection,
-  imapAppendMessage,
-  smtpSendEmail,
+  imapSaveDraft,
+  registerAccount,
+  smtpSendAndAppend,
   smtpTestConnection,
   type ImapConfig,
   type SmtpConfig,
 } from "../imap/tauriCommands";
 import { getAccount, type DbAccount } from "../db/accounts";
 import { findSpecialFolder } from "../imap/messageHelper";
-import { ensureFreshToken } from "../oauth/oauthTokenManager";
+import { openAttachmentFile } from "../attachments/openAttachmentFile";
+import { ensureFreshToken, hasFrontendTokens } from "../oauth/oauthTokenManager";
 import { upsertMessage } from "../db/messages";
 import { upsertThread, setThreadLabels, getThreadLabelIds } from "../db/threads";
+import { getThreadSubject } from "@/utils/subject";
+import { parseSearchQuery } from "../search/searchParser";
 
 /**
  * Decode base64url (Gmail/RFC 4648 URL-safe, no padding) to a UTF-8 string.
  */
-function base64UrlDecode(input: string): string {
+export function base64UrlDecode(input: string): string {
   // Convert base64url to standard base64
   let base64 = input.replace(/-/g, "+").replace(/_/g, "/");
   // Add padding if needed
@@ -43,7 +57,7 @@ function base64UrlDecode(input: string): string {
  * Parse basic RFC 2822 headers from a raw email string.
  * Returns a map of header name (lowercase) → header value.
  */
-function parseBasicHeaders(raw: string): Map<string, string> {
+export function parseBasicHeaders(raw: string): Map<string, string> {
   const headers = new Map<string, string>();
   // Headers end at the first blank line
   const headerEnd = raw.indexOf("\r\n\r\n");
@@ -63,6 +77,16 @@ function parseBasicHeaders(raw: string): Map<string, string> {
   return headers;
 }
 
+const IMAP_MONTHS = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+/**
+ * Format a timestamp (ms) as an IMAP SEARCH date, e.g. "1-Mar-2024" (UTC).
+ */
+export function formatImapDate(ms: number): string {
+  const d = new Date(ms);
+  return `${d.getUTCDate()}-${IMAP_MONTHS[d.getUTCMonth()]}-${d.getUTCFullYear()}`;
+}
+
 /**
  * Extract a plain-text snippet from a raw RFC 2822 email body.
  */
@@ -108,6 +132,7 @@ export class ImapSmtpProvider implements EmailProvider {
 
   private _imapConfig: ImapConfig | null = null;
   private _smtpConfig: SmtpConfig | null = null;
+  private _registered = false;
 
   constructor(accountId: string) {
     this.accountId = accountId;
@@ -123,7 +148,7 @@ export class ImapSmtpProvider implements EmailProvider {
 
   private async getImapConfig(): Promise<ImapConfig> {
     const account = await this.getAccount();
-    if (account.auth_method === "oauth2") {
+    if (hasFrontendTokens(account)) {
       // OAuth accounts need a fresh token every time
       const token = await ensureFreshToken(account);
       return buildImapConfig(account, token);
@@ -136,7 +161,7 @@ export class ImapSmtpProvider implements EmailProvider {
 
   private async getSmtpConfig(): Promise<SmtpConfig> {
     const account = await this.getAccount();
-    if (account.auth_method === "oauth2") {
+    if (hasFrontendTokens(account)) {
       const token = await ensureFreshToken(account);
       return buildSmtpConfig(account, token);
     }
@@ -146,19 +171,49 @@ export class ImapSmtpProvider implements EmailProvider {
     return this._smtpConfig;
   }
 
+  /**
+   * Make sure the backend holds this account's credentials and return the
+   * account id the IMAP/SMTP commands take. OAuth accounts whose tokens
+   * the frontend refreshes register again on every call so the backend
+   * always has a fresh token.
+   */
+  private async credentials(): Promise<string> {
+    let account = await this.getAccount();
+    if (hasFrontendTokens(account) || !this._registered) {
+      if (!this._registered && (await moveImapPasswordToKeychain(account)) !== account) {
+        // Register without the password now that the keychain holds it
+        this._imapConfig = null;
+        this._smtpConfig = null;
+      }
+      if (!this._registered) {
+        // Register without a token once the backend refreshes them
+        account = await moveOAuthGrantToKeychain(account);
+      }
+      await registerAccount(
+        this.accountId,
+        await this.getImapConfig(),
+        account.smtp_host ? await this.getSmtpConfig() : null,
+        !!account.ask_password,
+      );
+      this._registered = true;
+    }
+    return this.accountId;
+  }
+
   /**
    * Invalidate cached configs (e.g., after password change).
    */
   clearConfigCache(): void {
     this._imapConfig = null;
     this._smtpConfig = null;
+    this._registered = false;
   }
 
   // ---- Folder/Label operations ----
 
   async listFolders(): Promise<EmailFolder[]> {
-    const config = await this.getImapConfig();
-    const imapFolders = await imapListFolders(config);
+    const accountId = await this.credentials();
+    const imapFolders = await imapListFolders(accountId);
     const syncable = getSyncableFolders(imapFolders);
 
     return syncable.map((f) => {
@@ -176,28 +231,55 @@ export class ImapSmtpProvider implements EmailProvider {
     });
   }
 
-  async createFolder(
-    _name: string,
-    _parentPath?: string,
-  ): Promise<EmailFolder> {
-    throw new Error(
-      "Creating folders is not supported for IMAP accounts via the current command set. " +
-        "Please create the folder directly on the mail server.",
-    );
+  /** The delimiter of the folder at `path`, or "/" if it isn't listed. */
+  private async delimiterOf(accountId: string, path: string): Promise<string> {
+    const folders = await imapListFolders(accountId);
+    return folders.find((f) => f.path === path)?.delimiter ?? folders[0]?.delimiter ?? "/";
+  }
+
+  async createFolder(name: string, parentPath?: string): Promise<EmailFolder> {
+    const accountId = await this.credentials();
+    const delimiter = await this.delimiterOf(accountId, parentPath ?? "INBOX");
+    const path = parentPath ? `${parentPath}${delimiter}${name}` : name;
+    const rawPath = await imapCreateFolder(accountId, path);
+    const mapping = mapFolderToLabel({
+      path,
+      raw_path: rawPath,
+      name,
+      delimiter,
+      special_use: null,
+      exists: 0,
+      unseen: 0,
+      attributes: [],
+      no_select: false,
+      non_existent: false,
+    });
+    return {
+      id: mapping.labelId,
+      name: mapping.labelName,
+      path,
+      type: "user",
+      specialUse: null,
+      delimiter,
+      messageCount: 0,
+      unreadCount: 0,
+    };
   }
 
-  async deleteFolder(_path: string): Promise<void> {
-    throw new Error(
-      "Deleting folders is not supported for IMAP accounts via the current command set. " +
-        "Please delete the folder directly on the mail server.",
-    );
+  async deleteFolder(path: string): Promise<void> {
+    const accountId = await this.credentials();
+    await imapDeleteFolder(accountId, path);
   }
 
-  async renameFolder(_path: string, _newName: string): Promise<void> {
-    throw new Error(
-      "Renaming folders is not supported for IMAP accounts via the current command set. " +
-        "Please rename the folder directly on the mail server.",
-    );
+  /** `newName` replaces the last level of `path` unless it's a full path. */
+  async renameFolder(path: string, newName: string): Promise<void> {
+    const accountId = await this.credentials();
+    const delimiter = await this.delimiterOf(accountId, path);
+    const cut = path.lastIndexOf(delimiter);
+    const newPath = newName.includes(delimiter) || cut === -1
+      ? newName
+      : `${path.slice(0, cut)}${delimiter}${newName}`;
+    await imapRenameFolder(accountId, path, newPath);
   }
 
   // ---- Sync operations ----
@@ -224,8 +306,8 @@ export class ImapSmtpProvider implements EmailProvider {
       throw new Error(`Invalid IMAP message ID format: ${messageId}`);
     }
 
-    const config = await this.getImapConfig();
-    const imapMsg = await imapFetchMessageBody(config, folder, uid);
+    const accountId = await this.credentials();
+    const imapMsg = await imapFetchMessageBody(accountId, folder, uid);
 
     const { parsed } = imapMessageToParsedMessage(
       imapMsg,
@@ -247,11 +329,33 @@ export class ImapSmtpProvider implements EmailProvider {
       throw new Error(`Invalid IMAP message ID format: ${messageId}`);
     }
 
-    const config = await this.getImapConfig();
-    const data = await imapFetchAttachment(config, folder, uid, attachmentId);
+    const accountId = await this.credentials();
+    const data = await imapFetchAttachment(accountId, folder, uid, attachmentId);
     return { data, size: data.length };
   }
 
+  async openAttachment(
+    messageId: string,
+    attachmentId: string,
+    filename: string | null,
+  ): Promise<void> {
+    const { folder, uid } = this.parseImapMessageId(messageId);
+
+    if (uid === null || !folder) {
+      throw new Error(`Invalid IMAP message ID format: ${messageId}`);
+    }
+
+    // The backend fetches the part itself; no bytes pass through the webview
+    const accountId = await this.credentials();
+    await openAttachmentFile(messageId, filename, {
+      kind: "imap",
+      account_id: accountId,
+      folder,
+      uid,
+      part_id: attachmentId,
+    });
+  }
+
   async fetchRawMessage(messageId: string): Promise<string> {
     const { folder, uid } = this.parseImapMessageId(messageId);
 
@@ -259,8 +363,33 @@ export class ImapSmtpProvider implements EmailProvider {
       throw new Error(`Invalid IMAP message ID format: ${messageId}`);
     }
 
-    const config = await this.getImapConfig();
-    return imapFetchRawMessage(config, folder, uid);
+    const accountId = await this.credentials();
+    return imapFetchRawMessage(accountId, folder, uid);
+  }
+
+  async searchMessages(
+    query: string,
+    before: number | null,
+    limit: number,
+  ): Promise<ParsedMessage[]> {
+    // IMAP SEARCH has no equivalent for most operators; match the free text only
+    const text = parseSearchQuery(query).freeText;
+    if (!text) return [];
+
+    const accountId = await this.credentials();
+    const uids = await imapSearchText(
+      accountId,
+      "INBOX",
+      text,
+      before !== null ? formatImapDate(before) : null,
+    );
+    if (uids.length === 0) return [];
+
+    // UIDs ascend with arrival, so the newest hits are at the end
+    const result = await imapFetchMessages(accountId, "INBOX", uids.slice(-limit));
+    return result.messages.map(
+      (msg) => imapMessageToParsedMessage(msg, this.accountId, "INBOX").parsed,
+    );
   }
 
   // ---- Actions ----
@@ -269,14 +398,14 @@ export class ImapSmtpProvider implements EmailProvider {
     _threadId: string,
     _messageIds: string[],
   ): Promise<void> {
-    const config = await this.getImapConfig();
+    const accountId = await this.credentials();
     const grouped = this.groupByFolder(_messageIds);
     const archiveFolder =
       (await findSpecialFolder(this.accountId, "\\Archive")) ?? "Archive";
 
     for (const [folder, uids] of grouped) {
       if (folder === archiveFolder) continue;
-      await imapMoveMessages(config, folder, uids, archiveFolder);
+      await imapMoveMessages(accountId, folder, uids, archiveFolder);
     }
   }
 
@@ -284,14 +413,14 @@ export class ImapSmtpProvider implements EmailProvider {
     _threadId: string,
     _messageIds: string[],
   ): Promise<void> {
-    const config = await this.getImapConfig();
+    const accountId = await this.credentials();
     const grouped = this.groupByFolder(_messageIds);
     const trashFolder =
       (await findSpecialFolder(this.accountId, "\\Trash")) ?? "Trash";
 
     for (const [folder, uids] of grouped) {
       if (folder === trashFolder) continue;
-      await imapMoveMessages(config, folder, uids, trashFolder);
+      await imapMoveMessages(accountId, folder, uids, trashFolder);
     }
   }
 
@@ -299,11 +428,11 @@ export class ImapSmtpProvider implements EmailProvider {
     _threadId: string,
     _messageIds: string[],
   ): Promise<void> {
-    const config = await this.getImapConfig();
+    const accountId = await this.credentials();
     const grouped = this.groupByFolder(_messageIds);
 
     for (const [folder, uids] of grouped) {
-      await imapDeleteMessages(config, folder, uids);
+      await imapDeleteMessages(accountId, folder, uids);
     }
   }
 
@@ -312,11 +441,11 @@ export class ImapSmtpProvider implements EmailProvider {
     _messageIds: string[],
     read: boolean,
   ): Promise<void> {
-    const config = await this.getImapConfig();
+    const accountId = await this.credentials();
     const grouped = this.groupByFolder(_messageIds);
 
     for (const [folder, uids] of grouped) {
-      await imapSetFlags(config, folder, uids, ["Seen"], read);
+      await imapSetFlags(accountId, folder, uids, ["Seen"], read);
     }
   }
 
@@ -325,11 +454,11 @@ export class ImapSmtpProvider implements EmailProvider {
     _messageIds: string[],
     starred: boolean,
   ): Promise<void> {
-    const config = await this.getImapConfig();
+    const accountId = await this.credentials();
     const grouped = this.groupByFolder(_messageIds);
 
     for (const [folder, uids] of grouped) {
-      await imapSetFlags(config, folder, uids, ["Flagged"], starred);
+      await imapSetFlags(accountId, folder, uids, ["Flagged"], starred);
     }
   }
 
@@ -338,7 +467,7 @@ export class ImapSmtpProvider implements EmailProvider {
     _messageIds: string[],
     isSpam: boolean,
   ): Promise<void> {
-    const config = await this.getImapConfig();
+    const accountId = await this.credentials();
     const grouped = this.groupByFolder(_messageIds);
     const junkFolder =
       (await findSpecialFolder(this.accountId, "\\Junk")) ?? "Junk";
@@ -346,7 +475,7 @@ export class ImapSmtpProvider implements EmailProvider {
 
     for (const [folder, uids] of grouped) {
       if (folder === destination) continue;
-      await imapMoveMessages(config, folder, uids, destination);
+      await imapMoveMessages(accountId, folder, uids, destination);
     }
   }
 
@@ -355,12 +484,12 @@ export class ImapSmtpProvider implements EmailProvider {
     _messageIds: string[],
     folderPath: string,
   ): Promise<void> {
-    const config = await this.getImapConfig();
+    const accountId = await this.credentials();
     const grouped = this.groupByFolder(_messageIds);
 
     for (const [folder, uids] of grouped) {
       if (folder === folderPath) continue;
-      await imapMoveMessages(config, folder, uids, folderPath);
+      await imapMoveMessages(accountId, folder, uids, folderPath);
     }
   }
 
@@ -394,10 +523,13 @@ export class ImapSmtpProvider implements EmailProvider {
     rawBase64Url: string,
     _threadId?: string,
   ): Promise<{ id: string }> {
-    const smtpConfig = await this.getSmtpConfig();
-    const result = await smtpSendEmail(smtpConfig, rawBase64Url);
-    if (!result.success) {
-      throw new Error(`SMTP send failed: ${result.message}`);
+    const accountId = await this.credentials();
+    const sentFolder =
+      (await findSpecialFolder(this.accountId, "\\Sent").catch(() => null)) ?? "Sent";
+    // Sends and copies to the Sent folder on the server in one go
+    const { sent, sent_copy } = await smtpSendAndAppend(accountId, rawBase64Url, sentFolder);
+    if (!sent.success) {
+      throw new Error(`SMTP send failed: ${sent.message}`);
     }
 
     const messageId = `imap-sent-${Date.now()}-${Math.random().toString(36).slice(2)}`;
@@ -409,17 +541,11 @@ export class ImapSmtpProvider implements EmailProvider {
       console.warn("[IMAP] Failed to save sent message to local DB:", err);
     }
 
-    // Copy sent message to Sent folder on IMAP server
-    try {
-      const imapConfig = await this.getImapConfig();
-      const sentFolder =
-        (await findSpecialFolder(this.accountId, "\\Sent")) ?? "Sent";
-      await imapAppendMessage(imapConfig, sentFolder, rawBase64Url, "(\\Seen)");
-    } catch (err) {
+    if (sent_copy.status === "failed") {
       // Non-fatal: message was sent successfully, just not copied to server Sent folder
       console.error(
         "[IMAP] Failed to copy sent message to Sent folder on server:",
-        err,
+        sent_copy.error,
       );
     }
 
@@ -464,7 +590,7 @@ export class ImapSmtpProvider implements EmailProvider {
       await upsertThread({
         id: effectiveThreadId,
         accountId: this.accountId,
-        subject,
+        subject: getThreadSubject([subject]),
         snippet,
         lastMessageAt: now,
         messageCount: 1,
@@ -507,6 +633,7 @@ export class ImapSmtpProvider implements EmailProvider {
       messageIdHeader,
       referencesHeader: references,
       inReplyToHeader: inReplyTo,
+      isFromMe: true,
     });
   }
 
@@ -514,15 +641,7 @@ export class ImapSmtpProvider implements EmailProvider {
     rawBase64Url: string,
     _threadId?: string,
   ): Promise<{ draftId: string }> {
-    const config = await this.getImapConfig();
-    const draftsFolder =
-      (await findSpecialFolder(this.accountId, "\\Drafts")) ?? "Drafts";
-
-    await imapAppendMessage(config, draftsFolder, rawBase64Url, "(\\Draft)");
-
-    // IMAP APPEND does not return the new UID, so generate a pseudo draft ID
-    const draftId = `imap-draft-${Date.now()}-${Math.random().toString(36).slice(2)}`;
-    return { draftId };
+    return this.saveDraft(rawBase64Url, null);
   }
 
   async updateDraft(
@@ -530,14 +649,35 @@ export class ImapSmtpProvider implements EmailProvider {
     rawBase64Url: string,
     _threadId?: string,
   ): Promise<{ draftId: string }> {
-    // Delete the old draft first, then create a new one
+    // Save the new copy before deleting the old one, so unchanged
+    // attachments can be copied from it on the server
+    const { uid } = this.parseImapMessageId(draftId);
+    const result = await this.saveDraft(rawBase64Url, uid);
+
     try {
       await this.deleteDraft(draftId);
     } catch {
-      // Old draft may already be gone; continue with creating the new one
+      // Old draft may already be gone
     }
 
-    return this.createDraft(rawBase64Url, _threadId);
+    return result;
+  }
+
+  private async saveDraft(
+    rawBase64Url: string,
+    replacesUid: number | null,
+  ): Promise<{ draftId: string }> {
+    const accountId = await this.credentials();
+    const draftsFolder =
+      (await findSpecialFolder(this.accountId, "\\Drafts")) ?? "Drafts";
+
+    const uid = await imapSaveDraft(accountId, draftsFolder, rawBase64Url, replacesUid);
+
+    // Without UIDPLUS the server doesn't return the new UID, so generate a pseudo draft ID
+    const draftId = uid !== null
+      ? `imap-${this.accountId}-${draftsFolder}-${uid}`
+      : `imap-draft-${Date.now()}-${Math.random().toString(36).slice(2)}`;
+    return { draftId };
   }
 
   async deleteDraft(draftId: string): Promise<void> {
@@ -546,8 +686,8 @@ export class ImapSmtpProvider implements EmailProvider {
     const { folder, uid } = this.parseImapMessageId(draftId);
 
     if (uid !== null && folder) {
-      const config = await this.getImapConfig();
-      await imapDeleteMessages(config, folder, [uid]);
+      const accountId = await this.credentials();
+      await imapDeleteMessages(accountId, folder, [uid]);
     } else {
       // Generated draft IDs (imap-draft-...) can't be mapped back to a server UID
       console.warn(
@@ -0,0 +1,22 @@
+import { describe, it, expect } from "vitest";
+import { newsgroupsFor } from "./nntpProvider";
+
+const article = (headers: string) => `${headers}\r\nSubject: Hi\r\n\r\nBody`;
+
+describe("newsgroupsFor", () => {
+  it("posts replies to the groups of the thread", () => {
+    expect(
+      newsgroupsFor(article("To: someone@example.org"), ["folder-comp.lang.rust", "UNREAD"]),
+    ).toEqual(["comp.lang.rust"]);
+  });
+
+  it("takes group names from the recipients", () => {
+    expect(
+      newsgroupsFor(article("To: alt.test, friend@example.org\r\nCc: misc.test"), []),
+    ).toEqual(["alt.test", "misc.test"]);
+  });
+
+  it("finds nothing to post to in a plain email", () => {
+    expect(newsgroupsFor(article("To: Friend <friend@example.org>"), ["INBOX"])).toEqual([]);
+  });
+});
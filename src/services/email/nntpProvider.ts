@@ -0,0 +1,275 @@
+import type { EmailProvider, EmailFolder, SyncResult } from "./types";
+import type { ParsedMessage } from "../gmail/messageParser";
+import { base64UrlDecode, parseBasicHeaders } from "./imapSmtpProvider";
+import { imapMessageToParsedMessage } from "../imap/imapSync";
+import {
+  buildNntpConfig,
+  getSubscribedGroups,
+  groupLabelId,
+  nntpSync,
+  registerNntpCredentials,
+  subscribeGroup,
+  unsubscribeGroup,
+} from "../nntp/nntpSync";
+import {
+  nntpFetchArticles,
+  nntpFetchAttachment,
+  nntpFetchRawArticle,
+  nntpGroupStatus,
+  nntpPostArticle,
+  nntpTestConnection,
+} from "../nntp/tauriCommands";
+import { getAccount, type DbAccount } from "../db/accounts";
+import { getThreadLabelIds } from "../db/threads";
+import { openAttachmentFile } from "../attachments/openAttachmentFile";
+
+function groupFolder(group: string): EmailFolder {
+  return {
+    id: groupLabelId(group),
+    name: group,
+    path: group,
+    type: "user",
+    specialUse: null,
+    delimiter: ".",
+    messageCount: 0,
+    unreadCount: 0,
+  };
+}
+
+/**
+ * The groups a composed article goes to: those of the thread it replies to,
+ * plus any group names (recipients without an "@") in To, Cc or Newsgroups.
+ */
+export function newsgroupsFor(raw: string, threadLabelIds: string[]): string[] {
+  const groups = new Set(
+    threadLabelIds
+      .filter((id) => id.startsWith("folder-"))
+      .map((id) => id.slice("folder-".length)),
+  );
+  const headers = parseBasicHeaders(raw);
+  for (const name of ["to", "cc", "newsgroups"]) {
+    for (const entry of (headers.get(name) ?? "").split(",")) {
+      const group = entry.trim();
+      if (group && !group.includes("@") && !/[\s<>"]/.test(group)) groups.add(group);
+    }
+  }
+  return [...groups];
+}
+
+/**
+ * EmailProvider adapter for newsgroup (NNTP) accounts. Subscribed groups
+ * are the folders; read, starred and moved state is kept locally because a
+ * news server has none, so those actions don't reach the server.
+ */
+export class NntpProvider implements EmailProvider {
+  readonly accountId: string;
+  readonly type = "nntp" as const;
+
+  private _registered = false;
+
+  constructor(accountId: string) {
+    this.accountId = accountId;
+  }
+
+  private async getAccount(): Promise<DbAccount> {
+    const account = await getAccount(this.accountId);
+    if (!account) {
+      throw new Error(`Account ${this.accountId} not found`);
+    }
+    return account;
+  }
+
+  /** Register the news server with the backend once; returns the account id. */
+  private async credentials(): Promise<string> {
+    if (!this._registered) {
+      await registerNntpCredentials(await this.getAccount());
+      this._registered = true;
+    }
+    return this.accountId;
+  }
+
+  clearConfigCache(): void {
+    this._registered = false;
+  }
+
+  // ---- Folder/Label operations ----
+
+  async listFolders(): Promise<EmailFolder[]> {
+    const groups = await getSubscribedGroups(this.accountId);
+    return groups.map(groupFolder);
+  }
+
+  /** Subscribe to the group `name`; fails if the server doesn't carry it. */
+  async createFolder(name: string, _parentPath?: string): Promise<EmailFolder> {
+    const accountId = await this.credentials();
+    const status = await nntpGroupStatus(accountId, name);
+    await subscribeGroup(this.accountId, status.name);
+    return { ...groupFolder(status.name), messageCount: status.count };
+  }
+
+  async deleteFolder(path: string): Promise<void> {
+    await unsubscribeGroup(this.accountId, path);
+  }
+
+  async renameFolder(_path: string, _newName: string): Promise<void> {
+    throw new Error("Newsgroups can't be renamed");
+  }
+
+  // ---- Sync operations ----
+
+  async initialSync(
+    _daysBack: number,
+    _onProgress?: (phase: string, current: number, total: number) => void,
+  ): Promise<SyncResult> {
+    return nntpSync(this.accountId);
+  }
+
+  async deltaSync(_syncToken: string): Promise<SyncResult> {
+    return nntpSync(this.accountId);
+  }
+
+  // ---- Message operations ----
+
+  async fetchMessage(messageId: string): Promise<ParsedMessage> {
+    const { group, number } = this.parseArticleId(messageId);
+    const accountId = await this.credentials();
+    const [article] = await nntpFetchArticles(accountId, group, [number]);
+    if (!article) {
+      throw new Error(`Article ${number} is no longer in ${group}`);
+    }
+    const { parsed } = imapMessageToParsedMessage(article, this.accountId, groupLabelId(group));
+    parsed.id = messageId;
+    return parsed;
+  }
+
+  async fetchAttachment(
+    messageId: string,
+    attachmentId: string,
+  ): Promise<{ data: string; size: number }> {
+    const { group, number } = this.parseArticleId(messageId);
+    const accountId = await this.credentials();
+    const data = await nntpFetchAttachment(accountId, group, number, attachmentId);
+    return { data, size: data.length };
+  }
+
+  async openAttachment(
+    messageId: string,
+    attachmentId: string,
+    filename: string | null,
+  ): Promise<void> {
+    const { data } = await this.fetchAttachment(messageId, attachmentId);
+    await openAttachmentFile(messageId, filename, { kind: "data", data });
+  }
+
+  async fetchRawMessage(messageId: string): Promise<string> {
+    const { group, number } = this.parseArticleId(messageId);
+    const accountId = await this.credentials();
+    return nntpFetchRawArticle(accountId, group, number);
+  }
+
+  async searchMessages(
+    _query: string,
+    _before: number | null,
+    _limit: number,
+  ): Promise<ParsedMessage[]> {
+    // NNTP has no server-side search; the local index covers synced articles
+    return [];
+  }
+
+  // ---- Actions (local only) ----
+
+  async archive(_threadId: string, _messageIds: string[]): Promise<void> {}
+
+  async trash(_threadId: string, _messageIds: string[]): Promise<void> {}
+
+  async permanentDelete(_threadId: string, _messageIds: string[]): Promise<void> {}
+
+  async markRead(_threadId: string, _messageIds: string[], _read: boolean): Promise<void> {}
+
+  async star(_threadId: string, _messageIds: string[], _starred: boolean): Promise<void> {}
+
+  async spam(_threadId: string, _messageIds: string[], _isSpam: boolean): Promise<void> {}
+
+  async moveToFolder(
+    _threadId: string,
+    _messageIds: string[],
+    _folderPath: string,
+  ): Promise<void> {}
+
+  async addLabel(_threadId: string, _labelId: string): Promise<void> {}
+
+  async removeLabel(_threadId: string, _labelId: string): Promise<void> {}
+
+  // ---- Send/Draft operations ----
+
+  /** Post to the groups of the thread being replied to and those named in To. */
+  async sendMessage(
+    rawBase64Url: string,
+    threadId?: string,
+  ): Promise<{ id: string }> {
+    const threadLabels = threadId ? await getThreadLabelIds(this.accountId, threadId) : [];
+    const groups = newsgroupsFor(base64UrlDecode(rawBase64Url), threadLabels);
+    if (groups.length === 0) {
+      throw new Error("Add a newsgroup to the To field to post this article");
+    }
+    // The article appears in its groups on the next sync
+    await nntpPostArticle(await this.credentials(), rawBase64Url, groups);
+    return { id: `nntp-posted-${Date.now()}` };
+  }
+
+  // News servers have no drafts folder; drafts live in the composer only
+
+  async createDraft(
+    _rawBase64Url: string,
+    _threadId?: string,
+  ): Promise<{ draftId: string }> {
+    return { draftId: `nntp-draft-${Date.now()}-${Math.random().toString(36).slice(2)}` };
+  }
+
+  async updateDraft(
+    draftId: string,
+    _rawBase64Url: string,
+    _threadId?: string,
+  ): Promise<{ draftId: string }> {
+    return { draftId };
+  }
+
+  async deleteDraft(_draftId: string): Promise<void> {}
+
+  // ---- Connection ----
+
+  async testConnection(): Promise<{ success: boolean; message: string }> {
+    try {
+      const message = await nntpTestConnection(buildNntpConfig(await this.getAccount()));
+      return { success: true, message };
+    } catch (err) {
+      return {
+        success: false,
+        message: `NNTP connection failed: ${err instanceof Error ? err.message : String(err)}`,
+      };
+    }
+  }
+
+  async getProfile(): Promise<{ email: string; name?: string }> {
+    const account = await this.getAccount();
+    return {
+      email: account.email,
+      name: account.display_name ?? undefined,
+    };
+  }
+
+  // ---- Helpers ----
+
+  /** Article ids have the IMAP format: imap-{accountId}-{group}-{number}. */
+  private parseArticleId(messageId: string): { group: string; number: number } {
+    const prefix = `imap-${this.accountId}-`;
+    const remainder = messageId.startsWith(prefix) ? messageId.slice(prefix.length) : "";
+    const lastDash = remainder.lastIndexOf("-");
+    const group = remainder.slice(0, Math.max(lastDash, 0));
+    const number = parseInt(remainder.slice(lastDash + 1), 10);
+    if (!group || isNaN(number)) {
+      throw new Error(`Invalid article ID format: ${messageId}`);
+    }
+    return { group, number };
+  }
+}
@@ -1,6 +1,7 @@
 import type { EmailProvider } from "./types";
 import { GmailApiProvider } from "./gmailProvider";
 import { ImapSmtpProvider } from "./imapSmtpProvider";
+import { NntpProvider } from "./nntpProvider";
 import { getAccount } from "../db/accounts";
 import { getGmailClient } from "../gmail/tokenManager";
 
@@ -23,6 +24,8 @@ export async function getEmailProvider(
 
   if (account.provider === "imap") {
     provider = new ImapSmtpProvider(accountId);
+  } else if (account.provider === "nntp") {
+    provider = new NntpProvider(accountId);
   } else {
     // Default: gmail_api
     const client = await getGmailClient(accountId);
@@ -0,0 +1,125 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+
+vi.mock("@/services/identity", () => ({
+  matchMyAddresses: vi.fn(),
+}));
+
+import { matchMyAddresses } from "@/services/identity";
+import { buildReplyRecipients, getReplyRecipients, type ReplySource } from "./replyRecipients";
+
+function message(overrides: Partial<ReplySource> = {}): ReplySource {
+  return {
+    from_address: "alice@example.com",
+    reply_to: null,
+    to_addresses: "Me <me@example.com>, bob@example.com",
+    cc_addresses: "carol@example.com, me+lists@example.com",
+    is_from_me: 0,
+    ...overrides,
+  };
+}
+
+const isMine = (address: string) => /me(\+\w+)?@example\.com/.test(address);
+
+describe("buildReplyRecipients", () => {
+  it("replies to the sender, or Reply-To", () => {
+    expect(buildReplyRecipients(message(), "reply", isMine)).toEqual({
+      to: ["alice@example.com"],
+      cc: [],
+    });
+    expect(
+      buildReplyRecipients(message({ reply_to: "list@example.com" }), "reply", isMine).to,
+    ).toEqual(["list@example.com"]);
+  });
+
+  it("leaves the user's addresses and duplicates out of reply-all", () => {
+    const msg = message({ cc_addresses: "carol@example.com, me+lists@example.com, BOB@example.com" });
+    expect(buildReplyRecipients(msg, "replyAll", isMine)).toEqual({
+      to: ["alice@example.com", "bob@example.com"],
+      cc: ["carol@example.com"],
+    });
+  });
+
+  it("follows up with the recipients of the user's own message", () => {
+    const sent = message({
+      from_address: "me@example.com",
+      to_addresses: "bob@example.com",
+      cc_addresses: "carol@example.com",
+      is_from_me: 1,
+    });
+    expect(buildReplyRecipients(sent, "reply", isMine)).toEqual({ to: ["bob@example.com"], cc: [] });
+    expect(buildReplyRecipients(sent, "replyAll", isMine)).toEqual({
+      to: ["bob@example.com"],
+      cc: ["carol@example.com"],
+    });
+  });
+
+  it("keeps a note to self addressed to the user", () => {
+    const note = message({
+      from_address: "me@example.com",
+      to_addresses: "me@example.com",
+      cc_addresses: null,
+      is_from_me: 1,
+    });
+    expect(buildReplyRecipients(note, "replyAll", isMine)).toEqual({
+      to: ["me@example.com"],
+      cc: [],
+    });
+  });
+
+  it("sends reply-all to Mail-Followup-To when the sender set it", () => {
+    const msg = message({
+      mail_followup_to: "Dev <dev@lists.example.org>, me@example.com, DEV@lists.example.org",
+    });
+    expect(buildReplyRecipients(msg, "replyAll", isMine)).toEqual({
+      to: ["Dev <dev@lists.example.org>"],
+      cc: [],
+    });
+    // A plain reply still goes to the sender
+    expect(buildReplyRecipients(msg, "reply", isMine).to).toEqual(["alice@example.com"]);
+    // Only the user's own addresses: ignored
+    expect(
+      buildReplyRecipients(message({ mail_followup_to: "me@example.com" }), "replyAll", isMine).to,
+    ).toEqual(["alice@example.com", "bob@example.com"]);
+  });
+});
+
+describe("getReplyRecipients", () => {
+  beforeEach(() => {
+    vi.mocked(matchMyAddresses).mockReset();
+  });
+
+  it("asks the backend which addresses are the account's", async () => {
+    vi.mocked(matchMyAddresses).mockResolvedValue(new Set(["me+lists@example.com"]));
+
+    const result = await getReplyRecipients(message(), "replyAll", {
+      id: "acc-1",
+      email: "me@example.com",
+    });
+
+    expect(matchMyAddresses).toHaveBeenCalledWith(
+      [
+        "alice@example.com",
+        "Me <me@example.com>",
+        "bob@example.com",
+        "carol@example.com",
+        "me+lists@example.com",
+      ],
+      "acc-1",
+    );
+    // The account address counts as the user's even when not registered
+    expect(result).toEqual({ to: ["alice@example.com", "bob@example.com"], cc: ["carol@example.com"] });
+  });
+
+  it("falls back to the account address when matching fails", async () => {
+    vi.mocked(matchMyAddresses).mockRejectedValue(new Error("no backend"));
+    vi.spyOn(console, "warn").mockImplementation(() => {});
+
+    const result = await getReplyRecipients(message(), "replyAll", {
+      id: "acc-1",
+      email: "ME@example.com",
+    });
+
+    expect(result.to).toEqual(["alice@example.com", "bob@example.com"]);
+    expect(result.cc).toEqual(["carol@example.com", "me+lists@example.com"]);
+  });
+});
@@ -0,0 +1,116 @@
+import { matchMyAddresses } from "@/services/identity";
+
+/** The fields of a message that decide who a reply goes to. */
+export interface ReplySource {
+  from_address: string | null;
+  reply_to: string | null;
+  to_addresses: string | null;
+  cc_addresses: string | null;
+  is_from_me?: number | boolean | null;
+  mail_followup_to?: string | null;
+}
+
+export type ReplyRecipientMode = "reply" | "replyAll";
+
+export interface ReplyRecipients {
+  to: string[];
+  cc: string[];
+}
+
+function splitAddresses(field: string | null): string[] {
+  if (!field) return [];
+  return field.split(",").map((a) => a.trim()).filter(Boolean);
+}
+
+/** Lowercased bare address, for spotting the same recipient twice. */
+function addressKey(address: string): string {
+  const angle = /<([^>]+)>/.exec(address);
+  return (angle ? angle[1]! : address).trim().toLowerCase();
+}
+
+/** Every address a reply to `msg` could go to. */
+export function replyCandidates(msg: ReplySource): string[] {
+  const sender = msg.reply_to ?? msg.from_address;
+  return [
+    ...(sender ? [sender] : []),
+    ...splitAddresses(msg.to_addresses),
+    ...splitAddresses(msg.cc_addresses),
+    ...splitAddresses(msg.mail_followup_to ?? null),
+  ];
+}
+
+/**
+ * Recipients of a reply to `msg`. Replying to a message the user sent
+ * follows up with its recipients instead of writing to themselves;
+ * reply-all goes to the Mail-Followup-To addresses if the sender set them,
+ * and leaves out the user's own addresses (`isMine`) and anyone listed
+ * twice.
+ */
+export function buildReplyRecipients(
+  msg: ReplySource,
+  mode: ReplyRecipientMode,
+  isMine: (address: string) => boolean,
+): ReplyRecipients {
+  const sender = msg.reply_to ?? msg.from_address;
+  const toList = splitAddresses(msg.to_addresses);
+  const fromMe = !!msg.is_from_me;
+
+  const direct = fromMe && toList.length > 0 ? toList : sender ? [sender] : [];
+  if (mode === "reply") return { to: direct, cc: [] };
+
+  const seen = new Set<string>();
+  const keep = (address: string) => {
+    const key = addressKey(address);
+    if (isMine(address) || seen.has(key)) return false;
+    seen.add(key);
+    return true;
+  };
+
+  // The sender asked for follow-ups to go only there, e.g. to a list
+  // they're subscribed to, so they don't get a copy of their own
+  const followupTo = splitAddresses(msg.mail_followup_to ?? null).filter(keep);
+  if (followupTo.length > 0) return { to: followupTo, cc: [] };
+  seen.clear();
+
+  const to = (fromMe ? toList : [...(sender ? [sender] : []), ...toList]).filter(keep);
+  const cc = splitAddresses(msg.cc_addresses).filter(keep);
+
+  // A thread only between the user's own addresses
+  if (to.length === 0 && cc.length === 0) return { to: direct, cc: [] };
+  if (to.length === 0) return { to: cc, cc: [] };
+  return { to, cc };
+}
+
+/**
+ * Recipients of a reply from `account` to `msg`, asking the backend which
+ * addresses are the account's own. `account.email` counts as its own even
+ * before the account's addresses are registered.
+ */
+export async function getReplyRecipients(
+  msg: ReplySource,
+  mode: ReplyRecipientMode,
+  account: { id: string; email?: string | null },
+): Promise<ReplyRecipients> {
+  const mine = await findMyAddresses(msg, account);
+  return buildReplyRecipients(msg, mode, (a) => mine.has(a));
+}
+
+/** Which of the possible recipients of a reply to `msg` are `account`'s. */
+export async function findMyAddresses(
+  msg: ReplySource,
+  account: { id: string; email?: string | null },
+): Promise<Set<string>> {
+  const candidates = replyCandidates(msg);
+  const own = account.email ? addressKey(account.email) : null;
+  let mine: Set<string>;
+  try {
+    mine = await matchMyAddresses(candidates, account.id);
+  } catch (err) {
+    console.warn("Failed to match the user's addresses:", err);
+    mine = new Set();
+  }
+  for (const address of candidates) {
+    if (own && addressKey(address) === own) mine.add(address);
+  }
+  return mine;
+}
@@ -1,6 +1,6 @@
 import type { ParsedMessage } from "../gmail/messageParser";
 
-export type AccountProvider = "gmail_api" | "imap" | "caldav";
+export type AccountProvider = "gmail_api" | "imap" | "caldav" | "nntp";
 
 export interface EmailFolder {
   id: string;
@@ -46,7 +46,22 @@ export interface EmailProvider {
     messageId: string,
     attachmentId: string,
   ): Promise<{ data: string; size: number }>;
+  /** Open an attachment with the OS default app via a temp file. */
+  openAttachment(
+    messageId: string,
+    attachmentId: string,
+    filename: string | null,
+  ): Promise<void>;
   fetchRawMessage(messageId: string): Promise<string>;
+  /**
+   * Search the server for messages matching `query`, limited to those older
+   * than `before` (ms) when given, for ranges the local index doesn't cover.
+   */
+  searchMessages(
+    query: string,
+    before: number | null,
+    limit: number,
+  ): Promise<ParsedMessage[]>;
 
   // Actions (operate on thread/message level)
   archive(threadId: string, messageIds: string[]): Promise<void>;
@@ -1,4 +1,4 @@
-import { describe, it, expect, beforeEach, vi } from "vitest";
+import { describe, it, expect, beforeEach, afterEach, vi } from "vitest";
 
 // Mock dependencies
 vi.mock("@/stores/uiStore", () => ({
@@ -33,18 +33,45 @@ vi.mock("@/services/db/connection", () => ({
   ),
 }));
 
+vi.mock("@/services/db/auditLog", () => ({
+  getAuditTarget: vi.fn((_accountId: string, threadId: string, messageIds: string[]) =>
+    Promise.resolve({ threadId, folder: "INBOX", uids: messageIds }),
+  ),
+  recordAudit: vi.fn(() => Promise.resolve()),
+}));
+
+vi.mock("@/services/db/messages", () => ({
+  getThreadImapLocations: vi.fn(() => Promise.resolve([])),
+}));
+
+vi.mock("@/bindings", () => ({
+  commands: {
+    imapStageDelete: vi.fn(),
+    undoOperation: vi.fn(() => Promise.resolve({ status: "ok", data: false })),
+    stagedOperationList: vi.fn(),
+  },
+}));
+
 import { useUIStore } from "@/stores/uiStore";
 import { useThreadStore } from "@/stores/threadStore";
 import { getEmailProvider } from "@/services/email/providerFactory";
 import { enqueuePendingOperation } from "@/services/db/pendingOperations";
+import { recordAudit } from "@/services/db/auditLog";
+import { getThreadImapLocations } from "@/services/db/messages";
+import { commands } from "@/bindings";
 import {
   archiveThread,
   trashThread,
   starThread,
   markThreadRead,
   spamThread,
+  permanentDeleteThread,
+  permanentDeleteThreads,
   executeEmailAction,
 } from "./emailActions";
+import { undoOperation } from "./stagedOperations";
+import { useStagedOperationStore } from "@/stores/stagedOperationStore";
+import { READ_ONLY_ERROR } from "./readOnly";
 import { createMockEmailProvider, createMockUIStoreState, createMockThreadStoreState } from "@/test/mocks";
 
 const mockProvider = createMockEmailProvider();
@@ -72,6 +99,14 @@ describe("emailActions", () => {
       expect(mockProvider.archive).toHaveBeenCalledWith("t1", ["m1"]);
     });
 
+    it("keeps actions on feed entries local", async () => {
+      const result = await archiveThread("acct-1", "feed-f1-abc", ["feed-f1-abc"]);
+      expect(result.success).toBe(true);
+      expect(mockRemoveThread).toHaveBeenCalledWith("feed-f1-abc");
+      expect(getEmailProvider).not.toHaveBeenCalled();
+      expect(recordAudit).not.toHaveBeenCalled();
+    });
+
     it("trashes a thread via provider", async () => {
       const result = await trashThread("acct-1", "t1", ["m1"]);
       expect(result.success).toBe(true);
@@ -124,6 +159,20 @@ describe("emailActions", () => {
     });
   });
 
+  describe("read-only mode", () => {
+    it("refuses actions without touching the UI, queue or provider", async () => {
+      vi.mocked(useUIStore.getState).mockReturnValue(
+        createMockUIStoreState({ isReadOnly: true }) as never,
+      );
+
+      const result = await archiveThread("acct-1", "t1", ["m1"]);
+      expect(result).toEqual({ success: false, error: READ_ONLY_ERROR });
+      expect(mockRemoveThread).not.toHaveBeenCalled();
+      expect(mockProvider.archive).not.toHaveBeenCalled();
+      expect(enqueuePendingOperation).not.toHaveBeenCalled();
+    });
+  });
+
   describe("network error → queue fallback", () => {
     it("queues on retryable network error", async () => {
       vi.mocked(useUIStore.getState).mockReturnValue({ isOnline: true } as never);
@@ -159,6 +208,119 @@ describe("emailActions", () => {
     });
   });
 
+  describe("permanent delete undo window", () => {
+    beforeEach(() => {
+      vi.useFakeTimers();
+    });
+
+    afterEach(() => {
+      vi.useRealTimers();
+    });
+
+    it("holds the delete until the window closes", async () => {
+      const result = await permanentDeleteThread("acct-1", "t1", []);
+      expect(result.success).toBe(true);
+      expect(result.operationId).toBeTruthy();
+      expect(mockRemoveThread).toHaveBeenCalledWith("t1");
+      expect(mockProvider.permanentDelete).not.toHaveBeenCalled();
+
+      await vi.advanceTimersByTimeAsync(10_000);
+      expect(mockProvider.permanentDelete).toHaveBeenCalledWith("t1", []);
+    });
+
+    it("never deletes once undone and reloads the list", async () => {
+      const listener = vi.fn();
+      window.addEventListener("velo-sync-done", listener);
+
+      const result = await permanentDeleteThread("acct-1", "t1", []);
+      expect(await undoOperation(result.operationId!)).toBe(true);
+      await vi.advanceTimersByTimeAsync(10_000);
+
+      window.removeEventListener("velo-sync-done", listener);
+      expect(mockProvider.permanentDelete).not.toHaveBeenCalled();
+      expect(listener).toHaveBeenCalledTimes(1);
+      expect(await undoOperation(result.operationId!)).toBe(false);
+    });
+
+    it("stages several threads as one operation", async () => {
+      const result = await permanentDeleteThreads("acct-1", ["t1", "t2"]);
+      expect(result.operationId).toBeTruthy();
+      expect(mockProvider.permanentDelete).not.toHaveBeenCalled();
+
+      await vi.advanceTimersByTimeAsync(10_000);
+      expect(mockProvider.permanentDelete).toHaveBeenCalledTimes(2);
+      expect(mockProvider.permanentDelete).toHaveBeenCalledWith("t2", []);
+    });
+
+    it("hands IMAP deletes to the backend with their uids", async () => {
+      vi.mocked(getEmailProvider).mockResolvedValue({ ...mockProvider, type: "imap" } as never);
+      vi.mocked(getThreadImapLocations).mockImplementation((_accountId, threadId) =>
+        Promise.resolve([{ folder: "Trash", uids: threadId === "t1" ? [3, 4] : [9] }]),
+      );
+      vi.mocked(commands.imapStageDelete).mockResolvedValue({
+        status: "ok",
+        data: { id: "op-9", account_id: "acct-1", label: "", targets: [], run_at: 0, attempts: 0 },
+      });
+
+      const result = await permanentDeleteThreads("acct-1", ["t1", "t2"]);
+      expect(result).toEqual({ success: true, operationId: "op-9" });
+      expect(commands.imapStageDelete).toHaveBeenCalledWith(
+        "acct-1",
+        "2 conversations deleted",
+        [
+          { thread_id: "t1", folder: "Trash", uids: [3, 4] },
+          { thread_id: "t2", folder: "Trash", uids: [9] },
+        ],
+        10,
+      );
+
+      await vi.advanceTimersByTimeAsync(10_000);
+      expect(mockProvider.permanentDelete).not.toHaveBeenCalled();
+    });
+
+    it("tells the user when a held delete fails", async () => {
+      mockProvider.permanentDelete.mockRejectedValueOnce(new Error("Invalid request"));
+      vi.spyOn(console, "error").mockImplementation(() => {});
+      useStagedOperationStore.setState({ operations: [], error: null });
+
+      await permanentDeleteThread("acct-1", "t1", []);
+      await vi.advanceTimersByTimeAsync(10_000);
+
+      expect(useStagedOperationStore.getState().error).toBe("Delete failed: Invalid request");
+    });
+  });
+
+  describe("audit log", () => {
+    it("records a successful operation with its folder and uids", async () => {
+      await starThread("acct-1", "t1", ["m1"], true);
+      expect(recordAudit).toHaveBeenCalledWith(
+        "acct-1",
+        "star",
+        { threadId: "t1", folder: "INBOX", uids: ["m1"], detail: "starred" },
+        "ok",
+        undefined,
+      );
+    });
+
+    it("records a permanent failure with the error", async () => {
+      mockProvider.archive.mockRejectedValueOnce(new Error("Invalid request"));
+      await archiveThread("acct-1", "t1", ["m1"]);
+      expect(recordAudit).toHaveBeenCalledWith(
+        "acct-1",
+        "archive",
+        expect.objectContaining({ threadId: "t1" }),
+        "failed",
+        expect.any(String),
+      );
+    });
+
+    it("still succeeds when the audit write fails", async () => {
+      vi.mocked(recordAudit).mockRejectedValueOnce(new Error("disk full"));
+      const result = await trashThread("acct-1", "t1", ["m1"]);
+      expect(result.success).toBe(true);
+    });
+  });
+
   describe("executeEmailAction with draft actions", () => {
     it("sends a message via provider", async () => {
       const result = await executeEmailAction("acct-1", {
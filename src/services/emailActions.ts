@@ -5,6 +5,23 @@ import { enqueuePendingOperation } from "@/services/db/pendingOperations";
 import { classifyError } from "@/utils/networkErrors";
 import { getDb } from "@/services/db/connection";
 import { logEmailAction, getThreadMeta } from "@/services/ai/behaviorTracker";
+import { getThreadIdsWithLabel } from "@/services/db/threads";
+import { getThreadImapLocations } from "@/services/db/messages";
+import {
+  getUndoWindowMs,
+  reportOperationFailure,
+  stageImapDelete,
+  stageOperation,
+} from "@/services/stagedOperations";
+import type { DeleteTarget } from "@/bindings";
+import { READ_ONLY_ERROR } from "@/services/readOnly";
+import { isFeedThreadId, removeFromFeedsFolder } from "@/services/db/feeds";
+import {
+  getAuditTarget,
+  recordAudit,
+  type AuditResult,
+  type AuditTarget,
+} from "@/services/db/auditLog";
 
 // ---------------------------------------------------------------------------
 // Action types
@@ -67,6 +84,8 @@ export interface ActionResult {
   queued?: boolean;
   error?: string;
   data?: unknown;
+  /** Set when the action is held for the undo window; pass to undoOperation. */
+  operationId?: string;
 }
 
 // ---------------------------------------------------------------------------
@@ -286,12 +305,83 @@ export async function executeEmailAction(
   accountId: string,
   action: EmailAction,
 ): Promise<ActionResult> {
+  // Gmail changes go straight to the API, so the backend's read-only check
+  // can't catch them; refuse everything here before touching the UI
+  if (useUIStore.getState().isReadOnly) {
+    return { success: false, error: READ_ONLY_ERROR };
+  }
+
   // 0. Log behavior (non-blocking)
   logActionBehavior(accountId, action);
 
   // 1. Optimistic UI update
   applyOptimisticUpdate(action);
 
+  // Permanent deletes wait out the undo window before touching the DB or server
+  if (action.type === "permanentDelete") {
+    const windowMs = await getUndoWindowMs();
+    if (windowMs > 0) {
+      return stagePermanentDelete(accountId, [action], "Conversation deleted", windowMs);
+    }
+  }
+
+  return commitEmailAction(accountId, action);
+}
+
+/** Bring back threads hidden by an optimistic update that was undone. */
+function reloadThreadList(): void {
+  window.dispatchEvent(new Event("velo-sync-done"));
+}
+
+/**
+ * Hold permanent deletes for the undo window. IMAP deletes are staged in
+ * the backend, which keeps them across reloads and restarts; other
+ * providers delete from this window, so its timer holds them.
+ */
+async function stagePermanentDelete(
+  accountId: string,
+  threads: { threadId: string; messageIds: string[] }[],
+  label: string,
+  windowMs: number,
+): Promise<ActionResult> {
+  const provider = await getEmailProvider(accountId);
+  if (provider.type === "imap" && !threads.some((t) => isFeedThreadId(t.threadId))) {
+    try {
+      const targets: DeleteTarget[] = [];
+      for (const { threadId, messageIds } of threads) {
+        for (const { folder, uids } of await getThreadImapLocations(accountId, threadId, messageIds)) {
+          targets.push({ thread_id: threadId, folder, uids });
+        }
+      }
+      const operationId = await stageImapDelete(accountId, label, targets, windowMs, reloadThreadList);
+      return { success: true, operationId };
+    } catch (err) {
+      reportOperationFailure(err);
+      return { success: false, error: String(err) };
+    }
+  }
+
+  const commit = async () => {
+    for (const { threadId, messageIds } of threads) {
+      const result = await commitEmailAction(accountId, {
+        type: "permanentDelete",
+        threadId,
+        messageIds,
+      });
+      if (!result.success) throw new Error(result.error);
+    }
+  };
+  const operationId = stageOperation(label, windowMs, commit, reloadThreadList);
+  return { success: true, operationId };
+}
+
+async function commitEmailAction(
+  accountId: string,
+  action: EmailAction,
+): Promise<ActionResult> {
+  // Resolve folder/UIDs for the audit log while the local rows still exist
+  const auditTarget = await resolveAuditTarget(accountId, action);
+
   // 2. Local DB update
   try {
     await applyLocalDbUpdate(accountId, action);
@@ -299,6 +389,20 @@ export async function executeEmailAction(
     console.warn("Local DB update failed:", err);
   }
 
+  // Feed entries only exist in the local cache, so there's nothing to send
+  const threadId = "threadId" in action ? action.threadId : undefined;
+  if (threadId && isFeedThreadId(threadId)) {
+    if (
+      action.type === "archive" ||
+      action.type === "trash" ||
+      action.type === "spam" ||
+      action.type === "moveToFolder"
+    ) {
+      await removeFromFeedsFolder(accountId, threadId);
+    }
+    return { success: true };
+  }
+
   // 3. If offline, queue
   if (!useUIStore.getState().isOnline) {
     await enqueuePendingOperation(
@@ -307,12 +411,14 @@ export async function executeEmailAction(
       getResourceId(action),
       actionToParams(action),
     );
+    await audit(accountId, action.type, auditTarget, "queued");
     return { success: true, queued: true };
   }
 
   // 4. Try online execution
   try {
     const data = await executeViaProvider(accountId, action);
+    await audit(accountId, action.type, auditTarget, "ok");
     return { success: true, data };
   } catch (err) {
     const classified = classifyError(err);
@@ -325,16 +431,73 @@ export async function executeEmailAction(
         getResourceId(action),
         actionToParams(action),
       );
+      await audit(accountId, action.type, auditTarget, "queued", classified.message);
       return { success: true, queued: true };
     }
 
     // Permanent error — revert optimistic update
     revertOptimisticUpdate(action);
     console.error(`Email action ${action.type} failed permanently:`, err);
+    await audit(accountId, action.type, auditTarget, "failed", classified.message);
     return { success: false, error: classified.message };
   }
 }
 
+// ---------------------------------------------------------------------------
+// Audit log
+// ---------------------------------------------------------------------------
+
+function auditDetail(action: EmailAction): string | null {
+  switch (action.type) {
+    case "markRead":
+      return action.read ? "read" : "unread";
+    case "star":
+      return action.starred ? "starred" : "unstarred";
+    case "spam":
+      return action.isSpam ? "spam" : "not spam";
+    case "moveToFolder":
+      return action.folderPath;
+    case "addLabel":
+    case "removeLabel":
+      return action.labelId;
+    case "updateDraft":
+    case "deleteDraft":
+      return action.draftId;
+    default:
+      return null;
+  }
+}
+
+async function resolveAuditTarget(
+  accountId: string,
+  action: EmailAction,
+): Promise<AuditTarget> {
+  const threadId = "threadId" in action ? action.threadId ?? null : null;
+  const messageIds = "messageIds" in action ? action.messageIds : [];
+  const detail = auditDetail(action);
+  if (!threadId) return { threadId, uids: [], detail };
+  try {
+    return { ...(await getAuditTarget(accountId, threadId, messageIds)), detail };
+  } catch {
+    return { threadId, uids: messageIds, detail };
+  }
+}
+
+/** Record an operation in the audit log; never fails the action itself. */
+async function audit(
+  accountId: string,
+  operation: string,
+  target: AuditTarget,
+  result: AuditResult,
+  error?: string,
+): Promise<void> {
+  try {
+    await recordAudit(accountId, operation, target, result, error);
+  } catch (err) {
+    console.warn("Failed to record audit entry:", err);
+  }
+}
+
 // Log behavior (non-blocking, after action dispatch)
 function logActionBehavior(accountId: string, action: EmailAction): void {
   const threadId = "threadId" in action ? action.threadId : "";
@@ -349,13 +512,29 @@ function logActionBehavior(accountId: string, action: EmailAction): void {
 // Execute a queued operation (used by queue processor)
 // ---------------------------------------------------------------------------
 
+/**
+ * Run an operation from the offline queue. Failures are thrown, not
+ * audited: only the queue processor knows whether the operation will be
+ * tried again, so it records the outcome with `auditQueuedAction`.
+ */
 export async function executeQueuedAction(
   accountId: string,
   operationType: string,
   params: Record<string, unknown>,
+): Promise<void> {
+  await executeViaProvider(accountId, { type: operationType, ...params } as EmailAction);
+}
+
+/** Record how a queued operation ended: it ran, or it won't be tried again. */
+export async function auditQueuedAction(
+  accountId: string,
+  operationType: string,
+  params: Record<string, unknown>,
+  result: AuditResult,
+  error?: string,
 ): Promise<void> {
   const action = { type: operationType, ...params } as EmailAction;
-  await executeViaProvider(accountId, action);
+  await audit(accountId, action.type, await resolveAuditTarget(accountId, action), result, error);
 }
 
 // ---------------------------------------------------------------------------
@@ -398,6 +577,44 @@ export function permanentDeleteThread(
   });
 }
 
+/**
+ * Permanently delete several conversations as one undoable operation. The
+ * threads leave the list immediately; nothing is deleted until the undo
+ * window closes.
+ */
+export async function permanentDeleteThreads(
+  accountId: string,
+  threadIds: string[],
+): Promise<ActionResult> {
+  if (threadIds.length === 0) return { success: true };
+
+  useThreadStore.getState().removeThreads(threadIds);
+  const threads = threadIds.map((threadId) => ({ threadId, messageIds: [] }));
+
+  const windowMs = await getUndoWindowMs();
+  if (windowMs === 0) {
+    for (const { threadId, messageIds } of threads) {
+      const result = await commitEmailAction(accountId, {
+        type: "permanentDelete",
+        threadId,
+        messageIds,
+      });
+      if (!result.success) return result;
+    }
+    return { success: true };
+  }
+  const label = `${threadIds.length} conversation${threadIds.length !== 1 ? "s" : ""} deleted`;
+  return stagePermanentDelete(accountId, threads, label, windowMs);
+}
+
+/** Permanently delete everything in the account's trash, after the undo window. */
+export async function emptyTrash(accountId: string): Promise<ActionResult> {
+  return permanentDeleteThreads(
+    accountId,
+    await getThreadIdsWithLabel(accountId, "TRASH"),
+  );
+}
+
 export function markThreadRead(
   accountId: string,
   threadId: string,
@@ -0,0 +1,81 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+import { invoke } from "@tauri-apps/api/core";
+import { copyOtpCode, extractOtp } from "./otp";
+
+beforeEach(() => {
+  vi.clearAllMocks();
+});
+
+describe("extractOtp", () => {
+  it("finds codes in the subject", () => {
+    expect(
+      extractOtp({
+        subject: "G-482913 is your Google verification code",
+        body_text: null,
+        from_address: "noreply@google.com",
+      }),
+    ).toBe("482913");
+  });
+
+  it("finds codes in the body of automated mail", () => {
+    expect(
+      extractOtp({
+        subject: "Sign in to Example",
+        body_text: "Enter this code to sign in: 739-201\nIt expires in 10 minutes.",
+        from_address: "no-reply@example.com",
+      }),
+    ).toBe("739201");
+    expect(
+      extractOtp({
+        subject: "Confirm your email",
+        body_text: "Your code is\n\n  AB12CD\n",
+        from_address: "verify@example.com",
+      }),
+    ).toBe("AB12CD");
+  });
+
+  it("ignores numbers in a person's mail", () => {
+    expect(
+      extractOtp({
+        subject: "Lunch",
+        body_text: "The door code is 4821, see you at 12:30",
+        from_address: "alice@example.com",
+      }),
+    ).toBeNull();
+  });
+
+  it("ignores mail that doesn't mention a code", () => {
+    expect(
+      extractOtp({
+        subject: "Your order 123456 has shipped",
+        body_text: "Order 123456 is on its way",
+        from_address: "no-reply@shop.example",
+      }),
+    ).toBeNull();
+  });
+
+  it("doesn't take years or words for codes", () => {
+    expect(
+      extractOtp({
+        subject: "Your verification code",
+        body_text: "Use the code below.\n\n2026\n",
+        from_address: "security@example.com",
+      }),
+    ).toBeNull();
+  });
+});
+
+describe("copyOtpCode", () => {
+  it("leaves the copy to the backend", async () => {
+    vi.mocked(invoke).mockResolvedValue(undefined);
+
+    await copyOtpCode("482913");
+
+    expect(invoke).toHaveBeenCalledWith("otp_copy_code", { code: "482913" });
+  });
+});
@@ -0,0 +1,70 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * One-time codes in sign-in and verification mail. A code is only taken
+ * from a message that talks about one ("verification code", "your code is")
+ * and comes from an automated sender, or says so in its subject, so that
+ * numbers in ordinary mail aren't offered for copying.
+ */
+
+export interface OtpSource {
+  subject: string | null;
+  body_text: string | null;
+  from_address: string | null;
+}
+
+const KEYWORD_PATTERN =
+  /\b(?:verification|security|one[- ]time|login|log[- ]in|sign[- ]in|confirmation|authentication|2fa|two[- ]factor|otp|passcode|access)\s+(?:code|pin|password)\b|\b(?:your|the|this)\s+code\b|\bcode\s+is\b|\bpasscode\b|\bOTP\b/i;
+
+const AUTOMATED_SENDER =
+  /^(?:no-?reply|do-?not-?reply|verify|verification|security|accounts?|auth|login|signin|notifications?|alerts?|info|support|mfa|otp)\b/i;
+
+/** A code after "code"/"PIN", before "is your ... code", or on a line of its own. */
+const CODE_PATTERNS = [
+  /\b(?:code|pin|passcode|otp)\b[^\n\d]{0,40}?\b(\d{3}[- ]\d{3}|(?=[A-Z-]*\d)[A-Z0-9]{4,8})\b/i,
+  /\b(\d{4,8}|\d{3}[- ]\d{3})\s+is\s+your\b/i,
+  /^\s*(\d{3}[- ]\d{3}|(?=[A-Z-]*\d)[A-Z0-9]{4,8})\s*$/im,
+];
+
+/** Only the first part of the body; codes come early. */
+const BODY_SCAN_LENGTH = 2000;
+
+function isPlausible(code: string): boolean {
+  // Needs a digit so words like "below" aren't codes; four-digit years aren't either
+  return /\d/.test(code) && !/^(?:19|20)\d\d$/.test(code);
+}
+
+function findCode(text: string): string | null {
+  for (const pattern of CODE_PATTERNS) {
+    const candidate = pattern.exec(text)?.[1];
+    if (candidate && isPlausible(candidate)) return candidate.replace(/[- ]/g, "");
+  }
+  return null;
+}
+
+/** The one-time code in a message, or null if it doesn't carry one. */
+export function extractOtp(message: OtpSource): string | null {
+  const subject = message.subject ?? "";
+  const body = (message.body_text ?? "").slice(0, BODY_SCAN_LENGTH);
+  const subjectMentions = KEYWORD_PATTERN.test(subject);
+  if (!subjectMentions && !KEYWORD_PATTERN.test(body)) return null;
+
+  const localPart = message.from_address?.split("@")[0] ?? "";
+  if (!subjectMentions && !AUTOMATED_SENDER.test(localPart)) return null;
+
+  return findCode(subject) ?? findCode(body);
+}
+
+/** Copy a code; the backend clears it from the clipboard after a while. */
+export function copyOtpCode(code: string): Promise<void> {
+  return invoke("otp_copy_code", { code });
+}
+
+export function getOtpClipboardSeconds(): Promise<number> {
+  return invoke<number>("get_otp_clipboard_seconds");
+}
+
+/** 0 leaves copied codes on the clipboard. */
+export function setOtpClipboardSeconds(seconds: number): Promise<void> {
+  return invoke("set_otp_clipboard_seconds", { seconds });
+}
@@ -0,0 +1,32 @@
+import { detectPurchase } from "./receipts";
+import { upsertPurchase } from "@/services/db/purchases";
+import type { ParsedMessage } from "@/services/gmail/messageParser";
+
+/**
+ * Record the receipts and invoices among newly synced messages.
+ * Non-blocking — all errors are caught and logged.
+ */
+export async function recordPurchasesFromMessages(
+  accountId: string,
+  messages: ParsedMessage[],
+): Promise<void> {
+  for (const message of messages) {
+    // The user's own mail isn't something they bought
+    if (message.isFromMe) continue;
+    try {
+      const purchase = detectPurchase({
+        subject: message.subject,
+        body_html: message.bodyHtml,
+        body_text: message.bodyText,
+        from_address: message.fromAddress,
+        from_name: message.fromName,
+        date: message.date,
+      });
+      if (purchase) {
+        await upsertPurchase(accountId, message.id, message.threadId, purchase);
+      }
+    } catch (err) {
+      console.error(`Failed to record purchase from message ${message.id}:`, err);
+    }
+  }
+}
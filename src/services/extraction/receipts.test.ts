@@ -0,0 +1,127 @@
+import { describe, it, expect } from "vitest";
+import { detectPurchase, findTotal, parseAmount, type PurchaseSource } from "./receipts";
+
+const DATE = Date.UTC(2027, 2, 4);
+
+function message(overrides: Partial<PurchaseSource>): PurchaseSource {
+  return {
+    subject: null,
+    body_html: null,
+    body_text: null,
+    from_address: "orders@shop.example",
+    from_name: "Example Shop",
+    date: DATE,
+    ...overrides,
+  };
+}
+
+describe("parseAmount", () => {
+  it("reads either decimal mark", () => {
+    expect(parseAmount("1,234.50")).toBe(123450);
+    expect(parseAmount("1.234,50")).toBe(123450);
+    expect(parseAmount("12,5")).toBe(1250);
+    expect(parseAmount("1,299")).toBe(129900);
+    expect(parseAmount("42")).toBe(4200);
+    expect(parseAmount("abc")).toBeNull();
+  });
+});
+
+describe("findTotal", () => {
+  it("takes the final total after subtotals", () => {
+    expect(findTotal("Subtotal: $10.00\nTax: $0.80\nTotal: $10.80")).toEqual({
+      currency: "USD",
+      amountCents: 1080,
+    });
+  });
+
+  it("prefers the amount charged over a plain total", () => {
+    const text = "Amount paid: €25,00\nTotal (before discount): €30,00";
+    expect(findTotal(text)).toEqual({ currency: "EUR", amountCents: 2500 });
+  });
+
+  it("reads currency codes and trailing symbols", () => {
+    expect(findTotal("ORDER TOTAL GBP 1,299.00")).toEqual({ currency: "GBP", amountCents: 129900 });
+    expect(findTotal("Invoice total: 1.234,50 €")).toEqual({ currency: "EUR", amountCents: 123450 });
+  });
+
+  it("ignores unlabelled amounts", () => {
+    expect(findTotal("Save $20 on your next visit")).toBeNull();
+  });
+});
+
+describe("detectPurchase", () => {
+  it("reads Order markup", () => {
+    const html = `<script type="application/ld+json">${JSON.stringify({
+      "@context": "http://schema.org",
+      "@type": "Order",
+      merchant: { "@type": "Organization", name: "Amazon.com" },
+      orderNumber: "123-4567890",
+      orderDate: "2027-03-01T12:00:00Z",
+      priceCurrency: "USD",
+      price: "29.99",
+    })}</script>`;
+
+    expect(detectPurchase(message({ subject: "Shipped!", body_html: html }))).toEqual({
+      kind: "receipt",
+      vendor: "Amazon.com",
+      date: Date.UTC(2027, 2, 1, 12),
+      currency: "USD",
+      amountCents: 2999,
+      orderNumber: "123-4567890",
+    });
+  });
+
+  it("reads Invoice markup with a payment due", () => {
+    const html = `<script type="application/ld+json">${JSON.stringify({
+      "@type": "Invoice",
+      provider: { name: "Acme Utilities" },
+      totalPaymentDue: { "@type": "PriceSpecification", price: 70, priceCurrency: "USD" },
+      confirmationNumber: "INV-88",
+    })}</script>`;
+
+    expect(detectPurchase(message({ body_html: html }))).toMatchObject({
+      kind: "invoice",
+      vendor: "Acme Utilities",
+      date: DATE,
+      amountCents: 7000,
+      orderNumber: "INV-88",
+    });
+  });
+
+  it("recognizes a receipt by its subject and total", () => {
+    const purchase = detectPurchase(
+      message({
+        subject: "Your receipt from Example Shop",
+        body_html:
+          "<table><tr><td>Subtotal</td><td>$18.00</td></tr><tr><td>Total</td><td>$19.44</td></tr></table><p>Order #A1B2C3</p>",
+      }),
+    );
+
+    expect(purchase).toEqual({
+      kind: "receipt",
+      vendor: "Example Shop",
+      date: DATE,
+      currency: "USD",
+      amountCents: 1944,
+      orderNumber: "A1B2C3",
+    });
+  });
+
+  it("falls back to the sender's domain for the vendor", () => {
+    const purchase = detectPurchase(
+      message({
+        subject: "Invoice for March",
+        body_text: "Amount due: CHF 120.00",
+        from_name: null,
+        from_address: "billing@hosting.example",
+      }),
+    );
+
+    expect(purchase).toMatchObject({ kind: "invoice", vendor: "hosting.example", currency: "CHF" });
+  });
+
+  it("skips messages that don't look like receipts", () => {
+    expect(detectPurchase(message({ subject: "Weekly deals", body_text: "Total savings: $50" }))).toBeNull();
+    expect(detectPurchase(message({ subject: "Your receipt", body_text: "Thanks!" }))).toBeNull();
+  });
+});
@@ -0,0 +1,194 @@
+/**
+ * Receipts and invoices: who was paid, when, and how much.
+ *
+ * Schema.org `Order` and `Invoice` markup is used when the sender provides
+ * it. Otherwise a message counts as a purchase when its subject reads like
+ * a receipt or invoice and its body has a labelled total ("Order total:
+ * $12.34"). Everything runs locally on the synced message.
+ */
+
+import {
+  htmlToText,
+  isObject,
+  jsonLdNodes,
+  nameOf,
+  str,
+  typesOf,
+  type JsonObject,
+} from "./structuredData";
+
+export type PurchaseKind = "receipt" | "invoice";
+
+export interface PurchaseInfo {
+  kind: PurchaseKind;
+  vendor: string;
+  /** Unix milliseconds */
+  date: number;
+  /** ISO 4217 code */
+  currency: string;
+  /** Hundredths of the currency unit */
+  amountCents: number;
+  orderNumber: string | null;
+}
+
+export interface PurchaseSource {
+  subject: string | null;
+  body_html: string | null;
+  body_text: string | null;
+  from_address: string | null;
+  from_name: string | null;
+  /** Unix milliseconds */
+  date: number;
+}
+
+/** Codes accepted when written out, e.g. "EUR 12,50". */
+const CURRENCY_CODES = new Set([
+  "USD", "EUR", "GBP", "JPY", "CAD", "AUD", "NZD", "CHF", "SEK", "NOK", "DKK", "PLN",
+  "CZK", "HUF", "INR", "CNY", "HKD", "SGD", "KRW", "BRL", "MXN", "ZAR", "TRY",
+]);
+
+const CURRENCY_SYMBOLS: Record<string, string> = {
+  "US$": "USD",
+  "CA$": "CAD",
+  C$: "CAD",
+  "AU$": "AUD",
+  A$: "AUD",
+  "NZ$": "NZD",
+  "HK$": "HKD",
+  "S$": "SGD",
+  "R$": "BRL",
+  $: "USD",
+  "€": "EUR",
+  "£": "GBP",
+  "¥": "JPY",
+  "₹": "INR",
+  "₩": "KRW",
+};
+
+const SUBJECT_PATTERN =
+  /\b(?:receipt|invoice|order confirm|your order|order #|payment (?:received|confirmation)|thanks? (?:you )?for your (?:order|purchase)|purchase confirm|billing statement)/i;
+
+const SYMBOL = String.raw`(?:US\$|CA\$|C\$|AU\$|A\$|NZ\$|HK\$|S\$|R\$|\$|€|£|¥|₹|₩)`;
+const CODE = String.raw`(?:${[...CURRENCY_CODES].join("|")})\b`;
+const NUMBER = String.raw`\d{1,3}(?:[.,\s]\d{3})+(?:[.,]\d{1,2})?(?!\d)|\d+(?:[.,]\d{1,2})?(?!\d)`;
+const MONEY = String.raw`(?:(${SYMBOL}|${CODE})\s?(${NUMBER})|(${NUMBER})\s?(${SYMBOL}|${CODE}))`;
+
+/** Labels of the amount actually charged, strongest first. */
+const TOTAL_LABELS = [
+  String.raw`grand total|total charged|amount charged|total paid|amount paid|payment amount`,
+  String.raw`order total|invoice total|total due|amount due|balance due|total amount`,
+  String.raw`total`,
+];
+
+const ORDER_NUMBER_PATTERN =
+  /\b(?:order|invoice|receipt|confirmation)\s*(?:#|no\.?|number|id)\s*:?\s*#?\s*([A-Z0-9][A-Z0-9-]{3,})/i;
+
+export function currencyOf(token: string): string | null {
+  const upper = token.toUpperCase();
+  if (CURRENCY_SYMBOLS[token]) return CURRENCY_SYMBOLS[token]!;
+  return CURRENCY_CODES.has(upper) ? upper : null;
+}
+
+/**
+ * An amount in hundredths. A separator followed by one or two digits at the
+ * end is the decimal mark; any other is a thousands separator, so both
+ * "1,234.50" and "1.234,50" are 123450.
+ */
+export function parseAmount(text: string): number | null {
+  const cleaned = text.replace(/\s/g, "");
+  const decimal = /[.,](\d{1,2})$/.exec(cleaned);
+  const whole = (decimal ? cleaned.slice(0, decimal.index) : cleaned).replace(/[.,]/g, "");
+  if (!/^\d+$/.test(whole)) return null;
+  const cents = decimal ? Number(decimal[1]!.padEnd(2, "0")) : 0;
+  return Number(whole) * 100 + cents;
+}
+
+/** The labelled total in `text`, preferring the most specific label. */
+export function findTotal(text: string): { currency: string; amountCents: number } | null {
+  for (const labels of TOTAL_LABELS) {
+    const pattern = new RegExp(String.raw`\b(?:${labels})\b[^\n\d]{0,25}?${MONEY}`, "gi");
+    let found: { currency: string; amountCents: number } | null = null;
+    for (const match of text.matchAll(pattern)) {
+      const currency = currencyOf(match[1] ?? match[4] ?? "");
+      const amountCents = parseAmount(match[2] ?? match[3] ?? "");
+      // The last one wins: a receipt's final total comes after its subtotals
+      if (currency && amountCents !== null) found = { currency, amountCents };
+    }
+    if (found) return found;
+  }
+  return null;
+}
+
+function vendorFromSender(message: PurchaseSource): string | null {
+  const name = message.from_name?.trim();
+  if (name) return name;
+  const domain = message.from_address?.split("@")[1];
+  return domain ? domain.replace(/^(?:mail|email|e|info|billing|orders?|receipts?)\./i, "") : null;
+}
+
+function dateOf(value: unknown): number | null {
+  const text = str(value);
+  if (!text) return null;
+  const time = new Date(text).getTime();
+  return isNaN(time) ? null : time;
+}
+
+/** The price and currency of an Order or Invoice node. */
+function priceOf(node: JsonObject): { currency: string; amountCents: number } | null {
+  const spec = [node.totalPaymentDue, node.minimumPaymentDue, node.priceSpecification, node]
+    .find((s) => isObject(s) && (s.price !== undefined || s.value !== undefined));
+  if (!isObject(spec)) return null;
+  const currency = currencyOf(str(spec.priceCurrency) ?? str(spec.currency) ?? "");
+  const amountCents = parseAmount(str(spec.price) ?? str(spec.value) ?? "");
+  return currency && amountCents !== null ? { currency, amountCents } : null;
+}
+
+function fromJsonLd(html: string, message: PurchaseSource): PurchaseInfo | null {
+  for (const node of jsonLdNodes(html)) {
+    const types = typesOf(node);
+    const kind: PurchaseKind | null = types.includes("Invoice")
+      ? "invoice"
+      : types.includes("Order")
+        ? "receipt"
+        : null;
+    if (!kind) continue;
+    const price = priceOf(node);
+    if (!price) continue;
+    return {
+      kind,
+      vendor:
+        nameOf(node.seller) ??
+        nameOf(node.merchant) ??
+        nameOf(node.provider) ??
+        vendorFromSender(message) ??
+        "Unknown",
+      date: dateOf(node.orderDate) ?? dateOf(node.paymentDueDate) ?? message.date,
+      ...price,
+      orderNumber: str(node.orderNumber) ?? str(node.confirmationNumber) ?? str(node.identifier),
+    };
+  }
+  return null;
+}
+
+/** The purchase a message records, or null if it isn't a receipt or invoice. */
+export function detectPurchase(message: PurchaseSource): PurchaseInfo | null {
+  if (message.body_html) {
+    const marked = fromJsonLd(message.body_html, message);
+    if (marked) return marked;
+  }
+
+  const subject = message.subject ?? "";
+  if (!SUBJECT_PATTERN.test(subject)) return null;
+
+  const body = message.body_text ?? (message.body_html ? htmlToText(message.body_html) : "");
+  const total = findTotal(body);
+  if (!total) return null;
+
+  return {
+    kind: /invoice|billing statement/i.test(subject) ? "invoice" : "receipt",
+    vendor: vendorFromSender(message) ?? "Unknown",
+    date: message.date,
+    ...total,
+    orderNumber: ORDER_NUMBER_PATTERN.exec(`${subject}\n${body}`)?.[1] ?? null,
+  };
+}
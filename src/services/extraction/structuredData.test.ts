@@ -0,0 +1,193 @@
+import { describe, it, expect } from "vitest";
+import {
+  carrierFromName,
+  extractFromJsonLd,
+  extractFromText,
+  extractStructuredData,
+} from "./structuredData";
+
+function jsonLd(data: unknown): string {
+  return `<html><head><script type="application/ld+json">${JSON.stringify(data)}</script></head><body>Hi</body></html>`;
+}
+
+describe("extractFromJsonLd", () => {
+  it("reads a flight reservation", () => {
+    const html = jsonLd({
+      "@context": "http://schema.org",
+      "@type": "FlightReservation",
+      reservationNumber: "RXJ34P",
+      reservationFor: {
+        "@type": "Flight",
+        flightNumber: "110",
+        airline: { "@type": "Airline", name: "United", iataCode: "UA" },
+        departureAirport: { "@type": "Airport", name: "San Francisco Airport", iataCode: "SFO" },
+        departureTime: "2027-03-04T20:15:00-08:00",
+        arrivalAirport: { "@type": "Airport", name: "John F. Kennedy Airport", iataCode: "JFK" },
+        arrivalTime: "2027-03-05T06:30:00-05:00",
+      },
+    });
+
+    expect(extractFromJsonLd(html)).toEqual([
+      {
+        kind: "flight",
+        flightNumber: "UA 110",
+        airline: "United",
+        departureAirport: "SFO",
+        arrivalAirport: "JFK",
+        departureTime: "2027-03-04T20:15:00-08:00",
+        arrivalTime: "2027-03-05T06:30:00-05:00",
+        reservationNumber: "RXJ34P",
+      },
+    ]);
+  });
+
+  it("reads hotel stays and parcels from a @graph", () => {
+    const html = jsonLd({
+      "@context": "https://schema.org",
+      "@graph": [
+        {
+          "@type": "LodgingReservation",
+          reservationNumber: "abc456",
+          reservationFor: {
+            "@type": "LodgingBusiness",
+            name: "Hilton San Francisco Union Square",
+            address: {
+              "@type": "PostalAddress",
+              streetAddress: "333 O'Farrell St",
+              addressLocality: "San Francisco",
+              addressRegion: "CA",
+            },
+          },
+          checkinDate: "2027-04-11T16:00:00-08:00",
+          checkoutDate: "2027-04-13T11:00:00-08:00",
+        },
+        {
+          "@type": "ParcelDelivery",
+          trackingNumber: "1Z999AA10123456784",
+          carrier: { "@type": "Organization", name: "UPS" },
+          expectedArrivalUntil: "2027-04-10",
+        },
+      ],
+    });
+
+    const items = extractFromJsonLd(html);
+
+    expect(items[0]).toMatchObject({
+      kind: "hotel",
+      name: "Hilton San Francisco Union Square",
+      address: "333 O'Farrell St, San Francisco, CA",
+      checkIn: "2027-04-11T16:00:00-08:00",
+    });
+    expect(items[1]).toEqual({
+      kind: "parcel",
+      trackingNumber: "1Z999AA10123456784",
+      carrier: "ups",
+      carrierName: "UPS",
+      trackingUrl: "https://www.ups.com/track?tracknum=1Z999AA10123456784",
+      expectedArrival: "2027-04-10",
+    });
+  });
+
+  it("keeps the sender's https tracking link only", () => {
+    const delivery = (trackingUrl: string) =>
+      jsonLd({
+        "@type": "ParcelDelivery",
+        trackingNumber: "AB123",
+        provider: { name: "Acme Couriers" },
+        trackingUrl,
+      });
+
+    expect(extractFromJsonLd(delivery("https://acme.example/t/AB123"))[0]).toMatchObject({
+      carrier: "other",
+      carrierName: "Acme Couriers",
+      trackingUrl: "https://acme.example/t/AB123",
+    });
+    expect(extractFromJsonLd(delivery("javascript:alert(1)"))[0]).toMatchObject({
+      trackingUrl: null,
+    });
+  });
+
+  it("skips malformed blocks", () => {
+    const html = '<script type="application/ld+json">{ not json</script>';
+    expect(extractFromJsonLd(html)).toEqual([]);
+  });
+});
+
+describe("extractFromText", () => {
+  it("recognizes UPS and USPS numbers by format", () => {
+    const items = extractFromText(
+      "Your package 1Z999AA10123456784 and 9400111899223197428490 are on the way.",
+    );
+
+    expect(items.map((i) => i.kind === "parcel" && i.carrier)).toEqual(["ups", "usps"]);
+  });
+
+  it("needs the carrier and a label for FedEx and DHL numbers", () => {
+    expect(extractFromText("Order 123456789012 confirmed. Call 5551234567.")).toEqual([]);
+    expect(extractFromText("FedEx tracking number: 123456789012")[0]).toMatchObject({
+      carrier: "fedex",
+      trackingUrl: "https://www.fedex.com/fedextrack/?trknbr=123456789012",
+    });
+    expect(
+      extractFromText("Waybill 1234567890 is out for delivery", "noreply@dhl.com")[0],
+    ).toMatchObject({ carrier: "dhl" });
+  });
+
+  it("recognizes flights with their route and booking code", () => {
+    const items = extractFromText(
+      "Booking reference: X7K2PQ\nFlight LH 400 Frankfurt (FRA) to New York (JFK)",
+    );
+
+    expect(items).toEqual([
+      expect.objectContaining({
+        kind: "flight",
+        flightNumber: "LH 400",
+        departureAirport: "FRA",
+        arrivalAirport: "JFK",
+        reservationNumber: "X7K2PQ",
+      }),
+    ]);
+  });
+
+  it("doesn't take ordinary prose for a flight", () => {
+    expect(extractFromText("Our flight to 2 cities was delayed")).toEqual([]);
+  });
+});
+
+describe("extractStructuredData", () => {
+  it("prefers JSON-LD over the text", () => {
+    const html =
+      jsonLd({ "@type": "ParcelDelivery", trackingNumber: "1Z999AA10123456784", carrier: "UPS" }) +
+      "<p>Flight UA 110</p>";
+
+    const items = extractStructuredData({
+      subject: "Shipped",
+      body_html: html,
+      body_text: null,
+      from_address: "shop@example.com",
+    });
+
+    expect(items).toHaveLength(1);
+    expect(items[0]!.kind).toBe("parcel");
+  });
+
+  it("searches the subject and text and drops duplicates", () => {
+    const items = extractStructuredData({
+      subject: "Tracking 1Z999AA10123456784",
+      body_html: "<p>UPS: 1Z999AA10123456784</p>",
+      body_text: null,
+      from_address: null,
+    });
+
+    expect(items).toHaveLength(1);
+  });
+});
+
+describe("carrierFromName", () => {
+  it("tells USPS from UPS", () => {
+    expect(carrierFromName("USPS")).toBe("usps");
+    expect(carrierFromName("ups.com")).toBe("ups");
+    expect(carrierFromName("groups.example.com")).toBe("other");
+    expect(carrierFromName(null)).toBe("other");
+  });
+});
@@ -0,0 +1,328 @@
+/**
+ * Structured data in travel and shipping emails: flights, hotel stays and
+ * parcels with their tracking numbers.
+ *
+ * Senders that follow schema.org's email markup embed it as JSON-LD
+ * (`<script type="application/ld+json">`), which is read first. Without
+ * markup, tracking numbers of the major carriers and flight numbers are
+ * recognized in the text.
+ */
+
+export interface FlightInfo {
+  kind: "flight";
+  /** IATA designator and number, e.g. "UA 123" */
+  flightNumber: string;
+  airline: string | null;
+  departureAirport: string | null;
+  arrivalAirport: string | null;
+  /** ISO 8601 as given by the sender */
+  departureTime: string | null;
+  arrivalTime: string | null;
+  reservationNumber: string | null;
+}
+
+export interface HotelInfo {
+  kind: "hotel";
+  name: string;
+  address: string | null;
+  checkIn: string | null;
+  checkOut: string | null;
+  reservationNumber: string | null;
+}
+
+export type Carrier = "ups" | "usps" | "fedex" | "dhl" | "other";
+
+export interface ParcelInfo {
+  kind: "parcel";
+  trackingNumber: string;
+  carrier: Carrier;
+  carrierName: string | null;
+  /** Where to track the parcel; null when the carrier is unknown */
+  trackingUrl: string | null;
+  expectedArrival: string | null;
+}
+
+export type ExtractedItem = FlightInfo | HotelInfo | ParcelInfo;
+
+export interface ExtractionSource {
+  subject: string | null;
+  body_html: string | null;
+  body_text: string | null;
+  from_address: string | null;
+}
+
+const CARRIER_NAMES: Record<Exclude<Carrier, "other">, string> = {
+  ups: "UPS",
+  usps: "USPS",
+  fedex: "FedEx",
+  dhl: "DHL",
+};
+
+const TRACKING_URLS: Record<Exclude<Carrier, "other">, string> = {
+  ups: "https://www.ups.com/track?tracknum=",
+  usps: "https://tools.usps.com/go/TrackConfirmAction?tLabels=",
+  fedex: "https://www.fedex.com/fedextrack/?trknbr=",
+  dhl: "https://www.dhl.com/global-en/home/tracking/tracking-express.html?tracking-id=",
+};
+
+export type JsonObject = Record<string, unknown>;
+
+export function isObject(value: unknown): value is JsonObject {
+  return typeof value === "object" && value !== null && !Array.isArray(value);
+}
+
+export function str(value: unknown): string | null {
+  if (typeof value === "string") return value.trim() || null;
+  if (typeof value === "number") return String(value);
+  return null;
+}
+
+/** The schema.org type names of a node, without any URL prefix. */
+export function typesOf(node: JsonObject): string[] {
+  const type = node["@type"];
+  const types = Array.isArray(type) ? type : [type];
+  return types
+    .filter((t): t is string => typeof t === "string")
+    .map((t) => t.replace(/^https?:\/\/schema\.org\//, ""));
+}
+
+/** A name from a string or an object with `name` (e.g. an Organization). */
+export function nameOf(value: unknown): string | null {
+  return isObject(value) ? str(value.name) : str(value);
+}
+
+/** An airport as its IATA code, or its name. */
+function airportOf(value: unknown): string | null {
+  return isObject(value) ? (str(value.iataCode) ?? str(value.name)) : str(value);
+}
+
+function addressOf(value: unknown): string | null {
+  if (!isObject(value)) return str(value);
+  const parts = [
+    value.streetAddress,
+    value.addressLocality,
+    value.addressRegion,
+    value.postalCode,
+    nameOf(value.addressCountry),
+  ]
+    .map(str)
+    .filter((p): p is string => p !== null);
+  return parts.length > 0 ? parts.join(", ") : null;
+}
+
+export function carrierFromName(name: string | null): Carrier {
+  const lower = name?.toLowerCase() ?? "";
+  if (/\busps\b|postal service/.test(lower)) return "usps";
+  if (/\bups\b|united parcel/.test(lower)) return "ups";
+  if (/fedex|federal express/.test(lower)) return "fedex";
+  if (/\bdhl\b/.test(lower)) return "dhl";
+  return "other";
+}
+
+export function trackingUrlFor(carrier: Carrier, trackingNumber: string): string | null {
+  return carrier === "other" ? null : TRACKING_URLS[carrier] + encodeURIComponent(trackingNumber);
+}
+
+function parcel(
+  trackingNumber: string,
+  carrier: Carrier,
+  carrierName: string | null = null,
+  trackingUrl: string | null = null,
+  expectedArrival: string | null = null,
+): ParcelInfo {
+  const safeUrl = trackingUrl && /^https:\/\//i.test(trackingUrl) ? trackingUrl : null;
+  return {
+    kind: "parcel",
+    trackingNumber,
+    carrier,
+    carrierName: carrierName ?? (carrier === "other" ? null : CARRIER_NAMES[carrier]),
+    trackingUrl: safeUrl ?? trackingUrlFor(carrier, trackingNumber),
+    expectedArrival,
+  };
+}
+
+function fromNode(node: JsonObject): ExtractedItem | null {
+  const types = typesOf(node);
+
+  if (types.includes("FlightReservation") || types.includes("Flight")) {
+    const flight = types.includes("Flight") ? node : node.reservationFor;
+    if (!isObject(flight)) return null;
+    const airline = flight.airline;
+    const code = isObject(airline) ? str(airline.iataCode) : null;
+    const number = str(flight.flightNumber);
+    if (!number) return null;
+    return {
+      kind: "flight",
+      flightNumber: code && !number.startsWith(code) ? `${code} ${number}` : number,
+      airline: nameOf(airline),
+      departureAirport: airportOf(flight.departureAirport),
+      arrivalAirport: airportOf(flight.arrivalAirport),
+      departureTime: str(flight.departureTime),
+      arrivalTime: str(flight.arrivalTime),
+      reservationNumber: str(node.reservationNumber),
+    };
+  }
+
+  if (types.includes("LodgingReservation")) {
+    const lodging = node.reservationFor;
+    const name = nameOf(lodging);
+    if (!name) return null;
+    return {
+      kind: "hotel",
+      name,
+      address: isObject(lodging) ? addressOf(lodging.address) : null,
+      checkIn: str(node.checkinTime) ?? str(node.checkinDate),
+      checkOut: str(node.checkoutTime) ?? str(node.checkoutDate),
+      reservationNumber: str(node.reservationNumber),
+    };
+  }
+
+  if (types.includes("ParcelDelivery")) {
+    const trackingNumber = str(node.trackingNumber);
+    if (!trackingNumber) return null;
+    const carrierName = nameOf(node.carrier) ?? nameOf(node.provider);
+    return parcel(
+      trackingNumber,
+      carrierFromName(carrierName),
+      carrierName,
+      str(node.trackingUrl),
+      str(node.expectedArrivalUntil) ?? str(node.expectedArrivalFrom),
+    );
+  }
+
+  return null;
+}
+
+/** Every object in a JSON-LD document, following `@graph` and nested orders. */
+function collectNodes(value: unknown, out: JsonObject[], depth = 0): void {
+  if (depth > 4) return;
+  if (Array.isArray(value)) {
+    for (const item of value) collectNodes(item, out, depth + 1);
+    return;
+  }
+  if (!isObject(value)) return;
+  out.push(value);
+  collectNodes(value["@graph"], out, depth + 1);
+  // An Order carries its shipments in orderDelivery
+  collectNodes(value.orderDelivery, out, depth + 1);
+}
+
+/** The objects of all JSON-LD blocks in `html`. */
+export function jsonLdNodes(html: string): JsonObject[] {
+  const doc = new DOMParser().parseFromString(html, "text/html");
+  const nodes: JsonObject[] = [];
+  for (const script of doc.querySelectorAll('script[type="application/ld+json"]')) {
+    try {
+      collectNodes(JSON.parse(script.textContent ?? ""), nodes);
+    } catch {
+      // Malformed markup is common; skip the block
+    }
+  }
+  return nodes;
+}
+
+/** Items described by the JSON-LD blocks in `html`. */
+export function extractFromJsonLd(html: string): ExtractedItem[] {
+  return jsonLdNodes(html)
+    .map(fromNode)
+    .filter((item): item is ExtractedItem => item !== null);
+}
+
+/** The visible text of `html`, with table cells and blocks kept apart. */
+export function htmlToText(html: string): string {
+  const spaced = html.replace(/<(?:br|p|div|li|tr|td|th|h[1-6])\b/gi, " $&");
+  const doc = new DOMParser().parseFromString(spaced, "text/html");
+  for (const el of doc.querySelectorAll("script, style")) el.remove();
+  return doc.body?.textContent ?? "";
+}
+
+/** Tracking numbers that identify their carrier by format alone. */
+const SELF_EVIDENT_TRACKING: [RegExp, Carrier][] = [
+  [/\b1Z[0-9A-Z]{16}\b/g, "ups"],
+  [/\b9[2-5]\d{20}\b/g, "usps"],
+];
+
+/** Tracking numbers that are only recognized when the carrier is named. */
+const NAMED_TRACKING: [RegExp, "fedex" | "dhl"][] = [
+  [/\b(?:\d{12}|\d{15})\b/g, "fedex"],
+  [/\b\d{10}\b/g, "dhl"],
+];
+
+// The airline designator must be upper case so "flight to 2" isn't a flight
+const FLIGHT_PATTERN =
+  /\b(?:[Ff]light|FLIGHT)(?:\s+(?:[Nn]o\.?|[Nn]umber|NUMBER))?:?\s*([A-Z][A-Z0-9]|[A-Z0-9][A-Z])\s?(\d{1,4})\b/g;
+const BOOKING_PATTERN =
+  /\b(?:confirmation(?:\s+(?:code|number))?|booking\s+(?:reference|code)|record\s+locator|PNR)\s*:?\s*([A-Z0-9]{6})\b/i;
+const ROUTE_PATTERN = /\(([A-Z]{3})\)\s*(?:to|→|->|–|-)\s*[^()\n]{0,60}\(([A-Z]{3})\)/;
+
+/** Items recognized in plain text, with the sender hinting at the carrier. */
+export function extractFromText(text: string, fromAddress: string | null = null): ExtractedItem[] {
+  const items: ExtractedItem[] = [];
+  const lower = text.toLowerCase();
+  const senderCarrier = carrierFromName(fromAddress?.split("@")[1] ?? null);
+
+  for (const [pattern, carrier] of SELF_EVIDENT_TRACKING) {
+    for (const match of text.matchAll(pattern)) items.push(parcel(match[0], carrier));
+  }
+  for (const [pattern, carrier] of NAMED_TRACKING) {
+    if (senderCarrier !== carrier && !lower.includes(CARRIER_NAMES[carrier].toLowerCase())) {
+      continue;
+    }
+    // Only numbers labelled as tracking numbers, so order ids and phone
+    // numbers aren't taken for them
+    for (const match of text.matchAll(pattern)) {
+      const before = lower.slice(Math.max(0, (match.index ?? 0) - 40), match.index);
+      if (/tracking|waybill|awb|shipment/.test(before)) items.push(parcel(match[0], carrier));
+    }
+  }
+
+  const booking = BOOKING_PATTERN.exec(text)?.[1] ?? null;
+  const route = ROUTE_PATTERN.exec(text);
+  for (const match of text.matchAll(FLIGHT_PATTERN)) {
+    items.push({
+      kind: "flight",
+      flightNumber: `${match[1]} ${match[2]}`,
+      airline: null,
+      departureAirport: route?.[1] ?? null,
+      arrivalAirport: route?.[2] ?? null,
+      departureTime: null,
+      arrivalTime: null,
+      reservationNumber: booking,
+    });
+  }
+  return items;
+}
+
+function keyOf(item: ExtractedItem): string {
+  switch (item.kind) {
+    case "flight":
+      return `flight:${item.flightNumber.replace(/\s/g, "")}:${item.departureTime ?? ""}`;
+    case "hotel":
+      return `hotel:${item.name}:${item.checkIn ?? ""}`;
+    case "parcel":
+      return `parcel:${item.trackingNumber}`;
+  }
+}
+
+/**
+ * The flights, hotel stays and parcels in a message. JSON-LD markup wins;
+ * the text is only searched when there's none.
+ */
+export function extractStructuredData(message: ExtractionSource): ExtractedItem[] {
+  let items = message.body_html ? extractFromJsonLd(message.body_html) : [];
+  if (items.length === 0) {
+    const text = [
+      message.subject ?? "",
+      message.body_text ?? (message.body_html ? htmlToText(message.body_html) : ""),
+    ].join("\n");
+    items = extractFromText(text, message.from_address);
+  }
+
+  const seen = new Set<string>();
+  return items.filter((item) => {
+    const key = keyOf(item);
+    if (seen.has(key)) return false;
+    seen.add(key);
+    return true;
+  });
+}
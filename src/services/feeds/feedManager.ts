@@ -0,0 +1,188 @@
+import { fetch } from "@tauri-apps/plugin-http";
+import { getAllAccounts } from "../db/accounts";
+import {
+  FEEDS_LABEL_ID,
+  getFeedsForAccount,
+  getSeenFeedItems,
+  markFeedItemSeen,
+  updateFeedStatus,
+  type DbFeed,
+} from "../db/feeds";
+import { upsertMessage } from "../db/messages";
+import { setThreadLabels, upsertThread } from "../db/threads";
+import { createBackgroundChecker } from "../backgroundCheckers";
+import { getCurrentUnixTimestamp } from "@/utils/timestamp";
+import { escapeHtml } from "@/utils/sanitize";
+import { parseFeed, type FeedEntry } from "./feedParser";
+
+/**
+ * Feed reader: polls the RSS/Atom feeds subscribed from Settings and stores
+ * new entries as single-message threads labelled FEEDS, so they show up in
+ * the Feeds folder and open in the normal reading pane. Entries only exist
+ * locally; email actions on them never reach the mail server.
+ */
+
+/** How often each feed is fetched. */
+export const FEED_POLL_INTERVAL_SECS = 30 * 60;
+
+const SNIPPET_LENGTH = 200;
+
+/** Feeds are fetched over HTTPS only, like other outgoing requests. */
+export function isValidFeedUrl(url: string): boolean {
+  try {
+    return new URL(url).protocol === "https:";
+  } catch {
+    return false;
+  }
+}
+
+/** 53-bit string hash (cyrb53), enough to tell a feed's entries apart. */
+function hashGuid(guid: string): string {
+  let h1 = 0xdeadbeef;
+  let h2 = 0x41c6ce57;
+  for (let i = 0; i < guid.length; i++) {
+    const ch = guid.charCodeAt(i);
+    h1 = Math.imul(h1 ^ ch, 2654435761);
+    h2 = Math.imul(h2 ^ ch, 1597334677);
+  }
+  h1 = Math.imul(h1 ^ (h1 >>> 16), 2246822507) ^ Math.imul(h2 ^ (h2 >>> 13), 3266489909);
+  h2 = Math.imul(h2 ^ (h2 >>> 16), 2246822507) ^ Math.imul(h1 ^ (h1 >>> 13), 3266489909);
+  return (4294967296 * (2097151 & h2) + (h1 >>> 0)).toString(16);
+}
+
+export function feedThreadId(feedId: string, guid: string): string {
+  return `feed-${feedId}-${hashGuid(guid)}`;
+}
+
+function htmlToText(html: string): string {
+  const doc = new DOMParser().parseFromString(html, "text/html");
+  return (doc.body.textContent ?? "").replace(/\s+/g, " ").trim();
+}
+
+/** The entry's content followed by a link to the original. */
+export function entryBodyHtml(entry: FeedEntry): string {
+  const parts = [entry.html ?? ""];
+  if (entry.link) {
+    const href = escapeHtml(entry.link);
+    parts.push(`<p><a href="${href}">Read on the web</a></p>`);
+  }
+  return parts.join("\n");
+}
+
+async function storeEntry(feed: DbFeed, feedTitle: string | null, entry: FeedEntry): Promise<void> {
+  const id = feedThreadId(feed.id, entry.guid);
+  const text = entry.html ? htmlToText(entry.html) : "";
+  const snippet = text.slice(0, SNIPPET_LENGTH);
+  const date = entry.published ?? Date.now();
+  const subject = entry.title ?? "(untitled)";
+
+  await upsertThread({
+    id,
+    accountId: feed.account_id,
+    subject,
+    snippet,
+    lastMessageAt: date,
+    messageCount: 1,
+    isRead: false,
+    isStarred: false,
+    isImportant: false,
+    hasAttachments: false,
+  });
+  await upsertMessage({
+    id,
+    accountId: feed.account_id,
+    threadId: id,
+    fromAddress: null,
+    fromName: entry.author ?? feedTitle ?? feed.url,
+    toAddresses: null,
+    ccAddresses: null,
+    bccAddresses: null,
+    replyTo: null,
+    subject,
+    snippet,
+    date,
+    isRead: false,
+    isStarred: false,
+    bodyHtml: entryBodyHtml(entry),
+    bodyText: text,
+    rawSize: null,
+    internalDate: date,
+  });
+  await setThreadLabels(feed.account_id, id, [FEEDS_LABEL_ID]);
+}
+
+/**
+ * Fetch one feed and store its unseen entries. Returns how many were added;
+ * failures are recorded on the feed and rethrown.
+ */
+export async function refreshFeed(feed: DbFeed): Promise<number> {
+  const checkedAt = getCurrentUnixTimestamp();
+  try {
+    const headers: Record<string, string> = {
+      Accept: "application/rss+xml, application/atom+xml, application/xml;q=0.9, */*;q=0.8",
+    };
+    if (feed.etag) headers["If-None-Match"] = feed.etag;
+    if (feed.last_modified) headers["If-Modified-Since"] = feed.last_modified;
+
+    const response = await fetch(feed.url, { headers });
+    if (response.status === 304) {
+      await updateFeedStatus(feed.id, { error: null, checkedAt });
+      return 0;
+    }
+    if (!response.ok) throw new Error(`HTTP ${response.status}`);
+
+    const parsed = parseFeed(await response.text());
+    const seen = await getSeenFeedItems(
+      feed.id,
+      parsed.entries.map((e) => e.guid),
+    );
+    let added = 0;
+    for (const entry of parsed.entries) {
+      if (seen.has(entry.guid)) continue;
+      seen.add(entry.guid);
+      await storeEntry(feed, parsed.title ?? feed.title, entry);
+      await markFeedItemSeen(feed.id, entry.guid);
+      added++;
+    }
+
+    await updateFeedStatus(feed.id, {
+      title: parsed.title,
+      siteUrl: parsed.siteUrl,
+      etag: response.headers.get("ETag"),
+      lastModified: response.headers.get("Last-Modified"),
+      error: null,
+      checkedAt,
+    });
+    return added;
+  } catch (err) {
+    const message = err instanceof Error ? err.message : String(err);
+    await updateFeedStatus(feed.id, { error: message, checkedAt });
+    throw err;
+  }
+}
+
+/** Refresh the feeds of active accounts that are due. */
+async function checkFeeds(): Promise<void> {
+  const now = getCurrentUnixTimestamp();
+  let added = 0;
+
+  for (const account of await getAllAccounts()) {
+    if (!account.is_active) continue;
+    for (const feed of await getFeedsForAccount(account.id)) {
+      if (feed.last_checked_at && now - feed.last_checked_at < FEED_POLL_INTERVAL_SECS) continue;
+      try {
+        added += await refreshFeed(feed);
+      } catch (err) {
+        console.warn(`Failed to refresh feed ${feed.url}:`, err);
+      }
+    }
+  }
+
+  if (added > 0) {
+    window.dispatchEvent(new Event("velo-sync-done"));
+  }
+}
+
+const feedChecker = createBackgroundChecker("Feeds", checkFeeds, 5 * 60_000);
+export const startFeedChecker = feedChecker.start;
+export const stopFeedChecker = feedChecker.stop;
@@ -0,0 +1,103 @@
+import { describe, it, expect } from "vitest";
+import { parseFeed } from "./feedParser";
+
+describe("parseFeed", () => {
+  it("parses an RSS 2.0 feed", () => {
+    const feed = parseFeed(`<?xml version="1.0"?>
+      <rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/"
+           xmlns:atom="http://www.w3.org/2005/Atom">
+        <channel>
+          <title>Example Blog</title>
+          <link>https://blog.example.com/</link>
+          <atom:link href="https://blog.example.com/feed" rel="self"/>
+          <item>
+            <title>Hello</title>
+            <link>https://blog.example.com/hello</link>
+            <guid>post-1</guid>
+            <pubDate>Tue, 06 Oct 2026 09:30:00 GMT</pubDate>
+            <description>Short &lt;b&gt;summary&lt;/b&gt;</description>
+            <content:encoded><![CDATA[<p>Full <b>post</b></p>]]></content:encoded>
+          </item>
+          <item>
+            <title>No guid</title>
+            <link>https://blog.example.com/second</link>
+          </item>
+        </channel>
+      </rss>`);
+
+    expect(feed.title).toBe("Example Blog");
+    expect(feed.siteUrl).toBe("https://blog.example.com/");
+    expect(feed.entries).toHaveLength(2);
+    expect(feed.entries[0]).toEqual({
+      guid: "post-1",
+      title: "Hello",
+      link: "https://blog.example.com/hello",
+      author: null,
+      published: Date.UTC(2026, 9, 6, 9, 30),
+      html: "<p>Full <b>post</b></p>",
+    });
+    expect(feed.entries[1]!.guid).toBe("https://blog.example.com/second");
+  });
+
+  it("parses an Atom feed", () => {
+    const feed = parseFeed(`<?xml version="1.0" encoding="utf-8"?>
+      <feed xmlns="http://www.w3.org/2005/Atom">
+        <title>Release notes</title>
+        <link rel="self" href="https://example.com/atom.xml"/>
+        <link href="https://example.com/"/>
+        <entry>
+          <id>urn:uuid:1</id>
+          <title>v1.0</title>
+          <link rel="alternate" href="https://example.com/v1"/>
+          <updated>2026-10-01T12:00:00Z</updated>
+          <author><name>Ada</name></author>
+          <summary>Fixes &amp; features</summary>
+        </entry>
+        <entry>
+          <id>urn:uuid:2</id>
+          <title>v1.1</title>
+          <content type="xhtml"><div xmlns="http://www.w3.org/1999/xhtml"><p>Inline</p></div></content>
+        </entry>
+      </feed>`);
+
+    expect(feed.title).toBe("Release notes");
+    expect(feed.siteUrl).toBe("https://example.com/");
+    expect(feed.entries[0]).toEqual({
+      guid: "urn:uuid:1",
+      title: "v1.0",
+      link: "https://example.com/v1",
+      author: "Ada",
+      published: Date.UTC(2026, 9, 1, 12),
+      html: "Fixes &amp; features",
+    });
+    expect(feed.entries[1]!.html).toContain("<p");
+    expect(feed.entries[1]!.html).toContain("Inline");
+  });
+
+  it("parses RSS 1.0 items outside the channel", () => {
+    const feed = parseFeed(`<?xml version="1.0"?>
+      <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+               xmlns="http://purl.org/rss/1.0/" xmlns:dc="http://purl.org/dc/elements/1.1/">
+        <channel rdf:about="https://news.example.org/"><title>News</title></channel>
+        <item rdf:about="https://news.example.org/1">
+          <title>First</title>
+          <dc:creator>Bob</dc:creator>
+          <dc:date>2026-09-30T08:00:00Z</dc:date>
+        </item>
+      </rdf:RDF>`);
+
+    expect(feed.title).toBe("News");
+    expect(feed.entries).toEqual([
+      expect.objectContaining({
+        guid: "https://news.example.org/1",
+        author: "Bob",
+        published: Date.UTC(2026, 8, 30, 8),
+      }),
+    ]);
+  });
+
+  it("rejects documents that aren't feeds", () => {
+    expect(() => parseFeed("<html><body>hi</body></html>")).toThrow(/Not an RSS or Atom feed/);
+    expect(() => parseFeed("not xml at all <")).toThrow();
+  });
+});
@@ -0,0 +1,129 @@
+import { escapeHtml } from "@/utils/sanitize";
+
+export interface FeedEntry {
+  /** Stable identifier: the entry's id/guid, else its link. */
+  guid: string;
+  title: string | null;
+  link: string | null;
+  author: string | null;
+  /** Milliseconds since the epoch, or null when the entry isn't dated. */
+  published: number | null;
+  /** Entry content as HTML. */
+  html: string | null;
+}
+
+export interface ParsedFeed {
+  title: string | null;
+  siteUrl: string | null;
+  entries: FeedEntry[];
+}
+
+function children(el: Element, name: string): Element[] {
+  return Array.from(el.children).filter((c) => c.localName === name);
+}
+
+function child(el: Element, name: string): Element | null {
+  return children(el, name)[0] ?? null;
+}
+
+function childText(el: Element, ...names: string[]): string | null {
+  for (const name of names) {
+    for (const c of children(el, name)) {
+      const text = c.textContent?.trim();
+      if (text) return text;
+    }
+  }
+  return null;
+}
+
+function parseDate(value: string | null): number | null {
+  if (!value) return null;
+  const time = Date.parse(value);
+  return Number.isNaN(time) ? null : time;
+}
+
+/** Atom text constructs are plain text, escaped HTML, or inline XHTML. */
+function atomHtml(el: Element | null): string | null {
+  if (!el) return null;
+  const type = el.getAttribute("type") ?? "text";
+  if (type === "xhtml") {
+    const serializer = new XMLSerializer();
+    const root = child(el, "div") ?? el;
+    return Array.from(root.childNodes).map((n) => serializer.serializeToString(n)).join("");
+  }
+  const text = el.textContent?.trim();
+  if (!text) return null;
+  return type === "html" ? text : escapeHtml(text);
+}
+
+function atomLink(el: Element): string | null {
+  const links = children(el, "link");
+  const alternate =
+    links.find((l) => (l.getAttribute("rel") ?? "alternate") === "alternate") ?? links[0];
+  return alternate?.getAttribute("href") ?? null;
+}
+
+function parseAtom(feed: Element): ParsedFeed {
+  return {
+    title: childText(feed, "title"),
+    siteUrl: atomLink(feed),
+    entries: children(feed, "entry").flatMap((entry) => {
+      const link = atomLink(entry);
+      const guid = childText(entry, "id") ?? link;
+      if (!guid) return [];
+      const author = child(entry, "author");
+      return [{
+        guid,
+        title: childText(entry, "title"),
+        link,
+        author: author ? childText(author, "name", "email") : null,
+        published: parseDate(childText(entry, "published", "updated")),
+        html: atomHtml(child(entry, "content") ?? child(entry, "summary")),
+      }];
+    }),
+  };
+}
+
+/** RSS 2.0 and RSS 1.0 (RDF), whose items sit beside the channel. */
+function parseRss(root: Element): ParsedFeed {
+  const channel = child(root, "channel");
+  const items = [
+    ...(channel ? children(channel, "item") : []),
+    ...children(root, "item"),
+  ];
+  return {
+    title: channel ? childText(channel, "title") : null,
+    siteUrl: channel ? childText(channel, "link") : null,
+    entries: items.flatMap((item) => {
+      const link = childText(item, "link");
+      const guid = childText(item, "guid") ?? item.getAttribute("rdf:about") ?? link;
+      if (!guid) return [];
+      return [{
+        guid,
+        title: childText(item, "title"),
+        link,
+        author: childText(item, "creator", "author"),
+        published: parseDate(childText(item, "pubDate", "date")),
+        html: childText(item, "encoded", "description"),
+      }];
+    }),
+  };
+}
+
+/** Parse an RSS or Atom document. Throws if it is neither. */
+export function parseFeed(xml: string): ParsedFeed {
+  const doc = new DOMParser().parseFromString(xml, "application/xml");
+  const root = doc.documentElement;
+  if (!root || doc.getElementsByTagName("parsererror").length > 0) {
+    throw new Error("Not a valid feed: the document isn't well-formed XML");
+  }
+  switch (root.localName) {
+    case "feed":
+      return parseAtom(root);
+    case "rss":
+    case "RDF":
+      return parseRss(root);
+    default:
+      throw new Error(`Not an RSS or Atom feed (<${root.localName}>)`);
+  }
+}
@@ -64,6 +64,14 @@ describe("messageMatchesFilter", () => {
     expect(messageMatchesFilter(msg, criteria)).toBe(false);
   });
 
+  it("matches category criteria against IMAP keywords", () => {
+    const msg = createMockParsedMessage({ keywords: ["$label2", "Travel"] });
+    expect(messageMatchesFilter(msg, { category: "Work" })).toBe(true);
+    expect(messageMatchesFilter(msg, { category: "travel" })).toBe(true);
+    expect(messageMatchesFilter(msg, { category: "Personal" })).toBe(false);
+    expect(messageMatchesFilter(createMockParsedMessage(), { category: "Work" })).toBe(false);
+  });
+
   it("ANDs multiple criteria together", () => {
     const msg = createMockParsedMessage();
     const criteria: FilterCriteria = { from: "alice", subject: "project" };
@@ -2,6 +2,8 @@ import type { FilterCriteria, FilterActions } from "../db/filters";
 import { getEnabledFiltersForAccount } from "../db/filters";
 import type { ParsedMessage } from "../gmail/messageParser";
 import { addThreadLabel, removeThreadLabel, markThreadRead, starThread } from "../emailActions";
+import { buildWebhookPayload, callWebhook, type WebhookPayload } from "./webhook";
+import { hasCategory } from "../colorCategories";
 
 /**
  * Check if a parsed message matches the given filter criteria.
@@ -35,6 +37,10 @@ export function messageMatchesFilter(
     if (!message.hasAttachments) return false;
   }
 
+  if (criteria.category) {
+    if (!hasCategory(message.keywords, criteria.category)) return false;
+  }
+
   return true;
 }
 
@@ -79,7 +85,8 @@ export function computeFilterActions(actions: FilterActions): FilterResult {
 
 /**
  * Apply all enabled filters to a set of new messages for the given account.
- * Modifies threads via the Gmail API and updates local DB.
+ * Modifies threads via the Gmail API and updates local DB. Webhooks are
+ * called in the background so their retries don't hold up sync.
  */
 export async function applyFiltersToMessages(
   accountId: string,
@@ -92,6 +99,7 @@ export async function applyFiltersToMessages(
   const parsedFilters = filters.flatMap((filter) => {
     try {
       return [{
+        name: filter.name,
         criteria: JSON.parse(filter.criteria_json) as FilterCriteria,
         actions: JSON.parse(filter.actions_json) as FilterActions,
       }];
@@ -103,10 +111,17 @@ export async function applyFiltersToMessages(
 
   // Group actions by threadId so we can batch modifications
   const threadActions = new Map<string, FilterResult>();
+  const webhooks: { url: string; payload: WebhookPayload }[] = [];
 
   for (const msg of messages) {
-    for (const { criteria, actions } of parsedFilters) {
+    for (const { name, criteria, actions } of parsedFilters) {
       if (messageMatchesFilter(msg, criteria)) {
+        if (actions.webhookUrl) {
+          webhooks.push({
+            url: actions.webhookUrl,
+            payload: buildWebhookPayload(accountId, name, msg),
+          });
+        }
         const result = computeFilterActions(actions);
         const existing = threadActions.get(msg.threadId);
         if (existing) {
@@ -122,6 +137,8 @@ export async function applyFiltersToMessages(
     }
   }
 
+  void Promise.allSettled(webhooks.map(({ url, payload }) => callWebhook(url, payload)));
+
   // Apply combined actions per thread in parallel
   await Promise.allSettled(
     [...threadActions].map(async ([threadId, result]) => {
@@ -0,0 +1,93 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+
+vi.mock("@tauri-apps/plugin-http", () => ({
+  fetch: vi.fn(),
+}));
+
+import { fetch } from "@tauri-apps/plugin-http";
+import { buildWebhookPayload, callWebhook, isValidWebhookUrl } from "./webhook";
+import { createMockParsedMessage } from "@/test/mocks";
+
+const mockFetch = vi.mocked(fetch);
+const payload = buildWebhookPayload("acct-1", "Invoices", createMockParsedMessage({ date: 1_700_000_000_000 }));
+
+function respond(status: number) {
+  return { ok: status >= 200 && status < 300, status } as Response;
+}
+
+describe("isValidWebhookUrl", () => {
+  it("accepts HTTPS and plain HTTP to this computer only", () => {
+    expect(isValidWebhookUrl("https://hooks.example.com/a")).toBe(true);
+    expect(isValidWebhookUrl("http://localhost:8080/hook")).toBe(true);
+    expect(isValidWebhookUrl("http://127.0.0.1/hook")).toBe(true);
+    expect(isValidWebhookUrl("http://hooks.example.com/a")).toBe(false);
+    expect(isValidWebhookUrl("ftp://example.com")).toBe(false);
+    expect(isValidWebhookUrl("not a url")).toBe(false);
+  });
+});
+
+describe("buildWebhookPayload", () => {
+  it("summarizes the message without its body", () => {
+    expect(payload).toEqual({
+      event: "filter_matched",
+      filter: "Invoices",
+      accountId: "acct-1",
+      messageId: "msg-1",
+      threadId: "thread-1",
+      from: { name: "Alice Smith", address: "alice@example.com" },
+      to: "bob@example.com",
+      cc: null,
+      subject: "Project Update",
+      snippet: "Here is the latest update...",
+      date: 1_700_000_000_000,
+      hasAttachments: false,
+      attachments: [],
+    });
+  });
+});
+
+describe("callWebhook", () => {
+  beforeEach(() => {
+    mockFetch.mockReset();
+  });
+
+  it("posts the payload as JSON", async () => {
+    mockFetch.mockResolvedValue(respond(200));
+
+    await expect(callWebhook("https://hooks.example.com/a", payload, [])).resolves.toBe(true);
+    expect(mockFetch).toHaveBeenCalledWith("https://hooks.example.com/a", {
+      method: "POST",
+      headers: { "Content-Type": "application/json", "User-Agent": "Sora" },
+      body: JSON.stringify(payload),
+    });
+  });
+
+  it("retries server errors and network failures", async () => {
+    mockFetch
+      .mockResolvedValueOnce(respond(503))
+      .mockRejectedValueOnce(new Error("offline"))
+      .mockResolvedValueOnce(respond(202));
+
+    await expect(callWebhook("https://hooks.example.com/a", payload, [0, 0])).resolves.toBe(true);
+    expect(mockFetch).toHaveBeenCalledTimes(3);
+  });
+
+  it("gives up when retries run out", async () => {
+    mockFetch.mockResolvedValue(respond(500));
+
+    await expect(callWebhook("https://hooks.example.com/a", payload, [0])).resolves.toBe(false);
+    expect(mockFetch).toHaveBeenCalledTimes(2);
+  });
+
+  it("doesn't retry client errors", async () => {
+    mockFetch.mockResolvedValue(respond(404));
+
+    await expect(callWebhook("https://hooks.example.com/a", payload, [0, 0])).resolves.toBe(false);
+    expect(mockFetch).toHaveBeenCalledTimes(1);
+  });
+
+  it("refuses plain HTTP to other hosts", async () => {
+    await expect(callWebhook("http://hooks.example.com/a", payload)).resolves.toBe(false);
+    expect(mockFetch).not.toHaveBeenCalled();
+  });
+});
@@ -0,0 +1,105 @@
+import { fetch } from "@tauri-apps/plugin-http";
+import type { ParsedMessage } from "../gmail/messageParser";
+
+/**
+ * "Call webhook" filter action: POST a JSON summary of a matched message to
+ * a user-configured URL, for automation without forwarding mail.
+ */
+
+export interface WebhookPayload {
+  event: "filter_matched";
+  filter: string;
+  accountId: string;
+  messageId: string;
+  threadId: string;
+  from: { name: string | null; address: string | null };
+  to: string | null;
+  cc: string | null;
+  subject: string | null;
+  snippet: string;
+  /** Milliseconds since the epoch */
+  date: number;
+  hasAttachments: boolean;
+  attachments: string[];
+}
+
+/** Delays before the second and third attempt. */
+export const WEBHOOK_RETRY_DELAYS_MS = [5_000, 30_000];
+
+/**
+ * Webhooks must use HTTPS, except to this computer, so message details
+ * aren't sent over the network in the clear.
+ */
+export function isValidWebhookUrl(url: string): boolean {
+  let parsed: URL;
+  try {
+    parsed = new URL(url);
+  } catch {
+    return false;
+  }
+  if (parsed.protocol === "https:") return true;
+  return (
+    parsed.protocol === "http:" &&
+    (parsed.hostname === "localhost" || parsed.hostname === "127.0.0.1")
+  );
+}
+
+export function buildWebhookPayload(
+  accountId: string,
+  filterName: string,
+  message: ParsedMessage,
+): WebhookPayload {
+  return {
+    event: "filter_matched",
+    filter: filterName,
+    accountId,
+    messageId: message.id,
+    threadId: message.threadId,
+    from: { name: message.fromName, address: message.fromAddress },
+    to: message.toAddresses,
+    cc: message.ccAddresses,
+    subject: message.subject,
+    snippet: message.snippet,
+    date: message.date,
+    hasAttachments: message.hasAttachments,
+    attachments: message.attachments.map((a) => a.filename),
+  };
+}
+
+/**
+ * POST `payload` to `url`, retrying network errors, 429 and 5xx responses
+ * after each of `retryDelaysMs`. Resolves to whether the server accepted it.
+ */
+export async function callWebhook(
+  url: string,
+  payload: WebhookPayload,
+  retryDelaysMs: number[] = WEBHOOK_RETRY_DELAYS_MS,
+): Promise<boolean> {
+  if (!isValidWebhookUrl(url)) {
+    console.error(`Skipping webhook with invalid URL: ${url}`);
+    return false;
+  }
+
+  for (let attempt = 0; ; attempt++) {
+    let retryable = true;
+    try {
+      const response = await fetch(url, {
+        method: "POST",
+        headers: { "Content-Type": "application/json", "User-Agent": "Sora" },
+        body: JSON.stringify(payload),
+      });
+      if (response.ok) return true;
+      retryable = response.status === 429 || response.status >= 500;
+      console.warn(`Webhook ${url} returned ${response.status}`);
+    } catch (err) {
+      console.warn(`Webhook ${url} failed:`, err);
+    }
+
+    const delay = retryDelaysMs[attempt];
+    if (!retryable || delay === undefined) {
+      console.error(`Giving up on webhook ${url} for message ${payload.messageId}`);
+      return false;
+    }
+    await new Promise((resolve) => setTimeout(resolve, delay));
+  }
+}
@@ -1,16 +1,87 @@
-import { describe, it, expect } from "vitest";
+import { describe, it, expect, vi, beforeEach } from "vitest";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+vi.mock("@tauri-apps/plugin-opener", () => ({
+  openUrl: vi.fn(),
+}));
+
+import { invoke } from "@tauri-apps/api/core";
+import { openUrl } from "@tauri-apps/plugin-opener";
 import { startOAuthFlow } from "./auth";
 
+const tokens = {
+  access_token: "access",
+  refresh_token: "refresh",
+  expires_in: 3600,
+  token_type: "Bearer",
+  scope: "",
+};
+
+function mockBackend() {
+  let state = "";
+  vi.mocked(openUrl).mockImplementation(async (url) => {
+    state = new URL(String(url)).searchParams.get("state") ?? "";
+  });
+  vi.mocked(invoke).mockImplementation(async (cmd) => {
+    switch (cmd) {
+      case "oauth_generate_pkce":
+        return { code_verifier: "verifier", code_challenge: "challenge" };
+      case "start_oauth_server":
+        // Resolves after the browser has been opened with the state
+        await new Promise((r) => setTimeout(r, 150));
+        return { code: "auth-code", state };
+      case "oauth_exchange_token":
+        return tokens;
+      default:
+        throw new Error(`unexpected command ${String(cmd)}`);
+    }
+  });
+}
+
+beforeEach(() => {
+  vi.clearAllMocks();
+  vi.stubGlobal(
+    "fetch",
+    vi.fn().mockResolvedValue({
+      ok: true,
+      json: async () => ({ email: "user@gmail.com", name: "User", picture: "" }),
+    }),
+  );
+});
+
 describe("startOAuthFlow", () => {
-  it("throws when client secret is undefined", async () => {
-    await expect(startOAuthFlow("client-id")).rejects.toThrow(
-      "Client Secret is not configured. Go to Settings → Google API to add it.",
-    );
+  it("signs in as a public client with PKCE", async () => {
+    mockBackend();
+
+    const result = await startOAuthFlow("client-id");
+
+    const authUrl = new URL(String(vi.mocked(openUrl).mock.calls[0]![0]));
+    expect(authUrl.searchParams.get("code_challenge")).toBe("challenge");
+    expect(authUrl.searchParams.get("code_challenge_method")).toBe("S256");
+    expect(invoke).toHaveBeenCalledWith("oauth_exchange_token", {
+      tokenUrl: "https://oauth2.googleapis.com/token",
+      code: "auth-code",
+      clientId: "client-id",
+      redirectUri: "http://127.0.0.1:17248",
+      codeVerifier: "verifier",
+      clientSecret: null,
+      scope: null,
+    });
+    expect(result.tokens).toEqual(tokens);
+    expect(result.userInfo.email).toBe("user@gmail.com");
   });
 
-  it("throws when client secret is empty string", async () => {
-    await expect(startOAuthFlow("client-id", "")).rejects.toThrow(
-      "Client Secret is not configured",
+  it("sends the client secret when one is configured", async () => {
+    mockBackend();
+
+    await startOAuthFlow("client-id", "secret");
+
+    expect(invoke).toHaveBeenCalledWith(
+      "oauth_exchange_token",
+      expect.objectContaining({ codeVerifier: "verifier", clientSecret: "secret" }),
     );
   });
 });
@@ -1,5 +1,6 @@
 import { invoke } from "@tauri-apps/api/core";
 import { openUrl } from "@tauri-apps/plugin-opener";
+import { generatePkce } from "../oauth/oauthFlow";
 
 const GOOGLE_AUTH_URL = "https://accounts.google.com/o/oauth2/v2/auth";
 const GOOGLE_TOKEN_URL = "https://oauth2.googleapis.com/token";
@@ -35,19 +36,6 @@ export interface UserInfo {
   picture: string;
 }
 
-function generateCodeVerifier(): string {
-  const array = new Uint8Array(32);
-  crypto.getRandomValues(array);
-  return base64UrlEncode(array);
-}
-
-async function generateCodeChallenge(verifier: string): Promise<string> {
-  const encoder = new TextEncoder();
-  const data = encoder.encode(verifier);
-  const digest = await crypto.subtle.digest("SHA-256", data);
-  return base64UrlEncode(new Uint8Array(digest));
-}
-
 function base64UrlEncode(bytes: Uint8Array): string {
   let binary = "";
   for (const byte of bytes) {
@@ -71,14 +59,7 @@ export async function startOAuthFlow(
   clientId: string,
   clientSecret?: string,
 ): Promise<{ tokens: TokenResponse; userInfo: UserInfo }> {
-  if (!clientSecret) {
-    throw new Error(
-      "Client Secret is not configured. Go to Settings → Google API to add it.",
-    );
-  }
-
-  const codeVerifier = generateCodeVerifier();
-  const codeChallenge = await generateCodeChallenge(codeVerifier);
+  const { code_verifier: codeVerifier, code_challenge: codeChallenge } = await generatePkce();
 
   // Generate random state for CSRF protection
   const stateArray = new Uint8Array(32);
@@ -141,27 +122,16 @@ async function exchangeCodeForTokens(
   codeVerifier: string,
   clientSecret?: string,
 ): Promise<TokenResponse> {
-  const params: Record<string, string> = {
+  // The verifier proves the code is ours, so public clients need no secret
+  return invoke<TokenResponse>("oauth_exchange_token", {
+    tokenUrl: GOOGLE_TOKEN_URL,
     code,
-    client_id: clientId,
-    redirect_uri: redirectUri,
-    grant_type: "authorization_code",
-    code_verifier: codeVerifier,
-  };
-  if (clientSecret) params.client_secret = clientSecret;
-
-  const response = await fetch(GOOGLE_TOKEN_URL, {
-    method: "POST",
-    headers: { "Content-Type": "application/x-www-form-urlencoded" },
-    body: new URLSearchParams(params),
+    clientId,
+    redirectUri,
+    codeVerifier,
+    clientSecret: clientSecret || null,
+    scope: null,
   });
-
-  if (!response.ok) {
-    const error = await response.text();
-    throw new Error(`Token exchange failed: ${error}`);
-  }
-
-  return response.json();
 }
 
 /**
@@ -1,5 +1,6 @@
 import type { GmailMessage, GmailMessagePart, GmailHeader } from "./client";
 import { parseAuthenticationResults } from "./authParser";
+import { parseListHeaders } from "@/utils/listHeaders";
 
 export interface ParsedAttachment {
   filename: string;
@@ -34,6 +35,28 @@ export interface ParsedMessage {
   listUnsubscribe: string | null;
   listUnsubscribePost: string | null;
   authResults: string | null;
+  /** MIME parsing failed and only basic headers are available (IMAP only). */
+  parseFailed?: boolean;
+  /** Envelope recipient: the alias or catch-all address it was delivered to. */
+  deliveredTo?: string | null;
+  /** Mailing list headers (List-Id, List-Post, List-Archive) */
+  listId?: string | null;
+  listPost?: string | null;
+  listArchive?: string | null;
+  /** Sent from one of the user's addresses: the SENT label for Gmail, the
+   *  backend's identity matcher for IMAP. */
+  isFromMe?: boolean;
+  /** Mail-Followup-To: where the sender wants replies to all to go. */
+  mailFollowupTo?: string | null;
+  /** IMAP keywords, e.g. color categories like `$label1` (IMAP only). */
+  keywords?: string[];
+  /** Emails attached as message/rfc822 (IMAP only). */
+  attachedMessages?: ParsedAttachedMessage[];
+}
+
+export interface ParsedAttachedMessage {
+  partId: string;
+  message: ParsedMessage;
 }
 
 export function parseGmailMessage(msg: GmailMessage): ParsedMessage {
@@ -55,11 +78,13 @@ export function parseGmailMessage(msg: GmailMessage): ParsedMessage {
     ccAddresses: getHeader(headers, "Cc"),
     bccAddresses: getHeader(headers, "Bcc"),
     replyTo: getHeader(headers, "Reply-To"),
+    mailFollowupTo: getHeader(headers, "Mail-Followup-To"),
     subject: getHeader(headers, "Subject"),
     snippet: msg.snippet,
     date: parseInt(msg.internalDate, 10),
     isRead: !msg.labelIds.includes("UNREAD"),
     isStarred: msg.labelIds.includes("STARRED"),
+    isFromMe: msg.labelIds.includes("SENT"),
     bodyHtml: bodyHtml ? decodeBase64Url(bodyHtml) : null,
     bodyText: bodyText ? decodeBase64Url(bodyText) : null,
     rawSize: msg.sizeEstimate,
@@ -70,6 +95,14 @@ export function parseGmailMessage(msg: GmailMessage): ParsedMessage {
     listUnsubscribe: getHeader(headers, "List-Unsubscribe"),
     listUnsubscribePost: getHeader(headers, "List-Unsubscribe-Post"),
     authResults: authResult ? JSON.stringify(authResult) : null,
+    deliveredTo: parseEmailAddress(
+      getHeader(headers, "X-Original-To") ?? getHeader(headers, "Delivered-To"),
+    ).address,
+    ...parseListHeaders(
+      getHeader(headers, "List-Id"),
+      getHeader(headers, "List-Post"),
+      getHeader(headers, "List-Archive"),
+    ),
   };
 }
 
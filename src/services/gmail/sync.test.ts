@@ -1,6 +1,7 @@
 import { describe, it, expect, vi, beforeEach } from "vitest";
 import { deltaSync } from "./sync";
 import { GmailClient } from "./client";
+import { getSetting } from "../db/settings";
 
 // Mock all DB modules
 vi.mock("../db/threads", () => ({
@@ -47,9 +48,11 @@ vi.mock("@/services/db/pendingOperations", () => ({
 }));
 
 const mockNotify = vi.fn();
+const mockNotifyAfterGrace = vi.fn();
 const mockShouldNotify = vi.fn().mockReturnValue(true);
 vi.mock("../notifications/notificationManager", () => ({
   queueNewEmailNotification: (...args: unknown[]) => mockNotify(...args),
+  queueNewEmailNotificationAfterGrace: (...args: unknown[]) => mockNotifyAfterGrace(...args),
   shouldNotifyForMessage: (...args: unknown[]) => mockShouldNotify(...args),
 }));
 
@@ -109,8 +112,10 @@ function createMockClient(historyItems: unknown[]): GmailClient {
 describe("deltaSync notifications", () => {
   beforeEach(() => {
     mockNotify.mockClear();
+    mockNotifyAfterGrace.mockClear();
     mockShouldNotify.mockClear();
     mockShouldNotify.mockReturnValue(true);
+    vi.mocked(getSetting).mockResolvedValue(null);
   });
 
   it("sends notification for new unread inbox message", async () => {
@@ -137,6 +142,7 @@ describe("deltaSync notifications", () => {
       "thread-1",
       "account-1",
       "sender@example.com",
+      null,
     );
   });
 
@@ -181,4 +187,35 @@ describe("deltaSync notifications", () => {
 
     expect(mockNotify).not.toHaveBeenCalled();
   });
+
+  it("holds the notification for a junk re-check when a grace period is set", async () => {
+    vi.mocked(getSetting).mockImplementation(async (key: string) =>
+      key === "junk_grace_seconds" ? "30" : null,
+    );
+    const client = createMockClient([
+      {
+        id: "100",
+        messagesAdded: [
+          {
+            message: {
+              id: "msg-thread-4",
+              threadId: "thread-4",
+              labelIds: ["INBOX", "UNREAD"],
+            },
+          },
+        ],
+      },
+    ]);
+    (client as unknown as { getMessage: unknown }).getMessage = vi
+      .fn()
+      .mockResolvedValue({ id: "msg-thread-4", labelIds: ["SPAM", "UNREAD"] });
+
+    await deltaSync(client, "account-1", "99");
+
+    expect(mockNotify).not.toHaveBeenCalled();
+    expect(mockNotifyAfterGrace).toHaveBeenCalledTimes(1);
+    const [graceMs, isStillWanted] = mockNotifyAfterGrace.mock.calls[0] as [number, () => Promise<boolean>];
+    expect(graceMs).toBe(30_000);
+    await expect(isStillWanted()).resolves.toBe(false);
+  });
 });
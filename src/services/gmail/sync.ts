@@ -5,13 +5,19 @@ import { upsertThread, setThreadLabels } from "../db/threads";
 import { upsertMessage } from "../db/messages";
 import { upsertAttachment } from "../db/attachments";
 import { updateAccountSyncState } from "../db/accounts";
-import { shouldNotifyForMessage, queueNewEmailNotification } from "../notifications/notificationManager";
+import {
+  shouldNotifyForMessage,
+  queueNewEmailNotification,
+  queueNewEmailNotificationAfterGrace,
+} from "../notifications/notificationManager";
 import { applyFiltersToMessages } from "../filters/filterEngine";
+import { extractOtp } from "../extraction/otp";
 import { getSetting } from "../db/settings";
 import { getMutedThreadIds } from "../db/threads";
 import { getThreadCategory } from "../db/threadCategories";
 import { getVipSenders } from "../db/notificationVips";
 import { getPendingOpsForResource } from "../db/pendingOperations";
+import { getThreadSubject } from "@/utils/subject";
 
 async function loadAutoArchiveCategories(): Promise<Set<string>> {
   const raw = await getSetting("auto_archive_categories");
@@ -19,6 +25,16 @@ async function loadAutoArchiveCategories(): Promise<Set<string>> {
   return new Set(raw.split(",").map((s) => s.trim()).filter(Boolean));
 }
 
+/**
+ * Re-check a new message's labels after the junk grace period: only notify
+ * if it is still in the inbox and wasn't reclassified as spam.
+ */
+async function isStillInInbox(client: GmailClient, messageId: string): Promise<boolean> {
+  const message = await client.getMessage(messageId, "minimal");
+  const labels = message.labelIds ?? [];
+  return labels.includes("INBOX") && !labels.includes("SPAM");
+}
+
 export interface SyncProgress {
   phase: "labels" | "threads" | "messages" | "done";
   current: number;
@@ -39,7 +55,6 @@ async function processAndStoreThread(
   autoArchiveCategories?: Set<string>,
 ): Promise<void> {
   const lastMessage = parsedMessages[parsedMessages.length - 1]!;
-  const firstMessage = parsedMessages[0]!;
 
   const allLabelIds = new Set<string>();
   for (const msg of parsedMessages) {
@@ -56,7 +71,7 @@ async function processAndStoreThread(
   await upsertThread({
     id: thread.id,
     accountId,
-    subject: firstMessage.subject,
+    subject: getThreadSubject(parsedMessages.map((m) => m.subject)),
     snippet: lastMessage.snippet,
     lastMessageAt: lastMessage.date,
     messageCount: parsedMessages.length,
@@ -133,6 +148,12 @@ async function processAndStoreThread(
       listUnsubscribe: parsed.listUnsubscribe,
       listUnsubscribePost: parsed.listUnsubscribePost,
       authResults: parsed.authResults,
+      deliveredTo: parsed.deliveredTo,
+      listId: parsed.listId,
+      listPost: parsed.listPost,
+      listArchive: parsed.listArchive,
+      isFromMe: parsed.isFromMe,
+      mailFollowupTo: parsed.mailFollowupTo,
     });
 
     await Promise.all(parsed.attachments.map((att) =>
@@ -347,6 +368,7 @@ export async function deltaSync(
       ((await getSetting("notify_categories")) ?? "Primary").split(",").map((s) => s.trim()).filter(Boolean),
     );
     const vipSenders = smartNotifications ? await getVipSenders(accountId) : new Set<string>();
+    const junkGraceMs = (parseInt((await getSetting("junk_grace_seconds")) ?? "0", 10) || 0) * 1000;
 
     // Re-fetch affected threads in parallel (max 5 concurrent)
     const threadIds = [...affectedThreadIds];
@@ -389,13 +411,33 @@ export async function deltaSync(
               const fromAddr = parsed.fromAddress ?? undefined;
               if (shouldNotifyForMessage(smartNotifications, notifyCategories, vipSenders, await getThreadCategory(accountId, threadId), fromAddr)) {
                 const sender = parsed.fromName ?? parsed.fromAddress ?? "Unknown";
-                queueNewEmailNotification(
-                  sender,
-                  parsed.subject ?? "",
-                  parsed.threadId,
-                  accountId,
-                  fromAddr,
-                );
+                const code = extractOtp({
+                  subject: parsed.subject,
+                  body_text: parsed.bodyText,
+                  from_address: parsed.fromAddress,
+                });
+                if (junkGraceMs > 0) {
+                  // Hold the notification until spam filtering has had a chance to move it
+                  queueNewEmailNotificationAfterGrace(
+                    junkGraceMs,
+                    () => isStillInInbox(client, parsed.id),
+                    sender,
+                    parsed.subject ?? "",
+                    parsed.threadId,
+                    accountId,
+                    fromAddr,
+                    code,
+                  );
+                } else {
+                  queueNewEmailNotification(
+                    sender,
+                    parsed.subject ?? "",
+                    parsed.threadId,
+                    accountId,
+                    fromAddr,
+                    code,
+                  );
+                }
               }
             }
           }
@@ -413,6 +455,11 @@ export async function deltaSync(
             import("@/services/smartLabels/smartLabelManager")
               .then(({ applySmartLabelsToMessages }) => applySmartLabelsToMessages(accountId, newMessages))
               .catch((err) => console.error("Smart label error:", err));
+
+            // Record receipts and invoices (fire-and-forget, non-blocking)
+            import("@/services/extraction/purchaseManager")
+              .then(({ recordPurchasesFromMessages }) => recordPurchasesFromMessages(accountId, newMessages))
+              .catch((err) => console.error("Purchase extraction error:", err));
           }
         } catch (err) {
           console.error(`Failed to re-sync thread ${threadId}:`, err);
@@ -32,10 +32,24 @@ vi.mock("../db/folderSyncState", () => ({
 vi.mock("../oauth/oauthTokenManager", () => ({
   ensureFreshToken: vi.fn(),
 }));
+vi.mock("../imap/imapPush", () => ({
+  ensureImapPush: vi.fn().mockResolvedValue(undefined),
+}));
+vi.mock("../notes", () => ({
+  syncNotes: vi.fn().mockResolvedValue(undefined),
+}));
+vi.mock("../teamTags", () => ({
+  syncTeamTags: vi.fn().mockResolvedValue(0),
+}));
+vi.mock("../search/smartFolderCounts", () => ({
+  refreshSmartFolderCounts: vi.fn().mockResolvedValue({}),
+}));
 
 // Import after mocks
 import {
   syncAccount,
+  syncDueFolders,
+  retrySyncNow,
   startBackgroundSync,
   stopBackgroundSync,
   triggerSync,
@@ -43,6 +57,11 @@ import {
 import { getAccount } from "../db/accounts";
 import { getGmailClient } from "./tokenManager";
 import { initialSync, deltaSync } from "./sync";
+import {
+  FAILURES_TO_OPEN_CIRCUIT,
+  getAccountHealth,
+  resetAccountHealth,
+} from "../sync/accountHealth";
 
 const mockGetAccount = vi.mocked(getAccount);
 const mockGetGmailClient = vi.mocked(getGmailClient);
@@ -82,6 +101,7 @@ describe("syncManager", () => {
   beforeEach(() => {
     vi.clearAllMocks();
     stopBackgroundSync();
+    for (const id of ["a1", "a2", "bad", "good"]) resetAccountHealth(id);
     mockGetGmailClient.mockResolvedValue(
       {} as ReturnType<typeof getGmailClient> extends Promise<infer T>
         ? T
@@ -114,7 +134,7 @@ describe("syncManager", () => {
       expect(mockInitialSync).not.toHaveBeenCalled();
     });
 
-    it("queues a second account while sync is in progress", async () => {
+    it("syncs a second account while the first is in progress", async () => {
       const a1 = makeGmailAccount("a1", "100");
       const a2 = makeGmailAccount("a2", "200");
 
@@ -139,13 +159,31 @@ describe("syncManager", () => {
       });
 
       const first = syncAccount("a1");
-      // a2 will be queued since a1 is in progress
-      const second = syncAccount("a2");
+      // a2 has its own lane, so it doesn't wait for a1
+      await syncAccount("a2");
+      expect(mockDeltaSync).toHaveBeenCalledTimes(2);
 
       await first;
-      await second;
+    });
+
+    it("queues a repeat sync of the same account behind the running one", async () => {
+      mockGetAccount.mockResolvedValue(makeGmailAccount("a1", "100"));
+
+      let release: () => void = () => {};
+      mockDeltaSync.mockImplementationOnce(
+        () => new Promise<void>((r) => { release = r; }),
+      );
+
+      const first = syncAccount("a1");
+      const second = syncAccount("a1");
+      const third = syncAccount("a1");
+      await wait(10);
+      expect(mockDeltaSync).toHaveBeenCalledTimes(1);
+
+      release();
+      await Promise.all([first, second, third]);
 
-      // Both accounts synced (a1 directly, a2 via queue drain)
+      // The second and third requests shared one queued run
       expect(mockDeltaSync).toHaveBeenCalledTimes(2);
     });
   });
@@ -198,35 +236,83 @@ describe("syncManager", () => {
       expect(mockDeltaSync).not.toHaveBeenCalled();
     });
 
-    it("without the fix, new account sync would be blocked by existing account sync", async () => {
+    it("a hung account does not hold up the others", async () => {
       const existingAccount = makeGmailAccount("existing", "100");
       const newAccount = makeGmailAccount("new-acc");
 
-      // Track the order of sync calls
-      const syncOrder: string[] = [];
-
       mockGetAccount.mockImplementation(async (id: string) => {
         if (id === "existing") return existingAccount;
         if (id === "new-acc") return newAccount;
         return null;
       });
 
-      mockDeltaSync.mockImplementation(async () => {
-        syncOrder.push("delta-existing");
-      });
-      mockInitialSync.mockImplementation(async () => {
-        syncOrder.push("initial-new");
-      });
+      // The existing account's server never answers
+      mockDeltaSync.mockImplementation(() => new Promise<void>(() => {}));
 
-      // Old behavior: startBackgroundSync first (with immediate sync), then syncAccount
-      // This would queue new-acc behind existing account's delta sync
       startBackgroundSync(["existing", "new-acc"]);
-
-      // Wait for both to complete
       await wait(50);
 
-      // existing account's delta sync ran BEFORE new account's initial sync
-      expect(syncOrder).toEqual(["delta-existing", "initial-new"]);
+      expect(mockInitialSync).toHaveBeenCalledTimes(1);
+    });
+  });
+
+  describe("account health", () => {
+    it("opens the circuit after repeated failures and skips background ticks", async () => {
+      mockGetAccount.mockResolvedValue(makeGmailAccount("a1", "100"));
+      mockDeltaSync.mockRejectedValue(new Error("Connection refused"));
+
+      for (let i = 0; i < FAILURES_TO_OPEN_CIRCUIT; i++) {
+        await syncAccount("a1");
+      }
+      expect(getAccountHealth("a1")).toMatchObject({
+        state: "failing",
+        reason: "Connection refused",
+      });
+
+      await syncDueFolders("a1");
+      expect(mockDeltaSync).toHaveBeenCalledTimes(FAILURES_TO_OPEN_CIRCUIT);
+
+      // An explicit sync still probes the server and closes the circuit on success
+      mockDeltaSync.mockResolvedValue();
+      await syncAccount("a1");
+      expect(getAccountHealth("a1").state).toBe("ok");
+    });
+
+    it("retrySyncNow ignores the backoff", async () => {
+      mockGetAccount.mockResolvedValue(makeGmailAccount("a1", "100"));
+      mockDeltaSync.mockRejectedValueOnce(new Error("Connection refused"));
+
+      await syncAccount("a1");
+      expect(getAccountHealth("a1").nextRetryAt).not.toBeNull();
+
+      await syncDueFolders("a1");
+      expect(mockDeltaSync).toHaveBeenCalledTimes(1);
+
+      await retrySyncNow("a1");
+      expect(mockDeltaSync).toHaveBeenCalledTimes(2);
+      expect(getAccountHealth("a1").state).toBe("ok");
+    });
+
+    it("does not back off on auth failures", async () => {
+      mockGetAccount.mockResolvedValue(makeGmailAccount("a1", "100"));
+      mockDeltaSync.mockRejectedValueOnce(new Error("HTTP 401 Unauthorized"));
+
+      await syncAccount("a1");
+
+      expect(getAccountHealth("a1")).toMatchObject({ state: "failing", nextRetryAt: null });
+    });
+
+    it("failures in one account leave the others healthy", async () => {
+      mockGetAccount.mockImplementation(async (id: string) => makeGmailAccount(id, "100"));
+      mockGetGmailClient.mockImplementation(async (id: string) => {
+        if (id === "bad") throw new Error("getaddrinfo ENOTFOUND");
+        return {} as Awaited<ReturnType<typeof getGmailClient>>;
+      });
+
+      await triggerSync(["bad", "good"]);
+
+      expect(getAccountHealth("bad").state).toBe("degraded");
+      expect(getAccountHealth("good").state).toBe("ok");
     });
   });
 
@@ -1,21 +1,62 @@
 import { getGmailClient } from "./tokenManager";
 import { initialSync, deltaSync, type SyncProgress } from "./sync";
-import { getAccount, clearAccountHistoryId } from "../db/accounts";
+import { getAccount, clearAccountHistoryId, type DbAccount } from "../db/accounts";
 import { getSetting } from "../db/settings";
 import { getThreadCountForAccount, deleteAllThreadsForAccount } from "../db/threads";
 import { deleteAllMessagesForAccount } from "../db/messages";
 import { imapInitialSync, imapDeltaSync } from "../imap/imapSync";
+import { nntpSync } from "../nntp/nntpSync";
 import { clearAllFolderSyncStates } from "../db/folderSyncState";
+import {
+  hasDueFolders,
+  getNextPollDelay,
+  resetPollSchedule,
+  MAX_POLL_INTERVAL_MS,
+} from "../imap/pollScheduler";
+import { ensureImapPush } from "../imap/imapPush";
+import { syncNotes } from "../notes";
+import { syncTeamTags } from "../teamTags";
+import { refreshSmartFolderCounts } from "../search/smartFolderCounts";
 import { ensureFreshToken } from "../oauth/oauthTokenManager";
 import { hasCalendarSupport, getCalendarProvider } from "../calendar/providerFactory";
 import { getVisibleCalendars, upsertCalendar, updateCalendarSyncToken } from "../db/calendars";
 import { upsertCalendarEvent, deleteEventByRemoteId } from "../db/calendarEvents";
+import { classifyError } from "../../utils/networkErrors";
+import {
+  clearBackoff,
+  isBackingOff,
+  recordSyncFailure,
+  recordSyncSuccess,
+  resetAccountHealth,
+} from "../sync/accountHealth";
 
 const SYNC_INTERVAL_MS = 60_000; // 60 seconds — delta syncs are lightweight (single API call when idle)
+/** Lower bound between background ticks, so a burst of due folders can't spin the timer. */
+const MIN_TIMER_DELAY_MS = 5_000;
+/** A delta sync that takes longer than this is treated as hung. */
+const SYNC_TIMEOUT_MS = 5 * 60_000;
+/** Initial syncs download the whole sync period, so they get much longer. */
+const INITIAL_SYNC_TIMEOUT_MS = 60 * 60_000;
 
-let syncTimer: ReturnType<typeof setInterval> | null = null;
-let syncPromise: Promise<void> | null = null;
-let pendingAccountIds: string[] | null = null;
+let syncTimer: ReturnType<typeof setTimeout> | null = null;
+/** Bumped on stop/restart so an in-flight tick doesn't schedule a stale timer. */
+let timerGeneration = 0;
+
+/**
+ * Each account syncs in its own lane: at most one run in flight plus one
+ * queued behind it. Lanes run independently, so a slow or hung account never
+ * delays the others.
+ */
+interface AccountLane {
+  running: Promise<void>;
+  queued: Promise<void> | null;
+  /** Whether the queued run is a background tick (may skip when nothing is due). */
+  queuedBackground: boolean;
+}
+
+const lanes = new Map<string, AccountLane>();
+/** When each account last finished a sync. */
+const lastSyncAt = new Map<string, number>();
 
 export type SyncStatusCallback = (
   accountId: string,
@@ -73,7 +114,7 @@ async function syncGmailAccount(accountId: string): Promise<void> {
 /**
  * Run a sync for a single IMAP account (initial or delta).
  */
-async function syncImapAccount(accountId: string): Promise<void> {
+async function syncImapAccount(accountId: string, background: boolean): Promise<void> {
   const account = await getAccount(accountId);
 
   if (!account) {
@@ -90,7 +131,14 @@ async function syncImapAccount(accountId: string): Promise<void> {
 
   if (account.history_id) {
     // Delta sync — IMAP uses folder-level UID tracking
-    const result = await imapDeltaSync(accountId);
+    const result = await imapDeltaSync(accountId, { dueOnly: background });
+
+    if (result.messages.length > 0) {
+      // Record receipts and invoices (fire-and-forget, non-blocking)
+      import("@/services/extraction/purchaseManager")
+        .then(({ recordPurchasesFromMessages }) => recordPurchasesFromMessages(accountId, result.messages))
+        .catch((err) => console.error("Purchase extraction error:", err));
+    }
 
     // Recovery: if delta sync found nothing new but the DB has no threads,
     // the previous initial sync likely failed or stored data incorrectly.
@@ -196,11 +244,24 @@ async function syncCalendarForAccount(accountId: string): Promise<void> {
   }
 }
 
+/**
+ * Whether a background tick should sync this account now. IMAP accounts
+ * follow the adaptive per-folder poll schedule; others use the fixed interval.
+ */
+function isAccountDue(accountId: string, provider: string, now: number): boolean {
+  if (provider === "imap") return hasDueFolders(accountId, now);
+  const last = lastSyncAt.get(accountId);
+  return last === undefined || now - last >= SYNC_INTERVAL_MS - MIN_TIMER_DELAY_MS;
+}
+
 /**
  * Run a sync for a single account (initial or delta).
  * Routes to Gmail or IMAP sync based on account provider.
+ * Background ticks skip accounts that aren't due yet or are backing off after
+ * failures.
+ * The outcome is recorded in the account's health.
  */
-async function syncAccountInternal(accountId: string): Promise<void> {
+async function syncAccountInternal(accountId: string, background = false): Promise<void> {
   try {
     const account = await getAccount(accountId);
 
@@ -208,28 +269,33 @@ async function syncAccountInternal(accountId: string): Promise<void> {
       throw new Error("Account not found");
     }
 
-    statusCallback?.(accountId, "syncing");
-
-    console.log(`[syncManager] Syncing account ${accountId} (provider=${account.provider}, history_id=${account.history_id ?? "null"})`);
-
-    if (account.provider === "caldav") {
-      // CalDAV-only accounts — skip email sync, only sync calendar
-      await syncCalendarForAccount(accountId);
-      statusCallback?.(accountId, "done");
+    const now = Date.now();
+    if (background && (isBackingOff(accountId, now) || !isAccountDue(accountId, account.provider, now))) {
       return;
     }
 
-    if (account.provider === "imap") {
-      await syncImapAccount(accountId);
-    } else {
-      await syncGmailAccount(accountId);
-    }
+    statusCallback?.(accountId, "syncing");
+
+    const timeoutMs = account.history_id ? SYNC_TIMEOUT_MS : INITIAL_SYNC_TIMEOUT_MS;
+    await withTimeout(
+      syncMail(account, background),
+      timeoutMs,
+      `Sync timed out after ${Math.round(timeoutMs / 60_000)} minutes`,
+    );
+    recordSyncSuccess(accountId);
 
     // Always emit "done" when an initial sync completes (clears the bar).
     // Also emit for delta syncs that fell back to initial (recovery re-sync)
     // since those emit progress via statusCallback inside syncImapAccount.
     statusCallback?.(accountId, "done");
 
+    if (account.provider === "caldav") return;
+
+    // Recount smart folder badges against the freshly synced messages
+    refreshSmartFolderCounts(accountId).catch((err) => {
+      console.warn(`[syncManager] Smart folder count refresh failed for ${accountId}:`, err);
+    });
+
     // Sync calendar alongside email (non-blocking — calendar errors don't affect email sync)
     syncCalendarForAccount(accountId).catch((err) => {
       console.warn(`[syncManager] Calendar sync error for ${accountId}:`, err);
@@ -237,37 +303,109 @@ async function syncAccountInternal(accountId: string): Promise<void> {
   } catch (err) {
     const message = err instanceof Error ? err.message : "Unknown error";
     console.error(`[syncManager] Sync failed for account ${accountId}:`, message);
+    recordSyncFailure(accountId, message, classifyError(err).type === "auth");
     statusCallback?.(accountId, "error", undefined, message);
   }
 }
 
-async function runSync(accountIds: string[]): Promise<void> {
-  if (syncPromise) {
-    // Queue these accounts, merging with any already-pending IDs
-    const existing = new Set(pendingAccountIds ?? []);
-    for (const id of accountIds) existing.add(id);
-    pendingAccountIds = [...existing];
-    return syncPromise;
+/** Reject with `message` if `promise` hasn't settled within `ms`. */
+function withTimeout<T>(promise: Promise<T>, ms: number, message: string): Promise<T> {
+  let timer: ReturnType<typeof setTimeout> | undefined;
+  const timeout = new Promise<never>((_, reject) => {
+    timer = setTimeout(() => reject(new Error(message)), ms);
+  });
+  return Promise.race([promise, timeout]).finally(() => clearTimeout(timer));
+}
+
+/** The provider-specific part of an account sync. */
+async function syncMail(account: DbAccount, background: boolean): Promise<void> {
+  const accountId = account.id;
+  console.log(`[syncManager] Syncing account ${accountId} (provider=${account.provider}, history_id=${account.history_id ?? "null"})`);
+
+  if (account.provider === "caldav") {
+    // CalDAV-only accounts — skip email sync, only sync calendar
+    await syncCalendarForAccount(accountId);
+    return;
   }
 
-  syncPromise = (async () => {
-    try {
-      for (const id of accountIds) {
-        await syncAccountInternal(id);
-      }
-    } finally {
-      syncPromise = null;
-    }
+  if (account.provider === "imap") {
+    await syncImapAccount(accountId, background);
+    // Push for changes between polls where the server supports NOTIFY or IDLE
+    ensureImapPush(accountId).catch((err) => {
+      console.warn(`[syncManager] Push setup failed for ${accountId}:`, err);
+    });
+    syncNotes(accountId).catch((err) => {
+      console.warn(`[syncManager] Note sync failed for ${accountId}:`, err);
+    });
+    syncTeamTags(accountId).catch((err) => {
+      console.warn(`[syncManager] Team tag sync failed for ${accountId}:`, err);
+    });
+  } else if (account.provider === "nntp") {
+    await nntpSync(accountId);
+  } else {
+    await syncGmailAccount(accountId);
+  }
+  lastSyncAt.set(accountId, Date.now());
+}
 
-    // Drain the queue — if something was queued while we were syncing, run it now
-    if (pendingAccountIds) {
-      const queued = pendingAccountIds;
-      pendingAccountIds = null;
-      await runSync(queued);
-    }
-  })();
+/**
+ * Sync one account in its lane. If a run is in flight, one more run is
+ * queued behind it and further requests join that queued run; an explicit
+ * sync joining a queued background tick makes the queued run explicit.
+ */
+function runAccountSync(accountId: string, background: boolean): Promise<void> {
+  const lane = lanes.get(accountId);
+  if (!lane) return startLane(accountId, background);
+
+  if (lane.queued) {
+    lane.queuedBackground = lane.queuedBackground && background;
+  } else {
+    lane.queuedBackground = background;
+    lane.queued = lane.running.then(() => startLane(accountId, lane.queuedBackground));
+  }
+  return lane.queued;
+}
+
+function startLane(accountId: string, background: boolean): Promise<void> {
+  const lane: AccountLane = { running: Promise.resolve(), queued: null, queuedBackground: true };
+  lanes.set(accountId, lane);
+  // syncAccountInternal never rejects, and a timed-out run resolves, so a
+  // hung server frees the lane instead of blocking the account forever
+  lane.running = syncAccountInternal(accountId, background).finally(() => {
+    if (!lane.queued && lanes.get(accountId) === lane) lanes.delete(accountId);
+  });
+  return lane.running;
+}
+
+/** Sync the given accounts concurrently; resolves when all of them finish. */
+async function runSync(accountIds: string[], background = false): Promise<void> {
+  await Promise.all(accountIds.map((id) => runAccountSync(id, background)));
+}
+
+/**
+ * Delay until the next background tick: the earliest due IMAP folder, or the
+ * fixed interval for accounts without a poll schedule (Gmail, first sync).
+ */
+function nextSyncDelay(accountIds: string[]): number {
+  const now = Date.now();
+  let delay = MAX_POLL_INTERVAL_MS;
+  for (const id of accountIds) {
+    const folderDelay = getNextPollDelay([id], now);
+    const accountDelay =
+      folderDelay ?? SYNC_INTERVAL_MS - (now - (lastSyncAt.get(id) ?? now));
+    delay = Math.min(delay, accountDelay);
+  }
+  return Math.max(delay, MIN_TIMER_DELAY_MS);
+}
 
-  return syncPromise;
+function scheduleNextSync(accountIds: string[], generation: number): void {
+  syncTimer = setTimeout(() => {
+    syncTimer = null;
+    runSync(accountIds, true).finally(() => {
+      // Don't reschedule if the timer was stopped or restarted meanwhile
+      if (generation === timerGeneration) scheduleNextSync(accountIds, generation);
+    });
+  }, nextSyncDelay(accountIds));
 }
 
 /**
@@ -277,11 +415,30 @@ export async function syncAccount(accountId: string): Promise<void> {
   return runSync([accountId]);
 }
 
+/**
+ * Sync an account right away, ignoring any backoff from earlier failures.
+ */
+export async function retrySyncNow(accountId: string): Promise<void> {
+  clearBackoff(accountId);
+  return runSync([accountId]);
+}
+
+/**
+ * Sync only the folders of an account that are due, e.g. after a NOTIFY push
+ * marked one of them due. Queues behind any running sync.
+ */
+export async function syncDueFolders(accountId: string): Promise<void> {
+  return runSync([accountId], true);
+}
+
 /**
  * Start the background sync timer for all accounts.
  * When `skipImmediateSync` is true the first periodic sync is deferred to the
- * next interval tick — useful when the caller already triggered a sync for a
+ * next tick — useful when the caller already triggered a sync for a
  * newly-added account and doesn't want existing accounts to block it.
+ *
+ * Ticks are scheduled adaptively: IMAP folders are polled more often after
+ * new mail and back off while quiet (see pollScheduler).
  */
 export function startBackgroundSync(accountIds: string[], skipImmediateSync = false): void {
   stopBackgroundSync();
@@ -292,17 +449,16 @@ export function startBackgroundSync(accountIds: string[], skipImmediateSync = fa
   }
 
   // Periodic sync
-  syncTimer = setInterval(() => {
-    runSync(accountIds);
-  }, SYNC_INTERVAL_MS);
+  scheduleNextSync(accountIds, timerGeneration);
 }
 
 /**
  * Stop the background sync timer.
  */
 export function stopBackgroundSync(): void {
+  timerGeneration++;
   if (syncTimer) {
-    clearInterval(syncTimer);
+    clearTimeout(syncTimer);
     syncTimer = null;
   }
 }
@@ -332,6 +488,8 @@ export async function forceFullSync(accountIds: string[]): Promise<void> {
  * then runs a fresh initial sync.
  */
 export async function resyncAccount(accountId: string): Promise<void> {
+  resetPollSchedule(accountId);
+  resetAccountHealth(accountId);
   await deleteAllThreadsForAccount(accountId);
   await deleteAllMessagesForAccount(accountId);
   await clearAccountHistoryId(accountId);
@@ -0,0 +1,80 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+vi.mock("@/services/db/settings", () => ({
+  getSetting: vi.fn(),
+  setSetting: vi.fn(),
+}));
+
+vi.mock("@/services/db/sendAsAliases", () => ({
+  getAliasesForAccount: vi.fn(),
+}));
+
+import { invoke } from "@tauri-apps/api/core";
+import { getSetting, setSetting } from "@/services/db/settings";
+import { getAliasesForAccount } from "@/services/db/sendAsAliases";
+import type { DbSendAsAlias } from "@/services/db/sendAsAliases";
+import {
+  getExtraAddresses,
+  matchMyAddresses,
+  registerIdentity,
+  setExtraAddresses,
+} from "./identity";
+
+const mockInvoke = vi.mocked(invoke);
+const account = { id: "acc-1", email: "me@example.com" };
+
+describe("identity", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    vi.mocked(getSetting).mockResolvedValue(null);
+    vi.mocked(getAliasesForAccount).mockResolvedValue([]);
+    mockInvoke.mockResolvedValue(undefined);
+  });
+
+  it("tolerates missing or malformed extra addresses", async () => {
+    expect(await getExtraAddresses("acc-1")).toEqual([]);
+    vi.mocked(getSetting).mockResolvedValue("not json");
+    expect(await getExtraAddresses("acc-1")).toEqual([]);
+    vi.mocked(getSetting).mockResolvedValue('["old@example.com", 3]');
+    expect(await getExtraAddresses("acc-1")).toEqual(["old@example.com"]);
+    expect(getSetting).toHaveBeenCalledWith("identity_addresses:acc-1");
+  });
+
+  it("registers the account address, aliases and extra addresses", async () => {
+    vi.mocked(getAliasesForAccount).mockResolvedValue([
+      { email: "team@example.com" } as DbSendAsAlias,
+    ]);
+    vi.mocked(getSetting).mockResolvedValue('["old@example.com"]');
+
+    await registerIdentity(account);
+
+    expect(mockInvoke).toHaveBeenCalledWith("identity_set_addresses", {
+      accountId: "acc-1",
+      addresses: ["me@example.com", "team@example.com", "old@example.com"],
+    });
+  });
+
+  it("saves cleaned extra addresses and re-registers", async () => {
+    await setExtraAddresses(account, [" old@example.com", "nonsense", "old@example.com"]);
+
+    expect(setSetting).toHaveBeenCalledWith("identity_addresses:acc-1", '["old@example.com"]');
+    expect(mockInvoke).toHaveBeenCalledWith("identity_set_addresses", expect.anything());
+  });
+
+  it("returns the addresses the backend matched", async () => {
+    mockInvoke.mockResolvedValue([true, false]);
+
+    const mine = await matchMyAddresses(["Me <me+x@example.com>", "bob@example.com"], "acc-1");
+
+    expect(mine).toEqual(new Set(["Me <me+x@example.com>"]));
+    expect(mockInvoke).toHaveBeenCalledWith("identity_match", {
+      accountId: "acc-1",
+      addresses: ["Me <me+x@example.com>", "bob@example.com"],
+    });
+    expect(await matchMyAddresses([])).toEqual(new Set());
+  });
+});
@@ -0,0 +1,63 @@
+import { invoke } from "@tauri-apps/api/core";
+import { getSetting, setSetting } from "@/services/db/settings";
+import { getAliasesForAccount } from "@/services/db/sendAsAliases";
+
+/**
+ * The user's own addresses, so the backend can mark messages sent by them
+ * (`is_from_me`) and replies can leave them out of the recipients.
+ *
+ * Each account's addresses are its own address, its send-as aliases and any
+ * extra addresses the user lists in settings (old addresses, forwarding
+ * addresses). The backend ignores case and `+tag` suffixes when matching.
+ */
+
+function extraAddressesKey(accountId: string): string {
+  return `identity_addresses:${accountId}`;
+}
+
+/** Extra addresses the user listed as theirs for an account. */
+export async function getExtraAddresses(accountId: string): Promise<string[]> {
+  const value = await getSetting(extraAddressesKey(accountId));
+  if (!value) return [];
+  try {
+    const list: unknown = JSON.parse(value);
+    return Array.isArray(list) ? list.filter((v): v is string => typeof v === "string") : [];
+  } catch {
+    return [];
+  }
+}
+
+export async function setExtraAddresses(
+  account: { id: string; email: string },
+  addresses: string[],
+): Promise<void> {
+  const cleaned = [...new Set(addresses.map((a) => a.trim()).filter((a) => a.includes("@")))];
+  await setSetting(extraAddressesKey(account.id), JSON.stringify(cleaned));
+  await registerIdentity(account);
+}
+
+/** Tell the backend which addresses belong to an account. */
+export async function registerIdentity(account: { id: string; email: string }): Promise<void> {
+  const [aliases, extra] = await Promise.all([
+    getAliasesForAccount(account.id),
+    getExtraAddresses(account.id),
+  ]);
+  const addresses = [account.email, ...aliases.map((a) => a.email), ...extra];
+  await invoke<void>("identity_set_addresses", { accountId: account.id, addresses });
+}
+
+/**
+ * Which of `addresses` are the user's: of `accountId` if given, otherwise
+ * of any account.
+ */
+export async function matchMyAddresses(
+  addresses: string[],
+  accountId?: string,
+): Promise<Set<string>> {
+  if (addresses.length === 0) return new Set();
+  const matches = await invoke<boolean[]>("identity_match", {
+    accountId: accountId ?? null,
+    addresses,
+  });
+  return new Set(addresses.filter((_, i) => matches[i]));
+}
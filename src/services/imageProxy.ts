@@ -0,0 +1,24 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * Fetch remote images through the Rust backend and return a map of
+ * original URL → data URL. Images that couldn't be fetched are missing
+ * from the map. `proxy` is an HTTP(S) proxy URL or a shared image proxy
+ * URL containing `{url}`.
+ */
+export async function fetchProxiedImages(
+  urls: string[],
+  proxy?: string | null,
+): Promise<Map<string, string>> {
+  if (urls.length === 0) return new Map();
+  const result = await invoke<Record<string, string>>("image_proxy_fetch", {
+    urls,
+    proxy: proxy || null,
+  });
+  return new Map(Object.entries(result));
+}
+
+/** Delete all images cached by the image proxy. */
+export function clearImageProxyCache(): Promise<void> {
+  return invoke("image_proxy_clear_cache");
+}
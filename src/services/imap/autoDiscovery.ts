@@ -42,6 +42,19 @@ const wellKnownProviders: WellKnownProvider[] = [
     authMethods: ["oauth2"],
     oauthProviderId: "microsoft",
   },
+  {
+    domains: ["gmail.com", "googlemail.com"],
+    settings: {
+      imapHost: "imap.gmail.com",
+      imapPort: 993,
+      imapSecurity: "ssl",
+      smtpHost: "smtp.gmail.com",
+      smtpPort: 465,
+      smtpSecurity: "ssl",
+    },
+    authMethods: ["oauth2", "password"],
+    oauthProviderId: "google",
+  },
   {
     domains: ["yahoo.com", "yahoo.co.uk", "yahoo.co.jp", "ymail.com"],
     settings: {
@@ -0,0 +1,77 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+
+vi.mock("../db/labels", () => ({
+  deleteLabelsForFolder: vi.fn(),
+}));
+vi.mock("../db/folderSyncState", () => ({
+  deleteFolderSyncState: vi.fn(),
+}));
+vi.mock("./folderMapper", () => ({
+  syncFoldersToLabels: vi.fn(),
+  getSyncableFolders: vi.fn((folders: unknown[]) => folders),
+}));
+
+import { applyFolderChanges } from "./folderChanges";
+import { deleteLabelsForFolder } from "../db/labels";
+import { deleteFolderSyncState } from "../db/folderSyncState";
+import { syncFoldersToLabels } from "./folderMapper";
+import type { ImapFolder } from "./tauriCommands";
+import { useAccountStore } from "@/stores/accountStore";
+import { useLabelStore } from "@/stores/labelStore";
+
+function folder(path: string): ImapFolder {
+  return {
+    path,
+    raw_path: path,
+    name: path.split("/").pop()!,
+    delimiter: "/",
+    special_use: null,
+    exists: 0,
+    unseen: 0,
+    attributes: [],
+    no_select: false,
+    non_existent: false,
+  };
+}
+
+describe("applyFolderChanges", () => {
+  const loadLabels = vi.fn(() => Promise.resolve());
+
+  beforeEach(() => {
+    vi.clearAllMocks();
+    useAccountStore.setState({ activeAccountId: "acc-1" });
+    useLabelStore.setState({ loadLabels });
+  });
+
+  it("drops removed and renamed-from folders and adds the new ones", async () => {
+    await applyFolderChanges({
+      account_id: "acc-1",
+      added: [folder("Travel")],
+      removed: ["Old"],
+      renamed: [{ from: "Work/Projects", to: folder("Work/Clients") }],
+    });
+
+    expect(deleteLabelsForFolder).toHaveBeenCalledWith("acc-1", "Old");
+    expect(deleteLabelsForFolder).toHaveBeenCalledWith("acc-1", "Work/Projects");
+    expect(deleteFolderSyncState).toHaveBeenCalledWith("acc-1", "Old");
+    expect(deleteFolderSyncState).toHaveBeenCalledWith("acc-1", "Work/Projects");
+    expect(syncFoldersToLabels).toHaveBeenCalledWith("acc-1", [
+      folder("Travel"),
+      folder("Work/Clients"),
+    ]);
+    expect(loadLabels).toHaveBeenCalledWith("acc-1");
+  });
+
+  it("doesn't reload the sidebar for another account", async () => {
+    await applyFolderChanges({
+      account_id: "acc-2",
+      added: [],
+      removed: ["Old"],
+      renamed: [],
+    });
+
+    expect(deleteLabelsForFolder).toHaveBeenCalledWith("acc-2", "Old");
+    expect(syncFoldersToLabels).not.toHaveBeenCalled();
+    expect(loadLabels).not.toHaveBeenCalled();
+  });
+});
@@ -0,0 +1,45 @@
+import { deleteLabelsForFolder } from "../db/labels";
+import { deleteFolderSyncState } from "../db/folderSyncState";
+import { getSyncableFolders, syncFoldersToLabels } from "./folderMapper";
+import type { FoldersChangedEvent } from "./tauriCommands";
+import { useAccountStore } from "@/stores/accountStore";
+import { useLabelStore } from "@/stores/labelStore";
+
+/**
+ * Folders created, renamed or deleted on the server, e.g. from another
+ * client. The backend compares each folder LIST with the previous one and
+ * sends `folders-changed` with the difference.
+ *
+ * A renamed folder is synced again under its new path like a new one; its
+ * old label and sync state are dropped like a deleted folder's.
+ */
+export async function applyFolderChanges(change: FoldersChangedEvent): Promise<void> {
+  const accountId = change.account_id;
+  const gone = [...change.removed, ...change.renamed.map((r) => r.from)];
+  for (const path of gone) {
+    await deleteLabelsForFolder(accountId, path);
+    await deleteFolderSyncState(accountId, path);
+  }
+
+  const added = [...change.added, ...change.renamed.map((r) => r.to)];
+  if (added.length > 0) {
+    await syncFoldersToLabels(accountId, getSyncableFolders(added));
+  }
+
+  if (useAccountStore.getState().activeAccountId === accountId) {
+    await useLabelStore.getState().loadLabels(accountId);
+  }
+}
+
+/**
+ * Listen for folder list changes and keep labels in step. Returns an
+ * unlisten function.
+ */
+export async function initFolderChanges(): Promise<() => void> {
+  const { listen } = await import("@tauri-apps/api/event");
+  return listen<FoldersChangedEvent>("folders-changed", (event) => {
+    applyFolderChanges(event.payload).catch((err) => {
+      console.error("[folderChanges] Failed to apply folder changes:", err);
+    });
+  });
+}
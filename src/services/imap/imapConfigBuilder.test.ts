@@ -1,5 +1,38 @@
-import { describe, it, expect } from "vitest";
-import { buildImapConfig, buildSmtpConfig } from "./imapConfigBuilder";
+import { describe, it, expect, vi } from "vitest";
+
+vi.mock("./tauriCommands", () => ({
+  registerAccount: vi.fn(() => Promise.resolve()),
+  // No keychain unless a test says otherwise
+  credentialsStore: vi.fn(() => Promise.reject(new Error("Keychain unavailable"))),
+  oauthStoreGrant: vi.fn(() => Promise.reject(new Error("Keychain unavailable"))),
+}));
+vi.mock("@tauri-apps/api/core", () => ({
+  // The backend's Microsoft profile
+  invoke: vi.fn((_cmd: string, args: { tenant: string | null }) =>
+    Promise.resolve({
+      id: "microsoft",
+      name: "Microsoft",
+      auth_url: `https://login.microsoftonline.com/${args.tenant ?? "common"}/oauth2/v2.0/authorize`,
+      token_url: `https://login.microsoftonline.com/${args.tenant ?? "common"}/oauth2/v2.0/token`,
+      scopes: ["https://outlook.office.com/IMAP.AccessAsUser.All", "offline_access"],
+      use_pkce: true,
+    }),
+  ),
+}));
+vi.mock("../db/accounts", () => ({
+  clearImapPassword: vi.fn(() => Promise.resolve()),
+  clearOAuthTokens: vi.fn(() => Promise.resolve()),
+}));
+
+import {
+  accountDetails,
+  buildImapConfig,
+  buildSmtpConfig,
+  moveOAuthGrantToKeychain,
+  registerAccountCredentials,
+} from "./imapConfigBuilder";
+import { credentialsStore, oauthStoreGrant, registerAccount } from "./tauriCommands";
+import { clearImapPassword, clearOAuthTokens } from "../db/accounts";
 import { createMockDbAccount } from "@/test/mocks";
 
 describe("buildImapConfig", () => {
@@ -163,3 +196,199 @@ describe("accept_invalid_certs", () => {
     expect(smtpConfig.accept_invalid_certs).toBe(true);
   });
 });
+
+describe("delegated mailbox access", () => {
+  it("omits delegation fields when no mailbox is configured", () => {
+    const config = buildImapConfig(createMockDbAccount());
+    expect(config.authorize_as).toBeUndefined();
+    expect(config.delegation).toBeUndefined();
+  });
+
+  it("passes the delegated mailbox with sasl as the default method", () => {
+    const account = createMockDbAccount({ imap_authorize_as: "boss@example.com" });
+    const config = buildImapConfig(account);
+    expect(config.authorize_as).toBe("boss@example.com");
+    expect(config.delegation).toBe("sasl");
+  });
+
+  it("passes the exchange delegation method", () => {
+    const account = createMockDbAccount({
+      imap_authorize_as: "shared@example.com",
+      imap_delegation: "exchange",
+    });
+    expect(buildImapConfig(account).delegation).toBe("exchange");
+  });
+});
+
+describe("registerAccountCredentials", () => {
+  it("registers both configs and returns the account id", async () => {
+    const account = createMockDbAccount();
+    const id = await registerAccountCredentials(account);
+
+    expect(id).toBe(account.id);
+    expect(registerAccount).toHaveBeenCalledWith(
+      account.id,
+      buildImapConfig(account),
+      buildSmtpConfig(account),
+      false,
+      { email: account.email, displayName: account.display_name, oauth: null },
+    );
+  });
+
+  it("registers no SMTP config for receive-only accounts", async () => {
+    const account = createMockDbAccount({ smtp_host: null });
+    await registerAccountCredentials(account);
+
+    expect(registerAccount).toHaveBeenLastCalledWith(
+      account.id,
+      buildImapConfig(account),
+      null,
+      false,
+      accountDetails(account),
+    );
+  });
+
+  it("marks accounts without a stored password", async () => {
+    const account = createMockDbAccount({ imap_password: null, ask_password: 1 });
+    await registerAccountCredentials(account);
+
+    expect(registerAccount).toHaveBeenLastCalledWith(
+      account.id,
+      buildImapConfig(account),
+      buildSmtpConfig(account),
+      true,
+      accountDetails(account),
+    );
+  });
+
+  it("moves a stored password into the keychain and registers without it", async () => {
+    vi.mocked(credentialsStore).mockResolvedValueOnce(undefined);
+    const account = createMockDbAccount();
+    await registerAccountCredentials(account);
+
+    expect(credentialsStore).toHaveBeenLastCalledWith(account.id, "secret123");
+    expect(clearImapPassword).toHaveBeenCalledWith(account.id);
+    const [, imap, smtp] = vi.mocked(registerAccount).mock.lastCall!;
+    expect(imap?.password).toBe("");
+    expect(smtp?.password).toBe("");
+  });
+
+  it("leaves OAuth accounts alone", async () => {
+    vi.mocked(credentialsStore).mockClear();
+    const account = createMockDbAccount({ auth_method: "oauth2" });
+    await registerAccountCredentials(account, "token");
+
+    expect(credentialsStore).not.toHaveBeenCalled();
+  });
+
+  it("registers OAuth accounts without a token once the backend holds the grant", async () => {
+    vi.mocked(oauthStoreGrant).mockResolvedValueOnce(undefined);
+    const account = createMockDbAccount({
+      auth_method: "oauth2",
+      oauth_provider: "microsoft",
+      oauth_client_id: "client-1",
+      imap_password: null,
+      access_token: "access",
+      refresh_token: "refresh",
+      token_expires_at: 1_700_000_000,
+    });
+    await registerAccountCredentials(account, "access");
+
+    expect(clearOAuthTokens).toHaveBeenCalledWith(account.id);
+    const [, imap, smtp] = vi.mocked(registerAccount).mock.lastCall!;
+    expect(imap?.password).toBe("");
+    expect(smtp?.password).toBe("");
+  });
+
+  it("keeps registering OAuth tokens without a keychain", async () => {
+    const account = createMockDbAccount({
+      auth_method: "oauth2",
+      oauth_provider: "microsoft",
+      oauth_client_id: "client-1",
+      refresh_token: "refresh",
+    });
+    await registerAccountCredentials(account, "access");
+
+    const [, imap] = vi.mocked(registerAccount).mock.lastCall!;
+    expect(imap?.password).toBe("access");
+  });
+
+  it("keeps the OAuth provider with the definition", () => {
+    const account = createMockDbAccount({
+      auth_method: "oauth2",
+      oauth_provider: "microsoft",
+      oauth_client_id: "client-1",
+    });
+
+    expect(accountDetails(account).oauth).toEqual({ provider: "microsoft", client_id: "client-1" });
+  });
+});
+
+describe("moveOAuthGrantToKeychain", () => {
+  it("hands the provider's token endpoint and the refresh token to the backend", async () => {
+    vi.mocked(oauthStoreGrant).mockResolvedValueOnce(undefined);
+    const account = createMockDbAccount({
+      auth_method: "oauth2",
+      oauth_provider: "yahoo",
+      oauth_client_id: "client-1",
+      oauth_client_secret: "shh",
+      access_token: "access",
+      refresh_token: "refresh",
+      token_expires_at: 1_700_000_000,
+    });
+
+    const moved = await moveOAuthGrantToKeychain(account);
+
+    expect(oauthStoreGrant).toHaveBeenLastCalledWith(
+      account.id,
+      {
+        token_url: "https://api.login.yahoo.com/oauth2/get_token",
+        client_id: "client-1",
+        client_secret: "shh",
+        scope: null,
+        refresh_token: "refresh",
+      },
+      "access",
+      1_700_000_000,
+    );
+    expect(moved).toMatchObject({ access_token: null, refresh_token: null, token_expires_at: null });
+  });
+
+  it("uses the token endpoint of the account's Microsoft tenant", async () => {
+    vi.mocked(oauthStoreGrant).mockResolvedValueOnce(undefined);
+    const account = createMockDbAccount({
+      auth_method: "oauth2",
+      oauth_provider: "microsoft",
+      oauth_client_id: "client-1",
+      oauth_tenant: "contoso.onmicrosoft.com",
+      refresh_token: "refresh",
+    });
+
+    await moveOAuthGrantToKeychain(account);
+
+    expect(oauthStoreGrant).toHaveBeenLastCalledWith(
+      account.id,
+      expect.objectContaining({
+        token_url: "https://login.microsoftonline.com/contoso.onmicrosoft.com/oauth2/v2.0/token",
+        scope: "https://outlook.office.com/IMAP.AccessAsUser.All offline_access",
+      }),
+      null,
+      null,
+    );
+  });
+
+  it("leaves accounts without a refresh token or known provider alone", async () => {
+    vi.mocked(oauthStoreGrant).mockClear();
+    const password = createMockDbAccount();
+    const unknown = createMockDbAccount({
+      auth_method: "oauth2",
+      oauth_provider: "custom",
+      oauth_client_id: "client-1",
+      refresh_token: "refresh",
+    });
+
+    expect(await moveOAuthGrantToKeychain(password)).toBe(password);
+    expect(await moveOAuthGrantToKeychain(unknown)).toBe(unknown);
+    expect(oauthStoreGrant).not.toHaveBeenCalled();
+  });
+});
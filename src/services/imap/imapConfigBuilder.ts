@@ -1,11 +1,14 @@
-import type { DbAccount } from "../db/accounts";
-import type { ImapConfig, SmtpConfig } from "./tauriCommands";
+import { clearImapPassword, clearOAuthTokens, type DbAccount } from "../db/accounts";
+import { resolveOAuthProvider } from "../oauth/providers";
+import { hasFrontendTokens } from "../oauth/oauthTokenManager";
+import type { AccountDetails, ImapConfig, ImapDelegation, SmtpConfig } from "./tauriCommands";
+import { credentialsStore, oauthStoreGrant, registerAccount } from "./tauriCommands";
 
 /**
  * Map the DB-stored security value to the config type.
  * DB stores 'ssl' but the config type uses 'tls'.
  */
-function mapSecurity(security: string | null): "tls" | "starttls" | "none" {
+export function mapSecurity(security: string | null): "tls" | "starttls" | "none" {
   if (!security) return "tls";
   const lower = security.toLowerCase();
   if (lower === "ssl" || lower === "tls") return "tls";
@@ -22,6 +25,13 @@ function mapAuthMethod(method: string | null): "password" | "oauth2" {
   return "password";
 }
 
+/**
+ * Map the DB imap_delegation value to config type.
+ */
+function mapDelegation(delegation: string | null): ImapDelegation {
+  return delegation === "exchange" ? "exchange" : "sasl";
+}
+
 /**
  * Build an ImapConfig from a DbAccount's IMAP fields.
  * Assumes the account's imap_password has already been decrypted.
@@ -51,6 +61,10 @@ export function buildImapConfig(
     password,
     auth_method: authMethod,
     accept_invalid_certs: !!account.accept_invalid_certs,
+    authorize_as: account.imap_authorize_as || undefined,
+    delegation: account.imap_authorize_as
+      ? mapDelegation(account.imap_delegation)
+      : undefined,
   };
 }
 
@@ -85,3 +99,97 @@ export function buildSmtpConfig(
     accept_invalid_certs: !!account.accept_invalid_certs,
   };
 }
+
+/** The details the backend keeps with an account's definition. */
+export function accountDetails(account: DbAccount): AccountDetails {
+  return {
+    email: account.email,
+    displayName: account.display_name,
+    oauth: account.oauth_provider
+      ? { provider: account.oauth_provider, client_id: account.oauth_client_id }
+      : null,
+    transport: account.transport === "gmail_api" ? "gmail_api" : "imap",
+  };
+}
+
+/**
+ * Move a password account's stored password into the OS keychain, so it's
+ * registered with an empty password and the backend reads it from there.
+ * Returns the account as stored afterwards; unchanged when there's no
+ * keychain, in which case the encrypted password stays in the database.
+ */
+export async function moveImapPasswordToKeychain(account: DbAccount): Promise<DbAccount> {
+  if (account.auth_method === "oauth2" || account.ask_password || !account.imap_password) {
+    return account;
+  }
+  try {
+    await credentialsStore(account.id, account.imap_password);
+  } catch (err) {
+    console.warn("OS keychain unavailable, keeping the password in the database:", err);
+    return account;
+  }
+  await clearImapPassword(account.id);
+  return { ...account, imap_password: null };
+}
+
+/**
+ * Hand an OAuth account's refresh token to the backend, which keeps it in
+ * the OS keychain and refreshes access tokens itself, so the account is
+ * registered without a token. Returns the account as stored afterwards;
+ * unchanged when there's no keychain, in which case the frontend keeps
+ * refreshing tokens with `ensureFreshToken()`.
+ */
+export async function moveOAuthGrantToKeychain(account: DbAccount): Promise<DbAccount> {
+  if (account.auth_method !== "oauth2" || !account.refresh_token || !account.oauth_client_id) {
+    return account;
+  }
+  const provider = account.oauth_provider
+    ? await resolveOAuthProvider(account.oauth_provider, account.oauth_tenant)
+    : null;
+  if (!provider) return account;
+  try {
+    await oauthStoreGrant(
+      account.id,
+      {
+        token_url: provider.tokenUrl,
+        client_id: account.oauth_client_id,
+        client_secret: account.oauth_client_secret || null,
+        scope: provider.id === "microsoft" ? provider.scopes.join(" ") : null,
+        refresh_token: account.refresh_token,
+      },
+      account.access_token,
+      account.token_expires_at,
+    );
+  } catch (err) {
+    console.warn("OS keychain unavailable, keeping OAuth tokens in the database:", err);
+    return account;
+  }
+  await clearOAuthTokens(account.id);
+  return { ...account, access_token: null, refresh_token: null, token_expires_at: null };
+}
+
+/**
+ * Register an account's IMAP/SMTP configs with the backend so commands can
+ * refer to it by id. Returns the account id for chaining into a command.
+ *
+ * OAuth accounts whose grant the backend holds are registered without a
+ * token. Others need registering again after refreshing their token;
+ * registering replaces the previous configs.
+ */
+export async function registerAccountCredentials(
+  account: DbAccount,
+  accessToken?: string,
+): Promise<string> {
+  account = await moveImapPasswordToKeychain(account);
+  account = await moveOAuthGrantToKeychain(account);
+  // The backend supplies the tokens of accounts whose grant it holds
+  if (!hasFrontendTokens(account)) accessToken = undefined;
+  await registerAccount(
+    account.id,
+    account.imap_host ? buildImapConfig(account, accessToken) : null,
+    account.smtp_host ? buildSmtpConfig(account, accessToken) : null,
+    !!account.ask_password,
+    accountDetails(account),
+  );
+  return account.id;
+}
@@ -0,0 +1,69 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+
+vi.mock("../db/accounts", () => ({
+  getAccount: vi.fn((id: string) => Promise.resolve({ id, provider: "imap" })),
+}));
+vi.mock("../db/folderSyncState", () => ({
+  getAllFolderSyncStates: vi.fn(() =>
+    Promise.resolve([{ folder_path: "INBOX" }, { folder_path: "Sent" }]),
+  ),
+}));
+vi.mock("./imapConfigBuilder", () => ({
+  registerAccountCredentials: vi.fn((account: { id: string }) => Promise.resolve(account.id)),
+}));
+vi.mock("./tauriCommands", () => ({
+  imapStartNotify: vi.fn(),
+  imapStopNotify: vi.fn(),
+  imapStartIdle: vi.fn(),
+  imapStopIdle: vi.fn(),
+}));
+vi.mock("./pollScheduler", () => ({
+  markFolderDue: vi.fn(),
+}));
+
+import { ensureImapPush, isImapPushActive, stopImapPush } from "./imapPush";
+import { imapStartIdle, imapStartNotify } from "./tauriCommands";
+
+describe("ensureImapPush", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+  });
+
+  it("watches every synced folder with NOTIFY", async () => {
+    vi.mocked(imapStartNotify).mockResolvedValue(true);
+
+    await ensureImapPush("notify");
+
+    expect(imapStartNotify).toHaveBeenCalledWith("notify", ["INBOX", "Sent"]);
+    expect(imapStartIdle).not.toHaveBeenCalled();
+    expect(isImapPushActive("notify")).toBe(true);
+  });
+
+  it("falls back to IDLE on the inbox without NOTIFY", async () => {
+    vi.mocked(imapStartNotify).mockResolvedValue(false);
+    vi.mocked(imapStartIdle).mockResolvedValue(true);
+
+    await ensureImapPush("idle");
+    expect(imapStartIdle).toHaveBeenCalledWith("idle", "INBOX");
+    expect(isImapPushActive("idle")).toBe(true);
+
+    // Already watched
+    await ensureImapPush("idle");
+    expect(imapStartIdle).toHaveBeenCalledTimes(1);
+
+    await stopImapPush("idle");
+    expect(isImapPushActive("idle")).toBe(false);
+  });
+
+  it("doesn't ask again when neither is supported", async () => {
+    vi.mocked(imapStartNotify).mockResolvedValue(false);
+    vi.mocked(imapStartIdle).mockResolvedValue(false);
+
+    await ensureImapPush("polling");
+    await ensureImapPush("polling");
+
+    expect(imapStartNotify).toHaveBeenCalledTimes(1);
+    expect(imapStartIdle).toHaveBeenCalledTimes(1);
+    expect(isImapPushActive("polling")).toBe(false);
+  });
+});
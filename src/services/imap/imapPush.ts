@@ -0,0 +1,145 @@
+import { getAccount } from "../db/accounts";
+import { getAllFolderSyncStates } from "../db/folderSyncState";
+import { registerAccountCredentials } from "./imapConfigBuilder";
+import {
+  imapStartIdle,
+  imapStartNotify,
+  imapStopIdle,
+  imapStopNotify,
+  type ImapExpungeEvent,
+  type ImapFlagsChangedEvent,
+  type ImapNewMailEvent,
+  type ImapNotifyEvent,
+} from "./tauriCommands";
+import { markFolderDue } from "./pollScheduler";
+
+/**
+ * Push for IMAP accounts.
+ *
+ * Where the server supports NOTIFY (RFC 5465), one backend connection per
+ * account watches every synced folder. Otherwise the inbox is watched with
+ * IDLE (RFC 2177), which needs a connection per folder. Polling keeps
+ * running as a safety net; a push only makes the affected folder due
+ * immediately.
+ */
+
+/** Accounts with a live NOTIFY connection. */
+const activeAccounts = new Set<string>();
+/** Accounts whose server doesn't advertise NOTIFY (checked once per session). */
+const unsupportedAccounts = new Set<string>();
+/** Accounts without NOTIFY whose inbox has a live IDLE connection. */
+const idleAccounts = new Set<string>();
+/** Accounts whose server supports neither NOTIFY nor IDLE. */
+const idleUnsupportedAccounts = new Set<string>();
+/** Accounts currently being registered, to avoid duplicate connections. */
+const startingAccounts = new Set<string>();
+
+/**
+ * Start NOTIFY for an account if the server supports it and it isn't
+ * running yet, watching the folders that have been synced so far. Falls
+ * back to IDLE on the inbox.
+ */
+export async function ensureImapPush(accountId: string): Promise<void> {
+  if (
+    activeAccounts.has(accountId) ||
+    idleAccounts.has(accountId) ||
+    idleUnsupportedAccounts.has(accountId) ||
+    startingAccounts.has(accountId)
+  ) {
+    return;
+  }
+
+  startingAccounts.add(accountId);
+  try {
+    const account = await getAccount(accountId);
+    if (!account || account.provider !== "imap") return;
+
+    const folders = (await getAllFolderSyncStates(accountId)).map((s) => s.folder_path);
+    if (folders.length === 0) return;
+
+    await registerAccountCredentials(account);
+    if (!unsupportedAccounts.has(accountId)) {
+      if (await imapStartNotify(accountId, folders)) {
+        activeAccounts.add(accountId);
+        console.log(`[imapPush] NOTIFY active for ${accountId} (${folders.length} folders)`);
+        return;
+      }
+      unsupportedAccounts.add(accountId);
+    }
+
+    // Most new mail lands in the inbox; other folders are left to polling
+    const inbox = folders.find((f) => f.toUpperCase() === "INBOX");
+    if (!inbox) return;
+    if (await imapStartIdle(accountId, inbox)) {
+      idleAccounts.add(accountId);
+      console.log(`[imapPush] IDLE active on ${inbox} for ${accountId}`);
+    } else {
+      idleUnsupportedAccounts.add(accountId);
+    }
+  } finally {
+    startingAccounts.delete(accountId);
+  }
+}
+
+/**
+ * Stop push for an account (e.g. when it is removed).
+ */
+export async function stopImapPush(accountId: string): Promise<void> {
+  activeAccounts.delete(accountId);
+  unsupportedAccounts.delete(accountId);
+  idleAccounts.delete(accountId);
+  idleUnsupportedAccounts.delete(accountId);
+  await Promise.all([imapStopNotify(accountId), imapStopIdle(accountId)]);
+}
+
+export function isImapPushActive(accountId: string): boolean {
+  return activeAccounts.has(accountId) || idleAccounts.has(accountId);
+}
+
+/**
+ * Listen for pushed changes. `onChange` receives the account and the folder
+ * that changed, or null when the server dropped events and every folder
+ * needs checking. Returns an unlisten function.
+ */
+export async function initImapPush(
+  onChange: (accountId: string, folder: string | null) => void,
+): Promise<() => void> {
+  const { listen } = await import("@tauri-apps/api/event");
+
+  const unlistenNotify = await listen<ImapNotifyEvent>("imap-notify", (event) => {
+    const { account_id, folder } = event.payload;
+    if (folder) markFolderDue(account_id, folder);
+    onChange(account_id, folder);
+  });
+
+  // The connection dropped — the next sync of this account restarts it
+  const unlistenStopped = await listen<{ account_id: string; error: string | null }>(
+    "imap-notify-stopped",
+    (event) => {
+      activeAccounts.delete(event.payload.account_id);
+    },
+  );
+
+  const onIdleChange = (event: { payload: { account_id: string; folder: string } }) => {
+    const { account_id, folder } = event.payload;
+    markFolderDue(account_id, folder);
+    onChange(account_id, folder);
+  };
+  const unlistenIdle = await Promise.all([
+    listen<ImapNewMailEvent>("imap-new-mail", onIdleChange),
+    listen<ImapExpungeEvent>("imap-expunge", onIdleChange),
+    listen<ImapFlagsChangedEvent>("imap-flags-changed", onIdleChange),
+    listen<{ account_id: string; folder: string; error: string | null }>(
+      "imap-idle-stopped",
+      (event) => {
+        idleAccounts.delete(event.payload.account_id);
+      },
+    ),
+  ]);
+
+  return () => {
+    unlistenNotify();
+    unlistenStopped();
+    unlistenIdle.forEach((unlisten) => unlisten());
+  };
+}
@@ -11,14 +11,7 @@ vi.mock("./tauriCommands", () => ({
   imapDeltaCheck: vi.fn(),
 }));
 vi.mock("./imapConfigBuilder", () => ({
-  buildImapConfig: vi.fn(() => ({
-    host: "imap.example.com",
-    port: 993,
-    security: "ssl",
-    username: "user@example.com",
-    password: "secret",
-    auth_method: "password",
-  })),
+  registerAccountCredentials: vi.fn((account: { id: string }) => Promise.resolve(account.id)),
 }));
 vi.mock("./folderMapper", () => ({
   mapFolderToLabel: vi.fn((folder: { path: string }) => ({
@@ -44,6 +37,7 @@ vi.mock("../db/messages", () => ({
 vi.mock("../db/threads", () => ({
   upsertThread: vi.fn(),
   setThreadLabels: vi.fn(),
+  markFolderThreadsRead: vi.fn(() => Promise.resolve(0)),
 }));
 vi.mock("../db/attachments", () => ({
   upsertAttachment: vi.fn(),
@@ -59,18 +53,30 @@ vi.mock("../db/folderSyncState", () => ({
 vi.mock("../db/pendingOperations", () => ({
   getPendingOpsForResource: vi.fn(() => []),
 }));
+// The backend runs the same algorithm; an empty cache leaves its IDs as is
+vi.mock("../threading/threadBuilder", async (importOriginal) => {
+  const actual = await importOriginal<typeof import("../threading/threadBuilder")>();
+  return {
+    ...actual,
+    threadMessages: vi.fn((_accountId: string, messages: Parameters<typeof actual.buildThreads>[0]) =>
+      Promise.resolve(actual.buildThreads(messages)),
+    ),
+  };
+});
 
-import { imapMessageToParsedMessage, imapInitialSync } from "./imapSync";
+import { imapMessageToParsedMessage, imapInitialSync, imapDeltaSync } from "./imapSync";
 import {
   createMockImapMessage,
   createMockImapAccount,
   createMockImapFolder,
   createMockImapFolderSyncResult,
+  createMockImapFolderStatus,
 } from "@/test/mocks";
-import { imapListFolders, imapSyncFolder } from "./tauriCommands";
+import { imapListFolders, imapSyncFolder, imapDeltaCheck } from "./tauriCommands";
 import { getAccount } from "../db/accounts";
 import { upsertMessage, updateMessageThreadIds } from "../db/messages";
-import { upsertThread } from "../db/threads";
+import { upsertThread, markFolderThreadsRead } from "../db/threads";
+import { getAllFolderSyncStates } from "../db/folderSyncState";
 import { upsertAttachment } from "../db/attachments";
 
 describe("imapMessageToParsedMessage", () => {
@@ -225,6 +231,39 @@ describe("imapMessageToParsedMessage", () => {
     expect(parsed.authResults).toBe('{"spf":"pass","dkim":"pass"}');
   });
 
+  it("converts attached emails recursively", () => {
+    const msg = createMockImapMessage({
+      attached_messages: [
+        {
+          part_id: "2",
+          message: createMockImapMessage({
+            subject: "Report",
+            from_address: "carol@example.com",
+            attachments: [
+              {
+                part_id: "2.2",
+                filename: "q3.csv",
+                mime_type: "text/csv",
+                size: 3,
+                content_id: null,
+                is_inline: false,
+              },
+            ],
+          }),
+        },
+      ],
+    });
+    const { parsed } = imapMessageToParsedMessage(msg, "acc-1", "INBOX");
+
+    expect(parsed.attachedMessages).toHaveLength(1);
+    const attached = parsed.attachedMessages![0]!;
+    expect(attached.partId).toBe("2");
+    expect(attached.message.id).toBe(parsed.id);
+    expect(attached.message.subject).toBe("Report");
+    expect(attached.message.fromAddress).toBe("carol@example.com");
+    expect(attached.message.attachments[0]!.gmailAttachmentId).toBe("2.2");
+  });
+
   it("handles date=0 (unparseable Date header) without crashing", () => {
     const msg = createMockImapMessage({ date: 0 });
     const { parsed, threadable } = imapMessageToParsedMessage(msg, "acc-1", "INBOX");
@@ -424,7 +463,7 @@ describe("imapInitialSync", () => {
     // Should use imapSyncFolder (single connection) instead of separate search + fetch
     expect(mockImapSyncFolder).toHaveBeenCalledTimes(1);
     expect(mockImapSyncFolder).toHaveBeenCalledWith(
-      expect.objectContaining({ host: "imap.example.com" }),
+      "acc-1",
       "INBOX",
       50, // BATCH_SIZE
     );
@@ -488,3 +527,47 @@ describe("imapInitialSync", () => {
     expect(mockImapSyncFolder).toHaveBeenCalledTimes(6);
   });
 });
+
+describe("imapDeltaSync", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    vi.mocked(getAccount).mockResolvedValue(createMockImapAccount({ id: "acc-1" }));
+    vi.mocked(imapListFolders).mockResolvedValue([createMockImapFolder({ path: "INBOX" })]);
+    vi.mocked(getAllFolderSyncStates).mockResolvedValue([
+      {
+        account_id: "acc-1",
+        folder_path: "INBOX",
+        uidvalidity: 1,
+        last_uid: 99,
+        modseq: null,
+        last_sync_at: 0,
+      },
+    ]);
+  });
+
+  function deltaResult(unseen: number) {
+    return {
+      folder: "INBOX",
+      uidvalidity: 1,
+      new_uids: [],
+      uidvalidity_changed: false,
+      folder_status: createMockImapFolderStatus({ uidnext: 100, exists: 5, unseen }),
+    };
+  }
+
+  it("marks the folder read when the server reports nothing unseen", async () => {
+    vi.mocked(imapDeltaCheck).mockResolvedValue([deltaResult(0)]);
+
+    await imapDeltaSync("acc-1");
+
+    expect(markFolderThreadsRead).toHaveBeenCalledWith("acc-1", "INBOX");
+  });
+
+  it("leaves read state alone while messages are unseen", async () => {
+    vi.mocked(imapDeltaCheck).mockResolvedValue([deltaResult(3)]);
+
+    await imapDeltaSync("acc-1");
+
+    expect(markFolderThreadsRead).not.toHaveBeenCalled();
+  });
+});
@@ -1,4 +1,4 @@
-import type { ImapConfig, ImapMessage, DeltaCheckRequest, DeltaCheckResult } from "./tauriCommands";
+import type { ImapMessage, DeltaCheckRequest, DeltaCheckResult } from "./tauriCommands";
 import {
   imapListFolders,
   imapGetFolderStatus,
@@ -7,7 +7,7 @@ import {
   imapSyncFolder,
   imapDeltaCheck,
 } from "./tauriCommands";
-import { buildImapConfig } from "./imapConfigBuilder";
+import { registerAccountCredentials } from "./imapConfigBuilder";
 import {
   mapFolderToLabel,
   getLabelsForMessage,
@@ -17,7 +17,7 @@ import {
 import type { ParsedMessage, ParsedAttachment } from "../gmail/messageParser";
 import type { SyncResult } from "../email/types";
 import { upsertMessage, updateMessageThreadIds } from "../db/messages";
-import { upsertThread, setThreadLabels } from "../db/threads";
+import { upsertThread, setThreadLabels, markFolderThreadsRead } from "../db/threads";
 import { upsertAttachment } from "../db/attachments";
 import { getAccount, updateAccountSyncState } from "../db/accounts";
 import {
@@ -25,11 +25,13 @@ import {
   getAllFolderSyncStates,
 } from "../db/folderSyncState";
 import {
-  buildThreads,
+  threadMessages,
   type ThreadableMessage,
   type ThreadGroup,
 } from "../threading/threadBuilder";
 import { getPendingOpsForResource } from "../db/pendingOperations";
+import { isFolderDue, recordFolderPoll, pruneFolderSchedule } from "./pollScheduler";
+import { getThreadSubject } from "@/utils/subject";
 
 // ---------------------------------------------------------------------------
 // Constants
@@ -139,6 +141,20 @@ export function imapMessageToParsedMessage(
     listUnsubscribe: msg.list_unsubscribe,
     listUnsubscribePost: msg.list_unsubscribe_post,
     authResults: msg.auth_results,
+    parseFailed: msg.parse_failed,
+    deliveredTo: msg.delivered_to,
+    listId: msg.list_id,
+    listPost: msg.list_post,
+    listArchive: msg.list_archive,
+    isFromMe: msg.is_from_me,
+    mailFollowupTo: msg.mail_followup_to,
+    keywords: msg.keywords,
+    // Attached emails share the outer UID, so they map to the same local id
+    // and their attachment part ids can be fetched through it
+    attachedMessages: msg.attached_messages.map((attached) => ({
+      partId: attached.part_id,
+      message: imapMessageToParsedMessage(attached.message, accountId, folderLabelId).parsed,
+    })),
   };
 
   const threadable: ThreadableMessage = {
@@ -160,7 +176,7 @@ export function imapMessageToParsedMessage(
 /**
  * Store threads and their messages into the local DB.
  */
-async function storeThreadsAndMessages(
+export async function storeThreadsAndMessages(
   accountId: string,
   threadGroups: ThreadGroup[],
   parsedByLocalId: Map<string, ParsedMessage>,
@@ -191,7 +207,6 @@ async function storeThreadsAndMessages(
     // Sort by date ascending
     messages.sort((a, b) => a.date - b.date);
 
-    const firstMessage = messages[0]!;
     const lastMessage = messages[messages.length - 1]!;
 
     // Collect all label IDs across messages in this thread.
@@ -222,7 +237,7 @@ async function storeThreadsAndMessages(
     await upsertThread({
       id: group.threadId,
       accountId,
-      subject: firstMessage.subject,
+      subject: getThreadSubject(messages.map((m) => m.subject)),
       snippet: lastMessage.snippet,
       lastMessageAt: lastMessage.date,
       messageCount: messages.length,
@@ -265,6 +280,14 @@ async function storeThreadsAndMessages(
         inReplyToHeader: imapMsg?.in_reply_to ?? null,
         imapUid: imapMsg?.uid ?? null,
         imapFolder: imapMsg?.folder ?? null,
+        parseFailed: parsed.parseFailed,
+        deliveredTo: parsed.deliveredTo,
+        listId: parsed.listId,
+        listPost: parsed.listPost,
+        listArchive: parsed.listArchive,
+        isFromMe: parsed.isFromMe,
+        mailFollowupTo: parsed.mailFollowupTo,
+        keywords: parsed.keywords,
       });
 
       await Promise.all(parsed.attachments.map((att) =>
@@ -296,7 +319,7 @@ async function storeThreadsAndMessages(
  * Fetch messages from a folder in batches of BATCH_SIZE.
  */
 async function fetchMessagesInBatches(
-  config: ImapConfig,
+  accountId: string,
   folder: string,
   uids: number[],
   onBatch?: (fetched: number, total: number) => void,
@@ -307,7 +330,7 @@ async function fetchMessagesInBatches(
 
   for (let i = 0; i < uids.length; i += BATCH_SIZE) {
     const batch = uids.slice(i, i + BATCH_SIZE);
-    const result = await imapFetchMessages(config, folder, batch);
+    const result = await imapFetchMessages(accountId, folder, batch);
 
     allMessages.push(...result.messages);
     uidvalidity = result.folder_status.uidvalidity;
@@ -340,11 +363,11 @@ export async function imapInitialSync(
     throw new Error(`Account ${accountId} not found`);
   }
 
-  const config = buildImapConfig(account);
+  await registerAccountCredentials(account);
 
   // Phase 1: List and sync folders
   onProgress?.({ phase: "folders", current: 0, total: 1 });
-  const allFolders = await imapListFolders(config);
+  const allFolders = await imapListFolders(accountId);
   const syncableFolders = getSyncableFolders(allFolders);
   await syncFoldersToLabels(accountId, syncableFolders);
   console.log(`[imapSync] Initial sync for account ${accountId}: ${syncableFolders.length} syncable folders`);
@@ -420,7 +443,7 @@ export async function imapInitialSync(
 
     try {
       // Use single-connection sync: UID SEARCH ALL + batched UID FETCH in one session
-      const syncResult = await imapSyncFolder(config, folder.raw_path, BATCH_SIZE);
+      const syncResult = await imapSyncFolder(accountId, folder.raw_path, BATCH_SIZE);
       const uidsToFetch = syncResult.uids;
 
       // Reset circuit breaker on success
@@ -461,7 +484,7 @@ export async function imapInitialSync(
         await upsertThread({
           id: parsed.id,
           accountId,
-          subject: parsed.subject,
+          subject: getThreadSubject([parsed.subject]),
           snippet: parsed.snippet,
           lastMessageAt: parsed.date,
           messageCount: 1,
@@ -497,6 +520,14 @@ export async function imapInitialSync(
           inReplyToHeader: msg.in_reply_to ?? null,
           imapUid: msg.uid ?? null,
           imapFolder: msg.folder ?? null,
+          parseFailed: parsed.parseFailed,
+          deliveredTo: parsed.deliveredTo,
+          listId: parsed.listId,
+          listPost: parsed.listPost,
+          listArchive: parsed.listArchive,
+          isFromMe: parsed.isFromMe,
+          mailFollowupTo: parsed.mailFollowupTo,
+          keywords: parsed.keywords,
         });
 
         // Store attachments
@@ -587,7 +618,7 @@ export async function imapInitialSync(
   // Phase 3: Thread messages (lightweight — only IDs + headers in memory)
   // ---------------------------------------------------------------------------
   onProgress?.({ phase: "threading", current: 0, total: allThreadable.length });
-  const threadGroups = buildThreads(allThreadable);
+  const threadGroups = await threadMessages(accountId, allThreadable);
   console.log(
     `[imapSync] Threading: ${allThreadable.length} messages → ${threadGroups.length} thread groups`,
   );
@@ -612,7 +643,6 @@ export async function imapInitialSync(
     // Sort by date ascending
     messages.sort((a, b) => a.date - b.date);
 
-    const firstMessage = messages[0]!;
     const lastMessage = messages[messages.length - 1]!;
 
     // Collect all label IDs including cross-folder copies
@@ -636,7 +666,7 @@ export async function imapInitialSync(
     await upsertThread({
       id: group.threadId,
       accountId,
-      subject: firstMessage.subject,
+      subject: getThreadSubject(messages.map((m) => m.subject)),
       snippet: lastMessage.snippet,
       lastMessageAt: lastMessage.date,
       messageCount: messages.length,
@@ -682,22 +712,29 @@ export async function imapInitialSync(
 /**
  * Perform delta sync for an IMAP account.
  * Fetches only new messages since the last sync using stored UID state.
+ *
+ * With `dueOnly`, existing folders are only checked once their adaptive poll
+ * interval (see pollScheduler) has elapsed.
  */
-export async function imapDeltaSync(accountId: string): Promise<SyncResult> {
+export async function imapDeltaSync(
+  accountId: string,
+  options: { dueOnly?: boolean } = {},
+): Promise<SyncResult> {
   const account = await getAccount(accountId);
   if (!account) {
     throw new Error(`Account ${accountId} not found`);
   }
 
-  const config = buildImapConfig(account);
+  await registerAccountCredentials(account);
 
   // Get all folders we've synced before
   const syncStates = await getAllFolderSyncStates(accountId);
 
   // Also check for any new folders
-  const allFolders = await imapListFolders(config);
+  const allFolders = await imapListFolders(accountId);
   const syncableFolders = getSyncableFolders(allFolders);
   await syncFoldersToLabels(accountId, syncableFolders);
+  pruneFolderSchedule(accountId, syncableFolders.map((f) => f.raw_path));
 
   const syncStateMap = new Map(syncStates.map((s) => [s.folder_path, s]));
 
@@ -707,7 +744,12 @@ export async function imapDeltaSync(accountId: string): Promise<SyncResult> {
 
   // Separate folders into new (no saved state) vs existing (have saved state)
   const newFolders = syncableFolders.filter((f) => !syncStateMap.has(f.raw_path));
-  const existingFolders = syncableFolders.filter((f) => syncStateMap.has(f.raw_path));
+  const now = Date.now();
+  const existingFolders = syncableFolders.filter(
+    (f) =>
+      syncStateMap.has(f.raw_path) &&
+      (!options.dueOnly || isFolderDue(accountId, f.raw_path, now)),
+  );
 
   // Handle new folders individually using single-connection sync
   let consecutiveFailures = 0;
@@ -725,8 +767,9 @@ export async function imapDeltaSync(accountId: string): Promise<SyncResult> {
 
     const folderMapping = mapFolderToLabel(folder);
     try {
-      const syncResult = await imapSyncFolder(config, folder.raw_path, BATCH_SIZE);
+      const syncResult = await imapSyncFolder(accountId, folder.raw_path, BATCH_SIZE);
       consecutiveFailures = 0;
+      recordFolderPoll(accountId, folder.raw_path, syncResult.uids.length);
 
       if (syncResult.uids.length === 0) continue;
 
@@ -773,7 +816,7 @@ export async function imapDeltaSync(accountId: string): Promise<SyncResult> {
 
     let deltaResultMap: Map<string, DeltaCheckResult>;
     try {
-      const deltaResults = await imapDeltaCheck(config, deltaRequests);
+      const deltaResults = await imapDeltaCheck(accountId, deltaRequests);
       deltaResultMap = new Map(deltaResults.map((r) => [r.folder, r]));
       console.log(`[imapSync] Batch delta check: ${deltaResults.length}/${existingFolders.length} folders checked`);
     } catch (err) {
@@ -783,7 +826,7 @@ export async function imapDeltaSync(accountId: string): Promise<SyncResult> {
       for (const folder of existingFolders) {
         const savedState = syncStateMap.get(folder.raw_path)!;
         try {
-          const currentStatus = await imapGetFolderStatus(config, folder.raw_path);
+          const currentStatus = await imapGetFolderStatus(accountId, folder.raw_path);
           const uidvalidityChanged =
             savedState.uidvalidity !== null &&
             currentStatus.uidvalidity !== savedState.uidvalidity;
@@ -794,14 +837,16 @@ export async function imapDeltaSync(accountId: string): Promise<SyncResult> {
               uidvalidity: currentStatus.uidvalidity,
               new_uids: [],
               uidvalidity_changed: true,
+              folder_status: currentStatus,
             });
           } else {
-            const newUids = await imapFetchNewUids(config, folder.raw_path, savedState.last_uid);
+            const newUids = await imapFetchNewUids(accountId, folder.raw_path, savedState.last_uid);
             deltaResultMap.set(folder.raw_path, {
               folder: folder.raw_path,
               uidvalidity: currentStatus.uidvalidity,
               new_uids: newUids,
               uidvalidity_changed: false,
+              folder_status: currentStatus,
             });
           }
         } catch (folderErr) {
@@ -815,8 +860,28 @@ export async function imapDeltaSync(accountId: string): Promise<SyncResult> {
       const savedState = syncStateMap.get(folder.raw_path)!;
       const deltaResult = deltaResultMap.get(folder.raw_path);
 
+      // Failed checks count as quiet polls so a broken folder backs off too
+      recordFolderPoll(
+        accountId,
+        folder.raw_path,
+        deltaResult?.uidvalidity_changed ? 1 : (deltaResult?.new_uids.length ?? 0),
+      );
+
       if (!deltaResult) continue;
 
+      // Nothing unseen on the server: messages read in another client
+      // are read here too, so unread badges catch up without a resync
+      if (!deltaResult.uidvalidity_changed && deltaResult.folder_status?.unseen === 0) {
+        try {
+          const changed = await markFolderThreadsRead(accountId, folder.raw_path);
+          if (changed > 0) {
+            console.log(`[imapSync] ${changed} threads in ${folder.path} were read elsewhere`);
+          }
+        } catch (err) {
+          console.warn(`[imapSync] Failed to apply read state for ${folder.path}:`, err);
+        }
+      }
+
       try {
         if (deltaResult.uidvalidity_changed) {
           // UIDVALIDITY changed — full resync of this folder
@@ -825,7 +890,7 @@ export async function imapDeltaSync(accountId: string): Promise<SyncResult> {
               `(was ${savedState.uidvalidity}, now ${deltaResult.uidvalidity}). ` +
               `Doing full resync of this folder.`,
           );
-          const syncResult = await imapSyncFolder(config, folder.raw_path, BATCH_SIZE);
+          const syncResult = await imapSyncFolder(accountId, folder.raw_path, BATCH_SIZE);
           if (syncResult.uids.length === 0) continue;
 
           let lastUid = 0;
@@ -856,7 +921,7 @@ export async function imapDeltaSync(accountId: string): Promise<SyncResult> {
         if (deltaResult.new_uids.length === 0) continue;
 
         const { messages, lastUid, uidvalidity } = await fetchMessagesInBatches(
-          config,
+          accountId,
           folder.raw_path,
           deltaResult.new_uids,
         );
@@ -906,7 +971,7 @@ export async function imapDeltaSync(accountId: string): Promise<SyncResult> {
   }
 
   // Thread the new messages
-  const threadGroups = buildThreads(allThreadable);
+  const threadGroups = await threadMessages(accountId, allThreadable);
 
   // Store in DB
   const storedMessages = await storeThreadsAndMessages(
@@ -0,0 +1,66 @@
+import { describe, it, expect, beforeEach } from "vitest";
+import {
+  recordFolderPoll,
+  isFolderDue,
+  hasDueFolders,
+  getNextPollDelay,
+  pruneFolderSchedule,
+  resetPollSchedule,
+  getPollSchedule,
+  MIN_POLL_INTERVAL_MS,
+  DEFAULT_POLL_INTERVAL_MS,
+  MAX_POLL_INTERVAL_MS,
+} from "./pollScheduler";
+
+describe("pollScheduler", () => {
+  beforeEach(() => {
+    resetPollSchedule();
+  });
+
+  it("treats unknown folders and accounts as due", () => {
+    expect(isFolderDue("a1", "INBOX", 0)).toBe(true);
+    expect(hasDueFolders("a1", 0)).toBe(true);
+    expect(getNextPollDelay(["a1"], 0)).toBeNull();
+  });
+
+  it("starts at the default interval and backs off while quiet", () => {
+    const first = recordFolderPoll("a1", "INBOX", 0, 0);
+    expect(first.intervalMs).toBe(DEFAULT_POLL_INTERVAL_MS);
+
+    const second = recordFolderPoll("a1", "INBOX", 0, first.nextPollAt);
+    expect(second.intervalMs).toBeGreaterThan(first.intervalMs);
+
+    let state = second;
+    for (let i = 0; i < 20; i++) {
+      state = recordFolderPoll("a1", "INBOX", 0, state.nextPollAt);
+    }
+    expect(state.intervalMs).toBe(MAX_POLL_INTERVAL_MS);
+  });
+
+  it("drops to the minimum interval after new mail", () => {
+    recordFolderPoll("a1", "INBOX", 0, 0);
+    recordFolderPoll("a1", "INBOX", 0, 60_000);
+    const state = recordFolderPoll("a1", "INBOX", 3, 150_000);
+    expect(state.intervalMs).toBe(MIN_POLL_INTERVAL_MS);
+    expect(state.lastActivityAt).toBe(150_000);
+  });
+
+  it("reports due folders and the next poll delay per account", () => {
+    recordFolderPoll("a1", "INBOX", 1, 0);
+    recordFolderPoll("a1", "Archive", 0, 0);
+    recordFolderPoll("a2", "INBOX", 0, 0);
+
+    expect(isFolderDue("a1", "INBOX", 10_000)).toBe(false);
+    expect(hasDueFolders("a1", 10_000)).toBe(false);
+    expect(hasDueFolders("a1", MIN_POLL_INTERVAL_MS)).toBe(true);
+    expect(getNextPollDelay(["a1"], 10_000)).toBe(MIN_POLL_INTERVAL_MS - 10_000);
+    expect(getNextPollDelay(["a2"], 10_000)).toBe(DEFAULT_POLL_INTERVAL_MS - 10_000);
+  });
+
+  it("prunes folders that no longer exist", () => {
+    recordFolderPoll("a1", "INBOX", 0, 0);
+    recordFolderPoll("a1", "Old", 0, 0);
+    pruneFolderSchedule("a1", ["INBOX"]);
+    expect(getPollSchedule().map((s) => s.folder)).toEqual(["INBOX"]);
+  });
+});
@@ -0,0 +1,138 @@
+/**
+ * Adaptive per-folder polling schedule for IMAP accounts.
+ *
+ * Without IDLE we have to poll. Folders that just received mail are checked
+ * again soon; every quiet poll stretches the interval (up to a cap), so idle
+ * archives and rarely-used folders stop costing a round trip every minute.
+ */
+
+/** Shortest interval, used right after a folder received new mail. */
+export const MIN_POLL_INTERVAL_MS = 30_000;
+/** Interval for folders we haven't polled yet. */
+export const DEFAULT_POLL_INTERVAL_MS = 60_000;
+/** Longest interval a quiet folder backs off to. */
+export const MAX_POLL_INTERVAL_MS = 15 * 60_000;
+/** Multiplier applied to the interval after each poll with no new mail. */
+const BACKOFF_FACTOR = 1.5;
+
+export interface FolderPollState {
+  accountId: string;
+  folder: string;
+  intervalMs: number;
+  lastPollAt: number;
+  nextPollAt: number;
+  lastActivityAt: number | null;
+}
+
+const schedule = new Map<string, FolderPollState>();
+
+function key(accountId: string, folder: string): string {
+  return `${accountId}\u0000${folder}`;
+}
+
+/**
+ * Record the outcome of polling a folder and schedule its next check.
+ */
+export function recordFolderPoll(
+  accountId: string,
+  folder: string,
+  newMessages: number,
+  now = Date.now(),
+): FolderPollState {
+  const prev = schedule.get(key(accountId, folder));
+  const hadActivity = newMessages > 0;
+
+  const intervalMs = hadActivity
+    ? MIN_POLL_INTERVAL_MS
+    : prev
+      ? Math.min(Math.round(prev.intervalMs * BACKOFF_FACTOR), MAX_POLL_INTERVAL_MS)
+      : DEFAULT_POLL_INTERVAL_MS;
+
+  const state: FolderPollState = {
+    accountId,
+    folder,
+    intervalMs,
+    lastPollAt: now,
+    nextPollAt: now + intervalMs,
+    lastActivityAt: hadActivity ? now : (prev?.lastActivityAt ?? null),
+  };
+  schedule.set(key(accountId, folder), state);
+  return state;
+}
+
+/**
+ * Whether a folder should be checked now. Unknown folders are always due.
+ */
+export function isFolderDue(accountId: string, folder: string, now = Date.now()): boolean {
+  const state = schedule.get(key(accountId, folder));
+  return !state || state.nextPollAt <= now;
+}
+
+/**
+ * Make a folder due immediately, e.g. after a push notification for it.
+ */
+export function markFolderDue(accountId: string, folder: string, now = Date.now()): void {
+  const state = schedule.get(key(accountId, folder));
+  if (state) state.nextPollAt = now;
+}
+
+/**
+ * Whether any folder of the account is due. Accounts without a schedule
+ * (never polled) are always due.
+ */
+export function hasDueFolders(accountId: string, now = Date.now()): boolean {
+  let known = false;
+  for (const state of schedule.values()) {
+    if (state.accountId !== accountId) continue;
+    known = true;
+    if (state.nextPollAt <= now) return true;
+  }
+  return !known;
+}
+
+/**
+ * Time until the earliest scheduled folder poll across the given accounts,
+ * or `null` if none of them have a schedule yet.
+ */
+export function getNextPollDelay(accountIds: string[], now = Date.now()): number | null {
+  const ids = new Set(accountIds);
+  let earliest: number | null = null;
+  for (const state of schedule.values()) {
+    if (!ids.has(state.accountId)) continue;
+    if (earliest === null || state.nextPollAt < earliest) earliest = state.nextPollAt;
+  }
+  return earliest === null ? null : Math.max(0, earliest - now);
+}
+
+/**
+ * Forget the schedule for one account, or for all accounts.
+ */
+export function resetPollSchedule(accountId?: string): void {
+  if (!accountId) {
+    schedule.clear();
+    return;
+  }
+  for (const [k, state] of schedule) {
+    if (state.accountId === accountId) schedule.delete(k);
+  }
+}
+
+/**
+ * Drop schedule entries for folders of the account that no longer exist,
+ * so a deleted folder doesn't stay "due" forever.
+ */
+export function pruneFolderSchedule(accountId: string, folders: string[]): void {
+  const keep = new Set(folders);
+  for (const [k, state] of schedule) {
+    if (state.accountId === accountId && !keep.has(state.folder)) schedule.delete(k);
+  }
+}
+
+/**
+ * Snapshot of the effective schedule, soonest first (for debugging).
+ */
+export function getPollSchedule(): FolderPollState[] {
+  return [...schedule.values()]
+    .map((s) => ({ ...s }))
+    .sort((a, b) => a.nextPollAt - b.nextPollAt);
+}
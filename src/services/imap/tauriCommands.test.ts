@@ -20,7 +20,17 @@ import {
   imapGetFolderStatus,
   imapFetchAttachment,
   smtpSendEmail,
+  smtpSendAndAppend,
+  smtpQueueEmail,
   smtpTestConnection,
+  outboxRetry,
+  gmailListMessages,
+  gmailModifyLabels,
+  gmailSend,
+  registerAccount,
+  unregisterAccount,
+  listAccountDefinitions,
+  providePassword,
   type ImapConfig,
   type SmtpConfig,
 } from './tauriCommands';
@@ -47,6 +57,78 @@ beforeEach(() => {
   mockInvoke.mockReset();
 });
 
+describe('Account registry commands', () => {
+  it('registerAccount invokes with correct command and params', async () => {
+    mockInvoke.mockResolvedValue(undefined);
+
+    await registerAccount('acc-1', testImapConfig, testSmtpConfig);
+
+    expect(mockInvoke).toHaveBeenCalledWith('account_register', {
+      definition: {
+        id: 'acc-1',
+        email: null,
+        display_name: null,
+        imap: testImapConfig,
+        smtp: testSmtpConfig,
+        ask_password: false,
+        oauth: null,
+        transport: 'imap',
+      },
+    });
+  });
+
+  it('registerAccount passes account details', async () => {
+    mockInvoke.mockResolvedValue(undefined);
+
+    await registerAccount('acc-1', testImapConfig, null, true, {
+      email: 'user@example.com',
+      displayName: 'User',
+    });
+
+    expect(mockInvoke).toHaveBeenCalledWith('account_register', {
+      definition: expect.objectContaining({
+        id: 'acc-1',
+        email: 'user@example.com',
+        display_name: 'User',
+        ask_password: true,
+      }),
+    });
+  });
+
+  it('listAccountDefinitions invokes account_list', async () => {
+    mockInvoke.mockResolvedValue([]);
+
+    await expect(listAccountDefinitions()).resolves.toEqual([]);
+    expect(mockInvoke).toHaveBeenCalledWith('account_list');
+  });
+
+  it('providePassword invokes with correct command and params', async () => {
+    mockInvoke.mockResolvedValue(undefined);
+
+    await providePassword('acc-1', 'typed');
+    await providePassword('acc-1', null);
+
+    expect(mockInvoke).toHaveBeenCalledWith('account_provide_password', {
+      accountId: 'acc-1',
+      password: 'typed',
+    });
+    expect(mockInvoke).toHaveBeenCalledWith('account_provide_password', {
+      accountId: 'acc-1',
+      password: null,
+    });
+  });
+
+  it('unregisterAccount invokes with correct command and params', async () => {
+    mockInvoke.mockResolvedValue(undefined);
+
+    await unregisterAccount('acc-1');
+
+    expect(mockInvoke).toHaveBeenCalledWith('account_remove', {
+      accountId: 'acc-1',
+    });
+  });
+});
+
 describe('IMAP Tauri commands', () => {
   it('imapTestConnection invokes with correct command and params', async () => {
     mockInvoke.mockResolvedValue('Connected successfully. Found 5 folder(s).');
@@ -72,10 +154,10 @@ describe('IMAP Tauri commands', () => {
     ];
     mockInvoke.mockResolvedValue(folders);
 
-    const result = await imapListFolders(testImapConfig);
+    const result = await imapListFolders('acc-1');
 
     expect(mockInvoke).toHaveBeenCalledWith('imap_list_folders', {
-      config: testImapConfig,
+      accountId: 'acc-1',
     });
     expect(result).toEqual(folders);
   });
@@ -93,10 +175,10 @@ describe('IMAP Tauri commands', () => {
     };
     mockInvoke.mockResolvedValue(fetchResult);
 
-    const result = await imapFetchMessages(testImapConfig, 'INBOX', [1, 2, 3]);
+    const result = await imapFetchMessages('acc-1', 'INBOX', [1, 2, 3]);
 
     expect(mockInvoke).toHaveBeenCalledWith('imap_fetch_messages', {
-      config: testImapConfig,
+      accountId: 'acc-1',
       folder: 'INBOX',
       uids: [1, 2, 3],
     });
@@ -106,10 +188,10 @@ describe('IMAP Tauri commands', () => {
   it('imapFetchNewUids invokes with correct command and params', async () => {
     mockInvoke.mockResolvedValue([101, 102, 103]);
 
-    const result = await imapFetchNewUids(testImapConfig, 'INBOX', 100);
+    const result = await imapFetchNewUids('acc-1', 'INBOX', 100);
 
     expect(mockInvoke).toHaveBeenCalledWith('imap_fetch_new_uids', {
-      config: testImapConfig,
+      accountId: 'acc-1',
       folder: 'INBOX',
       sinceUid: 100,
     });
@@ -142,13 +224,15 @@ describe('IMAP Tauri commands', () => {
       list_unsubscribe_post: null,
       auth_results: null,
       attachments: [],
+      attached_messages: [],
+      parse_failed: false,
     };
     mockInvoke.mockResolvedValue(message);
 
-    const result = await imapFetchMessageBody(testImapConfig, 'INBOX', 42);
+    const result = await imapFetchMessageBody('acc-1', 'INBOX', 42);
 
     expect(mockInvoke).toHaveBeenCalledWith('imap_fetch_message_body', {
-      config: testImapConfig,
+      accountId: 'acc-1',
       folder: 'INBOX',
       uid: 42,
     });
@@ -158,24 +242,25 @@ describe('IMAP Tauri commands', () => {
   it('imapSetFlags invokes with correct command and params', async () => {
     mockInvoke.mockResolvedValue(undefined);
 
-    await imapSetFlags(testImapConfig, 'INBOX', [1, 2], ['Seen'], true);
+    await imapSetFlags('acc-1', 'INBOX', [1, 2], ['Seen'], true);
 
     expect(mockInvoke).toHaveBeenCalledWith('imap_set_flags', {
-      config: testImapConfig,
+      accountId: 'acc-1',
       folder: 'INBOX',
       uids: [1, 2],
       flags: ['Seen'],
       add: true,
+      keywords: null,
     });
   });
 
   it('imapMoveMessages invokes with correct command and params', async () => {
     mockInvoke.mockResolvedValue(undefined);
 
-    await imapMoveMessages(testImapConfig, 'INBOX', [1, 2], 'Trash');
+    await imapMoveMessages('acc-1', 'INBOX', [1, 2], 'Trash');
 
     expect(mockInvoke).toHaveBeenCalledWith('imap_move_messages', {
-      config: testImapConfig,
+      accountId: 'acc-1',
       folder: 'INBOX',
       uids: [1, 2],
       destination: 'Trash',
@@ -185,10 +270,10 @@ describe('IMAP Tauri commands', () => {
   it('imapDeleteMessages invokes with correct command and params', async () => {
     mockInvoke.mockResolvedValue(undefined);
 
-    await imapDeleteMessages(testImapConfig, 'INBOX', [1, 2]);
+    await imapDeleteMessages('acc-1', 'INBOX', [1, 2]);
 
     expect(mockInvoke).toHaveBeenCalledWith('imap_delete_messages', {
-      config: testImapConfig,
+      accountId: 'acc-1',
       folder: 'INBOX',
       uids: [1, 2],
     });
@@ -204,10 +289,10 @@ describe('IMAP Tauri commands', () => {
     };
     mockInvoke.mockResolvedValue(status);
 
-    const result = await imapGetFolderStatus(testImapConfig, 'INBOX');
+    const result = await imapGetFolderStatus('acc-1', 'INBOX');
 
     expect(mockInvoke).toHaveBeenCalledWith('imap_get_folder_status', {
-      config: testImapConfig,
+      accountId: 'acc-1',
       folder: 'INBOX',
     });
     expect(result).toEqual(status);
@@ -216,10 +301,10 @@ describe('IMAP Tauri commands', () => {
   it('imapFetchAttachment invokes with correct command and params', async () => {
     mockInvoke.mockResolvedValue('base64encodeddata==');
 
-    const result = await imapFetchAttachment(testImapConfig, 'INBOX', 42, '1.2');
+    const result = await imapFetchAttachment('acc-1', 'INBOX', 42, '1.2');
 
     expect(mockInvoke).toHaveBeenCalledWith('imap_fetch_attachment', {
-      config: testImapConfig,
+      accountId: 'acc-1',
       folder: 'INBOX',
       uid: 42,
       partId: '1.2',
@@ -233,15 +318,53 @@ describe('SMTP Tauri commands', () => {
     const sendResult = { success: true, message: 'Email sent successfully' };
     mockInvoke.mockResolvedValue(sendResult);
 
-    const result = await smtpSendEmail(testSmtpConfig, 'base64urlEncodedEmail');
+    const result = await smtpSendEmail('acc-1', 'base64urlEncodedEmail');
 
     expect(mockInvoke).toHaveBeenCalledWith('smtp_send_email', {
-      config: testSmtpConfig,
+      accountId: 'acc-1',
+      rawEmail: 'base64urlEncodedEmail',
+    });
+    expect(result).toEqual(sendResult);
+  });
+
+  it('smtpSendAndAppend invokes with correct command and params', async () => {
+    const sendResult = {
+      sent: { success: true, message: 'Email sent successfully' },
+      sent_copy: { status: 'failed', error: 'APPEND failed' },
+    };
+    mockInvoke.mockResolvedValue(sendResult);
+
+    const result = await smtpSendAndAppend('acc-1', 'base64urlEncodedEmail', 'Sent');
+
+    expect(mockInvoke).toHaveBeenCalledWith('smtp_send_and_append', {
+      accountId: 'acc-1',
       rawEmail: 'base64urlEncodedEmail',
+      sentFolder: 'Sent',
     });
     expect(result).toEqual(sendResult);
   });
 
+  it('smtpQueueEmail invokes with correct command and params', async () => {
+    const entry = { id: 'e1', account_id: 'acc-1', status: 'queued', attempts: 0 };
+    mockInvoke.mockResolvedValue(entry);
+
+    const result = await smtpQueueEmail('acc-1', 'base64urlEncodedEmail');
+
+    expect(mockInvoke).toHaveBeenCalledWith('smtp_queue_email', {
+      accountId: 'acc-1',
+      rawEmail: 'base64urlEncodedEmail',
+    });
+    expect(result).toEqual(entry);
+  });
+
+  it('outboxRetry retries everything when no id is given', async () => {
+    mockInvoke.mockResolvedValue(undefined);
+
+    await outboxRetry();
+
+    expect(mockInvoke).toHaveBeenCalledWith('outbox_retry', { id: null });
+  });
+
   it('smtpTestConnection invokes with correct command and params', async () => {
     const testResult = { success: true, message: 'Connection successful' };
     mockInvoke.mockResolvedValue(testResult);
@@ -257,7 +380,7 @@ describe('SMTP Tauri commands', () => {
   it('smtpSendEmail propagates errors', async () => {
     mockInvoke.mockRejectedValue('SMTP send error: Connection refused');
 
-    await expect(smtpSendEmail(testSmtpConfig, 'data')).rejects.toBe(
+    await expect(smtpSendEmail('acc-1', 'data')).rejects.toBe(
       'SMTP send error: Connection refused'
     );
   });
@@ -270,3 +393,44 @@ describe('SMTP Tauri commands', () => {
     );
   });
 });
+
+describe('Gmail API Tauri commands', () => {
+  it('gmailListMessages fills in unset options', async () => {
+    mockInvoke.mockResolvedValue({ messages: [], next_page_token: null, result_size_estimate: 0 });
+
+    await gmailListMessages('acc-1', { query: 'is:unread', maxResults: 50 });
+
+    expect(mockInvoke).toHaveBeenCalledWith('gmail_list_messages', {
+      accountId: 'acc-1',
+      query: 'is:unread',
+      labelIds: null,
+      maxResults: 50,
+      pageToken: null,
+    });
+  });
+
+  it('gmailModifyLabels invokes with correct command and params', async () => {
+    mockInvoke.mockResolvedValue(undefined);
+
+    await gmailModifyLabels('acc-1', ['18c1'], ['STARRED'], ['UNREAD']);
+
+    expect(mockInvoke).toHaveBeenCalledWith('gmail_modify_labels', {
+      accountId: 'acc-1',
+      messageIds: ['18c1'],
+      addLabelIds: ['STARRED'],
+      removeLabelIds: ['UNREAD'],
+    });
+  });
+
+  it('gmailSend passes the thread id', async () => {
+    mockInvoke.mockResolvedValue({ id: '18c2', thread_id: '18c0' });
+
+    await gmailSend('acc-1', 'base64urlEncodedEmail', '18c0');
+
+    expect(mockInvoke).toHaveBeenCalledWith('gmail_send', {
+      accountId: 'acc-1',
+      rawEmail: 'base64urlEncodedEmail',
+      threadId: '18c0',
+    });
+  });
+});
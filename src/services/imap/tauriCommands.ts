@@ -1,4 +1,6 @@
-import { invoke } from '@tauri-apps/api/core';
+import { commands } from '@/bindings';
+import { unwrap } from '../commandResult';
+import type { NntpConfig } from '../nntp/tauriCommands';
 
 // ---------- IMAP types ----------
 
@@ -10,8 +12,20 @@ export interface ImapConfig {
   password: string; // plaintext password or OAuth2 access token
   auth_method: 'password' | 'oauth2';
   accept_invalid_certs?: boolean;
+  /** Mailbox of another user to open with these credentials (delegated access) */
+  authorize_as?: string;
+  /** How `authorize_as` is sent; defaults to 'sasl' */
+  delegation?: ImapDelegation;
+  /** IMAP commands per minute, overriding the provider preset; 0 = unlimited */
+  rate_limit_per_minute?: number;
 }
 
+/**
+ * 'sasl': SASL PLAIN authorization identity (Dovecot master users, Zimbra, ...).
+ * 'exchange': Exchange/Office 365 "user@domain\shared@domain" login name.
+ */
+export type ImapDelegation = 'sasl' | 'exchange';
+
 export interface ImapFolder {
   path: string;       // decoded UTF-8 display name
   raw_path: string;   // original modified UTF-7 path for IMAP commands
@@ -20,6 +34,9 @@ export interface ImapFolder {
   special_use: string | null;
   exists: number;
   unseen: number;
+  attributes: string[]; // raw LIST attributes, e.g. "\\Noselect", "\\HasChildren"
+  no_select: boolean;    // hierarchy-only container, cannot be selected
+  non_existent: boolean; // listed (e.g. subscribed) but no longer exists
 }
 
 export interface ImapMessage {
@@ -47,6 +64,20 @@ export interface ImapMessage {
   list_unsubscribe_post: string | null;
   auth_results: string | null;
   attachments: ImapAttachment[];
+  attached_messages: ImapAttachedMessage[]; // message/rfc822 parts, parsed recursively
+  parse_failed: boolean; // MIME parsing failed; only basic headers were recovered
+  delivered_to: string | null; // envelope recipient: the alias/catch-all address it was delivered to
+  list_id: string | null; // List-Id, e.g. "dev.lists.example.org"
+  list_post: string | null; // List-Post address, for "reply to list"
+  list_archive: string | null; // List-Archive URL
+  is_from_me: boolean; // sent from one of the user's addresses (identity_set_addresses)
+  mail_followup_to: string | null; // Mail-Followup-To, for reply-all
+  keywords: string[]; // IMAP keywords, e.g. color categories ($label1..$label5)
+}
+
+export interface ImapAttachedMessage {
+  part_id: string; // section of the message/rfc822 part
+  message: ImapMessage; // attachment part ids are relative to the outer message
 }
 
 export interface ImapAttachment {
@@ -58,6 +89,32 @@ export interface ImapAttachment {
   is_inline: boolean;
 }
 
+/** A synced message's current keywords, from `imapFetchKeywords`. */
+export interface ImapMessageKeywords {
+  uid: number;
+  keywords: string[];
+}
+
+/** Envelope of a message fetched without its body, for the message list. */
+export interface ImapMessageHeader {
+  uid: number;
+  folder: string;
+  message_id: string | null;
+  in_reply_to: string | null;
+  from_address: string | null;
+  from_name: string | null;
+  to_addresses: string | null;
+  cc_addresses: string | null;
+  reply_to: string | null;
+  subject: string | null;
+  date: number;
+  is_read: boolean;
+  is_starred: boolean;
+  is_draft: boolean;
+  raw_size: number;
+  has_attachments: boolean;
+}
+
 export interface ImapFolderStatus {
   uidvalidity: number;
   uidnext: number;
@@ -92,6 +149,8 @@ export interface DeltaCheckResult {
   uidvalidity: number;
   new_uids: number[];
   uidvalidity_changed: boolean;
+  /** Current STATUS counts; null if the server rejected STATUS. */
+  folder_status: ImapFolderStatus | null;
 }
 
 // ---------- SMTP types ----------
@@ -104,6 +163,8 @@ export interface SmtpConfig {
   password: string;
   auth_method: 'password' | 'oauth2';
   accept_invalid_certs?: boolean;
+  /** Messages sent per minute, overriding the provider preset; 0 = unlimited */
+  rate_limit_per_minute?: number;
 }
 
 export interface SmtpSendResult {
@@ -111,6 +172,233 @@ export interface SmtpSendResult {
   message: string;
 }
 
+/** What became of the Sent folder copy of a message sent with `smtpSendAndAppend`. */
+export type SentCopy =
+  | { status: "saved" }
+  | { status: "saved_by_server" }
+  | { status: "skipped" }
+  | { status: "failed"; error: string };
+
+export interface SendAndAppendResult {
+  sent: SmtpSendResult;
+  sent_copy: SentCopy;
+}
+
+/** Where a composed attachment's content comes from. */
+export type ComposeAttachmentSource =
+  | { kind: 'path'; path: string }
+  | { kind: 'base64'; data: string };
+
+export interface ComposeAttachment {
+  filename: string;
+  /** Guessed from the filename when null. */
+  mime_type: string | null;
+  source: ComposeAttachmentSource;
+}
+
+/** An image the HTML body shows with `<img src="cid:...">`. */
+export interface ComposeInlineImage {
+  content_id: string;
+  filename: string | null;
+  mime_type: string | null;
+  source: ComposeAttachmentSource;
+}
+
+/** The parts of a message for `smtpComposeAndSend` to build. */
+export interface ComposeRequest {
+  from: string;
+  to: string[];
+  cc: string[];
+  bcc: string[];
+  reply_to: string | null;
+  subject: string;
+  text_body: string | null;
+  html_body: string | null;
+  attachments: ComposeAttachment[];
+  inline_images: ComposeInlineImage[];
+  /** Message-ID being replied to, with or without angle brackets. */
+  in_reply_to: string | null;
+  references: string[];
+}
+
+export interface ComposeSendResult extends SmtpSendResult {
+  /** Message-ID of the sent message, without angle brackets. */
+  message_id: string;
+  /** The message as sent, base64url; has a Bcc header only for Gmail API accounts. */
+  raw_email: string;
+}
+
+/** A message waiting in the outbox; the outbox keeps its own copy. */
+export interface OutboxEntry {
+  id: string;
+  account_id: string;
+  subject: string | null;
+  /** Milliseconds since the epoch */
+  queued_at: number;
+  next_attempt_at: number;
+  attempts: number;
+  last_error: string | null;
+  /** "failed" entries wait for `outboxRetry` or `outboxCancel`. */
+  status: 'queued' | 'sending' | 'failed';
+}
+
+/** Payload of `outbox-sent`: a queued message went out. */
+export interface OutboxSentEvent {
+  id: string;
+  account_id: string;
+  message: string;
+}
+
+/** Payload of `outbox-failed`: the outbox gave up on a message. */
+export interface OutboxFailedEvent {
+  id: string;
+  account_id: string;
+  error: string;
+  attempts: number;
+}
+
+// ---------- Account credentials ----------
+
+export interface OAuthSettings {
+  provider: string;
+  client_id: string | null;
+}
+
+/**
+ * How an account sends mail: its SMTP server, or the Gmail API for Gmail
+ * accounts that run into Gmail's IMAP/SMTP limits.
+ */
+export type MailTransport = 'imap' | 'gmail_api';
+
+/** An account as the backend stores it (`accounts.json`, no secrets). */
+export interface AccountDefinition {
+  id: string;
+  email: string | null;
+  display_name: string | null;
+  imap: ImapConfig | null;
+  smtp: SmtpConfig | null;
+  /** News server of a newsgroup account, which has no IMAP/SMTP. */
+  nntp?: NntpConfig | null;
+  ask_password: boolean;
+  oauth: OAuthSettings | null;
+  transport?: MailTransport;
+  /** Set by the managed policy; such accounts can't be edited or removed. */
+  managed?: boolean;
+}
+
+/** Details kept with a registered account; left out ones keep their value. */
+export interface AccountDetails {
+  email?: string;
+  displayName?: string | null;
+  oauth?: OAuthSettings | null;
+  transport?: MailTransport;
+}
+
+/**
+ * Hand an account's server credentials to the backend. Every IMAP/SMTP
+ * command below except the connection tests takes the account id and uses
+ * the credentials registered here. Register again to replace them (e.g.
+ * after refreshing an OAuth token). Only the main window and composers may
+ * call this.
+ *
+ * With `askPassword` the configs' passwords are ignored: the backend emits
+ * `password-required` the first time it needs one and keeps the answer
+ * for the session.
+ */
+export async function registerAccount(
+  accountId: string,
+  imap: ImapConfig | null,
+  smtp: SmtpConfig | null,
+  askPassword = false,
+  details: AccountDetails = {},
+): Promise<void> {
+  const definition: AccountDefinition = {
+    id: accountId,
+    email: details.email ?? null,
+    display_name: details.displayName ?? null,
+    imap,
+    smtp,
+    ask_password: askPassword,
+    oauth: details.oauth ?? null,
+    transport: details.transport ?? 'imap',
+  };
+  return unwrap<void>(await commands.accountRegister(definition));
+}
+
+/** Store a new account definition; rejects if the id is taken. */
+export async function addAccountDefinition(definition: AccountDefinition): Promise<void> {
+  return unwrap<void>(await commands.accountAdd(definition));
+}
+
+/** Replace a stored account definition; rejects if there's none. */
+export async function updateAccountDefinition(definition: AccountDefinition): Promise<void> {
+  return unwrap<void>(await commands.accountUpdate(definition));
+}
+
+/** The accounts the backend has stored, without passwords or tokens. */
+export async function listAccountDefinitions(): Promise<AccountDefinition[]> {
+  return unwrap<AccountDefinition[]>(await commands.accountList());
+}
+
+/**
+ * Answer a `password-required` event, or pass `null` to cancel the commands
+ * waiting for it. The password is only kept in memory.
+ */
+export async function providePassword(accountId: string, password: string | null): Promise<void> {
+  return unwrap<void>(await commands.accountProvidePassword(accountId, password));
+}
+
+/**
+ * Delete an account's stored definition and drop its credentials.
+ */
+export async function unregisterAccount(accountId: string): Promise<void> {
+  return unwrap<void>(await commands.accountRemove(accountId));
+}
+
+/** What the backend needs to refresh an account's OAuth access tokens. */
+export interface OAuthGrant {
+  token_url: string;
+  client_id: string;
+  client_secret: string | null;
+  scope: string | null;
+  refresh_token: string;
+}
+
+/**
+ * Hand an account's refresh token to the backend, which keeps it in the OS
+ * keychain and refreshes access tokens itself; configs registered without
+ * a token then always get a current one. `expiresAt` (Unix seconds) goes
+ * with `accessToken`. Rejects when there's no usable keychain.
+ */
+export async function oauthStoreGrant(
+  accountId: string,
+  grant: OAuthGrant,
+  accessToken: string | null,
+  expiresAt: number | null,
+): Promise<void> {
+  return unwrap<void>(await commands.oauthStoreGrant(accountId, grant, accessToken, expiresAt));
+}
+
+/**
+ * Store an account's password in the OS keychain. Configs registered with
+ * an empty password then get it from there. Rejects when there's no usable
+ * keychain.
+ */
+export async function credentialsStore(accountId: string, secret: string): Promise<void> {
+  return unwrap<void>(await commands.credentialsStore(accountId, secret));
+}
+
+export async function credentialsGet(accountId: string): Promise<string | null> {
+  return unwrap<string | null>(await commands.credentialsGet(accountId));
+}
+
+/**
+ * Remove an account's password from the OS keychain, if it's there.
+ */
+export async function credentialsDelete(accountId: string): Promise<void> {
+  return unwrap<void>(await commands.credentialsDelete(accountId));
+}
+
 // ---------- IMAP commands ----------
 
 /**
@@ -118,14 +406,46 @@ export interface SmtpSendResult {
  * Returns a success message string.
  */
 export async function imapTestConnection(config: ImapConfig): Promise<string> {
-  return invoke<string>('imap_test_connection', { config });
+  return unwrap<string>(await commands.imapTestConnection(config));
 }
 
 /**
  * List all IMAP folders/mailboxes on the server.
  */
-export async function imapListFolders(config: ImapConfig): Promise<ImapFolder[]> {
-  return invoke<ImapFolder[]>('imap_list_folders', { config });
+export async function imapListFolders(accountId: string): Promise<ImapFolder[]> {
+  return unwrap<ImapFolder[]>(await commands.imapListFolders(accountId));
+}
+
+/**
+ * Create a folder. Folder management commands take UTF-8 paths (as in
+ * `ImapFolder.path`, levels joined by the delimiter) and encode them to
+ * modified UTF-7 for the server. Resolves to the new folder's raw path.
+ */
+export async function imapCreateFolder(accountId: string, path: string): Promise<string> {
+  return unwrap<string>(await commands.imapCreateFolder(accountId, path));
+}
+
+/**
+ * Rename or move a folder, with its children. Resolves to the new raw path.
+ */
+export async function imapRenameFolder(
+  accountId: string,
+  path: string,
+  newPath: string
+): Promise<string> {
+  return unwrap<string>(await commands.imapRenameFolder(accountId, path, newPath));
+}
+
+export async function imapDeleteFolder(accountId: string, path: string): Promise<void> {
+  return unwrap<void>(await commands.imapDeleteFolder(accountId, path));
+}
+
+export async function imapSubscribeFolder(accountId: string, path: string): Promise<void> {
+  return unwrap<void>(await commands.imapSubscribeFolder(accountId, path));
+}
+
+export async function imapUnsubscribeFolder(accountId: string, path: string): Promise<void> {
+  return unwrap<void>(await commands.imapUnsubscribeFolder(accountId, path));
 }
 
 /**
@@ -133,22 +453,47 @@ export async function imapListFolders(config: ImapConfig): Promise<ImapFolder[]>
  * Returns parsed messages along with folder status metadata.
  */
 export async function imapFetchMessages(
-  config: ImapConfig,
+  accountId: string,
   folder: string,
   uids: number[]
 ): Promise<ImapFetchResult> {
-  return invoke<ImapFetchResult>('imap_fetch_messages', { config, folder, uids });
+  return unwrap<ImapFetchResult>(await commands.imapFetchMessages(accountId, folder, uids));
+}
+
+/**
+ * Fetch only the envelopes, flags and sizes of messages by UID list, without
+ * downloading their bodies. Bodies can be loaded later with
+ * `imapFetchMessageBody` when a message is opened.
+ */
+export async function imapFetchHeaders(
+  accountId: string,
+  folder: string,
+  uids: number[]
+): Promise<ImapMessageHeader[]> {
+  return unwrap<ImapMessageHeader[]>(await commands.imapFetchHeaders(accountId, folder, uids));
+}
+
+/**
+ * Fetch just the keywords of messages by UID list, to pick up keyword
+ * changes other clients made after the messages were synced.
+ */
+export async function imapFetchKeywords(
+  accountId: string,
+  folder: string,
+  uids: number[]
+): Promise<ImapMessageKeywords[]> {
+  return unwrap<ImapMessageKeywords[]>(await commands.imapFetchKeywords(accountId, folder, uids));
 }
 
 /**
  * Get UIDs of messages newer than `sinceUid` in the given folder.
  */
 export async function imapFetchNewUids(
-  config: ImapConfig,
+  accountId: string,
   folder: string,
   sinceUid: number
 ): Promise<number[]> {
-  return invoke<number[]>('imap_fetch_new_uids', { config, folder, sinceUid });
+  return unwrap<number[]>(await commands.imapFetchNewUids(accountId, folder, sinceUid));
 }
 
 /**
@@ -156,36 +501,53 @@ export async function imapFetchNewUids(
  * Returns real UIDs — avoids the sparse UID gap problem with generateUidRange.
  */
 export async function imapSearchAllUids(
-  config: ImapConfig,
+  accountId: string,
   folder: string
 ): Promise<number[]> {
-  return invoke<number[]>('imap_search_all_uids', { config, folder });
+  return unwrap<number[]>(await commands.imapSearchAllUids(accountId, folder));
+}
+
+/**
+ * Search a folder for messages containing `text` using UID SEARCH TEXT,
+ * optionally only those before an IMAP date such as "1-Mar-2024".
+ */
+export async function imapSearchText(
+  accountId: string,
+  folder: string,
+  text: string,
+  before: string | null
+): Promise<number[]> {
+  return unwrap<number[]>(await commands.imapSearchText(accountId, folder, text, before));
 }
 
 /**
  * Fetch a single message with full body by UID.
  */
 export async function imapFetchMessageBody(
-  config: ImapConfig,
+  accountId: string,
   folder: string,
   uid: number
 ): Promise<ImapMessage> {
-  return invoke<ImapMessage>('imap_fetch_message_body', { config, folder, uid });
+  return unwrap<ImapMessage>(await commands.imapFetchMessageBody(accountId, folder, uid));
 }
 
 /**
  * Set or remove flags on messages.
  * @param flags - Flag names (e.g. "Seen", "Flagged", "Draft"). Backslash prefix is added automatically.
  * @param add - true to add flags, false to remove them.
+ * @param keywords - IMAP keywords (e.g. "$label1", "Travel"), sent as given.
  */
 export async function imapSetFlags(
-  config: ImapConfig,
+  accountId: string,
   folder: string,
   uids: number[],
   flags: string[],
-  add: boolean
+  add: boolean,
+  keywords?: string[]
 ): Promise<void> {
-  return invoke<void>('imap_set_flags', { config, folder, uids, flags, add });
+  return unwrap<void>(
+    await commands.imapSetFlags(accountId, folder, uids, flags, add, keywords ?? null),
+  );
 }
 
 /**
@@ -193,23 +555,23 @@ export async function imapSetFlags(
  * Uses MOVE extension if available, falls back to COPY+DELETE.
  */
 export async function imapMoveMessages(
-  config: ImapConfig,
+  accountId: string,
   folder: string,
   uids: number[],
   destination: string
 ): Promise<void> {
-  return invoke<void>('imap_move_messages', { config, folder, uids, destination });
+  return unwrap<void>(await commands.imapMoveMessages(accountId, folder, uids, destination));
 }
 
 /**
  * Permanently delete messages (flag as Deleted + EXPUNGE).
  */
 export async function imapDeleteMessages(
-  config: ImapConfig,
+  accountId: string,
   folder: string,
   uids: number[]
 ): Promise<void> {
-  return invoke<void>('imap_delete_messages', { config, folder, uids });
+  return unwrap<void>(await commands.imapDeleteMessages(accountId, folder, uids));
 }
 
 /**
@@ -218,22 +580,55 @@ export async function imapDeleteMessages(
  * @param flags - Optional IMAP flags string (e.g. "(\\Seen)" or "(\\Draft)").
  */
 export async function imapAppendMessage(
-  config: ImapConfig,
+  accountId: string,
   folder: string,
   rawMessage: string,
   flags?: string
 ): Promise<void> {
-  return invoke<void>('imap_append_message', { config, folder, flags: flags ?? null, rawMessage });
+  return unwrap<void>(
+    await commands.imapAppendMessage(accountId, folder, flags ?? null, rawMessage),
+  );
+}
+
+/**
+ * Save a draft to the Drafts folder. When `replacesUid` is a draft saved
+ * earlier in this session and the server supports CATENATE, unchanged
+ * attachments are copied on the server instead of being uploaded again.
+ * The old copy is not deleted.
+ * @param rawMessage - The full email message encoded as base64url.
+ * @returns The new draft's UID, or null if the server doesn't report it.
+ */
+export async function imapSaveDraft(
+  accountId: string,
+  folder: string,
+  rawMessage: string,
+  replacesUid?: number | null
+): Promise<number | null> {
+  return unwrap<number | null>(
+    await commands.imapSaveDraft(accountId, folder, rawMessage, replacesUid ?? null),
+  );
 }
 
 /**
  * Get folder status (UIDVALIDITY, UIDNEXT, message count, unseen count).
  */
 export async function imapGetFolderStatus(
-  config: ImapConfig,
+  accountId: string,
   folder: string
 ): Promise<ImapFolderStatus> {
-  return invoke<ImapFolderStatus>('imap_get_folder_status', { config, folder });
+  return unwrap<ImapFolderStatus>(await commands.imapGetFolderStatus(accountId, folder));
+}
+
+/**
+ * List a message's attachments from its BODYSTRUCTURE without downloading
+ * the message. Part IDs can be passed to `imapFetchAttachment`.
+ */
+export async function imapFetchBodystructure(
+  accountId: string,
+  folder: string,
+  uid: number
+): Promise<ImapAttachment[]> {
+  return unwrap<ImapAttachment[]>(await commands.imapFetchBodystructure(accountId, folder, uid));
 }
 
 /**
@@ -241,12 +636,12 @@ export async function imapGetFolderStatus(
  * Returns the attachment data as a base64-encoded string.
  */
 export async function imapFetchAttachment(
-  config: ImapConfig,
+  accountId: string,
   folder: string,
   uid: number,
   partId: string
 ): Promise<string> {
-  return invoke<string>('imap_fetch_attachment', { config, folder, uid, partId });
+  return unwrap<string>(await commands.imapFetchAttachment(accountId, folder, uid, partId));
 }
 
 /**
@@ -254,11 +649,11 @@ export async function imapFetchAttachment(
  * Returns the full message as a UTF-8 string.
  */
 export async function imapFetchRawMessage(
-  config: ImapConfig,
+  accountId: string,
   folder: string,
   uid: number
 ): Promise<string> {
-  return invoke<string>('imap_fetch_raw_message', { config, folder, uid });
+  return unwrap<string>(await commands.imapFetchRawMessage(accountId, folder, uid));
 }
 
 /**
@@ -266,10 +661,10 @@ export async function imapFetchRawMessage(
  * Replaces N separate imapGetFolderStatus + imapFetchNewUids calls with one round-trip.
  */
 export async function imapDeltaCheck(
-  config: ImapConfig,
+  accountId: string,
   folders: DeltaCheckRequest[]
 ): Promise<DeltaCheckResult[]> {
-  return invoke<DeltaCheckResult[]>('imap_delta_check', { config, folders });
+  return unwrap<DeltaCheckResult[]>(await commands.imapDeltaCheck(accountId, folders));
 }
 
 /**
@@ -278,40 +673,287 @@ export async function imapDeltaCheck(
  * caused by separate imapSearchAllUids + imapFetchMessages calls.
  */
 export async function imapSyncFolder(
-  config: ImapConfig,
+  accountId: string,
   folder: string,
   batchSize: number,
 ): Promise<ImapFolderSyncResult> {
-  return invoke<ImapFolderSyncResult>('imap_sync_folder', { config, folder, batchSize });
+  return unwrap<ImapFolderSyncResult>(await commands.imapSyncFolder(accountId, folder, batchSize));
 }
 
 /**
  * Raw IMAP diagnostic: bypasses async-imap to show raw server responses.
  */
 export async function imapRawFetchDiagnostic(
-  config: ImapConfig,
+  accountId: string,
   folder: string,
   uidRange: string,
 ): Promise<string> {
-  return invoke<string>('imap_raw_fetch_diagnostic', { config, folder, uidRange });
+  return unwrap<string>(await commands.imapRawFetchDiagnostic(accountId, folder, uidRange));
 }
 
 // ---------- SMTP commands ----------
 
 /**
- * Send a pre-built RFC 2822 email via SMTP.
+ * Send a pre-built RFC 2822 email via SMTP with the account's registered
+ * credentials. Messages forwarded as attachments that are still on the
+ * server are reached with the account's IMAP credentials.
  * @param rawEmail - The full email message encoded as base64url.
  */
 export async function smtpSendEmail(
-  config: SmtpConfig,
-  rawEmail: string
+  accountId: string,
+  rawEmail: string,
 ): Promise<SmtpSendResult> {
-  return invoke<SmtpSendResult>('smtp_send_email', { config, rawEmail });
+  return unwrap<SmtpSendResult>(await commands.smtpSendEmail(accountId, rawEmail));
+}
+
+/**
+ * Send a message and save a `\\Seen` copy to `sentFolder` in one backend
+ * call, so a reload in between can't lose the copy. Saving is retried; if
+ * it still fails the message has been sent and `sent_copy` says so.
+ * @param rawEmail - The full email message encoded as base64url.
+ */
+export async function smtpSendAndAppend(
+  accountId: string,
+  rawEmail: string,
+  sentFolder: string,
+): Promise<SendAndAppendResult> {
+  return unwrap<SendAndAppendResult>(
+    await commands.smtpSendAndAppend(accountId, rawEmail, sentFolder),
+  );
+}
+
+/**
+ * Build a MIME message from its parts in the backend and send it, through
+ * SMTP or the Gmail API as the account is set up.
+ */
+export async function smtpComposeAndSend(
+  accountId: string,
+  request: ComposeRequest,
+): Promise<ComposeSendResult> {
+  return unwrap<ComposeSendResult>(await commands.smtpComposeAndSend(accountId, request));
+}
+
+/**
+ * Queue a message to be sent in the background instead of sending it now.
+ * Transient failures (the network dropping, a 4xx reply) are retried with
+ * backoff; `outbox-sent` or `outbox-failed` tells how it went.
+ * @param rawEmail - The full email message encoded as base64url.
+ */
+export async function smtpQueueEmail(
+  accountId: string,
+  rawEmail: string,
+): Promise<OutboxEntry> {
+  return unwrap<OutboxEntry>(await commands.smtpQueueEmail(accountId, rawEmail));
+}
+
+/** Messages in the outbox, oldest first. */
+export async function outboxList(): Promise<OutboxEntry[]> {
+  return commands.outboxList() as Promise<OutboxEntry[]>;
+}
+
+/** Try outbox entry `id` again now, or every entry when omitted. */
+export async function outboxRetry(id?: string): Promise<void> {
+  return commands.outboxRetry(id ?? null);
+}
+
+/** Drop a message from the outbox without sending it. */
+export async function outboxCancel(id: string): Promise<void> {
+  return unwrap<void>(await commands.outboxCancel(id));
 }
 
 /**
  * Test SMTP connectivity by connecting and authenticating.
  */
 export async function smtpTestConnection(config: SmtpConfig): Promise<SmtpSendResult> {
-  return invoke<SmtpSendResult>('smtp_test_connection', { config });
+  return unwrap<SmtpSendResult>(await commands.smtpTestConnection(config));
+}
+
+// ---------- Gmail API ----------
+// Parallel to the IMAP/SMTP commands for Gmail accounts signed in with
+// OAuth; they use the account's registered token.
+
+export interface GmailMessageRef {
+  id: string;
+  thread_id: string;
+}
+
+export interface GmailMessageList {
+  messages: GmailMessageRef[];
+  /** Pass back to get the next page; null on the last one. */
+  next_page_token: string | null;
+  result_size_estimate: number;
+}
+
+export interface GmailApiMessage {
+  id: string;
+  thread_id: string;
+  label_ids: string[];
+  snippet: string;
+  history_id: string | null;
+  /** Milliseconds since the epoch, as a string. */
+  internal_date: string | null;
+  size_estimate: number | null;
+  /** The message source, base64url-encoded; only in "raw" format. */
+  raw: string | null;
+}
+
+/**
+ * One page of the account's messages matching `query` (Gmail search
+ * syntax) and carrying all of `labelIds`, newest first.
+ */
+export async function gmailListMessages(
+  accountId: string,
+  options: { query?: string; labelIds?: string[]; maxResults?: number; pageToken?: string } = {},
+): Promise<GmailMessageList> {
+  return unwrap<GmailMessageList>(
+    await commands.gmailListMessages(
+      accountId,
+      options.query ?? null,
+      options.labelIds ?? null,
+      options.maxResults ?? null,
+      options.pageToken ?? null,
+    ),
+  );
+}
+
+export async function gmailGetMessage(
+  accountId: string,
+  messageId: string,
+  format: 'minimal' | 'metadata' | 'raw' = 'metadata',
+): Promise<GmailApiMessage> {
+  return unwrap<GmailApiMessage>(await commands.gmailGetMessage(accountId, messageId, format));
+}
+
+/** Add and remove labels, including UNREAD, STARRED and INBOX. */
+export async function gmailModifyLabels(
+  accountId: string,
+  messageIds: string[],
+  addLabelIds: string[],
+  removeLabelIds: string[],
+): Promise<void> {
+  return unwrap<void>(
+    await commands.gmailModifyLabels(accountId, messageIds, addLabelIds, removeLabelIds),
+  );
+}
+
+/**
+ * Send a base64url-encoded message through the Gmail API. `smtpSendEmail`
+ * does this too for accounts whose transport is "gmail_api".
+ */
+export async function gmailSend(
+  accountId: string,
+  rawEmail: string,
+  threadId?: string,
+): Promise<GmailApiMessage> {
+  return unwrap<GmailApiMessage>(await commands.gmailSend(accountId, rawEmail, threadId ?? null));
+}
+
+// ---------- NOTIFY push ----------
+
+/** Payload of the `imap-notify` event; `folder` is null after an overflow. */
+export interface ImapNotifyEvent {
+  account_id: string;
+  folder: string | null;
+  exists: number | null;
+  unseen: number | null;
+}
+
+/** Payload of `folders-changed`: the account's LIST differs from the last one. */
+export interface FoldersChangedEvent {
+  account_id: string;
+  added: ImapFolder[];
+  removed: string[]; // raw paths
+  renamed: { from: string; to: ImapFolder }[];
+}
+
+/**
+ * Register for NOTIFY (RFC 5465) push on the given folders (raw paths) over
+ * a single connection. Resolves to false if the server doesn't support it.
+ */
+export async function imapStartNotify(
+  accountId: string,
+  folders: string[],
+): Promise<boolean> {
+  return unwrap<boolean>(await commands.imapStartNotify(accountId, folders));
+}
+
+/**
+ * Close the NOTIFY connection for an account, or for all accounts.
+ */
+export async function imapStopNotify(accountId?: string): Promise<void> {
+  return commands.imapStopNotify(accountId ?? null);
+}
+
+// ---------- IDLE push ----------
+
+/** Payload of `imap-new-mail`: a folder watched with IDLE grew to `exists` messages. */
+export interface ImapNewMailEvent {
+  account_id: string;
+  folder: string;
+  exists: number;
+}
+
+/** Payload of `imap-expunge`; `seq` is a sequence number, not a UID. */
+export interface ImapExpungeEvent {
+  account_id: string;
+  folder: string;
+  seq: number;
+}
+
+/** Payload of `imap-flags-changed`; `uid` is null if the server left it out. */
+export interface ImapFlagsChangedEvent {
+  account_id: string;
+  folder: string;
+  seq: number;
+  uid: number | null;
+  flags: string[];
+}
+
+/**
+ * Watch one folder (raw path) with IDLE (RFC 2177) on its own connection,
+ * for servers without NOTIFY. Resolves to false if IDLE isn't supported.
+ */
+export async function imapStartIdle(accountId: string, folder: string): Promise<boolean> {
+  return unwrap<boolean>(await commands.imapStartIdle(accountId, folder));
+}
+
+/**
+ * Close the IDLE connection for a folder, every folder of an account, or
+ * everything.
+ */
+export async function imapStopIdle(accountId?: string, folder?: string): Promise<void> {
+  return commands.imapStopIdle(accountId ?? null, folder ?? null);
+}
+
+/**
+ * Log out the idle IMAP sessions the backend keeps for reuse, for an account
+ * or for all accounts.
+ */
+export async function imapCloseAccountSessions(accountId?: string): Promise<void> {
+  return unwrap<void>(await commands.imapCloseAccountSessions(accountId ?? null));
+}
+
+// ---------- Diagnostics ----------
+
+/** Timing summary for one server operation, from `get_metrics`. */
+export interface MetricSummary {
+  /** Server host; empty for local work such as message parsing */
+  host: string;
+  operation: string;
+  phase: 'connect' | 'auth' | 'command' | 'parse';
+  count: number;
+  errors: number;
+  avg_ms: number;
+  p50_ms: number;
+  p95_ms: number;
+  max_ms: number;
+  last_ms: number;
+}
+
+/**
+ * Connect/auth/command/parse timings recorded since startup (or the last
+ * reset), grouped by server host and operation.
+ */
+export async function getMetrics(reset = false): Promise<MetricSummary[]> {
+  return commands.getMetrics(reset) as Promise<MetricSummary[]>;
 }
@@ -0,0 +1,47 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { invoke } from "@tauri-apps/api/core";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+import { getLocalApi, regenerateLocalApiToken, setLocalApi } from "./localApi";
+
+const mockInvoke = vi.mocked(invoke);
+
+const status = {
+  enabled: true,
+  port: 17300,
+  running: true,
+  error: null,
+  token: "abc",
+};
+
+describe("localApi", () => {
+  beforeEach(() => {
+    mockInvoke.mockReset();
+  });
+
+  it("reads the status", async () => {
+    mockInvoke.mockResolvedValue(status);
+
+    await expect(getLocalApi()).resolves.toEqual(status);
+    expect(mockInvoke).toHaveBeenCalledWith("local_api_get");
+  });
+
+  it("saves the settings", async () => {
+    mockInvoke.mockResolvedValue(status);
+
+    await setLocalApi({ enabled: true, port: 17300 });
+    expect(mockInvoke).toHaveBeenCalledWith("local_api_set", {
+      localApi: { enabled: true, port: 17300 },
+    });
+  });
+
+  it("regenerates the token", async () => {
+    mockInvoke.mockResolvedValue({ ...status, token: "def" });
+
+    await expect(regenerateLocalApiToken()).resolves.toMatchObject({ token: "def" });
+    expect(mockInvoke).toHaveBeenCalledWith("local_api_regenerate_token");
+  });
+});
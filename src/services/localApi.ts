@@ -0,0 +1,36 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * Opt-in HTTP API on 127.0.0.1 for launchers and scripts: unread counts,
+ * search and opening the composer. Requests need the bearer token shown in
+ * Settings; the backend serves it from the `local_api` module.
+ */
+
+export interface LocalApiSettings {
+  enabled: boolean;
+  port: number;
+}
+
+export interface LocalApiStatus extends LocalApiSettings {
+  /** Whether the server is listening */
+  running: boolean;
+  /** Why an enabled server isn't running, e.g. the port is in use */
+  error: string | null;
+  /** Null while the API is off */
+  token: string | null;
+}
+
+export const DEFAULT_LOCAL_API_PORT = 17300;
+
+export async function getLocalApi(): Promise<LocalApiStatus> {
+  return invoke<LocalApiStatus>("local_api_get");
+}
+
+export async function setLocalApi(localApi: LocalApiSettings): Promise<LocalApiStatus> {
+  return invoke<LocalApiStatus>("local_api_set", { localApi });
+}
+
+/** Replace the token; tools using the old one stop working. */
+export async function regenerateLocalApiToken(): Promise<LocalApiStatus> {
+  return invoke<LocalApiStatus>("local_api_regenerate_token");
+}
@@ -0,0 +1,86 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+vi.mock("./db/accounts", () => ({
+  getAccount: vi.fn(),
+  insertImapAccount: vi.fn(),
+}));
+
+vi.mock("./imap/tauriCommands", () => ({
+  listAccountDefinitions: vi.fn(),
+}));
+
+import { invoke } from "@tauri-apps/api/core";
+import { getAccount, insertImapAccount, type DbAccount } from "./db/accounts";
+import { listAccountDefinitions, type AccountDefinition } from "./imap/tauriCommands";
+import { getManagedPolicy, importManagedAccounts } from "./managedPolicy";
+
+function definition(id: string, overrides: Partial<AccountDefinition> = {}): AccountDefinition {
+  return {
+    id,
+    email: `${id}@corp.example`,
+    display_name: null,
+    imap: {
+      host: "imap.corp.example",
+      port: 993,
+      security: "tls",
+      username: `${id}@corp.example`,
+      password: "",
+      auth_method: "password",
+    },
+    smtp: {
+      host: "smtp.corp.example",
+      port: 587,
+      security: "starttls",
+      username: `${id}@corp.example`,
+      password: "",
+      auth_method: "password",
+    },
+    ask_password: false,
+    oauth: null,
+    managed: true,
+    ...overrides,
+  };
+}
+
+describe("managedPolicy", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+  });
+
+  it("reads the policy summary", async () => {
+    vi.mocked(invoke).mockResolvedValue({ locked_settings: ["read_only"] });
+
+    await expect(getManagedPolicy()).resolves.toEqual({ locked_settings: ["read_only"] });
+    expect(invoke).toHaveBeenCalledWith("get_managed_policy");
+  });
+
+  it("adds missing managed password accounts", async () => {
+    vi.mocked(listAccountDefinitions).mockResolvedValue([
+      definition("new"),
+      definition("existing"),
+      definition("user", { managed: false }),
+      definition("oauth", {
+        imap: { ...definition("oauth").imap!, auth_method: "oauth2" },
+      }),
+    ]);
+    vi.mocked(getAccount).mockImplementation(async (id) =>
+      id === "existing" ? ({ id } as DbAccount) : null,
+    );
+
+    await expect(importManagedAccounts()).resolves.toEqual(["new"]);
+    expect(insertImapAccount).toHaveBeenCalledTimes(1);
+    expect(insertImapAccount).toHaveBeenCalledWith(
+      expect.objectContaining({
+        id: "new",
+        email: "new@corp.example",
+        imapSecurity: "ssl",
+        smtpSecurity: "starttls",
+        askPassword: true,
+      }),
+    );
+  });
+});
@@ -0,0 +1,68 @@
+import { invoke } from "@tauri-apps/api/core";
+import { getAccount, insertImapAccount } from "./db/accounts";
+import { listAccountDefinitions } from "./imap/tauriCommands";
+
+/**
+ * Managed deployment configuration: settings, sign-in restrictions and
+ * accounts an administrator set through a system-level config (MDM, group
+ * policy or `/etc/sora/managed.d`). The backend enforces it; the frontend
+ * only shows which controls are locked and adds the managed accounts.
+ */
+
+/** Same message as the backend's `MANAGED_SETTING_ERROR`. */
+export const MANAGED_SETTING_ERROR = "This setting is managed by your organization";
+
+export interface ManagedPolicy {
+  allowed_auth_methods: string[];
+  disable_accept_invalid_certs: boolean;
+  /** Backend settings keys the user can't change, e.g. `read_only`. */
+  locked_settings: string[];
+  account_ids: string[];
+}
+
+export async function getManagedPolicy(): Promise<ManagedPolicy> {
+  return invoke<ManagedPolicy>("get_managed_policy");
+}
+
+/**
+ * Add database rows for managed IMAP accounts the profile doesn't have yet,
+ * so they show up like any other account. The administrator can't ship a
+ * password, so the user is asked for it once per session. OAuth accounts
+ * need the user to sign in and are left to the add-account flow.
+ * Returns the ids of the accounts added.
+ */
+export async function importManagedAccounts(): Promise<string[]> {
+  const definitions = await listAccountDefinitions();
+  const added: string[] = [];
+  for (const definition of definitions) {
+    const { imap, smtp } = definition;
+    if (!definition.managed || !imap || !smtp) continue;
+    if (imap.auth_method !== "password") {
+      console.info(`Managed account ${definition.id} uses OAuth; add it to sign in`);
+      continue;
+    }
+    if (await getAccount(definition.id)) continue;
+
+    await insertImapAccount({
+      id: definition.id,
+      email: definition.email ?? imap.username,
+      displayName: definition.display_name,
+      avatarUrl: null,
+      imapHost: imap.host,
+      imapPort: imap.port,
+      imapSecurity: imap.security === "tls" ? "ssl" : imap.security,
+      smtpHost: smtp.host,
+      smtpPort: smtp.port,
+      smtpSecurity: smtp.security === "tls" ? "ssl" : smtp.security,
+      authMethod: "password",
+      password: "",
+      imapUsername: imap.username,
+      acceptInvalidCerts: imap.accept_invalid_certs,
+      authorizeAs: imap.authorize_as ?? null,
+      delegation: imap.delegation ?? null,
+      askPassword: true,
+    });
+    added.push(definition.id);
+  }
+  return added;
+}
@@ -0,0 +1,65 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+
+vi.mock("./db/messages", () => ({
+  getMessage: vi.fn(),
+  setMessagePinned: vi.fn(),
+}));
+
+vi.mock("./db/settings", () => ({
+  getSetting: vi.fn(),
+}));
+
+vi.mock("./imap/tauriCommands", () => ({
+  imapSetFlags: vi.fn(),
+}));
+
+import { getMessage, setMessagePinned } from "./db/messages";
+import { getSetting } from "./db/settings";
+import { imapSetFlags } from "./imap/tauriCommands";
+import { setPinned } from "./messagePins";
+
+describe("setPinned", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    vi.mocked(getMessage).mockResolvedValue({
+      id: "msg-1",
+      imap_uid: 42,
+      imap_folder: "INBOX",
+    } as Awaited<ReturnType<typeof getMessage>>);
+  });
+
+  it("pins locally and tells open views", async () => {
+    vi.mocked(getSetting).mockResolvedValue(null);
+    const listener = vi.fn();
+    window.addEventListener("velo-message-pinned", listener);
+
+    await setPinned("acct-1", "msg-1", true);
+
+    window.removeEventListener("velo-message-pinned", listener);
+    expect(setMessagePinned).toHaveBeenCalledWith("acct-1", "msg-1", true);
+    expect((listener.mock.calls[0]![0] as CustomEvent).detail).toEqual({
+      messageId: "msg-1",
+      pinned: true,
+    });
+    expect(imapSetFlags).not.toHaveBeenCalled();
+  });
+
+  it("mirrors the pin as a keyword when enabled", async () => {
+    vi.mocked(getSetting).mockResolvedValue("true");
+
+    await setPinned("acct-1", "msg-1", false);
+
+    expect(imapSetFlags).toHaveBeenCalledWith("acct-1", "INBOX", [42], ["$Pinned"], false);
+  });
+
+  it("keeps the local pin when the server refuses the keyword", async () => {
+    vi.mocked(getSetting).mockResolvedValue("true");
+    vi.mocked(imapSetFlags).mockRejectedValue(new Error("PERMANENTFLAGS"));
+    const warn = vi.spyOn(console, "warn").mockImplementation(() => {});
+
+    await expect(setPinned("acct-1", "msg-1", true)).resolves.toBeUndefined();
+
+    expect(setMessagePinned).toHaveBeenCalledWith("acct-1", "msg-1", true);
+    warn.mockRestore();
+  });
+});
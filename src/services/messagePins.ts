@@ -0,0 +1,39 @@
+import { getMessage, setMessagePinned } from "./db/messages";
+import { getSetting } from "./db/settings";
+import { imapSetFlags } from "./imap/tauriCommands";
+
+/**
+ * Pinned messages stay at the top of their folder. Pins live in the local
+ * database; with the `mirror_pins_imap` setting on, IMAP messages also get
+ * the `$Pinned` keyword so other clients that know it can show the pin.
+ */
+
+export const PINNED_KEYWORD = "$Pinned";
+
+export interface MessagePinnedDetail {
+  messageId: string;
+  pinned: boolean;
+}
+
+export async function setPinned(
+  accountId: string,
+  messageId: string,
+  pinned: boolean,
+): Promise<void> {
+  await setMessagePinned(accountId, messageId, pinned);
+  window.dispatchEvent(
+    new CustomEvent<MessagePinnedDetail>("velo-message-pinned", {
+      detail: { messageId, pinned },
+    }),
+  );
+
+  if ((await getSetting("mirror_pins_imap")) !== "true") return;
+  const message = await getMessage(accountId, messageId);
+  if (message?.imap_uid == null || !message.imap_folder) return;
+  try {
+    await imapSetFlags(accountId, message.imap_folder, [message.imap_uid], [PINNED_KEYWORD], pinned);
+  } catch (err) {
+    // The server may not allow custom keywords; the local pin still stands
+    console.warn(`Failed to mirror pin for ${messageId}:`, err);
+  }
+}
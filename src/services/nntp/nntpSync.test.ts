@@ -0,0 +1,62 @@
+import { describe, it, expect } from "vitest";
+import { articleRange, buildNntpConfig, INITIAL_ARTICLES } from "./nntpSync";
+import { createMockImapAccount } from "@/test/mocks";
+
+describe("articleRange", () => {
+  it("starts a new group with its newest articles", () => {
+    expect(articleRange(1, 5000, null)).toEqual({
+      start: 5000 - INITIAL_ARTICLES + 1,
+      end: 5000,
+    });
+    expect(articleRange(40, 60, null)).toEqual({ start: 40, end: 60 });
+  });
+
+  it("continues after the last article seen", () => {
+    expect(articleRange(1, 5000, 4990)).toEqual({ start: 4991, end: 5000 });
+    expect(articleRange(1, 5000, 5000)).toBeNull();
+  });
+
+  it("skips articles that expired since the last sync", () => {
+    expect(articleRange(4995, 5000, 4990)).toEqual({ start: 4995, end: 5000 });
+  });
+
+  it("restarts when the server's numbers went backwards", () => {
+    expect(articleRange(1, 100, 5000)).toEqual({ start: 1, end: 100 });
+  });
+
+  it("has nothing to fetch from an empty group", () => {
+    expect(articleRange(101, 100, null)).toBeNull();
+    expect(articleRange(0, 0, null)).toBeNull();
+  });
+});
+
+describe("buildNntpConfig", () => {
+  it("reads the server from the IMAP columns", () => {
+    const account = createMockImapAccount({
+      provider: "nntp",
+      imap_host: "news.example.org",
+      imap_port: 563,
+      imap_security: "ssl",
+      imap_username: "reader",
+      imap_password: "pass",
+    });
+
+    expect(buildNntpConfig(account)).toEqual({
+      host: "news.example.org",
+      port: 563,
+      security: "tls",
+      username: "reader",
+      password: "pass",
+      accept_invalid_certs: false,
+    });
+  });
+
+  it("sends no credentials to anonymous servers", () => {
+    const account = createMockImapAccount({ provider: "nntp", imap_username: null });
+
+    const config = buildNntpConfig(account);
+
+    expect(config.username).toBe("");
+    expect(config.password).toBe("");
+  });
+});
@@ -0,0 +1,193 @@
+import type { ImapMessage } from "../imap/tauriCommands";
+import {
+  nntpFetchArticles,
+  nntpFetchOverview,
+  nntpGroupStatus,
+  registerNntpAccount,
+  type NntpConfig,
+} from "./tauriCommands";
+import { mapSecurity, moveImapPasswordToKeychain } from "../imap/imapConfigBuilder";
+import { imapMessageToParsedMessage, storeThreadsAndMessages } from "../imap/imapSync";
+import type { ParsedMessage } from "../gmail/messageParser";
+import type { SyncResult } from "../email/types";
+import { getAccount, updateAccountSyncState, type DbAccount } from "../db/accounts";
+import { deleteLabelsForFolder, getLabelsForAccount, upsertLabel } from "../db/labels";
+import {
+  deleteFolderSyncState,
+  getAllFolderSyncStates,
+  upsertFolderSyncState,
+} from "../db/folderSyncState";
+import { threadMessages, type ThreadableMessage } from "../threading/threadBuilder";
+
+/**
+ * Newsgroup accounts: each subscribed group is a folder label
+ * (`folder-<group>`, like an IMAP user folder) and its articles are stored
+ * as messages through the IMAP sync code, with the article number as UID.
+ * The news server has no read or starred state, so those stay local.
+ */
+
+/** Newest articles fetched when a group is first synced. */
+export const INITIAL_ARTICLES = 200;
+/** Most articles fetched per group in one sync, newest first. */
+const MAX_ARTICLES_PER_SYNC = 1000;
+const BATCH_SIZE = 50;
+
+export function groupLabelId(group: string): string {
+  return `folder-${group}`;
+}
+
+/**
+ * Build an NntpConfig from the account's server columns (the IMAP ones).
+ * Anonymous accounts have no username and send no password.
+ */
+export function buildNntpConfig(account: DbAccount): NntpConfig {
+  if (!account.imap_host) {
+    throw new Error(`Account ${account.id} has no news server configured`);
+  }
+  const username = account.imap_username ?? "";
+  return {
+    host: account.imap_host,
+    port: account.imap_port ?? 563,
+    security: mapSecurity(account.imap_security),
+    username,
+    password: username ? (account.imap_password ?? "") : "",
+    accept_invalid_certs: !!account.accept_invalid_certs,
+  };
+}
+
+/**
+ * Register the account's news server with the backend, moving its
+ * password to the keychain first. Returns the account id.
+ */
+export async function registerNntpCredentials(account: DbAccount): Promise<string> {
+  if (account.imap_username) {
+    account = await moveImapPasswordToKeychain(account);
+  }
+  await registerNntpAccount(account.id, buildNntpConfig(account), {
+    email: account.email,
+    displayName: account.display_name,
+  });
+  return account.id;
+}
+
+/** The groups the account is subscribed to. */
+export async function getSubscribedGroups(accountId: string): Promise<string[]> {
+  const labels = await getLabelsForAccount(accountId);
+  return labels
+    .filter((l) => l.imap_folder_path && l.id === groupLabelId(l.imap_folder_path))
+    .map((l) => l.imap_folder_path!);
+}
+
+export async function subscribeGroup(accountId: string, group: string): Promise<void> {
+  await upsertLabel({
+    id: groupLabelId(group),
+    accountId,
+    name: group,
+    type: "user",
+    imapFolderPath: group,
+  });
+}
+
+/** Remove a group's folder; its articles stay in All Mail. */
+export async function unsubscribeGroup(accountId: string, group: string): Promise<void> {
+  await deleteLabelsForFolder(accountId, group);
+  await deleteFolderSyncState(accountId, group);
+}
+
+/**
+ * The article range to fetch: everything after `lastSeen`, or the newest
+ * INITIAL_ARTICLES for a new group. A server whose numbers went backwards
+ * (renumbered or restored) is treated like a new group.
+ */
+export function articleRange(
+  first: number,
+  last: number,
+  lastSeen: number | null,
+): { start: number; end: number } | null {
+  if (last < first || last === 0) return null;
+  const start = lastSeen === null || lastSeen > last
+    ? Math.max(first, last - INITIAL_ARTICLES + 1)
+    : Math.max(first, lastSeen + 1, last - MAX_ARTICLES_PER_SYNC + 1);
+  return start <= last ? { start, end: last } : null;
+}
+
+/** Fetch new articles of every subscribed group and store them as threads. */
+export async function nntpSync(accountId: string): Promise<SyncResult> {
+  const account = await getAccount(accountId);
+  if (!account) {
+    throw new Error(`Account ${accountId} not found`);
+  }
+  await registerNntpCredentials(account);
+
+  const groups = await getSubscribedGroups(accountId);
+  const syncStates = new Map(
+    (await getAllFolderSyncStates(accountId)).map((s) => [s.folder_path, s]),
+  );
+
+  const allParsed = new Map<string, ParsedMessage>();
+  const allThreadable: ThreadableMessage[] = [];
+  const allArticles = new Map<string, ImapMessage>();
+
+  for (const group of groups) {
+    try {
+      const status = await nntpGroupStatus(accountId, group);
+      const range = articleRange(
+        status.first,
+        status.last,
+        syncStates.get(group)?.last_uid ?? null,
+      );
+
+      if (range) {
+        // The overview skips expired and cancelled articles without fetching them
+        const overview = await nntpFetchOverview(accountId, group, range.start, range.end);
+        const numbers = overview.map((o) => o.number);
+        for (let i = 0; i < numbers.length; i += BATCH_SIZE) {
+          const articles = await nntpFetchArticles(accountId, group, numbers.slice(i, i + BATCH_SIZE));
+          for (const article of articles) {
+            const { parsed, threadable } = imapMessageToParsedMessage(
+              article,
+              accountId,
+              groupLabelId(group),
+            );
+            allParsed.set(parsed.id, parsed);
+            allThreadable.push(threadable);
+            allArticles.set(parsed.id, article);
+          }
+        }
+      }
+
+      await upsertFolderSyncState({
+        account_id: accountId,
+        folder_path: group,
+        uidvalidity: null,
+        last_uid: status.last,
+        modseq: null,
+        last_sync_at: Math.floor(Date.now() / 1000),
+      });
+    } catch (err) {
+      console.error(`[nntpSync] Sync failed for group ${group}:`, err);
+    }
+  }
+
+  await updateAccountSyncState(accountId, `nntp-synced-${Date.now()}`);
+  if (allThreadable.length === 0) {
+    return { messages: [] };
+  }
+
+  // Crossposted articles appear once per group; their thread gets every group
+  const labelsByRfcId = new Map<string, Set<string>>();
+  for (const threadable of allThreadable) {
+    const labels = labelsByRfcId.get(threadable.messageId) ?? new Set<string>();
+    for (const lid of allParsed.get(threadable.id)?.labelIds ?? []) labels.add(lid);
+    labelsByRfcId.set(threadable.messageId, labels);
+  }
+
+  const messages = await storeThreadsAndMessages(
+    accountId,
+    await threadMessages(accountId, allThreadable),
+    allParsed,
+    allArticles,
+    labelsByRfcId,
+  );
+  return { messages };
+}
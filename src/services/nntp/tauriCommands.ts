@@ -0,0 +1,128 @@
+import { commands } from '@/bindings';
+import { unwrap } from '../commandResult';
+import type { AccountDefinition, ImapMessage } from '../imap/tauriCommands';
+
+// ---------- NNTP types ----------
+
+export interface NntpConfig {
+  host: string;
+  port: number;
+  security: 'tls' | 'starttls' | 'none';
+  /** Empty for servers that allow reading without signing in */
+  username: string;
+  password: string;
+  accept_invalid_certs?: boolean;
+}
+
+export interface NntpGroup {
+  name: string;
+  first: number;
+  last: number;
+  posting_allowed: boolean;
+}
+
+export interface NntpGroupStatus {
+  name: string;
+  count: number;
+  first: number;
+  last: number;
+}
+
+export interface NntpOverview {
+  number: number;
+  subject: string;
+  from: string;
+  date: string;
+  message_id: string;
+  references: string;
+  bytes: number;
+  lines: number;
+}
+
+// ---------- NNTP commands ----------
+
+/**
+ * Hand a newsgroup account's server to the backend; the commands below
+ * take the account id. Register again to replace it.
+ */
+export async function registerNntpAccount(
+  accountId: string,
+  nntp: NntpConfig,
+  details: { email?: string; displayName?: string | null } = {},
+): Promise<void> {
+  const definition: AccountDefinition = {
+    id: accountId,
+    email: details.email ?? null,
+    display_name: details.displayName ?? null,
+    imap: null,
+    smtp: null,
+    nntp,
+    ask_password: false,
+    oauth: null,
+  };
+  return unwrap<void>(await commands.accountRegister(definition));
+}
+
+export async function nntpTestConnection(config: NntpConfig): Promise<string> {
+  return unwrap<string>(await commands.nntpTestConnection(config));
+}
+
+/** Groups matching the wildmat `pattern` (e.g. "comp.lang.*"), or all of them. */
+export async function nntpListGroups(
+  accountId: string,
+  pattern: string | null = null,
+): Promise<NntpGroup[]> {
+  return unwrap<NntpGroup[]>(await commands.nntpListGroups(accountId, pattern));
+}
+
+export async function nntpGroupStatus(
+  accountId: string,
+  group: string,
+): Promise<NntpGroupStatus> {
+  return unwrap<NntpGroupStatus>(await commands.nntpGroupStatus(accountId, group));
+}
+
+export async function nntpFetchOverview(
+  accountId: string,
+  group: string,
+  first: number,
+  last: number,
+): Promise<NntpOverview[]> {
+  return unwrap<NntpOverview[]>(await commands.nntpFetchOverview(accountId, group, first, last));
+}
+
+/** Articles parsed as messages: `uid` is the article number, `folder` the group. */
+export async function nntpFetchArticles(
+  accountId: string,
+  group: string,
+  numbers: number[],
+): Promise<ImapMessage[]> {
+  return unwrap<ImapMessage[]>(await commands.nntpFetchArticles(accountId, group, numbers));
+}
+
+export async function nntpFetchRawArticle(
+  accountId: string,
+  group: string,
+  number: number,
+): Promise<string> {
+  return unwrap<string>(await commands.nntpFetchRawArticle(accountId, group, number));
+}
+
+/** An attachment's decoded bytes, base64-encoded. */
+export async function nntpFetchAttachment(
+  accountId: string,
+  group: string,
+  number: number,
+  partId: string,
+): Promise<string> {
+  return unwrap<string>(await commands.nntpFetchAttachment(accountId, group, number, partId));
+}
+
+/** Post a composed message (base64url) to `newsgroups`. */
+export async function nntpPostArticle(
+  accountId: string,
+  rawArticle: string,
+  newsgroups: string[],
+): Promise<void> {
+  return unwrap<void>(await commands.nntpPostArticle(accountId, rawArticle, newsgroups));
+}
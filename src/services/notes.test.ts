@@ -0,0 +1,166 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+
+vi.mock("./db/accounts", () => ({
+  getAccount: vi.fn(),
+}));
+
+vi.mock("./db/notes", () => ({
+  deleteNote: vi.fn(),
+  importNotes: vi.fn(),
+  listNotes: vi.fn(),
+  markNotesSynced: vi.fn(),
+  saveNote: vi.fn(),
+}));
+
+vi.mock("./db/settings", () => ({
+  getSetting: vi.fn(),
+}));
+
+vi.mock("./imap/tauriCommands", () => ({
+  imapAppendMessage: vi.fn(),
+  imapCreateFolder: vi.fn(),
+  imapDeleteMessages: vi.fn(),
+  imapFetchMessages: vi.fn(),
+  imapListFolders: vi.fn(),
+  imapSearchAllUids: vi.fn(),
+  imapSearchText: vi.fn(),
+}));
+
+import { getAccount } from "./db/accounts";
+import { deleteNote, importNotes, listNotes, markNotesSynced, saveNote, type Note } from "./db/notes";
+import { getSetting } from "./db/settings";
+import {
+  imapAppendMessage,
+  imapCreateFolder,
+  imapDeleteMessages,
+  imapFetchMessages,
+  imapListFolders,
+  imapSearchAllUids,
+  imapSearchText,
+  type ImapMessage,
+} from "./imap/tauriCommands";
+import { buildNoteMessage, parseNoteMessage, syncNotes, writeNote } from "./notes";
+
+function note(overrides: Partial<Note> = {}): Note {
+  return {
+    id: "abc123",
+    account_id: "acct-1",
+    message_id_header: "orig@example.com",
+    thread_id: "thread-1",
+    body: "Call Ana back — she has the contract",
+    created_at: 1700000000,
+    updated_at: 1700000000,
+    synced_at: null,
+    ...overrides,
+  };
+}
+
+function decodeBody(raw: string): string {
+  const body = raw.split("\r\n\r\n")[1]!.replace(/\r\n/g, "");
+  return new TextDecoder().decode(Uint8Array.from(atob(body), (c) => c.charCodeAt(0)));
+}
+
+describe("note messages", () => {
+  it("builds a message linked to the noted message", () => {
+    const raw = buildNoteMessage(note(), "me@example.com");
+    expect(raw).toContain("Message-ID: <velo-note-abc123@example.com>");
+    expect(raw).toContain("In-Reply-To: <orig@example.com>");
+    expect(raw).toContain("Content-Type: text/plain; charset=UTF-8");
+    expect(decodeBody(raw)).toBe("Call Ana back — she has the contract");
+  });
+
+  it("reads a note back and ignores other messages", () => {
+    const message = {
+      message_id: "velo-note-abc123@example.com",
+      in_reply_to: "<orig@example.com>",
+      body_text: "Call Ana back\r\n",
+      date: 1700000500,
+    } as ImapMessage;
+    expect(parseNoteMessage("acct-1", message)).toMatchObject({
+      id: "abc123",
+      message_id_header: "orig@example.com",
+      body: "Call Ana back",
+      updated_at: 1700000500,
+    });
+    expect(parseNoteMessage("acct-1", { ...message, message_id: "apple-note@icloud.com" })).toBeNull();
+  });
+});
+
+describe("writeNote", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    vi.mocked(saveNote).mockResolvedValue(note());
+    vi.mocked(getAccount).mockResolvedValue({ provider: "imap", email: "me@example.com" } as Awaited<
+      ReturnType<typeof getAccount>
+    >);
+  });
+
+  it("only saves locally when notes don't sync", async () => {
+    vi.mocked(getSetting).mockResolvedValue(null);
+    await writeNote("acct-1", { threadId: "thread-1", body: "hi" });
+    expect(saveNote).toHaveBeenCalled();
+    expect(imapAppendMessage).not.toHaveBeenCalled();
+  });
+
+  it("replaces the server copy when notes sync", async () => {
+    vi.mocked(getSetting).mockResolvedValue("true");
+    vi.mocked(imapListFolders).mockResolvedValue([]);
+    vi.mocked(imapCreateFolder).mockResolvedValue("Notes");
+    vi.mocked(imapSearchText).mockResolvedValue([7]);
+
+    await writeNote("acct-1", { id: "abc123", body: "changed" });
+
+    expect(imapCreateFolder).toHaveBeenCalledWith("acct-1", "Notes");
+    expect(imapSearchText).toHaveBeenCalledWith("acct-1", "Notes", "velo-note-abc123", null);
+    expect(imapAppendMessage).toHaveBeenCalledWith("acct-1", "Notes", expect.any(String), "(\\Seen)");
+    expect(imapDeleteMessages).toHaveBeenCalledWith("acct-1", "Notes", [7]);
+    expect(markNotesSynced).toHaveBeenCalledWith("acct-1", ["abc123"], 1700000000);
+  });
+});
+
+describe("syncNotes", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    vi.mocked(getSetting).mockResolvedValue("true");
+    vi.mocked(getAccount).mockResolvedValue({ provider: "imap", email: "me@example.com" } as Awaited<
+      ReturnType<typeof getAccount>
+    >);
+    vi.mocked(imapListFolders).mockResolvedValue([
+      { path: "Notes", raw_path: "Notes" } as Awaited<ReturnType<typeof imapListFolders>>[number],
+    ]);
+    vi.mocked(imapSearchText).mockResolvedValue([]);
+  });
+
+  it("imports server notes, drops ones deleted elsewhere and pushes local changes", async () => {
+    vi.mocked(imapSearchAllUids).mockResolvedValue([1]);
+    vi.mocked(imapFetchMessages).mockResolvedValue({
+      messages: [
+        {
+          message_id: "velo-note-remote1@example.com",
+          in_reply_to: null,
+          body_text: "From the laptop",
+          date: 1700000100,
+        } as ImapMessage,
+      ],
+    } as Awaited<ReturnType<typeof imapFetchMessages>>);
+    vi.mocked(importNotes).mockResolvedValue(1);
+    vi.mocked(listNotes).mockResolvedValue([
+      note({ id: "remote1", updated_at: 1700000100, synced_at: 1700000100 }),
+      note({ id: "gone", updated_at: 1700000000, synced_at: 1700000000 }),
+      note({ id: "local", synced_at: null }),
+    ]);
+
+    await syncNotes("acct-1");
+
+    expect(vi.mocked(importNotes).mock.calls[0]![1].map((n) => n.id)).toEqual(["remote1"]);
+    expect(deleteNote).toHaveBeenCalledWith("acct-1", "gone");
+    expect(imapAppendMessage).toHaveBeenCalledTimes(1);
+    expect(markNotesSynced).toHaveBeenCalledWith("acct-1", ["local"], 1700000000);
+  });
+
+  it("does nothing for accounts that aren't IMAP", async () => {
+    vi.mocked(getAccount).mockResolvedValue({ provider: "gmail_api" } as Awaited<ReturnType<typeof getAccount>>);
+    await syncNotes("acct-1");
+    expect(imapListFolders).not.toHaveBeenCalled();
+  });
+});
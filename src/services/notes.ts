@@ -0,0 +1,196 @@
+import { getAccount } from "./db/accounts";
+import {
+  deleteNote,
+  importNotes,
+  listNotes,
+  markNotesSynced,
+  saveNote,
+  type Note,
+} from "./db/notes";
+import { getSetting } from "./db/settings";
+import {
+  imapAppendMessage,
+  imapCreateFolder,
+  imapDeleteMessages,
+  imapFetchMessages,
+  imapListFolders,
+  imapSearchAllUids,
+  imapSearchText,
+  type ImapMessage,
+} from "./imap/tauriCommands";
+
+/**
+ * Notes on messages and threads. They're kept locally; with the
+ * `sync_notes_imap` setting on, each note of an IMAP account also lives as
+ * a plain-text message in the account's Notes folder, so the user's other
+ * devices see it. The copy's Message-ID carries the note id, and its
+ * In-Reply-To the Message-ID of the message the note is on. A changed note
+ * replaces its copy; the copy with the newest Date wins.
+ */
+
+export const NOTES_FOLDER = "Notes";
+
+const NOTE_ID_PREFIX = "velo-note-";
+const NOTE_MESSAGE_ID = /^<?velo-note-([0-9a-f]+)@[^>]*>?$/i;
+
+export interface NotesChangedDetail {
+  accountId: string;
+  threadId: string | null;
+}
+
+function notifyChanged(accountId: string, threadId: string | null): void {
+  window.dispatchEvent(
+    new CustomEvent<NotesChangedDetail>("velo-notes-changed", {
+      detail: { accountId, threadId },
+    }),
+  );
+}
+
+function base64Encode(text: string): string {
+  let binary = "";
+  for (const b of new TextEncoder().encode(text)) binary += String.fromCharCode(b);
+  return btoa(binary);
+}
+
+/** The server copy of a note, as an RFC 2822 message (not yet encoded). */
+export function buildNoteMessage(note: Note, fromAddress: string): string {
+  const domain = fromAddress.split("@")[1] || "velomail.local";
+  const firstLine = note.body.split("\n")[0]!.trim();
+  const title = firstLine.length > 60 ? `${firstLine.slice(0, 60)}…` : firstLine;
+  const lines = [
+    `From: ${fromAddress}`,
+    `To: ${fromAddress}`,
+    `Date: ${new Date(note.updated_at * 1000).toUTCString()}`,
+    `Message-ID: <${NOTE_ID_PREFIX}${note.id}@${domain}>`,
+    `Subject: =?UTF-8?B?${base64Encode(`Note: ${title}`)}?=`,
+  ];
+  if (note.message_id_header) {
+    lines.push(`In-Reply-To: <${note.message_id_header}>`);
+    lines.push(`References: <${note.message_id_header}>`);
+  }
+  lines.push("MIME-Version: 1.0");
+  lines.push("Content-Type: text/plain; charset=UTF-8");
+  lines.push("Content-Transfer-Encoding: base64");
+  lines.push("");
+  const body = base64Encode(note.body);
+  for (let i = 0; i < body.length; i += 76) lines.push(body.slice(i, i + 76));
+  return lines.join("\r\n");
+}
+
+/** The note a message in the Notes folder holds, or null if it isn't one of ours. */
+export function parseNoteMessage(accountId: string, message: ImapMessage): Note | null {
+  const id = message.message_id?.trim().match(NOTE_MESSAGE_ID)?.[1];
+  if (!id) return null;
+  const body = (message.body_text ?? "").replace(/\r\n/g, "\n").trimEnd();
+  if (!body) return null;
+  const linked = message.in_reply_to?.trim().replace(/^<|>$/g, "") || null;
+  return {
+    id: id.toLowerCase(),
+    account_id: accountId,
+    message_id_header: linked,
+    thread_id: null,
+    body,
+    created_at: message.date,
+    updated_at: message.date,
+    synced_at: message.date,
+  };
+}
+
+/** The email address to sync notes for, when the account syncs them. */
+async function syncAddress(accountId: string): Promise<string | null> {
+  if ((await getSetting("sync_notes_imap")) !== "true") return null;
+  const account = await getAccount(accountId);
+  return account?.provider === "imap" ? account.email : null;
+}
+
+/** The Notes folder's raw path, created on the server when missing. */
+async function notesFolder(accountId: string): Promise<string> {
+  const folders = await imapListFolders(accountId);
+  const existing = folders.find((f) => f.path.toLowerCase() === NOTES_FOLDER.toLowerCase());
+  if (existing) return existing.raw_path;
+  return imapCreateFolder(accountId, NOTES_FOLDER);
+}
+
+/** Replace the server copy of a note. */
+async function pushNote(accountId: string, folder: string, address: string, note: Note): Promise<void> {
+  const previous = await imapSearchText(accountId, folder, `${NOTE_ID_PREFIX}${note.id}`, null);
+  const raw = base64Encode(buildNoteMessage(note, address))
+    .replace(/\+/g, "-")
+    .replace(/\//g, "_")
+    .replace(/=+$/, "");
+  await imapAppendMessage(accountId, folder, raw, "(\\Seen)");
+  if (previous.length > 0) await imapDeleteMessages(accountId, folder, previous);
+  await markNotesSynced(accountId, [note.id], note.updated_at);
+}
+
+/**
+ * Add a note or change one, and copy it to the server when notes sync. A
+ * failed copy is retried by the next `syncNotes`.
+ */
+export async function writeNote(
+  accountId: string,
+  input: { id?: string | null; messageIdHeader?: string | null; threadId?: string | null; body: string },
+): Promise<Note> {
+  const note = await saveNote(accountId, input);
+  notifyChanged(accountId, note.thread_id);
+  const address = await syncAddress(accountId);
+  if (address) {
+    try {
+      await pushNote(accountId, await notesFolder(accountId), address, note);
+    } catch (err) {
+      console.warn(`Failed to sync note ${note.id}:`, err);
+    }
+  }
+  return note;
+}
+
+/** Delete a note, and its server copy when notes sync. */
+export async function removeNote(accountId: string, id: string): Promise<void> {
+  const note = await deleteNote(accountId, id);
+  if (!note) return;
+  notifyChanged(accountId, note.thread_id);
+  if (note.synced_at == null) return;
+  if (!(await syncAddress(accountId))) return;
+  try {
+    const folder = await notesFolder(accountId);
+    const uids = await imapSearchText(accountId, folder, `${NOTE_ID_PREFIX}${note.id}`, null);
+    if (uids.length > 0) await imapDeleteMessages(accountId, folder, uids);
+  } catch (err) {
+    console.warn(`Failed to delete the server copy of note ${id}:`, err);
+  }
+}
+
+/**
+ * Bring an account's notes in line with its Notes folder: take notes added
+ * or changed on other devices, drop local ones deleted there, and copy up
+ * notes changed here since they were last synced. Does nothing unless the
+ * account syncs notes.
+ */
+export async function syncNotes(accountId: string): Promise<void> {
+  const address = await syncAddress(accountId);
+  if (!address) return;
+  const folder = await notesFolder(accountId);
+
+  const uids = await imapSearchAllUids(accountId, folder);
+  const messages = uids.length > 0 ? (await imapFetchMessages(accountId, folder, uids)).messages : [];
+  const remote = messages
+    .map((m) => parseNoteMessage(accountId, m))
+    .filter((n): n is Note => n !== null);
+  const remoteIds = new Set(remote.map((n) => n.id));
+  const changed = remote.length > 0 ? await importNotes(accountId, remote) : 0;
+
+  let deleted = 0;
+  for (const note of await listNotes(accountId)) {
+    const unchangedSinceSync = note.synced_at != null && note.updated_at <= note.synced_at;
+    if (remoteIds.has(note.id)) {
+      if (!unchangedSinceSync) await pushNote(accountId, folder, address, note);
+    } else if (unchangedSinceSync) {
+      // Synced before and gone from the server: deleted on another device
+      await deleteNote(accountId, note.id);
+      deleted++;
+    } else {
+      await pushNote(accountId, folder, address, note);
+    }
+  }
+  if (changed > 0 || deleted > 0) notifyChanged(accountId, null);
+}
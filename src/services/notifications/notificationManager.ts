@@ -10,6 +10,8 @@ import { WebviewWindow } from "@tauri-apps/api/webviewWindow";
 import { useComposerStore } from "../../stores/composerStore";
 import { navigateToLabel } from "../../router/navigate";
 import { normalizeEmail } from "@/utils/emailUtils";
+import { copyOtpCode } from "@/services/extraction/otp";
+import { notifyNewMail } from "./notificationRules";
 
 let initialized = false;
 let notificationsEnabled = true;
@@ -19,6 +21,8 @@ interface NotificationContext {
   accountId?: string;
   fromAddress?: string;
   subject?: string;
+  /** One-time code found in the message */
+  code?: string;
 }
 
 let lastNotificationContext: NotificationContext | null = null;
@@ -69,13 +73,23 @@ export async function initNotifications(): Promise<void> {
           { id: "archive", title: "Archive" },
         ],
       },
+      {
+        id: "otp",
+        actions: [{ id: "copy-code", title: "Copy code" }],
+      },
     ]);
 
     await onAction(async (event) => {
       const actionId = event.actionTypeId;
       const ctx = lastNotificationContext;
 
-      if (actionId === "reply" && ctx?.threadId && ctx?.accountId) {
+      if (actionId === "copy-code" && ctx?.code) {
+        try {
+          await copyOtpCode(ctx.code);
+        } catch (err) {
+          console.error("Failed to copy code from notification:", err);
+        }
+      } else if (actionId === "reply" && ctx?.threadId && ctx?.accountId) {
         await showAndFocusMainWindow();
         useComposerStore.getState().openComposer({
           mode: "reply",
@@ -103,47 +117,81 @@ export async function initNotifications(): Promise<void> {
 }
 
 /**
- * Show a notification for new emails.
- * Batches notifications to avoid spam during sync.
+ * Show a notification for new emails. The backend's notification rules
+ * decide how (see `notificationRules.ts`): VIP senders right away, others
+ * batched per sync or into digests, and nothing but VIPs during Do Not
+ * Disturb. Messages with a one-time code are shown right away, code
+ * included, with a "Copy code" action.
  */
-let pendingCount = 0;
-let notifyTimer: ReturnType<typeof setTimeout> | null = null;
-
 export function queueNewEmailNotification(
   from: string,
   subject: string,
   threadId?: string,
   accountId?: string,
   fromAddress?: string,
+  code?: string | null,
 ): void {
   if (!notificationsEnabled) return;
 
-  pendingCount++;
+  if (code) {
+    const ctx = { threadId, accountId, fromAddress, subject, code };
+    lastNotificationContext = ctx;
+    if (threadId) recentContexts.set(threadId, ctx);
+    sendNotification({
+      title: from,
+      body: `Code: ${code}${subject ? ` — ${subject}` : ""}`,
+      actionTypeId: "otp",
+    });
+    return;
+  }
 
   // Store context for action handling
   const ctx = { threadId, accountId, fromAddress, subject };
   lastNotificationContext = ctx;
   if (threadId) recentContexts.set(threadId, ctx);
 
-  // Debounce: wait 2s before showing, to batch during sync
-  if (notifyTimer) clearTimeout(notifyTimer);
-  notifyTimer = setTimeout(() => {
-    if (pendingCount === 1) {
-      sendNotification({
-        title: from,
-        body: subject || "(No subject)",
-        actionTypeId: "email",
-      });
-    } else if (pendingCount > 1) {
-      sendNotification({
-        title: "Velo",
-        body: `${pendingCount} new emails`,
-        actionTypeId: "email",
-      });
+  notifyNewMail({
+    account_id: accountId ?? "",
+    thread_id: threadId ?? null,
+    from_name: from,
+    from_address: fromAddress ?? null,
+    subject: subject || null,
+  }).catch((err) => console.error("Failed to notify about new mail:", err));
+}
+
+/**
+ * Queue a new-mail notification after a grace period, giving the provider's
+ * spam filter time to reclassify the message. `isStillWanted` is checked
+ * once the period ends; if it reports the message moved to Junk (or the
+ * check says no), the notification is dropped. Check failures still notify.
+ */
+export function queueNewEmailNotificationAfterGrace(
+  graceMs: number,
+  isStillWanted: () => Promise<boolean>,
+  from: string,
+  subject: string,
+  threadId?: string,
+  accountId?: string,
+  fromAddress?: string,
+  code?: string | null,
+): void {
+  if (!notificationsEnabled) return;
+  if (graceMs <= 0) {
+    queueNewEmailNotification(from, subject, threadId, accountId, fromAddress, code);
+    return;
+  }
+
+  setTimeout(async () => {
+    let wanted = true;
+    try {
+      wanted = await isStillWanted();
+    } catch (err) {
+      console.warn("Junk re-check failed, notifying anyway:", err);
+    }
+    if (wanted) {
+      queueNewEmailNotification(from, subject, threadId, accountId, fromAddress, code);
     }
-    pendingCount = 0;
-    notifyTimer = null;
-  }, 2000);
+  }, graceMs);
 }
 
 /**
@@ -0,0 +1,15 @@
+import { describe, it, expect } from "vitest";
+import { formatMinutes, parseMinutes } from "./notificationRules";
+
+describe("quiet hour times", () => {
+  it("formats minutes after midnight", () => {
+    expect(formatMinutes(0)).toBe("00:00");
+    expect(formatMinutes(22 * 60 + 30)).toBe("22:30");
+  });
+
+  it("parses time input values", () => {
+    expect(parseMinutes("07:05")).toBe(425);
+    expect(parseMinutes("24:00")).toBeNull();
+    expect(parseMinutes("")).toBeNull();
+  });
+});
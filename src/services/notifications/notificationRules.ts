@@ -0,0 +1,62 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { NotificationSound } from "./notificationSounds";
+
+/**
+ * Notification rules applied by the backend (`notify_rules.rs`): VIP
+ * senders notify at once with their own sound, even during Do Not
+ * Disturb; other mail can be collected into periodic digests.
+ */
+export interface NotificationRules {
+  /** Do Not Disturb: only VIP senders notify */
+  dnd: boolean;
+  /** Daily quiet hours in minutes after local midnight; a start after the end spans midnight */
+  quiet_start: number | null;
+  quiet_end: number | null;
+  /** Collect other notifications into one digest this often; null shows them as they come */
+  digest_minutes: number | null;
+  /** Sound for VIP notifications */
+  vip_sound: NotificationSound;
+  /** Sound for other senders, batches and digests */
+  sound: NotificationSound;
+  /** Per-account replacements for `sound`, by account id */
+  account_sounds: Record<string, NotificationSound>;
+}
+
+export interface MailNotice {
+  account_id: string;
+  thread_id: string | null;
+  from_name: string | null;
+  from_address: string | null;
+  subject: string | null;
+}
+
+/** What the backend did with a notice. */
+export type Delivery = "shown" | "batched" | "digest" | "suppressed";
+
+export function notifyNewMail(notice: MailNotice): Promise<Delivery> {
+  return invoke<Delivery>("notify_new_mail", { notice });
+}
+
+export function getNotificationRules(): Promise<NotificationRules> {
+  return invoke<NotificationRules>("get_notification_rules");
+}
+
+export function setNotificationRules(rules: NotificationRules): Promise<void> {
+  return invoke("set_notification_rules", { rules });
+}
+
+/** "22:30" for 1350 minutes after midnight. */
+export function formatMinutes(minutes: number): string {
+  const h = Math.floor(minutes / 60);
+  const m = minutes % 60;
+  return `${String(h).padStart(2, "0")}:${String(m).padStart(2, "0")}`;
+}
+
+/** Minutes after midnight for an "HH:MM" time input value, or null if it isn't one. */
+export function parseMinutes(value: string): number | null {
+  const match = /^(\d{1,2}):(\d{2})$/.exec(value.trim());
+  if (!match) return null;
+  const h = Number(match[1]);
+  const m = Number(match[2]);
+  return h < 24 && m < 60 ? h * 60 + m : null;
+}
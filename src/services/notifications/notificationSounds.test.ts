@@ -0,0 +1,20 @@
+import { describe, it, expect } from "vitest";
+import { soundFromKey, soundKey, type NotificationSound } from "./notificationSounds";
+
+describe("sound keys", () => {
+  it("round-trip every kind of sound", () => {
+    const sounds: NotificationSound[] = [
+      { kind: "silent" },
+      { kind: "system", name: "Glass" },
+      { kind: "bundled", id: "chime" },
+      { kind: "custom", file: "Ding: dong.wav" },
+    ];
+    for (const sound of sounds) {
+      expect(soundFromKey(soundKey(sound))).toEqual(sound);
+    }
+  });
+
+  it("treat unknown keys as silent", () => {
+    expect(soundFromKey("")).toEqual({ kind: "silent" });
+  });
+});
@@ -0,0 +1,68 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * Notification sounds (`notify_sounds.rs`). System sounds are played by the
+ * notification itself; bundled and added sound files are played by the
+ * backend, so they work with every window hidden.
+ */
+export type NotificationSound =
+  | { kind: "silent" }
+  | { kind: "system"; name: string }
+  | { kind: "bundled"; id: string }
+  | { kind: "custom"; file: string };
+
+export interface SoundOption {
+  sound: NotificationSound;
+  label: string;
+}
+
+/** File types that can be added as sounds; the backend checks which the OS player handles. */
+export const SOUND_FILE_EXTENSIONS = ["wav", "aiff", "aif", "mp3", "m4a", "caf", "ogg", "oga", "flac"];
+
+export function listNotificationSounds(): Promise<SoundOption[]> {
+  return invoke<SoundOption[]>("notification_sounds_list");
+}
+
+/** Copy a sound file into the profile. */
+export function addNotificationSound(path: string): Promise<SoundOption> {
+  return invoke<SoundOption>("notification_sound_add", { path });
+}
+
+export function removeNotificationSound(file: string): Promise<void> {
+  return invoke("notification_sound_remove", { file });
+}
+
+export function previewNotificationSound(sound: NotificationSound): Promise<void> {
+  return invoke("notification_sound_preview", { sound });
+}
+
+/** A stable string for a sound, e.g. as a `<select>` value. */
+export function soundKey(sound: NotificationSound): string {
+  switch (sound.kind) {
+    case "silent":
+      return "silent";
+    case "system":
+      return `system:${sound.name}`;
+    case "bundled":
+      return `bundled:${sound.id}`;
+    case "custom":
+      return `custom:${sound.file}`;
+  }
+}
+
+/** The sound for a key from `soundKey`. */
+export function soundFromKey(key: string): NotificationSound {
+  const sep = key.indexOf(":");
+  const kind = sep < 0 ? key : key.slice(0, sep);
+  const value = key.slice(sep + 1);
+  switch (kind) {
+    case "system":
+      return { kind: "system", name: value };
+    case "bundled":
+      return { kind: "bundled", id: value };
+    case "custom":
+      return { kind: "custom", file: value };
+    default:
+      return { kind: "silent" };
+  }
+}
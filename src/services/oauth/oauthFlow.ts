@@ -24,17 +24,14 @@ export interface ProviderUserInfo {
   picture?: string;
 }
 
-function generateCodeVerifier(): string {
-  const array = new Uint8Array(32);
-  crypto.getRandomValues(array);
-  return base64UrlEncode(array);
+export interface PkcePair {
+  code_verifier: string;
+  code_challenge: string;
 }
 
-async function generateCodeChallenge(verifier: string): Promise<string> {
-  const encoder = new TextEncoder();
-  const data = encoder.encode(verifier);
-  const digest = await crypto.subtle.digest("SHA-256", data);
-  return base64UrlEncode(new Uint8Array(digest));
+/** A PKCE verifier and its S256 challenge, generated by the backend. */
+export async function generatePkce(): Promise<PkcePair> {
+  return invoke<PkcePair>("oauth_generate_pkce");
 }
 
 function base64UrlEncode(bytes: Uint8Array): string {
@@ -61,8 +58,7 @@ export async function startProviderOAuthFlow(
   clientId: string,
   clientSecret?: string,
 ): Promise<{ tokens: TokenResponse; userInfo: ProviderUserInfo }> {
-  const codeVerifier = generateCodeVerifier();
-  const codeChallenge = await generateCodeChallenge(codeVerifier);
+  const { code_verifier: codeVerifier, code_challenge: codeChallenge } = await generatePkce();
 
   const stateArray = new Uint8Array(32);
   crypto.getRandomValues(stateArray);
@@ -5,7 +5,7 @@ vi.mock("../db/accounts", () => ({
 }));
 
 vi.mock("./providers", () => ({
-  getOAuthProvider: vi.fn(),
+  resolveOAuthProvider: vi.fn(),
 }));
 
 vi.mock("./oauthFlow", () => ({
@@ -14,7 +14,7 @@ vi.mock("./oauthFlow", () => ({
 
 import { ensureFreshToken } from "./oauthTokenManager";
 import { updateAccountTokens } from "../db/accounts";
-import { getOAuthProvider } from "./providers";
+import { resolveOAuthProvider } from "./providers";
 import { refreshProviderToken } from "./oauthFlow";
 import { createMockDbAccount } from "@/test/mocks";
 
@@ -63,7 +63,9 @@ describe("ensureFreshToken", () => {
     });
 
     const mockProvider = { id: "microsoft", name: "Microsoft" };
-    vi.mocked(getOAuthProvider).mockReturnValue(mockProvider as ReturnType<typeof getOAuthProvider>);
+    vi.mocked(resolveOAuthProvider).mockResolvedValue(
+      mockProvider as Awaited<ReturnType<typeof resolveOAuthProvider>>,
+    );
     vi.mocked(refreshProviderToken).mockResolvedValue({
       access_token: "new-token",
       expires_in: 3600,
@@ -73,6 +75,7 @@ describe("ensureFreshToken", () => {
     const token = await ensureFreshToken(account);
 
     expect(token).toBe("new-token");
+    expect(resolveOAuthProvider).toHaveBeenCalledWith("microsoft", null);
     expect(refreshProviderToken).toHaveBeenCalledWith(
       mockProvider,
       "refresh-token",
@@ -93,7 +96,9 @@ describe("ensureFreshToken", () => {
     });
 
     const mockProvider = { id: "microsoft", name: "Microsoft" };
-    vi.mocked(getOAuthProvider).mockReturnValue(mockProvider as ReturnType<typeof getOAuthProvider>);
+    vi.mocked(resolveOAuthProvider).mockResolvedValue(
+      mockProvider as Awaited<ReturnType<typeof resolveOAuthProvider>>,
+    );
     vi.mocked(refreshProviderToken).mockResolvedValue({
       access_token: "refreshed-token",
       expires_in: 3600,
@@ -124,7 +129,7 @@ describe("ensureFreshToken", () => {
       oauth_provider: "unknown",
       token_expires_at: Math.floor(Date.now() / 1000) - 60,
     });
-    vi.mocked(getOAuthProvider).mockReturnValue(null);
+    vi.mocked(resolveOAuthProvider).mockResolvedValue(null);
     await expect(ensureFreshToken(account)).rejects.toThrow("Unknown OAuth provider");
   });
 });
@@ -1,11 +1,20 @@
 import type { DbAccount } from "../db/accounts";
 import { updateAccountTokens } from "../db/accounts";
-import { getOAuthProvider } from "./providers";
+import { resolveOAuthProvider } from "./providers";
 import { refreshProviderToken } from "./oauthFlow";
 
 /** Buffer before expiry to trigger a refresh (5 minutes) */
 const REFRESH_BUFFER_MS = 5 * 60 * 1000;
 
+/**
+ * Whether the frontend refreshes this account's OAuth2 tokens: an OAuth
+ * IMAP account whose grant hasn't moved to the backend's keychain
+ * (`moveOAuthGrantToKeychain`). The backend refreshes the others itself.
+ */
+export function hasFrontendTokens(account: DbAccount): boolean {
+  return account.auth_method === "oauth2" && !!account.refresh_token;
+}
+
 /**
  * Ensure the account has a fresh OAuth2 access token.
  * If the token is within 5 minutes of expiry, refresh it and update the DB.
@@ -36,7 +45,7 @@ export async function ensureFreshToken(account: DbAccount): Promise<string> {
   }
 
   // Token expired or about to expire — refresh it
-  const provider = getOAuthProvider(account.oauth_provider);
+  const provider = await resolveOAuthProvider(account.oauth_provider, account.oauth_tenant);
   if (!provider) {
     throw new Error(`Unknown OAuth provider: ${account.oauth_provider}`);
   }
@@ -1,24 +1,17 @@
-import { describe, it, expect } from "vitest";
-import { getOAuthProvider, getAllOAuthProviders } from "./providers";
+import { describe, it, expect, vi, beforeEach } from "vitest";
 
-describe("getOAuthProvider", () => {
-  it("returns microsoft provider config", () => {
-    const provider = getOAuthProvider("microsoft");
-    expect(provider).not.toBeNull();
-    expect(provider!.id).toBe("microsoft");
-    expect(provider!.name).toBe("Microsoft");
-    expect(provider!.authUrl).toContain("login.microsoftonline.com");
-    expect(provider!.tokenUrl).toContain("login.microsoftonline.com");
-    expect(provider!.scopes).toContain("https://outlook.office.com/IMAP.AccessAsUser.All");
-    expect(provider!.scopes).toContain("https://outlook.office.com/SMTP.Send");
-    expect(provider!.scopes).toContain("offline_access");
-    expect(provider!.scopes).toContain("openid");
-    expect(provider!.scopes).toContain("profile");
-    expect(provider!.scopes).toContain("email");
-    expect(provider!.userInfoUrl).toBeUndefined();
-    expect(provider!.usePkce).toBe(true);
-  });
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+import { invoke } from "@tauri-apps/api/core";
+import { getOAuthProvider, getAllOAuthProviders, resolveOAuthProvider } from "./providers";
 
+beforeEach(() => {
+  vi.clearAllMocks();
+});
+
+describe("getOAuthProvider", () => {
   it("returns yahoo provider config", () => {
     const provider = getOAuthProvider("yahoo");
     expect(provider).not.toBeNull();
@@ -39,12 +32,55 @@ describe("getOAuthProvider", () => {
   });
 });
 
+describe("resolveOAuthProvider", () => {
+  it("takes microsoft from the backend's profile for the tenant", async () => {
+    vi.mocked(invoke).mockResolvedValue({
+      id: "microsoft",
+      name: "Microsoft",
+      auth_url: "https://login.microsoftonline.com/contoso.com/oauth2/v2.0/authorize",
+      token_url: "https://login.microsoftonline.com/contoso.com/oauth2/v2.0/token",
+      scopes: ["https://outlook.office.com/IMAP.AccessAsUser.All", "offline_access"],
+      use_pkce: true,
+    });
+
+    const provider = await resolveOAuthProvider("microsoft", " contoso.com ");
+
+    expect(invoke).toHaveBeenCalledWith("oauth_provider_profile", {
+      provider: "microsoft",
+      tenant: "contoso.com",
+    });
+    expect(provider).toEqual({
+      id: "microsoft",
+      name: "Microsoft",
+      authUrl: "https://login.microsoftonline.com/contoso.com/oauth2/v2.0/authorize",
+      tokenUrl: "https://login.microsoftonline.com/contoso.com/oauth2/v2.0/token",
+      scopes: ["https://outlook.office.com/IMAP.AccessAsUser.All", "offline_access"],
+      userInfoUrl: undefined,
+      usePkce: true,
+    });
+  });
+
+  it("leaves the tenant to the backend's default when blank", async () => {
+    vi.mocked(invoke).mockResolvedValue({ id: "microsoft", scopes: [] });
+
+    await resolveOAuthProvider("microsoft", "");
+
+    expect(invoke).toHaveBeenCalledWith("oauth_provider_profile", {
+      provider: "microsoft",
+      tenant: null,
+    });
+  });
+
+  it("returns other providers without asking the backend", async () => {
+    expect((await resolveOAuthProvider("yahoo"))?.id).toBe("yahoo");
+    expect(await resolveOAuthProvider("unknown")).toBeNull();
+    expect(invoke).not.toHaveBeenCalled();
+  });
+});
+
 describe("getAllOAuthProviders", () => {
-  it("returns all registered providers", () => {
-    const providers = getAllOAuthProviders();
-    expect(providers.length).toBeGreaterThanOrEqual(2);
-    const ids = providers.map((p) => p.id);
-    expect(ids).toContain("microsoft");
+  it("returns the statically defined providers", () => {
+    const ids = getAllOAuthProviders().map((p) => p.id);
     expect(ids).toContain("yahoo");
   });
 });
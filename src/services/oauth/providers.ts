@@ -1,3 +1,5 @@
+import { invoke } from "@tauri-apps/api/core";
+
 export interface OAuthProviderConfig {
   id: string;
   name: string;
@@ -9,23 +11,28 @@ export interface OAuthProviderConfig {
   usePkce: boolean;
 }
 
+/** A built-in OAuth profile as defined by the backend (`oauth.rs`). */
+interface OAuthProviderProfile {
+  id: string;
+  name: string;
+  auth_url: string;
+  token_url: string;
+  scopes: string[];
+  use_pkce: boolean;
+}
+
+/** Providers whose endpoints come from the backend's built-in profiles. */
+const BACKEND_PROFILES = new Set(["microsoft"]);
+
 const providers: Record<string, OAuthProviderConfig> = {
-  microsoft: {
-    id: "microsoft",
-    name: "Microsoft",
-    authUrl:
-      "https://login.microsoftonline.com/consumers/oauth2/v2.0/authorize",
-    tokenUrl:
-      "https://login.microsoftonline.com/consumers/oauth2/v2.0/token",
-    scopes: [
-      "https://outlook.office.com/IMAP.AccessAsUser.All",
-      "https://outlook.office.com/SMTP.Send",
-      "offline_access",
-      "openid",
-      "profile",
-      "email",
-    ],
-    userInfoUrl: undefined,
+  google: {
+    id: "google",
+    name: "Google",
+    authUrl: "https://accounts.google.com/o/oauth2/v2/auth",
+    tokenUrl: "https://oauth2.googleapis.com/token",
+    // Covers IMAP, SMTP and the Gmail API, so the account can switch transports
+    scopes: ["https://mail.google.com/", "openid", "email", "profile"],
+    userInfoUrl: "https://openidconnect.googleapis.com/v1/userinfo",
     usePkce: true,
   },
   yahoo: {
@@ -43,6 +50,31 @@ export function getOAuthProvider(id: string): OAuthProviderConfig | null {
   return providers[id] ?? null;
 }
 
+/**
+ * The provider config for `id`, including the providers defined in the
+ * backend. Microsoft signs in against `tenant` ("common" when omitted);
+ * other providers ignore it.
+ */
+export async function resolveOAuthProvider(
+  id: string,
+  tenant?: string | null,
+): Promise<OAuthProviderConfig | null> {
+  if (!BACKEND_PROFILES.has(id)) return getOAuthProvider(id);
+  const profile = await invoke<OAuthProviderProfile>("oauth_provider_profile", {
+    provider: id,
+    tenant: tenant?.trim() || null,
+  });
+  return {
+    id: profile.id,
+    name: profile.name,
+    authUrl: profile.auth_url,
+    tokenUrl: profile.token_url,
+    scopes: profile.scopes,
+    userInfoUrl: undefined,
+    usePkce: profile.use_pkce,
+  };
+}
+
 export function getAllOAuthProviders(): OAuthProviderConfig[] {
   return Object.values(providers);
 }
@@ -0,0 +1,75 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { invoke } from "@tauri-apps/api/core";
+import { getSetting } from "@/services/db/settings";
+import { reviewLink, type LinkCheckResult } from "./linkCheck";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+vi.mock("@/services/db/settings", () => ({
+  getSetting: vi.fn(),
+}));
+
+const mockInvoke = vi.mocked(invoke);
+const mockGetSetting = vi.mocked(getSetting);
+
+function checkResult(overrides: Partial<LinkCheckResult> = {}): LinkCheckResult {
+  return {
+    url: "https://bit.ly/abc",
+    final_url: "https://example.com/page",
+    redirects: [],
+    final_domain: "example.com",
+    display_domain: null,
+    domain_mismatch: false,
+    blocklist_source: null,
+    verdict: "safe",
+    reasons: [],
+    ...overrides,
+  };
+}
+
+describe("reviewLink", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    mockGetSetting.mockResolvedValue(null);
+  });
+
+  it("lets safe links through", async () => {
+    mockInvoke.mockResolvedValue(checkResult());
+
+    expect(await reviewLink("https://example.com/page", "Read more")).toBeNull();
+    expect(mockInvoke).toHaveBeenCalledWith("check_link", {
+      url: "https://example.com/page",
+      displayText: "Read more",
+    });
+  });
+
+  it("flags links the backend marks as dangerous", async () => {
+    const check = checkResult({
+      verdict: "dangerous",
+      blocklist_source: "OpenPhish",
+      reasons: ["Listed as malicious by OpenPhish"],
+    });
+    mockInvoke.mockResolvedValue(check);
+
+    const review = await reviewLink("https://bit.ly/abc", "Invoice");
+    expect(review?.check).toEqual(check);
+    expect(review?.analysis.url).toBe("https://bit.ly/abc");
+  });
+
+  it("still warns on heuristics when the backend check fails", async () => {
+    mockInvoke.mockRejectedValue(new Error("offline"));
+
+    const review = await reviewLink("http://192.168.0.1/paypal/login/verify", "paypal.com");
+    expect(review).not.toBeNull();
+    expect(review?.check).toBeNull();
+  });
+
+  it("skips checks when detection is disabled or the link isn't a web link", async () => {
+    expect(await reviewLink("mailto:a@example.com", "a@example.com")).toBeNull();
+    mockGetSetting.mockResolvedValue("false");
+    expect(await reviewLink("https://bit.ly/abc", "")).toBeNull();
+    expect(mockInvoke).not.toHaveBeenCalled();
+  });
+});
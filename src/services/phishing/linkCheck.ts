@@ -0,0 +1,87 @@
+import { invoke } from "@tauri-apps/api/core";
+import { getSetting } from "@/services/db/settings";
+import { analyzeLink, type LinkAnalysis } from "@/utils/phishingDetector";
+import { createBackgroundChecker } from "../backgroundCheckers";
+
+export type LinkVerdict = "safe" | "suspicious" | "dangerous";
+
+export interface LinkCheckResult {
+  url: string;
+  /** Destination after expanding URL shorteners */
+  final_url: string;
+  /** Intermediate redirect URLs, excluding url and final_url */
+  redirects: string[];
+  final_domain: string | null;
+  /** Domain the link text claims to go to, if it looks like a URL */
+  display_domain: string | null;
+  domain_mismatch: boolean;
+  /** Feed (OpenPhish, URLhaus) listing the link or one of its redirects */
+  blocklist_source: string | null;
+  verdict: LinkVerdict;
+  reasons: string[];
+}
+
+export interface BlocklistStatus {
+  entries: number;
+  updated_at: number | null;
+}
+
+export interface LinkReview {
+  analysis: LinkAnalysis;
+  check: LinkCheckResult | null;
+}
+
+/**
+ * Expand shorteners, compare the destination with the link text, and look
+ * the link up in the local phishing/malware blocklist.
+ */
+export function checkLink(url: string, displayText?: string): Promise<LinkCheckResult> {
+  return invoke<LinkCheckResult>("check_link", { url, displayText: displayText ?? null });
+}
+
+/** Download stale blocklist feeds (all of them with `force`) and reload. */
+export function refreshLinkBlocklist(force = false): Promise<BlocklistStatus> {
+  return invoke<BlocklistStatus>("link_blocklist_refresh", { force });
+}
+
+/**
+ * Decide whether a clicked link needs a warning before it is opened.
+ * Returns null when it can be opened directly (or detection is disabled).
+ */
+export async function reviewLink(url: string, displayText: string): Promise<LinkReview | null> {
+  const enabled = await getSetting("phishing_detection_enabled");
+  if (enabled === "false" || !/^https?:\/\//i.test(url)) return null;
+
+  const analysis = analyzeLink(url, displayText);
+  let check: LinkCheckResult | null = null;
+  try {
+    check = await checkLink(url, displayText);
+  } catch (err) {
+    console.warn("Link check failed:", err);
+  }
+
+  const risky =
+    analysis.riskLevel === "medium" ||
+    analysis.riskLevel === "high" ||
+    (check !== null && check.verdict !== "safe");
+  return risky ? { analysis, check } : null;
+}
+
+const blocklistUpdater = createBackgroundChecker(
+  "LinkBlocklist",
+  async () => {
+    const enabled = await getSetting("phishing_detection_enabled");
+    if (enabled === "false") return;
+    // The backend only downloads feeds that are out of date
+    await refreshLinkBlocklist();
+  },
+  60 * 60_000,
+);
+
+export function startLinkBlocklistUpdater(): void {
+  blocklistUpdater.start();
+}
+
+export function stopLinkBlocklistUpdater(): void {
+  blocklistUpdater.stop();
+}
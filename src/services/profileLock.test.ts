@@ -0,0 +1,57 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { invoke } from "@tauri-apps/api/core";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+import { getProfileStatus, unlockProfile, setMasterPassword } from "./profileLock";
+
+const mockInvoke = vi.mocked(invoke);
+
+describe("profileLock", () => {
+  beforeEach(() => {
+    mockInvoke.mockReset();
+  });
+
+  it("reads the profile status", async () => {
+    mockInvoke.mockResolvedValue({ protected: true, locked: true });
+
+    expect(await getProfileStatus()).toEqual({ protected: true, locked: true });
+    expect(mockInvoke).toHaveBeenCalledWith("profile_status");
+  });
+
+  it("unlocks with the master password", async () => {
+    mockInvoke.mockResolvedValue(undefined);
+
+    await unlockProfile("secret");
+    expect(mockInvoke).toHaveBeenCalledWith("profile_unlock", { password: "secret" });
+  });
+
+  it("propagates a wrong password", async () => {
+    mockInvoke.mockRejectedValue("Wrong master password");
+
+    await expect(unlockProfile("nope")).rejects.toBe("Wrong master password");
+  });
+
+  it("sets, changes and removes the master password", async () => {
+    mockInvoke.mockResolvedValue(undefined);
+
+    await setMasterPassword(null, "first");
+    await setMasterPassword("first", "second");
+    await setMasterPassword("second", null);
+
+    expect(mockInvoke).toHaveBeenNthCalledWith(1, "profile_set_master_password", {
+      currentPassword: null,
+      newPassword: "first",
+    });
+    expect(mockInvoke).toHaveBeenNthCalledWith(2, "profile_set_master_password", {
+      currentPassword: "first",
+      newPassword: "second",
+    });
+    expect(mockInvoke).toHaveBeenNthCalledWith(3, "profile_set_master_password", {
+      currentPassword: "second",
+      newPassword: null,
+    });
+  });
+});
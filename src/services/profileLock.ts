@@ -0,0 +1,34 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * Optional master password protecting the key that encrypts stored
+ * credentials (see `utils/crypto.ts`). The backend keeps the key; with a
+ * master password it stays locked at startup until `unlockProfile`.
+ */
+
+export interface ProfileStatus {
+  /** A master password is set. */
+  protected: boolean;
+  /** Stored credentials can't be decrypted until the profile is unlocked. */
+  locked: boolean;
+}
+
+export async function getProfileStatus(): Promise<ProfileStatus> {
+  return invoke<ProfileStatus>("profile_status");
+}
+
+/** Rejects with "Wrong master password" on a bad password. */
+export async function unlockProfile(password: string): Promise<void> {
+  return invoke<void>("profile_unlock", { password });
+}
+
+/**
+ * Set, change or (with `newPassword` null) remove the master password.
+ * Changing or removing it requires the current one.
+ */
+export async function setMasterPassword(
+  currentPassword: string | null,
+  newPassword: string | null,
+): Promise<void> {
+  return invoke<void>("profile_set_master_password", { currentPassword, newPassword });
+}
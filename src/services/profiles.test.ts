@@ -0,0 +1,66 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { invoke } from "@tauri-apps/api/core";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+const mockInvoke = vi.mocked(invoke);
+
+async function loadModule() {
+  vi.resetModules();
+  return import("./profiles");
+}
+
+describe("profiles", () => {
+  beforeEach(() => {
+    mockInvoke.mockReset();
+  });
+
+  it("asks the backend for the current profile once", async () => {
+    const { getCurrentProfile } = await loadModule();
+    mockInvoke.mockResolvedValue({
+      id: "work",
+      name: "Work",
+      dir: "profiles/work",
+      db_url: "sqlite:profiles/work/velo.db",
+    });
+
+    expect((await getCurrentProfile()).db_url).toBe("sqlite:profiles/work/velo.db");
+    await getCurrentProfile();
+    expect(mockInvoke).toHaveBeenCalledTimes(1);
+    expect(mockInvoke).toHaveBeenCalledWith("profile_current");
+  });
+
+  it("retries after a failed lookup", async () => {
+    const { getCurrentProfile } = await loadModule();
+    mockInvoke.mockRejectedValueOnce(new Error("not ready"));
+    await expect(getCurrentProfile()).rejects.toThrow("not ready");
+
+    mockInvoke.mockResolvedValue({ id: "default", name: "Default", dir: "", db_url: "sqlite:velo.db" });
+    expect((await getCurrentProfile()).id).toBe("default");
+  });
+
+  it("prefixes paths with the profile directory", async () => {
+    const { profilePath } = await loadModule();
+    mockInvoke.mockResolvedValue({ id: "work", name: "Work", dir: "profiles/work", db_url: "" });
+    expect(await profilePath("attachment_cache")).toBe("profiles/work/attachment_cache");
+  });
+
+  it("leaves paths alone in the default profile", async () => {
+    const { profilePath } = await loadModule();
+    mockInvoke.mockResolvedValue({ id: "default", name: "Default", dir: "", db_url: "sqlite:velo.db" });
+    expect(await profilePath("attachment_cache")).toBe("attachment_cache");
+  });
+
+  it("creates and switches profiles", async () => {
+    const { createProfile, switchProfile } = await loadModule();
+    mockInvoke.mockResolvedValueOnce({ id: "personal", name: "Personal" });
+    expect(await createProfile("Personal")).toEqual({ id: "personal", name: "Personal" });
+    expect(mockInvoke).toHaveBeenCalledWith("profile_create", { name: "Personal" });
+
+    mockInvoke.mockResolvedValueOnce(undefined);
+    await switchProfile("personal");
+    expect(mockInvoke).toHaveBeenCalledWith("profile_switch", { id: "personal" });
+  });
+});
@@ -0,0 +1,51 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * Isolated profiles (e.g. work and personal). Each has its own accounts,
+ * database, settings and attachment cache; the backend picks one at launch
+ * and switching restarts the app into another.
+ */
+
+export interface Profile {
+  id: string;
+  name: string;
+}
+
+export interface ActiveProfile extends Profile {
+  /** Profile directory relative to AppConfig/AppData, "" for the default profile. */
+  dir: string;
+  /** URL to pass to `Database.load`. */
+  db_url: string;
+}
+
+let current: Promise<ActiveProfile> | null = null;
+
+/** The profile this window belongs to. It can't change without a restart. */
+export function getCurrentProfile(): Promise<ActiveProfile> {
+  if (!current) {
+    current = invoke<ActiveProfile>("profile_current").catch((err) => {
+      current = null;
+      throw err;
+    });
+  }
+  return current;
+}
+
+/** Prefix `path` with the current profile's directory. */
+export async function profilePath(path: string): Promise<string> {
+  const { dir } = await getCurrentProfile();
+  return dir ? `${dir}/${path}` : path;
+}
+
+export async function listProfiles(): Promise<Profile[]> {
+  return invoke<Profile[]>("profile_list");
+}
+
+export async function createProfile(name: string): Promise<Profile> {
+  return invoke<Profile>("profile_create", { name });
+}
+
+/** Restart the app into profile `id`. Resolves only if it's already active. */
+export async function switchProfile(id: string): Promise<void> {
+  return invoke<void>("profile_switch", { id });
+}
@@ -10,13 +10,14 @@ vi.mock("../db/pendingOperations", () => ({
   getPendingOperations: vi.fn(() => Promise.resolve([])),
   updateOperationStatus: vi.fn(() => Promise.resolve()),
   deleteOperation: vi.fn(() => Promise.resolve()),
-  incrementRetry: vi.fn(() => Promise.resolve()),
+  incrementRetry: vi.fn(() => Promise.resolve(true)),
   getPendingOpsCount: vi.fn(() => Promise.resolve(0)),
   compactQueue: vi.fn(() => Promise.resolve(0)),
 }));
 
 vi.mock("../emailActions", () => ({
   executeQueuedAction: vi.fn(() => Promise.resolve()),
+  auditQueuedAction: vi.fn(() => Promise.resolve()),
 }));
 
 vi.mock("@/utils/networkErrors", () => ({
@@ -42,7 +43,7 @@ import {
   incrementRetry,
   compactQueue,
 } from "../db/pendingOperations";
-import { executeQueuedAction } from "../emailActions";
+import { auditQueuedAction, executeQueuedAction } from "../emailActions";
 import { classifyError } from "@/utils/networkErrors";
 import { startQueueProcessor, stopQueueProcessor, triggerQueueFlush } from "./queueProcessor";
 import { createMockUIStoreState } from "@/test/mocks";
@@ -67,6 +68,15 @@ describe("queueProcessor", () => {
     expect(getPendingOperations).not.toHaveBeenCalled();
   });
 
+  it("keeps queued operations while read-only mode is on", async () => {
+    vi.mocked(useUIStore.getState).mockReturnValue(createMockUIStoreState({
+      isReadOnly: true,
+      setPendingOpsCount: mockSetPendingOpsCount,
+    }) as never);
+    await triggerQueueFlush();
+    expect(getPendingOperations).not.toHaveBeenCalled();
+  });
+
   it("compacts queue before processing", async () => {
     await triggerQueueFlush();
     expect(compactQueue).toHaveBeenCalled();
@@ -97,6 +107,10 @@ describe("queueProcessor", () => {
       messageIds: [],
     });
     expect(deleteOperation).toHaveBeenCalledWith("op-1");
+    expect(auditQueuedAction).toHaveBeenCalledWith("acct-1", "archive", {
+      threadId: "t1",
+      messageIds: [],
+    }, "ok");
   });
 
   it("retries on retryable errors", async () => {
@@ -127,6 +141,40 @@ describe("queueProcessor", () => {
     expect(updateOperationStatus).toHaveBeenCalledWith("op-1", "pending", "Failed to fetch");
     expect(incrementRetry).toHaveBeenCalledWith("op-1");
     expect(deleteOperation).not.toHaveBeenCalled();
+    expect(auditQueuedAction).not.toHaveBeenCalled();
+  });
+
+  it("audits an operation that runs out of retries as failed", async () => {
+    vi.mocked(getPendingOperations).mockResolvedValueOnce([
+      {
+        id: "op-1",
+        account_id: "acct-1",
+        operation_type: "star",
+        resource_id: "t1",
+        params: '{"threadId":"t1","messageIds":[],"starred":true}',
+        status: "pending",
+        retry_count: 9,
+        max_retries: 10,
+        next_retry_at: null,
+        created_at: 1000,
+        error_message: null,
+      },
+    ]);
+    vi.mocked(executeQueuedAction).mockRejectedValueOnce(new Error("Failed to fetch"));
+    vi.mocked(classifyError).mockReturnValueOnce({
+      type: "network",
+      isRetryable: true,
+      message: "Failed to fetch",
+    });
+    vi.mocked(incrementRetry).mockResolvedValueOnce(false);
+
+    await triggerQueueFlush();
+
+    expect(auditQueuedAction).toHaveBeenCalledWith("acct-1", "star", {
+      threadId: "t1",
+      messageIds: [],
+      starred: true,
+    }, "failed", "Failed to fetch");
   });
 
   it("marks as failed on permanent errors", async () => {
@@ -155,6 +203,10 @@ describe("queueProcessor", () => {
     await triggerQueueFlush();
 
     expect(updateOperationStatus).toHaveBeenCalledWith("op-1", "failed", "Bad request");
+    expect(auditQueuedAction).toHaveBeenCalledWith("acct-1", "archive", {
+      threadId: "t1",
+      messageIds: [],
+    }, "failed", "Bad request");
   });
 
   it("updates pending count after processing", async () => {
@@ -8,7 +8,7 @@ import {
   getPendingOpsCount,
   compactQueue,
 } from "../db/pendingOperations";
-import { executeQueuedAction } from "../emailActions";
+import { auditQueuedAction, executeQueuedAction } from "../emailActions";
 import { classifyError } from "@/utils/networkErrors";
 
 const BATCH_SIZE = 50;
@@ -16,8 +16,9 @@ const BATCH_SIZE = 50;
 let checker: BackgroundChecker | null = null;
 
 async function processQueue(): Promise<void> {
-  // Skip if offline
-  if (!useUIStore.getState().isOnline) return;
+  // Skip if offline, or keep queued changes until read-only mode is off
+  const { isOnline, isReadOnly } = useUIStore.getState();
+  if (!isOnline || isReadOnly) return;
 
   // Compact first to eliminate redundant ops
   await compactQueue();
@@ -30,27 +31,31 @@ async function processQueue(): Promise<void> {
   }
 
   for (const op of ops) {
+    let params: Record<string, unknown> = {};
     try {
       // Mark as executing
       await updateOperationStatus(op.id, "executing");
 
       // Parse params and execute
-      const params = JSON.parse(op.params) as Record<string, unknown>;
+      params = JSON.parse(op.params) as Record<string, unknown>;
       await executeQueuedAction(op.account_id, op.operation_type, params);
 
       // Success — delete from queue
       await deleteOperation(op.id);
+      await auditQueuedAction(op.account_id, op.operation_type, params, "ok");
     } catch (err) {
       const classified = classifyError(err);
 
       if (classified.isRetryable) {
         // Increment retry with exponential backoff
         await updateOperationStatus(op.id, "pending", classified.message);
-        await incrementRetry(op.id);
+        if (await incrementRetry(op.id)) continue;
       } else {
         // Permanent failure
         await updateOperationStatus(op.id, "failed", classified.message);
       }
+      // Out of retries or permanent: the operation won't run again
+      await auditQueuedAction(op.account_id, op.operation_type, params, "failed", classified.message);
     }
   }
 
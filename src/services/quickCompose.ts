@@ -0,0 +1,21 @@
+import { commands } from "@/bindings";
+import { unwrap } from "./commandResult";
+
+/**
+ * The small frameless compose window the backend opens near the cursor from
+ * the tray or its own global shortcut (`quick_compose.rs`). It loads
+ * `ComposerWindow` with `quick=true`, which shows the compact composer.
+ */
+
+/** The window's global shortcut, or null when it's turned off. */
+export async function getQuickComposeShortcut(): Promise<string | null> {
+  return commands.getQuickComposeShortcut();
+}
+
+/**
+ * Change the shortcut, or turn it off with null. Throws when the shortcut
+ * can't be registered (e.g. another app holds it); the old one stays active.
+ */
+export async function setQuickComposeShortcut(shortcut: string | null): Promise<void> {
+  unwrap<null>(await commands.setQuickComposeShortcut(shortcut));
+}
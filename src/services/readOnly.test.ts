@@ -0,0 +1,32 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { invoke } from "@tauri-apps/api/core";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+import { getReadOnly, setReadOnly } from "./readOnly";
+
+const mockInvoke = vi.mocked(invoke);
+
+describe("readOnly", () => {
+  beforeEach(() => {
+    mockInvoke.mockReset();
+  });
+
+  it("reads the backend setting", async () => {
+    mockInvoke.mockResolvedValue(true);
+
+    expect(await getReadOnly()).toBe(true);
+    expect(mockInvoke).toHaveBeenCalledWith("get_read_only");
+  });
+
+  it("turns read-only mode on and off", async () => {
+    mockInvoke.mockResolvedValue(undefined);
+
+    await setReadOnly(true);
+    expect(mockInvoke).toHaveBeenCalledWith("set_read_only", { enabled: true });
+    await setReadOnly(false);
+    expect(mockInvoke).toHaveBeenCalledWith("set_read_only", { enabled: false });
+  });
+});
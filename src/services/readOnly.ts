@@ -0,0 +1,18 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * Read-only mode, for demos or reviewing an archive: the backend refuses
+ * every command that would change server state (send, flag, move, delete,
+ * append) and `emailActions` refuses Gmail changes before they're made.
+ */
+
+/** Same message as the backend's `READ_ONLY_ERROR`. */
+export const READ_ONLY_ERROR = "Read-only mode is on; changes are disabled";
+
+export async function getReadOnly(): Promise<boolean> {
+  return invoke<boolean>("get_read_only");
+}
+
+export async function setReadOnly(enabled: boolean): Promise<void> {
+  return invoke<void>("set_read_only", { enabled });
+}
@@ -0,0 +1,122 @@
+import { describe, it, expect, beforeEach, afterEach, vi } from "vitest";
+
+const mockGetAllAccounts = vi.fn();
+vi.mock("../db/accounts", () => ({
+  getAllAccounts: () => mockGetAllAccounts(),
+}));
+
+const mockGetOldestMessageDate = vi.fn();
+vi.mock("../db/messages", () => ({
+  getOldestMessageDate: (...args: unknown[]) => mockGetOldestMessageDate(...args),
+}));
+
+const mockSearchMessages = vi.fn();
+vi.mock("../db/search", () => ({
+  searchMessages: (...args: unknown[]) => mockSearchMessages(...args),
+}));
+
+const mockProviderSearch = vi.fn();
+vi.mock("../email/providerFactory", () => ({
+  getEmailProvider: vi.fn(() => Promise.resolve({ searchMessages: mockProviderSearch })),
+}));
+
+import {
+  searchAllAccounts,
+  SEARCH_RESULTS_EVENT,
+  type SearchResultsDetail,
+} from "./searchAllAccounts";
+
+function row(accountId: string, id: string, date: number) {
+  return {
+    message_id: id,
+    account_id: accountId,
+    thread_id: `t-${id}`,
+    subject: null,
+    from_name: null,
+    from_address: null,
+    snippet: null,
+    date,
+    rank: 0,
+  };
+}
+
+describe("searchAllAccounts", () => {
+  const events: SearchResultsDetail[] = [];
+  const listener = (e: Event) => events.push((e as CustomEvent<SearchResultsDetail>).detail);
+
+  beforeEach(() => {
+    vi.clearAllMocks();
+    events.length = 0;
+    window.addEventListener(SEARCH_RESULTS_EVENT, listener);
+    mockGetAllAccounts.mockResolvedValue([
+      { id: "acc-1", is_active: 1, provider: "gmail_api" },
+      { id: "acc-2", is_active: 1, provider: "imap" },
+      { id: "acc-off", is_active: 0, provider: "imap" },
+      { id: "acc-cal", is_active: 1, provider: "caldav" },
+    ]);
+  });
+
+  afterEach(() => {
+    window.removeEventListener(SEARCH_RESULTS_EVENT, listener);
+    vi.useRealTimers();
+  });
+
+  it("searches each active mail account and merges results newest first", async () => {
+    mockSearchMessages.mockImplementation((_q: string, accountId: string) =>
+      Promise.resolve(accountId === "acc-1" ? [row("acc-1", "a", 100)] : [row("acc-2", "b", 200)]),
+    );
+
+    const result = await searchAllAccounts("invoice", { searchId: "s-1" });
+
+    expect(mockSearchMessages).toHaveBeenCalledTimes(2);
+    expect(result.results.map((r) => r.message_id)).toEqual(["b", "a"]);
+    expect(result.results.every((r) => r.source === "local")).toBe(true);
+    expect(events.map((e) => e.accountId).sort()).toEqual(["acc-1", "acc-2"]);
+    expect(events.every((e) => e.searchId === "s-1")).toBe(true);
+    expect(result.timedOut).toEqual([]);
+    expect(mockProviderSearch).not.toHaveBeenCalled();
+  });
+
+  it("searches the server before the oldest synced message when asked", async () => {
+    mockSearchMessages.mockResolvedValue([]);
+    mockGetOldestMessageDate.mockResolvedValue(5_000);
+    mockProviderSearch.mockResolvedValue([
+      { id: "old", threadId: "t-old", subject: "Old", fromName: null, fromAddress: null, snippet: "", date: 1_000 },
+    ]);
+
+    const result = await searchAllAccounts("invoice", { includeServer: true, limit: 10 });
+
+    expect(mockProviderSearch).toHaveBeenCalledWith("invoice", 5_000, 10);
+    const server = result.results.filter((r) => r.source === "server");
+    expect(server).toHaveLength(2);
+    expect(server[0]!.message_id).toBe("old");
+  });
+
+  it("reports accounts still running at the deadline and drops late results", async () => {
+    vi.useFakeTimers();
+    mockSearchMessages.mockImplementation((_q: string, accountId: string) =>
+      accountId === "acc-1"
+        ? Promise.resolve([row("acc-1", "a", 100)])
+        : new Promise(() => {}),
+    );
+
+    const promise = searchAllAccounts("invoice", { deadlineMs: 1_000 });
+    await vi.advanceTimersByTimeAsync(1_000);
+    const result = await promise;
+
+    expect(result.results.map((r) => r.account_id)).toEqual(["acc-1"]);
+    expect(result.timedOut).toEqual(["acc-2"]);
+  });
+
+  it("records failed accounts without failing the whole search", async () => {
+    vi.spyOn(console, "warn").mockImplementation(() => {});
+    mockSearchMessages.mockImplementation((_q: string, accountId: string) =>
+      accountId === "acc-1" ? Promise.reject(new Error("fts")) : Promise.resolve([row("acc-2", "b", 1)]),
+    );
+
+    const result = await searchAllAccounts("invoice");
+
+    expect(result.failed).toEqual(["acc-1"]);
+    expect(result.results).toHaveLength(1);
+  });
+});
@@ -0,0 +1,153 @@
+import { getAllAccounts } from "../db/accounts";
+import { getOldestMessageDate } from "../db/messages";
+import { searchMessages, type SearchResult } from "../db/search";
+import { getEmailProvider } from "../email/providerFactory";
+import type { ParsedMessage } from "../gmail/messageParser";
+
+/** Window event carrying one account's batch of results for a running search. */
+export const SEARCH_RESULTS_EVENT = "velo-search-results";
+
+export type SearchHitSource = "local" | "server";
+
+export interface SearchHit extends SearchResult {
+  /** "server" hits aren't in the local database and must be opened from the server. */
+  source: SearchHitSource;
+}
+
+export interface SearchResultsDetail {
+  searchId: string;
+  accountId: string;
+  source: SearchHitSource;
+  results: SearchHit[];
+}
+
+export interface SearchAllAccountsOptions {
+  /** Id stamped on every event, so listeners can ignore superseded searches. */
+  searchId?: string;
+  /** Maximum results per account and source. */
+  limit?: number;
+  /** Give up on accounts that haven't answered after this long. */
+  deadlineMs?: number;
+  /** Also search the server for messages older than the local index. */
+  includeServer?: boolean;
+}
+
+export interface SearchAllAccountsResult {
+  searchId: string;
+  /** Every hit that arrived before the deadline, newest first. */
+  results: SearchHit[];
+  /** Accounts with a search still running at the deadline. */
+  timedOut: string[];
+  /** Accounts whose search failed. */
+  failed: string[];
+}
+
+function parsedToHit(accountId: string, msg: ParsedMessage): SearchHit {
+  return {
+    message_id: msg.id,
+    account_id: accountId,
+    thread_id: msg.threadId,
+    subject: msg.subject,
+    from_name: msg.fromName,
+    from_address: msg.fromAddress,
+    snippet: msg.snippet,
+    date: msg.date,
+    rank: 0,
+    source: "server",
+  };
+}
+
+async function searchServer(
+  accountId: string,
+  query: string,
+  limit: number,
+): Promise<SearchHit[]> {
+  // The local index covers everything synced; only look further back
+  const before = await getOldestMessageDate(accountId);
+  const provider = await getEmailProvider(accountId);
+  const messages = await provider.searchMessages(query, before, limit);
+  return messages.map((msg) => parsedToHit(accountId, msg));
+}
+
+/**
+ * Run a search against every active account in parallel. Each account's
+ * local results (and server results, when `includeServer` is set) are
+ * broadcast as a `velo-search-results` event as soon as they arrive, so the
+ * UI can render partial results. Resolves once every search has finished or
+ * the deadline passes; results that arrive later are dropped.
+ */
+export async function searchAllAccounts(
+  query: string,
+  options: SearchAllAccountsOptions = {},
+): Promise<SearchAllAccountsResult> {
+  const {
+    searchId = crypto.randomUUID(),
+    limit = 50,
+    deadlineMs = 10_000,
+    includeServer = false,
+  } = options;
+  const results: SearchHit[] = [];
+  const failed = new Set<string>();
+  const pending = new Map<string, number>();
+  let closed = false;
+
+  const accounts = (await getAllAccounts()).filter(
+    (a) => a.is_active && a.provider !== "caldav",
+  );
+
+  const run = async (
+    accountId: string,
+    source: SearchHitSource,
+    search: () => Promise<SearchHit[]>,
+  ): Promise<void> => {
+    pending.set(accountId, (pending.get(accountId) ?? 0) + 1);
+    try {
+      const hits = await search();
+      if (closed) return;
+      results.push(...hits);
+      window.dispatchEvent(
+        new CustomEvent<SearchResultsDetail>(SEARCH_RESULTS_EVENT, {
+          detail: { searchId, accountId, source, results: hits },
+        }),
+      );
+    } catch (err) {
+      console.warn(`[search] ${source} search failed for ${accountId}:`, err);
+      failed.add(accountId);
+    } finally {
+      const left = (pending.get(accountId) ?? 1) - 1;
+      if (left > 0) pending.set(accountId, left);
+      else pending.delete(accountId);
+    }
+  };
+
+  const searches = accounts.flatMap((account) => {
+    const tasks = [
+      run(account.id, "local", async () =>
+        (await searchMessages(query, account.id, limit)).map((r) => ({
+          ...r,
+          source: "local" as const,
+        })),
+      ),
+    ];
+    if (includeServer) {
+      tasks.push(run(account.id, "server", () => searchServer(account.id, query, limit)));
+    }
+    return tasks;
+  });
+
+  let timer: ReturnType<typeof setTimeout> | undefined;
+  const deadline = new Promise<void>((resolve) => {
+    timer = setTimeout(resolve, deadlineMs);
+  });
+  await Promise.race([Promise.all(searches), deadline]);
+  clearTimeout(timer);
+  closed = true;
+
+  results.sort((a, b) => b.date - a.date);
+  return {
+    searchId,
+    results,
+    timedOut: [...pending.keys()],
+    failed: [...failed],
+  };
+}
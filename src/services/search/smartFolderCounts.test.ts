@@ -0,0 +1,61 @@
+import { describe, it, expect, beforeEach, vi } from "vitest";
+
+const mockSelect = vi.fn();
+vi.mock("../db/connection", () => ({
+  getDb: vi.fn(() => Promise.resolve({ select: mockSelect })),
+}));
+
+const mockGetSmartFolders = vi.fn();
+vi.mock("../db/smartFolders", () => ({
+  getSmartFolders: (...args: unknown[]) => mockGetSmartFolders(...args),
+}));
+
+vi.mock("./smartFolderQuery", () => ({
+  getSmartFolderCount: vi.fn((query: string, _accountId: string, mode: string) => ({
+    sql: `COUNT ${mode} ${query}`,
+    params: [],
+  })),
+}));
+
+import {
+  refreshSmartFolderCounts,
+  SMART_FOLDER_COUNTS_EVENT,
+  type SmartFolderCountsDetail,
+} from "./smartFolderCounts";
+
+describe("refreshSmartFolderCounts", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+  });
+
+  it("counts each folder per its mode and broadcasts the result", async () => {
+    mockGetSmartFolders.mockResolvedValue([
+      { id: "sf-unread", query: "is:unread", count_mode: "unread" },
+      { id: "sf-awaiting", query: "in:sent", count_mode: "total" },
+      { id: "sf-quiet", query: "has:attachment", count_mode: "none" },
+    ]);
+    mockSelect.mockImplementation((sql: string) =>
+      Promise.resolve([{ count: sql.startsWith("COUNT total") ? 3 : 7 }]),
+    );
+    const listener = vi.fn();
+    window.addEventListener(SMART_FOLDER_COUNTS_EVENT, listener);
+
+    const counts = await refreshSmartFolderCounts("acc-1");
+
+    window.removeEventListener(SMART_FOLDER_COUNTS_EVENT, listener);
+    expect(mockGetSmartFolders).toHaveBeenCalledWith("acc-1");
+    expect(counts).toEqual({ "sf-unread": 7, "sf-awaiting": 3 });
+    expect(listener).toHaveBeenCalledTimes(1);
+    const detail = (listener.mock.calls[0]![0] as CustomEvent<SmartFolderCountsDetail>).detail;
+    expect(detail).toEqual({ accountId: "acc-1", counts });
+  });
+
+  it("counts a folder whose query fails as zero", async () => {
+    mockGetSmartFolders.mockResolvedValue([
+      { id: "sf-1", query: "is:unread", count_mode: "unread" },
+    ]);
+    mockSelect.mockRejectedValue(new Error("bad query"));
+
+    expect(await refreshSmartFolderCounts("acc-1")).toEqual({ "sf-1": 0 });
+  });
+});
@@ -0,0 +1,43 @@
+import { getDb } from "../db/connection";
+import { getSmartFolders } from "../db/smartFolders";
+import { getSmartFolderCount } from "./smartFolderQuery";
+
+/** Window event carrying fresh smart folder badge counts for one account. */
+export const SMART_FOLDER_COUNTS_EVENT = "velo-smart-folder-counts";
+
+export interface SmartFolderCountsDetail {
+  accountId: string;
+  counts: Record<string, number>;
+}
+
+/**
+ * Recount every smart folder visible to an account and broadcast the result
+ * as a `velo-smart-folder-counts` event. Folders with count mode "none" are
+ * skipped; a query that fails to run counts as zero.
+ */
+export async function refreshSmartFolderCounts(
+  accountId: string,
+): Promise<Record<string, number>> {
+  const folders = await getSmartFolders(accountId);
+  const db = await getDb();
+  const counts: Record<string, number> = {};
+
+  for (const folder of folders) {
+    const mode = folder.count_mode ?? "unread";
+    if (mode === "none") continue;
+    try {
+      const { sql, params } = getSmartFolderCount(folder.query, accountId, mode);
+      const rows = await db.select<{ count: number }[]>(sql, params);
+      counts[folder.id] = rows[0]?.count ?? 0;
+    } catch {
+      counts[folder.id] = 0;
+    }
+  }
+
+  window.dispatchEvent(
+    new CustomEvent<SmartFolderCountsDetail>(SMART_FOLDER_COUNTS_EVENT, {
+      detail: { accountId, counts },
+    }),
+  );
+  return counts;
+}
@@ -3,6 +3,7 @@ import {
   resolveQueryTokens,
   getSmartFolderSearchQuery,
   getSmartFolderUnreadCount,
+  getSmartFolderCount,
   mapSmartFolderRows,
   type SmartFolderRow,
 } from "./smartFolderQuery";
@@ -126,6 +127,22 @@ describe("getSmartFolderUnreadCount", () => {
   });
 });
 
+describe("getSmartFolderCount", () => {
+  it("counts matching threads without an unread filter in total mode", () => {
+    const { sql, params } = getSmartFolderCount("has:attachment", "acc-1", "total");
+    expect(sql).toMatch(/^SELECT COUNT\(DISTINCT m\.thread_id\) as count\s+FROM\b/i);
+    expect(sql).not.toContain("m.is_read = 0");
+    expect(sql).not.toMatch(/LIMIT/i);
+    expect(params).not.toContain(999999);
+  });
+
+  it("matches the unread count query in unread mode", () => {
+    expect(getSmartFolderCount("is:starred", "acc-1", "unread")).toEqual(
+      getSmartFolderUnreadCount("is:starred", "acc-1"),
+    );
+  });
+});
+
 describe("mapSmartFolderRows", () => {
   const makeRow = (overrides: Partial<SmartFolderRow> = {}): SmartFolderRow => ({
     message_id: "msg-1",
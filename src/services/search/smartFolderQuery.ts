@@ -55,23 +55,26 @@ export function getSmartFolderSearchQuery(
 }
 
 /**
- * Build a COUNT query for unread messages matching a smart folder's query.
+ * Build a COUNT query for a smart folder's badge: unread messages, or every
+ * matching thread for "total" folders such as "Awaiting reply".
  * Returns { sql, params } where sql produces a single row with `count` column.
  */
-export function getSmartFolderUnreadCount(
+export function getSmartFolderCount(
   rawQuery: string,
   accountId: string,
+  mode: "unread" | "total",
 ): { sql: string; params: unknown[] } {
   const resolved = resolveQueryTokens(rawQuery);
   const parsed = parseSearchQuery(resolved);
 
-  // Force unread filter
-  const withUnread = { ...parsed, isUnread: true };
-  const { sql: baseSql, params } = buildSearchQuery(withUnread, accountId, 999999);
+  // Unread badges force the unread filter
+  const filtered = mode === "unread" ? { ...parsed, isUnread: true } : parsed;
+  const { sql: baseSql, params } = buildSearchQuery(filtered, accountId, 999999);
+  const counted = mode === "unread" ? "m.id" : "m.thread_id";
 
   // Replace SELECT ... FROM with SELECT COUNT(DISTINCT ...) FROM and remove LIMIT
   const countSql = baseSql
-    .replace(/SELECT DISTINCT[\s\S]*?(?=\bFROM\s)/i, "SELECT COUNT(DISTINCT m.id) as count ")
+    .replace(/SELECT DISTINCT[\s\S]*?(?=\bFROM\s)/i, `SELECT COUNT(DISTINCT ${counted}) as count `)
     .replace(/ORDER BY[\s\S]*?(?=LIMIT|$)/i, "")
     .replace(/LIMIT \$\d+/i, "");
 
@@ -81,6 +84,17 @@ export function getSmartFolderUnreadCount(
   return { sql: countSql, params: countParams };
 }
 
+/**
+ * Build a COUNT query for unread messages matching a smart folder's query.
+ * Returns { sql, params } where sql produces a single row with `count` column.
+ */
+export function getSmartFolderUnreadCount(
+  rawQuery: string,
+  accountId: string,
+): { sql: string; params: unknown[] } {
+  return getSmartFolderCount(rawQuery, accountId, "unread");
+}
+
 export interface SmartFolderRow {
   message_id: string;
   account_id: string;
@@ -0,0 +1,68 @@
+import { describe, it, expect, beforeEach, vi } from "vitest";
+
+const mockInvoke = vi.fn();
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: (...args: unknown[]) => mockInvoke(...args),
+}));
+
+import { getThreadSnippets, splitHighlights } from "./snippets";
+
+function hit(accountId: string, messageId: string, threadId: string) {
+  return {
+    message_id: messageId,
+    account_id: accountId,
+    thread_id: threadId,
+    subject: null,
+    from_name: null,
+    from_address: null,
+    snippet: null,
+    date: 0,
+    rank: 0,
+  };
+}
+
+describe("splitHighlights", () => {
+  it("splits marked terms into highlighted segments", () => {
+    expect(splitHighlights("…pay the \u0002invoice\u0003 by \u0002Friday\u0003")).toEqual([
+      { text: "…pay the ", highlighted: false },
+      { text: "invoice", highlighted: true },
+      { text: " by ", highlighted: false },
+      { text: "Friday", highlighted: true },
+    ]);
+  });
+
+  it("returns plain text as a single segment", () => {
+    expect(splitHighlights("no markers")).toEqual([{ text: "no markers", highlighted: false }]);
+  });
+});
+
+describe("getThreadSnippets", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+  });
+
+  it("requests the first hit of each thread per account and keys by thread", async () => {
+    mockInvoke.mockImplementation((_cmd: string, args: { accountId: string }) =>
+      Promise.resolve(
+        args.accountId === "acc-1" ? { m1: "\u0002a\u0003" } : { m3: "\u0002c\u0003" },
+      ),
+    );
+
+    const snippets = await getThreadSnippets(
+      [hit("acc-1", "m1", "t1"), hit("acc-1", "m2", "t1"), hit("acc-2", "m3", "t2"), hit("acc-1", "m4", "t3")],
+      "a",
+    );
+
+    expect(mockInvoke).toHaveBeenCalledWith("search_snippets", {
+      accountId: "acc-1",
+      messageIds: ["m1", "m4"],
+      query: "a",
+    });
+    expect(mockInvoke).toHaveBeenCalledWith("search_snippets", {
+      accountId: "acc-2",
+      messageIds: ["m3"],
+      query: "a",
+    });
+    expect(snippets).toEqual({ t1: "\u0002a\u0003", t2: "\u0002c\u0003" });
+  });
+});
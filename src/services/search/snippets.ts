@@ -0,0 +1,84 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { SearchResult } from "../db/search";
+
+/** Marks the start of a highlighted term in a snippet from `search_snippets`. */
+export const HIGHLIGHT_START = "\u0002";
+/** Marks the end of a highlighted term. */
+export const HIGHLIGHT_END = "\u0003";
+
+export interface SnippetSegment {
+  text: string;
+  highlighted: boolean;
+}
+
+/**
+ * Highlighted context snippets for messages of one account, keyed by message
+ * id. Computed in the backend from the stored bodies; messages with no match
+ * in their body are missing from the result.
+ */
+export function getHighlightedSnippets(
+  accountId: string,
+  messageIds: string[],
+  query: string,
+): Promise<Record<string, string>> {
+  return invoke<Record<string, string>>("search_snippets", {
+    accountId,
+    messageIds,
+    query,
+  });
+}
+
+/**
+ * Highlighted snippets for search hits keyed by thread id, using the first
+ * (best ranked) hit of each thread.
+ */
+export async function getThreadSnippets(
+  hits: SearchResult[],
+  query: string,
+): Promise<Record<string, string>> {
+  const firstHits = new Map<string, SearchResult>();
+  for (const hit of hits) {
+    if (!firstHits.has(hit.thread_id)) firstHits.set(hit.thread_id, hit);
+  }
+
+  const byAccount = new Map<string, SearchResult[]>();
+  for (const hit of firstHits.values()) {
+    const list = byAccount.get(hit.account_id) ?? [];
+    list.push(hit);
+    byAccount.set(hit.account_id, list);
+  }
+
+  const snippets: Record<string, string> = {};
+  await Promise.all(
+    [...byAccount].map(async ([accountId, accountHits]) => {
+      const byMessage = await getHighlightedSnippets(
+        accountId,
+        accountHits.map((h) => h.message_id),
+        query,
+      );
+      for (const hit of accountHits) {
+        const snippet = byMessage[hit.message_id];
+        if (snippet) snippets[hit.thread_id] = snippet;
+      }
+    }),
+  );
+  return snippets;
+}
+
+/** Split a marked-up snippet into plain and highlighted runs for rendering. */
+export function splitHighlights(snippet: string): SnippetSegment[] {
+  const segments: SnippetSegment[] = [];
+  let highlighted = false;
+  let text = "";
+  for (const ch of snippet) {
+    if (ch === HIGHLIGHT_START || ch === HIGHLIGHT_END) {
+      if (text) segments.push({ text, highlighted });
+      text = "";
+      highlighted = ch === HIGHLIGHT_START;
+    } else {
+      text += ch;
+    }
+  }
+  if (text) segments.push({ text, highlighted });
+  return segments;
+}
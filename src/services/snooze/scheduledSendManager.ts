@@ -5,6 +5,7 @@ import {
 import { getGmailClient } from "../gmail/tokenManager";
 import { buildRawEmail, type EmailAttachment } from "@/utils/emailBuilder";
 import { getAccount } from "../db/accounts";
+import { getDisclaimer } from "../composer/disclaimer";
 import { createBackgroundChecker } from "../backgroundCheckers";
 
 /**
@@ -49,6 +50,7 @@ async function checkScheduledEmails(): Promise<void> {
         htmlBody: email.body_html,
         threadId: email.thread_id ?? undefined,
         attachments,
+        disclaimer: await getDisclaimer(account.email),
       });
 
       await client.sendMessage(raw, email.thread_id ?? undefined);
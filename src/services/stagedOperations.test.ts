@@ -0,0 +1,151 @@
+import { describe, it, expect, beforeEach, afterEach, vi } from "vitest";
+
+const mockGetSetting = vi.fn();
+vi.mock("@/services/db/settings", () => ({
+  getSetting: (...args: unknown[]) => mockGetSetting(...args),
+}));
+
+vi.mock("@/services/db/threads", () => ({
+  deleteThread: vi.fn(() => Promise.resolve()),
+}));
+
+vi.mock("@/bindings", () => ({
+  commands: {
+    imapStageDelete: vi.fn(),
+    undoOperation: vi.fn(),
+    stagedOperationList: vi.fn(),
+  },
+}));
+
+import { commands } from "@/bindings";
+import {
+  getUndoWindowMs,
+  stageOperation,
+  stageImapDelete,
+  undoOperation,
+  commitOperation,
+} from "./stagedOperations";
+import { useStagedOperationStore } from "@/stores/stagedOperationStore";
+
+describe("stagedOperations", () => {
+  beforeEach(() => {
+    vi.useFakeTimers();
+    vi.clearAllMocks();
+    useStagedOperationStore.setState({ operations: [], error: null });
+  });
+
+  afterEach(() => {
+    vi.useRealTimers();
+  });
+
+  it("runs the operation when the window closes", async () => {
+    const commit = vi.fn(() => Promise.resolve());
+    const id = stageOperation("Deleted", 5_000, commit);
+
+    expect(useStagedOperationStore.getState().operations.map((op) => op.id)).toEqual([id]);
+    await vi.advanceTimersByTimeAsync(4_999);
+    expect(commit).not.toHaveBeenCalled();
+    await vi.advanceTimersByTimeAsync(1);
+    expect(commit).toHaveBeenCalledTimes(1);
+    expect(useStagedOperationStore.getState().operations).toEqual([]);
+  });
+
+  it("cancels on undo and calls the undo hook", async () => {
+    const commit = vi.fn(() => Promise.resolve());
+    const onUndo = vi.fn();
+    const id = stageOperation("Deleted", 5_000, commit, onUndo);
+
+    expect(await undoOperation(id)).toBe(true);
+    await vi.advanceTimersByTimeAsync(5_000);
+
+    expect(commit).not.toHaveBeenCalled();
+    expect(onUndo).toHaveBeenCalledTimes(1);
+    expect(useStagedOperationStore.getState().operations).toEqual([]);
+  });
+
+  it("commits early on request and cannot be undone afterwards", async () => {
+    const commit = vi.fn(() => Promise.resolve());
+    const id = stageOperation("Deleted", 5_000, commit);
+
+    await commitOperation(id);
+
+    expect(commit).toHaveBeenCalledTimes(1);
+    vi.mocked(commands.undoOperation).mockResolvedValueOnce({ status: "ok", data: false });
+    expect(await undoOperation(id)).toBe(false);
+    await vi.advanceTimersByTimeAsync(5_000);
+    expect(commit).toHaveBeenCalledTimes(1);
+  });
+
+  it("shows the user why a commit failed", async () => {
+    const listener = vi.fn();
+    window.addEventListener("velo-sync-done", listener);
+    vi.spyOn(console, "error").mockImplementation(() => {});
+
+    stageOperation("Deleted", 5_000, () => Promise.reject(new Error("Mailbox is locked")));
+    await vi.advanceTimersByTimeAsync(5_000);
+
+    window.removeEventListener("velo-sync-done", listener);
+    expect(useStagedOperationStore.getState().error).toBe("Delete failed: Mailbox is locked");
+    expect(listener).toHaveBeenCalledTimes(1);
+  });
+
+  describe("stageImapDelete", () => {
+    const targets = [{ thread_id: "t1", folder: "INBOX", uids: [4, 7] }];
+
+    beforeEach(() => {
+      vi.mocked(commands.imapStageDelete).mockResolvedValue({
+        status: "ok",
+        data: {
+          id: "op-1",
+          account_id: "acct-1",
+          label: "Deleted",
+          targets,
+          run_at: 5_000,
+          attempts: 0,
+        },
+      });
+    });
+
+    it("hands the delete to the backend with the window in seconds", async () => {
+      const id = await stageImapDelete("acct-1", "Deleted", targets, 4_500);
+
+      expect(id).toBe("op-1");
+      expect(commands.imapStageDelete).toHaveBeenCalledWith("acct-1", "Deleted", targets, 5);
+      expect(useStagedOperationStore.getState().operations).toEqual([
+        { id: "op-1", label: "Deleted", expiresAt: 5_000 },
+      ]);
+    });
+
+    it("asks the backend to cancel on undo", async () => {
+      vi.mocked(commands.undoOperation).mockResolvedValueOnce({ status: "ok", data: true });
+      const onUndo = vi.fn();
+      const id = await stageImapDelete("acct-1", "Deleted", targets, 5_000, onUndo);
+
+      expect(await undoOperation(id)).toBe(true);
+      expect(commands.undoOperation).toHaveBeenCalledWith("op-1");
+      expect(onUndo).toHaveBeenCalledTimes(1);
+      expect(useStagedOperationStore.getState().operations).toEqual([]);
+    });
+
+    it("keeps the toast when the backend already ran it", async () => {
+      vi.mocked(commands.undoOperation).mockResolvedValueOnce({ status: "ok", data: false });
+      const onUndo = vi.fn();
+      const id = await stageImapDelete("acct-1", "Deleted", targets, 5_000, onUndo);
+
+      expect(await undoOperation(id)).toBe(false);
+      expect(onUndo).not.toHaveBeenCalled();
+    });
+  });
+
+  describe("getUndoWindowMs", () => {
+    it("defaults to ten seconds", async () => {
+      mockGetSetting.mockResolvedValueOnce(null);
+      expect(await getUndoWindowMs()).toBe(10_000);
+    });
+
+    it("treats zero as disabled", async () => {
+      mockGetSetting.mockResolvedValueOnce("0");
+      expect(await getUndoWindowMs()).toBe(0);
+    });
+  });
+});
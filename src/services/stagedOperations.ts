@@ -0,0 +1,149 @@
+import {
+  commands,
+  type DeleteTarget,
+  type StagedOperation,
+  type StagedOperationDone,
+} from "@/bindings";
+import { unwrap } from "@/services/commandResult";
+import { getSetting } from "@/services/db/settings";
+import { deleteThread } from "@/services/db/threads";
+import { useStagedOperationStore } from "@/stores/stagedOperationStore";
+
+const DEFAULT_UNDO_WINDOW_SECONDS = 10;
+
+interface PendingEntry {
+  /** Only for operations held in this window; the backend times its own. */
+  timer?: ReturnType<typeof setTimeout>;
+  commit?: () => Promise<void>;
+  onUndo?: () => void;
+}
+
+const pending = new Map<string, PendingEntry>();
+
+/** Undo window for destructive operations, from the `undo_delete_seconds` setting. */
+export async function getUndoWindowMs(): Promise<number> {
+  const value = await getSetting("undo_delete_seconds");
+  const seconds = value !== null ? parseInt(value, 10) : DEFAULT_UNDO_WINDOW_SECONDS;
+  return Number.isFinite(seconds) && seconds > 0 ? seconds * 1000 : 0;
+}
+
+/** Bring back threads hidden by an optimistic update that was undone or failed. */
+function reloadThreadList(): void {
+  window.dispatchEvent(new Event("velo-sync-done"));
+}
+
+/** Tell the user a delete didn't happen, and show its conversations again. */
+export function reportOperationFailure(error: unknown): void {
+  const message = error instanceof Error ? error.message : String(error);
+  useStagedOperationStore.getState().setError(`Delete failed: ${message}`);
+  reloadThreadList();
+}
+
+async function run(id: string): Promise<void> {
+  const entry = pending.get(id);
+  if (!entry?.commit) return;
+  pending.delete(id);
+  clearTimeout(entry.timer);
+  useStagedOperationStore.getState().removeOperation(id);
+  try {
+    await entry.commit();
+  } catch (err) {
+    console.error("Staged operation failed:", err);
+    reportOperationFailure(err);
+  }
+}
+
+/**
+ * Hold `commit` back for `windowMs` so the user can take it back with
+ * `undoOperation`. Nothing reaches the server until the window closes.
+ * This window keeps the timer, so reloading or quitting drops the
+ * operation; IMAP deletes use `stageImapDelete`, which the backend keeps.
+ */
+export function stageOperation(
+  label: string,
+  windowMs: number,
+  commit: () => Promise<void>,
+  onUndo?: () => void,
+): string {
+  const id = crypto.randomUUID();
+  const timer = setTimeout(() => {
+    run(id);
+  }, windowMs);
+  pending.set(id, { timer, commit, onUndo });
+  useStagedOperationStore.getState().addOperation({
+    id,
+    label,
+    expiresAt: Date.now() + windowMs,
+  });
+  return id;
+}
+
+/**
+ * Have the backend delete `targets` once `windowMs` has passed. It holds
+ * the operation across reloads and restarts and reports the outcome with
+ * `staged-operation-done` (see `initStagedOperations`).
+ */
+export async function stageImapDelete(
+  accountId: string,
+  label: string,
+  targets: DeleteTarget[],
+  windowMs: number,
+  onUndo?: () => void,
+): Promise<string> {
+  const op = unwrap<StagedOperation>(
+    await commands.imapStageDelete(accountId, label, targets, Math.ceil(windowMs / 1000)),
+  );
+  pending.set(op.id, { onUndo });
+  useStagedOperationStore.getState().addOperation({ id: op.id, label, expiresAt: op.run_at });
+  return op.id;
+}
+
+/** Cancel a staged operation before it runs. False if it already ran or is unknown. */
+export async function undoOperation(id: string): Promise<boolean> {
+  const entry = pending.get(id);
+  if (entry?.commit) {
+    clearTimeout(entry.timer);
+  } else if (!unwrap<boolean>(await commands.undoOperation(id))) {
+    return false;
+  }
+  pending.delete(id);
+  useStagedOperationStore.getState().removeOperation(id);
+  (entry?.onUndo ?? reloadThreadList)();
+  return true;
+}
+
+/** Run a staged operation now instead of waiting out its window. */
+export function commitOperation(id: string): Promise<void> {
+  return run(id);
+}
+
+/**
+ * Show the undo toast for deletes the backend is still holding (e.g. after
+ * a reload), and apply their outcome when they run: drop the deleted
+ * conversations from the local DB, or tell the user the delete failed.
+ */
+export async function initStagedOperations(): Promise<() => void> {
+  const store = useStagedOperationStore.getState();
+  for (const op of await commands.stagedOperationList()) {
+    if (!store.operations.some((o) => o.id === op.id)) {
+      store.addOperation({ id: op.id, label: op.label, expiresAt: op.run_at });
+    }
+  }
+
+  const { listen } = await import("@tauri-apps/api/event");
+  return listen<StagedOperationDone>("staged-operation-done", async ({ payload }) => {
+    pending.delete(payload.id);
+    useStagedOperationStore.getState().removeOperation(payload.id);
+    if (payload.error !== null) {
+      reportOperationFailure(payload.error);
+      return;
+    }
+    try {
+      for (const threadId of payload.thread_ids) {
+        await deleteThread(payload.account_id, threadId);
+      }
+    } catch (err) {
+      console.warn("Local DB update failed:", err);
+    }
+  });
+}
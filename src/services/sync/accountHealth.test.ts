@@ -0,0 +1,93 @@
+import { describe, it, expect, beforeEach, vi } from "vitest";
+import {
+  ACCOUNT_HEALTH_EVENT,
+  BASE_RETRY_DELAY_MS,
+  FAILURES_TO_OPEN_CIRCUIT,
+  MAX_RETRY_DELAY_MS,
+  clearBackoff,
+  getAccountHealth,
+  isBackingOff,
+  recordSyncFailure,
+  recordSyncSuccess,
+  resetAccountHealth,
+  retryDelayMs,
+} from "./accountHealth";
+import { useAccountHealthStore } from "@/stores/accountHealthStore";
+
+describe("accountHealth", () => {
+  beforeEach(() => {
+    resetAccountHealth("a1");
+    resetAccountHealth("a2");
+  });
+
+  it("starts healthy", () => {
+    expect(getAccountHealth("a1").state).toBe("ok");
+    expect(isBackingOff("a1")).toBe(false);
+  });
+
+  it("doubles the retry delay up to the cap", () => {
+    expect(retryDelayMs(1)).toBe(BASE_RETRY_DELAY_MS);
+    expect(retryDelayMs(2)).toBe(BASE_RETRY_DELAY_MS * 2);
+    expect(retryDelayMs(3)).toBe(BASE_RETRY_DELAY_MS * 4);
+    expect(retryDelayMs(50)).toBe(MAX_RETRY_DELAY_MS);
+  });
+
+  it("marks a single failure as degraded and backs off", () => {
+    const health = recordSyncFailure("a1", "Connection reset", false, 1000);
+    expect(health).toMatchObject({
+      state: "degraded",
+      reason: "Connection reset",
+      consecutiveFailures: 1,
+      nextRetryAt: 1000 + BASE_RETRY_DELAY_MS,
+    });
+    expect(isBackingOff("a1", 1000 + BASE_RETRY_DELAY_MS - 1)).toBe(true);
+    expect(isBackingOff("a1", 1000 + BASE_RETRY_DELAY_MS)).toBe(false);
+    expect(isBackingOff("a2", 1000)).toBe(false);
+  });
+
+  it("opens the circuit after repeated failures", () => {
+    for (let i = 0; i < FAILURES_TO_OPEN_CIRCUIT; i++) {
+      recordSyncFailure("a1", "Connection refused", false, 1000);
+    }
+    expect(getAccountHealth("a1")).toMatchObject({
+      state: "failing",
+      nextRetryAt: 1000 + retryDelayMs(FAILURES_TO_OPEN_CIRCUIT),
+    });
+  });
+
+  it("does not back off on auth errors", () => {
+    const health = recordSyncFailure("a1", "Invalid credentials", true, 1000);
+    expect(health).toMatchObject({ state: "failing", consecutiveFailures: 0, nextRetryAt: null });
+    expect(isBackingOff("a1", 1000)).toBe(false);
+  });
+
+  it("clearBackoff allows an immediate retry but keeps the reason", () => {
+    recordSyncFailure("a1", "Connection refused", false, Date.now());
+    clearBackoff("a1");
+    expect(isBackingOff("a1")).toBe(false);
+    expect(getAccountHealth("a1").reason).toBe("Connection refused");
+  });
+
+  it("a success clears failures", () => {
+    for (let i = 0; i < FAILURES_TO_OPEN_CIRCUIT; i++) {
+      recordSyncFailure("a1", "Connection refused", false, 1000);
+    }
+    recordSyncSuccess("a1", 2000);
+    expect(getAccountHealth("a1")).toMatchObject({ state: "ok", reason: null, consecutiveFailures: 0, lastSuccessAt: 2000 });
+    expect(isBackingOff("a1", 2000)).toBe(false);
+  });
+
+  it("publishes health to the store and as an event", () => {
+    const listener = vi.fn();
+    window.addEventListener(ACCOUNT_HEALTH_EVENT, listener);
+
+    recordSyncFailure("a1", "Timed out", false, 1000);
+
+    window.removeEventListener(ACCOUNT_HEALTH_EVENT, listener);
+    expect(useAccountHealthStore.getState().health["a1"]?.state).toBe("degraded");
+    expect((listener.mock.calls[0]![0] as CustomEvent).detail).toMatchObject({
+      accountId: "a1",
+      health: { nextRetryAt: 1000 + BASE_RETRY_DELAY_MS },
+    });
+  });
+});
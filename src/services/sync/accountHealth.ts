@@ -0,0 +1,128 @@
+/**
+ * Per-account sync health, backoff and circuit breaker.
+ *
+ * Each account's sync outcomes are tracked on their own, so one unreachable
+ * server only ever affects its own account. Every failure pushes the next
+ * background attempt out exponentially; once failures pile up the account is
+ * failing and its circuit is open until that retry time. Authentication
+ * errors are reported but not backed off — they need the user, not time.
+ */
+
+import { useAccountHealthStore } from "@/stores/accountHealthStore";
+
+export type AccountHealthState = "ok" | "degraded" | "failing";
+
+export interface AccountHealth {
+  state: AccountHealthState;
+  /** Last error message while degraded or failing. */
+  reason: string | null;
+  consecutiveFailures: number;
+  lastSuccessAt: number | null;
+  lastFailureAt: number | null;
+  /** Background syncs skip the account until this time. */
+  nextRetryAt: number | null;
+}
+
+/** Dispatched on window whenever an account's health changes. */
+export const ACCOUNT_HEALTH_EVENT = "velo-account-health";
+
+export interface AccountHealthDetail {
+  accountId: string;
+  health: AccountHealth;
+}
+
+/** Consecutive failures after which the account is failing and its circuit opens. */
+export const FAILURES_TO_OPEN_CIRCUIT = 3;
+/** Delay before retrying after the first failure; doubles with each failure. */
+export const BASE_RETRY_DELAY_MS = 30_000;
+/** Longest the backoff grows to. */
+export const MAX_RETRY_DELAY_MS = 30 * 60_000;
+
+const healthByAccount = new Map<string, AccountHealth>();
+
+function initialHealth(): AccountHealth {
+  return {
+    state: "ok",
+    reason: null,
+    consecutiveFailures: 0,
+    lastSuccessAt: null,
+    lastFailureAt: null,
+    nextRetryAt: null,
+  };
+}
+
+function update(accountId: string, health: AccountHealth): AccountHealth {
+  healthByAccount.set(accountId, health);
+  useAccountHealthStore.getState().setHealth(accountId, health);
+  window.dispatchEvent(
+    new CustomEvent<AccountHealthDetail>(ACCOUNT_HEALTH_EVENT, {
+      detail: { accountId, health },
+    }),
+  );
+  return health;
+}
+
+export function getAccountHealth(accountId: string): AccountHealth {
+  return healthByAccount.get(accountId) ?? initialHealth();
+}
+
+/** Backoff after `failures` consecutive failures. */
+export function retryDelayMs(failures: number): number {
+  return Math.min(BASE_RETRY_DELAY_MS * 2 ** Math.max(failures - 1, 0), MAX_RETRY_DELAY_MS);
+}
+
+/** A successful sync closes the circuit and clears any failure state. */
+export function recordSyncSuccess(accountId: string, now = Date.now()): AccountHealth {
+  return update(accountId, { ...initialHealth(), lastSuccessAt: now });
+}
+
+/**
+ * Record a failed sync and schedule the next background attempt. Auth
+ * failures mark the account failing right away but keep the normal poll
+ * schedule, so a re-authorization takes effect on the next tick.
+ */
+export function recordSyncFailure(
+  accountId: string,
+  reason: string,
+  isAuthError = false,
+  now = Date.now(),
+): AccountHealth {
+  const prev = getAccountHealth(accountId);
+  if (isAuthError) {
+    return update(accountId, {
+      ...prev,
+      state: "failing",
+      reason,
+      lastFailureAt: now,
+      nextRetryAt: null,
+    });
+  }
+
+  const consecutiveFailures = prev.consecutiveFailures + 1;
+  return update(accountId, {
+    ...prev,
+    state: consecutiveFailures >= FAILURES_TO_OPEN_CIRCUIT ? "failing" : "degraded",
+    reason,
+    consecutiveFailures,
+    lastFailureAt: now,
+    nextRetryAt: now + retryDelayMs(consecutiveFailures),
+  });
+}
+
+/** Whether background syncs should leave this account alone for now. */
+export function isBackingOff(accountId: string, now = Date.now()): boolean {
+  const until = healthByAccount.get(accountId)?.nextRetryAt;
+  return until != null && now < until;
+}
+
+/** Clear the backoff so the next sync attempt goes ahead, keeping the failure reason. */
+export function clearBackoff(accountId: string): void {
+  const prev = healthByAccount.get(accountId);
+  if (prev?.nextRetryAt != null) update(accountId, { ...prev, nextRetryAt: null });
+}
+
+/** Forget an account's health, e.g. when it is removed or resynced. */
+export function resetAccountHealth(accountId: string): void {
+  healthByAccount.delete(accountId);
+  useAccountHealthStore.getState().clearHealth(accountId);
+}
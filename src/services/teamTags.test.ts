@@ -0,0 +1,128 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+
+vi.mock("./db/accounts", () => ({
+  getAccount: vi.fn(),
+}));
+
+vi.mock("./db/messages", () => ({
+  getFolderMessageKeywords: vi.fn(),
+  getMessage: vi.fn(),
+  setMessageKeywords: vi.fn(),
+}));
+
+vi.mock("./imap/tauriCommands", () => ({
+  imapFetchKeywords: vi.fn(),
+  imapSetFlags: vi.fn(),
+}));
+
+import { getAccount } from "./db/accounts";
+import { getFolderMessageKeywords, getMessage, setMessageKeywords } from "./db/messages";
+import { imapFetchKeywords, imapSetFlags } from "./imap/tauriCommands";
+import { categoriesFromKeywords } from "./colorCategories";
+import { parseTeamMembers, setTeamAssignee, syncTeamTags, teamTagsFromKeywords } from "./teamTags";
+
+type Account = Awaited<ReturnType<typeof getAccount>>;
+type Message = Awaited<ReturnType<typeof getMessage>>;
+
+describe("team tag keywords", () => {
+  it("reads the assignee and status", () => {
+    expect(teamTagsFromKeywords("$label1 $Assigned:bob@example.com $Status:in_progress")).toEqual({
+      assignee: "bob@example.com",
+      status: "in_progress",
+    });
+    expect(teamTagsFromKeywords(null)).toEqual({ assignee: null, status: null });
+    expect(teamTagsFromKeywords(["$status:bogus"]).status).toBeNull();
+  });
+
+  it("picks the same assignee everywhere when two were set at once", () => {
+    expect(teamTagsFromKeywords("$Assigned:zoe@example.com $Assigned:ana@example.com").assignee).toBe(
+      "ana@example.com",
+    );
+  });
+
+  it("aren't shown as color categories", () => {
+    expect(categoriesFromKeywords("$Assigned:ana@example.com $Status:done $label2").map((c) => c.name)).toEqual([
+      "Work",
+    ]);
+  });
+
+  it("parses the member list", () => {
+    expect(parseTeamMembers("ana@example.com, bob@example.com\nzoe@example.com")).toEqual([
+      "ana@example.com",
+      "bob@example.com",
+      "zoe@example.com",
+    ]);
+    expect(parseTeamMembers(null)).toEqual([]);
+  });
+});
+
+describe("setTeamAssignee", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    vi.mocked(getMessage).mockResolvedValue({
+      id: "m1",
+      imap_uid: 42,
+      imap_folder: "Shared/Support",
+      keywords: "$Assigned:ana@example.com",
+    } as Message);
+  });
+
+  it("replaces assignees set by anyone, per the server", async () => {
+    vi.mocked(imapFetchKeywords).mockResolvedValue([
+      { uid: 42, keywords: ["$Assigned:ana@example.com", "$Assigned:zoe@example.com", "$label1"] },
+    ]);
+
+    await setTeamAssignee("acct-1", "m1", "bob@example.com");
+
+    expect(imapSetFlags).toHaveBeenCalledWith("acct-1", "Shared/Support", [42], [], false, [
+      "$Assigned:ana@example.com",
+      "$Assigned:zoe@example.com",
+    ]);
+    expect(imapSetFlags).toHaveBeenCalledWith("acct-1", "Shared/Support", [42], [], true, [
+      "$Assigned:bob@example.com",
+    ]);
+    expect(setMessageKeywords).toHaveBeenCalledWith("acct-1", "m1", ["$label1", "$Assigned:bob@example.com"]);
+  });
+
+  it("only removes when unassigning", async () => {
+    vi.mocked(imapFetchKeywords).mockResolvedValue([{ uid: 42, keywords: ["$Assigned:ana@example.com"] }]);
+
+    await setTeamAssignee("acct-1", "m1", null);
+
+    expect(imapSetFlags).toHaveBeenCalledTimes(1);
+    expect(setMessageKeywords).toHaveBeenCalledWith("acct-1", "m1", []);
+  });
+});
+
+describe("syncTeamTags", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    vi.mocked(getAccount).mockResolvedValue({
+      provider: "imap",
+      team_folder: "Shared/Support",
+      team_members: "ana@example.com",
+    } as Account);
+  });
+
+  it("stores keywords changed on the server", async () => {
+    vi.mocked(getFolderMessageKeywords).mockResolvedValue([
+      { id: "m1", imap_uid: 1, keywords: "$Status:open" },
+      { id: "m2", imap_uid: 2, keywords: "$label1 $Status:done" },
+    ]);
+    vi.mocked(imapFetchKeywords).mockResolvedValue([
+      { uid: 1, keywords: ["$Status:done", "$Assigned:ana@example.com"] },
+      { uid: 2, keywords: ["$Status:done", "$label1"] },
+    ]);
+
+    expect(await syncTeamTags("acct-1")).toBe(1);
+    expect(imapFetchKeywords).toHaveBeenCalledWith("acct-1", "Shared/Support", [1, 2]);
+    expect(setMessageKeywords).toHaveBeenCalledTimes(1);
+    expect(setMessageKeywords).toHaveBeenCalledWith("acct-1", "m1", ["$Status:done", "$Assigned:ana@example.com"]);
+  });
+
+  it("does nothing without a team folder", async () => {
+    vi.mocked(getAccount).mockResolvedValue({ provider: "imap", team_folder: null } as Account);
+    expect(await syncTeamTags("acct-1")).toBe(0);
+    expect(imapFetchKeywords).not.toHaveBeenCalled();
+  });
+});
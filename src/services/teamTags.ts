@@ -0,0 +1,169 @@
+import { getAccount } from "./db/accounts";
+import { getFolderMessageKeywords, getMessage, setMessageKeywords } from "./db/messages";
+import { imapFetchKeywords, imapSetFlags } from "./imap/tauriCommands";
+import type { MessageCategoriesDetail } from "./colorCategories";
+
+/**
+ * Experimental triage for a mailbox shared by a small team. Messages in the
+ * account's `team_folder` can be assigned to a member and given a status,
+ * both stored on the server as IMAP keywords (`$Assigned:<member>`,
+ * `$Status:<state>`), so every Velo instance on the same mailbox sees the
+ * same state. `syncTeamTags` re-reads the folder's keywords after each sync
+ * to pick up changes made elsewhere.
+ *
+ * If two people tag a message at the same moment it can end up with two
+ * assignees; every instance shows the same one (the first in sort order),
+ * and the next change replaces both.
+ */
+
+const ASSIGNED_PREFIX = "$Assigned:";
+const STATUS_PREFIX = "$Status:";
+
+export const TEAM_STATUSES = [
+  { value: "open", label: "Open" },
+  { value: "in_progress", label: "In Progress" },
+  { value: "waiting", label: "Waiting" },
+  { value: "done", label: "Done" },
+] as const;
+
+export type TeamStatus = (typeof TEAM_STATUSES)[number]["value"];
+
+export interface TeamTags {
+  assignee: string | null;
+  status: TeamStatus | null;
+}
+
+export interface TeamFolder {
+  folder: string;
+  members: string[];
+}
+
+function keywordList(keywords: string | string[] | null | undefined): string[] {
+  if (!keywords) return [];
+  return Array.isArray(keywords) ? keywords : keywords.split(" ").filter(Boolean);
+}
+
+function hasPrefix(keyword: string, prefix: string): boolean {
+  return keyword.toLowerCase().startsWith(prefix.toLowerCase());
+}
+
+/** Whether a keyword is a team tag rather than e.g. a color category. */
+export function isTeamKeyword(keyword: string): boolean {
+  return hasPrefix(keyword, ASSIGNED_PREFIX) || hasPrefix(keyword, STATUS_PREFIX);
+}
+
+/** A tag value as a keyword suffix: IMAP keywords can't hold spaces or `(){%*"\]`. */
+function keywordValue(value: string): string {
+  return value.trim().replace(/[^\x21-\x7e]|[(){%*"\\\]]/g, "_");
+}
+
+/** The values of a message's keywords with `prefix`, sorted so every client picks the same one. */
+function tagValues(keywords: string | string[] | null | undefined, prefix: string): string[] {
+  return keywordList(keywords)
+    .filter((k) => hasPrefix(k, prefix))
+    .map((k) => k.slice(prefix.length))
+    .filter(Boolean)
+    .sort();
+}
+
+export function teamTagsFromKeywords(keywords: string | string[] | null | undefined): TeamTags {
+  const status = tagValues(keywords, STATUS_PREFIX)
+    .map((v) => v.toLowerCase())
+    .find((v): v is TeamStatus => TEAM_STATUSES.some((s) => s.value === v));
+  return {
+    assignee: tagValues(keywords, ASSIGNED_PREFIX)[0] ?? null,
+    status: status ?? null,
+  };
+}
+
+/** The addresses in an account's `team_members` column. */
+export function parseTeamMembers(value: string | null | undefined): string[] {
+  return (value ?? "")
+    .split(/[,\s]+/)
+    .map((m) => m.trim())
+    .filter(Boolean);
+}
+
+/** The account's shared team folder and members, or null when it has none. */
+export async function getTeamFolder(accountId: string): Promise<TeamFolder | null> {
+  const account = await getAccount(accountId);
+  if (account?.provider !== "imap" || !account.team_folder) return null;
+  return { folder: account.team_folder, members: parseTeamMembers(account.team_members) };
+}
+
+function notifyKeywords(messageId: string, keywords: string[]): void {
+  window.dispatchEvent(
+    new CustomEvent<MessageCategoriesDetail>("velo-message-categories", {
+      detail: { messageId, keywords: keywords.join(" ") },
+    }),
+  );
+}
+
+/**
+ * Replace a message's team tag with `prefix` by `value` (or just remove it).
+ * Starts from the server's keywords so tags set by others are replaced too.
+ */
+async function replaceTeamTag(
+  accountId: string,
+  messageId: string,
+  prefix: string,
+  value: string | null,
+): Promise<void> {
+  const message = await getMessage(accountId, messageId);
+  if (message?.imap_uid == null || !message.imap_folder) return;
+  const { imap_folder: folder, imap_uid: uid } = message;
+
+  const server = await imapFetchKeywords(accountId, folder, [uid]);
+  const current = server[0]?.keywords ?? keywordList(message.keywords);
+  const stale = current.filter((k) => hasPrefix(k, prefix));
+  const next = value ? `${prefix}${keywordValue(value)}` : null;
+
+  const toRemove = stale.filter((k) => k !== next);
+  if (toRemove.length > 0) {
+    await imapSetFlags(accountId, folder, [uid], [], false, toRemove);
+  }
+  if (next && !stale.includes(next)) {
+    await imapSetFlags(accountId, folder, [uid], [], true, [next]);
+  }
+
+  const keywords = [...current.filter((k) => !hasPrefix(k, prefix)), ...(next ? [next] : [])];
+  await setMessageKeywords(accountId, messageId, keywords);
+  notifyKeywords(messageId, keywords);
+}
+
+/** Assign a message in the team folder to `member`, or unassign it. */
+export function setTeamAssignee(accountId: string, messageId: string, member: string | null): Promise<void> {
+  return replaceTeamTag(accountId, messageId, ASSIGNED_PREFIX, member);
+}
+
+/** Set or clear the status of a message in the team folder. */
+export function setTeamStatus(accountId: string, messageId: string, status: TeamStatus | null): Promise<void> {
+  return replaceTeamTag(accountId, messageId, STATUS_PREFIX, status);
+}
+
+/**
+ * Pick up keyword changes other clients made in the account's team folder
+ * since its messages were synced. Resolves to how many messages changed;
+ * does nothing for accounts without a team folder.
+ */
+export async function syncTeamTags(accountId: string): Promise<number> {
+  const team = await getTeamFolder(accountId);
+  if (!team) return 0;
+
+  const local = await getFolderMessageKeywords(accountId, team.folder);
+  if (local.length === 0) return 0;
+  const byUid = new Map(local.map((m) => [m.imap_uid, m]));
+
+  const server = await imapFetchKeywords(accountId, team.folder, [...byUid.keys()]);
+  let changed = 0;
+  for (const { uid, keywords } of server) {
+    const message = byUid.get(uid);
+    if (!message) continue;
+    const before = [...keywordList(message.keywords)].sort().join(" ");
+    if ([...keywords].sort().join(" ") === before) continue;
+    await setMessageKeywords(accountId, message.id, keywords);
+    notifyKeywords(message.id, keywords);
+    changed++;
+  }
+  return changed;
+}
@@ -3,9 +3,20 @@
  * Groups messages into conversation threads using Message-ID,
  * In-Reply-To, and References headers.
  *
+ * Sync threads through the backend (`threadMessages`, `threading.rs`),
+ * which runs the same algorithm and keeps thread IDs already in the local
+ * cache. `buildThreads` is the in-process equivalent and produces the same
+ * IDs for the same messages.
+ *
  * Reference: https://www.jwz.org/doc/threading.html
  */
 
+import { invoke } from "@tauri-apps/api/core";
+import { normalizeSubject } from "@/utils/subject";
+
+// Subject normalization lives in utils so thread storage shares it
+export { normalizeSubject };
+
 export interface ThreadableMessage {
   id: string; // local message ID (from DB)
   messageId: string; // RFC 2822 Message-ID header
@@ -20,6 +31,19 @@ export interface ThreadGroup {
   messageIds: string[]; // local message IDs belonging to this thread
 }
 
+/**
+ * Thread messages in the backend. Threads that already exist in the local
+ * cache (for a message in the group or one it refers to) keep their ID, so
+ * replies synced later land in the right thread.
+ */
+export function threadMessages(
+  accountId: string,
+  messages: ThreadableMessage[],
+): Promise<ThreadGroup[]> {
+  if (messages.length === 0) return Promise.resolve([]);
+  return invoke<ThreadGroup[]>("cache_thread_messages", { accountId, messages });
+}
+
 /**
  * Internal container used during threading. Each container wraps a message
  * (or is a phantom placeholder for a referenced but unseen Message-ID)
@@ -36,37 +60,6 @@ interface Container {
 // Utility functions
 // ---------------------------------------------------------------------------
 
-/**
- * Strip Re:/Fwd:/Fw: prefixes and normalize subject for comparison.
- * Also strips [list-prefix] tags.
- */
-export function normalizeSubject(subject: string | null): string {
-  if (!subject) return '';
-
-  let s = subject.trim();
-  let changed = true;
-
-  while (changed) {
-    changed = false;
-
-    // Strip leading [list-prefix] tags like [node-dev]
-    const bracketMatch = /^\[[^\]]*\]\s*/i.exec(s);
-    if (bracketMatch) {
-      s = s.slice(bracketMatch[0].length);
-      changed = true;
-    }
-
-    // Strip leading Re:/Fwd:/Fw: (case-insensitive)
-    const prefixMatch = /^(?:re|fwd|fw)\s*:\s*/i.exec(s);
-    if (prefixMatch) {
-      s = s.slice(prefixMatch[0].length);
-      changed = true;
-    }
-  }
-
-  return s.trim();
-}
-
 /**
  * Parse a References header into individual Message-IDs.
  * Handles angle-bracket-delimited IDs and bare IDs separated by whitespace.
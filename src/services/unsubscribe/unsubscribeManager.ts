@@ -86,11 +86,13 @@ export async function executeUnsubscribe(
         const { getAccount } = await import("../db/accounts");
         const account = await getAccount(accountId);
         const { buildRawEmail } = await import("../../utils/emailBuilder");
+        const { getDisclaimer } = await import("../composer/disclaimer");
         const raw = buildRawEmail({
           from: account?.email ?? "",
           to: [to],
           subject,
           htmlBody: "unsubscribe",
+          disclaimer: account ? await getDisclaimer(account.email) : null,
         });
         await client.sendMessage(raw);
         method = "mailto";
@@ -0,0 +1,19 @@
+import { create } from "zustand";
+import type { AccountHealth } from "@/services/sync/accountHealth";
+
+interface AccountHealthState {
+  health: Record<string, AccountHealth>;
+  setHealth: (accountId: string, health: AccountHealth) => void;
+  clearHealth: (accountId: string) => void;
+}
+
+export const useAccountHealthStore = create<AccountHealthState>((set) => ({
+  health: {},
+  setHealth: (accountId, health) =>
+    set((state) => ({ health: { ...state.health, [accountId]: health } })),
+  clearHealth: (accountId) =>
+    set((state) => {
+      const { [accountId]: _, ...rest } = state.health;
+      return { health: rest };
+    }),
+}));
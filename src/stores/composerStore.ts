@@ -1,15 +1,26 @@
 import { create } from "zustand";
 
-export type ComposerMode = "new" | "reply" | "replyAll" | "forward";
-export type ComposerViewMode = "modal" | "fullpage";
+export type ComposerMode = "new" | "reply" | "replyAll" | "replyList" | "forward";
+/** `compact` is the small frameless quick-compose window. */
+export type ComposerViewMode = "modal" | "fullpage" | "compact";
 
 export interface ComposerAttachment {
   id: string;
-  file: File;
+  file?: File;
   filename: string;
   mimeType: string;
   size: number;
-  content: string; // base64
+  content?: string; // base64, for attachments read in the webview
+  stagedId?: string; // backend-staged attachment (see services/attachments/staging)
+  path?: string; // picked file a staged attachment still reads from
+  thumbnail?: string | null; // data URL preview for staged images
+}
+
+/** Pasted image staged in the backend and shown in the editor via `src`. */
+export interface ComposerInlineImage {
+  stagedId: string;
+  contentId: string;
+  src: string; // preview data URL in the editor HTML
 }
 
 export interface ComposerState {
@@ -27,9 +38,12 @@ export interface ComposerState {
   undoSendTimer: ReturnType<typeof setTimeout> | null;
   undoSendVisible: boolean;
   attachments: ComposerAttachment[];
+  inlineImages: ComposerInlineImage[];
   lastSavedAt: number | null;
   isSaving: boolean;
   fromEmail: string | null;
+  /** Send from a per-recipient plus address (you+tag@domain) */
+  plusAddressing: boolean;
   viewMode: ComposerViewMode;
   signatureHtml: string;
   signatureId: string | null;
@@ -57,10 +71,13 @@ export interface ComposerState {
   setUndoSendVisible: (visible: boolean) => void;
   addAttachment: (attachment: ComposerAttachment) => void;
   removeAttachment: (id: string) => void;
+  updateAttachment: (id: string, patch: Partial<ComposerAttachment>) => void;
   clearAttachments: () => void;
+  addInlineImage: (image: ComposerInlineImage) => void;
   setLastSavedAt: (ts: number | null) => void;
   setIsSaving: (saving: boolean) => void;
   setFromEmail: (email: string | null) => void;
+  setPlusAddressing: (enabled: boolean) => void;
   setViewMode: (mode: ComposerViewMode) => void;
   setSignatureHtml: (html: string) => void;
   setSignatureId: (id: string | null) => void;
@@ -81,8 +98,10 @@ export const useComposerStore = create<ComposerState>((set) => ({
   undoSendTimer: null,
   undoSendVisible: false,
   attachments: [],
+  inlineImages: [],
   viewMode: "modal",
   fromEmail: null,
+  plusAddressing: false,
   lastSavedAt: null,
   isSaving: false,
   signatureHtml: "",
@@ -103,7 +122,9 @@ export const useComposerStore = create<ComposerState>((set) => ({
       draftId: opts?.draftId ?? null,
       viewMode: "modal",
       fromEmail: null,
+      plusAddressing: false,
       attachments: [],
+      inlineImages: [],
       lastSavedAt: null,
       isSaving: false,
       signatureHtml: "",
@@ -124,7 +145,9 @@ export const useComposerStore = create<ComposerState>((set) => ({
       draftId: null,
       viewMode: "modal",
       fromEmail: null,
+      plusAddressing: false,
       attachments: [],
+      inlineImages: [],
       lastSavedAt: null,
       isSaving: false,
       signatureHtml: "",
@@ -145,10 +168,17 @@ export const useComposerStore = create<ComposerState>((set) => ({
     set((state) => ({
       attachments: state.attachments.filter((a) => a.id !== id),
     })),
+  updateAttachment: (id, patch) =>
+    set((state) => ({
+      attachments: state.attachments.map((a) => (a.id === id ? { ...a, ...patch } : a)),
+    })),
   clearAttachments: () => set({ attachments: [] }),
+  addInlineImage: (image) =>
+    set((state) => ({ inlineImages: [...state.inlineImages, image] })),
   setLastSavedAt: (lastSavedAt) => set({ lastSavedAt }),
   setIsSaving: (isSaving) => set({ isSaving }),
   setFromEmail: (fromEmail) => set({ fromEmail }),
+  setPlusAddressing: (plusAddressing) => set({ plusAddressing }),
   setViewMode: (viewMode) => set({ viewMode }),
   setSignatureHtml: (signatureHtml) => set({ signatureHtml }),
   setSignatureId: (signatureId) => set({ signatureId }),
@@ -8,19 +8,9 @@ vi.mock("@/services/db/smartFolders", () => ({
   updateSmartFolderSortOrder: vi.fn(() => Promise.resolve()),
 }));
 
-vi.mock("@/services/search/smartFolderQuery", () => ({
-  getSmartFolderUnreadCount: vi.fn(() => ({
-    sql: "SELECT COUNT(DISTINCT m.id) as count FROM messages m WHERE m.is_read = 0",
-    params: [],
-  })),
-}));
-
-vi.mock("@/services/db/connection", () => ({
-  getDb: vi.fn(() =>
-    Promise.resolve({
-      select: vi.fn(() => Promise.resolve([{ count: 5 }])),
-    }),
-  ),
+const mockRefreshSmartFolderCounts = vi.fn();
+vi.mock("@/services/search/smartFolderCounts", () => ({
+  refreshSmartFolderCounts: (...args: unknown[]) => mockRefreshSmartFolderCounts(...args),
 }));
 
 import {
@@ -35,7 +25,7 @@ describe("smartFolderStore", () => {
     vi.clearAllMocks();
     useSmartFolderStore.setState({
       folders: [],
-      unreadCounts: {},
+      counts: {},
       isLoading: false,
     });
   });
@@ -53,6 +43,7 @@ describe("smartFolderStore", () => {
           sort_order: 0,
           is_default: 1,
           created_at: 1000,
+          count_mode: "unread",
         },
         {
           id: "sf-2",
@@ -64,6 +55,7 @@ describe("smartFolderStore", () => {
           sort_order: 1,
           is_default: 0,
           created_at: 2000,
+          count_mode: "unread",
         },
       ]);
 
@@ -81,6 +73,7 @@ describe("smartFolderStore", () => {
         color: null,
         isDefault: true,
         sortOrder: 0,
+        countMode: "unread",
       });
       expect(folders[1]).toEqual({
         id: "sf-2",
@@ -91,6 +84,7 @@ describe("smartFolderStore", () => {
         color: "#ff0000",
         isDefault: false,
         sortOrder: 1,
+        countMode: "unread",
       });
     });
 
@@ -154,6 +148,7 @@ describe("smartFolderStore", () => {
             color: null,
             isDefault: true,
             sortOrder: 0,
+            countMode: "unread",
           },
           {
             id: "sf-2",
@@ -164,24 +159,25 @@ describe("smartFolderStore", () => {
             color: null,
             isDefault: false,
             sortOrder: 1,
+            countMode: "unread",
           },
         ],
-        unreadCounts: { "sf-1": 5, "sf-2": 3 },
+        counts: { "sf-1": 5, "sf-2": 3 },
       });
 
       await useSmartFolderStore.getState().deleteFolder("sf-1");
 
-      const { folders, unreadCounts } = useSmartFolderStore.getState();
+      const { folders, counts } = useSmartFolderStore.getState();
       expect(folders).toHaveLength(1);
       expect(folders[0]?.id).toBe("sf-2");
-      expect(unreadCounts["sf-1"]).toBeUndefined();
-      expect(unreadCounts["sf-2"]).toBe(3);
+      expect(counts["sf-1"]).toBeUndefined();
+      expect(counts["sf-2"]).toBe(3);
       expect(deleteSmartFolder).toHaveBeenCalledWith("sf-1");
     });
   });
 
-  describe("refreshUnreadCounts", () => {
-    it("populates unread counts for all folders", async () => {
+  describe("refreshCounts", () => {
+    it("stores the counts computed for the account", async () => {
       useSmartFolderStore.setState({
         folders: [
           {
@@ -193,14 +189,27 @@ describe("smartFolderStore", () => {
             color: null,
             isDefault: true,
             sortOrder: 0,
+            countMode: "unread",
           },
         ],
       });
+      mockRefreshSmartFolderCounts.mockResolvedValueOnce({ "sf-1": 5 });
+
+      await useSmartFolderStore.getState().refreshCounts("acc-1");
+
+      expect(mockRefreshSmartFolderCounts).toHaveBeenCalledWith("acc-1");
+      const { counts } = useSmartFolderStore.getState();
+      expect(counts["sf-1"]).toBe(5);
+    });
+
+    it("keeps the previous counts when the refresh fails", async () => {
+      vi.spyOn(console, "error").mockImplementation(() => {});
+      useSmartFolderStore.setState({ counts: { "sf-1": 2 } });
+      mockRefreshSmartFolderCounts.mockRejectedValueOnce(new Error("db closed"));
 
-      await useSmartFolderStore.getState().refreshUnreadCounts("acc-1");
+      await useSmartFolderStore.getState().refreshCounts("acc-1");
 
-      const { unreadCounts } = useSmartFolderStore.getState();
-      expect(unreadCounts["sf-1"]).toBe(5);
+      expect(useSmartFolderStore.getState().counts).toEqual({ "sf-1": 2 });
     });
   });
 });
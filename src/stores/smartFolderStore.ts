@@ -5,9 +5,9 @@ import {
   updateSmartFolder as updateSmartFolderDb,
   deleteSmartFolder as deleteSmartFolderDb,
   type DbSmartFolder,
+  type SmartFolderCountMode,
 } from "@/services/db/smartFolders";
-import { getSmartFolderUnreadCount } from "@/services/search/smartFolderQuery";
-import { getDb } from "@/services/db/connection";
+import { refreshSmartFolderCounts } from "@/services/search/smartFolderCounts";
 
 export interface SmartFolder {
   id: string;
@@ -18,8 +18,17 @@ export interface SmartFolder {
   color: string | null;
   isDefault: boolean;
   sortOrder: number;
+  countMode: SmartFolderCountMode;
 }
 
+type SmartFolderUpdates = {
+  name?: string;
+  query?: string;
+  icon?: string;
+  color?: string;
+  countMode?: SmartFolderCountMode;
+};
+
 function mapDbFolder(db: DbSmartFolder): SmartFolder {
   return {
     id: db.id,
@@ -30,12 +39,14 @@ function mapDbFolder(db: DbSmartFolder): SmartFolder {
     color: db.color,
     isDefault: db.is_default === 1,
     sortOrder: db.sort_order,
+    countMode: db.count_mode ?? "unread",
   };
 }
 
 interface SmartFolderState {
   folders: SmartFolder[];
-  unreadCounts: Record<string, number>;
+  /** Badge count per folder id, per the folder's count mode. */
+  counts: Record<string, number>;
   isLoading: boolean;
   loadFolders: (accountId?: string) => Promise<void>;
   createFolder: (
@@ -44,18 +55,17 @@ interface SmartFolderState {
     accountId?: string,
     icon?: string,
     color?: string,
+    countMode?: SmartFolderCountMode,
   ) => Promise<string>;
-  updateFolder: (
-    id: string,
-    updates: { name?: string; query?: string; icon?: string; color?: string },
-  ) => Promise<void>;
+  updateFolder: (id: string, updates: SmartFolderUpdates) => Promise<void>;
   deleteFolder: (id: string) => Promise<void>;
-  refreshUnreadCounts: (accountId: string) => Promise<void>;
+  refreshCounts: (accountId: string) => Promise<void>;
+  setCounts: (counts: Record<string, number>) => void;
 }
 
 export const useSmartFolderStore = create<SmartFolderState>((set, get) => ({
   folders: [],
-  unreadCounts: {},
+  counts: {},
   isLoading: false,
 
   loadFolders: async (accountId?: string) => {
@@ -70,8 +80,8 @@ export const useSmartFolderStore = create<SmartFolderState>((set, get) => ({
     }
   },
 
-  createFolder: async (name, query, accountId?, icon?, color?) => {
-    const id = await insertSmartFolder({ name, query, accountId, icon, color });
+  createFolder: async (name, query, accountId?, icon?, color?, countMode?) => {
+    const id = await insertSmartFolder({ name, query, accountId, icon, color, countMode });
     const { folders } = get();
     set({
       folders: [
@@ -85,6 +95,7 @@ export const useSmartFolderStore = create<SmartFolderState>((set, get) => ({
           color: color ?? null,
           isDefault: false,
           sortOrder: folders.length,
+          countMode: countMode ?? "unread",
         },
       ],
     });
@@ -103,36 +114,22 @@ export const useSmartFolderStore = create<SmartFolderState>((set, get) => ({
 
   deleteFolder: async (id) => {
     await deleteSmartFolderDb(id);
-    const { folders, unreadCounts } = get();
-    const newCounts = { ...unreadCounts };
+    const { folders, counts } = get();
+    const newCounts = { ...counts };
     delete newCounts[id];
     set({
       folders: folders.filter((f) => f.id !== id),
-      unreadCounts: newCounts,
+      counts: newCounts,
     });
   },
 
-  refreshUnreadCounts: async (accountId: string) => {
-    const { folders } = get();
-    const counts: Record<string, number> = {};
-
+  refreshCounts: async (accountId: string) => {
     try {
-      const db = await getDb();
-      for (const folder of folders) {
-        try {
-          const { sql, params } = getSmartFolderUnreadCount(
-            folder.query,
-            accountId,
-          );
-          const rows = await db.select<{ count: number }[]>(sql, params);
-          counts[folder.id] = rows[0]?.count ?? 0;
-        } catch {
-          counts[folder.id] = 0;
-        }
-      }
-      set({ unreadCounts: counts });
+      set({ counts: await refreshSmartFolderCounts(accountId) });
     } catch (err) {
-      console.error("Failed to refresh smart folder unread counts:", err);
+      console.error("Failed to refresh smart folder counts:", err);
     }
   },
+
+  setCounts: (counts) => set({ counts }),
 }));
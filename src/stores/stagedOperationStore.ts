@@ -0,0 +1,27 @@
+import { create } from "zustand";
+
+/** A destructive operation waiting out its undo window. */
+export interface StagedOperation {
+  id: string;
+  /** Shown in the undo toast, e.g. "Conversation deleted". */
+  label: string;
+  expiresAt: number;
+}
+
+interface StagedOperationState {
+  operations: StagedOperation[];
+  /** Why the last operation to run failed, until the user dismisses it. */
+  error: string | null;
+  addOperation: (op: StagedOperation) => void;
+  removeOperation: (id: string) => void;
+  setError: (error: string | null) => void;
+}
+
+export const useStagedOperationStore = create<StagedOperationState>((set) => ({
+  operations: [],
+  error: null,
+  addOperation: (op) => set((state) => ({ operations: [...state.operations, op] })),
+  removeOperation: (id) =>
+    set((state) => ({ operations: state.operations.filter((op) => op.id !== id) })),
+  setError: (error) => set({ error }),
+}));
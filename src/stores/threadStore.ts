@@ -25,6 +25,7 @@ interface ThreadState {
   isLoading: boolean;
   searchQuery: string;
   searchThreadIds: Set<string> | null; // null = no active search
+  searchSnippets: Record<string, string>; // thread id → highlighted snippet for the active search
   setThreads: (threads: Thread[]) => void;
   selectThread: (id: string | null) => void;
   toggleThreadSelection: (id: string) => void;
@@ -37,6 +38,7 @@ interface ThreadState {
   removeThread: (id: string) => void;
   removeThreads: (ids: string[]) => void;
   setSearch: (query: string, threadIds: Set<string> | null) => void;
+  setSearchSnippets: (snippets: Record<string, string>) => void;
   clearSearch: () => void;
 }
 
@@ -48,6 +50,7 @@ export const useThreadStore = create<ThreadState>((set, get) => ({
   isLoading: false,
   searchQuery: "",
   searchThreadIds: null,
+  searchSnippets: {},
 
   setThreads: (threads) => set({ threads, threadMap: new Map(threads.map((t) => [t.id, t])) }),
   selectThread: (selectedThreadId) => set({ selectedThreadId, selectedThreadIds: new Set() }),
@@ -133,5 +136,6 @@ export const useThreadStore = create<ThreadState>((set, get) => ({
       };
     }),
   setSearch: (query, threadIds) => set({ searchQuery: query, searchThreadIds: threadIds }),
-  clearSearch: () => set({ searchQuery: "", searchThreadIds: null }),
+  setSearchSnippets: (snippets) => set({ searchSnippets: snippets }),
+  clearSearch: () => set({ searchQuery: "", searchThreadIds: null, searchSnippets: {} }),
 }));
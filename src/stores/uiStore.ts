@@ -33,7 +33,11 @@ interface UIState {
   taskSidebarVisible: boolean;
   sidebarNavConfig: SidebarNavItem[] | null;
   reduceMotion: boolean;
+  imageProxyEnabled: boolean;
+  imageProxyUrl: string;
   isOnline: boolean;
+  /** Backend read-only mode: server changes are refused. */
+  isReadOnly: boolean;
   pendingOpsCount: number;
   isSyncingFolder: string | null;
   setTheme: (theme: Theme) => void;
@@ -56,7 +60,10 @@ interface UIState {
   setSidebarNavConfig: (config: SidebarNavItem[]) => void;
   restoreSidebarNavConfig: (config: SidebarNavItem[]) => void;
   setReduceMotion: (reduce: boolean) => void;
+  setImageProxyEnabled: (enabled: boolean) => void;
+  setImageProxyUrl: (url: string) => void;
   setOnline: (online: boolean) => void;
+  setReadOnly: (readOnly: boolean) => void;
   setPendingOpsCount: (count: number) => void;
   setSyncingFolder: (folder: string | null) => void;
 }
@@ -78,7 +85,10 @@ export const useUIStore = create<UIState>((set) => ({
   taskSidebarVisible: false,
   sidebarNavConfig: null,
   reduceMotion: false,
+  imageProxyEnabled: false,
+  imageProxyUrl: "",
   isOnline: true,
+  isReadOnly: false,
   pendingOpsCount: 0,
   isSyncingFolder: null,
 
@@ -153,7 +163,16 @@ export const useUIStore = create<UIState>((set) => ({
     setSetting("reduce_motion", String(reduceMotion)).catch(() => {});
     set({ reduceMotion });
   },
+  setImageProxyEnabled: (imageProxyEnabled) => {
+    setSetting("image_proxy_enabled", String(imageProxyEnabled)).catch(() => {});
+    set({ imageProxyEnabled });
+  },
+  setImageProxyUrl: (imageProxyUrl) => {
+    setSetting("image_proxy_url", imageProxyUrl).catch(() => {});
+    set({ imageProxyUrl });
+  },
   setOnline: (isOnline) => set({ isOnline }),
+  setReadOnly: (isReadOnly) => set({ isReadOnly }),
   setPendingOpsCount: (pendingOpsCount) => set({ pendingOpsCount }),
   setSyncingFolder: (isSyncingFolder) => set({ isSyncingFolder }),
 }));
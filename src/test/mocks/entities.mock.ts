@@ -117,6 +117,8 @@ export function createMockGmailAccount(
     oauth_provider: null,
     oauth_client_id: null,
     oauth_client_secret: null,
+    oauth_tenant: null,
+    transport: "imap",
     imap_username: null,
     caldav_url: null,
     caldav_username: null,
@@ -125,6 +127,11 @@ export function createMockGmailAccount(
     caldav_home_url: null,
     calendar_provider: null,
     accept_invalid_certs: 0,
+    imap_authorize_as: null,
+    imap_delegation: null,
+    ask_password: 0,
+    team_folder: null,
+    team_members: null,
     ...overrides,
   };
 }
@@ -157,6 +164,8 @@ export function createMockImapAccount(
     oauth_provider: null,
     oauth_client_id: null,
     oauth_client_secret: null,
+    oauth_tenant: null,
+    transport: "imap",
     imap_username: null,
     caldav_url: null,
     caldav_username: null,
@@ -165,6 +174,11 @@ export function createMockImapAccount(
     caldav_home_url: null,
     calendar_provider: null,
     accept_invalid_certs: 0,
+    imap_authorize_as: null,
+    imap_delegation: null,
+    ask_password: 0,
+    team_folder: null,
+    team_members: null,
     ...overrides,
   };
 }
@@ -197,6 +211,8 @@ export function createMockDbAccount(
     oauth_provider: null,
     oauth_client_id: null,
     oauth_client_secret: null,
+    oauth_tenant: null,
+    transport: "imap",
     imap_username: null,
     caldav_url: null,
     caldav_username: null,
@@ -205,6 +221,11 @@ export function createMockDbAccount(
     caldav_home_url: null,
     calendar_provider: null,
     accept_invalid_certs: 0,
+    imap_authorize_as: null,
+    imap_delegation: null,
+    ask_password: 0,
+    team_folder: null,
+    team_members: null,
     ...overrides,
   };
 }
@@ -237,6 +258,15 @@ export function createMockImapMessage(
     list_unsubscribe_post: null,
     auth_results: null,
     attachments: [],
+    attached_messages: [],
+    parse_failed: false,
+    delivered_to: null,
+    list_id: null,
+    list_post: null,
+    list_archive: null,
+    is_from_me: false,
+    mail_followup_to: null,
+    keywords: [],
     ...overrides,
   };
 }
@@ -3,6 +3,7 @@ import { vi } from "vitest";
 export function createMockUIStoreState(overrides: Record<string, unknown> = {}) {
   return {
     isOnline: true,
+    isReadOnly: false,
     setPendingOpsCount: vi.fn(),
     ...overrides,
   };
@@ -0,0 +1,40 @@
+import { describe, it, expect } from "vitest";
+import {
+  expandGroupRecipients,
+  groupRecipient,
+  parseGroupRecipient,
+} from "./contactGroups";
+
+describe("contact group recipients", () => {
+  const groups = {
+    Team: ["a@example.com", "b@example.com"],
+    "R&D, Tokyo": ["c@example.com"],
+  };
+
+  it("round-trips group tokens", () => {
+    expect(parseGroupRecipient(groupRecipient("Team"))).toBe("Team");
+    expect(parseGroupRecipient("a@example.com")).toBeNull();
+  });
+
+  it("expands groups to their members without duplicates", () => {
+    expect(
+      expandGroupRecipients(["b@example.com", groupRecipient("Team")], groups),
+    ).toEqual(["b@example.com", "a@example.com"]);
+  });
+
+  it("uses group syntax when asked", () => {
+    expect(
+      expandGroupRecipients([groupRecipient("Team"), "d@example.com"], groups, true),
+    ).toEqual(["Team: a@example.com, b@example.com;", "d@example.com"]);
+  });
+
+  it("quotes group names that need it", () => {
+    expect(expandGroupRecipients([groupRecipient("R&D, Tokyo")], groups, true)).toEqual([
+      '"R&D, Tokyo": c@example.com;',
+    ]);
+  });
+
+  it("drops unknown groups when listing members", () => {
+    expect(expandGroupRecipients([groupRecipient("Nope")], groups)).toEqual([]);
+  });
+});
@@ -0,0 +1,57 @@
+/**
+ * Contact groups as composer recipients.
+ *
+ * A group is added to To/Cc/Bcc as a `group:<name>` token and replaced by
+ * its members when the message is built, either as a plain address list or
+ * as RFC 5322/6854 group syntax (`Team: a@x.com, b@x.com;`).
+ */
+
+export const GROUP_RECIPIENT_PREFIX = "group:";
+
+/** Recipient token for a contact group. */
+export function groupRecipient(name: string): string {
+  return `${GROUP_RECIPIENT_PREFIX}${name}`;
+}
+
+/** Group name of a recipient token, or null for a plain address. */
+export function parseGroupRecipient(recipient: string): string | null {
+  return recipient.startsWith(GROUP_RECIPIENT_PREFIX)
+    ? recipient.slice(GROUP_RECIPIENT_PREFIX.length)
+    : null;
+}
+
+/** Quote a display name unless it's all atext and spaces (RFC 5322 phrase). */
+function quotePhrase(name: string): string {
+  if (/^[A-Za-z0-9!#$%&'*+\-/=?^_`{|}~ ]+$/.test(name)) return name;
+  return `"${name.replace(/["\\]/g, "\\$&")}"`;
+}
+
+/**
+ * Expand group tokens in a recipient list. `groups` maps group names to
+ * member addresses; unknown groups expand to nothing. With `groupSyntax`
+ * each group becomes one `Name: members;` entry, otherwise its members are
+ * listed individually (duplicates dropped).
+ */
+export function expandGroupRecipients(
+  recipients: string[],
+  groups: Record<string, string[]>,
+  groupSyntax = false,
+): string[] {
+  const result: string[] = [];
+  for (const recipient of recipients) {
+    const name = parseGroupRecipient(recipient);
+    if (name === null) {
+      if (!result.includes(recipient)) result.push(recipient);
+      continue;
+    }
+    const members = groups[name] ?? [];
+    if (groupSyntax) {
+      result.push(`${quotePhrase(name)}: ${members.join(", ")};`);
+    } else {
+      for (const member of members) {
+        if (!result.includes(member)) result.push(member);
+      }
+    }
+  }
+  return result;
+}
@@ -1,14 +1,18 @@
 import { describe, it, expect, vi, beforeEach } from "vitest";
-import { createMockTauriFs } from "@/test/mocks";
+import { invoke } from "@tauri-apps/api/core";
 
-const tauriFs = createMockTauriFs();
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
 
-vi.mock("@tauri-apps/plugin-fs", () => tauriFs.mock);
+const mockInvoke = vi.mocked(invoke);
+const mockKey = btoa(String.fromCharCode(...new Uint8Array(32).fill(42)));
 
 describe("crypto", () => {
   beforeEach(() => {
     vi.resetModules();
-    tauriFs.store.clear();
+    mockInvoke.mockReset();
+    mockInvoke.mockResolvedValue(mockKey);
   });
 
   it("encrypts and decrypts a value roundtrip", async () => {
@@ -64,36 +68,20 @@ describe("crypto", () => {
     expect(decrypted).toBe(plaintext);
   });
 
-  it("uses baseDir option for FS operations", async () => {
-    const { encryptValue } = await import("./crypto");
-
-    await encryptValue("test");
-
-    expect(tauriFs.mock.exists).toHaveBeenCalledWith(
-      "velo.key",
-      expect.objectContaining({ baseDir: 26 }),
-    );
-    expect(tauriFs.mock.writeTextFile).toHaveBeenCalledWith(
-      "velo.key",
-      expect.any(String),
-      expect.objectContaining({ baseDir: 26 }),
-    );
-  });
-
-  it("reads existing key from file using baseDir", async () => {
-    // Pre-seed a key in the mock store
-    const mockKey = btoa(String.fromCharCode(...new Uint8Array(32).fill(42)));
-    tauriFs.store.set("velo.key", mockKey);
-
+  it("gets the key from the backend once", async () => {
     const { encryptValue, decryptValue } = await import("./crypto");
+
     const encrypted = await encryptValue("round-trip-test");
+    expect(await decryptValue(encrypted)).toBe("round-trip-test");
 
-    expect(tauriFs.mock.readTextFile).toHaveBeenCalledWith(
-      "velo.key",
-      expect.objectContaining({ baseDir: 26 }),
-    );
+    expect(mockInvoke).toHaveBeenCalledTimes(1);
+    expect(mockInvoke).toHaveBeenCalledWith("profile_data_key");
+  });
 
-    const decrypted = await decryptValue(encrypted);
-    expect(decrypted).toBe("round-trip-test");
+  it("fails while the profile is locked", async () => {
+    mockInvoke.mockRejectedValue("The profile is locked");
+    const { encryptValue } = await import("./crypto");
+
+    await expect(encryptValue("secret")).rejects.toBe("The profile is locked");
   });
 });
@@ -1,16 +1,15 @@
 /**
  * Application-level AES-GCM encryption using a device-derived key.
- * Key is randomly generated on first launch and stored in a separate file
- * via Tauri's filesystem in the app data directory.
+ * The key is randomly generated on first launch and kept by the backend in
+ * `velo.key` in the app data directory, optionally wrapped with a master
+ * password (see `services/profileLock.ts`). While the profile is locked the
+ * key isn't available and encrypting or decrypting throws.
  */
 
-import { exists, readTextFile, writeTextFile, mkdir, BaseDirectory } from "@tauri-apps/plugin-fs";
+import { invoke } from "@tauri-apps/api/core";
 
-const KEY_FILE_NAME = "velo.key";
 const ALGORITHM = "AES-GCM";
-const KEY_LENGTH = 256;
 const IV_LENGTH = 12;
-const FS_OPTIONS = { baseDir: BaseDirectory.AppData };
 
 let cachedKey: CryptoKey | null = null;
 
@@ -31,14 +30,6 @@ function base64Decode(str: string): Uint8Array {
   return bytes;
 }
 
-async function ensureAppDataDir(): Promise<void> {
-  try {
-    await mkdir("", { ...FS_OPTIONS, recursive: true });
-  } catch {
-    // directory may already exist
-  }
-}
-
 // Web Crypto API accepts BufferSource (ArrayBuffer | ArrayBufferView).
 // TypeScript's ES2021 lib types are strict about Uint8Array<ArrayBufferLike> vs ArrayBufferView<ArrayBuffer>.
 // This cast satisfies the type checker while passing the Uint8Array directly to the API.
@@ -49,19 +40,7 @@ function asBufferSource(arr: Uint8Array): BufferSource {
 async function getOrCreateKey(): Promise<CryptoKey> {
   if (cachedKey) return cachedKey;
 
-  let rawKeyB64: string;
-  if (await exists(KEY_FILE_NAME, FS_OPTIONS)) {
-    rawKeyB64 = (await readTextFile(KEY_FILE_NAME, FS_OPTIONS)).trim();
-  } else {
-    // Generate a new random key
-    const rawKey = new Uint8Array(KEY_LENGTH / 8);
-    crypto.getRandomValues(rawKey);
-    rawKeyB64 = base64Encode(rawKey);
-
-    await ensureAppDataDir();
-    await writeTextFile(KEY_FILE_NAME, rawKeyB64, FS_OPTIONS);
-  }
-
+  const rawKeyB64 = await invoke<string>("profile_data_key");
   const rawKey = base64Decode(rawKeyB64);
   cachedKey = await crypto.subtle.importKey(
     "raw",
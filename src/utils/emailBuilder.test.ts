@@ -54,6 +54,37 @@ describe("emailBuilder", () => {
     expect(decoded).toContain("Bcc: bcc@example.com");
   });
 
+  it("expands contact groups in recipient headers", () => {
+    const draft = {
+      from: "sender@example.com",
+      to: ["group:Team"],
+      bcc: ["group:Team"],
+      subject: "Test",
+      htmlBody: "<p>Hi</p>",
+      groups: { Team: ["a@example.com", "b@example.com"] },
+    };
+
+    const listed = decodeBase64Url(buildRawEmail(draft));
+    expect(listed).toContain("To: a@example.com, b@example.com");
+
+    const named = decodeBase64Url(buildRawEmail({ ...draft, groupSyntax: true }));
+    expect(named).toContain("To: Team: a@example.com, b@example.com;");
+    expect(named).toContain("Bcc: a@example.com, b@example.com");
+  });
+
+  it("includes Reply-To header when set", () => {
+    const raw = buildRawEmail({
+      from: "me+shop@example.com",
+      replyTo: "me+shop@example.com",
+      to: ["orders@shop.com"],
+      subject: "Test",
+      htmlBody: "<p>Hi</p>",
+    });
+
+    const decoded = decodeBase64Url(raw);
+    expect(decoded).toContain("Reply-To: me+shop@example.com");
+  });
+
   it("includes In-Reply-To header", () => {
     const raw = buildRawEmail({
       from: "sender@example.com",
@@ -82,6 +113,22 @@ describe("emailBuilder", () => {
     expect(decoded).toContain("text/html");
   });
 
+  it("appends the disclaimer to both parts", () => {
+    const raw = buildRawEmail({
+      from: "sender@corp.example",
+      to: ["recipient@example.com"],
+      subject: "Test",
+      htmlBody: "<p>Hello</p>",
+      disclaimer: { text: "Confidential", html: "<i>Confidential</i>" },
+    });
+
+    const decoded = decodeBase64Url(raw);
+    expect(decoded).toContain("Hello\n\nConfidential");
+    expect(decoded).toContain(
+      '<p>Hello</p><div class="disclaimer" style="margin-top:16px"><i>Confidential</i></div>',
+    );
+  });
+
   it("handles multiple recipients", () => {
     const raw = buildRawEmail({
       from: "sender@example.com",
@@ -150,6 +197,45 @@ describe("emailBuilder", () => {
     expect(decoded).toContain("multipart/alternative");
     expect(decoded).not.toContain("multipart/mixed");
   });
+
+  it("emits a placeholder part for staged attachments", () => {
+    const raw = buildRawEmail({
+      from: "sender@example.com",
+      to: ["to@example.com"],
+      subject: "Staged",
+      htmlBody: "<p>Big file</p>",
+      attachments: [
+        { filename: "video.mp4", mimeType: "video/mp4", stagedId: "att-1" },
+      ],
+    });
+
+    const decoded = decodeBase64Url(raw);
+    expect(decoded).toContain("multipart/mixed");
+    expect(decoded).toContain("X-Sora-Staged-Attachment: att-1\r\n\r\n");
+    expect(decoded).not.toContain('filename="video.mp4"');
+  });
+
+  it("links staged inline images to their cid and emits placeholder parts", () => {
+    const raw = buildRawEmail({
+      from: "sender@example.com",
+      to: ["to@example.com"],
+      subject: "Pasted",
+      htmlBody: '<p><img src="data:image/png;base64,AAAA"></p>',
+      stagedInlineImages: [
+        { stagedId: "att-2", contentId: "att-2@velomail", src: "data:image/png;base64,AAAA" },
+        { stagedId: "att-3", contentId: "att-3@velomail", src: "data:image/png;base64,BBBB" },
+      ],
+    });
+
+    const decoded = decodeBase64Url(raw);
+    expect(decoded).toContain("multipart/related");
+    expect(decoded).toContain('src="cid:att-2@velomail"');
+    expect(decoded).toContain("X-Sora-Staged-Attachment: att-2");
+    // Removed from the editor, so not sent
+    expect(decoded).not.toContain("att-3");
+    // Not re-extracted as a data URL inline image
+    expect(decoded).not.toContain("inline_");
+  });
 });
 
 function decodeBase64Url(encoded: string): string {
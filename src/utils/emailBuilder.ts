@@ -1,14 +1,28 @@
 /**
  * Build an RFC 2822 email message and encode as base64url for the Gmail API.
  */
+import { expandGroupRecipients } from "./contactGroups";
+
+/** Header of the placeholder part emitted for a backend-staged attachment. */
+export const STAGED_PART_HEADER = "X-Sora-Staged-Attachment";
+
 export interface EmailAttachment {
   filename: string;
   mimeType: string;
-  content: string; // base64-encoded content
+  content?: string; // base64-encoded content
+  stagedId?: string; // backend-staged attachment, filled in at send time
+}
+
+/** Backend-staged inline image shown in the HTML via its preview `src`. */
+export interface StagedInlineImageRef {
+  stagedId: string;
+  contentId: string;
+  src: string;
 }
 
 export interface EmailDraft {
   from: string;
+  replyTo?: string;
   to: string[];
   cc?: string[];
   bcc?: string[];
@@ -18,6 +32,19 @@ export interface EmailDraft {
   references?: string;
   threadId?: string;
   attachments?: EmailAttachment[];
+  stagedInlineImages?: StagedInlineImageRef[];
+  /** Members of contact groups used as recipients, keyed by group name. */
+  groups?: Record<string, string[]>;
+  /** Write groups in To/Cc as `Name: members;` instead of listing members. */
+  groupSyntax?: boolean;
+  /** Admin-provisioned footer appended to both the text and HTML parts. */
+  disclaimer?: Disclaimer | null;
+}
+
+/** A disclaimer required by the policy file (see `get_disclaimer`). */
+export interface Disclaimer {
+  text: string;
+  html: string;
 }
 
 function base64UrlEncode(str: string): string {
@@ -44,8 +71,16 @@ function htmlToPlainText(html: string): string {
     .trim();
 }
 
-function buildAlternativePart(boundary: string, htmlBody: string): string[] {
-  const textContent = htmlToPlainText(htmlBody);
+function buildAlternativePart(
+  boundary: string,
+  htmlBody: string,
+  disclaimer?: Disclaimer | null,
+): string[] {
+  let textContent = htmlToPlainText(htmlBody);
+  if (disclaimer) {
+    textContent += `\n\n${disclaimer.text}`;
+    htmlBody += `<div class="disclaimer" style="margin-top:16px">${disclaimer.html}</div>`;
+  }
   const lines: string[] = [];
 
   lines.push(`--${boundary}`);
@@ -87,6 +122,24 @@ function extractInlineImages(html: string): { html: string; images: InlineImage[
   return { html: processed, images };
 }
 
+/**
+ * Point staged inline images at their cid: and return the ones still in the HTML.
+ */
+function linkStagedInlineImages(
+  html: string,
+  staged: StagedInlineImageRef[],
+): { html: string; used: StagedInlineImageRef[] } {
+  const used: StagedInlineImageRef[] = [];
+  let processed = html;
+  for (const img of staged) {
+    const attr = `src="${img.src}"`;
+    if (!processed.includes(attr)) continue;
+    processed = processed.split(attr).join(`src="cid:${img.contentId}"`);
+    used.push(img);
+  }
+  return { html: processed, used };
+}
+
 /**
  * Generate a unique Message-ID for outgoing emails.
  */
@@ -99,16 +152,21 @@ function generateMessageId(from: string): string {
 
 export function buildRawEmail(draft: EmailDraft): string {
   const messageId = generateMessageId(draft.from);
+  const groups = draft.groups ?? {};
   const lines: string[] = [
     `From: ${draft.from}`,
-    `To: ${draft.to.join(", ")}`,
+    `To: ${expandGroupRecipients(draft.to, groups, draft.groupSyntax).join(", ")}`,
   ];
 
   if (draft.cc && draft.cc.length > 0) {
-    lines.push(`Cc: ${draft.cc.join(", ")}`);
+    lines.push(`Cc: ${expandGroupRecipients(draft.cc, groups, draft.groupSyntax).join(", ")}`);
   }
   if (draft.bcc && draft.bcc.length > 0) {
-    lines.push(`Bcc: ${draft.bcc.join(", ")}`);
+    // Bcc is never shown to recipients, so groups are always listed out
+    lines.push(`Bcc: ${expandGroupRecipients(draft.bcc, groups).join(", ")}`);
+  }
+  if (draft.replyTo) {
+    lines.push(`Reply-To: ${draft.replyTo}`);
   }
 
   lines.push(`Date: ${new Date().toUTCString()}`);
@@ -123,9 +181,13 @@ export function buildRawEmail(draft: EmailDraft): string {
     lines.push(`References: ${draft.references}`);
   }
 
-  const { html: processedHtml, images: inlineImages } = extractInlineImages(draft.htmlBody);
+  const { html: linkedHtml, used: stagedInline } = linkStagedInlineImages(
+    draft.htmlBody,
+    draft.stagedInlineImages ?? [],
+  );
+  const { html: processedHtml, images: inlineImages } = extractInlineImages(linkedHtml);
   const hasAttachments = draft.attachments && draft.attachments.length > 0;
-  const hasInlineImages = inlineImages.length > 0;
+  const hasInlineImages = inlineImages.length > 0 || stagedInline.length > 0;
 
   if (hasAttachments || hasInlineImages) {
     const mixedBoundary = `----=_Mixed_${Date.now()}_${Math.random().toString(36).slice(2)}`;
@@ -146,7 +208,7 @@ export function buildRawEmail(draft: EmailDraft): string {
       lines.push(`--${relatedBoundary}`);
       lines.push(`Content-Type: multipart/alternative; boundary="${altBoundary}"`);
       lines.push("");
-      lines.push(...buildAlternativePart(altBoundary, processedHtml));
+      lines.push(...buildAlternativePart(altBoundary, processedHtml, draft.disclaimer));
       lines.push("");
 
       // Inline image parts
@@ -162,12 +224,19 @@ export function buildRawEmail(draft: EmailDraft): string {
         }
         lines.push("");
       }
+      // Staged inline parts; the backend writes the headers and body
+      for (const img of stagedInline) {
+        lines.push(`--${relatedBoundary}`);
+        lines.push(`${STAGED_PART_HEADER}: ${img.stagedId}`);
+        lines.push("");
+        lines.push("");
+      }
       lines.push(`--${relatedBoundary}--`);
     } else {
       // No inline images, just alternative
       lines.push(`Content-Type: multipart/alternative; boundary="${altBoundary}"`);
       lines.push("");
-      lines.push(...buildAlternativePart(altBoundary, processedHtml));
+      lines.push(...buildAlternativePart(altBoundary, processedHtml, draft.disclaimer));
     }
 
     if (hasAttachments) {
@@ -175,11 +244,18 @@ export function buildRawEmail(draft: EmailDraft): string {
       // Attachment parts
       for (const att of draft.attachments!) {
         lines.push(`--${mixedBoundary}`);
+        if (att.stagedId) {
+          // Placeholder part; the backend writes the headers and body
+          lines.push(`${STAGED_PART_HEADER}: ${att.stagedId}`);
+          lines.push("");
+          lines.push("");
+          continue;
+        }
         lines.push(`Content-Type: ${att.mimeType}; name="${att.filename}"`);
         lines.push("Content-Transfer-Encoding: base64");
         lines.push(`Content-Disposition: attachment; filename="${att.filename}"`);
         lines.push("");
-        const raw = att.content;
+        const raw = att.content ?? "";
         for (let i = 0; i < raw.length; i += 76) {
           lines.push(raw.slice(i, i + 76));
         }
@@ -191,7 +267,7 @@ export function buildRawEmail(draft: EmailDraft): string {
     const altBoundary = `----=_Part_${Date.now()}_${Math.random().toString(36).slice(2)}`;
     lines.push(`Content-Type: multipart/alternative; boundary="${altBoundary}"`);
     lines.push("");
-    lines.push(...buildAlternativePart(altBoundary, processedHtml));
+    lines.push(...buildAlternativePart(altBoundary, processedHtml, draft.disclaimer));
   }
 
   return base64UrlEncode(lines.join("\r\n"));
@@ -1,5 +1,11 @@
 import { describe, it, expect } from "vitest";
-import { stripRemoteImages, restoreRemoteImages, hasBlockedImages } from "./imageBlocker";
+import {
+  stripRemoteImages,
+  restoreRemoteImages,
+  hasBlockedImages,
+  collectRemoteImageUrls,
+  proxyRemoteImages,
+} from "./imageBlocker";
 
 describe("stripRemoteImages", () => {
   it("blocks remote http images", () => {
@@ -92,3 +98,34 @@ describe("hasBlockedImages", () => {
     expect(hasBlockedImages(html)).toBe(false);
   });
 });
+
+describe("collectRemoteImageUrls", () => {
+  it("collects img and style urls, decoded and deduplicated", () => {
+    const html =
+      '<img src="https://a.example.com/x.png?w=1&amp;h=2">' +
+      '<div style="background-image: url(&quot;https://b.example.com/bg.jpg&quot;)"></div>' +
+      '<img src="https://a.example.com/x.png?w=1&amp;h=2">' +
+      '<img src="cid:logo"><img src="data:image/png;base64,abc">';
+    expect(collectRemoteImageUrls(html)).toEqual([
+      "https://a.example.com/x.png?w=1&h=2",
+      "https://b.example.com/bg.jpg",
+    ]);
+  });
+});
+
+describe("proxyRemoteImages", () => {
+  it("swaps in local copies and empties the rest", () => {
+    const html =
+      '<img alt="a" src="https://a.example.com/x.png?w=1&amp;h=2">' +
+      '<img src="https://t.example.com/pixel.gif">' +
+      "<td style=\"background: url('https://b.example.com/bg.jpg')\"></td>";
+    const proxied = new Map([
+      ["https://a.example.com/x.png?w=1&h=2", "data:image/png;base64,AAA"],
+    ]);
+    const result = proxyRemoteImages(html, proxied);
+    expect(result).toContain('<img alt="a" src="data:image/png;base64,AAA">');
+    expect(result).toContain('<img src="">');
+    expect(result).toContain("url('')");
+    expect(result).not.toContain("https://");
+  });
+});
@@ -39,3 +39,43 @@ export function restoreRemoteImages(html: string): string {
 export function hasBlockedImages(html: string): boolean {
   return /data-blocked-src\s*=\s*["']https?:\/\//i.test(html);
 }
+
+const REMOTE_IMG_SRC = /(<img\b[^>]*?\ssrc\s*=\s*)(["'])(https?:\/\/[^"']*)\2/gi;
+const REMOTE_CSS_URL = /url\(\s*(&quot;|["']?)(https?:\/\/.*?)\1\s*\)/gi;
+
+/** Attribute values in serialized HTML have "&" escaped. */
+function decodeAttrUrl(url: string): string {
+  return url.replace(/&amp;/g, "&");
+}
+
+/**
+ * Collect the remote image URLs in HTML (img src and inline style url()),
+ * decoded and without duplicates.
+ */
+export function collectRemoteImageUrls(html: string): string[] {
+  const urls = new Set<string>();
+  for (const match of html.matchAll(REMOTE_IMG_SRC)) urls.add(decodeAttrUrl(match[3]!));
+  for (const match of html.matchAll(REMOTE_CSS_URL)) urls.add(decodeAttrUrl(match[2]!));
+  return [...urls];
+}
+
+/**
+ * Point remote images at locally served copies (see `collectRemoteImageUrls`).
+ * Images without a local copy are emptied, so the webview never contacts
+ * the remote server.
+ */
+export function proxyRemoteImages(html: string, proxied: Map<string, string>): string {
+  let result = html.replace(
+    REMOTE_IMG_SRC,
+    (_match, prefix: string, quote: string, url: string) =>
+      `${prefix}${quote}${proxied.get(decodeAttrUrl(url)) ?? ""}${quote}`,
+  );
+  result = result.replace(
+    REMOTE_CSS_URL,
+    (_match, quote: string, url: string) => {
+      const local = proxied.get(decodeAttrUrl(url));
+      return local ? `url(${quote}${local}${quote})` : `url(${quote}${quote})`;
+    },
+  );
+  return result;
+}
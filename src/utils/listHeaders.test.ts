@@ -0,0 +1,31 @@
+import { describe, it, expect } from "vitest";
+import { parseListHeaders } from "./listHeaders";
+
+describe("parseListHeaders", () => {
+  it("parses id, posting address and archive", () => {
+    expect(
+      parseListHeaders(
+        '"Dev talk" <Dev.Lists.Example.org>',
+        "<mailto:dev@lists.example.org?subject=hi>",
+        "<ftp://ftp.example.org/dev>, <https://lists.example.org/archive/dev>",
+      ),
+    ).toEqual({
+      listId: "dev.lists.example.org",
+      listPost: "dev@lists.example.org",
+      listArchive: "https://lists.example.org/archive/dev",
+    });
+  });
+
+  it("handles missing headers and announcement lists", () => {
+    expect(parseListHeaders("announce.example.com", "NO (posting not allowed)", null)).toEqual({
+      listId: "announce.example.com",
+      listPost: null,
+      listArchive: null,
+    });
+    expect(parseListHeaders(null, null, null)).toEqual({
+      listId: null,
+      listPost: null,
+      listArchive: null,
+    });
+  });
+});
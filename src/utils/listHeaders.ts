@@ -0,0 +1,44 @@
+/**
+ * Mailing list headers: List-Id (RFC 2919), List-Post and List-Archive
+ * (RFC 2369). Mirrors the backend parser used for IMAP messages.
+ */
+
+export interface ListHeaders {
+  /** List identifier without angle brackets, lowercased */
+  listId: string | null;
+  /** Posting address; null when the list doesn't accept posts ("NO") */
+  listPost: string | null;
+  /** Archive URL, preferring http(s) */
+  listArchive: string | null;
+}
+
+/** Contents of each `<...>` in a header value, whitespace removed. */
+function angleEntries(value: string): string[] {
+  return [...value.matchAll(/<([^>]*)>/g)]
+    .map((m) => m[1]!.replace(/\s+/g, ""))
+    .filter(Boolean);
+}
+
+export function parseListHeaders(
+  listId: string | null,
+  listPost: string | null,
+  listArchive: string | null,
+): ListHeaders {
+  const id = listId ? (angleEntries(listId)[0] ?? listId.trim()) : "";
+
+  const post = listPost
+    ? angleEntries(listPost)
+        .filter((e) => /^mailto:/i.test(e))
+        .map((e) => e.slice(7).split("?")[0]!)
+        .find((address) => address.includes("@"))
+    : undefined;
+
+  const archives = listArchive ? angleEntries(listArchive) : [];
+  const archive = archives.find((e) => /^https?:\/\//i.test(e)) ?? archives[0];
+
+  return {
+    listId: id ? id.toLowerCase() : null,
+    listPost: post ?? null,
+    listArchive: archive ?? null,
+  };
+}
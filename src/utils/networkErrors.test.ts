@@ -31,6 +31,12 @@ describe("classifyError", () => {
     expect(result.isRetryable).toBe(false);
   });
 
+  it("classifies IMAP login rejections as auth", () => {
+    const result = classifyError(new Error("IMAP login failed: [AUTHENTICATIONFAILED] Invalid credentials"));
+    expect(result.type).toBe("auth");
+    expect(result.isRetryable).toBe(false);
+  });
+
   it("classifies 429 as quota (retryable)", () => {
     const result = classifyError(new Error("HTTP 429 Too Many Requests"));
     expect(result.type).toBe("quota");
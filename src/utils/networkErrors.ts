@@ -21,6 +21,15 @@ const NETWORK_PATTERNS = [
   "net::err",
 ];
 
+/** Login rejections from IMAP/SMTP servers, which carry no HTTP status. */
+const AUTH_PATTERNS = [
+  "authenticationfailed",
+  "authentication failed",
+  "invalid credentials",
+  "login failed",
+  "[auth]",
+];
+
 export function classifyError(error: unknown): ClassifiedError {
   const message =
     error instanceof Error ? error.message : String(error ?? "Unknown error");
@@ -42,6 +51,10 @@ export function classifyError(error: unknown): ClassifiedError {
     return { type: "server", isRetryable: true, message };
   }
 
+  if (AUTH_PATTERNS.some((pattern) => lower.includes(pattern))) {
+    return { type: "auth", isRetryable: false, message };
+  }
+
   // Check network error patterns
   if (NETWORK_PATTERNS.some((pattern) => lower.includes(pattern))) {
     return { type: "network", isRetryable: true, message };
@@ -0,0 +1,78 @@
+import {
+  parsePlusAddress,
+  makePlusAddress,
+  tagForRecipient,
+  plusAddressForRecipients,
+  groupByPlusTag,
+} from "./plusAddress";
+
+describe("parsePlusAddress", () => {
+  it("splits base address and tag", () => {
+    expect(parsePlusAddress("User+Shop@Example.com")).toEqual({
+      base: "user@example.com",
+      tag: "shop",
+    });
+    expect(parsePlusAddress("Jane <jane+news@example.com>")).toEqual({
+      base: "jane@example.com",
+      tag: "news",
+    });
+  });
+
+  it("returns null for untagged or malformed addresses", () => {
+    expect(parsePlusAddress("user@example.com")).toBeNull();
+    expect(parsePlusAddress("+tag@example.com")).toBeNull();
+    expect(parsePlusAddress("user+@example.com")).toBeNull();
+    expect(parsePlusAddress("not an address")).toBeNull();
+    expect(parsePlusAddress(null)).toBeNull();
+  });
+});
+
+describe("makePlusAddress", () => {
+  it("adds or replaces the tag", () => {
+    expect(makePlusAddress("user@example.com", "shop")).toBe("user+shop@example.com");
+    expect(makePlusAddress("user+old@example.com", "new")).toBe("user+new@example.com");
+  });
+
+  it("sanitizes the tag", () => {
+    expect(makePlusAddress("user@example.com", "Big Shop!")).toBe("user+big-shop@example.com");
+    expect(makePlusAddress("user@example.com", "!!!")).toBe("user@example.com");
+  });
+});
+
+describe("tagForRecipient", () => {
+  it("uses the organization part of the domain", () => {
+    expect(tagForRecipient("orders@shop.com")).toBe("shop");
+    expect(tagForRecipient("orders@mail.shop.com")).toBe("shop");
+    expect(tagForRecipient("Orders <orders@mail.shop.co.uk>")).toBe("shop");
+  });
+
+  it("returns null without an address", () => {
+    expect(tagForRecipient("nobody")).toBeNull();
+  });
+});
+
+describe("plusAddressForRecipients", () => {
+  it("tags the account address for the first recipient", () => {
+    expect(plusAddressForRecipients("me@example.com", ["sales@acme.io", "x@other.com"])).toBe(
+      "me+acme@example.com",
+    );
+    expect(plusAddressForRecipients("me@example.com", [])).toBeNull();
+  });
+});
+
+describe("groupByPlusTag", () => {
+  it("groups messages by the tag on the account address", () => {
+    const messages = [
+      { id: 1, toAddresses: "me+shop@example.com" },
+      { id: 2, toAddresses: "Other <other@example.com>", ccAddresses: "Me <me+shop@example.com>" },
+      { id: 3, toAddresses: "me+news@example.com" },
+      { id: 4, toAddresses: "me@example.com" },
+      { id: 5, toAddresses: "someone+shop@example.com" },
+    ];
+    const groups = groupByPlusTag(messages, "me@example.com");
+
+    expect([...groups.keys()]).toEqual(["shop", "news"]);
+    expect(groups.get("shop")!.map((m) => m.id)).toEqual([1, 2]);
+    expect(groups.get("news")!.map((m) => m.id)).toEqual([3]);
+  });
+});
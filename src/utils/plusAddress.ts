@@ -0,0 +1,116 @@
+/**
+ * Subaddressing ("plus addressing", RFC 5233): user+tag@domain is delivered
+ * to user@domain by most providers. Giving each correspondent its own tag
+ * shows who shared or leaked the address when mail later arrives on it.
+ */
+
+export interface PlusAddress {
+  /** Address without the tag, lowercased: "user@example.com" */
+  base: string;
+  /** Tag after the "+", lowercased: "shop" */
+  tag: string;
+}
+
+/** Split "user+tag@domain" into base address and tag. Null if untagged. */
+export function parsePlusAddress(address: string | null | undefined): PlusAddress | null {
+  const email = extractEmail(address);
+  if (!email) return null;
+  const at = email.lastIndexOf("@");
+  const local = email.slice(0, at);
+  const plus = local.indexOf("+");
+  if (plus <= 0 || plus === local.length - 1) return null;
+  return {
+    base: `${local.slice(0, plus)}${email.slice(at)}`,
+    tag: local.slice(plus + 1),
+  };
+}
+
+/** Lowercase and keep only characters that are safe in a local part. */
+export function sanitizeTag(tag: string): string {
+  return tag
+    .toLowerCase()
+    .replace(/[^a-z0-9._-]+/g, "-")
+    .replace(/^[-._]+|[-._]+$/g, "")
+    .slice(0, 32);
+}
+
+/**
+ * Build the tagged variant of `address` ("user@x.com" + "shop" →
+ * "user+shop@x.com"). An existing tag is replaced. Returns the address
+ * unchanged if the tag is empty after sanitizing.
+ */
+export function makePlusAddress(address: string, tag: string): string {
+  const email = extractEmail(address) ?? address.trim();
+  const at = email.lastIndexOf("@");
+  if (at <= 0) return address;
+  const clean = sanitizeTag(tag);
+  if (!clean) return email;
+  const base = parsePlusAddress(email)?.base ?? email;
+  const baseAt = base.lastIndexOf("@");
+  return `${base.slice(0, baseAt)}+${clean}${base.slice(baseAt)}`;
+}
+
+/** Second-level domains used under country codes (co.uk, com.au, ...). */
+const GENERIC_SLDS = new Set(["co", "com", "net", "org", "ac", "gov", "edu", "ne", "or"]);
+
+/**
+ * Tag for mail sent to `recipient`: the organization part of its domain
+ * ("orders@mail.shop.co.uk" → "shop").
+ */
+export function tagForRecipient(recipient: string): string | null {
+  const email = extractEmail(recipient);
+  if (!email) return null;
+  const labels = email.slice(email.lastIndexOf("@") + 1).split(".").filter(Boolean);
+  if (labels.length < 2) return sanitizeTag(labels[0] ?? "") || null;
+  let index = labels.length - 2;
+  if (labels.length > 2 && labels[labels.length - 1]!.length === 2 && GENERIC_SLDS.has(labels[index]!)) {
+    index -= 1;
+  }
+  return sanitizeTag(labels[index]!) || null;
+}
+
+/**
+ * Per-recipient From address for a new message: the account address tagged
+ * for the first recipient, or null if there is no usable recipient.
+ */
+export function plusAddressForRecipients(accountEmail: string, recipients: string[]): string | null {
+  const first = recipients.find((r) => extractEmail(r));
+  if (!first) return null;
+  const tag = tagForRecipient(first);
+  return tag ? makePlusAddress(accountEmail, tag) : null;
+}
+
+/**
+ * Group messages by the plus tag they were delivered to. Only tags on the
+ * account's own address count; untagged mail is left out.
+ */
+export function groupByPlusTag<T extends { toAddresses?: string | null; ccAddresses?: string | null }>(
+  messages: T[],
+  accountEmail: string,
+): Map<string, T[]> {
+  const base = (parsePlusAddress(accountEmail)?.base ?? extractEmail(accountEmail) ?? "").toLowerCase();
+  const groups = new Map<string, T[]>();
+  for (const message of messages) {
+    const recipients = [message.toAddresses, message.ccAddresses]
+      .filter(Boolean)
+      .join(",")
+      .split(",");
+    const tag = recipients
+      .map((r) => parsePlusAddress(r))
+      .find((p) => p?.base === base)?.tag;
+    if (!tag) continue;
+    const group = groups.get(tag);
+    if (group) group.push(message);
+    else groups.set(tag, [message]);
+  }
+  return groups;
+}
+
+/** Bare lowercased email from "Name <user@x.com>" or "user@x.com". */
+function extractEmail(address: string | null | undefined): string | null {
+  if (!address) return null;
+  const angle = /<([^>]+)>/.exec(address);
+  const email = (angle ? angle[1]! : address).trim().toLowerCase();
+  const at = email.lastIndexOf("@");
+  return at > 0 && at < email.length - 1 ? email : null;
+}
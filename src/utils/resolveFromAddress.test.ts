@@ -1,5 +1,9 @@
 import { describe, it, expect } from "vitest";
-import { resolveFromAddress } from "./resolveFromAddress";
+import {
+  resolveFromAddress,
+  resolveReplyFromEmail,
+  detectCatchAllAddress,
+} from "./resolveFromAddress";
 import { createMockSendAsAlias } from "@/test/mocks";
 
 describe("resolveFromAddress", () => {
@@ -98,3 +102,83 @@ describe("resolveFromAddress", () => {
     expect(result?.id).toBe("a2");
   });
 });
+
+describe("resolveFromAddress with deliveredTo", () => {
+  it("prefers the envelope recipient over To/CC matches", () => {
+    const aliases = [
+      createMockSendAsAlias({ id: "a1", email: "to@example.com" }),
+      createMockSendAsAlias({ id: "a2", email: "bcc@example.com" }),
+    ];
+
+    const result = resolveFromAddress(aliases, "to@example.com", null, "Bcc@Example.com");
+    expect(result?.id).toBe("a2");
+  });
+
+  it("ignores an envelope recipient that is not an alias", () => {
+    const aliases = [
+      createMockSendAsAlias({ id: "a1", email: "to@example.com" }),
+    ];
+
+    const result = resolveFromAddress(aliases, "to@example.com", null, "list@example.org");
+    expect(result?.id).toBe("a1");
+  });
+});
+
+describe("detectCatchAllAddress", () => {
+  const aliases = [createMockSendAsAlias({ email: "me@example.com", isPrimary: true })];
+
+  it("returns unlisted addresses on an own domain", () => {
+    expect(detectCatchAllAddress("sales@example.com", "me@example.com", aliases)).toBe(
+      "sales@example.com",
+    );
+    expect(detectCatchAllAddress("<Me+Shop@example.com>", "me@example.com", [])).toBe(
+      "me+shop@example.com",
+    );
+  });
+
+  it("returns null for known addresses and foreign domains", () => {
+    expect(detectCatchAllAddress("me@example.com", "me@example.com", aliases)).toBeNull();
+    expect(detectCatchAllAddress("list@other.org", "me@example.com", aliases)).toBeNull();
+    expect(detectCatchAllAddress(null, "me@example.com", aliases)).toBeNull();
+  });
+});
+
+describe("resolveReplyFromEmail", () => {
+  it("uses the matching alias", () => {
+    const aliases = [
+      createMockSendAsAlias({ email: "me@example.com", isPrimary: true }),
+      createMockSendAsAlias({ email: "support@example.com" }),
+    ];
+    const original = {
+      to_addresses: "Support <support@example.com>",
+      cc_addresses: null,
+      delivered_to: null,
+    };
+
+    expect(resolveReplyFromEmail(aliases, "me@example.com", original)).toBe("support@example.com");
+  });
+
+  it("replies from a catch-all address", () => {
+    const original = {
+      to_addresses: "anything@example.com",
+      cc_addresses: null,
+      delivered_to: "anything@example.com",
+    };
+
+    expect(resolveReplyFromEmail([], "me@example.com", original)).toBe("anything@example.com");
+  });
+
+  it("falls back to the default alias", () => {
+    const aliases = [
+      createMockSendAsAlias({ email: "me@example.com", isPrimary: true }),
+    ];
+    const original = {
+      to_addresses: "someone@other.org",
+      cc_addresses: null,
+      delivered_to: "someone@other.org",
+    };
+
+    expect(resolveReplyFromEmail(aliases, "me@example.com", original)).toBe("me@example.com");
+    expect(resolveReplyFromEmail([], "me@example.com", original)).toBeNull();
+  });
+});
@@ -3,9 +3,10 @@ import type { SendAsAlias } from "@/services/db/sendAsAliases";
 /**
  * Resolve which send-as alias to use as the "From" address.
  *
- * When replying: checks if any alias email matches an address in the
- * To or CC fields of the original message. If found, uses that alias
- * so the reply comes from the address the message was originally sent to.
+ * When replying: checks if any alias email matches the envelope recipient
+ * (`deliveredTo`) or an address in the To or CC fields of the original
+ * message. If found, uses that alias so the reply comes from the address
+ * the message was originally sent to.
  *
  * Falls back to the default alias (isDefault), then primary alias.
  * Returns null if no aliases are available.
@@ -14,31 +15,12 @@ export function resolveFromAddress(
   aliases: SendAsAlias[],
   toAddresses: string | null,
   ccAddresses: string | null,
+  deliveredTo?: string | null,
 ): SendAsAlias | null {
   if (aliases.length === 0) return null;
 
-  // Collect all addresses from To and CC into a normalized set
-  const recipientEmails = new Set<string>();
-  if (toAddresses) {
-    for (const addr of toAddresses.split(",")) {
-      const trimmed = addr.trim().toLowerCase();
-      if (trimmed) recipientEmails.add(trimmed);
-    }
-  }
-  if (ccAddresses) {
-    for (const addr of ccAddresses.split(",")) {
-      const trimmed = addr.trim().toLowerCase();
-      if (trimmed) recipientEmails.add(trimmed);
-    }
-  }
-
-  // Check if any alias matches a recipient address
-  if (recipientEmails.size > 0) {
-    const match = aliases.find((a) =>
-      recipientEmails.has(a.email.toLowerCase()),
-    );
-    if (match) return match;
-  }
+  const match = findRecipientAlias(aliases, toAddresses, ccAddresses, deliveredTo);
+  if (match) return match;
 
   // Fall back to default alias
   const defaultAlias = aliases.find((a) => a.isDefault);
@@ -51,3 +33,95 @@ export function resolveFromAddress(
   // Last resort: return first alias
   return aliases[0] ?? null;
 }
+
+/**
+ * Email address a reply to (or forward of) `original` should be sent from.
+ *
+ * Prefers the alias the message was delivered to. When it was delivered to
+ * an unlisted address on one of the account's own domains — a catch-all
+ * address or a plus-addressed variant — that address is used, so the
+ * correspondent keeps seeing the address they wrote to. Otherwise falls
+ * back like `resolveFromAddress`.
+ */
+export function resolveReplyFromEmail(
+  aliases: SendAsAlias[],
+  accountEmail: string,
+  original: {
+    to_addresses: string | null;
+    cc_addresses: string | null;
+    delivered_to: string | null;
+  },
+): string | null {
+  const match = findRecipientAlias(
+    aliases,
+    original.to_addresses,
+    original.cc_addresses,
+    original.delivered_to,
+  );
+  if (match) return match.email;
+
+  const catchAll = detectCatchAllAddress(original.delivered_to, accountEmail, aliases);
+  if (catchAll) return catchAll;
+
+  return resolveFromAddress(aliases, null, null)?.email ?? null;
+}
+
+/**
+ * The envelope recipient if it is on the account's (or an alias's) domain
+ * but is not the account address or a configured alias. Null otherwise.
+ */
+export function detectCatchAllAddress(
+  deliveredTo: string | null | undefined,
+  accountEmail: string,
+  aliases: SendAsAlias[],
+): string | null {
+  const delivered = extractAddress(deliveredTo);
+  if (!delivered) return null;
+
+  const known = [accountEmail, ...aliases.map((a) => a.email)]
+    .map(extractAddress)
+    .filter((a): a is string => a !== null);
+  if (known.includes(delivered)) return null;
+
+  const domain = domainOf(delivered);
+  return known.some((a) => domainOf(a) === domain) ? delivered : null;
+}
+
+function findRecipientAlias(
+  aliases: SendAsAlias[],
+  toAddresses: string | null,
+  ccAddresses: string | null,
+  deliveredTo: string | null | undefined,
+): SendAsAlias | null {
+  const byEmail = (address: string | null) =>
+    address ? aliases.find((a) => a.email.toLowerCase() === address) ?? null : null;
+
+  // The envelope recipient names the alias even when it isn't in To/CC (Bcc, lists)
+  const delivered = byEmail(extractAddress(deliveredTo));
+  if (delivered) return delivered;
+
+  // Collect all addresses from To and CC into a normalized set
+  const recipientEmails = new Set<string>();
+  for (const field of [toAddresses, ccAddresses]) {
+    if (!field) continue;
+    for (const addr of field.split(",")) {
+      const email = extractAddress(addr);
+      if (email) recipientEmails.add(email);
+    }
+  }
+
+  // Check if any alias matches a recipient address
+  return aliases.find((a) => recipientEmails.has(a.email.toLowerCase())) ?? null;
+}
+
+/** Lowercased bare address from "Name <user@x.com>" or "user@x.com". */
+function extractAddress(raw: string | null | undefined): string | null {
+  if (!raw) return null;
+  const angle = /<([^>]+)>/.exec(raw);
+  const address = (angle ? angle[1]! : raw).trim().toLowerCase();
+  return address || null;
+}
+
+function domainOf(address: string): string {
+  return address.slice(address.lastIndexOf("@") + 1);
+}
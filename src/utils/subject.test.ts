@@ -0,0 +1,54 @@
+import { normalizeSubject, getThreadSubject } from "./subject";
+
+describe("normalizeSubject", () => {
+  it("strips localized reply and forward prefixes", () => {
+    expect(normalizeSubject("AW: Angebot")).toBe("Angebot");
+    expect(normalizeSubject("WG: Angebot")).toBe("Angebot");
+    expect(normalizeSubject("SV: Möte")).toBe("Möte");
+    expect(normalizeSubject("TR: Réunion")).toBe("Réunion");
+    expect(normalizeSubject("回复: 会议")).toBe("会议");
+    expect(normalizeSubject("转发：会议")).toBe("会议");
+  });
+
+  it("strips numbered prefixes", () => {
+    expect(normalizeSubject("Re[2]: Hello")).toBe("Hello");
+    expect(normalizeSubject("RE(3): Hello")).toBe("Hello");
+    expect(normalizeSubject("Re^2: Hello")).toBe("Hello");
+  });
+
+  it("strips mixed prefixes and list tags in any order", () => {
+    expect(normalizeSubject("Re: [team] AW: Fwd: Plan")).toBe("Plan");
+    expect(normalizeSubject("[a] [b] Re: Plan")).toBe("Plan");
+  });
+
+  it("collapses whitespace", () => {
+    expect(normalizeSubject("  Re:  Quarterly\t\n  report  ")).toBe("Quarterly report");
+  });
+
+  it("keeps words that only start like a prefix", () => {
+    expect(normalizeSubject("Review: draft")).toBe("Review: draft");
+    expect(normalizeSubject("Travel plans")).toBe("Travel plans");
+  });
+
+  it("handles empty input", () => {
+    expect(normalizeSubject(null)).toBe("");
+    expect(normalizeSubject(undefined)).toBe("");
+    expect(normalizeSubject("Re:")).toBe("");
+  });
+});
+
+describe("getThreadSubject", () => {
+  it("uses the earliest message with a subject", () => {
+    expect(getThreadSubject(["Budget", "Re: Budget", "AW: Budget"])).toBe("Budget");
+    expect(getThreadSubject(["Re: Budget", "Budget"])).toBe("Budget");
+  });
+
+  it("skips messages without a subject", () => {
+    expect(getThreadSubject([null, "", "Re:", "Fwd: Budget"])).toBe("Budget");
+  });
+
+  it("returns null when no message has a subject", () => {
+    expect(getThreadSubject([null, ""])).toBeNull();
+    expect(getThreadSubject([])).toBeNull();
+  });
+});
@@ -0,0 +1,55 @@
+/**
+ * Reply/forward prefixes used by mail clients in various locales:
+ * Re/Fwd/Fw (English), AW/WG (German), SV/VS/VB (Nordic), TR (French),
+ * RV (Spanish), Antw (Dutch), Odp (Polish), YNT (Turkish),
+ * 回复/回覆/答复/转发/轉寄 (Chinese), 返信/転送 (Japanese).
+ * Allows counters like "Re[2]:", "Re(2):", "Re^2:" and full-width colons.
+ */
+const REPLY_PREFIX =
+  /^(?:re|fwd?|aw|wg|sv|vs|vb|tr|rv|antw|odp|ynt|回复|回覆|答复|答覆|转发|轉寄|轉發|返信|転送)\s*(?:\[\d+\]|\(\d+\)|\^\d+)?\s*[:：]\s*/i;
+
+/** Leading mailing-list tag like "[node-dev]". */
+const LIST_TAG = /^\[[^\]]*\]\s*/;
+
+/**
+ * Normalize a subject for comparison and display: strips reply/forward
+ * prefixes (in any order and repeated) and [list] tags, and collapses
+ * whitespace. Case is preserved.
+ */
+export function normalizeSubject(subject: string | null | undefined): string {
+  if (!subject) return "";
+
+  let s = subject.replace(/\s+/g, " ").trim();
+  let changed = true;
+
+  while (changed) {
+    changed = false;
+
+    const tagMatch = LIST_TAG.exec(s);
+    if (tagMatch) {
+      s = s.slice(tagMatch[0].length);
+      changed = true;
+    }
+
+    const prefixMatch = REPLY_PREFIX.exec(s);
+    if (prefixMatch) {
+      s = s.slice(prefixMatch[0].length);
+      changed = true;
+    }
+  }
+
+  return s.trim();
+}
+
+/**
+ * Canonical subject of a thread: the normalized subject of the earliest
+ * message that has one. `subjects` must be in date order. Returns null if
+ * no message has a subject left after normalization.
+ */
+export function getThreadSubject(subjects: (string | null | undefined)[]): string | null {
+  for (const subject of subjects) {
+    const normalized = normalizeSubject(subject);
+    if (normalized) return normalized;
+  }
+  return null;
+}